@@ -760,6 +760,9 @@ def external_crates_repository(name, static_openssl, cargo_lockfile, lockfile):
             "quickcheck": crate.spec(
                 version = "^1.0.3",
             ),
+            "quinn": crate.spec(
+                version = "^0.7.2",
+            ),
             "quote": crate.spec(
                 version = "^1.0",
             ),
@@ -1217,6 +1220,9 @@ def external_crates_repository(name, static_openssl, cargo_lockfile, lockfile):
                     "zeroize_derive",
                 ],
             ),
+            "zstd": crate.spec(
+                version = "^0.12.3",
+            ),
         },
         splicing_config = splicing_config(
             resolver_version = "2",
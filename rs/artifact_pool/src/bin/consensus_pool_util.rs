@@ -2,23 +2,36 @@ use clap::{arg, Arg, Command};
 use ic_artifact_pool::{
     certification_pool::CertificationPoolImpl,
     consensus_pool::{PoolSectionOps, UncachedConsensusPoolImpl},
+    consensus_pool_analysis::{
+        analyze_heights, verify_chain_linkage, verify_signatures, BlockInfo, BlockShares,
+        PayloadSizes,
+    },
 };
 use ic_config::artifact_pool::ArtifactPoolConfig;
 use ic_interfaces::consensus_pool::*;
 use ic_logger::{LoggerImpl, ReplicaLogger};
 use ic_metrics::MetricsRegistry;
+use ic_registry_client::client::RegistryClientImpl;
+use ic_registry_local_store::LocalStoreImpl;
 use ic_types::{
-    consensus::{certification::CertificationMessage, CatchUpPackage, ConsensusMessageHashable},
+    consensus::{
+        certification::CertificationMessage, Block, CatchUpPackage, ConsensusMessageHashable,
+    },
+    crypto::CryptoHashOf,
     time::current_time,
+    Height, NodeId, PrincipalId, SubnetId,
 };
 use prost::Message;
 use serde::{Deserialize, Serialize};
 use serde_bytes_repr::{ByteFmtDeserializer, ByteFmtSerializer};
 use serde_json::{Deserializer, Serializer};
+use std::collections::BTreeSet;
 use std::convert::TryFrom;
 use std::io::BufRead;
 use std::io::Write;
 use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::Arc;
 
 fn main() {
     let mut app = Command::new("ic-consensus-pool-util")
@@ -50,6 +63,44 @@ fn main() {
                         .takes_value(true),
                 ),
         )
+        .subcommand(
+            Command::new("verify")
+                .about(
+                    "Verify that the blocks form a valid block chain and, if a registry \
+                     local store is given, verify the notarization and finalization signatures",
+                )
+                .arg(
+                    Arg::new("registry-local-store")
+                        .long("registry-local-store")
+                        .value_name("PATH")
+                        .help("PATH to the registry local store to verify the signatures with")
+                        .requires("subnet-id")
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::new("subnet-id")
+                        .long("subnet-id")
+                        .value_name("PRINCIPAL")
+                        .help("ID of the subnet the consensus pool belongs to")
+                        .takes_value(true),
+                )
+                .args(height_range_args()),
+        )
+        .subcommand(
+            Command::new("shares")
+                .about("Report per height which replicas contributed shares")
+                .args(height_range_args()),
+        )
+        .subcommand(
+            Command::new("forks")
+                .about("Report the heights at which more than one block was notarized")
+                .args(height_range_args()),
+        )
+        .subcommand(
+            Command::new("payload-sizes")
+                .about("Summarize the payload sizes of the blocks per height")
+                .args(height_range_args()),
+        )
         .arg(arg!(<PATH>       "PATH to the consensus pool directory"));
     let mut help = Vec::new();
     app.write_help(&mut help)
@@ -64,6 +115,14 @@ fn main() {
         import(path)
     } else if let Some(matches) = matches.subcommand_matches("export-cup-proto") {
         export_cup_proto(path, matches)
+    } else if let Some(matches) = matches.subcommand_matches("verify") {
+        verify(path, matches)
+    } else if let Some(matches) = matches.subcommand_matches("shares") {
+        shares(path, matches)
+    } else if let Some(matches) = matches.subcommand_matches("forks") {
+        forks(path, matches)
+    } else if let Some(matches) = matches.subcommand_matches("payload-sizes") {
+        payload_sizes(path, matches)
     } else {
         eprintln!(
             "{}",
@@ -272,3 +331,158 @@ fn export_cup_proto(path: &str, matches: &clap::ArgMatches) {
     file.write_all(&buf)
         .unwrap_or_else(|err| panic!("Cannot write to file {}: {:?}", filename, err));
 }
+
+fn height_range_args() -> [Arg<'static>; 2] {
+    [
+        Arg::new("from-height")
+            .long("from-height")
+            .value_name("HEIGHT")
+            .help("Lowest height to analyze")
+            .takes_value(true),
+        Arg::new("to-height")
+            .long("to-height")
+            .value_name("HEIGHT")
+            .help("Highest height to analyze")
+            .takes_value(true),
+    ]
+}
+
+fn parse_height_range(matches: &clap::ArgMatches) -> HeightRange {
+    let parse_height = |name: &str, default: u64| {
+        matches
+            .value_of(name)
+            .map_or(Height::from(default), |height| {
+                Height::from(
+                    height
+                        .parse::<u64>()
+                        .unwrap_or_else(|err| panic!("Invalid {} '{}': {:?}", name, height, err)),
+                )
+            })
+    };
+    HeightRange::new(
+        parse_height("from-height", 0),
+        parse_height("to-height", u64::MAX),
+    )
+}
+
+fn open_registry(local_store_path: &str) -> Arc<RegistryClientImpl> {
+    let data_provider = Arc::new(LocalStoreImpl::new(local_store_path));
+    let registry = Arc::new(RegistryClientImpl::new(data_provider, None));
+    registry
+        .poll_once()
+        .unwrap_or_else(|err| panic!("Cannot read registry local store: {:?}", err));
+    registry
+}
+
+fn verify(path: &str, matches: &clap::ArgMatches) {
+    let range = parse_height_range(matches);
+    let consensus_pool = open_consensus_pool(path, true);
+    let reports = analyze_heights(consensus_pool.validated(), &range);
+    let mut violations = verify_chain_linkage(&reports);
+    if let Some(local_store_path) = matches.value_of("registry-local-store") {
+        let subnet_id = matches.value_of("subnet-id").unwrap();
+        let subnet_id = SubnetId::from(
+            PrincipalId::from_str(subnet_id)
+                .unwrap_or_else(|err| panic!("Invalid subnet id '{}': {:?}", subnet_id, err)),
+        );
+        let registry = open_registry(local_store_path);
+        let crypto = ic_crypto_for_verification_only::new(registry.clone());
+        violations.extend(verify_signatures(
+            consensus_pool.validated(),
+            &range,
+            subnet_id,
+            registry.as_ref(),
+            &crypto,
+        ));
+    } else {
+        eprintln!("No registry local store given, skipping signature verification");
+    }
+    for violation in &violations {
+        println!("{}", to_string(violation));
+    }
+    eprintln!(
+        "Found {} integrity violation(s) in {} height(s)",
+        violations.len(),
+        reports.len()
+    );
+    if !violations.is_empty() {
+        std::process::exit(1);
+    }
+}
+
+#[derive(Serialize)]
+struct SharesAtHeight<'a> {
+    height: Height,
+    random_beacon_share_signers: &'a BTreeSet<NodeId>,
+    shares: &'a [BlockShares],
+}
+
+fn shares(path: &str, matches: &clap::ArgMatches) {
+    let consensus_pool = open_consensus_pool(path, true);
+    for report in analyze_heights(consensus_pool.validated(), &parse_height_range(matches)) {
+        println!(
+            "{}",
+            to_string(&SharesAtHeight {
+                height: report.height,
+                random_beacon_share_signers: &report.random_beacon_share_signers,
+                shares: &report.shares,
+            })
+        );
+    }
+}
+
+#[derive(Serialize)]
+struct ForkAtHeight<'a> {
+    height: Height,
+    notarized_blocks: Vec<&'a BlockInfo>,
+    finalized_blocks: &'a [CryptoHashOf<Block>],
+}
+
+fn forks(path: &str, matches: &clap::ArgMatches) {
+    let consensus_pool = open_consensus_pool(path, true);
+    for report in analyze_heights(consensus_pool.validated(), &parse_height_range(matches)) {
+        if report.is_fork() {
+            println!(
+                "{}",
+                to_string(&ForkAtHeight {
+                    height: report.height,
+                    notarized_blocks: report
+                        .notarized_blocks
+                        .iter()
+                        .filter_map(|hash| report.block(hash))
+                        .collect(),
+                    finalized_blocks: &report.finalized_blocks,
+                })
+            );
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct BlockPayloadSizes<'a> {
+    height: Height,
+    block: &'a CryptoHashOf<Block>,
+    notarized: bool,
+    finalized: bool,
+    is_summary: bool,
+    payload_sizes: &'a PayloadSizes,
+}
+
+fn payload_sizes(path: &str, matches: &clap::ArgMatches) {
+    let consensus_pool = open_consensus_pool(path, true);
+    for report in analyze_heights(consensus_pool.validated(), &parse_height_range(matches)) {
+        for block in &report.blocks {
+            println!(
+                "{}",
+                to_string(&BlockPayloadSizes {
+                    height: report.height,
+                    block: &block.hash,
+                    notarized: report.notarized_blocks.contains(&block.hash),
+                    finalized: report.finalized_blocks.contains(&block.hash),
+                    is_summary: block.is_summary,
+                    payload_sizes: &block.payload_sizes,
+                })
+            );
+        }
+    }
+}
@@ -0,0 +1,838 @@
+//! Offline analysis of the validated section of a consensus pool.
+//!
+//! The functions in this module are used by `ic-consensus-pool-util` to
+//! diagnose a stalled subnet from a copy of a replica's consensus pool: they
+//! check that the artifacts in the pool form a valid block chain, verify the
+//! notarization and finalization signatures, report which replicas
+//! contributed shares at each height, find forks, and summarize payload sizes.
+use ic_interfaces::consensus_pool::{HeightRange, PoolSection, ValidatedConsensusArtifact};
+use ic_interfaces::crypto::MultiSigVerifier;
+use ic_interfaces_registry::RegistryClient;
+use ic_protobuf::types::v1 as pb;
+use ic_registry_client_helpers::subnet::SubnetRegistry;
+use ic_types::{
+    consensus::{
+        get_committee_size, get_faults_tolerated, Block, FinalizationContent, HasHeight,
+        NotarizationContent,
+    },
+    crypto::{CryptoHashOf, Signed},
+    signature::MultiSignature,
+    Height, NodeId, RegistryVersion, SubnetId,
+};
+use prost::Message;
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// The encoded sizes in bytes of the parts of a block's payload.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct PayloadSizes {
+    pub total: usize,
+    pub ingress: usize,
+    pub xnet: usize,
+    pub self_validating: usize,
+    pub canister_http: usize,
+    pub dkg: usize,
+    pub ecdsa: usize,
+}
+
+impl From<&Block> for PayloadSizes {
+    fn from(block: &Block) -> Self {
+        fn encoded_len<M: Message>(message: &Option<M>) -> usize {
+            message.as_ref().map_or(0, M::encoded_len)
+        }
+        let block = pb::Block::from(block);
+        let mut sizes = PayloadSizes {
+            total: 0,
+            ingress: encoded_len(&block.ingress_payload),
+            xnet: encoded_len(&block.xnet_payload),
+            self_validating: encoded_len(&block.self_validating_payload),
+            canister_http: encoded_len(&block.canister_http_payload),
+            dkg: encoded_len(&block.dkg_payload),
+            ecdsa: encoded_len(&block.ecdsa_payload),
+        };
+        sizes.total = sizes.ingress
+            + sizes.xnet
+            + sizes.self_validating
+            + sizes.canister_http
+            + sizes.dkg
+            + sizes.ecdsa;
+        sizes
+    }
+}
+
+/// A block found in the pool, either as block proposal or as part of a
+/// catch-up package.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct BlockInfo {
+    pub hash: CryptoHashOf<Block>,
+    pub parent: CryptoHashOf<Block>,
+    pub rank: u64,
+    /// The block maker, or `None` if the block is from a catch-up package.
+    pub block_maker: Option<NodeId>,
+    pub registry_version: RegistryVersion,
+    pub is_summary: bool,
+    pub payload_sizes: PayloadSizes,
+}
+
+/// The replicas that contributed shares for a block.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct BlockShares {
+    pub block: CryptoHashOf<Block>,
+    pub notarization_share_signers: BTreeSet<NodeId>,
+    pub finalization_share_signers: BTreeSet<NodeId>,
+}
+
+/// The artifacts in the validated pool at a single height.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct HeightReport {
+    pub height: Height,
+    pub blocks: Vec<BlockInfo>,
+    /// Hashes of the notarized blocks. Blocks from catch-up packages are
+    /// considered notarized.
+    pub notarized_blocks: Vec<CryptoHashOf<Block>>,
+    /// Hashes of the finalized blocks. Blocks from catch-up packages are
+    /// considered finalized.
+    pub finalized_blocks: Vec<CryptoHashOf<Block>>,
+    pub random_beacon_share_signers: BTreeSet<NodeId>,
+    pub shares: Vec<BlockShares>,
+}
+
+impl HeightReport {
+    /// Returns the block with the given hash if it is in the pool.
+    pub fn block(&self, hash: &CryptoHashOf<Block>) -> Option<&BlockInfo> {
+        self.blocks.iter().find(|block| &block.hash == hash)
+    }
+
+    /// Returns whether more than one block was notarized at this height.
+    pub fn is_fork(&self) -> bool {
+        self.notarized_blocks.len() > 1
+    }
+}
+
+/// A violation of the integrity of the consensus pool.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub enum IntegrityViolation {
+    /// A notarization or finalization refers to a block that is not in the
+    /// pool.
+    MissingBlock {
+        height: Height,
+        block: CryptoHashOf<Block>,
+        artifact: &'static str,
+    },
+    /// The parent of a notarized block is not notarized.
+    UnnotarizedParent {
+        height: Height,
+        block: CryptoHashOf<Block>,
+        parent: CryptoHashOf<Block>,
+    },
+    /// The parent of a finalized block is not the block finalized at the
+    /// previous height.
+    BrokenFinalizedChain {
+        height: Height,
+        block: CryptoHashOf<Block>,
+        parent: CryptoHashOf<Block>,
+    },
+    /// More than one block was finalized at the same height.
+    ConflictingFinalizations {
+        height: Height,
+        blocks: Vec<CryptoHashOf<Block>>,
+    },
+    /// The registry version to verify the signatures at a height with could
+    /// not be determined, because there is no suitable DKG summary block in
+    /// the pool.
+    UnknownRegistryVersion { height: Height },
+    /// A notarization or finalization signature is invalid.
+    InvalidSignature {
+        height: Height,
+        block: CryptoHashOf<Block>,
+        artifact: &'static str,
+        error: String,
+    },
+}
+
+/// Returns a report for every height in `range` at which the pool contains a
+/// block, notarization, finalization or share.
+pub fn analyze_heights(
+    pool: &dyn PoolSection<ValidatedConsensusArtifact>,
+    range: &HeightRange,
+) -> Vec<HeightReport> {
+    let height_range = || HeightRange::new(range.min, range.max);
+    let mut reports: BTreeMap<Height, HeightReport> = BTreeMap::new();
+    for cup in pool.catch_up_package().get_by_height_range(height_range()) {
+        let report = height_report(&mut reports, cup.height());
+        let hash = cup.content.block.get_hash().clone();
+        report
+            .blocks
+            .push(block_info(hash.clone(), cup.content.block.as_ref(), None));
+        report.notarized_blocks.push(hash.clone());
+        report.finalized_blocks.push(hash);
+    }
+    for proposal in pool.block_proposal().get_by_height_range(height_range()) {
+        let report = height_report(&mut reports, proposal.height());
+        let hash = proposal.content.get_hash().clone();
+        if report.block(&hash).is_none() {
+            report.blocks.push(block_info(
+                hash,
+                proposal.content.as_ref(),
+                Some(proposal.signature.signer),
+            ));
+        }
+    }
+    for notarization in pool.notarization().get_by_height_range(height_range()) {
+        let report = height_report(&mut reports, notarization.height());
+        push_unique(&mut report.notarized_blocks, notarization.content.block);
+    }
+    for finalization in pool.finalization().get_by_height_range(height_range()) {
+        let report = height_report(&mut reports, finalization.height());
+        push_unique(&mut report.finalized_blocks, finalization.content.block);
+    }
+    for share in pool
+        .random_beacon_share()
+        .get_by_height_range(height_range())
+    {
+        let report = height_report(&mut reports, share.height());
+        report
+            .random_beacon_share_signers
+            .insert(share.signature.signer);
+    }
+    for share in pool
+        .notarization_share()
+        .get_by_height_range(height_range())
+    {
+        let report = height_report(&mut reports, share.height());
+        block_shares(report, &share.content.block)
+            .notarization_share_signers
+            .insert(share.signature.signer);
+    }
+    for share in pool
+        .finalization_share()
+        .get_by_height_range(height_range())
+    {
+        let report = height_report(&mut reports, share.height());
+        block_shares(report, &share.content.block)
+            .finalization_share_signers
+            .insert(share.signature.signer);
+    }
+    reports.into_values().collect()
+}
+
+/// Checks that the blocks in the given reports form a valid block chain.
+///
+/// Only links between heights that are both contained in `reports` are
+/// checked, so the parents of the blocks at the lowest height, which are
+/// usually purged, are not reported as missing.
+pub fn verify_chain_linkage(reports: &[HeightReport]) -> Vec<IntegrityViolation> {
+    let mut violations = Vec::new();
+    let reports_by_height: BTreeMap<Height, &HeightReport> = reports
+        .iter()
+        .map(|report| (report.height, report))
+        .collect();
+    for report in reports {
+        let height = report.height;
+        for (artifact, hashes) in [
+            ("Notarization", &report.notarized_blocks),
+            ("Finalization", &report.finalized_blocks),
+        ] {
+            for hash in hashes {
+                if report.block(hash).is_none() {
+                    violations.push(IntegrityViolation::MissingBlock {
+                        height,
+                        block: hash.clone(),
+                        artifact,
+                    });
+                }
+            }
+        }
+        if report.finalized_blocks.len() > 1 {
+            violations.push(IntegrityViolation::ConflictingFinalizations {
+                height,
+                blocks: report.finalized_blocks.clone(),
+            });
+        }
+        let previous_report = match height
+            .get()
+            .checked_sub(1)
+            .and_then(|previous_height| reports_by_height.get(&Height::from(previous_height)))
+        {
+            Some(previous_report) => previous_report,
+            None => continue,
+        };
+        for hash in &report.notarized_blocks {
+            let block = match report.block(hash) {
+                Some(block) => block,
+                None => continue,
+            };
+            if !previous_report.notarized_blocks.contains(&block.parent) {
+                violations.push(IntegrityViolation::UnnotarizedParent {
+                    height,
+                    block: hash.clone(),
+                    parent: block.parent.clone(),
+                });
+            }
+            if report.finalized_blocks.contains(hash)
+                && !previous_report.finalized_blocks.is_empty()
+                && !previous_report.finalized_blocks.contains(&block.parent)
+            {
+                violations.push(IntegrityViolation::BrokenFinalizedChain {
+                    height,
+                    block: hash.clone(),
+                    parent: block.parent.clone(),
+                });
+            }
+        }
+    }
+    violations
+}
+
+/// Verifies the signatures of all notarizations and finalizations in
+/// `range`.
+///
+/// The signatures are verified at the registry version that consensus uses
+/// for the respective height, which is determined from the DKG summary
+/// blocks in the pool. At that registry version, all signers must be members
+/// of the subnet and there must be at least as many of them as the
+/// notarization threshold.
+pub fn verify_signatures<C>(
+    pool: &dyn PoolSection<ValidatedConsensusArtifact>,
+    range: &HeightRange,
+    subnet_id: SubnetId,
+    registry: &dyn RegistryClient,
+    crypto: &C,
+) -> Vec<IntegrityViolation>
+where
+    C: MultiSigVerifier<NotarizationContent> + MultiSigVerifier<FinalizationContent>,
+{
+    let height_range = || HeightRange::new(range.min, range.max);
+    let summary_blocks = summary_blocks(pool);
+    let mut violations = Vec::new();
+    let mut unknown_registry_versions = BTreeSet::new();
+    for notarization in pool.notarization().get_by_height_range(height_range()) {
+        let height = notarization.height();
+        match registry_version_at_height(&summary_blocks, height) {
+            Some(registry_version) => {
+                if let Err(error) =
+                    verify_multi_sig(crypto, subnet_id, registry, &notarization, registry_version)
+                {
+                    violations.push(IntegrityViolation::InvalidSignature {
+                        height,
+                        block: notarization.content.block,
+                        artifact: "Notarization",
+                        error,
+                    });
+                }
+            }
+            None => {
+                unknown_registry_versions.insert(height);
+            }
+        }
+    }
+    for finalization in pool.finalization().get_by_height_range(height_range()) {
+        let height = finalization.height();
+        match registry_version_at_height(&summary_blocks, height) {
+            Some(registry_version) => {
+                if let Err(error) =
+                    verify_multi_sig(crypto, subnet_id, registry, &finalization, registry_version)
+                {
+                    violations.push(IntegrityViolation::InvalidSignature {
+                        height,
+                        block: finalization.content.block,
+                        artifact: "Finalization",
+                        error,
+                    });
+                }
+            }
+            None => {
+                unknown_registry_versions.insert(height);
+            }
+        }
+    }
+    violations.extend(
+        unknown_registry_versions
+            .into_iter()
+            .map(|height| IntegrityViolation::UnknownRegistryVersion { height }),
+    );
+    violations
+}
+
+/// Verifies that the signers of `artifact` are a large enough subset of the
+/// subnet's nodes at `registry_version`, and that the multi-signature is
+/// valid. Notarizations and finalizations are both signed by the notary
+/// committee, which consists of all nodes of the subnet.
+fn verify_multi_sig<T, C: MultiSigVerifier<T>>(
+    crypto: &C,
+    subnet_id: SubnetId,
+    registry: &dyn RegistryClient,
+    artifact: &Signed<T, MultiSignature<T>>,
+    registry_version: RegistryVersion,
+) -> Result<(), String> {
+    let committee: BTreeSet<NodeId> = registry
+        .get_node_ids_on_subnet(subnet_id, registry_version)
+        .map_err(|err| {
+            format!(
+                "Failed to get the nodes of subnet {} at registry version {}: {}",
+                subnet_id, registry_version, err
+            )
+        })?
+        .ok_or_else(|| {
+            format!(
+                "Subnet {} does not exist at registry version {}",
+                subnet_id, registry_version
+            )
+        })?
+        .into_iter()
+        .collect();
+    let signers: BTreeSet<NodeId> = artifact.signature.signers.iter().cloned().collect();
+    if signers.len() != artifact.signature.signers.len() {
+        return Err("Duplicate signers".to_string());
+    }
+    if let Some(signer) = signers.difference(&committee).next() {
+        return Err(format!(
+            "Signer {} is not a member of subnet {} at registry version {}",
+            signer, subnet_id, registry_version
+        ));
+    }
+    let threshold = notarization_threshold(committee.len());
+    if signers.len() < threshold {
+        return Err(format!(
+            "Only {} signer(s), but the threshold at registry version {} is {}",
+            signers.len(),
+            registry_version,
+            threshold
+        ));
+    }
+    crypto
+        .verify_multi_sig_combined(
+            &artifact.signature.signature,
+            &artifact.content,
+            signers,
+            registry_version,
+        )
+        .map_err(|err| err.to_string())
+}
+
+/// Returns the notarization threshold of a subnet with `subnet_size` nodes,
+/// which also applies to finalizations. Consensus computes it the same way in
+/// `get_notarization_threshold_for_subnet_of_size`.
+fn notarization_threshold(subnet_size: usize) -> usize {
+    let committee_size = get_committee_size(subnet_size);
+    committee_size - get_faults_tolerated(committee_size)
+}
+
+/// Returns all DKG summary blocks in the pool, both from catch-up packages
+/// and from block proposals, sorted by height.
+fn summary_blocks(pool: &dyn PoolSection<ValidatedConsensusArtifact>) -> Vec<Block> {
+    let mut summary_blocks: BTreeMap<Height, Block> = pool
+        .catch_up_package()
+        .get_all()
+        .map(|cup| (cup.height(), cup.content.block.as_ref().clone()))
+        .collect();
+    for proposal in pool.block_proposal().get_all() {
+        let block = proposal.content.as_ref();
+        if block.payload.is_summary() {
+            summary_blocks
+                .entry(block.height)
+                .or_insert_with(|| block.clone());
+        }
+    }
+    summary_blocks.into_values().collect()
+}
+
+/// Returns the registry version that is active at `height`, following the
+/// same rules as consensus.
+fn registry_version_at_height(summary_blocks: &[Block], height: Height) -> Option<RegistryVersion> {
+    summary_blocks
+        .iter()
+        .rev()
+        .filter(|summary_block| summary_block.height <= height)
+        .find_map(|summary_block| {
+            let dkg_summary = &summary_block.payload.as_ref().as_summary().dkg;
+            if dkg_summary.current_interval_includes(height) {
+                Some(dkg_summary.registry_version)
+            } else if dkg_summary.next_interval_includes(height) {
+                Some(summary_block.context.registry_version)
+            } else {
+                None
+            }
+        })
+}
+
+fn height_report(
+    reports: &mut BTreeMap<Height, HeightReport>,
+    height: Height,
+) -> &mut HeightReport {
+    reports.entry(height).or_insert_with(|| HeightReport {
+        height,
+        blocks: Vec::new(),
+        notarized_blocks: Vec::new(),
+        finalized_blocks: Vec::new(),
+        random_beacon_share_signers: BTreeSet::new(),
+        shares: Vec::new(),
+    })
+}
+
+fn block_shares<'a>(
+    report: &'a mut HeightReport,
+    block: &CryptoHashOf<Block>,
+) -> &'a mut BlockShares {
+    let index = match report
+        .shares
+        .iter()
+        .position(|shares| &shares.block == block)
+    {
+        Some(index) => index,
+        None => {
+            report.shares.push(BlockShares {
+                block: block.clone(),
+                notarization_share_signers: BTreeSet::new(),
+                finalization_share_signers: BTreeSet::new(),
+            });
+            report.shares.len() - 1
+        }
+    };
+    &mut report.shares[index]
+}
+
+fn block_info(hash: CryptoHashOf<Block>, block: &Block, block_maker: Option<NodeId>) -> BlockInfo {
+    BlockInfo {
+        hash,
+        parent: block.parent.clone(),
+        rank: block.rank.0,
+        block_maker,
+        registry_version: block.context.registry_version,
+        is_summary: block.payload.is_summary(),
+        payload_sizes: PayloadSizes::from(block),
+    }
+}
+
+fn push_unique<T: PartialEq>(values: &mut Vec<T>, value: T) {
+    if !values.contains(&value) {
+        values.push(value);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ic_interfaces::consensus_pool::ConsensusPool;
+    use ic_test_artifact_pool::consensus_pool::TestConsensusPool;
+    use ic_test_utilities::{
+        consensus::fake::*,
+        crypto::CryptoReturningOk,
+        state_manager::FakeStateManager,
+        types::ids::{node_test_id, subnet_test_id},
+        FastForwardTimeSource,
+    };
+    use ic_test_utilities_registry::{setup_registry, SubnetRecordBuilder};
+    use ic_types::{
+        consensus::{BlockProposal, Rank},
+        crypto::{CombinedMultiSig, CombinedMultiSigOf},
+    };
+    use std::sync::Arc;
+
+    fn with_test_pool<F: FnOnce(TestConsensusPool)>(test: F) {
+        with_test_pool_and_registry(1, |pool, _| test(pool))
+    }
+
+    // Runs `test` with a pool of a subnet with `subnet_size` nodes and the
+    // registry of that subnet.
+    fn with_test_pool_and_registry<F: FnOnce(TestConsensusPool, Arc<dyn RegistryClient>)>(
+        subnet_size: u64,
+        test: F,
+    ) {
+        ic_test_utilities::artifact_pool_config::with_test_pool_config(|pool_config| {
+            let committee = committee(subnet_size);
+            let subnet_records = vec![(
+                1,
+                SubnetRecordBuilder::from(&committee)
+                    .with_dkg_interval_length(9)
+                    .build(),
+            )];
+            let registry = setup_registry(subnet_test_id(1), subnet_records);
+            let pool = TestConsensusPool::new(
+                subnet_test_id(1),
+                pool_config,
+                FastForwardTimeSource::new(),
+                registry.clone(),
+                Arc::new(CryptoReturningOk::default()),
+                Arc::new(FakeStateManager::new()),
+                None,
+            );
+            test(pool, registry)
+        })
+    }
+
+    fn committee(subnet_size: u64) -> Vec<NodeId> {
+        (0..subnet_size).map(node_test_id).collect()
+    }
+
+    fn signed_by<T>(content: T, signers: &[NodeId]) -> Signed<T, MultiSignature<T>> {
+        Signed {
+            content,
+            signature: MultiSignature {
+                signers: signers.to_vec(),
+                signature: CombinedMultiSigOf::new(CombinedMultiSig(vec![])),
+            },
+        }
+    }
+
+    // Advances the pool by `rounds` heights, notarizing and finalizing each new
+    // block with signatures of `signers`.
+    fn advance_rounds_signed_by(pool: &mut TestConsensusPool, rounds: u64, signers: &[NodeId]) {
+        for _ in 0..rounds {
+            let proposal = pool.make_next_block();
+            let height = proposal.height();
+            let hash = proposal.content.get_hash().clone();
+            pool.insert_validated(proposal);
+            pool.insert_validated(pool.make_next_beacon());
+            pool.insert_validated(signed_by(
+                NotarizationContent::new(height, hash.clone()),
+                signers,
+            ));
+            pool.insert_validated(signed_by(FinalizationContent::new(height, hash), signers));
+        }
+    }
+
+    fn verify_test_pool_signatures(
+        pool: &TestConsensusPool,
+        registry: &dyn RegistryClient,
+    ) -> Vec<IntegrityViolation> {
+        verify_signatures(
+            pool.validated(),
+            &all_heights(),
+            subnet_test_id(1),
+            registry,
+            &CryptoReturningOk::default(),
+        )
+    }
+
+    fn all_heights() -> HeightRange {
+        HeightRange::new(Height::from(0), Height::from(100))
+    }
+
+    fn report_at(reports: &[HeightReport], height: u64) -> &HeightReport {
+        reports
+            .iter()
+            .find(|report| report.height == Height::from(height))
+            .unwrap_or_else(|| panic!("missing report at height {}", height))
+    }
+
+    // Returns two different proposals for the next height.
+    fn make_competing_proposals(pool: &TestConsensusPool) -> (BlockProposal, BlockProposal) {
+        let proposal = pool.make_next_block();
+        let mut block = Block::from(proposal.clone());
+        block.rank = Rank(1);
+        (proposal, BlockProposal::fake(block, node_test_id(1)))
+    }
+
+    #[test]
+    fn should_report_blocks_and_shares_per_height() {
+        with_test_pool(|mut pool| {
+            pool.prepare_round()
+                .with_random_beacon_shares(1)
+                .with_notarization_shares(1)
+                .with_finalization_shares(1)
+                .advance();
+
+            let reports = analyze_heights(pool.validated(), &all_heights());
+
+            let genesis = report_at(&reports, 0);
+            assert_eq!(genesis.blocks.len(), 1);
+            assert!(genesis.blocks[0].is_summary);
+            assert_eq!(genesis.blocks[0].block_maker, None);
+            assert!(genesis.blocks[0].payload_sizes.dkg > 0);
+            let report = report_at(&reports, 1);
+            assert_eq!(report.blocks.len(), 1);
+            let block = &report.blocks[0];
+            assert_eq!(block.parent, genesis.blocks[0].hash);
+            assert_eq!(block.block_maker, Some(node_test_id(0)));
+            assert_eq!(report.notarized_blocks, vec![block.hash.clone()]);
+            assert_eq!(report.finalized_blocks, vec![block.hash.clone()]);
+            assert_eq!(
+                report.random_beacon_share_signers,
+                BTreeSet::from([node_test_id(0)])
+            );
+            assert_eq!(
+                report.shares,
+                vec![BlockShares {
+                    block: block.hash.clone(),
+                    notarization_share_signers: BTreeSet::from([node_test_id(0)]),
+                    finalization_share_signers: BTreeSet::from([node_test_id(0)]),
+                }]
+            );
+            assert!(!report.is_fork());
+        })
+    }
+
+    #[test]
+    fn should_find_no_violations_in_valid_pool() {
+        with_test_pool_and_registry(4, |mut pool, registry| {
+            advance_rounds_signed_by(&mut pool, 12, &committee(3));
+
+            let reports = analyze_heights(pool.validated(), &all_heights());
+
+            assert_eq!(reports.len(), 13);
+            assert!(reports.iter().all(|report| !report.is_fork()));
+            assert_eq!(verify_chain_linkage(&reports), vec![]);
+            assert_eq!(
+                verify_test_pool_signatures(&pool, registry.as_ref()),
+                vec![]
+            );
+        })
+    }
+
+    #[test]
+    fn should_reject_signatures_below_threshold() {
+        with_test_pool_and_registry(4, |mut pool, registry| {
+            advance_rounds_signed_by(&mut pool, 1, &[node_test_id(0)]);
+            let block = report_at(&analyze_heights(pool.validated(), &all_heights()), 1)
+                .notarized_blocks[0]
+                .clone();
+
+            let error = "Only 1 signer(s), but the threshold at registry version 1 is 3";
+            assert_eq!(
+                verify_test_pool_signatures(&pool, registry.as_ref()),
+                vec![
+                    IntegrityViolation::InvalidSignature {
+                        height: Height::from(1),
+                        block: block.clone(),
+                        artifact: "Notarization",
+                        error: error.to_string(),
+                    },
+                    IntegrityViolation::InvalidSignature {
+                        height: Height::from(1),
+                        block,
+                        artifact: "Finalization",
+                        error: error.to_string(),
+                    },
+                ]
+            );
+        })
+    }
+
+    #[test]
+    fn should_reject_signers_outside_of_subnet() {
+        with_test_pool_and_registry(4, |mut pool, registry| {
+            let signers = [node_test_id(0), node_test_id(1), node_test_id(7)];
+            advance_rounds_signed_by(&mut pool, 1, &signers);
+
+            let violations = verify_test_pool_signatures(&pool, registry.as_ref());
+
+            assert_eq!(violations.len(), 2);
+            for violation in violations {
+                match violation {
+                    IntegrityViolation::InvalidSignature { error, .. } => assert_eq!(
+                        error,
+                        format!(
+                            "Signer {} is not a member of subnet {} at registry version 1",
+                            node_test_id(7),
+                            subnet_test_id(1)
+                        )
+                    ),
+                    violation => panic!("Unexpected violation {:?}", violation),
+                }
+            }
+        })
+    }
+
+    #[test]
+    fn should_find_fork() {
+        with_test_pool(|mut pool| {
+            let (proposal, competing_proposal) = make_competing_proposals(&pool);
+            for proposal in [&proposal, &competing_proposal] {
+                pool.insert_validated(proposal.clone());
+                pool.notarize(proposal);
+            }
+
+            let reports = analyze_heights(pool.validated(), &all_heights());
+
+            let report = report_at(&reports, 1);
+            assert!(report.is_fork());
+            assert_eq!(report.blocks.len(), 2);
+            assert_eq!(verify_chain_linkage(&reports), vec![]);
+        })
+    }
+
+    #[test]
+    fn should_detect_conflicting_finalizations() {
+        with_test_pool(|mut pool| {
+            let (proposal, competing_proposal) = make_competing_proposals(&pool);
+            for proposal in [&proposal, &competing_proposal] {
+                pool.insert_validated(proposal.clone());
+                pool.notarize(proposal);
+                pool.finalize(proposal);
+            }
+
+            let reports = analyze_heights(pool.validated(), &all_heights());
+
+            assert_eq!(
+                verify_chain_linkage(&reports),
+                vec![IntegrityViolation::ConflictingFinalizations {
+                    height: Height::from(1),
+                    blocks: report_at(&reports, 1).finalized_blocks.clone(),
+                }]
+            );
+        })
+    }
+
+    #[test]
+    fn should_detect_notarization_of_missing_block() {
+        with_test_pool(|mut pool| {
+            let proposal = pool.make_next_block();
+            pool.notarize(&proposal);
+
+            let reports = analyze_heights(pool.validated(), &all_heights());
+
+            assert_eq!(
+                verify_chain_linkage(&reports),
+                vec![IntegrityViolation::MissingBlock {
+                    height: Height::from(1),
+                    block: proposal.content.get_hash().clone(),
+                    artifact: "Notarization",
+                }]
+            );
+        })
+    }
+
+    #[test]
+    fn should_detect_notarized_block_with_unnotarized_parent() {
+        with_test_pool(|mut pool| {
+            let parent = pool.make_next_block();
+            pool.insert_validated(parent.clone());
+            let child = pool.make_next_block_from_parent(parent.as_ref());
+            pool.insert_validated(child.clone());
+            pool.notarize(&child);
+
+            let reports = analyze_heights(pool.validated(), &all_heights());
+
+            assert_eq!(
+                verify_chain_linkage(&reports),
+                vec![IntegrityViolation::UnnotarizedParent {
+                    height: Height::from(2),
+                    block: child.content.get_hash().clone(),
+                    parent: parent.content.get_hash().clone(),
+                }]
+            );
+        })
+    }
+
+    #[test]
+    fn should_only_report_heights_in_range() {
+        with_test_pool(|mut pool| {
+            pool.advance_round_normal_operation_n(5);
+
+            let reports = analyze_heights(
+                pool.validated(),
+                &HeightRange::new(Height::from(2), Height::from(3)),
+            );
+
+            assert_eq!(
+                reports
+                    .iter()
+                    .map(|report| report.height.get())
+                    .collect::<Vec<_>>(),
+                vec![2, 3]
+            );
+            assert_eq!(verify_chain_linkage(&reports), vec![]);
+        })
+    }
+}
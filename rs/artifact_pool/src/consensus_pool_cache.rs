@@ -573,6 +573,11 @@ mod test {
                     next_in_creation: ecdsa::KeyTranscriptCreation::Begin,
                     key_id: EcdsaKeyId::from_str("Secp256k1:some_key").unwrap(),
                 },
+                schnorr_key_transcript: None,
+                ongoing_schnorr_signatures: BTreeMap::new(),
+                available_schnorr_presigs: BTreeMap::new(),
+                schnorr_presigs_in_creation: BTreeMap::new(),
+                ongoing_vetkd_requests: BTreeMap::new(),
             })),
         );
         assert_eq!(chain.len(), 2);
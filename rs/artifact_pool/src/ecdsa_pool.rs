@@ -24,7 +24,7 @@ use ic_types::consensus::{
     catchup::CUPWithOriginalProtobuf,
     ecdsa::{
         ecdsa_msg_id, EcdsaComplaint, EcdsaMessage, EcdsaMessageType, EcdsaOpening, EcdsaPrefixOf,
-        EcdsaSigShare, EcdsaStats, EcdsaStatsNoOp,
+        EcdsaSigShare, EcdsaStats, EcdsaStatsNoOp, SchnorrSigShare, VetKdKeyShare,
     },
 };
 use ic_types::crypto::canister_threshold_sig::idkg::{IDkgDealingSupport, SignedIDkgDealing};
@@ -228,6 +228,34 @@ impl EcdsaPoolSection for InMemoryEcdsaPoolSection {
         object_pool.iter_by_prefix(prefix)
     }
 
+    fn schnorr_signature_shares(
+        &self,
+    ) -> Box<dyn Iterator<Item = (EcdsaMessageId, SchnorrSigShare)> + '_> {
+        let object_pool = self.get_pool(EcdsaMessageType::SchnorrSigShare);
+        object_pool.iter()
+    }
+
+    fn schnorr_signature_shares_by_prefix(
+        &self,
+        prefix: EcdsaPrefixOf<SchnorrSigShare>,
+    ) -> Box<dyn Iterator<Item = (EcdsaMessageId, SchnorrSigShare)> + '_> {
+        let object_pool = self.get_pool(EcdsaMessageType::SchnorrSigShare);
+        object_pool.iter_by_prefix(prefix)
+    }
+
+    fn vetkd_key_shares(&self) -> Box<dyn Iterator<Item = (EcdsaMessageId, VetKdKeyShare)> + '_> {
+        let object_pool = self.get_pool(EcdsaMessageType::VetKdKeyShare);
+        object_pool.iter()
+    }
+
+    fn vetkd_key_shares_by_prefix(
+        &self,
+        prefix: EcdsaPrefixOf<VetKdKeyShare>,
+    ) -> Box<dyn Iterator<Item = (EcdsaMessageId, VetKdKeyShare)> + '_> {
+        let object_pool = self.get_pool(EcdsaMessageType::VetKdKeyShare);
+        object_pool.iter_by_prefix(prefix)
+    }
+
     fn complaints(&self) -> Box<dyn Iterator<Item = (EcdsaMessageId, EcdsaComplaint)> + '_> {
         let object_pool = self.get_pool(EcdsaMessageType::Complaint);
         object_pool.iter()
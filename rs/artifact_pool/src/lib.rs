@@ -1,6 +1,7 @@
 pub mod canister_http_pool;
 pub mod certification_pool;
 pub mod consensus_pool;
+pub mod consensus_pool_analysis;
 mod consensus_pool_cache;
 pub mod dkg_pool;
 pub mod ecdsa_pool;
@@ -21,7 +21,7 @@ use ic_types::{
         dkg,
         ecdsa::{
             ecdsa_msg_id, EcdsaComplaint, EcdsaMessage, EcdsaMessageType, EcdsaOpening,
-            EcdsaPrefix, EcdsaPrefixOf, EcdsaSigShare,
+            EcdsaPrefix, EcdsaPrefixOf, EcdsaSigShare, SchnorrSigShare, VetKdKeyShare,
         },
         BlockPayload, BlockProposal, CatchUpPackage, CatchUpPackageShare, ConsensusMessage,
         ConsensusMessageHash, ConsensusMessageHashable, Finalization, FinalizationShare, HasHeight,
@@ -1579,6 +1579,8 @@ impl PersistentEcdsaPoolSection {
             EcdsaMessageType::Dealing => TypeKey::new("ECD"),
             EcdsaMessageType::DealingSupport => TypeKey::new("ECS"),
             EcdsaMessageType::SigShare => TypeKey::new("ECI"),
+            EcdsaMessageType::SchnorrSigShare => TypeKey::new("ECT"),
+            EcdsaMessageType::VetKdKeyShare => TypeKey::new("ECV"),
             EcdsaMessageType::Complaint => TypeKey::new("ECC"),
             EcdsaMessageType::Opening => TypeKey::new("ECO"),
         }
@@ -1640,6 +1642,34 @@ impl EcdsaPoolSection for PersistentEcdsaPoolSection {
         message_db.iter(Some(prefix))
     }
 
+    fn schnorr_signature_shares(
+        &self,
+    ) -> Box<dyn Iterator<Item = (EcdsaMessageId, SchnorrSigShare)> + '_> {
+        let message_db = self.get_message_db(EcdsaMessageType::SchnorrSigShare);
+        message_db.iter(None)
+    }
+
+    fn schnorr_signature_shares_by_prefix(
+        &self,
+        prefix: EcdsaPrefixOf<SchnorrSigShare>,
+    ) -> Box<dyn Iterator<Item = (EcdsaMessageId, SchnorrSigShare)> + '_> {
+        let message_db = self.get_message_db(EcdsaMessageType::SchnorrSigShare);
+        message_db.iter(Some(prefix))
+    }
+
+    fn vetkd_key_shares(&self) -> Box<dyn Iterator<Item = (EcdsaMessageId, VetKdKeyShare)> + '_> {
+        let message_db = self.get_message_db(EcdsaMessageType::VetKdKeyShare);
+        message_db.iter(None)
+    }
+
+    fn vetkd_key_shares_by_prefix(
+        &self,
+        prefix: EcdsaPrefixOf<VetKdKeyShare>,
+    ) -> Box<dyn Iterator<Item = (EcdsaMessageId, VetKdKeyShare)> + '_> {
+        let message_db = self.get_message_db(EcdsaMessageType::VetKdKeyShare);
+        message_db.iter(Some(prefix))
+    }
+
     fn complaints(&self) -> Box<dyn Iterator<Item = (EcdsaMessageId, EcdsaComplaint)> + '_> {
         let message_db = self.get_message_db(EcdsaMessageType::Complaint);
         message_db.iter(None)
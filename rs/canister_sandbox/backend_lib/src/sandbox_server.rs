@@ -160,6 +160,7 @@ mod tests {
     };
     use ic_test_utilities::types::ids::{canister_test_id, subnet_test_id, user_test_id};
     use ic_types::{
+        canister_log::CanisterLog,
         ingress::WasmResult,
         messages::CallContextId,
         methods::{FuncRef, WasmMethod},
@@ -193,6 +194,7 @@ mod tests {
         SandboxSafeSystemState::new_internal(
             canister_test_id(0),
             user_test_id(0).get(),
+            BTreeSet::from([user_test_id(0).get()]),
             CanisterStatusView::Running,
             NumSeconds::from(3600),
             MemoryAllocation::BestEffort,
@@ -212,6 +214,8 @@ mod tests {
             SchedulerConfig::application_subnet().dirty_page_overhead,
             CanisterTimer::Inactive,
             0,
+            BTreeMap::new(),
+            CanisterLog::default(),
         )
     }
 
@@ -65,6 +65,10 @@ pub struct Request {
     pub method_payload: Bytes,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub cycles_payment: Option<Cycles>,
+    /// Deadline in seconds since the Unix epoch. Only encoded for best-effort
+    /// requests, so the encoding of guaranteed response requests is unchanged.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deadline: Option<u32>,
 }
 
 /// Canonical representation of `ic_types::messages::Response`.
@@ -80,6 +84,10 @@ pub struct Response {
     pub response_payload: Payload,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub cycles_refund: Option<Cycles>,
+    /// Deadline in seconds since the Unix epoch. Only encoded for best-effort
+    /// responses, so the encoding of guaranteed responses is unchanged.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deadline: Option<u32>,
 }
 
 /// Canonical representation of `ic_types::funds::Cycles`.
@@ -247,6 +255,7 @@ impl From<(&ic_types::messages::Request, CertificationVersion)> for Request {
             method_name: request.method_name.clone(),
             method_payload: request.method_payload.clone(),
             cycles_payment: None,
+            deadline: encode_deadline(request.deadline),
         }
     }
 }
@@ -268,6 +277,7 @@ impl TryFrom<Request> for ic_types::messages::Request {
             payment,
             method_name: request.method_name,
             method_payload: request.method_payload,
+            deadline: decode_deadline(request.deadline),
         })
     }
 }
@@ -287,6 +297,7 @@ impl From<(&ic_types::messages::Response, CertificationVersion)> for Response {
             refund: funds,
             response_payload: (&response.response_payload, certification_version).into(),
             cycles_refund: None,
+            deadline: encode_deadline(response.deadline),
         }
     }
 }
@@ -307,10 +318,26 @@ impl TryFrom<Response> for ic_types::messages::Response {
             originator_reply_callback: response.originator_reply_callback.into(),
             refund,
             response_payload: response.response_payload.try_into()?,
+            deadline: decode_deadline(response.deadline),
         })
     }
 }
 
+/// Encodes a message deadline, omitting it for guaranteed response messages.
+fn encode_deadline(deadline: ic_types::CoarseTime) -> Option<u32> {
+    match deadline {
+        ic_types::messages::NO_DEADLINE => None,
+        deadline => Some(deadline.as_secs_since_unix_epoch()),
+    }
+}
+
+/// Decodes an optional message deadline, defaulting to `NO_DEADLINE`.
+fn decode_deadline(deadline: Option<u32>) -> ic_types::CoarseTime {
+    deadline
+        .map(ic_types::CoarseTime::from_secs_since_unix_epoch)
+        .unwrap_or(ic_types::messages::NO_DEADLINE)
+}
+
 impl From<(&ic_types::funds::Cycles, CertificationVersion)> for Cycles {
     fn from(
         (cycles, _certification_version): (&ic_types::funds::Cycles, CertificationVersion),
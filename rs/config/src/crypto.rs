@@ -4,8 +4,11 @@
 #![allow(clippy::redundant_closure)]
 #![allow(clippy::unit_arg)]
 
+use ic_base_types::PrincipalId;
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
 use std::fs;
+use std::net::SocketAddr;
 use std::os::unix::fs::PermissionsExt;
 use std::path::Path;
 use std::path::PathBuf;
@@ -40,6 +43,81 @@ impl Default for CspVaultType {
     }
 }
 
+/// Encryption at rest of the secret key stores in `crypto_root`.
+///
+/// If encryption is enabled, the secret key stores are encrypted with a
+/// key-encryption key (KEK) obtained from the configured source. Existing
+/// plaintext stores are encrypted when they are opened for the first time.
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+#[cfg_attr(test, derive(Arbitrary))]
+pub enum SecretKeyStoreEncryption {
+    Plaintext,
+    /// The KEK is read from the file at the given path, which must contain
+    /// exactly 32 bytes.
+    #[cfg_attr(
+        test,
+        proptest(
+            strategy = "any::<String>().prop_map(|x| SecretKeyStoreEncryption::KekFile(PathBuf::from(x)))"
+        )
+    )]
+    KekFile(PathBuf),
+    /// The KEK is obtained from a key-provider process listening at the Unix
+    /// socket with the given path, which sends exactly 32 bytes to every
+    /// client that connects.
+    #[cfg_attr(
+        test,
+        proptest(
+            strategy = "any::<String>().prop_map(|x| SecretKeyStoreEncryption::KekProviderSocket(PathBuf::from(x)))"
+        )
+    )]
+    KekProviderSocket(PathBuf),
+}
+
+impl Default for SecretKeyStoreEncryption {
+    fn default() -> Self {
+        SecretKeyStoreEncryption::Plaintext
+    }
+}
+
+/// Hardening of the remote CSP vault server, i.e., the process started with
+/// `csp_vault_type: UnixSocket`. The settings are only used by the vault
+/// server and ignored by the replica.
+///
+/// Vault methods are referred to by their name in the vault's RPC interface,
+/// e.g., `sign`, `multi_sign`, `tls_sign` or `idkg_create_dealing`.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(default)]
+#[cfg_attr(test, derive(Arbitrary))]
+pub struct CspVaultServerConfig {
+    /// Whether each call of a vault method that uses secret key material
+    /// is written to the audit log, together with its outcome.
+    pub audit_log_enabled: bool,
+    /// Maximum number of calls per second of a vault method. Methods
+    /// without an entry are not rate limited. The server fails to start if
+    /// a name is not one of the vault methods that use secret keys.
+    pub rate_limits_per_second: BTreeMap<String, u32>,
+    /// Vault methods that the server refuses to execute. The server fails to
+    /// start if a name is not one of the vault methods that use secret keys.
+    pub disabled_methods: BTreeSet<String>,
+    /// If set, the node signing key only signs messages whose signature
+    /// domain (e.g. `block_domain`) is contained in this set.
+    pub allowed_node_signing_domains: Option<BTreeSet<String>>,
+    /// If set, the node signing key only signs while the node is a member of
+    /// this subnet according to the registry. The registry is fetched from
+    /// the NNS at `registration.nns_url` and verified with the NNS public key
+    /// at `registration.nns_pub_key_pem`.
+    #[cfg_attr(
+        test,
+        proptest(
+            strategy = "any::<Option<u64>>().prop_map(|x| x.map(PrincipalId::new_subnet_test_id))"
+        )
+    )]
+    pub registered_subnet_id: Option<PrincipalId>,
+    /// If set, the vault server exposes its Prometheus metrics at this address.
+    pub metrics_addr: Option<SocketAddr>,
+}
+
 #[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
 #[serde(default)]
 #[cfg_attr(test, derive(Arbitrary))]
@@ -59,6 +137,11 @@ pub struct CryptoConfig {
     )]
     pub crypto_root: PathBuf,
     pub csp_vault_type: CspVaultType,
+    /// Encryption at rest of the secret key stores. It is used by whichever
+    /// process hosts the `CspVault`, i.e., the replica or the `CspVault`-server.
+    pub secret_key_store_encryption: SecretKeyStoreEncryption,
+    /// Hardening of the vault server, if `csp_vault_type` is `UnixSocket`.
+    pub csp_vault_server: CspVaultServerConfig,
 }
 
 impl Default for CryptoConfig {
@@ -66,6 +149,8 @@ impl Default for CryptoConfig {
         Self {
             crypto_root: PathBuf::from(CRYPTO_ROOT_DEFAULT_PATH),
             csp_vault_type: CspVaultType::InReplica,
+            secret_key_store_encryption: SecretKeyStoreEncryption::Plaintext,
+            csp_vault_server: CspVaultServerConfig::default(),
         }
     }
 }
@@ -76,6 +161,8 @@ impl CryptoConfig {
         Self {
             crypto_root,
             csp_vault_type: CspVaultType::InReplica,
+            secret_key_store_encryption: SecretKeyStoreEncryption::Plaintext,
+            csp_vault_server: CspVaultServerConfig::default(),
         }
     }
 
@@ -85,6 +172,8 @@ impl CryptoConfig {
         Self {
             crypto_root,
             csp_vault_type: CspVaultType::UnixSocket(socket_path),
+            secret_key_store_encryption: SecretKeyStoreEncryption::Plaintext,
+            csp_vault_server: CspVaultServerConfig::default(),
         }
     }
 
@@ -171,6 +260,7 @@ impl CryptoConfig {
 mod tests {
     use super::*;
     use proptest::prelude::*;
+    use std::str::FromStr;
     use tempfile::tempdir as tempdir_deleted_at_end_of_scope;
 
     // TODO(CRP-1338): review the creation/usage of the temp dirs.
@@ -192,6 +282,83 @@ mod tests {
         CryptoConfig::run_with_temp_config(|config| serde_test(config));
     }
 
+    #[test]
+    fn should_deserialize_csp_vault_server_config() {
+        let config = r#"{
+            crypto_root: '/tmp/ic_crypto',
+            csp_vault_type: { unix_socket: '/run/ic-node/crypto-csp/socket' },
+            csp_vault_server: {
+                audit_log_enabled: true,
+                rate_limits_per_second: { sign: 100 },
+                disabled_methods: ['tls_sign'],
+                allowed_node_signing_domains: ['block_domain'],
+                registered_subnet_id: 'tdb26-jop6k-aogll-7ltgs-eruif-6kk7m-qpktf-gdiqx-mxtrf-vb5e6-eqe',
+                metrics_addr: '[::1]:9101',
+            },
+        }"#;
+
+        let deserialized: CryptoConfig = json5::from_str(config).unwrap();
+
+        let server_config = deserialized.csp_vault_server;
+        assert!(server_config.audit_log_enabled);
+        assert_eq!(
+            server_config.rate_limits_per_second,
+            BTreeMap::from([("sign".to_string(), 100)])
+        );
+        assert_eq!(
+            server_config.disabled_methods,
+            BTreeSet::from(["tls_sign".to_string()])
+        );
+        assert_eq!(
+            server_config.allowed_node_signing_domains,
+            Some(BTreeSet::from(["block_domain".to_string()]))
+        );
+        assert_eq!(
+            server_config.registered_subnet_id,
+            Some(
+                PrincipalId::from_str(
+                    "tdb26-jop6k-aogll-7ltgs-eruif-6kk7m-qpktf-gdiqx-mxtrf-vb5e6-eqe"
+                )
+                .unwrap()
+            )
+        );
+        assert_eq!(
+            server_config.metrics_addr,
+            Some("[::1]:9101".parse().unwrap())
+        );
+    }
+
+    #[test]
+    fn should_deserialize_secret_key_store_encryption() {
+        let config = r#"{
+            crypto_root: '/tmp/ic_crypto',
+            secret_key_store_encryption: { kek_file: '/var/lib/ic/sks_kek' },
+        }"#;
+
+        let deserialized: CryptoConfig = json5::from_str(config).unwrap();
+
+        assert_eq!(
+            deserialized.secret_key_store_encryption,
+            SecretKeyStoreEncryption::KekFile(PathBuf::from("/var/lib/ic/sks_kek"))
+        );
+    }
+
+    #[test]
+    fn should_default_optional_settings_if_missing() {
+        let config = "{ crypto_root: '/tmp/ic_crypto', csp_vault_type: 'in_replica' }";
+
+        let deserialized: CryptoConfig = json5::from_str(config).unwrap();
+
+        assert_eq!(
+            deserialized.secret_key_store_encryption,
+            SecretKeyStoreEncryption::Plaintext
+        );
+        assert_eq!(
+            deserialized.csp_vault_server,
+            CspVaultServerConfig::default()
+        );
+    }
+
     proptest! {
         #[allow(dead_code)]
         // #[test]
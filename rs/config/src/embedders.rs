@@ -59,6 +59,8 @@ pub struct FeatureFlags {
     /// Track dirty pages with a write barrier instead of the signal handler.
     pub write_barrier: FlagStatus,
     pub wasm_native_stable_memory: FlagStatus,
+    /// Accept canisters with a 64-bit main memory (the memory64 proposal).
+    pub wasm64: FlagStatus,
 }
 
 impl FeatureFlags {
@@ -67,6 +69,7 @@ impl FeatureFlags {
             rate_limiting_of_debug_prints: FlagStatus::Enabled,
             write_barrier: FlagStatus::Disabled,
             wasm_native_stable_memory: FlagStatus::Disabled,
+            wasm64: FlagStatus::Disabled,
         }
     }
 }
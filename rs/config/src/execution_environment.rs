@@ -6,7 +6,7 @@ use crate::{
 use ic_base_types::{CanisterId, NumSeconds};
 use ic_types::{
     Cycles, NumBytes, NumInstructions, NumPages, MAX_STABLE_MEMORY_IN_BYTES,
-    MAX_WASM_MEMORY_IN_BYTES,
+    MAX_WASM64_MEMORY_IN_BYTES, MAX_WASM_MEMORY_IN_BYTES,
 };
 use serde::{Deserialize, Serialize};
 use std::{str::FromStr, time::Duration};
@@ -118,6 +118,11 @@ pub struct Config {
     /// The maximum amount of memory that can be utilized by a single canister.
     pub max_canister_memory_size: NumBytes,
 
+    /// The maximum amount of memory that can be utilized by a single canister
+    /// if `wasm64` is enabled. It replaces `max_canister_memory_size`, whose
+    /// default only fits a 32-bit heap.
+    pub max_wasm64_canister_memory_size: NumBytes,
+
     /// The default value used when provisioning a canister
     /// if amount of cycles was not specified.
     pub default_provisional_cycles_balance: Cycles,
@@ -198,6 +203,10 @@ pub struct Config {
     /// The limit on the number of dirty pages in stable memory that a canister
     /// can create in a single message.
     pub stable_memory_dirty_page_limit: NumPages,
+
+    /// Indicates whether canisters with a 64-bit main memory (the memory64
+    /// proposal) are accepted or not.
+    pub wasm64: FlagStatus,
 }
 
 impl Default for Config {
@@ -221,6 +230,9 @@ impl Default for Config {
             max_canister_memory_size: NumBytes::new(
                 MAX_STABLE_MEMORY_IN_BYTES + MAX_WASM_MEMORY_IN_BYTES,
             ),
+            max_wasm64_canister_memory_size: NumBytes::new(
+                MAX_STABLE_MEMORY_IN_BYTES + MAX_WASM64_MEMORY_IN_BYTES,
+            ),
             default_provisional_cycles_balance: Cycles::new(100_000_000_000_000),
             // The default freeze threshold is 30 days.
             default_freeze_threshold: NumSeconds::from(30 * 24 * 60 * 60),
@@ -262,6 +274,18 @@ impl Default for Config {
             stable_memory_dirty_page_limit: NumPages::new(
                 embedders::STABLE_MEMORY_DIRTY_PAGE_LIMIT,
             ),
+            wasm64: FlagStatus::Disabled,
+        }
+    }
+}
+
+impl Config {
+    /// Returns the maximum amount of memory that can be utilized by a single
+    /// canister, taking into account whether `wasm64` is enabled.
+    pub fn canister_memory_limit(&self) -> NumBytes {
+        match self.wasm64 {
+            FlagStatus::Enabled => self.max_wasm64_canister_memory_size,
+            FlagStatus::Disabled => self.max_canister_memory_size,
         }
     }
 }
@@ -21,9 +21,10 @@ use ic_replicated_state::ReplicatedState;
 use ic_types::{
     batch::{CanisterHttpPayload, ValidationContext, MAX_CANISTER_HTTP_PAYLOAD_SIZE},
     canister_http::{
-        CanisterHttpResponse, CanisterHttpResponseDivergence, CanisterHttpResponseMetadata,
-        CanisterHttpResponseProof, CanisterHttpResponseShare, CanisterHttpResponseWithConsensus,
-        CANISTER_HTTP_MAX_RESPONSES_PER_BLOCK, CANISTER_HTTP_TIMEOUT_INTERVAL,
+        CanisterHttpRequestContext, CanisterHttpResponse, CanisterHttpResponseDivergence,
+        CanisterHttpResponseMetadata, CanisterHttpResponseProof, CanisterHttpResponseShare,
+        CanisterHttpResponseWithConsensus, Replication, CANISTER_HTTP_MAX_RESPONSES_PER_BLOCK,
+        CANISTER_HTTP_TIMEOUT_INTERVAL,
     },
     consensus::Committee,
     crypto::Signed,
@@ -180,6 +181,56 @@ fn group_shares_by_callback_id<'a, Shares: Iterator<Item = &'a CanisterHttpRespo
     map
 }
 
+/// A response that can be included into a payload once its signature shares
+/// are aggregated.
+type ResponseCandidate = (
+    CanisterHttpResponseMetadata,
+    BTreeSet<BasicSignature<CanisterHttpResponseMetadata>>,
+    CanisterHttpResponse,
+);
+
+/// Removes the shares of non-replicated requests from `grouped_shares` and
+/// returns the responses that can be included for them.
+///
+/// A non-replicated response can be included as soon as the share of the
+/// designated node is available, together with the content it signed. Shares
+/// of other nodes are ignored.
+fn take_non_replicated_responses(
+    grouped_shares: &mut BTreeMap<
+        CallbackId,
+        BTreeMap<CanisterHttpResponseMetadata, Vec<&CanisterHttpResponseShare>>,
+    >,
+    http_contexts: &BTreeMap<CallbackId, CanisterHttpRequestContext>,
+    pool: &dyn CanisterHttpPool,
+) -> Vec<ResponseCandidate> {
+    let mut responses = vec![];
+    for (callback_id, context) in http_contexts {
+        let designated_node = match context.replication {
+            Replication::NonReplicated(node_id) => node_id,
+            Replication::FullyReplicated => continue,
+        };
+        let shares = match grouped_shares.remove(callback_id) {
+            Some(shares) => shares,
+            None => continue,
+        };
+        let response = shares.iter().find_map(|(metadata, shares)| {
+            let share = shares
+                .iter()
+                .find(|share| share.signature.signer == designated_node)?;
+            pool.get_response_content_by_hash(&metadata.content_hash)
+                .map(|content| {
+                    (
+                        metadata.clone(),
+                        BTreeSet::from([share.signature.clone()]),
+                        content,
+                    )
+                })
+        });
+        responses.extend(response);
+    }
+    responses
+}
+
 impl CanisterHttpPayloadBuilder for CanisterHttpPayloadBuilderImpl {
     fn get_canister_http_payload(
         &self,
@@ -248,6 +299,19 @@ impl CanisterHttpPayloadBuilder for CanisterHttpPayloadBuilderImpl {
 
         let mut divergence_responses = vec![];
 
+        // The outstanding canister http requests, if the state is available.
+        let state = self
+            .state_manager
+            .get_state_at(validation_context.certified_height)
+            .ok();
+        let http_contexts = state.as_ref().map(|state| {
+            &state
+                .get_ref()
+                .metadata
+                .subnet_call_context_manager
+                .canister_http_request_contexts
+        });
+
         // Since aggegating the signatures is expensive, we don't want to do the
         // size checks after aggregation. Also we don't want to hold the lock on
         // the pool while aggregating. Therefore, we pick the candidates for the
@@ -280,7 +344,20 @@ impl CanisterHttpPayloadBuilder for CanisterHttpPayloadBuilderImpl {
                 .filter(|&response| !delivered_ids.contains(&response.content.id));
 
             // Group the shares by their metadata
-            let response_candidates_by_callback_id = group_shares_by_callback_id(share_candidates);
+            let mut response_candidates_by_callback_id =
+                group_shares_by_callback_id(share_candidates);
+
+            // Responses to non-replicated requests only need the share of the
+            // designated node, so they are handled separately.
+            let non_replicated_responses = http_contexts
+                .map(|http_contexts| {
+                    take_non_replicated_responses(
+                        &mut response_candidates_by_callback_id,
+                        http_contexts,
+                        &*pool_access,
+                    )
+                })
+                .unwrap_or_default();
 
             self.metrics.total_shares.set(total_share_count);
             self.metrics.active_shares.set(active_shares);
@@ -347,18 +424,9 @@ impl CanisterHttpPayloadBuilder for CanisterHttpPayloadBuilderImpl {
             // time out response. Instead, we scan the state metadata for timed
             // out requests and generate time out responses based on that
             let mut timeouts = vec![];
-            if let Ok(state) = self
-                .state_manager
-                .get_state_at(validation_context.certified_height)
-            {
+            if let Some(http_contexts) = http_contexts {
                 // Iterate over all outstanding canister http requests
-                for (callback_id, request) in state
-                    .get_ref()
-                    .metadata
-                    .subnet_call_context_manager
-                    .canister_http_request_contexts
-                    .iter()
-                {
+                for (callback_id, request) in http_contexts.iter() {
                     unique_includable_responses += 1;
                     let candidate_size = callback_id.count_bytes();
                     let size = NumBytes::new((accumulated_size + candidate_size) as u64);
@@ -376,6 +444,7 @@ impl CanisterHttpPayloadBuilder for CanisterHttpPayloadBuilderImpl {
                 }
             }
 
+            let responses = non_replicated_responses.into_iter().chain(responses);
             for (metadata, shares, content) in responses {
                 unique_includable_responses += 1;
                 // FIXME: This MUST be the same size calculation as
@@ -577,14 +646,32 @@ impl CanisterHttpPayloadBuilder for CanisterHttpPayloadBuilderImpl {
                     },
                 ));
             }
-            if valid_signers.len() < threshold {
-                return Err(CanisterHttpPayloadValidationError::Permanent(
-                    CanisterHttpPermanentValidationError::NotEnoughSigners {
-                        committee,
-                        signers: valid_signers,
-                        expected_threshold: threshold,
-                    },
-                ));
+            let replication = http_contexts
+                .get(&response.content.id)
+                .map(|context| &context.replication);
+            match replication {
+                // A non-replicated response must be signed by the designated node only.
+                Some(Replication::NonReplicated(designated_node)) => {
+                    if valid_signers != [*designated_node] {
+                        return Err(CanisterHttpPayloadValidationError::Permanent(
+                            CanisterHttpPermanentValidationError::InvalidNonReplicatedSigners {
+                                designated_node: *designated_node,
+                                signers: valid_signers,
+                            },
+                        ));
+                    }
+                }
+                Some(Replication::FullyReplicated) | None => {
+                    if valid_signers.len() < threshold {
+                        return Err(CanisterHttpPayloadValidationError::Permanent(
+                            CanisterHttpPermanentValidationError::NotEnoughSigners {
+                                committee,
+                                signers: valid_signers,
+                                expected_threshold: threshold,
+                            },
+                        ));
+                    }
+                }
             }
             self.crypto
                 .verify_aggregate(&response.proof, consensus_registry_version)
@@ -23,7 +23,9 @@ use ic_test_utilities::{
 };
 use ic_test_utilities_registry::SubnetRecordBuilder;
 use ic_types::{
-    canister_http::{CanisterHttpMethod, CanisterHttpRequestContext, CanisterHttpResponseContent},
+    canister_http::{
+        CanisterHttpMethod, CanisterHttpRequestContext, CanisterHttpResponseContent, Replication,
+    },
     consensus::get_faults_tolerated,
     crypto::{crypto_hash, BasicSig, BasicSigOf},
     signature::BasicSignatureBatch,
@@ -289,6 +291,7 @@ fn timeout_priority() {
                     transform: None,
                     // this is the important one
                     time: mock_time(),
+                    replication: Replication::FullyReplicated,
                 };
                 init_state
                     .metadata
@@ -327,6 +330,79 @@ fn timeout_priority() {
     });
 }
 
+/// Check that the response to a non-replicated request is included as soon as
+/// the designated node's share is available, and that validation requires the
+/// proof to be signed by the designated node only.
+#[test]
+fn non_replicated_request_test() {
+    test_config_with_http_feature(4, |mut payload_builder, canister_http_pool| {
+        let (response, metadata) = test_response_and_metadata(0);
+        {
+            let mut pool_access = canister_http_pool.write().unwrap();
+            // A share of a node that was not designated must not be included
+            let (other_response, other_metadata) = test_response_and_metadata_with_content(
+                0,
+                CanisterHttpResponseContent::Success(b"other".to_vec()),
+            );
+            add_own_share_to_pool(
+                pool_access.deref_mut(),
+                &metadata_to_share(1, &other_metadata),
+                &other_response,
+            );
+            add_own_share_to_pool(
+                pool_access.deref_mut(),
+                &metadata_to_share(2, &metadata),
+                &response,
+            );
+        }
+        payload_builder.state_manager = state_manager_with_non_replicated_request(0, 2);
+
+        let context = default_validation_context();
+        let payload = payload_builder.get_canister_http_payload(
+            Height::new(1),
+            &context,
+            &[],
+            NumBytes::new(4 * 1024 * 1024),
+        );
+
+        assert_eq!(payload.num_responses(), 1);
+        assert_eq!(payload.responses[0].content, response);
+        assert_eq!(
+            payload.responses[0]
+                .proof
+                .signature
+                .signatures_map
+                .keys()
+                .cloned()
+                .collect::<Vec<_>>(),
+            vec![node_test_id(2)]
+        );
+        assert!(payload_builder
+            .validate_canister_http_payload(Height::new(1), &payload, &context, &[])
+            .is_ok());
+
+        // The same payload is invalid if another node was designated
+        payload_builder.state_manager = state_manager_with_non_replicated_request(0, 3);
+        match payload_builder.validate_canister_http_payload(
+            Height::new(1),
+            &payload,
+            &context,
+            &[],
+        ) {
+            Err(CanisterHttpPayloadValidationError::Permanent(
+                CanisterHttpPermanentValidationError::InvalidNonReplicatedSigners {
+                    designated_node,
+                    signers,
+                },
+            )) => {
+                assert_eq!(designated_node, node_test_id(3));
+                assert_eq!(signers, vec![node_test_id(2)]);
+            }
+            other => panic!("Expected InvalidNonReplicatedSigners, got {:?}", other),
+        }
+    });
+}
+
 /// Check that the payload builder includes a divergence responses
 #[test]
 fn divergence_response_inclusion_test() {
@@ -891,6 +967,42 @@ fn test_config_with_http_feature<T>(
     })
 }
 
+/// Mocks up a state manager whose state contains a single non-replicated
+/// request, which is made by the given designated node.
+fn state_manager_with_non_replicated_request(
+    callback_id: u64,
+    designated_node: u64,
+) -> Arc<RefMockStateManager> {
+    let mut state = ic_test_utilities::state::get_initial_state(0, 0);
+    state
+        .metadata
+        .subnet_call_context_manager
+        .canister_http_request_contexts
+        .insert(
+            CallbackId::from(callback_id),
+            CanisterHttpRequestContext {
+                request: RequestBuilder::default().build(),
+                url: String::new(),
+                max_response_bytes: None,
+                headers: vec![],
+                body: None,
+                http_method: CanisterHttpMethod::GET,
+                transform: None,
+                time: mock_time(),
+                replication: Replication::NonReplicated(node_test_id(designated_node)),
+            },
+        );
+    let state_manager = Arc::new(RefMockStateManager::default());
+    state_manager
+        .get_mut()
+        .expect_get_state_at()
+        .return_const(Ok(ic_interfaces_state_manager::Labeled::new(
+            Height::new(0),
+            Arc::new(state),
+        )));
+    state_manager
+}
+
 /// The default validation context used in the validation tests
 fn default_validation_context() -> ValidationContext {
     ValidationContext {
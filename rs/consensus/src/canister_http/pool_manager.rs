@@ -178,6 +178,12 @@ impl CanisterHttpPoolManagerImpl {
             .collect();
 
         for (id, context) in http_requests {
+            // Non-replicated requests are only made by the designated node.
+            if let Replication::NonReplicated(node_id) = context.replication {
+                if node_id != self.replica_config.node_id {
+                    continue;
+                }
+            }
             if !request_ids_already_made.contains(&id) {
                 let timeout = context.time + Duration::from_secs(5 * 60);
                 if let Err(err) = self
@@ -285,6 +291,13 @@ impl CanisterHttpPoolManagerImpl {
             return Vec::new();
         };
 
+        let state = self.state_manager.get_latest_state();
+        let http_requests = &state
+            .get_ref()
+            .metadata
+            .subnet_call_context_manager
+            .canister_http_request_contexts;
+
         canister_http_pool
             .get_unvalidated_shares()
             .filter_map(|share| {
@@ -311,6 +324,18 @@ impl CanisterHttpPoolManagerImpl {
                             .to_string(),
                     ));
                 }
+                if let Some(Replication::NonReplicated(node_id)) = http_requests
+                    .get(&share.content.id)
+                    .map(|context| &context.replication)
+                {
+                    if *node_id != share.signature.signer {
+                        return Some(CanisterHttpChangeAction::HandleInvalid(
+                            ic_types::crypto::crypto_hash(share),
+                            "Share for a non-replicated request signed by a node other than the designated one"
+                                .to_string(),
+                        ));
+                    }
+                }
                 // TODO: more precise error handling
                 if let Err(err) = self.crypto.verify(share, registry_version) {
                     error!(self.log, "Unable to verify signature of share, {}", err);
@@ -408,7 +433,7 @@ pub mod test {
     use ic_interfaces_state_manager::Labeled;
     use ic_metrics::MetricsRegistry;
     use ic_registry_subnet_type::SubnetType;
-    use ic_test_utilities::types::ids::subnet_test_id;
+    use ic_test_utilities::types::ids::{node_test_id, subnet_test_id};
     use ic_test_utilities_logger::with_test_replica_logger;
     use ic_types::{
         crypto::{CryptoHash, CryptoHashOf},
@@ -482,6 +507,7 @@ pub mod test {
                     http_method: CanisterHttpMethod::GET,
                     transform: None,
                     time: ic_types::Time::from_nanos_since_unix_epoch(10),
+                    replication: Replication::FullyReplicated,
                 };
 
                 state_manager
@@ -628,6 +654,7 @@ pub mod test {
                     http_method: CanisterHttpMethod::GET,
                     transform: None,
                     time: ic_types::Time::from_nanos_since_unix_epoch(10),
+                    replication: Replication::FullyReplicated,
                 };
 
                 // Expect times to be called exactly once to check that already
@@ -703,4 +730,69 @@ pub mod test {
             });
         });
     }
+
+    #[test]
+    pub fn test_non_replicated_requests_only_made_by_designated_node() {
+        ic_test_utilities::artifact_pool_config::with_test_pool_config(|pool_config| {
+            with_test_replica_logger(|log| {
+                let Dependencies {
+                    pool,
+                    replica_config,
+                    crypto,
+                    state_manager,
+                    registry,
+                    membership,
+                    ..
+                } = dependencies(pool_config.clone(), 4);
+                let mut shim_mock = MockNonBlockingChannel::<CanisterHttpRequest>::new();
+                shim_mock
+                    .expect_try_receive()
+                    .return_const(Err(TryReceiveError::Empty));
+
+                let request = CanisterHttpRequestContext {
+                    request: ic_test_utilities::types::messages::RequestBuilder::new().build(),
+                    url: "".to_string(),
+                    max_response_bytes: None,
+                    headers: vec![],
+                    body: None,
+                    http_method: CanisterHttpMethod::GET,
+                    transform: None,
+                    time: ic_types::Time::from_nanos_since_unix_epoch(10),
+                    replication: Replication::NonReplicated(node_test_id(999)),
+                };
+                assert_ne!(replica_config.node_id, node_test_id(999));
+
+                // We haven't set an expectation on send, so this will fail if
+                // a request designated to another node is made.
+                let shim: Arc<Mutex<CanisterHttpAdapterClient>> =
+                    Arc::new(Mutex::new(Box::new(shim_mock)));
+
+                state_manager
+                    .get_mut()
+                    .expect_get_latest_state()
+                    .return_const(Labeled::new(
+                        Height::from(1),
+                        Arc::new(state_with_pending_http_calls(BTreeMap::from([(
+                            CallbackId::from(7),
+                            request,
+                        )]))),
+                    ));
+
+                let mut pool_manager = CanisterHttpPoolManagerImpl::new(
+                    state_manager,
+                    shim,
+                    crypto,
+                    membership,
+                    replica_config,
+                    Arc::clone(&registry) as Arc<_>,
+                    MetricsRegistry::new(),
+                    log,
+                );
+                let canister_http_pool = CanisterHttpPoolImpl::new(MetricsRegistry::new());
+                let change_set =
+                    pool_manager.generate_change_set(pool.as_cache(), &canister_http_pool);
+                assert!(change_set.is_empty());
+            });
+        });
+    }
 }
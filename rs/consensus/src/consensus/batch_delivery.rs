@@ -11,7 +11,7 @@ use crate::consensus::{
 use crate::ecdsa::utils::EcdsaBlockReaderImpl;
 use ic_artifact_pool::consensus_pool::build_consensus_block_chain;
 use ic_crypto::get_tecdsa_master_public_key;
-use ic_ic00_types::{EcdsaKeyId, SetupInitialDKGResponse};
+use ic_ic00_types::{EcdsaKeyId, SchnorrKeyId, SetupInitialDKGResponse};
 use ic_interfaces::messaging::{MessageRouting, MessageRoutingError};
 use ic_interfaces_registry::RegistryClient;
 use ic_logger::{debug, error, info, trace, warn, ReplicaLogger};
@@ -20,12 +20,12 @@ use ic_protobuf::registry::crypto::v1::PublicKey as PublicKeyProto;
 use ic_protobuf::registry::subnet::v1::InitialNiDkgTranscriptRecord;
 use ic_types::{
     canister_http::*,
-    consensus::ecdsa::{CompletedSignature, EcdsaBlockReader},
+    consensus::ecdsa::{CompletedSignature, EcdsaBlockReader, TranscriptRef},
     crypto::{
         canister_threshold_sig::MasterEcdsaPublicKey,
         threshold_sig::ni_dkg::{NiDkgId, NiDkgTag, NiDkgTranscript},
     },
-    messages::{CallbackId, Response},
+    messages::{CallbackId, Response, NO_DEADLINE},
     ReplicaVersion,
 };
 use std::collections::BTreeMap;
@@ -120,6 +120,19 @@ pub fn deliver_batches(
                     }
                 };
 
+                let schnorr_subnet_public_key =
+                    match get_schnorr_subnet_public_key(&block, pool, log) {
+                        Ok(maybe_key) => maybe_key,
+                        Err(e) => {
+                            warn!(
+                                every_n_seconds => 5,
+                                log,
+                                "Do not deliver height {:?}: {}", h, e
+                            );
+                            return Ok(last_delivered_batch_height);
+                        }
+                    };
+
                 let block_stats = BlockStats::from(&block);
 
                 // This flag can only be true, if we've called deliver_batches with a height
@@ -135,6 +148,7 @@ pub fn deliver_batches(
                     },
                     randomness,
                     ecdsa_subnet_public_keys: ecdsa_subnet_public_key.into_iter().collect(),
+                    schnorr_subnet_public_keys: schnorr_subnet_public_key.into_iter().collect(),
                     registry_version: block.context.registry_version,
                     time: block.context.time,
                     consensus_responses,
@@ -193,44 +207,80 @@ pub fn get_ecdsa_subnet_public_key(
     pool: &PoolReader<'_>,
     log: &ReplicaLogger,
 ) -> Result<Option<(EcdsaKeyId, MasterEcdsaPublicKey)>, String> {
-    let maybe_ecdsa_and_transcript_ref = block.payload.as_ref().as_ecdsa().and_then(|ecdsa| {
+    let maybe_key_id_and_transcript_ref = block.payload.as_ref().as_ecdsa().and_then(|ecdsa| {
         ecdsa
             .key_transcript
             .current
             .as_ref()
-            .map(|unmasked| (ecdsa, *unmasked.as_ref()))
+            .map(|unmasked| (ecdsa.key_transcript.key_id.clone(), *unmasked.as_ref()))
     });
-    let ecdsa_subnet_public_key =
-        if let Some((ecdsa, transcript_ref)) = maybe_ecdsa_and_transcript_ref {
-            let summary = match pool.dkg_summary_block_for_finalized_height(block.height) {
-                Some(b) => b,
-                None => {
-                    return Err(format!(
-                        "Failed to find dkg summary block for height {}",
-                        block.height
-                    ))
-                }
-            };
-            let chain = build_consensus_block_chain(pool.pool(), &summary, block);
-            let block_reader = EcdsaBlockReaderImpl::new(chain);
-            match block_reader.transcript(&transcript_ref) {
-                Ok(transcript) => get_tecdsa_master_public_key(&transcript)
-                    .ok()
-                    .map(|public_key| (ecdsa.key_transcript.key_id.clone(), public_key)),
-                Err(err) => {
-                    warn!(
-                        log,
-                        "deliver_batches(): failed to translate transcript ref {:?}: {:?}",
-                        transcript_ref,
-                        err
-                    );
-                    None
-                }
-            }
-        } else {
-            None
-        };
-    Ok(ecdsa_subnet_public_key)
+    match maybe_key_id_and_transcript_ref {
+        Some((key_id, transcript_ref)) => {
+            Ok(get_master_public_key(block, &transcript_ref, pool, log)?
+                .map(|public_key| (key_id, public_key)))
+        }
+        None => Ok(None),
+    }
+}
+
+/// This function returns the Schnorr subnet public key to be added to the batch, if
+/// required. The same rules as in [`get_ecdsa_subnet_public_key`] apply.
+pub fn get_schnorr_subnet_public_key(
+    block: &Block,
+    pool: &PoolReader<'_>,
+    log: &ReplicaLogger,
+) -> Result<Option<(SchnorrKeyId, MasterEcdsaPublicKey)>, String> {
+    let maybe_key_id_and_transcript_ref = block
+        .payload
+        .as_ref()
+        .as_ecdsa()
+        .and_then(|ecdsa| ecdsa.schnorr_key_transcript.as_ref())
+        .and_then(|key_transcript| {
+            key_transcript
+                .current
+                .as_ref()
+                .map(|unmasked| (key_transcript.key_id.clone(), *unmasked.as_ref()))
+        });
+    match maybe_key_id_and_transcript_ref {
+        Some((key_id, transcript_ref)) => {
+            Ok(get_master_public_key(block, &transcript_ref, pool, log)?
+                .map(|public_key| (key_id, public_key)))
+        }
+        None => Ok(None),
+    }
+}
+
+/// Looks up the key transcript referenced by `transcript_ref` in the chain ending
+/// at `block`, and extracts the master public key from it.
+fn get_master_public_key(
+    block: &Block,
+    transcript_ref: &TranscriptRef,
+    pool: &PoolReader<'_>,
+    log: &ReplicaLogger,
+) -> Result<Option<MasterEcdsaPublicKey>, String> {
+    let summary = match pool.dkg_summary_block_for_finalized_height(block.height) {
+        Some(b) => b,
+        None => {
+            return Err(format!(
+                "Failed to find dkg summary block for height {}",
+                block.height
+            ))
+        }
+    };
+    let chain = build_consensus_block_chain(pool.pool(), &summary, block);
+    let block_reader = EcdsaBlockReaderImpl::new(chain);
+    match block_reader.transcript(transcript_ref) {
+        Ok(transcript) => Ok(get_tecdsa_master_public_key(&transcript).ok()),
+        Err(err) => {
+            warn!(
+                log,
+                "deliver_batches(): failed to translate transcript ref {:?}: {:?}",
+                transcript_ref,
+                err
+            );
+            Ok(None)
+        }
+    }
 }
 
 /// This function creates responses to the system calls that are redirected to
@@ -284,6 +334,7 @@ pub fn generate_execution_responses_for_canister_http_responses(
                 originator: CanisterId::ic_00(),
                 respondent: CanisterId::ic_00(),
                 originator_reply_callback: content.id,
+                deadline: NO_DEADLINE,
                 refund: Cycles::zero(),
                 response_payload: match &content.content {
                     CanisterHttpResponseContent::Success(data) => {
@@ -304,6 +355,7 @@ pub fn generate_execution_responses_for_canister_http_responses(
                     originator: CanisterId::ic_00(),
                     respondent: CanisterId::ic_00(),
                     originator_reply_callback: *canister_http_timeout,
+                    deadline: NO_DEADLINE,
                     refund: Cycles::zero(),
                     response_payload: ic_types::messages::Payload::Reject(
                         ic_types::messages::RejectContext {
@@ -322,6 +374,7 @@ pub fn generate_execution_responses_for_canister_http_responses(
                         originator: CanisterId::ic_00(),
                         respondent: CanisterId::ic_00(),
                         originator_reply_callback: divergence_response.shares.get(0)?.content.id,
+                        deadline: NO_DEADLINE,
                         refund: Cycles::zero(),
                         response_payload: ic_types::messages::Payload::Reject(
                             ic_types::messages::RejectContext {
@@ -398,6 +451,7 @@ pub fn generate_responses_to_setup_initial_dkg_calls(
                 originator: CanisterId::ic_00(),
                 respondent: CanisterId::ic_00(),
                 originator_reply_callback: callback_id,
+                deadline: NO_DEADLINE,
                 refund: Cycles::zero(),
                 response_payload,
             });
@@ -323,7 +323,7 @@ mod tests {
         crypto::threshold_sig::ni_dkg::{
             NiDkgId, NiDkgTag, NiDkgTargetId, NiDkgTargetSubnet, NiDkgTranscript,
         },
-        messages::{CallbackId, Request},
+        messages::{CallbackId, Request, NO_DEADLINE},
     };
     use std::collections::BTreeMap;
     use std::{collections::BTreeSet, str::FromStr, sync::Arc};
@@ -543,6 +543,7 @@ mod tests {
                     receiver: CanisterId::from(0),
                     sender: CanisterId::from(0),
                     sender_reply_callback: CallbackId::from(0),
+                    deadline: NO_DEADLINE,
                     payment: Cycles::zero(),
                     method_name: "".to_string(),
                     method_payload: vec![],
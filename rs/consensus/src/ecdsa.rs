@@ -422,6 +422,12 @@ impl EcdsaPriorityFnArgs {
         for (request_id, _) in block_reader.requested_signatures() {
             requested_signatures.insert(*request_id);
         }
+        for (request_id, _) in block_reader.requested_schnorr_signatures() {
+            requested_signatures.insert(*request_id);
+        }
+        for (request_id, _) in block_reader.requested_vetkd_keys() {
+            requested_signatures.insert(*request_id);
+        }
 
         let mut active_transcripts = BTreeSet::new();
         for transcript_ref in block_reader.active_transcripts() {
@@ -485,7 +491,9 @@ fn compute_priority(
                 Priority::Stash
             }
         }
-        EcdsaMessageAttribute::EcdsaSigShare(request_id) => {
+        EcdsaMessageAttribute::EcdsaSigShare(request_id)
+        | EcdsaMessageAttribute::SchnorrSigShare(request_id)
+        | EcdsaMessageAttribute::VetKdKeyShare(request_id) => {
             if request_id.height <= args.finalized_height {
                 if args.requested_signatures.contains(request_id) {
                     Priority::Fetch
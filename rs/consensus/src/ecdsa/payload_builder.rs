@@ -11,7 +11,10 @@ use crate::consensus::{
 use ic_artifact_pool::consensus_pool::build_consensus_block_chain;
 use ic_crypto::{get_mega_pubkey, MegaKeyFromRegistryError};
 use ic_error_types::RejectCode;
-use ic_ic00_types::{EcdsaKeyId, Payload, SignWithECDSAReply};
+use ic_ic00_types::{
+    EcdsaCurve, EcdsaKeyId, Payload, SchnorrAlgorithm, SchnorrKeyId, SignWithECDSAReply,
+    SignWithSchnorrReply, VetKdEncryptedKeyReply, VetKdKeyId,
+};
 use ic_interfaces::{consensus_pool::ConsensusBlockChain, ecdsa::EcdsaPool};
 use ic_interfaces_registry::RegistryClient;
 use ic_interfaces_state_manager::{StateManager, StateManagerError};
@@ -36,6 +39,8 @@ use ic_types::{
             idkg::{IDkgTranscript, InitialIDkgDealings},
             ExtendedDerivationPath,
         },
+        threshold_sig::ni_dkg::{NiDkgId, NiDkgTag},
+        vetkd::VetKdArgs,
         AlgorithmId,
     },
     messages::{CallbackId, RejectContext},
@@ -171,6 +176,11 @@ pub fn make_bootstrap_summary(
             next_in_creation: ecdsa::KeyTranscriptCreation::Begin,
             key_id,
         },
+        schnorr_key_transcript: None,
+        ongoing_schnorr_signatures: BTreeMap::new(),
+        available_schnorr_presigs: BTreeMap::new(),
+        schnorr_presigs_in_creation: BTreeMap::new(),
+        ongoing_vetkd_requests: BTreeMap::new(),
     };
 
     // Update the next_in_creation if boot strapping from initial dealings
@@ -355,13 +365,13 @@ fn create_summary_payload_helper(
     // satisfied:
     // 1. Time to reshare key transcript (either due to membership change, or node key change)
     // 2. We don't have a key transcript creation in progress.
-    let next_in_creation = if is_time_to_reshare_key_transcript(
+    let is_time_to_reshare = is_time_to_reshare_key_transcript(
         registry_client,
         curr_interval_registry_version,
         next_interval_registry_version,
         subnet_id,
-    )? && created.is_some()
-    {
+    )?;
+    let next_in_creation = if is_time_to_reshare && created.is_some() {
         info!(
             log,
             "Noticed subnet membership change, will start key_transcript_creation: height = {:?} \
@@ -376,6 +386,47 @@ fn create_summary_payload_helper(
         ecdsa_payload.key_transcript.next_in_creation.clone()
     };
 
+    // The Schnorr key transcript follows the same rules as the ECDSA one.
+    let mut is_new_schnorr_key_transcript = false;
+    let schnorr_key_transcript = match &ecdsa_payload.schnorr_key_transcript {
+        Some(schnorr_key_transcript) => {
+            let schnorr_created = match &schnorr_key_transcript.next_in_creation {
+                ecdsa::KeyTranscriptCreation::Created(unmasked) => {
+                    let transcript = block_reader.transcript(unmasked.as_ref())?;
+                    Some(ecdsa::UnmaskedTranscriptWithAttributes::new(
+                        transcript.to_attributes(),
+                        *unmasked,
+                    ))
+                }
+                _ => None,
+            };
+            is_new_schnorr_key_transcript = match &schnorr_key_transcript.current {
+                Some(unmasked) => {
+                    Some(unmasked.transcript_id())
+                        != schnorr_created
+                            .as_ref()
+                            .map(|transcript| transcript.transcript_id())
+                }
+                None => schnorr_created.is_some(),
+            };
+            let next_in_creation = if is_time_to_reshare && schnorr_created.is_some() {
+                ecdsa::KeyTranscriptCreation::Begin
+            } else {
+                schnorr_key_transcript.next_in_creation.clone()
+            };
+            Some(ecdsa::SchnorrKeyTranscript {
+                current: if schnorr_created.is_none() {
+                    schnorr_key_transcript.current.clone()
+                } else {
+                    schnorr_created
+                },
+                next_in_creation,
+                key_id: schnorr_key_transcript.key_id.clone(),
+            })
+        }
+        None => None,
+    };
+
     let mut ecdsa_summary = ecdsa::EcdsaPayload {
         signature_agreements: ecdsa_payload.signature_agreements.clone(),
         ongoing_signatures: ecdsa_payload.ongoing_signatures.clone(),
@@ -410,6 +461,19 @@ fn create_summary_payload_helper(
             next_in_creation,
             key_id: ecdsa_payload.key_transcript.key_id.clone(),
         },
+        schnorr_key_transcript,
+        ongoing_schnorr_signatures: ecdsa_payload.ongoing_schnorr_signatures.clone(),
+        available_schnorr_presigs: if is_new_schnorr_key_transcript {
+            BTreeMap::new()
+        } else {
+            ecdsa_payload.available_schnorr_presigs.clone()
+        },
+        schnorr_presigs_in_creation: if is_new_schnorr_key_transcript {
+            BTreeMap::new()
+        } else {
+            ecdsa_payload.schnorr_presigs_in_creation.clone()
+        },
+        ongoing_vetkd_requests: ecdsa_payload.ongoing_vetkd_requests.clone(),
     };
     update_summary_refs(height, &mut ecdsa_summary, block_reader)?;
     Ok(Some(ecdsa_summary))
@@ -589,6 +653,22 @@ pub(crate) fn create_data_payload(
             "xnet_reshare_agreements",
             ecdsa_payload.xnet_reshare_agreements.len() as i64,
         );
+        ecdsa_payload_metrics.payload_metrics_set(
+            "ongoing_schnorr_signatures",
+            ecdsa_payload.ongoing_schnorr_signatures.len() as i64,
+        );
+        ecdsa_payload_metrics.payload_metrics_set(
+            "available_schnorr_presigs",
+            ecdsa_payload.available_schnorr_presigs.len() as i64,
+        );
+        ecdsa_payload_metrics.payload_metrics_set(
+            "schnorr_presigs_in_creation",
+            ecdsa_payload.schnorr_presigs_in_creation.len() as i64,
+        );
+        ecdsa_payload_metrics.payload_metrics_set(
+            "ongoing_vetkd_requests",
+            ecdsa_payload.ongoing_vetkd_requests.len() as i64,
+        );
     };
     Ok(new_payload)
 }
@@ -639,11 +719,27 @@ pub(crate) fn create_data_payload_helper(
         .metadata
         .subnet_call_context_manager
         .sign_with_ecdsa_contexts;
+    let all_schnorr_requests = &state
+        .get_ref()
+        .metadata
+        .subnet_call_context_manager
+        .sign_with_schnorr_contexts;
+    let all_vetkd_requests = &state
+        .get_ref()
+        .metadata
+        .subnet_call_context_manager
+        .vetkd_contexts;
     let ecdsa_dealings_contexts = &state
         .get_ref()
         .metadata
         .subnet_call_context_manager
         .ecdsa_dealings_contexts;
+    // vetKD keys are derived from the current high-threshold NI-DKG key of the subnet.
+    let vetkd_ni_dkg_id = summary
+        .dkg
+        .current_transcripts()
+        .get(&NiDkgTag::HighThreshold)
+        .map(|transcript| transcript.dkg_id);
 
     create_data_payload_helper_2(
         &mut ecdsa_payload,
@@ -653,6 +749,9 @@ pub(crate) fn create_data_payload_helper(
         next_interval_registry_version,
         &receivers,
         all_signing_requests,
+        all_schnorr_requests,
+        all_vetkd_requests,
+        vetkd_ni_dkg_id,
         ecdsa_dealings_contexts,
         block_reader,
         transcript_builder,
@@ -671,6 +770,9 @@ pub(crate) fn create_data_payload_helper_2(
     next_interval_registry_version: RegistryVersion,
     receivers: &[NodeId],
     all_signing_requests: &BTreeMap<CallbackId, SignWithEcdsaContext>,
+    all_schnorr_requests: &BTreeMap<CallbackId, SignWithSchnorrContext>,
+    all_vetkd_requests: &BTreeMap<CallbackId, VetKdContext>,
+    vetkd_ni_dkg_id: Option<NiDkgId>,
     ecdsa_dealings_contexts: &BTreeMap<CallbackId, EcdsaDealingsContext>,
     block_reader: &dyn EcdsaBlockReader,
     transcript_builder: &dyn EcdsaTranscriptBuilder,
@@ -690,8 +792,39 @@ pub(crate) fn create_data_payload_helper_2(
         }
     }
 
+    // Start creating the Schnorr key once it shows up in the config, and
+    // start using it as soon as it is created.
+    if ecdsa_payload.schnorr_key_transcript.is_none() {
+        if let Some(key_id) = ecdsa_config.schnorr_key_ids.first() {
+            info!(
+                log,
+                "Start to create Schnorr key {} at height {}", key_id, height
+            );
+            ecdsa_payload.schnorr_key_transcript =
+                Some(ecdsa::SchnorrKeyTranscript::new(key_id.clone()));
+        }
+    }
+    if let Some(schnorr_key_transcript) = &mut ecdsa_payload.schnorr_key_transcript {
+        if let ecdsa::KeyTranscriptCreation::Created(unmasked) =
+            &schnorr_key_transcript.next_in_creation
+        {
+            let transcript = block_reader.transcript(unmasked.as_ref())?;
+            if schnorr_key_transcript.current.is_none() {
+                schnorr_key_transcript.current =
+                    Some(ecdsa::UnmaskedTranscriptWithAttributes::new(
+                        transcript.to_attributes(),
+                        *unmasked,
+                    ));
+            }
+        }
+    }
+
     ecdsa_payload.uid_generator.update_height(height)?;
     let current_key_transcript = ecdsa_payload.key_transcript.current.as_ref().cloned();
+    let current_schnorr_key_transcript = ecdsa_payload
+        .schnorr_key_transcript
+        .as_ref()
+        .and_then(|key_transcript| key_transcript.current.clone());
 
     let valid_keys: BTreeSet<_> = ecdsa_config.key_ids.iter().cloned().collect();
     let request_expiry_time = ecdsa_config.signature_request_timeout_ns.and_then(|t| {
@@ -702,7 +835,30 @@ pub(crate) fn create_data_payload_helper_2(
             None
         }
     });
+    // ECDSA, Schnorr and vetKD agreements share the same map, so the
+    // agreements of pending Schnorr and vetKD requests have to survive the
+    // ECDSA update.
+    let other_agreements = ecdsa_payload
+        .signature_agreements
+        .keys()
+        .filter(|random_id| {
+            all_schnorr_requests
+                .values()
+                .any(|context| context.pseudo_random_id == **random_id)
+                || all_vetkd_requests
+                    .values()
+                    .any(|context| context.pseudo_random_id == **random_id)
+        })
+        .cloned()
+        .collect::<Vec<_>>();
     update_signature_agreements(all_signing_requests, signature_builder, ecdsa_payload);
+    for random_id in other_agreements {
+        ecdsa_payload
+            .signature_agreements
+            .insert(random_id, ecdsa::CompletedSignature::ReportedToExecution);
+    }
+    update_schnorr_signature_agreements(all_schnorr_requests, signature_builder, ecdsa_payload);
+    update_vetkd_agreements(all_vetkd_requests, signature_builder, ecdsa_payload);
     let new_signing_requests = get_signing_requests(
         height,
         request_expiry_time,
@@ -720,6 +876,39 @@ pub(crate) fn create_data_payload_helper_2(
     )?;
     make_new_quadruples_if_needed(current_key_transcript.as_ref(), ecdsa_config, ecdsa_payload)?;
 
+    let valid_schnorr_keys: BTreeSet<_> = ecdsa_config.schnorr_key_ids.iter().cloned().collect();
+    let new_schnorr_requests = get_schnorr_signing_requests(
+        height,
+        request_expiry_time,
+        ecdsa_payload,
+        all_schnorr_requests,
+        &valid_schnorr_keys,
+        ecdsa_payload_metrics,
+    );
+    update_ongoing_schnorr_signatures(
+        new_schnorr_requests,
+        current_schnorr_key_transcript.as_ref(),
+        ecdsa_config.quadruples_to_create_in_advance,
+        ecdsa_payload,
+        log.clone(),
+    )?;
+    make_new_schnorr_presigs_if_needed(
+        current_schnorr_key_transcript.as_ref(),
+        ecdsa_config,
+        ecdsa_payload,
+    )?;
+
+    let valid_vetkd_keys: BTreeSet<_> = ecdsa_config.vetkd_key_ids.iter().cloned().collect();
+    update_ongoing_vetkd_requests(
+        height,
+        request_expiry_time,
+        vetkd_ni_dkg_id,
+        ecdsa_payload,
+        all_vetkd_requests,
+        &valid_vetkd_keys,
+        ecdsa_payload_metrics,
+    );
+
     let mut new_transcripts = update_quadruples_in_creation(
         current_key_transcript.as_ref(),
         ecdsa_payload,
@@ -727,18 +916,41 @@ pub(crate) fn create_data_payload_helper_2(
         height,
         &log,
     )?;
+    new_transcripts.append(&mut update_schnorr_presigs_in_creation(
+        current_schnorr_key_transcript.as_ref(),
+        ecdsa_payload,
+        transcript_builder,
+        height,
+        &log,
+    )?);
     if let Some(new_transcript) = update_next_key_transcript(
         receivers,
         next_interval_registry_version,
         current_key_transcript.as_ref(),
         &mut ecdsa_payload.key_transcript.next_in_creation,
         &mut ecdsa_payload.uid_generator,
+        ecdsa_algorithm_id(&ecdsa_payload.key_transcript.key_id),
         transcript_builder,
         height,
         log.clone(),
     )? {
         new_transcripts.push(new_transcript);
     };
+    if let Some(schnorr_key_transcript) = &mut ecdsa_payload.schnorr_key_transcript {
+        if let Some(new_transcript) = update_next_key_transcript(
+            receivers,
+            next_interval_registry_version,
+            current_schnorr_key_transcript.as_ref(),
+            &mut schnorr_key_transcript.next_in_creation,
+            &mut ecdsa_payload.uid_generator,
+            schnorr_algorithm_id(&schnorr_key_transcript.key_id),
+            transcript_builder,
+            height,
+            log.clone(),
+        )? {
+            new_transcripts.push(new_transcript);
+        };
+    }
 
     // Drop transcripts from last round and keep only the
     // ones created in this round.
@@ -772,6 +984,7 @@ fn new_random_config(
     subnet_nodes: &[NodeId],
     summary_registry_version: RegistryVersion,
     uid_generator: &mut ecdsa::EcdsaUIDGenerator,
+    algorithm_id: AlgorithmId,
 ) -> Result<ecdsa::RandomTranscriptParams, EcdsaPayloadError> {
     let transcript_id = uid_generator.next_transcript_id();
     let dealers = subnet_nodes.iter().copied().collect::<BTreeSet<_>>();
@@ -781,7 +994,7 @@ fn new_random_config(
         dealers,
         receivers,
         summary_registry_version,
-        AlgorithmId::ThresholdEcdsaSecp256k1,
+        algorithm_id,
     ))
 }
 
@@ -798,6 +1011,7 @@ fn make_new_quadruples_if_needed(
         make_new_quadruples_if_needed_helper(
             &node_ids,
             key_transcript.registry_version(),
+            key_transcript.algorithm_id(),
             ecdsa_config,
             ecdsa_payload,
         )
@@ -809,6 +1023,7 @@ fn make_new_quadruples_if_needed(
 fn make_new_quadruples_if_needed_helper(
     subnet_nodes: &[NodeId],
     registry_version: RegistryVersion,
+    algorithm_id: AlgorithmId,
     ecdsa_config: &EcdsaConfig,
     ecdsa_payload: &mut ecdsa::EcdsaPayload,
 ) -> Result<(), EcdsaPayloadError> {
@@ -818,8 +1033,10 @@ fn make_new_quadruples_if_needed_helper(
         let quadruples_in_creation = &mut ecdsa_payload.quadruples_in_creation;
         let uid_generator = &mut ecdsa_payload.uid_generator;
         for _ in 0..(quadruples_to_create - unassigned_quadruples) {
-            let kappa_config = new_random_config(subnet_nodes, registry_version, uid_generator)?;
-            let lambda_config = new_random_config(subnet_nodes, registry_version, uid_generator)?;
+            let kappa_config =
+                new_random_config(subnet_nodes, registry_version, uid_generator, algorithm_id)?;
+            let lambda_config =
+                new_random_config(subnet_nodes, registry_version, uid_generator, algorithm_id)?;
             quadruples_in_creation.insert(
                 uid_generator.next_quadruple_id(),
                 ecdsa::QuadrupleInCreation::new(kappa_config, lambda_config),
@@ -950,6 +1167,7 @@ pub(crate) fn get_signing_requests<'a>(
                 originator: context.request.sender,
                 respondent: ic_types::CanisterId::ic_00(),
                 originator_reply_callback: *callback_id,
+                deadline: context.request.deadline,
                 refund: context.request.payment,
                 response_payload: ic_types::messages::Payload::Reject(RejectContext {
                     code: RejectCode::CanisterReject,
@@ -1006,6 +1224,7 @@ pub(crate) fn get_signing_requests<'a>(
                     originator: context.request.sender,
                     respondent: ic_types::CanisterId::ic_00(),
                     originator_reply_callback: *callback_id,
+                    deadline: context.request.deadline,
                     refund: context.request.payment,
                     response_payload: ic_types::messages::Payload::Reject(RejectContext {
                         code: RejectCode::CanisterError,
@@ -1089,6 +1308,7 @@ pub(crate) fn update_signature_agreements(
             originator: context.request.sender,
             respondent: ic_types::CanisterId::ic_00(),
             originator_reply_callback: **callback_id,
+            deadline: context.request.deadline,
             // Execution is responsible for burning the appropriate cycles
             // before pushing the new context, so any remaining cycles can
             // be refunded to the canister.
@@ -1142,7 +1362,8 @@ pub(crate) fn update_ongoing_signatures(
     Ok(())
 }
 
-/// Update configuration and data about the next ECDSA key transcript.
+/// Update configuration and data about the next key transcript. The given
+/// algorithm id is used when the very first key transcript has to be created.
 /// Returns the newly created transcript, if any.
 ///
 /// Note that when creating next key transcript we must use the registry version
@@ -1153,6 +1374,7 @@ fn update_next_key_transcript(
     current_key_transcript: Option<&ecdsa::UnmaskedTranscriptWithAttributes>,
     next_key_transcript_creation: &mut ecdsa::KeyTranscriptCreation,
     uid_generator: &mut ecdsa::EcdsaUIDGenerator,
+    algorithm_id: AlgorithmId,
     transcript_cache: &dyn EcdsaTranscriptBuilder,
     height: Height,
     log: ReplicaLogger,
@@ -1211,7 +1433,7 @@ fn update_next_key_transcript(
                     dealers_set,
                     receivers_set,
                     registry_version,
-                    AlgorithmId::ThresholdEcdsaSecp256k1,
+                    algorithm_id,
                 ),
             );
         }
@@ -1452,50 +1674,571 @@ fn update_quadruples_in_creation(
             ) {
                 newly_available.push(*key);
             }
-        }
-        for key in newly_available.into_iter() {
-            // the following unwraps are safe
-            let quadruple = payload.quadruples_in_creation.remove(&key).unwrap();
-            let lambda_masked = quadruple.lambda_masked.unwrap();
-            let kappa_unmasked = quadruple.kappa_unmasked.unwrap();
-            let key_times_lambda = quadruple.key_times_lambda.unwrap();
-            let kappa_times_lambda = quadruple.kappa_times_lambda.unwrap();
-            debug!(
-                log,
-                "update_quadruples_in_creation: making of quadruple {:?} is complete", key
-            );
-            payload.available_quadruples.insert(
-                key,
-                ecdsa::PreSignatureQuadrupleRef::new(
-                    kappa_unmasked,
-                    lambda_masked,
-                    kappa_times_lambda,
-                    key_times_lambda,
-                ),
+        }
+        for key in newly_available.into_iter() {
+            // the following unwraps are safe
+            let quadruple = payload.quadruples_in_creation.remove(&key).unwrap();
+            let lambda_masked = quadruple.lambda_masked.unwrap();
+            let kappa_unmasked = quadruple.kappa_unmasked.unwrap();
+            let key_times_lambda = quadruple.key_times_lambda.unwrap();
+            let kappa_times_lambda = quadruple.kappa_times_lambda.unwrap();
+            debug!(
+                log,
+                "update_quadruples_in_creation: making of quadruple {:?} is complete", key
+            );
+            payload.available_quadruples.insert(
+                key,
+                ecdsa::PreSignatureQuadrupleRef::new(
+                    kappa_unmasked,
+                    lambda_masked,
+                    kappa_times_lambda,
+                    key_times_lambda,
+                ),
+            );
+        }
+    }
+    Ok(new_transcripts)
+}
+
+/// Helper to build threshold signature inputs from the context and
+/// the pre-signature quadruple
+pub(crate) fn build_signature_inputs(
+    context: &SignWithEcdsaContext,
+    quadruple_ref: &ecdsa::PreSignatureQuadrupleRef,
+    key_transcript_ref: &ecdsa::UnmaskedTranscriptWithAttributes,
+) -> ecdsa::ThresholdEcdsaSigInputsRef {
+    let extended_derivation_path = ExtendedDerivationPath {
+        caller: context.request.sender.into(),
+        derivation_path: context.derivation_path.clone(),
+    };
+    ecdsa::ThresholdEcdsaSigInputsRef::new(
+        extended_derivation_path,
+        context.message_hash,
+        Id::from(context.pseudo_random_id),
+        quadruple_ref.clone(),
+        key_transcript_ref.unmasked_transcript(),
+    )
+}
+
+/// Returns the threshold algorithm used for the transcripts of the given
+/// ECDSA key.
+fn ecdsa_algorithm_id(key_id: &EcdsaKeyId) -> AlgorithmId {
+    match key_id.curve {
+        EcdsaCurve::Secp256k1 => AlgorithmId::ThresholdEcdsaSecp256k1,
+        EcdsaCurve::Secp256r1 => AlgorithmId::ThresholdEcdsaSecp256r1,
+    }
+}
+
+/// Returns the threshold algorithm used for the transcripts of the given
+/// Schnorr key.
+fn schnorr_algorithm_id(key_id: &SchnorrKeyId) -> AlgorithmId {
+    match key_id.algorithm {
+        SchnorrAlgorithm::Bip340Secp256k1 => AlgorithmId::ThresholdSchnorrBip340,
+        SchnorrAlgorithm::Ed25519 => AlgorithmId::ThresholdEd25519,
+    }
+}
+
+/// Return the set of new Schnorr signing requests by assigning them a
+/// RequestId. Requests are paired with Schnorr pre-signatures following the
+/// same rules as ECDSA requests are paired with quadruples, see
+/// [get_signing_requests].
+pub(crate) fn get_schnorr_signing_requests<'a>(
+    height: Height,
+    request_expiry_time: Option<Time>,
+    ecdsa_payload: &mut ecdsa::EcdsaPayload,
+    sign_with_schnorr_contexts: &'a BTreeMap<CallbackId, SignWithSchnorrContext>,
+    valid_keys: &BTreeSet<SchnorrKeyId>,
+    ecdsa_payload_metrics: Option<&EcdsaPayloadMetrics>,
+) -> BTreeMap<ecdsa::RequestId, &'a SignWithSchnorrContext> {
+    let mut known_random_ids_completed = ecdsa_payload
+        .signature_agreements
+        .keys()
+        .cloned()
+        .collect::<BTreeSet<_>>();
+    let known_random_ids_ongoing = ecdsa_payload
+        .ongoing_schnorr_signatures
+        .keys()
+        .map(|id| (id.pseudo_random_id, *id))
+        .collect::<BTreeMap<_, _>>();
+    let mut unassigned_presig_ids = ecdsa_payload
+        .unassigned_schnorr_presig_ids()
+        .collect::<Vec<_>>();
+    // sort in reverse order (bigger to smaller).
+    unassigned_presig_ids.sort_by(|a, b| b.cmp(a));
+    let mut new_requests = BTreeMap::new();
+
+    let reject = |callback_id: &CallbackId,
+                  context: &SignWithSchnorrContext,
+                  code: RejectCode,
+                  message: String| {
+        ecdsa::CompletedSignature::Unreported(ic_types::messages::Response {
+            originator: context.request.sender,
+            respondent: ic_types::CanisterId::ic_00(),
+            originator_reply_callback: *callback_id,
+            deadline: context.request.deadline,
+            refund: context.request.payment,
+            response_payload: ic_types::messages::Payload::Reject(RejectContext { code, message }),
+        })
+    };
+
+    // Reject new requests with unknown key ids. No pre-signatures are
+    // consumed at this stage.
+    for (callback_id, context) in sign_with_schnorr_contexts.iter() {
+        if !known_random_ids_completed.contains(&context.pseudo_random_id)
+            && !known_random_ids_ongoing.contains_key(&context.pseudo_random_id)
+            && !valid_keys.contains(&context.key_id)
+        {
+            ecdsa_payload.signature_agreements.insert(
+                context.pseudo_random_id,
+                reject(
+                    callback_id,
+                    context,
+                    RejectCode::CanisterReject,
+                    format!("Invalid key_id in signature request: {:?}", context.key_id),
+                ),
+            );
+            known_random_ids_completed.insert(context.pseudo_random_id);
+            if let Some(metrics) = ecdsa_payload_metrics {
+                metrics.payload_errors_inc("invalid_schnorr_keyid_requests");
+            }
+        }
+    }
+
+    // Traverse the requests in the order they were created.
+    for (callback_id, context) in sign_with_schnorr_contexts.iter() {
+        if known_random_ids_completed.contains(&context.pseudo_random_id) {
+            continue;
+        }
+        let known_request_id = known_random_ids_ongoing.get(&context.pseudo_random_id);
+        let request_id = match known_request_id {
+            Some(id) => Some(*id),
+            None => unassigned_presig_ids
+                .pop()
+                .map(|presig_id| ecdsa::RequestId {
+                    height,
+                    quadruple_id: presig_id,
+                    pseudo_random_id: context.pseudo_random_id,
+                }),
+        };
+
+        // Reject requests that timed out.
+        if let Some(expiry) = request_expiry_time {
+            if context.batch_time < expiry {
+                ecdsa_payload.signature_agreements.insert(
+                    context.pseudo_random_id,
+                    reject(
+                        callback_id,
+                        context,
+                        RejectCode::CanisterError,
+                        "Signature request expired".to_string(),
+                    ),
+                );
+                if let Some(metrics) = ecdsa_payload_metrics {
+                    metrics.payload_errors_inc("expired_schnorr_requests");
+                }
+                // Remove from other structures if request id exists
+                if let Some(request_id) = request_id {
+                    ecdsa_payload.ongoing_schnorr_signatures.remove(&request_id);
+                    ecdsa_payload
+                        .schnorr_presigs_in_creation
+                        .remove(&request_id.quadruple_id);
+                    ecdsa_payload
+                        .available_schnorr_presigs
+                        .remove(&request_id.quadruple_id);
+                }
+                continue;
+            }
+        }
+        if known_request_id.is_none() {
+            if let Some(request_id) = request_id {
+                new_requests.insert(request_id, context);
+            }
+        }
+    }
+    new_requests
+}
+
+/// Adds new Schnorr signature agreements as "Unreported" by combining the
+/// Schnorr shares in the ECDSA pool.
+///
+/// Unlike [update_signature_agreements], this does not clean up the existing
+/// agreements, as they are shared with the ECDSA requests.
+pub(crate) fn update_schnorr_signature_agreements(
+    all_requests: &BTreeMap<CallbackId, SignWithSchnorrContext>,
+    signature_builder: &dyn EcdsaSignatureBuilder,
+    payload: &mut ecdsa::EcdsaPayload,
+) {
+    let all_random_ids = all_requests
+        .iter()
+        .map(|(callback_id, context)| (context.pseudo_random_id, (callback_id, context)))
+        .collect::<BTreeMap<_, _>>();
+
+    let mut completed = BTreeMap::new();
+    for request_id in payload.ongoing_schnorr_signatures.keys() {
+        let (callback_id, context) = match all_random_ids.get(&request_id.pseudo_random_id) {
+            Some((callback_id, context)) => (callback_id, context),
+            None => continue,
+        };
+
+        let signature = match signature_builder.get_completed_schnorr_signature(request_id) {
+            Some(signature) => signature,
+            None => continue,
+        };
+
+        let response = ic_types::messages::Response {
+            originator: context.request.sender,
+            respondent: ic_types::CanisterId::ic_00(),
+            originator_reply_callback: **callback_id,
+            deadline: context.request.deadline,
+            // Execution is responsible for burning the appropriate cycles
+            // before pushing the new context, so any remaining cycles can
+            // be refunded to the canister.
+            refund: context.request.payment,
+            response_payload: ic_types::messages::Payload::Data(
+                SignWithSchnorrReply {
+                    signature: signature.signature.clone(),
+                }
+                .encode(),
+            ),
+        };
+        completed.insert(*request_id, ecdsa::CompletedSignature::Unreported(response));
+    }
+
+    for (request_id, signature) in completed {
+        payload.ongoing_schnorr_signatures.remove(&request_id);
+        payload
+            .signature_agreements
+            .insert(request_id.pseudo_random_id, signature);
+    }
+}
+
+/// For every new Schnorr signing request, we only start to work on them if
+/// their matched pre-signature has been fully produced.
+pub(crate) fn update_ongoing_schnorr_signatures(
+    new_requests: BTreeMap<ecdsa::RequestId, &SignWithSchnorrContext>,
+    current_key_transcript: Option<&ecdsa::UnmaskedTranscriptWithAttributes>,
+    max_ongoing_signatures: u32,
+    payload: &mut ecdsa::EcdsaPayload,
+    log: ReplicaLogger,
+) -> Result<(), EcdsaPayloadError> {
+    if let Some(key_transcript) = current_key_transcript {
+        debug!(
+            log,
+            "update_ongoing_schnorr_signatures: number of new_requests={}",
+            new_requests.len()
+        );
+        for (request_id, context) in new_requests.into_iter() {
+            if (payload.ongoing_schnorr_signatures.len() as u32) >= max_ongoing_signatures {
+                return Ok(());
+            }
+            if let Some(presig) = payload
+                .available_schnorr_presigs
+                .remove(&request_id.quadruple_id)
+            {
+                let sign_inputs = build_schnorr_signature_inputs(context, &presig, key_transcript);
+                payload
+                    .ongoing_schnorr_signatures
+                    .insert(request_id, sign_inputs);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Creates new Schnorr pre-signatures if necessary, so that the number of
+/// unassigned ones matches `quadruples_to_create_in_advance`.
+fn make_new_schnorr_presigs_if_needed(
+    current_key_transcript: Option<&ecdsa::UnmaskedTranscriptWithAttributes>,
+    ecdsa_config: &EcdsaConfig,
+    ecdsa_payload: &mut ecdsa::EcdsaPayload,
+) -> Result<(), EcdsaPayloadError> {
+    let key_transcript = match current_key_transcript {
+        Some(key_transcript) => key_transcript,
+        None => return Ok(()),
+    };
+    let node_ids: Vec<_> = key_transcript.receivers().iter().copied().collect();
+    let unassigned_presigs = ecdsa_payload.unassigned_schnorr_presig_ids().count();
+    let presigs_to_create = ecdsa_config.quadruples_to_create_in_advance as usize;
+    if presigs_to_create > unassigned_presigs {
+        let uid_generator = &mut ecdsa_payload.uid_generator;
+        for _ in 0..(presigs_to_create - unassigned_presigs) {
+            let blinder_config = new_random_config(
+                &node_ids,
+                key_transcript.registry_version(),
+                uid_generator,
+                key_transcript.algorithm_id(),
+            )?;
+            ecdsa_payload.schnorr_presigs_in_creation.insert(
+                uid_generator.next_quadruple_id(),
+                ecdsa::SchnorrPreSignatureInCreation::new(blinder_config),
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Update the Schnorr pre-signatures in the payload by:
+/// - gathering ready results (new transcripts) from ecdsa pool;
+/// - unmasking the blinder once its masked transcript is available;
+/// - moving completed pre-signatures from "in creation" to "available".
+/// Returns the newly created transcripts.
+fn update_schnorr_presigs_in_creation(
+    current_key_transcript: Option<&ecdsa::UnmaskedTranscriptWithAttributes>,
+    payload: &mut ecdsa::EcdsaPayload,
+    transcript_cache: &dyn EcdsaTranscriptBuilder,
+    height: Height,
+    log: &ReplicaLogger,
+) -> Result<Vec<IDkgTranscript>, EcdsaPayloadError> {
+    let mut newly_available = Vec::new();
+    let mut new_transcripts = Vec::new();
+    let key_transcript = match current_key_transcript {
+        Some(key_transcript) => key_transcript,
+        None => return Ok(new_transcripts),
+    };
+    let registry_version = key_transcript.registry_version();
+    let receivers = key_transcript.receivers().clone();
+    for (key, presig) in payload.schnorr_presigs_in_creation.iter_mut() {
+        if presig.blinder_masked.is_none() {
+            if let Some(transcript) = transcript_cache
+                .get_completed_transcript(presig.blinder_config.as_ref().transcript_id)
+            {
+                debug!(
+                    log,
+                    "update_schnorr_presigs_in_creation: {:?} blinder_masked transcript is made",
+                    key
+                );
+                presig.blinder_masked =
+                    Some(ecdsa::MaskedTranscript::try_from((height, &transcript))?);
+                new_transcripts.push(transcript);
+            }
+        }
+        if presig.blinder_unmasked.is_none() {
+            if let Some(config) = &presig.unmask_blinder_config {
+                if let Some(transcript) =
+                    transcript_cache.get_completed_transcript(config.as_ref().transcript_id)
+                {
+                    debug!(
+                        log,
+                        "update_schnorr_presigs_in_creation: {:?} blinder_unmasked transcript is made",
+                        key
+                    );
+                    presig.blinder_unmasked =
+                        Some(ecdsa::UnmaskedTranscript::try_from((height, &transcript))?);
+                    new_transcripts.push(transcript);
+                }
+            }
+        }
+        // Check what to do in the next step
+        if let (Some(blinder_masked), None) =
+            (&presig.blinder_masked, &presig.unmask_blinder_config)
+        {
+            presig.unmask_blinder_config = Some(ecdsa::ReshareOfMaskedParams::new(
+                payload.uid_generator.next_transcript_id(),
+                receivers.clone(),
+                registry_version,
+                presig.blinder_config.as_ref(),
+                *blinder_masked,
+            ));
+        }
+        if presig.blinder_unmasked.is_some() {
+            newly_available.push(*key);
+        }
+    }
+    for key in newly_available.into_iter() {
+        // the following unwraps are safe
+        let presig = payload.schnorr_presigs_in_creation.remove(&key).unwrap();
+        let blinder_unmasked = presig.blinder_unmasked.unwrap();
+        debug!(
+            log,
+            "update_schnorr_presigs_in_creation: making of pre-signature {:?} is complete", key
+        );
+        payload.available_schnorr_presigs.insert(
+            key,
+            ecdsa::SchnorrPreSignatureTranscriptRef::new(blinder_unmasked),
+        );
+    }
+    Ok(new_transcripts)
+}
+
+/// Helper to build threshold Schnorr signature inputs from the context and
+/// the pre-signature
+pub(crate) fn build_schnorr_signature_inputs(
+    context: &SignWithSchnorrContext,
+    presig_ref: &ecdsa::SchnorrPreSignatureTranscriptRef,
+    key_transcript_ref: &ecdsa::UnmaskedTranscriptWithAttributes,
+) -> ecdsa::ThresholdSchnorrSigInputsRef {
+    let extended_derivation_path = ExtendedDerivationPath {
+        caller: context.request.sender.into(),
+        derivation_path: context.derivation_path.clone(),
+    };
+    ecdsa::ThresholdSchnorrSigInputsRef::new(
+        extended_derivation_path,
+        context.message.clone(),
+        Id::from(context.pseudo_random_id),
+        presig_ref.clone(),
+        key_transcript_ref.unmasked_transcript(),
+    )
+}
+
+/// Updates the ongoing vetKD requests from the vetKD contexts in the state.
+///
+/// Requests with an unknown key id and requests that timed out are rejected.
+/// Since vetKD does not need pre-signatures, all other requests start right
+/// away, using the given NI-DKG transcript as master key. Each request gets a
+/// fresh quadruple id, which is only used to group its key shares in the pool.
+/// Ongoing requests are restarted if the NI-DKG transcript has changed.
+pub(crate) fn update_ongoing_vetkd_requests(
+    height: Height,
+    request_expiry_time: Option<Time>,
+    ni_dkg_id: Option<NiDkgId>,
+    ecdsa_payload: &mut ecdsa::EcdsaPayload,
+    vetkd_contexts: &BTreeMap<CallbackId, VetKdContext>,
+    valid_keys: &BTreeSet<VetKdKeyId>,
+    ecdsa_payload_metrics: Option<&EcdsaPayloadMetrics>,
+) {
+    let known_random_ids_completed = ecdsa_payload
+        .signature_agreements
+        .keys()
+        .cloned()
+        .collect::<BTreeSet<_>>();
+    let known_random_ids_ongoing = ecdsa_payload
+        .ongoing_vetkd_requests
+        .iter()
+        .map(|(id, args)| (id.pseudo_random_id, (*id, args.ni_dkg_id)))
+        .collect::<BTreeMap<_, _>>();
+
+    let reject = |callback_id: &CallbackId,
+                  context: &VetKdContext,
+                  code: RejectCode,
+                  message: String| {
+        ecdsa::CompletedSignature::Unreported(ic_types::messages::Response {
+            originator: context.request.sender,
+            respondent: ic_types::CanisterId::ic_00(),
+            originator_reply_callback: *callback_id,
+            deadline: context.request.deadline,
+            refund: context.request.payment,
+            response_payload: ic_types::messages::Payload::Reject(RejectContext { code, message }),
+        })
+    };
+
+    for (callback_id, context) in vetkd_contexts.iter() {
+        if known_random_ids_completed.contains(&context.pseudo_random_id) {
+            continue;
+        }
+        let ongoing = known_random_ids_ongoing.get(&context.pseudo_random_id);
+
+        let rejection = if !valid_keys.contains(&context.key_id) {
+            Some((
+                RejectCode::CanisterReject,
+                format!("Invalid key_id in vetKD request: {}", context.key_id),
+                "invalid_vetkd_keyid_requests",
+            ))
+        } else if request_expiry_time.map_or(false, |expiry| context.batch_time < expiry) {
+            Some((
+                RejectCode::CanisterError,
+                "vetKD request expired".to_string(),
+                "expired_vetkd_requests",
+            ))
+        } else {
+            None
+        };
+        if let Some((code, message, metric)) = rejection {
+            if let Some((request_id, _)) = ongoing {
+                ecdsa_payload.ongoing_vetkd_requests.remove(request_id);
+            }
+            ecdsa_payload.signature_agreements.insert(
+                context.pseudo_random_id,
+                reject(callback_id, context, code, message),
             );
+            if let Some(metrics) = ecdsa_payload_metrics {
+                metrics.payload_errors_inc(metric);
+            }
+            continue;
+        }
+
+        let ni_dkg_id = match ni_dkg_id {
+            Some(ni_dkg_id) => ni_dkg_id,
+            None => continue,
+        };
+        match ongoing {
+            Some((_, ongoing_ni_dkg_id)) if *ongoing_ni_dkg_id == ni_dkg_id => continue,
+            Some((request_id, _)) => {
+                ecdsa_payload.ongoing_vetkd_requests.remove(request_id);
+            }
+            None => (),
         }
+        let request_id = ecdsa::RequestId {
+            height,
+            quadruple_id: ecdsa_payload.uid_generator.next_quadruple_id(),
+            pseudo_random_id: context.pseudo_random_id,
+        };
+        let args = VetKdArgs {
+            ni_dkg_id,
+            derivation_path: ExtendedDerivationPath {
+                caller: context.request.sender.into(),
+                derivation_path: context.derivation_path.clone(),
+            },
+            input: context.input.clone(),
+            transport_public_key: context.transport_public_key.clone(),
+        };
+        ecdsa_payload
+            .ongoing_vetkd_requests
+            .insert(request_id, args);
     }
-    Ok(new_transcripts)
 }
 
-/// Helper to build threshold signature inputs from the context and
-/// the pre-signature quadruple
-pub(crate) fn build_signature_inputs(
-    context: &SignWithEcdsaContext,
-    quadruple_ref: &ecdsa::PreSignatureQuadrupleRef,
-    key_transcript_ref: &ecdsa::UnmaskedTranscriptWithAttributes,
-) -> ecdsa::ThresholdEcdsaSigInputsRef {
-    let extended_derivation_path = ExtendedDerivationPath {
-        caller: context.request.sender.into(),
-        derivation_path: context.derivation_path.clone(),
-    };
-    ecdsa::ThresholdEcdsaSigInputsRef::new(
-        extended_derivation_path,
-        context.message_hash,
-        Id::from(context.pseudo_random_id),
-        quadruple_ref.clone(),
-        key_transcript_ref.unmasked_transcript(),
-    )
+/// Adds new vetKD agreements as "Unreported" by combining the vetKD key
+/// shares in the ECDSA pool.
+///
+/// Like [update_schnorr_signature_agreements], this does not clean up the
+/// existing agreements, as they are shared with the ECDSA requests.
+pub(crate) fn update_vetkd_agreements(
+    all_requests: &BTreeMap<CallbackId, VetKdContext>,
+    signature_builder: &dyn EcdsaSignatureBuilder,
+    payload: &mut ecdsa::EcdsaPayload,
+) {
+    let all_random_ids = all_requests
+        .iter()
+        .map(|(callback_id, context)| (context.pseudo_random_id, (callback_id, context)))
+        .collect::<BTreeMap<_, _>>();
+
+    let mut completed = BTreeMap::new();
+    for request_id in payload.ongoing_vetkd_requests.keys() {
+        let (callback_id, context) = match all_random_ids.get(&request_id.pseudo_random_id) {
+            Some((callback_id, context)) => (callback_id, context),
+            None => continue,
+        };
+
+        let encrypted_key = match signature_builder.get_completed_vetkd_key(request_id) {
+            Some(encrypted_key) => encrypted_key,
+            None => continue,
+        };
+
+        let response = ic_types::messages::Response {
+            originator: context.request.sender,
+            respondent: ic_types::CanisterId::ic_00(),
+            originator_reply_callback: **callback_id,
+            deadline: context.request.deadline,
+            // Execution is responsible for burning the appropriate cycles
+            // before pushing the new context, so any remaining cycles can
+            // be refunded to the canister.
+            refund: context.request.payment,
+            response_payload: ic_types::messages::Payload::Data(
+                VetKdEncryptedKeyReply {
+                    encrypted_key: encrypted_key.encrypted_key,
+                }
+                .encode(),
+            ),
+        };
+        completed.insert(*request_id, ecdsa::CompletedSignature::Unreported(response));
+    }
+
+    for (request_id, response) in completed {
+        payload.ongoing_vetkd_requests.remove(&request_id);
+        payload
+            .signature_agreements
+            .insert(request_id.pseudo_random_id, response);
+    }
 }
 
 /// Checks for new reshare requests from execution and initiates the processing
@@ -1559,6 +2302,7 @@ fn make_reshare_dealings_response(
                         originator: context.request.sender,
                         respondent: ic_types::CanisterId::ic_00(),
                         originator_reply_callback: *callback_id,
+                        deadline: context.request.deadline,
                         refund: context.request.payment,
                         response_payload: ic_types::messages::Payload::Data(
                             ComputeInitialEcdsaDealingsResponse {
@@ -1742,6 +2486,8 @@ mod tests {
     use ic_types::crypto::canister_threshold_sig::{
         idkg::IDkgTranscriptId, ThresholdEcdsaCombinedSignature,
     };
+    use ic_types::crypto::threshold_sig::ni_dkg::NiDkgTargetSubnet;
+    use ic_types::crypto::vetkd::VetKdEncryptedKey;
     use ic_types::crypto::{CryptoHash, CryptoHashOf};
     use ic_types::{messages::CallbackId, Height, RegistryVersion};
     use std::collections::BTreeSet;
@@ -1833,10 +2579,20 @@ mod tests {
         uid_generator: &mut ecdsa::EcdsaUIDGenerator,
         quadruples_in_creation: &mut BTreeMap<ecdsa::QuadrupleId, ecdsa::QuadrupleInCreation>,
     ) -> (ecdsa::RandomTranscriptParams, ecdsa::RandomTranscriptParams) {
-        let kappa_config_ref =
-            new_random_config(subnet_nodes, registry_version, uid_generator).unwrap();
-        let lambda_config_ref =
-            new_random_config(subnet_nodes, registry_version, uid_generator).unwrap();
+        let kappa_config_ref = new_random_config(
+            subnet_nodes,
+            registry_version,
+            uid_generator,
+            AlgorithmId::ThresholdEcdsaSecp256k1,
+        )
+        .unwrap();
+        let lambda_config_ref = new_random_config(
+            subnet_nodes,
+            registry_version,
+            uid_generator,
+            AlgorithmId::ThresholdEcdsaSecp256k1,
+        )
+        .unwrap();
         quadruples_in_creation.insert(
             uid_generator.next_quadruple_id(),
             ecdsa::QuadrupleInCreation::new(kappa_config_ref.clone(), lambda_config_ref.clone()),
@@ -1876,6 +2632,7 @@ mod tests {
         let result = make_new_quadruples_if_needed_helper(
             &subnet_nodes,
             summary_registry_version,
+            AlgorithmId::ThresholdEcdsaSecp256k1,
             &ecdsa_config,
             &mut ecdsa_payload,
         );
@@ -1900,6 +2657,42 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_ecdsa_make_new_quadruples_for_secp256r1_key() {
+        let subnet_id = subnet_test_id(1);
+        let subnet_nodes = (0..4).map(node_test_id).collect::<Vec<_>>();
+        let mut ecdsa_payload = empty_ecdsa_payload(subnet_id);
+        let update_res = ecdsa_payload.uid_generator.update_height(Height::new(1));
+        assert!(update_res.is_ok());
+        let ecdsa_config = EcdsaConfig {
+            quadruples_to_create_in_advance: 2,
+            ..EcdsaConfig::default()
+        };
+        let result = make_new_quadruples_if_needed_helper(
+            &subnet_nodes,
+            RegistryVersion::new(10),
+            AlgorithmId::ThresholdEcdsaSecp256r1,
+            &ecdsa_config,
+            &mut ecdsa_payload,
+        );
+        assert!(result.is_ok());
+        assert_eq!(ecdsa_payload.quadruples_in_creation.len(), 2);
+        for quadruple in ecdsa_payload.quadruples_in_creation.values() {
+            assert_eq!(
+                quadruple.kappa_config.as_ref().algorithm_id,
+                AlgorithmId::ThresholdEcdsaSecp256r1
+            );
+            assert_eq!(
+                quadruple.lambda_config.as_ref().algorithm_id,
+                AlgorithmId::ThresholdEcdsaSecp256r1
+            );
+        }
+        assert_eq!(
+            ecdsa_algorithm_id(&EcdsaKeyId::from_str("Secp256r1:some_key").unwrap()),
+            AlgorithmId::ThresholdEcdsaSecp256r1
+        );
+    }
+
     #[test]
     fn test_ecdsa_signing_request_order() {
         let subnet_id = subnet_test_id(1);
@@ -2285,6 +3078,249 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_schnorr_request_with_invalid_key() {
+        let subnet_id = subnet_test_id(1);
+        let mut valid_keys = BTreeSet::new();
+        valid_keys.insert(SchnorrKeyId::from_str("Bip340Secp256k1:some_key").unwrap());
+        let mut contexts = BTreeMap::new();
+        contexts.insert(
+            CallbackId::from(1),
+            SignWithSchnorrContext {
+                request: RequestBuilder::new().build(),
+                key_id: SchnorrKeyId::from_str("Ed25519:some_key").unwrap(),
+                message: vec![1; 64],
+                derivation_path: vec![],
+                pseudo_random_id: [0; 32],
+                batch_time: mock_time(),
+            },
+        );
+        let mut ecdsa_payload = empty_ecdsa_payload(subnet_id);
+        let result = get_schnorr_signing_requests(
+            Height::from(1),
+            None,
+            &mut ecdsa_payload,
+            &contexts,
+            &valid_keys,
+            None,
+        );
+
+        // The request is rejected right away, without waiting for a
+        // pre-signature.
+        assert!(result.is_empty());
+        assert!(ecdsa_payload.ongoing_schnorr_signatures.is_empty());
+        assert_eq!(ecdsa_payload.signature_agreements.len(), 1);
+        let (_, response) = ecdsa_payload.signature_agreements.iter().next().unwrap();
+        if let ecdsa::CompletedSignature::Unreported(response) = response {
+            assert!(matches!(
+                response.response_payload,
+                ic_types::messages::Payload::Reject(..)
+            ));
+        } else {
+            panic!("Unexpected response");
+        }
+    }
+
+    fn fake_high_threshold_dkg_id(subnet_id: SubnetId, height: u64) -> NiDkgId {
+        NiDkgId {
+            start_block_height: Height::from(height),
+            dealer_subnet: subnet_id,
+            dkg_tag: NiDkgTag::HighThreshold,
+            target_subnet: NiDkgTargetSubnet::Local,
+        }
+    }
+
+    fn fake_vetkd_context(key_id: &str, pseudo_random_id: [u8; 32]) -> VetKdContext {
+        VetKdContext {
+            request: RequestBuilder::new().build(),
+            key_id: VetKdKeyId::from_str(key_id).unwrap(),
+            derivation_path: vec![],
+            input: vec![1; 32],
+            transport_public_key: vec![2; 48],
+            pseudo_random_id,
+            batch_time: mock_time(),
+        }
+    }
+
+    #[test]
+    fn test_vetkd_update_ongoing_requests() {
+        let subnet_id = subnet_test_id(1);
+        let mut valid_keys = BTreeSet::new();
+        valid_keys.insert(VetKdKeyId::from_str("Bls12381G2:some_key").unwrap());
+        let mut contexts = BTreeMap::new();
+        contexts.insert(
+            CallbackId::from(1),
+            fake_vetkd_context("Bls12381G2:some_key", [1; 32]),
+        );
+        contexts.insert(
+            CallbackId::from(2),
+            fake_vetkd_context("Bls12381G2:other_key", [2; 32]),
+        );
+        let ni_dkg_id = fake_high_threshold_dkg_id(subnet_id, 0);
+        let mut ecdsa_payload = empty_ecdsa_payload(subnet_id);
+        update_ongoing_vetkd_requests(
+            Height::from(1),
+            None,
+            Some(ni_dkg_id),
+            &mut ecdsa_payload,
+            &contexts,
+            &valid_keys,
+            None,
+        );
+
+        // The request with a valid key starts right away, the other one is
+        // rejected.
+        assert_eq!(ecdsa_payload.ongoing_vetkd_requests.len(), 1);
+        let (request_id, args) = ecdsa_payload.ongoing_vetkd_requests.iter().next().unwrap();
+        assert_eq!(request_id.pseudo_random_id, [1; 32]);
+        assert_eq!(request_id.height, Height::from(1));
+        assert_eq!(args.ni_dkg_id, ni_dkg_id);
+        assert_eq!(ecdsa_payload.signature_agreements.len(), 1);
+        match ecdsa_payload.signature_agreements.get(&[2; 32]) {
+            Some(ecdsa::CompletedSignature::Unreported(response)) => assert!(matches!(
+                response.response_payload,
+                ic_types::messages::Payload::Reject(..)
+            )),
+            _ => panic!("Unexpected response"),
+        }
+
+        // Nothing changes as long as the NI-DKG transcript stays the same.
+        let payload_before = ecdsa_payload.clone();
+        update_ongoing_vetkd_requests(
+            Height::from(2),
+            None,
+            Some(ni_dkg_id),
+            &mut ecdsa_payload,
+            &contexts,
+            &valid_keys,
+            None,
+        );
+        assert_eq!(ecdsa_payload, payload_before);
+
+        // The request is restarted once the NI-DKG transcript changes.
+        let next_ni_dkg_id = fake_high_threshold_dkg_id(subnet_id, 10);
+        update_ongoing_vetkd_requests(
+            Height::from(11),
+            None,
+            Some(next_ni_dkg_id),
+            &mut ecdsa_payload,
+            &contexts,
+            &valid_keys,
+            None,
+        );
+        assert_eq!(ecdsa_payload.ongoing_vetkd_requests.len(), 1);
+        let (request_id, args) = ecdsa_payload.ongoing_vetkd_requests.iter().next().unwrap();
+        assert_eq!(request_id.pseudo_random_id, [1; 32]);
+        assert_eq!(request_id.height, Height::from(11));
+        assert_eq!(args.ni_dkg_id, next_ni_dkg_id);
+    }
+
+    #[test]
+    fn test_vetkd_update_agreements() {
+        let subnet_id = subnet_test_id(1);
+        let mut valid_keys = BTreeSet::new();
+        valid_keys.insert(VetKdKeyId::from_str("Bls12381G2:some_key").unwrap());
+        let mut contexts = BTreeMap::new();
+        contexts.insert(
+            CallbackId::from(1),
+            fake_vetkd_context("Bls12381G2:some_key", [1; 32]),
+        );
+        let mut ecdsa_payload = empty_ecdsa_payload(subnet_id);
+        update_ongoing_vetkd_requests(
+            Height::from(1),
+            None,
+            Some(fake_high_threshold_dkg_id(subnet_id, 0)),
+            &mut ecdsa_payload,
+            &contexts,
+            &valid_keys,
+            None,
+        );
+        let request_id = *ecdsa_payload.ongoing_vetkd_requests.keys().next().unwrap();
+
+        // No agreement without a combined key.
+        let mut signature_builder = TestEcdsaSignatureBuilder::new();
+        update_vetkd_agreements(&contexts, &signature_builder, &mut ecdsa_payload);
+        assert!(ecdsa_payload.signature_agreements.is_empty());
+        assert_eq!(ecdsa_payload.ongoing_vetkd_requests.len(), 1);
+
+        signature_builder.vetkd_keys.insert(
+            request_id,
+            VetKdEncryptedKey {
+                encrypted_key: vec![3; 192],
+            },
+        );
+        update_vetkd_agreements(&contexts, &signature_builder, &mut ecdsa_payload);
+        assert!(ecdsa_payload.ongoing_vetkd_requests.is_empty());
+        match ecdsa_payload.signature_agreements.get(&[1; 32]) {
+            Some(ecdsa::CompletedSignature::Unreported(response)) => {
+                match &response.response_payload {
+                    ic_types::messages::Payload::Data(data) => assert_eq!(
+                        VetKdEncryptedKeyReply::decode(data).unwrap().encrypted_key,
+                        vec![3; 192]
+                    ),
+                    _ => panic!("Unexpected response payload"),
+                }
+            }
+            _ => panic!("Unexpected response"),
+        }
+    }
+
+    #[test]
+    fn test_schnorr_make_new_presigs_if_needed() {
+        let subnet_id = subnet_test_id(1);
+        let env = CanisterThresholdSigTestEnvironment::new(4);
+        let mut ecdsa_payload = empty_ecdsa_payload(subnet_id);
+        let update_res = ecdsa_payload.uid_generator.update_height(Height::new(1));
+        assert!(update_res.is_ok());
+        let quadruples_to_create_in_advance = 3;
+        let ecdsa_config = EcdsaConfig {
+            quadruples_to_create_in_advance,
+            ..EcdsaConfig::default()
+        };
+
+        // Nothing is created as long as there is no Schnorr key.
+        let result = make_new_schnorr_presigs_if_needed(None, &ecdsa_config, &mut ecdsa_payload);
+        assert!(result.is_ok());
+        assert!(ecdsa_payload.schnorr_presigs_in_creation.is_empty());
+
+        let idkg_key_transcript =
+            generate_key_transcript(&env, AlgorithmId::ThresholdSchnorrBip340);
+        let key_transcript_ref =
+            ecdsa::UnmaskedTranscript::try_from((Height::from(0), &idkg_key_transcript)).unwrap();
+        let key_transcript = ecdsa::UnmaskedTranscriptWithAttributes::new(
+            idkg_key_transcript.to_attributes(),
+            key_transcript_ref,
+        );
+        let result = make_new_schnorr_presigs_if_needed(
+            Some(&key_transcript),
+            &ecdsa_config,
+            &mut ecdsa_payload,
+        );
+        assert!(result.is_ok());
+        assert_eq!(
+            ecdsa_payload.schnorr_presigs_in_creation.len(),
+            quadruples_to_create_in_advance as usize
+        );
+        for presig in ecdsa_payload.schnorr_presigs_in_creation.values() {
+            assert_eq!(
+                presig.blinder_config.as_ref().algorithm_id,
+                AlgorithmId::ThresholdSchnorrBip340
+            );
+        }
+
+        // Calling again does not create more pre-signatures.
+        let result = make_new_schnorr_presigs_if_needed(
+            Some(&key_transcript),
+            &ecdsa_config,
+            &mut ecdsa_payload,
+        );
+        assert!(result.is_ok());
+        assert_eq!(
+            ecdsa_payload.schnorr_presigs_in_creation.len(),
+            quadruples_to_create_in_advance as usize
+        );
+    }
+
     #[test]
     fn test_ecdsa_update_next_key_transcript() {
         let num_of_nodes = 4;
@@ -2312,6 +3348,7 @@ mod tests {
             None,
             &mut payload.key_transcript.next_in_creation,
             &mut payload.uid_generator,
+            AlgorithmId::ThresholdEcdsaSecp256k1,
             &transcript_builder,
             cur_height,
             no_op_logger(),
@@ -2345,6 +3382,7 @@ mod tests {
             None,
             &mut payload.key_transcript.next_in_creation,
             &mut payload.uid_generator,
+            AlgorithmId::ThresholdEcdsaSecp256k1,
             &transcript_builder,
             cur_height,
             no_op_logger(),
@@ -2384,6 +3422,7 @@ mod tests {
             None,
             &mut payload.key_transcript.next_in_creation,
             &mut payload.uid_generator,
+            AlgorithmId::ThresholdEcdsaSecp256k1,
             &transcript_builder,
             cur_height,
             no_op_logger(),
@@ -2421,6 +3460,7 @@ mod tests {
             Some(&current_key_transcript),
             &mut payload.key_transcript.next_in_creation,
             &mut payload.uid_generator,
+            AlgorithmId::ThresholdEcdsaSecp256k1,
             &transcript_builder,
             cur_height,
             no_op_logger(),
@@ -2455,6 +3495,7 @@ mod tests {
             Some(&current_key_transcript),
             &mut payload.key_transcript.next_in_creation,
             &mut payload.uid_generator,
+            AlgorithmId::ThresholdEcdsaSecp256k1,
             &transcript_builder,
             cur_height,
             no_op_logger(),
@@ -2505,6 +3546,7 @@ mod tests {
             None,
             &mut payload.key_transcript.next_in_creation,
             &mut payload.uid_generator,
+            AlgorithmId::ThresholdEcdsaSecp256k1,
             &transcript_builder,
             cur_height,
             no_op_logger(),
@@ -2538,6 +3580,7 @@ mod tests {
             None,
             &mut payload.key_transcript.next_in_creation,
             &mut payload.uid_generator,
+            AlgorithmId::ThresholdEcdsaSecp256k1,
             &transcript_builder,
             cur_height,
             no_op_logger(),
@@ -2577,6 +3620,7 @@ mod tests {
             None,
             &mut payload.key_transcript.next_in_creation,
             &mut payload.uid_generator,
+            AlgorithmId::ThresholdEcdsaSecp256k1,
             &transcript_builder,
             cur_height,
             no_op_logger(),
@@ -2626,6 +3670,7 @@ mod tests {
             None,
             &mut payload.key_transcript.next_in_creation,
             &mut payload.uid_generator,
+            AlgorithmId::ThresholdEcdsaSecp256k1,
             &transcript_builder,
             cur_height,
             no_op_logger(),
@@ -2664,6 +3709,7 @@ mod tests {
             None,
             &mut payload.key_transcript.next_in_creation,
             &mut payload.uid_generator,
+            AlgorithmId::ThresholdEcdsaSecp256k1,
             &transcript_builder,
             cur_height,
             no_op_logger(),
@@ -2841,6 +3887,9 @@ mod tests {
             &[node_test_id(0)],
             &sign_with_ecdsa_contexts,
             &BTreeMap::default(),
+            &BTreeMap::default(),
+            None,
+            &BTreeMap::default(),
             &block_reader,
             &transcript_builder,
             &signature_builder,
@@ -2863,6 +3912,9 @@ mod tests {
             &[node_test_id(0)],
             &sign_with_ecdsa_contexts,
             &BTreeMap::default(),
+            &BTreeMap::default(),
+            None,
+            &BTreeMap::default(),
             &block_reader,
             &transcript_builder,
             &signature_builder,
@@ -3880,6 +4932,9 @@ mod tests {
                 &node_ids,
                 &BTreeMap::default(),
                 &BTreeMap::default(),
+                &BTreeMap::default(),
+                None,
+                &BTreeMap::default(),
                 &block_reader,
                 &transcript_builder,
                 &signature_builder,
@@ -31,6 +31,7 @@ use crate::ecdsa::payload_builder::{
     get_ecdsa_config_if_enabled,
 };
 use ic_crypto::MegaKeyFromRegistryError;
+use ic_interfaces::crypto::ErrorReproducibility;
 use ic_interfaces::validation::{ValidationError, ValidationResult};
 use ic_interfaces_registry::RegistryClient;
 use ic_interfaces_state_manager::{StateManager, StateManagerError};
@@ -46,10 +47,12 @@ use ic_types::{
         error::{
             IDkgVerifyInitialDealingsError, IDkgVerifyTranscriptError,
             ThresholdEcdsaVerifyCombinedSignatureError,
+            ThresholdSchnorrVerifyCombinedSignatureError,
         },
         idkg::{IDkgTranscript, IDkgTranscriptId, InitialIDkgDealings, SignedIDkgDealing},
-        ThresholdEcdsaCombinedSignature,
+        ThresholdEcdsaCombinedSignature, ThresholdSchnorrCombinedSignature,
     },
+    crypto::vetkd::{VetKdEncryptedKey, VetKdKeyVerificationError},
     registry::RegistryClientError,
     Height, RegistryVersion, SubnetId,
 };
@@ -63,6 +66,7 @@ pub enum TransientError {
     RegistryClientError(RegistryClientError),
     EcdsaPayloadError(EcdsaPayloadError),
     StateManagerError(StateManagerError),
+    VetKdKeyVerificationError(VetKdKeyVerificationError),
 }
 
 #[derive(Debug)]
@@ -73,8 +77,11 @@ pub enum PermanentError {
     UnexpectedDataPayload(Option<EcdsaPayloadError>),
     InvalidChainCacheError(InvalidChainCacheError),
     ThresholdEcdsaSigInputsError(ecdsa::ThresholdEcdsaSigInputsError),
+    ThresholdSchnorrSigInputsError(ecdsa::ThresholdSchnorrSigInputsError),
     TranscriptParamsError(ecdsa::TranscriptParamsError),
     ThresholdEcdsaVerifyCombinedSignatureError(ThresholdEcdsaVerifyCombinedSignatureError),
+    ThresholdSchnorrVerifyCombinedSignatureError(ThresholdSchnorrVerifyCombinedSignatureError),
+    VetKdKeyVerificationError(VetKdKeyVerificationError),
     IDkgVerifyTranscriptError(IDkgVerifyTranscriptError),
     IDkgVerifyInitialDealingsError(IDkgVerifyInitialDealingsError),
     MegaKeyFromRegistryError(MegaKeyFromRegistryError),
@@ -146,6 +153,12 @@ impl From<ecdsa::ThresholdEcdsaSigInputsError> for PermanentError {
     }
 }
 
+impl From<ecdsa::ThresholdSchnorrSigInputsError> for PermanentError {
+    fn from(err: ecdsa::ThresholdSchnorrSigInputsError) -> Self {
+        PermanentError::ThresholdSchnorrSigInputsError(err)
+    }
+}
+
 impl From<ecdsa::TranscriptParamsError> for PermanentError {
     fn from(err: ecdsa::TranscriptParamsError) -> Self {
         PermanentError::TranscriptParamsError(err)
@@ -373,11 +386,31 @@ pub fn validate_data_payload(
         || validate_new_signature_agreements(crypto, &block_reader, &prev_payload, curr_payload),
         metrics,
     )?;
+    let schnorr_signatures = timed_call(
+        "validate_new_schnorr_signature_agreements",
+        || {
+            validate_new_schnorr_signature_agreements(
+                crypto,
+                &block_reader,
+                &prev_payload,
+                curr_payload,
+            )
+        },
+        metrics,
+    )?;
+
+    let vetkd_keys = timed_call(
+        "validate_new_vetkd_key_agreements",
+        || validate_new_vetkd_key_agreements(crypto, &prev_payload, curr_payload),
+        metrics,
+    )?;
 
     let builder = CachedBuilder {
         transcripts,
         dealings,
         signatures,
+        schnorr_signatures,
+        vetkd_keys,
     };
 
     let ecdsa_payload = create_data_payload_helper(
@@ -406,6 +439,8 @@ struct CachedBuilder {
     transcripts: BTreeMap<IDkgTranscriptId, IDkgTranscript>,
     dealings: BTreeMap<IDkgTranscriptId, Vec<SignedIDkgDealing>>,
     signatures: BTreeMap<ecdsa::PseudoRandomId, ThresholdEcdsaCombinedSignature>,
+    schnorr_signatures: BTreeMap<ecdsa::PseudoRandomId, ThresholdSchnorrCombinedSignature>,
+    vetkd_keys: BTreeMap<ecdsa::PseudoRandomId, VetKdEncryptedKey>,
 }
 
 impl EcdsaTranscriptBuilder for CachedBuilder {
@@ -428,6 +463,19 @@ impl EcdsaSignatureBuilder for CachedBuilder {
     ) -> Option<ThresholdEcdsaCombinedSignature> {
         self.signatures.get(&request_id.pseudo_random_id).cloned()
     }
+
+    fn get_completed_schnorr_signature(
+        &self,
+        request_id: &ecdsa::RequestId,
+    ) -> Option<ThresholdSchnorrCombinedSignature> {
+        self.schnorr_signatures
+            .get(&request_id.pseudo_random_id)
+            .cloned()
+    }
+
+    fn get_completed_vetkd_key(&self, request_id: &ecdsa::RequestId) -> Option<VetKdEncryptedKey> {
+        self.vetkd_keys.get(&request_id.pseudo_random_id).cloned()
+    }
 }
 
 // Validate transcript references
@@ -540,6 +588,13 @@ fn validate_new_signature_agreements(
     use PermanentError::*;
     let mut new_signatures = BTreeMap::new();
     for (random_id, completed) in curr_payload.signature_agreements.iter() {
+        // Schnorr signatures and vetKD keys are validated by
+        // validate_new_schnorr_signature_agreements and
+        // validate_new_vetkd_key_agreements respectively
+        if is_schnorr_request(prev_payload, random_id) || is_vetkd_request(prev_payload, random_id)
+        {
+            continue;
+        }
         if let ecdsa::CompletedSignature::Unreported(response) = completed {
             if let ic_types::messages::Payload::Data(data) = &response.response_payload {
                 use ic_ic00_types::{Payload, SignWithECDSAReply};
@@ -575,6 +630,108 @@ fn validate_new_signature_agreements(
     Ok(new_signatures)
 }
 
+// Returns true if the given pseudo random id belongs to an ongoing Schnorr signature
+// request in the given payload.
+fn is_schnorr_request(payload: &ecdsa::EcdsaPayload, random_id: &ecdsa::PseudoRandomId) -> bool {
+    payload
+        .ongoing_schnorr_signatures
+        .keys()
+        .any(|request_id| request_id.pseudo_random_id == *random_id)
+}
+
+// Validate new Schnorr signature agreements in the current payload.
+// New signatures are those that are Unreported in the curr_payload and not in prev_payload,
+// and that belong to an ongoing Schnorr signature request in prev_payload.
+fn validate_new_schnorr_signature_agreements(
+    crypto: &dyn ConsensusCrypto,
+    block_reader: &dyn EcdsaBlockReader,
+    prev_payload: &ecdsa::EcdsaPayload,
+    curr_payload: &ecdsa::EcdsaPayload,
+) -> Result<BTreeMap<ecdsa::PseudoRandomId, ThresholdSchnorrCombinedSignature>, EcdsaValidationError>
+{
+    use PermanentError::*;
+    let mut new_signatures = BTreeMap::new();
+    for (request_id, sig_input_ref) in prev_payload.ongoing_schnorr_signatures.iter() {
+        let random_id = &request_id.pseudo_random_id;
+        if let Some(ecdsa::CompletedSignature::Unreported(response)) =
+            curr_payload.signature_agreements.get(random_id)
+        {
+            if let ic_types::messages::Payload::Data(data) = &response.response_payload {
+                use ic_ic00_types::{Payload, SignWithSchnorrReply};
+                let reply = SignWithSchnorrReply::decode(data)
+                    .map_err(|err| PermanentError::DecodingError(format!("{:?}", err)))?;
+                let signature = ThresholdSchnorrCombinedSignature {
+                    signature: reply.signature,
+                };
+                if prev_payload.signature_agreements.get(random_id).is_some() {
+                    return Err(PermanentError::NewSignatureUnexpected(*random_id).into());
+                }
+
+                let input = sig_input_ref
+                    .translate(block_reader)
+                    .map_err(PermanentError::from)?;
+                crypto
+                    .verify_schnorr_combined_sig(&input, &signature)
+                    .map_err(ThresholdSchnorrVerifyCombinedSignatureError)?;
+                new_signatures.insert(*random_id, signature);
+            }
+        }
+    }
+    Ok(new_signatures)
+}
+
+// Returns true if the given pseudo random id belongs to an ongoing vetKD request
+// in the given payload.
+fn is_vetkd_request(payload: &ecdsa::EcdsaPayload, random_id: &ecdsa::PseudoRandomId) -> bool {
+    payload
+        .ongoing_vetkd_requests
+        .keys()
+        .any(|request_id| request_id.pseudo_random_id == *random_id)
+}
+
+// Validate new vetKD key agreements in the current payload.
+// New keys are those that are Unreported in the curr_payload and not in prev_payload,
+// and that belong to an ongoing vetKD request in prev_payload.
+fn validate_new_vetkd_key_agreements(
+    crypto: &dyn ConsensusCrypto,
+    prev_payload: &ecdsa::EcdsaPayload,
+    curr_payload: &ecdsa::EcdsaPayload,
+) -> Result<BTreeMap<ecdsa::PseudoRandomId, VetKdEncryptedKey>, EcdsaValidationError> {
+    let mut new_keys = BTreeMap::new();
+    for (request_id, args) in prev_payload.ongoing_vetkd_requests.iter() {
+        let random_id = &request_id.pseudo_random_id;
+        if let Some(ecdsa::CompletedSignature::Unreported(response)) =
+            curr_payload.signature_agreements.get(random_id)
+        {
+            if let ic_types::messages::Payload::Data(data) = &response.response_payload {
+                use ic_ic00_types::{Payload, VetKdEncryptedKeyReply};
+                let reply = VetKdEncryptedKeyReply::decode(data)
+                    .map_err(|err| PermanentError::DecodingError(format!("{:?}", err)))?;
+                let encrypted_key = VetKdEncryptedKey {
+                    encrypted_key: reply.encrypted_key,
+                };
+                if prev_payload.signature_agreements.get(random_id).is_some() {
+                    return Err(PermanentError::NewSignatureUnexpected(*random_id).into());
+                }
+
+                crypto
+                    .verify_encrypted_key(&encrypted_key, args)
+                    .map_err(|err| {
+                        if err.is_reproducible() {
+                            EcdsaValidationError::from(PermanentError::VetKdKeyVerificationError(
+                                err,
+                            ))
+                        } else {
+                            TransientError::VetKdKeyVerificationError(err).into()
+                        }
+                    })?;
+                new_keys.insert(*random_id, encrypted_key);
+            }
+        }
+    }
+    Ok(new_keys)
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
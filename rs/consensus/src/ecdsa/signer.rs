@@ -10,18 +10,25 @@ use crate::ecdsa::utils::{load_transcripts, EcdsaBlockReaderImpl};
 use ic_interfaces::consensus_pool::ConsensusBlockCache;
 use ic_interfaces::crypto::{
     ErrorReproducibility, ThresholdEcdsaSigVerifier, ThresholdEcdsaSigner,
+    ThresholdSchnorrSigVerifier, ThresholdSchnorrSigner, VetKdProtocol,
 };
 use ic_interfaces::ecdsa::{EcdsaChangeAction, EcdsaChangeSet, EcdsaPool};
 use ic_logger::{debug, warn, ReplicaLogger};
 use ic_metrics::MetricsRegistry;
 use ic_types::artifact::EcdsaMessageId;
 use ic_types::consensus::ecdsa::{
-    sig_share_prefix, EcdsaBlockReader, EcdsaMessage, EcdsaSigShare, EcdsaStats, RequestId,
-    ThresholdEcdsaSigInputsRef,
+    schnorr_sig_share_prefix, sig_share_prefix, vetkd_key_share_prefix, EcdsaBlockReader,
+    EcdsaMessage, EcdsaSigShare, EcdsaStats, RequestId, SchnorrSigShare,
+    ThresholdEcdsaSigInputsRef, ThresholdSchnorrSigInputsRef, VetKdKeyShare,
 };
 use ic_types::crypto::canister_threshold_sig::{
-    error::ThresholdEcdsaCombineSigSharesError, ThresholdEcdsaCombinedSignature,
-    ThresholdEcdsaSigInputs, ThresholdEcdsaSigShare,
+    error::{ThresholdEcdsaCombineSigSharesError, ThresholdSchnorrCombineSigSharesError},
+    ExtendedDerivationPath, ThresholdEcdsaCombinedSignature, ThresholdEcdsaSigInputs,
+    ThresholdEcdsaSigShare, ThresholdSchnorrCombinedSignature, ThresholdSchnorrSigInputs,
+    ThresholdSchnorrSigShare,
+};
+use ic_types::crypto::vetkd::{
+    VetKdArgs, VetKdEncryptedKey, VetKdEncryptedKeyShare, VetKdKeyShareCombinationError,
 };
 use ic_types::{Height, NodeId};
 
@@ -171,6 +178,192 @@ impl EcdsaSignerImpl {
         ret
     }
 
+    /// Generates Schnorr signature shares for the newly added Schnorr
+    /// signature requests.
+    fn send_schnorr_signature_shares(
+        &self,
+        ecdsa_pool: &dyn EcdsaPool,
+        transcript_loader: &dyn EcdsaTranscriptLoader,
+        block_reader: &dyn EcdsaBlockReader,
+    ) -> EcdsaChangeSet {
+        block_reader
+            .requested_schnorr_signatures()
+            .filter(|(request_id, _)| {
+                !self.signer_has_issued_schnorr_signature_share(
+                    ecdsa_pool,
+                    &self.node_id,
+                    request_id,
+                )
+            })
+            .flat_map(|(request_id, sig_inputs_ref)| {
+                self.resolve_schnorr_ref(sig_inputs_ref, block_reader, "send_signature_shares")
+                    .map_or(Default::default(), |sig_inputs| {
+                        self.crypto_create_schnorr_signature_share(
+                            ecdsa_pool,
+                            transcript_loader,
+                            request_id,
+                            &sig_inputs,
+                        )
+                    })
+            })
+            .collect()
+    }
+
+    /// Processes the received Schnorr signature shares
+    fn validate_schnorr_signature_shares(
+        &self,
+        ecdsa_pool: &dyn EcdsaPool,
+        block_reader: &dyn EcdsaBlockReader,
+    ) -> EcdsaChangeSet {
+        let sig_inputs_map = block_reader
+            .requested_schnorr_signatures()
+            .map(|(request_id, sig_inputs)| (*request_id, sig_inputs))
+            .collect::<BTreeMap<_, _>>();
+
+        // Collection of validated shares
+        let mut validated_sig_shares = BTreeSet::new();
+
+        let mut ret = Vec::new();
+        for (id, share) in ecdsa_pool.unvalidated().schnorr_signature_shares() {
+            // Remove the duplicate entries
+            let key = (share.request_id, share.signer_id);
+            if validated_sig_shares.contains(&key) {
+                self.metrics
+                    .sign_errors_inc("duplicate_schnorr_sig_shares_in_batch");
+                ret.push(EcdsaChangeAction::HandleInvalid(
+                    id,
+                    format!("Duplicate share in unvalidated batch: {}", share),
+                ));
+                continue;
+            }
+
+            match Action::action(block_reader, &sig_inputs_map, &share.request_id) {
+                Action::Process(sig_inputs_ref) => {
+                    if self.signer_has_issued_schnorr_signature_share(
+                        ecdsa_pool,
+                        &share.signer_id,
+                        &share.request_id,
+                    ) {
+                        // The node already sent a valid share for this request
+                        self.metrics.sign_errors_inc("duplicate_schnorr_sig_share");
+                        ret.push(EcdsaChangeAction::HandleInvalid(
+                            id,
+                            format!("Duplicate share: {}", share),
+                        ))
+                    } else {
+                        match self.resolve_schnorr_ref(
+                            sig_inputs_ref,
+                            block_reader,
+                            "validate_signature_shares",
+                        ) {
+                            Some(sig_inputs) => {
+                                let action = self.crypto_verify_schnorr_signature_share(
+                                    &id,
+                                    &sig_inputs,
+                                    &share,
+                                    ecdsa_pool.stats(),
+                                );
+                                if let Some(EcdsaChangeAction::MoveToValidated(_)) = action {
+                                    validated_sig_shares.insert(key);
+                                }
+                                ret.append(&mut action.into_iter().collect());
+                            }
+                            None => {
+                                ret.push(EcdsaChangeAction::HandleInvalid(
+                                    id,
+                                    format!(
+                                        "validate_schnorr_signature_shares(): failed to translate: {}",
+                                        share
+                                    ),
+                                ));
+                            }
+                        }
+                    }
+                }
+                Action::Drop => ret.push(EcdsaChangeAction::RemoveUnvalidated(id)),
+                Action::Defer => {}
+            }
+        }
+        ret
+    }
+
+    /// Generates vetKD encrypted key shares for the newly added vetKD
+    /// requests.
+    fn send_vetkd_key_shares(
+        &self,
+        ecdsa_pool: &dyn EcdsaPool,
+        block_reader: &dyn EcdsaBlockReader,
+    ) -> EcdsaChangeSet {
+        block_reader
+            .requested_vetkd_keys()
+            .filter(|(request_id, _)| {
+                !self.signer_has_issued_vetkd_key_share(ecdsa_pool, &self.node_id, request_id)
+            })
+            .flat_map(|(request_id, args)| self.crypto_create_vetkd_key_share(request_id, args))
+            .collect()
+    }
+
+    /// Processes the received vetKD encrypted key shares
+    fn validate_vetkd_key_shares(
+        &self,
+        ecdsa_pool: &dyn EcdsaPool,
+        block_reader: &dyn EcdsaBlockReader,
+    ) -> EcdsaChangeSet {
+        let args_map = block_reader
+            .requested_vetkd_keys()
+            .map(|(request_id, args)| (*request_id, args))
+            .collect::<BTreeMap<_, _>>();
+
+        // Collection of validated shares
+        let mut validated_key_shares = BTreeSet::new();
+
+        let mut ret = Vec::new();
+        for (id, share) in ecdsa_pool.unvalidated().vetkd_key_shares() {
+            // Remove the duplicate entries
+            let key = (share.request_id, share.signer_id);
+            if validated_key_shares.contains(&key) {
+                self.metrics
+                    .sign_errors_inc("duplicate_vetkd_key_shares_in_batch");
+                ret.push(EcdsaChangeAction::HandleInvalid(
+                    id,
+                    format!("Duplicate share in unvalidated batch: {}", share),
+                ));
+                continue;
+            }
+
+            match Action::action(block_reader, &args_map, &share.request_id) {
+                Action::Process(args) => {
+                    if self.signer_has_issued_vetkd_key_share(
+                        ecdsa_pool,
+                        &share.signer_id,
+                        &share.request_id,
+                    ) {
+                        // The node already sent a valid share for this request
+                        self.metrics.sign_errors_inc("duplicate_vetkd_key_share");
+                        ret.push(EcdsaChangeAction::HandleInvalid(
+                            id,
+                            format!("Duplicate share: {}", share),
+                        ))
+                    } else {
+                        let action = self.crypto_verify_vetkd_key_share(
+                            &id,
+                            args,
+                            &share,
+                            ecdsa_pool.stats(),
+                        );
+                        if let Some(EcdsaChangeAction::MoveToValidated(_)) = action {
+                            validated_key_shares.insert(key);
+                        }
+                        ret.append(&mut action.into_iter().collect());
+                    }
+                }
+                Action::Drop => ret.push(EcdsaChangeAction::RemoveUnvalidated(id)),
+                Action::Defer => {}
+            }
+        }
+        ret
+    }
+
     /// Purges the entries no longer needed from the artifact pool
     fn purge_artifacts(
         &self,
@@ -180,6 +373,16 @@ impl EcdsaSignerImpl {
         let in_progress = block_reader
             .requested_signatures()
             .map(|(request_id, _)| *request_id)
+            .chain(
+                block_reader
+                    .requested_schnorr_signatures()
+                    .map(|(request_id, _)| *request_id),
+            )
+            .chain(
+                block_reader
+                    .requested_vetkd_keys()
+                    .map(|(request_id, _)| *request_id),
+            )
             .collect::<BTreeSet<_>>();
 
         let mut ret = Vec::new();
@@ -203,6 +406,42 @@ impl EcdsaSignerImpl {
             .collect();
         ret.append(&mut action);
 
+        // Unvalidated Schnorr signature shares.
+        let mut action = ecdsa_pool
+            .unvalidated()
+            .schnorr_signature_shares()
+            .filter(|(_, share)| should_purge(&share.request_id, current_height, &in_progress))
+            .map(|(id, _)| EcdsaChangeAction::RemoveUnvalidated(id))
+            .collect();
+        ret.append(&mut action);
+
+        // Validated Schnorr signature shares.
+        let mut action = ecdsa_pool
+            .validated()
+            .schnorr_signature_shares()
+            .filter(|(_, share)| should_purge(&share.request_id, current_height, &in_progress))
+            .map(|(id, _)| EcdsaChangeAction::RemoveValidated(id))
+            .collect();
+        ret.append(&mut action);
+
+        // Unvalidated vetKD key shares.
+        let mut action = ecdsa_pool
+            .unvalidated()
+            .vetkd_key_shares()
+            .filter(|(_, share)| should_purge(&share.request_id, current_height, &in_progress))
+            .map(|(id, _)| EcdsaChangeAction::RemoveUnvalidated(id))
+            .collect();
+        ret.append(&mut action);
+
+        // Validated vetKD key shares.
+        let mut action = ecdsa_pool
+            .validated()
+            .vetkd_key_shares()
+            .filter(|(_, share)| should_purge(&share.request_id, current_height, &in_progress))
+            .map(|(id, _)| EcdsaChangeAction::RemoveValidated(id))
+            .collect();
+        ret.append(&mut action);
+
         ret
     }
 
@@ -306,6 +545,183 @@ impl EcdsaSignerImpl {
         )
     }
 
+    /// Load necessary transcripts for the Schnorr inputs
+    fn load_schnorr_dependencies(
+        &self,
+        ecdsa_pool: &dyn EcdsaPool,
+        transcript_loader: &dyn EcdsaTranscriptLoader,
+        inputs: &ThresholdSchnorrSigInputs,
+    ) -> Option<EcdsaChangeSet> {
+        load_transcripts(
+            ecdsa_pool,
+            transcript_loader,
+            &[
+                inputs.presig_transcript().blinder_unmasked(),
+                inputs.key_transcript(),
+            ],
+        )
+    }
+
+    /// Helper to create the Schnorr signature share
+    fn crypto_create_schnorr_signature_share(
+        &self,
+        ecdsa_pool: &dyn EcdsaPool,
+        transcript_loader: &dyn EcdsaTranscriptLoader,
+        request_id: &RequestId,
+        sig_inputs: &ThresholdSchnorrSigInputs,
+    ) -> EcdsaChangeSet {
+        if let Some(changes) =
+            self.load_schnorr_dependencies(ecdsa_pool, transcript_loader, sig_inputs)
+        {
+            return changes;
+        }
+
+        ThresholdSchnorrSigner::create_schnorr_sig_share(&*self.crypto, sig_inputs).map_or_else(
+            |error| {
+                warn!(
+                    self.log,
+                    "Failed to create Schnorr share: request_id = {:?}, {:?}", request_id, error
+                );
+                self.metrics.sign_errors_inc("create_schnorr_sig_share");
+                Default::default()
+            },
+            |share| {
+                let sig_share = SchnorrSigShare {
+                    signer_id: self.node_id,
+                    request_id: *request_id,
+                    share,
+                };
+                self.metrics.sign_metrics_inc("schnorr_sig_shares_sent");
+                vec![EcdsaChangeAction::AddToValidated(
+                    EcdsaMessage::SchnorrSigShare(sig_share),
+                )]
+            },
+        )
+    }
+
+    /// Helper to verify the Schnorr signature share
+    fn crypto_verify_schnorr_signature_share(
+        &self,
+        id: &EcdsaMessageId,
+        sig_inputs: &ThresholdSchnorrSigInputs,
+        share: &SchnorrSigShare,
+        stats: &dyn EcdsaStats,
+    ) -> Option<EcdsaChangeAction> {
+        let start = std::time::Instant::now();
+        let ret = ThresholdSchnorrSigVerifier::verify_schnorr_sig_share(
+            &*self.crypto,
+            share.signer_id,
+            sig_inputs,
+            &share.share,
+        );
+        stats.record_sig_share_validation(&share.request_id, start.elapsed());
+
+        ret.map_or_else(
+            |error| {
+                if error.is_reproducible() {
+                    self.metrics
+                        .sign_errors_inc("verify_schnorr_sig_share_permanent");
+                    Some(EcdsaChangeAction::HandleInvalid(
+                        id.clone(),
+                        format!(
+                            "Share validation(permanent error): {}, error = {:?}",
+                            share, error
+                        ),
+                    ))
+                } else {
+                    // Defer in case of transient errors
+                    debug!(
+                        self.log,
+                        "Share validation(transient error): {}, error = {:?}", share, error
+                    );
+                    self.metrics
+                        .sign_errors_inc("verify_schnorr_sig_share_transient");
+                    None
+                }
+            },
+            |()| {
+                self.metrics.sign_metrics_inc("schnorr_sig_shares_received");
+                Some(EcdsaChangeAction::MoveToValidated(id.clone()))
+            },
+        )
+    }
+
+    /// Helper to create the vetKD encrypted key share
+    fn crypto_create_vetkd_key_share(
+        &self,
+        request_id: &RequestId,
+        args: &VetKdArgs,
+    ) -> EcdsaChangeSet {
+        VetKdProtocol::create_encrypted_key_share(&*self.crypto, args.clone()).map_or_else(
+            |error| {
+                warn!(
+                    self.log,
+                    "Failed to create vetKD key share: request_id = {:?}, {:?}", request_id, error
+                );
+                self.metrics.sign_errors_inc("create_vetkd_key_share");
+                Default::default()
+            },
+            |share| {
+                let key_share = VetKdKeyShare {
+                    signer_id: self.node_id,
+                    request_id: *request_id,
+                    share,
+                };
+                self.metrics.sign_metrics_inc("vetkd_key_shares_sent");
+                vec![EcdsaChangeAction::AddToValidated(
+                    EcdsaMessage::VetKdKeyShare(key_share),
+                )]
+            },
+        )
+    }
+
+    /// Helper to verify the vetKD encrypted key share
+    fn crypto_verify_vetkd_key_share(
+        &self,
+        id: &EcdsaMessageId,
+        args: &VetKdArgs,
+        share: &VetKdKeyShare,
+        stats: &dyn EcdsaStats,
+    ) -> Option<EcdsaChangeAction> {
+        let start = std::time::Instant::now();
+        let ret = VetKdProtocol::verify_encrypted_key_share(
+            &*self.crypto,
+            share.signer_id,
+            &share.share,
+            args,
+        );
+        stats.record_sig_share_validation(&share.request_id, start.elapsed());
+
+        ret.map_or_else(
+            |error| {
+                if error.is_reproducible() {
+                    self.metrics
+                        .sign_errors_inc("verify_vetkd_key_share_permanent");
+                    Some(EcdsaChangeAction::HandleInvalid(
+                        id.clone(),
+                        format!(
+                            "Share validation(permanent error): {}, error = {:?}",
+                            share, error
+                        ),
+                    ))
+                } else {
+                    // Defer in case of transient errors
+                    debug!(
+                        self.log,
+                        "Share validation(transient error): {}, error = {:?}", share, error
+                    );
+                    self.metrics
+                        .sign_errors_inc("verify_vetkd_key_share_transient");
+                    None
+                }
+            },
+            |()| {
+                self.metrics.sign_metrics_inc("vetkd_key_shares_received");
+                Some(EcdsaChangeAction::MoveToValidated(id.clone()))
+            },
+        )
+    }
+
     /// Checks if the signer node has already issued a signature share for the
     /// request
     fn signer_has_issued_signature_share(
@@ -321,6 +737,36 @@ impl EcdsaSignerImpl {
             .any(|(_, share)| share.request_id == *request_id && share.signer_id == *signer_id)
     }
 
+    /// Checks if the signer node has already issued a Schnorr signature share
+    /// for the request
+    fn signer_has_issued_schnorr_signature_share(
+        &self,
+        ecdsa_pool: &dyn EcdsaPool,
+        signer_id: &NodeId,
+        request_id: &RequestId,
+    ) -> bool {
+        let prefix = schnorr_sig_share_prefix(request_id, signer_id);
+        ecdsa_pool
+            .validated()
+            .schnorr_signature_shares_by_prefix(prefix)
+            .any(|(_, share)| share.request_id == *request_id && share.signer_id == *signer_id)
+    }
+
+    /// Checks if the signer node has already issued a vetKD key share for the
+    /// request
+    fn signer_has_issued_vetkd_key_share(
+        &self,
+        ecdsa_pool: &dyn EcdsaPool,
+        signer_id: &NodeId,
+        request_id: &RequestId,
+    ) -> bool {
+        let prefix = vetkd_key_share_prefix(request_id, signer_id);
+        ecdsa_pool
+            .validated()
+            .vetkd_key_shares_by_prefix(prefix)
+            .any(|(_, share)| share.request_id == *request_id && share.signer_id == *signer_id)
+    }
+
     /// Checks if the signature share should be purged
     fn should_purge(
         &self,
@@ -328,7 +774,7 @@ impl EcdsaSignerImpl {
         current_height: Height,
         in_progress: &BTreeSet<RequestId>,
     ) -> bool {
-        share.request_id.height <= current_height && !in_progress.contains(&share.request_id)
+        should_purge(&share.request_id, current_height, in_progress)
     }
 
     /// Resolves the ThresholdEcdsaSigInputsRef -> ThresholdEcdsaSigInputs
@@ -362,6 +808,38 @@ impl EcdsaSignerImpl {
             }
         }
     }
+
+    /// Resolves the ThresholdSchnorrSigInputsRef -> ThresholdSchnorrSigInputs
+    fn resolve_schnorr_ref(
+        &self,
+        sig_inputs_ref: &ThresholdSchnorrSigInputsRef,
+        block_reader: &dyn EcdsaBlockReader,
+        reason: &str,
+    ) -> Option<ThresholdSchnorrSigInputs> {
+        let _timer = self
+            .metrics
+            .on_state_change_duration
+            .with_label_values(&["resolve_transcript_refs"])
+            .start_timer();
+        match sig_inputs_ref.translate(block_reader) {
+            Ok(sig_inputs) => {
+                self.metrics.sign_metrics_inc("resolve_transcript_refs");
+                Some(sig_inputs)
+            }
+            Err(error) => {
+                warn!(
+                    self.log,
+                    "Failed to resolve Schnorr sig input ref: reason = {}, \
+                     sig_inputs_ref = {:?}, error = {:?}",
+                    reason,
+                    sig_inputs_ref,
+                    error
+                );
+                self.metrics.sign_errors_inc("resolve_transcript_refs");
+                None
+            }
+        }
+    }
 }
 
 impl EcdsaSigner for EcdsaSignerImpl {
@@ -391,6 +869,36 @@ impl EcdsaSigner for EcdsaSignerImpl {
             )
         };
 
+        let send_schnorr_signature_shares = || {
+            timed_call(
+                "send_schnorr_signature_shares",
+                || self.send_schnorr_signature_shares(ecdsa_pool, transcript_loader, &block_reader),
+                &metrics.on_state_change_duration,
+            )
+        };
+        let validate_schnorr_signature_shares = || {
+            timed_call(
+                "validate_schnorr_signature_shares",
+                || self.validate_schnorr_signature_shares(ecdsa_pool, &block_reader),
+                &metrics.on_state_change_duration,
+            )
+        };
+
+        let send_vetkd_key_shares = || {
+            timed_call(
+                "send_vetkd_key_shares",
+                || self.send_vetkd_key_shares(ecdsa_pool, &block_reader),
+                &metrics.on_state_change_duration,
+            )
+        };
+        let validate_vetkd_key_shares = || {
+            timed_call(
+                "validate_vetkd_key_shares",
+                || self.validate_vetkd_key_shares(ecdsa_pool, &block_reader),
+                &metrics.on_state_change_duration,
+            )
+        };
+
         let purge_artifacts = || {
             timed_call(
                 "purge_artifacts",
@@ -399,9 +907,13 @@ impl EcdsaSigner for EcdsaSignerImpl {
             )
         };
 
-        let calls: [&'_ dyn Fn() -> EcdsaChangeSet; 3] = [
+        let calls: [&'_ dyn Fn() -> EcdsaChangeSet; 7] = [
             &send_signature_shares,
             &validate_signature_shares,
+            &send_schnorr_signature_shares,
+            &validate_schnorr_signature_shares,
+            &send_vetkd_key_shares,
+            &validate_vetkd_key_shares,
             &purge_artifacts,
         ];
         self.schedule.call_next(&calls)
@@ -415,6 +927,17 @@ pub(crate) trait EcdsaSignatureBuilder {
         &self,
         request_id: &RequestId,
     ) -> Option<ThresholdEcdsaCombinedSignature>;
+
+    /// Returns the specified Schnorr signature if it can be successfully
+    /// built from the current Schnorr sig shares in the ECDSA pool
+    fn get_completed_schnorr_signature(
+        &self,
+        request_id: &RequestId,
+    ) -> Option<ThresholdSchnorrCombinedSignature>;
+
+    /// Returns the specified vetKD encrypted key if it can be successfully
+    /// built from the current vetKD key shares in the ECDSA pool
+    fn get_completed_vetkd_key(&self, request_id: &RequestId) -> Option<VetKdEncryptedKey>;
 }
 
 pub(crate) struct EcdsaSignatureBuilderImpl<'a> {
@@ -478,6 +1001,82 @@ impl<'a> EcdsaSignatureBuilderImpl<'a> {
             },
         )
     }
+
+    fn crypto_combine_schnorr_signature_shares(
+        &self,
+        request_id: &RequestId,
+        inputs: &ThresholdSchnorrSigInputs,
+        shares: &BTreeMap<NodeId, ThresholdSchnorrSigShare>,
+        stats: &dyn EcdsaStats,
+    ) -> Option<ThresholdSchnorrCombinedSignature> {
+        let start = std::time::Instant::now();
+        let ret =
+            ThresholdSchnorrSigVerifier::combine_schnorr_sig_shares(self.crypto, inputs, shares);
+        stats.record_sig_share_aggregation(request_id, start.elapsed());
+
+        ret.map_or_else(
+            |error| {
+                match error {
+                    ThresholdSchnorrCombineSigSharesError::UnsatisfiedReconstructionThreshold {
+                        threshold: _,
+                        share_count: _,
+                    } => (),
+                    _ => {
+                        warn!(
+                            self.log,
+                            "Failed to combine Schnorr signature shares: request_id = {:?}, {:?}",
+                            request_id,
+                            error
+                        );
+                        self.metrics.payload_errors_inc("combine_schnorr_sig_share");
+                    }
+                };
+                Default::default()
+            },
+            |combined_signature| {
+                self.metrics
+                    .payload_metrics_inc("schnorr_signatures_completed");
+                Some(combined_signature)
+            },
+        )
+    }
+
+    fn crypto_combine_vetkd_key_shares(
+        &self,
+        request_id: &RequestId,
+        args: &VetKdArgs,
+        shares: &BTreeMap<NodeId, VetKdEncryptedKeyShare>,
+        stats: &dyn EcdsaStats,
+    ) -> Option<VetKdEncryptedKey> {
+        let start = std::time::Instant::now();
+        let ret = VetKdProtocol::combine_encrypted_key_shares(self.crypto, shares, args);
+        stats.record_sig_share_aggregation(request_id, start.elapsed());
+
+        ret.map_or_else(
+            |error| {
+                match error {
+                    VetKdKeyShareCombinationError::UnsatisfiedReconstructionThreshold {
+                        threshold: _,
+                        share_count: _,
+                    } => (),
+                    _ => {
+                        warn!(
+                            self.log,
+                            "Failed to combine vetKD key shares: request_id = {:?}, {:?}",
+                            request_id,
+                            error
+                        );
+                        self.metrics.payload_errors_inc("combine_vetkd_key_share");
+                    }
+                };
+                Default::default()
+            },
+            |encrypted_key| {
+                self.metrics.payload_metrics_inc("vetkd_keys_completed");
+                Some(encrypted_key)
+            },
+        )
+    }
 }
 
 impl<'a> EcdsaSignatureBuilder for EcdsaSignatureBuilderImpl<'a> {
@@ -520,15 +1119,75 @@ impl<'a> EcdsaSignatureBuilder for EcdsaSignatureBuilderImpl<'a> {
             self.ecdsa_pool.stats(),
         )
     }
+
+    fn get_completed_schnorr_signature(
+        &self,
+        request_id: &RequestId,
+    ) -> Option<ThresholdSchnorrCombinedSignature> {
+        // Find the sig inputs for the request and translate the refs.
+        let (request_id, sig_inputs_ref) = self
+            .block_reader
+            .requested_schnorr_signatures()
+            .find(|(cur_request_id, _)| **cur_request_id == *request_id)?;
+        let sig_inputs = match sig_inputs_ref.translate(self.block_reader) {
+            Ok(sig_inputs) => sig_inputs,
+            Err(error) => {
+                warn!(
+                    self.log,
+                    "get_completed_schnorr_signature(): translate failed: sig_inputs_ref = {:?}, error = {:?}",
+                    sig_inputs_ref,
+                    error
+                );
+                self.metrics
+                    .payload_errors_inc("schnorr_sig_inputs_translate");
+                return None;
+            }
+        };
+
+        // Collect the signature shares for the request.
+        let mut sig_shares = BTreeMap::new();
+        for (_, share) in self.ecdsa_pool.validated().schnorr_signature_shares() {
+            if share.request_id == *request_id {
+                sig_shares.insert(share.signer_id, share.share.clone());
+            }
+        }
+
+        // Combine the signatures.
+        self.crypto_combine_schnorr_signature_shares(
+            request_id,
+            &sig_inputs,
+            &sig_shares,
+            self.ecdsa_pool.stats(),
+        )
+    }
+
+    fn get_completed_vetkd_key(&self, request_id: &RequestId) -> Option<VetKdEncryptedKey> {
+        // Find the args for the request.
+        let (request_id, args) = self
+            .block_reader
+            .requested_vetkd_keys()
+            .find(|(cur_request_id, _)| **cur_request_id == *request_id)?;
+
+        // Collect the key shares for the request.
+        let mut key_shares = BTreeMap::new();
+        for (_, share) in self.ecdsa_pool.validated().vetkd_key_shares() {
+            if share.request_id == *request_id {
+                key_shares.insert(share.signer_id, share.share.clone());
+            }
+        }
+
+        // Combine the key shares.
+        self.crypto_combine_vetkd_key_shares(request_id, args, &key_shares, self.ecdsa_pool.stats())
+    }
 }
 
 /// Specifies how to handle a received share
 #[derive(Eq, PartialEq)]
-enum Action<'a> {
+enum Action<'a, T> {
     /// The message is relevant to our current state, process it
-    /// immediately. The transcript params for this transcript
-    /// (as specified by the finalized block) is the argument
-    Process(&'a ThresholdEcdsaSigInputsRef),
+    /// immediately. The sig inputs for this request (as specified
+    /// by the finalized block) is the argument
+    Process(&'a T),
 
     /// Keep it to be processed later (e.g) this is from a node
     /// ahead of us
@@ -538,15 +1197,15 @@ enum Action<'a> {
     Drop,
 }
 
-impl<'a> Action<'a> {
+impl<'a, T> Action<'a, T> {
     /// Decides the action to take on a received message with the given
     /// height/RequestId
     #[allow(clippy::self_named_constructors)]
     fn action(
         block_reader: &'a dyn EcdsaBlockReader,
-        requested_signatures: &'a BTreeMap<RequestId, &'a ThresholdEcdsaSigInputsRef>,
+        requested_signatures: &'a BTreeMap<RequestId, &'a T>,
         msg_request_id: &RequestId,
-    ) -> Action<'a> {
+    ) -> Action<'a, T> {
         let msg_height = msg_request_id.height;
         if msg_height > block_reader.tip_height() {
             // Message is from a node ahead of us, keep it to be
@@ -564,14 +1223,37 @@ impl<'a> Action<'a> {
     }
 }
 
-impl<'a> Debug for Action<'a> {
+/// Gives access to the derivation path of the different sig inputs refs
+trait HasDerivationPath {
+    fn derivation_path(&self) -> &ExtendedDerivationPath;
+}
+
+impl HasDerivationPath for ThresholdEcdsaSigInputsRef {
+    fn derivation_path(&self) -> &ExtendedDerivationPath {
+        &self.derivation_path
+    }
+}
+
+impl HasDerivationPath for ThresholdSchnorrSigInputsRef {
+    fn derivation_path(&self) -> &ExtendedDerivationPath {
+        &self.derivation_path
+    }
+}
+
+impl HasDerivationPath for VetKdArgs {
+    fn derivation_path(&self) -> &ExtendedDerivationPath {
+        &self.derivation_path
+    }
+}
+
+impl<'a, T: HasDerivationPath> Debug for Action<'a, T> {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         match &self {
             Self::Process(sig_inputs) => {
                 write!(
                     f,
                     "Action::Process(): caller = {:?}",
-                    sig_inputs.derivation_path.caller
+                    sig_inputs.derivation_path().caller
                 )
             }
             Self::Defer => write!(f, "Action::Defer"),
@@ -580,6 +1262,15 @@ impl<'a> Debug for Action<'a> {
     }
 }
 
+/// Checks if the signature share for the given request should be purged
+fn should_purge(
+    request_id: &RequestId,
+    current_height: Height,
+    in_progress: &BTreeSet<RequestId>,
+) -> bool {
+    request_id.height <= current_height && !in_progress.contains(request_id)
+}
+
 /// Resolves the ThresholdEcdsaSigInputsRef -> ThresholdEcdsaSigInputs
 fn resolve_sig_inputs_refs(
     block_reader: &dyn EcdsaBlockReader,
@@ -8,11 +8,12 @@ use ic_protobuf::registry::subnet::v1 as pb;
 use ic_types::consensus::ecdsa::{EcdsaBlockReader, TranscriptRef};
 use ic_types::consensus::ecdsa::{
     EcdsaMessage, EcdsaPayload, IDkgTranscriptParamsRef, RequestId, ThresholdEcdsaSigInputsRef,
-    TranscriptLookupError,
+    ThresholdSchnorrSigInputsRef, TranscriptLookupError,
 };
 use ic_types::crypto::canister_threshold_sig::idkg::{
     IDkgTranscript, IDkgTranscriptOperation, InitialIDkgDealings,
 };
+use ic_types::crypto::vetkd::VetKdArgs;
 use ic_types::Height;
 use std::collections::BTreeSet;
 use std::convert::TryInto;
@@ -58,6 +59,24 @@ impl EcdsaBlockReader for EcdsaBlockReaderImpl {
             })
     }
 
+    fn requested_schnorr_signatures(
+        &self,
+    ) -> Box<dyn Iterator<Item = (&RequestId, &ThresholdSchnorrSigInputsRef)> + '_> {
+        self.tip_ecdsa_payload
+            .as_ref()
+            .map_or(Box::new(std::iter::empty()), |payload| {
+                Box::new(payload.ongoing_schnorr_signatures.iter())
+            })
+    }
+
+    fn requested_vetkd_keys(&self) -> Box<dyn Iterator<Item = (&RequestId, &VetKdArgs)> + '_> {
+        self.tip_ecdsa_payload
+            .as_ref()
+            .map_or(Box::new(std::iter::empty()), |payload| {
+                Box::new(payload.ongoing_vetkd_requests.iter())
+            })
+    }
+
     fn active_transcripts(&self) -> BTreeSet<TranscriptRef> {
         self.tip_ecdsa_payload
             .as_ref()
@@ -227,8 +246,8 @@ pub(crate) mod test_utils {
         EcdsaOpening, EcdsaOpeningContent, EcdsaPayload, EcdsaReshareRequest, EcdsaSigShare,
         EcdsaUIDGenerator, IDkgTranscriptAttributes, IDkgTranscriptParamsRef,
         KeyTranscriptCreation, MaskedTranscript, PreSignatureQuadrupleRef, RequestId,
-        ReshareOfMaskedParams, ThresholdEcdsaSigInputsRef, TranscriptLookupError, TranscriptRef,
-        UnmaskedTranscript,
+        ReshareOfMaskedParams, ThresholdEcdsaSigInputsRef, ThresholdSchnorrSigInputsRef,
+        TranscriptLookupError, TranscriptRef, UnmaskedTranscript,
     };
     use ic_types::crypto::canister_threshold_sig::idkg::{
         IDkgComplaint, IDkgDealing, IDkgDealingSupport, IDkgMaskedTranscriptOrigin, IDkgOpening,
@@ -237,7 +256,9 @@ pub(crate) mod test_utils {
     };
     use ic_types::crypto::canister_threshold_sig::{
         ExtendedDerivationPath, ThresholdEcdsaCombinedSignature, ThresholdEcdsaSigShare,
+        ThresholdSchnorrCombinedSignature,
     };
+    use ic_types::crypto::vetkd::{VetKdArgs, VetKdEncryptedKey};
     use ic_types::crypto::AlgorithmId;
     use ic_types::malicious_behaviour::MaliciousBehaviour;
     use ic_types::signature::*;
@@ -251,6 +272,7 @@ pub(crate) mod test_utils {
             originator: ic_types::CanisterId::ic_00(),
             respondent: ic_types::CanisterId::ic_00(),
             originator_reply_callback: ic_types::messages::CallbackId::from(0),
+            deadline: ic_types::messages::NO_DEADLINE,
             // Execution is responsible for burning the appropriate cycles
             // before pushing the new context, so any remaining cycles can
             // be refunded to the canister.
@@ -374,6 +396,16 @@ pub(crate) mod test_utils {
             )
         }
 
+        fn requested_schnorr_signatures(
+            &self,
+        ) -> Box<dyn Iterator<Item = (&RequestId, &ThresholdSchnorrSigInputsRef)> + '_> {
+            Box::new(std::iter::empty())
+        }
+
+        fn requested_vetkd_keys(&self) -> Box<dyn Iterator<Item = (&RequestId, &VetKdArgs)> + '_> {
+            Box::new(std::iter::empty())
+        }
+
         fn source_subnet_xnet_transcripts(
             &self,
         ) -> Box<dyn Iterator<Item = &IDkgTranscriptParamsRef> + '_> {
@@ -523,12 +555,16 @@ pub(crate) mod test_utils {
 
     pub(crate) struct TestEcdsaSignatureBuilder {
         pub(crate) signatures: BTreeMap<RequestId, ThresholdEcdsaCombinedSignature>,
+        pub(crate) schnorr_signatures: BTreeMap<RequestId, ThresholdSchnorrCombinedSignature>,
+        pub(crate) vetkd_keys: BTreeMap<RequestId, VetKdEncryptedKey>,
     }
 
     impl TestEcdsaSignatureBuilder {
         pub(crate) fn new() -> Self {
             Self {
                 signatures: BTreeMap::new(),
+                schnorr_signatures: BTreeMap::new(),
+                vetkd_keys: BTreeMap::new(),
             }
         }
     }
@@ -540,6 +576,17 @@ pub(crate) mod test_utils {
         ) -> Option<ThresholdEcdsaCombinedSignature> {
             self.signatures.get(request_id).cloned()
         }
+
+        fn get_completed_schnorr_signature(
+            &self,
+            request_id: &RequestId,
+        ) -> Option<ThresholdSchnorrCombinedSignature> {
+            self.schnorr_signatures.get(request_id).cloned()
+        }
+
+        fn get_completed_vetkd_key(&self, request_id: &RequestId) -> Option<VetKdEncryptedKey> {
+            self.vetkd_keys.get(request_id).cloned()
+        }
     }
 
     // Sets up the dependencies and creates the pre signer
@@ -1206,6 +1253,11 @@ pub(crate) mod test_utils {
                 next_in_creation: KeyTranscriptCreation::Begin,
                 key_id: EcdsaKeyId::from_str("Secp256k1:some_key").unwrap(),
             },
+            schnorr_key_transcript: None,
+            ongoing_schnorr_signatures: BTreeMap::new(),
+            available_schnorr_presigs: BTreeMap::new(),
+            schnorr_presigs_in_creation: BTreeMap::new(),
+            ongoing_vetkd_requests: BTreeMap::new(),
         }
     }
 
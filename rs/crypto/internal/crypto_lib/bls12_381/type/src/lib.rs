@@ -247,6 +247,62 @@ impl Scalar {
         result
     }
 
+    /// Hash an input to a Scalar
+    ///
+    /// This follows the hash_to_field construction of draft-irtf-cfrg-hash-to-curve-16
+    /// using expand_message_xmd with SHA-256 and L=64 bytes of output
+    ///
+    /// # Arguments
+    /// * `domain_sep` - some protocol specific domain seperator
+    /// * `input` - the input which will be hashed
+    pub fn hash(domain_sep: &[u8], input: &[u8]) -> Self {
+        use sha2::Digest;
+
+        const OUTPUT_BYTES: usize = 64;
+        const DIGEST_BYTES: usize = 32;
+        const BLOCK_BYTES: usize = 64;
+
+        assert!(domain_sep.len() <= 255);
+
+        let mut dst_prime = domain_sep.to_vec();
+        dst_prime.push(domain_sep.len() as u8);
+
+        let b_0 = {
+            let mut h = sha2::Sha256::new();
+            h.update([0u8; BLOCK_BYTES]);
+            h.update(input);
+            h.update((OUTPUT_BYTES as u16).to_be_bytes());
+            h.update([0u8]);
+            h.update(&dst_prime);
+            h.finalize()
+        };
+
+        let mut output = Vec::with_capacity(OUTPUT_BYTES);
+        let mut b_i = [0u8; DIGEST_BYTES];
+
+        for i in 1..=(OUTPUT_BYTES / DIGEST_BYTES) {
+            let mut h = sha2::Sha256::new();
+            let mut xored = [0u8; DIGEST_BYTES];
+            for j in 0..DIGEST_BYTES {
+                xored[j] = b_0[j] ^ b_i[j];
+            }
+            h.update(xored);
+            h.update([i as u8]);
+            h.update(&dst_prime);
+            b_i.copy_from_slice(&h.finalize());
+            output.extend_from_slice(&b_i);
+        }
+
+        // hash_to_field interprets the output as a big-endian integer, whereas
+        // from_bytes_wide expects a little-endian encoding
+        let mut wide = [0u8; OUTPUT_BYTES];
+        for i in 0..OUTPUT_BYTES {
+            wide[i] = output[OUTPUT_BYTES - 1 - i];
+        }
+
+        Self::new(ic_bls12_381::Scalar::from_bytes_wide(&wide))
+    }
+
     /// Return a random scalar within a small range
     ///
     /// Returns a scalar in range [0,n) using rejection sampling.
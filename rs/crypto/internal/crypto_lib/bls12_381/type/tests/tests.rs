@@ -692,6 +692,28 @@ fn test_verify_bls_signature() {
     assert!(!verify_bls_signature(&message, &pk, &signature));
 }
 
+#[test]
+fn test_hash_to_scalar_matches_known_values() {
+    // These values were computed using hash_to_field from
+    // draft-irtf-cfrg-hash-to-curve-16 with expand_message_xmd and SHA-256
+    let dst = b"QUUX-V01-CS02-with-BLS12381SCALAR_XMD:SHA-256_";
+
+    scalar_test_encoding(
+        Scalar::hash(&dst[..], b""),
+        "2a4e204f525ea459c2c951710520ab04315f17c5f94cc3ad4057ea573edea817",
+    );
+
+    scalar_test_encoding(
+        Scalar::hash(&dst[..], b"abc"),
+        "02c3006e159341d390101fdef9820f972e75d64ebd24d26006be386f982a013a",
+    );
+
+    scalar_test_encoding(
+        Scalar::hash(&dst[..], b"abcdef0123456789"),
+        "6b6461ba655f69ffb97b908f14b8f3eb55ef808098748c682e698ef8df0a04e5",
+    );
+}
+
 #[test]
 fn test_hash_to_g1_matches_draft() {
     /*
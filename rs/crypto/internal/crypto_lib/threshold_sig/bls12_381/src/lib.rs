@@ -7,6 +7,7 @@ pub mod api;
 pub mod crypto;
 pub mod ni_dkg;
 pub mod types;
+pub mod vetkd;
 
 mod cache;
 pub mod test_utils;
@@ -0,0 +1,488 @@
+//! Verifiably encrypted threshold key derivation (vetKD)
+//!
+//! The subnet's BLS12-381 threshold key `s` (with public key `g2*s`) is
+//! used to derive keys for a (canister id, derivation path, input) triple.
+//! The derived key is a BLS signature (in G1) on the input, made with the
+//! secret key `s + t` where `t` is a public offset computed from the
+//! canister id and derivation path. This key is never revealed to the nodes;
+//! instead each node creates a share of the key which is encrypted
+//! (ElGamal-style) under a transport public key chosen by the caller. The
+//! encrypted shares can be publicly verified and combined into an encrypted
+//! key, which only the holder of the transport secret key can decrypt.
+
+use crate::crypto::x_for_index;
+use crate::types::PublicCoefficients;
+use ic_crypto_internal_bls12_381_type::{
+    G1Affine, G1Projective, G2Affine, G2Prepared, G2Projective, Gt, Scalar,
+};
+use ic_types::NodeIndex;
+use rand::{CryptoRng, RngCore};
+use std::collections::BTreeMap;
+
+#[cfg(test)]
+mod tests;
+
+/// Domain separator used to hash the canister id and derivation path to the
+/// key offset
+const DERIVATION_DST: &[u8] = b"ic-vetkd-bls12-381-g2-derivation-path";
+
+/// Domain separator used to hash the derived public key and input to G1
+///
+/// This is the augmented BLS ciphersuite, where the message is prefixed with
+/// the public key, so the decrypted key is a valid BLS signature.
+const AUGMENTED_HASH_TO_G1_DST: &[u8] = b"BLS_SIG_BLS12381G1_XMD:SHA-256_SSWU_RO_AUG_";
+
+/// The derivation context of a vetKD key
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DerivationPath {
+    canister_id: Vec<u8>,
+    path: Vec<Vec<u8>>,
+}
+
+impl DerivationPath {
+    /// Create a new derivation path for a canister
+    pub fn new(canister_id: &[u8], path: &[Vec<u8>]) -> Self {
+        Self {
+            canister_id: canister_id.to_vec(),
+            path: path.to_vec(),
+        }
+    }
+
+    /// Return the offset added to the master secret key
+    fn offset(&self, master_pk: &G2Affine) -> Scalar {
+        let mut input = Vec::new();
+        input.extend_from_slice(&master_pk.serialize());
+        input.extend_from_slice(&(self.canister_id.len() as u64).to_be_bytes());
+        input.extend_from_slice(&self.canister_id);
+        input.extend_from_slice(&(self.path.len() as u64).to_be_bytes());
+        for elem in &self.path {
+            input.extend_from_slice(&(elem.len() as u64).to_be_bytes());
+            input.extend_from_slice(elem);
+        }
+        Scalar::hash(DERIVATION_DST, &input)
+    }
+}
+
+/// A derived public key
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DerivedPublicKey {
+    point: G2Affine,
+}
+
+impl DerivedPublicKey {
+    /// The length of the serialized encoding of this type
+    pub const BYTES: usize = G2Affine::BYTES;
+
+    /// Derive a public key from the master public key and derivation path
+    pub fn compute_derived_key(master_pk: &G2Affine, derivation_path: &DerivationPath) -> Self {
+        let offset = derivation_path.offset(master_pk);
+        let point = G2Projective::from(master_pk) + G2Affine::generator() * &offset;
+        Self {
+            point: point.to_affine(),
+        }
+    }
+
+    /// Deserialize a previously serialized derived public key
+    pub fn deserialize(bytes: &[u8]) -> Result<Self, PairingInvalidEncoding> {
+        let point = G2Affine::deserialize(&bytes).map_err(|_| PairingInvalidEncoding)?;
+        Ok(Self { point })
+    }
+
+    /// Serialize the derived public key
+    pub fn serialize(&self) -> [u8; Self::BYTES] {
+        self.point.serialize()
+    }
+
+    /// Return the point in G1 which the derived key is a signature of
+    fn hash_input(&self, input: &[u8]) -> G1Affine {
+        let mut buf = Vec::with_capacity(Self::BYTES + input.len());
+        buf.extend_from_slice(&self.serialize());
+        buf.extend_from_slice(input);
+        G1Affine::hash(AUGMENTED_HASH_TO_G1_DST, &buf)
+    }
+}
+
+/// Error indicating that a point encoding was invalid
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct PairingInvalidEncoding;
+
+/// A transport public key, supplied by the caller
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TransportPublicKey {
+    point: G1Affine,
+}
+
+impl TransportPublicKey {
+    /// The length of the serialized encoding of this type
+    pub const BYTES: usize = G1Affine::BYTES;
+
+    /// Deserialize a transport public key
+    ///
+    /// The identity element is rejected, since encryption under it would
+    /// reveal the key
+    pub fn deserialize(bytes: &[u8]) -> Result<Self, PairingInvalidEncoding> {
+        let point = G1Affine::deserialize(&bytes).map_err(|_| PairingInvalidEncoding)?;
+        if point.is_identity() {
+            return Err(PairingInvalidEncoding);
+        }
+        Ok(Self { point })
+    }
+
+    /// Serialize the transport public key
+    pub fn serialize(&self) -> [u8; Self::BYTES] {
+        self.point.serialize()
+    }
+}
+
+/// A transport secret key
+///
+/// This is only used by the recipient of an encrypted key; the replica never
+/// has access to it.
+pub struct TransportSecretKey {
+    secret_key: Scalar,
+}
+
+impl TransportSecretKey {
+    /// Generate a new random transport secret key
+    pub fn generate<R: RngCore + CryptoRng>(rng: &mut R) -> Self {
+        Self {
+            secret_key: Scalar::random(rng),
+        }
+    }
+
+    /// Return the public key associated with this secret key
+    pub fn public_key(&self) -> TransportPublicKey {
+        TransportPublicKey {
+            point: (G1Affine::generator() * &self.secret_key).to_affine(),
+        }
+    }
+
+    /// Decrypt an encrypted key
+    ///
+    /// Returns None if the encrypted key is not valid for the given
+    /// derived public key and input, otherwise the serialized key
+    pub fn decrypt(
+        &self,
+        encrypted_key: &EncryptedKey,
+        derived_public_key: &DerivedPublicKey,
+        input: &[u8],
+    ) -> Option<[u8; G1Affine::BYTES]> {
+        let key = (G1Projective::from(&encrypted_key.c3) - &encrypted_key.c1 * &self.secret_key)
+            .to_affine();
+        let msg = derived_public_key.hash_input(input);
+        let dpk_prepared = G2Prepared::from(&derived_public_key.point);
+        let is_valid =
+            Gt::multipairing(&[(&key, G2Prepared::neg_generator()), (&msg, &dpk_prepared)])
+                .is_identity();
+        if is_valid {
+            Some(key.serialize())
+        } else {
+            None
+        }
+    }
+}
+
+/// An encrypted key
+///
+/// This consists of `(c1, c2, c3)` where for some random `r`,
+/// `c1 = g1*r`, `c2 = g2*r` and `c3 = tpk*r + H(dpk || input)*(s + t)`
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct EncryptedKey {
+    c1: G1Affine,
+    c2: G2Affine,
+    c3: G1Affine,
+}
+
+/// Error returned when combining encrypted key shares fails
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum EncryptedKeyCombinationError {
+    /// There were not enough shares to reach the reconstruction threshold
+    InsufficientShares,
+    /// Not enough of the provided shares were valid
+    InsufficientValidShares,
+    /// The combined key was invalid
+    InvalidShares,
+}
+
+impl EncryptedKey {
+    /// The length of the serialized encoding of this type
+    pub const BYTES: usize = 2 * G1Affine::BYTES + G2Affine::BYTES;
+
+    /// Combine encrypted key shares without verifying them individually
+    ///
+    /// The resulting key is verified, and an error is returned if it is
+    /// invalid.
+    pub fn combine_all(
+        nodes: &BTreeMap<NodeIndex, EncryptedKeyShare>,
+        reconstruction_threshold: usize,
+        master_pk: &G2Affine,
+        transport_pk: &TransportPublicKey,
+        derivation_path: &DerivationPath,
+        input: &[u8],
+    ) -> Result<Self, EncryptedKeyCombinationError> {
+        if nodes.len() < reconstruction_threshold {
+            return Err(EncryptedKeyCombinationError::InsufficientShares);
+        }
+
+        let combined = Self::combine_unchecked(nodes.iter().take(reconstruction_threshold));
+
+        let dpk = DerivedPublicKey::compute_derived_key(master_pk, derivation_path);
+        if combined.is_valid(&dpk, input, transport_pk) {
+            Ok(combined)
+        } else {
+            Err(EncryptedKeyCombinationError::InvalidShares)
+        }
+    }
+
+    /// Combine encrypted key shares, skipping any share which is invalid
+    ///
+    /// The master public key shares are the individual public keys of the
+    /// nodes, indexed by node index.
+    pub fn combine_valid_shares(
+        nodes: &BTreeMap<NodeIndex, (G2Affine, EncryptedKeyShare)>,
+        reconstruction_threshold: usize,
+        master_pk: &G2Affine,
+        transport_pk: &TransportPublicKey,
+        derivation_path: &DerivationPath,
+        input: &[u8],
+    ) -> Result<Self, EncryptedKeyCombinationError> {
+        if nodes.len() < reconstruction_threshold {
+            return Err(EncryptedKeyCombinationError::InsufficientShares);
+        }
+
+        let valid_shares = nodes
+            .iter()
+            .filter(|(_index, (master_pk_share, share))| {
+                share.is_valid(
+                    master_pk,
+                    master_pk_share,
+                    derivation_path,
+                    input,
+                    transport_pk,
+                )
+            })
+            .take(reconstruction_threshold)
+            .map(|(index, (_master_pk_share, share))| (index, share))
+            .collect::<Vec<_>>();
+
+        if valid_shares.len() < reconstruction_threshold {
+            return Err(EncryptedKeyCombinationError::InsufficientValidShares);
+        }
+
+        let combined = Self::combine_unchecked(valid_shares.into_iter());
+
+        let dpk = DerivedPublicKey::compute_derived_key(master_pk, derivation_path);
+        if combined.is_valid(&dpk, input, transport_pk) {
+            Ok(combined)
+        } else {
+            Err(EncryptedKeyCombinationError::InvalidShares)
+        }
+    }
+
+    /// The node indices are distinct since they are the keys of a map
+    fn combine_unchecked<'a>(
+        shares: impl Iterator<Item = (&'a NodeIndex, &'a EncryptedKeyShare)>,
+    ) -> Self {
+        let mut c1 = Vec::new();
+        let mut c2 = Vec::new();
+        let mut c3 = Vec::new();
+
+        for (index, share) in shares {
+            let x = x_for_index(*index);
+            c1.push((x.clone(), G1Projective::from(&share.c1)));
+            c2.push((x.clone(), G2Projective::from(&share.c2)));
+            c3.push((x, G1Projective::from(&share.c3)));
+        }
+
+        let c1 = PublicCoefficients::interpolate_g1(&c1).expect("Duplicate indices");
+        let c2 = PublicCoefficients::interpolate_g2(&c2).expect("Duplicate indices");
+        let c3 = PublicCoefficients::interpolate_g1(&c3).expect("Duplicate indices");
+
+        Self {
+            c1: c1.to_affine(),
+            c2: c2.to_affine(),
+            c3: c3.to_affine(),
+        }
+    }
+
+    /// Check if this encrypted key is valid for the derived public key,
+    /// input and transport public key
+    pub fn is_valid(
+        &self,
+        derived_public_key: &DerivedPublicKey,
+        input: &[u8],
+        transport_pk: &TransportPublicKey,
+    ) -> bool {
+        check_ciphertext(
+            &self.c1,
+            &self.c2,
+            &self.c3,
+            &derived_public_key.point,
+            &derived_public_key.hash_input(input),
+            &transport_pk.point,
+        )
+    }
+
+    /// Deserialize an encrypted key
+    pub fn deserialize(bytes: &[u8]) -> Result<Self, PairingInvalidEncoding> {
+        let (c1, c2, c3) = deserialize_ciphertext(bytes)?;
+        Ok(Self { c1, c2, c3 })
+    }
+
+    /// Serialize the encrypted key
+    pub fn serialize(&self) -> [u8; Self::BYTES] {
+        serialize_ciphertext(&self.c1, &self.c2, &self.c3)
+    }
+}
+
+/// A share of an encrypted key, created by a single node
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct EncryptedKeyShare {
+    c1: G1Affine,
+    c2: G2Affine,
+    c3: G1Affine,
+}
+
+impl EncryptedKeyShare {
+    /// The length of the serialized encoding of this type
+    pub const BYTES: usize = 2 * G1Affine::BYTES + G2Affine::BYTES;
+
+    /// Create a new encrypted key share
+    ///
+    /// `master_sk_share` is the node's share of the master secret key whose
+    /// public key is `master_pk`.
+    pub fn create<R: RngCore + CryptoRng>(
+        rng: &mut R,
+        master_pk: &G2Affine,
+        master_sk_share: &Scalar,
+        transport_pk: &TransportPublicKey,
+        derivation_path: &DerivationPath,
+        input: &[u8],
+    ) -> Self {
+        let offset = derivation_path.offset(master_pk);
+        let dpk = DerivedPublicKey::compute_derived_key(master_pk, derivation_path);
+        let msg = dpk.hash_input(input);
+
+        let r = Scalar::random(rng);
+
+        let c1 = G1Affine::generator() * &r;
+        let c2 = G2Affine::generator() * &r;
+        let c3 = G1Projective::mul2(
+            &G1Projective::from(&transport_pk.point),
+            &r,
+            &G1Projective::from(&msg),
+            &(master_sk_share + &offset),
+        );
+
+        Self {
+            c1: c1.to_affine(),
+            c2: c2.to_affine(),
+            c3: c3.to_affine(),
+        }
+    }
+
+    /// Check if this encrypted key share is valid
+    ///
+    /// `master_pk_share` is the public key of the node's share of the
+    /// master secret key.
+    pub fn is_valid(
+        &self,
+        master_pk: &G2Affine,
+        master_pk_share: &G2Affine,
+        derivation_path: &DerivationPath,
+        input: &[u8],
+        transport_pk: &TransportPublicKey,
+    ) -> bool {
+        // The derived public key of this share uses the same offset as the
+        // derived public key of the master key, since the Lagrange
+        // coefficients at zero sum to one
+        let offset = derivation_path.offset(master_pk);
+        let dpk_share =
+            (G2Projective::from(master_pk_share) + G2Affine::generator() * &offset).to_affine();
+        // The hash is always computed with the derived public key of the
+        // master key, since that is what the combined key is verified against
+        let dpk = DerivedPublicKey::compute_derived_key(master_pk, derivation_path);
+        let msg = dpk.hash_input(input);
+
+        check_ciphertext(
+            &self.c1,
+            &self.c2,
+            &self.c3,
+            &dpk_share,
+            &msg,
+            &transport_pk.point,
+        )
+    }
+
+    /// Deserialize an encrypted key share
+    pub fn deserialize(bytes: &[u8]) -> Result<Self, PairingInvalidEncoding> {
+        let (c1, c2, c3) = deserialize_ciphertext(bytes)?;
+        Ok(Self { c1, c2, c3 })
+    }
+
+    /// Serialize the encrypted key share
+    pub fn serialize(&self) -> [u8; Self::BYTES] {
+        serialize_ciphertext(&self.c1, &self.c2, &self.c3)
+    }
+}
+
+/// Check that `(c1, c2, c3)` is an encryption under `tpk` of `msg*sk` where
+/// `g2*sk = pk`
+///
+/// This checks that `e(c1, g2) == e(g1, c2)` and that
+/// `e(c3, g2) == e(tpk, c2) * e(msg, pk)`
+fn check_ciphertext(
+    c1: &G1Affine,
+    c2: &G2Affine,
+    c3: &G1Affine,
+    pk: &G2Affine,
+    msg: &G1Affine,
+    tpk: &G1Affine,
+) -> bool {
+    let c2_prepared = G2Prepared::from(c2);
+    let pk_prepared = G2Prepared::from(pk);
+
+    let c1_c2 = Gt::multipairing(&[
+        (c1, G2Prepared::neg_generator()),
+        (G1Affine::generator(), &c2_prepared),
+    ]);
+
+    if !c1_c2.is_identity() {
+        return false;
+    }
+
+    let neg_c3 = c3.neg();
+    let c3_c2_pk = Gt::multipairing(&[
+        (&neg_c3, G2Prepared::generator()),
+        (tpk, &c2_prepared),
+        (msg, &pk_prepared),
+    ]);
+
+    c3_c2_pk.is_identity()
+}
+
+fn deserialize_ciphertext(
+    bytes: &[u8],
+) -> Result<(G1Affine, G2Affine, G1Affine), PairingInvalidEncoding> {
+    const C2_OFFSET: usize = G1Affine::BYTES;
+    const C3_OFFSET: usize = G1Affine::BYTES + G2Affine::BYTES;
+
+    if bytes.len() != EncryptedKey::BYTES {
+        return Err(PairingInvalidEncoding);
+    }
+
+    let c1 = G1Affine::deserialize(&&bytes[..C2_OFFSET]).map_err(|_| PairingInvalidEncoding)?;
+    let c2 =
+        G2Affine::deserialize(&&bytes[C2_OFFSET..C3_OFFSET]).map_err(|_| PairingInvalidEncoding)?;
+    let c3 = G1Affine::deserialize(&&bytes[C3_OFFSET..]).map_err(|_| PairingInvalidEncoding)?;
+
+    Ok((c1, c2, c3))
+}
+
+fn serialize_ciphertext(c1: &G1Affine, c2: &G2Affine, c3: &G1Affine) -> [u8; EncryptedKey::BYTES] {
+    let mut output = [0u8; EncryptedKey::BYTES];
+    output[..G1Affine::BYTES].copy_from_slice(&c1.serialize());
+    output[G1Affine::BYTES..G1Affine::BYTES + G2Affine::BYTES].copy_from_slice(&c2.serialize());
+    output[G1Affine::BYTES + G2Affine::BYTES..].copy_from_slice(&c3.serialize());
+    output
+}
@@ -0,0 +1,338 @@
+#![allow(clippy::unwrap_used)]
+//! Tests for vetKD
+
+use super::*;
+use crate::crypto::{combined_public_key, generate_threshold_key, individual_public_key};
+use crate::types::SecretKey;
+use ic_crypto_internal_seed::Seed;
+use ic_crypto_test_utils_reproducible_rng::reproducible_rng;
+use ic_types::NumberOfNodes;
+use rand::Rng;
+
+struct Setup {
+    master_pk: G2Affine,
+    master_pk_shares: Vec<G2Affine>,
+    master_sk_shares: Vec<SecretKey>,
+    threshold: usize,
+}
+
+impl Setup {
+    fn new<R: RngCore + CryptoRng>(rng: &mut R, threshold: u32, nodes: u32) -> Self {
+        let (public_coefficients, master_sk_shares) = generate_threshold_key(
+            Seed::from_rng(rng),
+            NumberOfNodes::new(threshold),
+            NumberOfNodes::new(nodes),
+        )
+        .unwrap();
+        let master_pk = combined_public_key(&public_coefficients).0.to_affine();
+        let master_pk_shares = (0..nodes)
+            .map(|index| {
+                individual_public_key(&public_coefficients, index)
+                    .0
+                    .to_affine()
+            })
+            .collect();
+        Self {
+            master_pk,
+            master_pk_shares,
+            master_sk_shares,
+            threshold: threshold as usize,
+        }
+    }
+
+    fn create_shares<R: RngCore + CryptoRng>(
+        &self,
+        rng: &mut R,
+        transport_pk: &TransportPublicKey,
+        derivation_path: &DerivationPath,
+        input: &[u8],
+    ) -> BTreeMap<NodeIndex, EncryptedKeyShare> {
+        self.master_sk_shares
+            .iter()
+            .enumerate()
+            .map(|(index, sk_share)| {
+                let share = EncryptedKeyShare::create(
+                    rng,
+                    &self.master_pk,
+                    sk_share,
+                    transport_pk,
+                    derivation_path,
+                    input,
+                );
+                (index as NodeIndex, share)
+            })
+            .collect()
+    }
+}
+
+fn random_derivation_path<R: RngCore>(rng: &mut R) -> DerivationPath {
+    let canister_id = rng.gen::<[u8; 10]>();
+    let path = (0..rng.gen_range(0..4))
+        .map(|_| rng.gen::<[u8; 8]>().to_vec())
+        .collect::<Vec<_>>();
+    DerivationPath::new(&canister_id, &path)
+}
+
+#[test]
+fn should_produce_verifiable_and_decryptable_keys() {
+    let rng = &mut reproducible_rng();
+    let setup = Setup::new(rng, 3, 5);
+
+    let tsk = TransportSecretKey::generate(rng);
+    let tpk = tsk.public_key();
+    let derivation_path = random_derivation_path(rng);
+    let input = rng.gen::<[u8; 32]>();
+
+    let shares = setup.create_shares(rng, &tpk, &derivation_path, &input);
+
+    for (index, share) in &shares {
+        assert!(share.is_valid(
+            &setup.master_pk,
+            &setup.master_pk_shares[*index as usize],
+            &derivation_path,
+            &input,
+            &tpk,
+        ));
+    }
+
+    let ek = EncryptedKey::combine_all(
+        &shares,
+        setup.threshold,
+        &setup.master_pk,
+        &tpk,
+        &derivation_path,
+        &input,
+    )
+    .unwrap();
+
+    let dpk = DerivedPublicKey::compute_derived_key(&setup.master_pk, &derivation_path);
+    assert!(ek.is_valid(&dpk, &input, &tpk));
+    assert!(tsk.decrypt(&ek, &dpk, &input).is_some());
+}
+
+#[test]
+fn should_derive_the_same_key_from_any_subset_of_shares() {
+    let rng = &mut reproducible_rng();
+    let setup = Setup::new(rng, 3, 5);
+
+    let tsk = TransportSecretKey::generate(rng);
+    let tpk = tsk.public_key();
+    let derivation_path = random_derivation_path(rng);
+    let input = rng.gen::<[u8; 32]>();
+    let dpk = DerivedPublicKey::compute_derived_key(&setup.master_pk, &derivation_path);
+
+    let shares = setup.create_shares(rng, &tpk, &derivation_path, &input);
+
+    let first_subset: BTreeMap<_, _> = shares.clone().into_iter().take(3).collect();
+    let second_subset: BTreeMap<_, _> = shares.into_iter().skip(2).collect();
+
+    let first_key = EncryptedKey::combine_all(
+        &first_subset,
+        setup.threshold,
+        &setup.master_pk,
+        &tpk,
+        &derivation_path,
+        &input,
+    )
+    .unwrap();
+    let second_key = EncryptedKey::combine_all(
+        &second_subset,
+        setup.threshold,
+        &setup.master_pk,
+        &tpk,
+        &derivation_path,
+        &input,
+    )
+    .unwrap();
+
+    // The encryptions differ due to the randomness but the keys are the same
+    assert_ne!(first_key, second_key);
+    assert_eq!(
+        tsk.decrypt(&first_key, &dpk, &input).unwrap(),
+        tsk.decrypt(&second_key, &dpk, &input).unwrap()
+    );
+}
+
+#[test]
+fn should_derive_different_keys_for_different_contexts() {
+    let rng = &mut reproducible_rng();
+    let setup = Setup::new(rng, 2, 3);
+
+    let first_path = DerivationPath::new(b"canister-1", &[b"path".to_vec()]);
+    let second_path = DerivationPath::new(b"canister-2", &[b"path".to_vec()]);
+    let third_path = DerivationPath::new(b"canister-1", &[b"pa".to_vec(), b"th".to_vec()]);
+
+    let first_dpk = DerivedPublicKey::compute_derived_key(&setup.master_pk, &first_path);
+    let second_dpk = DerivedPublicKey::compute_derived_key(&setup.master_pk, &second_path);
+    let third_dpk = DerivedPublicKey::compute_derived_key(&setup.master_pk, &third_path);
+
+    assert_ne!(first_dpk, second_dpk);
+    assert_ne!(first_dpk, third_dpk);
+    assert_ne!(second_dpk, third_dpk);
+}
+
+#[test]
+fn should_reject_share_for_wrong_input_or_node() {
+    let rng = &mut reproducible_rng();
+    let setup = Setup::new(rng, 2, 3);
+
+    let tpk = TransportSecretKey::generate(rng).public_key();
+    let derivation_path = random_derivation_path(rng);
+    let input = rng.gen::<[u8; 32]>();
+
+    let shares = setup.create_shares(rng, &tpk, &derivation_path, &input);
+    let share = &shares[&0];
+
+    assert!(!share.is_valid(
+        &setup.master_pk,
+        &setup.master_pk_shares[0],
+        &derivation_path,
+        b"wrong input",
+        &tpk,
+    ));
+    assert!(!share.is_valid(
+        &setup.master_pk,
+        &setup.master_pk_shares[1],
+        &derivation_path,
+        &input,
+        &tpk,
+    ));
+    assert!(!share.is_valid(
+        &setup.master_pk,
+        &setup.master_pk_shares[0],
+        &random_derivation_path(rng),
+        &input,
+        &tpk,
+    ));
+}
+
+#[test]
+fn should_combine_valid_shares_while_skipping_invalid_ones() {
+    let rng = &mut reproducible_rng();
+    let setup = Setup::new(rng, 3, 5);
+
+    let tsk = TransportSecretKey::generate(rng);
+    let tpk = tsk.public_key();
+    let derivation_path = random_derivation_path(rng);
+    let input = rng.gen::<[u8; 32]>();
+
+    let mut shares = setup.create_shares(rng, &tpk, &derivation_path, &input);
+    // Replace the share of node 0 by a share for a different input
+    let wrong_shares = setup.create_shares(rng, &tpk, &derivation_path, b"wrong input");
+    shares.insert(0, wrong_shares[&0].clone());
+
+    assert_eq!(
+        EncryptedKey::combine_all(
+            &shares,
+            setup.threshold,
+            &setup.master_pk,
+            &tpk,
+            &derivation_path,
+            &input,
+        ),
+        Err(EncryptedKeyCombinationError::InvalidShares)
+    );
+
+    let shares_with_keys: BTreeMap<_, _> = shares
+        .into_iter()
+        .map(|(index, share)| {
+            (
+                index,
+                (setup.master_pk_shares[index as usize].clone(), share),
+            )
+        })
+        .collect();
+
+    let ek = EncryptedKey::combine_valid_shares(
+        &shares_with_keys,
+        setup.threshold,
+        &setup.master_pk,
+        &tpk,
+        &derivation_path,
+        &input,
+    )
+    .unwrap();
+
+    let dpk = DerivedPublicKey::compute_derived_key(&setup.master_pk, &derivation_path);
+    assert!(tsk.decrypt(&ek, &dpk, &input).is_some());
+
+    let too_few: BTreeMap<_, _> = shares_with_keys.into_iter().take(3).collect();
+    assert_eq!(
+        EncryptedKey::combine_valid_shares(
+            &too_few,
+            setup.threshold,
+            &setup.master_pk,
+            &tpk,
+            &derivation_path,
+            &input,
+        ),
+        Err(EncryptedKeyCombinationError::InsufficientValidShares)
+    );
+}
+
+#[test]
+fn should_reject_too_few_shares() {
+    let rng = &mut reproducible_rng();
+    let setup = Setup::new(rng, 3, 5);
+
+    let tpk = TransportSecretKey::generate(rng).public_key();
+    let derivation_path = random_derivation_path(rng);
+    let input = rng.gen::<[u8; 32]>();
+
+    let shares: BTreeMap<_, _> = setup
+        .create_shares(rng, &tpk, &derivation_path, &input)
+        .into_iter()
+        .take(2)
+        .collect();
+
+    assert_eq!(
+        EncryptedKey::combine_all(
+            &shares,
+            setup.threshold,
+            &setup.master_pk,
+            &tpk,
+            &derivation_path,
+            &input,
+        ),
+        Err(EncryptedKeyCombinationError::InsufficientShares)
+    );
+}
+
+#[test]
+fn should_serialize_and_deserialize() {
+    let rng = &mut reproducible_rng();
+    let setup = Setup::new(rng, 2, 3);
+
+    let tpk = TransportSecretKey::generate(rng).public_key();
+    let derivation_path = random_derivation_path(rng);
+    let input = rng.gen::<[u8; 32]>();
+
+    let shares = setup.create_shares(rng, &tpk, &derivation_path, &input);
+    let ek = EncryptedKey::combine_all(
+        &shares,
+        setup.threshold,
+        &setup.master_pk,
+        &tpk,
+        &derivation_path,
+        &input,
+    )
+    .unwrap();
+    let dpk = DerivedPublicKey::compute_derived_key(&setup.master_pk, &derivation_path);
+
+    assert_eq!(
+        TransportPublicKey::deserialize(&tpk.serialize()).unwrap(),
+        tpk
+    );
+    assert_eq!(
+        DerivedPublicKey::deserialize(&dpk.serialize()).unwrap(),
+        dpk
+    );
+    assert_eq!(
+        EncryptedKeyShare::deserialize(&shares[&0].serialize()).unwrap(),
+        shares[&0]
+    );
+    assert_eq!(EncryptedKey::deserialize(&ek.serialize()).unwrap(), ek);
+
+    assert!(EncryptedKey::deserialize(&ek.serialize()[1..]).is_err());
+    assert!(TransportPublicKey::deserialize(&G1Affine::identity().serialize()).is_err());
+}
@@ -0,0 +1,354 @@
+//! Threshold BIP340 Schnorr signatures over secp256k1
+//!
+//! The protocol reuses the IDKG machinery of threshold ECDSA: the key
+//! transcript is an unmasked sharing of the secret key `x`, and each
+//! presignature is an unmasked sharing of a random value `k`, such
+//! that `R = k*G` is public.
+//!
+//! BIP340 requires both the public key and the presignature to have an
+//! even y coordinate. Instead of modifying the shares, each signer
+//! locally negates its share of `k` (resp. `x`) whenever the y coordinate
+//! of the rerandomized presignature (resp. the derived public key) is odd.
+use crate::*;
+use ic_crypto_sha::Sha256;
+
+const CURVE: EccCurveType = EccCurveType::K256;
+
+/// Returns true if the (non-infinity) point has an even y coordinate
+fn has_even_y(pt: &EccPoint) -> bool {
+    // The compressed SEC1 encoding uses 0x02 for even and 0x03 for odd y
+    pt.serialize()[0] == 0x02
+}
+
+/// Returns the scalar 1 or -1 to map `pt` onto the point with even y
+fn even_y_sign(pt: &EccPoint) -> EccScalar {
+    if has_even_y(pt) {
+        EccScalar::one(CURVE)
+    } else {
+        EccScalar::one(CURVE).negate()
+    }
+}
+
+/// Computes the BIP340 challenge `int(hash_BIP0340/challenge(R.x || P.x || m)) mod n`
+fn bip340_challenge(
+    presig: &EccPoint,
+    public_key: &EccPoint,
+    message: &[u8],
+) -> ThresholdEcdsaResult<EccScalar> {
+    let tag = Sha256::hash(b"BIP0340/challenge");
+    let mut sha = Sha256::new();
+    sha.write(&tag);
+    sha.write(&tag);
+    sha.write(&presig.affine_x()?.as_bytes());
+    sha.write(&public_key.affine_x()?.as_bytes());
+    sha.write(message);
+    EccScalar::from_bytes_wide(CURVE, &sha.finish())
+}
+
+/// The values derived from the randomness, the message and the derivation
+/// path that are shared by all signers.
+struct RerandomizedPresignature {
+    /// The additive tweak of the derived key
+    key_tweak: EccScalar,
+    /// The additive randomizer of the presignature
+    presig_randomizer: EccScalar,
+    /// The derived public key `P = (x + key_tweak)*G`
+    derived_key: EccPoint,
+    /// The rerandomized presignature `R = (k + presig_randomizer)*G`
+    randomized_pre_sig: EccPoint,
+}
+
+impl RerandomizedPresignature {
+    fn compute(
+        message: &[u8],
+        randomness: &Randomness,
+        derivation_path: &DerivationPath,
+        key_transcript: &IDkgTranscriptInternal,
+        presig_transcript: &IDkgTranscriptInternal,
+    ) -> ThresholdEcdsaResult<Self> {
+        let pre_sig = match &presig_transcript.combined_commitment {
+            CombinedCommitment::ByInterpolation(PolynomialCommitment::Simple(c)) => {
+                c.constant_term()
+            }
+            _ => return Err(ThresholdEcdsaError::UnexpectedCommitmentType),
+        };
+
+        let master_key = key_transcript.constant_term();
+
+        if pre_sig.curve_type() != CURVE || master_key.curve_type() != CURVE {
+            return Err(ThresholdEcdsaError::CurveMismatch);
+        }
+
+        let (key_tweak, _chain_key) = derivation_path.derive_tweak(&master_key)?;
+
+        let mut ro = ro::RandomOracle::new("ic-crypto-tschnorr-bip340-rerandomize-presig");
+        ro.add_bytestring("randomness", &randomness.get())?;
+        ro.add_bytestring("message", message)?;
+        ro.add_point("pre_sig", &pre_sig)?;
+        ro.add_scalar("key_tweak", &key_tweak)?;
+        let presig_randomizer = ro.output_scalar(CURVE)?;
+
+        let derived_key = master_key.add_points(&EccPoint::mul_by_g(&key_tweak)?)?;
+        let randomized_pre_sig = pre_sig.add_points(&EccPoint::mul_by_g(&presig_randomizer)?)?;
+
+        if derived_key.is_infinity()? || randomized_pre_sig.is_infinity()? {
+            return Err(ThresholdEcdsaError::InvalidPoint);
+        }
+
+        Ok(Self {
+            key_tweak,
+            presig_randomizer,
+            derived_key,
+            randomized_pre_sig,
+        })
+    }
+
+    fn challenge(&self, message: &[u8]) -> ThresholdEcdsaResult<EccScalar> {
+        bip340_challenge(&self.randomized_pre_sig, &self.derived_key, message)
+    }
+}
+
+/// A share of a threshold BIP340 signature
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct ThresholdBip340SignatureShareInternal {
+    s: EccScalar,
+}
+
+impl ThresholdBip340SignatureShareInternal {
+    /// Create a new BIP340 signature share
+    ///
+    /// The share is `s_i = σ_R*(k_i + δ) + e*σ_P*(x_i + t)` where `k_i` and
+    /// `x_i` are the signer's shares of the presignature and the key, `δ`
+    /// is the presignature randomizer, `t` the key tweak, `e` the BIP340
+    /// challenge, and `σ_R`, `σ_P` are the signs mapping the rerandomized
+    /// presignature and the derived key onto points with even y.
+    pub(crate) fn new(
+        derivation_path: &DerivationPath,
+        message: &[u8],
+        randomness: Randomness,
+        key_transcript: &IDkgTranscriptInternal,
+        key_opening: &CommitmentOpening,
+        presig_transcript: &IDkgTranscriptInternal,
+        presig_opening: &CommitmentOpening,
+    ) -> ThresholdEcdsaResult<Self> {
+        let rerandomized = RerandomizedPresignature::compute(
+            message,
+            &randomness,
+            derivation_path,
+            key_transcript,
+            presig_transcript,
+        )?;
+
+        let key_share = match key_opening {
+            CommitmentOpening::Simple(s) => s,
+            _ => return Err(ThresholdEcdsaError::UnexpectedCommitmentType),
+        };
+
+        let presig_share = match presig_opening {
+            CommitmentOpening::Simple(s) => s,
+            _ => return Err(ThresholdEcdsaError::UnexpectedCommitmentType),
+        };
+
+        let e = rerandomized.challenge(message)?;
+
+        let tweaked_key_share = key_share
+            .add(&rerandomized.key_tweak)?
+            .mul(&even_y_sign(&rerandomized.derived_key))?;
+        let randomized_presig_share = presig_share
+            .add(&rerandomized.presig_randomizer)?
+            .mul(&even_y_sign(&rerandomized.randomized_pre_sig))?;
+
+        let s = randomized_presig_share.add(&e.mul(&tweaked_key_share)?)?;
+
+        Ok(Self { s })
+    }
+
+    /// Verify a BIP340 signature share
+    ///
+    /// The check is `s_i*G == σ_R*(K_i + δ*G) + e*σ_P*(X_i + t*G)` where
+    /// `K_i` and `X_i` are the evaluations of the presignature and key
+    /// commitments at the signer's index.
+    pub fn verify(
+        &self,
+        derivation_path: &DerivationPath,
+        message: &[u8],
+        randomness: Randomness,
+        signer_index: NodeIndex,
+        key_transcript: &IDkgTranscriptInternal,
+        presig_transcript: &IDkgTranscriptInternal,
+    ) -> ThresholdEcdsaResult<()> {
+        let rerandomized = RerandomizedPresignature::compute(
+            message,
+            &randomness,
+            derivation_path,
+            key_transcript,
+            presig_transcript,
+        )?;
+
+        let e = rerandomized.challenge(message)?;
+
+        let key_j = key_transcript
+            .evaluate_at(signer_index)?
+            .add_points(&EccPoint::mul_by_g(&rerandomized.key_tweak)?)?;
+        let presig_j = presig_transcript
+            .evaluate_at(signer_index)?
+            .add_points(&EccPoint::mul_by_g(&rerandomized.presig_randomizer)?)?;
+
+        let key_j = key_j.scalar_mul(&e.mul(&even_y_sign(&rerandomized.derived_key))?)?;
+        let presig_j = presig_j.scalar_mul(&even_y_sign(&rerandomized.randomized_pre_sig))?;
+
+        if EccPoint::mul_by_g(&self.s)? != presig_j.add_points(&key_j)? {
+            return Err(ThresholdEcdsaError::InvalidSignatureShare);
+        }
+
+        Ok(())
+    }
+
+    pub fn serialize(&self) -> Vec<u8> {
+        self.s.serialize()
+    }
+
+    pub fn deserialize(bytes: &[u8]) -> ThresholdEcdsaResult<Self> {
+        let s = EccScalar::deserialize(CURVE, bytes)
+            .map_err(|e| ThresholdEcdsaError::SerializationError(format!("{:?}", e)))?;
+        Ok(Self { s })
+    }
+}
+
+/// A threshold BIP340 signature
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct ThresholdBip340CombinedSignatureInternal {
+    /// The presignature point, always with even y coordinate
+    r: EccPoint,
+    s: EccScalar,
+}
+
+impl ThresholdBip340CombinedSignatureInternal {
+    /// Serialize in the 64-byte format specified in BIP340
+    pub fn serialize(&self) -> ThresholdEcdsaResult<Vec<u8>> {
+        let mut sig = self.r.affine_x()?.as_bytes();
+        sig.extend_from_slice(&self.s.serialize());
+        Ok(sig)
+    }
+
+    /// Deserialize from the 64-byte format specified in BIP340
+    pub fn deserialize(bytes: &[u8]) -> ThresholdEcdsaResult<Self> {
+        const FIELD_BYTES: usize = 32;
+
+        if bytes.len() != 2 * FIELD_BYTES {
+            return Err(ThresholdEcdsaError::SerializationError(
+                "Bad signature length".to_string(),
+            ));
+        }
+
+        // The x coordinate encodes the point with even y (BIP340 lift_x)
+        let mut compressed_r = Vec::with_capacity(1 + FIELD_BYTES);
+        compressed_r.push(0x02);
+        compressed_r.extend_from_slice(&bytes[..FIELD_BYTES]);
+
+        let r = EccPoint::deserialize(CURVE, &compressed_r)
+            .map_err(|e| ThresholdEcdsaError::SerializationError(format!("{:?}", e)))?;
+        let s = EccScalar::deserialize(CURVE, &bytes[FIELD_BYTES..])
+            .map_err(|e| ThresholdEcdsaError::SerializationError(format!("{:?}", e)))?;
+
+        Ok(Self { r, s })
+    }
+
+    /// Combine sufficient signature shares into a BIP340 signature
+    ///
+    /// The signature shares must be verified prior to use
+    pub(crate) fn new(
+        derivation_path: &DerivationPath,
+        message: &[u8],
+        randomness: Randomness,
+        key_transcript: &IDkgTranscriptInternal,
+        presig_transcript: &IDkgTranscriptInternal,
+        reconstruction_threshold: NumberOfNodes,
+        sig_shares: &BTreeMap<NodeIndex, ThresholdBip340SignatureShareInternal>,
+    ) -> ThresholdEcdsaResult<Self> {
+        let reconstruction_threshold = reconstruction_threshold.get() as usize;
+        if sig_shares.len() < reconstruction_threshold {
+            return Err(ThresholdEcdsaError::InsufficientDealings);
+        }
+
+        let rerandomized = RerandomizedPresignature::compute(
+            message,
+            &randomness,
+            derivation_path,
+            key_transcript,
+            presig_transcript,
+        )?;
+
+        let mut x_values = Vec::with_capacity(reconstruction_threshold);
+        let mut samples = Vec::with_capacity(reconstruction_threshold);
+
+        for (index, sig_share) in sig_shares.iter().take(reconstruction_threshold) {
+            x_values.push(*index);
+            samples.push(sig_share.s.clone());
+        }
+
+        let coefficients = LagrangeCoefficients::at_zero(CURVE, &x_values)?;
+        let s = coefficients.interpolate_scalar(&samples)?;
+
+        let r = if has_even_y(&rerandomized.randomized_pre_sig) {
+            rerandomized.randomized_pre_sig
+        } else {
+            rerandomized.randomized_pre_sig.negate()
+        };
+
+        Ok(Self { r, s })
+    }
+
+    /// Verify a threshold BIP340 signature
+    ///
+    /// In addition to the BIP340 verification equation, this also checks
+    /// that the signature was generated with the provided presignature
+    /// transcript and randomness.
+    pub fn verify(
+        &self,
+        derivation_path: &DerivationPath,
+        message: &[u8],
+        randomness: Randomness,
+        presig_transcript: &IDkgTranscriptInternal,
+        key_transcript: &IDkgTranscriptInternal,
+    ) -> ThresholdEcdsaResult<()> {
+        if self.s.is_zero() || !has_even_y(&self.r) {
+            return Err(ThresholdEcdsaError::InvalidSignature);
+        }
+
+        let rerandomized = RerandomizedPresignature::compute(
+            message,
+            &randomness,
+            derivation_path,
+            key_transcript,
+            presig_transcript,
+        )?;
+
+        if self.r.affine_x()? != rerandomized.randomized_pre_sig.affine_x()? {
+            return Err(ThresholdEcdsaError::InvalidSignature);
+        }
+
+        // BIP340 uses the public key with even y
+        let public_key = if has_even_y(&rerandomized.derived_key) {
+            rerandomized.derived_key
+        } else {
+            rerandomized.derived_key.negate()
+        };
+
+        let e = bip340_challenge(&self.r, &public_key, message)?;
+
+        // R = s*G - e*P
+        let rp = EccPoint::mul_2_points(
+            &EccPoint::generator_g(CURVE),
+            &self.s,
+            &public_key,
+            &e.negate(),
+        )?;
+
+        if rp.is_infinity()? || !has_even_y(&rp) || rp != self.r {
+            return Err(ThresholdEcdsaError::InvalidSignature);
+        }
+
+        Ok(())
+    }
+}
@@ -189,8 +189,11 @@ impl IDkgDealingInternal {
             ));
         }
 
+        // The shares are on `curve`, while the recipients' MEGa keys may be
+        // on another curve; however all recipients must use the same one.
+        let key_curve = recipients[0].curve_type();
         for recipient in recipients {
-            if recipient.curve_type() != curve {
+            if recipient.curve_type() != key_curve {
                 return Err(ThresholdEcdsaError::InvalidRecipients);
             }
         }
@@ -426,7 +429,7 @@ impl IDkgDealingInternal {
         dealer_index: NodeIndex,
         recipient_index: NodeIndex,
     ) -> ThresholdEcdsaResult<()> {
-        if private_key.curve_type() != curve_type || public_key.curve_type() != curve_type {
+        if private_key.curve_type() != public_key.curve_type() {
             return Err(ThresholdEcdsaError::CurveMismatch);
         }
 
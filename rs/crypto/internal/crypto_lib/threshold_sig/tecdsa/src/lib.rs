@@ -69,6 +69,15 @@
 //! * Generation and verification of signature shares
 //! * Generation and verification of combined signatures
 //!
+//! ## Protocol: Threshold BIP340 Schnorr Signatures
+//!
+//! File: `bip340.rs`
+//!
+//! Generation and verification of signature shares and combined signatures
+//! for BIP340 Schnorr signatures over secp256k1. Uses the same IDKG
+//! transcripts as threshold ECDSA, with a single unmasked transcript as
+//! presignature.
+//!
 //! ## Protocol: Multi-encryption gadget (MEGa)
 //!
 //! File: `mega.rs`
@@ -246,6 +255,7 @@ pub enum ThresholdEcdsaError {
 
 pub type ThresholdEcdsaResult<T> = std::result::Result<T, ThresholdEcdsaError>;
 
+mod bip340;
 mod complaints;
 mod dealings;
 mod fe;
@@ -269,6 +279,7 @@ pub use crate::poly::*;
 pub use crate::transcript::*;
 
 pub use crate::key_derivation::{DerivationIndex, DerivationPath};
+pub use bip340::{ThresholdBip340CombinedSignatureInternal, ThresholdBip340SignatureShareInternal};
 pub use sign::{ThresholdEcdsaCombinedSigInternal, ThresholdEcdsaSigShareInternal};
 
 /// Create MEGa encryption keypair
@@ -316,10 +327,22 @@ pub fn create_dealing(
     seed: Seed,
 ) -> Result<IDkgDealingInternal, IdkgCreateDealingInternalError> {
     let curve = match algorithm_id {
-        AlgorithmId::ThresholdEcdsaSecp256k1 => Ok(EccCurveType::K256),
+        AlgorithmId::ThresholdEcdsaSecp256k1 | AlgorithmId::ThresholdSchnorrBip340 => {
+            Ok(EccCurveType::K256)
+        }
+        AlgorithmId::ThresholdEcdsaSecp256r1 => Ok(EccCurveType::P256),
         _ => Err(IdkgCreateDealingInternalError::UnsupportedAlgorithm),
     }?;
 
+    // MEGa encryption keys are always over secp256k1, whatever the curve of
+    // the shares being dealt.
+    if recipients
+        .iter()
+        .any(|recipient| recipient.curve_type() != EccCurveType::K256)
+    {
+        return Err(IdkgCreateDealingInternalError::InvalidRecipients);
+    }
+
     IDkgDealingInternal::new(
         shares,
         curve,
@@ -367,7 +390,10 @@ pub fn create_transcript(
     operation_mode: &IDkgTranscriptOperationInternal,
 ) -> Result<IDkgTranscriptInternal, IDkgCreateTranscriptInternalError> {
     let curve = match algorithm_id {
-        AlgorithmId::ThresholdEcdsaSecp256k1 => Ok(EccCurveType::K256),
+        AlgorithmId::ThresholdEcdsaSecp256k1 | AlgorithmId::ThresholdSchnorrBip340 => {
+            Ok(EccCurveType::K256)
+        }
+        AlgorithmId::ThresholdEcdsaSecp256r1 => Ok(EccCurveType::P256),
         _ => Err(IDkgCreateTranscriptInternalError::UnsupportedAlgorithm),
     }?;
 
@@ -564,7 +590,10 @@ pub fn publicly_verify_dealing(
     associated_data: &[u8],
 ) -> Result<(), IDkgVerifyDealingInternalError> {
     let curve = match algorithm_id {
-        AlgorithmId::ThresholdEcdsaSecp256k1 => Ok(EccCurveType::K256),
+        AlgorithmId::ThresholdEcdsaSecp256k1 | AlgorithmId::ThresholdSchnorrBip340 => {
+            Ok(EccCurveType::K256)
+        }
+        AlgorithmId::ThresholdEcdsaSecp256r1 => Ok(EccCurveType::P256),
         _ => Err(IDkgVerifyDealingInternalError::UnsupportedAlgorithm),
     }?;
 
@@ -596,7 +625,10 @@ pub fn privately_verify_dealing(
     recipient_index: NodeIndex,
 ) -> Result<(), IDkgVerifyDealingInternalError> {
     let curve = match algorithm_id {
-        AlgorithmId::ThresholdEcdsaSecp256k1 => Ok(EccCurveType::K256),
+        AlgorithmId::ThresholdEcdsaSecp256k1 | AlgorithmId::ThresholdSchnorrBip340 => {
+            Ok(EccCurveType::K256)
+        }
+        AlgorithmId::ThresholdEcdsaSecp256r1 => Ok(EccCurveType::P256),
         _ => Err(IDkgVerifyDealingInternalError::UnsupportedAlgorithm),
     }?;
 
@@ -660,6 +692,9 @@ fn signature_parameters(algorithm_id: AlgorithmId) -> Option<(EccCurveType, usiz
         AlgorithmId::ThresholdEcdsaSecp256k1 => {
             Some((EccCurveType::K256, EccCurveType::K256.scalar_bytes()))
         }
+        AlgorithmId::ThresholdEcdsaSecp256r1 => {
+            Some((EccCurveType::P256, EccCurveType::P256.scalar_bytes()))
+        }
         _ => None,
     }
 }
@@ -808,6 +843,7 @@ pub fn combine_sig_shares(
 ) -> Result<ThresholdEcdsaCombinedSigInternal, ThresholdEcdsaCombineSigSharesInternalError> {
     let curve_type = match algorithm_id {
         AlgorithmId::ThresholdEcdsaSecp256k1 => EccCurveType::K256,
+        AlgorithmId::ThresholdEcdsaSecp256r1 => EccCurveType::P256,
         _ => return Err(ThresholdEcdsaCombineSigSharesInternalError::UnsupportedAlgorithm),
     };
 
@@ -922,6 +958,189 @@ pub fn derive_public_key(
     )?)
 }
 
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub enum ThresholdBip340GenerateSigShareInternalError {
+    InconsistentCommitments,
+    InternalError(String),
+}
+
+impl From<ThresholdEcdsaError> for ThresholdBip340GenerateSigShareInternalError {
+    fn from(e: ThresholdEcdsaError) -> Self {
+        match e {
+            ThresholdEcdsaError::CurveMismatch => Self::InconsistentCommitments,
+            ThresholdEcdsaError::InvalidCommitment => Self::InconsistentCommitments,
+            ThresholdEcdsaError::UnexpectedCommitmentType => Self::InconsistentCommitments,
+            x => Self::InternalError(format!("{:?}", x)),
+        }
+    }
+}
+
+/// Create a new threshold BIP340 signature share
+///
+/// The nonce should be random and shared by all nodes, for instance
+/// by deriving a value from the random tape.
+///
+/// The presig_transcript is the transcript of the pre-signature and
+/// key_opening and presig_opening are our openings of the commitments
+/// in the key and presignature transcripts.
+///
+/// Unlike ECDSA, the message is not hashed by the caller and may have
+/// an arbitrary length.
+pub fn sign_share_bip340(
+    derivation_path: &DerivationPath,
+    message: &[u8],
+    nonce: Randomness,
+    key_transcript: &IDkgTranscriptInternal,
+    key_opening: &CommitmentOpening,
+    presig_transcript: &IDkgTranscriptInternal,
+    presig_opening: &CommitmentOpening,
+) -> Result<ThresholdBip340SignatureShareInternal, ThresholdBip340GenerateSigShareInternalError> {
+    ThresholdBip340SignatureShareInternal::new(
+        derivation_path,
+        message,
+        nonce,
+        key_transcript,
+        key_opening,
+        presig_transcript,
+        presig_opening,
+    )
+    .map_err(|e| e.into())
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub enum ThresholdBip340VerifySigShareInternalError {
+    InconsistentCommitments,
+    InvalidSignatureShare,
+    InternalError(String),
+}
+
+impl From<ThresholdEcdsaError> for ThresholdBip340VerifySigShareInternalError {
+    fn from(e: ThresholdEcdsaError) -> Self {
+        match e {
+            ThresholdEcdsaError::CurveMismatch => Self::InconsistentCommitments,
+            ThresholdEcdsaError::InvalidCommitment => Self::InconsistentCommitments,
+            ThresholdEcdsaError::UnexpectedCommitmentType => Self::InconsistentCommitments,
+            ThresholdEcdsaError::InvalidSignatureShare => Self::InvalidSignatureShare,
+            x => Self::InternalError(format!("{:?}", x)),
+        }
+    }
+}
+
+/// Verify a BIP340 signature share
+///
+/// The values provided must be consistent with when the signature share
+/// was created
+pub fn verify_bip340_signature_share(
+    sig_share: &ThresholdBip340SignatureShareInternal,
+    derivation_path: &DerivationPath,
+    message: &[u8],
+    randomness: Randomness,
+    signer_index: NodeIndex,
+    key_transcript: &IDkgTranscriptInternal,
+    presig_transcript: &IDkgTranscriptInternal,
+) -> Result<(), ThresholdBip340VerifySigShareInternalError> {
+    sig_share
+        .verify(
+            derivation_path,
+            message,
+            randomness,
+            signer_index,
+            key_transcript,
+            presig_transcript,
+        )
+        .map_err(|e| e.into())
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub enum ThresholdBip340CombineSigSharesInternalError {
+    InconsistentCommitments,
+    InsufficientShares,
+    InternalError(String),
+}
+
+impl From<ThresholdEcdsaError> for ThresholdBip340CombineSigSharesInternalError {
+    fn from(e: ThresholdEcdsaError) -> Self {
+        match e {
+            ThresholdEcdsaError::CurveMismatch => Self::InconsistentCommitments,
+            ThresholdEcdsaError::InvalidCommitment => Self::InconsistentCommitments,
+            ThresholdEcdsaError::UnexpectedCommitmentType => Self::InconsistentCommitments,
+            ThresholdEcdsaError::InsufficientDealings => Self::InsufficientShares,
+            x => Self::InternalError(format!("{:?}", x)),
+        }
+    }
+}
+
+/// Combine sufficient signature shares into a BIP340 signature
+///
+/// The signature shares must be verified prior to use, and there must
+/// be at least reconstruction_threshold many of them.
+pub fn combine_bip340_signature_shares(
+    derivation_path: &DerivationPath,
+    message: &[u8],
+    randomness: Randomness,
+    key_transcript: &IDkgTranscriptInternal,
+    presig_transcript: &IDkgTranscriptInternal,
+    reconstruction_threshold: NumberOfNodes,
+    sig_shares: &BTreeMap<NodeIndex, ThresholdBip340SignatureShareInternal>,
+) -> Result<ThresholdBip340CombinedSignatureInternal, ThresholdBip340CombineSigSharesInternalError>
+{
+    ThresholdBip340CombinedSignatureInternal::new(
+        derivation_path,
+        message,
+        randomness,
+        key_transcript,
+        presig_transcript,
+        reconstruction_threshold,
+        sig_shares,
+    )
+    .map_err(|e| e.into())
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub enum ThresholdBip340VerifySignatureInternalError {
+    InvalidSignature,
+    InconsistentCommitments,
+    InternalError(String),
+}
+
+impl From<ThresholdEcdsaError> for ThresholdBip340VerifySignatureInternalError {
+    fn from(e: ThresholdEcdsaError) -> Self {
+        match e {
+            ThresholdEcdsaError::CurveMismatch => Self::InconsistentCommitments,
+            ThresholdEcdsaError::InvalidCommitment => Self::InconsistentCommitments,
+            ThresholdEcdsaError::UnexpectedCommitmentType => Self::InconsistentCommitments,
+            ThresholdEcdsaError::InvalidSignature => Self::InvalidSignature,
+            x => Self::InternalError(format!("{:?}", x)),
+        }
+    }
+}
+
+/// Verify a threshold BIP340 signature
+///
+/// In addition to checking that the signature itself is consistent with
+/// the provided message and the public key associated with
+/// `derivation_path`, this function also verifies that the signature was
+/// generated correctly with regards to the provided presignature
+/// transcript and randomness.
+pub fn verify_threshold_bip340_signature(
+    signature: &ThresholdBip340CombinedSignatureInternal,
+    derivation_path: &DerivationPath,
+    message: &[u8],
+    randomness: Randomness,
+    presig_transcript: &IDkgTranscriptInternal,
+    key_transcript: &IDkgTranscriptInternal,
+) -> Result<(), ThresholdBip340VerifySignatureInternalError> {
+    signature
+        .verify(
+            derivation_path,
+            message,
+            randomness,
+            presig_transcript,
+            key_transcript,
+        )
+        .map_err(|e| e.into())
+}
+
 #[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
 pub enum IDkgGenerateComplaintsInternalError {
     InvalidArguments(String),
@@ -203,20 +203,22 @@ impl MEGaCiphertext {
     /// Simple type verification for MEGa ciphertexts
     ///
     /// Verifies that the ciphertext is of the expected type (single or pairs)
-    /// and is for the expected curve.
+    /// and that it encrypts scalars of the expected curve.
+    ///
+    /// The ephemeral key and the proof of possession are over the curve of
+    /// the recipients' MEGa keys, which need not be the same as the curve
+    /// of the plaintexts; they are only checked for consistency here.
     pub fn verify_is(
         &self,
         ctype: MEGaCiphertextType,
         curve: EccCurveType,
     ) -> ThresholdEcdsaResult<()> {
-        if self.ephemeral_key().curve_type() != curve {
-            return Err(ThresholdEcdsaError::CurveMismatch);
-        }
+        let key_curve = self.ephemeral_key().curve_type();
 
-        if self.pop_public_key().curve_type() != curve {
+        if self.pop_public_key().curve_type() != key_curve {
             return Err(ThresholdEcdsaError::CurveMismatch);
         }
-        if self.pop_proof().curve_type()? != curve {
+        if self.pop_proof().curve_type()? != key_curve {
             return Err(ThresholdEcdsaError::CurveMismatch);
         }
 
@@ -308,7 +310,7 @@ impl From<MEGaCiphertextPair> for MEGaCiphertext {
 fn check_plaintexts(
     plaintexts: &[EccScalar],
     recipients: &[MEGaPublicKey],
-) -> ThresholdEcdsaResult<EccCurveType> {
+) -> ThresholdEcdsaResult<(EccCurveType, EccCurveType)> {
     if plaintexts.len() != recipients.len() {
         return Err(ThresholdEcdsaError::InvalidArguments(
             "Must be as many plaintexts as recipients".to_string(),
@@ -329,19 +331,15 @@ fn check_plaintexts(
         }
     }
 
-    for recipient in recipients {
-        if recipient.curve_type() != curve_type {
-            return Err(ThresholdEcdsaError::CurveMismatch);
-        }
-    }
+    let key_curve = check_recipients(recipients)?;
 
-    Ok(curve_type)
+    Ok((key_curve, curve_type))
 }
 
 fn check_plaintexts_pair(
     plaintexts: &[(EccScalar, EccScalar)],
     recipients: &[MEGaPublicKey],
-) -> ThresholdEcdsaResult<EccCurveType> {
+) -> ThresholdEcdsaResult<(EccCurveType, EccCurveType)> {
     if plaintexts.len() != recipients.len() {
         return Err(ThresholdEcdsaError::InvalidArguments(
             "Must be as many plaintexts as recipients".to_string(),
@@ -362,13 +360,25 @@ fn check_plaintexts_pair(
         }
     }
 
+    let key_curve = check_recipients(recipients)?;
+
+    Ok((key_curve, curve_type))
+}
+
+/// Check that all recipient keys are on the same curve, and return it
+///
+/// The curve of the recipient keys may differ from the curve of the
+/// plaintexts being encrypted.
+fn check_recipients(recipients: &[MEGaPublicKey]) -> ThresholdEcdsaResult<EccCurveType> {
+    let key_curve = recipients[0].curve_type();
+
     for recipient in recipients {
-        if recipient.curve_type() != curve_type {
+        if recipient.curve_type() != key_curve {
             return Err(ThresholdEcdsaError::CurveMismatch);
         }
     }
 
-    Ok(curve_type)
+    Ok(key_curve)
 }
 
 fn mega_hash_to_scalars(
@@ -379,9 +389,8 @@ fn mega_hash_to_scalars(
     public_key: &EccPoint,
     ephemeral_key: &EccPoint,
     shared_secret: &EccPoint,
+    plaintext_curve: EccCurveType,
 ) -> ThresholdEcdsaResult<Vec<EccScalar>> {
-    let curve_type = public_key.curve_type();
-
     let count = match ctype {
         MEGaCiphertextType::Single => 1,
         MEGaCiphertextType::Pairs => 2,
@@ -394,7 +403,7 @@ fn mega_hash_to_scalars(
     ro.add_point("public_key", public_key)?;
     ro.add_point("ephemeral_key", ephemeral_key)?;
     ro.add_point("shared_secret", shared_secret)?;
-    ro.output_scalars(curve_type, count)
+    ro.output_scalars(plaintext_curve, count)
 }
 
 /// Compute the Proof Of Possession (PoP) base element
@@ -486,12 +495,12 @@ impl MEGaCiphertextSingle {
         dealer_index: NodeIndex,
         associated_data: &[u8],
     ) -> ThresholdEcdsaResult<Self> {
-        let curve_type = check_plaintexts(plaintexts, recipients)?;
+        let (key_curve, plaintext_curve) = check_plaintexts(plaintexts, recipients)?;
 
         let ctype = MEGaCiphertextType::Single;
 
         let (beta, v, pop_public_key, pop_proof) =
-            compute_eph_key_and_pop(ctype, curve_type, seed, associated_data, dealer_index)?;
+            compute_eph_key_and_pop(ctype, key_curve, seed, associated_data, dealer_index)?;
 
         let mut ctexts = Vec::with_capacity(recipients.len());
 
@@ -506,6 +515,7 @@ impl MEGaCiphertextSingle {
                 &pubkey.point,
                 &v,
                 &ubeta,
+                plaintext_curve,
             )?;
 
             let ctext = hm[0].add(ptext)?;
@@ -558,6 +568,7 @@ impl MEGaCiphertextSingle {
             &recipient_public_key.point,
             &self.ephemeral_key,
             shared_secret,
+            self.ctexts[recipient_index as usize].curve_type(),
         )?;
 
         self.ctexts[recipient_index as usize].sub(&hm[0])
@@ -597,12 +608,12 @@ impl MEGaCiphertextPair {
         dealer_index: NodeIndex,
         associated_data: &[u8],
     ) -> ThresholdEcdsaResult<Self> {
-        let curve_type = check_plaintexts_pair(plaintexts, recipients)?;
+        let (key_curve, plaintext_curve) = check_plaintexts_pair(plaintexts, recipients)?;
 
         let ctype = MEGaCiphertextType::Pairs;
 
         let (beta, v, pop_public_key, pop_proof) =
-            compute_eph_key_and_pop(ctype, curve_type, seed, associated_data, dealer_index)?;
+            compute_eph_key_and_pop(ctype, key_curve, seed, associated_data, dealer_index)?;
 
         let mut ctexts = Vec::with_capacity(recipients.len());
 
@@ -617,6 +628,7 @@ impl MEGaCiphertextPair {
                 &pubkey.point,
                 &v,
                 &ubeta,
+                plaintext_curve,
             )?;
 
             let ctext0 = hm[0].add(&ptext.0)?;
@@ -670,6 +682,7 @@ impl MEGaCiphertextPair {
             &recipient_public_key.point,
             &self.ephemeral_key,
             shared_secret,
+            self.ctexts[recipient_index as usize].0.curve_type(),
         )?;
 
         let ptext0 = self.ctexts[recipient_index as usize].0.sub(&hm[0])?;
@@ -222,6 +222,7 @@ impl ThresholdEcdsaCombinedSigInternal {
     pub fn deserialize(algorithm_id: AlgorithmId, bytes: &[u8]) -> ThresholdEcdsaResult<Self> {
         let curve_type = match algorithm_id {
             AlgorithmId::ThresholdEcdsaSecp256k1 => Ok(EccCurveType::K256),
+            AlgorithmId::ThresholdEcdsaSecp256r1 => Ok(EccCurveType::P256),
             x => Err(ThresholdEcdsaError::SerializationError(format!(
                 "Invalid algorithm {:?} for threshold ECDSA",
                 x
@@ -404,9 +405,12 @@ pub fn derive_public_key(
     derivation_path: &DerivationPath,
 ) -> ThresholdEcdsaResult<EcdsaPublicKey> {
     let raw_master_pk = match master_public_key.algorithm_id {
-        AlgorithmId::EcdsaSecp256k1 => {
+        AlgorithmId::EcdsaSecp256k1 | AlgorithmId::SchnorrSecp256k1 => {
             EccPoint::deserialize(EccCurveType::K256, &master_public_key.public_key)?
         }
+        AlgorithmId::EcdsaP256 => {
+            EccPoint::deserialize(EccCurveType::P256, &master_public_key.public_key)?
+        }
         _ => return Err(ThresholdEcdsaError::CurveMismatch),
     };
     // Compute tweak
@@ -404,7 +404,7 @@ impl CommitmentOpening {
         secret_key: &MEGaPrivateKey,
         public_key: &MEGaPublicKey,
     ) -> Result<Self, IDkgComputeSecretSharesInternalError> {
-        let curve = secret_key.curve_type();
+        let curve = transcript_commitment.commitment().curve_type();
         let mut openings = Vec::with_capacity(verified_dealings.len());
 
         for (dealer_index, dealing) in verified_dealings {
@@ -482,7 +482,7 @@ impl CommitmentOpening {
         secret_key: &MEGaPrivateKey,
         public_key: &MEGaPublicKey,
     ) -> Result<Self, IDkgComputeSecretSharesInternalError> {
-        let curve = secret_key.curve_type();
+        let curve = transcript_commitment.commitment().curve_type();
         let mut openings = Vec::with_capacity(verified_dealings.len());
 
         for (dealer_index, dealing) in verified_dealings {
@@ -0,0 +1,208 @@
+use ic_crypto_internal_threshold_sig_ecdsa::*;
+use ic_crypto_test_utils_reproducible_rng::reproducible_rng;
+use ic_types::*;
+use rand::Rng;
+use std::collections::BTreeMap;
+
+mod test_utils;
+
+use crate::test_utils::*;
+
+struct Bip340Setup {
+    key: ProtocolRound,
+    presig: ProtocolRound,
+    threshold: NumberOfNodes,
+    receivers: usize,
+}
+
+impl Bip340Setup {
+    fn new(receivers: usize, threshold: usize, seed: Seed) -> ThresholdEcdsaResult<Self> {
+        let setup = ProtocolSetup::new(EccCurveType::K256, receivers, threshold, seed)?;
+
+        let key = ProtocolRound::random(&setup, receivers, 0)?;
+        let key = ProtocolRound::reshare_of_masked(&setup, &key, receivers, 0)?;
+        let presig = ProtocolRound::random(&setup, receivers, 0)?;
+        let presig = ProtocolRound::reshare_of_masked(&setup, &presig, receivers, 0)?;
+
+        Ok(Self {
+            key,
+            presig,
+            threshold: NumberOfNodes::from(threshold as u32),
+            receivers,
+        })
+    }
+
+    fn sign_share(
+        &self,
+        node_index: usize,
+        path: &DerivationPath,
+        message: &[u8],
+        randomness: Randomness,
+    ) -> ThresholdBip340SignatureShareInternal {
+        sign_share_bip340(
+            path,
+            message,
+            randomness,
+            &self.key.transcript,
+            &self.key.openings[node_index],
+            &self.presig.transcript,
+            &self.presig.openings[node_index],
+        )
+        .expect("Failed to create sig share")
+    }
+
+    fn verify_share(
+        &self,
+        share: &ThresholdBip340SignatureShareInternal,
+        node_index: usize,
+        path: &DerivationPath,
+        message: &[u8],
+        randomness: Randomness,
+    ) -> Result<(), ThresholdBip340VerifySigShareInternalError> {
+        verify_bip340_signature_share(
+            share,
+            path,
+            message,
+            randomness,
+            node_index as NodeIndex,
+            &self.key.transcript,
+            &self.presig.transcript,
+        )
+    }
+}
+
+#[test]
+fn should_generate_and_verify_bip340_signatures() -> ThresholdEcdsaResult<()> {
+    let rng = &mut reproducible_rng();
+
+    for (receivers, threshold) in [(1, 1), (4, 2), (7, 3)] {
+        let setup = Bip340Setup::new(receivers, threshold, Seed::from_rng(rng))?;
+
+        for message_len in [0, 32, 100] {
+            let message: Vec<u8> = (0..message_len).map(|_| rng.gen::<u8>()).collect();
+            let randomness = Randomness::from(rng.gen::<[u8; 32]>());
+            let path = DerivationPath::new_bip32(&[rng.gen::<u32>()]);
+
+            let mut shares = BTreeMap::new();
+            for node_index in 0..setup.receivers {
+                let share = setup.sign_share(node_index, &path, &message, randomness);
+                assert!(setup
+                    .verify_share(&share, node_index, &path, &message, randomness)
+                    .is_ok());
+                shares.insert(node_index as NodeIndex, share);
+            }
+
+            let sig = combine_bip340_signature_shares(
+                &path,
+                &message,
+                randomness,
+                &setup.key.transcript,
+                &setup.presig.transcript,
+                setup.threshold,
+                &shares,
+            )
+            .expect("Failed to combine shares");
+
+            assert_eq!(
+                verify_threshold_bip340_signature(
+                    &sig,
+                    &path,
+                    &message,
+                    randomness,
+                    &setup.presig.transcript,
+                    &setup.key.transcript,
+                ),
+                Ok(())
+            );
+
+            let sig_bytes = sig.serialize()?;
+            assert_eq!(sig_bytes.len(), 64);
+            assert_eq!(
+                ThresholdBip340CombinedSignatureInternal::deserialize(&sig_bytes)?,
+                sig
+            );
+
+            // The signature does not verify for another message
+            let mut wrong_message = message.clone();
+            wrong_message.push(0);
+            assert_eq!(
+                verify_threshold_bip340_signature(
+                    &sig,
+                    &path,
+                    &wrong_message,
+                    randomness,
+                    &setup.presig.transcript,
+                    &setup.key.transcript,
+                ),
+                Err(ThresholdBip340VerifySignatureInternalError::InvalidSignature)
+            );
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn should_reject_bip340_share_from_wrong_signer() -> ThresholdEcdsaResult<()> {
+    let rng = &mut reproducible_rng();
+    let setup = Bip340Setup::new(4, 2, Seed::from_rng(rng))?;
+
+    let message = rng.gen::<[u8; 32]>();
+    let randomness = Randomness::from(rng.gen::<[u8; 32]>());
+    let path = DerivationPath::new_bip32(&[1, 2, 3]);
+
+    let share = setup.sign_share(0, &path, &message, randomness);
+    assert!(setup
+        .verify_share(&share, 0, &path, &message, randomness)
+        .is_ok());
+    assert_eq!(
+        setup.verify_share(&share, 1, &path, &message, randomness),
+        Err(ThresholdBip340VerifySigShareInternalError::InvalidSignatureShare)
+    );
+    assert_eq!(
+        setup.verify_share(&share, 0, &path, &message[1..], randomness),
+        Err(ThresholdBip340VerifySigShareInternalError::InvalidSignatureShare)
+    );
+
+    let share_bytes = share.serialize();
+    assert_eq!(
+        ThresholdBip340SignatureShareInternal::deserialize(&share_bytes)?,
+        share
+    );
+
+    Ok(())
+}
+
+#[test]
+fn should_reject_insufficient_bip340_shares() -> ThresholdEcdsaResult<()> {
+    let rng = &mut reproducible_rng();
+    let setup = Bip340Setup::new(4, 3, Seed::from_rng(rng))?;
+
+    let message = rng.gen::<[u8; 32]>();
+    let randomness = Randomness::from(rng.gen::<[u8; 32]>());
+    let path = DerivationPath::new_bip32(&[]);
+
+    let shares: BTreeMap<_, _> = (0..2)
+        .map(|i| {
+            (
+                i as NodeIndex,
+                setup.sign_share(i, &path, &message, randomness),
+            )
+        })
+        .collect();
+
+    assert_eq!(
+        combine_bip340_signature_shares(
+            &path,
+            &message,
+            randomness,
+            &setup.key.transcript,
+            &setup.presig.transcript,
+            setup.threshold,
+            &shares,
+        ),
+        Err(ThresholdBip340CombineSigSharesInternalError::InsufficientShares)
+    );
+
+    Ok(())
+}
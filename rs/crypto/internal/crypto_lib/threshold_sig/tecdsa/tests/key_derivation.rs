@@ -24,21 +24,26 @@ fn test_index_next_behavior() {
 }
 
 #[test]
-fn test_that_key_derivation_on_secp256r1_currently_fails() -> Result<(), ThresholdEcdsaError> {
+fn should_key_derivation_on_secp256r1_be_consistent() -> Result<(), ThresholdEcdsaError> {
     let mut rng = reproducible_rng();
-    let path = DerivationPath::new_bip32(&[1, 2, 3]);
     let master_key = EccPoint::hash_to_point(
         EccCurveType::P256,
         &rng.gen::<[u8; 32]>(),
         "public_key".as_bytes(),
     )?;
 
-    assert_eq!(
-        path.derive_tweak(&master_key),
-        Err(ThresholdEcdsaError::InvalidArguments(
-            "Currently key derivation not defined for secp256r1".to_string()
-        ))
-    );
+    let (tweak_1_2, chain_key_1_2) =
+        DerivationPath::new_bip32(&[1, 2]).derive_tweak(&master_key)?;
+    assert_eq!(tweak_1_2.curve_type(), EccCurveType::P256);
+
+    // Deriving [1, 2] in one go must match deriving [1] and then [2]
+    let (tweak_1, chain_key_1) = DerivationPath::new_bip32(&[1]).derive_tweak(&master_key)?;
+    let key_1 = master_key.add_points(&EccPoint::mul_by_g(&tweak_1)?)?;
+    let (tweak_2, chain_key_2) =
+        DerivationPath::new_bip32(&[2]).derive_tweak_with_chain_code(&key_1, &chain_key_1)?;
+
+    assert_eq!(tweak_1.add(&tweak_2)?, tweak_1_2);
+    assert_eq!(chain_key_2, chain_key_1_2);
 
     Ok(())
 }
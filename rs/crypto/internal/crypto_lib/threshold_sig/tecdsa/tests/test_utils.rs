@@ -28,11 +28,7 @@ impl ProtocolSetup {
     ) -> Result<Self, ThresholdEcdsaError> {
         let alg = match curve {
             EccCurveType::K256 => AlgorithmId::ThresholdEcdsaSecp256k1,
-            _ => {
-                return Err(ThresholdEcdsaError::InvalidArguments(
-                    "Unsupported curve".to_string(),
-                ))
-            }
+            EccCurveType::P256 => AlgorithmId::ThresholdEcdsaSecp256r1,
         };
 
         let mut rng = seed.into_rng();
@@ -42,7 +38,8 @@ impl ProtocolSetup {
         let mut pk = Vec::with_capacity(receivers);
 
         for _i in 0..receivers {
-            let k = MEGaPrivateKey::generate(curve, &mut rng);
+            // MEGa keys are always secp256k1, regardless of the curve in use
+            let k = MEGaPrivateKey::generate(EccCurveType::K256, &mut rng);
             pk.push(k.public_key()?);
             sk.push(k);
         }
@@ -738,7 +735,10 @@ impl SignatureProtocolSetup {
 
     pub fn public_key(&self, path: &DerivationPath) -> Result<EcdsaPublicKey, ThresholdEcdsaError> {
         let master_public_key = MasterEcdsaPublicKey {
-            algorithm_id: AlgorithmId::EcdsaSecp256k1,
+            algorithm_id: match self.setup.alg {
+                AlgorithmId::ThresholdEcdsaSecp256r1 => AlgorithmId::EcdsaP256,
+                _ => AlgorithmId::EcdsaSecp256k1,
+            },
             public_key: self.key.transcript.constant_term().serialize(),
         };
         ic_crypto_internal_threshold_sig_ecdsa::sign::derive_public_key(&master_public_key, path)
@@ -852,13 +852,27 @@ impl SignatureProtocolExecution {
 
         use k256::ecdsa::signature::{Signature, Verifier};
 
-        let vk = k256::ecdsa::VerifyingKey::from_sec1_bytes(&pk.public_key)
-            .expect("Failed to parse public key");
+        match self.setup.setup.alg {
+            AlgorithmId::ThresholdEcdsaSecp256k1 => {
+                let vk = k256::ecdsa::VerifyingKey::from_sec1_bytes(&pk.public_key)
+                    .expect("Failed to parse public key");
+
+                let sig = k256::ecdsa::Signature::from_bytes(&sig.serialize())
+                    .expect("Failed to parse signature");
+
+                assert!(vk.verify(&self.signed_message, &sig).is_ok());
+            }
+            AlgorithmId::ThresholdEcdsaSecp256r1 => {
+                let vk = p256::ecdsa::VerifyingKey::from_sec1_bytes(&pk.public_key)
+                    .expect("Failed to parse public key");
 
-        let sig = k256::ecdsa::Signature::from_bytes(&sig.serialize())
-            .expect("Failed to parse signature");
+                let sig = p256::ecdsa::Signature::from_bytes(&sig.serialize())
+                    .expect("Failed to parse signature");
 
-        assert!(vk.verify(&self.signed_message, &sig).is_ok());
+                assert!(vk.verify(&self.signed_message, &sig).is_ok());
+            }
+            alg => panic!("Unexpected algorithm {:?}", alg),
+        }
 
         Ok(())
     }
@@ -19,3 +19,19 @@ pub struct SecretKeyStore {
     pub key_id_to_secret_key_v1:
         ::std::collections::HashMap<::prost::alloc::string::String, SecretKeyV1>,
 }
+/// EncryptedSecretKeyStore is a SecretKeyStore encrypted at rest.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct EncryptedSecretKeyStore {
+    /// Identifier of the key-encryption key (KEK) the store is encrypted with.
+    #[prost(string, tag = "1")]
+    pub kek_id: ::prost::alloc::string::String,
+    /// Nonce of the AES-256-GCM encryption.
+    #[prost(bytes = "vec", tag = "2")]
+    pub nonce: ::prost::alloc::vec::Vec<u8>,
+    /// AES-256-GCM encryption of the serialized SecretKeyStore.
+    #[prost(bytes = "vec", tag = "3")]
+    pub ciphertext: ::prost::alloc::vec::Vec<u8>,
+    /// AES-256-GCM authentication tag.
+    #[prost(bytes = "vec", tag = "4")]
+    pub tag: ::prost::alloc::vec::Vec<u8>,
+}
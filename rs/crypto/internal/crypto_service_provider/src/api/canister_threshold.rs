@@ -11,6 +11,8 @@ use ic_types::crypto::canister_threshold_sig::error::{
     IDkgVerifyDealingPrivateError, IDkgVerifyDealingPublicError, IDkgVerifyOpeningError,
     IDkgVerifyTranscriptError, ThresholdEcdsaCombineSigSharesError, ThresholdEcdsaSignShareError,
     ThresholdEcdsaVerifyCombinedSignatureError, ThresholdEcdsaVerifySigShareError,
+    ThresholdSchnorrCombineSigSharesError, ThresholdSchnorrSignShareError,
+    ThresholdSchnorrVerifyCombinedSignatureError, ThresholdSchnorrVerifySigShareError,
 };
 use ic_types::crypto::canister_threshold_sig::ExtendedDerivationPath;
 use ic_types::crypto::AlgorithmId;
@@ -228,3 +230,66 @@ pub trait CspThresholdEcdsaSigVerifier {
         algorithm_id: AlgorithmId,
     ) -> Result<(), ThresholdEcdsaVerifyCombinedSignatureError>;
 }
+
+/// Crypto service provider (CSP) client for threshold Schnorr signature share
+/// generation.
+///
+/// Signature shares and combined signatures are passed around in their
+/// serialized form, whose encoding depends on `algorithm_id`.
+pub trait CspThresholdSchnorrSigner {
+    /// Generate a serialized signature share.
+    fn schnorr_sign_share(
+        &self,
+        derivation_path: &ExtendedDerivationPath,
+        message: &[u8],
+        nonce: &Randomness,
+        key: &IDkgTranscriptInternal,
+        presig: &IDkgTranscriptInternal,
+        algorithm_id: AlgorithmId,
+    ) -> Result<Vec<u8>, ThresholdSchnorrSignShareError>;
+}
+
+/// Crypto service provider (CSP) client for threshold Schnorr signature
+/// verification.
+pub trait CspThresholdSchnorrSigVerifier {
+    /// Combine serialized signature shares into a serialized signature.
+    #[allow(clippy::too_many_arguments)]
+    fn schnorr_combine_sig_shares(
+        &self,
+        derivation_path: &ExtendedDerivationPath,
+        message: &[u8],
+        nonce: &Randomness,
+        key: &IDkgTranscriptInternal,
+        presig: &IDkgTranscriptInternal,
+        reconstruction_threshold: NumberOfNodes,
+        sig_shares: &BTreeMap<NodeIndex, Vec<u8>>,
+        algorithm_id: AlgorithmId,
+    ) -> Result<Vec<u8>, ThresholdSchnorrCombineSigSharesError>;
+
+    /// Verify a serialized signature share
+    #[allow(clippy::too_many_arguments)]
+    fn schnorr_verify_sig_share(
+        &self,
+        share: &[u8],
+        signer_index: NodeIndex,
+        derivation_path: &ExtendedDerivationPath,
+        message: &[u8],
+        nonce: &Randomness,
+        key: &IDkgTranscriptInternal,
+        presig: &IDkgTranscriptInternal,
+        algorithm_id: AlgorithmId,
+    ) -> Result<(), ThresholdSchnorrVerifySigShareError>;
+
+    /// Verify a serialized combined Schnorr signature with respect to a
+    /// particular presignature transcript
+    fn schnorr_verify_combined_signature(
+        &self,
+        signature: &[u8],
+        derivation_path: &ExtendedDerivationPath,
+        message: &[u8],
+        nonce: &Randomness,
+        key: &IDkgTranscriptInternal,
+        presig: &IDkgTranscriptInternal,
+        algorithm_id: AlgorithmId,
+    ) -> Result<(), ThresholdSchnorrVerifyCombinedSignatureError>;
+}
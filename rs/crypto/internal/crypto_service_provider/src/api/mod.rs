@@ -5,9 +5,11 @@ mod keygen;
 mod sign;
 mod threshold;
 mod tls;
+mod vetkd;
 
 pub use canister_threshold::{
     CspCreateMEGaKeyError, CspIDkgProtocol, CspThresholdEcdsaSigVerifier, CspThresholdEcdsaSigner,
+    CspThresholdSchnorrSigVerifier, CspThresholdSchnorrSigner,
 };
 pub use keygen::{
     CspKeyGenerator, CspPublicAndSecretKeyStoreChecker, CspSecretKeyStoreChecker,
@@ -18,3 +20,4 @@ pub use threshold::{
     threshold_sign_error::CspThresholdSignError, NiDkgCspClient, ThresholdSignatureCspClient,
 };
 pub use tls::CspTlsHandshakeSignerProvider;
+pub use vetkd::CspVetKdProtocol;
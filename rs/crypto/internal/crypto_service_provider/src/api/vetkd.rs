@@ -0,0 +1,22 @@
+//! CSP vetKD traits
+
+use crate::types::CspPublicCoefficients;
+use crate::vault::api::VetKdEncryptedKeyShareCreationVaultError;
+use ic_types::crypto::canister_threshold_sig::ExtendedDerivationPath;
+
+/// Crypto service provider (CSP) client for verifiably encrypted threshold
+/// key derivation (vetKD).
+pub trait CspVetKdProtocol {
+    /// Creates a serialized encrypted key share with the threshold secret key
+    /// share that belongs to the given public coefficients.
+    ///
+    /// The master public key is the combined public key of
+    /// `public_coefficients`.
+    fn create_encrypted_vetkd_key_share(
+        &self,
+        public_coefficients: CspPublicCoefficients,
+        transport_public_key: Vec<u8>,
+        derivation_path: ExtendedDerivationPath,
+        input: Vec<u8>,
+    ) -> Result<Vec<u8>, VetKdEncryptedKeyShareCreationVaultError>;
+}
@@ -9,20 +9,24 @@ mod tests;
 
 use crate::api::{
     CspCreateMEGaKeyError, CspIDkgProtocol, CspThresholdEcdsaSigVerifier, CspThresholdEcdsaSigner,
+    CspThresholdSchnorrSigVerifier, CspThresholdSchnorrSigner,
 };
 use crate::{Csp, KeyId};
 use ic_crypto_internal_threshold_sig_ecdsa::{
-    combine_sig_shares as tecdsa_combine_sig_shares, create_transcript as tecdsa_create_transcript,
-    publicly_verify_dealing as tecdsa_verify_dealing_public,
+    combine_bip340_signature_shares, combine_sig_shares as tecdsa_combine_sig_shares,
+    create_transcript as tecdsa_create_transcript,
+    publicly_verify_dealing as tecdsa_verify_dealing_public, verify_bip340_signature_share,
     verify_complaint as tecdsa_verify_complaint,
     verify_dealing_opening as tecdsa_verify_dealing_opening,
-    verify_signature_share as tecdsa_verify_signature_share,
+    verify_signature_share as tecdsa_verify_signature_share, verify_threshold_bip340_signature,
     verify_threshold_signature as tecdsa_verify_combined_signature,
     verify_transcript as tecdsa_verify_transcript, CommitmentOpening, DerivationPath,
     IDkgComplaintInternal, IDkgDealingInternal, IDkgTranscriptInternal,
-    IDkgTranscriptOperationInternal, MEGaPublicKey, ThresholdEcdsaCombinedSigInternal,
-    ThresholdEcdsaSigShareInternal, ThresholdEcdsaVerifySigShareInternalError,
-    ThresholdEcdsaVerifySignatureInternalError,
+    IDkgTranscriptOperationInternal, MEGaPublicKey, ThresholdBip340CombineSigSharesInternalError,
+    ThresholdBip340CombinedSignatureInternal, ThresholdBip340SignatureShareInternal,
+    ThresholdBip340VerifySigShareInternalError, ThresholdBip340VerifySignatureInternalError,
+    ThresholdEcdsaCombinedSigInternal, ThresholdEcdsaSigShareInternal,
+    ThresholdEcdsaVerifySigShareInternalError, ThresholdEcdsaVerifySignatureInternalError,
 };
 use ic_crypto_internal_types::scope::{ConstScope, Scope};
 use ic_logger::debug;
@@ -32,6 +36,8 @@ use ic_types::crypto::canister_threshold_sig::error::{
     IDkgVerifyDealingPrivateError, IDkgVerifyDealingPublicError, IDkgVerifyOpeningError,
     IDkgVerifyTranscriptError, ThresholdEcdsaCombineSigSharesError, ThresholdEcdsaSignShareError,
     ThresholdEcdsaVerifyCombinedSignatureError, ThresholdEcdsaVerifySigShareError,
+    ThresholdSchnorrCombineSigSharesError, ThresholdSchnorrSignShareError,
+    ThresholdSchnorrVerifyCombinedSignatureError, ThresholdSchnorrVerifySigShareError,
 };
 use ic_types::crypto::canister_threshold_sig::ExtendedDerivationPath;
 use ic_types::crypto::AlgorithmId;
@@ -442,6 +448,184 @@ impl CspThresholdEcdsaSigVerifier for Csp {
     }
 }
 
+/// Threshold Schnorr signature share generation client.
+///
+/// Please see the trait definition for full documentation.
+impl CspThresholdSchnorrSigner for Csp {
+    fn schnorr_sign_share(
+        &self,
+        derivation_path: &ExtendedDerivationPath,
+        message: &[u8],
+        nonce: &Randomness,
+        key: &IDkgTranscriptInternal,
+        presig: &IDkgTranscriptInternal,
+        algorithm_id: AlgorithmId,
+    ) -> Result<Vec<u8>, ThresholdSchnorrSignShareError> {
+        debug!(self.logger; crypto.method_name => "schnorr_sign_share");
+
+        self.csp_vault.create_schnorr_sig_share(
+            derivation_path,
+            message,
+            nonce,
+            key,
+            presig,
+            algorithm_id,
+        )
+    }
+}
+
+/// Threshold Schnorr signature verification client.
+///
+/// Please see the trait definition for full documentation.
+impl CspThresholdSchnorrSigVerifier for Csp {
+    fn schnorr_combine_sig_shares(
+        &self,
+        derivation_path: &ExtendedDerivationPath,
+        message: &[u8],
+        nonce: &Randomness,
+        key: &IDkgTranscriptInternal,
+        presig: &IDkgTranscriptInternal,
+        reconstruction_threshold: NumberOfNodes,
+        sig_shares: &BTreeMap<NodeIndex, Vec<u8>>,
+        algorithm_id: AlgorithmId,
+    ) -> Result<Vec<u8>, ThresholdSchnorrCombineSigSharesError> {
+        debug!(self.logger; crypto.method_name => "schnorr_combine_sig_shares");
+
+        if algorithm_id != AlgorithmId::ThresholdSchnorrBip340 {
+            return Err(ThresholdSchnorrCombineSigSharesError::UnsupportedAlgorithm);
+        }
+
+        let sig_shares = sig_shares
+            .iter()
+            .map(|(&index, share)| {
+                ThresholdBip340SignatureShareInternal::deserialize(share)
+                    .map(|share| (index, share))
+                    .map_err(
+                        |e| ThresholdSchnorrCombineSigSharesError::SerializationError {
+                            internal_error: format!("{:?}", e),
+                        },
+                    )
+            })
+            .collect::<Result<BTreeMap<_, _>, _>>()?;
+
+        let signature = combine_bip340_signature_shares(
+            &DerivationPath::from(derivation_path),
+            message,
+            *nonce,
+            key,
+            presig,
+            reconstruction_threshold,
+            &sig_shares,
+        )
+        .map_err(|e| match e {
+            ThresholdBip340CombineSigSharesInternalError::InsufficientShares => {
+                ThresholdSchnorrCombineSigSharesError::UnsatisfiedReconstructionThreshold {
+                    threshold: reconstruction_threshold.get(),
+                    share_count: sig_shares.len(),
+                }
+            }
+            other => ThresholdSchnorrCombineSigSharesError::InternalError {
+                internal_error: format!("{:?}", other),
+            },
+        })?;
+
+        signature.serialize().map_err(|e| {
+            ThresholdSchnorrCombineSigSharesError::SerializationError {
+                internal_error: format!("{:?}", e),
+            }
+        })
+    }
+
+    fn schnorr_verify_sig_share(
+        &self,
+        share: &[u8],
+        signer_index: NodeIndex,
+        derivation_path: &ExtendedDerivationPath,
+        message: &[u8],
+        nonce: &Randomness,
+        key: &IDkgTranscriptInternal,
+        presig: &IDkgTranscriptInternal,
+        algorithm_id: AlgorithmId,
+    ) -> Result<(), ThresholdSchnorrVerifySigShareError> {
+        debug!(self.logger; crypto.method_name => "schnorr_verify_sig_share");
+
+        if algorithm_id != AlgorithmId::ThresholdSchnorrBip340 {
+            return Err(ThresholdSchnorrVerifySigShareError::UnsupportedAlgorithm);
+        }
+
+        let share = ThresholdBip340SignatureShareInternal::deserialize(share).map_err(|e| {
+            ThresholdSchnorrVerifySigShareError::SerializationError {
+                internal_error: format!("{:?}", e),
+            }
+        })?;
+
+        verify_bip340_signature_share(
+            &share,
+            &DerivationPath::from(derivation_path),
+            message,
+            *nonce,
+            signer_index,
+            key,
+            presig,
+        )
+        .map_err(|e| match e {
+            ThresholdBip340VerifySigShareInternalError::InternalError(s) => {
+                ThresholdSchnorrVerifySigShareError::InternalError { internal_error: s }
+            }
+            ThresholdBip340VerifySigShareInternalError::InconsistentCommitments
+            | ThresholdBip340VerifySigShareInternalError::InvalidSignatureShare => {
+                ThresholdSchnorrVerifySigShareError::InvalidSignatureShare
+            }
+        })
+    }
+
+    fn schnorr_verify_combined_signature(
+        &self,
+        signature: &[u8],
+        derivation_path: &ExtendedDerivationPath,
+        message: &[u8],
+        nonce: &Randomness,
+        key: &IDkgTranscriptInternal,
+        presig: &IDkgTranscriptInternal,
+        algorithm_id: AlgorithmId,
+    ) -> Result<(), ThresholdSchnorrVerifyCombinedSignatureError> {
+        debug!(self.logger; crypto.method_name => "schnorr_verify_combined_signature");
+
+        if algorithm_id != AlgorithmId::ThresholdSchnorrBip340 {
+            return Err(ThresholdSchnorrVerifyCombinedSignatureError::UnsupportedAlgorithm);
+        }
+
+        let signature =
+            ThresholdBip340CombinedSignatureInternal::deserialize(signature).map_err(|e| {
+                ThresholdSchnorrVerifyCombinedSignatureError::SerializationError {
+                    internal_error: format!("{:?}", e),
+                }
+            })?;
+
+        verify_threshold_bip340_signature(
+            &signature,
+            &DerivationPath::from(derivation_path),
+            message,
+            *nonce,
+            presig,
+            key,
+        )
+        .map_err(|e| match e {
+            ThresholdBip340VerifySignatureInternalError::InvalidSignature => {
+                ThresholdSchnorrVerifyCombinedSignatureError::InvalidSignature
+            }
+            ThresholdBip340VerifySignatureInternalError::InternalError(s) => {
+                ThresholdSchnorrVerifyCombinedSignatureError::InternalError { internal_error: s }
+            }
+            ThresholdBip340VerifySignatureInternalError::InconsistentCommitments => {
+                ThresholdSchnorrVerifyCombinedSignatureError::InternalError {
+                    internal_error: "Wrong commitment types".to_string(),
+                }
+            }
+        })
+    }
+}
+
 fn key_id_from_mega_public_key_or_panic(public_key: &MEGaPublicKey) -> KeyId {
     KeyId::try_from(public_key).unwrap_or_else(|err| panic!("{}", err))
 }
@@ -17,17 +17,20 @@ pub mod threshold;
 pub mod tls;
 pub mod types;
 pub mod vault;
+mod vetkd;
 
+pub use crate::secret_key_store::encryption::rotate_secret_key_stores_kek;
 pub use crate::vault::api::TlsHandshakeCspVault;
 pub use crate::vault::local_csp_vault::LocalCspVault;
-pub use crate::vault::remote_csp_vault::run_csp_vault_server;
+pub use crate::vault::remote_csp_vault::{run_csp_vault_server, SubnetMembership};
 use crate::vault::remote_csp_vault::RemoteCspVault;
 
 use crate::api::{
     CspIDkgProtocol, CspKeyGenerator, CspPublicAndSecretKeyStoreChecker, CspSecretKeyStoreChecker,
     CspSigVerifier, CspSigner, CspThresholdEcdsaSigVerifier, CspThresholdEcdsaSigner,
-    CspTlsHandshakeSignerProvider, DkgDealingEncryptionKeyIdRetrievalError, NiDkgCspClient,
-    NodePublicKeyData, NodePublicKeyDataError, ThresholdSignatureCspClient,
+    CspThresholdSchnorrSigVerifier, CspThresholdSchnorrSigner, CspTlsHandshakeSignerProvider,
+    CspVetKdProtocol, DkgDealingEncryptionKeyIdRetrievalError, NiDkgCspClient, NodePublicKeyData,
+    NodePublicKeyDataError, ThresholdSignatureCspClient,
 };
 use crate::public_key_store::proto_pubkey_store::ProtoPublicKeyStore;
 use crate::secret_key_store::SecretKeyStore;
@@ -67,10 +70,13 @@ pub trait CryptoServiceProvider:
     + CspIDkgProtocol
     + CspThresholdEcdsaSigner
     + CspThresholdEcdsaSigVerifier
+    + CspThresholdSchnorrSigner
+    + CspThresholdSchnorrSigVerifier
     + CspPublicAndSecretKeyStoreChecker
     + CspSecretKeyStoreChecker
     + CspPublicAndSecretKeyStoreChecker
     + CspTlsHandshakeSignerProvider
+    + CspVetKdProtocol
     + NodePublicKeyData
 {
 }
@@ -83,11 +89,14 @@ impl<T> CryptoServiceProvider for T where
         + CspIDkgProtocol
         + CspThresholdEcdsaSigner
         + CspThresholdEcdsaSigVerifier
+        + CspThresholdSchnorrSigner
+        + CspThresholdSchnorrSigVerifier
         + NiDkgCspClient
         + CspPublicAndSecretKeyStoreChecker
         + CspSecretKeyStoreChecker
         + CspPublicAndSecretKeyStoreChecker
         + CspTlsHandshakeSignerProvider
+        + CspVetKdProtocol
         + NodePublicKeyData
 {
 }
@@ -197,14 +206,16 @@ impl Csp {
             logger,
             "Proceeding with an in-replica csp_vault, CryptoConfig: {:?}", config
         );
-        let secret_key_store = ProtoSecretKeyStore::open(
+        let secret_key_store = ProtoSecretKeyStore::open_with_encryption(
             &config.crypto_root,
             SKS_DATA_FILENAME,
+            &config.secret_key_store_encryption,
             Some(new_logger!(&logger)),
         );
-        let canister_key_store = ProtoSecretKeyStore::open(
+        let canister_key_store = ProtoSecretKeyStore::open_with_encryption(
             &config.crypto_root,
             CANISTER_SKS_DATA_FILENAME,
+            &config.secret_key_store_encryption,
             Some(new_logger!(&logger)),
         );
         let public_key_store = ProtoPublicKeyStore::open(
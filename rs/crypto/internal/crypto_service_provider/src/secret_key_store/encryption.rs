@@ -0,0 +1,295 @@
+//! Encryption at rest of secret key stores.
+//!
+//! An encrypted secret key store is stored on disk as the magic bytes
+//! [`ENCRYPTED_SKS_MAGIC`] followed by an `EncryptedSecretKeyStore`-protobuf,
+//! which contains the AES-256-GCM encryption of the plaintext
+//! `SecretKeyStore`-protobuf under a key-encryption key (KEK). The KEK is
+//! obtained from a [`KeyEncryptionKeyProvider`] and never stored alongside the
+//! secret key store.
+use crate::secret_key_store::proto_store::{pb, ProtoSecretKeyStore};
+use crate::secret_key_store::SecretKeyStorePersistenceError;
+use crate::{CANISTER_SKS_DATA_FILENAME, SKS_DATA_FILENAME};
+use ic_config::crypto::SecretKeyStoreEncryption;
+use ic_crypto_secrets_containers::SecretArray;
+use ic_crypto_sha::{Context, DomainSeparationContext, Sha256};
+use ic_logger::{new_logger, ReplicaLogger};
+use openssl::symm::{decrypt_aead, encrypt_aead, Cipher};
+use prost::Message;
+use std::fmt;
+use std::fs;
+use std::io::Read;
+use std::os::unix::net::UnixStream;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use zeroize::Zeroizing;
+
+#[cfg(test)]
+mod tests;
+
+/// Size of a key-encryption key in bytes.
+pub const KEK_SIZE: usize = 32;
+
+/// Magic bytes at the beginning of an encrypted secret key store file. A
+/// plaintext `SecretKeyStore`-protobuf never starts with these bytes.
+const ENCRYPTED_SKS_MAGIC: &[u8; 8] = b"ICSKSENC";
+const KEK_ID_DOMAIN: &str = "ic-crypto-secret-key-store-kek-id";
+const NONCE_SIZE: usize = 12;
+const TAG_SIZE: usize = 16;
+const KEK_PROVIDER_SOCKET_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// A key-encryption key (KEK) that secret key stores are encrypted with.
+#[derive(Clone)]
+pub struct KeyEncryptionKey {
+    key: SecretArray<KEK_SIZE>,
+}
+
+impl KeyEncryptionKey {
+    pub fn new(key: SecretArray<KEK_SIZE>) -> Self {
+        Self { key }
+    }
+
+    /// Returns the identifier of the KEK, which is derived from the key and
+    /// stored alongside the data encrypted with it.
+    pub fn id(&self) -> String {
+        let mut hash =
+            Sha256::new_with_context(&DomainSeparationContext::new(KEK_ID_DOMAIN.to_string()));
+        hash.write(self.key.expose_secret());
+        hex::encode(&hash.finish()[..8])
+    }
+}
+
+impl fmt::Debug for KeyEncryptionKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "KeyEncryptionKey {{ id: {} }}", self.id())
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SecretKeyStoreEncryptionError {
+    /// The KEK could not be obtained from its provider.
+    KekUnavailable(String),
+    /// The data was encrypted with a different KEK than the one provided.
+    KekMismatch {
+        data_kek_id: String,
+        provided_kek_id: String,
+    },
+    /// The encrypted data could not be parsed.
+    MalformedData(String),
+    /// The data could not be encrypted.
+    EncryptionFailed(String),
+    /// The data could not be decrypted, e.g., because it was modified.
+    DecryptionFailed(String),
+}
+
+impl fmt::Display for SecretKeyStoreEncryptionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+/// Provides the KEK that secret key stores are encrypted with.
+pub trait KeyEncryptionKeyProvider: Send + Sync {
+    /// Returns the current KEK.
+    fn current_kek(&self) -> Result<KeyEncryptionKey, SecretKeyStoreEncryptionError>;
+}
+
+/// Reads the KEK from a file that contains exactly [`KEK_SIZE`] bytes.
+///
+/// The file is read whenever the KEK is needed, so that it does not have to
+/// be kept in memory.
+pub struct FileKekProvider {
+    path: PathBuf,
+}
+
+impl FileKekProvider {
+    pub fn new(path: &Path) -> Self {
+        Self {
+            path: path.to_path_buf(),
+        }
+    }
+}
+
+impl KeyEncryptionKeyProvider for FileKekProvider {
+    fn current_kek(&self) -> Result<KeyEncryptionKey, SecretKeyStoreEncryptionError> {
+        let bytes = Zeroizing::new(fs::read(&self.path).map_err(|e| {
+            SecretKeyStoreEncryptionError::KekUnavailable(format!(
+                "failed to read KEK file {}: {}",
+                self.path.display(),
+                e
+            ))
+        })?);
+        let mut key: [u8; KEK_SIZE] = bytes.as_slice().try_into().map_err(|_| {
+            SecretKeyStoreEncryptionError::KekUnavailable(format!(
+                "KEK file {} contains {} bytes instead of {}",
+                self.path.display(),
+                bytes.len(),
+                KEK_SIZE
+            ))
+        })?;
+        Ok(KeyEncryptionKey::new(
+            SecretArray::new_and_zeroize_argument(&mut key),
+        ))
+    }
+}
+
+/// Obtains the KEK from a key-provider process listening at a Unix socket.
+///
+/// The provider is expected to send exactly [`KEK_SIZE`] bytes to every
+/// client that connects, and to restrict access to the socket to the
+/// process hosting the secret key store.
+pub struct UnixSocketKekProvider {
+    socket_path: PathBuf,
+}
+
+impl UnixSocketKekProvider {
+    pub fn new(socket_path: &Path) -> Self {
+        Self {
+            socket_path: socket_path.to_path_buf(),
+        }
+    }
+}
+
+impl KeyEncryptionKeyProvider for UnixSocketKekProvider {
+    fn current_kek(&self) -> Result<KeyEncryptionKey, SecretKeyStoreEncryptionError> {
+        let kek_unavailable = |e: std::io::Error| {
+            SecretKeyStoreEncryptionError::KekUnavailable(format!(
+                "failed to obtain KEK from key provider at {}: {}",
+                self.socket_path.display(),
+                e
+            ))
+        };
+        let mut stream = UnixStream::connect(&self.socket_path).map_err(kek_unavailable)?;
+        stream
+            .set_read_timeout(Some(KEK_PROVIDER_SOCKET_TIMEOUT))
+            .map_err(kek_unavailable)?;
+        let mut key = [0_u8; KEK_SIZE];
+        stream.read_exact(&mut key).map_err(kek_unavailable)?;
+        Ok(KeyEncryptionKey::new(
+            SecretArray::new_and_zeroize_argument(&mut key),
+        ))
+    }
+}
+
+/// Returns the KEK provider for the given encryption config, or `None` if
+/// secret key stores are stored in plaintext.
+pub fn kek_provider_from_config(
+    encryption: &SecretKeyStoreEncryption,
+) -> Option<Arc<dyn KeyEncryptionKeyProvider>> {
+    match encryption {
+        SecretKeyStoreEncryption::Plaintext => None,
+        SecretKeyStoreEncryption::KekFile(path) => Some(Arc::new(FileKekProvider::new(path))),
+        SecretKeyStoreEncryption::KekProviderSocket(socket_path) => {
+            Some(Arc::new(UnixSocketKekProvider::new(socket_path)))
+        }
+    }
+}
+
+/// Re-encrypts the node's secret key stores in `sks_dir`, which are currently
+/// stored according to `current_encryption`, with the KEK of
+/// `new_kek_provider`.
+///
+/// This must only be called while no other process uses the secret key
+/// stores, and the configured encryption must be updated to the new KEK
+/// afterwards.
+///
+/// # Panics
+/// If the secret key stores cannot be opened, see
+/// [`ProtoSecretKeyStore::open_with_encryption`].
+pub fn rotate_secret_key_stores_kek(
+    sks_dir: &Path,
+    current_encryption: &SecretKeyStoreEncryption,
+    new_kek_provider: Arc<dyn KeyEncryptionKeyProvider>,
+    logger: ReplicaLogger,
+) -> Result<(), SecretKeyStorePersistenceError> {
+    for file_name in [SKS_DATA_FILENAME, CANISTER_SKS_DATA_FILENAME] {
+        if !sks_dir.join(file_name).exists() {
+            continue;
+        }
+        let mut secret_key_store = ProtoSecretKeyStore::open_with_encryption(
+            sks_dir,
+            file_name,
+            current_encryption,
+            Some(new_logger!(&logger)),
+        );
+        secret_key_store.rotate_key_encryption_key(Arc::clone(&new_kek_provider))?;
+    }
+    Ok(())
+}
+
+/// Returns whether `data` read from disk is an encrypted secret key store.
+pub(crate) fn is_encrypted(data: &[u8]) -> bool {
+    data.starts_with(ENCRYPTED_SKS_MAGIC)
+}
+
+/// Encrypts the serialized secret key store `plaintext` with `kek`.
+///
+/// The `associated_data` is authenticated but not encrypted, and must be
+/// the same when decrypting.
+pub(crate) fn encrypt(
+    plaintext: &[u8],
+    associated_data: &[u8],
+    kek: &KeyEncryptionKey,
+) -> Result<Vec<u8>, SecretKeyStoreEncryptionError> {
+    let mut nonce = [0_u8; NONCE_SIZE];
+    openssl::rand::rand_bytes(&mut nonce).map_err(|e| {
+        SecretKeyStoreEncryptionError::EncryptionFailed(format!("failed to generate nonce: {}", e))
+    })?;
+    let mut tag = [0_u8; TAG_SIZE];
+    let ciphertext = encrypt_aead(
+        Cipher::aes_256_gcm(),
+        kek.key.expose_secret(),
+        Some(&nonce),
+        associated_data,
+        plaintext,
+        &mut tag,
+    )
+    .map_err(|e| SecretKeyStoreEncryptionError::EncryptionFailed(e.to_string()))?;
+    let encrypted_sks = pb::EncryptedSecretKeyStore {
+        kek_id: kek.id(),
+        nonce: nonce.to_vec(),
+        ciphertext,
+        tag: tag.to_vec(),
+    };
+    let mut data = ENCRYPTED_SKS_MAGIC.to_vec();
+    data.extend_from_slice(&encrypted_sks.encode_to_vec());
+    Ok(data)
+}
+
+/// Decrypts an encrypted secret key store `data` with `kek`, and returns the
+/// serialized plaintext secret key store.
+pub(crate) fn decrypt(
+    data: &[u8],
+    associated_data: &[u8],
+    kek: &KeyEncryptionKey,
+) -> Result<Zeroizing<Vec<u8>>, SecretKeyStoreEncryptionError> {
+    let encrypted_sks_proto = data.strip_prefix(ENCRYPTED_SKS_MAGIC).ok_or_else(|| {
+        SecretKeyStoreEncryptionError::MalformedData("missing magic bytes".to_string())
+    })?;
+    let encrypted_sks = pb::EncryptedSecretKeyStore::decode(encrypted_sks_proto)
+        .map_err(|e| SecretKeyStoreEncryptionError::MalformedData(e.to_string()))?;
+    let provided_kek_id = kek.id();
+    if encrypted_sks.kek_id != provided_kek_id {
+        return Err(SecretKeyStoreEncryptionError::KekMismatch {
+            data_kek_id: encrypted_sks.kek_id,
+            provided_kek_id,
+        });
+    }
+    if encrypted_sks.nonce.len() != NONCE_SIZE || encrypted_sks.tag.len() != TAG_SIZE {
+        return Err(SecretKeyStoreEncryptionError::MalformedData(format!(
+            "invalid nonce or tag length: {} and {}",
+            encrypted_sks.nonce.len(),
+            encrypted_sks.tag.len()
+        )));
+    }
+    decrypt_aead(
+        Cipher::aes_256_gcm(),
+        kek.key.expose_secret(),
+        Some(&encrypted_sks.nonce),
+        associated_data,
+        &encrypted_sks.ciphertext,
+        &encrypted_sks.tag,
+    )
+    .map(Zeroizing::new)
+    .map_err(|e| SecretKeyStoreEncryptionError::DecryptionFailed(e.to_string()))
+}
@@ -0,0 +1,205 @@
+use super::*;
+use crate::secret_key_store::test_utils::FixedKekProvider;
+use assert_matches::assert_matches;
+use std::io::Write;
+use std::os::unix::net::UnixListener;
+use tempfile::tempdir;
+
+const PLAINTEXT: &[u8] = b"serialized secret key store";
+const ASSOCIATED_DATA: &[u8] = b"sks_data.pb";
+
+fn kek(kek_bytes: [u8; KEK_SIZE]) -> KeyEncryptionKey {
+    FixedKekProvider::new(kek_bytes)
+        .current_kek()
+        .expect("failed to get KEK")
+}
+
+mod encryption {
+    use super::*;
+
+    #[test]
+    fn should_decrypt_encrypted_data() {
+        let kek = kek([1; KEK_SIZE]);
+
+        let data = encrypt(PLAINTEXT, ASSOCIATED_DATA, &kek).expect("failed to encrypt");
+
+        assert!(is_encrypted(&data));
+        assert_eq!(
+            decrypt(&data, ASSOCIATED_DATA, &kek)
+                .expect("failed to decrypt")
+                .as_slice(),
+            PLAINTEXT
+        );
+    }
+
+    #[test]
+    fn should_not_contain_plaintext_in_encrypted_data() {
+        let data =
+            encrypt(PLAINTEXT, ASSOCIATED_DATA, &kek([1; KEK_SIZE])).expect("failed to encrypt");
+
+        assert!(!data
+            .windows(PLAINTEXT.len())
+            .any(|window| window == PLAINTEXT));
+    }
+
+    #[test]
+    fn should_use_fresh_nonce_for_every_encryption() {
+        let kek = kek([1; KEK_SIZE]);
+
+        let data_1 = encrypt(PLAINTEXT, ASSOCIATED_DATA, &kek).expect("failed to encrypt");
+        let data_2 = encrypt(PLAINTEXT, ASSOCIATED_DATA, &kek).expect("failed to encrypt");
+
+        assert_ne!(data_1, data_2);
+    }
+
+    #[test]
+    fn should_fail_to_decrypt_with_different_kek() {
+        let data =
+            encrypt(PLAINTEXT, ASSOCIATED_DATA, &kek([1; KEK_SIZE])).expect("failed to encrypt");
+
+        assert_matches!(
+            decrypt(&data, ASSOCIATED_DATA, &kek([2; KEK_SIZE])),
+            Err(SecretKeyStoreEncryptionError::KekMismatch { data_kek_id, provided_kek_id })
+            if data_kek_id == kek([1; KEK_SIZE]).id() && provided_kek_id == kek([2; KEK_SIZE]).id()
+        );
+    }
+
+    #[test]
+    fn should_fail_to_decrypt_with_different_associated_data() {
+        let kek = kek([1; KEK_SIZE]);
+        let data = encrypt(PLAINTEXT, ASSOCIATED_DATA, &kek).expect("failed to encrypt");
+
+        assert_matches!(
+            decrypt(&data, b"canister_sks_data.pb", &kek),
+            Err(SecretKeyStoreEncryptionError::DecryptionFailed(_))
+        );
+    }
+
+    #[test]
+    fn should_fail_to_decrypt_modified_data() {
+        let kek = kek([1; KEK_SIZE]);
+        let data = encrypt(PLAINTEXT, ASSOCIATED_DATA, &kek).expect("failed to encrypt");
+        let encrypted_sks = pb::EncryptedSecretKeyStore::decode(&data[ENCRYPTED_SKS_MAGIC.len()..])
+            .expect("failed to decode");
+        let mut modified_ciphertext = encrypted_sks.ciphertext.clone();
+        modified_ciphertext[0] ^= 1;
+        let mut modified_data = ENCRYPTED_SKS_MAGIC.to_vec();
+        modified_data.extend_from_slice(
+            &pb::EncryptedSecretKeyStore {
+                ciphertext: modified_ciphertext,
+                ..encrypted_sks
+            }
+            .encode_to_vec(),
+        );
+
+        assert_matches!(
+            decrypt(&modified_data, ASSOCIATED_DATA, &kek),
+            Err(SecretKeyStoreEncryptionError::DecryptionFailed(_))
+        );
+    }
+
+    #[test]
+    fn should_fail_to_decrypt_plaintext_data() {
+        assert!(!is_encrypted(PLAINTEXT));
+        assert_matches!(
+            decrypt(PLAINTEXT, ASSOCIATED_DATA, &kek([1; KEK_SIZE])),
+            Err(SecretKeyStoreEncryptionError::MalformedData(_))
+        );
+    }
+}
+
+mod kek_providers {
+    use super::*;
+
+    #[test]
+    fn should_read_kek_from_file() {
+        let temp_dir = tempdir().expect("failed to create temp dir");
+        let kek_file = temp_dir.path().join("sks_kek");
+        fs::write(&kek_file, [3; KEK_SIZE]).expect("failed to write KEK file");
+
+        let kek_from_file = FileKekProvider::new(&kek_file)
+            .current_kek()
+            .expect("failed to read KEK");
+
+        assert_eq!(kek_from_file.id(), kek([3; KEK_SIZE]).id());
+    }
+
+    #[test]
+    fn should_fail_to_read_kek_from_file_with_wrong_length() {
+        let temp_dir = tempdir().expect("failed to create temp dir");
+        let kek_file = temp_dir.path().join("sks_kek");
+        fs::write(&kek_file, [3; KEK_SIZE - 1]).expect("failed to write KEK file");
+
+        assert_matches!(
+            FileKekProvider::new(&kek_file).current_kek(),
+            Err(SecretKeyStoreEncryptionError::KekUnavailable(msg))
+            if msg.contains("contains 31 bytes instead of 32")
+        );
+    }
+
+    #[test]
+    fn should_fail_to_read_kek_from_missing_file() {
+        let temp_dir = tempdir().expect("failed to create temp dir");
+
+        assert_matches!(
+            FileKekProvider::new(&temp_dir.path().join("missing")).current_kek(),
+            Err(SecretKeyStoreEncryptionError::KekUnavailable(_))
+        );
+    }
+
+    #[test]
+    fn should_obtain_kek_from_key_provider_socket() {
+        let temp_dir = tempdir().expect("failed to create temp dir");
+        let socket_path = temp_dir.path().join("kek_provider.sock");
+        let listener = UnixListener::bind(&socket_path).expect("failed to bind socket");
+        let key_provider = std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().expect("failed to accept connection");
+            stream
+                .write_all(&[4; KEK_SIZE])
+                .expect("failed to send KEK");
+        });
+
+        let kek_from_socket = UnixSocketKekProvider::new(&socket_path)
+            .current_kek()
+            .expect("failed to obtain KEK");
+
+        key_provider.join().expect("key provider panicked");
+        assert_eq!(kek_from_socket.id(), kek([4; KEK_SIZE]).id());
+    }
+
+    #[test]
+    fn should_fail_to_obtain_kek_if_key_provider_sends_too_few_bytes() {
+        let temp_dir = tempdir().expect("failed to create temp dir");
+        let socket_path = temp_dir.path().join("kek_provider.sock");
+        let listener = UnixListener::bind(&socket_path).expect("failed to bind socket");
+        let key_provider = std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().expect("failed to accept connection");
+            stream
+                .write_all(&[4; KEK_SIZE - 1])
+                .expect("failed to send KEK");
+        });
+
+        let result = UnixSocketKekProvider::new(&socket_path).current_kek();
+
+        key_provider.join().expect("key provider panicked");
+        assert_matches!(
+            result,
+            Err(SecretKeyStoreEncryptionError::KekUnavailable(_))
+        );
+    }
+
+    #[test]
+    fn should_not_return_provider_for_plaintext_config() {
+        assert!(kek_provider_from_config(&SecretKeyStoreEncryption::Plaintext).is_none());
+    }
+
+    #[test]
+    fn should_not_reveal_kek_in_debug_output() {
+        let kek = kek([5; KEK_SIZE]);
+
+        assert_eq!(
+            format!("{:?}", kek),
+            format!("KeyEncryptionKey {{ id: {} }}", kek.id())
+        );
+    }
+}
@@ -7,6 +7,7 @@ pub use scope::Scope;
 use std::fmt;
 
 // Implementations
+pub mod encryption;
 pub mod proto_store;
 #[cfg(test)]
 pub mod temp_secret_key_store;
@@ -2,12 +2,15 @@
 #![allow(clippy::unwrap_used)]
 use crate::canister_threshold::IDKG_MEGA_SCOPE;
 use crate::key_id::KeyId;
+use crate::secret_key_store::encryption::{
+    self, kek_provider_from_config, KeyEncryptionKeyProvider,
+};
 use crate::secret_key_store::{
     Scope, SecretKeyStore, SecretKeyStoreError, SecretKeyStorePersistenceError,
 };
 use crate::types::CspSecretKey;
 use hex::{FromHex, ToHex};
-use ic_config::crypto::CryptoConfig;
+use ic_config::crypto::{CryptoConfig, SecretKeyStoreEncryption};
 use ic_crypto_internal_threshold_sig_bls12381::ni_dkg::groth20_bls12_381::types::convert_keyset_to_keyset_with_pop;
 use ic_crypto_internal_threshold_sig_bls12381::ni_dkg::types::CspFsEncryptionKeySet;
 use ic_logger::{info, replica_logger::no_op_logger, ReplicaLogger};
@@ -16,10 +19,11 @@ use prost::Message;
 use std::borrow::{Borrow, BorrowMut};
 use std::collections::HashMap;
 use std::fs;
-use std::io::ErrorKind;
+use std::io::{ErrorKind, Write};
 use std::path::{Path, PathBuf};
 use std::str::FromStr;
 use std::sync::Arc;
+use zeroize::Zeroizing;
 
 #[cfg(test)]
 mod tests;
@@ -40,40 +44,137 @@ type SecretKeys = HashMap<KeyId, (CspSecretKey, Option<Scope>)>;
 
 /// A secret key store that persists data to the filesystem, using protobufs for
 /// serialization
+///
+/// If a key-encryption key (KEK) provider is set, the data is encrypted at
+/// rest with the provider's current KEK, see [`encryption`].
 pub struct ProtoSecretKeyStore {
     proto_file: PathBuf,
     keys: Arc<RwLock<SecretKeys>>,
+    kek_provider: Option<Arc<dyn KeyEncryptionKeyProvider>>,
     logger: ReplicaLogger,
 }
 
 impl ProtoSecretKeyStore {
-    /// Creates a database instance.
+    /// Creates a database instance that stores the data in plaintext.
+    ///
+    /// # Panics
+    /// If the data on disk is encrypted.
     pub fn open(dir: &Path, file_name: &str, logger: Option<ReplicaLogger>) -> Self {
+        Self::open_with_kek_provider(dir, file_name, None, logger)
+    }
+
+    /// Creates a database instance that stores the data according to the
+    /// given `encryption` setting.
+    ///
+    /// See [`ProtoSecretKeyStore::open_with_kek_provider`].
+    pub fn open_with_encryption(
+        dir: &Path,
+        file_name: &str,
+        encryption: &SecretKeyStoreEncryption,
+        logger: Option<ReplicaLogger>,
+    ) -> Self {
+        Self::open_with_kek_provider(dir, file_name, kek_provider_from_config(encryption), logger)
+    }
+
+    /// Creates a database instance that encrypts the data at rest with the
+    /// KEK of `kek_provider`, or stores it in plaintext if `kek_provider` is
+    /// `None`.
+    ///
+    /// If a `kek_provider` is given and the data on disk is in plaintext, it
+    /// is encrypted and written back to disk immediately.
+    ///
+    /// # Panics
+    /// * If the data on disk is encrypted and no `kek_provider` is given.
+    /// * If the data on disk cannot be decrypted with the current KEK.
+    /// * If plaintext data on disk cannot be migrated to encrypted data.
+    pub fn open_with_kek_provider(
+        dir: &Path,
+        file_name: &str,
+        kek_provider: Option<Arc<dyn KeyEncryptionKeyProvider>>,
+        logger: Option<ReplicaLogger>,
+    ) -> Self {
         CryptoConfig::check_dir_has_required_permissions(dir)
             .expect("wrong crypto root permissions");
         let proto_file = dir.join(file_name);
-        let secret_keys = match Self::read_sks_data_from_disk(&proto_file) {
-            Some(sks_proto) => sks_proto,
+        let logger = logger.unwrap_or_else(no_op_logger);
+        let secret_keys = match Self::read_sks_data_from_disk(&proto_file, kek_provider.as_deref())
+        {
+            Some((secret_keys, was_encrypted)) => {
+                if let (Some(kek_provider), false) = (kek_provider.as_deref(), was_encrypted) {
+                    info!(
+                        logger,
+                        "Encrypting plaintext secret key store {}",
+                        proto_file.display()
+                    );
+                    Self::write_secret_keys_to_disk(&proto_file, &secret_keys, Some(kek_provider))
+                        .unwrap_or_else(|e| panic!("Error encrypting plaintext SKS data: {}", e));
+                }
+                secret_keys
+            }
             None => SecretKeys::new(),
         };
         ProtoSecretKeyStore {
             proto_file,
             keys: Arc::new(RwLock::new(secret_keys)),
-            logger: logger.unwrap_or_else(no_op_logger),
+            kek_provider,
+            logger,
         }
     }
 
+    /// Re-encrypts the data on disk with the current KEK of `new_kek_provider`
+    /// and uses the latter for all subsequent writes.
+    pub fn rotate_key_encryption_key(
+        &mut self,
+        new_kek_provider: Arc<dyn KeyEncryptionKeyProvider>,
+    ) -> Result<(), SecretKeyStorePersistenceError> {
+        with_write_lock(&self.keys, |keys| {
+            ProtoSecretKeyStore::write_secret_keys_to_disk(
+                &self.proto_file,
+                keys,
+                Some(new_kek_provider.as_ref()),
+            )
+        })?;
+        info!(
+            self.logger,
+            "Re-encrypted secret key store {}",
+            self.proto_file.display()
+        );
+        self.kek_provider = Some(new_kek_provider);
+        Ok(())
+    }
+
     /// Returns the path to the protobuf file storing the keys.
     pub fn proto_file_path(&self) -> &Path {
         self.proto_file.as_path()
     }
 
-    fn read_sks_data_from_disk(sks_data_file: &Path) -> Option<SecretKeys> {
+    /// Reads the secret keys from disk, and returns them together with
+    /// whether they were encrypted on disk.
+    fn read_sks_data_from_disk(
+        sks_data_file: &Path,
+        kek_provider: Option<&dyn KeyEncryptionKeyProvider>,
+    ) -> Option<(SecretKeys, bool)> {
         match fs::read(sks_data_file) {
             Ok(data) => {
-                let sks_pb = pb::SecretKeyStore::decode(&*data).expect("error parsing SKS data");
+                let data = Zeroizing::new(data);
+                let was_encrypted = encryption::is_encrypted(&data);
+                let sks_pb = if was_encrypted {
+                    let kek_provider = kek_provider.unwrap_or_else(|| {
+                        panic!("SKS data is encrypted, but no key-encryption key is configured")
+                    });
+                    let kek = kek_provider
+                        .current_kek()
+                        .unwrap_or_else(|e| panic!("Error obtaining key-encryption key: {}", e));
+                    let plaintext =
+                        encryption::decrypt(&data, &associated_data(sks_data_file), &kek)
+                            .unwrap_or_else(|e| panic!("Error decrypting SKS data: {}", e));
+                    pb::SecretKeyStore::decode(plaintext.as_slice())
+                } else {
+                    pb::SecretKeyStore::decode(data.as_slice())
+                }
+                .expect("error parsing SKS data");
                 let keys = ProtoSecretKeyStore::migrate_to_current_version(sks_pb);
-                Some(keys)
+                Some((keys, was_encrypted))
             }
             Err(err) => {
                 if err.kind() == ErrorKind::NotFound {
@@ -203,17 +304,51 @@ impl ProtoSecretKeyStore {
     fn write_secret_keys_to_disk(
         sks_data_file: &Path,
         secret_keys: &SecretKeys,
+        kek_provider: Option<&dyn KeyEncryptionKeyProvider>,
     ) -> Result<(), SecretKeyStorePersistenceError> {
         let sks_proto = ProtoSecretKeyStore::secret_keys_to_sks_proto(secret_keys)?;
-        ic_utils::fs::write_protobuf_using_tmp_file(sks_data_file, &sks_proto).map_err(|e| {
+        let kek_provider = match kek_provider {
+            None => {
+                return ic_utils::fs::write_protobuf_using_tmp_file(sks_data_file, &sks_proto)
+                    .map_err(|e| {
+                        SecretKeyStorePersistenceError::IoError(format!(
+                            "Secret key store internal error writing protobuf using tmp file: {}",
+                            e
+                        ))
+                    })
+            }
+            Some(kek_provider) => kek_provider,
+        };
+        let encryption_error = |e: encryption::SecretKeyStoreEncryptionError| {
             SecretKeyStorePersistenceError::IoError(format!(
-                "Secret key store internal error writing protobuf using tmp file: {}",
+                "Secret key store internal error encrypting data: {}",
                 e
             ))
-        })
+        };
+        let kek = kek_provider.current_kek().map_err(encryption_error)?;
+        let plaintext = Zeroizing::new(sks_proto.encode_to_vec());
+        let data = encryption::encrypt(&plaintext, &associated_data(sks_data_file), &kek)
+            .map_err(encryption_error)?;
+        ic_utils::fs::write_using_tmp_file(sks_data_file, |writer| writer.write_all(&data)).map_err(
+            |e| {
+                SecretKeyStorePersistenceError::IoError(format!(
+                    "Secret key store internal error writing encrypted data using tmp file: {}",
+                    e
+                ))
+            },
+        )
     }
 }
 
+/// The associated data used when encrypting the secret key store, which
+/// binds the ciphertext to the name of the file it is stored in.
+fn associated_data(sks_data_file: &Path) -> Vec<u8> {
+    sks_data_file
+        .file_name()
+        .map(|file_name| file_name.to_string_lossy().as_bytes().to_vec())
+        .unwrap_or_default()
+}
+
 impl SecretKeyStore for ProtoSecretKeyStore {
     fn insert(
         &mut self,
@@ -226,7 +361,11 @@ impl SecretKeyStore for ProtoSecretKeyStore {
                 Some(_) => Ok(false),
                 None => {
                     keys.insert(id, (key, scope));
-                    ProtoSecretKeyStore::write_secret_keys_to_disk(&self.proto_file, keys)?;
+                    ProtoSecretKeyStore::write_secret_keys_to_disk(
+                        &self.proto_file,
+                        keys,
+                        self.kek_provider.as_deref(),
+                    )?;
                     Ok(true)
                 }
             });
@@ -251,7 +390,11 @@ impl SecretKeyStore for ProtoSecretKeyStore {
         with_write_lock(&self.keys, |keys| match keys.get(id) {
             Some(_) => {
                 keys.remove(id);
-                ProtoSecretKeyStore::write_secret_keys_to_disk(&self.proto_file, keys)?;
+                ProtoSecretKeyStore::write_secret_keys_to_disk(
+                    &self.proto_file,
+                    keys,
+                    self.kek_provider.as_deref(),
+                )?;
                 Ok(true)
             }
             None => Ok(false),
@@ -277,7 +420,11 @@ impl SecretKeyStore for ProtoSecretKeyStore {
                 }
             }
             if keys.len() < orig_keys_count {
-                ProtoSecretKeyStore::write_secret_keys_to_disk(&self.proto_file, keys)?;
+                ProtoSecretKeyStore::write_secret_keys_to_disk(
+                    &self.proto_file,
+                    keys,
+                    self.kek_provider.as_deref(),
+                )?;
             }
             Ok(())
         })
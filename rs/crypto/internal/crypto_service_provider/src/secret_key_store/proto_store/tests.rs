@@ -345,6 +345,124 @@ fn should_fail_to_read_from_secret_key_store_with_no_read_permissions() {
     );
 }
 
+mod encryption_at_rest {
+    use super::*;
+    use crate::secret_key_store::encryption::is_encrypted;
+    use crate::secret_key_store::test_utils::FixedKekProvider;
+
+    const SKS_FILE_NAME: &str = "sks_data.pb";
+
+    fn kek_provider(kek_bytes: [u8; 32]) -> Arc<dyn KeyEncryptionKeyProvider> {
+        Arc::new(FixedKekProvider::new(kek_bytes))
+    }
+
+    fn open_encrypted(dir: &Path, kek_bytes: [u8; 32]) -> ProtoSecretKeyStore {
+        ProtoSecretKeyStore::open_with_kek_provider(
+            dir,
+            SKS_FILE_NAME,
+            Some(kek_provider(kek_bytes)),
+            None,
+        )
+    }
+
+    #[test]
+    fn should_store_encrypted_keys_that_can_be_read_after_reopening() {
+        let temp_dir = mk_temp_dir_with_permissions(0o700);
+        let (key_id, secret_key) = (make_key_id(42), make_secret_key(42));
+        let mut secret_key_store = open_encrypted(temp_dir.path(), [1; 32]);
+        secret_key_store
+            .insert(key_id, secret_key.clone(), None)
+            .expect("failed to insert key");
+
+        let data = fs::read(secret_key_store.proto_file_path()).expect("failed to read SKS");
+        assert!(is_encrypted(&data));
+
+        let reopened_secret_key_store = open_encrypted(temp_dir.path(), [1; 32]);
+        assert_eq!(reopened_secret_key_store.get(&key_id), Some(secret_key));
+    }
+
+    #[test]
+    fn should_encrypt_existing_plaintext_secret_key_store_when_opening() {
+        let (temp_dir, plaintext_secret_key_store) =
+            open_existing_secret_key_store_in_temp_dir(&SecretKeyStoreVersion::V3);
+        let file_name = existing_secret_key_store_file_name(&SecretKeyStoreVersion::V3);
+
+        let encrypted_secret_key_store = ProtoSecretKeyStore::open_with_kek_provider(
+            temp_dir.path(),
+            &file_name,
+            Some(kek_provider([1; 32])),
+            None,
+        );
+
+        let data =
+            fs::read(encrypted_secret_key_store.proto_file_path()).expect("failed to read SKS");
+        assert!(is_encrypted(&data));
+        assert_eq!(
+            with_read_lock(&encrypted_secret_key_store.keys, |keys| Some(keys.clone())),
+            with_read_lock(&plaintext_secret_key_store.keys, |keys| Some(keys.clone()))
+        );
+        let test_vector = TestVector::multi_bls();
+        assert_eq!(
+            encrypted_secret_key_store.get(&test_vector.key_id),
+            Some(test_vector.secret_key)
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "SKS data is encrypted, but no key-encryption key is configured")]
+    fn should_panic_when_opening_encrypted_secret_key_store_without_kek() {
+        let temp_dir = mk_temp_dir_with_permissions(0o700);
+        let mut secret_key_store = open_encrypted(temp_dir.path(), [1; 32]);
+        write_secret_key_store_to_disk(&mut secret_key_store);
+
+        ProtoSecretKeyStore::open(temp_dir.path(), SKS_FILE_NAME, None);
+    }
+
+    #[test]
+    #[should_panic(expected = "Error decrypting SKS data: KekMismatch")]
+    fn should_panic_when_opening_encrypted_secret_key_store_with_wrong_kek() {
+        let temp_dir = mk_temp_dir_with_permissions(0o700);
+        let mut secret_key_store = open_encrypted(temp_dir.path(), [1; 32]);
+        write_secret_key_store_to_disk(&mut secret_key_store);
+
+        open_encrypted(temp_dir.path(), [2; 32]);
+    }
+
+    #[test]
+    fn should_read_keys_with_new_kek_after_rotation() {
+        let temp_dir = mk_temp_dir_with_permissions(0o700);
+        let (key_id, secret_key) = (make_key_id(42), make_secret_key(42));
+        let mut secret_key_store = open_encrypted(temp_dir.path(), [1; 32]);
+        secret_key_store
+            .insert(key_id, secret_key.clone(), None)
+            .expect("failed to insert key");
+
+        secret_key_store
+            .rotate_key_encryption_key(kek_provider([2; 32]))
+            .expect("failed to rotate KEK");
+
+        let reopened_secret_key_store = open_encrypted(temp_dir.path(), [2; 32]);
+        assert_eq!(reopened_secret_key_store.get(&key_id), Some(secret_key));
+    }
+
+    #[test]
+    fn should_write_with_new_kek_after_rotation() {
+        let temp_dir = mk_temp_dir_with_permissions(0o700);
+        let mut secret_key_store = open_encrypted(temp_dir.path(), [1; 32]);
+        secret_key_store
+            .rotate_key_encryption_key(kek_provider([2; 32]))
+            .expect("failed to rotate KEK");
+        let (key_id, secret_key) = (make_key_id(42), make_secret_key(42));
+
+        secret_key_store
+            .insert(key_id, secret_key.clone(), None)
+            .expect("failed to insert key");
+
+        let reopened_secret_key_store = open_encrypted(temp_dir.path(), [2; 32]);
+        assert_eq!(reopened_secret_key_store.get(&key_id), Some(secret_key));
+    }
+}
+
 fn copy_file_to_dir(source_file: &Path, target_dir: &Path) {
     let filename = source_file.file_name().expect("expected file name");
     let target_file = target_dir.join(filename);
@@ -4,6 +4,9 @@
 // Ok, let's duplicate the mock and see what happens.
 
 use crate::key_id::KeyId;
+use crate::secret_key_store::encryption::{
+    KeyEncryptionKey, KeyEncryptionKeyProvider, SecretKeyStoreEncryptionError, KEK_SIZE,
+};
 use crate::types::CspSecretKey;
 use ic_crypto_internal_basic_sig_ed25519::types as ed25519_types;
 use ic_crypto_secrets_containers::SecretArray;
@@ -19,3 +22,22 @@ pub fn make_secret_key(seed: u64) -> CspSecretKey {
         SecretArray::new_and_dont_zeroize_argument(&ChaCha20Rng::seed_from_u64(seed).gen()),
     ))
 }
+
+/// A key-encryption key provider that always provides the same KEK.
+pub struct FixedKekProvider {
+    kek: KeyEncryptionKey,
+}
+
+impl FixedKekProvider {
+    pub fn new(kek_bytes: [u8; KEK_SIZE]) -> Self {
+        Self {
+            kek: KeyEncryptionKey::new(SecretArray::new_and_dont_zeroize_argument(&kek_bytes)),
+        }
+    }
+}
+
+impl KeyEncryptionKeyProvider for FixedKekProvider {
+    fn current_kek(&self) -> Result<KeyEncryptionKey, SecretKeyStoreEncryptionError> {
+        Ok(self.kek.clone())
+    }
+}
@@ -20,7 +20,7 @@ use ic_crypto_node_key_validation::ValidNodePublicKeys;
 use ic_crypto_tls_interfaces::TlsPublicKeyCert;
 use ic_types::crypto::canister_threshold_sig::error::{
     IDkgCreateDealingError, IDkgLoadTranscriptError, IDkgOpenTranscriptError, IDkgRetainKeysError,
-    IDkgVerifyDealingPrivateError, ThresholdEcdsaSignShareError,
+    IDkgVerifyDealingPrivateError, ThresholdEcdsaSignShareError, ThresholdSchnorrSignShareError,
 };
 use ic_types::crypto::canister_threshold_sig::ExtendedDerivationPath;
 use ic_types::crypto::{AlgorithmId, CryptoError, CurrentNodePublicKeys};
@@ -373,6 +373,8 @@ pub trait CspVault:
     + NiDkgCspVault
     + IDkgProtocolCspVault
     + ThresholdEcdsaSignerCspVault
+    + ThresholdSchnorrSignerCspVault
+    + VetKdCspVault
     + SecretKeyStoreCspVault
     + TlsHandshakeCspVault
     + PublicRandomSeedGenerator
@@ -390,6 +392,8 @@ impl<T> CspVault for T where
         + NiDkgCspVault
         + IDkgProtocolCspVault
         + ThresholdEcdsaSignerCspVault
+        + ThresholdSchnorrSignerCspVault
+        + VetKdCspVault
         + SecretKeyStoreCspVault
         + TlsHandshakeCspVault
         + PublicRandomSeedGenerator
@@ -854,6 +858,55 @@ pub trait ThresholdEcdsaSignerCspVault {
     ) -> Result<ThresholdEcdsaSigShareInternal, ThresholdEcdsaSignShareError>;
 }
 
+/// Operations of `CspVault` related to threshold Schnorr signatures (cf.
+/// `CspThresholdSchnorrSigner`).
+pub trait ThresholdSchnorrSignerCspVault {
+    /// Generate a serialized signature share.
+    fn create_schnorr_sig_share(
+        &self,
+        derivation_path: &ExtendedDerivationPath,
+        message: &[u8],
+        nonce: &Randomness,
+        key: &IDkgTranscriptInternal,
+        presig: &IDkgTranscriptInternal,
+        algorithm_id: AlgorithmId,
+    ) -> Result<Vec<u8>, ThresholdSchnorrSignShareError>;
+}
+
+/// Errors returned by [`VetKdCspVault::create_encrypted_vetkd_key_share`].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum VetKdEncryptedKeyShareCreationVaultError {
+    /// The secret key share is missing or has the wrong type.
+    SecretKeyMissingOrWrongType(String),
+    /// The master public key could not be deserialized.
+    InvalidArgumentMasterPublicKey,
+    /// The transport public key could not be deserialized.
+    InvalidArgumentTransportPublicKey,
+    /// Internal error, e.g., an RPC error.
+    TransientInternalError(String),
+}
+
+/// Operations of `CspVault` related to verifiably encrypted threshold key
+/// derivation (vetKD).
+pub trait VetKdCspVault {
+    /// Creates an encrypted vetKD key share.
+    ///
+    /// The share is created with the threshold secret key share identified
+    /// by `key_id`, whose public coefficients have the combined public key
+    /// `master_public_key`. The key share is encrypted under
+    /// `transport_public_key`.
+    ///
+    /// Returns the serialized encrypted key share.
+    fn create_encrypted_vetkd_key_share(
+        &self,
+        key_id: KeyId,
+        master_public_key: Vec<u8>,
+        transport_public_key: Vec<u8>,
+        derivation_path: ExtendedDerivationPath,
+        input: Vec<u8>,
+    ) -> Result<Vec<u8>, VetKdEncryptedKeyShareCreationVaultError>;
+}
+
 /// An error returned by failing to generate a public seed from [`CspVault`].
 #[derive(Serialize, Deserialize, Debug)]
 pub enum PublicRandomSeedGeneratorError {
@@ -57,7 +57,7 @@ impl<R: Rng + CryptoRng, S: SecretKeyStore, C: SecretKeyStore, P: PublicKeyStore
 impl<R: Rng + CryptoRng, S: SecretKeyStore, C: SecretKeyStore, P: PublicKeyStore>
     LocalCspVault<R, S, C, P>
 {
-    fn combined_commitment_opening_from_sks(
+    pub(super) fn combined_commitment_opening_from_sks(
         &self,
         combined_commitment: &CombinedCommitment,
     ) -> Result<CommitmentOpening, ThresholdEcdsaSignShareError> {
@@ -0,0 +1,96 @@
+use crate::public_key_store::PublicKeyStore;
+use crate::secret_key_store::SecretKeyStore;
+use crate::vault::api::ThresholdSchnorrSignerCspVault;
+use crate::vault::local_csp_vault::LocalCspVault;
+use ic_crypto_internal_logmon::metrics::{MetricsDomain, MetricsResult, MetricsScope};
+use ic_crypto_internal_threshold_sig_ecdsa::{sign_share_bip340, IDkgTranscriptInternal};
+use ic_types::crypto::canister_threshold_sig::error::{
+    ThresholdEcdsaSignShareError, ThresholdSchnorrSignShareError,
+};
+use ic_types::crypto::canister_threshold_sig::ExtendedDerivationPath;
+use ic_types::crypto::AlgorithmId;
+use ic_types::Randomness;
+use rand::{CryptoRng, Rng};
+
+impl<R: Rng + CryptoRng, S: SecretKeyStore, C: SecretKeyStore, P: PublicKeyStore>
+    ThresholdSchnorrSignerCspVault for LocalCspVault<R, S, C, P>
+{
+    fn create_schnorr_sig_share(
+        &self,
+        derivation_path: &ExtendedDerivationPath,
+        message: &[u8],
+        nonce: &Randomness,
+        key: &IDkgTranscriptInternal,
+        presig: &IDkgTranscriptInternal,
+        algorithm_id: AlgorithmId,
+    ) -> Result<Vec<u8>, ThresholdSchnorrSignShareError> {
+        let start_time = self.metrics.now();
+        let result = self.create_schnorr_sig_share_internal(
+            derivation_path,
+            message,
+            nonce,
+            key,
+            presig,
+            algorithm_id,
+        );
+        self.metrics.observe_duration_seconds(
+            MetricsDomain::ThresholdSchnorr,
+            MetricsScope::Local,
+            "create_schnorr_sig_share",
+            MetricsResult::from(&result),
+            start_time,
+        );
+        result
+    }
+}
+
+impl<R: Rng + CryptoRng, S: SecretKeyStore, C: SecretKeyStore, P: PublicKeyStore>
+    LocalCspVault<R, S, C, P>
+{
+    fn create_schnorr_sig_share_internal(
+        &self,
+        derivation_path: &ExtendedDerivationPath,
+        message: &[u8],
+        nonce: &Randomness,
+        key: &IDkgTranscriptInternal,
+        presig: &IDkgTranscriptInternal,
+        algorithm_id: AlgorithmId,
+    ) -> Result<Vec<u8>, ThresholdSchnorrSignShareError> {
+        match algorithm_id {
+            AlgorithmId::ThresholdSchnorrBip340 => {
+                let key_opening = self
+                    .combined_commitment_opening_from_sks(&key.combined_commitment)
+                    .map_err(schnorr_sign_share_error)?;
+                let presig_opening = self
+                    .combined_commitment_opening_from_sks(&presig.combined_commitment)
+                    .map_err(schnorr_sign_share_error)?;
+
+                let share = sign_share_bip340(
+                    &derivation_path.into(),
+                    message,
+                    *nonce,
+                    key,
+                    &key_opening,
+                    presig,
+                    &presig_opening,
+                )
+                .map_err(|e| ThresholdSchnorrSignShareError::InternalError {
+                    internal_error: format!("{:?}", e),
+                })?;
+                Ok(share.serialize())
+            }
+            _ => Err(ThresholdSchnorrSignShareError::UnsupportedAlgorithm),
+        }
+    }
+}
+
+fn schnorr_sign_share_error(error: ThresholdEcdsaSignShareError) -> ThresholdSchnorrSignShareError {
+    match error {
+        ThresholdEcdsaSignShareError::SecretSharesNotFound { commitment_string } => {
+            ThresholdSchnorrSignShareError::SecretSharesNotFound { commitment_string }
+        }
+        other => ThresholdSchnorrSignShareError::InternalError {
+            internal_error: format!("{:?}", other),
+        },
+    }
+}
@@ -0,0 +1,108 @@
+use crate::key_id::KeyId;
+use crate::public_key_store::PublicKeyStore;
+use crate::secret_key_store::SecretKeyStore;
+use crate::types::CspSecretKey;
+use crate::vault::api::{VetKdCspVault, VetKdEncryptedKeyShareCreationVaultError};
+use crate::vault::local_csp_vault::LocalCspVault;
+use ic_crypto_internal_bls12_381_type::{G2Affine, Scalar};
+use ic_crypto_internal_logmon::metrics::{MetricsDomain, MetricsResult, MetricsScope};
+use ic_crypto_internal_threshold_sig_bls12381::vetkd::{
+    DerivationPath, EncryptedKeyShare, TransportPublicKey,
+};
+use ic_types::crypto::canister_threshold_sig::ExtendedDerivationPath;
+use rand::{CryptoRng, Rng};
+
+#[cfg(test)]
+mod tests;
+
+impl<R: Rng + CryptoRng, S: SecretKeyStore, C: SecretKeyStore, P: PublicKeyStore> VetKdCspVault
+    for LocalCspVault<R, S, C, P>
+{
+    fn create_encrypted_vetkd_key_share(
+        &self,
+        key_id: KeyId,
+        master_public_key: Vec<u8>,
+        transport_public_key: Vec<u8>,
+        derivation_path: ExtendedDerivationPath,
+        input: Vec<u8>,
+    ) -> Result<Vec<u8>, VetKdEncryptedKeyShareCreationVaultError> {
+        let start_time = self.metrics.now();
+        let result = self.create_encrypted_vetkd_key_share_internal(
+            key_id,
+            master_public_key,
+            transport_public_key,
+            derivation_path,
+            input,
+        );
+        self.metrics.observe_duration_seconds(
+            MetricsDomain::VetKd,
+            MetricsScope::Local,
+            "create_encrypted_vetkd_key_share",
+            MetricsResult::from(&result),
+            start_time,
+        );
+        result
+    }
+}
+
+impl<R: Rng + CryptoRng, S: SecretKeyStore, C: SecretKeyStore, P: PublicKeyStore>
+    LocalCspVault<R, S, C, P>
+{
+    fn create_encrypted_vetkd_key_share_internal(
+        &self,
+        key_id: KeyId,
+        master_public_key: Vec<u8>,
+        transport_public_key: Vec<u8>,
+        derivation_path: ExtendedDerivationPath,
+        input: Vec<u8>,
+    ) -> Result<Vec<u8>, VetKdEncryptedKeyShareCreationVaultError> {
+        let master_public_key = G2Affine::deserialize(&master_public_key).map_err(|_| {
+            VetKdEncryptedKeyShareCreationVaultError::InvalidArgumentMasterPublicKey
+        })?;
+        let transport_public_key =
+            TransportPublicKey::deserialize(&transport_public_key).map_err(|_| {
+                VetKdEncryptedKeyShareCreationVaultError::InvalidArgumentTransportPublicKey
+            })?;
+
+        let secret_key_bytes =
+            match self.sks_read_lock().get(&key_id) {
+                Some(CspSecretKey::ThresBls12_381(secret_key_bytes)) => secret_key_bytes,
+                Some(_) => {
+                    return Err(
+                        VetKdEncryptedKeyShareCreationVaultError::SecretKeyMissingOrWrongType(
+                            format!("Secret key with key id {} has the wrong type", key_id),
+                        ),
+                    )
+                }
+                None => {
+                    return Err(
+                        VetKdEncryptedKeyShareCreationVaultError::SecretKeyMissingOrWrongType(
+                            format!("Missing secret key with key id {}", key_id),
+                        ),
+                    )
+                }
+            };
+        let secret_key_share = Scalar::try_from(&secret_key_bytes).map_err(|e| {
+            VetKdEncryptedKeyShareCreationVaultError::SecretKeyMissingOrWrongType(format!(
+                "Malformed secret key share with key id {}: {:?}",
+                key_id, e
+            ))
+        })?;
+
+        let derivation_path = DerivationPath::new(
+            derivation_path.caller.as_slice(),
+            &derivation_path.derivation_path,
+        );
+
+        let encrypted_key_share = EncryptedKeyShare::create(
+            &mut *self.rng_write_lock(),
+            &master_public_key,
+            &secret_key_share,
+            &transport_public_key,
+            &derivation_path,
+            &input,
+        );
+
+        Ok(encrypted_key_share.serialize().to_vec())
+    }
+}
@@ -0,0 +1,147 @@
+#![allow(clippy::unwrap_used)]
+use crate::key_id::KeyId;
+use crate::vault::api::{
+    ThresholdSignatureCspVault, VetKdCspVault, VetKdEncryptedKeyShareCreationVaultError,
+};
+use crate::vault::local_csp_vault::LocalCspVault;
+use ic_crypto_internal_bls12_381_type::G2Affine;
+use ic_crypto_internal_threshold_sig_bls12381 as clib;
+use ic_crypto_internal_threshold_sig_bls12381::vetkd::{
+    DerivationPath, EncryptedKeyShare, TransportSecretKey,
+};
+use ic_crypto_internal_types::sign::threshold_sig::public_coefficients::bls12_381::PublicCoefficientsBytes;
+use ic_crypto_test_utils_reproducible_rng::reproducible_rng;
+use ic_types::crypto::canister_threshold_sig::ExtendedDerivationPath;
+use ic_types::crypto::AlgorithmId;
+use ic_types::{NumberOfNodes, PrincipalId};
+use rand::{CryptoRng, Rng};
+
+#[test]
+fn should_create_valid_encrypted_key_share() {
+    let rng = &mut reproducible_rng();
+    let vault = LocalCspVault::builder()
+        .with_rng(reproducible_rng())
+        .build();
+
+    let (public_coefficients, key_ids) = vault
+        .threshold_keygen_for_test(
+            AlgorithmId::ThresBls12_381,
+            NumberOfNodes::new(2),
+            NumberOfNodes::new(3),
+        )
+        .unwrap();
+    let public_coefficients = PublicCoefficientsBytes::from(public_coefficients);
+    let master_public_key = clib::api::combined_public_key(&public_coefficients)
+        .unwrap()
+        .0
+        .to_vec();
+
+    let transport_public_key = TransportSecretKey::generate(rng).public_key();
+    let derivation_path = random_derivation_path(rng);
+    let input = rng.gen::<[u8; 32]>().to_vec();
+
+    for (index, key_id) in key_ids.into_iter().enumerate() {
+        let share = vault
+            .create_encrypted_vetkd_key_share(
+                key_id,
+                master_public_key.clone(),
+                transport_public_key.serialize().to_vec(),
+                derivation_path.clone(),
+                input.clone(),
+            )
+            .unwrap();
+
+        let public_key_share = clib::api::individual_public_key(&public_coefficients, index as u32)
+            .unwrap()
+            .0;
+        let share = EncryptedKeyShare::deserialize(&share).unwrap();
+        assert!(share.is_valid(
+            &G2Affine::deserialize(&master_public_key).unwrap(),
+            &G2Affine::deserialize(&public_key_share).unwrap(),
+            &DerivationPath::new(
+                derivation_path.caller.as_slice(),
+                &derivation_path.derivation_path
+            ),
+            &input,
+            &transport_public_key,
+        ));
+    }
+}
+
+#[test]
+fn should_fail_if_secret_key_is_missing() {
+    let rng = &mut reproducible_rng();
+    let vault = LocalCspVault::builder()
+        .with_rng(reproducible_rng())
+        .build();
+
+    let (public_coefficients, _key_ids) = vault
+        .threshold_keygen_for_test(
+            AlgorithmId::ThresBls12_381,
+            NumberOfNodes::new(1),
+            NumberOfNodes::new(1),
+        )
+        .unwrap();
+    let public_coefficients = PublicCoefficientsBytes::from(public_coefficients);
+    let master_public_key = clib::api::combined_public_key(&public_coefficients)
+        .unwrap()
+        .0
+        .to_vec();
+
+    let result = vault.create_encrypted_vetkd_key_share(
+        KeyId::from([42u8; 32]),
+        master_public_key,
+        TransportSecretKey::generate(rng)
+            .public_key()
+            .serialize()
+            .to_vec(),
+        random_derivation_path(rng),
+        b"input".to_vec(),
+    );
+
+    assert!(matches!(
+        result,
+        Err(VetKdEncryptedKeyShareCreationVaultError::SecretKeyMissingOrWrongType(_))
+    ));
+}
+
+#[test]
+fn should_fail_on_invalid_transport_public_key() {
+    let rng = &mut reproducible_rng();
+    let vault = LocalCspVault::builder()
+        .with_rng(reproducible_rng())
+        .build();
+
+    let (public_coefficients, key_ids) = vault
+        .threshold_keygen_for_test(
+            AlgorithmId::ThresBls12_381,
+            NumberOfNodes::new(1),
+            NumberOfNodes::new(1),
+        )
+        .unwrap();
+    let public_coefficients = PublicCoefficientsBytes::from(public_coefficients);
+    let master_public_key = clib::api::combined_public_key(&public_coefficients)
+        .unwrap()
+        .0
+        .to_vec();
+
+    let result = vault.create_encrypted_vetkd_key_share(
+        key_ids[0],
+        master_public_key,
+        vec![0u8; 48],
+        random_derivation_path(rng),
+        b"input".to_vec(),
+    );
+
+    assert_eq!(
+        result,
+        Err(VetKdEncryptedKeyShareCreationVaultError::InvalidArgumentTransportPublicKey)
+    );
+}
+
+fn random_derivation_path<R: Rng + CryptoRng>(rng: &mut R) -> ExtendedDerivationPath {
+    ExtendedDerivationPath {
+        caller: PrincipalId::new_user_test_id(rng.gen()),
+        derivation_path: vec![rng.gen::<[u8; 8]>().to_vec()],
+    }
+}
@@ -161,6 +161,8 @@ enum CspVaultMethod {
     IdkgGenDealingEncryptionKeyPair,
     IdkgOpenDealing,
     EcdsaSignShare,
+    CreateSchnorrSigShare,
+    CreateEncryptedVetKdKeyShare,
     NewPublicSeed,
 }
 
@@ -235,6 +237,12 @@ impl CspVaultMethod {
             ),
             CspVaultMethod::IdkgOpenDealing => (MetricsDomain::IdkgProtocol, "idkg_open_dealing"),
             CspVaultMethod::EcdsaSignShare => (MetricsDomain::ThresholdEcdsa, "ecdsa_sign_share"),
+            CspVaultMethod::CreateSchnorrSigShare => {
+                (MetricsDomain::ThresholdSchnorr, "create_schnorr_sig_share")
+            }
+            CspVaultMethod::CreateEncryptedVetKdKeyShare => {
+                (MetricsDomain::VetKd, "create_encrypted_vetkd_key_share")
+            }
             CspVaultMethod::NewPublicSeed => (MetricsDomain::PublicSeed, "new_public_seed"),
         }
     }
@@ -274,6 +282,8 @@ impl From<&TarpcCspVaultRequest> for CspVaultMethod {
             Req::IdkgGenDealingEncryptionKeyPair { .. } => Method::IdkgGenDealingEncryptionKeyPair,
             Req::IdkgOpenDealing { .. } => Method::IdkgOpenDealing,
             Req::EcdsaSignShare { .. } => Method::EcdsaSignShare,
+            Req::CreateSchnorrSigShare { .. } => Method::CreateSchnorrSigShare,
+            Req::CreateEncryptedVetkdKeyShare { .. } => Method::CreateEncryptedVetKdKeyShare,
             Req::NewPublicSeed { .. } => Method::NewPublicSeed,
         }
     }
@@ -313,6 +323,8 @@ impl From<&TarpcCspVaultResponse> for CspVaultMethod {
             Resp::IdkgGenDealingEncryptionKeyPair { .. } => Method::IdkgGenDealingEncryptionKeyPair,
             Resp::IdkgOpenDealing { .. } => Method::IdkgOpenDealing,
             Resp::EcdsaSignShare { .. } => Method::EcdsaSignShare,
+            Resp::CreateSchnorrSigShare { .. } => Method::CreateSchnorrSigShare,
+            Resp::CreateEncryptedVetkdKeyShare { .. } => Method::CreateEncryptedVetKdKeyShare,
             Resp::NewPublicSeed { .. } => Method::NewPublicSeed,
         }
     }
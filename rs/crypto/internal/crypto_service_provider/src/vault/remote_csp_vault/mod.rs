@@ -4,7 +4,7 @@ use crate::vault::api::{
     CspBasicSignatureError, CspBasicSignatureKeygenError, CspMultiSignatureError,
     CspMultiSignatureKeygenError, CspPublicKeyStoreError, CspSecretKeyStoreContainsError,
     CspThresholdSignatureKeygenError, CspTlsKeygenError, CspTlsSignError, PksAndSksCompleteError,
-    PksAndSksContainsErrors,
+    PksAndSksContainsErrors, VetKdEncryptedKeyShareCreationVaultError,
 };
 use ic_crypto_internal_seed::Seed;
 use ic_crypto_internal_threshold_sig_bls12381::api::ni_dkg_errors;
@@ -22,7 +22,7 @@ use ic_crypto_tls_interfaces::TlsPublicKeyCert;
 use ic_logger::ReplicaLogger;
 use ic_types::crypto::canister_threshold_sig::error::{
     IDkgCreateDealingError, IDkgLoadTranscriptError, IDkgOpenTranscriptError, IDkgRetainKeysError,
-    IDkgVerifyDealingPrivateError, ThresholdEcdsaSignShareError,
+    IDkgVerifyDealingPrivateError, ThresholdEcdsaSignShareError, ThresholdSchnorrSignShareError,
 };
 use ic_types::crypto::canister_threshold_sig::ExtendedDerivationPath;
 use ic_types::crypto::{AlgorithmId, CurrentNodePublicKeys};
@@ -33,13 +33,16 @@ use tokio::net::UnixListener;
 
 const FOUR_GIGA_BYTES: usize = 4 * 1024 * 1024 * 1024;
 mod codec;
+mod server_policy;
 mod tarpc_csp_vault_client;
 mod tarpc_csp_vault_server;
 
 use crate::key_id::KeyId;
 use crate::ExternalPublicKeys;
+use ic_config::crypto::{CspVaultServerConfig, SecretKeyStoreEncryption};
 use ic_crypto_internal_logmon::metrics::CryptoMetrics;
 use ic_crypto_node_key_validation::ValidNodePublicKeys;
+pub use server_policy::SubnetMembership;
 use std::sync::Arc;
 pub use tarpc_csp_vault_client::RemoteCspVault;
 pub use tarpc_csp_vault_server::TarpcCspVaultServerImpl;
@@ -233,21 +236,48 @@ pub trait TarpcCspVault {
         algorithm_id: AlgorithmId,
     ) -> Result<ThresholdEcdsaSigShareInternal, ThresholdEcdsaSignShareError>;
 
+    // Corresponds to `ThresholdSchnorrSignerCspVault.create_schnorr_sig_share`
+    async fn create_schnorr_sig_share(
+        derivation_path: ExtendedDerivationPath,
+        message: Vec<u8>,
+        nonce: Randomness,
+        key: IDkgTranscriptInternal,
+        presig: IDkgTranscriptInternal,
+        algorithm_id: AlgorithmId,
+    ) -> Result<Vec<u8>, ThresholdSchnorrSignShareError>;
+
+    // Corresponds to `VetKdCspVault.create_encrypted_vetkd_key_share`
+    async fn create_encrypted_vetkd_key_share(
+        key_id: KeyId,
+        master_public_key: Vec<u8>,
+        transport_public_key: Vec<u8>,
+        derivation_path: ExtendedDerivationPath,
+        input: Vec<u8>,
+    ) -> Result<Vec<u8>, VetKdEncryptedKeyShareCreationVaultError>;
+
     async fn new_public_seed() -> Result<Seed, PublicRandomSeedGeneratorError>;
 }
 
 pub async fn run_csp_vault_server(
     sks_dir: &Path,
+    sks_encryption: &SecretKeyStoreEncryption,
     listener: UnixListener,
     logger: ReplicaLogger,
     metrics: CryptoMetrics,
+    config: CspVaultServerConfig,
+    subnet_membership: Option<Arc<dyn SubnetMembership>>,
 ) {
-    let server = tarpc_csp_vault_server::TarpcCspVaultServerImpl::new(
+    let mut server = tarpc_csp_vault_server::TarpcCspVaultServerImpl::new_with_sks_encryption(
         sks_dir,
+        sks_encryption,
         listener,
         logger,
         Arc::new(metrics),
-    );
+    )
+    .with_config(config);
+    if let Some(subnet_membership) = subnet_membership {
+        server = server.with_subnet_membership(subnet_membership);
+    }
     server.run().await
 }
 
@@ -0,0 +1,560 @@
+//! Policy of the remote CSP vault server for calls that use secret keys.
+//!
+//! The policy is configured with a [`CspVaultServerConfig`] and makes sure
+//! that a compromised replica process cannot use the node's secret keys
+//! arbitrarily: methods can be disabled or rate limited, the node signing
+//! key can be restricted to a set of signature domains and to the subnet the
+//! node is registered to, and every call can be written to an audit log.
+use crate::api::{CspCreateMEGaKeyError, CspThresholdSignError};
+use crate::key_id::KeyId;
+use crate::vault::api::{
+    CspBasicSignatureError, CspBasicSignatureKeygenError, CspMultiSignatureError,
+    CspMultiSignatureKeygenError, CspTlsKeygenError, CspTlsSignError,
+    VetKdEncryptedKeyShareCreationVaultError,
+};
+use ic_config::crypto::CspVaultServerConfig;
+use ic_crypto_internal_logmon::metrics::{CryptoMetrics, VaultPolicyDecision};
+use ic_crypto_internal_threshold_sig_bls12381::api::dkg_errors::InternalError;
+use ic_crypto_internal_threshold_sig_bls12381::api::ni_dkg_errors::{
+    CspDkgCreateFsKeyError, CspDkgCreateReshareDealingError, CspDkgLoadPrivateKeyError,
+    CspDkgRetainThresholdKeysError, CspDkgUpdateFsEpochError,
+};
+use ic_logger::{info, warn, ReplicaLogger};
+use ic_types::crypto::canister_threshold_sig::error::{
+    IDkgCreateDealingError, IDkgLoadTranscriptError, IDkgOpenTranscriptError, IDkgRetainKeysError,
+    IDkgVerifyDealingPrivateError, ThresholdEcdsaSignShareError, ThresholdSchnorrSignShareError,
+};
+use ic_types::{NodeId, SubnetId};
+use parking_lot::Mutex;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use strum_macros::{Display, EnumIter, EnumString, IntoStaticStr};
+
+#[cfg(test)]
+mod tests;
+
+const RATE_LIMIT_WINDOW: Duration = Duration::from_secs(1);
+
+/// Looks up which subnet a node is a member of, e.g., in the registry.
+pub trait SubnetMembership: Send + Sync {
+    /// Returns the subnet that `node_id` is currently a member of, or `None`
+    /// if the node is not a member of any subnet.
+    fn subnet_id(&self, node_id: NodeId) -> Result<Option<SubnetId>, String>;
+}
+
+/// The methods of the [`TarpcCspVault`](super::TarpcCspVault) that use secret
+/// keys and are therefore subject to the [`ServerPolicy`]. In the
+/// [`CspVaultServerConfig`], a method is referred to by its name, e.g.,
+/// `gen_node_signing_key_pair`.
+#[derive(
+    Copy, Clone, Debug, Display, EnumIter, EnumString, Eq, IntoStaticStr, Ord, PartialEq, PartialOrd,
+)]
+#[strum(serialize_all = "snake_case")]
+pub enum VaultMethod {
+    Sign,
+    GenNodeSigningKeyPair,
+    MultiSign,
+    GenCommitteeSigningKeyPair,
+    ThresholdSign,
+    GenDealingEncryptionKeyPair,
+    UpdateForwardSecureEpoch,
+    CreateDealing,
+    LoadThresholdSigningKey,
+    RetainThresholdKeysIfPresent,
+    GenTlsKeyPair,
+    TlsSign,
+    IdkgCreateDealing,
+    IdkgVerifyDealingPrivate,
+    IdkgLoadTranscript,
+    IdkgLoadTranscriptWithOpenings,
+    IdkgRetainActiveKeys,
+    IdkgGenDealingEncryptionKeyPair,
+    IdkgOpenDealing,
+    EcdsaSignShare,
+    CreateSchnorrSigShare,
+    CreateEncryptedVetkdKeyShare,
+}
+
+/// A [`CspVaultServerConfig`] that refers to a method that is not a
+/// [`VaultMethod`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UnknownVaultMethod {
+    pub method_name: String,
+}
+
+impl fmt::Display for UnknownVaultMethod {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "CSP vault server config refers to unknown method '{}'",
+            self.method_name
+        )
+    }
+}
+
+fn parse_vault_method(method_name: &str) -> Result<VaultMethod, UnknownVaultMethod> {
+    VaultMethod::from_str(method_name).map_err(|_| UnknownVaultMethod {
+        method_name: method_name.to_string(),
+    })
+}
+
+/// A call of a vault method that was refused by the [`ServerPolicy`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PolicyViolation {
+    MethodDisabled {
+        method: VaultMethod,
+    },
+    RateLimitExceeded {
+        method: VaultMethod,
+        max_calls_per_second: u32,
+    },
+    NodeSigningDomainNotAllowed {
+        domain: Option<String>,
+    },
+    NodeNotInRegisteredSubnet {
+        node_id: NodeId,
+        registered_subnet_id: SubnetId,
+        subnet_id: Option<SubnetId>,
+    },
+    SubnetMembershipUnknown {
+        error: String,
+    },
+}
+
+impl fmt::Display for PolicyViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PolicyViolation::MethodDisabled { method } => write!(
+                f,
+                "CSP vault server policy violation: method '{method}' is disabled"
+            ),
+            PolicyViolation::RateLimitExceeded {
+                method,
+                max_calls_per_second,
+            } => write!(
+                f,
+                "CSP vault server policy violation: method '{method}' exceeded its rate limit of {max_calls_per_second} calls per second"
+            ),
+            PolicyViolation::NodeSigningDomainNotAllowed { domain } => write!(
+                f,
+                "CSP vault server policy violation: node signing in domain {domain:?} is not allowed"
+            ),
+            PolicyViolation::NodeNotInRegisteredSubnet {
+                node_id,
+                registered_subnet_id,
+                subnet_id,
+            } => write!(
+                f,
+                "CSP vault server policy violation: node {node_id} is registered to subnet {registered_subnet_id}, but is a member of subnet {subnet_id:?}"
+            ),
+            PolicyViolation::SubnetMembershipUnknown { error } => write!(
+                f,
+                "CSP vault server policy violation: the subnet membership of the node is unknown: {error}"
+            ),
+        }
+    }
+}
+
+/// Enforces the [`CspVaultServerConfig`] of a remote CSP vault server.
+pub struct ServerPolicy {
+    config: CspVaultServerConfig,
+    disabled_methods: BTreeSet<VaultMethod>,
+    rate_limiters: BTreeMap<VaultMethod, Mutex<RateLimiter>>,
+    subnet_membership: Option<Arc<dyn SubnetMembership>>,
+    metrics: Arc<CryptoMetrics>,
+    logger: ReplicaLogger,
+}
+
+impl ServerPolicy {
+    /// Creates the policy for `config`. The `subnet_membership` is required
+    /// if `config.registered_subnet_id` is set, otherwise the node signing key
+    /// refuses to sign.
+    ///
+    /// Returns an error if `config` disables or rate limits a method that is
+    /// not a [`VaultMethod`].
+    pub fn new(
+        config: CspVaultServerConfig,
+        subnet_membership: Option<Arc<dyn SubnetMembership>>,
+        metrics: Arc<CryptoMetrics>,
+        logger: ReplicaLogger,
+    ) -> Result<Self, UnknownVaultMethod> {
+        let disabled_methods = config
+            .disabled_methods
+            .iter()
+            .map(|method_name| parse_vault_method(method_name))
+            .collect::<Result<_, _>>()?;
+        let rate_limiters = config
+            .rate_limits_per_second
+            .iter()
+            .map(|(method_name, max_calls_per_second)| {
+                Ok((
+                    parse_vault_method(method_name)?,
+                    Mutex::new(RateLimiter::new(*max_calls_per_second)),
+                ))
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Self {
+            config,
+            disabled_methods,
+            rate_limiters,
+            subnet_membership,
+            metrics,
+            logger,
+        })
+    }
+
+    /// Checks whether the vault method `method` may be called now.
+    ///
+    /// Calls refused by the policy are written to the audit log (if enabled)
+    /// and logged as warning.
+    pub fn authorize(
+        &self,
+        method: VaultMethod,
+        key_id: Option<&KeyId>,
+    ) -> Result<(), PolicyViolation> {
+        let result = self.check_method(method);
+        self.observe_decision(method, key_id, &result);
+        result
+    }
+
+    /// Checks whether the node signing key may sign `message`, i.e., whether
+    /// the signature domain that `message` starts with is allowed, and whether
+    /// the node is a member of the subnet it is registered to. The ID of the
+    /// node is only computed with `node_id` if the latter is checked.
+    pub fn authorize_node_signing(
+        &self,
+        method: VaultMethod,
+        key_id: Option<&KeyId>,
+        message: &[u8],
+        node_id: impl FnOnce() -> Result<NodeId, String>,
+    ) -> Result<(), PolicyViolation> {
+        let result = self
+            .check_method(method)
+            .and_then(|()| self.check_node_signing_domain(message))
+            .and_then(|()| self.check_registered_subnet(node_id));
+        self.observe_decision(method, key_id, &result);
+        result
+    }
+
+    /// Writes the outcome of a call of the vault method `method` to the
+    /// audit log, if enabled.
+    pub fn audit<T, E: fmt::Debug>(
+        &self,
+        method: VaultMethod,
+        key_id: Option<&KeyId>,
+        result: &Result<T, E>,
+    ) {
+        if self.config.audit_log_enabled {
+            let outcome = match result {
+                Ok(_) => "ok".to_string(),
+                Err(e) => format!("error: {:?}", e),
+            };
+            info!(
+                self.logger,
+                "CSP vault audit: '{}' with key ID {}: {}",
+                method,
+                key_id_description(key_id),
+                outcome
+            );
+        }
+    }
+
+    fn check_method(&self, method: VaultMethod) -> Result<(), PolicyViolation> {
+        if self.disabled_methods.contains(&method) {
+            return Err(PolicyViolation::MethodDisabled { method });
+        }
+        if let Some(rate_limiter) = self.rate_limiters.get(&method) {
+            let mut rate_limiter = rate_limiter.lock();
+            if !rate_limiter.try_acquire(Instant::now()) {
+                return Err(PolicyViolation::RateLimitExceeded {
+                    method,
+                    max_calls_per_second: rate_limiter.max_calls_per_window,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    fn check_node_signing_domain(&self, message: &[u8]) -> Result<(), PolicyViolation> {
+        match &self.config.allowed_node_signing_domains {
+            None => Ok(()),
+            Some(allowed_domains) => {
+                let domain = signature_domain(message);
+                match &domain {
+                    Some(domain) if allowed_domains.contains(domain) => Ok(()),
+                    _ => Err(PolicyViolation::NodeSigningDomainNotAllowed { domain }),
+                }
+            }
+        }
+    }
+
+    fn check_registered_subnet(
+        &self,
+        node_id: impl FnOnce() -> Result<NodeId, String>,
+    ) -> Result<(), PolicyViolation> {
+        let registered_subnet_id = match self.config.registered_subnet_id {
+            None => return Ok(()),
+            Some(registered_subnet_id) => SubnetId::from(registered_subnet_id),
+        };
+        let subnet_membership = self.subnet_membership.as_ref().ok_or_else(|| {
+            PolicyViolation::SubnetMembershipUnknown {
+                error: "no subnet membership lookup is configured".to_string(),
+            }
+        })?;
+        let node_id =
+            node_id().map_err(|error| PolicyViolation::SubnetMembershipUnknown { error })?;
+        let subnet_id = subnet_membership
+            .subnet_id(node_id)
+            .map_err(|error| PolicyViolation::SubnetMembershipUnknown { error })?;
+        if subnet_id != Some(registered_subnet_id) {
+            return Err(PolicyViolation::NodeNotInRegisteredSubnet {
+                node_id,
+                registered_subnet_id,
+                subnet_id,
+            });
+        }
+        Ok(())
+    }
+
+    fn observe_decision(
+        &self,
+        method: VaultMethod,
+        key_id: Option<&KeyId>,
+        result: &Result<(), PolicyViolation>,
+    ) {
+        let decision = match result {
+            Ok(()) => VaultPolicyDecision::Allowed,
+            Err(PolicyViolation::RateLimitExceeded { .. }) => VaultPolicyDecision::RateLimited,
+            Err(_) => VaultPolicyDecision::Refused,
+        };
+        self.metrics
+            .observe_vault_server_policy_decision(method.into(), decision);
+        if let Err(violation) = result {
+            warn!(
+                self.logger,
+                "CSP vault refused '{}' with key ID {}: {}",
+                method,
+                key_id_description(key_id),
+                violation
+            );
+            self.audit(method, key_id, result);
+        }
+    }
+}
+
+fn key_id_description(key_id: Option<&KeyId>) -> String {
+    key_id.map_or_else(|| "none".to_string(), |key_id| format!("{}", key_id))
+}
+
+/// Returns the signature domain of a message signed with the node signing
+/// key, i.e., the length-prefixed domain separator that the message starts
+/// with, or `None` if the message does not start with a valid domain.
+fn signature_domain(message: &[u8]) -> Option<String> {
+    let (domain_len, rest) = message.split_first()?;
+    let domain = rest.get(..usize::from(*domain_len))?;
+    String::from_utf8(domain.to_vec()).ok()
+}
+
+/// Limits the number of calls within fixed windows of [`RATE_LIMIT_WINDOW`].
+struct RateLimiter {
+    max_calls_per_window: u32,
+    window_start: Option<Instant>,
+    calls_in_window: u32,
+}
+
+impl RateLimiter {
+    fn new(max_calls_per_window: u32) -> Self {
+        Self {
+            max_calls_per_window,
+            window_start: None,
+            calls_in_window: 0,
+        }
+    }
+
+    fn try_acquire(&mut self, now: Instant) -> bool {
+        let window_expired = self.window_start.map_or(true, |window_start| {
+            now.saturating_duration_since(window_start) >= RATE_LIMIT_WINDOW
+        });
+        if window_expired {
+            self.window_start = Some(now);
+            self.calls_in_window = 0;
+        }
+        if self.calls_in_window < self.max_calls_per_window {
+            self.calls_in_window += 1;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+impl From<PolicyViolation> for CspBasicSignatureError {
+    fn from(violation: PolicyViolation) -> Self {
+        CspBasicSignatureError::InternalError {
+            internal_error: violation.to_string(),
+        }
+    }
+}
+
+impl From<PolicyViolation> for CspBasicSignatureKeygenError {
+    fn from(violation: PolicyViolation) -> Self {
+        CspBasicSignatureKeygenError::InternalError {
+            internal_error: violation.to_string(),
+        }
+    }
+}
+
+impl From<PolicyViolation> for CspMultiSignatureError {
+    fn from(violation: PolicyViolation) -> Self {
+        CspMultiSignatureError::InternalError {
+            internal_error: violation.to_string(),
+        }
+    }
+}
+
+impl From<PolicyViolation> for CspMultiSignatureKeygenError {
+    fn from(violation: PolicyViolation) -> Self {
+        CspMultiSignatureKeygenError::InternalError {
+            internal_error: violation.to_string(),
+        }
+    }
+}
+
+impl From<PolicyViolation> for CspThresholdSignError {
+    fn from(violation: PolicyViolation) -> Self {
+        CspThresholdSignError::InternalError {
+            internal_error: violation.to_string(),
+        }
+    }
+}
+
+impl From<PolicyViolation> for CspDkgCreateFsKeyError {
+    fn from(violation: PolicyViolation) -> Self {
+        CspDkgCreateFsKeyError::InternalError(InternalError {
+            internal_error: violation.to_string(),
+        })
+    }
+}
+
+impl From<PolicyViolation> for CspDkgUpdateFsEpochError {
+    fn from(violation: PolicyViolation) -> Self {
+        // This error type has no permanent internal error variant.
+        CspDkgUpdateFsEpochError::TransientInternalError(InternalError {
+            internal_error: violation.to_string(),
+        })
+    }
+}
+
+impl From<PolicyViolation> for CspDkgCreateReshareDealingError {
+    fn from(violation: PolicyViolation) -> Self {
+        CspDkgCreateReshareDealingError::InternalError(InternalError {
+            internal_error: violation.to_string(),
+        })
+    }
+}
+
+impl From<PolicyViolation> for CspDkgLoadPrivateKeyError {
+    fn from(violation: PolicyViolation) -> Self {
+        // This error type has no permanent internal error variant.
+        CspDkgLoadPrivateKeyError::TransientInternalError(InternalError {
+            internal_error: violation.to_string(),
+        })
+    }
+}
+
+impl From<PolicyViolation> for CspDkgRetainThresholdKeysError {
+    fn from(violation: PolicyViolation) -> Self {
+        // This error type has no permanent internal error variant.
+        CspDkgRetainThresholdKeysError::TransientInternalError(InternalError {
+            internal_error: violation.to_string(),
+        })
+    }
+}
+
+impl From<PolicyViolation> for CspTlsKeygenError {
+    fn from(violation: PolicyViolation) -> Self {
+        CspTlsKeygenError::InternalError {
+            internal_error: violation.to_string(),
+        }
+    }
+}
+
+impl From<PolicyViolation> for CspTlsSignError {
+    fn from(violation: PolicyViolation) -> Self {
+        CspTlsSignError::InternalError {
+            internal_error: violation.to_string(),
+        }
+    }
+}
+
+impl From<PolicyViolation> for IDkgCreateDealingError {
+    fn from(violation: PolicyViolation) -> Self {
+        IDkgCreateDealingError::InternalError {
+            internal_error: violation.to_string(),
+        }
+    }
+}
+
+impl From<PolicyViolation> for IDkgVerifyDealingPrivateError {
+    fn from(violation: PolicyViolation) -> Self {
+        IDkgVerifyDealingPrivateError::InternalError(violation.to_string())
+    }
+}
+
+impl From<PolicyViolation> for IDkgLoadTranscriptError {
+    fn from(violation: PolicyViolation) -> Self {
+        IDkgLoadTranscriptError::InternalError {
+            internal_error: violation.to_string(),
+        }
+    }
+}
+
+impl From<PolicyViolation> for IDkgRetainKeysError {
+    fn from(violation: PolicyViolation) -> Self {
+        IDkgRetainKeysError::InternalError {
+            internal_error: violation.to_string(),
+        }
+    }
+}
+
+impl From<PolicyViolation> for CspCreateMEGaKeyError {
+    fn from(violation: PolicyViolation) -> Self {
+        CspCreateMEGaKeyError::InternalError {
+            internal_error: violation.to_string(),
+        }
+    }
+}
+
+impl From<PolicyViolation> for IDkgOpenTranscriptError {
+    fn from(violation: PolicyViolation) -> Self {
+        IDkgOpenTranscriptError::InternalError {
+            internal_error: violation.to_string(),
+        }
+    }
+}
+
+impl From<PolicyViolation> for ThresholdEcdsaSignShareError {
+    fn from(violation: PolicyViolation) -> Self {
+        ThresholdEcdsaSignShareError::InternalError {
+            internal_error: violation.to_string(),
+        }
+    }
+}
+
+impl From<PolicyViolation> for ThresholdSchnorrSignShareError {
+    fn from(violation: PolicyViolation) -> Self {
+        ThresholdSchnorrSignShareError::InternalError {
+            internal_error: violation.to_string(),
+        }
+    }
+}
+
+impl From<PolicyViolation> for VetKdEncryptedKeyShareCreationVaultError {
+    fn from(violation: PolicyViolation) -> Self {
+        // This error type has no permanent internal error variant.
+        VetKdEncryptedKeyShareCreationVaultError::TransientInternalError(violation.to_string())
+    }
+}
@@ -0,0 +1,408 @@
+use super::*;
+use ic_test_utilities_in_memory_logger::assertions::LogEntriesAssert;
+use ic_test_utilities_in_memory_logger::InMemoryReplicaLogger;
+use ic_types::PrincipalId;
+use std::collections::BTreeSet;
+use strum::IntoEnumIterator;
+
+fn key_id() -> KeyId {
+    KeyId::from([42; 32])
+}
+
+fn policy(config: CspVaultServerConfig) -> ServerPolicy {
+    ServerPolicy::new(
+        config,
+        None,
+        Arc::new(CryptoMetrics::none()),
+        ic_logger::replica_logger::no_op_logger(),
+    )
+    .unwrap()
+}
+
+fn node_id() -> Result<NodeId, String> {
+    Ok(NodeId::from(PrincipalId::new_node_test_id(1)))
+}
+
+fn subnet_id(id: u64) -> SubnetId {
+    SubnetId::from(PrincipalId::new_subnet_test_id(id))
+}
+
+/// Returns the same lookup result for every node.
+struct FakeSubnetMembership(Result<Option<SubnetId>, String>);
+
+impl SubnetMembership for FakeSubnetMembership {
+    fn subnet_id(&self, _node_id: NodeId) -> Result<Option<SubnetId>, String> {
+        self.0.clone()
+    }
+}
+
+fn policy_with_registered_subnet(
+    registered_subnet_id: SubnetId,
+    subnet_membership: Option<FakeSubnetMembership>,
+) -> ServerPolicy {
+    ServerPolicy::new(
+        CspVaultServerConfig {
+            registered_subnet_id: Some(registered_subnet_id.get()),
+            ..Default::default()
+        },
+        subnet_membership
+            .map(|subnet_membership| Arc::new(subnet_membership) as Arc<dyn SubnetMembership>),
+        Arc::new(CryptoMetrics::none()),
+        ic_logger::replica_logger::no_op_logger(),
+    )
+    .unwrap()
+}
+
+fn message_in_domain(domain: &str, content: &[u8]) -> Vec<u8> {
+    let mut message = vec![domain.len() as u8];
+    message.extend_from_slice(domain.as_bytes());
+    message.extend_from_slice(content);
+    message
+}
+
+mod authorize {
+    use super::*;
+
+    #[test]
+    fn should_allow_everything_with_default_config() {
+        let policy = policy(CspVaultServerConfig::default());
+
+        assert_eq!(policy.authorize(VaultMethod::Sign, Some(&key_id())), Ok(()));
+        assert_eq!(
+            policy.authorize_node_signing(
+                VaultMethod::Sign,
+                Some(&key_id()),
+                b"any message",
+                node_id
+            ),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn should_refuse_disabled_method() {
+        let policy = policy(CspVaultServerConfig {
+            disabled_methods: BTreeSet::from(["tls_sign".to_string()]),
+            ..Default::default()
+        });
+
+        assert_eq!(
+            policy.authorize(VaultMethod::TlsSign, Some(&key_id())),
+            Err(PolicyViolation::MethodDisabled {
+                method: VaultMethod::TlsSign
+            })
+        );
+        assert_eq!(policy.authorize(VaultMethod::Sign, Some(&key_id())), Ok(()));
+    }
+
+    #[test]
+    fn should_refuse_calls_exceeding_rate_limit() {
+        let policy = policy(CspVaultServerConfig {
+            rate_limits_per_second: BTreeMap::from([("multi_sign".to_string(), 3)]),
+            ..Default::default()
+        });
+
+        for _ in 0..3 {
+            assert_eq!(
+                policy.authorize(VaultMethod::MultiSign, Some(&key_id())),
+                Ok(())
+            );
+        }
+        assert_eq!(
+            policy.authorize(VaultMethod::MultiSign, Some(&key_id())),
+            Err(PolicyViolation::RateLimitExceeded {
+                method: VaultMethod::MultiSign,
+                max_calls_per_second: 3
+            })
+        );
+        assert_eq!(policy.authorize(VaultMethod::Sign, Some(&key_id())), Ok(()));
+    }
+
+    #[test]
+    fn should_only_sign_in_allowed_node_signing_domains() {
+        let policy = policy(CspVaultServerConfig {
+            allowed_node_signing_domains: Some(BTreeSet::from(["block_domain".to_string()])),
+            ..Default::default()
+        });
+
+        assert_eq!(
+            policy.authorize_node_signing(
+                VaultMethod::Sign,
+                Some(&key_id()),
+                &message_in_domain("block_domain", b"block"),
+                node_id
+            ),
+            Ok(())
+        );
+        assert_eq!(
+            policy.authorize_node_signing(
+                VaultMethod::Sign,
+                Some(&key_id()),
+                &message_in_domain("ic-request", b"request"),
+                node_id
+            ),
+            Err(PolicyViolation::NodeSigningDomainNotAllowed {
+                domain: Some("ic-request".to_string())
+            })
+        );
+    }
+
+    #[test]
+    fn should_refuse_node_signing_of_message_without_domain_if_domains_are_restricted() {
+        let policy = policy(CspVaultServerConfig {
+            allowed_node_signing_domains: Some(BTreeSet::from(["block_domain".to_string()])),
+            ..Default::default()
+        });
+
+        for message in [vec![], vec![200, b'a'], vec![2, 0xff, 0xfe]] {
+            assert_eq!(
+                policy.authorize_node_signing(
+                    VaultMethod::Sign,
+                    Some(&key_id()),
+                    &message,
+                    node_id
+                ),
+                Err(PolicyViolation::NodeSigningDomainNotAllowed { domain: None })
+            );
+        }
+    }
+}
+
+mod config {
+    use super::*;
+
+    #[test]
+    fn should_refer_to_every_vault_method_by_its_name() {
+        for method in VaultMethod::iter() {
+            assert_eq!(parse_vault_method(&method.to_string()), Ok(method));
+        }
+        assert_eq!(
+            parse_vault_method("gen_node_signing_key_pair"),
+            Ok(VaultMethod::GenNodeSigningKeyPair)
+        );
+    }
+
+    #[test]
+    fn should_fail_on_unknown_disabled_method() {
+        let result = ServerPolicy::new(
+            CspVaultServerConfig {
+                disabled_methods: BTreeSet::from(["tls_sign".to_string(), "tls_sing".to_string()]),
+                ..Default::default()
+            },
+            None,
+            Arc::new(CryptoMetrics::none()),
+            ic_logger::replica_logger::no_op_logger(),
+        );
+
+        assert_eq!(
+            result.err(),
+            Some(UnknownVaultMethod {
+                method_name: "tls_sing".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn should_fail_on_unknown_rate_limited_method() {
+        // `sks_contains` is a vault method that does not use secret keys and
+        // is thus not subject to the policy.
+        let result = ServerPolicy::new(
+            CspVaultServerConfig {
+                rate_limits_per_second: BTreeMap::from([("sks_contains".to_string(), 1)]),
+                ..Default::default()
+            },
+            None,
+            Arc::new(CryptoMetrics::none()),
+            ic_logger::replica_logger::no_op_logger(),
+        );
+
+        assert_eq!(
+            result.err(),
+            Some(UnknownVaultMethod {
+                method_name: "sks_contains".to_string()
+            })
+        );
+    }
+}
+
+mod registered_subnet {
+    use super::*;
+
+    #[test]
+    fn should_sign_only_while_node_is_member_of_registered_subnet() {
+        let registered = policy_with_registered_subnet(
+            subnet_id(1),
+            Some(FakeSubnetMembership(Ok(Some(subnet_id(1))))),
+        );
+        assert_eq!(
+            registered.authorize_node_signing(
+                VaultMethod::Sign,
+                Some(&key_id()),
+                b"message",
+                node_id
+            ),
+            Ok(())
+        );
+
+        for subnet_id_of_node in [Some(subnet_id(2)), None] {
+            let policy = policy_with_registered_subnet(
+                subnet_id(1),
+                Some(FakeSubnetMembership(Ok(subnet_id_of_node))),
+            );
+            assert_eq!(
+                policy.authorize_node_signing(
+                    VaultMethod::Sign,
+                    Some(&key_id()),
+                    b"message",
+                    node_id
+                ),
+                Err(PolicyViolation::NodeNotInRegisteredSubnet {
+                    node_id: node_id().unwrap(),
+                    registered_subnet_id: subnet_id(1),
+                    subnet_id: subnet_id_of_node,
+                })
+            );
+        }
+    }
+
+    #[test]
+    fn should_refuse_node_signing_if_subnet_membership_is_unknown() {
+        let without_lookup = policy_with_registered_subnet(subnet_id(1), None);
+        assert_eq!(
+            without_lookup.authorize_node_signing(
+                VaultMethod::Sign,
+                Some(&key_id()),
+                b"message",
+                node_id
+            ),
+            Err(PolicyViolation::SubnetMembershipUnknown {
+                error: "no subnet membership lookup is configured".to_string()
+            })
+        );
+
+        let failing_lookup = policy_with_registered_subnet(
+            subnet_id(1),
+            Some(FakeSubnetMembership(
+                Err("registry unavailable".to_string()),
+            )),
+        );
+        assert_eq!(
+            failing_lookup.authorize_node_signing(
+                VaultMethod::Sign,
+                Some(&key_id()),
+                b"message",
+                node_id
+            ),
+            Err(PolicyViolation::SubnetMembershipUnknown {
+                error: "registry unavailable".to_string()
+            })
+        );
+
+        let unknown_node_id = policy_with_registered_subnet(
+            subnet_id(1),
+            Some(FakeSubnetMembership(Ok(Some(subnet_id(1))))),
+        );
+        assert_eq!(
+            unknown_node_id.authorize_node_signing(
+                VaultMethod::Sign,
+                Some(&key_id()),
+                b"message",
+                || Err("no node signing public key".to_string())
+            ),
+            Err(PolicyViolation::SubnetMembershipUnknown {
+                error: "no node signing public key".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn should_not_look_up_node_id_without_registered_subnet() {
+        let policy = policy(CspVaultServerConfig::default());
+
+        assert_eq!(
+            policy.authorize_node_signing(VaultMethod::Sign, Some(&key_id()), b"message", || {
+                panic!("the node ID must not be looked up")
+            }),
+            Ok(())
+        );
+    }
+}
+
+mod rate_limiter {
+    use super::*;
+
+    #[test]
+    fn should_reset_limit_in_next_window() {
+        let mut rate_limiter = RateLimiter::new(2);
+        let start = Instant::now();
+
+        assert!(rate_limiter.try_acquire(start));
+        assert!(rate_limiter.try_acquire(start + Duration::from_millis(500)));
+        assert!(!rate_limiter.try_acquire(start + Duration::from_millis(999)));
+        assert!(rate_limiter.try_acquire(start + RATE_LIMIT_WINDOW));
+    }
+
+    #[test]
+    fn should_refuse_all_calls_with_zero_limit() {
+        let mut rate_limiter = RateLimiter::new(0);
+
+        assert!(!rate_limiter.try_acquire(Instant::now()));
+    }
+}
+
+mod audit_log {
+    use super::*;
+    use slog::Level;
+
+    #[test]
+    fn should_write_audit_log_entries_if_enabled() {
+        let in_memory_logger = InMemoryReplicaLogger::new();
+        let policy = ServerPolicy::new(
+            CspVaultServerConfig {
+                audit_log_enabled: true,
+                disabled_methods: BTreeSet::from(["tls_sign".to_string()]),
+                ..Default::default()
+            },
+            None,
+            Arc::new(CryptoMetrics::none()),
+            ReplicaLogger::from(&in_memory_logger),
+        )
+        .unwrap();
+
+        policy.audit::<(), String>(VaultMethod::Sign, Some(&key_id()), &Ok(()));
+        let _ = policy.authorize(VaultMethod::TlsSign, None);
+
+        let logs = in_memory_logger.drain_logs();
+        LogEntriesAssert::assert_that(logs)
+            .has_len(3)
+            .has_only_one_message_containing(
+                &Level::Info,
+                &format!("CSP vault audit: 'sign' with key ID {}: ok", key_id()),
+            )
+            .has_only_one_message_containing(
+                &Level::Warning,
+                "CSP vault refused 'tls_sign' with key ID none",
+            )
+            .has_only_one_message_containing(
+                &Level::Info,
+                "CSP vault audit: 'tls_sign' with key ID none: error: MethodDisabled",
+            );
+    }
+
+    #[test]
+    fn should_not_write_audit_log_entries_if_disabled() {
+        let in_memory_logger = InMemoryReplicaLogger::new();
+        let policy = ServerPolicy::new(
+            CspVaultServerConfig::default(),
+            None,
+            Arc::new(CryptoMetrics::none()),
+            ReplicaLogger::from(&in_memory_logger),
+        )
+        .unwrap();
+
+        policy.audit::<(), String>(VaultMethod::Sign, Some(&key_id()), &Ok(()));
+
+        let logs = in_memory_logger.drain_logs();
+        LogEntriesAssert::assert_that(logs).has_len(0);
+    }
+}
@@ -8,7 +8,8 @@ use crate::vault::api::{
     CspTlsSignError, IDkgProtocolCspVault, MultiSignatureCspVault, NiDkgCspVault,
     PksAndSksCompleteError, PksAndSksContainsErrors, PublicAndSecretKeyStoreCspVault,
     PublicKeyStoreCspVault, PublicRandomSeedGenerator, PublicRandomSeedGeneratorError,
-    SecretKeyStoreCspVault, ThresholdEcdsaSignerCspVault, ThresholdSignatureCspVault,
+    SecretKeyStoreCspVault, ThresholdEcdsaSignerCspVault, ThresholdSchnorrSignerCspVault,
+    ThresholdSignatureCspVault, VetKdCspVault, VetKdEncryptedKeyShareCreationVaultError,
 };
 use crate::vault::remote_csp_vault::codec::{CspVaultClientObserver, ObservableCodec};
 use crate::vault::remote_csp_vault::{remote_vault_codec_builder, TarpcCspVaultClient};
@@ -35,7 +36,7 @@ use ic_crypto_tls_interfaces::TlsPublicKeyCert;
 use ic_logger::{debug, new_logger, ReplicaLogger};
 use ic_types::crypto::canister_threshold_sig::error::{
     IDkgCreateDealingError, IDkgLoadTranscriptError, IDkgOpenTranscriptError, IDkgRetainKeysError,
-    IDkgVerifyDealingPrivateError, ThresholdEcdsaSignShareError,
+    IDkgVerifyDealingPrivateError, ThresholdEcdsaSignShareError, ThresholdSchnorrSignShareError,
 };
 use ic_types::crypto::canister_threshold_sig::ExtendedDerivationPath;
 use ic_types::crypto::{AlgorithmId, CurrentNodePublicKeys};
@@ -689,6 +690,60 @@ impl ThresholdEcdsaSignerCspVault for RemoteCspVault {
     }
 }
 
+impl ThresholdSchnorrSignerCspVault for RemoteCspVault {
+    fn create_schnorr_sig_share(
+        &self,
+        derivation_path: &ExtendedDerivationPath,
+        message: &[u8],
+        nonce: &Randomness,
+        key: &IDkgTranscriptInternal,
+        presig: &IDkgTranscriptInternal,
+        algorithm_id: AlgorithmId,
+    ) -> Result<Vec<u8>, ThresholdSchnorrSignShareError> {
+        self.tokio_block_on(self.tarpc_csp_client.create_schnorr_sig_share(
+            context_with_timeout(self.rpc_timeout),
+            derivation_path.clone(),
+            message.to_vec(),
+            *nonce,
+            key.clone(),
+            presig.clone(),
+            algorithm_id,
+        ))
+        .unwrap_or_else(|rpc_error: tarpc::client::RpcError| {
+            Err(ThresholdSchnorrSignShareError::InternalError {
+                internal_error: rpc_error.to_string(),
+            })
+        })
+    }
+}
+
+impl VetKdCspVault for RemoteCspVault {
+    fn create_encrypted_vetkd_key_share(
+        &self,
+        key_id: KeyId,
+        master_public_key: Vec<u8>,
+        transport_public_key: Vec<u8>,
+        derivation_path: ExtendedDerivationPath,
+        input: Vec<u8>,
+    ) -> Result<Vec<u8>, VetKdEncryptedKeyShareCreationVaultError> {
+        self.tokio_block_on(self.tarpc_csp_client.create_encrypted_vetkd_key_share(
+            context_with_timeout(self.rpc_timeout),
+            key_id,
+            master_public_key,
+            transport_public_key,
+            derivation_path,
+            input,
+        ))
+        .unwrap_or_else(|rpc_error: tarpc::client::RpcError| {
+            Err(
+                VetKdEncryptedKeyShareCreationVaultError::TransientInternalError(
+                    rpc_error.to_string(),
+                ),
+            )
+        })
+    }
+}
+
 impl PublicRandomSeedGenerator for RemoteCspVault {
     fn new_public_seed(&self) -> Result<Seed, PublicRandomSeedGeneratorError> {
         self.tokio_block_on(
@@ -7,13 +7,16 @@ use crate::vault::api::{
     CspBasicSignatureError, CspBasicSignatureKeygenError, CspMultiSignatureError,
     CspMultiSignatureKeygenError, CspSecretKeyStoreContainsError, CspThresholdSignatureKeygenError,
     CspTlsKeygenError, CspTlsSignError, PksAndSksCompleteError, PublicRandomSeedGeneratorError,
+    VetKdEncryptedKeyShareCreationVaultError,
 };
 use crate::vault::api::{CspPublicKeyStoreError, CspVault};
 use crate::vault::local_csp_vault::LocalCspVault;
+use crate::vault::remote_csp_vault::server_policy::{ServerPolicy, SubnetMembership, VaultMethod};
 use crate::vault::remote_csp_vault::PksAndSksContainsErrors;
 use crate::vault::remote_csp_vault::{remote_vault_codec_builder, TarpcCspVault};
 use crate::ExternalPublicKeys;
 use crate::{CANISTER_SKS_DATA_FILENAME, PUBLIC_KEY_STORE_DATA_FILENAME, SKS_DATA_FILENAME};
+use ic_config::crypto::{CspVaultServerConfig, SecretKeyStoreEncryption};
 use ic_crypto_internal_logmon::metrics::CryptoMetrics;
 use ic_crypto_internal_seed::Seed;
 use ic_crypto_internal_threshold_sig_bls12381::api::ni_dkg_errors::{
@@ -31,13 +34,13 @@ use ic_crypto_internal_types::sign::threshold_sig::ni_dkg::{
     CspNiDkgDealing, CspNiDkgTranscript, Epoch,
 };
 use ic_crypto_internal_types::NodeIndex;
-use ic_crypto_node_key_validation::ValidNodePublicKeys;
+use ic_crypto_node_key_validation::{ValidNodePublicKeys, ValidNodeSigningPublicKey};
 use ic_crypto_tls_interfaces::TlsPublicKeyCert;
 use ic_logger::replica_logger::no_op_logger;
 use ic_logger::{new_logger, ReplicaLogger};
 use ic_types::crypto::canister_threshold_sig::error::{
     IDkgCreateDealingError, IDkgLoadTranscriptError, IDkgOpenTranscriptError, IDkgRetainKeysError,
-    IDkgVerifyDealingPrivateError, ThresholdEcdsaSignShareError,
+    IDkgVerifyDealingPrivateError, ThresholdEcdsaSignShareError, ThresholdSchnorrSignShareError,
 };
 use ic_types::crypto::canister_threshold_sig::ExtendedDerivationPath;
 use ic_types::crypto::{AlgorithmId, CurrentNodePublicKeys};
@@ -55,11 +58,16 @@ use threadpool::ThreadPool;
 use tokio::net::UnixListener;
 
 /// Crypto service provider (CSP) vault server based on the tarpc RPC framework.
+///
+/// Calls that use secret keys are subject to the server's policy, which is
+/// configured with [`Self::with_config`] and allows everything by default.
 pub struct TarpcCspVaultServerImpl<C: CspVault> {
     local_csp_vault: Arc<C>,
     listener: UnixListener,
     thread_pool: ThreadPool,
-    #[allow(unused)]
+    config: CspVaultServerConfig,
+    subnet_membership: Option<Arc<dyn SubnetMembership>>,
+    metrics: Arc<CryptoMetrics>,
     logger: ReplicaLogger,
 }
 
@@ -73,6 +81,7 @@ pub struct TarpcCspVaultServerImpl<C: CspVault> {
 struct TarpcCspVaultServerWorker<C: CspVault> {
     local_csp_vault: Arc<C>,
     thread_pool_handle: ThreadPool,
+    policy: Arc<ServerPolicy>,
 }
 
 async fn execute_on_thread_pool<F, T>(thread_pool_handle: ThreadPool, job: F) -> T
@@ -103,6 +112,7 @@ impl<C: CspVault> Clone for TarpcCspVaultServerWorker<C> {
         Self {
             local_csp_vault: Arc::clone(&self.local_csp_vault),
             thread_pool_handle: self.thread_pool_handle.clone(),
+            policy: Arc::clone(&self.policy),
         }
     }
 }
@@ -117,18 +127,29 @@ impl<C: CspVault + 'static> TarpcCspVault for TarpcCspVaultServerWorker<C> {
         msg: Vec<u8>,
         key_id: KeyId,
     ) -> Result<CspSignature, CspBasicSignatureError> {
+        self.policy
+            .authorize_node_signing(VaultMethod::Sign, Some(&key_id), &msg, || {
+                node_id(self.local_csp_vault.as_ref())
+            })?;
         let vault = self.local_csp_vault;
         let job = move || vault.sign(algorithm_id, &msg, key_id);
-        execute_on_thread_pool(self.thread_pool_handle, job).await
+        let result = execute_on_thread_pool(self.thread_pool_handle, job).await;
+        self.policy.audit(VaultMethod::Sign, Some(&key_id), &result);
+        result
     }
 
     async fn gen_node_signing_key_pair(
         self,
         _: context::Context,
     ) -> Result<CspPublicKey, CspBasicSignatureKeygenError> {
+        self.policy
+            .authorize(VaultMethod::GenNodeSigningKeyPair, None)?;
         let vault = self.local_csp_vault;
         let job = move || vault.gen_node_signing_key_pair();
-        execute_on_thread_pool(self.thread_pool_handle, job).await
+        let result = execute_on_thread_pool(self.thread_pool_handle, job).await;
+        self.policy
+            .audit(VaultMethod::GenNodeSigningKeyPair, None, &result);
+        result
     }
 
     // `MultiSignatureCspVault`-methods.
@@ -139,18 +160,28 @@ impl<C: CspVault + 'static> TarpcCspVault for TarpcCspVaultServerWorker<C> {
         message: Vec<u8>,
         key_id: KeyId,
     ) -> Result<CspSignature, CspMultiSignatureError> {
+        self.policy
+            .authorize(VaultMethod::MultiSign, Some(&key_id))?;
         let vault = self.local_csp_vault;
         let job = move || vault.multi_sign(algorithm_id, &message, key_id);
-        execute_on_thread_pool(self.thread_pool_handle, job).await
+        let result = execute_on_thread_pool(self.thread_pool_handle, job).await;
+        self.policy
+            .audit(VaultMethod::MultiSign, Some(&key_id), &result);
+        result
     }
 
     async fn gen_committee_signing_key_pair(
         self,
         _: context::Context,
     ) -> Result<(CspPublicKey, CspPop), CspMultiSignatureKeygenError> {
+        self.policy
+            .authorize(VaultMethod::GenCommitteeSigningKeyPair, None)?;
         let vault = self.local_csp_vault;
         let job = move || vault.gen_committee_signing_key_pair();
-        execute_on_thread_pool(self.thread_pool_handle, job).await
+        let result = execute_on_thread_pool(self.thread_pool_handle, job).await;
+        self.policy
+            .audit(VaultMethod::GenCommitteeSigningKeyPair, None, &result);
+        result
     }
 
     // `ThresholdSignatureCspVault`-methods.
@@ -161,9 +192,14 @@ impl<C: CspVault + 'static> TarpcCspVault for TarpcCspVaultServerWorker<C> {
         message: Vec<u8>,
         key_id: KeyId,
     ) -> Result<CspSignature, CspThresholdSignError> {
+        self.policy
+            .authorize(VaultMethod::ThresholdSign, Some(&key_id))?;
         let vault = self.local_csp_vault;
         let job = move || vault.threshold_sign(algorithm_id, &message, key_id);
-        execute_on_thread_pool(self.thread_pool_handle, job).await
+        let result = execute_on_thread_pool(self.thread_pool_handle, job).await;
+        self.policy
+            .audit(VaultMethod::ThresholdSign, Some(&key_id), &result);
+        result
     }
 
     async fn threshold_keygen_for_test(
@@ -184,9 +220,14 @@ impl<C: CspVault + 'static> TarpcCspVault for TarpcCspVaultServerWorker<C> {
         _: context::Context,
         node_id: NodeId,
     ) -> Result<(CspFsEncryptionPublicKey, CspFsEncryptionPop), CspDkgCreateFsKeyError> {
+        self.policy
+            .authorize(VaultMethod::GenDealingEncryptionKeyPair, None)?;
         let vault = self.local_csp_vault;
         let job = move || vault.gen_dealing_encryption_key_pair(node_id);
-        execute_on_thread_pool(self.thread_pool_handle, job).await
+        let result = execute_on_thread_pool(self.thread_pool_handle, job).await;
+        self.policy
+            .audit(VaultMethod::GenDealingEncryptionKeyPair, None, &result);
+        result
     }
 
     async fn update_forward_secure_epoch(
@@ -196,9 +237,17 @@ impl<C: CspVault + 'static> TarpcCspVault for TarpcCspVaultServerWorker<C> {
         key_id: KeyId,
         epoch: Epoch,
     ) -> Result<(), CspDkgUpdateFsEpochError> {
+        self.policy
+            .authorize(VaultMethod::UpdateForwardSecureEpoch, Some(&key_id))?;
         let vault = self.local_csp_vault;
         let job = move || vault.update_forward_secure_epoch(algorithm_id, key_id, epoch);
-        execute_on_thread_pool(self.thread_pool_handle, job).await
+        let result = execute_on_thread_pool(self.thread_pool_handle, job).await;
+        self.policy.audit(
+            VaultMethod::UpdateForwardSecureEpoch,
+            Some(&key_id),
+            &result,
+        );
+        result
     }
 
     async fn create_dealing(
@@ -211,6 +260,8 @@ impl<C: CspVault + 'static> TarpcCspVault for TarpcCspVaultServerWorker<C> {
         receiver_keys: BTreeMap<NodeIndex, CspFsEncryptionPublicKey>,
         maybe_resharing_secret: Option<KeyId>,
     ) -> Result<CspNiDkgDealing, CspDkgCreateReshareDealingError> {
+        self.policy
+            .authorize(VaultMethod::CreateDealing, maybe_resharing_secret.as_ref())?;
         let vault = self.local_csp_vault;
         let job = move || {
             vault.create_dealing(
@@ -222,7 +273,13 @@ impl<C: CspVault + 'static> TarpcCspVault for TarpcCspVaultServerWorker<C> {
                 maybe_resharing_secret,
             )
         };
-        execute_on_thread_pool(self.thread_pool_handle, job).await
+        let result = execute_on_thread_pool(self.thread_pool_handle, job).await;
+        self.policy.audit(
+            VaultMethod::CreateDealing,
+            maybe_resharing_secret.as_ref(),
+            &result,
+        );
+        result
     }
 
     async fn load_threshold_signing_key(
@@ -234,6 +291,8 @@ impl<C: CspVault + 'static> TarpcCspVault for TarpcCspVaultServerWorker<C> {
         fs_key_id: KeyId,
         receiver_index: NodeIndex,
     ) -> Result<(), CspDkgLoadPrivateKeyError> {
+        self.policy
+            .authorize(VaultMethod::LoadThresholdSigningKey, Some(&fs_key_id))?;
         let vault = self.local_csp_vault;
         let job = move || {
             vault.load_threshold_signing_key(
@@ -244,7 +303,13 @@ impl<C: CspVault + 'static> TarpcCspVault for TarpcCspVaultServerWorker<C> {
                 receiver_index,
             )
         };
-        execute_on_thread_pool(self.thread_pool_handle, job).await
+        let result = execute_on_thread_pool(self.thread_pool_handle, job).await;
+        self.policy.audit(
+            VaultMethod::LoadThresholdSigningKey,
+            Some(&fs_key_id),
+            &result,
+        );
+        result
     }
 
     async fn retain_threshold_keys_if_present(
@@ -252,9 +317,14 @@ impl<C: CspVault + 'static> TarpcCspVault for TarpcCspVaultServerWorker<C> {
         _: context::Context,
         active_key_ids: BTreeSet<KeyId>,
     ) -> Result<(), CspDkgRetainThresholdKeysError> {
+        self.policy
+            .authorize(VaultMethod::RetainThresholdKeysIfPresent, None)?;
         let vault = self.local_csp_vault;
         let job = move || vault.retain_threshold_keys_if_present(active_key_ids);
-        execute_on_thread_pool(self.thread_pool_handle, job).await
+        let result = execute_on_thread_pool(self.thread_pool_handle, job).await;
+        self.policy
+            .audit(VaultMethod::RetainThresholdKeysIfPresent, None, &result);
+        result
     }
 
     // SecretKeyStoreCspVault-methods.
@@ -320,9 +390,12 @@ impl<C: CspVault + 'static> TarpcCspVault for TarpcCspVaultServerWorker<C> {
         node: NodeId,
         not_after: String,
     ) -> Result<TlsPublicKeyCert, CspTlsKeygenError> {
+        self.policy.authorize(VaultMethod::GenTlsKeyPair, None)?;
         let vault = self.local_csp_vault;
         let job = move || vault.gen_tls_key_pair(node, &not_after);
-        execute_on_thread_pool(self.thread_pool_handle, job).await
+        let result = execute_on_thread_pool(self.thread_pool_handle, job).await;
+        self.policy.audit(VaultMethod::GenTlsKeyPair, None, &result);
+        result
     }
 
     async fn tls_sign(
@@ -331,9 +404,13 @@ impl<C: CspVault + 'static> TarpcCspVault for TarpcCspVaultServerWorker<C> {
         message: Vec<u8>,
         key_id: KeyId,
     ) -> Result<CspSignature, CspTlsSignError> {
+        self.policy.authorize(VaultMethod::TlsSign, Some(&key_id))?;
         let vault = self.local_csp_vault;
         let job = move || vault.tls_sign(&message, &key_id);
-        execute_on_thread_pool(self.thread_pool_handle, job).await
+        let result = execute_on_thread_pool(self.thread_pool_handle, job).await;
+        self.policy
+            .audit(VaultMethod::TlsSign, Some(&key_id), &result);
+        result
     }
 
     // `IDkgProtocolCspVault`-methods.
@@ -347,6 +424,8 @@ impl<C: CspVault + 'static> TarpcCspVault for TarpcCspVaultServerWorker<C> {
         receiver_keys: Vec<MEGaPublicKey>,
         transcript_operation: IDkgTranscriptOperationInternal,
     ) -> Result<IDkgDealingInternal, IDkgCreateDealingError> {
+        self.policy
+            .authorize(VaultMethod::IdkgCreateDealing, None)?;
         let vault = self.local_csp_vault;
         let job = move || {
             vault.idkg_create_dealing(
@@ -358,7 +437,10 @@ impl<C: CspVault + 'static> TarpcCspVault for TarpcCspVaultServerWorker<C> {
                 &transcript_operation,
             )
         };
-        execute_on_thread_pool(self.thread_pool_handle, job).await
+        let result = execute_on_thread_pool(self.thread_pool_handle, job).await;
+        self.policy
+            .audit(VaultMethod::IdkgCreateDealing, None, &result);
+        result
     }
 
     async fn idkg_verify_dealing_private(
@@ -371,6 +453,10 @@ impl<C: CspVault + 'static> TarpcCspVault for TarpcCspVaultServerWorker<C> {
         receiver_key_id: KeyId,
         context_data: Vec<u8>,
     ) -> Result<(), IDkgVerifyDealingPrivateError> {
+        self.policy.authorize(
+            VaultMethod::IdkgVerifyDealingPrivate,
+            Some(&receiver_key_id),
+        )?;
         let vault = self.local_csp_vault;
         let job = move || {
             vault.idkg_verify_dealing_private(
@@ -382,7 +468,13 @@ impl<C: CspVault + 'static> TarpcCspVault for TarpcCspVaultServerWorker<C> {
                 &context_data,
             )
         };
-        execute_on_thread_pool(self.thread_pool_handle, job).await
+        let result = execute_on_thread_pool(self.thread_pool_handle, job).await;
+        self.policy.audit(
+            VaultMethod::IdkgVerifyDealingPrivate,
+            Some(&receiver_key_id),
+            &result,
+        );
+        result
     }
 
     async fn idkg_load_transcript(
@@ -394,6 +486,8 @@ impl<C: CspVault + 'static> TarpcCspVault for TarpcCspVaultServerWorker<C> {
         key_id: KeyId,
         transcript: IDkgTranscriptInternal,
     ) -> Result<BTreeMap<NodeIndex, IDkgComplaintInternal>, IDkgLoadTranscriptError> {
+        self.policy
+            .authorize(VaultMethod::IdkgLoadTranscript, Some(&key_id))?;
         let vault = self.local_csp_vault;
         let job = move || {
             vault.idkg_load_transcript(
@@ -404,7 +498,10 @@ impl<C: CspVault + 'static> TarpcCspVault for TarpcCspVaultServerWorker<C> {
                 &transcript,
             )
         };
-        execute_on_thread_pool(self.thread_pool_handle, job).await
+        let result = execute_on_thread_pool(self.thread_pool_handle, job).await;
+        self.policy
+            .audit(VaultMethod::IdkgLoadTranscript, Some(&key_id), &result);
+        result
     }
 
     async fn idkg_load_transcript_with_openings(
@@ -417,6 +514,8 @@ impl<C: CspVault + 'static> TarpcCspVault for TarpcCspVaultServerWorker<C> {
         key_id: KeyId,
         transcript: IDkgTranscriptInternal,
     ) -> Result<(), IDkgLoadTranscriptError> {
+        self.policy
+            .authorize(VaultMethod::IdkgLoadTranscriptWithOpenings, Some(&key_id))?;
         let vault = self.local_csp_vault;
         let job = move || {
             vault.idkg_load_transcript_with_openings(
@@ -428,7 +527,13 @@ impl<C: CspVault + 'static> TarpcCspVault for TarpcCspVaultServerWorker<C> {
                 &transcript,
             )
         };
-        execute_on_thread_pool(self.thread_pool_handle, job).await
+        let result = execute_on_thread_pool(self.thread_pool_handle, job).await;
+        self.policy.audit(
+            VaultMethod::IdkgLoadTranscriptWithOpenings,
+            Some(&key_id),
+            &result,
+        );
+        result
     }
 
     async fn idkg_retain_active_keys(
@@ -437,18 +542,28 @@ impl<C: CspVault + 'static> TarpcCspVault for TarpcCspVaultServerWorker<C> {
         active_key_ids: BTreeSet<KeyId>,
         oldest_public_key: MEGaPublicKey,
     ) -> Result<(), IDkgRetainKeysError> {
+        self.policy
+            .authorize(VaultMethod::IdkgRetainActiveKeys, None)?;
         let vault = self.local_csp_vault;
         let job = move || vault.idkg_retain_active_keys(active_key_ids, oldest_public_key);
-        execute_on_thread_pool(self.thread_pool_handle, job).await
+        let result = execute_on_thread_pool(self.thread_pool_handle, job).await;
+        self.policy
+            .audit(VaultMethod::IdkgRetainActiveKeys, None, &result);
+        result
     }
 
     async fn idkg_gen_dealing_encryption_key_pair(
         self,
         _: context::Context,
     ) -> Result<MEGaPublicKey, CspCreateMEGaKeyError> {
+        self.policy
+            .authorize(VaultMethod::IdkgGenDealingEncryptionKeyPair, None)?;
         let vault = self.local_csp_vault;
         let job = move || vault.idkg_gen_dealing_encryption_key_pair();
-        execute_on_thread_pool(self.thread_pool_handle, job).await
+        let result = execute_on_thread_pool(self.thread_pool_handle, job).await;
+        self.policy
+            .audit(VaultMethod::IdkgGenDealingEncryptionKeyPair, None, &result);
+        result
     }
 
     async fn idkg_open_dealing(
@@ -460,6 +575,8 @@ impl<C: CspVault + 'static> TarpcCspVault for TarpcCspVaultServerWorker<C> {
         opener_index: NodeIndex,
         opener_key_id: KeyId,
     ) -> Result<CommitmentOpening, IDkgOpenTranscriptError> {
+        self.policy
+            .authorize(VaultMethod::IdkgOpenDealing, Some(&opener_key_id))?;
         let vault = self.local_csp_vault;
         let job = move || {
             vault.idkg_open_dealing(
@@ -470,7 +587,10 @@ impl<C: CspVault + 'static> TarpcCspVault for TarpcCspVaultServerWorker<C> {
                 &opener_key_id,
             )
         };
-        execute_on_thread_pool(self.thread_pool_handle, job).await
+        let result = execute_on_thread_pool(self.thread_pool_handle, job).await;
+        self.policy
+            .audit(VaultMethod::IdkgOpenDealing, Some(&opener_key_id), &result);
+        result
     }
 
     // `ThresholdEcdsaSignerCspVault`-methods
@@ -487,6 +607,7 @@ impl<C: CspVault + 'static> TarpcCspVault for TarpcCspVaultServerWorker<C> {
         key_times_lambda: IDkgTranscriptInternal,
         algorithm_id: AlgorithmId,
     ) -> Result<ThresholdEcdsaSigShareInternal, ThresholdEcdsaSignShareError> {
+        self.policy.authorize(VaultMethod::EcdsaSignShare, None)?;
         let vault = self.local_csp_vault;
         let job = move || {
             vault.ecdsa_sign_share(
@@ -501,7 +622,71 @@ impl<C: CspVault + 'static> TarpcCspVault for TarpcCspVaultServerWorker<C> {
                 algorithm_id,
             )
         };
-        execute_on_thread_pool(self.thread_pool_handle, job).await
+        let result = execute_on_thread_pool(self.thread_pool_handle, job).await;
+        self.policy
+            .audit(VaultMethod::EcdsaSignShare, None, &result);
+        result
+    }
+
+    // `ThresholdSchnorrSignerCspVault`-methods
+    async fn create_schnorr_sig_share(
+        self,
+        _: context::Context,
+        derivation_path: ExtendedDerivationPath,
+        message: Vec<u8>,
+        nonce: Randomness,
+        key: IDkgTranscriptInternal,
+        presig: IDkgTranscriptInternal,
+        algorithm_id: AlgorithmId,
+    ) -> Result<Vec<u8>, ThresholdSchnorrSignShareError> {
+        self.policy
+            .authorize(VaultMethod::CreateSchnorrSigShare, None)?;
+        let vault = self.local_csp_vault;
+        let job = move || {
+            vault.create_schnorr_sig_share(
+                &derivation_path,
+                &message,
+                &nonce,
+                &key,
+                &presig,
+                algorithm_id,
+            )
+        };
+        let result = execute_on_thread_pool(self.thread_pool_handle, job).await;
+        self.policy
+            .audit(VaultMethod::CreateSchnorrSigShare, None, &result);
+        result
+    }
+
+    // `VetKdCspVault`-methods
+    async fn create_encrypted_vetkd_key_share(
+        self,
+        _: context::Context,
+        key_id: KeyId,
+        master_public_key: Vec<u8>,
+        transport_public_key: Vec<u8>,
+        derivation_path: ExtendedDerivationPath,
+        input: Vec<u8>,
+    ) -> Result<Vec<u8>, VetKdEncryptedKeyShareCreationVaultError> {
+        self.policy
+            .authorize(VaultMethod::CreateEncryptedVetkdKeyShare, Some(&key_id))?;
+        let vault = self.local_csp_vault;
+        let job = move || {
+            vault.create_encrypted_vetkd_key_share(
+                key_id,
+                master_public_key,
+                transport_public_key,
+                derivation_path,
+                input,
+            )
+        };
+        let result = execute_on_thread_pool(self.thread_pool_handle, job).await;
+        self.policy.audit(
+            VaultMethod::CreateEncryptedVetkdKeyShare,
+            Some(&key_id),
+            &result,
+        );
+        result
     }
 
     async fn new_public_seed(
@@ -514,6 +699,18 @@ impl<C: CspVault + 'static> TarpcCspVault for TarpcCspVaultServerWorker<C> {
     }
 }
 
+/// Returns the ID of the node whose node signing key is stored in `vault`.
+fn node_id<C: CspVault>(vault: &C) -> Result<NodeId, String> {
+    let node_signing_public_key = vault
+        .current_node_public_keys()
+        .map_err(|e| format!("failed to read the node public keys: {:?}", e))?
+        .node_signing_public_key
+        .ok_or_else(|| "no node signing public key".to_string())?;
+    ValidNodeSigningPublicKey::try_from(node_signing_public_key)
+        .map(|public_key| *public_key.derived_node_id())
+        .map_err(|e| format!("invalid node signing public key: {:?}", e))
+}
+
 impl
     TarpcCspVaultServerImpl<
         LocalCspVault<OsRng, ProtoSecretKeyStore, ProtoSecretKeyStore, ProtoPublicKeyStore>,
@@ -525,11 +722,34 @@ impl
         logger: ReplicaLogger,
         metrics: Arc<CryptoMetrics>,
     ) -> Self {
-        let node_secret_key_store =
-            ProtoSecretKeyStore::open(key_store_dir, SKS_DATA_FILENAME, Some(new_logger!(&logger)));
-        let canister_secret_key_store = ProtoSecretKeyStore::open(
+        Self::new_with_sks_encryption(
+            key_store_dir,
+            &SecretKeyStoreEncryption::Plaintext,
+            listener,
+            logger,
+            metrics,
+        )
+    }
+
+    /// Creates a server whose secret key stores are stored according to the
+    /// given `sks_encryption` setting.
+    pub fn new_with_sks_encryption(
+        key_store_dir: &Path,
+        sks_encryption: &SecretKeyStoreEncryption,
+        listener: UnixListener,
+        logger: ReplicaLogger,
+        metrics: Arc<CryptoMetrics>,
+    ) -> Self {
+        let node_secret_key_store = ProtoSecretKeyStore::open_with_encryption(
+            key_store_dir,
+            SKS_DATA_FILENAME,
+            sks_encryption,
+            Some(new_logger!(&logger)),
+        );
+        let canister_secret_key_store = ProtoSecretKeyStore::open_with_encryption(
             key_store_dir,
             CANISTER_SKS_DATA_FILENAME,
+            sks_encryption,
             Some(new_logger!(&logger)),
         );
         let public_key_store = ProtoPublicKeyStore::open(
@@ -541,10 +761,10 @@ impl
             node_secret_key_store,
             canister_secret_key_store,
             public_key_store,
-            metrics,
+            Arc::clone(&metrics),
             new_logger!(&logger),
         ));
-        Self::new_with_local_csp_vault(local_csp_server, listener, logger)
+        Self::new_with_local_csp_vault(local_csp_server, listener, logger, metrics)
     }
 }
 
@@ -554,13 +774,33 @@ impl<C: CspVault> TarpcCspVaultServerImpl<C> {
     /// Note: This MUST NOT be used in production as the secrecy of the secret
     /// key store is not guaranteed.
     pub fn new_for_test(local_csp_vault: Arc<C>, listener: UnixListener) -> Self {
-        Self::new_with_local_csp_vault(local_csp_vault, listener, no_op_logger())
+        Self::new_with_local_csp_vault(
+            local_csp_vault,
+            listener,
+            no_op_logger(),
+            Arc::new(CryptoMetrics::none()),
+        )
+    }
+
+    /// Configures the policy that the server enforces on calls that use
+    /// secret keys, i.e., rate limits, key usage restrictions, and audit logging.
+    pub fn with_config(mut self, config: CspVaultServerConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Sets the lookup of the node's subnet, which the policy requires if
+    /// the node signing key is restricted to a registered subnet.
+    pub fn with_subnet_membership(mut self, subnet_membership: Arc<dyn SubnetMembership>) -> Self {
+        self.subnet_membership = Some(subnet_membership);
+        self
     }
 
     fn new_with_local_csp_vault(
         local_csp_vault: Arc<C>,
         listener: UnixListener,
         logger: ReplicaLogger,
+        metrics: Arc<CryptoMetrics>,
     ) -> Self {
         let thread_pool = threadpool::Builder::new()
             .thread_name("ic-crypto-csp".to_string())
@@ -569,6 +809,9 @@ impl<C: CspVault> TarpcCspVaultServerImpl<C> {
             local_csp_vault,
             listener,
             thread_pool,
+            config: CspVaultServerConfig::default(),
+            subnet_membership: None,
+            metrics,
             logger,
         }
     }
@@ -578,6 +821,14 @@ impl<C: CspVault + 'static> TarpcCspVaultServerImpl<C> {
     pub async fn run(self) {
         // Wrap data in telegrams with a length header.
         let codec_builder = remote_vault_codec_builder();
+        let policy = ServerPolicy::new(
+            self.config,
+            self.subnet_membership,
+            self.metrics,
+            new_logger!(&self.logger),
+        )
+        .unwrap_or_else(|e| panic!("Invalid CSP vault server config: {}", e));
+        let policy = Arc::new(policy);
 
         // Listen for connections; spawns one `tokio` task per client.
         loop {
@@ -590,12 +841,14 @@ impl<C: CspVault + 'static> TarpcCspVaultServerImpl<C> {
             });
             let local_csp_vault = Arc::clone(&self.local_csp_vault);
             let thread_pool_handle = self.thread_pool.clone(); // creates a pool handle similar to Arc
+            let policy = Arc::clone(&policy);
             tokio::spawn(async move {
                 let framed = codec_builder.new_framed(conn);
                 let transport = serde_transport::new(framed, Bincode::default());
                 let worker = TarpcCspVaultServerWorker {
                     local_csp_vault,
                     thread_pool_handle,
+                    policy,
                 };
                 let channel_executor =
                     BaseChannel::with_defaults(transport).execute(worker.serve());
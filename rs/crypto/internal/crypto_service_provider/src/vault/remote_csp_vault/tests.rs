@@ -1038,3 +1038,202 @@ mod pks_and_sks {
         test_utils::pks_and_sks::should_return_error_for_pks_and_sks_contains_if_external_idkg_dealing_encryption_key_is_malformed(csp_vault);
     }
 }
+
+mod server_policy {
+    use super::*;
+    use crate::types::CspSignature;
+    use crate::vault::api::CspBasicSignatureError;
+    use crate::vault::api::CspBasicSignatureKeygenError;
+    use crate::vault::remote_csp_vault::SubnetMembership;
+    use crate::KeyId;
+    use ic_config::crypto::CspVaultServerConfig;
+    use ic_crypto_internal_threshold_sig_bls12381::api::ni_dkg_errors::CspDkgRetainThresholdKeysError;
+    use ic_types::crypto::AlgorithmId;
+    use ic_types::{NodeId, PrincipalId, SubnetId};
+    use std::collections::{BTreeMap, BTreeSet};
+
+    fn new_remote_csp_vault_with_config(
+        rt_handle: &tokio::runtime::Handle,
+        config: CspVaultServerConfig,
+    ) -> Arc<dyn CspVault> {
+        new_remote_csp_vault_with_config_and_subnet_membership(rt_handle, config, None)
+    }
+
+    fn new_remote_csp_vault_with_config_and_subnet_membership(
+        rt_handle: &tokio::runtime::Handle,
+        config: CspVaultServerConfig,
+        subnet_membership: Option<Arc<dyn SubnetMembership>>,
+    ) -> Arc<dyn CspVault> {
+        let (socket_path, sks_dir, listener) = setup_listener(rt_handle);
+        let mut server = TarpcCspVaultServerImpl::new_for_test(
+            LocalCspVault::builder().build_into_arc(),
+            listener,
+        )
+        .with_config(config);
+        if let Some(subnet_membership) = subnet_membership {
+            server = server.with_subnet_membership(subnet_membership);
+        }
+
+        rt_handle.spawn(async move {
+            let _move_temp_dir_here_to_ensure_it_is_not_cleaned_up = sks_dir;
+            server.run().await;
+        });
+        let remote_csp_vault = RemoteCspVault::new_for_test(&socket_path, rt_handle.clone(), None)
+            .expect("Could not create RemoteCspVault");
+        Arc::new(remote_csp_vault)
+    }
+
+    fn subnet_id(id: u64) -> SubnetId {
+        SubnetId::from(PrincipalId::new_subnet_test_id(id))
+    }
+
+    /// Puts every node on the same subnet.
+    struct SingleSubnet(SubnetId);
+
+    impl SubnetMembership for SingleSubnet {
+        fn subnet_id(&self, _node_id: NodeId) -> Result<Option<SubnetId>, String> {
+            Ok(Some(self.0))
+        }
+    }
+
+    fn sign_with_new_node_signing_key(
+        csp_vault: &Arc<dyn CspVault>,
+    ) -> Result<CspSignature, CspBasicSignatureError> {
+        let public_key = csp_vault
+            .gen_node_signing_key_pair()
+            .expect("failed to generate node signing key pair");
+        csp_vault.sign(
+            AlgorithmId::Ed25519,
+            &message_in_domain("block_domain"),
+            KeyId::try_from(&public_key).unwrap(),
+        )
+    }
+
+    fn message_in_domain(domain: &str) -> Vec<u8> {
+        let mut message = vec![domain.len() as u8];
+        message.extend_from_slice(domain.as_bytes());
+        message.extend_from_slice(b"message");
+        message
+    }
+
+    #[test]
+    fn should_refuse_disabled_method() {
+        let tokio_rt = new_tokio_runtime();
+        let csp_vault = new_remote_csp_vault_with_config(
+            tokio_rt.handle(),
+            CspVaultServerConfig {
+                disabled_methods: BTreeSet::from(["gen_node_signing_key_pair".to_string()]),
+                ..Default::default()
+            },
+        );
+
+        let result = csp_vault.gen_node_signing_key_pair();
+
+        assert_matches!(result,
+            Err(CspBasicSignatureKeygenError::InternalError { internal_error })
+            if internal_error.contains("method 'gen_node_signing_key_pair' is disabled")
+        );
+    }
+
+    #[test]
+    fn should_refuse_calls_exceeding_rate_limit() {
+        let tokio_rt = new_tokio_runtime();
+        let csp_vault = new_remote_csp_vault_with_config(
+            tokio_rt.handle(),
+            CspVaultServerConfig {
+                rate_limits_per_second: BTreeMap::from([(
+                    "gen_node_signing_key_pair".to_string(),
+                    1,
+                )]),
+                ..Default::default()
+            },
+        );
+
+        assert!(csp_vault.gen_node_signing_key_pair().is_ok());
+        let result = csp_vault.gen_node_signing_key_pair();
+
+        assert_matches!(result,
+            Err(CspBasicSignatureKeygenError::InternalError { internal_error })
+            if internal_error.contains("exceeded its rate limit of 1 calls per second")
+        );
+    }
+
+    #[test]
+    fn should_only_sign_with_node_signing_key_in_allowed_domains() {
+        let tokio_rt = new_tokio_runtime();
+        let csp_vault = new_remote_csp_vault_with_config(
+            tokio_rt.handle(),
+            CspVaultServerConfig {
+                allowed_node_signing_domains: Some(BTreeSet::from(["block_domain".to_string()])),
+                ..Default::default()
+            },
+        );
+        let key_id = KeyId::from([42; 32]);
+
+        let refused = csp_vault.sign(
+            AlgorithmId::Ed25519,
+            &message_in_domain("ic-request"),
+            key_id,
+        );
+        // The secret key does not exist, so an allowed call fails only after
+        // passing the policy check.
+        let allowed = csp_vault.sign(
+            AlgorithmId::Ed25519,
+            &message_in_domain("block_domain"),
+            key_id,
+        );
+
+        assert_matches!(refused,
+            Err(CspBasicSignatureError::InternalError { internal_error })
+            if internal_error.contains("node signing in domain Some(\"ic-request\") is not allowed")
+        );
+        assert_matches!(
+            allowed,
+            Err(CspBasicSignatureError::SecretKeyNotFound { .. })
+        );
+    }
+
+    #[test]
+    fn should_refuse_threshold_key_retention_if_disabled() {
+        let tokio_rt = new_tokio_runtime();
+        let csp_vault = new_remote_csp_vault_with_config(
+            tokio_rt.handle(),
+            CspVaultServerConfig {
+                disabled_methods: BTreeSet::from(["retain_threshold_keys_if_present".to_string()]),
+                ..Default::default()
+            },
+        );
+
+        let result = csp_vault.retain_threshold_keys_if_present(BTreeSet::new());
+
+        assert_matches!(result,
+            Err(CspDkgRetainThresholdKeysError::TransientInternalError(internal_error))
+            if internal_error.internal_error.contains("method 'retain_threshold_keys_if_present' is disabled")
+        );
+    }
+
+    #[test]
+    fn should_only_sign_with_node_signing_key_in_registered_subnet() {
+        let tokio_rt = new_tokio_runtime();
+        let config = CspVaultServerConfig {
+            registered_subnet_id: Some(subnet_id(1).get()),
+            ..Default::default()
+        };
+        let in_registered_subnet = new_remote_csp_vault_with_config_and_subnet_membership(
+            tokio_rt.handle(),
+            config.clone(),
+            Some(Arc::new(SingleSubnet(subnet_id(1)))),
+        );
+        let in_other_subnet = new_remote_csp_vault_with_config_and_subnet_membership(
+            tokio_rt.handle(),
+            config,
+            Some(Arc::new(SingleSubnet(subnet_id(2)))),
+        );
+
+        assert!(sign_with_new_node_signing_key(&in_registered_subnet).is_ok());
+        assert_matches!(sign_with_new_node_signing_key(&in_other_subnet),
+            Err(CspBasicSignatureError::InternalError { internal_error })
+            if internal_error.contains(&format!("is registered to subnet {}", subnet_id(1)))
+        );
+    }
+}
@@ -0,0 +1,36 @@
+//! vetKD implementation for the CSP
+use crate::api::CspVetKdProtocol;
+use crate::key_id::KeyId;
+use crate::types::CspPublicCoefficients;
+use crate::vault::api::VetKdEncryptedKeyShareCreationVaultError;
+use crate::Csp;
+use ic_crypto_internal_threshold_sig_bls12381 as clib;
+use ic_crypto_internal_types::sign::threshold_sig::public_coefficients::bls12_381::PublicCoefficientsBytes;
+use ic_logger::debug;
+use ic_types::crypto::canister_threshold_sig::ExtendedDerivationPath;
+
+impl CspVetKdProtocol for Csp {
+    fn create_encrypted_vetkd_key_share(
+        &self,
+        public_coefficients: CspPublicCoefficients,
+        transport_public_key: Vec<u8>,
+        derivation_path: ExtendedDerivationPath,
+        input: Vec<u8>,
+    ) -> Result<Vec<u8>, VetKdEncryptedKeyShareCreationVaultError> {
+        debug!(self.logger; crypto.method_name => "create_encrypted_vetkd_key_share");
+
+        let key_id = KeyId::from(&public_coefficients);
+        let master_public_key =
+            clib::api::combined_public_key(&PublicCoefficientsBytes::from(public_coefficients))
+                .map_err(|_| {
+                    VetKdEncryptedKeyShareCreationVaultError::InvalidArgumentMasterPublicKey
+                })?;
+        self.csp_vault.create_encrypted_vetkd_key_share(
+            key_id,
+            master_public_key.0.to_vec(),
+            transport_public_key,
+            derivation_path,
+            input,
+        )
+    }
+}
@@ -216,6 +216,21 @@ impl CryptoMetrics {
         }
     }
 
+    /// Observes the decision of the CSP vault server's policy on a call of
+    /// the vault method `method_name`.
+    pub fn observe_vault_server_policy_decision(
+        &self,
+        method_name: &str,
+        decision: VaultPolicyDecision,
+    ) {
+        if let Some(metrics) = &self.metrics {
+            metrics
+                .crypto_vault_server_policy_decisions
+                .with_label_values(&[method_name, &format!("{}", decision)])
+                .inc();
+        }
+    }
+
     /// Observes the cache statistics for the verification of threshold BLS12-381 signatures.
     pub fn observe_bls12_381_sig_cache_stats(&self, size: usize, hits: u64, misses: u64) {
         if let Some(metrics) = &self.metrics {
@@ -262,6 +277,8 @@ pub enum MetricsDomain {
     TlsHandshake,
     IdkgProtocol,
     ThresholdEcdsa,
+    ThresholdSchnorr,
+    VetKd,
     PublicSeed,
     KeyManagement,
 }
@@ -311,6 +328,14 @@ pub enum MessageType {
     Response,
 }
 
+/// The decision of the CSP vault server's policy on a call of a vault method.
+#[derive(Copy, Clone, Debug, EnumIter, Eq, IntoStaticStr, PartialOrd, Ord, PartialEq)]
+pub enum VaultPolicyDecision {
+    Allowed,
+    RateLimited,
+    Refused,
+}
+
 /// Keeps track of the number of node keys. This information is collected and provided to the
 /// metrics component. The type of keys for which the key counts are tracked are the following:
 ///  - `pk_registry`: The number of node public keys (and TLS x.509 certificates) stored
@@ -462,6 +487,11 @@ struct Metrics {
     /// The 'message_type' label indicates whether the message is a request or a response.
     pub crypto_vault_message_sizes: HistogramVec,
 
+    /// Counter vector of the decisions of the CSP vault server's policy.
+    /// The 'method_name' label indicates the vault method, such as `sign`.
+    /// The 'decision' label indicates whether the call was allowed, rate limited, or refused.
+    pub crypto_vault_server_policy_decisions: IntCounterVec,
+
     /// Metrics for the cache of successfully verified BLS12-381 threshold signatures.
     pub crypto_bls12_381_sig_cache_metrics: bls12_381_sig_cache::Metrics,
 
@@ -518,6 +548,13 @@ impl Display for MessageType {
     }
 }
 
+impl Display for VaultPolicyDecision {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let value: &'static str = self.into();
+        write!(f, "{}", value.to_case(Case::Snake))
+    }
+}
+
 impl Metrics {
     pub fn new(r: &MetricsRegistry) -> Self {
         let durations = r.histogram_vec(
@@ -612,6 +649,11 @@ impl Metrics {
                 ],
                 &["service_type", "message_type", "domain", "method_name"],
             ),
+            crypto_vault_server_policy_decisions: r.int_counter_vec(
+                "crypto_vault_server_policy_decisions",
+                "Decisions of the CSP vault server's policy on vault method calls",
+                &["method_name", "decision"],
+            ),
             crypto_bls12_381_sig_cache_metrics: bls12_381_sig_cache::Metrics {
                 cache_size: r.int_gauge(
                     "crypto_bls12_381_sig_cache_size",
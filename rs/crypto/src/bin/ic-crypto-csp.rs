@@ -1,10 +1,23 @@
-use clap::Parser;
+use clap::{Parser, Subcommand};
+use ic_config::crypto::SecretKeyStoreEncryption;
+use ic_config::metrics::{Config as MetricsConfig, Exporter};
 use ic_config::{Config, ConfigSource};
+use ic_crypto_internal_csp::secret_key_store::encryption::kek_provider_from_config;
+use ic_crypto_internal_csp::SubnetMembership;
 use ic_crypto_internal_logmon::metrics::CryptoMetrics;
+use ic_crypto_utils_threshold_sig_der::parse_threshold_sig_key;
+use ic_http_endpoints_metrics::MetricsHttpEndpoint;
+use ic_interfaces_registry::RegistryClient;
 use ic_logger::{info, new_replica_logger_from_config};
 use ic_metrics::MetricsRegistry;
+use ic_registry_client::client::RegistryClientImpl;
+use ic_registry_client_helpers::node::NodeRegistry;
+use ic_registry_nns_data_provider::create_nns_data_provider;
+use ic_types::{NodeId, SubnetId};
 use std::os::unix::io::FromRawFd;
 use std::path::PathBuf;
+use std::sync::Arc;
+use url::Url;
 
 const IC_CRYPTO_CSP_SOCKET_NAME: &str = "ic-crypto-csp.socket";
 
@@ -20,6 +33,29 @@ struct Opts {
     /// Sets the replica configuration file
     #[clap(long = "replica-config-file", parse(from_os_str))]
     config: PathBuf,
+
+    #[clap(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Re-encrypts the secret key stores with a new key-encryption key (KEK)
+    /// and exits. The CspVault server must not be running, and the replica
+    /// configuration must be updated to the new KEK afterwards.
+    RotateSksKek {
+        /// File containing the new 32-byte KEK
+        #[clap(
+            long = "new-kek-file",
+            parse(from_os_str),
+            conflicts_with = "new_kek_provider_socket",
+            required_unless_present = "new_kek_provider_socket"
+        )]
+        new_kek_file: Option<PathBuf>,
+        /// Unix socket of the key provider serving the new 32-byte KEK
+        #[clap(long = "new-kek-provider-socket", parse(from_os_str))]
+        new_kek_provider_socket: Option<PathBuf>,
+    },
 }
 
 #[tokio::main]
@@ -29,6 +65,20 @@ async fn main() {
 
     let sks_dir = ic_config.crypto.crypto_root.as_path();
 
+    if let Some(Command::RotateSksKek {
+        new_kek_file,
+        new_kek_provider_socket,
+    }) = opts.command
+    {
+        let new_encryption = match (new_kek_file, new_kek_provider_socket) {
+            (Some(new_kek_file), _) => SecretKeyStoreEncryption::KekFile(new_kek_file),
+            (None, Some(socket_path)) => SecretKeyStoreEncryption::KekProviderSocket(socket_path),
+            (None, None) => unreachable!("clap requires one of the new KEK options"),
+        };
+        rotate_sks_kek(&ic_config, &new_encryption);
+        return;
+    }
+
     ensure_single_named_systemd_socket(IC_CRYPTO_CSP_SOCKET_NAME);
     let systemd_socket_listener = listener_from_first_systemd_socket();
 
@@ -47,9 +97,124 @@ async fn main() {
     // We abort the whole program with a core dump if a single thread panics.
     // This way we can capture all the context if a critical error happens.
     abort_on_panic();
+    let server_config = ic_config.crypto.csp_vault_server.clone();
+    // The endpoint must be kept in scope for the metrics to remain exposed.
+    let _metrics_endpoint = server_config.metrics_addr.map(|metrics_addr| {
+        info!(logger;
+            crypto.method_name => "main",
+            crypto.description => format!("Exposing CspVault server metrics at {}", metrics_addr)
+        );
+        MetricsHttpEndpoint::new_insecure(
+            tokio::runtime::Handle::current(),
+            MetricsConfig {
+                exporter: Exporter::Http(metrics_addr),
+                ..Default::default()
+            },
+            MetricsRegistry::global(),
+            &logger.inner_logger.root,
+        )
+    });
     let metrics = CryptoMetrics::new(Some(&MetricsRegistry::global()));
-    ic_crypto_internal_csp::run_csp_vault_server(sks_dir, systemd_socket_listener, logger, metrics)
-        .await;
+    // The node signing key is restricted to the registered subnet by looking
+    // up the node's subnet in the registry. The lookup must not rely on the
+    // registry local store, which is written by the node's other processes.
+    let subnet_membership = server_config
+        .registered_subnet_id
+        .map(|_| Arc::new(RegistrySubnetMembership::new(&ic_config)) as Arc<dyn SubnetMembership>);
+    ic_crypto_internal_csp::run_csp_vault_server(
+        sks_dir,
+        &ic_config.crypto.secret_key_store_encryption,
+        systemd_socket_listener,
+        logger,
+        metrics,
+        server_config,
+        subnet_membership,
+    )
+    .await;
+}
+
+/// Looks up the subnet of a node at the latest registry version known to the
+/// CspVault server.
+///
+/// The registry is read-only and fetched directly from the registry canister
+/// on the NNS. Only updates certified by the NNS subnet, whose public key is
+/// pinned in the config, are accepted.
+struct RegistrySubnetMembership {
+    registry_client: RegistryClientImpl,
+}
+
+impl RegistrySubnetMembership {
+    fn new(ic_config: &Config) -> Self {
+        let (nns_urls, nns_pub_key_pem) = match (
+            &ic_config.registration.nns_url,
+            &ic_config.registration.nns_pub_key_pem,
+        ) {
+            (Some(nns_urls), Some(nns_pub_key_pem)) => (nns_urls, nns_pub_key_pem),
+            _ => panic!(
+                "The CspVault server config restricts node signing to a registered subnet, \
+                 but the replica config lacks the NNS URLs or the NNS public key"
+            ),
+        };
+        let nns_urls = nns_urls
+            .split(',')
+            .map(|url| {
+                Url::parse(url).unwrap_or_else(|e| panic!("Invalid NNS URL '{}': {}", url, e))
+            })
+            .collect();
+        let nns_public_key = parse_threshold_sig_key(nns_pub_key_pem).unwrap_or_else(|e| {
+            panic!(
+                "Failed to parse the NNS public key in '{}': {}",
+                nns_pub_key_pem.display(),
+                e
+            )
+        });
+        let data_provider = create_nns_data_provider(
+            tokio::runtime::Handle::current(),
+            nns_urls,
+            Some(nns_public_key),
+        );
+        let registry_client =
+            RegistryClientImpl::new(data_provider, Some(&MetricsRegistry::global()));
+        registry_client
+            .fetch_and_start_polling()
+            .unwrap_or_else(|e| panic!("fetch_and_start_polling failed: {}", e));
+        Self { registry_client }
+    }
+}
+
+impl SubnetMembership for RegistrySubnetMembership {
+    fn subnet_id(&self, node_id: NodeId) -> Result<Option<SubnetId>, String> {
+        let registry_version = self.registry_client.get_latest_version();
+        self.registry_client
+            .get_subnet_id_from_node_id(node_id, registry_version)
+            .map_err(|e| {
+                format!(
+                    "failed to look up the subnet of node {} at registry version {}: {}",
+                    node_id, registry_version, e
+                )
+            })
+    }
+}
+
+fn rotate_sks_kek(ic_config: &Config, new_encryption: &SecretKeyStoreEncryption) {
+    let (logger, _async_log_guard) = new_replica_logger_from_config(&ic_config.csp_vault_logger);
+    let new_kek_provider =
+        kek_provider_from_config(new_encryption).expect("new encryption must use a KEK");
+    ic_crypto_internal_csp::rotate_secret_key_stores_kek(
+        ic_config.crypto.crypto_root.as_path(),
+        &ic_config.crypto.secret_key_store_encryption,
+        new_kek_provider,
+        logger.clone(),
+    )
+    .unwrap_or_else(|e| panic!("Failed to rotate the key-encryption key: {}", e));
+    info!(logger;
+        crypto.method_name => "rotate_sks_kek",
+        crypto.description => format!(
+            "Re-encrypted the secret key stores in '{}', update the replica configuration to {:?}",
+            ic_config.crypto.crypto_root.display(),
+            new_encryption
+        )
+    );
 }
 
 /// Aborts the whole program with a core dump if a single thread panics.
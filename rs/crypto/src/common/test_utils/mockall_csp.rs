@@ -8,7 +8,8 @@ use ic_base_types::RegistryVersion;
 use ic_crypto_internal_csp::api::{
     CspCreateMEGaKeyError, CspIDkgProtocol, CspKeyGenerator, CspPublicAndSecretKeyStoreChecker,
     CspSecretKeyStoreChecker, CspSigVerifier, CspSigner, CspThresholdEcdsaSigVerifier,
-    CspThresholdEcdsaSigner, CspThresholdSignError, CspTlsHandshakeSignerProvider, NiDkgCspClient,
+    CspThresholdEcdsaSigner, CspThresholdSchnorrSigVerifier, CspThresholdSchnorrSigner,
+    CspThresholdSignError, CspTlsHandshakeSignerProvider, CspVetKdProtocol, NiDkgCspClient,
     NodePublicKeyData, ThresholdSignatureCspClient,
 };
 use ic_crypto_internal_csp::api::{
@@ -19,6 +20,7 @@ use ic_crypto_internal_csp::types::ExternalPublicKeys;
 use ic_crypto_internal_csp::types::{CspPop, CspPublicCoefficients, CspPublicKey, CspSignature};
 use ic_crypto_internal_csp::vault::api::PksAndSksCompleteError;
 use ic_crypto_internal_csp::vault::api::PksAndSksContainsErrors;
+use ic_crypto_internal_csp::vault::api::VetKdEncryptedKeyShareCreationVaultError;
 use ic_crypto_internal_csp::TlsHandshakeCspVault;
 use ic_crypto_internal_threshold_sig_bls12381::api::ni_dkg_errors::{
     CspDkgCreateDealingError, CspDkgCreateFsKeyError, CspDkgCreateReshareDealingError,
@@ -43,6 +45,8 @@ use ic_types::crypto::canister_threshold_sig::error::{
     IDkgVerifyDealingPrivateError, IDkgVerifyDealingPublicError, IDkgVerifyOpeningError,
     IDkgVerifyTranscriptError, ThresholdEcdsaCombineSigSharesError, ThresholdEcdsaSignShareError,
     ThresholdEcdsaVerifyCombinedSignatureError, ThresholdEcdsaVerifySigShareError,
+    ThresholdSchnorrCombineSigSharesError, ThresholdSchnorrSignShareError,
+    ThresholdSchnorrVerifyCombinedSignatureError, ThresholdSchnorrVerifySigShareError,
 };
 use ic_types::crypto::canister_threshold_sig::ExtendedDerivationPath;
 use ic_types::crypto::threshold_sig::ni_dkg::NiDkgId;
@@ -433,4 +437,63 @@ mock! {
             algorithm_id: AlgorithmId,
         ) -> Result<(), ThresholdEcdsaVerifyCombinedSignatureError>;
     }
+
+    pub trait CspThresholdSchnorrSigner {
+        fn schnorr_sign_share(
+            &self,
+            derivation_path: &ExtendedDerivationPath,
+            message: &[u8],
+            nonce: &Randomness,
+            key: &IDkgTranscriptInternal,
+            presig: &IDkgTranscriptInternal,
+            algorithm_id: AlgorithmId,
+        ) -> Result<Vec<u8>, ThresholdSchnorrSignShareError>;
+    }
+
+    pub trait CspThresholdSchnorrSigVerifier {
+        fn schnorr_combine_sig_shares(
+            &self,
+            derivation_path: &ExtendedDerivationPath,
+            message: &[u8],
+            nonce: &Randomness,
+            key: &IDkgTranscriptInternal,
+            presig: &IDkgTranscriptInternal,
+            reconstruction_threshold: NumberOfNodes,
+            sig_shares: &BTreeMap<NodeIndex, Vec<u8>>,
+            algorithm_id: AlgorithmId,
+        ) -> Result<Vec<u8>, ThresholdSchnorrCombineSigSharesError>;
+
+        fn schnorr_verify_sig_share(
+            &self,
+            share: &[u8],
+            signer_index: NodeIndex,
+            derivation_path: &ExtendedDerivationPath,
+            message: &[u8],
+            nonce: &Randomness,
+            key: &IDkgTranscriptInternal,
+            presig: &IDkgTranscriptInternal,
+            algorithm_id: AlgorithmId,
+        ) -> Result<(), ThresholdSchnorrVerifySigShareError>;
+
+        fn schnorr_verify_combined_signature(
+            &self,
+            signature: &[u8],
+            derivation_path: &ExtendedDerivationPath,
+            message: &[u8],
+            nonce: &Randomness,
+            key: &IDkgTranscriptInternal,
+            presig: &IDkgTranscriptInternal,
+            algorithm_id: AlgorithmId,
+        ) -> Result<(), ThresholdSchnorrVerifyCombinedSignatureError>;
+    }
+
+    pub trait CspVetKdProtocol {
+        fn create_encrypted_vetkd_key_share(
+            &self,
+            public_coefficients: CspPublicCoefficients,
+            transport_public_key: Vec<u8>,
+            derivation_path: ExtendedDerivationPath,
+            input: Vec<u8>,
+        ) -> Result<Vec<u8>, VetKdEncryptedKeyShareCreationVaultError>;
+    }
 }
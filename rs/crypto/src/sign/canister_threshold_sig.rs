@@ -1,5 +1,6 @@
 pub mod ecdsa;
 mod idkg;
+pub mod schnorr;
 
 pub use idkg::{
     fetch_idkg_dealing_encryption_public_key_from_registry, get_mega_pubkey,
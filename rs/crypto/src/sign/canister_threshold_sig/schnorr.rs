@@ -0,0 +1,195 @@
+//! Implementations of ThresholdSchnorrSigner
+use ic_crypto_internal_csp::api::{CspThresholdSchnorrSigVerifier, CspThresholdSchnorrSigner};
+use ic_crypto_internal_threshold_sig_ecdsa::IDkgTranscriptInternal;
+use ic_types::crypto::canister_threshold_sig::error::{
+    ThresholdSchnorrCombineSigSharesError, ThresholdSchnorrSignShareError,
+    ThresholdSchnorrVerifyCombinedSignatureError, ThresholdSchnorrVerifySigShareError,
+};
+use ic_types::crypto::canister_threshold_sig::idkg::{IDkgReceivers, IDkgTranscript};
+use ic_types::crypto::canister_threshold_sig::{
+    ThresholdSchnorrCombinedSignature, ThresholdSchnorrSigInputs, ThresholdSchnorrSigShare,
+};
+use ic_types::{NodeId, NodeIndex};
+use std::collections::{BTreeMap, BTreeSet};
+use std::convert::TryFrom;
+
+pub fn sign_share<C: CspThresholdSchnorrSigner>(
+    csp_client: &C,
+    self_node_id: &NodeId,
+    inputs: &ThresholdSchnorrSigInputs,
+) -> Result<ThresholdSchnorrSigShare, ThresholdSchnorrSignShareError> {
+    ensure_self_was_receiver(self_node_id, inputs.receivers().get())?;
+
+    let presig =
+        internal_transcript_from_transcript(inputs.presig_transcript().blinder_unmasked())?;
+    let key = internal_transcript_from_transcript(inputs.key_transcript())?;
+
+    let sig_share_raw = csp_client.schnorr_sign_share(
+        inputs.derivation_path(),
+        inputs.message(),
+        inputs.nonce(),
+        &key,
+        &presig,
+        inputs.algorithm_id(),
+    )?;
+
+    Ok(ThresholdSchnorrSigShare { sig_share_raw })
+}
+
+pub fn verify_sig_share<C: CspThresholdSchnorrSigVerifier>(
+    csp_client: &C,
+    signer: NodeId,
+    inputs: &ThresholdSchnorrSigInputs,
+    share: &ThresholdSchnorrSigShare,
+) -> Result<(), ThresholdSchnorrVerifySigShareError> {
+    let presig =
+        internal_transcript_from_transcript(inputs.presig_transcript().blinder_unmasked())?;
+    let key = internal_transcript_from_transcript(inputs.key_transcript())?;
+
+    let signer_index = inputs.key_transcript().index_for_signer_id(signer).ok_or(
+        ThresholdSchnorrVerifySigShareError::InvalidArgumentMissingSignerInTranscript {
+            signer_id: signer,
+        },
+    )?;
+
+    csp_client.schnorr_verify_sig_share(
+        &share.sig_share_raw,
+        signer_index,
+        inputs.derivation_path(),
+        inputs.message(),
+        inputs.nonce(),
+        &key,
+        &presig,
+        inputs.algorithm_id(),
+    )
+}
+
+pub fn verify_combined_signature<C: CspThresholdSchnorrSigVerifier>(
+    csp_client: &C,
+    inputs: &ThresholdSchnorrSigInputs,
+    signature: &ThresholdSchnorrCombinedSignature,
+) -> Result<(), ThresholdSchnorrVerifyCombinedSignatureError> {
+    let presig =
+        internal_transcript_from_transcript(inputs.presig_transcript().blinder_unmasked())?;
+    let key = internal_transcript_from_transcript(inputs.key_transcript())?;
+
+    csp_client.schnorr_verify_combined_signature(
+        &signature.signature,
+        inputs.derivation_path(),
+        inputs.message(),
+        inputs.nonce(),
+        &key,
+        &presig,
+        inputs.algorithm_id(),
+    )
+}
+
+pub fn combine_sig_shares<C: CspThresholdSchnorrSigVerifier>(
+    csp_client: &C,
+    inputs: &ThresholdSchnorrSigInputs,
+    shares: &BTreeMap<NodeId, ThresholdSchnorrSigShare>,
+) -> Result<ThresholdSchnorrCombinedSignature, ThresholdSchnorrCombineSigSharesError> {
+    ensure_sufficient_sig_shares_collected(inputs, shares)?;
+
+    let presig =
+        internal_transcript_from_transcript(inputs.presig_transcript().blinder_unmasked())?;
+    let key = internal_transcript_from_transcript(inputs.key_transcript())?;
+    let shares_by_index = sig_shares_by_index(shares, inputs.receivers())?;
+
+    let signature = csp_client.schnorr_combine_sig_shares(
+        inputs.derivation_path(),
+        inputs.message(),
+        inputs.nonce(),
+        &key,
+        &presig,
+        inputs.reconstruction_threshold(),
+        &shares_by_index,
+        inputs.algorithm_id(),
+    )?;
+
+    Ok(ThresholdSchnorrCombinedSignature { signature })
+}
+
+fn ensure_self_was_receiver(
+    self_node_id: &NodeId,
+    receivers: &BTreeSet<NodeId>,
+) -> Result<(), ThresholdSchnorrSignShareError> {
+    if receivers.contains(self_node_id) {
+        Ok(())
+    } else {
+        Err(ThresholdSchnorrSignShareError::NotAReceiver)
+    }
+}
+
+fn ensure_sufficient_sig_shares_collected(
+    inputs: &ThresholdSchnorrSigInputs,
+    shares: &BTreeMap<NodeId, ThresholdSchnorrSigShare>,
+) -> Result<(), ThresholdSchnorrCombineSigSharesError> {
+    if shares.len() < inputs.reconstruction_threshold().get() as usize {
+        Err(
+            ThresholdSchnorrCombineSigSharesError::UnsatisfiedReconstructionThreshold {
+                threshold: inputs.reconstruction_threshold().get(),
+                share_count: shares.len(),
+            },
+        )
+    } else {
+        Ok(())
+    }
+}
+
+/// Map each raw signature share by signer index (rather than signer Id).
+fn sig_shares_by_index(
+    shares: &BTreeMap<NodeId, ThresholdSchnorrSigShare>,
+    receivers: &IDkgReceivers,
+) -> Result<BTreeMap<NodeIndex, Vec<u8>>, ThresholdSchnorrCombineSigSharesError> {
+    shares
+        .iter()
+        .map(|(&id, share)| {
+            let index = receivers
+                .position(id)
+                .ok_or(ThresholdSchnorrCombineSigSharesError::SignerNotAllowed { node_id: id })?;
+            Ok((index, share.sig_share_raw.clone()))
+        })
+        .collect()
+}
+
+fn internal_transcript_from_transcript(
+    transcript: &IDkgTranscript,
+) -> Result<IDkgTranscriptInternal, TranscriptDeserializationError> {
+    IDkgTranscriptInternal::try_from(transcript)
+        .map_err(|e| TranscriptDeserializationError(format!("{:?}", e)))
+}
+
+struct TranscriptDeserializationError(String);
+
+impl From<TranscriptDeserializationError> for ThresholdSchnorrSignShareError {
+    fn from(transcript_deserialization_error: TranscriptDeserializationError) -> Self {
+        ThresholdSchnorrSignShareError::SerializationError {
+            internal_error: transcript_deserialization_error.0,
+        }
+    }
+}
+
+impl From<TranscriptDeserializationError> for ThresholdSchnorrVerifySigShareError {
+    fn from(transcript_deserialization_error: TranscriptDeserializationError) -> Self {
+        ThresholdSchnorrVerifySigShareError::SerializationError {
+            internal_error: transcript_deserialization_error.0,
+        }
+    }
+}
+
+impl From<TranscriptDeserializationError> for ThresholdSchnorrCombineSigSharesError {
+    fn from(transcript_deserialization_error: TranscriptDeserializationError) -> Self {
+        ThresholdSchnorrCombineSigSharesError::SerializationError {
+            internal_error: transcript_deserialization_error.0,
+        }
+    }
+}
+
+impl From<TranscriptDeserializationError> for ThresholdSchnorrVerifyCombinedSignatureError {
+    fn from(transcript_deserialization_error: TranscriptDeserializationError) -> Self {
+        ThresholdSchnorrVerifyCombinedSignatureError::SerializationError {
+            internal_error: transcript_deserialization_error.0,
+        }
+    }
+}
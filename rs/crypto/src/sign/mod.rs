@@ -12,18 +12,26 @@ use ic_crypto_internal_threshold_sig_bls12381::api::bls_signature_cache_statisti
 use ic_interfaces::crypto::{
     BasicSigVerifier, BasicSigVerifierByPublicKey, BasicSigner, CanisterSigVerifier,
     MultiSigVerifier, MultiSigner, ThresholdEcdsaSigVerifier, ThresholdEcdsaSigner,
-    ThresholdSigVerifier, ThresholdSigVerifierByPublicKey, ThresholdSigner,
+    ThresholdSchnorrSigVerifier, ThresholdSchnorrSigner, ThresholdSigVerifier,
+    ThresholdSigVerifierByPublicKey, ThresholdSigner, VetKdProtocol,
 };
 use ic_logger::{debug, new_logger};
 use ic_types::crypto::canister_threshold_sig::error::{
     ThresholdEcdsaCombineSigSharesError, ThresholdEcdsaSignShareError,
     ThresholdEcdsaVerifyCombinedSignatureError, ThresholdEcdsaVerifySigShareError,
+    ThresholdSchnorrCombineSigSharesError, ThresholdSchnorrSignShareError,
+    ThresholdSchnorrVerifyCombinedSignatureError, ThresholdSchnorrVerifySigShareError,
 };
 use ic_types::crypto::canister_threshold_sig::{
     ThresholdEcdsaCombinedSignature, ThresholdEcdsaSigInputs, ThresholdEcdsaSigShare,
+    ThresholdSchnorrCombinedSignature, ThresholdSchnorrSigInputs, ThresholdSchnorrSigShare,
 };
 use ic_types::crypto::threshold_sig::errors::threshold_sign_error::ThresholdSignError;
 use ic_types::crypto::threshold_sig::ni_dkg::DkgId;
+use ic_types::crypto::vetkd::{
+    VetKdArgs, VetKdEncryptedKey, VetKdEncryptedKeyShare, VetKdKeyShareCombinationError,
+    VetKdKeyShareCreationError, VetKdKeyShareVerificationError, VetKdKeyVerificationError,
+};
 use ic_types::crypto::KeyPurpose::CommitteeSigning;
 use ic_types::crypto::{
     AlgorithmId, BasicSig, BasicSigOf, CanisterSigOf, CombinedMultiSig, CombinedMultiSigOf,
@@ -41,6 +49,7 @@ mod canister_sig;
 mod canister_threshold_sig;
 mod multi_sig;
 mod threshold_sig;
+mod vetkd;
 
 pub use canister_threshold_sig::{
     fetch_idkg_dealing_encryption_public_key_from_registry, get_mega_pubkey,
@@ -841,6 +850,299 @@ impl<C: CryptoServiceProvider> ThresholdEcdsaSigVerifier for CryptoComponentImpl
     }
 }
 
+impl<C: CryptoServiceProvider> ThresholdSchnorrSigner for CryptoComponentImpl<C> {
+    fn create_schnorr_sig_share(
+        &self,
+        inputs: &ThresholdSchnorrSigInputs,
+    ) -> Result<ThresholdSchnorrSigShare, ThresholdSchnorrSignShareError> {
+        let log_id = get_log_id(&self.logger, module_path!());
+        let logger = new_logger!(&self.logger;
+            crypto.log_id => log_id,
+            crypto.trait_name => "ThresholdSchnorrSigner",
+            crypto.method_name => "create_schnorr_sig_share",
+        );
+        debug!(logger;
+            crypto.description => "start",
+            crypto.signature_inputs => format!("{:?}", inputs),
+        );
+        let start_time = self.metrics.now();
+        let result = canister_threshold_sig::schnorr::sign_share(&self.csp, &self.node_id, inputs);
+        self.metrics.observe_duration_seconds(
+            MetricsDomain::ThresholdSchnorr,
+            MetricsScope::Full,
+            "create_schnorr_sig_share",
+            MetricsResult::from(&result),
+            start_time,
+        );
+        debug!(logger;
+            crypto.description => "end",
+            crypto.is_ok => result.is_ok(),
+            crypto.error => log_err(result.as_ref().err()),
+            crypto.signature_shares => log_ok_content(&result),
+        );
+        result
+    }
+}
+
+impl<C: CryptoServiceProvider> ThresholdSchnorrSigVerifier for CryptoComponentImpl<C> {
+    fn verify_schnorr_sig_share(
+        &self,
+        signer: NodeId,
+        inputs: &ThresholdSchnorrSigInputs,
+        share: &ThresholdSchnorrSigShare,
+    ) -> Result<(), ThresholdSchnorrVerifySigShareError> {
+        let log_id = get_log_id(&self.logger, module_path!());
+        let logger = new_logger!(&self.logger;
+            crypto.log_id => log_id,
+            crypto.trait_name => "ThresholdSchnorrSigVerifier",
+            crypto.method_name => "verify_schnorr_sig_share",
+        );
+        debug!(logger;
+            crypto.description => "start",
+            crypto.signature_shares => format!("{:?}", share),
+            crypto.signer => format!("{:?}", signer),
+            crypto.signature_inputs => format!("{:?}", inputs),
+        );
+        let start_time = self.metrics.now();
+        let result =
+            canister_threshold_sig::schnorr::verify_sig_share(&self.csp, signer, inputs, share);
+        self.metrics.observe_duration_seconds(
+            MetricsDomain::ThresholdSchnorr,
+            MetricsScope::Full,
+            "verify_schnorr_sig_share",
+            MetricsResult::from(&result),
+            start_time,
+        );
+        debug!(logger;
+            crypto.description => "end",
+            crypto.is_ok => result.is_ok(),
+            crypto.error => log_err(result.as_ref().err()),
+        );
+        result
+    }
+
+    fn combine_schnorr_sig_shares(
+        &self,
+        inputs: &ThresholdSchnorrSigInputs,
+        shares: &BTreeMap<NodeId, ThresholdSchnorrSigShare>,
+    ) -> Result<ThresholdSchnorrCombinedSignature, ThresholdSchnorrCombineSigSharesError> {
+        let log_id = get_log_id(&self.logger, module_path!());
+        let logger = new_logger!(&self.logger;
+            crypto.log_id => log_id,
+            crypto.trait_name => "ThresholdSchnorrSigVerifier",
+            crypto.method_name => "combine_schnorr_sig_shares",
+        );
+        debug!(logger;
+            crypto.description => "start",
+            crypto.signature_inputs => format!("{:?}", inputs),
+            crypto.signature_shares => format!{"{:?}", shares},
+        );
+        let start_time = self.metrics.now();
+        let result = canister_threshold_sig::schnorr::combine_sig_shares(&self.csp, inputs, shares);
+        self.metrics.observe_duration_seconds(
+            MetricsDomain::ThresholdSchnorr,
+            MetricsScope::Full,
+            "combine_schnorr_sig_shares",
+            MetricsResult::from(&result),
+            start_time,
+        );
+        debug!(logger;
+            crypto.description => "end",
+            crypto.is_ok => result.is_ok(),
+            crypto.error => log_err(result.as_ref().err()),
+            crypto.signature => log_ok_content(&result),
+        );
+        result
+    }
+
+    fn verify_schnorr_combined_sig(
+        &self,
+        inputs: &ThresholdSchnorrSigInputs,
+        signature: &ThresholdSchnorrCombinedSignature,
+    ) -> Result<(), ThresholdSchnorrVerifyCombinedSignatureError> {
+        let log_id = get_log_id(&self.logger, module_path!());
+        let logger = new_logger!(&self.logger;
+            crypto.log_id => log_id,
+            crypto.trait_name => "ThresholdSchnorrSigVerifier",
+            crypto.method_name => "verify_schnorr_combined_sig",
+        );
+        debug!(logger;
+            crypto.description => "start",
+            crypto.signature_inputs => format!("{:?}", inputs),
+            crypto.signature => format!("{:?}", signature),
+        );
+        let start_time = self.metrics.now();
+        let result = canister_threshold_sig::schnorr::verify_combined_signature(
+            &self.csp, inputs, signature,
+        );
+        self.metrics.observe_duration_seconds(
+            MetricsDomain::ThresholdSchnorr,
+            MetricsScope::Full,
+            "verify_schnorr_combined_sig",
+            MetricsResult::from(&result),
+            start_time,
+        );
+        debug!(logger;
+            crypto.description => "end",
+            crypto.is_ok => result.is_ok(),
+            crypto.error => log_err(result.as_ref().err()),
+        );
+        result
+    }
+}
+
+impl<C: CryptoServiceProvider> VetKdProtocol for CryptoComponentImpl<C> {
+    fn create_encrypted_key_share(
+        &self,
+        args: VetKdArgs,
+    ) -> Result<VetKdEncryptedKeyShare, VetKdKeyShareCreationError> {
+        let log_id = get_log_id(&self.logger, module_path!());
+        let logger = new_logger!(&self.logger;
+            crypto.log_id => log_id,
+            crypto.trait_name => "VetKdProtocol",
+            crypto.method_name => "create_encrypted_key_share",
+        );
+        debug!(logger;
+            crypto.description => "start",
+            crypto.signature_inputs => format!("{}", args),
+        );
+        let start_time = self.metrics.now();
+        let result = vetkd::create_encrypted_key_share(
+            &self.lockable_threshold_sig_data_store,
+            &self.csp,
+            args,
+        );
+        self.metrics.observe_duration_seconds(
+            MetricsDomain::VetKd,
+            MetricsScope::Full,
+            "create_encrypted_key_share",
+            MetricsResult::from(&result),
+            start_time,
+        );
+        debug!(logger;
+            crypto.description => "end",
+            crypto.is_ok => result.is_ok(),
+            crypto.error => log_err(result.as_ref().err()),
+            crypto.signature_shares => log_ok_content(&result),
+        );
+        result
+    }
+
+    fn verify_encrypted_key_share(
+        &self,
+        signer: NodeId,
+        key_share: &VetKdEncryptedKeyShare,
+        args: &VetKdArgs,
+    ) -> Result<(), VetKdKeyShareVerificationError> {
+        let log_id = get_log_id(&self.logger, module_path!());
+        let logger = new_logger!(&self.logger;
+            crypto.log_id => log_id,
+            crypto.trait_name => "VetKdProtocol",
+            crypto.method_name => "verify_encrypted_key_share",
+        );
+        debug!(logger;
+            crypto.description => "start",
+            crypto.signature_inputs => format!("{}", args),
+            crypto.signature_shares => format!("{}", key_share),
+            crypto.signer => format!("{:?}", signer),
+        );
+        let start_time = self.metrics.now();
+        let result = vetkd::verify_encrypted_key_share(
+            &self.lockable_threshold_sig_data_store,
+            &self.csp,
+            signer,
+            key_share,
+            args,
+        );
+        self.metrics.observe_duration_seconds(
+            MetricsDomain::VetKd,
+            MetricsScope::Full,
+            "verify_encrypted_key_share",
+            MetricsResult::from(&result),
+            start_time,
+        );
+        debug!(logger;
+            crypto.description => "end",
+            crypto.is_ok => result.is_ok(),
+            crypto.error => log_err(result.as_ref().err()),
+        );
+        result
+    }
+
+    fn combine_encrypted_key_shares(
+        &self,
+        shares: &BTreeMap<NodeId, VetKdEncryptedKeyShare>,
+        args: &VetKdArgs,
+    ) -> Result<VetKdEncryptedKey, VetKdKeyShareCombinationError> {
+        let log_id = get_log_id(&self.logger, module_path!());
+        let logger = new_logger!(&self.logger;
+            crypto.log_id => log_id,
+            crypto.trait_name => "VetKdProtocol",
+            crypto.method_name => "combine_encrypted_key_shares",
+        );
+        debug!(logger;
+            crypto.description => "start",
+            crypto.signature_inputs => format!("{}", args),
+            crypto.signature_shares => format!("{:?}", shares),
+        );
+        let start_time = self.metrics.now();
+        let result = vetkd::combine_encrypted_key_shares(
+            &self.lockable_threshold_sig_data_store,
+            &self.csp,
+            shares,
+            args,
+        );
+        self.metrics.observe_duration_seconds(
+            MetricsDomain::VetKd,
+            MetricsScope::Full,
+            "combine_encrypted_key_shares",
+            MetricsResult::from(&result),
+            start_time,
+        );
+        debug!(logger;
+            crypto.description => "end",
+            crypto.is_ok => result.is_ok(),
+            crypto.error => log_err(result.as_ref().err()),
+            crypto.signature => log_ok_content(&result),
+        );
+        result
+    }
+
+    fn verify_encrypted_key(
+        &self,
+        key: &VetKdEncryptedKey,
+        args: &VetKdArgs,
+    ) -> Result<(), VetKdKeyVerificationError> {
+        let log_id = get_log_id(&self.logger, module_path!());
+        let logger = new_logger!(&self.logger;
+            crypto.log_id => log_id,
+            crypto.trait_name => "VetKdProtocol",
+            crypto.method_name => "verify_encrypted_key",
+        );
+        debug!(logger;
+            crypto.description => "start",
+            crypto.signature_inputs => format!("{}", args),
+            crypto.signature => format!("{}", key),
+        );
+        let start_time = self.metrics.now();
+        let result =
+            vetkd::verify_encrypted_key(&self.lockable_threshold_sig_data_store, key, args);
+        self.metrics.observe_duration_seconds(
+            MetricsDomain::VetKd,
+            MetricsScope::Full,
+            "verify_encrypted_key",
+            MetricsResult::from(&result),
+            start_time,
+        );
+        debug!(logger;
+            crypto.description => "end",
+            crypto.is_ok => result.is_ok(),
+            crypto.error => log_err(result.as_ref().err()),
+        );
+        result
+    }
+}
+
 fn log_err<T: fmt::Display>(error_option: Option<&T>) -> String {
     if let Some(error) = error_option {
         return format!("{}", error);
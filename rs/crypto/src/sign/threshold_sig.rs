@@ -41,7 +41,7 @@ impl ThresholdSignerInternal {
 
 // Use this if only the pub coeffs are needed from the store. If also indices
 // are required, use transcript_data_from_store instead.
-fn pub_coeffs_from_store(
+pub(super) fn pub_coeffs_from_store(
     dkg_id: DkgId,
     lockable_threshold_sig_data_store: &LockableThresholdSigDataStore,
 ) -> Result<CspPublicCoefficients, ThresholdSigDataNotFoundError> {
@@ -52,7 +52,7 @@ fn pub_coeffs_from_store(
     maybe_coeffs.ok_or(ThresholdSigDataNotFoundError::ThresholdSigDataNotFound { dkg_id })
 }
 
-fn transcript_data_from_store(
+pub(super) fn transcript_data_from_store(
     dkg_id: DkgId,
     lockable_threshold_sig_data_store: &LockableThresholdSigDataStore,
 ) -> Result<TranscriptData, ThresholdSigDataNotFoundError> {
@@ -171,7 +171,7 @@ impl ThresholdSigVerifierInternal {
 /// Given that both cases indicate that the implementations of DKG and threshold
 /// signatures are not aligned and also a caller could not recover from this, we
 /// panic.
-fn lazily_calculated_public_key_from_store<C: ThresholdSignatureCspClient>(
+pub(super) fn lazily_calculated_public_key_from_store<C: ThresholdSignatureCspClient>(
     lockable_threshold_sig_data_store: &LockableThresholdSigDataStore,
     threshold_sig_csp_client: &C,
     dkg_id: DkgId,
@@ -0,0 +1,268 @@
+//! Verifiably encrypted threshold key derivation (vetKD) using the threshold
+//! key of an NI-DKG transcript as master key.
+use super::threshold_sig::{
+    lazily_calculated_public_key_from_store, pub_coeffs_from_store, transcript_data_from_store,
+};
+use crate::LockableThresholdSigDataStore;
+use ic_crypto_internal_bls12_381_type::G2Affine;
+use ic_crypto_internal_csp::api::{CspVetKdProtocol, ThresholdSignatureCspClient};
+use ic_crypto_internal_csp::key_id::KeyId;
+use ic_crypto_internal_csp::types::CspPublicCoefficients;
+use ic_crypto_internal_csp::vault::api::VetKdEncryptedKeyShareCreationVaultError;
+use ic_crypto_internal_threshold_sig_bls12381 as clib;
+use ic_crypto_internal_threshold_sig_bls12381::vetkd::{
+    DerivationPath, DerivedPublicKey, EncryptedKey, EncryptedKeyCombinationError,
+    EncryptedKeyShare, TransportPublicKey,
+};
+use ic_crypto_internal_types::sign::threshold_sig::public_coefficients::bls12_381::PublicCoefficientsBytes;
+use ic_crypto_internal_types::sign::threshold_sig::public_key::CspThresholdSigPublicKey;
+use ic_types::crypto::canister_threshold_sig::ExtendedDerivationPath;
+use ic_types::crypto::threshold_sig::ni_dkg::DkgId;
+use ic_types::crypto::vetkd::{
+    VetKdArgs, VetKdEncryptedKey, VetKdEncryptedKeyShare, VetKdKeyShareCombinationError,
+    VetKdKeyShareCreationError, VetKdKeyShareVerificationError, VetKdKeyVerificationError,
+};
+use ic_types::crypto::CryptoError;
+use ic_types::NodeId;
+use std::collections::BTreeMap;
+
+pub fn create_encrypted_key_share<C: CspVetKdProtocol>(
+    lockable_threshold_sig_data_store: &LockableThresholdSigDataStore,
+    csp: &C,
+    args: VetKdArgs,
+) -> Result<VetKdEncryptedKeyShare, VetKdKeyShareCreationError> {
+    let pub_coeffs = pub_coeffs_from_store(
+        DkgId::NiDkgId(args.ni_dkg_id),
+        lockable_threshold_sig_data_store,
+    )
+    .map_err(|e| VetKdKeyShareCreationError::ThresholdSigDataNotFound(e.to_string()))?;
+    let key_id = KeyId::from(&pub_coeffs);
+
+    let encrypted_key_share = csp
+        .create_encrypted_vetkd_key_share(
+            pub_coeffs,
+            args.transport_public_key,
+            args.derivation_path,
+            args.input,
+        )
+        .map_err(|error| match error {
+            VetKdEncryptedKeyShareCreationVaultError::SecretKeyMissingOrWrongType(_) => {
+                VetKdKeyShareCreationError::SecretKeyNotFound {
+                    dkg_id: args.ni_dkg_id,
+                    key_id: key_id.to_string(),
+                }
+            }
+            VetKdEncryptedKeyShareCreationVaultError::InvalidArgumentTransportPublicKey => {
+                VetKdKeyShareCreationError::InvalidArgumentTransportPublicKey
+            }
+            VetKdEncryptedKeyShareCreationVaultError::InvalidArgumentMasterPublicKey
+            | VetKdEncryptedKeyShareCreationVaultError::TransientInternalError(_) => {
+                VetKdKeyShareCreationError::InternalError {
+                    internal_error: format!("{:?}", error),
+                }
+            }
+        })?;
+
+    Ok(VetKdEncryptedKeyShare {
+        encrypted_key_share,
+    })
+}
+
+pub fn verify_encrypted_key_share<C: ThresholdSignatureCspClient>(
+    lockable_threshold_sig_data_store: &LockableThresholdSigDataStore,
+    csp: &C,
+    signer: NodeId,
+    key_share: &VetKdEncryptedKeyShare,
+    args: &VetKdArgs,
+) -> Result<(), VetKdKeyShareVerificationError> {
+    let dkg_id = DkgId::NiDkgId(args.ni_dkg_id);
+    let pub_coeffs = pub_coeffs_from_store(dkg_id, lockable_threshold_sig_data_store)
+        .map_err(|e| VetKdKeyShareVerificationError::ThresholdSigDataNotFound(e.to_string()))?;
+    let master_public_key = master_public_key(pub_coeffs).map_err(|internal_error| {
+        VetKdKeyShareVerificationError::InternalError { internal_error }
+    })?;
+    let master_public_key_share = lazily_calculated_public_key_from_store(
+        lockable_threshold_sig_data_store,
+        csp,
+        dkg_id,
+        signer,
+    )
+    .map_err(|error| match error {
+        CryptoError::ThresholdSigDataNotFound { .. } => {
+            VetKdKeyShareVerificationError::ThresholdSigDataNotFound(error.to_string())
+        }
+        _ => VetKdKeyShareVerificationError::InternalError {
+            internal_error: error.to_string(),
+        },
+    })
+    .and_then(|public_key| {
+        public_key_share(public_key).map_err(|internal_error| {
+            VetKdKeyShareVerificationError::InternalError { internal_error }
+        })
+    })?;
+
+    let transport_public_key = TransportPublicKey::deserialize(&args.transport_public_key)
+        .map_err(|_| VetKdKeyShareVerificationError::InvalidArgumentTransportPublicKey)?;
+    let encrypted_key_share = EncryptedKeyShare::deserialize(&key_share.encrypted_key_share)
+        .map_err(|_| VetKdKeyShareVerificationError::InvalidArgumentEncryptedKeyShare)?;
+
+    if encrypted_key_share.is_valid(
+        &master_public_key,
+        &master_public_key_share,
+        &clib_derivation_path(&args.derivation_path),
+        &args.input,
+        &transport_public_key,
+    ) {
+        Ok(())
+    } else {
+        Err(VetKdKeyShareVerificationError::VerificationError)
+    }
+}
+
+pub fn combine_encrypted_key_shares<C: ThresholdSignatureCspClient>(
+    lockable_threshold_sig_data_store: &LockableThresholdSigDataStore,
+    csp: &C,
+    shares: &BTreeMap<NodeId, VetKdEncryptedKeyShare>,
+    args: &VetKdArgs,
+) -> Result<VetKdEncryptedKey, VetKdKeyShareCombinationError> {
+    let dkg_id = DkgId::NiDkgId(args.ni_dkg_id);
+    let transcript_data = transcript_data_from_store(dkg_id, lockable_threshold_sig_data_store)
+        .map_err(|e| VetKdKeyShareCombinationError::ThresholdSigDataNotFound(e.to_string()))?;
+    let pub_coeffs = transcript_data.public_coefficients().clone();
+    let threshold = PublicCoefficientsBytes::from(pub_coeffs.clone())
+        .coefficients
+        .len();
+    if shares.len() < threshold {
+        return Err(
+            VetKdKeyShareCombinationError::UnsatisfiedReconstructionThreshold {
+                threshold: u32::try_from(threshold).expect("threshold exceeds u32::MAX"),
+                share_count: shares.len(),
+            },
+        );
+    }
+    let master_public_key = master_public_key(pub_coeffs).map_err(|internal_error| {
+        VetKdKeyShareCombinationError::InternalError { internal_error }
+    })?;
+    let transport_public_key = TransportPublicKey::deserialize(&args.transport_public_key)
+        .map_err(|_| VetKdKeyShareCombinationError::InvalidArgumentTransportPublicKey)?;
+    let derivation_path = clib_derivation_path(&args.derivation_path);
+
+    let mut clib_shares = BTreeMap::new();
+    for (node_id, share) in shares {
+        let node_index = transcript_data.index(*node_id).copied().ok_or(
+            VetKdKeyShareCombinationError::IndividualPublicKeyNotFound { node_id: *node_id },
+        )?;
+        let clib_share = EncryptedKeyShare::deserialize(&share.encrypted_key_share)
+            .map_err(|_| VetKdKeyShareCombinationError::InvalidArgumentEncryptedKeyShare)?;
+        clib_shares.insert(node_index, (*node_id, clib_share));
+    }
+
+    let all_shares = clib_shares
+        .iter()
+        .map(|(index, (_node_id, share))| (*index, share.clone()))
+        .collect();
+    let encrypted_key = match EncryptedKey::combine_all(
+        &all_shares,
+        threshold,
+        &master_public_key,
+        &transport_public_key,
+        &derivation_path,
+        &args.input,
+    ) {
+        Ok(encrypted_key) => encrypted_key,
+        Err(EncryptedKeyCombinationError::InvalidShares) => {
+            // At least one of the shares is invalid, so fall back to verifying
+            // each share individually and combining only the valid ones.
+            let mut shares_with_public_keys = BTreeMap::new();
+            for (index, (node_id, share)) in clib_shares {
+                let public_key = lazily_calculated_public_key_from_store(
+                    lockable_threshold_sig_data_store,
+                    csp,
+                    dkg_id,
+                    node_id,
+                )
+                .map_err(|_| {
+                    VetKdKeyShareCombinationError::IndividualPublicKeyNotFound { node_id }
+                })?;
+                let public_key_share = public_key_share(public_key).map_err(|internal_error| {
+                    VetKdKeyShareCombinationError::InternalError { internal_error }
+                })?;
+                shares_with_public_keys.insert(index, (public_key_share, share));
+            }
+            EncryptedKey::combine_valid_shares(
+                &shares_with_public_keys,
+                threshold,
+                &master_public_key,
+                &transport_public_key,
+                &derivation_path,
+                &args.input,
+            )
+            .map_err(map_combination_error)?
+        }
+        Err(error) => return Err(map_combination_error(error)),
+    };
+
+    Ok(VetKdEncryptedKey {
+        encrypted_key: encrypted_key.serialize().to_vec(),
+    })
+}
+
+pub fn verify_encrypted_key(
+    lockable_threshold_sig_data_store: &LockableThresholdSigDataStore,
+    key: &VetKdEncryptedKey,
+    args: &VetKdArgs,
+) -> Result<(), VetKdKeyVerificationError> {
+    let pub_coeffs = pub_coeffs_from_store(
+        DkgId::NiDkgId(args.ni_dkg_id),
+        lockable_threshold_sig_data_store,
+    )
+    .map_err(|e| VetKdKeyVerificationError::ThresholdSigDataNotFound(e.to_string()))?;
+    let master_public_key = master_public_key(pub_coeffs)
+        .map_err(|internal_error| VetKdKeyVerificationError::InternalError { internal_error })?;
+    let transport_public_key = TransportPublicKey::deserialize(&args.transport_public_key)
+        .map_err(|_| VetKdKeyVerificationError::InvalidArgumentTransportPublicKey)?;
+    let encrypted_key = EncryptedKey::deserialize(&key.encrypted_key)
+        .map_err(|_| VetKdKeyVerificationError::InvalidArgumentEncryptedKey)?;
+
+    let derived_public_key = DerivedPublicKey::compute_derived_key(
+        &master_public_key,
+        &clib_derivation_path(&args.derivation_path),
+    );
+    if encrypted_key.is_valid(&derived_public_key, &args.input, &transport_public_key) {
+        Ok(())
+    } else {
+        Err(VetKdKeyVerificationError::VerificationError)
+    }
+}
+
+fn master_public_key(pub_coeffs: CspPublicCoefficients) -> Result<G2Affine, String> {
+    let public_key = clib::api::combined_public_key(&PublicCoefficientsBytes::from(pub_coeffs))
+        .map_err(|e| format!("failed to compute master public key: {}", e))?;
+    G2Affine::deserialize(&public_key.0)
+        .map_err(|_| "failed to deserialize master public key".to_string())
+}
+
+fn public_key_share(public_key: CspThresholdSigPublicKey) -> Result<G2Affine, String> {
+    let CspThresholdSigPublicKey::ThresBls12_381(public_key_bytes) = public_key;
+    G2Affine::deserialize(&public_key_bytes.0)
+        .map_err(|_| "failed to deserialize individual public key".to_string())
+}
+
+fn clib_derivation_path(derivation_path: &ExtendedDerivationPath) -> DerivationPath {
+    DerivationPath::new(
+        derivation_path.caller.as_slice(),
+        &derivation_path.derivation_path,
+    )
+}
+
+fn map_combination_error(error: EncryptedKeyCombinationError) -> VetKdKeyShareCombinationError {
+    match error {
+        EncryptedKeyCombinationError::InsufficientValidShares => {
+            VetKdKeyShareCombinationError::InsufficientValidKeyShares
+        }
+        EncryptedKeyCombinationError::InsufficientShares
+        | EncryptedKeyCombinationError::InvalidShares => {
+            VetKdKeyShareCombinationError::CombinationError(format!("{:?}", error))
+        }
+    }
+}
@@ -3,7 +3,7 @@ use async_trait::async_trait;
 use ic_crypto_internal_logmon::metrics::{MetricsDomain, MetricsResult, MetricsScope};
 use ic_crypto_tls_interfaces::{
     AllowedClients, AuthenticatedPeer, MalformedPeerCertificateError, TlsClientHandshakeError,
-    TlsHandshake, TlsPublicKeyCert, TlsServerHandshakeError, TlsStream,
+    TlsConfig, TlsConfigError, TlsHandshake, TlsPublicKeyCert, TlsServerHandshakeError, TlsStream,
 };
 use ic_logger::{debug, new_logger};
 use ic_types::registry::RegistryClientError;
@@ -13,6 +13,7 @@ use openssl::string::OpensslString;
 use openssl::x509::{X509NameEntries, X509NameEntryRef};
 use std::str::FromStr;
 use tokio::net::TcpStream;
+use tokio_rustls::rustls::{ClientConfig, ServerConfig};
 
 mod rustls;
 
@@ -146,6 +147,89 @@ where
     }
 }
 
+impl<CSP> TlsConfig for CryptoComponentImpl<CSP>
+where
+    CSP: CryptoServiceProvider + Send + Sync,
+{
+    fn server_config(
+        &self,
+        allowed_clients: AllowedClients,
+        registry_version: RegistryVersion,
+    ) -> Result<ServerConfig, TlsConfigError> {
+        let log_id = get_log_id(&self.logger, module_path!());
+        let logger = new_logger!(&self.logger;
+            crypto.log_id => log_id,
+            crypto.trait_name => "TlsConfig",
+            crypto.method_name => "server_config",
+        );
+        debug!(logger;
+            crypto.description => "start",
+            crypto.registry_version => registry_version.get(),
+            crypto.allowed_tls_clients => format!("{:?}", allowed_clients),
+        );
+        let start_time = self.metrics.now();
+        let result = rustls::server_handshake::server_config(
+            &self.csp,
+            self.node_id,
+            Arc::clone(&self.registry_client),
+            allowed_clients,
+            registry_version,
+        );
+        self.metrics.observe_duration_seconds(
+            MetricsDomain::TlsHandshake,
+            MetricsScope::Full,
+            "server_config",
+            MetricsResult::from(&result),
+            start_time,
+        );
+        debug!(logger;
+            crypto.description => "end",
+            crypto.is_ok => result.is_ok(),
+            crypto.error => log_err(result.as_ref().err()),
+        );
+        result
+    }
+
+    fn client_config(
+        &self,
+        server: NodeId,
+        registry_version: RegistryVersion,
+    ) -> Result<ClientConfig, TlsConfigError> {
+        let log_id = get_log_id(&self.logger, module_path!());
+        let logger = new_logger!(&self.logger;
+            crypto.log_id => log_id,
+            crypto.trait_name => "TlsConfig",
+            crypto.method_name => "client_config",
+        );
+        debug!(logger;
+            crypto.description => "start",
+            crypto.registry_version => registry_version.get(),
+            crypto.tls_server => format!("{}", server),
+        );
+        let start_time = self.metrics.now();
+        let result = rustls::client_handshake::client_config(
+            &self.csp,
+            self.node_id,
+            Arc::clone(&self.registry_client),
+            server,
+            registry_version,
+        );
+        self.metrics.observe_duration_seconds(
+            MetricsDomain::TlsHandshake,
+            MetricsScope::Full,
+            "client_config",
+            MetricsResult::from(&result),
+            start_time,
+        );
+        debug!(logger;
+            crypto.description => "end",
+            crypto.is_ok => result.is_ok(),
+            crypto.error => log_err(result.as_ref().err()),
+        );
+        result
+    }
+}
+
 fn node_id_from_cert_subject_common_name(
     cert: &TlsPublicKeyCert,
 ) -> Result<NodeId, MalformedPeerCertificateError> {
@@ -234,6 +318,24 @@ impl From<RegistryClientError> for TlsCertFromRegistryError {
     }
 }
 
+impl From<TlsCertFromRegistryError> for TlsConfigError {
+    fn from(registry_error: TlsCertFromRegistryError) -> Self {
+        match registry_error {
+            TlsCertFromRegistryError::RegistryError(e) => TlsConfigError::RegistryError(e),
+            TlsCertFromRegistryError::CertificateNotInRegistry {
+                node_id,
+                registry_version,
+            } => TlsConfigError::CertificateNotInRegistry {
+                node_id,
+                registry_version,
+            },
+            TlsCertFromRegistryError::CertificateMalformed { internal_error } => {
+                TlsConfigError::MalformedSelfCertificate { internal_error }
+            }
+        }
+    }
+}
+
 fn log_err<T: fmt::Display>(error_option: Option<&T>) -> String {
     if let Some(error) = error_option {
         return format!("{}", error);
@@ -2,10 +2,12 @@ use crate::tls::rustls::cert_resolver::StaticCertResolver;
 use crate::tls::rustls::csp_server_signing_key::CspServerEd25519SigningKey;
 use crate::tls::rustls::node_cert_verifier::NodeServerCertVerifier;
 use crate::tls::rustls::{certified_key, RustlsTlsStream};
-use crate::tls::{tls_cert_from_registry, TlsCertFromRegistryError};
+use crate::tls::tls_cert_from_registry;
 use ic_crypto_internal_csp::api::CspTlsHandshakeSignerProvider;
 use ic_crypto_internal_csp::key_id::KeyId;
-use ic_crypto_tls_interfaces::{SomeOrAllNodes, TlsClientHandshakeError, TlsStream};
+use ic_crypto_tls_interfaces::{
+    SomeOrAllNodes, TlsClientHandshakeError, TlsConfigError, TlsStream,
+};
 use ic_interfaces_registry::RegistryClient;
 use ic_types::{NodeId, RegistryVersion};
 use std::sync::Arc;
@@ -24,10 +26,29 @@ pub async fn perform_tls_client_handshake<P: CspTlsHandshakeSignerProvider>(
     server: NodeId,
     registry_version: RegistryVersion,
 ) -> Result<Box<dyn TlsStream>, TlsClientHandshakeError> {
+    let config = client_config(
+        signer_provider,
+        self_node_id,
+        registry_client,
+        server,
+        registry_version,
+    )?;
+
+    connect(tcp_stream, config).await
+}
+
+/// Returns the client configuration used by `perform_tls_client_handshake`.
+pub fn client_config<P: CspTlsHandshakeSignerProvider>(
+    signer_provider: &P,
+    self_node_id: NodeId,
+    registry_client: Arc<dyn RegistryClient>,
+    server: NodeId,
+    registry_version: RegistryVersion,
+) -> Result<ClientConfig, TlsConfigError> {
     let self_tls_cert =
         tls_cert_from_registry(registry_client.as_ref(), self_node_id, registry_version)?;
     let self_tls_cert_key_id = KeyId::try_from(&self_tls_cert).map_err(|error| {
-        TlsClientHandshakeError::MalformedSelfCertificate {
+        TlsConfigError::MalformedSelfCertificate {
             internal_error: format!("Cannot instantiate KeyId: {:?}", error),
         }
     })?;
@@ -48,8 +69,7 @@ pub async fn perform_tls_client_handshake<P: CspTlsHandshakeSignerProvider>(
     config
         .dangerous()
         .set_certificate_verifier(Arc::new(server_cert_verifier));
-
-    connect(tcp_stream, config).await
+    Ok(config)
 }
 
 fn static_cert_resolver(key: CertifiedKey, scheme: SignatureScheme) -> Arc<dyn ResolvesClientCert> {
@@ -76,21 +96,3 @@ async fn connect(
         tokio_rustls::TlsStream::from(tls_stream),
     )))
 }
-
-impl From<TlsCertFromRegistryError> for TlsClientHandshakeError {
-    fn from(registry_error: TlsCertFromRegistryError) -> Self {
-        match registry_error {
-            TlsCertFromRegistryError::RegistryError(e) => TlsClientHandshakeError::RegistryError(e),
-            TlsCertFromRegistryError::CertificateNotInRegistry {
-                node_id,
-                registry_version,
-            } => TlsClientHandshakeError::CertificateNotInRegistry {
-                node_id,
-                registry_version,
-            },
-            TlsCertFromRegistryError::CertificateMalformed { internal_error } => {
-                TlsClientHandshakeError::MalformedSelfCertificate { internal_error }
-            }
-        }
-    }
-}
@@ -8,7 +8,8 @@ use crate::tls::{
 use ic_crypto_internal_csp::api::CspTlsHandshakeSignerProvider;
 use ic_crypto_internal_csp::key_id::KeyId;
 use ic_crypto_tls_interfaces::{
-    AllowedClients, AuthenticatedPeer, TlsPublicKeyCert, TlsServerHandshakeError, TlsStream,
+    AllowedClients, AuthenticatedPeer, TlsConfigError, TlsPublicKeyCert, TlsServerHandshakeError,
+    TlsStream,
 };
 use ic_interfaces_registry::RegistryClient;
 use ic_types::{NodeId, RegistryVersion};
@@ -30,25 +31,13 @@ pub async fn perform_tls_server_handshake<P: CspTlsHandshakeSignerProvider>(
     allowed_clients: AllowedClients,
     registry_version: RegistryVersion,
 ) -> Result<(Box<dyn TlsStream>, AuthenticatedPeer), TlsServerHandshakeError> {
-    let self_tls_cert =
-        tls_cert_from_registry(registry_client.as_ref(), self_node_id, registry_version)?;
-    let self_tls_cert_key_id = KeyId::try_from(&self_tls_cert).map_err(|error| {
-        TlsServerHandshakeError::MalformedSelfCertificate {
-            internal_error: format!("Cannot instantiate KeyId: {:?}", error),
-        }
-    })?;
-    let client_cert_verifier = NodeClientCertVerifier::new_with_mandatory_client_auth(
-        allowed_clients.nodes().clone(),
+    let config = server_config(
+        signer_provider,
+        self_node_id,
         registry_client,
+        allowed_clients,
         registry_version,
-    );
-    let ed25519_signing_key =
-        CspServerEd25519SigningKey::new(self_tls_cert_key_id, signer_provider.handshake_signer());
-    let config = server_config_with_tls13_and_aes_ciphersuites_and_ed25519_signing_key(
-        Arc::new(client_cert_verifier),
-        self_tls_cert,
-        ed25519_signing_key,
-    );
+    )?;
 
     let rustls_stream = accept_connection(tcp_stream, config).await?;
 
@@ -62,6 +51,37 @@ pub async fn perform_tls_server_handshake<P: CspTlsHandshakeSignerProvider>(
     ))
 }
 
+/// Returns the server configuration used by `perform_tls_server_handshake`.
+pub fn server_config<P: CspTlsHandshakeSignerProvider>(
+    signer_provider: &P,
+    self_node_id: NodeId,
+    registry_client: Arc<dyn RegistryClient>,
+    allowed_clients: AllowedClients,
+    registry_version: RegistryVersion,
+) -> Result<ServerConfig, TlsConfigError> {
+    let self_tls_cert =
+        tls_cert_from_registry(registry_client.as_ref(), self_node_id, registry_version)?;
+    let self_tls_cert_key_id = KeyId::try_from(&self_tls_cert).map_err(|error| {
+        TlsConfigError::MalformedSelfCertificate {
+            internal_error: format!("Cannot instantiate KeyId: {:?}", error),
+        }
+    })?;
+    let client_cert_verifier = NodeClientCertVerifier::new_with_mandatory_client_auth(
+        allowed_clients.nodes().clone(),
+        registry_client,
+        registry_version,
+    );
+    let ed25519_signing_key =
+        CspServerEd25519SigningKey::new(self_tls_cert_key_id, signer_provider.handshake_signer());
+    Ok(
+        server_config_with_tls13_and_aes_ciphersuites_and_ed25519_signing_key(
+            Arc::new(client_cert_verifier),
+            self_tls_cert,
+            ed25519_signing_key,
+        ),
+    )
+}
+
 pub async fn perform_tls_server_handshake_without_client_auth<P: CspTlsHandshakeSignerProvider>(
     signer_provider: &P,
     self_node_id: NodeId,
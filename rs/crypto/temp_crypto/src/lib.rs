@@ -10,8 +10,8 @@ use ic_crypto_node_key_generation::{
     generate_idkg_dealing_encryption_keys, generate_node_signing_keys, generate_tls_keys,
 };
 use ic_crypto_tls_interfaces::{
-    AllowedClients, AuthenticatedPeer, TlsClientHandshakeError, TlsHandshake, TlsPublicKeyCert,
-    TlsServerHandshakeError, TlsStream,
+    AllowedClients, AuthenticatedPeer, ClientConfig, ServerConfig, TlsClientHandshakeError,
+    TlsConfig, TlsConfigError, TlsHandshake, TlsPublicKeyCert, TlsServerHandshakeError, TlsStream,
 };
 use ic_crypto_utils_time::CurrentSystemTimeSource;
 use ic_interfaces::crypto::{
@@ -19,7 +19,8 @@ use ic_interfaces::crypto::{
     CurrentNodePublicKeysError, IDkgDealingEncryptionKeyRotationError, IDkgProtocol,
     IdkgDealingEncPubKeysCountError, KeyManager, LoadTranscriptResult, MultiSigVerifier,
     MultiSigner, NiDkgAlgorithm, PublicKeyRegistrationStatus, ThresholdEcdsaSigVerifier,
-    ThresholdEcdsaSigner, ThresholdSigVerifier, ThresholdSigVerifierByPublicKey, ThresholdSigner,
+    ThresholdEcdsaSigner, ThresholdSchnorrSigVerifier, ThresholdSchnorrSigner,
+    ThresholdSigVerifier, ThresholdSigVerifierByPublicKey, ThresholdSigner, VetKdProtocol,
 };
 use ic_interfaces::time_source::TimeSource;
 use ic_interfaces_registry::RegistryClient;
@@ -39,7 +40,9 @@ use ic_types::crypto::canister_threshold_sig::error::{
     IDkgVerifyDealingPrivateError, IDkgVerifyDealingPublicError, IDkgVerifyInitialDealingsError,
     IDkgVerifyOpeningError, IDkgVerifyTranscriptError, ThresholdEcdsaCombineSigSharesError,
     ThresholdEcdsaSignShareError, ThresholdEcdsaVerifyCombinedSignatureError,
-    ThresholdEcdsaVerifySigShareError,
+    ThresholdEcdsaVerifySigShareError, ThresholdSchnorrCombineSigSharesError,
+    ThresholdSchnorrSignShareError, ThresholdSchnorrVerifyCombinedSignatureError,
+    ThresholdSchnorrVerifySigShareError,
 };
 use ic_types::crypto::canister_threshold_sig::idkg::{
     BatchSignedIDkgDealing, IDkgComplaint, IDkgOpening, IDkgTranscript, IDkgTranscriptParams,
@@ -47,6 +50,7 @@ use ic_types::crypto::canister_threshold_sig::idkg::{
 };
 use ic_types::crypto::canister_threshold_sig::{
     ThresholdEcdsaCombinedSignature, ThresholdEcdsaSigInputs, ThresholdEcdsaSigShare,
+    ThresholdSchnorrCombinedSignature, ThresholdSchnorrSigInputs, ThresholdSchnorrSigShare,
 };
 use ic_types::crypto::threshold_sig::ni_dkg::config::NiDkgConfig;
 use ic_types::crypto::threshold_sig::ni_dkg::errors::{
@@ -55,6 +59,10 @@ use ic_types::crypto::threshold_sig::ni_dkg::errors::{
     verify_dealing_error::DkgVerifyDealingError,
 };
 use ic_types::crypto::threshold_sig::ni_dkg::{DkgId, NiDkgDealing, NiDkgTranscript};
+use ic_types::crypto::vetkd::{
+    VetKdArgs, VetKdEncryptedKey, VetKdEncryptedKeyShare, VetKdKeyShareCombinationError,
+    VetKdKeyShareCreationError, VetKdKeyShareVerificationError, VetKdKeyVerificationError,
+};
 use ic_types::crypto::{
     BasicSigOf, CanisterSigOf, CombinedMultiSigOf, CombinedThresholdSigOf, CryptoResult,
     CurrentNodePublicKeys, IndividualMultiSigOf, KeyPurpose, Signable, ThresholdSigShareOf,
@@ -544,6 +552,8 @@ impl EcdsaSubnetConfig {
                         curve: EcdsaCurve::Secp256k1.into(),
                         name: "dummy_ecdsa_key_id".to_string(),
                     }],
+                    schnorr_key_ids: vec![],
+                    vetkd_key_ids: vec![],
                     max_queue_size: 20,
                     signature_request_timeout_ns: None,
                     idkg_key_rotation_period_ms: key_rotation_period
@@ -845,6 +855,81 @@ impl<C: CryptoServiceProvider> ThresholdEcdsaSigVerifier for TempCryptoComponent
     }
 }
 
+impl<C: CryptoServiceProvider> ThresholdSchnorrSigner for TempCryptoComponentGeneric<C> {
+    fn create_schnorr_sig_share(
+        &self,
+        inputs: &ThresholdSchnorrSigInputs,
+    ) -> Result<ThresholdSchnorrSigShare, ThresholdSchnorrSignShareError> {
+        self.crypto_component.create_schnorr_sig_share(inputs)
+    }
+}
+
+impl<C: CryptoServiceProvider> ThresholdSchnorrSigVerifier for TempCryptoComponentGeneric<C> {
+    fn verify_schnorr_sig_share(
+        &self,
+        signer: NodeId,
+        inputs: &ThresholdSchnorrSigInputs,
+        share: &ThresholdSchnorrSigShare,
+    ) -> Result<(), ThresholdSchnorrVerifySigShareError> {
+        self.crypto_component
+            .verify_schnorr_sig_share(signer, inputs, share)
+    }
+
+    fn combine_schnorr_sig_shares(
+        &self,
+        inputs: &ThresholdSchnorrSigInputs,
+        shares: &BTreeMap<NodeId, ThresholdSchnorrSigShare>,
+    ) -> Result<ThresholdSchnorrCombinedSignature, ThresholdSchnorrCombineSigSharesError> {
+        self.crypto_component
+            .combine_schnorr_sig_shares(inputs, shares)
+    }
+
+    fn verify_schnorr_combined_sig(
+        &self,
+        inputs: &ThresholdSchnorrSigInputs,
+        signature: &ThresholdSchnorrCombinedSignature,
+    ) -> Result<(), ThresholdSchnorrVerifyCombinedSignatureError> {
+        self.crypto_component
+            .verify_schnorr_combined_sig(inputs, signature)
+    }
+}
+
+impl<C: CryptoServiceProvider> VetKdProtocol for TempCryptoComponentGeneric<C> {
+    fn create_encrypted_key_share(
+        &self,
+        args: VetKdArgs,
+    ) -> Result<VetKdEncryptedKeyShare, VetKdKeyShareCreationError> {
+        self.crypto_component.create_encrypted_key_share(args)
+    }
+
+    fn verify_encrypted_key_share(
+        &self,
+        signer: NodeId,
+        key_share: &VetKdEncryptedKeyShare,
+        args: &VetKdArgs,
+    ) -> Result<(), VetKdKeyShareVerificationError> {
+        self.crypto_component
+            .verify_encrypted_key_share(signer, key_share, args)
+    }
+
+    fn combine_encrypted_key_shares(
+        &self,
+        shares: &BTreeMap<NodeId, VetKdEncryptedKeyShare>,
+        args: &VetKdArgs,
+    ) -> Result<VetKdEncryptedKey, VetKdKeyShareCombinationError> {
+        self.crypto_component
+            .combine_encrypted_key_shares(shares, args)
+    }
+
+    fn verify_encrypted_key(
+        &self,
+        key: &VetKdEncryptedKey,
+        args: &VetKdArgs,
+    ) -> Result<(), VetKdKeyVerificationError> {
+        self.crypto_component.verify_encrypted_key(key, args)
+    }
+}
+
 #[async_trait]
 impl<C: CryptoServiceProvider + Send + Sync> TlsHandshake for TempCryptoComponentGeneric<C> {
     async fn perform_tls_server_handshake(
@@ -880,6 +965,26 @@ impl<C: CryptoServiceProvider + Send + Sync> TlsHandshake for TempCryptoComponen
     }
 }
 
+impl<C: CryptoServiceProvider + Send + Sync> TlsConfig for TempCryptoComponentGeneric<C> {
+    fn server_config(
+        &self,
+        allowed_clients: AllowedClients,
+        registry_version: RegistryVersion,
+    ) -> Result<ServerConfig, TlsConfigError> {
+        self.crypto_component
+            .server_config(allowed_clients, registry_version)
+    }
+
+    fn client_config(
+        &self,
+        server: NodeId,
+        registry_version: RegistryVersion,
+    ) -> Result<ClientConfig, TlsConfigError> {
+        self.crypto_component
+            .client_config(server, registry_version)
+    }
+}
+
 impl<C: CryptoServiceProvider, T: Signable> BasicSigVerifier<T> for TempCryptoComponentGeneric<C> {
     fn verify_basic_sig(
         &self,
@@ -23,8 +23,11 @@ use ic_crypto_internal_csp::vault::api::PublicRandomSeedGenerator;
 use ic_crypto_internal_csp::vault::api::PublicRandomSeedGeneratorError;
 use ic_crypto_internal_csp::vault::api::SecretKeyStoreCspVault;
 use ic_crypto_internal_csp::vault::api::ThresholdEcdsaSignerCspVault;
+use ic_crypto_internal_csp::vault::api::ThresholdSchnorrSignerCspVault;
 use ic_crypto_internal_csp::vault::api::ThresholdSignatureCspVault;
 use ic_crypto_internal_csp::vault::api::TlsHandshakeCspVault;
+use ic_crypto_internal_csp::vault::api::VetKdCspVault;
+use ic_crypto_internal_csp::vault::api::VetKdEncryptedKeyShareCreationVaultError;
 use ic_crypto_internal_seed::Seed;
 use ic_crypto_internal_threshold_sig_bls12381::api::ni_dkg_errors;
 use ic_crypto_internal_threshold_sig_ecdsa::{
@@ -41,7 +44,7 @@ use ic_crypto_node_key_validation::ValidNodePublicKeys;
 use ic_crypto_tls_interfaces::TlsPublicKeyCert;
 use ic_types::crypto::canister_threshold_sig::error::{
     IDkgCreateDealingError, IDkgLoadTranscriptError, IDkgOpenTranscriptError, IDkgRetainKeysError,
-    IDkgVerifyDealingPrivateError, ThresholdEcdsaSignShareError,
+    IDkgVerifyDealingPrivateError, ThresholdEcdsaSignShareError, ThresholdSchnorrSignShareError,
 };
 use ic_types::crypto::canister_threshold_sig::ExtendedDerivationPath;
 use ic_types::crypto::{AlgorithmId, CurrentNodePublicKeys};
@@ -203,6 +206,29 @@ mock! {
         ) -> Result<ThresholdEcdsaSigShareInternal, ThresholdEcdsaSignShareError>;
     }
 
+    pub trait ThresholdSchnorrSignerCspVault {
+        fn create_schnorr_sig_share(
+            &self,
+            derivation_path: &ExtendedDerivationPath,
+            message: &[u8],
+            nonce: &Randomness,
+            key: &IDkgTranscriptInternal,
+            presig: &IDkgTranscriptInternal,
+            algorithm_id: AlgorithmId,
+        ) -> Result<Vec<u8>, ThresholdSchnorrSignShareError>;
+    }
+
+    pub trait VetKdCspVault {
+        fn create_encrypted_vetkd_key_share(
+            &self,
+            key_id: KeyId,
+            master_public_key: Vec<u8>,
+            transport_public_key: Vec<u8>,
+            derivation_path: ExtendedDerivationPath,
+            input: Vec<u8>,
+        ) -> Result<Vec<u8>, VetKdEncryptedKeyShareCreationVaultError>;
+    }
+
     pub trait SecretKeyStoreCspVault {
         fn sks_contains(&self, key_id: &KeyId) -> Result<bool, CspSecretKeyStoreContainsError>;
     }
@@ -802,7 +802,7 @@ fn should_fail_check_keys_with_registry_if_no_idkg_key_in_registry() {
 /// Ensure the structs are consistent and then update the test below.
 #[test]
 fn algorithm_id_should_match_algorithm_id_proto() {
-    let algorithm_id_variants = 17;
+    let algorithm_id_variants = 19;
     assert_eq!(AlgorithmId::iter().count(), algorithm_id_variants);
 
     for i in 0..algorithm_id_variants {
@@ -875,6 +875,18 @@ fn algorithm_id_should_match_algorithm_id_proto() {
         AlgorithmId::MegaSecp256k1 as i32,
         AlgorithmIdProto::MegaSecp256k1 as i32
     );
+    assert_eq!(
+        AlgorithmId::ThresholdSchnorrBip340 as i32,
+        AlgorithmIdProto::ThresholdSchnorrBip340 as i32
+    );
+    assert_eq!(
+        AlgorithmId::ThresholdEd25519 as i32,
+        AlgorithmIdProto::ThresholdEd25519 as i32
+    );
+    assert_eq!(
+        AlgorithmId::ThresholdEcdsaSecp256r1 as i32,
+        AlgorithmIdProto::ThresholdEcdsaSecp256r1 as i32
+    );
 }
 
 #[test]
@@ -0,0 +1,213 @@
+#![allow(clippy::unwrap_used)]
+use ic_crypto_internal_bls12_381_type::G2Affine;
+use ic_crypto_internal_threshold_sig_bls12381::vetkd::{
+    DerivationPath, DerivedPublicKey, EncryptedKey, TransportSecretKey,
+};
+use ic_crypto_temp_crypto::TempCryptoComponent;
+use ic_crypto_test_utils::crypto_for;
+use ic_crypto_test_utils_reproducible_rng::reproducible_rng;
+use ic_crypto_test_utils_threshold_sigs::non_interactive::{
+    run_ni_dkg_and_create_single_transcript, NiDkgTestEnvironment, RandomNiDkgConfig,
+};
+use ic_interfaces::crypto::{LoadTranscriptResult, NiDkgAlgorithm, VetKdProtocol};
+use ic_types::crypto::canister_threshold_sig::ExtendedDerivationPath;
+use ic_types::crypto::threshold_sig::ni_dkg::config::NiDkgConfig;
+use ic_types::crypto::threshold_sig::ni_dkg::{NiDkgTag, NiDkgTranscript};
+use ic_types::crypto::threshold_sig::ThresholdSigPublicKey;
+use ic_types::crypto::vetkd::{
+    VetKdArgs, VetKdEncryptedKeyShare, VetKdKeyShareCombinationError,
+    VetKdKeyShareVerificationError,
+};
+use ic_types::{NodeId, PrincipalId};
+use rand::prelude::*;
+use std::collections::BTreeMap;
+
+#[test]
+fn should_derive_decryptable_key_from_valid_shares() {
+    let rng = &mut reproducible_rng();
+    let (config, crypto_components) = setup_with_random_ni_dkg_config(rng);
+    let transcript = run_ni_dkg_and_load_transcript_for_receivers(&config, &crypto_components);
+
+    let transport_secret_key = TransportSecretKey::generate(rng);
+    let args = vetkd_args(&config, &transport_secret_key, rng);
+
+    let shares = create_key_shares(&config, &args, &crypto_components);
+    let verifier = random_node_in(&config, rng);
+    for (signer, share) in &shares {
+        assert_eq!(
+            crypto_for(verifier, &crypto_components)
+                .verify_encrypted_key_share(*signer, share, &args),
+            Ok(())
+        );
+    }
+
+    let combiner = random_node_in(&config, rng);
+    let encrypted_key = crypto_for(combiner, &crypto_components)
+        .combine_encrypted_key_shares(&shares, &args)
+        .unwrap();
+    assert_eq!(
+        crypto_for(verifier, &crypto_components).verify_encrypted_key(&encrypted_key, &args),
+        Ok(())
+    );
+
+    let master_public_key =
+        G2Affine::deserialize(&ThresholdSigPublicKey::from(&transcript).into_bytes()).unwrap();
+    let derived_public_key = DerivedPublicKey::compute_derived_key(
+        &master_public_key,
+        &DerivationPath::new(
+            args.derivation_path.caller.as_slice(),
+            &args.derivation_path.derivation_path,
+        ),
+    );
+    let encrypted_key = EncryptedKey::deserialize(&encrypted_key.encrypted_key).unwrap();
+    assert!(transport_secret_key
+        .decrypt(&encrypted_key, &derived_public_key, &args.input)
+        .is_some());
+}
+
+#[test]
+fn should_combine_shares_and_skip_invalid_ones() {
+    let rng = &mut reproducible_rng();
+    let (config, crypto_components) = setup_with_random_ni_dkg_config(rng);
+    run_ni_dkg_and_load_transcript_for_receivers(&config, &crypto_components);
+
+    let args = vetkd_args(&config, &TransportSecretKey::generate(rng), rng);
+    let mut shares = create_key_shares(&config, &args, &crypto_components);
+
+    // Replace one share by a share for a different input
+    let other_args = VetKdArgs {
+        input: b"other input".to_vec(),
+        ..args.clone()
+    };
+    let node_with_wrong_share = random_node_in(&config, rng);
+    let wrong_share = crypto_for(node_with_wrong_share, &crypto_components)
+        .create_encrypted_key_share(other_args)
+        .unwrap();
+    shares.insert(node_with_wrong_share, wrong_share.clone());
+
+    assert_eq!(
+        crypto_for(random_node_in(&config, rng), &crypto_components).verify_encrypted_key_share(
+            node_with_wrong_share,
+            &wrong_share,
+            &args
+        ),
+        Err(VetKdKeyShareVerificationError::VerificationError)
+    );
+
+    let threshold = config.threshold().get().get() as usize;
+    if shares.len() > threshold {
+        let encrypted_key = crypto_for(random_node_in(&config, rng), &crypto_components)
+            .combine_encrypted_key_shares(&shares, &args)
+            .unwrap();
+        assert_eq!(
+            crypto_for(random_node_in(&config, rng), &crypto_components)
+                .verify_encrypted_key(&encrypted_key, &args),
+            Ok(())
+        );
+    } else {
+        assert_eq!(
+            crypto_for(random_node_in(&config, rng), &crypto_components)
+                .combine_encrypted_key_shares(&shares, &args),
+            Err(VetKdKeyShareCombinationError::InsufficientValidKeyShares)
+        );
+    }
+}
+
+#[test]
+fn should_fail_to_combine_insufficient_shares() {
+    let rng = &mut reproducible_rng();
+    let (config, crypto_components) = setup_with_random_ni_dkg_config(rng);
+    run_ni_dkg_and_load_transcript_for_receivers(&config, &crypto_components);
+
+    let args = vetkd_args(&config, &TransportSecretKey::generate(rng), rng);
+    let threshold = config.threshold().get().get();
+    let shares: BTreeMap<_, _> = create_key_shares(&config, &args, &crypto_components)
+        .into_iter()
+        .take(threshold as usize - 1)
+        .collect();
+
+    assert_eq!(
+        crypto_for(random_node_in(&config, rng), &crypto_components)
+            .combine_encrypted_key_shares(&shares, &args),
+        Err(
+            VetKdKeyShareCombinationError::UnsatisfiedReconstructionThreshold {
+                threshold,
+                share_count: shares.len(),
+            }
+        )
+    );
+}
+
+fn setup_with_random_ni_dkg_config<R: Rng>(
+    rng: &mut R,
+) -> (NiDkgConfig, BTreeMap<NodeId, TempCryptoComponent>) {
+    let config = RandomNiDkgConfig::builder()
+        .subnet_size(rng.gen_range(1..7))
+        .dkg_tag(NiDkgTag::HighThreshold)
+        .build()
+        .into_config();
+    let crypto_components = NiDkgTestEnvironment::new_for_config(&config).crypto_components;
+    (config, crypto_components)
+}
+
+fn run_ni_dkg_and_load_transcript_for_receivers(
+    config: &NiDkgConfig,
+    crypto_components: &BTreeMap<NodeId, TempCryptoComponent>,
+) -> NiDkgTranscript {
+    let transcript = run_ni_dkg_and_create_single_transcript(config, crypto_components);
+    for node_id in config.receivers().get() {
+        let result = crypto_for(*node_id, crypto_components)
+            .load_transcript(&transcript)
+            .unwrap_or_else(|e| {
+                panic!(
+                    "failed to load transcript {} for node {}: {}",
+                    transcript, *node_id, e
+                )
+            });
+        assert_eq!(result, LoadTranscriptResult::SigningKeyAvailable);
+    }
+    transcript
+}
+
+fn vetkd_args<R: Rng>(
+    config: &NiDkgConfig,
+    transport_secret_key: &TransportSecretKey,
+    rng: &mut R,
+) -> VetKdArgs {
+    VetKdArgs {
+        ni_dkg_id: config.dkg_id(),
+        derivation_path: ExtendedDerivationPath {
+            caller: PrincipalId::new_user_test_id(rng.gen()),
+            derivation_path: vec![rng.gen::<[u8; 10]>().to_vec()],
+        },
+        input: rng.gen::<[u8; 32]>().to_vec(),
+        transport_public_key: transport_secret_key.public_key().serialize().to_vec(),
+    }
+}
+
+fn create_key_shares(
+    config: &NiDkgConfig,
+    args: &VetKdArgs,
+    crypto_components: &BTreeMap<NodeId, TempCryptoComponent>,
+) -> BTreeMap<NodeId, VetKdEncryptedKeyShare> {
+    config
+        .receivers()
+        .get()
+        .iter()
+        .map(|node_id| {
+            let share = crypto_for(*node_id, crypto_components)
+                .create_encrypted_key_share(args.clone())
+                .unwrap_or_else(|e| panic!("key share creation by node {} failed: {}", node_id, e));
+            (*node_id, share)
+        })
+        .collect()
+}
+
+fn random_node_in<R: Rng>(config: &NiDkgConfig, rng: &mut R) -> NodeId {
+    *config
+        .receivers()
+        .get()
+        .iter()
+        .choose(rng)
+        .expect("nodes empty")
+}
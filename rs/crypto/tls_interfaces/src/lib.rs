@@ -17,6 +17,9 @@ use std::hash::{Hash, Hasher};
 use tokio::io::{AsyncRead, AsyncWrite};
 use tokio::net::TcpStream;
 
+/// The rustls configurations returned by [`TlsConfig`].
+pub use tokio_rustls::rustls::{ClientConfig, ServerConfig};
+
 #[cfg(test)]
 mod tests;
 
@@ -226,6 +229,64 @@ impl From<MalformedPeerCertificateError> for TlsClientHandshakeError {
     }
 }
 
+#[derive(Clone, Debug, PartialEq, Eq)]
+/// Errors from creating a TLS configuration. Please refer to the `TlsConfig`
+/// methods for detailed error variant descriptions.
+pub enum TlsConfigError {
+    RegistryError(RegistryClientError),
+    CertificateNotInRegistry {
+        node_id: NodeId,
+        registry_version: RegistryVersion,
+    },
+    MalformedSelfCertificate {
+        internal_error: String,
+    },
+}
+
+impl Display for TlsConfigError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl std::error::Error for TlsConfigError {}
+
+impl From<TlsConfigError> for TlsServerHandshakeError {
+    fn from(config_error: TlsConfigError) -> Self {
+        match config_error {
+            TlsConfigError::RegistryError(e) => TlsServerHandshakeError::RegistryError(e),
+            TlsConfigError::CertificateNotInRegistry {
+                node_id,
+                registry_version,
+            } => TlsServerHandshakeError::CertificateNotInRegistry {
+                node_id,
+                registry_version,
+            },
+            TlsConfigError::MalformedSelfCertificate { internal_error } => {
+                TlsServerHandshakeError::MalformedSelfCertificate { internal_error }
+            }
+        }
+    }
+}
+
+impl From<TlsConfigError> for TlsClientHandshakeError {
+    fn from(config_error: TlsConfigError) -> Self {
+        match config_error {
+            TlsConfigError::RegistryError(e) => TlsClientHandshakeError::RegistryError(e),
+            TlsConfigError::CertificateNotInRegistry {
+                node_id,
+                registry_version,
+            } => TlsClientHandshakeError::CertificateNotInRegistry {
+                node_id,
+                registry_version,
+            },
+            TlsConfigError::MalformedSelfCertificate { internal_error } => {
+                TlsClientHandshakeError::MalformedSelfCertificate { internal_error }
+            }
+        }
+    }
+}
+
 /// A stream over a secure connection protected by TLS.
 ///
 /// Implementing streams are expected to behave like a `BufWriter`. This means
@@ -390,6 +451,61 @@ pub trait TlsHandshake {
     ) -> Result<Box<dyn TlsStream>, TlsClientHandshakeError>;
 }
 
+/// Implementors provide TLS configurations for protocols that perform the TLS
+/// handshake themselves instead of on top of a TCP stream, such as QUIC.
+///
+/// The configurations use the same parameters and perform the same peer
+/// authentication as the corresponding `TlsHandshake` methods, i.e., the peer
+/// is authenticated with its node certificate in the registry. Unlike with
+/// `TlsHandshake`, it is the caller's responsibility to determine the node ID
+/// of an authenticated client from the subject CN of the certificate it
+/// presented, after the handshake succeeded.
+pub trait TlsConfig {
+    /// Returns the configuration for a TLS server that only accepts clients
+    /// that authenticate as one of the `allowed_clients` with their node
+    /// certificate at `registry_version`. The configuration is the same as
+    /// for `TlsHandshake::perform_tls_server_handshake`.
+    ///
+    /// # Errors
+    /// * TlsConfigError::RegistryError if the registry cannot be accessed.
+    /// * TlsConfigError::CertificateNotInRegistry if the node's own
+    ///   certificate is not found in the registry.
+    /// * TlsConfigError::MalformedSelfCertificate if the node's own
+    ///   certificate is malformed.
+    ///
+    /// # Panics
+    /// * If, during a handshake, the secret key corresponding to the server
+    ///   certificate cannot be found or is malformed in the server's secret
+    ///   key store.
+    fn server_config(
+        &self,
+        allowed_clients: AllowedClients,
+        registry_version: RegistryVersion,
+    ) -> Result<ServerConfig, TlsConfigError>;
+
+    /// Returns the configuration for a TLS client that only accepts the
+    /// given `server`, authenticated with its node certificate at
+    /// `registry_version`. The configuration is the same as for
+    /// `TlsHandshake::perform_tls_client_handshake`.
+    ///
+    /// # Errors
+    /// * TlsConfigError::RegistryError if the registry cannot be accessed.
+    /// * TlsConfigError::CertificateNotInRegistry if the node's own
+    ///   certificate is not found in the registry.
+    /// * TlsConfigError::MalformedSelfCertificate if the node's own
+    ///   certificate is malformed.
+    ///
+    /// # Panics
+    /// * If, during a handshake, the secret key corresponding to the client
+    ///   certificate cannot be found or is malformed in the client's secret
+    ///   key store.
+    fn client_config(
+        &self,
+        server: NodeId,
+        registry_version: RegistryVersion,
+    ) -> Result<ClientConfig, TlsConfigError>;
+}
+
 #[derive(Clone, Debug)]
 /// A list of allowed TLS peers, which can be `All` to allow any node to connect.
 pub struct AllowedClients {
@@ -0,0 +1,37 @@
+use ic_crypto_internal_bls12_381_type::G2Affine;
+use ic_crypto_internal_threshold_sig_bls12381::vetkd::{
+    DerivationPath, DerivedPublicKey, TransportPublicKey,
+};
+use ic_types::crypto::canister_threshold_sig::ExtendedDerivationPath;
+use ic_types::crypto::threshold_sig::ThresholdSigPublicKey;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum VetKdDerivePublicKeyError {
+    InvalidArgumentMasterPublicKey,
+}
+
+/// Derives the vetKD public key from the specified `master_public_key` for
+/// the given `extended_derivation_path`.
+///
+/// The master public key is the threshold public key of the subnet's
+/// high-threshold NI-DKG transcript.
+pub fn derive_vetkd_public_key(
+    master_public_key: &ThresholdSigPublicKey,
+    extended_derivation_path: &ExtendedDerivationPath,
+) -> Result<Vec<u8>, VetKdDerivePublicKeyError> {
+    let master_public_key = G2Affine::deserialize(&master_public_key.into_bytes())
+        .map_err(|_| VetKdDerivePublicKeyError::InvalidArgumentMasterPublicKey)?;
+    let derivation_path = DerivationPath::new(
+        extended_derivation_path.caller.as_slice(),
+        &extended_derivation_path.derivation_path,
+    );
+    let derived_public_key =
+        DerivedPublicKey::compute_derived_key(&master_public_key, &derivation_path);
+    Ok(derived_public_key.serialize().to_vec())
+}
+
+/// Returns true if `transport_public_key` is a valid encoding of a vetKD
+/// transport public key.
+pub fn is_valid_transport_public_key(transport_public_key: &[u8]) -> bool {
+    TransportPublicKey::deserialize(transport_public_key).is_ok()
+}
@@ -478,6 +478,11 @@ impl CyclesAccountManager {
         self.scale_cost(self.config.ecdsa_signature_fee, subnet_size)
     }
 
+    /// Amount to charge for a Schnorr signature.
+    pub fn schnorr_signature_fee(&self, subnet_size: usize) -> Cycles {
+        self.scale_cost(self.config.schnorr_signature_fee, subnet_size)
+    }
+
     ////////////////////////////////////////////////////////////////////////////
     //
     // Storage
@@ -562,11 +567,8 @@ impl CyclesAccountManager {
         //   - the fee to send the request (by size)
         //   - the fee for the largest possible response
         //   - the fee for executing the largest allowed response when it eventually arrives.
-        let fee = self.scale_cost(
-            self.config.xnet_call_fee
-                + self.config.xnet_byte_transmission_fee * request.payload_size_bytes().get(),
-            subnet_size,
-        ) + prepayment_for_response_transmission
+        let fee = self.xnet_call_request_fee(request.payload_size_bytes(), subnet_size)
+            + prepayment_for_response_transmission
             + prepayment_for_response_execution;
         self.withdraw_with_threshold(
             canister_id,
@@ -582,6 +584,24 @@ impl CyclesAccountManager {
         )
     }
 
+    /// Returns the total amount of cycles withdrawn when making an
+    /// inter-canister call whose method name and argument take `payload_size`
+    /// bytes, including the prepayments for the response.
+    pub fn xnet_call_total_fee(&self, payload_size: NumBytes, subnet_size: usize) -> Cycles {
+        self.xnet_call_request_fee(payload_size, subnet_size)
+            + self.prepayment_for_response_transmission(subnet_size)
+            + self.prepayment_for_response_execution(subnet_size)
+    }
+
+    /// Returns the fee for performing a xnet call and transmitting its request
+    /// of `payload_size` bytes.
+    fn xnet_call_request_fee(&self, payload_size: NumBytes, subnet_size: usize) -> Cycles {
+        self.scale_cost(
+            self.config.xnet_call_fee + self.config.xnet_byte_transmission_fee * payload_size.get(),
+            subnet_size,
+        )
+    }
+
     /// Returns the amount of cycles required for executing the longest-running
     /// response callback.
     pub fn prepayment_for_response_execution(&self, subnet_size: usize) -> Cycles {
@@ -830,6 +850,33 @@ impl CyclesAccountManager {
             subnet_size,
         )
     }
+
+    /// Returns the fee for a non-replicated http request.
+    ///
+    /// Only a single node of the subnet sends the request, so the request
+    /// bytes are charged for one node only. The response is still included in
+    /// a block and processed by every node, so the baseline fee and the
+    /// response bytes are charged as for a replicated request.
+    pub fn non_replicated_http_request_fee(
+        &self,
+        request_size: NumBytes,
+        response_size_limit: Option<NumBytes>,
+        subnet_size: usize,
+    ) -> Cycles {
+        let response_size = match response_size_limit {
+            Some(response_size) => response_size.get(),
+            // Defaults to maximum response size.
+            None => MAX_CANISTER_HTTP_RESPONSE_BYTES,
+        };
+        let request_fee =
+            (self.config.http_request_per_byte_fee * request_size.get()) / subnet_size.max(1);
+        self.scale_cost(
+            self.config.http_request_baseline_fee
+                + self.config.http_request_per_byte_fee * response_size
+                + request_fee,
+            subnet_size,
+        )
+    }
 }
 
 /// Encapsulates the payer and cost of inducting an ingress messages.
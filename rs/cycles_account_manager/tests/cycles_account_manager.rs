@@ -12,7 +12,7 @@ use ic_test_utilities::{
     state::{new_canister_state, SystemStateBuilder},
     types::{
         ids::{canister_test_id, subnet_test_id, user_test_id},
-        messages::SignedIngressBuilder,
+        messages::{RequestBuilder, SignedIngressBuilder},
     },
 };
 use ic_test_utilities_logger::with_test_replica_logger;
@@ -694,3 +694,73 @@ fn consume_cycles_updates_consumed_cycles() {
         NominalCycles::from(1_000_000)
     );
 }
+
+#[test]
+fn withdraw_request_cycles_withdraws_xnet_call_total_fee() {
+    let mut system_state = SystemStateBuilder::new().build();
+    let cycles_account_manager = CyclesAccountManagerBuilder::new()
+        .with_subnet_type(SubnetType::Application)
+        .build();
+    let request = RequestBuilder::new()
+        .method_name("method")
+        .method_payload(vec![0; 1000])
+        .build();
+
+    let balance_before = system_state.balance();
+    cycles_account_manager
+        .withdraw_request_cycles(
+            system_state.canister_id,
+            system_state.balance_mut(),
+            system_state.freeze_threshold,
+            system_state.memory_allocation,
+            NumBytes::from(0),
+            ComputeAllocation::default(),
+            &request,
+            cycles_account_manager.prepayment_for_response_execution(SMALL_APP_SUBNET_MAX_SIZE),
+            cycles_account_manager.prepayment_for_response_transmission(SMALL_APP_SUBNET_MAX_SIZE),
+            SMALL_APP_SUBNET_MAX_SIZE,
+        )
+        .unwrap();
+
+    assert_eq!(
+        balance_before - system_state.balance(),
+        cycles_account_manager
+            .xnet_call_total_fee(request.payload_size_bytes(), SMALL_APP_SUBNET_MAX_SIZE)
+    );
+}
+
+#[test]
+fn non_replicated_http_request_fee_is_not_larger_than_replicated_fee() {
+    let cycles_account_manager = CyclesAccountManagerBuilder::new()
+        .with_subnet_type(SubnetType::Application)
+        .build();
+    let request_size = NumBytes::from(1_000);
+    let response_size_limit = Some(NumBytes::from(2_000));
+
+    let replicated_fee = cycles_account_manager.http_request_fee(
+        request_size,
+        response_size_limit,
+        SMALL_APP_SUBNET_MAX_SIZE,
+    );
+    let non_replicated_fee = cycles_account_manager.non_replicated_http_request_fee(
+        request_size,
+        response_size_limit,
+        SMALL_APP_SUBNET_MAX_SIZE,
+    );
+    assert!(non_replicated_fee < replicated_fee);
+
+    // Without any request bytes, only the baseline and response bytes are
+    // charged, which are the same in both modes.
+    assert_eq!(
+        cycles_account_manager.non_replicated_http_request_fee(
+            NumBytes::from(0),
+            response_size_limit,
+            SMALL_APP_SUBNET_MAX_SIZE,
+        ),
+        cycles_account_manager.http_request_fee(
+            NumBytes::from(0),
+            response_size_limit,
+            SMALL_APP_SUBNET_MAX_SIZE,
+        )
+    );
+}
@@ -1,14 +1,16 @@
 //! Standalone interface for testing application canisters.
 
-use crate::message::{msg_stream_from_file, Message};
+use crate::message::{msg_stream_from_file, ExpectedReply, Message};
+use candid::{IDLArgs, TypeEnv};
 use hex::encode;
 use ic_config::{subnet_config::SubnetConfigs, Config};
 use ic_cycles_account_manager::CyclesAccountManager;
 use ic_error_types::{ErrorCode, UserError};
 use ic_execution_environment::ExecutionServices;
 use ic_http_endpoints_metrics::MetricsHttpEndpoint;
+use ic_ic00_types::{CanisterStatusResultV2, Payload};
 use ic_interfaces::{execution_environment::IngressHistoryReader, messaging::MessageRouting};
-use ic_interfaces_state_manager::StateReader;
+use ic_interfaces_state_manager::{CertificationScope, StateManager, StateReader};
 use ic_messaging::MessageRoutingImpl;
 use ic_metrics::MetricsRegistry;
 use ic_protobuf::registry::{
@@ -34,16 +36,18 @@ use ic_types::malicious_flags::MaliciousFlags;
 use ic_types::{
     batch::{Batch, BatchPayload, IngressPayload},
     ingress::{IngressState, IngressStatus, WasmResult},
-    messages::{MessageId, SignedIngress},
+    messages::{extract_effective_canister_id, MessageId, SignedIngress},
     replica_config::ReplicaConfig,
-    time, CanisterId, NodeId, PrincipalId, Randomness, RegistryVersion, SubnetId,
+    time::{self, current_time_and_expiry_time},
+    CanisterId, Cycles, NodeId, PrincipalId, Randomness, RegistryVersion, SubnetId, Time,
 };
 use rand::distributions::{Distribution, Uniform};
+use serde_json::json;
 use slog::{Drain, Logger};
 use std::collections::BTreeMap;
 use std::fs::OpenOptions;
 use std::path::PathBuf;
-use std::sync::{Arc, Mutex};
+use std::sync::{Arc, Mutex, RwLock};
 use std::{thread::sleep, time::Duration};
 
 mod message;
@@ -53,28 +57,71 @@ mod message;
 const MAX_BATCHES_UNTIL_RESPONSE: u64 = 10000;
 // how long to wait between batches
 const WAIT_PER_BATCH: Duration = Duration::from_millis(5);
+// histograms whose sums add up to all instructions executed by the replica
+const INSTRUCTIONS_METRICS: [&str; 2] = [
+    "execution_round_instructions",
+    "execution_query_instructions",
+];
+
+/// How `drun` reports the outcome of each message.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// One human readable line per message.
+    Text,
+    /// One JSON object per line and message, also reporting the instructions
+    /// executed and cycles charged while processing the message.
+    Json,
+}
 
 pub struct DrunOptions {
     pub msg_filename: String,
     pub cfg: Config,
     pub extra_batches: u64,
     pub log_file: Option<PathBuf>,
+    pub output_format: OutputFormat,
 }
 
-/// Deliver a single message to the Message Routing layer
+/// The replica time as seen by `drun`: wall clock time, shifted forward by all
+/// `advance-time` messages processed so far.
+#[derive(Clone, Default)]
+pub(crate) struct Clock(Arc<RwLock<Duration>>);
+
+impl Clock {
+    fn time(&self) -> Time {
+        time::current_time() + *self.0.read().unwrap()
+    }
+
+    /// Returns an ingress expiry time that is valid at `self.time()`.
+    pub(crate) fn expiry_time(&self) -> Time {
+        current_time_and_expiry_time().1 + *self.0.read().unwrap()
+    }
+
+    fn advance(&self, duration: Duration) {
+        *self.0.write().unwrap() += duration;
+    }
+}
+
+/// Deliver a single message to the Message Routing layer and return its result.
 fn deliver_message(
     msg: SignedIngress,
     message_routing: &dyn MessageRouting,
     ingress_hist_reader: &dyn IngressHistoryReader,
     extra_batches: u64,
-) {
+    clock: &Clock,
+) -> Result<WasmResult, UserError> {
     let message_id = msg.id();
 
-    let _ = execute_ingress_message(message_routing, msg, &message_id, ingress_hist_reader);
-    // print result after waiting, to not interleave the result
+    let _ = execute_ingress_message(
+        message_routing,
+        msg,
+        &message_id,
+        ingress_hist_reader,
+        clock,
+    );
+    // retrieve result after waiting, to not interleave the result
     // with debug.print messages from subsequent calls. revise after DFN-1269.
-    wait_extra_batches(message_routing, extra_batches);
-    print_ingress_result(&message_id, ingress_hist_reader);
+    wait_extra_batches(message_routing, extra_batches, clock);
+    ingress_result(&message_id, ingress_hist_reader)
 }
 
 fn setup_logger(log_file: PathBuf) -> Logger {
@@ -156,6 +203,7 @@ pub fn run_drun(uo: DrunOptions) -> Result<(), String> {
         cfg,
         extra_batches,
         log_file,
+        output_format,
     } = uo;
     // Hardcoded magic values to create a ReplicaConfig that parses.
     let subnet_type = SubnetType::System;
@@ -167,7 +215,8 @@ pub fn run_drun(uo: DrunOptions) -> Result<(), String> {
         subnet_id,
     };
 
-    let mut msg_stream = msg_stream_from_file(&msg_filename)?;
+    let clock = Clock::default();
+    let msg_stream = msg_stream_from_file(&msg_filename, clock.clone())?;
     let log = match log_file {
         Some(log_file) => setup_logger(log_file),
         None => slog::Logger::root(slog::Discard, slog::o!()),
@@ -234,49 +283,114 @@ pub fn run_drun(uo: DrunOptions) -> Result<(), String> {
         MaliciousFlags::default(),
     );
 
-    msg_stream.try_for_each(|parse_result| {
-        parse_result.map(|msg| match msg {
-            Message::Install(msg) => {
-                deliver_message(
+    // The result of the last message that produced one, for assertions.
+    let mut last_result: Option<Result<WasmResult, UserError>> = None;
+    let mut failed_assertions = 0;
+
+    for parse_result in msg_stream {
+        let msg = parse_result?;
+        let kind = msg.kind();
+        let target_canister = target_canister(&msg, subnet_id);
+        let instructions_before = total_instructions_executed(&metrics_registry);
+        let cycles_before = consumed_cycles(state_manager.as_ref(), target_canister);
+
+        let outcome = match msg {
+            Message::Install(msg)
+            | Message::Ingress(msg)
+            | Message::Create(msg)
+            | Message::Stop(msg)
+            | Message::Start(msg)
+            | Message::CanisterStatus(msg) => {
+                let result = deliver_message(
                     msg,
                     &message_routing,
                     ingress_hist_reader.as_ref(),
                     extra_batches,
+                    &clock,
                 );
+                last_result = Some(result.clone());
+                Outcome::Result(result)
             }
 
             Message::Query(q) => {
                 // NOTE: Data certificates aren't supported in drun yet.
                 // To support them, we'd need to do something similar to
                 // http_handler::get_latest_certified_state_and_data_certificate
-                print_query_result(query_handler.query(
-                    q,
-                    state_manager.get_latest_state().take(),
-                    Vec::new(),
-                ));
+                let result =
+                    query_handler.query(q, state_manager.get_latest_state().take(), Vec::new());
+                last_result = Some(result.clone());
+                Outcome::Result(result)
             }
 
-            Message::Ingress(msg) => {
-                deliver_message(
-                    msg,
-                    &message_routing,
-                    ingress_hist_reader.as_ref(),
-                    extra_batches,
-                );
+            Message::AssertReply(expected) => {
+                Outcome::Assertion(check_reply(&expected, last_result.as_ref()))
             }
-            Message::Create(msg) => {
-                deliver_message(
-                    msg,
-                    &message_routing,
-                    ingress_hist_reader.as_ref(),
-                    extra_batches,
-                );
+
+            Message::AssertReject(expected) => {
+                Outcome::Assertion(check_reject(expected.as_deref(), last_result.as_ref()))
             }
-        })
-    })
+
+            Message::AdvanceTime(duration) => {
+                clock.advance(duration);
+                // Execute a round at the new time, e.g. to run canister timers.
+                wait_extra_batches(&message_routing, 1, &clock);
+                Outcome::Done
+            }
+
+            Message::SetCycles(canister_id, cycles) => {
+                wait_until_idle(&message_routing, state_manager.as_ref());
+                set_cycles(state_manager.as_ref(), canister_id, cycles)?;
+                Outcome::Done
+            }
+        };
+
+        if let Outcome::Assertion(Err(_)) = outcome {
+            failed_assertions += 1;
+        }
+        match output_format {
+            OutputFormat::Text => print_outcome(kind, &outcome),
+            OutputFormat::Json => {
+                let instructions = total_instructions_executed(&metrics_registry)
+                    .saturating_sub(instructions_before);
+                let cycles = consumed_cycles(state_manager.as_ref(), target_canister)
+                    .saturating_sub(cycles_before);
+                print_json_outcome(kind, &outcome, instructions, cycles)
+            }
+        }
+    }
+
+    if failed_assertions > 0 {
+        return Err(format!("{} assertion(s) failed", failed_assertions));
+    }
+    Ok(())
+}
+
+/// The outcome of processing a single message.
+enum Outcome {
+    /// The result of an ingress message or query.
+    Result(Result<WasmResult, UserError>),
+    /// The result of an assertion, with the reason for a failure.
+    Assertion(Result<(), String>),
+    /// A message that produces no result.
+    Done,
 }
 
-fn print_query_result(res: Result<WasmResult, UserError>) {
+fn print_outcome(kind: &str, outcome: &Outcome) {
+    match (kind, outcome) {
+        ("query", Outcome::Result(res)) => print_query_result(res),
+        ("canister-status", Outcome::Result(Ok(WasmResult::Reply(reply)))) => {
+            print_canister_status(reply)
+        }
+        ("canister-status", Outcome::Result(Err(error))) => {
+            println!("canister-status Err: {}", error)
+        }
+        (_, Outcome::Result(res)) => print_ingress_result(res),
+        (_, Outcome::Assertion(Ok(()))) | (_, Outcome::Done) => println!("{} Ok", kind),
+        (_, Outcome::Assertion(Err(reason))) => println!("{} Failed: {}", kind, reason),
+    }
+}
+
+fn print_query_result(res: &Result<WasmResult, UserError>) {
     match res {
         Ok(payload) => {
             print!("Ok: ");
@@ -286,29 +400,221 @@ fn print_query_result(res: Result<WasmResult, UserError>) {
     }
 }
 
-fn print_ingress_result(message_id: &MessageId, ingress_hist_reader: &dyn IngressHistoryReader) {
-    let status = (ingress_hist_reader.get_latest_status())(message_id);
+fn print_ingress_result(res: &Result<WasmResult, UserError>) {
     print!("ingress ");
-    match status {
-        IngressStatus::Known {
-            state: IngressState::Completed(result),
-            ..
-        } => {
+    match res {
+        Ok(result) => {
             print!("Completed: ");
             print_wasm_result(result)
         }
+        Err(error) => println!("Err: {}", error),
+    };
+}
+
+fn print_canister_status(reply: &[u8]) {
+    match CanisterStatusResultV2::decode(reply) {
+        Ok(status) => println!(
+            "canister-status Completed: status: {}, cycles: {}, memory size: {}",
+            status.status(),
+            status.cycles(),
+            status.memory_size()
+        ),
+        Err(err) => println!("canister-status Err: Failed to decode reply: {}", err),
+    }
+}
+
+fn print_wasm_result(wasm_result: &WasmResult) {
+    match wasm_result {
+        WasmResult::Reply(v) => println!("Reply: 0x{}", encode(v)),
+        WasmResult::Reject(e) => println!("Reject: {}", e),
+    }
+}
+
+fn print_json_outcome(kind: &str, outcome: &Outcome, instructions: u64, cycles: u128) {
+    let mut output = json!({
+        "kind": kind,
+        "instructions_used": instructions,
+        "cycles_charged": cycles,
+    });
+    let fields = match outcome {
+        Outcome::Result(Ok(WasmResult::Reply(reply))) => {
+            json!({"status": "reply", "reply": format!("0x{}", encode(reply))})
+        }
+        Outcome::Result(Ok(WasmResult::Reject(message))) => {
+            json!({"status": "reject", "message": message})
+        }
+        Outcome::Result(Err(error)) => json!({
+            "status": "error",
+            "error_code": error.code().to_string(),
+            "message": error.description(),
+        }),
+        Outcome::Assertion(Ok(())) => json!({"status": "passed"}),
+        Outcome::Assertion(Err(reason)) => json!({"status": "failed", "message": reason}),
+        Outcome::Done => json!({"status": "ok"}),
+    };
+    if let (Some(output), Some(fields)) = (output.as_object_mut(), fields.as_object()) {
+        output.extend(fields.clone());
+    }
+    println!("{}", output);
+}
+
+/// Returns the latest status of the given ingress message as a result.
+///
+/// Panics if the message has not finished processing.
+fn ingress_result(
+    message_id: &MessageId,
+    ingress_hist_reader: &dyn IngressHistoryReader,
+) -> Result<WasmResult, UserError> {
+    match (ingress_hist_reader.get_latest_status())(message_id) {
+        IngressStatus::Known {
+            state: IngressState::Completed(result),
+            ..
+        } => Ok(result),
         IngressStatus::Known {
             state: IngressState::Failed(error),
             ..
-        } => println!("Err: {}", error),
+        } => Err(error),
         _ => panic!("Ingress message has not finished processing."),
+    }
+}
+
+/// Checks the result of the preceding message against an `assert-reply`.
+fn check_reply(
+    expected: &ExpectedReply,
+    result: Option<&Result<WasmResult, UserError>>,
+) -> Result<(), String> {
+    let reply = match result {
+        Some(Ok(WasmResult::Reply(reply))) => reply,
+        Some(Ok(WasmResult::Reject(message))) => {
+            return Err(format!("Expected a reply, got reject: {}", message))
+        }
+        Some(Err(error)) => return Err(format!("Expected a reply, got error: {}", error)),
+        None => return Err("No preceding message to assert on".to_string()),
+    };
+
+    match expected {
+        ExpectedReply::Bytes(expected) if expected == reply => Ok(()),
+        ExpectedReply::Bytes(expected) => Err(format!(
+            "Expected reply 0x{}, got 0x{}",
+            encode(expected),
+            encode(reply)
+        )),
+        ExpectedReply::Candid(expected) => {
+            let actual = IDLArgs::from_bytes(reply).map_err(|e| {
+                format!(
+                    "Failed to decode reply 0x{} as Candid: {}",
+                    encode(reply),
+                    e
+                )
+            })?;
+            // Annotate the expected values with the types of the actual reply,
+            // so that e.g. `42` matches a `nat`.
+            let expected = expected
+                .parse::<IDLArgs>()
+                .and_then(|args| args.annotate_types(true, &TypeEnv::new(), &actual.get_types()))
+                .map_err(|e| format!("Expected reply {}, got {}: {}", expected, actual, e))?;
+            if expected.args == actual.args {
+                Ok(())
+            } else {
+                Err(format!("Expected reply {}, got {}", expected, actual))
+            }
+        }
+    }
+}
+
+/// Checks the result of the preceding message against an `assert-reject`.
+fn check_reject(
+    expected: Option<&str>,
+    result: Option<&Result<WasmResult, UserError>>,
+) -> Result<(), String> {
+    let message = match result {
+        Some(Ok(WasmResult::Reject(message))) => message.clone(),
+        Some(Err(error)) => error.to_string(),
+        Some(Ok(WasmResult::Reply(reply))) => {
+            return Err(format!("Expected a reject, got reply 0x{}", encode(reply)))
+        }
+        None => return Err("No preceding message to assert on".to_string()),
     };
+
+    match expected {
+        Some(expected) if !message.contains(expected) => Err(format!(
+            "Expected a reject containing \"{}\", got: {}",
+            expected, message
+        )),
+        _ => Ok(()),
+    }
 }
 
-fn print_wasm_result(wasm_result: WasmResult) {
-    match wasm_result {
-        WasmResult::Reply(v) => println!("Reply: 0x{}", encode(v)),
-        WasmResult::Reject(e) => println!("Reject: {}", e),
+/// Returns the canister that a message is executed on, if any.
+fn target_canister(msg: &Message, subnet_id: SubnetId) -> Option<CanisterId> {
+    match msg {
+        Message::Install(msg)
+        | Message::Ingress(msg)
+        | Message::Create(msg)
+        | Message::Stop(msg)
+        | Message::Start(msg)
+        | Message::CanisterStatus(msg) => {
+            match extract_effective_canister_id(msg.content(), subnet_id) {
+                Ok(Some(canister_id)) => Some(canister_id),
+                _ => Some(msg.canister_id()),
+            }
+        }
+        Message::Query(query) => Some(query.receiver),
+        Message::SetCycles(canister_id, _) => Some(*canister_id),
+        Message::AssertReply(_) | Message::AssertReject(_) | Message::AdvanceTime(_) => None,
+    }
+}
+
+/// Returns the total number of instructions executed so far.
+fn total_instructions_executed(metrics_registry: &MetricsRegistry) -> u64 {
+    metrics_registry
+        .prometheus_registry()
+        .gather()
+        .iter()
+        .filter(|family| INSTRUCTIONS_METRICS.contains(&family.get_name()))
+        .flat_map(|family| family.get_metric().iter())
+        .map(|metric| metric.get_histogram().get_sample_sum() as u64)
+        .sum()
+}
+
+/// Returns the cycles consumed so far by the given canister; or zero if there
+/// is no such canister.
+fn consumed_cycles(state_manager: &StateManagerImpl, canister_id: Option<CanisterId>) -> u128 {
+    let state = state_manager.get_latest_state().take();
+    canister_id
+        .and_then(|canister_id| state.canister_state(&canister_id))
+        .map(|canister| {
+            canister
+                .system_state
+                .canister_metrics
+                .consumed_cycles_since_replica_started
+                .get()
+        })
+        .unwrap_or(0)
+}
+
+/// Sets the cycles balance of the given canister by committing a modified copy
+/// of the latest state.
+fn set_cycles(
+    state_manager: &StateManagerImpl,
+    canister_id: CanisterId,
+    cycles: Cycles,
+) -> Result<(), String> {
+    let (height, mut state) = state_manager.take_tip();
+    let canister = state
+        .canister_state_mut(&canister_id)
+        .ok_or_else(|| format!("Canister {} not found", canister_id))?;
+    *canister.system_state.balance_mut() = cycles;
+    state_manager.commit_and_certify(state, height.increment(), CertificationScope::Metadata);
+    Ok(())
+}
+
+/// Blocks until Message Routing has committed a state for every delivered
+/// batch, so that the tip may be taken.
+fn wait_until_idle(message_routing: &dyn MessageRouting, state_manager: &StateManagerImpl) {
+    while state_manager.latest_state_height().increment() < message_routing.expected_batch_height()
+    {
+        sleep(WAIT_PER_BATCH);
     }
 }
 
@@ -319,7 +625,11 @@ fn get_random_seed() -> [u8; 32] {
     seed.try_into().unwrap()
 }
 
-fn build_batch(message_routing: &dyn MessageRouting, msgs: Vec<SignedIngress>) -> Batch {
+fn build_batch(
+    message_routing: &dyn MessageRouting,
+    msgs: Vec<SignedIngress>,
+    time: Time,
+) -> Batch {
     Batch {
         batch_number: message_routing.expected_batch_height(),
         requires_full_state_hash: !msgs.is_empty(),
@@ -329,8 +639,9 @@ fn build_batch(message_routing: &dyn MessageRouting, msgs: Vec<SignedIngress>) -
         },
         randomness: Randomness::from(get_random_seed()),
         ecdsa_subnet_public_keys: BTreeMap::new(),
+        schnorr_subnet_public_keys: BTreeMap::new(),
         registry_version: RegistryVersion::from(1),
-        time: time::current_time(),
+        time,
         consensus_responses: vec![],
     }
 }
@@ -343,8 +654,9 @@ fn execute_ingress_message(
     msg: SignedIngress,
     msg_id: &MessageId,
     ingress_history: &dyn IngressHistoryReader,
+    clock: &Clock,
 ) -> Result<WasmResult, UserError> {
-    let mut batch = build_batch(message_routing, vec![msg]);
+    let mut batch = build_batch(message_routing, vec![msg], clock.time());
     for _ in 0..MAX_BATCHES_UNTIL_RESPONSE {
         // In the first batch we try to send the ingress message itself. If it fails, we
         // repeat with the same batch.
@@ -355,7 +667,7 @@ fn execute_ingress_message(
         // potential inter-canister messages that the ingress message may have
         // triggered.
         if message_routing.deliver_batch(batch.clone()).is_ok() {
-            batch = build_batch(message_routing, vec![])
+            batch = build_batch(message_routing, vec![], clock.time())
         }
         sleep(WAIT_PER_BATCH);
 
@@ -401,10 +713,10 @@ fn execute_ingress_message(
 ///
 /// This is a temporary measure until DFN-1269 is resolved. In that ticket, we
 /// will actually try to wait until all messages have been executed.
-fn wait_extra_batches(message_routing: &dyn MessageRouting, extra_batches: u64) {
+fn wait_extra_batches(message_routing: &dyn MessageRouting, extra_batches: u64, clock: &Clock) {
     for _ in 0..extra_batches {
         loop {
-            let batch = build_batch(message_routing, vec![]);
+            let batch = build_batch(message_routing, vec![], clock.time());
             let ok = message_routing.deliver_batch(batch).is_ok();
             sleep(WAIT_PER_BATCH);
             if ok {
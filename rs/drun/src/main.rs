@@ -4,7 +4,7 @@ use ic_canister_sandbox_backend_lib::{
 };
 use ic_canister_sandbox_launcher::sandbox_launcher_main;
 use ic_config::{flag_status::FlagStatus, Config, ConfigSource};
-use ic_drun::{run_drun, DrunOptions};
+use ic_drun::{run_drun, DrunOptions, OutputFormat};
 use std::path::PathBuf;
 
 const DEFAULT_CONFIG_FILE: &str = "ic.json5";
@@ -13,6 +13,7 @@ const ARG_CONF: &str = "config";
 const ARG_LOG_FILE: &str = "log-file";
 const ARG_MESSAGES: &str = "messages";
 const ARG_EXTRA_BATCHES: &str = "extra-batches";
+const ARG_FORMAT: &str = "format";
 
 fn main() -> Result<(), String> {
     // Check if `drun` is running in the canister sandbox mode where it waits
@@ -61,11 +62,17 @@ async fn drun_main() -> Result<(), String> {
             })
             .unwrap_or(DEFAULT_EXTRA_BATCHES);
 
+        let output_format = match matches.value_of(ARG_FORMAT) {
+            Some("json") => OutputFormat::Json,
+            _ => OutputFormat::Text,
+        };
+
         let uo = DrunOptions {
             msg_filename: matches.value_of(ARG_MESSAGES).unwrap().to_string(),
             cfg,
             extra_batches,
             log_file,
+            output_format,
         };
         run_drun(uo)
     })
@@ -107,6 +114,14 @@ fn get_arg_matches() -> ArgMatches {
                 .value_name("Query/Ingress Messages")
                 .help("Text file containing one message per line."),
         )
+        .arg(
+            Arg::new(ARG_FORMAT)
+                .long(ARG_FORMAT)
+                .value_name("format")
+                .help("Output format, one of `text` or `json` (default: text).")
+                .possible_values(["text", "json"])
+                .takes_value(true),
+        )
         .arg(
             Arg::new(ARG_LOG_FILE)
                 .long(ARG_LOG_FILE)
@@ -1,11 +1,11 @@
-use super::CanisterId;
+use super::{CanisterId, Clock};
 
+use candid::IDLArgs;
 use hex::decode;
-use ic_ic00_types::{self as ic00, CanisterInstallMode, Payload};
+use ic_ic00_types::{self as ic00, CanisterIdRecord, CanisterInstallMode, Payload};
 use ic_types::{
     messages::{SignedIngress, UserQuery},
-    time::current_time_and_expiry_time,
-    PrincipalId, UserId,
+    Cycles, PrincipalId, Time, UserId,
 };
 
 use std::{
@@ -15,6 +15,7 @@ use std::{
     io::{self, Read},
     str::Chars,
     string::FromUtf8Error,
+    time::Duration,
 };
 
 #[derive(Debug, PartialEq)]
@@ -23,6 +24,44 @@ pub(crate) enum Message {
     Query(UserQuery),
     Install(SignedIngress),
     Create(SignedIngress),
+    Stop(SignedIngress),
+    Start(SignedIngress),
+    CanisterStatus(SignedIngress),
+    /// Asserts that the preceding message was replied to with the given reply.
+    AssertReply(ExpectedReply),
+    /// Asserts that the preceding message was rejected, optionally with a
+    /// reject message containing the given string.
+    AssertReject(Option<String>),
+    AdvanceTime(Duration),
+    SetCycles(CanisterId, Cycles),
+}
+
+impl Message {
+    /// The keyword introducing this kind of message in a `drun` script.
+    pub(crate) fn kind(&self) -> &'static str {
+        match self {
+            Message::Ingress(_) => "ingress",
+            Message::Query(_) => "query",
+            Message::Install(_) => "install",
+            Message::Create(_) => "create",
+            Message::Stop(_) => "stop",
+            Message::Start(_) => "start",
+            Message::CanisterStatus(_) => "canister-status",
+            Message::AssertReply(_) => "assert-reply",
+            Message::AssertReject(_) => "assert-reject",
+            Message::AdvanceTime(_) => "advance-time",
+            Message::SetCycles(_, _) => "set-cycles",
+        }
+    }
+}
+
+/// The reply expected by an `assert-reply` message.
+#[derive(Debug, PartialEq)]
+pub(crate) enum ExpectedReply {
+    /// The exact reply bytes.
+    Bytes(Vec<u8>),
+    /// Candid text, compared against the decoded reply.
+    Candid(String),
 }
 
 #[derive(Debug)]
@@ -123,8 +162,12 @@ impl<R: Read> Iterator for LineIterator<R> {
     }
 }
 
+/// Returns an iterator over the messages in the given file. Messages are
+/// parsed lazily, so ingress expiry times are relative to `clock` at the time
+/// the preceding message has been processed.
 pub(crate) fn msg_stream_from_file(
     filename: &str,
+    clock: Clock,
 ) -> Result<impl Iterator<Item = Result<Message, String>>, String> {
     let f = File::open(filename).map_err(|e| e.to_string())?;
     let line_iterator = LineIterator::new(f);
@@ -137,15 +180,28 @@ pub(crate) fn msg_stream_from_file(
             _ => true,
         })
         .map(|(i, line)| match line {
-            Ok(line) => {
-                parse_message(&line, i as u64).map_err(|e| format!("Line {}: {}", i + 1, e))
-            }
+            Ok(line) => parse_message(&line, i as u64, clock.expiry_time())
+                .map_err(|e| format!("Line {}: {}", i + 1, e)),
             Err(e) => Err(format!("Error while reading line {}: {}", i, e)),
         }))
 }
 
-fn parse_message(s: &str, nonce: u64) -> Result<Message, String> {
+fn parse_message(s: &str, nonce: u64, expiry_time: Time) -> Result<Message, String> {
     let s = s.trim_end();
+
+    // Expected replies and reject messages may contain whitespace.
+    match s.split_once(char::is_whitespace) {
+        Some(("assert-reply", expected)) => {
+            return parse_expected_reply(expected.trim()).map(Message::AssertReply)
+        }
+        Some(("assert-reject", expected)) => {
+            let expected = parse_quoted(expected.trim())?;
+            let expected = String::from_utf8(expected).map_err(|e| e.to_string())?;
+            return Ok(Message::AssertReject(Some(expected)));
+        }
+        _ => {}
+    }
+
     let tokens: Vec<&str> = s.splitn(4, char::is_whitespace).collect();
 
     match &tokens[..] {
@@ -164,6 +220,7 @@ fn parse_message(s: &str, nonce: u64) -> Result<Message, String> {
                 .method_name(method_name)
                 .method_payload(method_payload)
                 .nonce(nonce)
+                .expiry_time(expiry_time)
                 .build();
             Ok(Message::Ingress(signed_ingress))
         }
@@ -172,18 +229,65 @@ fn parse_message(s: &str, nonce: u64) -> Result<Message, String> {
             receiver: parse_canister_id(canister_id)?,
             method_name: validate_method_name(method_name)?,
             method_payload: parse_octet_string(payload)?,
-            ingress_expiry: current_time_and_expiry_time().1.as_nanos_since_unix_epoch(),
+            ingress_expiry: expiry_time.as_nanos_since_unix_epoch(),
             nonce: Some(nonce.to_le_bytes().to_vec()),
         })),
-        ["create"] => parse_create(nonce),
-        ["install", canister_id, wasm_file, payload] => {
-            parse_install(nonce, canister_id, payload, wasm_file, "install")
-        }
-        ["reinstall", canister_id, wasm_file, payload] => {
-            parse_install(nonce, canister_id, payload, wasm_file, "reinstall")
+        ["create"] => parse_create(nonce, expiry_time),
+        ["install", canister_id, wasm_file, payload] => parse_install(
+            nonce,
+            expiry_time,
+            canister_id,
+            payload,
+            wasm_file,
+            "install",
+        ),
+        ["reinstall", canister_id, wasm_file, payload] => parse_install(
+            nonce,
+            expiry_time,
+            canister_id,
+            payload,
+            wasm_file,
+            "reinstall",
+        ),
+        ["upgrade", canister_id, wasm_file, payload] => parse_install(
+            nonce,
+            expiry_time,
+            canister_id,
+            payload,
+            wasm_file,
+            "upgrade",
+        ),
+        ["stop", canister_id] => Ok(Message::Stop(parse_canister_management(
+            nonce,
+            expiry_time,
+            ic00::Method::StopCanister,
+            canister_id,
+        )?)),
+        ["start", canister_id] => Ok(Message::Start(parse_canister_management(
+            nonce,
+            expiry_time,
+            ic00::Method::StartCanister,
+            canister_id,
+        )?)),
+        ["canister-status", canister_id] => Ok(Message::CanisterStatus(parse_canister_management(
+            nonce,
+            expiry_time,
+            ic00::Method::CanisterStatus,
+            canister_id,
+        )?)),
+        ["assert-reject"] => Ok(Message::AssertReject(None)),
+        ["advance-time", seconds] => {
+            let seconds = seconds
+                .parse::<u64>()
+                .map_err(|e| format!("Failed to parse number of seconds {}: {}", seconds, e))?;
+            Ok(Message::AdvanceTime(Duration::from_secs(seconds)))
         }
-        ["upgrade", canister_id, wasm_file, payload] => {
-            parse_install(nonce, canister_id, payload, wasm_file, "upgrade")
+        ["set-cycles", canister_id, cycles] => {
+            let canister_id = parse_canister_id(canister_id)?;
+            let cycles = cycles
+                .parse::<u128>()
+                .map_err(|e| format!("Failed to parse cycles amount {}: {}", cycles, e))?;
+            Ok(Message::SetCycles(canister_id, Cycles::new(cycles)))
         }
         _ => Err(format!(
             "Failed to parse line {}, don't have a pattern to match this with",
@@ -209,7 +313,7 @@ fn parse_canister_id(canister_id: &str) -> Result<CanisterId, String> {
     }
 }
 
-fn parse_create(nonce: u64) -> Result<Message, String> {
+fn parse_create(nonce: u64, expiry_time: Time) -> Result<Message, String> {
     use ic_test_utilities::types::messages::SignedIngressBuilder;
 
     let signed_ingress = SignedIngressBuilder::new()
@@ -217,13 +321,36 @@ fn parse_create(nonce: u64) -> Result<Message, String> {
         .canister_id(ic00::IC_00)
         .method_payload(ic00::ProvisionalCreateCanisterWithCyclesArgs::new(None, None).encode())
         .nonce(nonce)
+        .expiry_time(expiry_time)
         .build();
 
     Ok(Message::Create(signed_ingress))
 }
 
+/// Builds an ingress message calling the given management canister method
+/// with a `CanisterIdRecord` argument.
+fn parse_canister_management(
+    nonce: u64,
+    expiry_time: Time,
+    method: ic00::Method,
+    canister_id: &str,
+) -> Result<SignedIngress, String> {
+    use ic_test_utilities::types::messages::SignedIngressBuilder;
+
+    let canister_id = parse_canister_id(canister_id)?;
+
+    Ok(SignedIngressBuilder::new()
+        .method_name(method)
+        .canister_id(ic00::IC_00)
+        .method_payload(CanisterIdRecord::from(canister_id).encode())
+        .nonce(nonce)
+        .expiry_time(expiry_time)
+        .build())
+}
+
 fn parse_install(
     nonce: u64,
+    expiry_time: Time,
     canister_id: &str,
     payload: &str,
     wasm_file: &str,
@@ -259,10 +386,24 @@ fn parse_install(
             .encode(),
         )
         .nonce(nonce)
+        .expiry_time(expiry_time)
         .build();
     Ok(Message::Install(signed_ingress))
 }
 
+/// Parses the expected reply of an `assert-reply` message: either Candid text
+/// enclosed in parentheses or an octet string.
+fn parse_expected_reply(expected: &str) -> Result<ExpectedReply, String> {
+    if expected.starts_with('(') {
+        expected
+            .parse::<IDLArgs>()
+            .map_err(|e| format!("Failed to parse Candid value {}: {}", expected, e))?;
+        Ok(ExpectedReply::Candid(expected.to_string()))
+    } else {
+        parse_octet_string(expected).map(ExpectedReply::Bytes)
+    }
+}
+
 fn validate_method_name(method_name: &str) -> Result<String, String> {
     fn is_ident_start(c: char) -> bool {
         c.is_ascii() && (c.is_alphabetic() || c == '_')
@@ -372,6 +513,7 @@ enum Radix {
 mod tests {
     use super::*;
     use ic_test_utilities::types::{ids::canister_test_id, messages::SignedIngressBuilder};
+    use ic_types::time::current_time_and_expiry_time;
     use std::io::Cursor;
 
     const APP_CANISTER_URL: &str = "ryjl3-tyaaa-aaaaa-aaaba-cai";
@@ -383,7 +525,7 @@ mod tests {
             "ingress {} write \"payload \\x0a\\b00010001\"",
             APP_CANISTER_URL
         );
-        let parsed_message = parse_message(s, 0).unwrap();
+        let parsed_message = parse_message(s, 0, current_time_and_expiry_time().1).unwrap();
         let expiry_time = match &parsed_message {
             Message::Ingress(signed_ingress) => signed_ingress.expiry_time(),
             _ => panic!(
@@ -406,7 +548,7 @@ mod tests {
     #[test]
     fn test_parse_message_hex_payload_succeeds() {
         let s = &format!("ingress {} write 0x010203", APP_CANISTER_URL);
-        let parsed_message = parse_message(s, 0).unwrap();
+        let parsed_message = parse_message(s, 0, current_time_and_expiry_time().1).unwrap();
         let expiry_time = match &parsed_message {
             Message::Ingress(signed_ingress) => signed_ingress.expiry_time(),
             _ => panic!(
@@ -427,7 +569,7 @@ mod tests {
 
         let s = &format!("query {} read 0x010203", APP_CANISTER_URL);
         let nonce: u64 = 0;
-        let parsed_message = parse_message(s, 0).unwrap();
+        let parsed_message = parse_message(s, 0, current_time_and_expiry_time().1).unwrap();
         let ingress_expiry = match &parsed_message {
             Message::Query(query) => query.ingress_expiry,
             _ => panic!(
@@ -449,25 +591,86 @@ mod tests {
     #[test]
     fn test_parse_message_invalid_escapes_fails() {
         let s = &format!("query {} read \"\\xzz\"", APP_CANISTER_URL);
-        assert!(parse_message(s, 0).is_err());
+        assert!(parse_message(s, 0, current_time_and_expiry_time().1).is_err());
 
         let s = &format!("query {} read \"\\b01\"", APP_CANISTER_URL);
-        assert!(parse_message(s, 0).is_err());
+        assert!(parse_message(s, 0, current_time_and_expiry_time().1).is_err());
 
         let s = &format!("query {} read \"\\x1\"", APP_CANISTER_URL);
-        assert!(parse_message(s, 0).is_err());
+        assert!(parse_message(s, 0, current_time_and_expiry_time().1).is_err());
 
         let s = &format!("query {} read \"\\b2\"", APP_CANISTER_URL);
-        assert!(parse_message(s, 0).is_err());
+        assert!(parse_message(s, 0, current_time_and_expiry_time().1).is_err());
     }
 
     #[test]
     fn test_illegal_method_name_must_fail() {
         let s = &format!("query {} 0read \"\\xzz\"", APP_CANISTER_URL);
-        assert!(parse_message(s, 0).is_err());
+        assert!(parse_message(s, 0, current_time_and_expiry_time().1).is_err());
 
         let s = &format!("query {} üread \"\\xzz\"", APP_CANISTER_URL);
-        assert!(parse_message(s, 0).is_err());
+        assert!(parse_message(s, 0, current_time_and_expiry_time().1).is_err());
+    }
+
+    #[test]
+    fn test_parse_assertions() {
+        let expiry_time = current_time_and_expiry_time().1;
+        assert_eq!(
+            Ok(Message::AssertReply(ExpectedReply::Bytes(vec![1, 2, 3]))),
+            parse_message("assert-reply 0x010203", 0, expiry_time)
+        );
+        assert_eq!(
+            Ok(Message::AssertReply(ExpectedReply::Candid(
+                "(\"hello world\", 42 : nat)".to_string()
+            ))),
+            parse_message("assert-reply (\"hello world\", 42 : nat)", 0, expiry_time)
+        );
+        assert!(parse_message("assert-reply (\"unterminated", 0, expiry_time).is_err());
+        assert_eq!(
+            Ok(Message::AssertReject(None)),
+            parse_message("assert-reject", 0, expiry_time)
+        );
+        assert_eq!(
+            Ok(Message::AssertReject(Some("Canister trapped".to_string()))),
+            parse_message("assert-reject \"Canister trapped\"", 0, expiry_time)
+        );
+    }
+
+    #[test]
+    fn test_parse_advance_time_and_set_cycles() {
+        let expiry_time = current_time_and_expiry_time().1;
+        assert_eq!(
+            Ok(Message::AdvanceTime(Duration::from_secs(60))),
+            parse_message("advance-time 60", 0, expiry_time)
+        );
+        assert!(parse_message("advance-time -1", 0, expiry_time).is_err());
+        assert_eq!(
+            Ok(Message::SetCycles(
+                canister_test_id(APP_CANISTER_ID),
+                Cycles::new(1_000_000)
+            )),
+            parse_message(
+                &format!("set-cycles {} 1000000", APP_CANISTER_URL),
+                0,
+                expiry_time
+            )
+        );
+    }
+
+    #[test]
+    fn test_parse_canister_management() {
+        let expiry_time = current_time_and_expiry_time().1;
+        let expected = SignedIngressBuilder::new()
+            .method_name(ic00::Method::StopCanister)
+            .canister_id(ic00::IC_00)
+            .method_payload(CanisterIdRecord::from(canister_test_id(APP_CANISTER_ID)).encode())
+            .nonce(3)
+            .expiry_time(expiry_time)
+            .build();
+        assert_eq!(
+            Ok(Message::Stop(expected)),
+            parse_message(&format!("stop {}", APP_CANISTER_URL), 3, expiry_time)
+        );
     }
 
     #[test]
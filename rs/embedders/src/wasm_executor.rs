@@ -29,7 +29,9 @@ use ic_sys::{page_bytes_from_ptr, PageBytes, PageIndex, PAGE_SIZE};
 use ic_system_api::{
     system_api_empty::SystemApiEmpty, ExecutionParameters, ModificationTracking, SystemApiImpl,
 };
-use ic_types::{CanisterId, NumBytes, NumInstructions};
+use ic_types::{
+    canister_log::CanisterLog, CanisterId, NumBytes, NumInstructions, MAX_WASM64_MEMORY_IN_BYTES,
+};
 use ic_wasm_types::{BinaryEncodedWasm, CanisterModule};
 use std::collections::hash_map::DefaultHasher;
 use std::hash::{Hash, Hasher};
@@ -474,6 +476,7 @@ pub fn wasm_execution_error(
             allocated_bytes: NumBytes::from(0),
             allocated_message_bytes: NumBytes::from(0),
             instance_stats: InstanceStats::default(),
+            canister_log: CanisterLog::default(),
         },
         None,
     )
@@ -638,6 +641,7 @@ pub fn process(
                     allocated_bytes: NumBytes::from(0),
                     allocated_message_bytes: NumBytes::from(0),
                     instance_stats: InstanceStats::default(),
+                    canister_log: CanisterLog::default(),
                 },
                 None,
                 Err(system_api),
@@ -672,6 +676,7 @@ pub fn process(
     let message_instructions_left = message_instruction_limit - message_instructions_executed;
 
     let instance_stats = instance.get_stats();
+    let canister_log = instance.store_data_mut().system_api.take_canister_log();
 
     // Has the side effect of deallocating memory if message failed and
     // returning cycles from a request that wasn't sent.
@@ -681,8 +686,12 @@ pub fn process(
         .take_execution_result(run_result.as_ref().err());
 
     let wasm_heap_size_after = instance.heap_size(CanisterMemoryType::Heap);
-    let wasm_heap_limit =
-        NumWasmPages::from(wasmtime_environ::WASM32_MAX_PAGES as usize) - wasm_reserved_pages;
+    let wasm_max_pages = if instance.is_wasm64() {
+        (MAX_WASM64_MEMORY_IN_BYTES / wasmtime_environ::WASM_PAGE_SIZE as u64) as usize
+    } else {
+        wasmtime_environ::WASM32_MAX_PAGES as usize
+    };
+    let wasm_heap_limit = NumWasmPages::from(wasm_max_pages) - wasm_reserved_pages;
 
     if wasm_heap_size_after > wasm_heap_limit {
         wasm_result = Err(HypervisorError::WasmReservedPages);
@@ -760,6 +769,7 @@ pub fn process(
             allocated_bytes,
             allocated_message_bytes,
             instance_stats,
+            canister_log,
         },
         wasm_state_changes,
         Ok(instance),
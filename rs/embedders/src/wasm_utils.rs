@@ -49,6 +49,23 @@ pub struct WasmValidationDetails {
     pub imports_details: WasmImportsDetails,
     pub wasm_metadata: WasmMetadata,
     pub largest_function_instruction_count: NumInstructions,
+    // True if the main memory of the module is a 64-bit memory.
+    pub is_wasm64: bool,
+}
+
+/// Returns true if the main memory of the module (the memory with index 0) is
+/// a 64-bit memory as defined by the memory64 proposal.
+fn is_wasm64(module: &wasm_transform::Module) -> bool {
+    // Imported memories precede the defined ones in the memory index space.
+    module
+        .imports
+        .iter()
+        .find_map(|import| match import.ty {
+            wasmparser::TypeRef::Memory(memory) => Some(memory.memory64),
+            _ => None,
+        })
+        .or_else(|| module.memories.first().map(|memory| memory.memory64))
+        .unwrap_or(false)
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
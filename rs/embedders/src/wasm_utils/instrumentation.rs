@@ -112,12 +112,12 @@
 
 use super::system_api_replacements::replacement_functions;
 use super::validation::API_VERSION_IC0;
-use super::{InstrumentationOutput, Segments, SystemApiFunc};
+use super::{is_wasm64, InstrumentationOutput, Segments, SystemApiFunc};
 use ic_config::flag_status::FlagStatus;
 use ic_registry_subnet_type::SubnetType;
 use ic_replicated_state::NumWasmPages;
 use ic_sys::PAGE_SIZE;
-use ic_types::{methods::WasmMethod, MAX_WASM_MEMORY_IN_BYTES};
+use ic_types::{methods::WasmMethod, MAX_WASM64_MEMORY_IN_BYTES, MAX_WASM_MEMORY_IN_BYTES};
 use ic_types::{NumInstructions, MAX_STABLE_MEMORY_IN_BYTES};
 use ic_wasm_types::{BinaryEncodedWasm, WasmError, WasmInstrumentationError};
 use wasmtime_environ::WASM_PAGE_SIZE;
@@ -197,6 +197,10 @@ const CANISTER_START_STR: &str = "canister_start";
 const BYTEMAP_SIZE_IN_WASM_PAGES: u64 =
     MAX_WASM_MEMORY_IN_BYTES / (PAGE_SIZE as u64) / (WASM_PAGE_SIZE as u64);
 
+const MAX_WASM64_MEMORY_IN_WASM_PAGES: u64 = MAX_WASM64_MEMORY_IN_BYTES / (WASM_PAGE_SIZE as u64);
+/// There is one byte for each OS page in the 64-bit wasm heap.
+const WASM64_BYTEMAP_SIZE_IN_WASM_PAGES: u64 = MAX_WASM64_MEMORY_IN_WASM_PAGES / (PAGE_SIZE as u64);
+
 const MAX_STABLE_MEMORY_IN_WASM_PAGES: u64 = MAX_STABLE_MEMORY_IN_BYTES / (WASM_PAGE_SIZE as u64);
 /// There is one byte for each OS page in the stable memory.
 const STABLE_BYTEMAP_SIZE_IN_WASM_PAGES: u64 = MAX_STABLE_MEMORY_IN_WASM_PAGES / (PAGE_SIZE as u64);
@@ -242,10 +246,20 @@ fn mutate_function_indices(module: &mut Module, f: impl Fn(u32) -> u32) {
     }
 }
 
-fn inject_helper_functions(mut module: Module, wasm_native_stable_memory: FlagStatus) -> Module {
+fn inject_helper_functions(
+    mut module: Module,
+    wasm_native_stable_memory: FlagStatus,
+    is_wasm64: bool,
+) -> Module {
     // insert types
     let ooi_type = Type::Func(FuncType::new([], []));
-    let uam_type = Type::Func(FuncType::new([ValType::I32, ValType::I32], [ValType::I32]));
+    // `update_available_memory` takes and returns the operands of `memory.grow`,
+    // which are `i64` for a 64-bit memory.
+    let uam_type = if is_wasm64 {
+        Type::Func(FuncType::new([ValType::I64, ValType::I64], [ValType::I64]))
+    } else {
+        Type::Func(FuncType::new([ValType::I32, ValType::I32], [ValType::I32]))
+    };
 
     let ooi_type_idx = add_type(&mut module, ooi_type);
     let uam_type_idx = add_type(&mut module, uam_type);
@@ -355,10 +369,11 @@ pub(super) fn instrument(
     dirty_page_overhead: NumInstructions,
 ) -> Result<InstrumentationOutput, WasmInstrumentationError> {
     let stable_memory_index;
-    let mut module = inject_helper_functions(module, wasm_native_stable_memory);
+    let is_wasm64 = is_wasm64(&module);
+    let mut module = inject_helper_functions(module, wasm_native_stable_memory, is_wasm64);
     module = export_table(module);
     (module, stable_memory_index) =
-        update_memories(module, write_barrier, wasm_native_stable_memory);
+        update_memories(module, write_barrier, wasm_native_stable_memory, is_wasm64);
 
     let mut extra_strs: Vec<String> = Vec::new();
     module = export_mutable_globals(module, &mut extra_strs);
@@ -408,7 +423,7 @@ pub(super) fn instrument(
 
     // inject instructions counter decrementation
     for func_body in &mut module.code_sections {
-        inject_metering(&mut func_body.instructions, &special_indices);
+        inject_metering(&mut func_body.instructions, &special_indices, is_wasm64);
     }
 
     // Collect all the function types of the locally defined functions inside the
@@ -428,9 +443,9 @@ pub(super) fn instrument(
     if !func_types.is_empty() {
         let func_bodies = &mut module.code_sections;
         for (func_ix, func_type) in func_types.into_iter().enumerate() {
-            inject_update_available_memory(&mut func_bodies[func_ix], &func_type);
+            inject_update_available_memory(&mut func_bodies[func_ix], &func_type, is_wasm64);
             if write_barrier == FlagStatus::Enabled {
-                inject_mem_barrier(&mut func_bodies[func_ix], &func_type);
+                inject_mem_barrier(&mut func_bodies[func_ix], &func_type, is_wasm64);
             }
         }
     }
@@ -798,7 +813,7 @@ impl InjectionPoint {
 // - we insert a function call before each dynamic cost instruction which
 //   performs an overflow check and then decrements the counter by the value at
 //   the top of the stack.
-fn inject_metering(code: &mut Vec<Operator>, export_data_module: &SpecialIndices) {
+fn inject_metering(code: &mut Vec<Operator>, export_data_module: &SpecialIndices, is_wasm64: bool) {
     let points = injections(code);
     let points = points.iter().filter(|point| match point.cost_detail {
         InjectionPointCostDetail::StaticCost {
@@ -846,16 +861,29 @@ fn inject_metering(code: &mut Vec<Operator>, export_data_module: &SpecialIndices
                 }
             }
             InjectionPointCostDetail::DynamicCost => {
-                elems.extend_from_slice(&[
-                    I64ExtendI32U,
-                    Call {
+                // The size operand of `memory.fill` and `memory.copy` is
+                // already an `i64` if the memory is 64-bit.
+                let is_64_bit_size = is_wasm64
+                    && matches!(
+                        orig_elems[point.position],
+                        MemoryFill { .. } | MemoryCopy { .. }
+                    );
+                if is_64_bit_size {
+                    elems.push(Call {
                         function_index: export_data_module.decr_instruction_counter_fn,
-                    },
-                    // decr_instruction_counter returns it's argument unchanged,
-                    // so we can convert back to I32 without worrying about
-                    // overflows.
-                    I32WrapI64,
-                ]);
+                    });
+                } else {
+                    elems.extend_from_slice(&[
+                        I64ExtendI32U,
+                        Call {
+                            function_index: export_data_module.decr_instruction_counter_fn,
+                        },
+                        // decr_instruction_counter returns it's argument unchanged,
+                        // so we can convert back to I32 without worrying about
+                        // overflows.
+                        I32WrapI64,
+                    ]);
+                }
             }
         }
         last_injection_position = point.position;
@@ -870,76 +898,79 @@ fn write_barrier_instructions<'a>(
     offset: u64,
     val_arg_idx: u32,
     addr_arg_idx: u32,
+    is_wasm64: bool,
 ) -> Vec<Operator<'a>> {
     use Operator::*;
     let page_size_shift = PAGE_SIZE.trailing_zeros() as i32;
     let tracking_mem_idx = 1;
-    if offset % PAGE_SIZE as u64 == 0 {
-        vec![
-            LocalSet {
-                local_index: val_arg_idx,
-            }, // value
-            LocalTee {
-                local_index: addr_arg_idx,
-            }, // address
-            I32Const {
-                value: page_size_shift,
-            },
-            I32ShrU,
-            I32Const { value: 1 },
-            I32Store8 {
-                memarg: wasmparser::MemArg {
-                    align: 0,
-                    max_align: 0,
-                    offset: offset >> page_size_shift,
-                    memory: tracking_mem_idx,
+    let mut instructions = vec![
+        LocalSet {
+            local_index: val_arg_idx,
+        }, // value
+        LocalTee {
+            local_index: addr_arg_idx,
+        }, // address
+    ];
+    let bytemap_offset = if offset % PAGE_SIZE as u64 == 0 {
+        offset >> page_size_shift
+    } else {
+        instructions.extend_from_slice(&if is_wasm64 {
+            [
+                I64Const {
+                    value: offset as i64,
                 },
+                I64Add,
+            ]
+        } else {
+            [
+                I32Const {
+                    value: offset as i32,
+                },
+                I32Add,
+            ]
+        });
+        0
+    };
+    if is_wasm64 {
+        // The bytemap is a 32-bit memory, so the page index is converted to
+        // an `i32` address after the shift.
+        instructions.extend_from_slice(&[
+            I64Const {
+                value: page_size_shift as i64,
             },
-            // Put original params on the stack
-            LocalGet {
-                local_index: addr_arg_idx,
-            },
-            LocalGet {
-                local_index: val_arg_idx,
-            },
-        ]
+            I64ShrU,
+            I32WrapI64,
+        ]);
     } else {
-        vec![
-            LocalSet {
-                local_index: val_arg_idx,
-            }, // value
-            LocalTee {
-                local_index: addr_arg_idx,
-            }, // address
-            I32Const {
-                value: offset as i32,
-            },
-            I32Add,
+        instructions.extend_from_slice(&[
             I32Const {
                 value: page_size_shift,
             },
             I32ShrU,
-            I32Const { value: 1 },
-            I32Store8 {
-                memarg: wasmparser::MemArg {
-                    align: 0,
-                    max_align: 0,
-                    offset: 0,
-                    memory: tracking_mem_idx,
-                },
-            },
-            // Put original params on the stack
-            LocalGet {
-                local_index: addr_arg_idx,
-            },
-            LocalGet {
-                local_index: val_arg_idx,
-            },
-        ]
+        ]);
     }
+    instructions.extend_from_slice(&[
+        I32Const { value: 1 },
+        I32Store8 {
+            memarg: wasmparser::MemArg {
+                align: 0,
+                max_align: 0,
+                offset: bytemap_offset,
+                memory: tracking_mem_idx,
+            },
+        },
+        // Put original params on the stack
+        LocalGet {
+            local_index: addr_arg_idx,
+        },
+        LocalGet {
+            local_index: val_arg_idx,
+        },
+    ]);
+    instructions
 }
 
-fn inject_mem_barrier(func_body: &mut wasm_transform::Body, func_type: &FuncType) {
+fn inject_mem_barrier(func_body: &mut wasm_transform::Body, func_type: &FuncType, is_wasm64: bool) {
     use Operator::*;
     let mut val_i32_needed = false;
     let mut val_i64_needed = false;
@@ -978,7 +1009,7 @@ fn inject_mem_barrier(func_body: &mut wasm_transform::Body, func_type: &FuncType
         // the total number of locals.
         let n_locals: u32 = func_body.locals.iter().map(|x| x.0).sum();
         let mut next_local = func_type.params().len() as u32 + n_locals;
-        let arg_i32_addr_idx = next_local;
+        let arg_addr_idx = next_local;
         next_local += 1;
 
         // conditionally add following locals
@@ -987,7 +1018,16 @@ fn inject_mem_barrier(func_body: &mut wasm_transform::Body, func_type: &FuncType
         let arg_f32_val_idx;
         let arg_f64_val_idx;
 
-        if val_i32_needed {
+        if is_wasm64 {
+            func_body.locals.push((1, ValType::I64)); // addr local
+            if val_i32_needed {
+                arg_i32_val_idx = next_local;
+                next_local += 1;
+                func_body.locals.push((1, ValType::I32)); // val local
+            } else {
+                arg_i32_val_idx = u32::MAX; // not used
+            }
+        } else if val_i32_needed {
             arg_i32_val_idx = next_local;
             next_local += 1;
             func_body.locals.push((2, ValType::I32)); // addr and val locals
@@ -1032,7 +1072,8 @@ fn inject_mem_barrier(func_body: &mut wasm_transform::Body, func_type: &FuncType
                     elems.extend_from_slice(&write_barrier_instructions(
                         memarg.offset,
                         arg_i32_val_idx,
-                        arg_i32_addr_idx,
+                        arg_addr_idx,
+                        is_wasm64,
                     ));
                 }
                 I64Store { memarg }
@@ -1042,21 +1083,24 @@ fn inject_mem_barrier(func_body: &mut wasm_transform::Body, func_type: &FuncType
                     elems.extend_from_slice(&write_barrier_instructions(
                         memarg.offset,
                         arg_i64_val_idx,
-                        arg_i32_addr_idx,
+                        arg_addr_idx,
+                        is_wasm64,
                     ));
                 }
                 F32Store { memarg } => {
                     elems.extend_from_slice(&write_barrier_instructions(
                         memarg.offset,
                         arg_f32_val_idx,
-                        arg_i32_addr_idx,
+                        arg_addr_idx,
+                        is_wasm64,
                     ));
                 }
                 F64Store { memarg } => {
                     elems.extend_from_slice(&write_barrier_instructions(
                         memarg.offset,
                         arg_f64_val_idx,
-                        arg_i32_addr_idx,
+                        arg_addr_idx,
+                        is_wasm64,
                     ));
                 }
                 _ => {}
@@ -1075,7 +1119,11 @@ fn inject_mem_barrier(func_body: &mut wasm_transform::Body, func_type: &FuncType
 // instruction to make sure that there's enough available memory left to support
 // the requested extra memory. If no `memory.grow` instructions are present then
 // the function's code remains unchanged.
-fn inject_update_available_memory(func_body: &mut wasm_transform::Body, func_type: &FuncType) {
+fn inject_update_available_memory(
+    func_body: &mut wasm_transform::Body,
+    func_type: &FuncType,
+    is_wasm64: bool,
+) {
     use Operator::*;
     let mut injection_points: Vec<usize> = Vec::new();
     {
@@ -1095,7 +1143,12 @@ fn inject_update_available_memory(func_body: &mut wasm_transform::Body, func_typ
         // the total number of locals.
         let n_locals: u32 = func_body.locals.iter().map(|x| x.0).sum();
         let memory_local_ix = func_type.params().len() as u32 + n_locals;
-        func_body.locals.push((1, ValType::I32));
+        let memory_local_type = if is_wasm64 {
+            ValType::I64
+        } else {
+            ValType::I32
+        };
+        func_body.locals.push((1, memory_local_type));
 
         let orig_elems = &func_body.instructions;
         let mut elems: Vec<Operator> = Vec::new();
@@ -1195,6 +1248,7 @@ fn get_data(
                     offset_expr,
                 } => match offset_expr {
                     Operator::I32Const { value } => *value as usize,
+                    Operator::I64Const { value } => *value as usize,
                     _ => return Err(WasmInstrumentationError::WasmDeserializeError(WasmError::new(
                         "complex initialization expressions for data segments are not supported!".into()
                     ))),
@@ -1241,9 +1295,22 @@ fn update_memories(
     mut module: Module,
     write_barrier: FlagStatus,
     wasm_native_stable_memory: FlagStatus,
+    is_wasm64: bool,
 ) -> (Module, u32) {
     let mut stable_index = 0;
 
+    // A 64-bit heap is capped at `MAX_WASM64_MEMORY_IN_BYTES`.
+    if let Some(memory) = module.memories.first_mut() {
+        if memory.memory64 {
+            memory.maximum = Some(
+                memory
+                    .maximum
+                    .unwrap_or(MAX_WASM64_MEMORY_IN_WASM_PAGES)
+                    .min(MAX_WASM64_MEMORY_IN_WASM_PAGES),
+            );
+        }
+    }
+
     let mut memory_already_exported = false;
     for export in &mut module.exports {
         if let ExternalKind::Memory = export.kind {
@@ -1262,11 +1329,16 @@ fn update_memories(
     }
 
     if write_barrier == FlagStatus::Enabled && !module.memories.is_empty() {
+        let bytemap_size = if is_wasm64 {
+            WASM64_BYTEMAP_SIZE_IN_WASM_PAGES
+        } else {
+            BYTEMAP_SIZE_IN_WASM_PAGES
+        };
         module.memories.push(MemoryType {
             memory64: false,
             shared: false,
-            initial: BYTEMAP_SIZE_IN_WASM_PAGES,
-            maximum: Some(BYTEMAP_SIZE_IN_WASM_PAGES),
+            initial: bytemap_size,
+            maximum: Some(bytemap_size),
         });
 
         module.exports.push(Export {
@@ -1,9 +1,9 @@
 //! This module is responsible for validating the wasm binaries that are
 //! installed on the Internet Computer.
 
-use super::{WasmImportsDetails, WasmValidationDetails};
+use super::{is_wasm64, WasmImportsDetails, WasmValidationDetails};
 
-use ic_config::embedders::Config as EmbeddersConfig;
+use ic_config::{embedders::Config as EmbeddersConfig, flag_status::FlagStatus};
 use ic_replicated_state::canister_state::execution_state::{
     CustomSection, CustomSectionType, WasmMetadata,
 };
@@ -52,7 +52,14 @@ pub(super) const API_VERSION_IC0: &str = "ic0";
 // user tries to import a function that doesn't exist in any of the expected
 // modules vs the case where the function exists but is imported from the wrong
 // module.
-fn get_valid_system_apis() -> HashMap<String, HashMap<String, FunctionSignature>> {
+fn get_valid_system_apis(is_wasm64: bool) -> HashMap<String, HashMap<String, FunctionSignature>> {
+    // Addresses and sizes of regions in the Wasm heap are `i64` in modules
+    // with a 64-bit main memory.
+    let ptr_type = if is_wasm64 {
+        ValType::I64
+    } else {
+        ValType::I32
+    };
     let valid_system_apis = vec![
         (
             // Public methods
@@ -61,7 +68,7 @@ fn get_valid_system_apis() -> HashMap<String, HashMap<String, FunctionSignature>
                 API_VERSION_IC0,
                 FunctionSignature {
                     param_types: vec![],
-                    return_type: vec![ValType::I32],
+                    return_type: vec![ptr_type],
                 },
             )],
         ),
@@ -70,7 +77,7 @@ fn get_valid_system_apis() -> HashMap<String, HashMap<String, FunctionSignature>
             vec![(
                 API_VERSION_IC0,
                 FunctionSignature {
-                    param_types: vec![ValType::I32, ValType::I32, ValType::I32],
+                    param_types: vec![ptr_type, ptr_type, ptr_type],
                     return_type: vec![],
                 },
             )],
@@ -81,7 +88,7 @@ fn get_valid_system_apis() -> HashMap<String, HashMap<String, FunctionSignature>
                 API_VERSION_IC0,
                 FunctionSignature {
                     param_types: vec![],
-                    return_type: vec![ValType::I32],
+                    return_type: vec![ptr_type],
                 },
             )],
         ),
@@ -90,7 +97,7 @@ fn get_valid_system_apis() -> HashMap<String, HashMap<String, FunctionSignature>
             vec![(
                 API_VERSION_IC0,
                 FunctionSignature {
-                    param_types: vec![ValType::I32, ValType::I32, ValType::I32],
+                    param_types: vec![ptr_type, ptr_type, ptr_type],
                     return_type: vec![],
                 },
             )],
@@ -101,7 +108,7 @@ fn get_valid_system_apis() -> HashMap<String, HashMap<String, FunctionSignature>
                 API_VERSION_IC0,
                 FunctionSignature {
                     param_types: vec![],
-                    return_type: vec![ValType::I32],
+                    return_type: vec![ptr_type],
                 },
             )],
         ),
@@ -110,7 +117,7 @@ fn get_valid_system_apis() -> HashMap<String, HashMap<String, FunctionSignature>
             vec![(
                 API_VERSION_IC0,
                 FunctionSignature {
-                    param_types: vec![ValType::I32, ValType::I32, ValType::I32],
+                    param_types: vec![ptr_type, ptr_type, ptr_type],
                     return_type: vec![],
                 },
             )],
@@ -141,7 +148,7 @@ fn get_valid_system_apis() -> HashMap<String, HashMap<String, FunctionSignature>
                 API_VERSION_IC0,
                 FunctionSignature {
                     param_types: vec![],
-                    return_type: vec![ValType::I32],
+                    return_type: vec![ptr_type],
                 },
             )],
         ),
@@ -150,7 +157,7 @@ fn get_valid_system_apis() -> HashMap<String, HashMap<String, FunctionSignature>
             vec![(
                 API_VERSION_IC0,
                 FunctionSignature {
-                    param_types: vec![ValType::I32, ValType::I32, ValType::I32],
+                    param_types: vec![ptr_type, ptr_type, ptr_type],
                     return_type: vec![],
                 },
             )],
@@ -160,7 +167,7 @@ fn get_valid_system_apis() -> HashMap<String, HashMap<String, FunctionSignature>
             vec![(
                 API_VERSION_IC0,
                 FunctionSignature {
-                    param_types: vec![ValType::I32, ValType::I32],
+                    param_types: vec![ptr_type, ptr_type],
                     return_type: vec![],
                 },
             )],
@@ -180,7 +187,7 @@ fn get_valid_system_apis() -> HashMap<String, HashMap<String, FunctionSignature>
             vec![(
                 API_VERSION_IC0,
                 FunctionSignature {
-                    param_types: vec![ValType::I32, ValType::I32],
+                    param_types: vec![ptr_type, ptr_type],
                     return_type: vec![],
                 },
             )],
@@ -191,7 +198,7 @@ fn get_valid_system_apis() -> HashMap<String, HashMap<String, FunctionSignature>
                 API_VERSION_IC0,
                 FunctionSignature {
                     param_types: vec![],
-                    return_type: vec![ValType::I32],
+                    return_type: vec![ptr_type],
                 },
             )],
         ),
@@ -200,7 +207,7 @@ fn get_valid_system_apis() -> HashMap<String, HashMap<String, FunctionSignature>
             vec![(
                 API_VERSION_IC0,
                 FunctionSignature {
-                    param_types: vec![ValType::I32, ValType::I32, ValType::I32],
+                    param_types: vec![ptr_type, ptr_type, ptr_type],
                     return_type: vec![],
                 },
             )],
@@ -211,7 +218,7 @@ fn get_valid_system_apis() -> HashMap<String, HashMap<String, FunctionSignature>
                 API_VERSION_IC0,
                 FunctionSignature {
                     param_types: vec![],
-                    return_type: vec![ValType::I32],
+                    return_type: vec![ptr_type],
                 },
             )],
         ),
@@ -220,7 +227,7 @@ fn get_valid_system_apis() -> HashMap<String, HashMap<String, FunctionSignature>
             vec![(
                 API_VERSION_IC0,
                 FunctionSignature {
-                    param_types: vec![ValType::I32, ValType::I32, ValType::I32],
+                    param_types: vec![ptr_type, ptr_type, ptr_type],
                     return_type: vec![],
                 },
             )],
@@ -241,16 +248,7 @@ fn get_valid_system_apis() -> HashMap<String, HashMap<String, FunctionSignature>
             vec![(
                 API_VERSION_IC0,
                 FunctionSignature {
-                    param_types: vec![
-                        ValType::I32,
-                        ValType::I32,
-                        ValType::I32,
-                        ValType::I32,
-                        ValType::I32,
-                        ValType::I32,
-                        ValType::I32,
-                        ValType::I32,
-                    ],
+                    param_types: vec![ptr_type; 8],
                     return_type: vec![],
                 },
             )],
@@ -260,7 +258,7 @@ fn get_valid_system_apis() -> HashMap<String, HashMap<String, FunctionSignature>
             vec![(
                 API_VERSION_IC0,
                 FunctionSignature {
-                    param_types: vec![ValType::I32, ValType::I32],
+                    param_types: vec![ptr_type, ptr_type],
                     return_type: vec![],
                 },
             )],
@@ -270,7 +268,17 @@ fn get_valid_system_apis() -> HashMap<String, HashMap<String, FunctionSignature>
             vec![(
                 API_VERSION_IC0,
                 FunctionSignature {
-                    param_types: vec![ValType::I32, ValType::I32],
+                    param_types: vec![ptr_type, ptr_type],
+                    return_type: vec![],
+                },
+            )],
+        ),
+        (
+            "call_with_best_effort_response",
+            vec![(
+                API_VERSION_IC0,
+                FunctionSignature {
+                    param_types: vec![ValType::I32],
                     return_type: vec![],
                 },
             )],
@@ -301,7 +309,7 @@ fn get_valid_system_apis() -> HashMap<String, HashMap<String, FunctionSignature>
             vec![(
                 API_VERSION_IC0,
                 FunctionSignature {
-                    param_types: vec![ValType::I32, ValType::I32],
+                    param_types: vec![ptr_type, ptr_type],
                     return_type: vec![],
                 },
             )],
@@ -426,12 +434,72 @@ fn get_valid_system_apis() -> HashMap<String, HashMap<String, FunctionSignature>
                 },
             )],
         ),
+        (
+            "is_controller",
+            vec![(
+                API_VERSION_IC0,
+                FunctionSignature {
+                    param_types: vec![ptr_type, ptr_type],
+                    return_type: vec![ValType::I32],
+                },
+            )],
+        ),
+        (
+            "in_replicated_execution",
+            vec![(
+                API_VERSION_IC0,
+                FunctionSignature {
+                    param_types: vec![],
+                    return_type: vec![ValType::I32],
+                },
+            )],
+        ),
+        (
+            "cost_call",
+            vec![(
+                API_VERSION_IC0,
+                FunctionSignature {
+                    param_types: vec![ValType::I64, ValType::I64, ptr_type],
+                    return_type: vec![],
+                },
+            )],
+        ),
+        (
+            "cost_create_canister",
+            vec![(
+                API_VERSION_IC0,
+                FunctionSignature {
+                    param_types: vec![ptr_type],
+                    return_type: vec![],
+                },
+            )],
+        ),
+        (
+            "cost_http_request",
+            vec![(
+                API_VERSION_IC0,
+                FunctionSignature {
+                    param_types: vec![ValType::I64, ValType::I64, ptr_type],
+                    return_type: vec![],
+                },
+            )],
+        ),
+        (
+            "cost_sign_with_ecdsa",
+            vec![(
+                API_VERSION_IC0,
+                FunctionSignature {
+                    param_types: vec![ptr_type, ptr_type, ValType::I32, ptr_type],
+                    return_type: vec![ValType::I32],
+                },
+            )],
+        ),
         (
             "trap",
             vec![(
                 API_VERSION_IC0,
                 FunctionSignature {
-                    param_types: vec![ValType::I32, ValType::I32],
+                    param_types: vec![ptr_type, ptr_type],
                     return_type: vec![],
                 },
             )],
@@ -481,7 +549,7 @@ fn get_valid_system_apis() -> HashMap<String, HashMap<String, FunctionSignature>
             vec![(
                 API_VERSION_IC0,
                 FunctionSignature {
-                    param_types: vec![ValType::I32, ValType::I32],
+                    param_types: vec![ptr_type, ptr_type],
                     return_type: vec![],
                 },
             )],
@@ -502,7 +570,7 @@ fn get_valid_system_apis() -> HashMap<String, HashMap<String, FunctionSignature>
                 API_VERSION_IC0,
                 FunctionSignature {
                     param_types: vec![],
-                    return_type: vec![ValType::I32],
+                    return_type: vec![ptr_type],
                 },
             )],
         ),
@@ -511,7 +579,7 @@ fn get_valid_system_apis() -> HashMap<String, HashMap<String, FunctionSignature>
             vec![(
                 API_VERSION_IC0,
                 FunctionSignature {
-                    param_types: vec![ValType::I32, ValType::I32, ValType::I32],
+                    param_types: vec![ptr_type, ptr_type, ptr_type],
                     return_type: vec![],
                 },
             )],
@@ -551,7 +619,7 @@ fn get_valid_system_apis() -> HashMap<String, HashMap<String, FunctionSignature>
             vec![(
                 API_VERSION_IC0,
                 FunctionSignature {
-                    param_types: vec![ValType::I32],
+                    param_types: vec![ptr_type],
                     return_type: vec![],
                 },
             )],
@@ -561,7 +629,7 @@ fn get_valid_system_apis() -> HashMap<String, HashMap<String, FunctionSignature>
             vec![(
                 API_VERSION_IC0,
                 FunctionSignature {
-                    param_types: vec![ValType::I32],
+                    param_types: vec![ptr_type],
                     return_type: vec![],
                 },
             )],
@@ -571,7 +639,7 @@ fn get_valid_system_apis() -> HashMap<String, HashMap<String, FunctionSignature>
             vec![(
                 API_VERSION_IC0,
                 FunctionSignature {
-                    param_types: vec![ValType::I32],
+                    param_types: vec![ptr_type],
                     return_type: vec![],
                 },
             )],
@@ -581,7 +649,7 @@ fn get_valid_system_apis() -> HashMap<String, HashMap<String, FunctionSignature>
             vec![(
                 API_VERSION_IC0,
                 FunctionSignature {
-                    param_types: vec![ValType::I64, ValType::I64, ValType::I32],
+                    param_types: vec![ValType::I64, ValType::I64, ptr_type],
                     return_type: vec![],
                 },
             )],
@@ -729,11 +797,14 @@ fn set_imports_details(import_details: &mut WasmImportsDetails, import_module: &
 //
 // Returns information about what IC0 methods are imported via
 // `WasmImportsDetails`.
-fn validate_import_section(module: &Module) -> Result<WasmImportsDetails, WasmValidationError> {
+fn validate_import_section(
+    module: &Module,
+    is_wasm64: bool,
+) -> Result<WasmImportsDetails, WasmValidationError> {
     let mut imports_details = WasmImportsDetails::default();
 
     if !module.imports.is_empty() {
-        let valid_system_apis = get_valid_system_apis();
+        let valid_system_apis = get_valid_system_apis(is_wasm64);
         for entry in &module.imports {
             let import_module = entry.module;
             let field = entry.name;
@@ -893,6 +964,8 @@ fn validate_data_section(module: &Module) -> Result<(), WasmValidationError> {
                 offset_expr,
             } => match offset_expr {
                 Operator::I32Const { .. } => Ok(()),
+                // Data segments of 64-bit memories have 64-bit offsets.
+                Operator::I64Const { .. } => Ok(()),
                 _ => Err(WasmValidationError::InvalidDataSection(format!(
                     "Invalid offset expression in data segment: {:?}",
                     offset_expr
@@ -1126,9 +1199,15 @@ pub fn ensure_determinism(config: &mut Config) {
         .cranelift_nan_canonicalization(true);
 }
 
-fn can_compile(wasm: &BinaryEncodedWasm) -> Result<(), WasmValidationError> {
+fn can_compile(
+    wasm: &BinaryEncodedWasm,
+    embedders_config: &EmbeddersConfig,
+) -> Result<(), WasmValidationError> {
     let mut config = wasmtime::Config::default();
     ensure_determinism(&mut config);
+    if embedders_config.feature_flags.wasm64 == FlagStatus::Enabled {
+        config.wasm_memory64(true);
+    }
     let engine = wasmtime::Engine::new(&config).map_err(|_| {
         WasmValidationError::WasmtimeValidation(String::from("Failed to initialize Wasm engine"))
     })?;
@@ -1159,10 +1238,25 @@ pub(super) fn validate_wasm_binary<'a>(
     wasm: &'a BinaryEncodedWasm,
     config: &EmbeddersConfig,
 ) -> Result<(WasmValidationDetails, Module<'a>), WasmValidationError> {
-    can_compile(wasm)?;
+    can_compile(wasm, config)?;
     let module = Module::parse(wasm.as_slice(), false)
         .map_err(|err| WasmValidationError::DecodingError(format!("{}", err)))?;
-    let imports_details = validate_import_section(&module)?;
+    let is_wasm64 = is_wasm64(&module);
+    if is_wasm64 {
+        if config.feature_flags.wasm64 == FlagStatus::Disabled {
+            return Err(WasmValidationError::WasmtimeValidation(
+                "64-bit Wasm memories are not supported".to_string(),
+            ));
+        }
+        // The Wasm-native stable memory replacements of the stable memory
+        // System API only support copying to and from a 32-bit heap.
+        if config.feature_flags.wasm_native_stable_memory == FlagStatus::Enabled {
+            return Err(WasmValidationError::WasmtimeValidation(
+                "64-bit Wasm memories are not supported with Wasm-native stable memory".to_string(),
+            ));
+        }
+    }
+    let imports_details = validate_import_section(&module, is_wasm64)?;
     let reserved_exports = validate_export_section(&module)?;
     validate_data_section(&module)?;
     validate_global_section(&module, config.max_globals)?;
@@ -1175,6 +1269,7 @@ pub(super) fn validate_wasm_binary<'a>(
             imports_details,
             wasm_metadata,
             largest_function_instruction_count,
+            is_wasm64,
         },
         module,
     ))
@@ -13,7 +13,8 @@ use std::{
 
 use ic_system_api::ModificationTracking;
 use wasmtime::{
-    unix::StoreExt, Engine, Instance, Memory, Module, Mutability, OptLevel, Store, Val, ValType,
+    unix::StoreExt, Engine, ExternType, Instance, Memory, Module, Mutability, OptLevel, Store, Val,
+    ValType,
 };
 
 pub use host_memory::WasmtimeMemoryCreator;
@@ -172,7 +173,9 @@ impl WasmtimeEmbedder {
         {
             config.wasm_multi_memory(true);
         }
-        if embedder_config.feature_flags.wasm_native_stable_memory == FlagStatus::Enabled {
+        if embedder_config.feature_flags.wasm_native_stable_memory == FlagStatus::Enabled
+            || embedder_config.feature_flags.wasm64 == FlagStatus::Enabled
+        {
             config.wasm_memory64(true);
         }
         config
@@ -300,13 +303,28 @@ impl WasmtimeEmbedder {
             },
         );
 
-        let linker = system_api::syscalls(
-            self.log.clone(),
-            canister_id,
-            &store,
-            self.config.feature_flags,
-            self.config.stable_memory_dirty_page_limit,
+        // Canisters with a 64-bit main memory pass pointers and sizes as `i64`.
+        let is_wasm64 = matches!(
+            module.get_export(WASM_HEAP_MEMORY_NAME),
+            Some(ExternType::Memory(memory_type)) if memory_type.is_64()
         );
+        let linker = if is_wasm64 {
+            system_api::syscalls::<S, i64>(
+                self.log.clone(),
+                canister_id,
+                &store,
+                self.config.feature_flags,
+                self.config.stable_memory_dirty_page_limit,
+            )
+        } else {
+            system_api::syscalls::<S, i32>(
+                self.log.clone(),
+                canister_id,
+                &store,
+                self.config.feature_flags,
+                self.config.stable_memory_dirty_page_limit,
+            )
+        };
 
         let instance = match linker.instantiate(&mut store, module) {
             Ok(instance) => instance,
@@ -750,27 +768,39 @@ impl<S: SystemApi> WasmtimeInstance<S> {
 
         let result = match &func_ref {
             FuncRef::Method(wasm_method) => self.invoke_export(&wasm_method.to_string(), &[]),
-            FuncRef::QueryClosure(closure) | FuncRef::UpdateClosure(closure) => self
-                .instance
-                .get_export(&mut self.store, "table")
-                .ok_or_else(|| HypervisorError::ContractViolation("table not found".to_string()))?
-                .into_table()
-                .ok_or_else(|| {
-                    HypervisorError::ContractViolation("export 'table' is not a table".to_string())
-                })?
-                .get(&mut self.store, closure.func_idx)
-                .ok_or(HypervisorError::FunctionNotFound(0, closure.func_idx))?
-                .funcref()
-                .ok_or_else(|| {
-                    HypervisorError::ContractViolation("not a function reference".to_string())
-                })?
-                .ok_or_else(|| {
-                    HypervisorError::ContractViolation(
-                        "unexpected null function reference".to_string(),
-                    )
-                })?
-                .call(&mut self.store, &[Val::I32(closure.env as i32)], &mut [])
-                .map_err(wasmtime_error_to_hypervisor_error),
+            FuncRef::QueryClosure(closure) | FuncRef::UpdateClosure(closure) => {
+                let func = *self
+                    .instance
+                    .get_export(&mut self.store, "table")
+                    .ok_or_else(|| {
+                        HypervisorError::ContractViolation("table not found".to_string())
+                    })?
+                    .into_table()
+                    .ok_or_else(|| {
+                        HypervisorError::ContractViolation(
+                            "export 'table' is not a table".to_string(),
+                        )
+                    })?
+                    .get(&mut self.store, closure.func_idx)
+                    .ok_or(HypervisorError::FunctionNotFound(0, closure.func_idx))?
+                    .funcref()
+                    .ok_or_else(|| {
+                        HypervisorError::ContractViolation("not a function reference".to_string())
+                    })?
+                    .ok_or_else(|| {
+                        HypervisorError::ContractViolation(
+                            "unexpected null function reference".to_string(),
+                        )
+                    })?;
+                // Callbacks of canisters with a 64-bit main memory take the
+                // environment as `i64`.
+                let env = match func.ty(&self.store).params().next() {
+                    Some(ValType::I64) => Val::I64(closure.env as i64),
+                    _ => Val::I32(closure.env as i32),
+                };
+                func.call(&mut self.store, &[env], &mut [])
+                    .map_err(wasmtime_error_to_hypervisor_error)
+            }
         }
         .map_err(|e| {
             self.store
@@ -970,7 +1000,8 @@ impl<S: SystemApi> WasmtimeInstance<S> {
     }
 
     /// Returns the heap size.
-    /// Result is guaranteed to fit in a `u32`.
+    /// Result is guaranteed to fit in a `u32` unless the main memory is a
+    /// 64-bit memory.
     pub fn heap_size(&mut self, canister_memory_type: CanisterMemoryType) -> NumWasmPages {
         let name = match canister_memory_type {
             CanisterMemoryType::Heap => WASM_HEAP_MEMORY_NAME,
@@ -979,6 +1010,12 @@ impl<S: SystemApi> WasmtimeInstance<S> {
         NumWasmPages::from(self.get_memory(name).map_or(0, |mem| mem.size(&self.store)) as usize)
     }
 
+    /// Returns true if the main memory of the instance is a 64-bit memory.
+    pub fn is_wasm64(&mut self) -> bool {
+        self.get_memory(WASM_HEAP_MEMORY_NAME)
+            .map_or(false, |mem| mem.ty(&self.store).is_64())
+    }
+
     /// Returns a list of exported globals.
     pub fn get_exported_globals(&mut self) -> Vec<Global> {
         let globals_to_ignore = globals_to_ignore(self.wasm_native_stable_memory);
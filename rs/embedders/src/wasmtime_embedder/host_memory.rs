@@ -65,6 +65,11 @@ unsafe impl wasmtime::MemoryCreator for WasmtimeMemoryCreator {
         reserved_size_in_bytes: Option<usize>,
         guard_size: usize,
     ) -> Result<Box<dyn wasmtime::LinearMemory>, String> {
+        // The limits of the individual memories are set by instrumentation,
+        // which knows the heap and the stable memory by their index: it caps
+        // the declared maximum of a 64-bit heap at `MAX_WASM64_MEMORY_IN_BYTES`
+        // and declares the stable memory with `MAX_STABLE_MEMORY_IN_BYTES`.
+        // Here we only enforce the overall limit of each memory kind.
         let max_pages = if ty.is_64() {
             MAX_STABLE_MEMORY_IN_BYTES / (WASM_PAGE_SIZE as u64)
         } else {
@@ -73,7 +78,11 @@ unsafe impl wasmtime::MemoryCreator for WasmtimeMemoryCreator {
         let min = std::cmp::min(ty.minimum(), max_pages) as usize;
         let max = std::cmp::min(ty.maximum().unwrap_or(max_pages), max_pages) as usize;
 
-        let mem_size = reserved_size_in_bytes.unwrap_or_else(wasm_max_mem_size_in_bytes);
+        // A 64-bit memory may grow beyond 4GiB, so the whole address range up
+        // to its maximum is reserved.
+        let mem_size = reserved_size_in_bytes.unwrap_or_else(|| {
+            std::cmp::max(convert_pages_to_bytes(max), wasm_max_mem_size_in_bytes())
+        });
 
         let mem = MmapMemory::new(mem_size, guard_size);
 
@@ -16,6 +16,7 @@ use wasmtime::{AsContextMut, Caller, Global, Linker, Store, Val};
 
 use crate::InternalErrorCode;
 use std::convert::TryFrom;
+use std::num::TryFromIntError;
 
 fn process_err<S: SystemApi>(
     store: &mut impl AsContextMut<Data = StoreData<S>>,
@@ -153,7 +154,7 @@ fn charge_for_system_api_call<S: SystemApi>(
     canister_id: CanisterId,
     caller: &mut Caller<'_, StoreData<S>>,
     system_api_overhead: NumInstructions,
-    num_bytes: u32,
+    num_bytes: usize,
     complexity: ExecutionComplexity,
     dirty_page_cost: NumInstructions,
     stable_memory_dirty_page_limit: NumPages,
@@ -332,6 +333,40 @@ fn observe_execution_complexity<S: SystemApi>(
     Ok(())
 }
 
+/// The Wasm type of pointer and size arguments of the System API: `i32` for
+/// canisters with a 32-bit main memory and `i64` for canisters with a 64-bit
+/// main memory.
+pub(crate) trait WasmPointer:
+    wasmtime::WasmTy + TryFrom<usize, Error = TryFromIntError> + Into<i64> + Copy + 'static
+{
+    /// Reinterprets the argument as an unsigned heap offset or size.
+    fn to_usize(self) -> usize;
+}
+
+impl WasmPointer for i32 {
+    fn to_usize(self) -> usize {
+        self as u32 as usize
+    }
+}
+
+impl WasmPointer for i64 {
+    fn to_usize(self) -> usize {
+        self as u64 as usize
+    }
+}
+
+/// Converts a closure table index or environment to `u32`. Closures are
+/// persisted as 32-bit values, so larger arguments from 64-bit canisters are
+/// rejected.
+fn closure_arg_to_u32<I: WasmPointer>(arg: I) -> HypervisorResult<u32> {
+    u32::try_from(arg.to_usize()).map_err(|_| {
+        HypervisorError::ContractViolation(format!(
+            "Callback argument {} does not fit into 32 bits",
+            arg.to_usize()
+        ))
+    })
+}
+
 /// A helper to pass wasmtime counters to the System API
 fn ic0_performance_counter_helper<S: SystemApi>(
     log: &ReplicaLogger,
@@ -360,7 +395,7 @@ fn ic0_performance_counter_helper<S: SystemApi>(
     }
 }
 
-pub(crate) fn syscalls<S: SystemApi>(
+pub(crate) fn syscalls<S: SystemApi, I: WasmPointer>(
     log: ReplicaLogger,
     canister_id: CanisterId,
     store: &Store<StoreData<S>>,
@@ -405,7 +440,7 @@ pub(crate) fn syscalls<S: SystemApi>(
     linker
         .func_wrap("ic0", "msg_caller_copy", {
             let log = log.clone();
-            move |mut caller: Caller<'_, StoreData<S>>, dst: i32, offset: i32, size: i32| {
+            move |mut caller: Caller<'_, StoreData<S>>, dst: I, offset: I, size: I| {
                 observe_execution_complexity(
                     &log,
                     canister_id,
@@ -417,10 +452,15 @@ pub(crate) fn syscalls<S: SystemApi>(
                     stable_memory_dirty_page_limit,
                 )?;
                 with_memory_and_system_api(&mut caller, |system_api, memory| {
-                    system_api.ic0_msg_caller_copy(dst as u32, offset as u32, size as u32, memory)
+                    system_api.ic0_msg_caller_copy(
+                        dst.to_usize(),
+                        offset.to_usize(),
+                        size.to_usize(),
+                        memory,
+                    )
                 })?;
                 if feature_flags.write_barrier == FlagStatus::Enabled {
-                    mark_writes_on_bytemap(&mut caller, dst as u32 as usize, size as u32 as usize)
+                    mark_writes_on_bytemap(&mut caller, dst.to_usize(), size.to_usize())
                 } else {
                     Ok(())
                 }
@@ -434,7 +474,7 @@ pub(crate) fn syscalls<S: SystemApi>(
                 with_system_api(&mut caller, |s| s.ic0_msg_caller_size())
                     .map_err(|e| process_err(&mut caller, e))
                     .and_then(|s| {
-                        i32::try_from(s).map_err(|e| {
+                        I::try_from(s).map_err(|e| {
                             anyhow::Error::msg(format!("ic0::msg_caller_size failed: {}", e))
                         })
                     })
@@ -448,7 +488,7 @@ pub(crate) fn syscalls<S: SystemApi>(
                 with_system_api(&mut caller, |s| s.ic0_msg_arg_data_size())
                     .map_err(|e| process_err(&mut caller, e))
                     .and_then(|s| {
-                        i32::try_from(s).map_err(|e| {
+                        I::try_from(s).map_err(|e| {
                             anyhow::Error::msg(format!("ic0::msg_arg_data_size failed: {}", e))
                         })
                     })
@@ -459,13 +499,13 @@ pub(crate) fn syscalls<S: SystemApi>(
     linker
         .func_wrap("ic0", "msg_arg_data_copy", {
             let log = log.clone();
-            move |mut caller: Caller<'_, StoreData<S>>, dst: i32, offset: i32, size: i32| {
+            move |mut caller: Caller<'_, StoreData<S>>, dst: I, offset: I, size: I| {
                 charge_for_system_api_call(
                     &log,
                     canister_id,
                     &mut caller,
                     system_api_complexity::overhead::MSG_ARG_DATA_COPY,
-                    size as u32,
+                    size.to_usize(),
                     ExecutionComplexity {
                         cpu: system_api_complexity::cpu::MSG_ARG_DATA_COPY,
                         ..Default::default()
@@ -474,10 +514,15 @@ pub(crate) fn syscalls<S: SystemApi>(
                     stable_memory_dirty_page_limit,
                 )?;
                 with_memory_and_system_api(&mut caller, |system_api, mem| {
-                    system_api.ic0_msg_arg_data_copy(dst as u32, offset as u32, size as u32, mem)
+                    system_api.ic0_msg_arg_data_copy(
+                        dst.to_usize(),
+                        offset.to_usize(),
+                        size.to_usize(),
+                        mem,
+                    )
                 })?;
                 if feature_flags.write_barrier == FlagStatus::Enabled {
-                    mark_writes_on_bytemap(&mut caller, dst as u32 as usize, size as u32 as usize)
+                    mark_writes_on_bytemap(&mut caller, dst.to_usize(), size.to_usize())
                 } else {
                     Ok(())
                 }
@@ -491,7 +536,7 @@ pub(crate) fn syscalls<S: SystemApi>(
                 with_system_api(&mut caller, |s| s.ic0_msg_method_name_size())
                     .map_err(|e| process_err(&mut caller, e))
                     .and_then(|s| {
-                        i32::try_from(s).map_err(|e| {
+                        I::try_from(s).map_err(|e| {
                             anyhow::Error::msg(format!("ic0::msg_metohd_name_size failed: {}", e))
                         })
                     })
@@ -502,13 +547,13 @@ pub(crate) fn syscalls<S: SystemApi>(
     linker
         .func_wrap("ic0", "msg_method_name_copy", {
             let log = log.clone();
-            move |mut caller: Caller<'_, StoreData<S>>, dst: i32, offset: i32, size: i32| {
+            move |mut caller: Caller<'_, StoreData<S>>, dst: I, offset: I, size: I| {
                 charge_for_system_api_call(
                     &log,
                     canister_id,
                     &mut caller,
                     system_api_complexity::overhead::MSG_METHOD_NAME_COPY,
-                    size as u32,
+                    size.to_usize(),
                     ExecutionComplexity {
                         cpu: system_api_complexity::cpu::MSG_METHOD_NAME_COPY,
                         ..Default::default()
@@ -518,14 +563,14 @@ pub(crate) fn syscalls<S: SystemApi>(
                 )?;
                 with_memory_and_system_api(&mut caller, |system_api, memory| {
                     system_api.ic0_msg_method_name_copy(
-                        dst as u32,
-                        offset as u32,
-                        size as u32,
+                        dst.to_usize(),
+                        offset.to_usize(),
+                        size.to_usize(),
                         memory,
                     )
                 })?;
                 if feature_flags.write_barrier == FlagStatus::Enabled {
-                    mark_writes_on_bytemap(&mut caller, dst as u32 as usize, size as u32 as usize)
+                    mark_writes_on_bytemap(&mut caller, dst.to_usize(), size.to_usize())
                 } else {
                     Ok(())
                 }
@@ -545,13 +590,13 @@ pub(crate) fn syscalls<S: SystemApi>(
     linker
         .func_wrap("ic0", "msg_reply_data_append", {
             let log = log.clone();
-            move |mut caller: Caller<'_, StoreData<S>>, src: i32, size: i32| {
+            move |mut caller: Caller<'_, StoreData<S>>, src: I, size: I| {
                 charge_for_system_api_call(
                     &log,
                     canister_id,
                     &mut caller,
                     system_api_complexity::overhead::MSG_REPLY_DATA_APPEND,
-                    size as u32,
+                    size.to_usize(),
                     ExecutionComplexity {
                         cpu: system_api_complexity::cpu::MSG_REPLY_DATA_APPEND,
                         ..Default::default()
@@ -560,7 +605,7 @@ pub(crate) fn syscalls<S: SystemApi>(
                     stable_memory_dirty_page_limit,
                 )?;
                 with_memory_and_system_api(&mut caller, |system_api, memory| {
-                    system_api.ic0_msg_reply_data_append(src as u32, size as u32, memory)
+                    system_api.ic0_msg_reply_data_append(src.to_usize(), size.to_usize(), memory)
                 })
             }
         })
@@ -587,13 +632,13 @@ pub(crate) fn syscalls<S: SystemApi>(
     linker
         .func_wrap("ic0", "msg_reject", {
             let log = log.clone();
-            move |mut caller: Caller<'_, StoreData<S>>, src: i32, size: i32| {
+            move |mut caller: Caller<'_, StoreData<S>>, src: I, size: I| {
                 charge_for_system_api_call(
                     &log,
                     canister_id,
                     &mut caller,
                     system_api_complexity::overhead::MSG_REJECT,
-                    size as u32,
+                    size.to_usize(),
                     ExecutionComplexity {
                         cpu: system_api_complexity::cpu::MSG_REJECT,
                         ..Default::default()
@@ -602,7 +647,7 @@ pub(crate) fn syscalls<S: SystemApi>(
                     stable_memory_dirty_page_limit,
                 )?;
                 with_memory_and_system_api(&mut caller, |system_api, memory| {
-                    system_api.ic0_msg_reject(src as u32, size as u32, memory)
+                    system_api.ic0_msg_reject(src.to_usize(), size.to_usize(), memory)
                 })
             }
         })
@@ -614,7 +659,7 @@ pub(crate) fn syscalls<S: SystemApi>(
                 with_system_api(&mut caller, |s| s.ic0_msg_reject_msg_size())
                     .map_err(|e| process_err(&mut caller, e))
                     .and_then(|s| {
-                        i32::try_from(s).map_err(|e| {
+                        I::try_from(s).map_err(|e| {
                             anyhow::Error::msg(format!("ic0_msg_reject_msg_size failed: {}", e))
                         })
                     })
@@ -625,13 +670,13 @@ pub(crate) fn syscalls<S: SystemApi>(
     linker
         .func_wrap("ic0", "msg_reject_msg_copy", {
             let log = log.clone();
-            move |mut caller: Caller<'_, StoreData<S>>, dst: i32, offset: i32, size: i32| {
+            move |mut caller: Caller<'_, StoreData<S>>, dst: I, offset: I, size: I| {
                 charge_for_system_api_call(
                     &log,
                     canister_id,
                     &mut caller,
                     system_api_complexity::overhead::MSG_REJECT_MSG_COPY,
-                    size as u32,
+                    size.to_usize(),
                     ExecutionComplexity {
                         cpu: system_api_complexity::cpu::MSG_REJECT_MSG_COPY,
                         ..Default::default()
@@ -641,14 +686,14 @@ pub(crate) fn syscalls<S: SystemApi>(
                 )?;
                 with_memory_and_system_api(&mut caller, |system_api, memory| {
                     system_api.ic0_msg_reject_msg_copy(
-                        dst as u32,
-                        offset as u32,
-                        size as u32,
+                        dst.to_usize(),
+                        offset.to_usize(),
+                        size.to_usize(),
                         memory,
                     )
                 })?;
                 if feature_flags.write_barrier == FlagStatus::Enabled {
-                    mark_writes_on_bytemap(&mut caller, dst as u32 as usize, size as u32 as usize)
+                    mark_writes_on_bytemap(&mut caller, dst.to_usize(), size.to_usize())
                 } else {
                     Ok(())
                 }
@@ -662,7 +707,7 @@ pub(crate) fn syscalls<S: SystemApi>(
                 with_system_api(&mut caller, |s| s.ic0_canister_self_size())
                     .map_err(|e| process_err(&mut caller, e))
                     .and_then(|s| {
-                        i32::try_from(s).map_err(|e| {
+                        I::try_from(s).map_err(|e| {
                             anyhow::Error::msg(format!("ic0_canister_self_size failed: {}", e))
                         })
                     })
@@ -673,7 +718,7 @@ pub(crate) fn syscalls<S: SystemApi>(
     linker
         .func_wrap("ic0", "canister_self_copy", {
             let log = log.clone();
-            move |mut caller: Caller<'_, StoreData<S>>, dst: i32, offset: i32, size: i32| {
+            move |mut caller: Caller<'_, StoreData<S>>, dst: I, offset: I, size: I| {
                 observe_execution_complexity(
                     &log,
                     canister_id,
@@ -686,14 +731,14 @@ pub(crate) fn syscalls<S: SystemApi>(
                 )?;
                 with_memory_and_system_api(&mut caller, |system_api, memory| {
                     system_api.ic0_canister_self_copy(
-                        dst as u32,
-                        offset as u32,
-                        size as u32,
+                        dst.to_usize(),
+                        offset.to_usize(),
+                        size.to_usize(),
                         memory,
                     )
                 })?;
                 if feature_flags.write_barrier == FlagStatus::Enabled {
-                    mark_writes_on_bytemap(&mut caller, dst as u32 as usize, size as u32 as usize)
+                    mark_writes_on_bytemap(&mut caller, dst.to_usize(), size.to_usize())
                 } else {
                     Ok(())
                 }
@@ -707,7 +752,7 @@ pub(crate) fn syscalls<S: SystemApi>(
                 with_system_api(&mut caller, |s| s.ic0_controller_size())
                     .map_err(|e| process_err(&mut caller, e))
                     .and_then(|s| {
-                        i32::try_from(s).map_err(|e| {
+                        I::try_from(s).map_err(|e| {
                             anyhow::Error::msg(format!("ic0_controller_size failed: {}", e))
                         })
                     })
@@ -718,7 +763,7 @@ pub(crate) fn syscalls<S: SystemApi>(
     linker
         .func_wrap("ic0", "controller_copy", {
             let log = log.clone();
-            move |mut caller: Caller<'_, StoreData<S>>, dst: i32, offset: i32, size: i32| {
+            move |mut caller: Caller<'_, StoreData<S>>, dst: I, offset: I, size: I| {
                 observe_execution_complexity(
                     &log,
                     canister_id,
@@ -730,10 +775,15 @@ pub(crate) fn syscalls<S: SystemApi>(
                     stable_memory_dirty_page_limit,
                 )?;
                 with_memory_and_system_api(&mut caller, |system_api, memory| {
-                    system_api.ic0_controller_copy(dst as u32, offset as u32, size as u32, memory)
+                    system_api.ic0_controller_copy(
+                        dst.to_usize(),
+                        offset.to_usize(),
+                        size.to_usize(),
+                        memory,
+                    )
                 })?;
                 if feature_flags.write_barrier == FlagStatus::Enabled {
-                    mark_writes_on_bytemap(&mut caller, dst as u32 as usize, size as u32 as usize)
+                    mark_writes_on_bytemap(&mut caller, dst.to_usize(), size.to_usize())
                 } else {
                     Ok(())
                 }
@@ -744,13 +794,13 @@ pub(crate) fn syscalls<S: SystemApi>(
     linker
         .func_wrap("ic0", "debug_print", {
             let log = log.clone();
-            move |mut caller: Caller<'_, StoreData<S>>, offset: i32, length: i32| {
+            move |mut caller: Caller<'_, StoreData<S>>, offset: I, length: I| {
                 charge_for_system_api_call(
                     &log,
                     canister_id,
                     &mut caller,
                     system_api_complexity::overhead::DEBUG_PRINT,
-                    length as u32,
+                    length.to_usize(),
                     ExecutionComplexity {
                         cpu: system_api_complexity::cpu::DEBUG_PRINT,
                         ..Default::default()
@@ -758,6 +808,16 @@ pub(crate) fn syscalls<S: SystemApi>(
                     NumInstructions::from(0),
                     stable_memory_dirty_page_limit,
                 )?;
+                // The message goes to the canister log regardless of rate limiting.
+                with_memory_and_system_api(&mut caller, |system_api, memory| {
+                    system_api.save_log_message(
+                        false,
+                        offset.to_usize(),
+                        length.to_usize(),
+                        memory,
+                    );
+                    Ok(())
+                })?;
                 match (
                     caller.data().system_api.subnet_type(),
                     feature_flags.rate_limiting_of_debug_prints,
@@ -769,7 +829,7 @@ pub(crate) fn syscalls<S: SystemApi>(
                     // debug print produces output.
                     (_, FlagStatus::Disabled) | (SubnetType::System, FlagStatus::Enabled) => {
                         with_memory_and_system_api(&mut caller, |system_api, memory| {
-                            system_api.ic0_debug_print(offset as u32, length as u32, memory)
+                            system_api.ic0_debug_print(offset.to_usize(), length.to_usize(), memory)
                         })
                     }
                 }
@@ -780,13 +840,13 @@ pub(crate) fn syscalls<S: SystemApi>(
     linker
         .func_wrap("ic0", "trap", {
             let log = log.clone();
-            move |mut caller: Caller<'_, StoreData<S>>, offset: i32, length: i32| -> Result<(), _> {
+            move |mut caller: Caller<'_, StoreData<S>>, offset: I, length: I| -> Result<(), _> {
                 charge_for_system_api_call(
                     &log,
                     canister_id,
                     &mut caller,
                     system_api_complexity::overhead::TRAP,
-                    length as u32,
+                    length.to_usize(),
                     ExecutionComplexity {
                         cpu: system_api_complexity::cpu::TRAP,
                         ..Default::default()
@@ -795,7 +855,8 @@ pub(crate) fn syscalls<S: SystemApi>(
                     stable_memory_dirty_page_limit,
                 )?;
                 with_memory_and_system_api(&mut caller, |system_api, memory| {
-                    system_api.ic0_trap(offset as u32, length as u32, memory)
+                    system_api.save_log_message(true, offset.to_usize(), length.to_usize(), memory);
+                    system_api.ic0_trap(offset.to_usize(), length.to_usize(), memory)
                 })
             }
         })
@@ -805,14 +866,14 @@ pub(crate) fn syscalls<S: SystemApi>(
         .func_wrap("ic0", "call_new", {
             let log = log.clone();
             move |mut caller: Caller<'_, StoreData<S>>,
-                  callee_src: i32,
-                  callee_size: i32,
-                  name_src: i32,
-                  name_len: i32,
-                  reply_fun: i32,
-                  reply_env: i32,
-                  reject_fun: i32,
-                  reject_env: i32| {
+                  callee_src: I,
+                  callee_size: I,
+                  name_src: I,
+                  name_len: I,
+                  reply_fun: I,
+                  reply_env: I,
+                  reject_fun: I,
+                  reject_env: I| {
                 observe_execution_complexity(
                     &log,
                     canister_id,
@@ -825,14 +886,14 @@ pub(crate) fn syscalls<S: SystemApi>(
                 )?;
                 with_memory_and_system_api(&mut caller, |system_api, memory| {
                     system_api.ic0_call_new(
-                        callee_src as u32,
-                        callee_size as u32,
-                        name_src as u32,
-                        name_len as u32,
-                        reply_fun as u32,
-                        reply_env as u32,
-                        reject_fun as u32,
-                        reject_env as u32,
+                        callee_src.to_usize(),
+                        callee_size.to_usize(),
+                        name_src.to_usize(),
+                        name_len.to_usize(),
+                        closure_arg_to_u32(reply_fun)?,
+                        closure_arg_to_u32(reply_env)?,
+                        closure_arg_to_u32(reject_fun)?,
+                        closure_arg_to_u32(reject_env)?,
                         memory,
                     )
                 })
@@ -843,13 +904,13 @@ pub(crate) fn syscalls<S: SystemApi>(
     linker
         .func_wrap("ic0", "call_data_append", {
             let log = log.clone();
-            move |mut caller: Caller<'_, StoreData<S>>, src: i32, size: i32| {
+            move |mut caller: Caller<'_, StoreData<S>>, src: I, size: I| {
                 charge_for_system_api_call(
                     &log,
                     canister_id,
                     &mut caller,
                     system_api_complexity::overhead::CALL_DATA_APPEND,
-                    size as u32,
+                    size.to_usize(),
                     ExecutionComplexity {
                         cpu: system_api_complexity::cpu::CALL_DATA_APPEND,
                         ..Default::default()
@@ -858,7 +919,7 @@ pub(crate) fn syscalls<S: SystemApi>(
                     stable_memory_dirty_page_limit,
                 )?;
                 with_memory_and_system_api(&mut caller, |system_api, memory| {
-                    system_api.ic0_call_data_append(src as u32, size as u32, memory)
+                    system_api.ic0_call_data_append(src.to_usize(), size.to_usize(), memory)
                 })
             }
         })
@@ -866,9 +927,20 @@ pub(crate) fn syscalls<S: SystemApi>(
 
     linker
         .func_wrap("ic0", "call_on_cleanup", {
-            move |mut caller: Caller<'_, StoreData<S>>, fun: i32, env: i32| {
+            move |mut caller: Caller<'_, StoreData<S>>, fun: I, env: I| {
                 with_system_api(&mut caller, |s| {
-                    s.ic0_call_on_cleanup(fun as u32, env as u32)
+                    s.ic0_call_on_cleanup(closure_arg_to_u32(fun)?, closure_arg_to_u32(env)?)
+                })
+                .map_err(|e| process_err(&mut caller, e))
+            }
+        })
+        .unwrap();
+
+    linker
+        .func_wrap("ic0", "call_with_best_effort_response", {
+            move |mut caller: Caller<'_, StoreData<S>>, timeout_seconds: i32| {
+                with_system_api(&mut caller, |s| {
+                    s.ic0_call_with_best_effort_response(timeout_seconds as u32)
                 })
                 .map_err(|e| process_err(&mut caller, e))
             }
@@ -961,7 +1033,7 @@ pub(crate) fn syscalls<S: SystemApi>(
                     canister_id,
                     &mut caller,
                     system_api_complexity::overhead::STABLE_READ,
-                    size as u32,
+                    size as u32 as usize,
                     ExecutionComplexity {
                         cpu: system_api_complexity::cpu::STABLE_READ,
                         ..Default::default()
@@ -995,7 +1067,7 @@ pub(crate) fn syscalls<S: SystemApi>(
                     canister_id,
                     &mut caller,
                     system_api_complexity::overhead::STABLE_WRITE,
-                    size,
+                    size as usize,
                     ExecutionComplexity {
                         cpu: system_api_complexity::cpu::STABLE_WRITE,
                         stable_dirty_pages,
@@ -1055,7 +1127,7 @@ pub(crate) fn syscalls<S: SystemApi>(
                     canister_id,
                     &mut caller,
                     system_api_complexity::overhead::STABLE64_READ,
-                    size as u32,
+                    size as u32 as usize,
                     ExecutionComplexity {
                         cpu: system_api_complexity::cpu::STABLE64_READ,
                         ..Default::default()
@@ -1067,7 +1139,7 @@ pub(crate) fn syscalls<S: SystemApi>(
                     system_api.ic0_stable64_read(dst as u64, offset as u64, size as u64, memory)
                 })?;
                 if feature_flags.write_barrier == FlagStatus::Enabled {
-                    mark_writes_on_bytemap(&mut caller, dst as u32 as usize, size as u32 as usize)
+                    mark_writes_on_bytemap(&mut caller, dst as usize, size as usize)
                 } else {
                     Ok(())
                 }
@@ -1089,7 +1161,7 @@ pub(crate) fn syscalls<S: SystemApi>(
                     canister_id,
                     &mut caller,
                     system_api_complexity::overhead::STABLE64_WRITE,
-                    size as u32,
+                    size as u32 as usize,
                     ExecutionComplexity {
                         cpu: system_api_complexity::cpu::STABLE64_WRITE,
                         stable_dirty_pages,
@@ -1157,6 +1229,163 @@ pub(crate) fn syscalls<S: SystemApi>(
         })
         .unwrap();
 
+    linker
+        .func_wrap("ic0", "is_controller", {
+            let log = log.clone();
+            move |mut caller: Caller<'_, StoreData<S>>, src: I, size: I| {
+                charge_for_system_api_call(
+                    &log,
+                    canister_id,
+                    &mut caller,
+                    system_api_complexity::overhead::IS_CONTROLLER,
+                    size.to_usize(),
+                    ExecutionComplexity {
+                        cpu: system_api_complexity::cpu::IS_CONTROLLER,
+                        ..Default::default()
+                    },
+                    NumInstructions::from(0),
+                    stable_memory_dirty_page_limit,
+                )?;
+                with_memory_and_system_api(&mut caller, |system_api, memory| {
+                    system_api.ic0_is_controller(src.to_usize(), size.to_usize(), memory)
+                })
+                .map(|result| result as i32)
+            }
+        })
+        .unwrap();
+
+    linker
+        .func_wrap("ic0", "in_replicated_execution", {
+            move |mut caller: Caller<'_, StoreData<S>>| {
+                with_system_api(&mut caller, |s| s.ic0_in_replicated_execution())
+                    .map_err(|e| process_err(&mut caller, e))
+            }
+        })
+        .unwrap();
+
+    linker
+        .func_wrap("ic0", "cost_call", {
+            let log = log.clone();
+            move |mut caller: Caller<'_, StoreData<S>>,
+                  method_name_size: u64,
+                  payload_size: u64,
+                  dst: I| {
+                observe_execution_complexity(
+                    &log,
+                    canister_id,
+                    &mut caller,
+                    ExecutionComplexity {
+                        cpu: system_api_complexity::cpu::COST_CALL,
+                        ..Default::default()
+                    },
+                    stable_memory_dirty_page_limit,
+                )?;
+                with_memory_and_system_api(&mut caller, |system_api, memory| {
+                    system_api.ic0_cost_call(method_name_size, payload_size, dst.to_usize(), memory)
+                })?;
+                if feature_flags.write_barrier == FlagStatus::Enabled {
+                    mark_writes_on_bytemap(&mut caller, dst.to_usize(), 16)
+                } else {
+                    Ok(())
+                }
+            }
+        })
+        .unwrap();
+
+    linker
+        .func_wrap("ic0", "cost_create_canister", {
+            let log = log.clone();
+            move |mut caller: Caller<'_, StoreData<S>>, dst: I| {
+                observe_execution_complexity(
+                    &log,
+                    canister_id,
+                    &mut caller,
+                    ExecutionComplexity {
+                        cpu: system_api_complexity::cpu::COST_CREATE_CANISTER,
+                        ..Default::default()
+                    },
+                    stable_memory_dirty_page_limit,
+                )?;
+                with_memory_and_system_api(&mut caller, |system_api, memory| {
+                    system_api.ic0_cost_create_canister(dst.to_usize(), memory)
+                })?;
+                if feature_flags.write_barrier == FlagStatus::Enabled {
+                    mark_writes_on_bytemap(&mut caller, dst.to_usize(), 16)
+                } else {
+                    Ok(())
+                }
+            }
+        })
+        .unwrap();
+
+    linker
+        .func_wrap("ic0", "cost_http_request", {
+            let log = log.clone();
+            move |mut caller: Caller<'_, StoreData<S>>,
+                  request_size: u64,
+                  max_res_bytes: u64,
+                  dst: I| {
+                observe_execution_complexity(
+                    &log,
+                    canister_id,
+                    &mut caller,
+                    ExecutionComplexity {
+                        cpu: system_api_complexity::cpu::COST_HTTP_REQUEST,
+                        ..Default::default()
+                    },
+                    stable_memory_dirty_page_limit,
+                )?;
+                with_memory_and_system_api(&mut caller, |system_api, memory| {
+                    system_api.ic0_cost_http_request(
+                        request_size,
+                        max_res_bytes,
+                        dst.to_usize(),
+                        memory,
+                    )
+                })?;
+                if feature_flags.write_barrier == FlagStatus::Enabled {
+                    mark_writes_on_bytemap(&mut caller, dst.to_usize(), 16)
+                } else {
+                    Ok(())
+                }
+            }
+        })
+        .unwrap();
+
+    linker
+        .func_wrap("ic0", "cost_sign_with_ecdsa", {
+            let log = log.clone();
+            move |mut caller: Caller<'_, StoreData<S>>, src: I, size: I, curve: u32, dst: I| {
+                charge_for_system_api_call(
+                    &log,
+                    canister_id,
+                    &mut caller,
+                    system_api_complexity::overhead::COST_SIGN_WITH_ECDSA,
+                    size.to_usize(),
+                    ExecutionComplexity {
+                        cpu: system_api_complexity::cpu::COST_SIGN_WITH_ECDSA,
+                        ..Default::default()
+                    },
+                    NumInstructions::from(0),
+                    stable_memory_dirty_page_limit,
+                )?;
+                let result = with_memory_and_system_api(&mut caller, |system_api, memory| {
+                    system_api.ic0_cost_sign_with_ecdsa(
+                        src.to_usize(),
+                        size.to_usize(),
+                        curve,
+                        dst.to_usize(),
+                        memory,
+                    )
+                })?;
+                if result == 0 && feature_flags.write_barrier == FlagStatus::Enabled {
+                    mark_writes_on_bytemap(&mut caller, dst.to_usize(), 16)?;
+                }
+                Ok(result)
+            }
+        })
+        .unwrap();
+
     linker
         .func_wrap("ic0", "canister_cycle_balance", {
             move |mut caller: Caller<'_, StoreData<S>>| {
@@ -1174,7 +1403,7 @@ pub(crate) fn syscalls<S: SystemApi>(
     linker
         .func_wrap("ic0", "canister_cycle_balance128", {
             let log = log.clone();
-            move |mut caller: Caller<'_, StoreData<S>>, dst: u32| {
+            move |mut caller: Caller<'_, StoreData<S>>, dst: I| {
                 observe_execution_complexity(
                     &log,
                     canister_id,
@@ -1186,10 +1415,10 @@ pub(crate) fn syscalls<S: SystemApi>(
                     stable_memory_dirty_page_limit,
                 )?;
                 with_memory_and_system_api(&mut caller, |system_api, memory| {
-                    system_api.ic0_canister_cycle_balance128(dst, memory)
+                    system_api.ic0_canister_cycle_balance128(dst.to_usize(), memory)
                 })?;
                 if feature_flags.write_barrier == FlagStatus::Enabled {
-                    mark_writes_on_bytemap(&mut caller, dst as usize, 16)
+                    mark_writes_on_bytemap(&mut caller, dst.to_usize(), 16)
                 } else {
                     Ok(())
                 }
@@ -1214,7 +1443,7 @@ pub(crate) fn syscalls<S: SystemApi>(
     linker
         .func_wrap("ic0", "msg_cycles_available128", {
             let log = log.clone();
-            move |mut caller: Caller<'_, StoreData<S>>, dst: u32| {
+            move |mut caller: Caller<'_, StoreData<S>>, dst: I| {
                 observe_execution_complexity(
                     &log,
                     canister_id,
@@ -1226,10 +1455,10 @@ pub(crate) fn syscalls<S: SystemApi>(
                     stable_memory_dirty_page_limit,
                 )?;
                 with_memory_and_system_api(&mut caller, |system_api, memory| {
-                    system_api.ic0_msg_cycles_available128(dst, memory)
+                    system_api.ic0_msg_cycles_available128(dst.to_usize(), memory)
                 })?;
                 if feature_flags.write_barrier == FlagStatus::Enabled {
-                    mark_writes_on_bytemap(&mut caller, dst as usize, 16)
+                    mark_writes_on_bytemap(&mut caller, dst.to_usize(), 16)
                 } else {
                     Ok(())
                 }
@@ -1254,7 +1483,7 @@ pub(crate) fn syscalls<S: SystemApi>(
     linker
         .func_wrap("ic0", "msg_cycles_refunded128", {
             let log = log.clone();
-            move |mut caller: Caller<'_, StoreData<S>>, dst: u32| {
+            move |mut caller: Caller<'_, StoreData<S>>, dst: I| {
                 observe_execution_complexity(
                     &log,
                     canister_id,
@@ -1266,10 +1495,10 @@ pub(crate) fn syscalls<S: SystemApi>(
                     stable_memory_dirty_page_limit,
                 )?;
                 with_memory_and_system_api(&mut caller, |system_api, memory| {
-                    system_api.ic0_msg_cycles_refunded128(dst, memory)
+                    system_api.ic0_msg_cycles_refunded128(dst.to_usize(), memory)
                 })?;
                 if feature_flags.write_barrier == FlagStatus::Enabled {
-                    mark_writes_on_bytemap(&mut caller, dst as usize, 16)
+                    mark_writes_on_bytemap(&mut caller, dst.to_usize(), 16)
                 } else {
                     Ok(())
                 }
@@ -1292,7 +1521,7 @@ pub(crate) fn syscalls<S: SystemApi>(
             move |mut caller: Caller<'_, StoreData<S>>,
                   amount_high: i64,
                   amount_low: i64,
-                  dst: u32| {
+                  dst: I| {
                 observe_execution_complexity(
                     &log,
                     canister_id,
@@ -1306,12 +1535,12 @@ pub(crate) fn syscalls<S: SystemApi>(
                 with_memory_and_system_api(&mut caller, |system_api, memory| {
                     system_api.ic0_msg_cycles_accept128(
                         Cycles::from_parts(amount_high as u64, amount_low as u64),
-                        dst,
+                        dst.to_usize(),
                         memory,
                     )
                 })?;
                 if feature_flags.write_barrier == FlagStatus::Enabled {
-                    mark_writes_on_bytemap(&mut caller, dst as usize, 16)
+                    mark_writes_on_bytemap(&mut caller, dst.to_usize(), 16)
                 } else {
                     Ok(())
                 }
@@ -1336,12 +1565,12 @@ pub(crate) fn syscalls<S: SystemApi>(
     linker
         .func_wrap("__", "update_available_memory", {
             move |mut caller: Caller<'_, StoreData<S>>,
-                  native_memory_grow_res: i32,
-                  additional_pages: i32| {
+                  native_memory_grow_res: I,
+                  additional_pages: I| {
                 with_system_api(&mut caller, |s| {
                     s.update_available_memory(
-                        native_memory_grow_res as i64,
-                        additional_pages as u32 as u64,
+                        native_memory_grow_res.into(),
+                        additional_pages.to_usize() as u64,
                     )
                 })
                 .map(|()| native_memory_grow_res)
@@ -1393,7 +1622,7 @@ pub(crate) fn syscalls<S: SystemApi>(
     linker
         .func_wrap("ic0", "certified_data_set", {
             let log = log.clone();
-            move |mut caller: Caller<'_, StoreData<S>>, src: u32, size: u32| {
+            move |mut caller: Caller<'_, StoreData<S>>, src: I, size: I| {
                 observe_execution_complexity(
                     &log,
                     canister_id,
@@ -1405,7 +1634,7 @@ pub(crate) fn syscalls<S: SystemApi>(
                     stable_memory_dirty_page_limit,
                 )?;
                 with_memory_and_system_api(&mut caller, |system_api, memory| {
-                    system_api.ic0_certified_data_set(src, size, memory)
+                    system_api.ic0_certified_data_set(src.to_usize(), size.to_usize(), memory)
                 })
             }
         })
@@ -1425,13 +1654,18 @@ pub(crate) fn syscalls<S: SystemApi>(
             move |mut caller: Caller<'_, StoreData<S>>| {
                 with_system_api(&mut caller, |s| s.ic0_data_certificate_size())
                     .map_err(|e| process_err(&mut caller, e))
+                    .and_then(|s| {
+                        I::try_from(s).map_err(|e| {
+                            anyhow::Error::msg(format!("ic0_data_certificate_size failed: {}", e))
+                        })
+                    })
             }
         })
         .unwrap();
 
     linker
         .func_wrap("ic0", "data_certificate_copy", {
-            move |mut caller: Caller<'_, StoreData<S>>, dst: u32, offset: u32, size: u32| {
+            move |mut caller: Caller<'_, StoreData<S>>, dst: I, offset: I, size: I| {
                 observe_execution_complexity(
                     &log,
                     canister_id,
@@ -1443,10 +1677,15 @@ pub(crate) fn syscalls<S: SystemApi>(
                     stable_memory_dirty_page_limit,
                 )?;
                 with_memory_and_system_api(&mut caller, |system_api, memory| {
-                    system_api.ic0_data_certificate_copy(dst, offset, size, memory)
+                    system_api.ic0_data_certificate_copy(
+                        dst.to_usize(),
+                        offset.to_usize(),
+                        size.to_usize(),
+                        memory,
+                    )
                 })?;
                 if feature_flags.write_barrier == FlagStatus::Enabled {
-                    mark_writes_on_bytemap(&mut caller, dst as usize, size as usize)
+                    mark_writes_on_bytemap(&mut caller, dst.to_usize(), size.to_usize())
                 } else {
                     Ok(())
                 }
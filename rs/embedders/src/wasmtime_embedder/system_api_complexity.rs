@@ -35,6 +35,8 @@ pub mod overhead {
     pub const STABLE64_READ: NumInstructions = NumInstructions::new(20);
     pub const STABLE64_WRITE: NumInstructions = NumInstructions::new(20);
     pub const PERFORMANCE_COUNTER: NumInstructions = NumInstructions::new(200);
+    pub const IS_CONTROLLER: NumInstructions = NumInstructions::new(1_000);
+    pub const COST_SIGN_WITH_ECDSA: NumInstructions = NumInstructions::new(100);
 }
 
 ///
@@ -104,4 +106,9 @@ pub mod cpu {
     pub const MSG_CYCLES_ACCEPT128: CpuComplexity = from_nanos(80);
     pub const CERTIFIED_DATA_SET: CpuComplexity = from_nanos(70);
     pub const PERFORMANCE_COUNTER: CpuComplexity = from_nanos(50);
+    pub const IS_CONTROLLER: CpuComplexity = from_nanos(70);
+    pub const COST_CALL: CpuComplexity = from_nanos(50);
+    pub const COST_CREATE_CANISTER: CpuComplexity = from_nanos(50);
+    pub const COST_HTTP_REQUEST: CpuComplexity = from_nanos(50);
+    pub const COST_SIGN_WITH_ECDSA: CpuComplexity = from_nanos(100);
 }
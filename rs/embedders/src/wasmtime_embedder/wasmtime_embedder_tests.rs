@@ -96,7 +96,7 @@ fn test_wasmtime_system_api() {
     let module = Module::new(&engine, instrumentation_output.binary.as_slice())
         .expect("failed to instantiate module");
 
-    let linker = system_api::syscalls(
+    let linker = system_api::syscalls::<_, i32>(
         no_op_logger(),
         canister_id,
         &store,
@@ -1,5 +1,5 @@
 use assert_matches::assert_matches;
-use ic_config::embedders::Config as EmbeddersConfig;
+use ic_config::{embedders::Config as EmbeddersConfig, flag_status::FlagStatus};
 use ic_embedders::{
     wasm_utils::{
         validate_and_instrument_for_testing,
@@ -872,3 +872,50 @@ fn complex_function_rejected() {
         })
     )
 }
+
+#[test]
+fn can_reject_wasm64_memory_by_default() {
+    let wasm = wat2wasm(r#"(module (memory (export "memory") i64 1))"#).unwrap();
+    assert_matches!(
+        validate_wasm_binary(&wasm, &EmbeddersConfig::default()),
+        Err(WasmValidationError::WasmtimeValidation(_))
+    );
+}
+
+#[test]
+fn can_validate_wasm64_memory_with_feature_flag() {
+    let wasm = wat2wasm(
+        r#"(module
+                (import "ic0" "msg_reply_data_append"
+                    (func $msg_reply_data_append (param i64 i64)))
+                (memory (export "memory") i64 1)
+                (data (i64.const 0) "hello"))"#,
+    )
+    .unwrap();
+    let mut config = EmbeddersConfig::default();
+    config.feature_flags.wasm64 = FlagStatus::Enabled;
+    assert_eq!(
+        validate_wasm_binary(&wasm, &config),
+        Ok(WasmValidationDetails {
+            is_wasm64: true,
+            ..Default::default()
+        })
+    );
+}
+
+#[test]
+fn can_reject_wasm64_memory_with_32_bit_system_api_imports() {
+    let wasm = wat2wasm(
+        r#"(module
+                (import "ic0" "msg_reply_data_append"
+                    (func $msg_reply_data_append (param i32 i32)))
+                (memory (export "memory") i64 1))"#,
+    )
+    .unwrap();
+    let mut config = EmbeddersConfig::default();
+    config.feature_flags.wasm64 = FlagStatus::Enabled;
+    assert_matches!(
+        validate_wasm_binary(&wasm, &config),
+        Err(WasmValidationError::InvalidFunctionSignature(_))
+    );
+}
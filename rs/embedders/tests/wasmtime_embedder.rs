@@ -1378,4 +1378,58 @@ mod test {
         let err = instance.run(func_ref("test_len_both")).unwrap_err();
         assert_eq!(err, Trapped(StableMemoryOutOfBounds));
     }
+
+    #[test]
+    fn wasm64_trap_reads_message_from_heap() {
+        let wat = r#"
+            (module
+                (import "ic0" "trap" (func $ic_trap (param i64 i64)))
+                (func $test (export "canister_update test")
+                    (i64.store8 (i64.const 10) (i64.const 72))
+                    (i64.store8 (i64.const 11) (i64.const 101))
+                    (i64.store8 (i64.const 12) (i64.const 108))
+                    (i64.store8 (i64.const 13) (i64.const 108))
+                    (i64.store8 (i64.const 14) (i64.const 111))
+                    (call $ic_trap (i64.const 10) (i64.const 5))
+                )
+                (memory (export "memory") i64 1)
+            )"#;
+        let mut config = ic_config::embedders::Config::default();
+        config.feature_flags.wasm64 = ic_config::flag_status::FlagStatus::Enabled;
+        let mut instance = WasmtimeInstanceBuilder::new()
+            .with_config(config)
+            .with_wat(wat)
+            .build();
+        let err = instance
+            .run(FuncRef::Method(WasmMethod::Update("test".to_string())))
+            .unwrap_err();
+        assert_eq!(err, HypervisorError::CalledTrap("Hello".to_string()));
+    }
+
+    #[test]
+    fn wasm64_heap_cannot_grow_beyond_wasm64_limit() {
+        // The heap declares the maximum of the stable memory, but is still
+        // capped at the Wasm64 heap limit of 8GiB (131072 pages).
+        let wat = r#"
+            (module
+                (func $test (export "canister_update test")
+                    (if (i64.ne (memory.grow (i64.const 131072)) (i64.const -1))
+                        (then (unreachable))
+                    )
+                    (if (i64.ne (memory.grow (i64.const 131071)) (i64.const 1))
+                        (then (unreachable))
+                    )
+                )
+                (memory (export "memory") i64 1 786432)
+            )"#;
+        let mut config = ic_config::embedders::Config::default();
+        config.feature_flags.wasm64 = ic_config::flag_status::FlagStatus::Enabled;
+        let mut instance = WasmtimeInstanceBuilder::new()
+            .with_config(config)
+            .with_wat(wat)
+            .build();
+        instance
+            .run(FuncRef::Method(WasmMethod::Update("test".to_string())))
+            .unwrap();
+    }
 }
@@ -1,5 +1,6 @@
 use crate::execution::install_code::{
-    validate_compute_allocation, validate_controller, validate_memory_allocation, OriginalContext,
+    canister_layout, validate_compute_allocation, validate_controller, validate_memory_allocation,
+    OriginalContext,
 };
 use crate::execution::{install::execute_install, upgrade::execute_upgrade};
 use crate::execution_environment::{CompilationCostHandling, RoundContext, RoundLimits};
@@ -14,8 +15,9 @@ use ic_config::flag_status::FlagStatus;
 use ic_cycles_account_manager::CyclesAccountManager;
 use ic_error_types::{ErrorCode, RejectCode, UserError};
 use ic_ic00_types::{
-    CanisterInstallMode, CanisterStatusResultV2, CanisterStatusType, InstallCodeArgs,
-    Method as Ic00Method,
+    CanisterChangeDetails, CanisterChangeOrigin, CanisterInfoResponse, CanisterInstallMode,
+    CanisterSnapshotResponse, CanisterStatusResultV2, CanisterStatusType, ChunkHash,
+    InstallChunkedCodeArgs, InstallCodeArgs, LogVisibility, Method as Ic00Method,
 };
 use ic_interfaces::execution_environment::{
     CanisterOutOfCyclesError, HypervisorError, IngressHistoryWriter, SubnetAvailableMemory,
@@ -25,8 +27,10 @@ use ic_logger::{error, fatal, info, ReplicaLogger};
 use ic_registry_provisional_whitelist::ProvisionalWhitelist;
 use ic_registry_subnet_type::SubnetType;
 use ic_replicated_state::{
-    CallOrigin, CanisterState, CanisterStatus, NetworkTopology, ReplicatedState, SchedulerState,
-    SystemState,
+    canister_snapshots::MAX_SNAPSHOTS_PER_CANISTER,
+    canister_state::{execution_state::WasmBinary, system_state::wasm_chunk_store::chunk_hash},
+    CallOrigin, CanisterSnapshot, CanisterState, CanisterStatus, ExecutionState, Memory,
+    NetworkTopology, PageMap, ReplicatedState, SchedulerState, SnapshotId, SystemState,
 };
 use ic_system_api::ExecutionParameters;
 use ic_types::messages::{MessageId, SignedIngressContent};
@@ -37,7 +41,8 @@ use ic_types::{
     messages::{Payload, RejectContext, Response as CanisterResponse, StopCanisterContext},
     CanisterId, CanisterTimer, ComputeAllocation, Cycles, InvalidComputeAllocationError,
     InvalidMemoryAllocationError, InvalidQueryAllocationError, MemoryAllocation, NumBytes,
-    PrincipalId, QueryAllocation, SubnetId, Time,
+    PrincipalId, QueryAllocation, SubnetId, Time, MAX_MEMORY_ALLOCATION,
+    MAX_WASM64_MEMORY_ALLOCATION,
 };
 use ic_wasm_types::CanisterModule;
 use num_traits::cast::ToPrimitive;
@@ -101,6 +106,7 @@ pub(crate) struct CanisterMgrConfig {
     pub(crate) own_subnet_type: SubnetType,
     pub(crate) max_controllers: usize,
     pub(crate) rate_limiting_of_instructions: FlagStatus,
+    pub(crate) wasm64: FlagStatus,
 }
 
 impl CanisterMgrConfig {
@@ -115,6 +121,7 @@ impl CanisterMgrConfig {
         compute_capacity: usize,
         rate_limiting_of_instructions: FlagStatus,
         allocatable_capacity_in_percent: usize,
+        wasm64: FlagStatus,
     ) -> Self {
         Self {
             subnet_memory_capacity,
@@ -126,13 +133,23 @@ impl CanisterMgrConfig {
             compute_capacity: (compute_capacity * allocatable_capacity_in_percent.min(100) / 100)
                 as u64,
             rate_limiting_of_instructions,
+            wasm64,
+        }
+    }
+
+    /// Returns the upper limit on the memory allocation of a canister, which
+    /// is higher if canisters may use a 64-bit main memory.
+    pub(crate) fn max_memory_allocation(&self) -> NumBytes {
+        match self.wasm64 {
+            FlagStatus::Enabled => MAX_WASM64_MEMORY_ALLOCATION,
+            FlagStatus::Disabled => MAX_MEMORY_ALLOCATION,
         }
     }
 }
 
 #[derive(Clone, Debug)]
 pub struct InstallCodeContext {
-    pub sender: PrincipalId,
+    pub origin: CanisterChangeOrigin,
     pub mode: CanisterInstallMode,
     pub canister_id: CanisterId,
     pub wasm_module: CanisterModule,
@@ -142,7 +159,13 @@ pub struct InstallCodeContext {
     pub query_allocation: QueryAllocation,
 }
 
-/// Errors that can occur when converting from (sender, [`InstallCodeArgs`]) to
+impl InstallCodeContext {
+    pub fn sender(&self) -> PrincipalId {
+        self.origin.origin()
+    }
+}
+
+/// Errors that can occur when converting from (origin, [`InstallCodeArgs`]) to
 /// an [`InstallCodeContext`].
 #[derive(Debug)]
 pub enum InstallCodeContextError {
@@ -207,11 +230,23 @@ impl From<InvalidMemoryAllocationError> for InstallCodeContextError {
     }
 }
 
-impl TryFrom<(PrincipalId, InstallCodeArgs)> for InstallCodeContext {
+impl TryFrom<(CanisterChangeOrigin, InstallCodeArgs)> for InstallCodeContext {
+    type Error = InstallCodeContextError;
+
+    fn try_from(input: (CanisterChangeOrigin, InstallCodeArgs)) -> Result<Self, Self::Error> {
+        let (origin, args) = input;
+        InstallCodeContext::try_from((origin, args, MAX_MEMORY_ALLOCATION))
+    }
+}
+
+/// Converts the arguments with the given upper limit on the memory allocation.
+impl TryFrom<(CanisterChangeOrigin, InstallCodeArgs, NumBytes)> for InstallCodeContext {
     type Error = InstallCodeContextError;
 
-    fn try_from(input: (PrincipalId, InstallCodeArgs)) -> Result<Self, Self::Error> {
-        let (sender, args) = input;
+    fn try_from(
+        input: (CanisterChangeOrigin, InstallCodeArgs, NumBytes),
+    ) -> Result<Self, Self::Error> {
+        let (origin, args, max_memory_allocation) = input;
         let canister_id = CanisterId::new(args.canister_id).map_err(|err| {
             InstallCodeContextError::InvalidCanisterId(format!(
                 "Converting canister id {} failed with {}",
@@ -229,11 +264,14 @@ impl TryFrom<(PrincipalId, InstallCodeArgs)> for InstallCodeContext {
             None => None,
         };
         let memory_allocation = match args.memory_allocation {
-            Some(ma) => Some(MemoryAllocation::try_from(NumBytes::from(
-                ma.0.to_u64().ok_or_else(|| {
-                    InstallCodeContextError::MemoryAllocation(InvalidMemoryAllocationError::new(ma))
-                })?,
-            ))?),
+            Some(ma) => Some(MemoryAllocation::try_from_with_max(
+                NumBytes::from(ma.0.to_u64().ok_or_else(|| {
+                    InstallCodeContextError::MemoryAllocation(
+                        InvalidMemoryAllocationError::with_max(ma, max_memory_allocation),
+                    )
+                })?),
+                max_memory_allocation,
+            )?),
             None => None,
         };
 
@@ -241,7 +279,7 @@ impl TryFrom<(PrincipalId, InstallCodeArgs)> for InstallCodeContext {
         let query_allocation = QueryAllocation::default();
 
         Ok(InstallCodeContext {
-            sender,
+            origin,
             mode: args.mode,
             canister_id,
             wasm_module: CanisterModule::new(args.wasm_module),
@@ -279,6 +317,11 @@ impl CanisterManager {
         }
     }
 
+    /// Returns the upper limit on the memory allocation of a canister.
+    pub(crate) fn max_memory_allocation(&self) -> NumBytes {
+        self.config.max_memory_allocation()
+    }
+
     /// Checks if a given ingress message directed to the management canister
     /// should be accepted or not.
     pub(crate) fn should_accept_ingress_message(
@@ -298,9 +341,14 @@ impl CanisterManager {
             // are not allowed to send.
             Err(_)
             | Ok(Ic00Method::CreateCanister)
+            | Ok(Ic00Method::CanisterInfo)
             | Ok(Ic00Method::ECDSAPublicKey)
             | Ok(Ic00Method::SetupInitialDKG)
             | Ok(Ic00Method::SignWithECDSA)
+            | Ok(Ic00Method::SchnorrPublicKey)
+            | Ok(Ic00Method::SignWithSchnorr)
+            | Ok(Ic00Method::VetKdPublicKey)
+            | Ok(Ic00Method::VetKdEncryptedKey)
             | Ok(Ic00Method::ComputeInitialEcdsaDealings)
             // "DepositCycles" can be called by anyone however as ingress message
             // cannot carry cycles, it does not make sense to allow them from users.
@@ -318,6 +366,12 @@ impl CanisterManager {
                 format!("Only canisters can call ic00 method {}", method_name),
             )),
 
+            // `fetch_canister_logs` is only available as a query.
+            Ok(Ic00Method::FetchCanisterLogs) => Err(UserError::new(
+                ErrorCode::CanisterRejectedMessage,
+                format!("ic00 method {} can only be called as a query", method_name),
+            )),
+
 
             // These methods are only valid if they are sent by the controller
             // of the canister. We assume that the canister always wants to
@@ -329,7 +383,15 @@ impl CanisterManager {
             | Ok(Ic00Method::DeleteCanister) |
             Ok(Ic00Method::UpdateSettings)|
             Ok(Ic00Method::InstallCode) |
-            Ok(Ic00Method::SetController) => {
+            Ok(Ic00Method::SetController) |
+            Ok(Ic00Method::TakeCanisterSnapshot) |
+            Ok(Ic00Method::LoadCanisterSnapshot) |
+            Ok(Ic00Method::ListCanisterSnapshots) |
+            Ok(Ic00Method::DeleteCanisterSnapshot) |
+            Ok(Ic00Method::UploadChunk) |
+            Ok(Ic00Method::ClearChunkStore) |
+            Ok(Ic00Method::StoredChunks) |
+            Ok(Ic00Method::InstallChunkedCode) => {
                 match effective_canister_id {
                     Some(canister_id) => {
                         let canister = state.canister_state(&canister_id).ok_or_else(|| UserError::new(
@@ -450,17 +512,22 @@ impl CanisterManager {
         if let Some(freezing_threshold) = settings.freezing_threshold {
             canister.system_state.freeze_threshold = freezing_threshold;
         }
+        if let Some(log_visibility) = settings.log_visibility {
+            canister.system_state.log_visibility = log_visibility;
+        }
     }
 
     /// Tries to apply the requested settings on the canister identified by
     /// `canister_id`.
     pub(crate) fn update_settings(
         &self,
-        sender: PrincipalId,
+        timestamp: Time,
+        origin: CanisterChangeOrigin,
         settings: CanisterSettings,
         canister: &mut CanisterState,
         round_limits: &mut RoundLimits,
     ) -> Result<(), CanisterManagerError> {
+        let sender = origin.origin();
         // Verify controller.
         validate_controller(canister, &sender)?;
         validate_compute_allocation(
@@ -486,6 +553,8 @@ impl CanisterManager {
             .bytes()
             .max(old_usage);
         let old_compute_allocation = canister.scheduler_state.compute_allocation.as_percent();
+        let controllers_changed =
+            validated_settings.controller.is_some() || validated_settings.controllers.is_some();
 
         self.do_update_settings(validated_settings, canister);
 
@@ -520,6 +589,14 @@ impl CanisterManager {
         }
 
         canister.system_state.canister_version += 1;
+        if controllers_changed {
+            let new_controllers = canister.system_state.controllers.iter().copied().collect();
+            canister.system_state.add_canister_change(
+                timestamp,
+                origin,
+                CanisterChangeDetails::controllers_change(new_controllers),
+            );
+        }
 
         Ok(())
     }
@@ -529,7 +606,7 @@ impl CanisterManager {
     /// Returns the auto-generated id the new canister that has been created.
     pub(crate) fn create_canister(
         &self,
-        sender: PrincipalId,
+        origin: CanisterChangeOrigin,
         sender_subnet_id: SubnetId,
         cycles: Cycles,
         settings: CanisterSettings,
@@ -573,7 +650,7 @@ impl CanisterManager {
             Err(err) => (Err(err), cycles),
             Ok(validate_settings) => {
                 let canister_id = match self.create_canister_helper(
-                    sender,
+                    origin,
                     cycles,
                     fee,
                     validate_settings,
@@ -697,7 +774,7 @@ impl CanisterManager {
         execution_refund_error_counter: &IntCounter,
         subnet_size: usize,
     ) -> DtsInstallCodeResult {
-        if let Err(err) = validate_controller(&canister, &context.sender) {
+        if let Err(err) = validate_controller(&canister, &context.sender()) {
             return DtsInstallCodeResult::Finished {
                 canister,
                 message,
@@ -742,7 +819,8 @@ impl CanisterManager {
             subnet_size,
             requested_compute_allocation: context.compute_allocation,
             requested_memory_allocation: context.memory_allocation,
-            sender: context.sender,
+            sender: context.sender(),
+            canister_change_origin: context.origin.clone(),
             canister_id: canister.canister_id(),
         };
 
@@ -770,10 +848,11 @@ impl CanisterManager {
     /// See https://sdk.dfinity.org/docs/interface-spec/index.html#ic-uninstall_code
     pub(crate) fn uninstall_code(
         &self,
+        origin: CanisterChangeOrigin,
         canister_id: CanisterId,
-        sender: PrincipalId,
         state: &mut ReplicatedState,
     ) -> Result<(), CanisterManagerError> {
+        let sender = origin.origin();
         let time = state.time();
         let canister = match state.canister_state_mut(&canister_id) {
             Some(canister) => canister,
@@ -788,6 +867,12 @@ impl CanisterManager {
         }
 
         let rejects = uninstall_canister(&self.log, canister, time);
+        canister.system_state.add_canister_change(
+            time,
+            origin,
+            CanisterChangeDetails::code_uninstall(),
+        );
+        state.delete_canister_snapshots(canister_id);
         crate::util::process_responses(
             rejects,
             state,
@@ -908,6 +993,33 @@ impl CanisterManager {
         Ok(stop_contexts)
     }
 
+    /// Returns the module hash, controllers and the `num_requested_changes`
+    /// most recent entries of the history of the canister.
+    pub(crate) fn get_canister_info(
+        &self,
+        num_requested_changes: Option<u64>,
+        canister: &CanisterState,
+    ) -> CanisterInfoResponse {
+        let canister_history = canister.system_state.get_canister_history();
+        let num_requested_changes =
+            usize::try_from(num_requested_changes.unwrap_or(0)).unwrap_or(usize::MAX);
+        let changes = canister_history
+            .get_changes(num_requested_changes)
+            .cloned()
+            .collect();
+        let module_hash = canister
+            .execution_state
+            .as_ref()
+            .map(|execution_state| execution_state.wasm_binary.binary.module_hash().to_vec());
+        let controllers = canister.controllers().iter().copied().collect();
+        CanisterInfoResponse::new(
+            canister_history.get_total_num_changes(),
+            changes,
+            module_hash,
+            controllers,
+        )
+    }
+
     /// Fetches the current status of the canister.
     pub(crate) fn get_canister_status(
         &self,
@@ -961,12 +1073,13 @@ impl CanisterManager {
     /// the canister is able to run this, otherwise an error is returned.
     pub(crate) fn set_controller(
         &self,
-        sender: PrincipalId,
+        origin: CanisterChangeOrigin,
         canister_id: CanisterId,
         new_controller: PrincipalId,
         state: &mut ReplicatedState,
         round_limits: &mut RoundLimits,
     ) -> Result<(), CanisterManagerError> {
+        let time = state.time();
         let canister = state
             .canister_state_mut(&canister_id)
             .ok_or(CanisterManagerError::CanisterNotFound(canister_id))?;
@@ -974,7 +1087,7 @@ impl CanisterManager {
         let settings = CanisterSettingsBuilder::new()
             .with_controller(new_controller)
             .build();
-        self.update_settings(sender, settings, canister, round_limits)
+        self.update_settings(time, origin, settings, canister, round_limits)
     }
 
     /// Permanently deletes a canister from `ReplicatedState`.
@@ -1018,6 +1131,9 @@ impl CanisterManager {
         // - its state is permanently deleted, and
         // - its cycles are discarded.
 
+        // Delete the canister's snapshots.
+        state.delete_canister_snapshots(canister_id_to_delete);
+
         // Take out the canister from `ReplicatedState`.
         let canister_to_delete = state.take_canister_state(&canister_id_to_delete).unwrap();
         // Leftover cycles in the balance are considered `consumed`.
@@ -1047,7 +1163,7 @@ impl CanisterManager {
     /// Returns the auto-generated id the new canister that has been created.
     pub(crate) fn create_canister_with_cycles(
         &self,
-        sender: PrincipalId,
+        origin: CanisterChangeOrigin,
         cycles_amount: Option<u128>,
         settings: CanisterSettings,
         specified_id: Option<PrincipalId>,
@@ -1056,6 +1172,7 @@ impl CanisterManager {
         max_number_of_canisters: u64,
         round_limits: &mut RoundLimits,
     ) -> Result<CanisterId, CanisterManagerError> {
+        let sender = origin.origin();
         if !provisional_whitelist.contains(&sender) {
             return Err(CanisterManagerError::SenderNotInWhitelist(sender));
         }
@@ -1074,7 +1191,7 @@ impl CanisterManager {
         ) {
             Err(err) => Err(err),
             Ok(validated_settings) => self.create_canister_helper(
-                sender,
+                origin,
                 cycles,
                 Cycles::new(0),
                 validated_settings,
@@ -1122,7 +1239,7 @@ impl CanisterManager {
 
     fn create_canister_helper(
         &self,
-        sender: PrincipalId,
+        origin: CanisterChangeOrigin,
         cycles: Cycles,
         creation_fee: Cycles,
         settings: ValidatedCanisterSettings,
@@ -1131,6 +1248,7 @@ impl CanisterManager {
         round_limits: &mut RoundLimits,
         specified_id: Option<PrincipalId>,
     ) -> Result<CanisterId, CanisterManagerError> {
+        let sender = origin.origin();
         // A value of 0 is equivalent to setting no limit.
         // See documentation of `SubnetRecord` for the semantics of `max_number_of_canisters`.
         if max_number_of_canisters > 0 && state.num_canisters() as u64 >= max_number_of_canisters {
@@ -1163,6 +1281,17 @@ impl CanisterManager {
         let mut new_canister = CanisterState::new(system_state, None, scheduler_state);
 
         self.do_update_settings(settings, &mut new_canister);
+        let controllers = new_canister
+            .system_state
+            .controllers
+            .iter()
+            .copied()
+            .collect();
+        new_canister.system_state.add_canister_change(
+            state.time(),
+            origin,
+            CanisterChangeDetails::canister_creation(controllers),
+        );
         let new_usage = new_canister.memory_usage(self.config.own_subnet_type);
         let new_mem = new_canister
             .system_state
@@ -1216,6 +1345,450 @@ impl CanisterManager {
         Ok(())
     }
 
+    /// Takes a snapshot of the canister's Wasm module, memories, globals and
+    /// certified data and stores it in the replicated state. If
+    /// `replace_snapshot` is given, that snapshot is deleted in favor of the
+    /// new one.
+    ///
+    /// The memory taken by the snapshot is added to the canister's memory
+    /// usage, so that the canister is charged storage fees for it.
+    pub(crate) fn take_canister_snapshot(
+        &self,
+        sender: PrincipalId,
+        canister_id: CanisterId,
+        replace_snapshot: Option<Vec<u8>>,
+        state: &mut ReplicatedState,
+        round_limits: &mut RoundLimits,
+        subnet_size: usize,
+    ) -> Result<CanisterSnapshotResponse, CanisterManagerError> {
+        let time = state.time();
+        let canister = self.validate_canister_exists(state, canister_id)?;
+        validate_controller(canister, &sender)?;
+
+        let replaced_snapshot = match replace_snapshot {
+            Some(snapshot_id) => {
+                let snapshot_id = self.parse_snapshot_id(canister_id, &snapshot_id)?;
+                let snapshot = self.get_snapshot(state, canister_id, snapshot_id)?;
+                Some((snapshot_id, snapshot.size()))
+            }
+            None => {
+                if state.canister_snapshots.list_snapshots(canister_id).len()
+                    >= MAX_SNAPSHOTS_PER_CANISTER
+                {
+                    return Err(CanisterManagerError::CanisterSnapshotLimitExceeded {
+                        canister_id,
+                        limit: MAX_SNAPSHOTS_PER_CANISTER,
+                    });
+                }
+                None
+            }
+        };
+
+        let snapshot = CanisterSnapshot::from_canister(canister, time).ok_or(
+            CanisterManagerError::Hypervisor(canister_id, HypervisorError::WasmModuleNotFound),
+        )?;
+        let snapshot_size = snapshot.size();
+        let replaced_size = replaced_snapshot
+            .map(|(_, size)| size)
+            .unwrap_or_else(|| NumBytes::from(0));
+
+        // Check that the canister can pay for the additional memory.
+        let new_memory_usage =
+            canister.memory_usage(self.config.own_subnet_type) + snapshot_size - replaced_size;
+        self.validate_memory_growth(
+            canister,
+            new_memory_usage,
+            NumBytes::from(snapshot_size.get().saturating_sub(replaced_size.get())),
+            round_limits,
+            subnet_size,
+        )?;
+
+        if let Some((snapshot_id, _)) = replaced_snapshot {
+            state.canister_snapshots.remove(snapshot_id);
+        }
+        let canister = state.canister_state_mut(&canister_id).unwrap();
+        let snapshot_id = SnapshotId::new(canister_id, canister.system_state.next_snapshot_id);
+        canister.system_state.next_snapshot_id += 1;
+        canister.system_state.snapshots_memory_usage =
+            canister.system_state.snapshots_memory_usage + snapshot_size - replaced_size;
+        state
+            .canister_snapshots
+            .push(snapshot_id, Arc::new(snapshot));
+
+        Ok(CanisterSnapshotResponse {
+            id: snapshot_id.to_bytes(),
+            taken_at_timestamp: time.as_nanos_since_unix_epoch(),
+            total_size: snapshot_size.get(),
+        })
+    }
+
+    /// Restores the canister's Wasm module, memories, globals and certified
+    /// data from the given snapshot. The snapshot itself is kept.
+    ///
+    /// The restored module is recorded in the canister history as a code
+    /// deployment in reinstall mode, as both memories are replaced.
+    pub(crate) fn load_canister_snapshot(
+        &self,
+        origin: CanisterChangeOrigin,
+        canister_id: CanisterId,
+        snapshot_id: Vec<u8>,
+        state: &mut ReplicatedState,
+        round_limits: &mut RoundLimits,
+        subnet_size: usize,
+    ) -> Result<(), CanisterManagerError> {
+        let sender = origin.origin();
+        let time = state.time();
+        let canister = self.validate_canister_exists(state, canister_id)?;
+        validate_controller(canister, &sender)?;
+
+        let snapshot_id = self.parse_snapshot_id(canister_id, &snapshot_id)?;
+        let snapshot = Arc::clone(self.get_snapshot(state, canister_id, snapshot_id)?);
+        let execution_snapshot = snapshot.execution_snapshot();
+
+        let fd_factory = self.hypervisor.fd_factory();
+        let canister_root = match canister.execution_state.as_ref() {
+            Some(execution_state) => execution_state.canister_root.clone(),
+            None => canister_layout(&PathBuf::from("NOT_USED"), &canister_id).raw_path(),
+        };
+        let new_execution_state = ExecutionState::new(
+            canister_root,
+            WasmBinary::new(execution_snapshot.wasm_binary.clone()),
+            execution_snapshot.exports.clone(),
+            Memory::new(
+                PageMap::new_detached_copy(
+                    &execution_snapshot.wasm_memory.page_map,
+                    Arc::clone(&fd_factory),
+                ),
+                execution_snapshot.wasm_memory.size,
+            ),
+            Memory::new(
+                PageMap::new_detached_copy(
+                    &execution_snapshot.stable_memory.page_map,
+                    Arc::clone(&fd_factory),
+                ),
+                execution_snapshot.stable_memory.size,
+            ),
+            execution_snapshot.exported_globals.clone(),
+            execution_snapshot.metadata.clone(),
+        );
+
+        // Check that the canister can pay for the memory of the restored state.
+        let old_execution_memory = canister
+            .execution_state
+            .as_ref()
+            .map_or(NumBytes::from(0), |es| es.memory_usage());
+        let new_execution_memory = new_execution_state.memory_usage();
+        let new_memory_usage = canister.memory_usage(self.config.own_subnet_type)
+            - old_execution_memory
+            + new_execution_memory;
+        self.validate_memory_growth(
+            canister,
+            new_memory_usage,
+            NumBytes::from(
+                new_execution_memory
+                    .get()
+                    .saturating_sub(old_execution_memory.get()),
+            ),
+            round_limits,
+            subnet_size,
+        )?;
+
+        let canister = state.canister_state_mut(&canister_id).unwrap();
+        canister.execution_state = Some(new_execution_state);
+        canister.system_state.certified_data = snapshot.certified_data().clone();
+        canister.system_state.canister_version += 1;
+        canister.system_state.add_canister_change(
+            time,
+            origin,
+            CanisterChangeDetails::code_deployment(
+                CanisterInstallMode::Reinstall,
+                execution_snapshot.wasm_binary.module_hash(),
+            ),
+        );
+        Ok(())
+    }
+
+    /// Returns the snapshots of the canister.
+    pub(crate) fn list_canister_snapshots(
+        &self,
+        sender: PrincipalId,
+        canister_id: CanisterId,
+        state: &ReplicatedState,
+    ) -> Result<Vec<CanisterSnapshotResponse>, CanisterManagerError> {
+        let canister = self.validate_canister_exists(state, canister_id)?;
+        validate_controller(canister, &sender)?;
+
+        Ok(state
+            .canister_snapshots
+            .list_snapshots(canister_id)
+            .into_iter()
+            .map(|(snapshot_id, snapshot)| CanisterSnapshotResponse {
+                id: snapshot_id.to_bytes(),
+                taken_at_timestamp: snapshot.taken_at_timestamp().as_nanos_since_unix_epoch(),
+                total_size: snapshot.size().get(),
+            })
+            .collect())
+    }
+
+    /// Deletes the given snapshot of the canister and releases its memory.
+    pub(crate) fn delete_canister_snapshot(
+        &self,
+        sender: PrincipalId,
+        canister_id: CanisterId,
+        snapshot_id: Vec<u8>,
+        state: &mut ReplicatedState,
+        round_limits: &mut RoundLimits,
+    ) -> Result<(), CanisterManagerError> {
+        let canister = self.validate_canister_exists(state, canister_id)?;
+        validate_controller(canister, &sender)?;
+
+        let snapshot_id = self.parse_snapshot_id(canister_id, &snapshot_id)?;
+        let snapshot_size = self.get_snapshot(state, canister_id, snapshot_id)?.size();
+
+        state.canister_snapshots.remove(snapshot_id);
+        let canister = state.canister_state_mut(&canister_id).unwrap();
+        canister.system_state.snapshots_memory_usage = NumBytes::from(
+            canister
+                .system_state
+                .snapshots_memory_usage
+                .get()
+                .saturating_sub(snapshot_size.get()),
+        );
+        if canister.memory_allocation() == MemoryAllocation::BestEffort {
+            round_limits
+                .subnet_available_memory
+                .increment(snapshot_size, NumBytes::from(0));
+        }
+        Ok(())
+    }
+
+    /// Stores the chunk in the canister's Wasm chunk store and returns its
+    /// hash. The memory taken by the chunk is charged to the canister.
+    pub(crate) fn upload_chunk(
+        &self,
+        sender: PrincipalId,
+        canister_id: CanisterId,
+        chunk: Vec<u8>,
+        state: &mut ReplicatedState,
+        round_limits: &mut RoundLimits,
+        subnet_size: usize,
+    ) -> Result<ChunkHash, CanisterManagerError> {
+        let canister = self.validate_canister_exists(state, canister_id)?;
+        validate_controller(canister, &sender)?;
+
+        let store = &canister.system_state.wasm_chunk_store;
+        store
+            .can_insert_chunk(&chunk)
+            .map_err(|message| CanisterManagerError::WasmChunkStoreError { message })?;
+
+        // Uploading a chunk that is already stored takes no additional memory.
+        if !store.contains(&chunk_hash(&chunk)) {
+            let additional_memory = NumBytes::from(chunk.len() as u64);
+            let new_memory_usage =
+                canister.memory_usage(self.config.own_subnet_type) + additional_memory;
+            self.validate_memory_growth(
+                canister,
+                new_memory_usage,
+                additional_memory,
+                round_limits,
+                subnet_size,
+            )?;
+        }
+
+        let canister = state.canister_state_mut(&canister_id).unwrap();
+        let hash = canister.system_state.wasm_chunk_store.insert_chunk(chunk);
+        Ok(ChunkHash {
+            hash: hash.to_vec(),
+        })
+    }
+
+    /// Removes all chunks from the canister's Wasm chunk store.
+    pub(crate) fn clear_chunk_store(
+        &self,
+        sender: PrincipalId,
+        canister_id: CanisterId,
+        state: &mut ReplicatedState,
+        round_limits: &mut RoundLimits,
+    ) -> Result<(), CanisterManagerError> {
+        let canister = self.validate_canister_exists(state, canister_id)?;
+        validate_controller(canister, &sender)?;
+
+        let canister = state.canister_state_mut(&canister_id).unwrap();
+        let freed_memory = canister.system_state.wasm_chunk_store.memory_usage();
+        canister.system_state.wasm_chunk_store.clear();
+        if canister.memory_allocation() == MemoryAllocation::BestEffort {
+            round_limits
+                .subnet_available_memory
+                .increment(freed_memory, NumBytes::from(0));
+        }
+        Ok(())
+    }
+
+    /// Returns the hashes of the chunks in the canister's Wasm chunk store.
+    pub(crate) fn stored_chunks(
+        &self,
+        sender: PrincipalId,
+        canister_id: CanisterId,
+        state: &ReplicatedState,
+    ) -> Result<Vec<ChunkHash>, CanisterManagerError> {
+        let canister = self.validate_canister_exists(state, canister_id)?;
+        validate_controller(canister, &sender)?;
+
+        Ok(canister
+            .system_state
+            .wasm_chunk_store
+            .keys()
+            .map(|hash| ChunkHash {
+                hash: hash.to_vec(),
+            })
+            .collect())
+    }
+
+    /// Assembles the Wasm module of an `install_chunked_code` message from the
+    /// chunk store of the store canister and returns the context to install it
+    /// with. Fails if a chunk is missing or if the hash of the assembled module
+    /// does not match the expected one.
+    pub(crate) fn install_chunked_code_context(
+        &self,
+        origin: CanisterChangeOrigin,
+        args: InstallChunkedCodeArgs,
+        state: &ReplicatedState,
+    ) -> Result<InstallCodeContext, UserError> {
+        let store_canister_id = args.store_canister_id();
+        let store_canister = self.validate_canister_exists(state, store_canister_id)?;
+        validate_controller(store_canister, &origin.origin())?;
+
+        let store = &store_canister.system_state.wasm_chunk_store;
+        let mut wasm_module = Vec::new();
+        for ChunkHash { hash } in &args.chunk_hashes_list {
+            let chunk = <[u8; 32]>::try_from(hash.as_slice())
+                .ok()
+                .and_then(|hash| store.get_chunk(&hash))
+                .ok_or_else(|| CanisterManagerError::WasmChunkStoreError {
+                    message: format!(
+                        "Chunk {} not found in the chunk store of canister {}",
+                        hex::encode(hash),
+                        store_canister_id
+                    ),
+                })?;
+            wasm_module.extend_from_slice(chunk);
+        }
+
+        let install_args = InstallCodeArgs::new(
+            args.mode,
+            args.target_canister_id(),
+            wasm_module,
+            args.arg,
+            None,
+            None,
+            None,
+        );
+        let install_context = InstallCodeContext::try_from((origin, install_args))?;
+        let module_hash = install_context.wasm_module.module_hash();
+        if module_hash[..] != args.wasm_module_hash[..] {
+            return Err(CanisterManagerError::WasmChunkStoreError {
+                message: format!(
+                    "Hash {} of the assembled Wasm module does not match the expected hash {}",
+                    hex::encode(module_hash),
+                    hex::encode(&args.wasm_module_hash)
+                ),
+            }
+            .into());
+        }
+        Ok(install_context)
+    }
+
+    fn parse_snapshot_id(
+        &self,
+        canister_id: CanisterId,
+        snapshot_id: &[u8],
+    ) -> Result<SnapshotId, CanisterManagerError> {
+        SnapshotId::try_from(snapshot_id).map_err(|_| {
+            CanisterManagerError::CanisterSnapshotNotFound {
+                canister_id,
+                snapshot_id: snapshot_id.to_vec(),
+            }
+        })
+    }
+
+    /// Returns the snapshot with the given id if it exists and belongs to the
+    /// given canister.
+    fn get_snapshot<'a>(
+        &self,
+        state: &'a ReplicatedState,
+        canister_id: CanisterId,
+        snapshot_id: SnapshotId,
+    ) -> Result<&'a Arc<CanisterSnapshot>, CanisterManagerError> {
+        if snapshot_id.canister_id() != canister_id {
+            return Err(CanisterManagerError::CanisterSnapshotNotFound {
+                canister_id,
+                snapshot_id: snapshot_id.to_bytes(),
+            });
+        }
+        state.canister_snapshots.get(snapshot_id).ok_or_else(|| {
+            CanisterManagerError::CanisterSnapshotNotFound {
+                canister_id,
+                snapshot_id: snapshot_id.to_bytes(),
+            }
+        })
+    }
+
+    /// Validates that the canister can grow to `new_memory_usage` bytes of
+    /// memory as a result of an operation that needs `additional_memory` new
+    /// bytes, and reserves the memory in `round_limits`.
+    fn validate_memory_growth(
+        &self,
+        canister: &CanisterState,
+        new_memory_usage: NumBytes,
+        additional_memory: NumBytes,
+        round_limits: &mut RoundLimits,
+        subnet_size: usize,
+    ) -> Result<(), CanisterManagerError> {
+        let threshold = self.cycles_account_manager.freeze_threshold_cycles(
+            canister.system_state.freeze_threshold,
+            canister.memory_allocation(),
+            new_memory_usage,
+            canister.scheduler_state.compute_allocation,
+            subnet_size,
+        );
+        if canister.system_state.balance() < threshold {
+            return Err(CanisterManagerError::InsufficientCyclesForMemoryGrowth {
+                canister_id: canister.canister_id(),
+                available: canister.system_state.balance(),
+                threshold,
+            });
+        }
+
+        match canister.memory_allocation() {
+            MemoryAllocation::Reserved(bytes) => {
+                if new_memory_usage > bytes {
+                    return Err(CanisterManagerError::NotEnoughMemoryAllocationGiven {
+                        canister_id: canister.canister_id(),
+                        memory_allocation_given: canister.memory_allocation(),
+                        memory_usage_needed: new_memory_usage,
+                    });
+                }
+            }
+            MemoryAllocation::BestEffort => {
+                round_limits
+                    .subnet_available_memory
+                    .try_decrement(additional_memory, NumBytes::from(0))
+                    .map_err(
+                        |_| CanisterManagerError::SubnetMemoryCapacityOverSubscribed {
+                            requested: additional_memory,
+                            available: NumBytes::from(
+                                round_limits
+                                    .subnet_available_memory
+                                    .get_total_memory()
+                                    .max(0) as u64,
+                            ),
+                        },
+                    )?;
+            }
+        }
+        Ok(())
+    }
+
     fn validate_canister_is_stopped(
         &self,
         canister: &CanisterState,
@@ -1311,6 +1884,22 @@ pub(crate) enum CanisterManagerError {
     CanisterNotHostedBySubnet {
         message: String,
     },
+    CanisterSnapshotNotFound {
+        canister_id: CanisterId,
+        snapshot_id: Vec<u8>,
+    },
+    CanisterSnapshotLimitExceeded {
+        canister_id: CanisterId,
+        limit: usize,
+    },
+    InsufficientCyclesForMemoryGrowth {
+        canister_id: CanisterId,
+        available: Cycles,
+        threshold: Cycles,
+    },
+    WasmChunkStoreError {
+        message: String,
+    },
 }
 
 impl From<CanisterManagerError> for UserError {
@@ -1467,6 +2056,39 @@ impl From<CanisterManagerError> for UserError {
                     format!("Unsuccessful validation of specified ID: {}", message),
                 )
             }
+            CanisterSnapshotNotFound { canister_id, snapshot_id } => {
+                Self::new(
+                    ErrorCode::CanisterSnapshotNotFound,
+                    format!(
+                        "Could not find the snapshot ID {} for canister {}.",
+                        hex::encode(snapshot_id), canister_id,
+                    ),
+                )
+            }
+            CanisterSnapshotLimitExceeded { canister_id, limit } => {
+                Self::new(
+                    ErrorCode::CanisterContractViolation,
+                    format!(
+                        "Canister {} has reached the maximum number of {} snapshots. Delete a snapshot or replace it when taking a new one.",
+                        canister_id, limit,
+                    ),
+                )
+            }
+            InsufficientCyclesForMemoryGrowth { canister_id, available, threshold } => {
+                Self::new(
+                    ErrorCode::CanisterOutOfCycles,
+                    format!(
+                        "Canister {} does not have enough cycles to pay for the additional memory: available {}, required at least {} to remain above the freezing threshold.",
+                        canister_id, available, threshold,
+                    ),
+                )
+            }
+            WasmChunkStoreError { message } => {
+                Self::new(
+                    ErrorCode::CanisterContractViolation,
+                    format!("Error from Wasm chunk store: {}", message),
+                )
+            }
         }
     }
 }
@@ -1501,6 +2123,9 @@ pub fn uninstall_canister(
     // Drop its certified data.
     canister.system_state.certified_data = Vec::new();
 
+    // Drop the chunks uploaded for installing code.
+    canister.system_state.wasm_chunk_store.clear();
+
     // Deactivate global timer.
     canister.system_state.global_timer = CanisterTimer::Inactive;
     // Increment canister version.
@@ -1537,11 +2162,12 @@ pub fn uninstall_canister(
                         },
                     }));
                 }
-                CallOrigin::CanisterUpdate(caller_canister_id, callback_id) => {
+                CallOrigin::CanisterUpdate(caller_canister_id, callback_id, deadline) => {
                     rejects.push(Response::Canister(CanisterResponse {
                         originator: *caller_canister_id,
                         respondent: canister_id,
                         originator_reply_callback: *callback_id,
+                        deadline: *deadline,
                         refund: call_context.available_cycles(),
                         response_payload: Payload::Reject(RejectContext {
                             code: RejectCode::CanisterReject,
@@ -1572,6 +2198,7 @@ struct ValidatedCanisterSettings {
     pub compute_allocation: Option<ComputeAllocation>,
     pub memory_allocation: Option<MemoryAllocation>,
     pub freezing_threshold: Option<NumSeconds>,
+    pub log_visibility: Option<LogVisibility>,
 }
 
 impl TryFrom<(CanisterSettings, usize)> for ValidatedCanisterSettings {
@@ -1607,6 +2234,7 @@ impl TryFrom<(CanisterSettings, usize)> for ValidatedCanisterSettings {
             compute_allocation: settings.compute_allocation(),
             memory_allocation: settings.memory_allocation(),
             freezing_threshold: settings.freezing_threshold(),
+            log_visibility: settings.log_visibility(),
         })
     }
 }
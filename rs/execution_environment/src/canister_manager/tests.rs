@@ -20,8 +20,9 @@ use ic_constants::SMALL_APP_SUBNET_MAX_SIZE;
 use ic_cycles_account_manager::CyclesAccountManager;
 use ic_error_types::{ErrorCode, UserError};
 use ic_ic00_types::{
-    CanisterIdRecord, CanisterInstallMode, CanisterSettingsArgs, CanisterStatusType,
-    CreateCanisterArgs, EmptyBlob, InstallCodeArgs, Method, Payload, UpdateSettingsArgs,
+    CanisterChangeOrigin, CanisterIdRecord, CanisterInstallMode, CanisterSettingsArgs,
+    CanisterStatusType, CreateCanisterArgs, EmptyBlob, InstallCodeArgs, Method, Payload,
+    UpdateSettingsArgs,
 };
 use ic_interfaces::{
     execution_environment::{
@@ -63,7 +64,7 @@ use ic_types::{
     messages::{CallbackId, StopCanisterContext},
     nominal_cycles::NominalCycles,
     CanisterId, CanisterTimer, ComputeAllocation, Cycles, MemoryAllocation, NumBytes,
-    NumInstructions, QueryAllocation, SubnetId, UserId,
+    NumInstructions, QueryAllocation, SubnetId, UserId, MAX_MEMORY_ALLOCATION,
 };
 use ic_wasm_types::{CanisterModule, WasmValidationError};
 use lazy_static::lazy_static;
@@ -112,8 +113,8 @@ pub struct InstallCodeContextBuilder {
 }
 
 impl InstallCodeContextBuilder {
-    pub fn sender(mut self, sender: PrincipalId) -> Self {
-        self.ctx.sender = sender;
+    pub fn origin(mut self, origin: CanisterChangeOrigin) -> Self {
+        self.ctx.origin = origin;
         self
     }
 
@@ -162,7 +163,7 @@ impl Default for InstallCodeContextBuilder {
     fn default() -> Self {
         Self {
             ctx: InstallCodeContext {
-                sender: PrincipalId::new_user_test_id(0),
+                origin: CanisterChangeOrigin::from_user(PrincipalId::new_user_test_id(0)),
                 canister_id: canister_test_id(0),
                 wasm_module: CanisterModule::new(wat::parse_str(EMPTY_WAT).unwrap()),
                 arg: vec![],
@@ -253,6 +254,7 @@ fn canister_manager_config(
         100,
         rate_limiting_of_instructions,
         100,
+        FlagStatus::Disabled,
     )
 }
 
@@ -307,7 +309,7 @@ fn install_code(
         None,
     );
     let ingress = IngressBuilder::new()
-        .source(UserId::from(context.sender))
+        .source(UserId::from(context.sender()))
         .receiver(CanisterId::ic_00())
         .method_name(Method::InstallCode)
         .method_payload(args.encode())
@@ -351,7 +353,7 @@ fn install_canister_makes_subnet_oversubscribed() {
         };
         let canister_id1 = canister_manager
             .create_canister(
-                sender,
+                CanisterChangeOrigin::from_canister(sender, None),
                 sender_subnet_id,
                 *INITIAL_CYCLES,
                 CanisterSettings::default(),
@@ -364,7 +366,7 @@ fn install_canister_makes_subnet_oversubscribed() {
             .unwrap();
         let canister_id2 = canister_manager
             .create_canister(
-                sender,
+                CanisterChangeOrigin::from_canister(sender, None),
                 sender_subnet_id,
                 *INITIAL_CYCLES,
                 CanisterSettings::default(),
@@ -377,7 +379,7 @@ fn install_canister_makes_subnet_oversubscribed() {
             .unwrap();
         let canister_id3 = canister_manager
             .create_canister(
-                sender,
+                CanisterChangeOrigin::from_canister(sender, None),
                 sender_subnet_id,
                 *INITIAL_CYCLES,
                 CanisterSettings::default(),
@@ -392,7 +394,7 @@ fn install_canister_makes_subnet_oversubscribed() {
         let res = install_code(
             &canister_manager,
             InstallCodeContextBuilder::default()
-                .sender(sender)
+                .origin(CanisterChangeOrigin::from_canister(sender, None))
                 .canister_id(canister_id1)
                 .compute_allocation(ComputeAllocation::try_from(50).unwrap())
                 .build(),
@@ -405,7 +407,7 @@ fn install_canister_makes_subnet_oversubscribed() {
         let res = install_code(
             &canister_manager,
             InstallCodeContextBuilder::default()
-                .sender(sender)
+                .origin(CanisterChangeOrigin::from_canister(sender, None))
                 .canister_id(canister_id2)
                 .compute_allocation(ComputeAllocation::try_from(25).unwrap())
                 .build(),
@@ -419,7 +421,7 @@ fn install_canister_makes_subnet_oversubscribed() {
         let (num_instructions, res, canister) = install_code(
             &canister_manager,
             InstallCodeContextBuilder::default()
-                .sender(sender)
+                .origin(CanisterChangeOrigin::from_canister(sender, None))
                 .canister_id(canister_id3)
                 .wasm_module(
                     ic_test_utilities::universal_canister::UNIVERSAL_CANISTER_WASM.to_vec(),
@@ -489,7 +491,7 @@ fn upgrade_canister_with_no_wasm_fails() {
         };
         let canister_id = canister_manager
             .create_canister(
-                sender,
+                CanisterChangeOrigin::from_canister(sender, None),
                 sender_subnet_id,
                 *INITIAL_CYCLES,
                 CanisterSettings::default(),
@@ -504,7 +506,7 @@ fn upgrade_canister_with_no_wasm_fails() {
         let res = install_code(
             &canister_manager,
             InstallCodeContextBuilder::default()
-                .sender(sender)
+                .origin(CanisterChangeOrigin::from_canister(sender, None))
                 .mode(CanisterInstallMode::Upgrade)
                 .wasm_module(
                     ic_test_utilities::universal_canister::UNIVERSAL_CANISTER_WASM.to_vec(),
@@ -540,7 +542,7 @@ fn can_update_compute_allocation_during_upgrade() {
         };
         let canister_id1 = canister_manager
             .create_canister(
-                sender,
+                CanisterChangeOrigin::from_canister(sender, None),
                 sender_subnet_id,
                 Cycles::new(2_000_000_000_000_000),
                 CanisterSettings::default(),
@@ -556,7 +558,7 @@ fn can_update_compute_allocation_during_upgrade() {
         let res = install_code(
             &canister_manager,
             InstallCodeContextBuilder::default()
-                .sender(sender)
+                .origin(CanisterChangeOrigin::from_canister(sender, None))
                 .canister_id(canister_id1)
                 .compute_allocation(ComputeAllocation::try_from(60).unwrap())
                 .build(),
@@ -578,7 +580,7 @@ fn can_update_compute_allocation_during_upgrade() {
         let res = install_code(
             &canister_manager,
             InstallCodeContextBuilder::default()
-                .sender(sender)
+                .origin(CanisterChangeOrigin::from_canister(sender, None))
                 .canister_id(canister_id1)
                 .compute_allocation(ComputeAllocation::try_from(80).unwrap())
                 .mode(CanisterInstallMode::Upgrade)
@@ -611,7 +613,7 @@ fn upgrading_canister_makes_subnet_oversubscribed() {
         let initial_cycles = Cycles::new(30_000_000_000_000);
         let canister_id1 = canister_manager
             .create_canister(
-                sender,
+                CanisterChangeOrigin::from_canister(sender, None),
                 sender_subnet_id,
                 initial_cycles,
                 CanisterSettings::default(),
@@ -624,7 +626,7 @@ fn upgrading_canister_makes_subnet_oversubscribed() {
             .unwrap();
         let canister_id2 = canister_manager
             .create_canister(
-                sender,
+                CanisterChangeOrigin::from_canister(sender, None),
                 sender_subnet_id,
                 initial_cycles,
                 CanisterSettings::default(),
@@ -637,7 +639,7 @@ fn upgrading_canister_makes_subnet_oversubscribed() {
             .unwrap();
         let canister_id3 = canister_manager
             .create_canister(
-                sender,
+                CanisterChangeOrigin::from_canister(sender, None),
                 sender_subnet_id,
                 initial_cycles,
                 CanisterSettings::default(),
@@ -652,7 +654,7 @@ fn upgrading_canister_makes_subnet_oversubscribed() {
         let res = install_code(
             &canister_manager,
             InstallCodeContextBuilder::default()
-                .sender(sender)
+                .origin(CanisterChangeOrigin::from_canister(sender, None))
                 .canister_id(canister_id1)
                 .compute_allocation(ComputeAllocation::try_from(50).unwrap())
                 .build(),
@@ -665,7 +667,7 @@ fn upgrading_canister_makes_subnet_oversubscribed() {
         let res = install_code(
             &canister_manager,
             InstallCodeContextBuilder::default()
-                .sender(sender)
+                .origin(CanisterChangeOrigin::from_canister(sender, None))
                 .canister_id(canister_id2)
                 .compute_allocation(ComputeAllocation::try_from(25).unwrap())
                 .build(),
@@ -678,7 +680,7 @@ fn upgrading_canister_makes_subnet_oversubscribed() {
         let res = install_code(
             &canister_manager,
             InstallCodeContextBuilder::default()
-                .sender(sender)
+                .origin(CanisterChangeOrigin::from_canister(sender, None))
                 .canister_id(canister_id3)
                 .compute_allocation(ComputeAllocation::try_from(20).unwrap())
                 .build(),
@@ -692,7 +694,7 @@ fn upgrading_canister_makes_subnet_oversubscribed() {
         let res = install_code(
             &canister_manager,
             InstallCodeContextBuilder::default()
-                .sender(sender)
+                .origin(CanisterChangeOrigin::from_canister(sender, None))
                 .canister_id(canister_id3)
                 .wasm_module(
                     ic_test_utilities::universal_canister::UNIVERSAL_CANISTER_WASM.to_vec(),
@@ -813,7 +815,7 @@ fn can_update_memory_allocation_during_upgrade() {
         };
         let canister_id = canister_manager
             .create_canister(
-                sender,
+                CanisterChangeOrigin::from_canister(sender, None),
                 sender_subnet_id,
                 *INITIAL_CYCLES,
                 CanisterSettings::default(),
@@ -830,7 +832,7 @@ fn can_update_memory_allocation_during_upgrade() {
         let res = install_code(
             &canister_manager,
             InstallCodeContextBuilder::default()
-                .sender(sender)
+                .origin(CanisterChangeOrigin::from_canister(sender, None))
                 .canister_id(canister_id)
                 .memory_allocation(initial_memory_allocation)
                 .build(),
@@ -852,7 +854,7 @@ fn can_update_memory_allocation_during_upgrade() {
         let res = install_code(
             &canister_manager,
             InstallCodeContextBuilder::default()
-                .sender(sender)
+                .origin(CanisterChangeOrigin::from_canister(sender, None))
                 .canister_id(canister_id)
                 .memory_allocation(final_memory_allocation)
                 .mode(CanisterInstallMode::Upgrade)
@@ -910,7 +912,7 @@ fn install_code_preserves_messages() {
         let res = install_code(
             &canister_manager,
             InstallCodeContextBuilder::default()
-                .sender(sender)
+                .origin(CanisterChangeOrigin::from_canister(sender, None))
                 .canister_id(canister_test_id(0))
                 .wasm_module(
                     ic_test_utilities::universal_canister::UNIVERSAL_CANISTER_WASM.to_vec(),
@@ -949,7 +951,7 @@ fn can_create_canister() {
         assert_eq!(
             canister_manager
                 .create_canister(
-                    canister,
+                    CanisterChangeOrigin::from_canister(canister, None),
                     sender_subnet_id,
                     *INITIAL_CYCLES,
                     CanisterSettings::default(),
@@ -965,7 +967,7 @@ fn can_create_canister() {
         assert_eq!(
             canister_manager
                 .create_canister(
-                    canister,
+                    CanisterChangeOrigin::from_canister(canister, None),
                     sender_subnet_id,
                     *INITIAL_CYCLES,
                     CanisterSettings::default(),
@@ -996,7 +998,7 @@ fn create_canister_fails_if_not_enough_cycles_are_sent_with_the_request() {
 
         assert_eq!(
             canister_manager.create_canister(
-                canister,
+                CanisterChangeOrigin::from_canister(canister, None),
                 sender_subnet_id,
                 Cycles::new(100),
                 CanisterSettings::default(),
@@ -1033,7 +1035,7 @@ fn can_create_canister_with_extra_cycles() {
         assert_eq!(
             canister_manager
                 .create_canister(
-                    canister,
+                    CanisterChangeOrigin::from_canister(canister, None),
                     sender_subnet_id,
                     Cycles::from(cycles),
                     CanisterSettings::default(),
@@ -1063,7 +1065,7 @@ fn cannot_install_non_empty_canister() {
         };
         let canister_id = canister_manager
             .create_canister(
-                sender,
+                CanisterChangeOrigin::from_canister(sender, None),
                 sender_subnet_id,
                 *INITIAL_CYCLES,
                 CanisterSettings::default(),
@@ -1079,7 +1081,7 @@ fn cannot_install_non_empty_canister() {
         let res = install_code(
             &canister_manager,
             InstallCodeContextBuilder::default()
-                .sender(sender)
+                .origin(CanisterChangeOrigin::from_canister(sender, None))
                 .canister_id(canister_id)
                 .build(),
             &mut state,
@@ -1093,7 +1095,7 @@ fn cannot_install_non_empty_canister() {
         let res = install_code(
             &canister_manager,
             InstallCodeContextBuilder::default()
-                .sender(sender)
+                .origin(CanisterChangeOrigin::from_canister(sender, None))
                 .canister_id(canister_id)
                 .wasm_module(
                     ic_test_utilities::universal_canister::UNIVERSAL_CANISTER_WASM.to_vec(),
@@ -1128,7 +1130,7 @@ fn install_code_with_wrong_controller_fails() {
         // Create a canister with canister_test_id 1 as controller.
         let canister_id = canister_manager
             .create_canister(
-                canister_test_id(1).get(),
+                CanisterChangeOrigin::from_canister(canister_test_id(1).get(), None),
                 subnet_test_id(1),
                 *INITIAL_CYCLES,
                 CanisterSettings::default(),
@@ -1145,7 +1147,10 @@ fn install_code_with_wrong_controller_fails() {
             let res = install_code(
                 &canister_manager,
                 InstallCodeContextBuilder::default()
-                    .sender(canister_test_id(2).get())
+                    .origin(CanisterChangeOrigin::from_canister(
+                        canister_test_id(2).get(),
+                        None,
+                    ))
                     .canister_id(canister_id)
                     .wasm_module(
                         ic_test_utilities::universal_canister::UNIVERSAL_CANISTER_WASM.to_vec(),
@@ -1193,7 +1198,7 @@ fn create_canister_sets_correct_allocations() {
         };
         let canister_id = canister_manager
             .create_canister(
-                canister_test_id(1).get(),
+                CanisterChangeOrigin::from_canister(canister_test_id(1).get(), None),
                 subnet_test_id(1),
                 *INITIAL_CYCLES,
                 settings,
@@ -1222,7 +1227,7 @@ fn create_canister_updates_consumed_cycles_metric_correctly() {
         };
         let canister_id = canister_manager
             .create_canister(
-                canister_test_id(1).get(),
+                CanisterChangeOrigin::from_canister(canister_test_id(1).get(), None),
                 subnet_test_id(1),
                 *INITIAL_CYCLES,
                 CanisterSettings::default(),
@@ -1263,7 +1268,7 @@ fn provisional_create_canister_has_no_creation_fee() {
         };
         let canister_id = canister_manager
             .create_canister_with_cycles(
-                canister_test_id(1).get(),
+                CanisterChangeOrigin::from_canister(canister_test_id(1).get(), None),
                 Some(INITIAL_CYCLES.get()),
                 CanisterSettings::default(),
                 None,
@@ -1299,7 +1304,7 @@ fn reinstall_on_empty_canister_succeeds() {
         let sender = canister_test_id(42).get();
         let canister_id = canister_manager
             .create_canister(
-                sender,
+                CanisterChangeOrigin::from_canister(sender, None),
                 subnet_test_id(1),
                 *INITIAL_CYCLES,
                 CanisterSettings::default(),
@@ -1315,7 +1320,7 @@ fn reinstall_on_empty_canister_succeeds() {
         let res = install_code(
             &canister_manager,
             InstallCodeContextBuilder::default()
-                .sender(sender)
+                .origin(CanisterChangeOrigin::from_canister(sender, None))
                 .canister_id(canister_id)
                 .mode(CanisterInstallMode::Reinstall)
                 .build(),
@@ -1407,7 +1412,7 @@ fn install_puts_canister_back_after_invalid_wasm() {
         let sender_subnet_id = subnet_test_id(1);
         let canister_id = canister_manager
             .create_canister(
-                sender,
+                CanisterChangeOrigin::from_canister(sender, None),
                 sender_subnet_id,
                 *INITIAL_CYCLES,
                 CanisterSettings::default(),
@@ -1423,7 +1428,7 @@ fn install_puts_canister_back_after_invalid_wasm() {
         let res = install_code(
             &canister_manager,
             InstallCodeContextBuilder::default()
-                .sender(sender)
+                .origin(CanisterChangeOrigin::from_canister(sender, None))
                 .canister_id(canister_id)
                 .wasm_module(wasm)
                 .build(),
@@ -1463,7 +1468,7 @@ fn reinstall_clears_stable_memory() {
         let sender_subnet_id = subnet_test_id(1);
         let canister_id = canister_manager
             .create_canister(
-                sender,
+                CanisterChangeOrigin::from_canister(sender, None),
                 sender_subnet_id,
                 *INITIAL_CYCLES,
                 CanisterSettings::default(),
@@ -1478,7 +1483,7 @@ fn reinstall_clears_stable_memory() {
         let res = install_code(
             &canister_manager,
             InstallCodeContextBuilder::default()
-                .sender(sender)
+                .origin(CanisterChangeOrigin::from_canister(sender, None))
                 .canister_id(canister_id)
                 .build(),
             &mut state,
@@ -1520,7 +1525,7 @@ fn reinstall_clears_stable_memory() {
         let res = install_code(
             &canister_manager,
             InstallCodeContextBuilder::default()
-                .sender(sender)
+                .origin(CanisterChangeOrigin::from_canister(sender, None))
                 .canister_id(canister_id)
                 .mode(CanisterInstallMode::Reinstall)
                 .build(),
@@ -1557,7 +1562,7 @@ fn stop_a_running_canister() {
         let sender_subnet_id = subnet_test_id(1);
         let canister_id = canister_manager
             .create_canister(
-                sender.get(),
+                CanisterChangeOrigin::from_canister(sender.get(), None),
                 sender_subnet_id,
                 *INITIAL_CYCLES,
                 CanisterSettings::default(),
@@ -1690,7 +1695,7 @@ fn stop_a_canister_with_incorrect_controller() {
         let sender_subnet_id = subnet_test_id(1);
         let canister_id = canister_manager
             .create_canister(
-                sender,
+                CanisterChangeOrigin::from_canister(sender, None),
                 sender_subnet_id,
                 *INITIAL_CYCLES,
                 CanisterSettings::default(),
@@ -1764,7 +1769,7 @@ fn start_a_canister_with_incorrect_controller() {
         let sender_subnet_id = subnet_test_id(1);
         let canister_id = canister_manager
             .create_canister(
-                sender,
+                CanisterChangeOrigin::from_canister(sender, None),
                 sender_subnet_id,
                 *INITIAL_CYCLES,
                 CanisterSettings::default(),
@@ -1803,7 +1808,7 @@ fn starting_an_already_running_canister_keeps_it_running() {
         let sender_subnet_id = subnet_test_id(1);
         let canister_id = canister_manager
             .create_canister(
-                sender,
+                CanisterChangeOrigin::from_canister(sender, None),
                 sender_subnet_id,
                 *INITIAL_CYCLES,
                 CanisterSettings::default(),
@@ -1911,7 +1916,7 @@ fn get_canister_status_with_incorrect_controller() {
         let sender_subnet_id = subnet_test_id(1);
         let canister_id = canister_manager
             .create_canister(
-                sender,
+                CanisterChangeOrigin::from_canister(sender, None),
                 sender_subnet_id,
                 *INITIAL_CYCLES,
                 CanisterSettings::default(),
@@ -1950,7 +1955,7 @@ fn get_canister_status_of_running_canister() {
         let sender_subnet_id = subnet_test_id(1);
         let canister_id = canister_manager
             .create_canister(
-                sender,
+                CanisterChangeOrigin::from_canister(sender, None),
                 sender_subnet_id,
                 *INITIAL_CYCLES,
                 CanisterSettings::default(),
@@ -1984,7 +1989,7 @@ fn get_canister_status_of_self() {
         let sender_subnet_id = subnet_test_id(1);
         let canister_id = canister_manager
             .create_canister(
-                sender,
+                CanisterChangeOrigin::from_canister(sender, None),
                 sender_subnet_id,
                 *INITIAL_CYCLES,
                 CanisterSettings::default(),
@@ -2060,7 +2065,7 @@ fn set_controller_with_incorrect_controller() {
         // Set the controller from the wrong controller. Should fail.
         assert_eq!(
             canister_manager.set_controller(
-                wrong_controller,
+                CanisterChangeOrigin::from_user(wrong_controller),
                 canister_id,
                 new_controller,
                 &mut state,
@@ -2104,7 +2109,7 @@ fn set_controller_with_correct_controller() {
         // Set the controller from the correct controller. Should succeed.
         assert!(canister_manager
             .set_controller(
-                controller,
+                CanisterChangeOrigin::from_user(controller),
                 canister_id,
                 new_controller,
                 &mut state,
@@ -2248,7 +2253,7 @@ fn install_canister_with_query_allocation() {
         let sender_subnet_id = subnet_test_id(1);
         let canister_id = canister_manager
             .create_canister(
-                sender,
+                CanisterChangeOrigin::from_canister(sender, None),
                 sender_subnet_id,
                 *INITIAL_CYCLES,
                 CanisterSettings::default(),
@@ -2263,7 +2268,7 @@ fn install_canister_with_query_allocation() {
         assert!(install_code(
             &canister_manager,
             InstallCodeContextBuilder::default()
-                .sender(sender)
+                .origin(CanisterChangeOrigin::from_canister(sender, None))
                 .canister_id(canister_id)
                 .query_allocation(query_allocation)
                 .build(),
@@ -2318,7 +2323,7 @@ fn create_canister_with_cycles_sender_in_whitelist() {
     let sender = canister_test_id(1).get();
     let canister_id = canister_manager
         .create_canister_with_cycles(
-            sender,
+            CanisterChangeOrigin::from_canister(sender, None),
             Some(123),
             CanisterSettings::default(),
             None,
@@ -2354,7 +2359,7 @@ fn create_canister_with_specified_id(
     let creator = canister_test_id(1).get();
 
     let creation_result = canister_manager.create_canister_with_cycles(
-        creator,
+        CanisterChangeOrigin::from_canister(creator, None),
         Some(123),
         CanisterSettings::default(),
         Some(specified_id),
@@ -2486,7 +2491,7 @@ fn installing_a_canister_with_not_enough_memory_allocation_fails() {
         let sender_subnet_id = subnet_test_id(1);
         let canister_id = canister_manager
             .create_canister(
-                sender,
+                CanisterChangeOrigin::from_canister(sender, None),
                 sender_subnet_id,
                 *INITIAL_CYCLES,
                 CanisterSettings::default(),
@@ -2503,7 +2508,7 @@ fn installing_a_canister_with_not_enough_memory_allocation_fails() {
         let res = install_code(
             &canister_manager,
             InstallCodeContextBuilder::default()
-                .sender(sender)
+                .origin(CanisterChangeOrigin::from_canister(sender, None))
                 .canister_id(canister_id)
                 .wasm_module(
                     ic_test_utilities::universal_canister::UNIVERSAL_CANISTER_WASM.to_vec(),
@@ -2530,7 +2535,7 @@ fn installing_a_canister_with_not_enough_memory_allocation_fails() {
         let res = install_code(
             &canister_manager,
             InstallCodeContextBuilder::default()
-                .sender(sender)
+                .origin(CanisterChangeOrigin::from_canister(sender, None))
                 .canister_id(canister_id)
                 .wasm_module(
                     ic_test_utilities::universal_canister::UNIVERSAL_CANISTER_WASM.to_vec(),
@@ -2548,7 +2553,7 @@ fn installing_a_canister_with_not_enough_memory_allocation_fails() {
         let res = install_code(
             &canister_manager,
             InstallCodeContextBuilder::default()
-                .sender(sender)
+                .origin(CanisterChangeOrigin::from_canister(sender, None))
                 .canister_id(canister_id)
                 .mode(CanisterInstallMode::Reinstall)
                 .wasm_module(
@@ -2580,7 +2585,7 @@ fn upgrading_canister_with_not_enough_memory_allocation_fails() {
         let sender_subnet_id = subnet_test_id(1);
         let canister_id = canister_manager
             .create_canister(
-                sender,
+                CanisterChangeOrigin::from_canister(sender, None),
                 sender_subnet_id,
                 *INITIAL_CYCLES,
                 CanisterSettings::default(),
@@ -2595,7 +2600,7 @@ fn upgrading_canister_with_not_enough_memory_allocation_fails() {
         let res = install_code(
             &canister_manager,
             InstallCodeContextBuilder::default()
-                .sender(sender)
+                .origin(CanisterChangeOrigin::from_canister(sender, None))
                 .canister_id(canister_id)
                 .wasm_module(
                     ic_test_utilities::universal_canister::UNIVERSAL_CANISTER_WASM.to_vec(),
@@ -2613,7 +2618,7 @@ fn upgrading_canister_with_not_enough_memory_allocation_fails() {
             install_code(
                 &canister_manager,
                 InstallCodeContextBuilder::default()
-                    .sender(sender)
+                    .origin(CanisterChangeOrigin::from_canister(sender, None))
                     .canister_id(canister_id)
                     .wasm_module(
                         ic_test_utilities::universal_canister::UNIVERSAL_CANISTER_WASM.to_vec()
@@ -2705,7 +2710,7 @@ fn installing_a_canister_with_not_enough_cycles_fails() {
         let sender_subnet_id = subnet_test_id(1);
         let canister_id = canister_manager
             .create_canister(
-                sender,
+                CanisterChangeOrigin::from_canister(sender, None),
                 sender_subnet_id,
                 // Give the new canister a relatively small number of cycles so it doesn't have
                 // enough to be installed.
@@ -2722,7 +2727,7 @@ fn installing_a_canister_with_not_enough_cycles_fails() {
         let res = install_code(
             &canister_manager,
             InstallCodeContextBuilder::default()
-                .sender(sender)
+                .origin(CanisterChangeOrigin::from_canister(sender, None))
                 .canister_id(canister_id)
                 .wasm_module(
                     ic_test_utilities::universal_canister::UNIVERSAL_CANISTER_WASM.to_vec(),
@@ -2815,7 +2820,7 @@ fn failed_upgrade_hooks_consume_instructions() {
         let sender = canister_test_id(100).get();
         let canister_id = canister_manager
             .create_canister(
-                sender,
+                CanisterChangeOrigin::from_canister(sender, None),
                 subnet_id,
                 *INITIAL_CYCLES,
                 CanisterSettings::default(),
@@ -2830,7 +2835,7 @@ fn failed_upgrade_hooks_consume_instructions() {
         let res = install_code(
             &canister_manager,
             InstallCodeContext {
-                sender,
+                origin: CanisterChangeOrigin::from_canister(sender, None),
                 canister_id,
                 wasm_module: CanisterModule::new(initial_wasm),
                 arg: vec![],
@@ -2856,7 +2861,7 @@ fn failed_upgrade_hooks_consume_instructions() {
         let (instructions_left, result, _) = install_code(
             &canister_manager,
             InstallCodeContext {
-                sender,
+                origin: CanisterChangeOrigin::from_canister(sender, None),
                 canister_id,
                 wasm_module: CanisterModule::new(upgrade_wasm),
                 arg: vec![],
@@ -2959,7 +2964,7 @@ fn failed_install_hooks_consume_instructions() {
         let sender = canister_test_id(100).get();
         let canister_id = canister_manager
             .create_canister(
-                sender,
+                CanisterChangeOrigin::from_canister(sender, None),
                 subnet_id,
                 *INITIAL_CYCLES,
                 CanisterSettings::default(),
@@ -2975,7 +2980,7 @@ fn failed_install_hooks_consume_instructions() {
         let (instructions_left, result, _) = install_code(
             &canister_manager,
             InstallCodeContext {
-                sender,
+                origin: CanisterChangeOrigin::from_canister(sender, None),
                 canister_id,
                 wasm_module: CanisterModule::new(wasm),
                 arg: vec![],
@@ -3044,7 +3049,7 @@ fn install_code_respects_instruction_limit() {
     let sender = canister_test_id(100).get();
     let canister_id = canister_manager
         .create_canister(
-            sender,
+            CanisterChangeOrigin::from_canister(sender, None),
             subnet_id,
             *INITIAL_CYCLES,
             CanisterSettings::default(),
@@ -3093,7 +3098,7 @@ fn install_code_respects_instruction_limit() {
     let (instructions_left, result, canister) = install_code(
         &canister_manager,
         InstallCodeContext {
-            sender,
+            origin: CanisterChangeOrigin::from_canister(sender, None),
             canister_id,
             wasm_module: CanisterModule::new(wasm.clone()),
             arg: vec![],
@@ -3125,7 +3130,7 @@ fn install_code_respects_instruction_limit() {
     let (instructions_left, result, canister) = install_code(
         &canister_manager,
         InstallCodeContext {
-            sender,
+            origin: CanisterChangeOrigin::from_canister(sender, None),
             canister_id,
             wasm_module: CanisterModule::new(wasm.clone()),
             arg: vec![],
@@ -3151,7 +3156,7 @@ fn install_code_respects_instruction_limit() {
     let (instructions_left, result, canister) = install_code(
         &canister_manager,
         InstallCodeContext {
-            sender,
+            origin: CanisterChangeOrigin::from_canister(sender, None),
             canister_id,
             wasm_module: CanisterModule::new(wasm.clone()),
             arg: vec![],
@@ -3183,7 +3188,7 @@ fn install_code_respects_instruction_limit() {
     let (instructions_left, result, _) = install_code(
         &canister_manager,
         InstallCodeContext {
-            sender,
+            origin: CanisterChangeOrigin::from_canister(sender, None),
             canister_id,
             wasm_module: CanisterModule::new(wasm),
             arg: vec![],
@@ -3242,7 +3247,7 @@ fn install_code_preserves_system_state_and_scheduler_state() {
     // 1. INSTALL
     let install_code_context = InstallCodeContextBuilder::default()
         .mode(CanisterInstallMode::Install)
-        .sender(controller.into())
+        .origin(CanisterChangeOrigin::from_canister(controller.into(), None))
         .canister_id(canister_id)
         .build();
     let compilation_cost = wasm_compilation_cost(install_code_context.wasm_module.as_slice());
@@ -3251,7 +3256,7 @@ fn install_code_preserves_system_state_and_scheduler_state() {
         &canister_manager,
         InstallCodeContextBuilder::default()
             .mode(CanisterInstallMode::Install)
-            .sender(controller.into())
+            .origin(CanisterChangeOrigin::from_canister(controller.into(), None))
             .canister_id(canister_id)
             .build(),
         &mut state,
@@ -3293,7 +3298,7 @@ fn install_code_preserves_system_state_and_scheduler_state() {
         &canister_manager,
         InstallCodeContextBuilder::default()
             .mode(CanisterInstallMode::Reinstall)
-            .sender(controller.into())
+            .origin(CanisterChangeOrigin::from_canister(controller.into(), None))
             .canister_id(canister_id)
             .build(),
         &mut state,
@@ -3337,7 +3342,7 @@ fn install_code_preserves_system_state_and_scheduler_state() {
         &canister_manager,
         InstallCodeContextBuilder::default()
             .mode(CanisterInstallMode::Upgrade)
-            .sender(controller.into())
+            .origin(CanisterChangeOrigin::from_canister(controller.into(), None))
             .canister_id(canister_id)
             .build(),
         &mut state,
@@ -3394,7 +3399,7 @@ fn lower_memory_allocation_than_usage_fails() {
         let sender = canister_test_id(100).get();
         let canister_id = canister_manager
             .create_canister(
-                sender,
+                CanisterChangeOrigin::from_canister(sender, None),
                 subnet_id,
                 *INITIAL_CYCLES,
                 CanisterSettings::default(),
@@ -3409,7 +3414,7 @@ fn lower_memory_allocation_than_usage_fails() {
         let res = install_code(
             &canister_manager,
             InstallCodeContext {
-                sender,
+                origin: CanisterChangeOrigin::from_canister(sender, None),
                 canister_id,
                 wasm_module: CanisterModule::new(wasm),
                 arg: vec![],
@@ -3431,7 +3436,13 @@ fn lower_memory_allocation_than_usage_fails() {
         let canister = state.canister_state_mut(&canister_id).unwrap();
 
         assert_matches!(
-            canister_manager.update_settings(sender, settings, canister, &mut round_limits,),
+            canister_manager.update_settings(
+                mock_time(),
+                CanisterChangeOrigin::from_canister(sender, None),
+                settings,
+                canister,
+                &mut round_limits,
+            ),
             Err(CanisterManagerError::NotEnoughMemoryAllocationGiven { .. })
         );
     })
@@ -3454,7 +3465,7 @@ fn test_install_when_updating_memory_allocation_via_canister_settings() {
             .build();
         let canister_id = canister_manager
             .create_canister(
-                sender,
+                CanisterChangeOrigin::from_canister(sender, None),
                 subnet_id,
                 *INITIAL_CYCLES,
                 settings,
@@ -3470,7 +3481,7 @@ fn test_install_when_updating_memory_allocation_via_canister_settings() {
         let res = install_code(
             &canister_manager,
             InstallCodeContext {
-                sender,
+                origin: CanisterChangeOrigin::from_canister(sender, None),
                 canister_id,
                 wasm_module: CanisterModule::new(wasm.clone()),
                 arg: vec![],
@@ -3499,13 +3510,19 @@ fn test_install_when_updating_memory_allocation_via_canister_settings() {
         let canister = state.canister_state_mut(&canister_id).unwrap();
 
         canister_manager
-            .update_settings(sender, settings, canister, &mut round_limits)
+            .update_settings(
+                mock_time(),
+                CanisterChangeOrigin::from_canister(sender, None),
+                settings,
+                canister,
+                &mut round_limits,
+            )
             .unwrap();
 
         install_code(
             &canister_manager,
             InstallCodeContext {
-                sender,
+                origin: CanisterChangeOrigin::from_canister(sender, None),
                 canister_id,
                 wasm_module: CanisterModule::new(wasm),
                 arg: vec![],
@@ -3544,7 +3561,7 @@ fn test_upgrade_when_updating_memory_allocation_via_canister_settings() {
         let wasm = wat::parse_str(wat).unwrap();
         let canister_id = canister_manager
             .create_canister(
-                sender,
+                CanisterChangeOrigin::from_canister(sender, None),
                 subnet_id,
                 *INITIAL_CYCLES,
                 settings,
@@ -3559,7 +3576,7 @@ fn test_upgrade_when_updating_memory_allocation_via_canister_settings() {
         let res = install_code(
             &canister_manager,
             InstallCodeContext {
-                sender,
+                origin: CanisterChangeOrigin::from_canister(sender, None),
                 canister_id,
                 wasm_module: CanisterModule::new(wasm),
                 arg: vec![],
@@ -3585,7 +3602,7 @@ fn test_upgrade_when_updating_memory_allocation_via_canister_settings() {
         let res = install_code(
             &canister_manager,
             InstallCodeContext {
-                sender,
+                origin: CanisterChangeOrigin::from_canister(sender, None),
                 canister_id,
                 wasm_module: CanisterModule::new(wasm.clone()),
                 arg: vec![],
@@ -3615,13 +3632,19 @@ fn test_upgrade_when_updating_memory_allocation_via_canister_settings() {
         let canister = state.canister_state_mut(&canister_id).unwrap();
 
         canister_manager
-            .update_settings(sender, settings, canister, &mut round_limits)
+            .update_settings(
+                mock_time(),
+                CanisterChangeOrigin::from_canister(sender, None),
+                settings,
+                canister,
+                &mut round_limits,
+            )
             .unwrap();
 
         install_code(
             &canister_manager,
             InstallCodeContext {
-                sender,
+                origin: CanisterChangeOrigin::from_canister(sender, None),
                 canister_id,
                 wasm_module: CanisterModule::new(wasm),
                 arg: vec![],
@@ -3662,8 +3685,8 @@ fn uninstall_code_can_be_invoked_by_governance_canister() {
 
     canister_manager
         .uninstall_code(
+            CanisterChangeOrigin::from_canister(GOVERNANCE_CANISTER_ID.get(), None),
             canister_test_id(0),
-            GOVERNANCE_CANISTER_ID.get(),
             &mut state,
         )
         .unwrap();
@@ -3693,7 +3716,7 @@ fn test_install_when_setting_memory_allocation_to_zero() {
         let settings = CanisterSettings::default();
         let canister_id = canister_manager
             .create_canister(
-                sender,
+                CanisterChangeOrigin::from_canister(sender, None),
                 subnet_id,
                 *INITIAL_CYCLES,
                 settings,
@@ -3714,7 +3737,8 @@ fn test_install_when_setting_memory_allocation_to_zero() {
 
         canister_manager
             .update_settings(
-                sender,
+                mock_time(),
+                CanisterChangeOrigin::from_canister(sender, None),
                 settings,
                 canister,
                 //memory_allocation_used,
@@ -3725,7 +3749,7 @@ fn test_install_when_setting_memory_allocation_to_zero() {
         install_code(
             &canister_manager,
             InstallCodeContext {
-                sender,
+                origin: CanisterChangeOrigin::from_canister(sender, None),
                 canister_id,
                 wasm_module: CanisterModule::new(wasm),
                 arg: vec![],
@@ -3761,7 +3785,7 @@ fn test_upgrade_when_setting_memory_allocation_to_zero() {
             .build();
         let canister_id = canister_manager
             .create_canister(
-                sender,
+                CanisterChangeOrigin::from_canister(sender, None),
                 subnet_id,
                 *INITIAL_CYCLES,
                 settings,
@@ -3776,7 +3800,7 @@ fn test_upgrade_when_setting_memory_allocation_to_zero() {
         let res = install_code(
             &canister_manager,
             InstallCodeContext {
-                sender,
+                origin: CanisterChangeOrigin::from_canister(sender, None),
                 canister_id,
                 wasm_module: CanisterModule::new(wasm.clone()),
                 arg: vec![],
@@ -3799,13 +3823,19 @@ fn test_upgrade_when_setting_memory_allocation_to_zero() {
         let canister = state.canister_state_mut(&canister_id).unwrap();
 
         canister_manager
-            .update_settings(sender, settings, canister, &mut round_limits)
+            .update_settings(
+                mock_time(),
+                CanisterChangeOrigin::from_canister(sender, None),
+                settings,
+                canister,
+                &mut round_limits,
+            )
             .unwrap();
 
         install_code(
             &canister_manager,
             InstallCodeContext {
-                sender,
+                origin: CanisterChangeOrigin::from_canister(sender, None),
                 canister_id,
                 wasm_module: CanisterModule::new(wasm),
                 arg: vec![],
@@ -3837,7 +3867,7 @@ fn max_number_of_canisters_is_respected_when_creating_canisters() {
         // Create 3 canisters with `max_number_of_canisters = 3`, should succeed.
         canister_manager
             .create_canister(
-                sender,
+                CanisterChangeOrigin::from_canister(sender, None),
                 sender_subnet_id,
                 *INITIAL_CYCLES,
                 CanisterSettings::default(),
@@ -3850,7 +3880,7 @@ fn max_number_of_canisters_is_respected_when_creating_canisters() {
             .unwrap();
         canister_manager
             .create_canister(
-                sender,
+                CanisterChangeOrigin::from_canister(sender, None),
                 sender_subnet_id,
                 *INITIAL_CYCLES,
                 CanisterSettings::default(),
@@ -3863,7 +3893,7 @@ fn max_number_of_canisters_is_respected_when_creating_canisters() {
             .unwrap();
         canister_manager
             .create_canister(
-                sender,
+                CanisterChangeOrigin::from_canister(sender, None),
                 sender_subnet_id,
                 *INITIAL_CYCLES,
                 CanisterSettings::default(),
@@ -3879,7 +3909,7 @@ fn max_number_of_canisters_is_respected_when_creating_canisters() {
         // Creating a fourth canister with 3 already created and
         // `max_number_of_canisters = 3` should fail.
         let (res, _) = canister_manager.create_canister(
-            sender,
+            CanisterChangeOrigin::from_canister(sender, None),
             sender_subnet_id,
             *INITIAL_CYCLES,
             CanisterSettings::default(),
@@ -3897,7 +3927,7 @@ fn max_number_of_canisters_is_respected_when_creating_canisters() {
         // `max_number_of_canisters = 10` should succeed.
         canister_manager
             .create_canister(
-                sender,
+                CanisterChangeOrigin::from_canister(sender, None),
                 sender_subnet_id,
                 *INITIAL_CYCLES,
                 CanisterSettings::default(),
@@ -5051,3 +5081,20 @@ fn install_code_cannot_switch_from_reserved_to_best_effort_memory_allocation() {
         NumWasmPages::from(10)
     )
 }
+
+#[test]
+fn memory_allocation_above_wasm32_limit_requires_wasm64() {
+    let mut config = canister_manager_config(
+        subnet_test_id(1),
+        SubnetType::Application,
+        FlagStatus::Disabled,
+    );
+    let bytes = MAX_MEMORY_ALLOCATION + NumBytes::from(1);
+    assert!(MemoryAllocation::try_from_with_max(bytes, config.max_memory_allocation()).is_err());
+
+    config.wasm64 = FlagStatus::Enabled;
+    assert_eq!(
+        MemoryAllocation::try_from_with_max(bytes, config.max_memory_allocation()).unwrap(),
+        MemoryAllocation::Reserved(bytes)
+    );
+}
@@ -1,9 +1,9 @@
 use ic_base_types::{NumBytes, NumSeconds};
 use ic_error_types::{ErrorCode, UserError};
-use ic_ic00_types::CanisterSettingsArgs;
+use ic_ic00_types::{CanisterSettingsArgs, LogVisibility};
 use ic_types::{
     ComputeAllocation, InvalidComputeAllocationError, InvalidMemoryAllocationError,
-    MemoryAllocation, PrincipalId,
+    MemoryAllocation, PrincipalId, MAX_MEMORY_ALLOCATION,
 };
 use num_traits::cast::ToPrimitive;
 use std::convert::TryFrom;
@@ -16,6 +16,7 @@ pub(crate) struct CanisterSettings {
     pub(crate) compute_allocation: Option<ComputeAllocation>,
     pub(crate) memory_allocation: Option<MemoryAllocation>,
     pub(crate) freezing_threshold: Option<NumSeconds>,
+    pub(crate) log_visibility: Option<LogVisibility>,
 }
 
 impl CanisterSettings {
@@ -25,6 +26,7 @@ impl CanisterSettings {
         compute_allocation: Option<ComputeAllocation>,
         memory_allocation: Option<MemoryAllocation>,
         freezing_threshold: Option<NumSeconds>,
+        log_visibility: Option<LogVisibility>,
     ) -> Self {
         Self {
             controller,
@@ -32,6 +34,7 @@ impl CanisterSettings {
             compute_allocation,
             memory_allocation,
             freezing_threshold,
+            log_visibility,
         }
     }
 
@@ -54,12 +57,26 @@ impl CanisterSettings {
     pub fn freezing_threshold(&self) -> Option<NumSeconds> {
         self.freezing_threshold
     }
+
+    pub fn log_visibility(&self) -> Option<LogVisibility> {
+        self.log_visibility
+    }
 }
 
 impl TryFrom<CanisterSettingsArgs> for CanisterSettings {
     type Error = UpdateSettingsError;
 
     fn try_from(input: CanisterSettingsArgs) -> Result<Self, Self::Error> {
+        CanisterSettings::try_from((input, MAX_MEMORY_ALLOCATION))
+    }
+}
+
+/// Converts the settings with the given upper limit on the memory allocation.
+impl TryFrom<(CanisterSettingsArgs, NumBytes)> for CanisterSettings {
+    type Error = UpdateSettingsError;
+
+    fn try_from(input: (CanisterSettingsArgs, NumBytes)) -> Result<Self, Self::Error> {
+        let (input, max_memory_allocation) = input;
         let controller = input.get_controller();
         let compute_allocation = match input.compute_allocation {
             Some(ca) => Some(ComputeAllocation::try_from(ca.0.to_u64().ok_or_else(
@@ -69,11 +86,15 @@ impl TryFrom<CanisterSettingsArgs> for CanisterSettings {
         };
 
         let memory_allocation = match input.memory_allocation {
-            Some(ma) => Some(MemoryAllocation::try_from(NumBytes::from(
-                ma.0.to_u64().ok_or_else(|| {
-                    UpdateSettingsError::MemoryAllocation(InvalidMemoryAllocationError::new(ma))
-                })?,
-            ))?),
+            Some(ma) => Some(MemoryAllocation::try_from_with_max(
+                NumBytes::from(ma.0.to_u64().ok_or_else(|| {
+                    UpdateSettingsError::MemoryAllocation(InvalidMemoryAllocationError::with_max(
+                        ma,
+                        max_memory_allocation,
+                    ))
+                })?),
+                max_memory_allocation,
+            )?),
             None => None,
         };
 
@@ -90,6 +111,7 @@ impl TryFrom<CanisterSettingsArgs> for CanisterSettings {
             compute_allocation,
             memory_allocation,
             freezing_threshold,
+            input.log_visibility,
         ))
     }
 }
@@ -111,6 +133,7 @@ pub(crate) struct CanisterSettingsBuilder {
     compute_allocation: Option<ComputeAllocation>,
     memory_allocation: Option<MemoryAllocation>,
     freezing_threshold: Option<NumSeconds>,
+    log_visibility: Option<LogVisibility>,
 }
 
 #[allow(dead_code)]
@@ -122,6 +145,7 @@ impl CanisterSettingsBuilder {
             compute_allocation: None,
             memory_allocation: None,
             freezing_threshold: None,
+            log_visibility: None,
         }
     }
 
@@ -132,6 +156,7 @@ impl CanisterSettingsBuilder {
             compute_allocation: self.compute_allocation,
             memory_allocation: self.memory_allocation,
             freezing_threshold: self.freezing_threshold,
+            log_visibility: self.log_visibility,
         }
     }
 
@@ -169,6 +194,13 @@ impl CanisterSettingsBuilder {
             ..self
         }
     }
+
+    pub fn with_log_visibility(self, log_visibility: LogVisibility) -> Self {
+        Self {
+            log_visibility: Some(log_visibility),
+            ..self
+        }
+    }
 }
 
 pub enum UpdateSettingsError {
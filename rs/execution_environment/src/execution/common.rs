@@ -22,7 +22,7 @@ use ic_system_api::sandbox_safe_system_state::SystemStateChanges;
 use ic_types::ingress::{IngressState, IngressStatus, WasmResult};
 use ic_types::messages::{CallContextId, CallbackId, MessageId, Payload, RejectContext, Response};
 use ic_types::methods::{Callback, WasmMethod};
-use ic_types::{Cycles, MemoryAllocation, NumInstructions, Time, UserId};
+use ic_types::{CoarseTime, Cycles, MemoryAllocation, NumInstructions, Time, UserId};
 
 use crate::execution_environment::ExecutionResponse;
 use crate::{as_round_instructions, ExecuteMessageResult, RoundLimits};
@@ -68,8 +68,8 @@ pub(crate) fn action_to_response(
             time,
             log,
         ),
-        CallOrigin::CanisterUpdate(caller_canister_id, callback_id) => {
-            action_to_request_response(canister, action, caller_canister_id, callback_id)
+        CallOrigin::CanisterUpdate(caller_canister_id, callback_id, deadline) => {
+            action_to_request_response(canister, action, caller_canister_id, callback_id, deadline)
         }
         CallOrigin::CanisterQuery(_, _) | CallOrigin::Query(_) => fatal!(
             log,
@@ -90,6 +90,7 @@ pub(crate) fn action_to_request_response(
     action: CallContextAction,
     originator: CanisterId,
     reply_callback_id: CallbackId,
+    deadline: CoarseTime,
 ) -> ExecutionResponse {
     let response_payload_and_refund = match action {
         CallContextAction::NotYetResponded | CallContextAction::AlreadyResponded => None,
@@ -128,6 +129,7 @@ pub(crate) fn action_to_request_response(
             originator,
             respondent: canister.canister_id(),
             originator_reply_callback: reply_callback_id,
+            deadline,
             refund,
             response_payload,
         })
@@ -239,11 +241,12 @@ pub(crate) fn wasm_result_to_query_response(
         CallOrigin::Ingress(user_id, message_id) => {
             wasm_result_to_ingress_response(result, canister, user_id, message_id, time)
         }
-        CallOrigin::CanisterUpdate(caller_canister_id, callback_id) => {
+        CallOrigin::CanisterUpdate(caller_canister_id, callback_id, deadline) => {
             let response = Response {
                 originator: caller_canister_id,
                 respondent: canister.canister_id(),
                 originator_reply_callback: callback_id,
+                deadline,
                 refund: Cycles::zero(),
                 response_payload: Payload::from(result),
             };
@@ -422,7 +425,8 @@ fn try_apply_canister_state_changes(
 
 /// Applies canister state change after Wasm execution if possible.
 /// Otherwise, the function sets the corresponding error in
-/// `output.wasm_result`.
+/// `output.wasm_result`. The log records of the execution are kept in
+/// either case.
 /// Potential causes of failure:
 /// - Changes in the environment such as subnet available memory while the
 ///   long-execution with deterministic time slicing was in progress.
@@ -492,6 +496,7 @@ pub fn apply_canister_state_changes(
             }
         }
     }
+    system_state.canister_log.append(&mut output.canister_log);
 }
 
 pub(crate) fn finish_call_with_error(
@@ -509,6 +514,7 @@ pub(crate) fn finish_call_with_error(
                 originator: request.sender,
                 respondent: canister.canister_id(),
                 originator_reply_callback: request.sender_reply_callback,
+                deadline: request.deadline,
                 refund: request.payment,
                 response_payload: Payload::from(Err(user_error)),
             };
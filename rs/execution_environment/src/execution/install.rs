@@ -113,7 +113,7 @@ pub(crate) fn execute_install(
         // If the Wasm module does not export the method, then this execution
         // succeeds as a no-op.
         install_stage_2b_continue_install_after_start(
-            context.sender,
+            original.sender,
             context.arg,
             clean_canister,
             helper,
@@ -139,7 +139,7 @@ pub(crate) fn execute_install(
                 install_stage_2a_process_start_result(
                     canister_state_changes,
                     output,
-                    context.sender,
+                    original.sender,
                     context.arg,
                     clean_canister,
                     helper,
@@ -161,7 +161,7 @@ pub(crate) fn execute_install(
                 let paused_execution = Box::new(PausedStartExecutionDuringInstall {
                     paused_wasm_execution,
                     paused_helper: helper.pause(),
-                    context_sender: context.sender,
+                    context_sender: original.sender,
                     context_arg: context.arg,
                     original,
                 });
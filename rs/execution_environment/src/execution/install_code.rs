@@ -6,7 +6,7 @@ use std::path::{Path, PathBuf};
 use ic_base_types::{CanisterId, NumBytes, PrincipalId};
 use ic_config::flag_status::FlagStatus;
 use ic_embedders::wasm_executor::CanisterStateChanges;
-use ic_ic00_types::CanisterInstallMode;
+use ic_ic00_types::{CanisterChangeDetails, CanisterChangeOrigin, CanisterInstallMode};
 use ic_interfaces::{
     execution_environment::{
         HypervisorError, HypervisorResult, SubnetAvailableMemory, SubnetAvailableMemoryError,
@@ -266,6 +266,13 @@ impl InstallCodeHelper {
 
         let old_wasm_hash = get_wasm_hash(&clean_canister);
         let new_wasm_hash = get_wasm_hash(&self.canister);
+        if let Some(module_hash) = new_wasm_hash {
+            self.canister.system_state.add_canister_change(
+                original.time,
+                original.canister_change_origin,
+                CanisterChangeDetails::code_deployment(original.mode, module_hash),
+            );
+        }
         DtsInstallCodeResult::Finished {
             canister: self.canister,
             message: original.message,
@@ -428,7 +435,7 @@ impl InstallCodeHelper {
     pub fn handle_wasm_execution(
         &mut self,
         canister_state_changes: Option<CanisterStateChanges>,
-        output: WasmExecutionOutput,
+        mut output: WasmExecutionOutput,
         original: &OriginalContext,
         round: &RoundContext,
     ) -> Result<(), CanisterManagerError> {
@@ -441,6 +448,13 @@ impl InstallCodeHelper {
             .instruction_limits
             .update(output.num_instructions_left);
 
+        // The log records are kept only if the whole `install_code` succeeds,
+        // because the canister is rolled back otherwise.
+        self.canister
+            .system_state
+            .canister_log
+            .append(&mut output.canister_log);
+
         match output.wasm_result {
             Ok(None) => {}
             Ok(Some(_response)) => {
@@ -563,6 +577,7 @@ pub(crate) struct OriginalContext {
     pub requested_compute_allocation: Option<ComputeAllocation>,
     pub requested_memory_allocation: Option<MemoryAllocation>,
     pub sender: PrincipalId,
+    pub canister_change_origin: CanisterChangeOrigin,
     pub canister_id: CanisterId,
 }
 
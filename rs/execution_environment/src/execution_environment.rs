@@ -22,15 +22,20 @@ use ic_config::execution_environment::Config as ExecutionConfig;
 use ic_config::flag_status::FlagStatus;
 use ic_constants::{LOG_CANISTER_OPERATION_CYCLES_THRESHOLD, SMALL_APP_SUBNET_MAX_SIZE};
 use ic_crypto_tecdsa::derive_tecdsa_public_key;
+use ic_crypto_utils_threshold_sig_der::parse_threshold_sig_key_from_der;
+use ic_crypto_vetkd::{derive_vetkd_public_key, is_valid_transport_public_key};
 use ic_cycles_account_manager::{CyclesAccountManager, IngressInductionCost};
 use ic_error_types::{ErrorCode, RejectCode, UserError};
 use ic_ic00_types::{
-    CanisterHttpRequestArgs, CanisterIdRecord, CanisterSettingsArgs,
-    ComputeInitialEcdsaDealingsArgs, CreateCanisterArgs, ECDSAPublicKeyArgs,
-    ECDSAPublicKeyResponse, EcdsaKeyId, EmptyBlob, InstallCodeArgs, Method as Ic00Method,
+    CanisterChangeOrigin, CanisterHttpRequestArgs, CanisterIdRecord, CanisterInfoRequest,
+    CanisterSettingsArgs, ComputeInitialEcdsaDealingsArgs, CreateCanisterArgs,
+    DeleteCanisterSnapshotArgs, ECDSAPublicKeyArgs, ECDSAPublicKeyResponse, EcdsaKeyId, EmptyBlob,
+    InstallChunkedCodeArgs, InstallCodeArgs, LoadCanisterSnapshotArgs, Method as Ic00Method,
     Payload as Ic00Payload, ProvisionalCreateCanisterWithCyclesArgs, ProvisionalTopUpCanisterArgs,
-    SetControllerArgs, SetupInitialDKGArgs, SignWithECDSAArgs, UninstallCodeArgs,
-    UpdateSettingsArgs, IC_00,
+    SchnorrKeyId, SchnorrPublicKeyArgs, SchnorrPublicKeyResponse, SetControllerArgs,
+    SetupInitialDKGArgs, SignWithECDSAArgs, SignWithSchnorrArgs, TakeCanisterSnapshotArgs,
+    UninstallCodeArgs, UpdateSettingsArgs, UploadChunkArgs, VetKdEncryptedKeyArgs, VetKdKeyId,
+    VetKdPublicKeyArgs, VetKdPublicKeyResponse, IC_00,
 };
 use ic_interfaces::{
     execution_environment::{
@@ -53,24 +58,25 @@ use ic_replicated_state::{
 };
 use ic_replicated_state::{
     metadata_state::subnet_call_context_manager::{
-        EcdsaDealingsContext, SetupInitialDkgContext, SignWithEcdsaContext,
+        EcdsaDealingsContext, SetupInitialDkgContext, SignWithEcdsaContext, SignWithSchnorrContext,
+        VetKdContext,
     },
-    CanisterState, NetworkTopology, ReplicatedState,
+    CanisterState, NetworkTopology, ReplicatedState, SubnetTopology,
 };
 use ic_system_api::{ExecutionParameters, InstructionLimits};
 use ic_types::{
-    canister_http::CanisterHttpRequestContext,
+    canister_http::{CanisterHttpRequestContext, Replication},
     crypto::canister_threshold_sig::{ExtendedDerivationPath, MasterEcdsaPublicKey},
-    crypto::threshold_sig::ni_dkg::NiDkgTargetId,
+    crypto::threshold_sig::{ni_dkg::NiDkgTargetId, ThresholdSigPublicKey},
     ingress::{IngressState, IngressStatus, WasmResult},
     messages::{
         extract_effective_canister_id, AnonymousQuery, Payload, RejectContext, Request, Response,
-        SignedIngressContent, StopCanisterContext,
+        SignedIngressContent, StopCanisterContext, NO_DEADLINE,
     },
     methods::SystemMethod,
     nominal_cycles::NominalCycles,
-    CanisterId, CpuComplexity, Cycles, LongExecutionMode, NumBytes, NumInstructions, SubnetId,
-    Time,
+    CanisterId, CpuComplexity, Cycles, LongExecutionMode, NodeId, NumBytes, NumInstructions,
+    SubnetId, Time,
 };
 use ic_types::{messages::MessageId, methods::WasmMethod};
 use ic_wasm_types::WasmHash;
@@ -306,6 +312,7 @@ impl ExecutionEnvironment {
             compute_capacity,
             config.rate_limiting_of_instructions,
             config.allocatable_compute_capacity_in_percent,
+            config.wasm64,
         );
         let canister_manager = CanisterManager::new(
             Arc::clone(&hypervisor),
@@ -367,6 +374,7 @@ impl ExecutionEnvironment {
         instruction_limits: InstructionLimits,
         rng: &mut dyn RngCore,
         ecdsa_subnet_public_keys: &BTreeMap<EcdsaKeyId, MasterEcdsaPublicKey>,
+        schnorr_subnet_public_keys: &BTreeMap<SchnorrKeyId, MasterEcdsaPublicKey>,
         registry_settings: &RegistryExecutionSettings,
         round_limits: &mut RoundLimits,
     ) -> (ReplicatedState, Option<NumInstructions>) {
@@ -405,6 +413,7 @@ impl ExecutionEnvironment {
                                 originator: request.sender,
                                 respondent: CanisterId::from(self.own_subnet_id),
                                 originator_reply_callback: request.sender_reply_callback,
+                                deadline: request.deadline,
                                 refund: request.payment,
                                 response_payload: response.response_payload.clone(),
                             }
@@ -423,7 +432,7 @@ impl ExecutionEnvironment {
         let method = Ic00Method::from_str(msg.method_name());
         let payload = msg.method_payload();
         let result = match method {
-            Ok(Ic00Method::InstallCode) => {
+            Ok(Ic00Method::InstallCode) | Ok(Ic00Method::InstallChunkedCode) => {
                 // Tail call is needed for deterministic time slicing here to
                 // properly handle the case of a paused execution.
                 return self.execute_install_code(
@@ -452,6 +461,7 @@ impl ExecutionEnvironment {
                                 originator: request.sender,
                                 respondent: CanisterId::from(self.own_subnet_id),
                                 originator_reply_callback: request.sender_reply_callback,
+                                deadline: request.deadline,
                                 refund: request.payment,
                                 response_payload: messages::Payload::Reject(
                                     messages::RejectContext {
@@ -498,6 +508,60 @@ impl ExecutionEnvironment {
                 }
             },
 
+            Ok(Ic00Method::SignWithSchnorr) => match &msg {
+                CanisterCall::Request(request) => match SignWithSchnorrArgs::decode(payload) {
+                    Err(err) => Some((Err(candid_error_to_user_error(err)), msg.take_cycles())),
+                    Ok(args) => match get_master_schnorr_public_key(
+                        schnorr_subnet_public_keys,
+                        self.own_subnet_id,
+                        &args.key_id,
+                    ) {
+                        Err(err) => Some((Err(err), msg.take_cycles())),
+                        Ok(_) => self
+                            .sign_with_schnorr(
+                                (**request).clone(),
+                                args.message,
+                                args.derivation_path,
+                                args.key_id,
+                                registry_settings.max_ecdsa_queue_size,
+                                &mut state,
+                                rng,
+                                registry_settings.subnet_size,
+                            )
+                            .map_or_else(|err| Some((Err(err), msg.take_cycles())), |()| None),
+                    },
+                },
+                CanisterCall::Ingress(_) => {
+                    self.reject_unexpected_ingress(Ic00Method::SignWithSchnorr)
+                }
+            },
+
+            Ok(Ic00Method::VetKdEncryptedKey) => match &msg {
+                CanisterCall::Request(request) => match VetKdEncryptedKeyArgs::decode(payload) {
+                    Err(err) => Some((Err(candid_error_to_user_error(err)), msg.take_cycles())),
+                    Ok(args) => match get_vetkd_subnet_topology(
+                        &state.metadata.network_topology,
+                        self.own_subnet_id,
+                        &args.key_id,
+                    ) {
+                        Err(err) => Some((Err(err), msg.take_cycles())),
+                        Ok(_) => self
+                            .vetkd_encrypted_key(
+                                (**request).clone(),
+                                args,
+                                registry_settings.max_ecdsa_queue_size,
+                                &mut state,
+                                rng,
+                                registry_settings.subnet_size,
+                            )
+                            .map_or_else(|err| Some((Err(err), msg.take_cycles())), |()| None),
+                    },
+                },
+                CanisterCall::Ingress(_) => {
+                    self.reject_unexpected_ingress(Ic00Method::VetKdEncryptedKey)
+                }
+            },
+
             Ok(Ic00Method::CreateCanister) => {
                 match &mut msg {
                     CanisterCall::Ingress(_) =>
@@ -521,10 +585,10 @@ impl ExecutionEnvironment {
                                             None => CanisterSettingsArgs::default(),
                                             Some(settings) => settings,
                                         };
-                                        let result = match CanisterSettings::try_from(settings) {
+                                        let result = match CanisterSettings::try_from((settings, self.canister_manager.max_memory_allocation())) {
                                             Err(err) => Some((Err(err.into()), cycles)),
                                             Ok(settings) =>
-                                                Some(self.create_canister(*msg.sender(), cycles, settings, registry_settings.max_number_of_canisters, &mut state, registry_settings.subnet_size, round_limits))
+                                                Some(self.create_canister(msg.canister_change_origin(args.get_sender_canister_version()), cycles, settings, registry_settings.max_number_of_canisters, &mut state, registry_settings.subnet_size, round_limits))
                                         };
                                         info!(
                                             self.log,
@@ -547,7 +611,11 @@ impl ExecutionEnvironment {
                     Err(err) => Err(candid_error_to_user_error(err)),
                     Ok(args) => self
                         .canister_manager
-                        .uninstall_code(args.get_canister_id(), *msg.sender(), &mut state)
+                        .uninstall_code(
+                            msg.canister_change_origin(args.get_sender_canister_version()),
+                            args.get_canister_id(),
+                            &mut state,
+                        )
                         .map(|()| EmptyBlob.encode())
                         .map_err(|err| err.into()),
                 };
@@ -562,10 +630,14 @@ impl ExecutionEnvironment {
                         let timer = Timer::start();
 
                         let canister_id = args.get_canister_id();
-                        let result = match CanisterSettings::try_from(args.settings) {
+                        let sender_canister_version = args.get_sender_canister_version();
+                        let result = match CanisterSettings::try_from((
+                            args.settings,
+                            self.canister_manager.max_memory_allocation(),
+                        )) {
                             Err(err) => Err(err.into()),
                             Ok(settings) => self.update_settings(
-                                *msg.sender(),
+                                msg.canister_change_origin(sender_canister_version),
                                 settings,
                                 canister_id,
                                 &mut state,
@@ -618,7 +690,7 @@ impl ExecutionEnvironment {
                     Ok(args) => self
                         .canister_manager
                         .set_controller(
-                            *msg.sender(),
+                            msg.canister_change_origin(args.get_sender_canister_version()),
                             args.get_canister_id(),
                             args.get_new_controller(),
                             &mut state,
@@ -643,6 +715,23 @@ impl ExecutionEnvironment {
                 Some((res, msg.take_cycles()))
             }
 
+            Ok(Ic00Method::CanisterInfo) => match &msg {
+                CanisterCall::Request(_) => {
+                    let res = match CanisterInfoRequest::decode(payload) {
+                        Err(err) => Err(candid_error_to_user_error(err)),
+                        Ok(args) => self.get_canister_info(
+                            args.get_canister_id(),
+                            args.num_requested_changes(),
+                            &mut state,
+                        ),
+                    };
+                    Some((res, msg.take_cycles()))
+                }
+                CanisterCall::Ingress(_) => {
+                    self.reject_unexpected_ingress(Ic00Method::CanisterInfo)
+                }
+            },
+
             Ok(Ic00Method::StartCanister) => {
                 let res = match CanisterIdRecord::decode(payload) {
                     Err(err) => Err(candid_error_to_user_error(err)),
@@ -721,50 +810,74 @@ impl ExecutionEnvironment {
                                 Err(err) => {
                                     Some((Err(candid_error_to_user_error(err)), msg.take_cycles()))
                                 }
-                                Ok(args) => match CanisterHttpRequestContext::try_from((
-                                    state.time(),
-                                    request.as_ref(),
-                                    args,
-                                )) {
-                                    Err(err) => Some((Err(err.into()), msg.take_cycles())),
-                                    Ok(mut canister_http_request_context) => {
-                                        let http_request_fee =
-                                            self.cycles_account_manager.http_request_fee(
-                                                canister_http_request_context.variable_parts_size(),
-                                                canister_http_request_context.max_response_bytes,
-                                                registry_settings.subnet_size,
-                                            );
-                                        if request.payment < http_request_fee {
-                                            let err = Err(UserError::new(
+                                Ok(args) => {
+                                    let is_replicated = args.is_replicated();
+                                    match CanisterHttpRequestContext::try_from((
+                                        state.time(),
+                                        request.as_ref(),
+                                        args,
+                                    )) {
+                                        Err(err) => Some((Err(err.into()), msg.take_cycles())),
+                                        Ok(mut canister_http_request_context) => {
+                                            if !is_replicated {
+                                                canister_http_request_context.replication =
+                                                    designate_http_request_node(&state, rng);
+                                            }
+                                            let http_request_fee =
+                                                match canister_http_request_context.replication {
+                                                    Replication::FullyReplicated => self
+                                                        .cycles_account_manager
+                                                        .http_request_fee(
+                                                            canister_http_request_context
+                                                                .variable_parts_size(),
+                                                            canister_http_request_context
+                                                                .max_response_bytes,
+                                                            registry_settings.subnet_size,
+                                                        ),
+                                                    Replication::NonReplicated(_) => self
+                                                        .cycles_account_manager
+                                                        .non_replicated_http_request_fee(
+                                                            canister_http_request_context
+                                                                .variable_parts_size(),
+                                                            canister_http_request_context
+                                                                .max_response_bytes,
+                                                            registry_settings.subnet_size,
+                                                        ),
+                                                };
+                                            if request.payment < http_request_fee {
+                                                let err = Err(UserError::new(
                                                         ErrorCode::CanisterRejectedMessage,
                                                         format!(
                                                             "http_request request sent with {} cycles, but {} cycles are required.",
                                                             request.payment, http_request_fee
                                                         ),
                                                     ));
-                                            Some((err, msg.take_cycles()))
-                                        } else {
-                                            canister_http_request_context.request.payment -=
-                                                http_request_fee;
-                                            state
-                                                .metadata
-                                                .subnet_metrics
-                                                .consumed_cycles_http_outcalls +=
-                                                NominalCycles::from(http_request_fee);
-                                            state
-                                                .metadata
-                                                .subnet_call_context_manager
-                                                .push_http_request(canister_http_request_context);
-                                            self.metrics.observe_message_with_label(
-                                                &request.method_name,
-                                                timer.elapsed(),
-                                                SUBMITTED_OUTCOME_LABEL.into(),
-                                                SUCCESS_STATUS_LABEL.into(),
-                                            );
-                                            None
+                                                Some((err, msg.take_cycles()))
+                                            } else {
+                                                canister_http_request_context.request.payment -=
+                                                    http_request_fee;
+                                                state
+                                                    .metadata
+                                                    .subnet_metrics
+                                                    .consumed_cycles_http_outcalls +=
+                                                    NominalCycles::from(http_request_fee);
+                                                state
+                                                    .metadata
+                                                    .subnet_call_context_manager
+                                                    .push_http_request(
+                                                        canister_http_request_context,
+                                                    );
+                                                self.metrics.observe_message_with_label(
+                                                    &request.method_name,
+                                                    timer.elapsed(),
+                                                    SUBMITTED_OUTCOME_LABEL.into(),
+                                                    SUCCESS_STATUS_LABEL.into(),
+                                                );
+                                                None
+                                            }
                                         }
                                     }
-                                },
+                                }
                             },
                         }
                     }
@@ -829,6 +942,81 @@ impl ExecutionEnvironment {
                 }
             }
 
+            Ok(Ic00Method::SchnorrPublicKey) => {
+                let cycles = msg.take_cycles();
+                match &msg {
+                    CanisterCall::Request(request) => {
+                        let res = match SchnorrPublicKeyArgs::decode(request.method_payload()) {
+                            Err(err) => Some(Err(candid_error_to_user_error(err))),
+                            Ok(args) => match get_master_schnorr_public_key(
+                                schnorr_subnet_public_keys,
+                                self.own_subnet_id,
+                                &args.key_id,
+                            ) {
+                                Err(err) => Some(Err(err)),
+                                Ok(pubkey) => {
+                                    let canister_id = match args.canister_id {
+                                        Some(id) => id.into(),
+                                        None => *msg.sender(),
+                                    };
+                                    Some(
+                                        self.get_schnorr_public_key(
+                                            pubkey,
+                                            canister_id,
+                                            args.derivation_path,
+                                        )
+                                        .map(|res| res.encode()),
+                                    )
+                                }
+                            },
+                        };
+                        res.map(|res| (res, cycles))
+                    }
+                    CanisterCall::Ingress(_) => {
+                        self.reject_unexpected_ingress(Ic00Method::SchnorrPublicKey)
+                    }
+                }
+            }
+
+            Ok(Ic00Method::VetKdPublicKey) => {
+                let cycles = msg.take_cycles();
+                match &msg {
+                    CanisterCall::Request(request) => {
+                        let res = match VetKdPublicKeyArgs::decode(request.method_payload()) {
+                            Err(err) => Some(Err(candid_error_to_user_error(err))),
+                            Ok(args) => match get_master_vetkd_public_key(
+                                &state.metadata.network_topology,
+                                self.own_subnet_id,
+                                &args.key_id,
+                            ) {
+                                Err(err) => Some(Err(err)),
+                                Ok(pubkey) => {
+                                    let canister_id = match args.canister_id {
+                                        Some(id) => id.into(),
+                                        None => *msg.sender(),
+                                    };
+                                    Some(
+                                        self.get_vetkd_public_key(
+                                            &pubkey,
+                                            canister_id,
+                                            vetkd_derivation_path(
+                                                &args.key_id,
+                                                args.derivation_path,
+                                            ),
+                                        )
+                                        .map(|res| res.encode()),
+                                    )
+                                }
+                            },
+                        };
+                        res.map(|res| (res, cycles))
+                    }
+                    CanisterCall::Ingress(_) => {
+                        self.reject_unexpected_ingress(Ic00Method::VetKdPublicKey)
+                    }
+                }
+            }
+
             Ok(Ic00Method::ComputeInitialEcdsaDealings) => {
                 let cycles = msg.take_cycles();
                 match &msg {
@@ -866,11 +1054,15 @@ impl ExecutionEnvironment {
                     Err(err) => Err(candid_error_to_user_error(err)),
                     Ok(args) => {
                         let cycles_amount = args.to_u128();
-                        match CanisterSettings::try_from(args.settings) {
+                        let sender_canister_version = args.get_sender_canister_version();
+                        match CanisterSettings::try_from((
+                            args.settings,
+                            self.canister_manager.max_memory_allocation(),
+                        )) {
                             Ok(settings) => self
                                 .canister_manager
                                 .create_canister_with_cycles(
-                                    *msg.sender(),
+                                    msg.canister_change_origin(sender_canister_version),
                                     cycles_amount,
                                     settings,
                                     args.specified_id,
@@ -938,6 +1130,135 @@ impl ExecutionEnvironment {
             }
             .map(|payload| (payload, msg.take_cycles())),
 
+            Ok(Ic00Method::TakeCanisterSnapshot) => {
+                let res = match TakeCanisterSnapshotArgs::decode(payload) {
+                    Err(err) => Err(candid_error_to_user_error(err)),
+                    Ok(args) => self
+                        .canister_manager
+                        .take_canister_snapshot(
+                            *msg.sender(),
+                            args.get_canister_id(),
+                            args.replace_snapshot().map(|id| id.to_vec()),
+                            &mut state,
+                            round_limits,
+                            registry_settings.subnet_size,
+                        )
+                        .map(|response| response.encode())
+                        .map_err(|err| err.into()),
+                };
+                Some((res, msg.take_cycles()))
+            }
+
+            Ok(Ic00Method::LoadCanisterSnapshot) => {
+                let res = match LoadCanisterSnapshotArgs::decode(payload) {
+                    Err(err) => Err(candid_error_to_user_error(err)),
+                    Ok(args) => self
+                        .canister_manager
+                        .load_canister_snapshot(
+                            msg.canister_change_origin(args.get_sender_canister_version()),
+                            args.get_canister_id(),
+                            args.snapshot_id().to_vec(),
+                            &mut state,
+                            round_limits,
+                            registry_settings.subnet_size,
+                        )
+                        .map(|()| EmptyBlob.encode())
+                        .map_err(|err| err.into()),
+                };
+                Some((res, msg.take_cycles()))
+            }
+
+            Ok(Ic00Method::ListCanisterSnapshots) => {
+                let res = match CanisterIdRecord::decode(payload) {
+                    Err(err) => Err(candid_error_to_user_error(err)),
+                    Ok(args) => self
+                        .canister_manager
+                        .list_canister_snapshots(*msg.sender(), args.get_canister_id(), &state)
+                        .map(|snapshots| Encode!(&snapshots).unwrap())
+                        .map_err(|err| err.into()),
+                };
+                Some((res, msg.take_cycles()))
+            }
+
+            Ok(Ic00Method::DeleteCanisterSnapshot) => {
+                let res = match DeleteCanisterSnapshotArgs::decode(payload) {
+                    Err(err) => Err(candid_error_to_user_error(err)),
+                    Ok(args) => self
+                        .canister_manager
+                        .delete_canister_snapshot(
+                            *msg.sender(),
+                            args.get_canister_id(),
+                            args.snapshot_id().to_vec(),
+                            &mut state,
+                            round_limits,
+                        )
+                        .map(|()| EmptyBlob.encode())
+                        .map_err(|err| err.into()),
+                };
+                Some((res, msg.take_cycles()))
+            }
+
+            Ok(Ic00Method::UploadChunk) => {
+                let res = match UploadChunkArgs::decode(payload) {
+                    Err(err) => Err(candid_error_to_user_error(err)),
+                    Ok(args) => {
+                        let canister_id = args.get_canister_id();
+                        self.canister_manager
+                            .upload_chunk(
+                                *msg.sender(),
+                                canister_id,
+                                args.take_chunk(),
+                                &mut state,
+                                round_limits,
+                                registry_settings.subnet_size,
+                            )
+                            .map(|hash| hash.encode())
+                            .map_err(|err| err.into())
+                    }
+                };
+                Some((res, msg.take_cycles()))
+            }
+
+            Ok(Ic00Method::ClearChunkStore) => {
+                let res = match CanisterIdRecord::decode(payload) {
+                    Err(err) => Err(candid_error_to_user_error(err)),
+                    Ok(args) => self
+                        .canister_manager
+                        .clear_chunk_store(
+                            *msg.sender(),
+                            args.get_canister_id(),
+                            &mut state,
+                            round_limits,
+                        )
+                        .map(|()| EmptyBlob.encode())
+                        .map_err(|err| err.into()),
+                };
+                Some((res, msg.take_cycles()))
+            }
+
+            Ok(Ic00Method::StoredChunks) => {
+                let res = match CanisterIdRecord::decode(payload) {
+                    Err(err) => Err(candid_error_to_user_error(err)),
+                    Ok(args) => self
+                        .canister_manager
+                        .stored_chunks(*msg.sender(), args.get_canister_id(), &state)
+                        .map(|hashes| Encode!(&hashes).unwrap())
+                        .map_err(|err| err.into()),
+                };
+                Some((res, msg.take_cycles()))
+            }
+
+            Ok(Ic00Method::FetchCanisterLogs) => Some((
+                Err(UserError::new(
+                    ErrorCode::CanisterRejectedMessage,
+                    format!(
+                        "ic00 method {} can only be called as a query",
+                        Ic00Method::FetchCanisterLogs
+                    ),
+                )),
+                msg.take_cycles(),
+            )),
+
             Ok(Ic00Method::BitcoinGetBalance)
             | Ok(Ic00Method::BitcoinGetUtxos)
             | Ok(Ic00Method::BitcoinSendTransaction)
@@ -978,8 +1299,9 @@ impl ExecutionEnvironment {
                 // responded to (which currently happens in the scheduler).
                 //
                 // This scenario also happens in the case of
-                // Ic00Method::SetupInitialDKG, Ic00Method::HttpRequest, and
-                // Ic00Method::SignWithECDSA. The request is saved and the
+                // Ic00Method::SetupInitialDKG, Ic00Method::HttpRequest,
+                // Ic00Method::SignWithECDSA, Ic00Method::SignWithSchnorr, and
+                // Ic00Method::VetKdEncryptedKey. The request is saved and the
                 // response from consensus is handled separately.
                 state
             }
@@ -1166,7 +1488,7 @@ impl ExecutionEnvironment {
     /// Returns the maximum amount of memory that can be utilized by a single
     /// canister.
     pub fn max_canister_memory_size(&self) -> NumBytes {
-        self.config.max_canister_memory_size
+        self.config.canister_memory_limit()
     }
 
     /// Returns the subnet memory capacity.
@@ -1184,7 +1506,7 @@ impl ExecutionEnvironment {
     ) -> ExecutionParameters {
         ExecutionParameters {
             instruction_limits,
-            canister_memory_limit: canister.memory_limit(self.config.max_canister_memory_size),
+            canister_memory_limit: canister.memory_limit(self.config.canister_memory_limit()),
             compute_allocation: canister.scheduler_state.compute_allocation,
             subnet_type: self.own_subnet_type,
             execution_mode,
@@ -1193,7 +1515,7 @@ impl ExecutionEnvironment {
 
     fn create_canister(
         &self,
-        sender: PrincipalId,
+        origin: CanisterChangeOrigin,
         cycles: Cycles,
         settings: CanisterSettings,
         max_number_of_canisters: u64,
@@ -1201,10 +1523,10 @@ impl ExecutionEnvironment {
         subnet_size: usize,
         round_limits: &mut RoundLimits,
     ) -> (Result<Vec<u8>, UserError>, Cycles) {
-        match state.find_subnet_id(sender) {
+        match state.find_subnet_id(origin.origin()) {
             Ok(sender_subnet_id) => {
                 let (res, cycles) = self.canister_manager.create_canister(
-                    sender,
+                    origin,
                     sender_subnet_id,
                     cycles,
                     settings,
@@ -1225,15 +1547,16 @@ impl ExecutionEnvironment {
 
     fn update_settings(
         &self,
-        sender: PrincipalId,
+        origin: CanisterChangeOrigin,
         settings: CanisterSettings,
         canister_id: CanisterId,
         state: &mut ReplicatedState,
         round_limits: &mut RoundLimits,
     ) -> Result<Vec<u8>, UserError> {
+        let timestamp = state.time();
         let canister = get_canister_mut(canister_id, state)?;
         self.canister_manager
-            .update_settings(sender, settings, canister, round_limits)
+            .update_settings(timestamp, origin, settings, canister, round_limits)
             .map(|()| EmptyBlob.encode())
             .map_err(|err| err.into())
     }
@@ -1305,6 +1628,19 @@ impl ExecutionEnvironment {
             .map_err(|err| err.into())
     }
 
+    fn get_canister_info(
+        &self,
+        canister_id: CanisterId,
+        num_requested_changes: Option<u64>,
+        state: &mut ReplicatedState,
+    ) -> Result<Vec<u8>, UserError> {
+        let canister = get_canister_mut(canister_id, state)?;
+        Ok(self
+            .canister_manager
+            .get_canister_info(num_requested_changes, canister)
+            .encode())
+    }
+
     fn stop_canister(
         &self,
         canister_id: CanisterId,
@@ -1552,6 +1888,7 @@ impl ExecutionEnvironment {
                     originator: req.sender,
                     respondent: subnet_id_as_canister_id,
                     originator_reply_callback: req.sender_reply_callback,
+                    deadline: req.deadline,
                     refund,
                     response_payload: payload,
                 };
@@ -1630,6 +1967,7 @@ impl ExecutionEnvironment {
                         originator: sender,
                         respondent: subnet_id_as_canister_id,
                         originator_reply_callback: reply_callback,
+                        deadline: NO_DEADLINE,
                         refund: cycles,
                         response_payload: Payload::Reject(RejectContext {
                             code: RejectCode::CanisterReject,
@@ -1780,6 +2118,186 @@ impl ExecutionEnvironment {
         Ok(())
     }
 
+    fn get_schnorr_public_key(
+        &self,
+        subnet_public_key: &MasterEcdsaPublicKey,
+        principal_id: PrincipalId,
+        derivation_path: Vec<Vec<u8>>,
+    ) -> Result<SchnorrPublicKeyResponse, UserError> {
+        let _ = CanisterId::new(principal_id).map_err(|err| {
+            UserError::new(
+                ErrorCode::CanisterContractViolation,
+                format!("Not a canister id: {}", err),
+            )
+        })?;
+        let path = ExtendedDerivationPath {
+            caller: principal_id,
+            derivation_path,
+        };
+        derive_tecdsa_public_key(subnet_public_key, &path)
+            .map_err(|err| UserError::new(ErrorCode::CanisterRejectedMessage, format!("{}", err)))
+            .map(|res| SchnorrPublicKeyResponse {
+                public_key: res.public_key,
+                chain_code: res.chain_key,
+            })
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn sign_with_schnorr(
+        &self,
+        mut request: Request,
+        message: Vec<u8>,
+        derivation_path: Vec<Vec<u8>>,
+        key_id: SchnorrKeyId,
+        max_queue_size: u32,
+        state: &mut ReplicatedState,
+        rng: &mut dyn RngCore,
+        subnet_size: usize,
+    ) -> Result<(), UserError> {
+        // If the request isn't from the NNS, then we need to charge for it.
+        // Consensus will return any remaining cycles.
+        let source_subnet = state
+            .metadata
+            .network_topology
+            .routing_table
+            .route(request.sender.get());
+        if source_subnet != Some(state.metadata.network_topology.nns_subnet_id) {
+            let signature_fee = self
+                .cycles_account_manager
+                .schnorr_signature_fee(subnet_size);
+            if request.payment < signature_fee {
+                return Err(UserError::new(
+                    ErrorCode::CanisterRejectedMessage,
+                    format!(
+                        "sign_with_schnorr request sent with {} cycles, but {} cycles are required.",
+                        request.payment, signature_fee
+                    ),
+                ));
+            } else {
+                request.payment -= signature_fee;
+                state
+                    .metadata
+                    .subnet_metrics
+                    .consumed_cycles_schnorr_outcalls += NominalCycles::from(signature_fee);
+            }
+        }
+
+        let mut pseudo_random_id = [0u8; 32];
+        rng.fill_bytes(&mut pseudo_random_id);
+
+        info!(
+            self.log,
+            "Assigned the pseudo_random_id {:?} to the new sign_with_schnorr request from {:?}",
+            pseudo_random_id,
+            request.sender()
+        );
+        state
+            .metadata
+            .subnet_call_context_manager
+            .push_sign_with_schnorr_request(
+                SignWithSchnorrContext {
+                    request,
+                    key_id,
+                    message,
+                    derivation_path,
+                    pseudo_random_id,
+                    batch_time: state.metadata.batch_time,
+                },
+                max_queue_size,
+            )?;
+        Ok(())
+    }
+
+    fn get_vetkd_public_key(
+        &self,
+        subnet_public_key: &ThresholdSigPublicKey,
+        principal_id: PrincipalId,
+        derivation_path: Vec<Vec<u8>>,
+    ) -> Result<VetKdPublicKeyResponse, UserError> {
+        let _ = CanisterId::new(principal_id).map_err(|err| {
+            UserError::new(
+                ErrorCode::CanisterContractViolation,
+                format!("Not a canister id: {}", err),
+            )
+        })?;
+        let path = ExtendedDerivationPath {
+            caller: principal_id,
+            derivation_path,
+        };
+        derive_vetkd_public_key(subnet_public_key, &path)
+            .map_err(|err| UserError::new(ErrorCode::CanisterRejectedMessage, format!("{:?}", err)))
+            .map(|public_key| VetKdPublicKeyResponse { public_key })
+    }
+
+    fn vetkd_encrypted_key(
+        &self,
+        mut request: Request,
+        args: VetKdEncryptedKeyArgs,
+        max_queue_size: u32,
+        state: &mut ReplicatedState,
+        rng: &mut dyn RngCore,
+        subnet_size: usize,
+    ) -> Result<(), UserError> {
+        if !is_valid_transport_public_key(&args.transport_public_key) {
+            return Err(UserError::new(
+                ErrorCode::CanisterRejectedMessage,
+                "vetkd_encrypted_key request sent with an invalid transport public key."
+                    .to_string(),
+            ));
+        }
+
+        // If the request isn't from the NNS, then we need to charge for it.
+        // vetKD keys are priced like threshold ECDSA signatures.
+        let source_subnet = state
+            .metadata
+            .network_topology
+            .routing_table
+            .route(request.sender.get());
+        if source_subnet != Some(state.metadata.network_topology.nns_subnet_id) {
+            let fee = self.cycles_account_manager.ecdsa_signature_fee(subnet_size);
+            if request.payment < fee {
+                return Err(UserError::new(
+                    ErrorCode::CanisterRejectedMessage,
+                    format!(
+                        "vetkd_encrypted_key request sent with {} cycles, but {} cycles are required.",
+                        request.payment, fee
+                    ),
+                ));
+            } else {
+                request.payment -= fee;
+                state.metadata.subnet_metrics.consumed_cycles_ecdsa_outcalls +=
+                    NominalCycles::from(fee);
+            }
+        }
+
+        let mut pseudo_random_id = [0u8; 32];
+        rng.fill_bytes(&mut pseudo_random_id);
+
+        info!(
+            self.log,
+            "Assigned the pseudo_random_id {:?} to the new vetkd_encrypted_key request from {:?}",
+            pseudo_random_id,
+            request.sender()
+        );
+        let derivation_path = vetkd_derivation_path(&args.key_id, args.derivation_path);
+        state
+            .metadata
+            .subnet_call_context_manager
+            .push_vetkd_request(
+                VetKdContext {
+                    request,
+                    key_id: args.key_id,
+                    derivation_path,
+                    input: args.input,
+                    transport_public_key: args.transport_public_key,
+                    pseudo_random_id,
+                    batch_time: state.metadata.batch_time,
+                },
+                max_queue_size,
+            )?;
+        Ok(())
+    }
+
     fn compute_initial_ecdsa_dealings(
         &self,
         state: &mut ReplicatedState,
@@ -1819,7 +2337,7 @@ impl ExecutionEnvironment {
     /// exceeds the given slice limit.
     ///
     /// Precondition:
-    /// - The given message is an `install_code` message.
+    /// - The given message is an `install_code` or `install_chunked_code` message.
     /// - The canister does not have any paused execution in its task queue.
     ///
     /// Postcondition:
@@ -1839,11 +2357,28 @@ impl ExecutionEnvironment {
         // A helper function to make error handling more compact using `?`.
         fn decode_input_and_take_canister(
             msg: &CanisterCall,
+            canister_manager: &CanisterManager,
             state: &mut ReplicatedState,
         ) -> Result<(InstallCodeContext, CanisterState), UserError> {
             let payload = msg.method_payload();
-            let args = InstallCodeArgs::decode(payload).map_err(candid_error_to_user_error)?;
-            let install_context = InstallCodeContext::try_from((*msg.sender(), args))?;
+            let install_context = match Ic00Method::from_str(msg.method_name()) {
+                Ok(Ic00Method::InstallChunkedCode) => {
+                    let args = InstallChunkedCodeArgs::decode(payload)
+                        .map_err(candid_error_to_user_error)?;
+                    let origin = msg.canister_change_origin(args.get_sender_canister_version());
+                    canister_manager.install_chunked_code_context(origin, args, state)?
+                }
+                _ => {
+                    let args =
+                        InstallCodeArgs::decode(payload).map_err(candid_error_to_user_error)?;
+                    let origin = msg.canister_change_origin(args.get_sender_canister_version());
+                    InstallCodeContext::try_from((
+                        origin,
+                        args,
+                        canister_manager.max_memory_allocation(),
+                    ))?
+                }
+            };
             let canister = state
                 .take_canister_state(&install_context.canister_id)
                 .ok_or(CanisterManagerError::CanisterNotFound(
@@ -1855,16 +2390,16 @@ impl ExecutionEnvironment {
         // Start logging execution time for `install_code`.
         let timer = Timer::start();
 
-        let (install_context, old_canister) = match decode_input_and_take_canister(&msg, &mut state)
-        {
-            Ok(result) => result,
-            Err(err) => {
-                let refund = msg.take_cycles();
-                let state =
-                    self.finish_subnet_message_execution(state, msg, Err(err), refund, timer);
-                return (state, Some(NumInstructions::from(0)));
-            }
-        };
+        let (install_context, old_canister) =
+            match decode_input_and_take_canister(&msg, &self.canister_manager, &mut state) {
+                Ok(result) => result,
+                Err(err) => {
+                    let refund = msg.take_cycles();
+                    let state =
+                        self.finish_subnet_message_execution(state, msg, Err(err), refund, timer);
+                    return (state, Some(NumInstructions::from(0)));
+                }
+            };
 
         // Check the precondition.
         match old_canister.next_execution() {
@@ -2434,6 +2969,24 @@ pub fn execute_canister(
     )
 }
 
+/// Picks the node of the own subnet that makes a non-replicated canister http
+/// request. Falls back to a fully replicated request if the own subnet's nodes
+/// are unknown.
+fn designate_http_request_node(state: &ReplicatedState, rng: &mut dyn RngCore) -> Replication {
+    let nodes: Vec<NodeId> = state
+        .metadata
+        .network_topology
+        .subnets
+        .get(&state.metadata.own_subnet_id)
+        .map(|topology| topology.nodes.keys().copied().collect())
+        .unwrap_or_default();
+    if nodes.is_empty() {
+        return Replication::FullyReplicated;
+    }
+    let index = (rng.next_u64() % nodes.len() as u64) as usize;
+    Replication::NonReplicated(nodes[index])
+}
+
 fn get_master_ecdsa_public_key<'a>(
     ecdsa_subnet_public_keys: &'a BTreeMap<EcdsaKeyId, MasterEcdsaPublicKey>,
     subnet_id: SubnetId,
@@ -2447,3 +3000,60 @@ fn get_master_ecdsa_public_key<'a>(
         Some(master_key) => Ok(master_key),
     }
 }
+
+fn get_master_schnorr_public_key<'a>(
+    schnorr_subnet_public_keys: &'a BTreeMap<SchnorrKeyId, MasterEcdsaPublicKey>,
+    subnet_id: SubnetId,
+    key_id: &SchnorrKeyId,
+) -> Result<&'a MasterEcdsaPublicKey, UserError> {
+    match schnorr_subnet_public_keys.get(key_id) {
+        None => Err(UserError::new(
+            ErrorCode::CanisterRejectedMessage,
+            format!("Subnet {} does not hold Schnorr key {}.", subnet_id, key_id),
+        )),
+        Some(master_key) => Ok(master_key),
+    }
+}
+
+/// Returns the topology of the given subnet if it holds the requested vetKD key.
+fn get_vetkd_subnet_topology<'a>(
+    network_topology: &'a NetworkTopology,
+    subnet_id: SubnetId,
+    key_id: &VetKdKeyId,
+) -> Result<&'a SubnetTopology, UserError> {
+    network_topology
+        .subnets
+        .get(&subnet_id)
+        .filter(|topology| topology.vetkd_keys_held.contains(key_id))
+        .ok_or_else(|| {
+            UserError::new(
+                ErrorCode::CanisterRejectedMessage,
+                format!("Subnet {} does not hold vetKD key {}.", subnet_id, key_id),
+            )
+        })
+}
+
+/// Returns the derivation path of a vetKD key. All vetKD keys of a subnet are
+/// derived from the same master key, so the key id is prepended to the path
+/// requested by the caller to make the keys of different key ids independent.
+fn vetkd_derivation_path(key_id: &VetKdKeyId, derivation_path: Vec<Vec<u8>>) -> Vec<Vec<u8>> {
+    std::iter::once(key_id.to_string().into_bytes())
+        .chain(derivation_path)
+        .collect()
+}
+
+/// Returns the vetKD master public key of the given subnet, which is the
+/// subnet's threshold public key, if the subnet holds the requested key.
+fn get_master_vetkd_public_key(
+    network_topology: &NetworkTopology,
+    subnet_id: SubnetId,
+    key_id: &VetKdKeyId,
+) -> Result<ThresholdSigPublicKey, UserError> {
+    let subnet_topology = get_vetkd_subnet_topology(network_topology, subnet_id, key_id)?;
+    parse_threshold_sig_key_from_der(&subnet_topology.public_key).map_err(|err| {
+        UserError::new(
+            ErrorCode::CanisterRejectedMessage,
+            format!("Invalid public key of subnet {}: {}", subnet_id, err),
+        )
+    })
+}
@@ -8,7 +8,7 @@ use ic_ic00_types::{
     self as ic00, CanisterHttpRequestArgs, CanisterIdRecord, CanisterStatusResultV2,
     CanisterStatusType, EcdsaCurve, EcdsaKeyId, EmptyBlob, HttpMethod, Method,
     Payload as Ic00Payload, ProvisionalCreateCanisterWithCyclesArgs, ProvisionalTopUpCanisterArgs,
-    TransformContext, TransformFunc, IC_00,
+    SchnorrAlgorithm, SchnorrKeyId, TransformContext, TransformFunc, VetKdCurve, VetKdKeyId, IC_00,
 };
 use ic_registry_routing_table::canister_id_into_u64;
 use ic_registry_routing_table::CanisterIdRange;
@@ -25,16 +25,24 @@ use ic_test_utilities_execution_environment::{
 use ic_test_utilities_metrics::{fetch_histogram_vec_count, metric_vec};
 use ic_types::canister_http::Transform;
 use ic_types::{
-    canister_http::CanisterHttpMethod,
+    canister_http::{CanisterHttpMethod, Replication},
     ingress::{IngressState, IngressStatus, WasmResult},
     messages::{
         CallbackId, Payload, RejectContext, RequestOrResponse, Response, MAX_RESPONSE_COUNT_BYTES,
+        NO_DEADLINE,
     },
     CanisterId, Cycles, PrincipalId, RegistryVersion,
 };
 use ic_types_test_utils::ids::{canister_test_id, node_test_id, subnet_test_id, user_test_id};
 use ic_universal_canister::{call_args, wasm};
 
+#[cfg(test)]
+mod canister_history;
+#[cfg(test)]
+mod canister_logging;
+#[cfg(test)]
+mod canister_snapshots;
+
 #[cfg(test)]
 mod canister_task;
 
@@ -42,6 +50,8 @@ mod canister_task;
 mod compilation;
 #[cfg(test)]
 mod orthogonal_persistence;
+#[cfg(test)]
+mod wasm_chunk_store;
 
 const BALANCE_EPSILON: Cycles = Cycles::new(10_000_000);
 const ONE_GIB: i64 = 1 << 30;
@@ -1099,6 +1109,7 @@ fn setup_initial_dkg_sender_not_on_nns() {
             originator: other_canister,
             respondent: CanisterId::from(own_subnet),
             originator_reply_callback: CallbackId::new(0),
+            deadline: NO_DEADLINE,
             refund: test.canister_creation_fee(),
             response_payload: Payload::Reject(RejectContext {
                 code: RejectCode::CanisterError,
@@ -1461,6 +1472,7 @@ fn execute_canister_http_request() {
             }),
             context: transform_context.clone(),
         }),
+        is_replicated: None,
     };
 
     // Create request to HTTP_REQUEST method.
@@ -1505,6 +1517,61 @@ fn execute_canister_http_request() {
     );
 }
 
+#[test]
+fn execute_non_replicated_canister_http_request() {
+    let own_subnet = subnet_test_id(1);
+    let caller_canister = canister_test_id(10);
+    let mut test = ExecutionTestBuilder::new()
+        .with_own_subnet_id(own_subnet)
+        .with_caller(own_subnet, caller_canister)
+        .build();
+    test.state_mut().metadata.own_subnet_features.http_requests = true;
+
+    let response_size_limit = 1000u64;
+    let args = CanisterHttpRequestArgs {
+        url: "https://".to_string(),
+        max_response_bytes: Some(response_size_limit),
+        headers: Vec::new(),
+        body: Some(vec![0; 100]),
+        method: HttpMethod::GET,
+        transform: None,
+        is_replicated: Some(false),
+    };
+
+    let payment = Cycles::new(1_000_000_000);
+    test.inject_call_to_ic00(Method::HttpRequest, args.encode(), payment);
+    test.execute_all();
+
+    let http_request_context = test
+        .state()
+        .metadata
+        .subnet_call_context_manager
+        .canister_http_request_contexts
+        .get(&CallbackId::from(0))
+        .unwrap()
+        .clone();
+
+    // The designated node must be a node of the own subnet.
+    let own_nodes = &test.state().metadata.network_topology.subnets[&own_subnet].nodes;
+    match http_request_context.replication {
+        Replication::NonReplicated(node_id) => assert!(own_nodes.contains_key(&node_id)),
+        Replication::FullyReplicated => panic!("Expected a non-replicated request"),
+    }
+
+    // The caller is charged the non-replicated fee, which is cheaper.
+    let fee = test.non_replicated_http_request_fee(
+        http_request_context.variable_parts_size(),
+        Some(NumBytes::from(response_size_limit)),
+    );
+    assert!(
+        fee < test.http_request_fee(
+            http_request_context.variable_parts_size(),
+            Some(NumBytes::from(response_size_limit)),
+        )
+    );
+    assert_eq!(http_request_context.request.payment, payment - fee);
+}
+
 #[test]
 fn execute_canister_http_request_disabled() {
     let own_subnet = subnet_test_id(1);
@@ -1530,6 +1597,7 @@ fn execute_canister_http_request_disabled() {
             }),
             context: vec![0, 1, 2],
         }),
+        is_replicated: None,
     };
 
     // Create request to HTTP_REQUEST method.
@@ -1801,6 +1869,289 @@ fn ecdsa_signature_with_unknown_key_rejected() {
     );
 }
 
+fn make_schnorr_key(name: &str) -> SchnorrKeyId {
+    SchnorrKeyId {
+        algorithm: SchnorrAlgorithm::Bip340Secp256k1,
+        name: name.to_string(),
+    }
+}
+
+#[test]
+fn schnorr_signature_fee_charged() {
+    let fee = 1_000_000;
+    let payment = 2_000_000;
+    let schnorr_key = make_schnorr_key("bip340");
+    let mut test = ExecutionTestBuilder::new()
+        .with_subnet_type(SubnetType::System)
+        .with_own_subnet_id(subnet_test_id(1))
+        .with_nns_subnet_id(subnet_test_id(2))
+        .with_schnorr_signature_fee(fee)
+        .with_schnorr_key(schnorr_key.clone())
+        .build();
+    let canister_id = test.universal_canister().unwrap();
+    let ecdsa_cycles_before = test
+        .state()
+        .metadata
+        .subnet_metrics
+        .consumed_cycles_ecdsa_outcalls;
+    let args = ic00::SignWithSchnorrArgs {
+        message: vec![1; 64],
+        derivation_path: vec![],
+        key_id: schnorr_key,
+    };
+    let run = wasm()
+        .call_with_cycles(
+            ic00::IC_00,
+            Method::SignWithSchnorr,
+            call_args()
+                .other_side(args.encode())
+                .on_reject(wasm().reject_message().reject()),
+            (0, payment),
+        )
+        .build();
+
+    let (_, ingress_status) = test.ingress_raw(canister_id, "update", run);
+    assert_eq!(
+        ingress_status,
+        IngressStatus::Known {
+            receiver: canister_id.get(),
+            user_id: test.user_id(),
+            time: test.time(),
+            state: IngressState::Processing,
+        }
+    );
+    let (_, context) = test
+        .state()
+        .metadata
+        .subnet_call_context_manager
+        .sign_with_schnorr_contexts
+        .iter()
+        .next()
+        .unwrap();
+    assert_eq!(context.request.payment.get(), payment as u128 - fee);
+    assert_eq!(context.message, vec![1; 64]);
+    let subnet_metrics = &test.state().metadata.subnet_metrics;
+    assert_eq!(
+        subnet_metrics.consumed_cycles_schnorr_outcalls,
+        NominalCycles::from(fee)
+    );
+    assert_eq!(
+        subnet_metrics.consumed_cycles_ecdsa_outcalls,
+        ecdsa_cycles_before
+    );
+}
+
+#[test]
+fn schnorr_signature_rejected_without_fee() {
+    let fee = 2_000_000;
+    let schnorr_key = make_schnorr_key("bip340");
+    let mut test = ExecutionTestBuilder::new()
+        .with_subnet_type(SubnetType::System)
+        .with_own_subnet_id(subnet_test_id(1))
+        .with_nns_subnet_id(subnet_test_id(2))
+        .with_schnorr_signature_fee(fee)
+        .with_schnorr_key(schnorr_key.clone())
+        .build();
+    let canister_id = test.universal_canister().unwrap();
+    let args = ic00::SignWithSchnorrArgs {
+        message: vec![1; 32],
+        derivation_path: vec![],
+        key_id: schnorr_key,
+    };
+    let run = wasm()
+        .call_with_cycles(
+            ic00::IC_00,
+            Method::SignWithSchnorr,
+            call_args()
+                .other_side(args.encode())
+                .on_reject(wasm().reject_message().reject()),
+            (0, fee as u64 - 1),
+        )
+        .build();
+
+    let result = test.ingress(canister_id, "update", run).unwrap();
+    assert_eq!(
+        WasmResult::Reject(
+            "sign_with_schnorr request sent with 1_999_999 cycles, but 2_000_000 cycles are required."
+                .into()
+        ),
+        result
+    );
+}
+
+fn make_vetkd_key(name: &str) -> VetKdKeyId {
+    VetKdKeyId {
+        curve: VetKdCurve::Bls12381G2,
+        name: name.to_string(),
+    }
+}
+
+/// The compressed encoding of the generator of BLS12-381 G1, which is a valid
+/// vetKD transport public key.
+fn valid_transport_public_key() -> Vec<u8> {
+    hex::decode("97f1d3a73197d7942695638c4fa9ac0fc3688c4f9774b905a14e3a3f171bac586c55e83ff97a1aeffb3af00adb22c6bb").unwrap()
+}
+
+fn vetkd_encrypted_key_call(
+    key_id: VetKdKeyId,
+    transport_public_key: Vec<u8>,
+    payment: u64,
+) -> Vec<u8> {
+    let args = ic00::VetKdEncryptedKeyArgs {
+        derivation_path: vec![vec![0; 10]],
+        input: vec![1; 32],
+        key_id,
+        transport_public_key,
+    };
+    wasm()
+        .call_with_cycles(
+            ic00::IC_00,
+            Method::VetKdEncryptedKey,
+            call_args()
+                .other_side(args.encode())
+                .on_reject(wasm().reject_message().reject()),
+            (0, payment),
+        )
+        .build()
+}
+
+#[test]
+fn vetkd_encrypted_key_fee_charged() {
+    let fee = 1_000_000;
+    let payment = 2_000_000;
+    let vetkd_key = make_vetkd_key("bls12_381_g2");
+    let mut test = ExecutionTestBuilder::new()
+        .with_subnet_type(SubnetType::System)
+        .with_own_subnet_id(subnet_test_id(1))
+        .with_nns_subnet_id(subnet_test_id(2))
+        .with_ecdsa_signature_fee(fee)
+        .with_vetkd_key(vetkd_key.clone())
+        .build();
+    let canister_id = test.universal_canister().unwrap();
+    let run = vetkd_encrypted_key_call(vetkd_key, valid_transport_public_key(), payment);
+
+    let (_, ingress_status) = test.ingress_raw(canister_id, "update", run);
+    assert_eq!(
+        ingress_status,
+        IngressStatus::Known {
+            receiver: canister_id.get(),
+            user_id: test.user_id(),
+            time: test.time(),
+            state: IngressState::Processing,
+        }
+    );
+    let (_, context) = test
+        .state()
+        .metadata
+        .subnet_call_context_manager
+        .vetkd_contexts
+        .iter()
+        .next()
+        .unwrap();
+    assert_eq!(context.request.payment.get(), payment as u128 - fee);
+    assert_eq!(context.input, vec![1; 32]);
+    assert_eq!(context.transport_public_key, valid_transport_public_key());
+}
+
+#[test]
+fn vetkd_encrypted_key_derivation_path_starts_with_key_id() {
+    let vetkd_key = make_vetkd_key("bls12_381_g2");
+    let mut test = ExecutionTestBuilder::new()
+        .with_subnet_type(SubnetType::System)
+        .with_own_subnet_id(subnet_test_id(1))
+        .with_nns_subnet_id(subnet_test_id(2))
+        .with_vetkd_key(vetkd_key.clone())
+        .build();
+    let canister_id = test.universal_canister().unwrap();
+    let run = vetkd_encrypted_key_call(
+        vetkd_key.clone(),
+        valid_transport_public_key(),
+        1_000_000_000,
+    );
+
+    test.ingress_raw(canister_id, "update", run);
+    let (_, context) = test
+        .state()
+        .metadata
+        .subnet_call_context_manager
+        .vetkd_contexts
+        .iter()
+        .next()
+        .unwrap();
+    assert_eq!(
+        context.derivation_path,
+        vec![vetkd_key.to_string().into_bytes(), vec![0; 10]]
+    );
+}
+
+#[test]
+fn vetkd_encrypted_key_rejected_without_fee() {
+    let fee = 2_000_000;
+    let vetkd_key = make_vetkd_key("bls12_381_g2");
+    let mut test = ExecutionTestBuilder::new()
+        .with_subnet_type(SubnetType::System)
+        .with_own_subnet_id(subnet_test_id(1))
+        .with_nns_subnet_id(subnet_test_id(2))
+        .with_ecdsa_signature_fee(fee)
+        .with_vetkd_key(vetkd_key.clone())
+        .build();
+    let canister_id = test.universal_canister().unwrap();
+    let run = vetkd_encrypted_key_call(vetkd_key, valid_transport_public_key(), fee as u64 - 1);
+
+    let result = test.ingress(canister_id, "update", run).unwrap();
+    assert_eq!(
+        WasmResult::Reject(
+            "vetkd_encrypted_key request sent with 1_999_999 cycles, but 2_000_000 cycles are required."
+                .into()
+        ),
+        result
+    );
+}
+
+#[test]
+fn vetkd_encrypted_key_with_invalid_transport_public_key_rejected() {
+    let vetkd_key = make_vetkd_key("bls12_381_g2");
+    let mut test = ExecutionTestBuilder::new()
+        .with_subnet_type(SubnetType::System)
+        .with_own_subnet_id(subnet_test_id(1))
+        .with_nns_subnet_id(subnet_test_id(2))
+        .with_vetkd_key(vetkd_key.clone())
+        .build();
+    let canister_id = test.universal_canister().unwrap();
+    let run = vetkd_encrypted_key_call(vetkd_key, vec![1; 48], 0);
+
+    let result = test.ingress(canister_id, "update", run).unwrap();
+    assert_eq!(
+        WasmResult::Reject(
+            "vetkd_encrypted_key request sent with an invalid transport public key.".into()
+        ),
+        result
+    );
+}
+
+#[test]
+fn vetkd_encrypted_key_with_unknown_key_rejected() {
+    let mut test = ExecutionTestBuilder::new()
+        .with_subnet_type(SubnetType::System)
+        .with_own_subnet_id(subnet_test_id(1))
+        .with_nns_subnet_id(subnet_test_id(2))
+        .with_vetkd_key(make_vetkd_key("correct_key"))
+        .build();
+    let canister_id = test.universal_canister().unwrap();
+    let run =
+        vetkd_encrypted_key_call(make_vetkd_key("wrong_key"), valid_transport_public_key(), 0);
+
+    let result = test.ingress(canister_id, "update", run).unwrap();
+    assert_eq!(
+        result,
+        WasmResult::Reject(format!(
+            "Unable to route management canister request vetkd_encrypted_key: VetKdKeyError(\"Requested vetKD key: {}, existing keys: [{}]\")",
+            make_vetkd_key("wrong_key"),
+            make_vetkd_key("correct_key"),
+        ))
+    );
+}
+
 #[test]
 fn ecdsa_public_key_req_with_unknown_key_rejected() {
     let correct_key = make_key("correct_key");
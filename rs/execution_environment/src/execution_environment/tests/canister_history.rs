@@ -0,0 +1,177 @@
+use candid::Decode;
+use ic_crypto_sha::Sha256;
+use ic_ic00_types::{
+    CanisterChangeDetails, CanisterChangeOrigin, CanisterInfoRequest, CanisterInfoResponse,
+    CanisterInstallMode, CanisterSnapshotResponse, Method, Payload, UninstallCodeArgs,
+};
+use ic_replicated_state::canister_state::system_state::MAX_CANISTER_HISTORY_CHANGES;
+use ic_test_utilities_execution_environment::{get_reply, ExecutionTest, ExecutionTestBuilder};
+use ic_types::{CanisterId, Cycles};
+use ic_universal_canister::{call_args, wasm, UNIVERSAL_CANISTER_WASM};
+
+/// Calls `canister_info` on `canister_id` from the universal canister `caller`.
+fn canister_info(
+    test: &mut ExecutionTest,
+    caller: CanisterId,
+    canister_id: CanisterId,
+    num_requested_changes: Option<u64>,
+) -> CanisterInfoResponse {
+    let args = CanisterInfoRequest::new(canister_id, num_requested_changes).encode();
+    let payload = wasm()
+        .call_simple(
+            CanisterId::ic_00(),
+            Method::CanisterInfo,
+            call_args().other_side(args),
+        )
+        .build();
+    let result = test.ingress(caller, "update", payload);
+    CanisterInfoResponse::decode(&get_reply(result)).unwrap()
+}
+
+#[test]
+fn canister_info_returns_module_hash_controllers_and_changes() {
+    let mut test = ExecutionTestBuilder::new().build();
+    let caller = test.universal_canister().unwrap();
+    let canister_id = test.universal_canister().unwrap();
+    let user = test.user_id().get();
+
+    let info = canister_info(&mut test, caller, canister_id, Some(10));
+    assert_eq!(info.total_num_changes(), 2);
+    assert_eq!(
+        info.module_hash(),
+        Some(&Sha256::hash(UNIVERSAL_CANISTER_WASM)[..])
+    );
+    assert_eq!(info.controllers(), &[user]);
+
+    let changes = info.changes();
+    assert_eq!(changes.len(), 2);
+    assert_eq!(changes[0].canister_version(), 0);
+    assert_eq!(changes[0].origin(), &CanisterChangeOrigin::from_user(user));
+    assert_eq!(
+        changes[0].details(),
+        &CanisterChangeDetails::canister_creation(vec![user])
+    );
+    assert_eq!(changes[1].canister_version(), 1);
+    assert_eq!(changes[1].origin(), &CanisterChangeOrigin::from_user(user));
+    assert_eq!(
+        changes[1].details(),
+        &CanisterChangeDetails::code_deployment(
+            CanisterInstallMode::Install,
+            Sha256::hash(UNIVERSAL_CANISTER_WASM)
+        )
+    );
+}
+
+#[test]
+fn canister_info_records_controllers_change_and_uninstall() {
+    let mut test = ExecutionTestBuilder::new().build();
+    let caller = test.universal_canister().unwrap();
+    let canister_id = test.universal_canister().unwrap();
+    let user = test.user_id().get();
+
+    test.set_controller(canister_id, caller.get()).unwrap();
+    let info = canister_info(&mut test, caller, canister_id, Some(1));
+    assert_eq!(info.total_num_changes(), 3);
+    assert_eq!(info.controllers(), &[caller.get()]);
+    assert_eq!(info.changes().len(), 1);
+    assert_eq!(
+        info.changes()[0].origin(),
+        &CanisterChangeOrigin::from_user(user)
+    );
+    assert_eq!(
+        info.changes()[0].details(),
+        &CanisterChangeDetails::controllers_change(vec![caller.get()])
+    );
+
+    // Uninstall the code from the new controller.
+    let uninstall = wasm()
+        .call_simple(
+            CanisterId::ic_00(),
+            Method::UninstallCode,
+            call_args().other_side(UninstallCodeArgs::new(canister_id, None).encode()),
+        )
+        .build();
+    get_reply(test.ingress(caller, "update", uninstall));
+
+    let info = canister_info(&mut test, caller, canister_id, Some(1));
+    assert_eq!(info.total_num_changes(), 4);
+    assert_eq!(info.module_hash(), None);
+    assert_eq!(
+        info.changes()[0].origin(),
+        &CanisterChangeOrigin::from_canister(caller.get(), None)
+    );
+    assert_eq!(
+        info.changes()[0].details(),
+        &CanisterChangeDetails::code_uninstall()
+    );
+}
+
+#[test]
+fn canister_info_records_loading_a_snapshot() {
+    let mut test = ExecutionTestBuilder::new().build();
+    let caller = test.universal_canister().unwrap();
+    let canister_id = test.universal_canister().unwrap();
+    let user = test.user_id().get();
+
+    let result = test.take_canister_snapshot(canister_id, None);
+    let snapshot = Decode!(&get_reply(result), CanisterSnapshotResponse).unwrap();
+    let empty_wasm = wat::parse_str("(module)").unwrap();
+    test.reinstall_canister(canister_id, empty_wasm.clone())
+        .unwrap();
+    let info = canister_info(&mut test, caller, canister_id, Some(1));
+    assert_eq!(info.module_hash(), Some(&Sha256::hash(&empty_wasm)[..]));
+
+    test.load_canister_snapshot(canister_id, snapshot.id)
+        .unwrap();
+
+    let info = canister_info(&mut test, caller, canister_id, Some(1));
+    assert_eq!(info.total_num_changes(), 4);
+    assert_eq!(
+        info.module_hash(),
+        Some(&Sha256::hash(UNIVERSAL_CANISTER_WASM)[..])
+    );
+    assert_eq!(info.changes()[0].canister_version(), 3);
+    assert_eq!(
+        info.changes()[0].origin(),
+        &CanisterChangeOrigin::from_user(user)
+    );
+    assert_eq!(
+        info.changes()[0].details(),
+        &CanisterChangeDetails::code_deployment(
+            CanisterInstallMode::Reinstall,
+            Sha256::hash(UNIVERSAL_CANISTER_WASM)
+        )
+    );
+}
+
+#[test]
+fn canister_info_returns_no_changes_by_default() {
+    let mut test = ExecutionTestBuilder::new().build();
+    let caller = test.universal_canister().unwrap();
+    let canister_id = test.create_canister(Cycles::new(1_000_000_000_000));
+
+    let info = canister_info(&mut test, caller, canister_id, None);
+    assert_eq!(info.total_num_changes(), 1);
+    assert!(info.changes().is_empty());
+    assert_eq!(info.module_hash(), None);
+}
+
+#[test]
+fn canister_history_is_bounded() {
+    let mut test = ExecutionTestBuilder::new().build();
+    let caller = test.universal_canister().unwrap();
+    let canister_id = test.create_canister(Cycles::new(1_000_000_000_000));
+    let user = test.user_id().get();
+
+    for _ in 0..MAX_CANISTER_HISTORY_CHANGES {
+        test.set_controller(canister_id, user).unwrap();
+    }
+
+    let info = canister_info(&mut test, caller, canister_id, Some(u64::MAX));
+    assert_eq!(info.total_num_changes(), MAX_CANISTER_HISTORY_CHANGES + 1);
+    assert_eq!(info.changes().len() as u64, MAX_CANISTER_HISTORY_CHANGES);
+    assert!(info.changes().iter().all(|change| matches!(
+        change.details(),
+        CanisterChangeDetails::CanisterControllersChange(_)
+    )));
+}
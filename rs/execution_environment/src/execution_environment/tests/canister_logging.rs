@@ -0,0 +1,106 @@
+use ic_error_types::ErrorCode;
+use ic_ic00_types::{FetchCanisterLogsResponse, LogVisibility, Payload};
+use ic_test_utilities_execution_environment::{get_reply, ExecutionTestBuilder};
+use ic_types_test_utils::ids::user_test_id;
+use ic_universal_canister::wasm;
+
+fn log_contents(response: FetchCanisterLogsResponse) -> Vec<Vec<u8>> {
+    response
+        .canister_log_records
+        .into_iter()
+        .map(|record| record.content)
+        .collect()
+}
+
+#[test]
+fn debug_print_and_trap_messages_are_saved_in_canister_log() {
+    let mut test = ExecutionTestBuilder::new().build();
+    let canister_id = test.universal_canister().unwrap();
+
+    test.ingress(
+        canister_id,
+        "update",
+        wasm().debug_print(b"hello").reply().build(),
+    )
+    .unwrap();
+    test.ingress(
+        canister_id,
+        "update",
+        wasm()
+            .debug_print(b"about to trap")
+            .trap_with_blob(b"oops")
+            .build(),
+    )
+    .unwrap_err();
+
+    let result = test.fetch_canister_logs(test.user_id(), canister_id);
+    let response = FetchCanisterLogsResponse::decode(&get_reply(result)).unwrap();
+    let indices: Vec<_> = response
+        .canister_log_records
+        .iter()
+        .map(|record| record.idx)
+        .collect();
+    assert_eq!(indices, vec![0, 1, 2]);
+    assert_eq!(
+        log_contents(response),
+        vec![
+            b"hello".to_vec(),
+            b"about to trap".to_vec(),
+            b"[TRAP]: oops".to_vec()
+        ]
+    );
+}
+
+#[test]
+fn queries_do_not_add_to_canister_log() {
+    let mut test = ExecutionTestBuilder::new().build();
+    let canister_id = test.universal_canister().unwrap();
+
+    test.anonymous_query(
+        canister_id,
+        "query",
+        wasm().debug_print(b"not logged").reply().build(),
+    )
+    .unwrap();
+
+    let result = test.fetch_canister_logs(test.user_id(), canister_id);
+    let response = FetchCanisterLogsResponse::decode(&get_reply(result)).unwrap();
+    assert!(response.canister_log_records.is_empty());
+}
+
+#[test]
+fn fetch_canister_logs_respects_log_visibility() {
+    let mut test = ExecutionTestBuilder::new().build();
+    let canister_id = test.universal_canister().unwrap();
+    test.ingress(
+        canister_id,
+        "update",
+        wasm().debug_print(b"hello").reply().build(),
+    )
+    .unwrap();
+
+    let err = test
+        .fetch_canister_logs(user_test_id(42), canister_id)
+        .unwrap_err();
+    assert_eq!(err.code(), ErrorCode::CanisterRejectedMessage);
+
+    test.canister_update_log_visibility(canister_id, LogVisibility::Public)
+        .unwrap();
+    let result = test.fetch_canister_logs(user_test_id(42), canister_id);
+    let response = FetchCanisterLogsResponse::decode(&get_reply(result)).unwrap();
+    assert_eq!(log_contents(response), vec![b"hello".to_vec()]);
+}
+
+#[test]
+fn fetch_canister_logs_is_rejected_in_replicated_mode() {
+    let mut test = ExecutionTestBuilder::new().build();
+    let canister_id = test.universal_canister().unwrap();
+
+    let err = test
+        .subnet_message(
+            "fetch_canister_logs",
+            ic_ic00_types::FetchCanisterLogsRequest::new(canister_id).encode(),
+        )
+        .unwrap_err();
+    assert_eq!(err.code(), ErrorCode::CanisterRejectedMessage);
+}
@@ -0,0 +1,80 @@
+use candid::Decode;
+use ic_error_types::ErrorCode;
+use ic_ic00_types::CanisterSnapshotResponse;
+use ic_test_utilities_execution_environment::{get_reply, ExecutionTestBuilder};
+use ic_types::NumBytes;
+use ic_universal_canister::wasm;
+
+#[test]
+fn load_canister_snapshot_restores_canister_state() {
+    let mut test = ExecutionTestBuilder::new().build();
+    let canister_id = test.universal_canister().unwrap();
+
+    let set_global_data = |data: &[u8]| wasm().set_global_data(data).reply().build();
+    let get_global_data = wasm().get_global_data().append_and_reply().build();
+
+    test.ingress(canister_id, "update", set_global_data(b"before"))
+        .unwrap();
+    let result = test.take_canister_snapshot(canister_id, None);
+    let snapshot = Decode!(&get_reply(result), CanisterSnapshotResponse).unwrap();
+    assert_eq!(
+        test.canister_state(canister_id)
+            .system_state
+            .snapshots_memory_usage,
+        NumBytes::from(snapshot.total_size)
+    );
+
+    test.ingress(canister_id, "update", set_global_data(b"after"))
+        .unwrap();
+    test.load_canister_snapshot(canister_id, snapshot.id.clone())
+        .unwrap();
+    let result = test.ingress(canister_id, "query", get_global_data);
+    assert_eq!(get_reply(result), b"before".to_vec());
+}
+
+#[test]
+fn list_and_delete_canister_snapshots() {
+    let mut test = ExecutionTestBuilder::new().build();
+    let canister_id = test.universal_canister().unwrap();
+
+    let result = test.take_canister_snapshot(canister_id, None);
+    let snapshot = Decode!(&get_reply(result), CanisterSnapshotResponse).unwrap();
+
+    let result = test.list_canister_snapshots(canister_id);
+    let snapshots = Decode!(&get_reply(result), Vec<CanisterSnapshotResponse>).unwrap();
+    assert_eq!(snapshots, vec![snapshot.clone()]);
+
+    test.delete_canister_snapshot(canister_id, snapshot.id.clone())
+        .unwrap();
+    let result = test.list_canister_snapshots(canister_id);
+    let snapshots = Decode!(&get_reply(result), Vec<CanisterSnapshotResponse>).unwrap();
+    assert!(snapshots.is_empty());
+    assert_eq!(
+        test.canister_state(canister_id)
+            .system_state
+            .snapshots_memory_usage,
+        NumBytes::from(0)
+    );
+
+    let err = test
+        .load_canister_snapshot(canister_id, snapshot.id)
+        .unwrap_err();
+    assert_eq!(err.code(), ErrorCode::CanisterSnapshotNotFound);
+}
+
+#[test]
+fn take_canister_snapshot_fails_when_limit_is_reached() {
+    let mut test = ExecutionTestBuilder::new().build();
+    let canister_id = test.universal_canister().unwrap();
+
+    let result = test.take_canister_snapshot(canister_id, None);
+    let snapshot = Decode!(&get_reply(result), CanisterSnapshotResponse).unwrap();
+
+    let err = test.take_canister_snapshot(canister_id, None).unwrap_err();
+    assert_eq!(err.code(), ErrorCode::CanisterContractViolation);
+
+    // Replacing the existing snapshot does not count towards the limit.
+    let result = test.take_canister_snapshot(canister_id, Some(snapshot.id.clone()));
+    let new_snapshot = Decode!(&get_reply(result), CanisterSnapshotResponse).unwrap();
+    assert_ne!(new_snapshot.id, snapshot.id);
+}
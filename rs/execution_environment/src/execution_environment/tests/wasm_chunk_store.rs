@@ -0,0 +1,109 @@
+use candid::Decode;
+use ic_crypto_sha::Sha256;
+use ic_error_types::ErrorCode;
+use ic_ic00_types::{CanisterInstallMode, ChunkHash, InstallChunkedCodeArgs, Payload};
+use ic_test_utilities_execution_environment::{get_reply, ExecutionTestBuilder};
+use ic_types::{Cycles, NumBytes};
+use ic_universal_canister::{wasm, UNIVERSAL_CANISTER_WASM};
+
+const CHUNK_SIZE: usize = 100_000;
+
+#[test]
+fn install_chunked_code_installs_module_assembled_from_chunks() {
+    let mut test = ExecutionTestBuilder::new().build();
+    let canister_id = test.create_canister(Cycles::new(1_000_000_000_000));
+
+    let mut chunk_hashes_list = vec![];
+    for chunk in UNIVERSAL_CANISTER_WASM.chunks(CHUNK_SIZE) {
+        let result = test.upload_chunk(canister_id, chunk.to_vec());
+        let hash = ChunkHash::decode(&get_reply(result)).unwrap().hash;
+        assert_eq!(hash, Sha256::hash(chunk).to_vec());
+        chunk_hashes_list.push(hash);
+    }
+    assert_eq!(
+        test.canister_state(canister_id)
+            .system_state
+            .wasm_chunk_store
+            .memory_usage(),
+        NumBytes::from(UNIVERSAL_CANISTER_WASM.len() as u64)
+    );
+
+    test.install_chunked_code(InstallChunkedCodeArgs::new(
+        CanisterInstallMode::Install,
+        canister_id,
+        None,
+        chunk_hashes_list,
+        Sha256::hash(UNIVERSAL_CANISTER_WASM).to_vec(),
+        vec![],
+    ))
+    .unwrap();
+
+    let result = test.ingress(canister_id, "update", wasm().reply_data(b"hi").build());
+    assert_eq!(get_reply(result), b"hi".to_vec());
+}
+
+#[test]
+fn install_chunked_code_fails_on_hash_mismatch() {
+    let mut test = ExecutionTestBuilder::new().build();
+    let canister_id = test.create_canister(Cycles::new(1_000_000_000_000));
+
+    let chunk_hashes_list = UNIVERSAL_CANISTER_WASM
+        .chunks(CHUNK_SIZE)
+        .map(|chunk| {
+            let result = test.upload_chunk(canister_id, chunk.to_vec());
+            ChunkHash::decode(&get_reply(result)).unwrap().hash
+        })
+        .collect::<Vec<_>>();
+
+    let err = test
+        .install_chunked_code(InstallChunkedCodeArgs::new(
+            CanisterInstallMode::Install,
+            canister_id,
+            None,
+            chunk_hashes_list,
+            vec![0; 32],
+            vec![],
+        ))
+        .unwrap_err();
+    assert_eq!(err.code(), ErrorCode::CanisterContractViolation);
+    assert!(test.canister_state(canister_id).execution_state.is_none());
+
+    let err = test
+        .install_chunked_code(InstallChunkedCodeArgs::new(
+            CanisterInstallMode::Install,
+            canister_id,
+            None,
+            vec![vec![1; 32]],
+            Sha256::hash(UNIVERSAL_CANISTER_WASM).to_vec(),
+            vec![],
+        ))
+        .unwrap_err();
+    assert_eq!(err.code(), ErrorCode::CanisterContractViolation);
+}
+
+#[test]
+fn stored_chunks_and_clear_chunk_store() {
+    let mut test = ExecutionTestBuilder::new().build();
+    let canister_id = test.create_canister(Cycles::new(1_000_000_000_000));
+
+    let result = test.upload_chunk(canister_id, vec![1, 2, 3]);
+    let hash = ChunkHash::decode(&get_reply(result)).unwrap();
+    // Uploading the same chunk again does not store it twice.
+    test.upload_chunk(canister_id, vec![1, 2, 3]).unwrap();
+
+    let result = test.stored_chunks(canister_id);
+    let hashes = Decode!(&get_reply(result), Vec<ChunkHash>).unwrap();
+    assert_eq!(hashes, vec![hash]);
+
+    test.clear_chunk_store(canister_id).unwrap();
+    let result = test.stored_chunks(canister_id);
+    let hashes = Decode!(&get_reply(result), Vec<ChunkHash>).unwrap();
+    assert!(hashes.is_empty());
+    assert_eq!(
+        test.canister_state(canister_id)
+            .system_state
+            .wasm_chunk_store
+            .memory_usage(),
+        NumBytes::from(0)
+    );
+}
@@ -295,5 +295,6 @@ fn dashboard_label_value_from(code: ErrorCode) -> &'static str {
         QueryCallGraphTotalInstructionLimitExceeded => "Total instructions limit exceeded for query call graph",
         CompositeQueryCalledInReplicatedMode => "Composite query cannot be called in replicated mode",
         CanisterNotHostedBySubnet => "Canister is not hosted by subnet",
+        CanisterSnapshotNotFound => "Canister snapshot not found",
     }
 }
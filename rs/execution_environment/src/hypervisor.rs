@@ -22,7 +22,9 @@ use std::{path::PathBuf, sync::Arc};
 
 use crate::execution::common::{apply_canister_state_changes, update_round_limits};
 use crate::execution_environment::{as_round_instructions, CompilationCostHandling, RoundLimits};
-use ic_replicated_state::page_map::PageAllocatorFileDescriptor;
+use ic_replicated_state::page_map::{
+    PageAllocatorFileDescriptor, TestPageAllocatorFileDescriptorImpl,
+};
 
 #[cfg(test)]
 mod tests;
@@ -117,6 +119,7 @@ pub struct Hypervisor {
     deterministic_time_slicing: FlagStatus,
     cost_to_compile_wasm_instruction: NumInstructions,
     dirty_page_overhead: NumInstructions,
+    fd_factory: Arc<dyn PageAllocatorFileDescriptor>,
 }
 
 impl Hypervisor {
@@ -192,6 +195,7 @@ impl Hypervisor {
             config.query_execution_threads_per_canister;
         embedder_config.feature_flags.rate_limiting_of_debug_prints =
             config.rate_limiting_of_debug_prints;
+        embedder_config.feature_flags.wasm64 = config.wasm64;
         embedder_config.cost_to_compile_wasm_instruction = config.cost_to_compile_wasm_instruction;
         embedder_config.max_sandbox_count = config.max_sandbox_count;
         embedder_config.max_sandbox_idle_time = config.max_sandbox_idle_time;
@@ -232,6 +236,7 @@ impl Hypervisor {
             deterministic_time_slicing: config.deterministic_time_slicing,
             cost_to_compile_wasm_instruction: config.cost_to_compile_wasm_instruction,
             dirty_page_overhead,
+            fd_factory,
         }
     }
 
@@ -258,9 +263,16 @@ impl Hypervisor {
             deterministic_time_slicing,
             cost_to_compile_wasm_instruction,
             dirty_page_overhead,
+            fd_factory: Arc::new(TestPageAllocatorFileDescriptorImpl::new()),
         }
     }
 
+    /// Returns the factory of file descriptors backing the page allocators of
+    /// newly created page maps.
+    pub(crate) fn fd_factory(&self) -> Arc<dyn PageAllocatorFileDescriptor> {
+        Arc::clone(&self.fd_factory)
+    }
+
     #[cfg(test)]
     pub fn compile_count(&self) -> u64 {
         self.metrics.compile.get_sample_count()
@@ -1601,6 +1601,116 @@ fn ic0_canister_self_copy_works() {
     assert_eq!(WasmResult::Reply(canister_id.get().into_vec()), result);
 }
 
+#[test]
+fn ic0_is_controller_works() {
+    let mut test = ExecutionTestBuilder::new().build();
+    let wat = r#"
+        (module
+            (import "ic0" "msg_arg_data_size" (func $msg_arg_data_size (result i32)))
+            (import "ic0" "msg_arg_data_copy"
+                (func $msg_arg_data_copy (param i32 i32 i32))
+            )
+            (import "ic0" "is_controller"
+                (func $is_controller (param i32 i32) (result i32))
+            )
+            (import "ic0" "msg_reply" (func $msg_reply))
+            (import "ic0" "msg_reply_data_append"
+                (func $msg_reply_data_append (param i32 i32))
+            )
+            (func (export "canister_update test")
+                ;; heap[0..size] = principal from the argument
+                (call $msg_arg_data_copy (i32.const 0) (i32.const 0) (call $msg_arg_data_size))
+                ;; heap[100] = is_controller(heap[0..size])
+                (i32.store8 (i32.const 100)
+                    (call $is_controller (i32.const 0) (call $msg_arg_data_size))
+                )
+                (call $msg_reply_data_append (i32.const 100) (i32.const 1))
+                (call $msg_reply)
+            )
+            (memory 1 1)
+        )"#;
+    let canister_id = test.canister_from_wat(wat).unwrap();
+    let controller = test.user_id().get().into_vec();
+    let result = test.ingress(canister_id, "test", controller).unwrap();
+    assert_eq!(WasmResult::Reply(vec![1]), result);
+
+    let result = test
+        .ingress(canister_id, "test", canister_id.get().into_vec())
+        .unwrap();
+    assert_eq!(WasmResult::Reply(vec![0]), result);
+
+    let err = test.ingress(canister_id, "test", vec![0; 30]).unwrap_err();
+    assert_eq!(ErrorCode::CanisterTrapped, err.code());
+}
+
+#[test]
+fn ic0_in_replicated_execution_works() {
+    let mut test = ExecutionTestBuilder::new().build();
+    let wat = r#"
+        (module
+            (import "ic0" "in_replicated_execution"
+                (func $in_replicated_execution (result i32))
+            )
+            (import "ic0" "msg_reply" (func $msg_reply))
+            (import "ic0" "msg_reply_data_append"
+                (func $msg_reply_data_append (param i32 i32))
+            )
+            (func $reply_mode
+                (i32.store8 (i32.const 0) (call $in_replicated_execution))
+                (call $msg_reply_data_append (i32.const 0) (i32.const 1))
+                (call $msg_reply)
+            )
+            (export "canister_update update" (func $reply_mode))
+            (export "canister_query query" (func $reply_mode))
+            (memory 1 1)
+        )"#;
+    let canister_id = test.canister_from_wat(wat).unwrap();
+    let result = test.ingress(canister_id, "update", vec![]).unwrap();
+    assert_eq!(WasmResult::Reply(vec![1]), result);
+
+    // Queries sent as ingress messages are executed in replicated mode.
+    let result = test.ingress(canister_id, "query", vec![]).unwrap();
+    assert_eq!(WasmResult::Reply(vec![1]), result);
+
+    let result = test.anonymous_query(canister_id, "query", vec![]).unwrap();
+    assert_eq!(WasmResult::Reply(vec![0]), result);
+}
+
+#[test]
+fn ic0_cost_create_canister_and_cost_http_request_work() {
+    let mut test = ExecutionTestBuilder::new().build();
+    let wat = r#"
+        (module
+            (import "ic0" "cost_create_canister"
+                (func $cost_create_canister (param i32))
+            )
+            (import "ic0" "cost_http_request"
+                (func $cost_http_request (param i64 i64 i32))
+            )
+            (import "ic0" "msg_reply" (func $msg_reply))
+            (import "ic0" "msg_reply_data_append"
+                (func $msg_reply_data_append (param i32 i32))
+            )
+            (func (export "canister_update test")
+                (call $cost_create_canister (i32.const 0))
+                (call $cost_http_request (i64.const 100) (i64.const 2000) (i32.const 16))
+                (call $msg_reply_data_append (i32.const 0) (i32.const 32))
+                (call $msg_reply)
+            )
+            (memory 1 1)
+        )"#;
+    let canister_id = test.canister_from_wat(wat).unwrap();
+    let result = test.ingress(canister_id, "test", vec![]).unwrap();
+    let expected = [
+        test.canister_creation_fee().get().to_le_bytes(),
+        test.http_request_fee(NumBytes::from(100), Some(NumBytes::from(2000)))
+            .get()
+            .to_le_bytes(),
+    ]
+    .concat();
+    assert_eq!(WasmResult::Reply(expected), result);
+}
+
 #[test]
 fn ic0_call_has_no_effect_on_trap() {
     let mut test = ExecutionTestBuilder::new().build();
@@ -16,6 +16,10 @@ use ic_config::flag_status::FlagStatus;
 use ic_crypto_tree_hash::{flatmap, Label, LabeledTree, LabeledTree::SubTree};
 use ic_cycles_account_manager::CyclesAccountManager;
 use ic_error_types::{ErrorCode, RejectCode, UserError};
+use ic_ic00_types::{
+    FetchCanisterLogsRequest, FetchCanisterLogsResponse, LogVisibility, Method as Ic00Method,
+    Payload,
+};
 use ic_interfaces::execution_environment::{QueryExecutionService, QueryHandler};
 use ic_interfaces_state_manager::StateReader;
 use ic_logger::ReplicaLogger;
@@ -28,13 +32,14 @@ use ic_types::{
         Blob, Certificate, CertificateDelegation, HttpQueryResponse, HttpQueryResponseReply,
         UserQuery,
     },
-    CanisterId, NumInstructions,
+    CanisterId, NumInstructions, PrincipalId,
 };
 use serde::Serialize;
 use std::{
     convert::Infallible,
     future::Future,
     pin::Pin,
+    str::FromStr,
     sync::Arc,
     task::{Context, Poll},
 };
@@ -88,6 +93,48 @@ fn label<T: Into<Label>>(t: T) -> Label {
     t.into()
 }
 
+/// Answers a `fetch_canister_logs` query from the given state.
+fn fetch_canister_logs(
+    sender: PrincipalId,
+    state: &ReplicatedState,
+    args: FetchCanisterLogsRequest,
+) -> Result<WasmResult, UserError> {
+    let canister_id = args.get_canister_id();
+    let canister = state.canister_state(&canister_id).ok_or_else(|| {
+        UserError::new(
+            ErrorCode::CanisterNotFound,
+            format!("Canister {} not found", canister_id),
+        )
+    })?;
+
+    match canister.system_state.log_visibility {
+        LogVisibility::Public => {}
+        LogVisibility::Controllers => {
+            if !canister.system_state.controllers.contains(&sender) {
+                return Err(UserError::new(
+                    ErrorCode::CanisterRejectedMessage,
+                    format!(
+                        "Caller {} is not allowed to query ic00 method {}",
+                        sender,
+                        Ic00Method::FetchCanisterLogs
+                    ),
+                ));
+            }
+        }
+    }
+
+    let response = FetchCanisterLogsResponse {
+        canister_log_records: canister
+            .system_state
+            .canister_log
+            .records()
+            .iter()
+            .cloned()
+            .collect(),
+    };
+    Ok(WasmResult::Reply(response.encode()))
+}
+
 pub struct InternalHttpQueryHandler {
     log: ReplicaLogger,
     hypervisor: Arc<Hypervisor>,
@@ -142,10 +189,34 @@ impl QueryHandler for InternalHttpQueryHandler {
     ) -> Result<WasmResult, UserError> {
         let measurement_scope = MeasurementScope::root(&self.metrics.query);
 
+        // Queries to the management canister are answered directly from the
+        // state, without executing any canister code.
+        if query.receiver == CanisterId::ic_00() {
+            return match Ic00Method::from_str(query.method_name.as_str()) {
+                Ok(Ic00Method::FetchCanisterLogs) => {
+                    FetchCanisterLogsRequest::decode(&query.method_payload)
+                        .map_err(|err| {
+                            UserError::new(
+                                ErrorCode::InvalidManagementPayload,
+                                format!("Error decoding candid: {}", err),
+                            )
+                        })
+                        .and_then(|args| fetch_canister_logs(query.source.get(), &state, args))
+                }
+                _ => Err(UserError::new(
+                    ErrorCode::CanisterMethodNotFound,
+                    format!(
+                        "Query method {} not found in the management canister.",
+                        query.method_name
+                    ),
+                )),
+            };
+        }
+
         // Letting the canister grow arbitrarily when executing the
         // query is fine as we do not persist state modifications.
         let subnet_available_memory = subnet_memory_capacity(&self.config);
-        let max_canister_memory_size = self.config.max_canister_memory_size;
+        let max_canister_memory_size = self.config.canister_memory_limit();
 
         let mut context = query_context::QueryContext::new(
             &self.log,
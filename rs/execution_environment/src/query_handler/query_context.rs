@@ -61,6 +61,7 @@ use ic_types::{
     ingress::WasmResult,
     messages::{
         CallbackId, Payload, RejectContext, Request, RequestOrResponse, Response, UserQuery,
+        NO_DEADLINE,
     },
     methods::WasmMethod,
     CanisterId, Cycles, NumInstructions, NumMessages, Time,
@@ -104,6 +105,7 @@ fn generate_response(request: Arc<Request>, payload: Payload) -> Response {
         originator: request.sender,
         respondent: request.receiver,
         originator_reply_callback: request.sender_reply_callback,
+        deadline: request.deadline,
         response_payload: payload,
         refund: Cycles::zero(),
     }
@@ -430,7 +432,7 @@ impl<'a> QueryContext<'a> {
                         // Messages of these types are not produced by this
                         // module so must have existed on the canister's output
                         // queue from before.
-                        CallOrigin::CanisterUpdate(_, _)
+                        CallOrigin::CanisterUpdate(_, _, _)
                         | CallOrigin::SystemTask
                         | CallOrigin::Ingress(_, _) => continue,
 
@@ -565,7 +567,7 @@ impl<'a> QueryContext<'a> {
         };
         let func_ref = match call_origin {
             CallOrigin::Ingress(_, _)
-            | CallOrigin::CanisterUpdate(_, _)
+            | CallOrigin::CanisterUpdate(_, _, _)
             | CallOrigin::SystemTask => unreachable!("Unreachable in the QueryContext."),
             CallOrigin::CanisterQuery(_, _) | CallOrigin::Query(_) => {
                 FuncRef::QueryClosure(closure)
@@ -685,7 +687,7 @@ impl<'a> QueryContext<'a> {
     ) -> (NumInstructions, Result<Option<WasmResult>, HypervisorError>) {
         let func_ref = match call_origin {
             CallOrigin::Ingress(_, _)
-            | CallOrigin::CanisterUpdate(_, _)
+            | CallOrigin::CanisterUpdate(_, _, _)
             | CallOrigin::SystemTask => unreachable!("Unreachable in the QueryContext."),
             CallOrigin::CanisterQuery(_, _) | CallOrigin::Query(_) => {
                 FuncRef::QueryClosure(cleanup_closure)
@@ -929,6 +931,7 @@ impl<'a> QueryContext<'a> {
                 originator,
                 respondent: canister_id,
                 originator_reply_callback: callback_id,
+                deadline: NO_DEADLINE,
                 response_payload: payload,
                 refund: Cycles::zero(),
             };
@@ -1059,7 +1062,7 @@ impl<'a> QueryContext<'a> {
         match call_origin {
             CallOrigin::Query(_) => self.handle_response_with_query_origin(canister, action),
 
-            CallOrigin::CanisterUpdate(_, _)
+            CallOrigin::CanisterUpdate(_, _, _)
             | CallOrigin::Ingress(_, _)
             | CallOrigin::SystemTask => fatal!(
                 self.log,
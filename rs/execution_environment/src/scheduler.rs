@@ -12,7 +12,7 @@ use ic_config::subnet_config::SchedulerConfig;
 use ic_crypto_prng::{Csprng, RandomnessPurpose::ExecutionThread};
 use ic_cycles_account_manager::CyclesAccountManager;
 use ic_error_types::{ErrorCode, UserError};
-use ic_ic00_types::{CanisterStatusType, EcdsaKeyId, Method as Ic00Method};
+use ic_ic00_types::{CanisterStatusType, EcdsaKeyId, Method as Ic00Method, SchnorrKeyId};
 use ic_interfaces::execution_environment::{
     ExecutionComplexity, ExecutionRoundType, RegistryExecutionSettings,
 };
@@ -434,6 +434,7 @@ impl SchedulerImpl {
         long_running_canister_ids: BTreeSet<CanisterId>,
         registry_settings: &RegistryExecutionSettings,
         ecdsa_subnet_public_keys: &BTreeMap<EcdsaKeyId, MasterEcdsaPublicKey>,
+        schnorr_subnet_public_keys: &BTreeMap<SchnorrKeyId, MasterEcdsaPublicKey>,
     ) -> ReplicatedState {
         loop {
             let mut available_subnet_messages = false;
@@ -466,6 +467,7 @@ impl SchedulerImpl {
                     instruction_limits,
                     csprng,
                     ecdsa_subnet_public_keys,
+                    schnorr_subnet_public_keys,
                     registry_settings,
                     round_limits,
                 );
@@ -956,6 +958,7 @@ impl SchedulerImpl {
     ) {
         let state_time = state.time();
         let mut all_rejects = Vec::new();
+        let mut uninstalled_canisters = Vec::new();
         for canister in state.canisters_iter_mut() {
             // Postpone charging for resources when a canister has a paused execution
             // to avoid modifying the balance of a canister during an unfinished operation.
@@ -988,6 +991,7 @@ impl SchedulerImpl {
                     .is_err()
                 {
                     all_rejects.push(uninstall_canister(&self.log, canister, state_time));
+                    uninstalled_canisters.push(canister.canister_id());
                     canister.scheduler_state.compute_allocation = ComputeAllocation::zero();
                     canister.system_state.memory_allocation = MemoryAllocation::BestEffort;
                     // Burn the remaining balance of the canister.
@@ -1006,6 +1010,11 @@ impl SchedulerImpl {
             }
         }
 
+        // Snapshots of uninstalled canisters are deleted along with their code.
+        for canister_id in uninstalled_canisters {
+            state.delete_canister_snapshots(canister_id);
+        }
+
         // Send rejects to any requests that were forcibly closed while uninstalling.
         for rejects in all_rejects.into_iter() {
             process_responses(
@@ -1275,6 +1284,7 @@ impl Scheduler for SchedulerImpl {
         mut state: ReplicatedState,
         randomness: Randomness,
         ecdsa_subnet_public_keys: BTreeMap<EcdsaKeyId, MasterEcdsaPublicKey>,
+        schnorr_subnet_public_keys: BTreeMap<SchnorrKeyId, MasterEcdsaPublicKey>,
         current_round: ExecutionRound,
         current_round_type: ExecutionRoundType,
         registry_settings: &RegistryExecutionSettings,
@@ -1389,6 +1399,7 @@ impl Scheduler for SchedulerImpl {
                     instruction_limits,
                     &mut csprng,
                     &ecdsa_subnet_public_keys,
+                    &schnorr_subnet_public_keys,
                     registry_settings,
                     &mut round_limits,
                 );
@@ -1433,6 +1444,7 @@ impl Scheduler for SchedulerImpl {
                 long_running_canister_ids,
                 registry_settings,
                 &ecdsa_subnet_public_keys,
+                &schnorr_subnet_public_keys,
             );
         }
 
@@ -1939,6 +1951,12 @@ fn observe_replicated_state_metrics(
     // Add the consumed cycles in ecdsa outcalls.
     consumed_cycles_total += state.metadata.subnet_metrics.consumed_cycles_ecdsa_outcalls;
 
+    // Add the consumed cycles in schnorr outcalls.
+    consumed_cycles_total += state
+        .metadata
+        .subnet_metrics
+        .consumed_cycles_schnorr_outcalls;
+
     // Add the consumed cycles in http outcalls.
     consumed_cycles_total += state.metadata.subnet_metrics.consumed_cycles_http_outcalls;
 
@@ -2020,7 +2038,9 @@ fn can_execute_msg(
         };
 
         // Only one install code message allowed at a time.
-        if let Some(Ic00Method::InstallCode) = maybe_instal_code_method {
+        if let Some(Ic00Method::InstallCode) | Some(Ic00Method::InstallChunkedCode) =
+            maybe_instal_code_method
+        {
             return false;
         }
     }
@@ -2058,6 +2078,7 @@ fn get_instructions_limits_for_subnet_message(
             | CreateCanister
             | DeleteCanister
             | DepositCycles
+            | CanisterInfo
             | ECDSAPublicKey
             | RawRand
             | SetController
@@ -2076,8 +2097,16 @@ fn get_instructions_limits_for_subnet_message(
             | BitcoinGetCurrentFeePercentiles
             | BitcoinGetSuccessors
             | ProvisionalCreateCanisterWithCycles
-            | ProvisionalTopUpCanister => default_limits,
-            InstallCode => InstructionLimits::new(
+            | ProvisionalTopUpCanister
+            | TakeCanisterSnapshot
+            | LoadCanisterSnapshot
+            | ListCanisterSnapshots
+            | DeleteCanisterSnapshot
+            | UploadChunk
+            | ClearChunkStore
+            | StoredChunks
+            | FetchCanisterLogs => default_limits,
+            InstallCode | InstallChunkedCode => InstructionLimits::new(
                 dts,
                 config.max_instructions_per_install_code,
                 config.max_instructions_per_install_code_slice,
@@ -48,9 +48,12 @@ use ic_test_utilities::{
 };
 use ic_test_utilities_execution_environment::{generate_subnets, test_registry_settings};
 use ic_types::{
+    canister_log::CanisterLog,
     crypto::{canister_threshold_sig::MasterEcdsaPublicKey, AlgorithmId},
     ingress::{IngressState, IngressStatus},
-    messages::{CallContextId, Ingress, MessageId, Request, RequestOrResponse, Response},
+    messages::{
+        CallContextId, Ingress, MessageId, Request, RequestOrResponse, Response, NO_DEADLINE,
+    },
     methods::{Callback, FuncRef, SystemMethod, WasmClosure, WasmMethod},
     CanisterTimer, ComputeAllocation, Cycles, ExecutionRound, MemoryAllocation, NumInstructions,
     Randomness, Time, UserId,
@@ -468,6 +471,7 @@ impl SchedulerTest {
             state,
             Randomness::from([0; 32]),
             self.ecdsa_subnet_public_keys.clone(),
+            BTreeMap::new(),
             self.round,
             round_type,
             self.registry_settings(),
@@ -524,6 +528,7 @@ impl SchedulerTest {
             long_running_canister_ids,
             self.registry_settings(),
             &BTreeMap::new(),
+            &BTreeMap::new(),
         )
     }
 
@@ -1099,6 +1104,7 @@ impl TestWasmExecutorCore {
                 allocated_bytes: NumBytes::from(0),
                 allocated_message_bytes: NumBytes::from(0),
                 instance_stats: InstanceStats::default(),
+                canister_log: CanisterLog::default(),
             };
             self.schedule
                 .push((self.round, canister_id, instructions_to_execute));
@@ -1140,6 +1146,7 @@ impl TestWasmExecutorCore {
             allocated_message_bytes: NumBytes::from(0),
             num_instructions_left: instructions_left,
             instance_stats,
+            canister_log: CanisterLog::default(),
         };
         self.schedule
             .push((self.round, canister_id, instructions_to_execute));
@@ -1236,12 +1243,14 @@ impl TestWasmExecutorCore {
                 on_reply: closure.clone(),
                 on_reject: closure,
                 on_cleanup: None,
+                deadline: NO_DEADLINE,
             })
             .map_err(|err| err.to_string())?;
         let request = Request {
             receiver,
             sender,
             sender_reply_callback: callback,
+            deadline: NO_DEADLINE,
             payment: Cycles::zero(),
             method_name: "update".into(),
             method_payload: encode_message_id_as_payload(call_message_id),
@@ -39,7 +39,9 @@ use ic_test_utilities::{
 use ic_test_utilities_metrics::{
     fetch_counter, fetch_gauge, fetch_int_gauge, fetch_int_gauge_vec, metric_vec,
 };
-use ic_types::messages::{CallbackId, Payload, RejectContext, Response, MAX_RESPONSE_COUNT_BYTES};
+use ic_types::messages::{
+    CallbackId, Payload, RejectContext, Response, MAX_RESPONSE_COUNT_BYTES, NO_DEADLINE,
+};
 use ic_types::methods::SystemMethod;
 use ic_types::{time::UNIX_EPOCH, ComputeAllocation, Cycles, NumBytes};
 use proptest::prelude::*;
@@ -2865,6 +2867,7 @@ fn ecdsa_signature_agreements_metric_is_updated() {
         originator: context.request.sender,
         respondent: ic_types::CanisterId::ic_00(),
         originator_reply_callback: *callback_id,
+        deadline: NO_DEADLINE,
         refund: context.request.payment,
         response_payload: Payload::Reject(RejectContext {
             code: RejectCode::SysFatal,
@@ -2911,6 +2914,7 @@ fn ecdsa_signature_agreements_metric_is_updated() {
         originator: context.request.sender,
         respondent: ic_types::CanisterId::ic_00(),
         originator_reply_callback: *callback_id,
+        deadline: NO_DEADLINE,
         refund: context.request.payment,
         response_payload: Payload::Data(
             ic00::SignWithECDSAReply {
@@ -3073,6 +3077,7 @@ fn consumed_cycles_http_outcalls_are_added_to_consumed_cycles_total() {
             }),
             context: transform_context,
         }),
+        is_replicated: None,
     };
 
     // Create request to `HttpRequest` method.
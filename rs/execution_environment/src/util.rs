@@ -7,7 +7,7 @@ use ic_logger::{error, ReplicaLogger};
 use ic_replicated_state::{CanisterStatus, ReplicatedState};
 use ic_types::{
     ingress::{IngressState, IngressStatus, WasmResult},
-    messages::{Payload, StopCanisterContext},
+    messages::{Payload, StopCanisterContext, NO_DEADLINE},
     CanisterId,
 };
 use std::{mem, sync::Arc};
@@ -96,6 +96,7 @@ pub fn process_stopping_canisters(
                             originator: sender,
                             respondent: subnet_id_as_canister_id,
                             originator_reply_callback: reply_callback,
+                            deadline: NO_DEADLINE,
                             refund: cycles,
                             response_payload: Payload::Data(EmptyBlob.encode()),
                         };
@@ -29,6 +29,7 @@ const B: u64 = 1_000_000_000;
 
 const DEFAULT_REFERENCE_SUBNET_SIZE: usize = 13;
 pub const ECDSA_SIGNATURE_FEE: Cycles = Cycles::new(10 * B as u128);
+pub const SCHNORR_SIGNATURE_FEE: Cycles = Cycles::new(10 * B as u128);
 
 const TEST_SUBNET_SIZE_MAX: usize = 34;
 const DEFAULT_CYCLES_PER_NODE: Cycles = Cycles::new(100 * B as u128);
@@ -457,6 +458,7 @@ fn simulate_http_request_cost(subnet_type: SubnetType, subnet_size: usize) -> Cy
                         }),
                         context: vec![],
                     }),
+                    is_replicated: None,
                 })
                 .unwrap(),
             ),
@@ -663,6 +665,7 @@ fn get_cycles_account_manager_config(subnet_type: SubnetType) -> CyclesAccountMa
             /// explicit exception for requests originating from the NNS when the
             /// charging occurs.
             ecdsa_signature_fee: ECDSA_SIGNATURE_FEE,
+            schnorr_signature_fee: SCHNORR_SIGNATURE_FEE,
             http_request_baseline_fee: Cycles::new(0),
             http_request_per_byte_fee: Cycles::new(0),
         },
@@ -684,6 +687,7 @@ fn get_cycles_account_manager_config(subnet_type: SubnetType) -> CyclesAccountMa
             gib_storage_per_second_fee: Cycles::new(127_000),
             duration_between_allocation_charges: Duration::from_secs(10),
             ecdsa_signature_fee: ECDSA_SIGNATURE_FEE,
+            schnorr_signature_fee: SCHNORR_SIGNATURE_FEE,
             http_request_baseline_fee: Cycles::new(400_000_000),
             http_request_per_byte_fee: Cycles::new(100_000),
         },
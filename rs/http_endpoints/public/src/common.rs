@@ -119,6 +119,7 @@ pub(crate) fn make_response(user_error: UserError) -> Response<Body> {
         C::QueryCallGraphTotalInstructionLimitExceeded => StatusCode::INTERNAL_SERVER_ERROR,
         C::CompositeQueryCalledInReplicatedMode => StatusCode::INTERNAL_SERVER_ERROR,
         C::CanisterNotHostedBySubnet => StatusCode::NOT_FOUND,
+        C::CanisterSnapshotNotFound => StatusCode::NOT_FOUND,
     };
     make_plaintext_response(status, user_error.description().to_string())
 }
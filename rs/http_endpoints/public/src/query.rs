@@ -11,6 +11,7 @@ use crossbeam::atomic::AtomicCell;
 use futures_util::FutureExt;
 use http::Request;
 use hyper::{Body, Response, StatusCode};
+use ic_ic00_types::{FetchCanisterLogsRequest, Payload};
 use ic_interfaces::execution_environment::QueryExecutionService;
 use ic_interfaces_registry::RegistryClient;
 use ic_logger::{error, ReplicaLogger};
@@ -20,6 +21,7 @@ use ic_types::{
         CertificateDelegation, HasCanisterId, HttpQueryContent, HttpRequest, HttpRequestEnvelope,
         SignedRequestBytes, UserQuery,
     },
+    CanisterId,
 };
 use std::convert::{Infallible, TryFrom};
 use std::future::Future;
@@ -135,13 +137,19 @@ impl Service<Request<Vec<u8>>> for QueryService {
             }
         };
 
-        // Reject requests where `canister_id` != `effective_canister_id`. In comparison to update
-        // requests we don't need to check for the mgmt canister since all mgmt canister calls are updated calls.
+        // Reject requests where `canister_id` != `effective_canister_id`. The only query
+        // method of the mgmt canister is `fetch_canister_logs`, whose effective canister id
+        // is the canister whose logs are fetched.
         // This needs to be enforced because boundary nodes block access based on the `effective_canister_id`
         // in the url and the replica processes the request based on the `canister_id`.
         // If this is not enforced, a blocked canisters can still be accessed by specifying
         // a non-blocked `effective_canister_id` and a blocked `canister_id`.
-        let canister_id = request.content().canister_id();
+        let mut canister_id = request.content().canister_id();
+        if canister_id == CanisterId::ic_00() {
+            if let Ok(args) = FetchCanisterLogsRequest::decode(&request.content().method_payload) {
+                canister_id = args.get_canister_id();
+            }
+        }
         if canister_id != effective_canister_id {
             let res = make_plaintext_response(
                 StatusCode::BAD_REQUEST,
@@ -33,7 +33,8 @@ use ic_registry_keys::make_crypto_threshold_signing_pubkey_key;
 use ic_registry_routing_table::{CanisterMigrations, RoutingTable};
 use ic_registry_subnet_type::SubnetType;
 use ic_replicated_state::{
-    BitcoinState, CanisterQueues, NetworkTopology, ReplicatedState, SystemMetadata,
+    BitcoinState, CanisterQueues, CanisterSnapshots, NetworkTopology, ReplicatedState,
+    SystemMetadata,
 };
 use ic_test_utilities::{
     consensus::MockConsensusCache,
@@ -196,6 +197,7 @@ fn test_healthy_behind() {
                     metadata,
                     CanisterQueues::default(),
                     BitcoinState::default(),
+                    CanisterSnapshots::default(),
                 )),
             )
         });
@@ -18,6 +18,9 @@ pub(crate) const LABEL_DOWNLOAD: &str = "down";
 pub struct AdapterMetrics {
     /// The number of requests served by adapter.
     pub requests: IntCounter,
+    /// The number of requests served by adapter that are made only by this
+    /// replica, i.e. whose responses do not go through consensus.
+    pub non_replicated_requests: IntCounter,
     /// Network traffic generated by adapter.
     pub network_traffic: IntCounterVec,
     /// Request failure types.
@@ -32,6 +35,10 @@ impl AdapterMetrics {
                 "requests_total",
                 "Total number of requests served by adapter",
             ),
+            non_replicated_requests: metrics_registry.int_counter(
+                "non_replicated_requests_total",
+                "Total number of non-replicated requests served by adapter",
+            ),
             network_traffic: metrics_registry.int_counter_vec(
                 "network_traffic_bytes_total",
                 "Network traffic generated by adapter.",
@@ -64,6 +64,14 @@ impl CanisterHttpService for CanisterHttp {
 
         let req = request.into_inner();
 
+        // Non-replicated requests are only made by this replica. The adapter
+        // treats them like any other request, but they are accounted for
+        // separately since a failure here can not be masked by other replicas.
+        if !req.is_replicated {
+            self.metrics.non_replicated_requests.inc();
+            debug!(self.logger, "Serving non-replicated request to {}", req.url);
+        }
+
         let uri = req.url.parse::<Uri>().map_err(|err| {
             debug!(self.logger, "Failed to parse URL: {}", err);
             self.metrics
@@ -102,6 +110,9 @@ impl CanisterHttpService for CanisterHttp {
                 HttpMethod::Get => Ok(Method::GET),
                 HttpMethod::Post => Ok(Method::POST),
                 HttpMethod::Head => Ok(Method::HEAD),
+                HttpMethod::Put => Ok(Method::PUT),
+                HttpMethod::Delete => Ok(Method::DELETE),
+                HttpMethod::Patch => Ok(Method::PATCH),
                 _ => {
                     self.metrics
                         .request_errors
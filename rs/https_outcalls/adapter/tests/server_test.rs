@@ -115,9 +115,26 @@ MIGHAgEAMBMGByqGSM49AgEGCCqGSM49AwEHBG0wawIBAQQgob29X4H4m2XOkSZE
             .and(warp::path("head"))
             .map(|| warp::reply::reply());
 
+        let basic_put = warp::put()
+            .and(warp::path("put"))
+            .and(warp::body::json())
+            .map(|req: u64| Response::builder().body(req.to_string()));
+
+        let basic_delete = warp::delete()
+            .and(warp::path("delete"))
+            .map(|| warp::reply::reply());
+
+        let basic_patch = warp::patch()
+            .and(warp::path("patch"))
+            .and(warp::body::json())
+            .map(|req: u64| Response::builder().body(req.to_string()));
+
         let routes = basic_post
             .or(basic_get)
             .or(basic_head)
+            .or(basic_put)
+            .or(basic_delete)
+            .or(basic_patch)
             .or(get_response_size)
             .or(get_delay)
             .or(invalid_header);
@@ -147,6 +164,7 @@ MIGHAgEAMBMGByqGSM49AgEGCCqGSM49AwEHBG0wawIBAQQgob29X4H4m2XOkSZE
             body: "hello".to_string().as_bytes().to_vec(),
             max_response_size_bytes: 512,
             socks_proxy_allowed: false,
+            is_replicated: true,
         });
         let response = client.canister_http_send(request).await;
         let http_response = response.unwrap().into_inner();
@@ -170,6 +188,7 @@ MIGHAgEAMBMGByqGSM49AgEGCCqGSM49AwEHBG0wawIBAQQgob29X4H4m2XOkSZE
             body: "hello".to_string().as_bytes().to_vec(),
             max_response_size_bytes: 512,
             socks_proxy_allowed: false,
+            is_replicated: true,
         });
         let response = client.canister_http_send(request).await;
         assert_eq!(
@@ -198,6 +217,7 @@ MIGHAgEAMBMGByqGSM49AgEGCCqGSM49AwEHBG0wawIBAQQgob29X4H4m2XOkSZE
             body: "420".to_string().as_bytes().to_vec(),
             max_response_size_bytes: 512,
             socks_proxy_allowed: false,
+            is_replicated: true,
         });
 
         let response = client.canister_http_send(request).await;
@@ -222,11 +242,86 @@ MIGHAgEAMBMGByqGSM49AgEGCCqGSM49AwEHBG0wawIBAQQgob29X4H4m2XOkSZE
             body: "".to_string().as_bytes().to_vec(),
             max_response_size_bytes: 512,
             socks_proxy_allowed: false,
+            is_replicated: true,
+        });
+
+        let response = client.canister_http_send(request).await;
+        let http_response = response.unwrap().into_inner();
+        assert_eq!(http_response.status, StatusCode::OK.as_u16() as u32);
+    }
+
+    #[tokio::test]
+    async fn test_canister_http_server_put() {
+        let server_config = Config {
+            ..Default::default()
+        };
+
+        let url = start_server(CERT_INIT.get_or_init(generate_certs));
+        let mut client = spawn_grpc_server(server_config);
+
+        let request = tonic::Request::new(CanisterHttpSendRequest {
+            url: format!("https://{}/put", &url),
+            headers: Vec::new(),
+            method: HttpMethod::Put as i32,
+            body: "420".to_string().as_bytes().to_vec(),
+            max_response_size_bytes: 512,
+            socks_proxy_allowed: false,
+            is_replicated: true,
+        });
+
+        let response = client.canister_http_send(request).await;
+        let http_response = response.unwrap().into_inner();
+        assert_eq!(http_response.status, StatusCode::OK.as_u16() as u32);
+        assert_eq!(String::from_utf8_lossy(&http_response.content), "420");
+    }
+
+    #[tokio::test]
+    async fn test_canister_http_server_delete() {
+        let server_config = Config {
+            ..Default::default()
+        };
+
+        let url = start_server(CERT_INIT.get_or_init(generate_certs));
+        let mut client = spawn_grpc_server(server_config);
+
+        let request = tonic::Request::new(CanisterHttpSendRequest {
+            url: format!("https://{}/delete", &url),
+            headers: Vec::new(),
+            method: HttpMethod::Delete as i32,
+            body: "".to_string().as_bytes().to_vec(),
+            max_response_size_bytes: 512,
+            socks_proxy_allowed: false,
+            is_replicated: true,
+        });
+
+        let response = client.canister_http_send(request).await;
+        let http_response = response.unwrap().into_inner();
+        assert_eq!(http_response.status, StatusCode::OK.as_u16() as u32);
+    }
+
+    #[tokio::test]
+    async fn test_canister_http_server_patch() {
+        let server_config = Config {
+            ..Default::default()
+        };
+
+        let url = start_server(CERT_INIT.get_or_init(generate_certs));
+        let mut client = spawn_grpc_server(server_config);
+
+        let request = tonic::Request::new(CanisterHttpSendRequest {
+            url: format!("https://{}/patch", &url),
+            headers: Vec::new(),
+            method: HttpMethod::Patch as i32,
+            body: "421".to_string().as_bytes().to_vec(),
+            max_response_size_bytes: 512,
+            socks_proxy_allowed: false,
+            is_replicated: true,
         });
 
         let response = client.canister_http_send(request).await;
         let http_response = response.unwrap().into_inner();
         assert_eq!(http_response.status, StatusCode::OK.as_u16() as u32);
+        assert_eq!(String::from_utf8_lossy(&http_response.content), "421");
     }
 
     #[tokio::test]
@@ -247,6 +342,7 @@ MIGHAgEAMBMGByqGSM49AgEGCCqGSM49AwEHBG0wawIBAQQgob29X4H4m2XOkSZE
             body: format!("{}", response_limit + 1).as_bytes().to_vec(),
             max_response_size_bytes: response_limit,
             socks_proxy_allowed: false,
+            is_replicated: true,
         });
 
         let response = client.canister_http_send(request).await;
@@ -277,6 +373,7 @@ MIGHAgEAMBMGByqGSM49AgEGCCqGSM49AwEHBG0wawIBAQQgob29X4H4m2XOkSZE
             body: format!("{}", response_size).as_bytes().to_vec(),
             max_response_size_bytes: response_size * 2,
             socks_proxy_allowed: false,
+            is_replicated: true,
         });
 
         let response = client.canister_http_send(request).await;
@@ -302,6 +399,7 @@ MIGHAgEAMBMGByqGSM49AgEGCCqGSM49AwEHBG0wawIBAQQgob29X4H4m2XOkSZE
             body: format!("{}", delay).as_bytes().to_vec(),
             max_response_size_bytes: 512,
             socks_proxy_allowed: false,
+            is_replicated: true,
         });
 
         let response = client.canister_http_send(request).await;
@@ -336,6 +434,7 @@ MIGHAgEAMBMGByqGSM49AgEGCCqGSM49AwEHBG0wawIBAQQgob29X4H4m2XOkSZE
             body: "hello".to_string().as_bytes().to_vec(),
             max_response_size_bytes: 64,
             socks_proxy_allowed: false,
+            is_replicated: true,
         });
         let response = client.canister_http_send(request).await;
         assert_eq!(
@@ -365,6 +464,7 @@ MIGHAgEAMBMGByqGSM49AgEGCCqGSM49AwEHBG0wawIBAQQgob29X4H4m2XOkSZE
             body: "hello".as_bytes().to_vec(),
             max_response_size_bytes: response_limit,
             socks_proxy_allowed: false,
+            is_replicated: true,
         });
 
         let response = client.canister_http_send(request).await;
@@ -388,6 +488,7 @@ MIGHAgEAMBMGByqGSM49AgEGCCqGSM49AwEHBG0wawIBAQQgob29X4H4m2XOkSZE
             body: "hello".to_string().as_bytes().to_vec(),
             max_response_size_bytes: 512,
             socks_proxy_allowed: false,
+            is_replicated: true,
         });
         let response = client.canister_http_send(request).await;
         let _ = response.unwrap_err();
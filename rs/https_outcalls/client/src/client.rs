@@ -15,7 +15,7 @@ use ic_types::{
     canister_http::{
         validate_http_headers_and_body, CanisterHttpMethod, CanisterHttpReject,
         CanisterHttpRequest, CanisterHttpRequestContext, CanisterHttpResponse,
-        CanisterHttpResponseContent, Transform, MAX_CANISTER_HTTP_RESPONSE_BYTES,
+        CanisterHttpResponseContent, Replication, Transform, MAX_CANISTER_HTTP_RESPONSE_BYTES,
     },
     messages::{AnonymousQuery, AnonymousQueryResponse, Request},
     CanisterId, NumBytes,
@@ -135,6 +135,7 @@ impl NonBlockingChannel<CanisterHttpRequest> for CanisterHttpAdapterClientImpl {
                         http_method: request_http_method,
                         max_response_bytes: request_max_response_bytes,
                         transform: request_transform,
+                        replication: request_replication,
                         ..
                     },
             } = canister_http_request;
@@ -148,6 +149,9 @@ impl NonBlockingChannel<CanisterHttpRequest> for CanisterHttpAdapterClientImpl {
                         CanisterHttpMethod::GET => HttpMethod::Get.into(),
                         CanisterHttpMethod::POST => HttpMethod::Post.into(),
                         CanisterHttpMethod::HEAD => HttpMethod::Head.into(),
+                        CanisterHttpMethod::PUT => HttpMethod::Put.into(),
+                        CanisterHttpMethod::DELETE => HttpMethod::Delete.into(),
+                        CanisterHttpMethod::PATCH => HttpMethod::Patch.into(),
                     },
                     max_response_size_bytes: request_max_response_bytes.unwrap_or(NumBytes::new(MAX_CANISTER_HTTP_RESPONSE_BYTES)).get(),
                     headers: request_headers
@@ -161,7 +165,8 @@ impl NonBlockingChannel<CanisterHttpRequest> for CanisterHttpAdapterClientImpl {
                     // Socks proxy is only enabled on system subnets.
                     // socks_proxy_allowed: matches!(subnet_type, SubnetType::System)
                     // WIP: enabled when feature is complete.
-                    socks_proxy_allowed: false
+                    socks_proxy_allowed: false,
+                    is_replicated: matches!(request_replication, Replication::FullyReplicated),
                 })
                 .map_err(|grpc_status| {
                     (
@@ -425,6 +430,7 @@ mod tests {
                     context: vec![],
                 }),
                 time: mock_time(),
+                replication: Replication::FullyReplicated,
             },
         }
     }
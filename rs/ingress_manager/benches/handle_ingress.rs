@@ -31,7 +31,9 @@ use ic_registry_client::client::RegistryClientImpl;
 use ic_registry_keys::make_subnet_record_key;
 use ic_registry_proto_data_provider::ProtoRegistryDataProvider;
 use ic_registry_subnet_type::SubnetType;
-use ic_replicated_state::{BitcoinState, CanisterQueues, ReplicatedState, SystemMetadata};
+use ic_replicated_state::{
+    BitcoinState, CanisterQueues, CanisterSnapshots, ReplicatedState, SystemMetadata,
+};
 use ic_test_utilities::{
     consensus::MockConsensusCache,
     crypto::temp_crypto_component_with_fake_registry,
@@ -187,6 +189,7 @@ where
                         metadata,
                         CanisterQueues::default(),
                         BitcoinState::default(),
+                        CanisterSnapshots::default(),
                     )),
                 )
             });
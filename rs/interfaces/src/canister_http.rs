@@ -68,6 +68,12 @@ pub enum CanisterHttpPermanentValidationError {
         signers: Vec<NodeId>,
         expected_threshold: Threshold,
     },
+    /// The response to a non-replicated request was not signed by exactly the
+    /// designated node
+    InvalidNonReplicatedSigners {
+        designated_node: NodeId,
+        signers: Vec<NodeId>,
+    },
     /// The payload contains a duplicate response
     DuplicateResponse(CallbackId),
     DivergenceProofContainsMultipleCallbackIds,
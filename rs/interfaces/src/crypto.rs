@@ -23,6 +23,10 @@ pub use sign::ThresholdSigner;
 
 pub use sign::canister_threshold_sig::*;
 
+mod vetkd;
+
+pub use vetkd::VetKdProtocol;
+
 use ic_types::consensus::certification::CertificationContent;
 use ic_types::consensus::dkg as consensus_dkg;
 use ic_types::consensus::{
@@ -71,6 +75,10 @@ pub trait Crypto:
     + IDkgProtocol
     + ThresholdEcdsaSigner
     + ThresholdEcdsaSigVerifier
+    + ThresholdSchnorrSigner
+    + ThresholdSchnorrSigVerifier
+    // vetKD
+    + VetKdProtocol
     // CanisterHttpResponse
     + BasicSigner<CanisterHttpResponseMetadata>
     + BasicSigVerifier<CanisterHttpResponseMetadata>
@@ -133,6 +141,9 @@ impl<T> Crypto for T where
         + IDkgProtocol
         + ThresholdEcdsaSigner
         + ThresholdEcdsaSigVerifier
+        + ThresholdSchnorrSigner
+        + ThresholdSchnorrSigVerifier
+        + VetKdProtocol
         + BasicSigVerifierByPublicKey<MessageId>
         + BasicSigVerifierByPublicKey<WebAuthnEnvelope>
         + ThresholdSigner<CatchUpContent>
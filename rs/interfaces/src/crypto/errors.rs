@@ -3,11 +3,13 @@ use ic_types::crypto::canister_threshold_sig::error::{
     IDkgVerifyComplaintError, IDkgVerifyDealingPrivateError, IDkgVerifyDealingPublicError,
     IDkgVerifyInitialDealingsError, IDkgVerifyOpeningError, IDkgVerifyTranscriptError,
     ThresholdEcdsaVerifyCombinedSignatureError, ThresholdEcdsaVerifySigShareError,
+    ThresholdSchnorrVerifyCombinedSignatureError, ThresholdSchnorrVerifySigShareError,
 };
 use ic_types::crypto::threshold_sig::ni_dkg::errors::create_transcript_error::DkgCreateTranscriptError;
 use ic_types::crypto::threshold_sig::ni_dkg::errors::key_removal_error::DkgKeyRemovalError;
 use ic_types::crypto::threshold_sig::ni_dkg::errors::load_transcript_error::DkgLoadTranscriptError;
 use ic_types::crypto::threshold_sig::ni_dkg::errors::verify_dealing_error::DkgVerifyDealingError;
+use ic_types::crypto::vetkd::{VetKdKeyShareVerificationError, VetKdKeyVerificationError};
 use ic_types::crypto::CryptoError;
 use ic_types::registry::RegistryClientError;
 
@@ -333,6 +335,91 @@ impl ErrorReproducibility for ThresholdEcdsaVerifyCombinedSignatureError {
     }
 }
 
+impl ErrorReproducibility for ThresholdSchnorrVerifySigShareError {
+    fn is_reproducible(&self) -> bool {
+        // The match below is intentionally explicit on all possible values,
+        // to avoid defaults, which might be error-prone.
+        // Upon addition of any new error this match has to be updated.
+
+        // Signature share verification does not depend on any local or private
+        // state and so is inherently replicated.
+        match self {
+            // The error returned if the signature share is invalid
+            Self::InvalidSignatureShare => true,
+            // The purported signer does exist in the transcript
+            Self::InvalidArgumentMissingSignerInTranscript { .. } => true,
+            // The signature share could not even be deserialized correctly
+            Self::SerializationError { .. } => true,
+            // The share included an invalid commitment type
+            Self::InternalError { .. } => true,
+            // The algorithm is the same on all replicas
+            Self::UnsupportedAlgorithm => true,
+        }
+    }
+}
+
+impl ErrorReproducibility for ThresholdSchnorrVerifyCombinedSignatureError {
+    fn is_reproducible(&self) -> bool {
+        // The match below is intentionally explicit on all possible values,
+        // to avoid defaults, which might be error-prone.
+        // Upon addition of any new error this match has to be updated.
+
+        // Signature verification does not depend on any local or
+        // private state and so is inherently replicated.
+        match self {
+            // The Schnorr signature was invalid or did not match the
+            // presignature transcript
+            Self::InvalidSignature => true,
+            // The signature could not even be deserialized correctly
+            Self::SerializationError { .. } => true,
+            // Invalid commitment type or wrong algorithm ID
+            Self::InternalError { .. } => true,
+            // The algorithm is the same on all replicas
+            Self::UnsupportedAlgorithm => true,
+        }
+    }
+}
+
+impl ErrorReproducibility for VetKdKeyShareVerificationError {
+    fn is_reproducible(&self) -> bool {
+        // The match below is intentionally explicit on all possible values,
+        // to avoid defaults, which might be error-prone.
+        // Upon addition of any new error this match has to be updated.
+        match self {
+            // false, as the transcript may not have been loaded yet
+            Self::ThresholdSigDataNotFound(_) => false,
+            // true, as this is a stable property of the arguments.
+            Self::InvalidArgumentTransportPublicKey => true,
+            // true, as this is a stable property of the arguments.
+            Self::InvalidArgumentEncryptedKeyShare => true,
+            // true, as verification does not depend on any private state
+            Self::VerificationError => true,
+            // true, as verification does not depend on any private state
+            Self::InternalError { .. } => true,
+        }
+    }
+}
+
+impl ErrorReproducibility for VetKdKeyVerificationError {
+    fn is_reproducible(&self) -> bool {
+        // The match below is intentionally explicit on all possible values,
+        // to avoid defaults, which might be error-prone.
+        // Upon addition of any new error this match has to be updated.
+        match self {
+            // false, as the transcript may not have been loaded yet
+            Self::ThresholdSigDataNotFound(_) => false,
+            // true, as this is a stable property of the arguments.
+            Self::InvalidArgumentEncryptedKey => true,
+            // true, as this is a stable property of the arguments.
+            Self::InvalidArgumentTransportPublicKey => true,
+            // true, as verification does not depend on any private state
+            Self::VerificationError => true,
+            // true, as verification does not depend on any private state
+            Self::InternalError { .. } => true,
+        }
+    }
+}
+
 impl ErrorReproducibility for IDkgVerifyOpeningError {
     fn is_reproducible(&self) -> bool {
         match self {
@@ -36,7 +36,9 @@ use ic_types::crypto::canister_threshold_sig::error::{
     IDkgVerifyDealingPrivateError, IDkgVerifyDealingPublicError, IDkgVerifyInitialDealingsError,
     IDkgVerifyOpeningError, IDkgVerifyTranscriptError, ThresholdEcdsaCombineSigSharesError,
     ThresholdEcdsaSignShareError, ThresholdEcdsaVerifyCombinedSignatureError,
-    ThresholdEcdsaVerifySigShareError,
+    ThresholdEcdsaVerifySigShareError, ThresholdSchnorrCombineSigSharesError,
+    ThresholdSchnorrSignShareError, ThresholdSchnorrVerifyCombinedSignatureError,
+    ThresholdSchnorrVerifySigShareError,
 };
 use ic_types::crypto::canister_threshold_sig::idkg::{
     BatchSignedIDkgDealing, IDkgComplaint, IDkgOpening, IDkgTranscript, IDkgTranscriptParams,
@@ -44,6 +46,7 @@ use ic_types::crypto::canister_threshold_sig::idkg::{
 };
 use ic_types::crypto::canister_threshold_sig::{
     ThresholdEcdsaCombinedSignature, ThresholdEcdsaSigInputs, ThresholdEcdsaSigShare,
+    ThresholdSchnorrCombinedSignature, ThresholdSchnorrSigInputs, ThresholdSchnorrSigShare,
 };
 use std::collections::{BTreeMap, HashSet};
 
@@ -421,3 +424,47 @@ pub trait ThresholdEcdsaSigVerifier {
         signature: &ThresholdEcdsaCombinedSignature,
     ) -> Result<(), ThresholdEcdsaVerifyCombinedSignatureError>;
 }
+
+/// A Crypto Component interface to generate threshold Schnorr signature shares.
+///
+/// Threshold Schnorr signatures reuse the IDKG protocol for generating the
+/// signing key and the presignatures: a presignature consists of a single
+/// unmasked transcript of a random value, which is used as the nonce of
+/// exactly one signature.
+pub trait ThresholdSchnorrSigner {
+    /// Generate a signature share.
+    fn create_schnorr_sig_share(
+        &self,
+        inputs: &ThresholdSchnorrSigInputs,
+    ) -> Result<ThresholdSchnorrSigShare, ThresholdSchnorrSignShareError>;
+}
+
+/// A Crypto Component interface to perform public operations in the threshold
+/// Schnorr signature scheme.
+pub trait ThresholdSchnorrSigVerifier {
+    /// Verify that the given signature share was correctly created from
+    /// `inputs`.
+    fn verify_schnorr_sig_share(
+        &self,
+        signer: NodeId,
+        inputs: &ThresholdSchnorrSigInputs,
+        share: &ThresholdSchnorrSigShare,
+    ) -> Result<(), ThresholdSchnorrVerifySigShareError>;
+
+    /// Combine the given signature shares into a conventional Schnorr
+    /// signature of the algorithm given by `inputs`.
+    ///
+    /// The signature is returned as raw bytes.
+    fn combine_schnorr_sig_shares(
+        &self,
+        inputs: &ThresholdSchnorrSigInputs,
+        shares: &BTreeMap<NodeId, ThresholdSchnorrSigShare>,
+    ) -> Result<ThresholdSchnorrCombinedSignature, ThresholdSchnorrCombineSigSharesError>;
+
+    /// Verify that a combined signature was properly created from the inputs.
+    fn verify_schnorr_combined_sig(
+        &self,
+        inputs: &ThresholdSchnorrSigInputs,
+        signature: &ThresholdSchnorrCombinedSignature,
+    ) -> Result<(), ThresholdSchnorrVerifyCombinedSignatureError>;
+}
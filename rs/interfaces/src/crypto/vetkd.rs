@@ -0,0 +1,48 @@
+//! Traits for verifiably encrypted threshold key derivation (vetKD)
+use ic_types::crypto::vetkd::{
+    VetKdArgs, VetKdEncryptedKey, VetKdEncryptedKeyShare, VetKdKeyShareCombinationError,
+    VetKdKeyShareCreationError, VetKdKeyShareVerificationError, VetKdKeyVerificationError,
+};
+use ic_types::NodeId;
+use std::collections::BTreeMap;
+
+/// Verifiably encrypted threshold key derivation using the subnet's
+/// NI-DKG threshold key as master key.
+pub trait VetKdProtocol {
+    /// Creates an encrypted key share for the derivation path and input in
+    /// `args`, encrypted under the transport public key in `args`.
+    ///
+    /// The share is created with this node's share of the threshold key of
+    /// the NI-DKG transcript identified by `args.ni_dkg_id`, which must have
+    /// been loaded previously.
+    fn create_encrypted_key_share(
+        &self,
+        args: VetKdArgs,
+    ) -> Result<VetKdEncryptedKeyShare, VetKdKeyShareCreationError>;
+
+    /// Verifies that `key_share` was created by `signer` for `args`.
+    fn verify_encrypted_key_share(
+        &self,
+        signer: NodeId,
+        key_share: &VetKdEncryptedKeyShare,
+        args: &VetKdArgs,
+    ) -> Result<(), VetKdKeyShareVerificationError>;
+
+    /// Combines encrypted key shares into an encrypted key.
+    ///
+    /// The shares need not have been verified individually; invalid shares
+    /// are skipped if the combination of all shares is not valid. The
+    /// resulting encrypted key is guaranteed to be valid for `args`.
+    fn combine_encrypted_key_shares(
+        &self,
+        shares: &BTreeMap<NodeId, VetKdEncryptedKeyShare>,
+        args: &VetKdArgs,
+    ) -> Result<VetKdEncryptedKey, VetKdKeyShareCombinationError>;
+
+    /// Verifies that `key` is a valid encrypted key for `args`.
+    fn verify_encrypted_key(
+        &self,
+        key: &VetKdEncryptedKey,
+        args: &VetKdArgs,
+    ) -> Result<(), VetKdKeyVerificationError>;
+}
@@ -4,6 +4,7 @@ use crate::artifact_pool::UnvalidatedArtifact;
 use ic_types::artifact::EcdsaMessageId;
 use ic_types::consensus::ecdsa::{
     EcdsaComplaint, EcdsaMessage, EcdsaOpening, EcdsaPrefixOf, EcdsaSigShare, EcdsaStats,
+    SchnorrSigShare, VetKdKeyShare,
 };
 use ic_types::crypto::canister_threshold_sig::idkg::{IDkgDealingSupport, SignedIDkgDealing};
 
@@ -88,6 +89,30 @@ pub trait EcdsaPoolSection: Send + Sync {
         unimplemented!()
     }
 
+    /// Iterator for Schnorr signature share objects.
+    fn schnorr_signature_shares(
+        &self,
+    ) -> Box<dyn Iterator<Item = (EcdsaMessageId, SchnorrSigShare)> + '_>;
+
+    /// Iterator for Schnorr signature share objects matching the prefix.
+    fn schnorr_signature_shares_by_prefix(
+        &self,
+        _prefix: EcdsaPrefixOf<SchnorrSigShare>,
+    ) -> Box<dyn Iterator<Item = (EcdsaMessageId, SchnorrSigShare)> + '_> {
+        unimplemented!()
+    }
+
+    /// Iterator for vetKD key share objects.
+    fn vetkd_key_shares(&self) -> Box<dyn Iterator<Item = (EcdsaMessageId, VetKdKeyShare)> + '_>;
+
+    /// Iterator for vetKD key share objects matching the prefix.
+    fn vetkd_key_shares_by_prefix(
+        &self,
+        _prefix: EcdsaPrefixOf<VetKdKeyShare>,
+    ) -> Box<dyn Iterator<Item = (EcdsaMessageId, VetKdKeyShare)> + '_> {
+        unimplemented!()
+    }
+
     /// Iterator for complaint objects.
     fn complaints(&self) -> Box<dyn Iterator<Item = (EcdsaMessageId, EcdsaComplaint)> + '_>;
 
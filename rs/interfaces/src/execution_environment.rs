@@ -4,11 +4,12 @@ mod errors;
 pub use errors::{CanisterOutOfCyclesError, HypervisorError, TrapCode};
 use ic_base_types::NumBytes;
 use ic_error_types::UserError;
-use ic_ic00_types::EcdsaKeyId;
+use ic_ic00_types::{EcdsaKeyId, SchnorrKeyId};
 use ic_registry_provisional_whitelist::ProvisionalWhitelist;
 use ic_registry_subnet_type::SubnetType;
 use ic_sys::{PageBytes, PageIndex};
 use ic_types::{
+    canister_log::CanisterLog,
     crypto::canister_threshold_sig::MasterEcdsaPublicKey,
     ingress::{IngressStatus, WasmResult},
     messages::{
@@ -439,39 +440,39 @@ pub trait SystemApi {
     /// id in case of requests or the user id in case of an ingress message.
     fn ic0_msg_caller_copy(
         &self,
-        dst: u32,
-        offset: u32,
-        size: u32,
+        dst: usize,
+        offset: usize,
+        size: usize,
         heap: &mut [u8],
     ) -> HypervisorResult<()>;
 
     /// Returns the size of the opaque caller blob.
-    fn ic0_msg_caller_size(&self) -> HypervisorResult<u32>;
+    fn ic0_msg_caller_size(&self) -> HypervisorResult<usize>;
 
     /// Returns the size of msg.payload.
-    fn ic0_msg_arg_data_size(&self) -> HypervisorResult<u32>;
+    fn ic0_msg_arg_data_size(&self) -> HypervisorResult<usize>;
 
     /// Copies `length` bytes from msg.payload[offset..offset+size] to
     /// memory[dst..dst+size].
     fn ic0_msg_arg_data_copy(
         &self,
-        dst: u32,
-        offset: u32,
-        size: u32,
+        dst: usize,
+        offset: usize,
+        size: usize,
         heap: &mut [u8],
     ) -> HypervisorResult<()>;
 
     /// Used to look up the size of the method_name that the message wants to
     /// call. Can only be called in the context of inspecting messages.
-    fn ic0_msg_method_name_size(&self) -> HypervisorResult<u32>;
+    fn ic0_msg_method_name_size(&self) -> HypervisorResult<usize>;
 
     /// Used to copy the method_name that the message wants to call to heap. Can
     /// only be called in the context of inspecting messages.
     fn ic0_msg_method_name_copy(
         &self,
-        dst: u32,
-        offset: u32,
-        size: u32,
+        dst: usize,
+        offset: usize,
+        size: usize,
         heap: &mut [u8],
     ) -> HypervisorResult<()>;
 
@@ -484,8 +485,8 @@ pub trait SystemApi {
     /// it to the (initially empty) data reply.
     fn ic0_msg_reply_data_append(
         &mut self,
-        src: u32,
-        size: u32,
+        src: usize,
+        size: usize,
         heap: &[u8],
     ) -> HypervisorResult<()>;
 
@@ -501,14 +502,14 @@ pub trait SystemApi {
     fn ic0_msg_reject_code(&self) -> HypervisorResult<i32>;
 
     /// Replies to sender with an error message
-    fn ic0_msg_reject(&mut self, src: u32, size: u32, heap: &[u8]) -> HypervisorResult<()>;
+    fn ic0_msg_reject(&mut self, src: usize, size: usize, heap: &[u8]) -> HypervisorResult<()>;
 
     /// Returns the length of the reject message in bytes.
     ///
     /// # Panics
     ///
     /// This traps if not invoked from a reject callback.
-    fn ic0_msg_reject_msg_size(&self) -> HypervisorResult<u32>;
+    fn ic0_msg_reject_msg_size(&self) -> HypervisorResult<usize>;
 
     /// Copies length bytes from self.reject_msg[offset..offset+size] to
     /// memory[dst..dst+size]
@@ -520,9 +521,9 @@ pub trait SystemApi {
     /// called from inside a reject callback.
     fn ic0_msg_reject_msg_copy(
         &self,
-        dst: u32,
-        offset: u32,
-        size: u32,
+        dst: usize,
+        offset: usize,
+        size: usize,
         heap: &mut [u8],
     ) -> HypervisorResult<()>;
 
@@ -533,9 +534,9 @@ pub trait SystemApi {
     /// canister to heap[dst..dst+size].
     fn ic0_canister_self_copy(
         &mut self,
-        dst: u32,
-        offset: u32,
-        size: u32,
+        dst: usize,
+        offset: usize,
+        size: usize,
         heap: &mut [u8],
     ) -> HypervisorResult<()>;
 
@@ -546,17 +547,22 @@ pub trait SystemApi {
     /// controller to heap[dst..dst+size].
     fn ic0_controller_copy(
         &mut self,
-        dst: u32,
-        offset: u32,
-        size: u32,
+        dst: usize,
+        offset: usize,
+        size: usize,
         heap: &mut [u8],
     ) -> HypervisorResult<()>;
 
     /// Outputs the specified bytes on the heap as a string on STDOUT.
-    fn ic0_debug_print(&self, src: u32, size: u32, heap: &[u8]) -> HypervisorResult<()>;
+    fn ic0_debug_print(&self, src: usize, size: usize, heap: &[u8]) -> HypervisorResult<()>;
 
     /// Traps, with a possibly helpful message
-    fn ic0_trap(&self, src: u32, size: u32, heap: &[u8]) -> HypervisorResult<()>;
+    fn ic0_trap(&self, src: usize, size: usize, heap: &[u8]) -> HypervisorResult<()>;
+
+    /// Saves the specified bytes on the heap to the canister log, if the
+    /// execution is replicated. Called for the messages of both
+    /// `ic0.debug_print` and `ic0.trap`; the latter are marked as traps.
+    fn save_log_message(&mut self, is_trap: bool, src: usize, size: usize, heap: &[u8]);
 
     /// Begins assembling a call to the canister specified by
     /// callee_src/callee_size at method name_src/name_size. Two mandatory
@@ -570,10 +576,10 @@ pub trait SystemApi {
     #[allow(clippy::too_many_arguments)]
     fn ic0_call_new(
         &mut self,
-        callee_src: u32,
-        callee_size: u32,
-        name_src: u32,
-        name_len: u32,
+        callee_src: usize,
+        callee_size: usize,
+        name_src: usize,
+        name_len: usize,
         reply_fun: u32,
         reply_env: u32,
         reject_fun: u32,
@@ -584,7 +590,12 @@ pub trait SystemApi {
     /// Appends the specified bytes to the argument of the call. Initially, the
     /// argument is empty. This can be called multiple times between
     /// `ic0.call_new` and `ic0.call_perform`.
-    fn ic0_call_data_append(&mut self, src: u32, size: u32, heap: &[u8]) -> HypervisorResult<()>;
+    fn ic0_call_data_append(
+        &mut self,
+        src: usize,
+        size: usize,
+        heap: &[u8],
+    ) -> HypervisorResult<()>;
 
     /// Specifies the closure to be called if the reply/reject closures trap.
     /// Can be called at most once between `ic0.call_new` and
@@ -593,6 +604,13 @@ pub trait SystemApi {
     /// See https://sdk.dfinity.org/docs/interface-spec/index.html#system-api-call
     fn ic0_call_on_cleanup(&mut self, fun: u32, env: u32) -> HypervisorResult<()>;
 
+    /// Turns the call under construction into a best-effort call: its
+    /// response is not guaranteed to be delivered and, if no response is
+    /// received within `timeout_seconds` (capped at a system-defined maximum),
+    /// the call is rejected with `SYS_UNKNOWN`. Can be called at most once
+    /// between `ic0.call_new` and `ic0.call_perform`.
+    fn ic0_call_with_best_effort_response(&mut self, timeout_seconds: u32) -> HypervisorResult<()>;
+
     /// (deprecated) Please use `ic0_call_cycles_add128` instead, as this API
     /// can only add a 64-bit value.
     ///
@@ -726,6 +744,58 @@ pub trait SystemApi {
     /// The canister can query the IC for its version.
     fn ic0_canister_version(&self) -> HypervisorResult<u64>;
 
+    /// Checks whether the principal identified by `src`/`size` is one of the
+    /// controllers of the canister. Returns 1 if it is and 0 otherwise.
+    ///
+    /// Traps if the bytes do not form a valid principal.
+    fn ic0_is_controller(&self, src: usize, size: usize, heap: &[u8]) -> HypervisorResult<u32>;
+
+    /// Returns 1 if the canister is being run in replicated mode (e.g. an
+    /// update call or a replicated query) and 0 otherwise (e.g. a query
+    /// executed by a single replica).
+    fn ic0_in_replicated_execution(&self) -> HypervisorResult<i32>;
+
+    /// Copies to the canister memory at `dst` the 128-bit amount of cycles
+    /// withdrawn when making an inter-canister call with a method name of
+    /// `method_name_size` bytes and an argument of `payload_size` bytes.
+    fn ic0_cost_call(
+        &self,
+        method_name_size: u64,
+        payload_size: u64,
+        dst: usize,
+        heap: &mut [u8],
+    ) -> HypervisorResult<()>;
+
+    /// Copies to the canister memory at `dst` the 128-bit amount of cycles
+    /// charged for creating a canister.
+    fn ic0_cost_create_canister(&self, dst: usize, heap: &mut [u8]) -> HypervisorResult<()>;
+
+    /// Copies to the canister memory at `dst` the 128-bit amount of cycles
+    /// charged for an HTTP outcall with a request of `request_size` bytes and
+    /// a response of at most `max_res_bytes` bytes.
+    fn ic0_cost_http_request(
+        &self,
+        request_size: u64,
+        max_res_bytes: u64,
+        dst: usize,
+        heap: &mut [u8],
+    ) -> HypervisorResult<()>;
+
+    /// Copies to the canister memory at `dst` the 128-bit amount of cycles
+    /// charged for a signature with the ECDSA key whose name is given by
+    /// `src`/`size` and whose curve is given by `curve`.
+    ///
+    /// Returns 0 on success, 1 if the curve is unknown and 2 if no subnet
+    /// signs with the key. Nothing is copied in the error cases.
+    fn ic0_cost_sign_with_ecdsa(
+        &self,
+        src: usize,
+        size: usize,
+        curve: u32,
+        dst: usize,
+        heap: &mut [u8],
+    ) -> HypervisorResult<u32>;
+
     /// The canister can query the "performance counter", which is
     /// a deterministic monotonically increasing integer approximating
     /// the amount of work the canister has done since the beginning of
@@ -787,7 +857,7 @@ pub trait SystemApi {
     /// The amount of cycles is represented by a 128-bit value
     /// and is copied in the canister memory starting
     /// starting at the location `dst`.
-    fn ic0_canister_cycle_balance128(&self, dst: u32, heap: &mut [u8]) -> HypervisorResult<()>;
+    fn ic0_canister_cycle_balance128(&self, dst: usize, heap: &mut [u8]) -> HypervisorResult<()>;
 
     /// (deprecated) Please use `ic0_msg_cycles_available128` instead.
     /// This API supports only 64-bit values.
@@ -803,7 +873,7 @@ pub trait SystemApi {
     /// The amount of cycles is represented by a 128-bit value
     /// and is copied in the canister memory starting
     /// starting at the location `dst`.
-    fn ic0_msg_cycles_available128(&self, dst: u32, heap: &mut [u8]) -> HypervisorResult<()>;
+    fn ic0_msg_cycles_available128(&self, dst: usize, heap: &mut [u8]) -> HypervisorResult<()>;
 
     /// (deprecated) Please use `ic0_msg_cycles_refunded128` instead.
     /// This API supports only 64-bit values.
@@ -819,7 +889,7 @@ pub trait SystemApi {
     /// The amount of cycles is represented by a 128-bit value
     /// and is copied in the canister memory starting
     /// starting at the location `dst`.
-    fn ic0_msg_cycles_refunded128(&self, dst: u32, heap: &mut [u8]) -> HypervisorResult<()>;
+    fn ic0_msg_cycles_refunded128(&self, dst: usize, heap: &mut [u8]) -> HypervisorResult<()>;
 
     /// (deprecated) Please use `ic0_msg_cycles_accept128` instead.
     /// This API supports only 64-bit values.
@@ -865,13 +935,18 @@ pub trait SystemApi {
     fn ic0_msg_cycles_accept128(
         &mut self,
         max_amount: Cycles,
-        dst: u32,
+        dst: usize,
         heap: &mut [u8],
     ) -> HypervisorResult<()>;
 
     /// Sets the certified data for the canister.
     /// See: https://sdk.dfinity.org/docs/interface-spec/index.html#system-api-certified-data
-    fn ic0_certified_data_set(&mut self, src: u32, size: u32, heap: &[u8]) -> HypervisorResult<()>;
+    fn ic0_certified_data_set(
+        &mut self,
+        src: usize,
+        size: usize,
+        heap: &[u8],
+    ) -> HypervisorResult<()>;
 
     /// If run in non-replicated execution (i.e. query),
     /// returns 1 if the data certificate is present, 0 otherwise.
@@ -882,16 +957,16 @@ pub trait SystemApi {
     /// Returns the size of the data certificate if it is present
     /// (i.e. data_certificate_present returns 1).
     /// Traps if data_certificate_present returns 0.
-    fn ic0_data_certificate_size(&self) -> HypervisorResult<i32>;
+    fn ic0_data_certificate_size(&self) -> HypervisorResult<usize>;
 
     /// Copies the data certificate into the heap if it is present
     /// (i.e. data_certificate_present returns 1).
     /// Traps if data_certificate_present returns 0.
     fn ic0_data_certificate_copy(
         &self,
-        dst: u32,
-        offset: u32,
-        size: u32,
+        dst: usize,
+        offset: usize,
+        size: usize,
         heap: &mut [u8],
     ) -> HypervisorResult<()>;
 
@@ -978,6 +1053,7 @@ pub trait Scheduler: Send {
         state: Self::State,
         randomness: Randomness,
         ecdsa_subnet_public_keys: BTreeMap<EcdsaKeyId, MasterEcdsaPublicKey>,
+        schnorr_subnet_public_keys: BTreeMap<SchnorrKeyId, MasterEcdsaPublicKey>,
         current_round: ExecutionRound,
         current_round_type: ExecutionRoundType,
         registry_settings: &RegistryExecutionSettings,
@@ -991,6 +1067,9 @@ pub struct WasmExecutionOutput {
     pub allocated_bytes: NumBytes,
     pub allocated_message_bytes: NumBytes,
     pub instance_stats: InstanceStats,
+    /// Log records produced by the execution, to be appended to the canister
+    /// log regardless of whether the execution succeeded.
+    pub canister_log: CanisterLog,
 }
 
 impl fmt::Display for WasmExecutionOutput {
@@ -1,4 +1,5 @@
 //! Messages used in various components.
+use ic_ic00_types::CanisterChangeOrigin;
 use ic_types::{
     messages::{Ingress, Request, Response, StopCanisterContext},
     methods::SystemMethod,
@@ -85,6 +86,20 @@ impl CanisterCall {
             CanisterCall::Ingress(_) => Cycles::zero(),
         }
     }
+
+    /// Returns the origin to record in the canister history for a change
+    /// requested by this message.
+    pub fn canister_change_origin(
+        &self,
+        sender_canister_version: Option<u64>,
+    ) -> CanisterChangeOrigin {
+        match self {
+            CanisterCall::Request(request) => {
+                CanisterChangeOrigin::from_canister(request.sender.get(), sender_canister_version)
+            }
+            CanisterCall::Ingress(ingress) => CanisterChangeOrigin::from_user(ingress.source.get()),
+        }
+    }
 }
 
 impl From<CanisterCall> for StopCanisterContext {
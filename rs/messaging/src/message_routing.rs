@@ -5,7 +5,7 @@ use crate::{
 use ic_config::execution_environment::{BitcoinConfig, Config as HypervisorConfig};
 use ic_constants::SMALL_APP_SUBNET_MAX_SIZE;
 use ic_cycles_account_manager::CyclesAccountManager;
-use ic_ic00_types::EcdsaKeyId;
+use ic_ic00_types::{EcdsaKeyId, SchnorrKeyId, VetKdKeyId};
 use ic_interfaces::{
     execution_environment::{IngressHistoryWriter, RegistryExecutionSettings, Scheduler},
     messaging::{MessageRouting, MessageRoutingError},
@@ -73,6 +73,8 @@ const PHASE_COMMIT: &str = "commit";
 const METRIC_PROCESS_BATCH_DURATION: &str = "mr_process_batch_duration_seconds";
 const METRIC_PROCESS_BATCH_PHASE_DURATION: &str = "mr_process_batch_phase_duration_seconds";
 const METRIC_TIMED_OUT_REQUESTS_TOTAL: &str = "mr_timed_out_requests_total";
+const METRIC_TIMED_OUT_CALLBACKS_TOTAL: &str = "mr_timed_out_callbacks_total";
+const METRIC_SHED_MESSAGES_TOTAL: &str = "mr_shed_messages_total";
 
 const CRITICAL_ERROR_MISSING_SUBNET_SIZE: &str = "cycles_account_manager_missing_subnet_size_error";
 const CRITICAL_ERROR_NO_CANISTER_ALLOCATION_RANGE: &str = "mr_empty_canister_allocation_range";
@@ -253,6 +255,10 @@ pub(crate) struct MessageRoutingMetrics {
     critical_error_no_canister_allocation_range: IntCounter,
     /// Number of timed out requests.
     pub timed_out_requests_total: IntCounter,
+    /// Number of timed out best-effort callbacks.
+    pub timed_out_callbacks_total: IntCounter,
+    /// Number of best-effort messages dropped due to memory pressure.
+    pub shed_messages_total: IntCounter,
 }
 
 impl MessageRoutingMetrics {
@@ -300,6 +306,14 @@ impl MessageRoutingMetrics {
                 METRIC_TIMED_OUT_REQUESTS_TOTAL,
                 "Count of timed out requests.",
             ),
+            timed_out_callbacks_total: metrics_registry.int_counter(
+                METRIC_TIMED_OUT_CALLBACKS_TOTAL,
+                "Count of timed out best-effort callbacks.",
+            ),
+            shed_messages_total: metrics_registry.int_counter(
+                METRIC_SHED_MESSAGES_TOTAL,
+                "Count of best-effort messages dropped due to memory pressure.",
+            ),
         }
     }
 
@@ -510,6 +524,8 @@ impl BatchProcessorImpl {
             let subnet_type = self.get_subnet_type(*subnet_id, registry_version);
             let subnet_features = self.get_subnet_features(*subnet_id, registry_version);
             let ecdsa_keys_held = self.get_ecdsa_keys_held(*subnet_id, registry_version);
+            let schnorr_keys_held = self.get_schnorr_keys_held(*subnet_id, registry_version);
+            let vetkd_keys_held = self.get_vetkd_keys_held(*subnet_id, registry_version);
             subnets.insert(
                 *subnet_id,
                 SubnetTopology {
@@ -518,6 +534,8 @@ impl BatchProcessorImpl {
                     subnet_type,
                     subnet_features,
                     ecdsa_keys_held,
+                    schnorr_keys_held,
+                    vetkd_keys_held,
                 },
             );
         }
@@ -623,6 +641,47 @@ impl BatchProcessorImpl {
             .unwrap_or_default()
     }
 
+    fn get_schnorr_keys_held(
+        &self,
+        subnet_id: SubnetId,
+        registry_version: RegistryVersion,
+    ) -> BTreeSet<SchnorrKeyId> {
+        let record = self.get_subnet_record(subnet_id, registry_version);
+        record
+            .ecdsa_config
+            .map(|ecdsa_config| {
+                ecdsa_config
+                    .schnorr_key_ids
+                    .into_iter()
+                    .map(|k| {
+                        SchnorrKeyId::try_from(k)
+                            .expect("Could not read SchnorrKeyId from protobuf")
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    fn get_vetkd_keys_held(
+        &self,
+        subnet_id: SubnetId,
+        registry_version: RegistryVersion,
+    ) -> BTreeSet<VetKdKeyId> {
+        let record = self.get_subnet_record(subnet_id, registry_version);
+        record
+            .ecdsa_config
+            .map(|ecdsa_config| {
+                ecdsa_config
+                    .vetkd_key_ids
+                    .into_iter()
+                    .map(|k| {
+                        VetKdKeyId::try_from(k).expect("Could not read VetKdKeyId from protobuf")
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
     fn get_max_number_of_canisters(
         &self,
         subnet_id: SubnetId,
@@ -17,7 +17,7 @@ use ic_types::{
         MAX_INTER_CANISTER_PAYLOAD_IN_BYTES,
     },
     xnet::QueueId,
-    CountBytes, SubnetId,
+    CoarseTime, CountBytes, SubnetId,
 };
 #[cfg(test)]
 use mockall::automock;
@@ -74,6 +74,7 @@ const LABEL_VALUE_TYPE_RESPONSE: &str = "response";
 const LABEL_VALUE_STATUS_SUCCESS: &str = "success";
 const LABEL_VALUE_STATUS_CANISTER_NOT_FOUND: &str = "canister_not_found";
 const LABEL_VALUE_STATUS_PAYLOAD_TOO_LARGE: &str = "payload_too_large";
+const LABEL_VALUE_STATUS_DEADLINE_EXPIRED: &str = "deadline_expired";
 
 const CRITICAL_ERROR_INFINITE_LOOP: &str = "mr_stream_builder_infinite_loop";
 const CRITICAL_ERROR_PAYLOAD_TOO_LARGE: &str = "mr_stream_builder_payload_too_large";
@@ -190,6 +191,7 @@ impl StreamBuilderImpl {
                     originator: req.sender,
                     respondent: req.receiver,
                     originator_reply_callback: req.sender_reply_callback,
+                    deadline: req.deadline,
                     refund: req.payment,
                     response_payload: Payload::Reject(
                         RejectContext::new_with_message_length_limit(
@@ -301,6 +303,9 @@ impl StreamBuilderImpl {
         let mut requests_to_reject = Vec::new();
         let mut oversized_requests = Vec::new();
 
+        // Best-effort messages with a deadline at or before this time have expired.
+        let current_time = CoarseTime::floor(state.time());
+
         let mut output_iter = state.output_into_iter();
         let mut last_output_size = usize::MAX;
 
@@ -326,6 +331,15 @@ impl StreamBuilderImpl {
             }
             last_output_size = output_size;
 
+            // Drop expired best-effort messages. The originator's callback has (or is
+            // about to) time out with a `SYS_UNKNOWN` reject, so delivering them would
+            // only waste stream capacity.
+            if msg.is_best_effort() && msg.deadline() <= current_time {
+                self.observe_message_status(&msg, LABEL_VALUE_STATUS_DEADLINE_EXPIRED);
+                validated_next(&mut output_iter, (queue_id, &msg));
+                continue;
+            }
+
             match routing_table.route(queue_id.dst_canister.get()) {
                 // Destination subnet found.
                 Some(dst_net_id) => {
@@ -430,6 +444,10 @@ impl StreamBuilderImpl {
                         RequestOrResponse::Request(req) => {
                             requests_to_reject.push(req);
                         }
+                        RequestOrResponse::Response(rep) if rep.is_best_effort() => {
+                            // A best-effort Response: silently discard it, best-effort
+                            // responses may be dropped at any time.
+                        }
                         RequestOrResponse::Response(rep) => {
                             // A Response: discard it.
                             error!(
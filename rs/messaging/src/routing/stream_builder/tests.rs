@@ -24,10 +24,10 @@ use ic_test_utilities_metrics::{
 use ic_types::{
     messages::{
         CallbackId, Payload, RejectContext, Request, RequestOrResponse, Response,
-        MAX_INTER_CANISTER_PAYLOAD_IN_BYTES_U64,
+        MAX_INTER_CANISTER_PAYLOAD_IN_BYTES_U64, NO_DEADLINE,
     },
     xnet::{StreamIndex, StreamIndexedQueue},
-    CanisterId, Cycles, SubnetId, Time,
+    CanisterId, CoarseTime, Cycles, SubnetId, Time,
 };
 use ic_utils::str::StrTruncate;
 use lazy_static::lazy_static;
@@ -113,6 +113,7 @@ fn reject_local_request() {
                     originator: msg.sender,
                     respondent: msg.receiver,
                     originator_reply_callback: msg.sender_reply_callback,
+                    deadline: msg.deadline,
                     refund: msg.payment,
                     response_payload: Payload::Reject(RejectContext {
                         code: RejectCode::SysFatal,
@@ -179,6 +180,7 @@ fn reject_local_request_for_subnet() {
                     originator: msg.sender,
                     respondent: msg.receiver,
                     originator_reply_callback: msg.sender_reply_callback,
+                    deadline: msg.deadline,
                     refund: msg.payment,
                     response_payload: Payload::Reject(RejectContext {
                         code: RejectCode::SysFatal,
@@ -607,6 +609,68 @@ fn build_streams_reject_response_on_unknown_destination_subnet() {
     });
 }
 
+#[test]
+fn build_streams_drops_expired_best_effort_messages() {
+    with_test_replica_logger(|log| {
+        let (stream_builder, mut provided_state, metrics_registry) = new_fixture(&log);
+        provided_state.metadata.batch_time = Time::from_nanos_since_unix_epoch(20_000_000_000);
+
+        let best_effort_request = |callback_id: u64, deadline_seconds: u32| {
+            RequestBuilder::default()
+                .sender(canister_test_id(1))
+                .receiver(canister_test_id(2))
+                .sender_reply_callback(CallbackId::from(callback_id))
+                .deadline(CoarseTime::from_secs_since_unix_epoch(deadline_seconds))
+                .build()
+        };
+        // Deadline is at the current batch time, i.e. expired.
+        let expired = best_effort_request(1, 20);
+        let not_expired = best_effort_request(2, 30);
+
+        // Set up the provided_canister_states.
+        let provided_canister_states =
+            canister_states_with_outputs(vec![expired, not_expired.clone()]);
+        provided_state.put_canister_states(provided_canister_states);
+
+        // Expect all messages in canister output queues to have been consumed.
+        let mut expected_state = consume_output_queues(&provided_state);
+
+        // The expired request is dropped, the other one is rejected for lack of a
+        // route (there is no routing table).
+        stream_builder.reject_local_request(
+            &mut expected_state,
+            &not_expired,
+            RejectCode::DestinationInvalid,
+            format!("No route to canister {}", not_expired.receiver),
+        );
+
+        let result_state = stream_builder.build_streams(provided_state);
+
+        assert_eq!(result_state, expected_state);
+
+        assert_routed_messages_eq(
+            metric_vec(&[
+                (
+                    &[
+                        (LABEL_TYPE, LABEL_VALUE_TYPE_REQUEST),
+                        (LABEL_STATUS, LABEL_VALUE_STATUS_CANISTER_NOT_FOUND),
+                    ],
+                    1,
+                ),
+                (
+                    &[
+                        (LABEL_TYPE, LABEL_VALUE_TYPE_REQUEST),
+                        (LABEL_STATUS, LABEL_VALUE_STATUS_DEADLINE_EXPIRED),
+                    ],
+                    1,
+                ),
+            ]),
+            &metrics_registry,
+        );
+        assert_eq_critical_errors(0, 0, &metrics_registry);
+    });
+}
+
 #[test]
 fn build_streams_with_messages_targeted_to_other_subnets() {
     with_test_replica_logger(|log| {
@@ -707,6 +771,7 @@ fn build_streams_with_oversized_payloads() {
             sender: local_canister,
             receiver: local_canister,
             sender_reply_callback: CallbackId::from(1),
+            deadline: NO_DEADLINE,
             payment: Cycles::new(1),
             method_name: method_name.clone(),
             method_payload: oversized_request_payload.clone(),
@@ -718,6 +783,7 @@ fn build_streams_with_oversized_payloads() {
             sender: local_canister,
             receiver: remote_canister,
             sender_reply_callback: CallbackId::from(2),
+            deadline: NO_DEADLINE,
             payment: Cycles::new(2),
             method_name,
             method_payload: oversized_request_payload,
@@ -727,6 +793,7 @@ fn build_streams_with_oversized_payloads() {
             originator: local_canister,
             respondent: remote_canister,
             originator_reply_callback: CallbackId::from(2),
+            deadline: NO_DEADLINE,
             refund: Cycles::new(2),
             response_payload: Payload::Reject(RejectContext::new(
                 RejectCode::CanisterError,
@@ -744,6 +811,7 @@ fn build_streams_with_oversized_payloads() {
             originator: local_canister,
             respondent: local_canister,
             originator_reply_callback: CallbackId::from(3),
+            deadline: NO_DEADLINE,
             refund: Cycles::new(3),
             response_payload: Payload::Data(oversized_response_payload),
         };
@@ -752,6 +820,7 @@ fn build_streams_with_oversized_payloads() {
             originator: local_canister,
             respondent: local_canister,
             originator_reply_callback: CallbackId::from(3),
+            deadline: NO_DEADLINE,
             refund: Cycles::new(3),
             response_payload: Payload::Reject(RejectContext::new(
                 RejectCode::CanisterError,
@@ -769,6 +838,7 @@ fn build_streams_with_oversized_payloads() {
             originator: local_canister,
             respondent: local_canister,
             originator_reply_callback: CallbackId::from(4),
+            deadline: NO_DEADLINE,
             refund: Cycles::new(4),
             response_payload: Payload::Reject(RejectContext::new(
                 RejectCode::SysTransient,
@@ -780,6 +850,7 @@ fn build_streams_with_oversized_payloads() {
             originator: local_canister,
             respondent: local_canister,
             originator_reply_callback: CallbackId::from(4),
+            deadline: NO_DEADLINE,
             refund: Cycles::new(4),
             response_payload: Payload::Reject(RejectContext::new(
                 RejectCode::SysTransient,
@@ -202,7 +202,7 @@ impl StreamHandlerImpl {
     ) -> Self {
         Self {
             subnet_id,
-            max_canister_memory_size: hypervisor_config.max_canister_memory_size,
+            max_canister_memory_size: hypervisor_config.canister_memory_limit(),
             subnet_memory_capacity: hypervisor_config.subnet_memory_capacity,
             subnet_message_memory_capacity: hypervisor_config.subnet_message_memory_capacity,
             metrics: StreamHandlerMetrics::new(metrics_registry),
@@ -630,8 +630,20 @@ impl StreamHandlerImpl {
                                 let code = reject_code_for_state_error(&err);
                                 stream.push(generate_reject_response(msg, code, err.to_string()))
                             }
+                            RequestOrResponse::Response(response) if response.is_best_effort() => {
+                                // Best-effort responses may be dropped (e.g. the callback
+                                // has already expired). The originator already got, or
+                                // will get, a `SYS_UNKNOWN` reject.
+                                debug!(
+                                    self.log,
+                                    "Dropping best-effort response that failed induction with error '{}': {:?}",
+                                    &err,
+                                    response
+                                );
+                            }
                             RequestOrResponse::Response(response) => {
-                                // Critical error, responses should always be inducted successfully.
+                                // Critical error, guaranteed responses should always be
+                                // inducted successfully.
                                 error!(
                                     self.log,
                                     "{}: Inducting response failed: {:?}",
@@ -851,6 +863,7 @@ fn generate_reject_response(
             originator: msg.sender,
             respondent: msg.receiver,
             originator_reply_callback: msg.sender_reply_callback,
+            deadline: msg.deadline,
             refund: msg.payment,
             response_payload: Payload::Reject(RejectContext::new_with_message_length_limit(
                 reject_code,
@@ -1271,6 +1271,7 @@ fn generate_reject_response_queue_full() {
             originator: msg.sender,
             respondent: msg.receiver,
             originator_reply_callback: msg.sender_reply_callback,
+            deadline: msg.deadline,
             refund: msg.payment,
             response_payload: Payload::Reject(RejectContext::new(
                 RejectCode::SysTransient,
@@ -1311,6 +1312,7 @@ fn generate_reject_response_canister_not_found() {
             originator: msg.sender,
             respondent: msg.receiver,
             originator_reply_callback: msg.sender_reply_callback,
+            deadline: msg.deadline,
             refund: msg.payment,
             response_payload: Payload::Reject(RejectContext::new(
                 RejectCode::DestinationInvalid,
@@ -6,7 +6,9 @@ use ic_interfaces::execution_environment::{
 use ic_logger::{fatal, ReplicaLogger};
 use ic_metrics::Timer;
 use ic_registry_subnet_features::SubnetFeatures;
-use ic_replicated_state::{NetworkTopology, ReplicatedState};
+use ic_replicated_state::{
+    replicated_state::BEST_EFFORT_MESSAGE_MEMORY_CAPACITY, NetworkTopology, ReplicatedState,
+};
 use ic_types::{batch::Batch, ExecutionRound};
 use std::sync::Arc;
 
@@ -17,6 +19,8 @@ const PHASE_INDUCTION: &str = "induction";
 const PHASE_EXECUTION: &str = "execution";
 const PHASE_MESSAGE_ROUTING: &str = "message_routing";
 const PHASE_TIME_OUT_REQUESTS: &str = "time_out_requests";
+const PHASE_TIME_OUT_CALLBACKS: &str = "time_out_callbacks";
+const PHASE_SHED_MESSAGES: &str = "shed_messages";
 
 pub(crate) trait StateMachine: Send {
     fn execute_round(
@@ -97,6 +101,20 @@ impl StateMachine for StateMachineImpl {
             .inc_by(timed_out_requests);
         self.observe_phase_duration(PHASE_TIME_OUT_REQUESTS, &phase_timer);
 
+        // Time out expired best-effort callbacks.
+        let phase_timer = Timer::start();
+        let timed_out_callbacks = state.time_out_callbacks(batch.time);
+        self.metrics
+            .timed_out_callbacks_total
+            .inc_by(timed_out_callbacks);
+        self.observe_phase_duration(PHASE_TIME_OUT_CALLBACKS, &phase_timer);
+
+        // Shed best-effort messages if above the memory limit.
+        let phase_timer = Timer::start();
+        let shed_messages = state.shed_best_effort_messages(BEST_EFFORT_MESSAGE_MEMORY_CAPACITY);
+        self.metrics.shed_messages_total.inc_by(shed_messages);
+        self.observe_phase_duration(PHASE_SHED_MESSAGES, &phase_timer);
+
         // Preprocess messages and add messages to the induction pool through the Demux.
         let phase_timer = Timer::start();
         let mut state_with_messages = self.demux.process_payload(state, batch.payload);
@@ -120,6 +138,7 @@ impl StateMachine for StateMachineImpl {
             state_with_messages,
             batch.randomness,
             batch.ecdsa_subnet_public_keys,
+            batch.schnorr_subnet_public_keys,
             ExecutionRound::from(batch.batch_number.get()),
             execution_round_type,
             registry_settings,
@@ -3,7 +3,7 @@ use crate::{
     routing::demux::MockDemux, routing::stream_builder::MockStreamBuilder,
     state_machine::StateMachineImpl,
 };
-use ic_ic00_types::EcdsaKeyId;
+use ic_ic00_types::{EcdsaKeyId, SchnorrKeyId};
 use ic_interfaces::execution_environment::Scheduler;
 use ic_interfaces_state_manager::StateManager;
 use ic_metrics::MetricsRegistry;
@@ -33,6 +33,7 @@ mock! {
             state: ic_replicated_state::ReplicatedState,
             randomness: ic_types::Randomness,
             ecdsa_subnet_public_keys: BTreeMap<EcdsaKeyId, MasterEcdsaPublicKey>,
+            schnorr_subnet_public_keys: BTreeMap<SchnorrKeyId, MasterEcdsaPublicKey>,
             current_round: ExecutionRound,
             current_round_type: ExecutionRoundType,
             registry_settings: &RegistryExecutionSettings,
@@ -86,11 +87,12 @@ fn test_fixture(provided_batch: &Batch) -> StateMachineTestFixture {
             always(),
             eq(provided_batch.randomness),
             eq(provided_batch.ecdsa_subnet_public_keys.clone()),
+            eq(provided_batch.schnorr_subnet_public_keys.clone()),
             eq(round),
             eq(round_type),
             eq(test_registry_settings()),
         )
-        .returning(|state, _, _, _, _, _| state);
+        .returning(|state, _, _, _, _, _, _| state);
 
     let mut stream_builder = Box::new(MockStreamBuilder::new());
     stream_builder
@@ -109,6 +111,8 @@ fn test_fixture(provided_batch: &Batch) -> StateMachineTestFixture {
             subnet_type: SubnetType::Application,
             subnet_features: SubnetFeatures::default(),
             ecdsa_keys_held: BTreeSet::new(),
+            schnorr_keys_held: BTreeSet::new(),
+            vetkd_keys_held: BTreeSet::new(),
         },
     );
 
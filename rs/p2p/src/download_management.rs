@@ -977,6 +977,7 @@ impl GossipImpl {
 pub mod tests {
     use super::*;
     use crate::download_prioritization::DownloadPrioritizerError;
+    use crate::utils::{TransportChannelIdMapper, P2P_TRANSPORT_CHANNELS};
     use ic_interfaces::artifact_manager::{ArtifactManager, OnArtifactError};
     use ic_interfaces::consensus_pool::ConsensusPoolCache;
     use ic_interfaces_registry::RegistryClient;
@@ -994,20 +995,20 @@ pub mod tests {
         types::ids::{node_id_to_u64, node_test_id, subnet_test_id},
     };
     use ic_test_utilities_registry::{add_subnet_record, SubnetRecordBuilder};
-    use ic_types::artifact::{DkgMessage, DkgMessageAttribute};
+    use ic_types::artifact::{DkgMessage, DkgMessageAttribute, StateSyncArtifactId};
     use ic_types::consensus::dkg::DealingContent;
     use ic_types::crypto::{
         threshold_sig::ni_dkg::{NiDkgDealing, NiDkgId, NiDkgTag, NiDkgTargetSubnet},
         {CryptoHash, CryptoHashOf},
     };
     use ic_types::signature::BasicSignature;
-    use ic_types::SubnetId;
     use ic_types::{
         artifact,
         artifact::{Artifact, ArtifactAttribute, ArtifactPriorityFn, Priority},
         chunkable::{ArtifactChunk, ArtifactChunkData, Chunkable, ChunkableArtifact},
         Height, NodeId, PrincipalId,
     };
+    use ic_types::{CryptoHashOfState, SubnetId};
     use parking_lot::Mutex;
     use std::collections::HashSet;
     use std::convert::TryFrom;
@@ -1175,7 +1176,9 @@ pub mod tests {
         // Set up the prioritizer.
         let metrics_registry = MetricsRegistry::new();
 
-        let transport_channels = vec![TransportChannelId::from(0)];
+        let transport_channels = (0..P2P_TRANSPORT_CHANNELS)
+            .map(TransportChannelId::from)
+            .collect();
 
         // Create fake peers.
         let artifact_manager = Arc::new(artifact_manager);
@@ -1769,4 +1772,53 @@ pub mod tests {
             gossip.metrics.integrity_hash_check_failed.get() as usize
         );
     }
+
+    /// Tests that chunks and state sync chunks are sent on different transport
+    /// channels than adverts and requests.
+    #[test]
+    fn transport_channel_mapping_separates_flows() {
+        let transport_channels = (0..P2P_TRANSPORT_CHANNELS)
+            .map(TransportChannelId::from)
+            .collect();
+        let mapper = TransportChannelIdMapper::new(transport_channels);
+
+        let advert = receive_check_test_create_adverts(0..1).pop().unwrap();
+        let chunk_request = GossipChunkRequest {
+            artifact_id: advert.artifact_id.clone(),
+            integrity_hash: advert.integrity_hash.clone(),
+            chunk_id: ChunkId::from(0),
+        };
+        let artifact_chunk = receive_check_test_create_chunk(
+            ChunkId::from(0),
+            advert.artifact_id.clone(),
+            0,
+            advert.integrity_hash.clone(),
+        );
+        let state_sync_chunk = receive_check_test_create_chunk(
+            ChunkId::from(1),
+            ArtifactId::StateSync(StateSyncArtifactId {
+                height: Height::from(1),
+                hash: CryptoHashOfState::from(CryptoHash(vec![])),
+            }),
+            0,
+            advert.integrity_hash.clone(),
+        );
+
+        let control_channel = mapper.map(&GossipMessage::Advert(advert));
+        assert_eq!(
+            mapper.map(&GossipMessage::ChunkRequest(chunk_request)),
+            control_channel
+        );
+        assert_eq!(
+            mapper.map(&GossipMessage::RetransmissionRequest(
+                ArtifactFilter::default()
+            )),
+            control_channel
+        );
+        let artifact_chunk_channel = mapper.map(&GossipMessage::Chunk(artifact_chunk));
+        let state_sync_chunk_channel = mapper.map(&GossipMessage::Chunk(state_sync_chunk));
+        assert_ne!(artifact_chunk_channel, control_channel);
+        assert_ne!(state_sync_chunk_channel, control_channel);
+        assert_ne!(state_sync_chunk_channel, artifact_chunk_channel);
+    }
 }
@@ -1,6 +1,5 @@
 use crate::{P2PError, P2PErrorCode, P2PResult};
 use bincode::{deserialize, serialize};
-use ic_interfaces_transport::TransportChannelId;
 use ic_protobuf::p2p::v1 as pb;
 use ic_protobuf::p2p::v1::gossip_chunk::Response;
 use ic_protobuf::p2p::v1::gossip_message::Body;
@@ -51,17 +50,6 @@ pub(crate) enum GossipMessage {
     RetransmissionRequest(ArtifactFilter),
 }
 
-/// A *Gossip* message can be converted into a
-/// `TransportChannelId`.
-impl From<&GossipMessage> for TransportChannelId {
-    /// The method returns the flow tag corresponding to the gossip message.
-    ///
-    /// Currently, the flow tag is always 0.
-    fn from(_: &GossipMessage) -> Self {
-        TransportChannelId::from(0)
-    }
-}
-
 /// A *Gossip* message can be converted into a
 /// `pb::GossipMessage`.
 impl From<GossipMessage> for pb::GossipMessage {
@@ -120,15 +120,31 @@ mod metrics;
 mod peer_context;
 
 pub use event_handler::{AdvertBroadcaster, P2PThreadJoiner};
+pub use utils::P2P_TRANSPORT_CHANNELS;
 
 /// Custom P2P result type returning a P2P error in case of error.
 pub(crate) type P2PResult<T> = std::result::Result<T, P2PError>;
 
 pub(crate) mod utils {
     //! The utils module provides a mapping from a gossip message to the
-    //! corresponding flow tag.
+    //! corresponding transport channel.
     use crate::gossip_types::GossipMessage;
     use ic_interfaces_transport::TransportChannelId;
+    use ic_types::artifact::ArtifactId;
+
+    /// The number of transport channels used by P2P.
+    pub const P2P_TRANSPORT_CHANNELS: usize = 3;
+
+    /// Index of the channel carrying adverts, chunk requests and retransmission
+    /// requests. These messages are small and latency sensitive, so they must
+    /// not queue up behind chunks.
+    const CONTROL_CHANNEL_INDEX: usize = 0;
+    /// Index of the channel carrying the chunks of all artifacts except state
+    /// sync.
+    const ARTIFACT_CHUNK_CHANNEL_INDEX: usize = 1;
+    /// Index of the channel carrying state sync chunks, which can saturate the
+    /// connection for minutes while a node catches up.
+    const STATE_SYNC_CHUNK_CHANNEL_INDEX: usize = 2;
 
     /// An ordered collection of transport channels.
     pub(crate) struct TransportChannelIdMapper {
@@ -138,13 +154,22 @@ pub(crate) mod utils {
     impl TransportChannelIdMapper {
         /// The function creates a new TransportChannelIdMapper instance.
         pub(crate) fn new(transport_channels: Vec<TransportChannelId>) -> Self {
-            assert_eq!(transport_channels.len(), 1);
+            assert_eq!(transport_channels.len(), P2P_TRANSPORT_CHANNELS);
             Self { transport_channels }
         }
 
-        /// The function returns the flow tag of the flow the message maps to.
-        pub(crate) fn map(&self, _msg: &GossipMessage) -> TransportChannelId {
-            self.transport_channels[0]
+        /// The function returns the transport channel the message maps to.
+        pub(crate) fn map(&self, msg: &GossipMessage) -> TransportChannelId {
+            let index = match msg {
+                GossipMessage::Advert(_)
+                | GossipMessage::ChunkRequest(_)
+                | GossipMessage::RetransmissionRequest(_) => CONTROL_CHANNEL_INDEX,
+                GossipMessage::Chunk(chunk) => match chunk.request.artifact_id {
+                    ArtifactId::StateSync(_) => STATE_SYNC_CHUNK_CHANNEL_INDEX,
+                    _ => ARTIFACT_CHUNK_CHANNEL_INDEX,
+                },
+            };
+            self.transport_channels[index]
         }
     }
 }
@@ -163,7 +188,9 @@ pub fn start_p2p(
     artifact_manager: Arc<dyn ArtifactManager>,
     advert_broadcaster: &AdvertBroadcaster,
 ) -> P2PThreadJoiner {
-    let p2p_transport_channels = vec![TransportChannelId::from(0)];
+    let p2p_transport_channels = (0..utils::P2P_TRANSPORT_CHANNELS)
+        .map(TransportChannelId::from)
+        .collect();
     let gossip = Arc::new(gossip_protocol::GossipImpl::new(
         node_id,
         subnet_id,
@@ -109,6 +109,7 @@ fn execute_test(
             subnet_id,
             Some(transport),
             Arc::new(FakeTlsHandshake::new()),
+            Arc::new(FakeTlsHandshake::new()),
             Arc::clone(&state_manager) as Arc<_>,
             Arc::clone(&state_manager) as Arc<_>,
             no_state_sync_client,
@@ -271,6 +272,7 @@ fn execute_test_chunking_pool(
             subnet_id,
             Some(transport),
             Arc::new(FakeTlsHandshake::new()),
+            Arc::new(FakeTlsHandshake::new()),
             Arc::clone(&state_manager) as Arc<_>,
             Arc::clone(&state_manager) as Arc<_>,
             state_sync_client,
@@ -41,6 +41,44 @@ pub struct EcdsaKeyId {
     #[prost(string, tag = "2")]
     pub name: ::prost::alloc::string::String,
 }
+#[derive(serde::Serialize, serde::Deserialize)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SchnorrKeyId {
+    #[prost(enumeration = "SchnorrAlgorithm", tag = "1")]
+    pub algorithm: i32,
+    #[prost(string, tag = "2")]
+    pub name: ::prost::alloc::string::String,
+}
+#[derive(serde::Serialize, serde::Deserialize)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct VetKdKeyId {
+    #[prost(enumeration = "VetKdCurve", tag = "1")]
+    pub curve: i32,
+    #[prost(string, tag = "2")]
+    pub name: ::prost::alloc::string::String,
+}
+/// A master public key id, which is either an ECDSA or a Schnorr key id.
+#[derive(serde::Serialize, serde::Deserialize)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct MasterPublicKeyId {
+    #[prost(oneof = "master_public_key_id::KeyId", tags = "1, 2")]
+    pub key_id: ::core::option::Option<master_public_key_id::KeyId>,
+}
+/// Nested message and enum types in `MasterPublicKeyId`.
+pub mod master_public_key_id {
+    #[derive(serde::Serialize, serde::Deserialize)]
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Oneof)]
+    pub enum KeyId {
+        #[prost(message, tag = "1")]
+        Ecdsa(super::EcdsaKeyId),
+        #[prost(message, tag = "2")]
+        Schnorr(super::SchnorrKeyId),
+    }
+}
 /// An algorithm ID. This is used to specify the signature algorithm associated with a public key.
 #[derive(serde::Serialize, serde::Deserialize)]
 #[allow(clippy::derive_partial_eq_without_eq)]
@@ -64,6 +102,9 @@ pub enum AlgorithmId {
     RsaSha256 = 14,
     ThresholdEcdsaSecp256k1 = 15,
     MegaSecp256k1 = 16,
+    ThresholdSchnorrBip340 = 17,
+    ThresholdEd25519 = 18,
+    ThresholdEcdsaSecp256r1 = 19,
 }
 impl AlgorithmId {
     /// String value of the enum field names used in the ProtoBuf definition.
@@ -89,6 +130,9 @@ impl AlgorithmId {
             AlgorithmId::RsaSha256 => "ALGORITHM_ID_RSA_SHA256",
             AlgorithmId::ThresholdEcdsaSecp256k1 => "ALGORITHM_ID_THRESHOLD_ECDSA_SECP_256K1",
             AlgorithmId::MegaSecp256k1 => "ALGORITHM_ID_MEGA_SECP_256K1",
+            AlgorithmId::ThresholdSchnorrBip340 => "ALGORITHM_ID_THRESHOLD_SCHNORR_BIP340",
+            AlgorithmId::ThresholdEd25519 => "ALGORITHM_ID_THRESHOLD_ED25519",
+            AlgorithmId::ThresholdEcdsaSecp256r1 => "ALGORITHM_ID_THRESHOLD_ECDSA_SECP_256R1",
         }
     }
 }
@@ -100,6 +144,7 @@ impl AlgorithmId {
 pub enum EcdsaCurve {
     Unspecified = 0,
     Secp256k1 = 1,
+    Secp256r1 = 2,
 }
 impl EcdsaCurve {
     /// String value of the enum field names used in the ProtoBuf definition.
@@ -110,6 +155,51 @@ impl EcdsaCurve {
         match self {
             EcdsaCurve::Unspecified => "ECDSA_CURVE_UNSPECIFIED",
             EcdsaCurve::Secp256k1 => "ECDSA_CURVE_SECP256K1",
+            EcdsaCurve::Secp256r1 => "ECDSA_CURVE_SECP256R1",
+        }
+    }
+}
+/// Types of algorithms that can be used for Schnorr signatures.
+#[derive(serde::Serialize, serde::Deserialize)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum SchnorrAlgorithm {
+    Unspecified = 0,
+    Bip340Secp256k1 = 1,
+    Ed25519 = 2,
+}
+impl SchnorrAlgorithm {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            SchnorrAlgorithm::Unspecified => "SCHNORR_ALGORITHM_UNSPECIFIED",
+            SchnorrAlgorithm::Bip340Secp256k1 => "SCHNORR_ALGORITHM_BIP340_SECP256K1",
+            SchnorrAlgorithm::Ed25519 => "SCHNORR_ALGORITHM_ED25519",
+        }
+    }
+}
+/// Types of curves that can be used for vetKD.
+#[derive(serde::Serialize, serde::Deserialize)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum VetKdCurve {
+    Unspecified = 0,
+    Bls12381G2 = 1,
+}
+impl VetKdCurve {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            VetKdCurve::Unspecified => "VET_KD_CURVE_UNSPECIFIED",
+            VetKdCurve::Bls12381G2 => "VET_KD_CURVE_BLS12_381_G2",
         }
     }
 }
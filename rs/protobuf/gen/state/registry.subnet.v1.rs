@@ -332,6 +332,10 @@ pub struct SubnetFeatures {
     /// Status of the SEV-SNP feature.
     #[prost(enumeration = "SevFeatureStatus", optional, tag = "7")]
     pub sev_status: ::core::option::Option<i32>,
+    /// Whether the nodes of the subnet communicate using the QUIC transport
+    /// instead of TLS over TCP.
+    #[prost(bool, optional, tag = "8")]
+    pub quic_transport: ::core::option::Option<bool>,
 }
 /// Per subnet ECDSA configuration
 #[allow(clippy::derive_partial_eq_without_eq)]
@@ -353,6 +357,12 @@ pub struct EcdsaConfig {
     /// If none is specified key rotation is disabled.
     #[prost(uint64, optional, tag = "6")]
     pub idkg_key_rotation_period_ms: ::core::option::Option<u64>,
+    /// Identifiers for threshold Schnorr keys held by the subnet.
+    #[prost(message, repeated, tag = "7")]
+    pub schnorr_key_ids: ::prost::alloc::vec::Vec<super::super::crypto::v1::SchnorrKeyId>,
+    /// Identifiers for vetKD keys held by the subnet.
+    #[prost(message, repeated, tag = "8")]
+    pub vetkd_key_ids: ::prost::alloc::vec::Vec<super::super::crypto::v1::VetKdKeyId>,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
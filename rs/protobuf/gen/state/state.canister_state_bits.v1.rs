@@ -29,6 +29,10 @@ pub mod call_context {
         pub canister_id: ::core::option::Option<super::super::super::super::types::v1::CanisterId>,
         #[prost(uint64, tag = "2")]
         pub callback_id: u64,
+        /// Deadline of a best-effort call, in seconds since UNIX epoch. Zero for
+        /// guaranteed response calls.
+        #[prost(uint32, tag = "3")]
+        pub deadline_seconds: u32,
     }
     /// System task is either a Heartbeat or a GlobalTimer.
     #[allow(clippy::derive_partial_eq_without_eq)]
@@ -87,6 +91,10 @@ pub struct Callback {
     #[prost(message, optional, tag = "9")]
     pub prepayment_for_response_transmission:
         ::core::option::Option<super::super::queues::v1::Cycles>,
+    /// Deadline of a best-effort call, in seconds since UNIX epoch. Zero for
+    /// guaranteed response calls.
+    #[prost(uint32, tag = "10")]
+    pub deadline_seconds: u32,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -107,6 +115,10 @@ pub struct CallContextManager {
     pub call_contexts: ::prost::alloc::vec::Vec<CallContextEntry>,
     #[prost(message, repeated, tag = "4")]
     pub callbacks: ::prost::alloc::vec::Vec<CallbackEntry>,
+    /// IDs of best-effort callbacks that have expired and for which a reject
+    /// response was enqueued.
+    #[prost(uint64, repeated, tag = "5")]
+    pub expired_callbacks: ::prost::alloc::vec::Vec<u64>,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -365,6 +377,98 @@ pub mod execution_task {
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
+pub struct CanisterLogRecord {
+    #[prost(uint64, tag = "1")]
+    pub idx: u64,
+    #[prost(uint64, tag = "2")]
+    pub timestamp_nanos: u64,
+    #[prost(bytes = "vec", tag = "3")]
+    pub content: ::prost::alloc::vec::Vec<u8>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct CanisterChangeFromUser {
+    #[prost(message, optional, tag = "1")]
+    pub user_id: ::core::option::Option<super::super::super::types::v1::PrincipalId>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct CanisterChangeFromCanister {
+    #[prost(message, optional, tag = "1")]
+    pub canister_id: ::core::option::Option<super::super::super::types::v1::PrincipalId>,
+    #[prost(uint64, optional, tag = "2")]
+    pub canister_version: ::core::option::Option<u64>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct CanisterCreation {
+    #[prost(message, repeated, tag = "1")]
+    pub controllers: ::prost::alloc::vec::Vec<super::super::super::types::v1::PrincipalId>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct CanisterCodeUninstall {}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct CanisterCodeDeployment {
+    #[prost(enumeration = "CanisterInstallMode", tag = "1")]
+    pub mode: i32,
+    #[prost(bytes = "vec", tag = "2")]
+    pub module_hash: ::prost::alloc::vec::Vec<u8>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct CanisterControllersChange {
+    #[prost(message, repeated, tag = "1")]
+    pub controllers: ::prost::alloc::vec::Vec<super::super::super::types::v1::PrincipalId>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct CanisterChange {
+    #[prost(uint64, tag = "1")]
+    pub timestamp_nanos: u64,
+    #[prost(uint64, tag = "2")]
+    pub canister_version: u64,
+    #[prost(oneof = "canister_change::ChangeOrigin", tags = "3, 4")]
+    pub change_origin: ::core::option::Option<canister_change::ChangeOrigin>,
+    #[prost(oneof = "canister_change::ChangeDetails", tags = "5, 6, 7, 8")]
+    pub change_details: ::core::option::Option<canister_change::ChangeDetails>,
+}
+/// Nested message and enum types in `CanisterChange`.
+pub mod canister_change {
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Oneof)]
+    pub enum ChangeOrigin {
+        #[prost(message, tag = "3")]
+        CanisterChangeFromUser(super::CanisterChangeFromUser),
+        #[prost(message, tag = "4")]
+        CanisterChangeFromCanister(super::CanisterChangeFromCanister),
+    }
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Oneof)]
+    pub enum ChangeDetails {
+        #[prost(message, tag = "5")]
+        CanisterCreation(super::CanisterCreation),
+        #[prost(message, tag = "6")]
+        CanisterCodeUninstall(super::CanisterCodeUninstall),
+        #[prost(message, tag = "7")]
+        CanisterCodeDeployment(super::CanisterCodeDeployment),
+        #[prost(message, tag = "8")]
+        CanisterControllersChange(super::CanisterControllersChange),
+    }
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct CanisterHistory {
+    /// The most recent changes, oldest first.
+    #[prost(message, repeated, tag = "1")]
+    pub changes: ::prost::alloc::vec::Vec<CanisterChange>,
+    /// Number of changes ever recorded, including the ones already dropped.
+    #[prost(uint64, tag = "2")]
+    pub total_num_changes: u64,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
 pub struct CanisterStateBits {
     #[prost(uint64, tag = "2")]
     pub last_full_execution_round: u64,
@@ -425,6 +529,27 @@ pub struct CanisterStateBits {
     /// Canister version.
     #[prost(uint64, tag = "34")]
     pub canister_version: u64,
+    /// Memory used by the snapshots of this canister, in bytes.
+    #[prost(uint64, tag = "35")]
+    pub snapshots_memory_usage: u64,
+    /// Local id to assign to the next snapshot taken of this canister.
+    #[prost(uint64, tag = "36")]
+    pub next_snapshot_id: u64,
+    /// Chunks of Wasm modules uploaded via `upload_chunk`.
+    #[prost(bytes = "vec", repeated, tag = "37")]
+    pub wasm_chunk_store: ::prost::alloc::vec::Vec<::prost::alloc::vec::Vec<u8>>,
+    /// Most recent log records of the canister.
+    #[prost(message, repeated, tag = "38")]
+    pub canister_log_records: ::prost::alloc::vec::Vec<CanisterLogRecord>,
+    /// Index to assign to the next log record of the canister.
+    #[prost(uint64, tag = "39")]
+    pub next_canister_log_record_idx: u64,
+    /// Who is allowed to read the canister logs.
+    #[prost(enumeration = "LogVisibility", tag = "40")]
+    pub log_visibility: i32,
+    /// Bounded history of changes made to the canister.
+    #[prost(message, optional, tag = "41")]
+    pub canister_history: ::core::option::Option<CanisterHistory>,
     #[prost(oneof = "canister_state_bits::CanisterStatus", tags = "11, 12, 13")]
     pub canister_status: ::core::option::Option<canister_state_bits::CanisterStatus>,
 }
@@ -442,6 +567,32 @@ pub mod canister_state_bits {
     }
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct CanisterSnapshotBits {
+    /// Time the snapshot was taken at, in nanoseconds since Unix epoch.
+    #[prost(uint64, tag = "1")]
+    pub taken_at_timestamp: u64,
+    /// Version of the canister at the time the snapshot was taken.
+    #[prost(uint64, tag = "2")]
+    pub canister_version: u64,
+    #[prost(bytes = "vec", tag = "3")]
+    pub certified_data: ::prost::alloc::vec::Vec<u8>,
+    #[prost(message, repeated, tag = "4")]
+    pub exported_globals: ::prost::alloc::vec::Vec<Global>,
+    #[prost(message, repeated, tag = "5")]
+    pub exports: ::prost::alloc::vec::Vec<WasmMethod>,
+    #[prost(message, optional, tag = "6")]
+    pub metadata: ::core::option::Option<WasmMetadata>,
+    #[prost(bytes = "vec", tag = "7")]
+    pub binary_hash: ::prost::alloc::vec::Vec<u8>,
+    /// Size of the Wasm heap, in Wasm pages.
+    #[prost(uint64, tag = "8")]
+    pub wasm_memory_size: u64,
+    /// Size of the stable memory, in Wasm pages.
+    #[prost(uint64, tag = "9")]
+    pub stable_memory_size: u64,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
 #[repr(i32)]
 pub enum CustomSectionType {
@@ -462,3 +613,47 @@ impl CustomSectionType {
         }
     }
 }
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum LogVisibility {
+    Unspecified = 0,
+    Controllers = 1,
+    Public = 2,
+}
+impl LogVisibility {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            LogVisibility::Unspecified => "LOG_VISIBILITY_UNSPECIFIED",
+            LogVisibility::Controllers => "LOG_VISIBILITY_CONTROLLERS",
+            LogVisibility::Public => "LOG_VISIBILITY_PUBLIC",
+        }
+    }
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum CanisterInstallMode {
+    Unspecified = 0,
+    Install = 1,
+    Reinstall = 2,
+    Upgrade = 3,
+}
+impl CanisterInstallMode {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            CanisterInstallMode::Unspecified => "CANISTER_INSTALL_MODE_UNSPECIFIED",
+            CanisterInstallMode::Install => "CANISTER_INSTALL_MODE_INSTALL",
+            CanisterInstallMode::Reinstall => "CANISTER_INSTALL_MODE_REINSTALL",
+            CanisterInstallMode::Upgrade => "CANISTER_INSTALL_MODE_UPGRADE",
+        }
+    }
+}
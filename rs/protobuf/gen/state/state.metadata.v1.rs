@@ -40,6 +40,12 @@ pub struct SubnetTopology {
     #[prost(message, repeated, tag = "5")]
     pub ecdsa_keys_held:
         ::prost::alloc::vec::Vec<super::super::super::registry::crypto::v1::EcdsaKeyId>,
+    #[prost(message, repeated, tag = "6")]
+    pub schnorr_keys_held:
+        ::prost::alloc::vec::Vec<super::super::super::registry::crypto::v1::SchnorrKeyId>,
+    #[prost(message, repeated, tag = "7")]
+    pub vetkd_keys_held:
+        ::prost::alloc::vec::Vec<super::super::super::registry::crypto::v1::VetKdKeyId>,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -128,6 +134,56 @@ pub struct SignWithEcdsaContextTree {
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SignWithSchnorrContext {
+    #[prost(message, optional, tag = "1")]
+    pub request: ::core::option::Option<super::super::queues::v1::Request>,
+    #[prost(message, optional, tag = "2")]
+    pub key_id: ::core::option::Option<super::super::super::registry::crypto::v1::SchnorrKeyId>,
+    #[prost(bytes = "vec", tag = "3")]
+    pub message: ::prost::alloc::vec::Vec<u8>,
+    #[prost(bytes = "vec", repeated, tag = "4")]
+    pub derivation_path_vec: ::prost::alloc::vec::Vec<::prost::alloc::vec::Vec<u8>>,
+    #[prost(bytes = "vec", tag = "5")]
+    pub pseudo_random_id: ::prost::alloc::vec::Vec<u8>,
+    #[prost(uint64, tag = "6")]
+    pub batch_time: u64,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SignWithSchnorrContextTree {
+    #[prost(uint64, tag = "1")]
+    pub callback_id: u64,
+    #[prost(message, optional, tag = "2")]
+    pub context: ::core::option::Option<SignWithSchnorrContext>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct VetKdContext {
+    #[prost(message, optional, tag = "1")]
+    pub request: ::core::option::Option<super::super::queues::v1::Request>,
+    #[prost(message, optional, tag = "2")]
+    pub key_id: ::core::option::Option<super::super::super::registry::crypto::v1::VetKdKeyId>,
+    #[prost(bytes = "vec", repeated, tag = "3")]
+    pub derivation_path_vec: ::prost::alloc::vec::Vec<::prost::alloc::vec::Vec<u8>>,
+    #[prost(bytes = "vec", tag = "4")]
+    pub input: ::prost::alloc::vec::Vec<u8>,
+    #[prost(bytes = "vec", tag = "5")]
+    pub transport_public_key: ::prost::alloc::vec::Vec<u8>,
+    #[prost(bytes = "vec", tag = "6")]
+    pub pseudo_random_id: ::prost::alloc::vec::Vec<u8>,
+    #[prost(uint64, tag = "7")]
+    pub batch_time: u64,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct VetKdContextTree {
+    #[prost(uint64, tag = "1")]
+    pub callback_id: u64,
+    #[prost(message, optional, tag = "2")]
+    pub context: ::core::option::Option<VetKdContext>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
 pub struct HttpHeader {
     #[prost(string, tag = "1")]
     pub name: ::prost::alloc::string::String,
@@ -155,6 +211,9 @@ pub struct CanisterHttpRequestContext {
     pub max_response_bytes: ::core::option::Option<u64>,
     #[prost(message, optional, tag = "10")]
     pub transform_context: ::core::option::Option<::prost::alloc::vec::Vec<u8>>,
+    /// If set, the request is non-replicated and only made by this node.
+    #[prost(message, optional, tag = "11")]
+    pub non_replicated_node_id: ::core::option::Option<super::super::super::types::v1::NodeId>,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -243,6 +302,10 @@ pub struct SubnetCallContextManager {
     #[prost(message, repeated, tag = "9")]
     pub bitcoin_send_transaction_internal_contexts:
         ::prost::alloc::vec::Vec<BitcoinSendTransactionInternalContextTree>,
+    #[prost(message, repeated, tag = "10")]
+    pub sign_with_schnorr_contexts: ::prost::alloc::vec::Vec<SignWithSchnorrContextTree>,
+    #[prost(message, repeated, tag = "11")]
+    pub vetkd_contexts: ::prost::alloc::vec::Vec<VetKdContextTree>,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -258,6 +321,9 @@ pub struct SubnetMetrics {
         ::core::option::Option<super::super::super::types::v1::NominalCycles>,
     #[prost(uint64, optional, tag = "4")]
     pub ecdsa_signature_agreements: ::core::option::Option<u64>,
+    #[prost(message, optional, tag = "5")]
+    pub consumed_cycles_schnorr_outcalls:
+        ::core::option::Option<super::super::super::types::v1::NominalCycles>,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -330,6 +396,9 @@ pub enum HttpMethod {
     Get = 1,
     Post = 2,
     Head = 3,
+    Put = 4,
+    Delete = 5,
+    Patch = 6,
 }
 impl HttpMethod {
     /// String value of the enum field names used in the ProtoBuf definition.
@@ -342,6 +411,9 @@ impl HttpMethod {
             HttpMethod::Get => "HTTP_METHOD_GET",
             HttpMethod::Post => "HTTP_METHOD_POST",
             HttpMethod::Head => "HTTP_METHOD_HEAD",
+            HttpMethod::Put => "HTTP_METHOD_PUT",
+            HttpMethod::Delete => "HTTP_METHOD_DELETE",
+            HttpMethod::Patch => "HTTP_METHOD_PATCH",
         }
     }
 }
@@ -51,6 +51,10 @@ pub struct Request {
     pub method_payload: ::prost::alloc::vec::Vec<u8>,
     #[prost(message, optional, tag = "7")]
     pub cycles_payment: ::core::option::Option<Cycles>,
+    /// Deadline of a best-effort request, in seconds since UNIX epoch. Zero for
+    /// guaranteed response requests.
+    #[prost(uint32, tag = "8")]
+    pub deadline_seconds: u32,
 }
 #[derive(serde::Serialize, serde::Deserialize)]
 #[allow(clippy::derive_partial_eq_without_eq)]
@@ -75,6 +79,10 @@ pub struct Response {
     pub refund: ::core::option::Option<Funds>,
     #[prost(message, optional, tag = "7")]
     pub cycles_refund: ::core::option::Option<Cycles>,
+    /// Deadline of a best-effort response, in seconds since UNIX epoch. Zero for
+    /// guaranteed responses.
+    #[prost(uint32, tag = "8")]
+    pub deadline_seconds: u32,
     #[prost(oneof = "response::ResponsePayload", tags = "5, 6")]
     pub response_payload: ::core::option::Option<response::ResponsePayload>,
 }
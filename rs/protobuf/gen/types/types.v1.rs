@@ -262,6 +262,63 @@ pub struct EcdsaPayload {
     pub next_key_in_creation: ::core::option::Option<KeyTranscriptCreation>,
     #[prost(message, optional, tag = "12")]
     pub key_id: ::core::option::Option<super::super::registry::crypto::v1::EcdsaKeyId>,
+    #[prost(message, optional, tag = "13")]
+    pub schnorr_key_transcript: ::core::option::Option<SchnorrKeyTranscript>,
+    #[prost(message, repeated, tag = "14")]
+    pub ongoing_schnorr_signatures: ::prost::alloc::vec::Vec<OngoingSchnorrSignature>,
+    #[prost(message, repeated, tag = "15")]
+    pub available_schnorr_presigs: ::prost::alloc::vec::Vec<AvailableSchnorrPreSignature>,
+    #[prost(message, repeated, tag = "16")]
+    pub schnorr_presigs_in_creation: ::prost::alloc::vec::Vec<SchnorrPreSignatureInProgress>,
+    #[prost(message, repeated, tag = "17")]
+    pub ongoing_vetkd_requests: ::prost::alloc::vec::Vec<OngoingVetKdRequest>,
+}
+#[derive(serde::Serialize, serde::Deserialize)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SchnorrKeyTranscript {
+    #[prost(message, optional, tag = "1")]
+    pub current: ::core::option::Option<UnmaskedTranscriptWithAttributes>,
+    #[prost(message, optional, tag = "2")]
+    pub next_in_creation: ::core::option::Option<KeyTranscriptCreation>,
+    #[prost(message, optional, tag = "3")]
+    pub key_id: ::core::option::Option<super::super::registry::crypto::v1::SchnorrKeyId>,
+}
+#[derive(serde::Serialize, serde::Deserialize)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct OngoingSchnorrSignature {
+    #[prost(message, optional, tag = "1")]
+    pub request_id: ::core::option::Option<RequestId>,
+    #[prost(message, optional, tag = "2")]
+    pub sig_inputs: ::core::option::Option<ThresholdSchnorrSigInputsRef>,
+}
+#[derive(serde::Serialize, serde::Deserialize)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct AvailableSchnorrPreSignature {
+    #[prost(uint64, tag = "1")]
+    pub quadruple_id: u64,
+    #[prost(message, optional, tag = "2")]
+    pub presig: ::core::option::Option<SchnorrPreSignatureTranscriptRef>,
+}
+#[derive(serde::Serialize, serde::Deserialize)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SchnorrPreSignatureInProgress {
+    #[prost(uint64, tag = "1")]
+    pub quadruple_id: u64,
+    #[prost(message, optional, tag = "2")]
+    pub presig: ::core::option::Option<SchnorrPreSignatureInCreation>,
+}
+#[derive(serde::Serialize, serde::Deserialize)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct OngoingVetKdRequest {
+    #[prost(message, optional, tag = "1")]
+    pub request_id: ::core::option::Option<RequestId>,
+    #[prost(message, optional, tag = "2")]
+    pub args: ::core::option::Option<VetKdArgs>,
 }
 #[derive(serde::Serialize, serde::Deserialize)]
 #[allow(clippy::derive_partial_eq_without_eq)]
@@ -475,6 +532,56 @@ pub struct ThresholdEcdsaSigInputsRef {
 #[derive(serde::Serialize, serde::Deserialize)]
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SchnorrPreSignatureInCreation {
+    #[prost(message, optional, tag = "1")]
+    pub blinder_config: ::core::option::Option<RandomTranscriptParams>,
+    #[prost(message, optional, tag = "2")]
+    pub blinder_masked: ::core::option::Option<MaskedTranscript>,
+    #[prost(message, optional, tag = "3")]
+    pub unmask_blinder_config: ::core::option::Option<ReshareOfMaskedParams>,
+    #[prost(message, optional, tag = "4")]
+    pub blinder_unmasked: ::core::option::Option<UnmaskedTranscript>,
+}
+#[derive(serde::Serialize, serde::Deserialize)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SchnorrPreSignatureTranscriptRef {
+    #[prost(message, optional, tag = "1")]
+    pub blinder_unmasked_ref: ::core::option::Option<UnmaskedTranscript>,
+}
+#[derive(serde::Serialize, serde::Deserialize)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ThresholdSchnorrSigInputsRef {
+    #[prost(message, optional, tag = "1")]
+    pub derivation_path:
+        ::core::option::Option<super::super::registry::subnet::v1::ExtendedDerivationPath>,
+    #[prost(bytes = "vec", tag = "2")]
+    pub message: ::prost::alloc::vec::Vec<u8>,
+    #[prost(bytes = "vec", tag = "3")]
+    pub nonce: ::prost::alloc::vec::Vec<u8>,
+    #[prost(message, optional, tag = "4")]
+    pub presig_transcript_ref: ::core::option::Option<SchnorrPreSignatureTranscriptRef>,
+    #[prost(message, optional, tag = "5")]
+    pub key_transcript_ref: ::core::option::Option<UnmaskedTranscript>,
+}
+#[derive(serde::Serialize, serde::Deserialize)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct VetKdArgs {
+    #[prost(message, optional, tag = "1")]
+    pub ni_dkg_id: ::core::option::Option<NiDkgId>,
+    #[prost(message, optional, tag = "2")]
+    pub derivation_path:
+        ::core::option::Option<super::super::registry::subnet::v1::ExtendedDerivationPath>,
+    #[prost(bytes = "vec", tag = "3")]
+    pub input: ::prost::alloc::vec::Vec<u8>,
+    #[prost(bytes = "vec", tag = "4")]
+    pub transport_public_key: ::prost::alloc::vec::Vec<u8>,
+}
+#[derive(serde::Serialize, serde::Deserialize)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
 pub struct CompletedSignature {
     #[prost(message, optional, tag = "1")]
     pub request_id: ::core::option::Option<RequestId>,
@@ -1753,6 +1753,8 @@ impl ProposalTitleAndPayload<UpdateSubnetPayload> for ProposeToUpdateSubnetCmd {
                     .ecdsa_quadruples_to_create_in_advance
                     .unwrap_or_else(|| current_quadruples_value.unwrap_or(1)),
                 key_ids: current_keys,
+                schnorr_key_ids: vec![],
+                vetkd_key_ids: vec![],
                 max_queue_size: Some(self.max_ecdsa_queue_size.unwrap_or_else(|| {
                     current_max_queue_size.unwrap_or(DEFAULT_ECDSA_MAX_QUEUE_SIZE)
                 })),
@@ -291,6 +291,11 @@ impl From<EcdsaInitialConfig> for EcdsaConfig {
                 .iter()
                 .map(|val| (&val.key_id).into())
                 .collect::<Vec<_>>(),
+            // Schnorr keys cannot be reshared into a new subnet, they are added to a
+            // subnet with an update_subnet proposal.
+            schnorr_key_ids: vec![],
+            // Like Schnorr keys, vetKD keys are only added with an update_subnet proposal.
+            vetkd_key_ids: vec![],
             max_queue_size: val.max_queue_size.unwrap_or(DEFAULT_ECDSA_MAX_QUEUE_SIZE),
             signature_request_timeout_ns: val.signature_request_timeout_ns,
             idkg_key_rotation_period_ms: val.idkg_key_rotation_period_ms,
@@ -413,6 +418,8 @@ mod test {
             EcdsaConfig {
                 quadruples_to_create_in_advance: 1,
                 key_ids: vec![key_id.clone()],
+                schnorr_key_ids: vec![],
+                vetkd_key_ids: vec![],
                 max_queue_size: Some(DEFAULT_ECDSA_MAX_QUEUE_SIZE),
                 signature_request_timeout_ns: None,
                 idkg_key_rotation_period_ms: None,
@@ -467,6 +474,8 @@ mod test {
             EcdsaConfig {
                 quadruples_to_create_in_advance: 1,
                 key_ids: vec![key_id.clone()],
+                schnorr_key_ids: vec![],
+                vetkd_key_ids: vec![],
                 max_queue_size: Some(DEFAULT_ECDSA_MAX_QUEUE_SIZE),
                 signature_request_timeout_ns: None,
                 idkg_key_rotation_period_ms: None,
@@ -249,6 +249,8 @@ mod test {
             EcdsaConfig {
                 quadruples_to_create_in_advance: 1,
                 key_ids: vec![key_id],
+                schnorr_key_ids: vec![],
+                vetkd_key_ids: vec![],
                 max_queue_size: Some(DEFAULT_ECDSA_MAX_QUEUE_SIZE),
                 signature_request_timeout_ns: None,
                 idkg_key_rotation_period_ms: None,
@@ -370,6 +372,8 @@ mod test {
             EcdsaConfig {
                 quadruples_to_create_in_advance: 1,
                 key_ids: vec![key_id],
+                schnorr_key_ids: vec![],
+                vetkd_key_ids: vec![],
                 max_queue_size: Some(DEFAULT_ECDSA_MAX_QUEUE_SIZE),
                 signature_request_timeout_ns: None,
                 idkg_key_rotation_period_ms: Some(idkg_key_rotation_period_ms),
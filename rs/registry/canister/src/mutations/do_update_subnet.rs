@@ -54,7 +54,8 @@ impl Registry {
         self.maybe_apply_mutation_internal(mutations);
     }
 
-    /// Validates that EcdsaKeyId's are globally unique across all subnets
+    /// Validates that EcdsaKeyId's and SchnorrKeyId's are globally unique across all subnets,
+    /// and that all SchnorrKeyId's use a supported algorithm.
     /// Panics if they are not
     fn validate_update_payload_ecdsa_config(&self, payload: &UpdateSubnetPayload) {
         if payload.ecdsa_config.is_none() {
@@ -78,6 +79,31 @@ impl Registry {
             }
         });
 
+        // Schnorr keys are generated by the subnet holding them, just like ECDSA keys, so they
+        // must be globally unique as well. A key with an algorithm the IDKG protocol cannot sign
+        // with would never be generated, and requests for it would never be answered.
+        for key_id in &ecdsa_config.schnorr_key_ids {
+            if !key_id.algorithm.is_supported() {
+                panic!(
+                    "{}Schnorr key with id '{}' uses algorithm {}, which is not supported.",
+                    LOG_PREFIX, key_id, key_id.algorithm
+                );
+            }
+        }
+        let new_schnorr_keys = self.get_schnorr_keys_that_will_be_added_to_subnet(
+            subnet_id,
+            ecdsa_config.schnorr_key_ids.clone(),
+        );
+        let schnorr_subnet_map = self.get_schnorr_keys_to_subnets_map();
+        new_schnorr_keys.iter().for_each(|key_id| {
+            if schnorr_subnet_map.contains_key(key_id) {
+                panic!(
+                    "{}Schnorr key with id '{}' already exists.  ID must be globally unique.",
+                    LOG_PREFIX, key_id
+                );
+            }
+        });
+
         // Signing cannot be enabled unless the key was previously held by the subnet.
         if let Some(ref ecdsa_key_signing_enable) = payload.ecdsa_key_signing_enable {
             let current_keys = self.get_ecdsa_keys_held_by_subnet(subnet_id);
@@ -344,7 +370,7 @@ mod tests {
         add_fake_subnet, get_invariant_compliant_subnet_record, invariant_compliant_registry,
         prepare_registry_with_nodes,
     };
-    use ic_ic00_types::{EcdsaCurve, EcdsaKeyId};
+    use ic_ic00_types::{EcdsaCurve, EcdsaKeyId, SchnorrAlgorithm, SchnorrKeyId};
     use ic_nervous_system_common_test_keys::{TEST_USER1_PRINCIPAL, TEST_USER2_PRINCIPAL};
     use ic_protobuf::registry::subnet::v1::{GossipConfig, SubnetRecord};
     use ic_registry_subnet_features::DEFAULT_ECDSA_MAX_QUEUE_SIZE;
@@ -399,10 +425,13 @@ mod tests {
                 http_requests: false,
                 bitcoin: None,
                 sev_status: None,
+                quic_transport: None,
             }),
             ecdsa_config: Some(EcdsaConfig {
                 quadruples_to_create_in_advance: 10,
                 key_ids: vec![make_ecdsa_key("key_id_1")],
+                schnorr_key_ids: vec![],
+                vetkd_key_ids: vec![],
                 max_queue_size: Some(DEFAULT_ECDSA_MAX_QUEUE_SIZE),
                 signature_request_timeout_ns: None,
                 idkg_key_rotation_period_ms: None,
@@ -519,10 +548,13 @@ mod tests {
                 http_requests: false,
                 bitcoin: None,
                 sev_status: None,
+                quic_transport: None,
             }),
             ecdsa_config: Some(EcdsaConfig {
                 quadruples_to_create_in_advance: 10,
                 key_ids: vec![make_ecdsa_key("key_id_1")],
+                schnorr_key_ids: vec![],
+                vetkd_key_ids: vec![],
                 max_queue_size: Some(DEFAULT_ECDSA_MAX_QUEUE_SIZE),
                 signature_request_timeout_ns: None,
                 idkg_key_rotation_period_ms: None,
@@ -568,6 +600,7 @@ mod tests {
                         http_requests: false,
                         bitcoin: None,
                         sev_status: None,
+                        quic_transport: None,
                     }
                     .into()
                 ),
@@ -575,6 +608,8 @@ mod tests {
                     EcdsaConfig {
                         quadruples_to_create_in_advance: 10,
                         key_ids: vec![make_ecdsa_key("key_id_1")],
+                        schnorr_key_ids: vec![],
+                        vetkd_key_ids: vec![],
                         max_queue_size: Some(DEFAULT_ECDSA_MAX_QUEUE_SIZE),
                         signature_request_timeout_ns: None,
                         idkg_key_rotation_period_ms: None,
@@ -1033,6 +1068,8 @@ mod tests {
         payload.ecdsa_config = Some(EcdsaConfig {
             quadruples_to_create_in_advance: 1,
             key_ids: vec![key.clone()],
+            schnorr_key_ids: vec![],
+            vetkd_key_ids: vec![],
             max_queue_size: Some(DEFAULT_ECDSA_MAX_QUEUE_SIZE),
             signature_request_timeout_ns: None,
             idkg_key_rotation_period_ms: None,
@@ -1073,6 +1110,8 @@ mod tests {
             EcdsaConfig {
                 quadruples_to_create_in_advance: 1,
                 key_ids: vec![existing_key_id],
+                schnorr_key_ids: vec![],
+                vetkd_key_ids: vec![],
                 max_queue_size: Some(DEFAULT_ECDSA_MAX_QUEUE_SIZE),
                 signature_request_timeout_ns: None,
                 idkg_key_rotation_period_ms: None,
@@ -1105,11 +1144,118 @@ mod tests {
                 curve: EcdsaCurve::Secp256k1,
                 name: "existing_key_id".to_string(),
             }],
+            schnorr_key_ids: vec![],
+            vetkd_key_ids: vec![],
+            max_queue_size: Some(DEFAULT_ECDSA_MAX_QUEUE_SIZE),
+            signature_request_timeout_ns: None,
+            idkg_key_rotation_period_ms: None,
+        });
+
+        registry.do_update_subnet(payload);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Schnorr key with id 'Bip340Secp256k1:existing_key_id' already exists.  \
+                    ID must be globally unique."
+    )]
+    fn test_schnorr_key_ids_must_be_globally_unique() {
+        let existing_key_id = SchnorrKeyId {
+            algorithm: SchnorrAlgorithm::Bip340Secp256k1,
+            name: "existing_key_id".to_string(),
+        };
+        let subnet_holding_key_id = SubnetId::from(*TEST_USER1_PRINCIPAL);
+        let subnet_to_update_id = SubnetId::from(*TEST_USER2_PRINCIPAL);
+
+        let mut registry = invariant_compliant_registry();
+
+        let (mutate_request, mut node_ids) = prepare_registry_with_nodes(2);
+        registry.maybe_apply_mutation_internal(mutate_request.mutations);
+
+        let mut subnet_list_record = registry.get_subnet_list_record();
+
+        // Create first subnet that holds the Schnorr key.
+        let mut subnet_holding_key_record =
+            get_invariant_compliant_subnet_record(vec![node_ids.pop().unwrap()]);
+        subnet_holding_key_record.ecdsa_config = Some(
+            EcdsaConfig {
+                quadruples_to_create_in_advance: 1,
+                key_ids: vec![],
+                schnorr_key_ids: vec![existing_key_id.clone()],
+                vetkd_key_ids: vec![],
+                max_queue_size: Some(DEFAULT_ECDSA_MAX_QUEUE_SIZE),
+                signature_request_timeout_ns: None,
+                idkg_key_rotation_period_ms: None,
+            }
+            .into(),
+        );
+
+        registry.maybe_apply_mutation_internal(add_fake_subnet(
+            subnet_holding_key_id,
+            &mut subnet_list_record,
+            subnet_holding_key_record,
+        ));
+
+        // Create second subnet that does not hold the key.
+        let subnet_to_update = get_invariant_compliant_subnet_record(vec![node_ids.pop().unwrap()]);
+
+        registry.maybe_apply_mutation_internal(add_fake_subnet(
+            subnet_to_update_id,
+            &mut subnet_list_record,
+            subnet_to_update,
+        ));
+
+        let mut payload = make_empty_update_payload(subnet_to_update_id);
+        payload.ecdsa_config = Some(EcdsaConfig {
+            quadruples_to_create_in_advance: 1,
+            key_ids: vec![],
+            schnorr_key_ids: vec![existing_key_id],
+            vetkd_key_ids: vec![],
+            max_queue_size: Some(DEFAULT_ECDSA_MAX_QUEUE_SIZE),
+            signature_request_timeout_ns: None,
+            idkg_key_rotation_period_ms: None,
+        });
+
+        registry.do_update_subnet(payload);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Schnorr key with id 'Ed25519:key_id' uses algorithm Ed25519, which is not \
+                    supported."
+    )]
+    fn test_schnorr_key_ids_must_use_supported_algorithm() {
+        let mut registry = invariant_compliant_registry();
+
+        let (mutate_request, mut node_ids) = prepare_registry_with_nodes(1);
+        registry.maybe_apply_mutation_internal(mutate_request.mutations);
+
+        let mut subnet_list_record = registry.get_subnet_list_record();
+
+        let subnet_record = get_invariant_compliant_subnet_record(vec![node_ids.pop().unwrap()]);
+
+        let subnet_id = subnet_test_id(1000);
+        registry.maybe_apply_mutation_internal(add_fake_subnet(
+            subnet_id,
+            &mut subnet_list_record,
+            subnet_record,
+        ));
+
+        let mut payload = make_empty_update_payload(subnet_id);
+        payload.ecdsa_config = Some(EcdsaConfig {
+            quadruples_to_create_in_advance: 1,
+            key_ids: vec![],
+            schnorr_key_ids: vec![SchnorrKeyId {
+                algorithm: SchnorrAlgorithm::Ed25519,
+                name: "key_id".to_string(),
+            }],
+            vetkd_key_ids: vec![],
             max_queue_size: Some(DEFAULT_ECDSA_MAX_QUEUE_SIZE),
             signature_request_timeout_ns: None,
             idkg_key_rotation_period_ms: None,
         });
 
+        // Should panic because no subnet can sign with an Ed25519 key.
         registry.do_update_subnet(payload);
     }
 
@@ -1144,6 +1290,8 @@ mod tests {
         payload.ecdsa_config = Some(EcdsaConfig {
             quadruples_to_create_in_advance: 1,
             key_ids: vec![first_key.clone()],
+            schnorr_key_ids: vec![],
+            vetkd_key_ids: vec![],
             max_queue_size: Some(DEFAULT_ECDSA_MAX_QUEUE_SIZE),
             signature_request_timeout_ns: None,
             idkg_key_rotation_period_ms: None,
@@ -1164,6 +1312,8 @@ mod tests {
         payload.ecdsa_config = Some(EcdsaConfig {
             quadruples_to_create_in_advance: 1,
             key_ids: vec![first_key.clone(), second_key.clone()],
+            schnorr_key_ids: vec![],
+            vetkd_key_ids: vec![],
             max_queue_size: Some(DEFAULT_ECDSA_MAX_QUEUE_SIZE),
             signature_request_timeout_ns: None,
             idkg_key_rotation_period_ms: None,
@@ -1202,6 +1352,8 @@ mod tests {
             EcdsaConfig {
                 quadruples_to_create_in_advance: 1,
                 key_ids: vec![key.clone()],
+                schnorr_key_ids: vec![],
+                vetkd_key_ids: vec![],
                 max_queue_size: Some(DEFAULT_ECDSA_MAX_QUEUE_SIZE),
                 signature_request_timeout_ns: None,
                 idkg_key_rotation_period_ms: None,
@@ -1220,6 +1372,8 @@ mod tests {
         payload.ecdsa_config = Some(EcdsaConfig {
             quadruples_to_create_in_advance: 1,
             key_ids: vec![key.clone()],
+            schnorr_key_ids: vec![],
+            vetkd_key_ids: vec![],
             max_queue_size: Some(DEFAULT_ECDSA_MAX_QUEUE_SIZE),
             signature_request_timeout_ns: None,
             idkg_key_rotation_period_ms: None,
@@ -1248,6 +1402,8 @@ mod tests {
         payload.ecdsa_config = Some(EcdsaConfig {
             quadruples_to_create_in_advance: 1,
             key_ids: vec![],
+            schnorr_key_ids: vec![],
+            vetkd_key_ids: vec![],
             max_queue_size: Some(DEFAULT_ECDSA_MAX_QUEUE_SIZE),
             signature_request_timeout_ns: None,
             idkg_key_rotation_period_ms: None,
@@ -1292,6 +1448,8 @@ mod tests {
             EcdsaConfig {
                 quadruples_to_create_in_advance: 1,
                 key_ids: vec![key_held_by_subnet.clone()],
+                schnorr_key_ids: vec![],
+                vetkd_key_ids: vec![],
                 max_queue_size: Some(DEFAULT_ECDSA_MAX_QUEUE_SIZE),
                 signature_request_timeout_ns: None,
                 idkg_key_rotation_period_ms: None,
@@ -1310,6 +1468,8 @@ mod tests {
         payload.ecdsa_config = Some(EcdsaConfig {
             quadruples_to_create_in_advance: 1,
             key_ids: vec![key_held_by_subnet.clone()],
+            schnorr_key_ids: vec![],
+            vetkd_key_ids: vec![],
             max_queue_size: Some(DEFAULT_ECDSA_MAX_QUEUE_SIZE),
             signature_request_timeout_ns: None,
             idkg_key_rotation_period_ms: None,
@@ -1379,6 +1539,8 @@ mod tests {
             EcdsaConfig {
                 quadruples_to_create_in_advance: 1,
                 key_ids: vec![key.clone()],
+                schnorr_key_ids: vec![],
+                vetkd_key_ids: vec![],
                 max_queue_size: Some(DEFAULT_ECDSA_MAX_QUEUE_SIZE),
                 signature_request_timeout_ns: None,
                 idkg_key_rotation_period_ms: None,
@@ -1397,6 +1559,8 @@ mod tests {
         payload.ecdsa_config = Some(EcdsaConfig {
             quadruples_to_create_in_advance: 1,
             key_ids: vec![key.clone()],
+            schnorr_key_ids: vec![],
+            vetkd_key_ids: vec![],
             max_queue_size: Some(DEFAULT_ECDSA_MAX_QUEUE_SIZE),
             signature_request_timeout_ns: None,
             idkg_key_rotation_period_ms: None,
@@ -12,7 +12,7 @@ use ic_base_types::{
     subnet_id_into_protobuf, CanisterId, NodeId, PrincipalId, RegistryVersion, SubnetId,
 };
 use ic_ic00_types::{
-    ComputeInitialEcdsaDealingsArgs, ComputeInitialEcdsaDealingsResponse, EcdsaKeyId,
+    ComputeInitialEcdsaDealingsArgs, ComputeInitialEcdsaDealingsResponse, EcdsaKeyId, SchnorrKeyId,
 };
 use ic_protobuf::registry::crypto::v1::EcdsaSigningSubnetList;
 use ic_protobuf::registry::subnet::v1::EcdsaInitialization;
@@ -166,6 +166,25 @@ impl Registry {
         key_map
     }
 
+    /// Get a map representing SchnorrKeyId => Subnets that hold the key.
+    pub fn get_schnorr_keys_to_subnets_map(&self) -> HashMap<SchnorrKeyId, Vec<SubnetId>> {
+        let mut key_map: HashMap<SchnorrKeyId, Vec<SubnetId>> = HashMap::new();
+
+        get_subnet_ids_from_subnet_list(self.get_subnet_list_record())
+            .iter()
+            .for_each(|subnet_id| {
+                let subnet_record = self.get_subnet_or_panic(*subnet_id);
+                if let Some(ref ecdsa_conf) = subnet_record.ecdsa_config {
+                    ecdsa_conf.schnorr_key_ids.iter().for_each(|key_id| {
+                        let key_id: SchnorrKeyId = key_id.clone().try_into().unwrap();
+                        key_map.entry(key_id).or_default().push(*subnet_id);
+                    })
+                }
+            });
+
+        key_map
+    }
+
     /// Get the initial ECDSA dealings via a call to IC00 for a given EcdsaInitialConfig and a set of
     /// nodes to receive them.
     pub async fn get_all_initial_ecdsa_dealings_from_ic00(
@@ -304,6 +323,32 @@ impl Registry {
             .unwrap_or_default()
     }
 
+    /// Get a list of all SchnorrKeyId's held by a given subnet.
+    pub fn get_schnorr_keys_held_by_subnet(&self, subnet_id: SubnetId) -> Vec<SchnorrKeyId> {
+        let subnet_record = self.get_subnet_or_panic(subnet_id);
+        subnet_record
+            .ecdsa_config
+            .map(|c| {
+                c.schnorr_key_ids
+                    .iter()
+                    .map(|k| k.clone().try_into().unwrap())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Get a list of Schnorr keys that will be added to a subnet given the complete list of
+    /// Schnorr keys to be held by that subnet.
+    pub fn get_schnorr_keys_that_will_be_added_to_subnet(
+        &self,
+        subnet_id: SubnetId,
+        updated_key_list: Vec<SchnorrKeyId>,
+    ) -> Vec<SchnorrKeyId> {
+        let current_keys = vec_to_set(self.get_schnorr_keys_held_by_subnet(subnet_id));
+        let requested_keys = vec_to_set(updated_key_list);
+        requested_keys.difference(&current_keys).cloned().collect()
+    }
+
     /// Get a list of keys that will be removed from a subnet given the complete list of keys to be
     /// held by that subnet.
     pub(crate) fn get_keys_that_will_be_removed_from_subnet(
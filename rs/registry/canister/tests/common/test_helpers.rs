@@ -68,6 +68,8 @@ pub fn get_subnet_holding_ecdsa_keys(
         EcdsaConfig {
             quadruples_to_create_in_advance: 1,
             key_ids: ecdsa_key_ids.to_vec(),
+            schnorr_key_ids: vec![],
+            vetkd_key_ids: vec![],
             max_queue_size: Some(DEFAULT_ECDSA_MAX_QUEUE_SIZE),
             signature_request_timeout_ns: None,
             idkg_key_rotation_period_ms: None,
@@ -261,6 +261,8 @@ fn test_accepted_proposal_with_ecdsa_gets_keys_from_other_subnet() {
             subnet_record.ecdsa_config = Some(EcdsaConfig {
                 quadruples_to_create_in_advance: 100,
                 key_ids: vec![(&key_1).into()],
+                schnorr_key_ids: vec![],
+                vetkd_key_ids: vec![],
                 max_queue_size: DEFAULT_ECDSA_MAX_QUEUE_SIZE,
                 signature_request_timeout_ns: None,
                 idkg_key_rotation_period_ms: None,
@@ -265,6 +265,8 @@ fn test_recover_subnet_gets_ecdsa_keys_when_needed() {
             subnet_record.ecdsa_config = Some(EcdsaConfig {
                 quadruples_to_create_in_advance: 1,
                 key_ids: vec![(&key_1).into()],
+                schnorr_key_ids: vec![],
+                vetkd_key_ids: vec![],
                 max_queue_size: DEFAULT_ECDSA_MAX_QUEUE_SIZE,
                 signature_request_timeout_ns: None,
                 idkg_key_rotation_period_ms: None,
@@ -466,6 +468,8 @@ fn test_recover_subnet_without_ecdsa_key_removes_it_from_signing_list() {
             subnet_record.ecdsa_config = Some(EcdsaConfig {
                 quadruples_to_create_in_advance: 1,
                 key_ids: vec![(&key_1).into()],
+                schnorr_key_ids: vec![],
+                vetkd_key_ids: vec![],
                 max_queue_size: DEFAULT_ECDSA_MAX_QUEUE_SIZE,
                 signature_request_timeout_ns: None,
                 idkg_key_rotation_period_ms: None,
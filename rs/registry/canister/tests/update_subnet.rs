@@ -482,6 +482,8 @@ fn test_subnets_configuration_ecdsa_fields_are_updated_correctly() {
             ecdsa_config: Some(EcdsaConfig {
                 quadruples_to_create_in_advance: 10,
                 key_ids: vec![make_ecdsa_key("key_id_1")],
+                schnorr_key_ids: vec![],
+                vetkd_key_ids: vec![],
                 max_queue_size: Some(DEFAULT_ECDSA_MAX_QUEUE_SIZE),
                 signature_request_timeout_ns,
                 idkg_key_rotation_period_ms,
@@ -540,6 +542,8 @@ fn test_subnets_configuration_ecdsa_fields_are_updated_correctly() {
             ecdsa_config: Some(EcdsaConfig {
                 quadruples_to_create_in_advance: 10,
                 key_ids: vec![make_ecdsa_key("key_id_1")],
+                schnorr_key_ids: vec![],
+                vetkd_key_ids: vec![],
                 max_queue_size: Some(DEFAULT_ECDSA_MAX_QUEUE_SIZE),
                 signature_request_timeout_ns,
                 idkg_key_rotation_period_ms,
@@ -571,6 +575,8 @@ fn test_subnets_configuration_ecdsa_fields_are_updated_correctly() {
                     EcdsaConfig {
                         quadruples_to_create_in_advance: 10,
                         key_ids: vec![make_ecdsa_key("key_id_1")],
+                        schnorr_key_ids: vec![],
+                        vetkd_key_ids: vec![],
                         max_queue_size: Some(DEFAULT_ECDSA_MAX_QUEUE_SIZE),
                         signature_request_timeout_ns,
                         idkg_key_rotation_period_ms,
@@ -586,6 +592,8 @@ fn test_subnets_configuration_ecdsa_fields_are_updated_correctly() {
             ecdsa_config: Some(EcdsaConfig {
                 quadruples_to_create_in_advance: 10,
                 key_ids: vec![make_ecdsa_key("key_id_1")],
+                schnorr_key_ids: vec![],
+                vetkd_key_ids: vec![],
                 max_queue_size: Some(DEFAULT_ECDSA_MAX_QUEUE_SIZE),
                 signature_request_timeout_ns,
                 idkg_key_rotation_period_ms,
@@ -1,5 +1,5 @@
 use candid::CandidType;
-use ic_ic00_types::{BitcoinNetwork, EcdsaKeyId};
+use ic_ic00_types::{BitcoinNetwork, EcdsaKeyId, SchnorrKeyId, VetKdKeyId};
 use ic_protobuf::{proxy::ProxyDecodeError, registry::subnet::v1 as pb};
 use serde::{Deserialize, Serialize};
 use std::{convert::TryFrom, str::FromStr};
@@ -21,6 +21,11 @@ pub struct SubnetFeatures {
     pub bitcoin: Option<BitcoinFeature>,
 
     pub sev_status: Option<SevFeatureStatus>,
+
+    /// Determines whether the nodes of this subnet talk to each other over QUIC
+    /// instead of TLS over TCP. Takes effect at the first CUP whose registry
+    /// version enables (or disables) it.
+    pub quic_transport: Option<bool>,
 }
 
 impl SubnetFeatures {
@@ -33,6 +38,9 @@ impl SubnetFeatures {
     pub fn sev_status(&self) -> SevFeatureStatus {
         self.sev_status.unwrap_or(SevFeatureStatus::Disabled)
     }
+    pub fn quic_transport(&self) -> bool {
+        self.quic_transport.unwrap_or(false)
+    }
 }
 
 impl From<SubnetFeatures> for pb::SubnetFeatures {
@@ -58,6 +66,7 @@ impl From<SubnetFeatures> for pb::SubnetFeatures {
                 SevFeatureStatus::SecureNoUpgradeEnabled => 3,
                 SevFeatureStatus::SecureEnabled => 4,
             }),
+            quic_transport: features.quic_transport,
         }
     }
 }
@@ -97,6 +106,7 @@ impl From<pb::SubnetFeatures> for SubnetFeatures {
                 4 => SevFeatureStatus::SecureEnabled,
                 _ => SevFeatureStatus::Disabled,
             }),
+            quic_transport: features.quic_transport,
         }
     }
 }
@@ -116,6 +126,7 @@ impl FromStr for SubnetFeatures {
             match feature {
                 "canister_sandboxing" => features.canister_sandboxing = true,
                 "http_requests" => features.http_requests = true,
+                "quic_transport" => features.quic_transport = Some(true),
                 "bitcoin_testnet" => {
                     if features.bitcoin.is_some() {
                         // Feature was already set. Return an error.
@@ -234,6 +245,10 @@ impl From<i32> for BitcoinFeatureStatus {
 pub struct EcdsaConfig {
     pub quadruples_to_create_in_advance: u32,
     pub key_ids: Vec<EcdsaKeyId>,
+    #[serde(default)]
+    pub schnorr_key_ids: Vec<SchnorrKeyId>,
+    #[serde(default)]
+    pub vetkd_key_ids: Vec<VetKdKeyId>,
     pub max_queue_size: Option<u32>,
     pub signature_request_timeout_ns: Option<u64>,
     pub idkg_key_rotation_period_ms: Option<u64>,
@@ -244,6 +259,8 @@ impl From<EcdsaConfig> for pb::EcdsaConfig {
         pb::EcdsaConfig {
             quadruples_to_create_in_advance: item.quadruples_to_create_in_advance,
             key_ids: item.key_ids.iter().map(|key| key.into()).collect(),
+            schnorr_key_ids: item.schnorr_key_ids.iter().map(|key| key.into()).collect(),
+            vetkd_key_ids: item.vetkd_key_ids.iter().map(|key| key.into()).collect(),
             max_queue_size: item.max_queue_size.unwrap_or(DEFAULT_ECDSA_MAX_QUEUE_SIZE),
             signature_request_timeout_ns: item.signature_request_timeout_ns,
             idkg_key_rotation_period_ms: item.idkg_key_rotation_period_ms,
@@ -259,9 +276,19 @@ impl TryFrom<pb::EcdsaConfig> for EcdsaConfig {
         for key in value.key_ids {
             key_ids.push(EcdsaKeyId::try_from(key)?);
         }
+        let mut schnorr_key_ids = vec![];
+        for key in value.schnorr_key_ids {
+            schnorr_key_ids.push(SchnorrKeyId::try_from(key)?);
+        }
+        let mut vetkd_key_ids = vec![];
+        for key in value.vetkd_key_ids {
+            vetkd_key_ids.push(VetKdKeyId::try_from(key)?);
+        }
         Ok(EcdsaConfig {
             quadruples_to_create_in_advance: value.quadruples_to_create_in_advance,
             key_ids,
+            schnorr_key_ids,
+            vetkd_key_ids,
             max_queue_size: Some(value.max_queue_size),
             signature_request_timeout_ns: value.signature_request_timeout_ns,
             idkg_key_rotation_period_ms: value.idkg_key_rotation_period_ms,
@@ -303,8 +330,10 @@ mod tests {
 
     #[test]
     fn test_all_can_be_set_true() {
-        let result =
-            SubnetFeatures::from_str("canister_sandboxing,http_requests,bitcoin_testnet").unwrap();
+        let result = SubnetFeatures::from_str(
+            "canister_sandboxing,http_requests,bitcoin_testnet,quic_transport",
+        )
+        .unwrap();
         assert_eq!(
             result,
             SubnetFeatures {
@@ -315,6 +344,7 @@ mod tests {
                     status: BitcoinFeatureStatus::Enabled
                 }),
                 sev_status: None,
+                quic_transport: Some(true),
             }
         );
     }
@@ -334,6 +364,7 @@ mod tests {
                     status: BitcoinFeatureStatus::Paused
                 }),
                 sev_status: None,
+                quic_transport: None,
             }
         );
     }
@@ -352,6 +383,7 @@ mod tests {
                     status: BitcoinFeatureStatus::Enabled
                 }),
                 sev_status: None,
+                quic_transport: None,
             }
         );
     }
@@ -743,6 +743,7 @@ impl Player {
             // Use a fake randomness here since we don't have random tape for extra messages
             randomness,
             ecdsa_subnet_public_keys: BTreeMap::new(),
+            schnorr_subnet_public_keys: BTreeMap::new(),
             registry_version,
             time,
             consensus_responses: Vec::new(),
@@ -19,7 +19,7 @@ use ic_consensus::{
     consensus::{pool_reader::PoolReader, ConsensusCrypto, Membership},
     dkg, ecdsa,
 };
-use ic_crypto_tls_interfaces::TlsHandshake;
+use ic_crypto_tls_interfaces::{TlsConfig, TlsHandshake};
 use ic_cycles_account_manager::CyclesAccountManager;
 use ic_ingress_manager::IngressManager;
 use ic_interfaces::{
@@ -37,11 +37,11 @@ use ic_interfaces_state_manager::{StateManager, StateReader};
 use ic_interfaces_transport::Transport;
 use ic_logger::{info, replica_logger::ReplicaLogger};
 use ic_metrics::MetricsRegistry;
-use ic_p2p::{start_p2p, AdvertBroadcaster, P2PThreadJoiner};
+use ic_p2p::{start_p2p, AdvertBroadcaster, P2PThreadJoiner, P2P_TRANSPORT_CHANNELS};
 use ic_registry_client_helpers::subnet::SubnetRegistry;
 use ic_replicated_state::ReplicatedState;
 use ic_state_manager::state_sync::StateSync;
-use ic_transport::transport::create_transport;
+use ic_transport::transport::{create_switching_transport, SwitchingTransport};
 use ic_types::{
     artifact::{Advert, ArtifactKind, ArtifactTag, FileTreeSyncAttribute},
     consensus::catchup::CUPWithOriginalProtobuf,
@@ -52,7 +52,14 @@ use ic_types::{
     replica_config::ReplicaConfig,
     NodeId, SubnetId,
 };
-use std::sync::{Arc, Mutex, RwLock};
+use std::{
+    sync::{Arc, Mutex, RwLock, Weak},
+    time::Duration,
+};
+
+/// How often the transport checks whether it has to switch between TCP and
+/// QUIC.
+const TRANSPORT_SWITCH_CHECK_INTERVAL: Duration = Duration::from_secs(5);
 
 /// The P2P state sync client.
 pub enum P2PStateSyncClient {
@@ -99,6 +106,7 @@ pub fn create_networking_stack(
     // constructs it from the 'transport_config'.
     transport: Option<Arc<dyn Transport>>,
     tls_handshake: Arc<dyn TlsHandshake + Send + Sync>,
+    tls_config: Arc<dyn TlsConfig + Send + Sync>,
     state_manager: Arc<dyn StateManager<State = ReplicatedState>>,
     state_reader: Arc<dyn StateReader<State = ReplicatedState>>,
     state_sync_client: P2PStateSyncClient,
@@ -150,16 +158,34 @@ pub fn create_networking_stack(
     .unwrap();
 
     let transport = transport.unwrap_or_else(|| {
-        create_transport(
+        let consensus_pool_cache = artifact_pools.consensus_pool_cache.clone();
+        let transport = create_switching_transport(
             node_id,
-            transport_config.clone(),
+            TransportConfig {
+                // QUIC carries each P2P channel on its own streams.
+                max_streams: transport_config.max_streams.max(P2P_TRANSPORT_CHANNELS),
+                ..transport_config.clone()
+            },
             registry_client.get_latest_version(),
             metrics_registry.clone(),
             tls_handshake,
+            tls_config,
             rt_handle.clone(),
             log.clone(),
-            false,
-        )
+            quic_transport_enabled(
+                registry_client.as_ref(),
+                consensus_pool_cache.as_ref(),
+                subnet_id,
+            ),
+        );
+        spawn_transport_switch_task(
+            Arc::downgrade(&transport),
+            registry_client.clone(),
+            consensus_pool_cache,
+            subnet_id,
+            &rt_handle,
+        );
+        transport as Arc<dyn Transport>
     });
 
     let ingress_event_handler = {
@@ -187,6 +213,57 @@ pub fn create_networking_stack(
     (ingress_event_handler, p2p_thread)
 }
 
+/// Returns true if the `quic_transport` feature is enabled for the subnet at
+/// the registry version of the latest CUP. All nodes of the subnet see the same
+/// CUPs, so they switch between the transports at the same point.
+fn quic_transport_enabled(
+    registry_client: &dyn RegistryClient,
+    consensus_pool_cache: &dyn ConsensusPoolCache,
+    subnet_id: SubnetId,
+) -> bool {
+    let registry_version = consensus_pool_cache
+        .catch_up_package()
+        .content
+        .registry_version();
+    registry_client
+        .get_features(subnet_id, registry_version)
+        .ok()
+        .flatten()
+        .unwrap_or_default()
+        .quic_transport()
+}
+
+/// Spawns a task that switches the transport whenever the `quic_transport`
+/// feature changes at the registry version of the latest CUP. The task stops
+/// once the transport is dropped.
+fn spawn_transport_switch_task(
+    transport: Weak<SwitchingTransport>,
+    registry_client: Arc<dyn RegistryClient>,
+    consensus_pool_cache: Arc<dyn ConsensusPoolCache>,
+    subnet_id: SubnetId,
+    rt_handle: &tokio::runtime::Handle,
+) {
+    rt_handle.spawn(async move {
+        let mut interval = tokio::time::interval(TRANSPORT_SWITCH_CHECK_INTERVAL);
+        loop {
+            interval.tick().await;
+            let transport = match transport.upgrade() {
+                Some(transport) => transport,
+                None => return,
+            };
+            let use_quic = quic_transport_enabled(
+                registry_client.as_ref(),
+                consensus_pool_cache.as_ref(),
+                subnet_id,
+            );
+            if transport.uses_quic() != use_quic {
+                // Switching blocks on the transport's locks.
+                let _ = tokio::task::spawn_blocking(move || transport.set_use_quic(use_quic)).await;
+            }
+        }
+    });
+}
+
 /// The function sets up and returns the Artifact Manager and Consensus Pool.
 ///
 /// The Artifact Manager runs all artifact clients as separate actors.
@@ -62,6 +62,7 @@ pub fn push_response(
                 originator: context.request.sender(),
                 respondent: CanisterId::ic_00(),
                 originator_reply_callback: callback_id,
+                deadline: context.request.deadline,
                 refund: context.request.take_cycles(),
                 response_payload,
             });
@@ -104,6 +105,7 @@ pub fn push_response(
                 originator: context.request.sender(),
                 respondent: CanisterId::ic_00(),
                 originator_reply_callback: callback_id,
+                deadline: context.request.deadline,
                 refund: context.request.take_cycles(),
                 response_payload,
             });
@@ -0,0 +1,323 @@
+use crate::{
+    canister_state::execution_state::{ExportedFunctions, Global, WasmMetadata},
+    num_bytes_try_from, CanisterState, NumWasmPages, PageMap,
+};
+use ic_types::{CanisterId, NumBytes, Time};
+use ic_wasm_types::CanisterModule;
+use std::{collections::BTreeMap, convert::TryFrom, sync::Arc};
+
+/// The maximum number of snapshots a single canister may hold at a time.
+pub const MAX_SNAPSHOTS_PER_CANISTER: usize = 1;
+
+/// Unique identifier of a canister snapshot.
+///
+/// It consists of the id of the canister the snapshot belongs to and a local
+/// id that is unique among the snapshots ever taken of that canister. Since
+/// the canister id comes first, all snapshots of a canister are adjacent in
+/// any ordered collection of `SnapshotId`s.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SnapshotId {
+    canister_id: CanisterId,
+    local_id: u64,
+}
+
+impl SnapshotId {
+    pub fn new(canister_id: CanisterId, local_id: u64) -> Self {
+        Self {
+            canister_id,
+            local_id,
+        }
+    }
+
+    /// Returns the id of the canister the snapshot belongs to.
+    pub fn canister_id(&self) -> CanisterId {
+        self.canister_id
+    }
+
+    /// Returns the id of the snapshot among the snapshots of its canister.
+    pub fn local_id(&self) -> u64 {
+        self.local_id
+    }
+
+    /// Encodes the snapshot id as the blob returned to users: the big-endian
+    /// local id followed by the canister id bytes.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = self.local_id.to_be_bytes().to_vec();
+        bytes.extend_from_slice(self.canister_id.get_ref().as_slice());
+        bytes
+    }
+}
+
+impl TryFrom<&[u8]> for SnapshotId {
+    type Error = String;
+
+    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+        const LOCAL_ID_LEN: usize = std::mem::size_of::<u64>();
+        if bytes.len() <= LOCAL_ID_LEN {
+            return Err(format!(
+                "Snapshot id must be longer than {} bytes, got {}",
+                LOCAL_ID_LEN,
+                bytes.len()
+            ));
+        }
+        let (local_id, canister_id) = bytes.split_at(LOCAL_ID_LEN);
+        let local_id = u64::from_be_bytes(local_id.try_into().unwrap());
+        let canister_id = CanisterId::try_from(canister_id)
+            .map_err(|err| format!("Snapshot id has an invalid canister id: {}", err))?;
+        Ok(Self::new(canister_id, local_id))
+    }
+}
+
+impl std::fmt::Display for SnapshotId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}-{}", self.canister_id, self.local_id)
+    }
+}
+
+/// A Wasm memory (heap or stable) as captured in a snapshot.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PageMemory {
+    /// The contents of the memory.
+    pub page_map: PageMap,
+    /// The size of the memory in Wasm pages.
+    pub size: NumWasmPages,
+}
+
+impl PageMemory {
+    pub fn new(page_map: PageMap, size: NumWasmPages) -> Self {
+        Self { page_map, size }
+    }
+
+    fn size_bytes(&self) -> NumBytes {
+        num_bytes_try_from(self.size)
+            .expect("could not convert from wasm memory number of pages to bytes")
+    }
+}
+
+/// The parts of a canister's `ExecutionState` that are captured by a
+/// snapshot and restored when the snapshot is loaded.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ExecutionStateSnapshot {
+    /// The raw canister module.
+    pub wasm_binary: CanisterModule,
+    /// The state of exported globals.
+    pub exported_globals: Vec<Global>,
+    /// The functions exported by the Wasm module.
+    pub exports: ExportedFunctions,
+    /// Metadata extracted from the Wasm module.
+    pub metadata: WasmMetadata,
+    /// The Wasm heap.
+    pub wasm_memory: PageMemory,
+    /// The stable memory.
+    pub stable_memory: PageMemory,
+}
+
+/// A snapshot of the state of a canister, taken by a controller via
+/// `take_canister_snapshot` and restored via `load_canister_snapshot`.
+///
+/// Snapshots are immutable: the page maps they hold share their pages with
+/// the canister at the time of taking the snapshot, and later executions
+/// only ever modify the canister's copies.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CanisterSnapshot {
+    /// The canister the snapshot was taken of.
+    canister_id: CanisterId,
+    /// The time at which the snapshot was taken.
+    taken_at_timestamp: Time,
+    /// The version of the canister at the time the snapshot was taken.
+    canister_version: u64,
+    /// The certified data of the canister.
+    certified_data: Vec<u8>,
+    /// The captured execution state.
+    execution_snapshot: ExecutionStateSnapshot,
+}
+
+impl CanisterSnapshot {
+    pub fn new(
+        canister_id: CanisterId,
+        taken_at_timestamp: Time,
+        canister_version: u64,
+        certified_data: Vec<u8>,
+        execution_snapshot: ExecutionStateSnapshot,
+    ) -> Self {
+        Self {
+            canister_id,
+            taken_at_timestamp,
+            canister_version,
+            certified_data,
+            execution_snapshot,
+        }
+    }
+
+    /// Captures the current state of the given canister. Returns `None` if
+    /// the canister has no Wasm module installed.
+    pub fn from_canister(canister: &CanisterState, taken_at_timestamp: Time) -> Option<Self> {
+        let execution_state = canister.execution_state.as_ref()?;
+        Some(Self::new(
+            canister.canister_id(),
+            taken_at_timestamp,
+            canister.system_state.canister_version,
+            canister.system_state.certified_data.clone(),
+            ExecutionStateSnapshot {
+                wasm_binary: execution_state.wasm_binary.binary.clone(),
+                exported_globals: execution_state.exported_globals.clone(),
+                exports: execution_state.exports.clone(),
+                metadata: execution_state.metadata.clone(),
+                wasm_memory: PageMemory::new(
+                    execution_state.wasm_memory.page_map.clone(),
+                    execution_state.wasm_memory.size,
+                ),
+                stable_memory: PageMemory::new(
+                    execution_state.stable_memory.page_map.clone(),
+                    execution_state.stable_memory.size,
+                ),
+            },
+        ))
+    }
+
+    pub fn canister_id(&self) -> CanisterId {
+        self.canister_id
+    }
+
+    pub fn taken_at_timestamp(&self) -> Time {
+        self.taken_at_timestamp
+    }
+
+    pub fn canister_version(&self) -> u64 {
+        self.canister_version
+    }
+
+    pub fn certified_data(&self) -> &Vec<u8> {
+        &self.certified_data
+    }
+
+    pub fn execution_snapshot(&self) -> &ExecutionStateSnapshot {
+        &self.execution_snapshot
+    }
+
+    /// Returns the memory taken by the snapshot. This is the amount the
+    /// canister is charged for while the snapshot exists.
+    pub fn size(&self) -> NumBytes {
+        // We use 8 bytes per global, the same as for the execution state.
+        let globals_size_bytes = 8 * self.execution_snapshot.exported_globals.len() as u64;
+        self.execution_snapshot.wasm_memory.size_bytes()
+            + self.execution_snapshot.stable_memory.size_bytes()
+            + NumBytes::from(globals_size_bytes)
+            + NumBytes::from(self.execution_snapshot.wasm_binary.len() as u64)
+            + NumBytes::from(self.certified_data.len() as u64)
+    }
+}
+
+/// The snapshots of all canisters on the subnet, indexed by snapshot id.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct CanisterSnapshots {
+    snapshots: BTreeMap<SnapshotId, Arc<CanisterSnapshot>>,
+}
+
+impl CanisterSnapshots {
+    pub fn new(snapshots: BTreeMap<SnapshotId, Arc<CanisterSnapshot>>) -> Self {
+        Self { snapshots }
+    }
+
+    /// Adds the snapshot with the given id, replacing any snapshot that was
+    /// stored under the same id.
+    pub fn push(&mut self, snapshot_id: SnapshotId, snapshot: Arc<CanisterSnapshot>) {
+        self.snapshots.insert(snapshot_id, snapshot);
+    }
+
+    /// Returns the snapshot with the given id, if any.
+    pub fn get(&self, snapshot_id: SnapshotId) -> Option<&Arc<CanisterSnapshot>> {
+        self.snapshots.get(&snapshot_id)
+    }
+
+    /// Removes and returns the snapshot with the given id, if any.
+    pub fn remove(&mut self, snapshot_id: SnapshotId) -> Option<Arc<CanisterSnapshot>> {
+        self.snapshots.remove(&snapshot_id)
+    }
+
+    /// Returns the snapshots of the given canister, ordered by snapshot id.
+    pub fn list_snapshots(
+        &self,
+        canister_id: CanisterId,
+    ) -> Vec<(SnapshotId, Arc<CanisterSnapshot>)> {
+        self.snapshots
+            .range(SnapshotId::new(canister_id, 0)..=SnapshotId::new(canister_id, u64::MAX))
+            .map(|(snapshot_id, snapshot)| (*snapshot_id, Arc::clone(snapshot)))
+            .collect()
+    }
+
+    /// Removes all snapshots of the given canister.
+    pub fn delete_snapshots(&mut self, canister_id: CanisterId) {
+        for (snapshot_id, _) in self.list_snapshots(canister_id) {
+            self.snapshots.remove(&snapshot_id);
+        }
+    }
+
+    /// Returns an iterator over all snapshots, ordered by snapshot id.
+    pub fn iter(&self) -> impl Iterator<Item = (&SnapshotId, &Arc<CanisterSnapshot>)> {
+        self.snapshots.iter()
+    }
+
+    /// Returns true if there are no snapshots.
+    pub fn is_empty(&self) -> bool {
+        self.snapshots.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ic_test_utilities::types::ids::canister_test_id;
+
+    #[test]
+    fn snapshot_id_round_trips_through_bytes() {
+        let snapshot_id = SnapshotId::new(canister_test_id(7), 42);
+        assert_eq!(
+            SnapshotId::try_from(snapshot_id.to_bytes().as_slice()).unwrap(),
+            snapshot_id
+        );
+        assert!(SnapshotId::try_from(&[0u8; 8][..]).is_err());
+    }
+
+    #[test]
+    fn snapshots_are_grouped_by_canister() {
+        let snapshot = |canister_id| {
+            Arc::new(CanisterSnapshot::new(
+                canister_id,
+                Time::from_nanos_since_unix_epoch(0),
+                0,
+                vec![],
+                ExecutionStateSnapshot {
+                    wasm_binary: CanisterModule::new(vec![]),
+                    exported_globals: vec![],
+                    exports: ExportedFunctions::new(Default::default()),
+                    metadata: WasmMetadata::default(),
+                    wasm_memory: PageMemory::new(PageMap::new_for_testing(), NumWasmPages::from(0)),
+                    stable_memory: PageMemory::new(
+                        PageMap::new_for_testing(),
+                        NumWasmPages::from(0),
+                    ),
+                },
+            ))
+        };
+        let mut snapshots = CanisterSnapshots::default();
+        for (canister, local_id) in [(1, 0), (2, 0), (2, 1), (3, 5)] {
+            let canister_id = canister_test_id(canister);
+            snapshots.push(
+                SnapshotId::new(canister_id, local_id),
+                snapshot(canister_id),
+            );
+        }
+
+        let ids: Vec<_> = snapshots
+            .list_snapshots(canister_test_id(2))
+            .into_iter()
+            .map(|(id, _)| id.local_id())
+            .collect();
+        assert_eq!(ids, vec![0, 1]);
+
+        snapshots.delete_snapshots(canister_test_id(2));
+        assert!(snapshots.list_snapshots(canister_test_id(2)).is_empty());
+        assert_eq!(snapshots.iter().count(), 2);
+    }
+}
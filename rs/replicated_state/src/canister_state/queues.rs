@@ -15,7 +15,7 @@ use ic_protobuf::{
 };
 use ic_types::{
     messages::{
-        Ingress, Payload, RejectContext, Request, RequestOrResponse, Response,
+        CallbackId, Ingress, Payload, RejectContext, Request, RequestOrResponse, Response,
         MAX_RESPONSE_COUNT_BYTES,
     },
     xnet::{QueueId, SessionId},
@@ -626,6 +626,7 @@ impl CanisterQueues {
             originator: request.sender,
             respondent: request.receiver,
             originator_reply_callback: request.sender_reply_callback,
+            deadline: request.deadline,
             refund: request.payment,
             response_payload: Payload::Reject(reject_context),
         }));
@@ -937,11 +938,19 @@ impl CanisterQueues {
             RequestOrResponse::Request(_) => 0,
             RequestOrResponse::Response(_) => 1,
         };
+        let best_effort_size_bytes = |msg: &RequestOrResponse| {
+            if msg.is_best_effort() {
+                msg.count_bytes()
+            } else {
+                0
+            }
+        };
         for (q, _) in canister_queues.values() {
             stats.message_count += q.num_messages();
             stats.response_count += q.calculate_stat_sum(response_count);
             stats.reserved_slots += q.reserved_slots() as isize;
             stats.size_bytes += q.calculate_size_bytes();
+            stats.best_effort_size_bytes += q.calculate_stat_sum(best_effort_size_bytes);
             stats.cycles += q.cycles_in_queue();
         }
         stats
@@ -1028,6 +1037,14 @@ impl CanisterQueues {
                 self.memory_usage_stats -= MemoryUsageStats::stats_delta(QueueOp::Pop, &request);
                 self.output_queues_stats -= OutputQueuesStats::stats_delta(&request);
 
+                // Best-effort requests are dropped silently: the `SYS_UNKNOWN` reject
+                // produced when the callback expires (no later than the request times
+                // out) is going to fill the reserved slot.
+                if request.is_best_effort() {
+                    timed_out_requests_count += 1;
+                    continue;
+                }
+
                 // Push response, update stats.
                 let iq_stats_delta = InputQueuesStats::stats_delta(QueueOp::Push, &response);
                 let mu_stats_delta = MemoryUsageStats::stats_delta(QueueOp::Push, &response);
@@ -1054,6 +1071,71 @@ impl CanisterQueues {
         timed_out_requests_count
     }
 
+    /// Returns `true` if the input queue from `respondent` holds a response for
+    /// `callback_id`.
+    pub(super) fn has_input_response_for_callback(
+        &self,
+        respondent: &CanisterId,
+        callback_id: CallbackId,
+    ) -> bool {
+        self.canister_queues
+            .get(respondent)
+            .map_or(false, |(input_queue, _)| {
+                input_queue.has_response_for_callback(callback_id)
+            })
+    }
+
+    /// Drops the largest best-effort message across all input queues, in order
+    /// to free up memory. Returns the dropped message; or `None` if the input
+    /// queues hold no best-effort messages.
+    ///
+    /// The slot of a dropped response reverts to a reservation, to be filled by
+    /// the `SYS_UNKNOWN` reject response produced when the callback expires. A
+    /// dropped request also releases the response reservation in the matching
+    /// output queue; any cycles attached to it are lost.
+    pub fn shed_largest_best_effort_message(&mut self) -> Option<RequestOrResponse> {
+        let (sender, index, _) = self
+            .canister_queues
+            .iter()
+            .filter_map(|(sender, (input_queue, _))| {
+                input_queue
+                    .largest_best_effort_message()
+                    .map(|(index, size_bytes)| (*sender, index, size_bytes))
+            })
+            .max_by_key(|(_, _, size_bytes)| *size_bytes)?;
+
+        let (input_queue, output_queue) = self.canister_queues.get_mut(&sender).unwrap();
+        let msg = input_queue.remove(index).unwrap();
+        self.input_queues_stats -= InputQueuesStats::stats_delta(QueueOp::Pop, &msg);
+        self.memory_usage_stats -= MemoryUsageStats::stats_delta(QueueOp::Pop, &msg);
+        match &msg {
+            RequestOrResponse::Response(_) => {
+                // The response slot reverts to a reservation.
+                self.input_queues_stats.reserved_slots += 1;
+                self.memory_usage_stats += MemoryUsageStats::response_slot_delta();
+            }
+            RequestOrResponse::Request(_) => {
+                // No response will be produced, release the reservation.
+                output_queue.release_reserved_slot();
+                self.memory_usage_stats -= MemoryUsageStats::response_slot_delta();
+            }
+        }
+
+        // Only senders with non-empty input queues are scheduled.
+        if input_queue.num_messages() == 0 {
+            self.local_subnet_input_schedule.retain(|id| *id != sender);
+            self.remote_subnet_input_schedule.retain(|id| *id != sender);
+        }
+        debug_assert!(self.stats_ok());
+
+        Some(msg)
+    }
+
+    /// Returns the total byte size of best-effort messages in input queues.
+    pub fn best_effort_input_messages_size_bytes(&self) -> usize {
+        self.input_queues_stats.best_effort_size_bytes
+    }
+
     /// Re-partitions `self.local_subnet_input_schedule` and
     /// `self.remote_subnet_input_schedule` based on the set of all local canisters
     /// plus `own_canister_id` (since Rust's ownership rules would prevent us from
@@ -1104,6 +1186,7 @@ fn generate_timeout_response(request: &Arc<Request>) -> RequestOrResponse {
         originator: request.sender,
         respondent: request.receiver,
         originator_reply_callback: request.sender_reply_callback,
+        deadline: request.deadline,
         refund: request.payment,
         response_payload: Payload::Reject(RejectContext::new_with_message_length_limit(
             RejectCode::SysTransient,
@@ -1247,6 +1330,9 @@ pub struct InputQueuesStats {
     /// Byte size of input queues (queues + messages).
     size_bytes: usize,
 
+    /// Byte size of best-effort messages in input queues.
+    best_effort_size_bytes: usize,
+
     /// Total amount of cycles contained in the input messages.
     cycles: Cycles,
 }
@@ -1265,11 +1351,18 @@ impl InputQueuesStats {
             _ => 0,
         };
 
+        let best_effort_size_bytes = if msg.is_best_effort() {
+            msg.count_bytes()
+        } else {
+            0
+        };
+
         InputQueuesStats {
             message_count: 1,
             response_count,
             reserved_slots,
             size_bytes: msg.count_bytes(),
+            best_effort_size_bytes,
             cycles: msg.cycles(),
         }
     }
@@ -1281,6 +1374,7 @@ impl AddAssign<InputQueuesStats> for InputQueuesStats {
         self.response_count += rhs.response_count;
         self.reserved_slots += rhs.reserved_slots;
         self.size_bytes += rhs.size_bytes;
+        self.best_effort_size_bytes += rhs.best_effort_size_bytes;
         self.cycles += rhs.cycles;
     }
 }
@@ -1291,6 +1385,7 @@ impl SubAssign<InputQueuesStats> for InputQueuesStats {
         self.response_count -= rhs.response_count;
         self.reserved_slots -= rhs.reserved_slots;
         self.size_bytes -= rhs.size_bytes;
+        self.best_effort_size_bytes -= rhs.best_effort_size_bytes;
         self.cycles -= rhs.cycles;
     }
 }
@@ -4,7 +4,7 @@ mod tests;
 
 use ic_protobuf::proxy::ProxyDecodeError;
 use ic_protobuf::state::{ingress::v1 as pb_ingress, queues::v1 as pb_queues};
-use ic_types::messages::{Ingress, Request, RequestOrResponse, Response};
+use ic_types::messages::{CallbackId, Ingress, Request, RequestOrResponse, Response};
 use ic_types::{CountBytes, Cycles, Time};
 use std::{
     collections::VecDeque,
@@ -189,6 +189,33 @@ impl<T: QueueItem<T> + std::clone::Clone> QueueWithReservation<T> {
         self.queue.front()
     }
 
+    /// Removes the item at position `index` from the queue and returns it;
+    /// or `None` if `index` is out of bounds.
+    ///
+    /// Removing a response turns its slot back into a reservation (so that a
+    /// response may be enqueued again for the same request), whereas removing
+    /// a request frees up its request slot.
+    fn remove(&mut self, index: usize) -> Option<T> {
+        let msg = self.queue.remove(index);
+        if let Some(msg) = &msg {
+            if !msg.is_response() {
+                self.num_request_slots = self.num_request_slots.checked_sub(1).unwrap();
+            }
+        }
+        debug_assert!(self.check_invariants());
+        msg
+    }
+
+    /// Releases a response reservation that is no longer needed (e.g. because
+    /// the request it was made for was dropped).
+    ///
+    /// Panics if there are no reserved slots.
+    fn release_reserved_slot(&mut self) {
+        assert!(self.reserved_slots() > 0);
+        self.num_response_slots -= 1;
+        debug_assert!(self.check_invariants());
+    }
+
     /// Returns the number of reserved slots in the queue.
     pub(super) fn reserved_slots(&self) -> usize {
         (self.num_request_slots + self.num_response_slots)
@@ -373,6 +400,34 @@ impl InputQueue {
         self.queue.pop()
     }
 
+    /// Returns the index and byte size of the largest best-effort message in
+    /// the queue; or `None` if the queue holds no best-effort messages.
+    pub(super) fn largest_best_effort_message(&self) -> Option<(usize, usize)> {
+        self.queue
+            .queue
+            .iter()
+            .enumerate()
+            .filter(|(_, msg)| msg.is_best_effort())
+            .map(|(index, msg)| (index, msg.count_bytes()))
+            .max_by_key(|(_, size_bytes)| *size_bytes)
+    }
+
+    /// Removes the message at position `index` from the queue and returns it.
+    /// If the message was a response, its slot reverts to a reservation.
+    pub(super) fn remove(&mut self, index: usize) -> Option<RequestOrResponse> {
+        self.queue.remove(index)
+    }
+
+    /// Returns `true` if the queue holds a response for the given callback.
+    pub(super) fn has_response_for_callback(&self, callback_id: CallbackId) -> bool {
+        self.queue.queue.iter().any(|msg| match msg {
+            RequestOrResponse::Response(response) => {
+                response.originator_reply_callback == callback_id
+            }
+            RequestOrResponse::Request(_) => false,
+        })
+    }
+
     /// Returns the number of messages in the queue.
     pub(super) fn num_messages(&self) -> usize {
         self.queue.queue.len()
@@ -534,6 +589,12 @@ impl OutputQueue {
         self.queue.reserve_slot()
     }
 
+    /// Releases a response reservation, e.g. after the incoming request it was
+    /// made for was dropped.
+    pub(super) fn release_reserved_slot(&mut self) {
+        self.queue.release_reserved_slot()
+    }
+
     /// Pops a message off the queue and returns it.
     ///
     /// Ensures there is always a 'Some' at the beginning.
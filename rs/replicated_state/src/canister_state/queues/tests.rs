@@ -16,7 +16,11 @@ use ic_test_utilities::{
         messages::{IngressBuilder, RequestBuilder, ResponseBuilder},
     },
 };
-use ic_types::{messages::CallbackId, time::current_time_and_expiry_time};
+use ic_types::{
+    messages::{CallbackId, NO_DEADLINE},
+    time::current_time_and_expiry_time,
+    CoarseTime,
+};
 use maplit::btreemap;
 use proptest::prelude::*;
 use std::convert::TryInto;
@@ -1003,6 +1007,7 @@ fn test_stats() {
             response_count: 0,
             reserved_slots: 0,
             size_bytes: iq_size + msg_size[i],
+            best_effort_size_bytes: 0,
             cycles: Cycles::new(5),
         };
         assert_eq!(expected_iq_stats, queues.input_queues_stats);
@@ -1024,6 +1029,7 @@ fn test_stats() {
         response_count: 0,
         reserved_slots: 0,
         size_bytes: msg_size[0],
+        best_effort_size_bytes: 0,
         cycles: Cycles::new(5),
     };
     assert_eq!(expected_iq_stats, queues.input_queues_stats);
@@ -1152,6 +1158,7 @@ fn test_stats() {
         response_count: 1,
         reserved_slots: -1,
         size_bytes: msg_size[5],
+        best_effort_size_bytes: 0,
         cycles: Cycles::new(5),
     };
     assert_eq!(expected_iq_stats, queues.input_queues_stats);
@@ -1178,6 +1185,7 @@ fn test_stats() {
         response_count: 0,
         reserved_slots: 0,
         size_bytes: msg_size[1],
+        best_effort_size_bytes: 0,
         cycles: Cycles::new(5),
     };
     assert_eq!(expected_iq_stats, queues.input_queues_stats);
@@ -1196,6 +1204,7 @@ fn test_stats() {
         response_count: 0,
         reserved_slots: 0,
         size_bytes: msg_size[2],
+        best_effort_size_bytes: 0,
         cycles: Cycles::new(5),
     };
     assert_eq!(expected_iq_stats, queues.input_queues_stats);
@@ -1214,6 +1223,7 @@ fn test_stats() {
         response_count: 1,
         reserved_slots: 0,
         size_bytes: msg_size[5],
+        best_effort_size_bytes: 0,
         cycles: Cycles::new(5),
     };
     assert_eq!(expected_iq_stats, queues.input_queues_stats);
@@ -1267,6 +1277,7 @@ fn test_stats_induct_message_to_self() {
         response_count: 0,
         reserved_slots: 0,
         size_bytes: request_size,
+        best_effort_size_bytes: 0,
         cycles: Cycles::zero(),
     };
     assert_eq!(expected_iq_stats, queues.input_queues_stats);
@@ -1283,6 +1294,7 @@ fn test_stats_induct_message_to_self() {
         response_count: 0,
         reserved_slots: 0,
         size_bytes: request_size,
+        best_effort_size_bytes: 0,
         cycles: Cycles::zero(),
     };
     assert_eq!(expected_iq_stats, queues.input_queues_stats);
@@ -1316,6 +1328,7 @@ fn test_stats_induct_message_to_self() {
         response_count: 1,
         reserved_slots: -1,
         size_bytes: response_size,
+        best_effort_size_bytes: 0,
         cycles: Cycles::zero(),
     };
     assert_eq!(expected_iq_stats, queues.input_queues_stats);
@@ -1332,6 +1345,7 @@ fn test_stats_induct_message_to_self() {
         response_count: 1,
         reserved_slots: 0,
         size_bytes: response_size,
+        best_effort_size_bytes: 0,
         cycles: Cycles::zero(),
     };
     assert_eq!(expected_iq_stats, queues.input_queues_stats);
@@ -1806,6 +1820,7 @@ fn time_out_requests_pushes_correct_reject_responses() {
                     receiver: canister_id,
                     sender: own_canister_id,
                     sender_reply_callback: CallbackId::from(callback_id),
+                    deadline: NO_DEADLINE,
                     payment: Cycles::from(cycles as u64),
                     method_name: "No-Op".to_string(),
                     method_payload: vec![],
@@ -1859,6 +1874,7 @@ fn time_out_requests_pushes_correct_reject_responses() {
                 originator: own_canister_id,
                 respondent: remote_canister_id,
                 originator_reply_callback: CallbackId::from(2),
+                deadline: NO_DEADLINE,
                 refund: Cycles::from(7_u64),
                 response_payload: Payload::Reject(RejectContext::new_with_message_length_limit(
                     RejectCode::SysTransient,
@@ -1898,3 +1914,151 @@ fn time_out_requests_pushes_correct_reject_responses() {
         VecDeque::from(vec![remote_canister_id]),
     );
 }
+
+/// Tests that timing out a best-effort output request does not enqueue a reject
+/// response, but retains the reserved response slot for the `SYS_UNKNOWN` reject
+/// produced once the callback expires.
+#[test]
+fn time_out_requests_does_not_reject_best_effort_requests() {
+    let mut canister_queues = CanisterQueues::default();
+
+    let own_canister_id = canister_test_id(67);
+    let remote_canister_id = canister_test_id(97);
+
+    let time = Time::from_nanos_since_unix_epoch(1);
+    canister_queues
+        .push_output_request(
+            Arc::new(
+                RequestBuilder::default()
+                    .sender(own_canister_id)
+                    .receiver(remote_canister_id)
+                    .deadline(CoarseTime::from_secs_since_unix_epoch(100))
+                    .build(),
+            ),
+            time,
+        )
+        .unwrap();
+
+    assert_eq!(
+        1,
+        canister_queues.time_out_requests(
+            time + REQUEST_LIFETIME,
+            &own_canister_id,
+            &BTreeMap::new()
+        ),
+    );
+
+    let (input_queue, output_queue) = canister_queues
+        .canister_queues
+        .get(&remote_canister_id)
+        .unwrap();
+    assert_eq!(0, output_queue.num_messages());
+    assert_eq!(0, input_queue.num_messages());
+    assert_eq!(1, input_queue.reserved_slots());
+    assert!(!canister_queues.has_input());
+}
+
+/// Tests that `shed_largest_best_effort_message()` sheds best-effort messages
+/// in decreasing order of size and never sheds guaranteed response messages.
+#[test]
+fn shed_largest_best_effort_message_sheds_largest_first() {
+    let mut canister_queues = CanisterQueues::default();
+    let deadline = CoarseTime::from_secs_since_unix_epoch(100);
+
+    let small = RequestBuilder::default()
+        .sender(canister_test_id(1))
+        .deadline(deadline)
+        .method_payload(vec![0; 10])
+        .build();
+    let large = RequestBuilder::default()
+        .sender(canister_test_id(2))
+        .deadline(deadline)
+        .method_payload(vec![0; 1000])
+        .build();
+    let guaranteed = RequestBuilder::default()
+        .sender(canister_test_id(3))
+        .method_payload(vec![0; 10_000])
+        .build();
+    let small = RequestOrResponse::from(small);
+    let large = RequestOrResponse::from(large);
+    for msg in [&small, &large, &guaranteed.into()] {
+        canister_queues
+            .push_input(msg.clone(), RemoteSubnet)
+            .unwrap();
+    }
+    assert_eq!(3, canister_queues.reserved_slots());
+    assert_eq!(
+        small.count_bytes() + large.count_bytes(),
+        canister_queues.best_effort_input_messages_size_bytes()
+    );
+
+    assert_eq!(
+        Some(large),
+        canister_queues.shed_largest_best_effort_message()
+    );
+    assert_eq!(
+        small.count_bytes(),
+        canister_queues.best_effort_input_messages_size_bytes()
+    );
+    assert_eq!(
+        Some(small),
+        canister_queues.shed_largest_best_effort_message()
+    );
+    assert_eq!(0, canister_queues.best_effort_input_messages_size_bytes());
+    assert_eq!(None, canister_queues.shed_largest_best_effort_message());
+
+    // Shed senders were dropped from the input schedule, the guaranteed response
+    // request is still there.
+    assert_eq!(
+        &VecDeque::from(vec![canister_test_id(3)]),
+        canister_queues.get_remote_subnet_input_schedule()
+    );
+    assert_eq!(1, canister_queues.input_queues_message_count());
+    assert_eq!(1, canister_queues.reserved_slots());
+}
+
+/// Tests that shedding a best-effort response reverts its slot to a
+/// reservation, so that a reject response can still be enqueued later.
+#[test]
+fn shed_best_effort_response_restores_reservation() {
+    let mut canister_queues = CanisterQueues::default();
+    let own_canister_id = canister_test_id(1);
+    let other_canister_id = canister_test_id(2);
+    let deadline = CoarseTime::from_secs_since_unix_epoch(100);
+
+    canister_queues
+        .push_output_request(
+            Arc::new(
+                RequestBuilder::default()
+                    .sender(own_canister_id)
+                    .receiver(other_canister_id)
+                    .deadline(deadline)
+                    .build(),
+            ),
+            mock_time(),
+        )
+        .unwrap();
+    assert_eq!(1, canister_queues.reserved_slots());
+
+    let response: RequestOrResponse = ResponseBuilder::default()
+        .originator(own_canister_id)
+        .respondent(other_canister_id)
+        .deadline(deadline)
+        .build()
+        .into();
+    canister_queues
+        .push_input(response.clone(), RemoteSubnet)
+        .unwrap();
+    assert_eq!(0, canister_queues.reserved_slots());
+
+    assert_eq!(
+        Some(response.clone()),
+        canister_queues.shed_largest_best_effort_message()
+    );
+    assert_eq!(1, canister_queues.reserved_slots());
+    assert!(!canister_queues.has_input());
+
+    // The reserved slot can still be used, e.g. by a `SYS_UNKNOWN` reject.
+    canister_queues.push_input(response, RemoteSubnet).unwrap();
+    assert_eq!(0, canister_queues.reserved_slots());
+}
@@ -1,11 +1,15 @@
 mod call_context_manager;
+pub mod wasm_chunk_store;
 
 use super::queues::can_push;
 pub use super::queues::memory_required_to_push_request;
 pub use crate::canister_state::queues::CanisterOutputQueuesIterator;
+use crate::replicated_state::MR_SYNTHETIC_REJECT_MESSAGE_MAX_LEN;
 use crate::{CanisterQueues, CanisterState, InputQueueType, StateError};
 pub use call_context_manager::{CallContext, CallContextAction, CallContextManager, CallOrigin};
 use ic_base_types::NumSeconds;
+use ic_error_types::RejectCode;
+use ic_ic00_types::{CanisterChange, CanisterChangeDetails, CanisterChangeOrigin, LogVisibility};
 use ic_interfaces::messages::{CanisterCall, CanisterMessage, CanisterMessageOrTask, CanisterTask};
 use ic_logger::{error, ReplicaLogger};
 use ic_protobuf::{
@@ -14,9 +18,13 @@ use ic_protobuf::{
 };
 use ic_registry_subnet_type::SubnetType;
 use ic_types::{
-    messages::{Ingress, RejectContext, Request, RequestOrResponse, Response, StopCanisterContext},
+    canister_log::CanisterLog,
+    messages::{
+        Ingress, Payload, RejectContext, Request, RequestOrResponse, Response, StopCanisterContext,
+    },
     nominal_cycles::NominalCycles,
-    CanisterId, CanisterTimer, Cycles, MemoryAllocation, NumBytes, PrincipalId, Time,
+    CanisterId, CanisterTimer, CoarseTime, CountBytes, Cycles, MemoryAllocation, NumBytes,
+    PrincipalId, Time,
 };
 use lazy_static::lazy_static;
 use maplit::btreeset;
@@ -27,6 +35,7 @@ use std::{
 };
 use std::{collections::BTreeSet, sync::Arc};
 use std::{collections::VecDeque, str::FromStr};
+pub use wasm_chunk_store::WasmChunkStore;
 
 lazy_static! {
     static ref DEFAULT_PRINCIPAL_MULTIPLE_CONTROLLERS: PrincipalId =
@@ -112,6 +121,29 @@ pub struct SystemState {
 
     /// Canister version.
     pub canister_version: u64,
+
+    /// Memory taken by the snapshots of this canister. Snapshots are stored in
+    /// `ReplicatedState::canister_snapshots`; their size is accounted here so
+    /// that the canister is charged for them like for its own memory.
+    pub snapshots_memory_usage: NumBytes,
+
+    /// The local id to assign to the next snapshot of this canister. See
+    /// `SnapshotId` for details.
+    pub next_snapshot_id: u64,
+
+    /// Chunks of Wasm modules uploaded via `upload_chunk`.
+    pub wasm_chunk_store: WasmChunkStore,
+
+    /// Most recent output of `ic0.debug_print` and `ic0.trap` produced during
+    /// replicated execution.
+    pub canister_log: CanisterLog,
+
+    /// Who is allowed to read `canister_log` via `fetch_canister_logs`.
+    pub log_visibility: LogVisibility,
+
+    /// Bounded history of changes made to the canister. Must only be
+    /// modified through `add_canister_change`.
+    canister_history: CanisterHistory,
 }
 
 /// A wrapper around the different canister statuses.
@@ -387,6 +419,73 @@ impl TryFrom<pb::ExecutionTask> for ExecutionTask {
     }
 }
 
+/// Maximum number of changes kept in the history of a canister.
+pub const MAX_CANISTER_HISTORY_CHANGES: u64 = 20;
+
+/// The history of changes made to a canister: its creation, code deployments
+/// and uninstalls, and changes of its controllers.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CanisterHistory {
+    /// The most recent changes, oldest first. At most
+    /// `MAX_CANISTER_HISTORY_CHANGES` changes are kept.
+    changes: Arc<VecDeque<CanisterChange>>,
+
+    /// Number of changes ever recorded, including the ones already dropped.
+    total_num_changes: u64,
+}
+
+impl CanisterHistory {
+    /// Records a change, dropping the oldest one if the history is full.
+    pub fn add_canister_change(&mut self, change: CanisterChange) {
+        let changes = Arc::make_mut(&mut self.changes);
+        if changes.len() >= MAX_CANISTER_HISTORY_CHANGES as usize {
+            changes.pop_front();
+        }
+        changes.push_back(change);
+        self.total_num_changes += 1;
+    }
+
+    /// Returns the `num_requested_changes` most recent changes (or fewer if
+    /// fewer are kept), oldest first.
+    pub fn get_changes(
+        &self,
+        num_requested_changes: usize,
+    ) -> impl Iterator<Item = &CanisterChange> {
+        let num_changes = num_requested_changes.min(self.changes.len());
+        self.changes.range((self.changes.len() - num_changes)..)
+    }
+
+    /// Returns the number of changes ever recorded.
+    pub fn get_total_num_changes(&self) -> u64 {
+        self.total_num_changes
+    }
+}
+
+impl From<&CanisterHistory> for pb::CanisterHistory {
+    fn from(item: &CanisterHistory) -> Self {
+        Self {
+            changes: item.changes.iter().map(|change| change.into()).collect(),
+            total_num_changes: item.total_num_changes,
+        }
+    }
+}
+
+impl TryFrom<pb::CanisterHistory> for CanisterHistory {
+    type Error = ProxyDecodeError;
+
+    fn try_from(value: pb::CanisterHistory) -> Result<Self, Self::Error> {
+        let changes = value
+            .changes
+            .into_iter()
+            .map(CanisterChange::try_from)
+            .collect::<Result<VecDeque<_>, _>>()?;
+        Ok(Self {
+            changes: Arc::new(changes),
+            total_num_changes: value.total_num_changes,
+        })
+    }
+}
+
 impl SystemState {
     pub fn new_running(
         canister_id: CanisterId,
@@ -457,6 +556,12 @@ impl SystemState {
             task_queue: Default::default(),
             global_timer: CanisterTimer::Inactive,
             canister_version: 0,
+            snapshots_memory_usage: NumBytes::from(0),
+            next_snapshot_id: 0,
+            wasm_chunk_store: WasmChunkStore::default(),
+            canister_log: CanisterLog::default(),
+            log_visibility: LogVisibility::default(),
+            canister_history: CanisterHistory::default(),
         }
     }
 
@@ -491,6 +596,12 @@ impl SystemState {
         task_queue: VecDeque<ExecutionTask>,
         global_timer: CanisterTimer,
         canister_version: u64,
+        snapshots_memory_usage: NumBytes,
+        next_snapshot_id: u64,
+        wasm_chunk_store: WasmChunkStore,
+        canister_log: CanisterLog,
+        log_visibility: LogVisibility,
+        canister_history: CanisterHistory,
     ) -> Self {
         Self {
             controllers,
@@ -506,6 +617,12 @@ impl SystemState {
             task_queue,
             global_timer,
             canister_version,
+            snapshots_memory_usage,
+            next_snapshot_id,
+            wasm_chunk_store,
+            canister_log,
+            log_visibility,
+            canister_history,
         }
     }
 
@@ -513,6 +630,28 @@ impl SystemState {
         self.canister_id
     }
 
+    /// Records a change in the canister history, attributed to the current
+    /// canister version.
+    pub fn add_canister_change(
+        &mut self,
+        timestamp: Time,
+        origin: CanisterChangeOrigin,
+        details: CanisterChangeDetails,
+    ) {
+        let change = CanisterChange::new(
+            timestamp.as_nanos_since_unix_epoch(),
+            self.canister_version,
+            origin,
+            details,
+        );
+        self.canister_history.add_canister_change(change);
+    }
+
+    /// Returns the history of changes made to the canister.
+    pub fn get_canister_history(&self) -> &CanisterHistory {
+        &self.canister_history
+    }
+
     /// Returns a mutable reference to the balance of the canister.
     pub fn balance_mut(&mut self) -> &mut Cycles {
         &mut self.cycles_balance
@@ -706,6 +845,10 @@ impl SystemState {
     ///  * `Running` system states accept requests and responses.
     ///  * `Stopping` system states accept responses only.
     ///  * `Stopped` system states accept neither.
+    ///  * Late responses for expired best-effort callbacks (and best-effort
+    ///    responses for unknown callbacks) are silently dropped, as a
+    ///    `SYS_UNKNOWN` reject response has already been enqueued in their
+    ///    place.
     ///
     /// # Errors
     ///
@@ -763,6 +906,13 @@ impl SystemState {
                 },
             ) => {
                 if let RequestOrResponse::Response(response) = &msg {
+                    let callback_id = &response.originator_reply_callback;
+                    if call_context_manager.is_callback_expired(callback_id)
+                        || (response.is_best_effort()
+                            && call_context_manager.callback(callback_id).is_none())
+                    {
+                        return Ok(());
+                    }
                     call_context_manager
                         .validate_response(response)
                         .map_err(|err| (err, msg.clone()))?;
@@ -943,6 +1093,103 @@ impl SystemState {
             .time_out_requests(current_time, own_canister_id, local_canisters)
     }
 
+    /// Returns `true` if the canister has best-effort callbacks whose deadline
+    /// has expired and that have not been timed out yet.
+    pub fn has_expired_callbacks(&self, current_time: CoarseTime) -> bool {
+        self.call_context_manager()
+            .map_or(false, |ccm| ccm.has_expired_callbacks(current_time))
+    }
+
+    /// Times out best-effort callbacks whose deadline has expired: enqueues a
+    /// `SYS_UNKNOWN` reject response for each into the input queue from the
+    /// respondent (consuming the response reservation) and marks the callback
+    /// as expired, so that any late response is dropped. Returns the number of
+    /// callbacks that were timed out.
+    ///
+    /// Callbacks with a response already enqueued are left alone, as are all
+    /// callbacks while the canister has a paused or aborted execution (which
+    /// may be handling the response to one of them).
+    pub fn time_out_callbacks(
+        &mut self,
+        current_time: CoarseTime,
+        own_canister_id: &CanisterId,
+        local_canisters: &BTreeMap<CanisterId, CanisterState>,
+    ) -> u64 {
+        if self.task_queue.iter().any(|task| {
+            matches!(
+                task,
+                ExecutionTask::PausedExecution(_) | ExecutionTask::AbortedExecution { .. }
+            )
+        }) {
+            return 0;
+        }
+
+        let call_context_manager = match &mut self.status {
+            CanisterStatus::Running {
+                call_context_manager,
+            }
+            | CanisterStatus::Stopping {
+                call_context_manager,
+                ..
+            } => call_context_manager,
+            CanisterStatus::Stopped => return 0,
+        };
+
+        let mut timed_out_callbacks_count = 0;
+        for callback_id in call_context_manager.expired_callbacks(current_time) {
+            let callback = call_context_manager.callback(&callback_id).unwrap();
+            let respondent = match callback.respondent {
+                Some(respondent) => respondent,
+                None => continue,
+            };
+            if self
+                .queues
+                .has_input_response_for_callback(&respondent, callback_id)
+            {
+                continue;
+            }
+
+            let response = RequestOrResponse::Response(Arc::new(Response {
+                originator: self.canister_id,
+                respondent,
+                originator_reply_callback: callback_id,
+                deadline: callback.deadline,
+                refund: Cycles::zero(),
+                response_payload: Payload::Reject(RejectContext::new_with_message_length_limit(
+                    RejectCode::SysUnknown,
+                    "Call deadline has expired.".to_string(),
+                    MR_SYNTHETIC_REJECT_MESSAGE_MAX_LEN,
+                )),
+            }));
+            let input_queue_type =
+                if &respondent == own_canister_id || local_canisters.contains_key(&respondent) {
+                    InputQueueType::LocalSubnet
+                } else {
+                    InputQueueType::RemoteSubnet
+                };
+            // Only fails if there is no reservation for the response, in which case
+            // the response must be in flight (e.g. being executed).
+            if self.queues.push_input(response, input_queue_type).is_ok() {
+                call_context_manager.mark_callback_expired(callback_id);
+                timed_out_callbacks_count += 1;
+            }
+        }
+
+        timed_out_callbacks_count
+    }
+
+    /// Drops the largest best-effort message from the input queues, to free
+    /// up memory. Returns the byte size of the dropped message; or `None` if
+    /// the input queues hold no best-effort messages.
+    ///
+    /// See [`CanisterQueues::shed_largest_best_effort_message`] for further
+    /// details.
+    pub fn shed_largest_best_effort_message(&mut self) -> Option<usize> {
+        self.queues
+            .shed_largest_best_effort_message()
+            .map(|msg| msg.count_bytes())
+    }
+
     /// Re-partitions the local and remote input schedules of `self.queues`
     /// following a canister migration, based on the updated set of local canisters.
     ///
@@ -8,15 +8,15 @@ use ic_protobuf::proxy::{try_from_option_field, ProxyDecodeError};
 use ic_protobuf::state::canister_state_bits::v1 as pb;
 use ic_protobuf::types::v1 as pb_types;
 use ic_types::messages::Response;
-use ic_types::Time;
 use ic_types::{
     ingress::WasmResult,
     messages::{CallContextId, CallbackId, MessageId},
     methods::Callback,
     user_id_into_protobuf, user_id_try_from_protobuf, CanisterId, Cycles, Funds, UserId,
 };
+use ic_types::{CoarseTime, Time};
 use serde::{Deserialize, Serialize};
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 use std::convert::{From, TryFrom, TryInto};
 use std::time::Duration;
 
@@ -211,12 +211,25 @@ pub struct CallContextManager {
     // maps call context to its responded status
     call_contexts: BTreeMap<CallContextId, CallContext>,
     callbacks: BTreeMap<CallbackId, Callback>,
+    /// Deadlines of outstanding best-effort callbacks that have not expired
+    /// yet, for efficiently looking up expired callbacks. Not persisted, but
+    /// rebuilt from `callbacks` and `expired_callbacks` on deserialization.
+    #[serde(default)]
+    callback_deadlines: BTreeSet<(CoarseTime, CallbackId)>,
+    /// Best-effort callbacks whose deadline has passed and for which a
+    /// `SYS_UNKNOWN` reject response has already been enqueued. Any late
+    /// response for one of these callbacks is dropped.
+    #[serde(default)]
+    expired_callbacks: BTreeSet<CallbackId>,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub enum CallOrigin {
     Ingress(UserId, MessageId),
-    CanisterUpdate(CanisterId, CallbackId),
+    /// A call from another canister, identified by the caller's callback ID.
+    /// The deadline is `NO_DEADLINE` for guaranteed response calls and is
+    /// copied onto the response for best-effort calls.
+    CanisterUpdate(CanisterId, CallbackId, CoarseTime),
     Query(UserId),
     CanisterQuery(CanisterId, CallbackId),
     /// System task is either a Heartbeat or a GlobalTimer.
@@ -230,10 +243,11 @@ impl From<&CallOrigin> for pb::call_context::CallOrigin {
                 user_id: Some(user_id_into_protobuf(*user_id)),
                 message_id: message_id.as_bytes().to_vec(),
             }),
-            CallOrigin::CanisterUpdate(canister_id, callback_id) => {
+            CallOrigin::CanisterUpdate(canister_id, callback_id, deadline) => {
                 Self::CanisterUpdate(pb::call_context::CanisterUpdateOrQuery {
                     canister_id: Some(pb_types::CanisterId::from(*canister_id)),
                     callback_id: callback_id.get(),
+                    deadline_seconds: deadline.as_secs_since_unix_epoch(),
                 })
             }
             CallOrigin::Query(user_id) => Self::Query(user_id_into_protobuf(*user_id)),
@@ -241,6 +255,7 @@ impl From<&CallOrigin> for pb::call_context::CallOrigin {
                 Self::CanisterQuery(pb::call_context::CanisterUpdateOrQuery {
                     canister_id: Some(pb_types::CanisterId::from(*canister_id)),
                     callback_id: callback_id.get(),
+                    deadline_seconds: 0,
                 })
             }
             CallOrigin::SystemTask => Self::SystemTask(pb::call_context::SystemTask {}),
@@ -266,10 +281,12 @@ impl TryFrom<pb::call_context::CallOrigin> for CallOrigin {
                 pb::call_context::CanisterUpdateOrQuery {
                     canister_id,
                     callback_id,
+                    deadline_seconds,
                 },
             ) => Self::CanisterUpdate(
                 try_from_option_field(canister_id, "CallOrigin::CanisterUpdate::canister_id")?,
                 callback_id.into(),
+                CoarseTime::from_secs_since_unix_epoch(deadline_seconds),
             ),
             pb::call_context::CallOrigin::Query(user_id) => {
                 Self::Query(user_id_try_from_protobuf(user_id)?)
@@ -278,6 +295,7 @@ impl TryFrom<pb::call_context::CallOrigin> for CallOrigin {
                 pb::call_context::CanisterUpdateOrQuery {
                     canister_id,
                     callback_id,
+                    ..
                 },
             ) => Self::CanisterQuery(
                 try_from_option_field(canister_id, "CallOrigin::CanisterQuery::canister_id")?,
@@ -489,6 +507,10 @@ impl CallContextManager {
     pub fn register_callback(&mut self, callback: Callback) -> CallbackId {
         self.next_callback_id += 1;
         let callback_id = CallbackId::from(self.next_callback_id);
+        if callback.is_best_effort() {
+            self.callback_deadlines
+                .insert((callback.deadline, callback_id));
+        }
         self.callbacks.insert(callback_id, callback);
         callback_id
     }
@@ -501,7 +523,48 @@ impl CallContextManager {
     /// If we get a response for one of the outstanding calls, we unregister
     /// the callback and return it.
     pub fn unregister_callback(&mut self, callback_id: CallbackId) -> Option<Callback> {
-        self.callbacks.remove(&callback_id)
+        let callback = self.callbacks.remove(&callback_id)?;
+        self.callback_deadlines
+            .remove(&(callback.deadline, callback_id));
+        self.expired_callbacks.remove(&callback_id);
+        Some(callback)
+    }
+
+    /// Returns `true` iff at least one best-effort callback has a deadline at
+    /// or before `current_time` and has not been marked as expired yet.
+    pub fn has_expired_callbacks(&self, current_time: CoarseTime) -> bool {
+        matches!(
+            self.callback_deadlines.first(),
+            Some((deadline, _)) if *deadline <= current_time
+        )
+    }
+
+    /// Returns the IDs of all best-effort callbacks with a deadline at or
+    /// before `current_time` that have not been marked as expired yet, in
+    /// deadline order.
+    pub fn expired_callbacks(&self, current_time: CoarseTime) -> Vec<CallbackId> {
+        self.callback_deadlines
+            .iter()
+            .take_while(|(deadline, _)| *deadline <= current_time)
+            .map(|(_, callback_id)| *callback_id)
+            .collect()
+    }
+
+    /// Marks the given best-effort callback as expired, i.e. a `SYS_UNKNOWN`
+    /// reject response was enqueued for it and any late response is to be
+    /// dropped.
+    pub fn mark_callback_expired(&mut self, callback_id: CallbackId) {
+        if let Some(callback) = self.callbacks.get(&callback_id) {
+            self.callback_deadlines
+                .remove(&(callback.deadline, callback_id));
+            self.expired_callbacks.insert(callback_id);
+        }
+    }
+
+    /// Returns `true` iff the callback with `callback_id` has expired (i.e. a
+    /// `SYS_UNKNOWN` reject response was already enqueued for it).
+    pub fn is_callback_expired(&self, callback_id: &CallbackId) -> bool {
+        self.expired_callbacks.contains(callback_id)
     }
 
     /// Returns the call origin, which is either the message id of the ingress
@@ -576,9 +639,11 @@ impl CallContextManager {
 impl From<&CanisterCall> for CallOrigin {
     fn from(msg: &CanisterCall) -> Self {
         match msg {
-            CanisterCall::Request(request) => {
-                CallOrigin::CanisterUpdate(request.sender, request.sender_reply_callback)
-            }
+            CanisterCall::Request(request) => CallOrigin::CanisterUpdate(
+                request.sender,
+                request.sender_reply_callback,
+                request.deadline,
+            ),
             CanisterCall::Ingress(ingress) => {
                 CallOrigin::Ingress(ingress.source, ingress.message_id.clone())
             }
@@ -616,6 +681,7 @@ impl From<&CallContextManager> for pb::CallContextManager {
                     callback: Some(callback.into()),
                 })
                 .collect(),
+            expired_callbacks: item.expired_callbacks.iter().map(|id| id.get()).collect(),
         }
     }
 }
@@ -646,11 +712,24 @@ impl TryFrom<pb::CallContextManager> for CallContextManager {
             );
         }
 
+        let expired_callbacks: BTreeSet<CallbackId> = value
+            .expired_callbacks
+            .into_iter()
+            .map(CallbackId::from)
+            .collect();
+        let callback_deadlines = callbacks
+            .iter()
+            .filter(|(id, callback)| callback.is_best_effort() && !expired_callbacks.contains(id))
+            .map(|(id, callback)| (callback.deadline, *id))
+            .collect();
+
         Ok(Self {
             next_call_context_id: value.next_call_context_id,
             next_callback_id: value.next_callback_id,
             call_contexts,
             callbacks,
+            callback_deadlines,
+            expired_callbacks,
         })
     }
 }
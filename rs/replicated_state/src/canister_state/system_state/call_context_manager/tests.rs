@@ -1,6 +1,6 @@
 use super::*;
 use ic_test_utilities::types::ids::canister_test_id;
-use ic_types::methods::WasmClosure;
+use ic_types::{messages::NO_DEADLINE, methods::WasmClosure};
 
 #[test]
 fn call_context_origin() {
@@ -8,13 +8,13 @@ fn call_context_origin() {
     let id = canister_test_id(42);
     let cb_id = CallbackId::from(1);
     let cc_id = ccm.new_call_context(
-        CallOrigin::CanisterUpdate(id, cb_id),
+        CallOrigin::CanisterUpdate(id, cb_id, NO_DEADLINE),
         Cycles::new(10),
         Time::from_nanos_since_unix_epoch(0),
     );
     assert_eq!(
         ccm.call_contexts().get(&cc_id).unwrap().call_origin,
-        CallOrigin::CanisterUpdate(id, cb_id)
+        CallOrigin::CanisterUpdate(id, cb_id, NO_DEADLINE)
     );
 }
 
@@ -26,18 +26,18 @@ fn call_context_handling() {
 
     // On two incoming calls
     let call_context_id1 = call_context_manager.new_call_context(
-        CallOrigin::CanisterUpdate(canister_test_id(123), CallbackId::from(1)),
+        CallOrigin::CanisterUpdate(canister_test_id(123), CallbackId::from(1), NO_DEADLINE),
         Cycles::zero(),
         Time::from_nanos_since_unix_epoch(0),
     );
     let call_context_id2 = call_context_manager.new_call_context(
-        CallOrigin::CanisterUpdate(canister_test_id(123), CallbackId::from(2)),
+        CallOrigin::CanisterUpdate(canister_test_id(123), CallbackId::from(2), NO_DEADLINE),
         Cycles::zero(),
         Time::from_nanos_since_unix_epoch(0),
     );
 
     let call_context_id3 = call_context_manager.new_call_context(
-        CallOrigin::CanisterUpdate(canister_test_id(123), CallbackId::from(3)),
+        CallOrigin::CanisterUpdate(canister_test_id(123), CallbackId::from(3), NO_DEADLINE),
         Cycles::zero(),
         Time::from_nanos_since_unix_epoch(0),
     );
@@ -78,6 +78,7 @@ fn call_context_handling() {
         WasmClosure::new(0, 1),
         WasmClosure::new(2, 3),
         None,
+        NO_DEADLINE,
     ));
     let callback_id2 = call_context_manager.register_callback(Callback::new(
         call_context_id1,
@@ -89,6 +90,7 @@ fn call_context_handling() {
         WasmClosure::new(4, 5),
         WasmClosure::new(6, 7),
         None,
+        NO_DEADLINE,
     ));
 
     // There are 2 ougoing calls
@@ -105,6 +107,7 @@ fn call_context_handling() {
         WasmClosure::new(8, 9),
         WasmClosure::new(10, 11),
         None,
+        NO_DEADLINE,
     ));
     // There is 1 outgoing call
     assert_eq!(call_context_manager.outstanding_calls(call_context_id2), 1);
@@ -254,7 +257,7 @@ fn withdraw_cycles_fails_when_not_enough_available_cycles() {
     let id = canister_test_id(42);
     let cb_id = CallbackId::from(1);
     let cc_id = ccm.new_call_context(
-        CallOrigin::CanisterUpdate(id, cb_id),
+        CallOrigin::CanisterUpdate(id, cb_id, NO_DEADLINE),
         Cycles::new(30),
         Time::from_nanos_since_unix_epoch(0),
     );
@@ -276,7 +279,7 @@ fn withdraw_cycles_succeeds_when_enough_available_cycles() {
     let id = canister_test_id(42);
     let cb_id = CallbackId::from(1);
     let cc_id = ccm.new_call_context(
-        CallOrigin::CanisterUpdate(id, cb_id),
+        CallOrigin::CanisterUpdate(id, cb_id, NO_DEADLINE),
         Cycles::new(30),
         Time::from_nanos_since_unix_epoch(0),
     );
@@ -288,3 +291,59 @@ fn withdraw_cycles_succeeds_when_enough_available_cycles() {
         Ok(())
     );
 }
+
+#[test]
+fn best_effort_callbacks_expire() {
+    let mut ccm = CallContextManager::default();
+    let cc_id = ccm.new_call_context(
+        CallOrigin::SystemTask,
+        Cycles::zero(),
+        Time::from_nanos_since_unix_epoch(0),
+    );
+    let callback_with_deadline = |deadline| {
+        Callback::new(
+            cc_id,
+            Some(canister_test_id(1)),
+            Some(canister_test_id(2)),
+            Cycles::zero(),
+            Some(Cycles::zero()),
+            Some(Cycles::zero()),
+            WasmClosure::new(0, 1),
+            WasmClosure::new(2, 3),
+            None,
+            deadline,
+        )
+    };
+    let deadline1 = CoarseTime::from_secs_since_unix_epoch(10);
+    let deadline2 = CoarseTime::from_secs_since_unix_epoch(20);
+    let guaranteed_id = ccm.register_callback(callback_with_deadline(NO_DEADLINE));
+    let cb_id1 = ccm.register_callback(callback_with_deadline(deadline1));
+    let cb_id2 = ccm.register_callback(callback_with_deadline(deadline2));
+
+    // Guaranteed response callbacks never expire.
+    assert!(!ccm.has_expired_callbacks(CoarseTime::from_secs_since_unix_epoch(9)));
+    assert!(ccm.has_expired_callbacks(deadline1));
+    assert_eq!(vec![cb_id1], ccm.expired_callbacks(deadline1));
+    assert_eq!(
+        vec![cb_id1, cb_id2],
+        ccm.expired_callbacks(CoarseTime::from_secs_since_unix_epoch(u32::MAX))
+    );
+
+    // An expired callback is no longer reported as expiring, but is retained
+    // until the `SYS_UNKNOWN` reject has been executed.
+    ccm.mark_callback_expired(cb_id1);
+    assert!(ccm.is_callback_expired(&cb_id1));
+    assert!(!ccm.has_expired_callbacks(deadline1));
+    assert!(ccm.callback(&cb_id1).is_some());
+
+    // Expired callbacks and deadlines survive a roundtrip through protobuf.
+    let pb_ccm: pb::CallContextManager = (&ccm).into();
+    assert_eq!(ccm, CallContextManager::try_from(pb_ccm).unwrap());
+
+    // Unregistering the callback clears its expired state.
+    ccm.unregister_callback(cb_id1);
+    assert!(!ccm.is_callback_expired(&cb_id1));
+    ccm.unregister_callback(cb_id2);
+    assert!(!ccm.has_expired_callbacks(CoarseTime::from_secs_since_unix_epoch(u32::MAX)));
+    assert!(ccm.callback(&guaranteed_id).is_some());
+}
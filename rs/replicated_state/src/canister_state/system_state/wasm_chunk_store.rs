@@ -0,0 +1,134 @@
+use ic_types::NumBytes;
+use std::{collections::BTreeMap, sync::Arc};
+
+/// The maximum size of a single chunk, in bytes.
+pub const CHUNK_SIZE: usize = 1024 * 1024;
+
+/// The maximum number of chunks a canister's chunk store may hold.
+pub const MAX_CHUNKS_IN_STORE: usize = 100;
+
+/// The SHA-256 hash of a chunk, which is also its key in the store.
+pub type WasmChunkHash = [u8; 32];
+
+/// Computes the hash under which `chunk` is stored.
+pub fn chunk_hash(chunk: &[u8]) -> WasmChunkHash {
+    ic_crypto_sha::Sha256::hash(chunk)
+}
+
+/// Chunks of Wasm modules uploaded via `upload_chunk`, from which a module
+/// can be assembled and installed via `install_chunked_code`.
+///
+/// Chunks are addressed by their hash, so uploading the same chunk twice
+/// stores it once. The memory taken by the chunks counts towards the memory
+/// usage of the canister.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct WasmChunkStore {
+    chunks: BTreeMap<WasmChunkHash, Arc<Vec<u8>>>,
+}
+
+impl WasmChunkStore {
+    /// Checks that `chunk` can be inserted into the store.
+    pub fn can_insert_chunk(&self, chunk: &[u8]) -> Result<(), String> {
+        if chunk.len() > CHUNK_SIZE {
+            return Err(format!(
+                "Chunk size {} exceeds the maximum of {} bytes",
+                chunk.len(),
+                CHUNK_SIZE
+            ));
+        }
+        if self.chunks.len() >= MAX_CHUNKS_IN_STORE && !self.contains(&chunk_hash(chunk)) {
+            return Err(format!(
+                "Chunk store is full: it already holds the maximum of {} chunks",
+                MAX_CHUNKS_IN_STORE
+            ));
+        }
+        Ok(())
+    }
+
+    /// Inserts the chunk and returns its hash. Inserting a chunk that is
+    /// already stored is a no-op.
+    ///
+    /// Callers are expected to check `can_insert_chunk` first.
+    pub fn insert_chunk(&mut self, chunk: Vec<u8>) -> WasmChunkHash {
+        let hash = chunk_hash(&chunk);
+        self.chunks.entry(hash).or_insert_with(|| Arc::new(chunk));
+        hash
+    }
+
+    /// Returns true if a chunk with the given hash is stored.
+    pub fn contains(&self, hash: &WasmChunkHash) -> bool {
+        self.chunks.contains_key(hash)
+    }
+
+    /// Returns the chunk with the given hash, if any.
+    pub fn get_chunk(&self, hash: &WasmChunkHash) -> Option<&[u8]> {
+        self.chunks.get(hash).map(|chunk| chunk.as_slice())
+    }
+
+    /// Returns the hashes of all stored chunks, in ascending order.
+    pub fn keys(&self) -> impl Iterator<Item = &WasmChunkHash> {
+        self.chunks.keys()
+    }
+
+    /// Returns all stored chunks, ordered by hash.
+    pub fn chunks(&self) -> impl Iterator<Item = &[u8]> {
+        self.chunks.values().map(|chunk| chunk.as_slice())
+    }
+
+    /// Removes all chunks.
+    pub fn clear(&mut self) {
+        self.chunks.clear();
+    }
+
+    /// Returns the memory taken by the stored chunks.
+    pub fn memory_usage(&self) -> NumBytes {
+        NumBytes::from(
+            self.chunks
+                .values()
+                .map(|chunk| chunk.len() as u64)
+                .sum::<u64>(),
+        )
+    }
+}
+
+impl FromIterator<Vec<u8>> for WasmChunkStore {
+    fn from_iter<T: IntoIterator<Item = Vec<u8>>>(chunks: T) -> Self {
+        let mut store = Self::default();
+        for chunk in chunks {
+            store.insert_chunk(chunk);
+        }
+        store
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn chunks_are_deduplicated_by_hash() {
+        let mut store = WasmChunkStore::default();
+        let hash = store.insert_chunk(vec![1, 2, 3]);
+        assert_eq!(store.insert_chunk(vec![1, 2, 3]), hash);
+        assert_eq!(store.keys().count(), 1);
+        assert_eq!(store.get_chunk(&hash), Some(&[1, 2, 3][..]));
+        assert_eq!(store.memory_usage(), NumBytes::from(3));
+
+        store.clear();
+        assert!(!store.contains(&hash));
+        assert_eq!(store.memory_usage(), NumBytes::from(0));
+    }
+
+    #[test]
+    fn can_insert_chunk_enforces_limits() {
+        let mut store = WasmChunkStore::default();
+        assert!(store.can_insert_chunk(&vec![0; CHUNK_SIZE + 1]).is_err());
+
+        for i in 0..MAX_CHUNKS_IN_STORE {
+            store.insert_chunk((i as u64).to_le_bytes().to_vec());
+        }
+        assert!(store.can_insert_chunk(&[42]).is_err());
+        // Re-uploading a stored chunk is still allowed.
+        assert!(store.can_insert_chunk(&0_u64.to_le_bytes()).is_ok());
+    }
+}
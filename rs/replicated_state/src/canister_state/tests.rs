@@ -3,6 +3,7 @@ use crate::canister_state::execution_state::WasmMetadata;
 use crate::CallOrigin;
 use crate::Memory;
 use ic_base_types::NumSeconds;
+use ic_error_types::RejectCode;
 use ic_logger::replica_logger::no_op_logger;
 use ic_test_utilities::mock_time;
 use ic_test_utilities::types::{
@@ -10,16 +11,17 @@ use ic_test_utilities::types::{
     messages::{RequestBuilder, ResponseBuilder},
 };
 use ic_types::messages::CallContextId;
-use ic_types::{
-    messages::CallbackId,
-    methods::{Callback, WasmClosure},
-    Time,
-};
 use ic_types::{
     messages::MAX_RESPONSE_COUNT_BYTES, nominal_cycles::NominalCycles, xnet::QueueId, CountBytes,
     Cycles,
 };
+use ic_types::{
+    messages::{CallbackId, Payload, NO_DEADLINE},
+    methods::{Callback, WasmClosure},
+    CoarseTime, Time,
+};
 use ic_wasm_types::CanisterModule;
+use std::collections::BTreeMap;
 
 const CANISTER_ID: CanisterId = CanisterId::from_u64(42);
 const OTHER_CANISTER_ID: CanisterId = CanisterId::from_u64(13);
@@ -71,13 +73,17 @@ impl CanisterStateFixture {
     }
 
     fn make_callback(&mut self) -> CallbackId {
+        self.make_callback_with_deadline(NO_DEADLINE)
+    }
+
+    fn make_callback_with_deadline(&mut self, deadline: CoarseTime) -> CallbackId {
         let call_context_id = self
             .canister_state
             .system_state
             .call_context_manager_mut()
             .unwrap()
             .new_call_context(
-                CallOrigin::CanisterUpdate(CANISTER_ID, CallbackId::from(1)),
+                CallOrigin::CanisterUpdate(CANISTER_ID, CallbackId::from(1), NO_DEADLINE),
                 Cycles::zero(),
                 Time::from_nanos_since_unix_epoch(0),
             );
@@ -95,6 +101,7 @@ impl CanisterStateFixture {
                 WasmClosure::new(0, 2),
                 WasmClosure::new(0, 2),
                 None,
+                deadline,
             ))
     }
 
@@ -168,6 +175,80 @@ fn canister_state_push_input_response_success() {
         .unwrap();
 }
 
+#[test]
+fn time_out_callbacks_enqueues_sys_unknown_reject() {
+    let mut fixture = CanisterStateFixture::new();
+    let deadline = CoarseTime::from_secs_since_unix_epoch(10);
+    // Make an input queue reservation.
+    fixture.with_input_reservation();
+    let callback_id = fixture.make_callback_with_deadline(deadline);
+    let system_state = &mut fixture.canister_state.system_state;
+
+    // Nothing to time out before the deadline.
+    let before_deadline = CoarseTime::from_secs_since_unix_epoch(9);
+    assert!(!system_state.has_expired_callbacks(before_deadline));
+    assert_eq!(
+        0,
+        system_state.time_out_callbacks(before_deadline, &CANISTER_ID, &BTreeMap::new())
+    );
+
+    // At the deadline, a `SYS_UNKNOWN` reject is enqueued exactly once.
+    assert!(system_state.has_expired_callbacks(deadline));
+    assert_eq!(
+        1,
+        system_state.time_out_callbacks(deadline, &CANISTER_ID, &BTreeMap::new())
+    );
+    assert_eq!(
+        0,
+        system_state.time_out_callbacks(deadline, &CANISTER_ID, &BTreeMap::new())
+    );
+    match fixture.canister_state.pop_input() {
+        Some(CanisterMessage::Response(response)) => {
+            assert_eq!(callback_id, response.originator_reply_callback);
+            assert_eq!(deadline, response.deadline);
+            match &response.response_payload {
+                Payload::Reject(context) => assert_eq!(RejectCode::SysUnknown, context.code()),
+                payload => panic!("Expected a reject, got {:?}", payload),
+            }
+        }
+        msg => panic!("Expected a response, got {:?}", msg),
+    }
+}
+
+#[test]
+fn late_best_effort_response_is_dropped() {
+    let mut fixture = CanisterStateFixture::new();
+    let deadline = CoarseTime::from_secs_since_unix_epoch(10);
+    fixture.with_input_reservation();
+    let callback_id = fixture.make_callback_with_deadline(deadline);
+    assert_eq!(
+        1,
+        fixture.canister_state.system_state.time_out_callbacks(
+            deadline,
+            &CANISTER_ID,
+            &BTreeMap::new()
+        )
+    );
+    fixture.canister_state.pop_input().unwrap();
+
+    // The actual response arriving after the callback expired is silently dropped.
+    let response: RequestOrResponse = ResponseBuilder::default()
+        .originator(CANISTER_ID)
+        .respondent(OTHER_CANISTER_ID)
+        .originator_reply_callback(callback_id)
+        .deadline(deadline)
+        .build()
+        .into();
+    fixture
+        .push_input(
+            response,
+            SubnetType::Application,
+            InputQueueType::RemoteSubnet,
+        )
+        .unwrap();
+    assert!(!fixture.canister_state.has_input());
+}
+
 #[test]
 #[should_panic(expected = "Expected `RequestOrResponse` to be targeted to canister ID")]
 fn canister_state_push_input_request_mismatched_receiver() {
@@ -552,6 +633,7 @@ fn canister_state_callback_round_trip() {
         WasmClosure::new(0, 2),
         WasmClosure::new(0, 2),
         None,
+        NO_DEADLINE,
     );
 
     let pb_callback = pb::Callback::from(&callback);
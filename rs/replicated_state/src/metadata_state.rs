@@ -8,7 +8,7 @@ use ic_btc_types::Network as BitcoinNetwork;
 use ic_btc_types_internal::BlockBlob;
 use ic_certification_version::{CertificationVersion, CURRENT_CERTIFICATION_VERSION};
 use ic_constants::MAX_INGRESS_TTL;
-use ic_ic00_types::EcdsaKeyId;
+use ic_ic00_types::{EcdsaKeyId, SchnorrKeyId, VetKdKeyId};
 use ic_protobuf::{
     proxy::{try_from_option_field, ProxyDecodeError},
     registry::subnet::v1 as pb_subnet,
@@ -332,6 +332,11 @@ pub struct SubnetTopology {
     /// a backup. An additional NNS proposal will be needed to allow the subnet
     /// holding the key as backup to actually produce signatures.
     pub ecdsa_keys_held: BTreeSet<EcdsaKeyId>,
+    /// Schnorr keys held by this subnet. Unlike ECDSA keys, a subnet holding a
+    /// Schnorr key is always enabled to sign with it.
+    pub schnorr_keys_held: BTreeSet<SchnorrKeyId>,
+    /// vetKD keys held by this subnet.
+    pub vetkd_keys_held: BTreeSet<VetKdKeyId>,
 }
 
 impl From<&SubnetTopology> for pb_metadata::SubnetTopology {
@@ -349,6 +354,8 @@ impl From<&SubnetTopology> for pb_metadata::SubnetTopology {
             subnet_type: i32::from(item.subnet_type),
             subnet_features: Some(pb_subnet::SubnetFeatures::from(item.subnet_features)),
             ecdsa_keys_held: item.ecdsa_keys_held.iter().map(|k| k.into()).collect(),
+            schnorr_keys_held: item.schnorr_keys_held.iter().map(|k| k.into()).collect(),
+            vetkd_keys_held: item.vetkd_keys_held.iter().map(|k| k.into()).collect(),
         }
     }
 }
@@ -372,6 +379,16 @@ impl TryFrom<pb_metadata::SubnetTopology> for SubnetTopology {
             ecdsa_keys_held.insert(EcdsaKeyId::try_from(key)?);
         }
 
+        let mut schnorr_keys_held = BTreeSet::new();
+        for key in item.schnorr_keys_held {
+            schnorr_keys_held.insert(SchnorrKeyId::try_from(key)?);
+        }
+
+        let mut vetkd_keys_held = BTreeSet::new();
+        for key in item.vetkd_keys_held {
+            vetkd_keys_held.insert(VetKdKeyId::try_from(key)?);
+        }
+
         Ok(Self {
             public_key: item.public_key,
             nodes,
@@ -384,6 +401,8 @@ impl TryFrom<pb_metadata::SubnetTopology> for SubnetTopology {
                 .map(SubnetFeatures::from)
                 .unwrap_or_default(),
             ecdsa_keys_held,
+            schnorr_keys_held,
+            vetkd_keys_held,
         })
     }
 }
@@ -418,6 +437,7 @@ pub struct SubnetMetrics {
     pub consumed_cycles_by_deleted_canisters: NominalCycles,
     pub consumed_cycles_http_outcalls: NominalCycles,
     pub consumed_cycles_ecdsa_outcalls: NominalCycles,
+    pub consumed_cycles_schnorr_outcalls: NominalCycles,
     pub ecdsa_signature_agreements: u64,
 }
 
@@ -429,6 +449,7 @@ impl From<&SubnetMetrics> for pb_metadata::SubnetMetrics {
             ),
             consumed_cycles_http_outcalls: Some((&item.consumed_cycles_http_outcalls).into()),
             consumed_cycles_ecdsa_outcalls: Some((&item.consumed_cycles_ecdsa_outcalls).into()),
+            consumed_cycles_schnorr_outcalls: Some((&item.consumed_cycles_schnorr_outcalls).into()),
             ecdsa_signature_agreements: Some(item.ecdsa_signature_agreements),
         }
     }
@@ -452,6 +473,11 @@ impl TryFrom<pb_metadata::SubnetMetrics> for SubnetMetrics {
                 "SubnetMetrics::consumed_cycles_ecdsa_outcalls",
             )
             .unwrap_or_else(|_| NominalCycles::from(0_u128)),
+            consumed_cycles_schnorr_outcalls: try_from_option_field(
+                item.consumed_cycles_schnorr_outcalls,
+                "SubnetMetrics::consumed_cycles_schnorr_outcalls",
+            )
+            .unwrap_or_else(|_| NominalCycles::from(0_u128)),
             ecdsa_signature_agreements: item.ecdsa_signature_agreements.unwrap_or_default(),
         })
     }
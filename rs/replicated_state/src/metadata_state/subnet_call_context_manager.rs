@@ -1,6 +1,6 @@
 use ic_btc_types_internal::{CanisterGetSuccessorsRequestInitial, CanisterSendTransactionRequest};
 use ic_error_types::{ErrorCode, UserError};
-use ic_ic00_types::EcdsaKeyId;
+use ic_ic00_types::{EcdsaKeyId, SchnorrKeyId, VetKdKeyId};
 use ic_logger::{info, ReplicaLogger};
 use ic_protobuf::{
     proxy::{try_from_option_field, ProxyDecodeError},
@@ -20,6 +20,8 @@ use std::{
 pub enum SubnetCallContext {
     SetupInitialDKG(SetupInitialDkgContext),
     SignWithEcsda(SignWithEcdsaContext),
+    SignWithSchnorr(SignWithSchnorrContext),
+    VetKd(VetKdContext),
     CanisterHttpRequest(CanisterHttpRequestContext),
     EcdsaDealings(EcdsaDealingsContext),
     BitcoinGetSuccessors(BitcoinGetSuccessorsContext),
@@ -31,6 +33,8 @@ impl SubnetCallContext {
         match &self {
             SubnetCallContext::SetupInitialDKG(context) => &context.request,
             SubnetCallContext::SignWithEcsda(context) => &context.request,
+            SubnetCallContext::SignWithSchnorr(context) => &context.request,
+            SubnetCallContext::VetKd(context) => &context.request,
             SubnetCallContext::CanisterHttpRequest(context) => &context.request,
             SubnetCallContext::EcdsaDealings(context) => &context.request,
             SubnetCallContext::BitcoinGetSuccessors(context) => &context.request,
@@ -42,6 +46,8 @@ impl SubnetCallContext {
         match &self {
             SubnetCallContext::SetupInitialDKG(context) => context.time,
             SubnetCallContext::SignWithEcsda(context) => context.batch_time,
+            SubnetCallContext::SignWithSchnorr(context) => context.batch_time,
+            SubnetCallContext::VetKd(context) => context.batch_time,
             SubnetCallContext::CanisterHttpRequest(context) => context.time,
             SubnetCallContext::EcdsaDealings(context) => context.time,
             SubnetCallContext::BitcoinGetSuccessors(context) => context.time,
@@ -60,6 +66,8 @@ pub struct SubnetCallContextManager {
     pub bitcoin_get_successors_contexts: BTreeMap<CallbackId, BitcoinGetSuccessorsContext>,
     pub bitcoin_send_transaction_internal_contexts:
         BTreeMap<CallbackId, BitcoinSendTransactionInternalContext>,
+    pub sign_with_schnorr_contexts: BTreeMap<CallbackId, SignWithSchnorrContext>,
+    pub vetkd_contexts: BTreeMap<CallbackId, VetKdContext>,
 }
 
 impl SubnetCallContextManager {
@@ -89,6 +97,44 @@ impl SubnetCallContextManager {
         }
     }
 
+    pub fn push_sign_with_schnorr_request(
+        &mut self,
+        context: SignWithSchnorrContext,
+        max_queue_size: u32,
+    ) -> Result<(), UserError> {
+        if self.sign_with_schnorr_contexts.len() >= max_queue_size as usize {
+            Err(UserError::new(
+                ErrorCode::CanisterRejectedMessage,
+                "sign_with_schnorr request could not be handled, the Schnorr signature queue is full."
+                    .to_string(),
+            ))
+        } else {
+            let callback_id = CallbackId::new(self.next_callback_id);
+            self.next_callback_id += 1;
+            self.sign_with_schnorr_contexts.insert(callback_id, context);
+            Ok(())
+        }
+    }
+
+    pub fn push_vetkd_request(
+        &mut self,
+        context: VetKdContext,
+        max_queue_size: u32,
+    ) -> Result<(), UserError> {
+        if self.vetkd_contexts.len() >= max_queue_size as usize {
+            Err(UserError::new(
+                ErrorCode::CanisterRejectedMessage,
+                "vetkd_encrypted_key request could not be handled, the vetKD queue is full."
+                    .to_string(),
+            ))
+        } else {
+            let callback_id = CallbackId::new(self.next_callback_id);
+            self.next_callback_id += 1;
+            self.vetkd_contexts.insert(callback_id, context);
+            Ok(())
+        }
+    }
+
     pub fn push_http_request(&mut self, context: CanisterHttpRequestContext) {
         let callback_id = CallbackId::new(self.next_callback_id);
         self.next_callback_id += 1;
@@ -156,6 +202,30 @@ impl SubnetCallContextManager {
                         SubnetCallContext::SignWithEcsda(context)
                     })
             })
+            .or_else(|| {
+                self.sign_with_schnorr_contexts
+                    .remove(&callback_id)
+                    .map(|context| {
+                        info!(
+                            logger,
+                            "Received the response for SignWithSchnorr request with id {:?} from {:?}",
+                            context.pseudo_random_id,
+                            context.request.sender
+                        );
+                        SubnetCallContext::SignWithSchnorr(context)
+                    })
+            })
+            .or_else(|| {
+                self.vetkd_contexts.remove(&callback_id).map(|context| {
+                    info!(
+                        logger,
+                        "Received the response for VetKdEncryptedKey request with id {:?} from {:?}",
+                        context.pseudo_random_id,
+                        context.request.sender
+                    );
+                    SubnetCallContext::VetKd(context)
+                })
+            })
             .or_else(|| {
                 self.ecdsa_dealings_contexts
                     .remove(&callback_id)
@@ -275,6 +345,24 @@ impl From<&SubnetCallContextManager> for pb_metadata::SubnetCallContextManager {
                     }
                 })
                 .collect(),
+            sign_with_schnorr_contexts: item
+                .sign_with_schnorr_contexts
+                .iter()
+                .map(
+                    |(callback_id, context)| pb_metadata::SignWithSchnorrContextTree {
+                        callback_id: callback_id.get(),
+                        context: Some(context.into()),
+                    },
+                )
+                .collect(),
+            vetkd_contexts: item
+                .vetkd_contexts
+                .iter()
+                .map(|(callback_id, context)| pb_metadata::VetKdContextTree {
+                    callback_id: callback_id.get(),
+                    context: Some(context.into()),
+                })
+                .collect(),
         }
     }
 }
@@ -338,6 +426,20 @@ impl TryFrom<(Time, pb_metadata::SubnetCallContextManager)> for SubnetCallContex
                 .insert(CallbackId::new(entry.callback_id), context);
         }
 
+        let mut sign_with_schnorr_contexts = BTreeMap::<CallbackId, SignWithSchnorrContext>::new();
+        for entry in item.sign_with_schnorr_contexts {
+            let context: SignWithSchnorrContext =
+                try_from_option_field(entry.context, "SystemMetadata::SignWithSchnorrContext")?;
+            sign_with_schnorr_contexts.insert(CallbackId::new(entry.callback_id), context);
+        }
+
+        let mut vetkd_contexts = BTreeMap::<CallbackId, VetKdContext>::new();
+        for entry in item.vetkd_contexts {
+            let context: VetKdContext =
+                try_from_option_field(entry.context, "SystemMetadata::VetKdContext")?;
+            vetkd_contexts.insert(CallbackId::new(entry.callback_id), context);
+        }
+
         Ok(Self {
             next_callback_id: item.next_callback_id,
             setup_initial_dkg_contexts,
@@ -346,6 +448,8 @@ impl TryFrom<(Time, pb_metadata::SubnetCallContextManager)> for SubnetCallContex
             ecdsa_dealings_contexts,
             bitcoin_get_successors_contexts,
             bitcoin_send_transaction_internal_contexts,
+            sign_with_schnorr_contexts,
+            vetkd_contexts,
         })
     }
 }
@@ -459,6 +563,94 @@ impl TryFrom<pb_metadata::SignWithEcdsaContext> for SignWithEcdsaContext {
     }
 }
 
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SignWithSchnorrContext {
+    pub request: Request,
+    pub key_id: SchnorrKeyId,
+    pub message: Vec<u8>,
+    pub derivation_path: Vec<Vec<u8>>,
+    pub pseudo_random_id: [u8; 32],
+    pub batch_time: Time,
+}
+
+impl From<&SignWithSchnorrContext> for pb_metadata::SignWithSchnorrContext {
+    fn from(context: &SignWithSchnorrContext) -> Self {
+        pb_metadata::SignWithSchnorrContext {
+            request: Some((&context.request).into()),
+            key_id: Some((&context.key_id).into()),
+            message: context.message.clone(),
+            derivation_path_vec: context.derivation_path.clone(),
+            pseudo_random_id: context.pseudo_random_id.to_vec(),
+            batch_time: context.batch_time.as_nanos_since_unix_epoch(),
+        }
+    }
+}
+
+impl TryFrom<pb_metadata::SignWithSchnorrContext> for SignWithSchnorrContext {
+    type Error = ProxyDecodeError;
+    fn try_from(context: pb_metadata::SignWithSchnorrContext) -> Result<Self, Self::Error> {
+        let request: Request =
+            try_from_option_field(context.request, "SignWithSchnorrContext::request")?;
+        let key_id = try_from_option_field(context.key_id, "SignWithSchnorrContext::key_id")?;
+        Ok(SignWithSchnorrContext {
+            request,
+            key_id,
+            message: context.message,
+            derivation_path: context.derivation_path_vec,
+            pseudo_random_id: context
+                .pseudo_random_id
+                .try_into()
+                .map_err(|_| Self::Error::Other("pseudo_random_id is not 32 bytes.".to_string()))?,
+            batch_time: Time::from_nanos_since_unix_epoch(context.batch_time),
+        })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VetKdContext {
+    pub request: Request,
+    pub key_id: VetKdKeyId,
+    pub derivation_path: Vec<Vec<u8>>,
+    pub input: Vec<u8>,
+    pub transport_public_key: Vec<u8>,
+    pub pseudo_random_id: [u8; 32],
+    pub batch_time: Time,
+}
+
+impl From<&VetKdContext> for pb_metadata::VetKdContext {
+    fn from(context: &VetKdContext) -> Self {
+        pb_metadata::VetKdContext {
+            request: Some((&context.request).into()),
+            key_id: Some((&context.key_id).into()),
+            derivation_path_vec: context.derivation_path.clone(),
+            input: context.input.clone(),
+            transport_public_key: context.transport_public_key.clone(),
+            pseudo_random_id: context.pseudo_random_id.to_vec(),
+            batch_time: context.batch_time.as_nanos_since_unix_epoch(),
+        }
+    }
+}
+
+impl TryFrom<pb_metadata::VetKdContext> for VetKdContext {
+    type Error = ProxyDecodeError;
+    fn try_from(context: pb_metadata::VetKdContext) -> Result<Self, Self::Error> {
+        let request: Request = try_from_option_field(context.request, "VetKdContext::request")?;
+        let key_id = try_from_option_field(context.key_id, "VetKdContext::key_id")?;
+        Ok(VetKdContext {
+            request,
+            key_id,
+            derivation_path: context.derivation_path_vec,
+            input: context.input,
+            transport_public_key: context.transport_public_key,
+            pseudo_random_id: context
+                .pseudo_random_id
+                .try_into()
+                .map_err(|_| Self::Error::Other("pseudo_random_id is not 32 bytes.".to_string()))?,
+            batch_time: Time::from_nanos_since_unix_epoch(context.batch_time),
+        })
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct EcdsaDealingsContext {
     pub request: Request,
@@ -8,8 +8,8 @@ use ic_test_utilities::{
     mock_time,
     types::{
         ids::{
-            canister_test_id, message_test_id, subnet_test_id, user_test_id, SUBNET_0, SUBNET_1,
-            SUBNET_2,
+            canister_test_id, message_test_id, node_test_id, subnet_test_id, user_test_id,
+            SUBNET_0, SUBNET_1, SUBNET_2,
         },
         messages::{RequestBuilder, ResponseBuilder},
         xnet::{StreamHeaderBuilder, StreamSliceBuilder},
@@ -17,7 +17,7 @@ use ic_test_utilities::{
 };
 use ic_types::canister_http::Transform;
 use ic_types::{
-    canister_http::{CanisterHttpMethod, CanisterHttpRequestContext},
+    canister_http::{CanisterHttpMethod, CanisterHttpRequestContext, Replication},
     ingress::WasmResult,
     messages::{CallbackId, Payload},
 };
@@ -471,6 +471,7 @@ fn subnet_call_contexts_deserialization() {
         http_method: CanisterHttpMethod::GET,
         transform: Some(transform.clone()),
         time: mock_time(),
+        replication: Replication::NonReplicated(node_test_id(1)),
     };
     system_call_context_manager.push_http_request(canister_http_request);
 
@@ -496,6 +497,10 @@ fn subnet_call_contexts_deserialization() {
         CanisterHttpMethod::GET
     );
     assert_eq!(deserialized_http_request_context.transform, Some(transform));
+    assert_eq!(
+        deserialized_http_request_context.replication,
+        Replication::NonReplicated(node_test_id(1))
+    );
 }
 
 #[test]
@@ -526,6 +531,8 @@ fn network_topology_bitcoin_testnet_subnets() {
                 subnet_type: SubnetType::Application,
                 subnet_features: SubnetFeatures::from_str("bitcoin_testnet").unwrap(),
                 ecdsa_keys_held: BTreeSet::new(),
+                schnorr_keys_held: BTreeSet::new(),
+                vetkd_keys_held: BTreeSet::new(),
             },
 
             // A subnet with the bitcoin testnet feature paused.
@@ -535,6 +542,8 @@ fn network_topology_bitcoin_testnet_subnets() {
                 subnet_type: SubnetType::Application,
                 subnet_features: SubnetFeatures::from_str("bitcoin_testnet_paused").unwrap(),
                 ecdsa_keys_held: BTreeSet::new(),
+                schnorr_keys_held: BTreeSet::new(),
+                vetkd_keys_held: BTreeSet::new(),
             },
 
             // A subnet without the bitcoin feature enabled.
@@ -544,6 +553,8 @@ fn network_topology_bitcoin_testnet_subnets() {
                 subnet_type: SubnetType::Application,
                 subnet_features: SubnetFeatures::default(),
                 ecdsa_keys_held: BTreeSet::new(),
+                schnorr_keys_held: BTreeSet::new(),
+                vetkd_keys_held: BTreeSet::new(),
             }
         ],
         routing_table: Arc::new(RoutingTable::default()),
@@ -361,6 +361,22 @@ impl PageMap {
         })
     }
 
+    /// Creates a page map with the same contents as `other` that is not backed
+    /// by any checkpoint file: all pages are copied into the page delta.
+    ///
+    /// This is used when the contents of a page map are installed in place of
+    /// a page map backed by a different file (e.g. when loading a canister
+    /// snapshot), so that the next flush rewrites the file from scratch.
+    pub fn new_detached_copy(
+        other: &PageMap,
+        fd_factory: Arc<dyn PageAllocatorFileDescriptor>,
+    ) -> Self {
+        let mut page_map = Self::new(fd_factory);
+        let pages: Vec<_> = other.host_pages_iter().collect();
+        page_map.update(&pages);
+        page_map
+    }
+
     /// Returns a serialization-friendly representation of the page-map.
     pub fn serialize(&self) -> PageMapSerialization {
         PageMapSerialization {
@@ -440,6 +456,35 @@ impl PageMap {
         self.persist_to_file(&self.unflushed_delta, dst)
     }
 
+    /// Persists all pages of this page map, including the ones backed by the
+    /// checkpoint, to the specified destination. The destination must not
+    /// exist yet.
+    pub fn persist_all(&self, dst: &Path) -> Result<(), PersistenceError> {
+        let mut file = OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(dst)
+            .map_err(|err| PersistenceError::FileSystemError {
+                path: dst.display().to_string(),
+                context: "Failed to create file".to_string(),
+                internal_error: err.to_string(),
+            })?;
+        let num_host_pages = self.num_host_pages() as u64;
+        let mut start = 0;
+        while start < num_host_pages {
+            let end = num_host_pages.min(start + MAXIMUM_GAP);
+            let mut buffer = WriteBuffer {
+                content: (start..end)
+                    .map(|i| &self.get_page(PageIndex::from(i))[..])
+                    .collect(),
+                start_index: PageIndex::from(start),
+            };
+            buffer.apply_to_file(&mut file, dst)?;
+            start = end;
+        }
+        Ok(())
+    }
+
     /// Returns the iterator over host pages managed by this `PageMap`.
     pub fn host_pages_iter(&self) -> impl Iterator<Item = (PageIndex, &PageBytes)> + '_ {
         (0..self.num_host_pages()).map(move |i| {
@@ -159,6 +159,48 @@ fn persisted_map_is_equivalent_to_the_original() {
     assert_eq!(persisted_map, original_map);
 }
 
+#[test]
+fn persist_all_writes_checkpoint_and_delta_pages() {
+    let tmp = tempfile::Builder::new()
+        .prefix("checkpoints")
+        .tempdir()
+        .unwrap();
+    let heap_file = tmp.path().join("heap");
+    let copy_file = tmp.path().join("copy");
+
+    let base_page = [42u8; PAGE_SIZE];
+    let mut base_map = PageMap::new_for_testing();
+    base_map.update(&[
+        (PageIndex::new(0), &base_page),
+        (PageIndex::new(300), &base_page),
+    ]);
+    base_map.persist_delta(&heap_file).unwrap();
+
+    let mut original_map = PageMap::open(
+        &heap_file,
+        Height::new(0),
+        Arc::new(TestPageAllocatorFileDescriptorImpl::new()),
+    )
+    .unwrap();
+    let page_1 = [1u8; PAGE_SIZE];
+    let page_500 = [5u8; PAGE_SIZE];
+    original_map.update(&[
+        (PageIndex::new(1), &page_1),
+        (PageIndex::new(500), &page_500),
+    ]);
+
+    original_map.persist_all(&copy_file).unwrap();
+    let persisted_map = PageMap::open(
+        &copy_file,
+        Height::new(0),
+        Arc::new(TestPageAllocatorFileDescriptorImpl::new()),
+    )
+    .unwrap();
+
+    assert_eq!(persisted_map, original_map);
+    assert!(original_map.persist_all(&copy_file).is_err());
+}
+
 #[test]
 fn can_persist_and_load_an_empty_page_map() {
     let tmp = tempfile::Builder::new()
@@ -7,7 +7,7 @@ use crate::{
     canister_state::queues::CanisterQueuesLoopDetector,
     canister_state::system_state::{push_input, CanisterOutputQueuesIterator},
     metadata_state::StreamMap,
-    CanisterQueues,
+    CanisterQueues, CanisterSnapshots,
 };
 use ic_base_types::PrincipalId;
 use ic_btc_types_internal::{BitcoinAdapterRequestWrapper, BitcoinAdapterResponse};
@@ -21,7 +21,7 @@ use ic_types::{
     ingress::IngressStatus,
     messages::{CallbackId, MessageId, RequestOrResponse, Response},
     xnet::QueueId,
-    CanisterId, MemoryAllocation, NumBytes, SubnetId, Time,
+    CanisterId, CoarseTime, MemoryAllocation, NumBytes, SubnetId, Time,
 };
 use rand::{Rng, SeedableRng};
 use rand_chacha::ChaChaRng;
@@ -33,6 +33,11 @@ use std::sync::Arc;
 /// routing.
 pub const MR_SYNTHETIC_REJECT_MESSAGE_MAX_LEN: usize = 255;
 
+/// Maximum total byte size of best-effort messages in canister input queues,
+/// across all canisters. Above this limit, the largest best-effort messages
+/// are dropped until memory usage falls back under the limit.
+pub const BEST_EFFORT_MESSAGE_MEMORY_CAPACITY: usize = 1 << 30;
+
 /// Input queue type: local or remote subnet.
 #[derive(Clone, Copy, Eq, Debug, PartialEq)]
 pub enum InputQueueType {
@@ -329,6 +334,9 @@ pub struct ReplicatedState {
     pub consensus_queue: Vec<Response>,
 
     bitcoin: BitcoinState,
+
+    /// Snapshots of canisters on this subnet, taken via `take_canister_snapshot`.
+    pub canister_snapshots: CanisterSnapshots,
 }
 
 impl ReplicatedState {
@@ -340,6 +348,7 @@ impl ReplicatedState {
             subnet_queues: CanisterQueues::default(),
             consensus_queue: Vec::new(),
             bitcoin: BitcoinState::default(),
+            canister_snapshots: CanisterSnapshots::default(),
         }
     }
 
@@ -349,6 +358,7 @@ impl ReplicatedState {
         metadata: SystemMetadata,
         subnet_queues: CanisterQueues,
         bitcoin: BitcoinState,
+        canister_snapshots: CanisterSnapshots,
     ) -> Self {
         let mut res = Self {
             canister_states,
@@ -356,6 +366,7 @@ impl ReplicatedState {
             subnet_queues,
             consensus_queue: Vec::new(),
             bitcoin,
+            canister_snapshots,
         };
         res.update_stream_responses_size_bytes();
         res
@@ -402,6 +413,15 @@ impl ReplicatedState {
         self.canister_states = canisters;
     }
 
+    /// Deletes all snapshots of the given canister and resets the memory
+    /// usage the canister is charged for on their account.
+    pub fn delete_canister_snapshots(&mut self, canister_id: CanisterId) {
+        self.canister_snapshots.delete_snapshots(canister_id);
+        if let Some(canister) = self.canister_states.get_mut(&canister_id) {
+            canister.system_state.snapshots_memory_usage = NumBytes::from(0);
+        }
+    }
+
     /// Returns an iterator over canister states, ordered by canister ID.
     pub fn canisters_iter(
         &self,
@@ -815,6 +835,85 @@ impl ReplicatedState {
 
         timed_out_requests_count
     }
+
+    /// Times out expired best-effort callbacks of all canisters, enqueuing a
+    /// `SYS_UNKNOWN` reject response for each. Returns the number of callbacks
+    /// that were timed out.
+    ///
+    /// See `SystemState::time_out_callbacks` for further details.
+    #[allow(clippy::needless_collect)]
+    pub fn time_out_callbacks(&mut self, current_time: Time) -> u64 {
+        let current_time = CoarseTime::floor(current_time);
+
+        // Same as for `time_out_requests()`, only remove-call-replace the (usually
+        // much fewer) canisters with expired callbacks.
+        let canister_ids_with_expired_callbacks = self
+            .canister_states
+            .iter()
+            .filter(|(_, canister_state)| {
+                canister_state
+                    .system_state
+                    .has_expired_callbacks(current_time)
+            })
+            .map(|(canister_id, _)| *canister_id)
+            .collect::<Vec<_>>();
+
+        let mut timed_out_callbacks_count = 0;
+        for canister_id in canister_ids_with_expired_callbacks {
+            let mut canister = self.canister_states.remove(&canister_id).unwrap();
+            timed_out_callbacks_count += canister.system_state.time_out_callbacks(
+                current_time,
+                &canister_id,
+                &self.canister_states,
+            );
+            self.canister_states.insert(canister_id, canister);
+        }
+
+        timed_out_callbacks_count
+    }
+
+    /// Drops the largest best-effort messages from canister input queues until
+    /// the total byte size of best-effort input messages across all canisters
+    /// is at most `memory_limit`. Returns the number of dropped messages.
+    ///
+    /// See `CanisterQueues::shed_largest_best_effort_message` for further
+    /// details.
+    pub fn shed_best_effort_messages(&mut self, memory_limit: usize) -> u64 {
+        let mut best_effort_memory_usage: usize = self
+            .canister_states
+            .values()
+            .map(|canister| {
+                canister
+                    .system_state
+                    .queues()
+                    .best_effort_input_messages_size_bytes()
+            })
+            .sum();
+
+        let mut shed_messages_count = 0;
+        while best_effort_memory_usage > memory_limit {
+            // Shed from the canister with the most best-effort message memory.
+            let canister = self
+                .canister_states
+                .values_mut()
+                .max_by_key(|canister| {
+                    canister
+                        .system_state
+                        .queues()
+                        .best_effort_input_messages_size_bytes()
+                })
+                .unwrap();
+            match canister.system_state.shed_largest_best_effort_message() {
+                Some(size_bytes) => {
+                    best_effort_memory_usage -= size_bytes;
+                    shed_messages_count += 1;
+                }
+                None => break,
+            }
+        }
+
+        shed_messages_count
+    }
 }
 
 /// A trait exposing `ReplicatedState` functionality for the exclusive use of
@@ -376,10 +376,12 @@ pub struct TransferFrom {
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct Approve {
-    #[prost(message, optional, tag = "1")]
-    pub allowance: ::core::option::Option<SignedTokens>,
+    #[prost(message, optional, tag = "3")]
+    pub allowance: ::core::option::Option<Tokens>,
     #[prost(message, optional, tag = "2")]
     pub expires_at: ::core::option::Option<TimeStamp>,
+    #[prost(message, optional, tag = "4")]
+    pub expected_allowance: ::core::option::Option<Tokens>,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct Mint {
@@ -0,0 +1,828 @@
+use candid::{CandidType, Decode, Encode, Nat};
+use ic_base_types::CanisterId;
+use ic_icrc1::Account;
+use ic_stable_structures::memory_manager::{MemoryId, MemoryManager, VirtualMemory};
+use ic_stable_structures::{
+    cell::Cell as StableCell, log::Log as StableLog, BoundedStorable, DefaultMemoryImpl,
+    StableBTreeMap, Storable,
+};
+use icp_ledger::{
+    AccountIdentifier, ArchivedBlocksRange, BlockIndex, CandidBlock, CandidOperation,
+    CandidTransaction, GetBlocksArgs, GetBlocksResult, QueryBlocksResponse, TimeStamp,
+    MAX_BLOCKS_PER_REQUEST,
+};
+use num_traits::cast::ToPrimitive;
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::cell::RefCell;
+
+// Maximum number of transactions that can be returned
+// by [get_account_identifier_transactions]
+const MAX_TRANSACTIONS_PER_RESPONSE: u64 = 1000;
+
+// One second in nanosecond
+const SEC_NANOS: f64 = 1_000_000_000_f64;
+const DEFAULT_MAX_WAIT_TIME_NANOS: f64 = 60_f64 * SEC_NANOS;
+
+const LOG_PREFIX: &str = "[ic-icp-index] ";
+
+const STATE_MEMORY_ID: MemoryId = MemoryId::new(0);
+const BLOCK_LOG_INDEX_MEMORY_ID: MemoryId = MemoryId::new(1);
+const BLOCK_LOG_DATA_MEMORY_ID: MemoryId = MemoryId::new(2);
+const ACCOUNT_BLOCK_IDS_MEMORY_ID: MemoryId = MemoryId::new(3);
+const ACCOUNT_DATA_MEMORY_ID: MemoryId = MemoryId::new(4);
+
+type VM = VirtualMemory<DefaultMemoryImpl>;
+type StateCell = StableCell<State, VM>;
+type BlockLog = StableLog<Vec<u8>, VM, VM>;
+type AccountBlockIdsMap = StableBTreeMap<AccountBlockIdsKey, (), VM>;
+type AccountDataMap = StableBTreeMap<AccountIdentifierKey, AccountData, VM>;
+
+/// The part of the index state that is not a block or an account entry.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+struct State {
+    // The id of the Ledger canister to index
+    ledger_id: CanisterId,
+
+    // Whether there is a [heartbeat] running right now
+    is_heartbeat_running: bool,
+
+    // Next time to call [build_index]
+    next_build_index_time: u64,
+}
+
+// NOTE: the default configuration is dysfunctional, but it's convenient to have
+// a Default impl for the initialization of the [STATE] variable below.
+impl Default for State {
+    fn default() -> Self {
+        Self {
+            ledger_id: CanisterId::ic_00(),
+            is_heartbeat_running: false,
+            next_build_index_time: 0,
+        }
+    }
+}
+
+impl Storable for State {
+    fn to_bytes(&self) -> Cow<[u8]> {
+        let mut buf = vec![];
+        ciborium::ser::into_writer(self, &mut buf).expect("failed to encode index state");
+        Cow::Owned(buf)
+    }
+
+    fn from_bytes(bytes: Cow<'_, [u8]>) -> Self {
+        ciborium::de::from_reader(&bytes[..]).expect("failed to decode index state")
+    }
+}
+
+/// The key of the account-to-blocks index.
+///
+/// The key is the account identifier address followed by the big-endian
+/// bitwise negation of the block index, so that iterating over the keys of an
+/// account yields its blocks from the most recent to the oldest.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+struct AccountBlockIdsKey([u8; 40]);
+
+impl AccountBlockIdsKey {
+    fn new(address: [u8; 32], block_index: BlockIndex) -> Self {
+        let mut key = [0u8; 40];
+        key[..32].copy_from_slice(&address);
+        key[32..].copy_from_slice(&(!block_index).to_be_bytes());
+        Self(key)
+    }
+
+    fn block_index(&self) -> BlockIndex {
+        let mut buf = [0u8; 8];
+        buf.copy_from_slice(&self.0[32..]);
+        !u64::from_be_bytes(buf)
+    }
+}
+
+impl Storable for AccountBlockIdsKey {
+    fn to_bytes(&self) -> Cow<[u8]> {
+        Cow::Borrowed(&self.0)
+    }
+
+    fn from_bytes(bytes: Cow<[u8]>) -> Self {
+        Self(
+            bytes[..]
+                .try_into()
+                .expect("bug: account block ids key must be 40 bytes long"),
+        )
+    }
+}
+
+impl BoundedStorable for AccountBlockIdsKey {
+    const MAX_SIZE: u32 = 40;
+    const IS_FIXED_SIZE: bool = true;
+}
+
+/// The address of an account identifier used as a key in stable maps.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+struct AccountIdentifierKey([u8; 32]);
+
+impl Storable for AccountIdentifierKey {
+    fn to_bytes(&self) -> Cow<[u8]> {
+        Cow::Borrowed(&self.0)
+    }
+
+    fn from_bytes(bytes: Cow<[u8]>) -> Self {
+        Self(
+            bytes[..]
+                .try_into()
+                .expect("bug: account identifier key must be 32 bytes long"),
+        )
+    }
+}
+
+impl BoundedStorable for AccountIdentifierKey {
+    const MAX_SIZE: u32 = 32;
+    const IS_FIXED_SIZE: bool = true;
+}
+
+/// Per-account data maintained by the index.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+struct AccountData {
+    // The balance of the account in e8s
+    balance: u64,
+    // The index of the first block that touched the account
+    oldest_block_index: BlockIndex,
+}
+
+impl Storable for AccountData {
+    fn to_bytes(&self) -> Cow<[u8]> {
+        let mut buf = Vec::with_capacity(16);
+        buf.extend_from_slice(&self.balance.to_be_bytes());
+        buf.extend_from_slice(&self.oldest_block_index.to_be_bytes());
+        Cow::Owned(buf)
+    }
+
+    fn from_bytes(bytes: Cow<[u8]>) -> Self {
+        let mut balance = [0u8; 8];
+        let mut oldest_block_index = [0u8; 8];
+        balance.copy_from_slice(&bytes[..8]);
+        oldest_block_index.copy_from_slice(&bytes[8..16]);
+        Self {
+            balance: u64::from_be_bytes(balance),
+            oldest_block_index: u64::from_be_bytes(oldest_block_index),
+        }
+    }
+}
+
+impl BoundedStorable for AccountData {
+    const MAX_SIZE: u32 = 16;
+    const IS_FIXED_SIZE: bool = true;
+}
+
+thread_local! {
+    static MEMORY_MANAGER: RefCell<MemoryManager<DefaultMemoryImpl>> =
+        RefCell::new(MemoryManager::init(DefaultMemoryImpl::default()));
+
+    static STATE: RefCell<StateCell> = with_memory_manager(|memory_manager| {
+        RefCell::new(StateCell::init(memory_manager.get(STATE_MEMORY_ID), State::default())
+            .expect("failed to initialize stable cell"))
+    });
+
+    /// Append-only list of candid-encoded blocks stored in stable memory.
+    static BLOCKS: RefCell<BlockLog> = with_memory_manager(|memory_manager| {
+        RefCell::new(BlockLog::init(memory_manager.get(BLOCK_LOG_INDEX_MEMORY_ID), memory_manager.get(BLOCK_LOG_DATA_MEMORY_ID))
+            .expect("failed to initialize stable log"))
+    });
+
+    /// The set of (account identifier, block index) pairs.
+    static ACCOUNT_BLOCK_IDS: RefCell<AccountBlockIdsMap> = with_memory_manager(|memory_manager| {
+        RefCell::new(AccountBlockIdsMap::init(memory_manager.get(ACCOUNT_BLOCK_IDS_MEMORY_ID)))
+    });
+
+    /// The balance and the oldest block index of each account identifier.
+    static ACCOUNT_DATA: RefCell<AccountDataMap> = with_memory_manager(|memory_manager| {
+        RefCell::new(AccountDataMap::init(memory_manager.get(ACCOUNT_DATA_MEMORY_ID)))
+    });
+}
+
+/// A helper function to access the memory manager.
+fn with_memory_manager<R>(f: impl FnOnce(&MemoryManager<DefaultMemoryImpl>) -> R) -> R {
+    MEMORY_MANAGER.with(|cell| f(&cell.borrow()))
+}
+
+/// A helper function to access the state.
+fn with_state<R>(f: impl FnOnce(&State) -> R) -> R {
+    STATE.with(|cell| f(cell.borrow().get()))
+}
+
+/// A helper function to change the state.
+fn change_state(f: impl FnOnce(&mut State)) {
+    STATE.with(|cell| {
+        let mut state = cell.borrow().get().clone();
+        f(&mut state);
+        cell.borrow_mut()
+            .set(state)
+            .expect("failed to set index state");
+    });
+}
+
+/// A helper function to access the block list.
+fn with_blocks<R>(f: impl FnOnce(&BlockLog) -> R) -> R {
+    BLOCKS.with(|cell| f(&cell.borrow()))
+}
+
+/// A helper function to access the account-to-blocks index.
+fn with_account_block_ids<R>(f: impl FnOnce(&mut AccountBlockIdsMap) -> R) -> R {
+    ACCOUNT_BLOCK_IDS.with(|cell| f(&mut cell.borrow_mut()))
+}
+
+/// A helper function to access the account data.
+fn with_account_data<R>(f: impl FnOnce(&mut AccountDataMap) -> R) -> R {
+    ACCOUNT_DATA.with(|cell| f(&mut cell.borrow_mut()))
+}
+
+pub fn ledger_id() -> CanisterId {
+    with_state(|state| state.ledger_id)
+}
+
+struct HeartbeatGuard;
+
+impl HeartbeatGuard {
+    fn new() -> Option<HeartbeatGuard> {
+        if with_state(|state| state.is_heartbeat_running) {
+            return None;
+        }
+        change_state(|state| state.is_heartbeat_running = true);
+        Some(HeartbeatGuard {})
+    }
+}
+
+impl Drop for HeartbeatGuard {
+    fn drop(&mut self) {
+        change_state(|state| state.is_heartbeat_running = false)
+    }
+}
+
+#[derive(CandidType, Clone, Debug, Deserialize)]
+pub struct InitArgs {
+    // The Ledger canister id of the Ledger to index.
+    pub ledger_id: CanisterId,
+}
+
+pub fn init(init_args: InitArgs) {
+    change_state(|state| {
+        *state = State {
+            ledger_id: init_args.ledger_id,
+            ..State::default()
+        }
+    });
+}
+
+#[derive(CandidType, Debug, Deserialize, PartialEq, Eq)]
+pub struct GetAccountIdentifierTransactionsArgs {
+    // The hex-encoded account identifier.
+    pub account_identifier: String,
+    // The block index of the last transaction seen by the client.
+    // If None then the results will start from the most recent
+    // block index.
+    pub start: Option<BlockIndex>,
+    // Maximum number of transactions to fetch.
+    pub max_results: u64,
+}
+
+#[derive(CandidType, Debug, Deserialize, PartialEq, Eq)]
+pub struct GetAccountTransactionsArgs {
+    pub account: Account,
+    // The block index of the last transaction seen by the client.
+    // If None then the results will start from the most recent
+    // block index.
+    pub start: Option<Nat>,
+    // Maximum number of transactions to fetch.
+    pub max_results: Nat,
+}
+
+#[derive(CandidType, Debug, Deserialize, PartialEq, Eq)]
+pub struct TransactionWithId {
+    pub id: BlockIndex,
+    pub transaction: CandidTransaction,
+    pub timestamp: TimeStamp,
+}
+
+#[derive(CandidType, Debug, Deserialize, PartialEq, Eq)]
+pub struct GetAccountIdentifierTransactionsResponse {
+    // The balance of the account in e8s
+    pub balance: u64,
+    pub transactions: Vec<TransactionWithId>,
+    // The block index of the oldest transaction the account has
+    pub oldest_tx_id: Option<BlockIndex>,
+}
+
+#[derive(CandidType, Debug, Deserialize, PartialEq, Eq)]
+pub struct GetAccountIdentifierTransactionsError {
+    pub message: String,
+}
+
+pub type GetAccountIdentifierTransactionsResult =
+    Result<GetAccountIdentifierTransactionsResponse, GetAccountIdentifierTransactionsError>;
+
+#[derive(CandidType, Debug, Deserialize, PartialEq, Eq)]
+pub struct Status {
+    pub num_blocks_synced: u64,
+}
+
+pub fn status() -> Status {
+    Status {
+        num_blocks_synced: with_blocks(|blocks| blocks.len()),
+    }
+}
+
+pub async fn heartbeat() {
+    let _guard = match HeartbeatGuard::new() {
+        Some(guard) => guard,
+        None => return,
+    };
+
+    if ic_cdk::api::time() < with_state(|state| state.next_build_index_time) {
+        return;
+    }
+
+    if let Err(err) = build_index().await {
+        ic_cdk::eprintln!("{}Failed to fetch blocks: {}", LOG_PREFIX, err);
+    }
+}
+
+async fn query_blocks_from_ledger(
+    start: BlockIndex,
+    length: usize,
+) -> Result<QueryBlocksResponse, String> {
+    let (res,): (QueryBlocksResponse,) = ic_cdk::call(
+        ledger_id().get().0,
+        "query_blocks",
+        (GetBlocksArgs { start, length },),
+    )
+    .await
+    .map_err(|(code, str)| format!("code: {:#?} message: {}", code, str))?;
+    Ok(res)
+}
+
+async fn get_blocks_from_archive(
+    archived: &ArchivedBlocksRange,
+    start: BlockIndex,
+    length: usize,
+) -> Result<Vec<CandidBlock>, String> {
+    let (res,): (GetBlocksResult,) = ic_cdk::call(
+        archived.callback.canister_id.get().0,
+        &archived.callback.method,
+        (GetBlocksArgs { start, length },),
+    )
+    .await
+    .map_err(|(code, str)| format!("code: {:#?} message: {}", code, str))?;
+    res.map(|range| range.blocks).map_err(|e| {
+        format!(
+            "archive {} failed to return blocks: {:?}",
+            archived.callback.canister_id, e
+        )
+    })
+}
+
+async fn build_index() -> Result<(), String> {
+    let next_block_index = with_blocks(|blocks| blocks.len());
+    let res = query_blocks_from_ledger(next_block_index, MAX_BLOCKS_PER_REQUEST).await?;
+    let mut num_indexed: usize = 0;
+    for archived in res.archived_blocks {
+        // The archive node limits the number of blocks returned by a
+        // single get_blocks call.
+        let next_block_index = with_blocks(|blocks| blocks.len());
+        if archived.start != next_block_index {
+            return Err(format!(
+                "the archived range starts at {} but the next block to index is {}",
+                archived.start, next_block_index
+            ));
+        }
+        let end = archived.start + archived.length;
+        let mut start = archived.start;
+        while start < end {
+            let blocks = get_blocks_from_archive(&archived, start, (end - start) as usize).await?;
+            if blocks.is_empty() {
+                return Err(format!(
+                    "archive {} returned no blocks for range [{}, {})",
+                    archived.callback.canister_id, start, end
+                ));
+            }
+            start += blocks.len() as u64;
+            num_indexed += append_blocks(blocks)?;
+        }
+    }
+    if !res.blocks.is_empty() {
+        let next_block_index = with_blocks(|blocks| blocks.len());
+        if res.first_block_index != next_block_index {
+            return Err(format!(
+                "the Ledger returned blocks starting at {} but the next block to index is {}",
+                res.first_block_index, next_block_index
+            ));
+        }
+        num_indexed += append_blocks(res.blocks)?;
+    }
+    let wait_time = compute_wait_time(num_indexed);
+    ic_cdk::eprintln!(
+        "{}Indexed: {} waiting : {}",
+        LOG_PREFIX,
+        num_indexed,
+        wait_time
+    );
+    change_state(|state| state.next_build_index_time = ic_cdk::api::time() + wait_time);
+    Ok(())
+}
+
+/// Compute the waiting time before next indexing
+pub fn compute_wait_time(indexed_tx_count: usize) -> u64 {
+    if indexed_tx_count >= MAX_BLOCKS_PER_REQUEST {
+        // If we indexed a full batch, we index again on the next heartbeat.
+        return 0;
+    }
+    ((1_f64 - indexed_tx_count as f64 / MAX_BLOCKS_PER_REQUEST as f64)
+        * DEFAULT_MAX_WAIT_TIME_NANOS) as u64
+}
+
+fn append_blocks(blocks: Vec<CandidBlock>) -> Result<usize, String> {
+    let num_blocks = blocks.len();
+    for block in blocks {
+        append_block(block)?;
+    }
+    Ok(num_blocks)
+}
+
+/// The effect of an operation on the index: the accounts it touches and the
+/// amounts it moves.
+struct BalanceChanges {
+    accounts: Vec<[u8; 32]>,
+    debit: Option<([u8; 32], u64)>,
+    credit: Option<([u8; 32], u64)>,
+}
+
+fn balance_changes(operation: &CandidOperation) -> Result<BalanceChanges, String> {
+    let debit_amount = |amount: u64, fee: u64| {
+        amount
+            .checked_add(fee)
+            .ok_or_else(|| format!("amount {} plus fee {} overflows", amount, fee))
+    };
+    Ok(match *operation {
+        CandidOperation::Burn { from, amount } => BalanceChanges {
+            accounts: vec![from],
+            debit: Some((from, amount.get_e8s())),
+            credit: None,
+        },
+        CandidOperation::Mint { to, amount } => BalanceChanges {
+            accounts: vec![to],
+            debit: None,
+            credit: Some((to, amount.get_e8s())),
+        },
+        CandidOperation::Transfer {
+            from,
+            to,
+            amount,
+            fee,
+        } => BalanceChanges {
+            accounts: vec![from, to],
+            debit: Some((from, debit_amount(amount.get_e8s(), fee.get_e8s())?)),
+            credit: Some((to, amount.get_e8s())),
+        },
+        CandidOperation::Approve {
+            from, spender, fee, ..
+        } => BalanceChanges {
+            accounts: vec![from, spender],
+            debit: Some((from, fee.get_e8s())),
+            credit: None,
+        },
+        CandidOperation::TransferFrom {
+            from,
+            to,
+            spender,
+            amount,
+            fee,
+        } => BalanceChanges {
+            accounts: vec![from, to, spender],
+            debit: Some((from, debit_amount(amount.get_e8s(), fee.get_e8s())?)),
+            credit: Some((to, amount.get_e8s())),
+        },
+    })
+}
+
+fn get_balance(address: [u8; 32]) -> u64 {
+    with_account_data(|data| data.get(&AccountIdentifierKey(address)))
+        .map(|d| d.balance)
+        .unwrap_or_default()
+}
+
+/// Appends the block to the block list and indexes its operation.
+///
+/// The block is checked before anything is written, so that a block that
+/// doesn't apply cleanly leaves the index untouched.
+fn append_block(block: CandidBlock) -> Result<(), String> {
+    let block_index = with_blocks(|blocks| blocks.len());
+    let changes = balance_changes(&block.transaction.operation)
+        .map_err(|e| format!("block {}: {}", block_index, e))?;
+    if let Some((from, amount)) = changes.debit {
+        let balance = get_balance(from);
+        if balance < amount {
+            return Err(format!(
+                "block {}: cannot debit {} from {} with balance {}",
+                block_index,
+                amount,
+                hex::encode(from),
+                balance
+            ));
+        }
+    }
+
+    let encoded = Encode!(&block).map_err(|e| format!("failed to encode a block: {}", e))?;
+    with_blocks(|blocks| blocks.append(&encoded))
+        .map_err(|e| format!("failed to append a block: {:?}", e))?;
+
+    for address in changes.accounts {
+        add_block(block_index, address);
+    }
+    if let Some((from, amount)) = changes.debit {
+        change_balance(from, |balance| balance - amount);
+    }
+    if let Some((to, amount)) = changes.credit {
+        change_balance(to, |balance| {
+            // The total supply of the Ledger fits in a u64.
+            balance.checked_add(amount).expect("bug: balance overflow")
+        });
+    }
+    Ok(())
+}
+
+fn add_block(block_index: BlockIndex, address: [u8; 32]) {
+    with_account_block_ids(|ids| ids.insert(AccountBlockIdsKey::new(address, block_index), ()));
+    with_account_data(|data| {
+        let key = AccountIdentifierKey(address);
+        if data.get(&key).is_none() {
+            data.insert(
+                key,
+                AccountData {
+                    balance: 0,
+                    oldest_block_index: block_index,
+                },
+            );
+        }
+    });
+}
+
+fn change_balance(address: [u8; 32], f: impl FnOnce(u64) -> u64) {
+    with_account_data(|data| {
+        let key = AccountIdentifierKey(address);
+        let mut account_data = data.get(&key).unwrap_or_default();
+        account_data.balance = f(account_data.balance);
+        data.insert(key, account_data);
+    })
+}
+
+fn get_block(block_index: BlockIndex) -> Option<CandidBlock> {
+    let bytes = with_blocks(|blocks| blocks.get(block_index))?;
+    Some(Decode!(&bytes[..], CandidBlock).unwrap_or_else(|e| {
+        ic_cdk::api::trap(&format!("failed to decode block {}: {}", block_index, e))
+    }))
+}
+
+/// Returns at most max_results transactions of the given account
+/// identifier starting from the block with index start, from the most
+/// recent to the least recent.
+///
+/// If start is not set then the results start from the most recent
+/// transaction of the account.
+fn get_transactions(
+    account_identifier: AccountIdentifier,
+    start: Option<BlockIndex>,
+    max_results: u64,
+) -> GetAccountIdentifierTransactionsResponse {
+    let address = account_identifier.to_address();
+    let max_results = max_results.min(MAX_TRANSACTIONS_PER_RESPONSE) as usize;
+    let first = AccountBlockIdsKey::new(address, start.unwrap_or(u64::MAX));
+    let last = AccountBlockIdsKey::new(address, 0);
+    let block_ids: Vec<BlockIndex> = with_account_block_ids(|ids| {
+        ids.range(first..=last)
+            .take(max_results)
+            .map(|(key, ())| key.block_index())
+            .collect()
+    });
+    let transactions = block_ids
+        .into_iter()
+        .map(|id| {
+            let block = get_block(id)
+                .unwrap_or_else(|| ic_cdk::api::trap(&format!("block {} not found", id)));
+            TransactionWithId {
+                id,
+                transaction: block.transaction,
+                timestamp: block.timestamp,
+            }
+        })
+        .collect();
+    let account_data = with_account_data(|data| data.get(&AccountIdentifierKey(address)));
+    GetAccountIdentifierTransactionsResponse {
+        balance: account_data.map(|d| d.balance).unwrap_or_default(),
+        transactions,
+        oldest_tx_id: account_data.map(|d| d.oldest_block_index),
+    }
+}
+
+pub fn get_account_identifier_transactions(
+    args: GetAccountIdentifierTransactionsArgs,
+) -> GetAccountIdentifierTransactionsResult {
+    let account_identifier =
+        AccountIdentifier::from_hex(&args.account_identifier).map_err(|e| {
+            GetAccountIdentifierTransactionsError {
+                message: format!(
+                    "Invalid account identifier {}: {}",
+                    args.account_identifier, e
+                ),
+            }
+        })?;
+    Ok(get_transactions(
+        account_identifier,
+        args.start,
+        args.max_results,
+    ))
+}
+
+pub fn get_account_transactions(
+    args: GetAccountTransactionsArgs,
+) -> GetAccountIdentifierTransactionsResult {
+    // Block indices of the ICP Ledger are u64, so any larger start
+    // includes all the transactions of the account.
+    let start = args.start.map(|n| n.0.to_u64().unwrap_or(u64::MAX));
+    let max_results = args.max_results.0.to_u64().unwrap_or(u64::MAX);
+    Ok(get_transactions(
+        AccountIdentifier::from(args.account),
+        start,
+        max_results,
+    ))
+}
+
+pub fn get_account_identifier_balance(account_identifier: AccountIdentifier) -> u64 {
+    get_balance(account_identifier.to_address())
+}
+
+pub fn encode_metrics(w: &mut ic_metrics_encoder::MetricsEncoder<Vec<u8>>) -> std::io::Result<()> {
+    w.encode_gauge(
+        "index_stable_memory_pages",
+        ic_cdk::api::stable::stable_size() as f64,
+        "Size of the stable memory allocated by this canister measured in 64K Wasm pages.",
+    )?;
+    w.encode_gauge(
+        "index_stable_memory_bytes",
+        (ic_cdk::api::stable::stable_size() * 64 * 1024) as f64,
+        "Size of the stable memory allocated by this canister.",
+    )?;
+    w.encode_gauge(
+        "index_cycle_balance",
+        ic_cdk::api::canister_balance128() as f64,
+        "Cycle balance on this canister.",
+    )?;
+    w.encode_gauge(
+        "index_number_of_blocks",
+        with_blocks(|blocks| blocks.len()) as f64,
+        "Total number of blocks stored in the stable memory.",
+    )?;
+    w.encode_gauge(
+        "index_number_of_accounts",
+        with_account_data(|data| data.len()) as f64,
+        "Total number of account identifiers indexed.",
+    )?;
+    Ok(())
+}
+
+pub fn post_upgrade() {
+    // NB. all the index data lives in stable memory, the variable initializers
+    // take care of the decoding. We access the state here to make sure that the
+    // system rolls back the upgrade if the initialization traps.
+    change_state(|state| state.is_heartbeat_running = false);
+    with_blocks(|blocks| blocks.len());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ic_base_types::PrincipalId;
+    use icp_ledger::{Memo, Tokens};
+    use proptest::prelude::*;
+
+    fn address(n: u64) -> [u8; 32] {
+        AccountIdentifier::new(PrincipalId::new_user_test_id(n), None).to_address()
+    }
+
+    fn block(operation: CandidOperation) -> CandidBlock {
+        CandidBlock {
+            parent_hash: None,
+            transaction: CandidTransaction {
+                operation,
+                memo: Memo(0),
+                icrc1_memo: None,
+                created_at_time: TimeStamp::from_nanos_since_unix_epoch(0),
+            },
+            timestamp: TimeStamp::from_nanos_since_unix_epoch(0),
+        }
+    }
+
+    fn mint(to: u64, amount: u64) -> CandidBlock {
+        block(CandidOperation::Mint {
+            to: address(to),
+            amount: Tokens::from_e8s(amount),
+        })
+    }
+
+    fn transfer(from: u64, to: u64, amount: u64) -> CandidBlock {
+        block(CandidOperation::Transfer {
+            from: address(from),
+            to: address(to),
+            amount: Tokens::from_e8s(amount),
+            fee: Tokens::from_e8s(10),
+        })
+    }
+
+    fn block_ids(account: u64, start: Option<u64>, max_results: u64) -> Vec<BlockIndex> {
+        get_transactions(
+            AccountIdentifier::from_address(address(account)).unwrap(),
+            start,
+            max_results,
+        )
+        .transactions
+        .into_iter()
+        .map(|tx| tx.id)
+        .collect()
+    }
+
+    proptest! {
+        #[test]
+        fn account_block_ids_key_roundtrip(block_index: u64) {
+            let key = AccountBlockIdsKey::new(address(1), block_index);
+            prop_assert_eq!(key.block_index(), block_index);
+            prop_assert_eq!(AccountBlockIdsKey::from_bytes(key.to_bytes()), key);
+        }
+
+        #[test]
+        fn account_block_ids_key_order(l: u64, r: u64) {
+            // More recent blocks must come first.
+            let lkey = AccountBlockIdsKey::new(address(1), l);
+            let rkey = AccountBlockIdsKey::new(address(1), r);
+            prop_assert_eq!(lkey.cmp(&rkey), r.cmp(&l));
+        }
+    }
+
+    #[test]
+    fn account_data_roundtrip() {
+        let data = AccountData {
+            balance: 1_000_000,
+            oldest_block_index: u64::MAX,
+        };
+        assert_eq!(AccountData::from_bytes(data.to_bytes()), data);
+    }
+
+    #[test]
+    fn index_transactions_and_balances() {
+        append_blocks(vec![
+            mint(1, 100_000),    // block=0
+            mint(2, 200_000),    // block=1
+            transfer(1, 2, 1),   // block=2
+            transfer(2, 1, 10),  // block=3
+            transfer(2, 3, 20),  // block=4
+            transfer(1, 3, 100), // block=5
+        ])
+        .unwrap();
+
+        assert_eq!(block_ids(1, None, u64::MAX), vec![5, 3, 2, 0]);
+        assert_eq!(block_ids(1, None, 2), vec![5, 3]);
+        assert_eq!(block_ids(1, Some(4), 2), vec![3, 2]);
+        assert_eq!(block_ids(1, Some(3), u64::MAX), vec![3, 2, 0]);
+        assert_eq!(block_ids(2, None, u64::MAX), vec![4, 3, 2, 1]);
+        assert_eq!(block_ids(3, Some(4), u64::MAX), vec![4]);
+        assert_eq!(block_ids(4, None, u64::MAX), Vec::<u64>::new());
+
+        let balance = |n| {
+            get_account_identifier_balance(AccountIdentifier::from_address(address(n)).unwrap())
+        };
+        assert_eq!(balance(1), 100_000 - 1 - 10 + 10 - 100 - 10);
+        assert_eq!(balance(2), 200_000 + 1 - 10 - 10 - 20 - 10);
+        assert_eq!(balance(3), 20 + 100);
+        assert_eq!(balance(4), 0);
+
+        let res = get_transactions(
+            AccountIdentifier::from_address(address(3)).unwrap(),
+            None,
+            u64::MAX,
+        );
+        assert_eq!(res.oldest_tx_id, Some(4));
+        assert_eq!(res.balance, 120);
+
+        // Debiting more than the balance is an error.
+        assert!(append_blocks(vec![transfer(4, 1, 1)]).is_err());
+        // A block that fails to apply is not appended.
+        assert_eq!(status().num_blocks_synced, 6);
+    }
+
+    #[test]
+    fn test_compute_wait_time() {
+        for indexed_tx_count in 0..10_000_usize {
+            let wait_time = compute_wait_time(indexed_tx_count);
+            let next_wait_time = compute_wait_time(indexed_tx_count + 1);
+            assert!(wait_time <= 100 * SEC_NANOS as u64);
+            assert!(next_wait_time <= wait_time);
+        }
+    }
+}
@@ -0,0 +1,120 @@
+use candid::candid_method;
+use ic_base_types::CanisterId;
+use ic_canisters_http_types::{HttpRequest, HttpResponse, HttpResponseBuilder};
+use ic_cdk_macros::{heartbeat, init, post_upgrade, query};
+use ic_icp_index::{
+    encode_metrics, GetAccountIdentifierTransactionsArgs, GetAccountIdentifierTransactionsResult,
+    GetAccountTransactionsArgs, InitArgs, Status,
+};
+use ic_icrc1::Account;
+use icp_ledger::AccountIdentifier;
+
+fn main() {}
+
+#[init]
+fn init(args: InitArgs) {
+    ic_icp_index::init(args);
+}
+
+#[heartbeat]
+async fn heartbeat() {
+    ic_icp_index::heartbeat().await;
+}
+
+#[query]
+#[candid_method(query)]
+fn get_account_identifier_transactions(
+    args: GetAccountIdentifierTransactionsArgs,
+) -> GetAccountIdentifierTransactionsResult {
+    ic_icp_index::get_account_identifier_transactions(args)
+}
+
+#[query]
+#[candid_method(query)]
+fn get_account_transactions(
+    args: GetAccountTransactionsArgs,
+) -> GetAccountIdentifierTransactionsResult {
+    ic_icp_index::get_account_transactions(args)
+}
+
+#[query]
+#[candid_method(query)]
+fn get_account_identifier_balance(account_identifier: String) -> u64 {
+    let account_identifier = AccountIdentifier::from_hex(&account_identifier).unwrap_or_else(|e| {
+        ic_cdk::api::trap(&format!(
+            "Invalid account identifier {}: {}",
+            account_identifier, e
+        ))
+    });
+    ic_icp_index::get_account_identifier_balance(account_identifier)
+}
+
+#[query]
+#[candid_method(query)]
+fn icrc1_balance_of(account: Account) -> u64 {
+    ic_icp_index::get_account_identifier_balance(AccountIdentifier::from(account))
+}
+
+#[query]
+#[candid_method(query)]
+fn ledger_id() -> CanisterId {
+    ic_icp_index::ledger_id()
+}
+
+#[query]
+#[candid_method(query)]
+fn status() -> Status {
+    ic_icp_index::status()
+}
+
+#[candid_method(query)]
+#[query]
+fn http_request(req: HttpRequest) -> HttpResponse {
+    if req.path() == "/metrics" {
+        let mut writer =
+            ic_metrics_encoder::MetricsEncoder::new(vec![], ic_cdk::api::time() as i64 / 1_000_000);
+
+        match encode_metrics(&mut writer) {
+            Ok(()) => HttpResponseBuilder::ok()
+                .header("Content-Type", "text/plain; version=0.0.4")
+                .with_body_and_content_length(writer.into_inner())
+                .build(),
+            Err(err) => {
+                HttpResponseBuilder::server_error(format!("Failed to encode metrics: {}", err))
+                    .build()
+            }
+        }
+    } else {
+        HttpResponseBuilder::not_found().build()
+    }
+}
+
+#[post_upgrade]
+fn post_upgrade() {
+    ic_icp_index::post_upgrade()
+}
+
+#[query]
+fn __get_candid_interface_tmp_hack() -> &'static str {
+    include_str!(env!("INDEX_DID_PATH"))
+}
+
+#[test]
+fn check_candid_interface() {
+    use candid::utils::{service_compatible, CandidSource};
+    use std::path::PathBuf;
+
+    candid::export_service!();
+
+    let new_interface = __export_service();
+
+    // check the public interface against the actual one
+    let old_interface =
+        PathBuf::from(std::env::var("CARGO_MANIFEST_DIR").unwrap()).join("index.did");
+
+    service_compatible(
+        CandidSource::Text(&new_interface),
+        CandidSource::File(old_interface.as_path()),
+    )
+    .expect("the index interface is not compatible with index.did");
+}
@@ -0,0 +1,333 @@
+use candid::{Decode, Encode, Nat};
+use ic_base_types::PrincipalId;
+use ic_icp_index::{
+    GetAccountIdentifierTransactionsArgs, GetAccountIdentifierTransactionsResponse,
+    GetAccountIdentifierTransactionsResult, GetAccountTransactionsArgs, InitArgs, Status,
+    TransactionWithId,
+};
+use ic_icrc1::{
+    endpoints::{TransferArg, TransferError},
+    Account,
+};
+use ic_ledger_core::block::BlockIndex;
+use ic_state_machine_tests::{CanisterId, StateMachine};
+use icp_ledger::{
+    AccountIdentifier, ArchiveOptions, CandidOperation, LedgerCanisterInitPayload, Tokens,
+};
+use num_traits::cast::ToPrimitive;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::time::Duration;
+
+const FEE: u64 = 10_000;
+const ARCHIVE_TRIGGER_THRESHOLD: u64 = 10;
+const NUM_BLOCKS_TO_ARCHIVE: usize = 5;
+
+const MINTER: Account = Account {
+    owner: PrincipalId::new(0, [0u8; 29]),
+    subaccount: None,
+};
+
+fn index_wasm() -> Vec<u8> {
+    ic_test_utilities_load_wasm::load_wasm(
+        std::env::var("CARGO_MANIFEST_DIR").unwrap(),
+        "ic-icp-index",
+        &[],
+    )
+}
+
+fn ledger_wasm() -> Vec<u8> {
+    ic_test_utilities_load_wasm::load_wasm(
+        PathBuf::from(std::env::var("CARGO_MANIFEST_DIR").unwrap())
+            .parent()
+            .unwrap()
+            .join("ledger"),
+        "ledger-canister",
+        &[],
+    )
+}
+
+fn default_archive_options() -> ArchiveOptions {
+    ArchiveOptions {
+        trigger_threshold: ARCHIVE_TRIGGER_THRESHOLD as usize,
+        num_blocks_to_archive: NUM_BLOCKS_TO_ARCHIVE,
+        node_max_memory_size_bytes: None,
+        max_message_size_bytes: None,
+        controller_id: PrincipalId::new_user_test_id(100),
+        cycles_for_archive_creation: None,
+        max_transactions_per_response: None,
+    }
+}
+
+fn install_ledger(
+    env: &StateMachine,
+    initial_balances: HashMap<AccountIdentifier, Tokens>,
+    archive_options: ArchiveOptions,
+) -> CanisterId {
+    let args = LedgerCanisterInitPayload::builder()
+        .minting_account(MINTER.into())
+        .icrc1_minting_account(MINTER)
+        .initial_values(initial_balances)
+        .archive_options(archive_options)
+        .transfer_fee(Tokens::from_e8s(FEE))
+        .token_symbol_and_name("ICP", "Internet Computer")
+        .build()
+        .unwrap();
+    env.install_canister(ledger_wasm(), Encode!(&args).unwrap(), None)
+        .unwrap()
+}
+
+fn install_index(env: &StateMachine, ledger_id: CanisterId) -> CanisterId {
+    let args = InitArgs { ledger_id };
+    env.install_canister(index_wasm(), Encode!(&args).unwrap(), None)
+        .unwrap()
+}
+
+fn account(n: u64) -> Account {
+    Account {
+        owner: PrincipalId::new_user_test_id(n),
+        subaccount: None,
+    }
+}
+
+fn transfer(
+    env: &StateMachine,
+    ledger: CanisterId,
+    from: Account,
+    to: Account,
+    amount: u64,
+) -> BlockIndex {
+    let arg = TransferArg {
+        from_subaccount: from.subaccount,
+        to,
+        fee: None,
+        created_at_time: None,
+        amount: Nat::from(amount),
+        memo: None,
+    };
+    Decode!(
+        &env.execute_ingress_as(from.owner, ledger, "icrc1_transfer", Encode!(&arg).unwrap())
+            .expect("failed to transfer funds")
+            .bytes(),
+        Result<Nat, TransferError>
+    )
+    .expect("failed to decode transfer response")
+    .map(|n| n.0.to_u64().unwrap())
+    .expect("transfer failed")
+}
+
+fn burn(env: &StateMachine, ledger: CanisterId, from: Account, amount: u64) -> BlockIndex {
+    transfer(env, ledger, from, MINTER, amount)
+}
+
+fn mint(env: &StateMachine, ledger: CanisterId, to: Account, amount: u64) -> BlockIndex {
+    transfer(env, ledger, MINTER, to, amount)
+}
+
+fn wait_until_sync(env: &StateMachine) {
+    env.advance_time(Duration::from_secs(60));
+    env.tick(); // trigger index heartbeat
+    env.run_until_completion(10_000);
+}
+
+fn status(env: &StateMachine, index: CanisterId) -> Status {
+    Decode!(
+        &env.query(index, "status", Encode!().unwrap())
+            .expect("failed to query status")
+            .bytes(),
+        Status
+    )
+    .expect("failed to decode status response")
+}
+
+fn get_account_identifier_transactions(
+    env: &StateMachine,
+    index: CanisterId,
+    account: Account,
+    start: Option<u64>,
+    max_results: u64,
+) -> GetAccountIdentifierTransactionsResponse {
+    Decode!(
+        &env.query(
+            index,
+            "get_account_identifier_transactions",
+            Encode!(&GetAccountIdentifierTransactionsArgs {
+                account_identifier: AccountIdentifier::from(account).to_hex(),
+                start,
+                max_results,
+            })
+            .unwrap()
+        )
+        .expect("failed to get_account_identifier_transactions")
+        .bytes(),
+        GetAccountIdentifierTransactionsResult
+    )
+    .expect("failed to decode get_account_identifier_transactions response")
+    .expect("failed to get the range of transactions!")
+}
+
+fn get_account_transactions(
+    env: &StateMachine,
+    index: CanisterId,
+    account: Account,
+    start: Option<u64>,
+    max_results: u64,
+) -> GetAccountIdentifierTransactionsResponse {
+    Decode!(
+        &env.query(
+            index,
+            "get_account_transactions",
+            Encode!(&GetAccountTransactionsArgs {
+                account,
+                start: start.map(Nat::from),
+                max_results: Nat::from(max_results),
+            })
+            .unwrap()
+        )
+        .expect("failed to get_account_transactions")
+        .bytes(),
+        GetAccountIdentifierTransactionsResult
+    )
+    .expect("failed to decode get_account_transactions response")
+    .expect("failed to get the range of transactions!")
+}
+
+fn ledger_balance_of(env: &StateMachine, ledger: CanisterId, account: Account) -> u64 {
+    Decode!(
+        &env.query(ledger, "icrc1_balance_of", Encode!(&account).unwrap())
+            .expect("failed to query balance")
+            .bytes(),
+        Nat
+    )
+    .expect("failed to decode balance_of response")
+    .0
+    .to_u64()
+    .unwrap()
+}
+
+fn index_balance_of(env: &StateMachine, index: CanisterId, account: Account) -> u64 {
+    Decode!(
+        &env.query(index, "icrc1_balance_of", Encode!(&account).unwrap())
+            .expect("failed to query balance")
+            .bytes(),
+        u64
+    )
+    .expect("failed to decode balance_of response")
+}
+
+fn tx_ids(txs: &[TransactionWithId]) -> Vec<BlockIndex> {
+    txs.iter().map(|tx| tx.id).collect()
+}
+
+fn check_transfer(id: BlockIndex, from: Account, to: Account, amount: u64, tx: &TransactionWithId) {
+    assert_eq!(tx.id, id);
+    assert_eq!(
+        tx.transaction.operation,
+        CandidOperation::Transfer {
+            from: AccountIdentifier::from(from).to_address(),
+            to: AccountIdentifier::from(to).to_address(),
+            amount: Tokens::from_e8s(amount),
+            fee: Tokens::from_e8s(FEE),
+        }
+    );
+}
+
+// Checks that the balances reported by the index match the Ledger ones.
+fn assert_balances_match(env: &StateMachine, ledger: CanisterId, index: CanisterId, n: u64) {
+    for i in 1..=n {
+        assert_eq!(
+            ledger_balance_of(env, ledger, account(i)),
+            index_balance_of(env, index, account(i)),
+            "balance mismatch for account {}",
+            i
+        );
+    }
+}
+
+#[test]
+fn test_ledger_index_sync() {
+    let env = StateMachine::new();
+    let ledger_id = install_ledger(&env, HashMap::new(), default_archive_options());
+    let index_id = install_index(&env, ledger_id);
+
+    mint(&env, ledger_id, account(1), 100_000); // block=0
+    mint(&env, ledger_id, account(2), 200_000); // block=1
+    transfer(&env, ledger_id, account(1), account(2), 1); // block=2
+    transfer(&env, ledger_id, account(2), account(1), 10); // block=3
+    transfer(&env, ledger_id, account(2), account(1), 20); // block=4
+    burn(&env, ledger_id, account(1), 10_000); // block=5
+
+    wait_until_sync(&env);
+
+    assert_eq!(status(&env, index_id).num_blocks_synced, 6);
+
+    let res = get_account_identifier_transactions(&env, index_id, account(1), None, u64::MAX);
+    assert_eq!(res.oldest_tx_id, Some(0));
+    assert_eq!(tx_ids(&res.transactions), vec![5, 4, 3, 2, 0]);
+    assert_eq!(res.balance, ledger_balance_of(&env, ledger_id, account(1)));
+    check_transfer(4, account(2), account(1), 20, &res.transactions[1]);
+
+    // ICRC-1 accounts are indexed by their account identifier.
+    let res = get_account_transactions(&env, index_id, account(2), None, u64::MAX);
+    assert_eq!(res.oldest_tx_id, Some(1));
+    assert_eq!(tx_ids(&res.transactions), vec![4, 3, 2, 1]);
+
+    // Pagination starts from the given block index inclusive.
+    let res = get_account_identifier_transactions(&env, index_id, account(1), Some(4), 2);
+    assert_eq!(tx_ids(&res.transactions), vec![4, 3]);
+
+    assert_balances_match(&env, ledger_id, index_id, 3);
+}
+
+#[test]
+fn test_archived_blocks() {
+    let env = StateMachine::new();
+    let initial_balances = (1..=20)
+        .map(|i| (account(i).into(), Tokens::from_e8s(1_000_000)))
+        .collect();
+    let ledger_id = install_ledger(&env, initial_balances, default_archive_options());
+    for i in 1..20 {
+        transfer(&env, ledger_id, account(i), account(i + 1), i * 100);
+    }
+    env.run_until_completion(10_000);
+
+    let index_id = install_index(&env, ledger_id);
+    wait_until_sync(&env);
+
+    assert_eq!(status(&env, index_id).num_blocks_synced, 39);
+    assert_balances_match(&env, ledger_id, index_id, 20);
+
+    // account(2) received the initial mint, then a transfer from account(1)
+    // (block 20) and sent a transfer to account(3) (block 21).
+    let res = get_account_transactions(&env, index_id, account(2), None, u64::MAX);
+    assert_eq!(res.transactions.len(), 3);
+    check_transfer(21, account(2), account(3), 200, &res.transactions[0]);
+    check_transfer(20, account(1), account(2), 100, &res.transactions[1]);
+    assert!(res.oldest_tx_id.unwrap() < 20);
+}
+
+#[test]
+fn test_upgrade() {
+    let env = StateMachine::new();
+    let ledger_id = install_ledger(&env, HashMap::new(), default_archive_options());
+    let index_id = install_index(&env, ledger_id);
+
+    mint(&env, ledger_id, account(1), 100_000);
+    transfer(&env, ledger_id, account(1), account(2), 1_000);
+    wait_until_sync(&env);
+
+    let before = get_account_transactions(&env, index_id, account(1), None, u64::MAX);
+
+    env.upgrade_canister(index_id, index_wasm(), Encode!(&()).unwrap())
+        .unwrap();
+
+    let after = get_account_transactions(&env, index_id, account(1), None, u64::MAX);
+    assert_eq!(before, after);
+
+    // The index keeps syncing after the upgrade.
+    transfer(&env, ledger_id, account(2), account(1), 100);
+    wait_until_sync(&env);
+
+    assert_eq!(status(&env, index_id).num_blocks_synced, 3);
+    assert_balances_match(&env, ledger_id, index_id, 2);
+}
@@ -3,7 +3,9 @@ use ic_base_types::{CanisterId, PrincipalId};
 use ic_icrc1::Account;
 use ic_ledger_canister_core::archive::ArchiveCanisterWasm;
 use ic_ledger_canister_core::blockchain::Blockchain;
-use ic_ledger_canister_core::ledger::{self as core_ledger, LedgerData, TransactionInfo};
+use ic_ledger_canister_core::ledger::{
+    self as core_ledger, LedgerContext, LedgerData, LedgerTransaction, TransactionInfo,
+};
 use ic_ledger_core::{
     balances::Balances,
     block::{EncodedBlock, HashOf},
@@ -11,8 +13,8 @@ use ic_ledger_core::{
 };
 use ic_ledger_core::{block::BlockIndex, tokens::Tokens};
 use icp_ledger::{
-    AccountIdentifier, Block, LedgerBalances, Memo, Operation, PaymentError, Transaction,
-    TransferError, TransferFee, DEFAULT_TRANSFER_FEE,
+    AccountIdentifier, Block, LedgerAllowances, LedgerBalances, Memo, Operation, PaymentError,
+    Transaction, TransferError, TransferFee, DEFAULT_TRANSFER_FEE,
 };
 use intmap::IntMap;
 use lazy_static::lazy_static;
@@ -62,6 +64,8 @@ fn unknown_token() -> String {
 #[derive(Serialize, Deserialize, Debug)]
 pub struct Ledger {
     pub balances: LedgerBalances,
+    #[serde(default)]
+    pub approvals: LedgerAllowances,
     pub blockchain: Blockchain<dfn_runtime::DfnRuntime, IcpLedgerArchiveWasm>,
     // A cap on the maximum number of accounts
     pub maximum_number_of_accounts: usize,
@@ -101,13 +105,33 @@ pub struct Ledger {
     pub token_name: String,
 }
 
-impl LedgerData for Ledger {
+impl LedgerContext for Ledger {
     type AccountId = AccountIdentifier;
+    type BalancesStore = HashMap<AccountIdentifier, Tokens>;
+    type Approvals = LedgerAllowances;
+
+    fn balances(&self) -> &Balances<Self::BalancesStore> {
+        &self.balances
+    }
+
+    fn balances_mut(&mut self) -> &mut Balances<Self::BalancesStore> {
+        &mut self.balances
+    }
+
+    fn approvals(&self) -> &Self::Approvals {
+        &self.approvals
+    }
+
+    fn approvals_mut(&mut self) -> &mut Self::Approvals {
+        &mut self.approvals
+    }
+}
+
+impl LedgerData for Ledger {
     type Runtime = dfn_runtime::DfnRuntime;
     type ArchiveWasm = IcpLedgerArchiveWasm;
     type Transaction = Transaction;
     type Block = Block;
-    type BalancesStore = HashMap<AccountIdentifier, Tokens>;
 
     fn transaction_window(&self) -> Duration {
         self.transaction_window
@@ -137,14 +161,6 @@ impl LedgerData for Ledger {
         &self.token_symbol
     }
 
-    fn balances(&self) -> &Balances<Self::BalancesStore> {
-        &self.balances
-    }
-
-    fn balances_mut(&mut self) -> &mut Balances<Self::BalancesStore> {
-        &mut self.balances
-    }
-
     fn blockchain(&self) -> &Blockchain<Self::Runtime, Self::ArchiveWasm> {
         &self.blockchain
     }
@@ -178,6 +194,7 @@ impl Default for Ledger {
     fn default() -> Self {
         Self {
             balances: LedgerBalances::default(),
+            approvals: LedgerAllowances::default(),
             blockchain: Blockchain::default(),
             maximum_number_of_accounts: 28_000_000,
             accounts_overflow_trim_quantity: 100_000,
@@ -265,6 +282,20 @@ impl Ledger {
                     )
                     .to_string(),
                 ),
+                CTE::InsufficientAllowance { allowance } => PaymentError::Reject(format!(
+                    "the spender allowance is insufficient, current allowance: {}",
+                    allowance
+                )),
+                CTE::ExpiredApproval { now } => PaymentError::Reject(format!(
+                    "the approval expiration time is in the past, ledger time: {}",
+                    now.as_nanos_since_unix_epoch()
+                )),
+                CTE::AllowanceChanged { current_allowance } => {
+                    PaymentError::Reject(format!(
+                        "the current allowance does not match the expected allowance, current allowance: {}",
+                        current_allowance
+                    ))
+                }
             }
         })
     }
@@ -272,7 +303,9 @@ impl Ledger {
     /// This adds a pre created block to the ledger. This should only be used
     /// during canister migration or upgrade
     pub fn add_block(&mut self, block: Block) -> Result<BlockIndex, String> {
-        icp_ledger::apply_operation(&mut self.balances, &block.transaction.operation)
+        block
+            .transaction
+            .apply(self, block.timestamp, Tokens::ZERO)
             .map_err(|e| format!("failed to execute transfer {:?}: {:?}", block, e))?;
         self.blockchain.add_block(block)
     }
@@ -8,7 +8,10 @@ use dfn_protobuf::protobuf;
 use ic_base_types::{CanisterId, PrincipalId};
 use ic_canister_log::{LogEntry, Sink};
 use ic_icrc1::{
-    endpoints::{StandardRecord, TransferArg, Value},
+    endpoints::{
+        Allowance, AllowanceArgs, ApproveArgs, ApproveError, StandardRecord, TransferArg,
+        TransferFromArgs, TransferFromError, Value,
+    },
     Account,
 };
 use ic_ledger_canister_core::{
@@ -19,6 +22,7 @@ use ic_ledger_canister_core::{
     range_utils,
 };
 use ic_ledger_core::{
+    approvals::Approvals,
     block::{BlockIndex, BlockType, EncodedBlock},
     timestamp::TimeStamp,
     tokens::{Tokens, DECIMAL_PLACES},
@@ -533,10 +537,16 @@ fn icrc1_balance_of(acc: Account) -> Nat {
 
 #[candid_method(query, rename = "icrc1_supported_standards")]
 fn icrc1_supported_standards() -> Vec<StandardRecord> {
-    vec![StandardRecord {
-        name: "ICRC-1".to_string(),
-        url: "https://github.com/dfinity/ICRC-1".to_string(),
-    }]
+    vec![
+        StandardRecord {
+            name: "ICRC-1".to_string(),
+            url: "https://github.com/dfinity/ICRC-1".to_string(),
+        },
+        StandardRecord {
+            name: "ICRC-2".to_string(),
+            url: "https://github.com/dfinity/ICRC-1/tree/main/standards/ICRC-2".to_string(),
+        },
+    ]
 }
 
 #[candid_method(query, rename = "icrc1_minting_account")]
@@ -793,6 +803,168 @@ async fn icrc1_transfer(arg: TransferArg) -> Result<Nat, ic_icrc1::endpoints::Tr
     ))
 }
 
+#[candid_method(update, rename = "icrc2_approve")]
+async fn icrc2_approve(arg: ApproveArgs) -> Result<Nat, ApproveError> {
+    let now = TimeStamp::from_nanos_since_unix_epoch(ic_cdk::api::time());
+    let from_account = Account {
+        owner: PrincipalId::from(ic_cdk::api::caller()),
+        subaccount: arg.from_subaccount,
+    };
+    if from_account.owner == arg.spender.owner {
+        trap_with("self approval is not allowed");
+    }
+    let from = AccountIdentifier::from(from_account);
+    let spender = AccountIdentifier::from(arg.spender);
+    let minting_acc = LEDGER
+        .read()
+        .unwrap()
+        .minting_account_id
+        .expect("Minting canister id not initialized");
+    if from == minting_acc {
+        trap_with("the minting account cannot delegate mints");
+    }
+
+    // Allowances larger than the total supply are indistinguishable
+    // from the maximum allowance.
+    let allowance = arg
+        .amount
+        .0
+        .to_u64()
+        .map(Tokens::from_e8s)
+        .unwrap_or(Tokens::MAX);
+    let expected_allowance = match arg.expected_allowance {
+        Some(n) => match n.0.to_u64() {
+            Some(n) => Some(Tokens::from_e8s(n)),
+            None => {
+                let current_allowance = LEDGER
+                    .read()
+                    .unwrap()
+                    .approvals
+                    .allowance(&from, &spender, now)
+                    .amount;
+                return Err(ApproveError::AllowanceChanged {
+                    current_allowance: Nat::from(current_allowance.get_e8s()),
+                });
+            }
+        },
+        None => None,
+    };
+
+    let expected_fee = LEDGER.read().unwrap().transfer_fee;
+    if arg.fee.is_some() && arg.fee.as_ref() != Some(&Nat::from(expected_fee.get_e8s())) {
+        return Err(ApproveError::BadFee {
+            expected_fee: Nat::from(expected_fee.get_e8s()),
+        });
+    }
+
+    let block_index = {
+        let mut ledger = LEDGER.write().unwrap();
+        let tx = Transaction {
+            operation: Operation::Approve {
+                from,
+                spender,
+                allowance,
+                expected_allowance,
+                expires_at: arg.expires_at.map(TimeStamp::from_nanos_since_unix_epoch),
+                fee: expected_fee,
+            },
+            memo: Memo(0),
+            icrc1_memo: arg.memo.map(|x| x.0),
+            created_at_time: arg
+                .created_at_time
+                .map(TimeStamp::from_nanos_since_unix_epoch),
+        };
+        let (block_index, hash) =
+            apply_transaction(&mut *ledger, tx, now, expected_fee).map_err(ApproveError::from)?;
+
+        set_certified_data(&hash.into_bytes());
+
+        block_index
+    };
+
+    let max_msg_size = *MAX_MESSAGE_SIZE_BYTES.read().unwrap();
+    archive_blocks::<Access>(DebugOutSink, max_msg_size as u64).await;
+    Ok(Nat::from(block_index))
+}
+
+#[candid_method(update, rename = "icrc2_transfer_from")]
+async fn icrc2_transfer_from(arg: TransferFromArgs) -> Result<Nat, TransferFromError> {
+    let now = TimeStamp::from_nanos_since_unix_epoch(ic_cdk::api::time());
+    let spender = AccountIdentifier::from(Account {
+        owner: PrincipalId::from(ic_cdk::api::caller()),
+        subaccount: arg.spender_subaccount,
+    });
+    let from = AccountIdentifier::from(arg.from);
+    let to = AccountIdentifier::from(arg.to);
+    let minting_acc = LEDGER
+        .read()
+        .unwrap()
+        .minting_account_id
+        .expect("Minting canister id not initialized");
+    if from == minting_acc || to == minting_acc {
+        trap_with("transfer_from cannot mint or burn tokens");
+    }
+
+    let amount = match arg.amount.0.to_u64() {
+        Some(n) => Tokens::from_e8s(n),
+        None => {
+            // No one can have so many tokens
+            let balance = Nat::from(account_balance(from).get_e8s());
+            assert!(balance < arg.amount);
+            return Err(TransferFromError::InsufficientFunds { balance });
+        }
+    };
+
+    let expected_fee = LEDGER.read().unwrap().transfer_fee;
+    if arg.fee.is_some() && arg.fee.as_ref() != Some(&Nat::from(expected_fee.get_e8s())) {
+        return Err(TransferFromError::BadFee {
+            expected_fee: Nat::from(expected_fee.get_e8s()),
+        });
+    }
+
+    let block_index = {
+        let mut ledger = LEDGER.write().unwrap();
+        let tx = Transaction {
+            operation: Operation::TransferFrom {
+                from,
+                to,
+                spender,
+                amount,
+                fee: expected_fee,
+            },
+            memo: Memo(0),
+            icrc1_memo: arg.memo.map(|x| x.0),
+            created_at_time: arg
+                .created_at_time
+                .map(TimeStamp::from_nanos_since_unix_epoch),
+        };
+        let (block_index, hash) = apply_transaction(&mut *ledger, tx, now, expected_fee)
+            .map_err(TransferFromError::from)?;
+
+        set_certified_data(&hash.into_bytes());
+
+        block_index
+    };
+
+    let max_msg_size = *MAX_MESSAGE_SIZE_BYTES.read().unwrap();
+    archive_blocks::<Access>(DebugOutSink, max_msg_size as u64).await;
+    Ok(Nat::from(block_index))
+}
+
+#[candid_method(query, rename = "icrc2_allowance")]
+fn icrc2_allowance(arg: AllowanceArgs) -> Allowance {
+    let now = TimeStamp::from_nanos_since_unix_epoch(ic_cdk::api::time());
+    let allowance = LEDGER.read().unwrap().approvals.allowance(
+        &AccountIdentifier::from(arg.account),
+        &AccountIdentifier::from(arg.spender),
+        now,
+    );
+    Allowance {
+        allowance: Nat::from(allowance.amount.get_e8s()),
+        expires_at: allowance.expires_at.map(|t| t.as_nanos_since_unix_epoch()),
+    }
+}
+
 #[export_name = "canister_update transfer"]
 fn transfer() {
     over_async(candid_one, transfer_candid)
@@ -809,6 +981,33 @@ fn icrc1_transfer_candid() {
     })
 }
 
+#[export_name = "canister_update icrc2_approve"]
+fn icrc2_approve_candid() {
+    over_async_may_reject(candid_one, |arg: ApproveArgs| async {
+        if !LEDGER.read().unwrap().can_send(&caller()) {
+            return Err("Anonymous principal cannot approve token transfers.".to_string());
+        }
+
+        Ok(icrc2_approve(arg).await)
+    })
+}
+
+#[export_name = "canister_update icrc2_transfer_from"]
+fn icrc2_transfer_from_candid() {
+    over_async_may_reject(candid_one, |arg: TransferFromArgs| async {
+        if !LEDGER.read().unwrap().can_send(&caller()) {
+            return Err("Anonymous principal cannot transfer tokens on the ledger.".to_string());
+        }
+
+        Ok(icrc2_transfer_from(arg).await)
+    })
+}
+
+#[export_name = "canister_query icrc2_allowance"]
+fn icrc2_allowance_candid() {
+    over(candid_one, icrc2_allowance)
+}
+
 /// See caveats of use on send_dfx
 #[cfg(feature = "notify-method")]
 #[export_name = "canister_update notify_dfx"]
@@ -1194,6 +1393,28 @@ mod tests {
             });
         }
     }
+
+    #[test]
+    fn check_icrc2_endpoints_are_in_ledger_did() {
+        // The compatibility check above only covers the endpoints that are
+        // declared in ledger.did, so make sure the ICRC-2 ones are.
+        let manifest_dir = PathBuf::from(std::env::var("CARGO_MANIFEST_DIR").unwrap());
+        let ledger_did = manifest_dir.join("../ledger.did");
+        let (env, service) = CandidSource::File(ledger_did.as_path())
+            .load()
+            .expect("failed to load ledger.did");
+        let service = service.expect("ledger.did does not declare a service");
+        let methods = env.as_service(&service).unwrap();
+        for endpoint in ["icrc2_approve", "icrc2_transfer_from", "icrc2_allowance"] {
+            assert!(
+                methods.iter().any(|(name, _)| name == endpoint),
+                "{} does not declare {}",
+                ledger_did.display(),
+                endpoint
+            );
+        }
+    }
+
     // FI-510 Backwards compatibility testing for Candid and Protobuf
     #[test]
     fn check_candid_interface_backwards_compatibility() {
@@ -55,6 +55,19 @@ fn test_single_transfer() {
     ic_icrc1_ledger_sm_tests::test_single_transfer(ledger_wasm(), encode_init_args);
 }
 
+#[test]
+fn test_approve_and_transfer_from() {
+    ic_icrc1_ledger_sm_tests::test_approve_and_transfer_from(ledger_wasm(), encode_init_args);
+}
+
+#[test]
+fn test_approve_expected_allowance_and_expiration() {
+    ic_icrc1_ledger_sm_tests::test_approve_expected_allowance_and_expiration(
+        ledger_wasm(),
+        encode_init_args,
+    );
+}
+
 #[ignore = "requires fix for FI-541"]
 #[test]
 fn test_tx_deduplication() {
@@ -4,8 +4,9 @@ use ic_base_types::{CanisterId, PrincipalId};
 use ic_crypto_sha::Sha256;
 use ic_icrc1::Account;
 pub use ic_ledger_canister_core::archive::ArchiveOptions;
-use ic_ledger_canister_core::ledger::LedgerTransaction;
+use ic_ledger_canister_core::ledger::{LedgerContext, LedgerTransaction, TxApplyError};
 use ic_ledger_core::{
+    approvals::{AllowanceTable, Approvals},
     balances::{BalanceError, Balances, BalancesStore},
     block::{BlockType, EncodedBlock, HashOf, HASH_LENGTH},
 };
@@ -42,6 +43,18 @@ pub const MAX_BLOCKS_PER_REQUEST: usize = 2000;
 
 pub type LedgerBalances = Balances<HashMap<AccountIdentifier, Tokens>>;
 
+/// The key of the allowance table: the pair of the approving account and the spender.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct ApprovalKey(AccountIdentifier, AccountIdentifier);
+
+impl From<(&AccountIdentifier, &AccountIdentifier)> for ApprovalKey {
+    fn from((account, spender): (&AccountIdentifier, &AccountIdentifier)) -> Self {
+        Self(*account, *spender)
+    }
+}
+
+pub type LedgerAllowances = AllowanceTable<ApprovalKey, AccountIdentifier, AccountIdentifier>;
+
 #[derive(
     Serialize,
     Deserialize,
@@ -92,7 +105,8 @@ pub enum Operation {
     Approve {
         from: AccountIdentifier,
         spender: AccountIdentifier,
-        allowance: SignedTokens,
+        allowance: Tokens,
+        expected_allowance: Option<Tokens>,
         expires_at: Option<TimeStamp>,
         fee: Tokens,
     },
@@ -105,6 +119,10 @@ pub enum Operation {
     },
 }
 
+/// Applies the balance changes of the operation.
+///
+/// This function does not check or modify allowances, see
+/// [Transaction::apply] for the full semantics of approvals.
 pub fn apply_operation<S>(
     balances: &mut Balances<S>,
     operation: &Operation,
@@ -121,8 +139,14 @@ where
         } => balances.transfer(from, to, *amount, *fee),
         Operation::Burn { from, amount, .. } => balances.burn(from, *amount),
         Operation::Mint { to, amount, .. } => balances.mint(to, *amount),
-        Operation::Approve { .. } => unimplemented!(),
-        Operation::TransferFrom { .. } => unimplemented!(),
+        Operation::Approve { from, fee, .. } => balances.burn(from, *fee),
+        Operation::TransferFrom {
+            from,
+            to,
+            amount,
+            fee,
+            ..
+        } => balances.transfer(from, to, *amount, *fee),
     }
 }
 
@@ -166,15 +190,64 @@ impl LedgerTransaction for Transaction {
         HashOf::new(state.finish())
     }
 
-    fn apply<S>(
+    fn apply<C>(
         &self,
-        balances: &mut Balances<S>,
+        context: &mut C,
+        now: TimeStamp,
         _effective_fee: Tokens,
-    ) -> Result<(), BalanceError>
+    ) -> Result<(), TxApplyError>
     where
-        S: Default + BalancesStore<AccountId = Self::AccountId>,
+        C: LedgerContext<AccountId = Self::AccountId>,
     {
-        apply_operation(balances, &self.operation)
+        match &self.operation {
+            Operation::Approve {
+                from,
+                spender,
+                allowance,
+                expected_allowance,
+                expires_at,
+                fee,
+            } => {
+                let balance = context.balances().account_balance(from);
+                if balance < *fee {
+                    return Err(TxApplyError::InsufficientFunds { balance });
+                }
+                context.approvals_mut().approve(
+                    from,
+                    spender,
+                    *allowance,
+                    *expected_allowance,
+                    *expires_at,
+                    now,
+                )?;
+                context
+                    .balances_mut()
+                    .burn(from, *fee)
+                    .expect("bug: cannot pay the approval fee");
+                Ok(())
+            }
+            Operation::TransferFrom {
+                from,
+                spender,
+                amount,
+                fee,
+                ..
+            } => {
+                let balance = context.balances().account_balance(from);
+                let required =
+                    (*amount + *fee).map_err(|_| TxApplyError::InsufficientFunds { balance })?;
+                if balance < required {
+                    return Err(TxApplyError::InsufficientFunds { balance });
+                }
+                context
+                    .approvals_mut()
+                    .use_allowance(from, spender, required, now)?;
+                apply_operation(context.balances_mut(), &self.operation)
+                    .expect("bug: cannot transfer funds that must have existed");
+                Ok(())
+            }
+            _ => Ok(apply_operation(context.balances_mut(), &self.operation)?),
+        }
     }
 }
 
@@ -612,7 +685,8 @@ pub enum CandidOperation {
     Approve {
         from: AccountIdBlob,
         spender: AccountIdBlob,
-        allowance_e8s: i128,
+        allowance: Tokens,
+        expected_allowance: Option<Tokens>,
         fee: Tokens,
         expires_at: Option<TimeStamp>,
     },
@@ -651,12 +725,14 @@ impl From<Operation> for CandidOperation {
                 from,
                 spender,
                 allowance,
+                expected_allowance,
                 fee,
                 expires_at,
             } => Self::Approve {
                 from: from.to_address(),
                 spender: spender.to_address(),
-                allowance_e8s: allowance.to_i128(),
+                allowance,
+                expected_allowance,
                 fee,
                 expires_at,
             },
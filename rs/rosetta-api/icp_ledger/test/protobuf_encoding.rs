@@ -1,8 +1,6 @@
 use dfn_protobuf::ToProto;
 use ic_ledger_core::block::HashOf;
-use icp_ledger::{
-    AccountIdentifier, Block, Memo, Operation, SignedTokens, TimeStamp, Tokens, Transaction,
-};
+use icp_ledger::{AccountIdentifier, Block, Memo, Operation, TimeStamp, Tokens, Transaction};
 use proptest::array::{uniform28, uniform32};
 use proptest::collection::vec as pvec;
 use proptest::prelude::*;
@@ -14,19 +12,6 @@ prop_compose! {
     }
 }
 
-prop_compose! {
-    fn arb_signed_tokens()(
-        amount in any::<u64>(),
-        negative in any::<bool>()
-    ) -> SignedTokens {
-        if negative {
-            SignedTokens::Minus(Tokens::from_e8s(amount))
-        } else {
-            SignedTokens::Plus(Tokens::from_e8s(amount))
-        }
-    }
-}
-
 prop_compose! {
     fn arb_ts()(ts in any::<u64>()) -> TimeStamp {
         TimeStamp::from_nanos_since_unix_epoch(ts)
@@ -76,7 +61,8 @@ prop_compose! {
     fn arb_approve()(
         from in arb_account_id(),
         spender in arb_account_id(),
-        allowance in arb_signed_tokens(),
+        allowance in arb_tokens(),
+        expected_allowance in proptest::option::of(arb_tokens()),
         expires_at in proptest::option::of(arb_ts()),
         fee in 0..100_000u64,
     ) -> Operation {
@@ -84,6 +70,7 @@ prop_compose! {
             from,
             spender,
             allowance,
+            expected_allowance,
             expires_at,
             fee: Tokens::from_e8s(fee)
         }
@@ -1,10 +1,12 @@
-use candid::{candid_method, Principal};
+use candid::{candid_method, Nat, Principal};
 use ic_canisters_http_types::{HttpRequest, HttpResponse, HttpResponseBuilder};
 use ic_cdk_macros::{init, post_upgrade, query, update};
 use ic_icrc1::{
     endpoints::{GetTransactionsRequest, Transaction, TransactionRange},
+    icrc3::encoded_block_to_value,
     Block,
 };
+use ic_ledger_canister_core::icrc3::{BlockWithId, GetBlocksArgs, GetBlocksResult, ICRC3Value};
 use ic_ledger_core::block::{BlockIndex, BlockType, EncodedBlock};
 use ic_stable_structures::memory_manager::{MemoryId, VirtualMemory};
 use ic_stable_structures::{
@@ -121,6 +123,11 @@ fn decode_transaction(txid: u64, bytes: Vec<u8>) -> Transaction {
         .into()
 }
 
+fn decode_icrc3_value(index: u64, bytes: Vec<u8>) -> ICRC3Value {
+    encoded_block_to_value(&EncodedBlock::from(bytes))
+        .unwrap_or_else(|e| ic_cdk::api::trap(&format!("failed to convert block {}: {}", index, e)))
+}
+
 #[init]
 #[candid_method(init)]
 fn init(
@@ -242,6 +249,36 @@ fn get_transactions(req: GetTransactionsRequest) -> TransactionRange {
     TransactionRange { transactions }
 }
 
+#[query]
+#[candid_method(query)]
+fn icrc3_get_blocks(args: GetBlocksArgs) -> GetBlocksResult {
+    let (start, length) = args
+        .as_start_and_length()
+        .unwrap_or_else(|msg| ic_cdk::api::trap(&msg));
+
+    let (offset, max_length) =
+        with_archive_opts(|opts| (opts.block_index_offset, opts.max_transactions_per_response));
+    let length = length.min(max_length);
+
+    with_blocks(|blocks| {
+        // Blocks below the offset live in other archives, so we skip them.
+        let first = start.max(offset) - offset;
+        let limit = blocks
+            .len()
+            .min(start.saturating_add(length).saturating_sub(offset));
+        GetBlocksResult {
+            log_length: Nat::from(offset + blocks.len()),
+            blocks: (first..limit)
+                .map(|i| BlockWithId {
+                    id: Nat::from(offset + i),
+                    block: decode_icrc3_value(offset + i, blocks.get(i).unwrap()),
+                })
+                .collect(),
+            archived_blocks: vec![],
+        }
+    })
+}
+
 #[query]
 fn __get_candid_interface_tmp_hack() -> &'static str {
     include_str!(env!("ARCHIVE_DID_PATH"))
@@ -3,17 +3,22 @@ use ic_base_types::PrincipalId;
 use ic_icrc1::endpoints::{Transaction as Tx, TransactionRange};
 use ic_icrc1::{
     endpoints::{
-        ArchiveInfo, GetTransactionsRequest, GetTransactionsResponse, StandardRecord, Transfer,
-        TransferArg, TransferError, Value,
+        Allowance, AllowanceArgs, ApproveArgs, ApproveError, ArchiveInfo, GetTransactionsRequest,
+        GetTransactionsResponse, StandardRecord, Transfer, TransferArg, TransferError,
+        TransferFromArgs, TransferFromError, Value,
     },
     Account, Block, Memo, Operation, Transaction,
 };
 use ic_ledger_canister_core::archive::ArchiveOptions;
+use ic_ledger_canister_core::icrc3::{
+    GetArchivesArgs, GetBlocksArgs, GetBlocksResult, ICRC3ArchiveInfo, ICRC3Value,
+};
 use ic_ledger_core::block::{BlockIndex, BlockType, HashOf};
 use ic_state_machine_tests::{CanisterId, ErrorCode, StateMachine};
 use num_traits::ToPrimitive;
 use proptest::prelude::*;
 use proptest::test_runner::{Config as TestRunnerConfig, TestCaseResult, TestRunner};
+use serde_bytes::ByteBuf;
 use std::{
     collections::{BTreeMap, HashMap},
     time::{Duration, SystemTime},
@@ -187,6 +192,86 @@ fn transfer(
     )
 }
 
+fn send_approval(
+    env: &StateMachine,
+    ledger: CanisterId,
+    from: PrincipalId,
+    arg: &ApproveArgs,
+) -> Result<BlockIndex, ApproveError> {
+    Decode!(
+        &env.execute_ingress_as(from, ledger, "icrc2_approve", Encode!(arg).unwrap())
+            .expect("failed to apply approval")
+            .bytes(),
+        Result<Nat, ApproveError>
+    )
+    .expect("failed to decode approve response")
+    .map(|n| n.0.to_u64().unwrap())
+}
+
+fn send_transfer_from(
+    env: &StateMachine,
+    ledger: CanisterId,
+    spender: PrincipalId,
+    arg: &TransferFromArgs,
+) -> Result<BlockIndex, TransferFromError> {
+    Decode!(
+        &env.execute_ingress_as(spender, ledger, "icrc2_transfer_from", Encode!(arg).unwrap())
+            .expect("failed to transfer funds")
+            .bytes(),
+        Result<Nat, TransferFromError>
+    )
+    .expect("failed to decode transfer_from response")
+    .map(|n| n.0.to_u64().unwrap())
+}
+
+fn get_allowance(
+    env: &StateMachine,
+    ledger: CanisterId,
+    account: impl Into<Account>,
+    spender: impl Into<Account>,
+) -> Allowance {
+    let arg = AllowanceArgs {
+        account: account.into(),
+        spender: spender.into(),
+    };
+    Decode!(
+        &env.query(ledger, "icrc2_allowance", Encode!(&arg).unwrap())
+            .expect("failed to query allowance")
+            .bytes(),
+        Allowance
+    )
+    .expect("failed to decode allowance response")
+}
+
+fn default_approve_args(spender: impl Into<Account>, amount: u64) -> ApproveArgs {
+    ApproveArgs {
+        from_subaccount: None,
+        spender: spender.into(),
+        amount: Nat::from(amount),
+        expected_allowance: None,
+        expires_at: None,
+        fee: None,
+        memo: None,
+        created_at_time: None,
+    }
+}
+
+fn default_transfer_from_args(
+    from: impl Into<Account>,
+    to: impl Into<Account>,
+    amount: u64,
+) -> TransferFromArgs {
+    TransferFromArgs {
+        spender_subaccount: None,
+        from: from.into(),
+        to: to.into(),
+        amount: Nat::from(amount),
+        fee: None,
+        memo: None,
+        created_at_time: None,
+    }
+}
+
 fn list_archives(env: &StateMachine, ledger: CanisterId) -> Vec<ArchiveInfo> {
     Decode!(
         &env.query(ledger, "archives", Encode!().unwrap())
@@ -257,6 +342,46 @@ fn get_archive_transactions(
     .expect("failed to decode get_transactions archive response")
 }
 
+fn icrc3_get_blocks(
+    env: &StateMachine,
+    canister_id: CanisterId,
+    start: u64,
+    length: u64,
+) -> GetBlocksResult {
+    Decode!(
+        &env.query(
+            canister_id,
+            "icrc3_get_blocks",
+            Encode!(&GetBlocksArgs::new(start, length)).unwrap()
+        )
+        .expect("failed to query blocks")
+        .bytes(),
+        GetBlocksResult
+    )
+    .expect("failed to decode icrc3_get_blocks response")
+}
+
+fn icrc3_get_archives(
+    env: &StateMachine,
+    ledger: CanisterId,
+    from: Option<PrincipalId>,
+) -> Vec<ICRC3ArchiveInfo> {
+    Decode!(
+        &env.query(
+            ledger,
+            "icrc3_get_archives",
+            Encode!(&GetArchivesArgs {
+                from: from.map(|p| p.0)
+            })
+            .unwrap()
+        )
+        .expect("failed to query archives")
+        .bytes(),
+        Vec<ICRC3ArchiveInfo>
+    )
+    .expect("failed to decode icrc3_get_archives response")
+}
+
 pub fn total_supply(env: &StateMachine, ledger: CanisterId) -> u64 {
     Decode!(
         &env.query(ledger, "icrc1_total_supply", Encode!().unwrap())
@@ -339,15 +464,38 @@ fn arb_transfer() -> impl Strategy<Value = Operation> {
         arb_account(),
         arb_amount(),
         proptest::option::of(arb_amount()),
+        proptest::option::of(arb_account()),
     )
-        .prop_map(|(from, to, amount, fee)| Operation::Transfer {
+        .prop_map(|(from, to, amount, fee, spender)| Operation::Transfer {
             from,
             to,
+            spender,
             amount,
             fee,
         })
 }
 
+fn arb_approve() -> impl Strategy<Value = Operation> {
+    (
+        arb_account(),
+        arb_account(),
+        arb_amount(),
+        proptest::option::of(arb_amount()),
+        proptest::option::of(any::<u64>()),
+        proptest::option::of(arb_amount()),
+    )
+        .prop_map(
+            |(from, spender, amount, expected_allowance, expires_at, fee)| Operation::Approve {
+                from,
+                spender,
+                amount,
+                expected_allowance,
+                expires_at,
+                fee,
+            },
+        )
+}
+
 fn arb_mint() -> impl Strategy<Value = Operation> {
     (arb_account(), arb_amount()).prop_map(|(to, amount)| Operation::Mint { to, amount })
 }
@@ -357,7 +505,7 @@ fn arb_burn() -> impl Strategy<Value = Operation> {
 }
 
 fn arb_operation() -> impl Strategy<Value = Operation> {
-    prop_oneof![arb_transfer(), arb_mint(), arb_burn()]
+    prop_oneof![arb_transfer(), arb_mint(), arb_burn(), arb_approve()]
 }
 
 fn arb_transaction() -> impl Strategy<Value = Transaction> {
@@ -519,10 +667,16 @@ where
     let standards = supported_standards(&env, canister_id);
     assert_eq!(
         standards,
-        vec![StandardRecord {
-            name: "ICRC-1".to_string(),
-            url: "https://github.com/dfinity/ICRC-1".to_string(),
-        }]
+        vec![
+            StandardRecord {
+                name: "ICRC-1".to_string(),
+                url: "https://github.com/dfinity/ICRC-1".to_string(),
+            },
+            StandardRecord {
+                name: "ICRC-2".to_string(),
+                url: "https://github.com/dfinity/ICRC-1/tree/main/standards/ICRC-2".to_string(),
+            }
+        ]
     );
 }
 pub fn test_metadata<T>(ledger_wasm: Vec<u8>, encode_init_args: fn(InitArgs) -> T)
@@ -586,10 +740,20 @@ where
     let standards = supported_standards(&env, canister_id);
     assert_eq!(
         standards,
-        vec![StandardRecord {
-            name: "ICRC-1".to_string(),
-            url: "https://github.com/dfinity/ICRC-1".to_string(),
-        }]
+        vec![
+            StandardRecord {
+                name: "ICRC-1".to_string(),
+                url: "https://github.com/dfinity/ICRC-1".to_string(),
+            },
+            StandardRecord {
+                name: "ICRC-2".to_string(),
+                url: "https://github.com/dfinity/ICRC-1/tree/main/standards/ICRC-2".to_string(),
+            },
+            StandardRecord {
+                name: "ICRC-3".to_string(),
+                url: "https://github.com/dfinity/ICRC-1/tree/main/standards/ICRC-3".to_string(),
+            }
+        ]
     );
 }
 
@@ -644,6 +808,157 @@ where
     assert_eq!(6_000_000u64, balance_of(&env, canister_id, p2));
 }
 
+pub fn test_approve_and_transfer_from<T>(ledger_wasm: Vec<u8>, encode_init_args: fn(InitArgs) -> T)
+where
+    T: CandidType,
+{
+    let p1 = PrincipalId::new_user_test_id(1);
+    let p2 = PrincipalId::new_user_test_id(2);
+    let p3 = PrincipalId::new_user_test_id(3);
+    let (env, canister_id) = setup(
+        ledger_wasm,
+        encode_init_args,
+        vec![(Account::from(p1), 10_000_000)],
+    );
+
+    assert_eq!(
+        send_transfer_from(
+            &env,
+            canister_id,
+            p2,
+            &default_transfer_from_args(p1, p3, 1_000_000)
+        ),
+        Err(TransferFromError::InsufficientAllowance {
+            allowance: Nat::from(0u64)
+        })
+    );
+
+    send_approval(&env, canister_id, p1, &default_approve_args(p2, 3_000_000))
+        .expect("approval failed");
+
+    assert_eq!(10_000_000 - FEE, balance_of(&env, canister_id, p1));
+    assert_eq!(
+        get_allowance(&env, canister_id, p1, p2),
+        Allowance {
+            allowance: Nat::from(3_000_000u64),
+            expires_at: None
+        }
+    );
+
+    send_transfer_from(
+        &env,
+        canister_id,
+        p2,
+        &default_transfer_from_args(p1, p3, 1_000_000),
+    )
+    .expect("transfer_from failed");
+
+    assert_eq!(9_000_000 - 2 * FEE, balance_of(&env, canister_id, p1));
+    assert_eq!(0, balance_of(&env, canister_id, p2));
+    assert_eq!(1_000_000, balance_of(&env, canister_id, p3));
+    assert_eq!(
+        get_allowance(&env, canister_id, p1, p2).allowance,
+        Nat::from(2_000_000u64 - FEE)
+    );
+
+    assert_eq!(
+        send_transfer_from(
+            &env,
+            canister_id,
+            p2,
+            &default_transfer_from_args(p1, p3, 2_000_000)
+        ),
+        Err(TransferFromError::InsufficientAllowance {
+            allowance: Nat::from(2_000_000u64 - FEE)
+        })
+    );
+
+    // Approving zero tokens revokes the allowance.
+    send_approval(&env, canister_id, p1, &default_approve_args(p2, 0)).expect("approval failed");
+    assert_eq!(
+        get_allowance(&env, canister_id, p1, p2).allowance,
+        Nat::from(0u64)
+    );
+}
+
+pub fn test_approve_expected_allowance_and_expiration<T>(
+    ledger_wasm: Vec<u8>,
+    encode_init_args: fn(InitArgs) -> T,
+) where
+    T: CandidType,
+{
+    let p1 = PrincipalId::new_user_test_id(1);
+    let p2 = PrincipalId::new_user_test_id(2);
+    let (env, canister_id) = setup(
+        ledger_wasm,
+        encode_init_args,
+        vec![(Account::from(p1), 10_000_000)],
+    );
+    let now = system_time_to_nanos(env.time());
+
+    send_approval(&env, canister_id, p1, &default_approve_args(p2, 1_000_000))
+        .expect("approval failed");
+
+    assert_eq!(
+        send_approval(
+            &env,
+            canister_id,
+            p1,
+            &ApproveArgs {
+                expected_allowance: Some(Nat::from(500_000u64)),
+                ..default_approve_args(p2, 2_000_000)
+            }
+        ),
+        Err(ApproveError::AllowanceChanged {
+            current_allowance: Nat::from(1_000_000u64)
+        })
+    );
+
+    assert!(matches!(
+        send_approval(
+            &env,
+            canister_id,
+            p1,
+            &ApproveArgs {
+                expires_at: Some(now - 1),
+                ..default_approve_args(p2, 2_000_000)
+            }
+        ),
+        Err(ApproveError::Expired { .. })
+    ));
+
+    let expires_at = now + Duration::from_secs(3600).as_nanos() as u64;
+    send_approval(
+        &env,
+        canister_id,
+        p1,
+        &ApproveArgs {
+            expected_allowance: Some(Nat::from(1_000_000u64)),
+            expires_at: Some(expires_at),
+            ..default_approve_args(p2, 2_000_000)
+        },
+    )
+    .expect("approval failed");
+
+    assert_eq!(
+        get_allowance(&env, canister_id, p1, p2),
+        Allowance {
+            allowance: Nat::from(2_000_000u64),
+            expires_at: Some(expires_at)
+        }
+    );
+
+    env.advance_time(Duration::from_secs(3600));
+
+    assert_eq!(
+        get_allowance(&env, canister_id, p1, p2),
+        Allowance {
+            allowance: Nat::from(0u64),
+            expires_at: None
+        }
+    );
+}
+
 pub fn test_tx_deduplication<T>(ledger_wasm: Vec<u8>, encode_init_args: fn(InitArgs) -> T)
 where
     T: CandidType,
@@ -1087,6 +1402,7 @@ pub fn test_archiving<T>(
         let expected_tx = Transfer {
             from: p1.into(),
             to: p2.into(),
+            spender: None,
             amount: Nat::from(10_000 + i - 1),
             fee: Some(Nat::from(FEE)),
             memo: None,
@@ -1123,6 +1439,7 @@ pub fn test_archiving<T>(
             Some(Transfer {
                 from: p1.into(),
                 to: p2.into(),
+                spender: None,
                 amount: Nat::from(10_000 + i - 1),
                 fee: Some(Nat::from(FEE)),
                 memo: None,
@@ -1158,6 +1475,87 @@ pub fn test_archiving<T>(
         }
     }
 }
+pub fn test_icrc3_get_blocks<T>(ledger_wasm: Vec<u8>, encode_init_args: fn(InitArgs) -> T)
+where
+    T: CandidType,
+{
+    let p1 = PrincipalId::new_user_test_id(1);
+    let p2 = PrincipalId::new_user_test_id(2);
+
+    let (env, canister_id) = setup(
+        ledger_wasm,
+        encode_init_args,
+        vec![(Account::from(p1), 10_000_000)],
+    );
+
+    for i in 0..ARCHIVE_TRIGGER_THRESHOLD {
+        transfer(&env, canister_id, p1, p2, 10_000 + i).expect("transfer failed");
+    }
+
+    env.run_until_completion(/*max_ticks=*/ 10);
+
+    let archives = icrc3_get_archives(&env, canister_id, None);
+    assert_eq!(archives.len(), 1);
+    assert_eq!(archives[0].start, Nat::from(0));
+    assert_eq!(archives[0].end, Nat::from(NUM_BLOCKS_TO_ARCHIVE - 1));
+    let archive_id = PrincipalId(archives[0].canister_id);
+    assert_eq!(
+        icrc3_get_archives(&env, canister_id, Some(archive_id)),
+        vec![]
+    );
+
+    let resp = icrc3_get_blocks(&env, canister_id, 0, 1_000_000);
+    assert_eq!(resp.log_length, Nat::from(ARCHIVE_TRIGGER_THRESHOLD + 1));
+    assert_eq!(resp.archived_blocks.len(), 1);
+    assert_eq!(
+        resp.archived_blocks[0].args,
+        GetBlocksArgs::new(0, NUM_BLOCKS_TO_ARCHIVE)
+    );
+    assert_eq!(
+        resp.archived_blocks[0].callback.canister_id.get(),
+        archive_id
+    );
+    assert_eq!(
+        resp.blocks.len(),
+        (ARCHIVE_TRIGGER_THRESHOLD - NUM_BLOCKS_TO_ARCHIVE + 1) as usize
+    );
+
+    let archived = icrc3_get_blocks(
+        &env,
+        resp.archived_blocks[0].callback.canister_id,
+        0,
+        NUM_BLOCKS_TO_ARCHIVE,
+    );
+    assert!(archived.archived_blocks.is_empty());
+
+    let blocks: Vec<_> = archived.blocks.into_iter().chain(resp.blocks).collect();
+    assert_eq!(blocks.len(), (ARCHIVE_TRIGGER_THRESHOLD + 1) as usize);
+
+    // Check that the blocks form a hash chain.
+    for (i, block) in blocks.iter().enumerate() {
+        assert_eq!(block.id, Nat::from(i));
+        let phash = match &block.block {
+            ICRC3Value::Map(map) => map.get("phash").cloned(),
+            v => panic!("expected block {} to be a map, got {:?}", i, v),
+        };
+        let expected_phash =
+            (i > 0).then(|| ICRC3Value::Blob(ByteBuf::from(blocks[i - 1].block.hash().to_vec())));
+        assert_eq!(phash, expected_phash);
+    }
+
+    // Check that the archive handles requested ranges correctly.
+    let partial = icrc3_get_blocks(
+        &env,
+        resp.archived_blocks[0].callback.canister_id,
+        2,
+        1_000_000,
+    );
+    assert_eq!(
+        partial.blocks,
+        blocks[2..NUM_BLOCKS_TO_ARCHIVE as usize].to_vec()
+    );
+}
+
 // Generate random blocks and check that their CBOR encoding complies with the CDDL spec.
 pub fn block_encoding_agrees_with_the_schema() {
     use std::path::PathBuf;
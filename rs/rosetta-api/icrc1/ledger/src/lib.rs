@@ -5,14 +5,18 @@ use candid::{
     types::number::{Int, Nat},
     CandidType,
 };
+use ic_crypto_tree_hash::{Label, MixedHashTree};
 use ic_icrc1::endpoints::{
     ArchivedTransactionRange, GetTransactionsResponse, QueryArchiveFn, Transaction as Tx, Value,
 };
-use ic_icrc1::{Account, Block, LedgerBalances, Transaction};
+use ic_icrc1::{
+    icrc3::encoded_block_to_value, Account, Block, LedgerAllowances, LedgerBalances, Transaction,
+};
 use ic_ledger_canister_core::{
     archive::{ArchiveCanisterWasm, ArchiveOptions},
     blockchain::Blockchain,
-    ledger::{apply_transaction, block_locations, LedgerData, TransactionInfo},
+    icrc3::{self, DataCertificate, GetBlocksResult},
+    ledger::{apply_transaction, block_locations, LedgerContext, LedgerData, TransactionInfo},
     range_utils,
 };
 use ic_ledger_core::{
@@ -122,6 +126,8 @@ pub enum LedgerArgument {
 #[derive(Serialize, Deserialize, Debug)]
 pub struct Ledger {
     balances: LedgerBalances,
+    #[serde(default)]
+    approvals: LedgerAllowances,
     blockchain: Blockchain<CdkRuntime, Icrc1ArchiveWasm>,
 
     minting_account: Account,
@@ -150,6 +156,7 @@ impl Ledger {
     ) -> Self {
         let mut ledger = Self {
             balances: LedgerBalances::default(),
+            approvals: LedgerAllowances::default(),
             blockchain: Blockchain::new_with_archive(archive_options),
             transactions_by_hash: BTreeMap::new(),
             transactions_by_height: VecDeque::new(),
@@ -179,13 +186,33 @@ impl Ledger {
     }
 }
 
-impl LedgerData for Ledger {
+impl LedgerContext for Ledger {
     type AccountId = Account;
+    type BalancesStore = HashMap<Self::AccountId, Tokens>;
+    type Approvals = LedgerAllowances;
+
+    fn balances(&self) -> &Balances<Self::BalancesStore> {
+        &self.balances
+    }
+
+    fn balances_mut(&mut self) -> &mut Balances<Self::BalancesStore> {
+        &mut self.balances
+    }
+
+    fn approvals(&self) -> &Self::Approvals {
+        &self.approvals
+    }
+
+    fn approvals_mut(&mut self) -> &mut Self::Approvals {
+        &mut self.approvals
+    }
+}
+
+impl LedgerData for Ledger {
     type Runtime = CdkRuntime;
     type ArchiveWasm = Icrc1ArchiveWasm;
     type Transaction = Transaction;
     type Block = Block;
-    type BalancesStore = HashMap<Self::AccountId, Tokens>;
 
     fn transaction_window(&self) -> Duration {
         TRANSACTION_WINDOW
@@ -215,14 +242,6 @@ impl LedgerData for Ledger {
         &self.token_symbol
     }
 
-    fn balances(&self) -> &Balances<Self::BalancesStore> {
-        &self.balances
-    }
-
-    fn balances_mut(&mut self) -> &mut Balances<Self::BalancesStore> {
-        &mut self.balances
-    }
-
     fn blockchain(&self) -> &Blockchain<Self::Runtime, Self::ArchiveWasm> {
         &self.blockchain
     }
@@ -296,15 +315,60 @@ impl Ledger {
     /// The canister code must call set_certified_data with the value this function returns after
     /// each successful modification of the ledger.
     pub fn root_hash(&self) -> [u8; 32] {
-        use ic_crypto_tree_hash::{Label, MixedHashTree as T};
-        let tree = match self.blockchain().last_hash {
-            Some(hash) => T::Labeled(
-                Label::from("tip_hash"),
-                Box::new(T::Leaf(hash.as_slice().to_vec())),
-            ),
+        self.hash_tree().digest().0
+    }
+
+    /// Returns the hash tree of the certified ledger state.
+    ///
+    /// The tree contains the `last_block_index` and `last_block_hash` labels required by ICRC-3
+    /// and the legacy `tip_hash` label.
+    fn hash_tree(&self) -> MixedHashTree {
+        use MixedHashTree as T;
+        match self.blockchain().last_hash {
+            Some(hash) => {
+                let last_block_index = self.blockchain().chain_length() - 1;
+                let mut index_buf = vec![];
+                leb128::write::unsigned(&mut index_buf, last_block_index)
+                    .expect("bug: failed to encode the last block index");
+                let hash_leaf = || Box::new(T::Leaf(hash.as_slice().to_vec()));
+                T::Fork(Box::new((
+                    T::Fork(Box::new((
+                        T::Labeled(Label::from("last_block_hash"), hash_leaf()),
+                        T::Labeled(
+                            Label::from("last_block_index"),
+                            Box::new(T::Leaf(index_buf)),
+                        ),
+                    ))),
+                    T::Labeled(Label::from("tip_hash"), hash_leaf()),
+                )))
+            }
             None => T::Empty,
-        };
-        tree.digest().0
+        }
+    }
+
+    /// Returns the ICRC-3 certificate of the ledger tip given the data certificate provided by
+    /// the system.
+    pub fn tip_certificate(&self, certificate: Vec<u8>) -> DataCertificate {
+        let mut hash_tree = vec![];
+        ciborium::ser::into_writer(&self.hash_tree(), &mut hash_tree)
+            .expect("bug: failed to encode the hash tree");
+        DataCertificate {
+            certificate: ByteBuf::from(certificate),
+            hash_tree: ByteBuf::from(hash_tree),
+        }
+    }
+
+    /// Returns blocks in the specified range in the ICRC-3 format.
+    pub fn icrc3_get_blocks(&self, start: BlockIndex, length: usize) -> GetBlocksResult {
+        icrc3::get_blocks(
+            self,
+            start,
+            length,
+            MAX_TRANSACTIONS_PER_REQUEST,
+            |enc_block| {
+                encoded_block_to_value(enc_block).expect("bug: failed to convert encoded block")
+            },
+        )
     }
 
     /// Returns transactions in the specified range.
@@ -7,16 +7,20 @@ use ic_cdk::api::stable::{StableReader, StableWriter};
 use ic_cdk_macros::{init, post_upgrade, pre_upgrade, query, update};
 use ic_icrc1::{
     endpoints::{
-        ArchiveInfo, GetTransactionsRequest, GetTransactionsResponse, StandardRecord, TransferArg,
-        TransferError, Value,
+        Allowance, AllowanceArgs, ApproveArgs, ApproveError, ArchiveInfo, GetTransactionsRequest,
+        GetTransactionsResponse, StandardRecord, TransferArg, TransferError, TransferFromArgs,
+        TransferFromError, Value,
     },
     Account, Operation, Transaction,
 };
 use ic_icrc1_ledger::{Ledger, LedgerArgument};
-use ic_ledger_canister_core::ledger::{
-    apply_transaction, archive_blocks, LedgerAccess, LedgerData,
+use ic_ledger_canister_core::{
+    icrc3::{
+        self, DataCertificate, GetArchivesArgs, GetBlocksArgs, GetBlocksResult, ICRC3ArchiveInfo,
+    },
+    ledger::{apply_transaction, archive_blocks, LedgerAccess, LedgerContext, LedgerData},
 };
-use ic_ledger_core::{timestamp::TimeStamp, tokens::Tokens};
+use ic_ledger_core::{approvals::Approvals, timestamp::TimeStamp, tokens::Tokens};
 use num_traits::ToPrimitive;
 use std::cell::RefCell;
 
@@ -56,7 +60,8 @@ fn init(args: LedgerArgument) {
     match args {
         LedgerArgument::Init(init_args) => {
             let now = TimeStamp::from_nanos_since_unix_epoch(ic_cdk::api::time());
-            LEDGER.with(|cell| *cell.borrow_mut() = Some(Ledger::from_init_args(init_args, now)))
+            LEDGER.with(|cell| *cell.borrow_mut() = Some(Ledger::from_init_args(init_args, now)));
+            ic_cdk::api::set_certified_data(&Access::with_ledger(Ledger::root_hash));
         }
         LedgerArgument::Upgrade(_) => {
             panic!("Cannot initialize the canister with an Upgrade argument. Please provide an Init argument.");
@@ -89,6 +94,10 @@ fn post_upgrade(args: Option<LedgerArgument>) {
             }
         }
     }
+
+    // NB. the layout of the certified tree might change between versions, so we recompute the
+    // certified data on every upgrade.
+    ic_cdk::api::set_certified_data(&Access::with_ledger(Ledger::root_hash));
 }
 
 fn encode_metrics(w: &mut ic_metrics_encoder::MetricsEncoder<Vec<u8>>) -> std::io::Result<()> {
@@ -336,6 +345,145 @@ async fn icrc1_transfer(arg: TransferArg) -> Result<Nat, TransferError> {
     Ok(Nat::from(block_idx))
 }
 
+#[update]
+#[candid_method(update)]
+async fn icrc2_approve(arg: ApproveArgs) -> Result<Nat, ApproveError> {
+    let block_idx = Access::with_ledger_mut(|ledger| {
+        let now = TimeStamp::from_nanos_since_unix_epoch(ic_cdk::api::time());
+        let created_at_time = arg
+            .created_at_time
+            .map(TimeStamp::from_nanos_since_unix_epoch);
+
+        let from_account = Account {
+            owner: PrincipalId::from(ic_cdk::api::caller()),
+            subaccount: arg.from_subaccount,
+        };
+        if from_account.owner == arg.spender.owner {
+            ic_cdk::api::trap("self approval is not allowed");
+        }
+        if &from_account == ledger.minting_account() {
+            ic_cdk::api::trap("the minting account cannot delegate mints");
+        }
+
+        // Allowances larger than the total supply are indistinguishable
+        // from the maximum allowance.
+        let amount = arg
+            .amount
+            .0
+            .to_u64()
+            .map(Tokens::from_e8s)
+            .unwrap_or(Tokens::MAX);
+        let expected_allowance = match arg.expected_allowance {
+            Some(n) => match n.0.to_u64() {
+                Some(n) => Some(Tokens::from_e8s(n)),
+                None => {
+                    let current_allowance = ledger
+                        .approvals()
+                        .allowance(&from_account, &arg.spender, now)
+                        .amount;
+                    return Err(ApproveError::AllowanceChanged {
+                        current_allowance: Nat::from(current_allowance.get_e8s()),
+                    });
+                }
+            },
+            None => None,
+        };
+
+        let expected_fee_tokens = ledger.transfer_fee();
+        let expected_fee = Nat::from(expected_fee_tokens.get_e8s());
+        if arg.fee.is_some() && arg.fee.as_ref() != Some(&expected_fee) {
+            return Err(ApproveError::BadFee { expected_fee });
+        }
+
+        let tx = Transaction::approve(
+            from_account,
+            arg.spender,
+            amount,
+            expected_allowance,
+            arg.expires_at.map(TimeStamp::from_nanos_since_unix_epoch),
+            arg.fee.map(|_| expected_fee_tokens),
+            created_at_time,
+            arg.memo,
+        );
+
+        let (block_idx, _) = apply_transaction(ledger, tx, now, expected_fee_tokens)?;
+        Ok(block_idx)
+    })?;
+
+    ic_cdk::api::set_certified_data(&Access::with_ledger(Ledger::root_hash));
+
+    archive_blocks::<Access>(&LOG, MAX_MESSAGE_SIZE).await;
+    Ok(Nat::from(block_idx))
+}
+
+#[update]
+#[candid_method(update)]
+async fn icrc2_transfer_from(arg: TransferFromArgs) -> Result<Nat, TransferFromError> {
+    let block_idx = Access::with_ledger_mut(|ledger| {
+        let now = TimeStamp::from_nanos_since_unix_epoch(ic_cdk::api::time());
+        let created_at_time = arg
+            .created_at_time
+            .map(TimeStamp::from_nanos_since_unix_epoch);
+
+        let spender = Account {
+            owner: PrincipalId::from(ic_cdk::api::caller()),
+            subaccount: arg.spender_subaccount,
+        };
+        if &arg.from == ledger.minting_account() || &arg.to == ledger.minting_account() {
+            ic_cdk::api::trap("transfer_from cannot mint or burn tokens");
+        }
+
+        let amount = match arg.amount.0.to_u64() {
+            Some(n) => Tokens::from_e8s(n),
+            None => {
+                // No one can have so many tokens
+                let balance = Nat::from(ledger.balances().account_balance(&arg.from).get_e8s());
+                assert!(balance < arg.amount);
+                return Err(TransferFromError::InsufficientFunds { balance });
+            }
+        };
+
+        let expected_fee_tokens = ledger.transfer_fee();
+        let expected_fee = Nat::from(expected_fee_tokens.get_e8s());
+        if arg.fee.is_some() && arg.fee.as_ref() != Some(&expected_fee) {
+            return Err(TransferFromError::BadFee { expected_fee });
+        }
+
+        let tx = Transaction::transfer_from(
+            arg.from,
+            arg.to,
+            spender,
+            amount,
+            arg.fee.map(|_| expected_fee_tokens),
+            created_at_time,
+            arg.memo,
+        );
+
+        let (block_idx, _) = apply_transaction(ledger, tx, now, expected_fee_tokens)?;
+        Ok(block_idx)
+    })?;
+
+    ic_cdk::api::set_certified_data(&Access::with_ledger(Ledger::root_hash));
+
+    archive_blocks::<Access>(&LOG, MAX_MESSAGE_SIZE).await;
+    Ok(Nat::from(block_idx))
+}
+
+#[query]
+#[candid_method(query)]
+fn icrc2_allowance(arg: AllowanceArgs) -> Allowance {
+    Access::with_ledger(|ledger| {
+        let now = TimeStamp::from_nanos_since_unix_epoch(ic_cdk::api::time());
+        let allowance = ledger
+            .approvals()
+            .allowance(&arg.account, &arg.spender, now);
+        Allowance {
+            allowance: Nat::from(allowance.amount.get_e8s()),
+            expires_at: allowance.expires_at.map(|t| t.as_nanos_since_unix_epoch()),
+        }
+    })
+}
+
 #[query]
 fn archives() -> Vec<ArchiveInfo> {
     Access::with_ledger(|ledger| {
@@ -363,10 +511,20 @@ fn archives() -> Vec<ArchiveInfo> {
 #[query(name = "icrc1_supported_standards")]
 #[candid_method(query, rename = "icrc1_supported_standards")]
 fn supported_standards() -> Vec<StandardRecord> {
-    vec![StandardRecord {
-        name: "ICRC-1".to_string(),
-        url: "https://github.com/dfinity/ICRC-1".to_string(),
-    }]
+    vec![
+        StandardRecord {
+            name: "ICRC-1".to_string(),
+            url: "https://github.com/dfinity/ICRC-1".to_string(),
+        },
+        StandardRecord {
+            name: "ICRC-2".to_string(),
+            url: "https://github.com/dfinity/ICRC-1/tree/main/standards/ICRC-2".to_string(),
+        },
+        StandardRecord {
+            name: "ICRC-3".to_string(),
+            url: "https://github.com/dfinity/ICRC-1/tree/main/standards/ICRC-3".to_string(),
+        },
+    ]
 }
 
 #[query]
@@ -378,6 +536,30 @@ fn get_transactions(req: GetTransactionsRequest) -> GetTransactionsResponse {
     Access::with_ledger(|ledger| ledger.get_transactions(start, length as usize))
 }
 
+#[query]
+#[candid_method(query)]
+fn icrc3_get_blocks(args: GetBlocksArgs) -> GetBlocksResult {
+    let (start, length) = args
+        .as_start_and_length()
+        .unwrap_or_else(|msg| ic_cdk::api::trap(&msg));
+    Access::with_ledger(|ledger| ledger.icrc3_get_blocks(start, length as usize))
+}
+
+#[query]
+#[candid_method(query)]
+fn icrc3_get_tip_certificate() -> Option<DataCertificate> {
+    let certificate = ic_cdk::api::data_certificate()?;
+    Some(Access::with_ledger(|ledger| {
+        ledger.tip_certificate(certificate)
+    }))
+}
+
+#[query]
+#[candid_method(query)]
+fn icrc3_get_archives(args: GetArchivesArgs) -> Vec<ICRC3ArchiveInfo> {
+    Access::with_ledger(|ledger| icrc3::get_archives(ledger, args))
+}
+
 candid::export_service!();
 
 #[query]
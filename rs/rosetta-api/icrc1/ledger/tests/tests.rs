@@ -77,6 +77,19 @@ fn test_single_transfer() {
     ic_icrc1_ledger_sm_tests::test_single_transfer(ledger_wasm(), encode_init_args);
 }
 
+#[test]
+fn test_approve_and_transfer_from() {
+    ic_icrc1_ledger_sm_tests::test_approve_and_transfer_from(ledger_wasm(), encode_init_args);
+}
+
+#[test]
+fn test_approve_expected_allowance_and_expiration() {
+    ic_icrc1_ledger_sm_tests::test_approve_expected_allowance_and_expiration(
+        ledger_wasm(),
+        encode_init_args,
+    );
+}
+
 #[test]
 fn test_account_canonicalization() {
     ic_icrc1_ledger_sm_tests::test_account_canonicalization(ledger_wasm(), encode_init_args);
@@ -97,6 +110,11 @@ fn test_archiving() {
     ic_icrc1_ledger_sm_tests::test_archiving(ledger_wasm(), encode_init_args, archive_wasm());
 }
 // Generate random blocks and check that their CBOR encoding complies with the CDDL spec.
+#[test]
+fn test_icrc3_get_blocks() {
+    ic_icrc1_ledger_sm_tests::test_icrc3_get_blocks(ledger_wasm(), encode_init_args);
+}
+
 #[test]
 fn block_encoding_agrees_with_the_schema() {
     ic_icrc1_ledger_sm_tests::block_encoding_agrees_with_the_schema();
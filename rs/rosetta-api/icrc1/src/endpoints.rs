@@ -42,10 +42,19 @@ impl From<CoreTransferError> for TransferError {
             LTE::TxDuplicate { duplicate_of } => TE::Duplicate {
                 duplicate_of: Nat::from(duplicate_of),
             },
+            LTE::InsufficientAllowance { .. }
+            | LTE::ExpiredApproval { .. }
+            | LTE::AllowanceChanged { .. } => TE::GenericError {
+                error_code: Nat::from(GENERIC_ERROR_CODE),
+                message: format!("unexpected approval error: {:?}", err),
+            },
         }
     }
 }
 
+/// The error code the ledger uses for errors that do not have a dedicated variant.
+const GENERIC_ERROR_CODE: u64 = 0;
+
 #[derive(CandidType, Deserialize, Clone, Debug, PartialEq, Eq)]
 pub struct TransferArg {
     #[serde(default)]
@@ -60,6 +69,143 @@ pub struct TransferArg {
     pub amount: NumTokens,
 }
 
+#[derive(CandidType, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ApproveArgs {
+    #[serde(default)]
+    pub from_subaccount: Option<Subaccount>,
+    pub spender: Account,
+    pub amount: NumTokens,
+    #[serde(default)]
+    pub expected_allowance: Option<NumTokens>,
+    #[serde(default)]
+    pub expires_at: Option<u64>,
+    #[serde(default)]
+    pub fee: Option<NumTokens>,
+    #[serde(default)]
+    pub memo: Option<Memo>,
+    #[serde(default)]
+    pub created_at_time: Option<u64>,
+}
+
+#[derive(CandidType, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub enum ApproveError {
+    BadFee { expected_fee: NumTokens },
+    InsufficientFunds { balance: NumTokens },
+    AllowanceChanged { current_allowance: NumTokens },
+    Expired { ledger_time: u64 },
+    TooOld,
+    CreatedInFuture { ledger_time: u64 },
+    Duplicate { duplicate_of: BlockIndex },
+    TemporarilyUnavailable,
+    GenericError { error_code: Nat, message: String },
+}
+
+impl From<CoreTransferError> for ApproveError {
+    fn from(err: CoreTransferError) -> Self {
+        use ic_ledger_canister_core::ledger::TransferError as LTE;
+        use ApproveError as AE;
+
+        match err {
+            LTE::BadFee { expected_fee } => AE::BadFee {
+                expected_fee: Nat::from(expected_fee.get_e8s()),
+            },
+            LTE::InsufficientFunds { balance } => AE::InsufficientFunds {
+                balance: Nat::from(balance.get_e8s()),
+            },
+            LTE::AllowanceChanged { current_allowance } => AE::AllowanceChanged {
+                current_allowance: Nat::from(current_allowance.get_e8s()),
+            },
+            LTE::ExpiredApproval { now } => AE::Expired {
+                ledger_time: now.as_nanos_since_unix_epoch(),
+            },
+            LTE::TxTooOld { .. } => AE::TooOld,
+            LTE::TxCreatedInFuture { ledger_time } => AE::CreatedInFuture {
+                ledger_time: ledger_time.as_nanos_since_unix_epoch(),
+            },
+            LTE::TxThrottled => AE::TemporarilyUnavailable,
+            LTE::TxDuplicate { duplicate_of } => AE::Duplicate {
+                duplicate_of: Nat::from(duplicate_of),
+            },
+            LTE::InsufficientAllowance { .. } => AE::GenericError {
+                error_code: Nat::from(GENERIC_ERROR_CODE),
+                message: format!("unexpected approval error: {:?}", err),
+            },
+        }
+    }
+}
+
+#[derive(CandidType, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct TransferFromArgs {
+    #[serde(default)]
+    pub spender_subaccount: Option<Subaccount>,
+    pub from: Account,
+    pub to: Account,
+    pub amount: NumTokens,
+    #[serde(default)]
+    pub fee: Option<NumTokens>,
+    #[serde(default)]
+    pub memo: Option<Memo>,
+    #[serde(default)]
+    pub created_at_time: Option<u64>,
+}
+
+#[derive(CandidType, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub enum TransferFromError {
+    BadFee { expected_fee: NumTokens },
+    BadBurn { min_burn_amount: NumTokens },
+    InsufficientFunds { balance: NumTokens },
+    InsufficientAllowance { allowance: NumTokens },
+    TooOld,
+    CreatedInFuture { ledger_time: u64 },
+    Duplicate { duplicate_of: BlockIndex },
+    TemporarilyUnavailable,
+    GenericError { error_code: Nat, message: String },
+}
+
+impl From<CoreTransferError> for TransferFromError {
+    fn from(err: CoreTransferError) -> Self {
+        use ic_ledger_canister_core::ledger::TransferError as LTE;
+        use TransferFromError as TFE;
+
+        match err {
+            LTE::BadFee { expected_fee } => TFE::BadFee {
+                expected_fee: Nat::from(expected_fee.get_e8s()),
+            },
+            LTE::InsufficientFunds { balance } => TFE::InsufficientFunds {
+                balance: Nat::from(balance.get_e8s()),
+            },
+            LTE::InsufficientAllowance { allowance } => TFE::InsufficientAllowance {
+                allowance: Nat::from(allowance.get_e8s()),
+            },
+            LTE::TxTooOld { .. } => TFE::TooOld,
+            LTE::TxCreatedInFuture { ledger_time } => TFE::CreatedInFuture {
+                ledger_time: ledger_time.as_nanos_since_unix_epoch(),
+            },
+            LTE::TxThrottled => TFE::TemporarilyUnavailable,
+            LTE::TxDuplicate { duplicate_of } => TFE::Duplicate {
+                duplicate_of: Nat::from(duplicate_of),
+            },
+            LTE::ExpiredApproval { .. } | LTE::AllowanceChanged { .. } => TFE::GenericError {
+                error_code: Nat::from(GENERIC_ERROR_CODE),
+                message: format!("unexpected approval error: {:?}", err),
+            },
+        }
+    }
+}
+
+#[derive(CandidType, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct AllowanceArgs {
+    pub account: Account,
+    pub spender: Account,
+}
+
+#[derive(CandidType, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct Allowance {
+    pub allowance: NumTokens,
+    #[serde(default)]
+    pub expires_at: Option<u64>,
+}
+
 /// Variant type for the `metadata` endpoint values.
 #[derive(CandidType, Deserialize, Clone, Debug, PartialEq, Eq)]
 pub enum Value {
@@ -187,6 +333,19 @@ pub struct Transfer {
     pub amount: Nat,
     pub from: Account,
     pub to: Account,
+    pub spender: Option<Account>,
+    pub memo: Option<Memo>,
+    pub fee: Option<Nat>,
+    pub created_at_time: Option<u64>,
+}
+
+#[derive(CandidType, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct Approve {
+    pub from: Account,
+    pub spender: Account,
+    pub amount: Nat,
+    pub expected_allowance: Option<Nat>,
+    pub expires_at: Option<u64>,
     pub memo: Option<Memo>,
     pub fee: Option<Nat>,
     pub created_at_time: Option<u64>,
@@ -198,6 +357,7 @@ pub struct Transaction {
     pub mint: Option<Mint>,
     pub burn: Option<Burn>,
     pub transfer: Option<Transfer>,
+    pub approve: Option<Approve>,
     pub timestamp: u64,
 }
 
@@ -276,6 +436,7 @@ impl From<Block> for Transaction {
             mint: None,
             burn: None,
             transfer: None,
+            approve: None,
             timestamp: b.timestamp,
         };
         let created_at_time = b.transaction.created_at_time;
@@ -303,6 +464,7 @@ impl From<Block> for Transaction {
             Operation::Transfer {
                 from,
                 to,
+                spender,
                 amount,
                 fee,
             } => {
@@ -310,7 +472,30 @@ impl From<Block> for Transaction {
                 tx.transfer = Some(Transfer {
                     from,
                     to,
+                    spender,
+                    amount: Nat::from(amount),
+                    fee: fee
+                        .map(Nat::from)
+                        .or_else(|| b.effective_fee.map(Nat::from)),
+                    created_at_time,
+                    memo,
+                });
+            }
+            Operation::Approve {
+                from,
+                spender,
+                amount,
+                expected_allowance,
+                expires_at,
+                fee,
+            } => {
+                tx.kind = "approve".to_string();
+                tx.approve = Some(Approve {
+                    from,
+                    spender,
                     amount: Nat::from(amount),
+                    expected_allowance: expected_allowance.map(Nat::from),
+                    expires_at,
                     fee: fee
                         .map(Nat::from)
                         .or_else(|| b.effective_fee.map(Nat::from)),
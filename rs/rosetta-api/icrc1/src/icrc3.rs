@@ -0,0 +1,163 @@
+use candid::{Int, Nat};
+use ciborium::value::Value as CborValue;
+use ic_ledger_canister_core::icrc3::ICRC3Value;
+use ic_ledger_core::block::EncodedBlock;
+use num_traits::ToPrimitive;
+use serde_bytes::ByteBuf;
+use std::collections::BTreeMap;
+
+/// Converts an encoded block into its ICRC-3 generic representation.
+///
+/// The CBOR encoding of ICRC-1 blocks maps one-to-one onto ICRC-3 values, so
+/// the representation-independent hash of the resulting value is equal to the
+/// block hash and the `phash` fields form a valid ICRC-3 hash chain.
+pub fn encoded_block_to_value(block: &EncodedBlock) -> Result<ICRC3Value, String> {
+    let value: CborValue = ciborium::de::from_reader(block.as_slice())
+        .map_err(|e| format!("failed to decode a block: {}", e))?;
+    cbor_to_value(value)
+}
+
+fn cbor_to_value(value: CborValue) -> Result<ICRC3Value, String> {
+    match value {
+        CborValue::Integer(int) => {
+            let v: i128 = int.into();
+            if v < 0 {
+                Ok(ICRC3Value::Int(Int::from(v)))
+            } else {
+                Ok(ICRC3Value::Nat(Nat::from(v as u128)))
+            }
+        }
+        CborValue::Bytes(bytes) => Ok(ICRC3Value::Blob(ByteBuf::from(bytes))),
+        CborValue::Text(text) => Ok(ICRC3Value::Text(text)),
+        CborValue::Tag(_tag, value) => cbor_to_value(*value),
+        CborValue::Array(values) => values
+            .into_iter()
+            .map(cbor_to_value)
+            .collect::<Result<Vec<_>, _>>()
+            .map(ICRC3Value::Array),
+        CborValue::Map(entries) => {
+            let mut map = BTreeMap::new();
+            for (k, v) in entries {
+                let key = match k {
+                    CborValue::Text(key) => key,
+                    _ => return Err(format!("map key {:?} is not a string", k)),
+                };
+                map.insert(key, cbor_to_value(v)?);
+            }
+            Ok(ICRC3Value::Map(map))
+        }
+        _ => Err(format!("unsupported value type: {:?}", value)),
+    }
+}
+
+/// Converts an ICRC-3 block value back into the CBOR encoding of ICRC-1 blocks.
+///
+/// This is the inverse of [encoded_block_to_value]: map keys are written in
+/// their lexicographic order, so the resulting bytes may differ from the
+/// ledger's encoding, but the block hash is the same.
+pub fn value_to_encoded_block(value: &ICRC3Value) -> Result<EncodedBlock, String> {
+    let value = CborValue::Tag(SELF_DESCRIBED_CBOR_TAG, Box::new(value_to_cbor(value)?));
+    let mut bytes = vec![];
+    ciborium::ser::into_writer(&value, &mut bytes)
+        .map_err(|e| format!("failed to encode a block: {}", e))?;
+    Ok(EncodedBlock::from_vec(bytes))
+}
+
+const SELF_DESCRIBED_CBOR_TAG: u64 = 55799;
+
+fn value_to_cbor(value: &ICRC3Value) -> Result<CborValue, String> {
+    match value {
+        ICRC3Value::Nat(nat) => nat
+            .0
+            .to_u64()
+            .map(|n| CborValue::Integer(n.into()))
+            .ok_or_else(|| format!("nat {} does not fit into u64", nat)),
+        ICRC3Value::Int(int) => int
+            .0
+            .to_i64()
+            .map(|n| CborValue::Integer(n.into()))
+            .ok_or_else(|| format!("int {} does not fit into i64", int)),
+        ICRC3Value::Blob(bytes) => Ok(CborValue::Bytes(bytes.to_vec())),
+        ICRC3Value::Text(text) => Ok(CborValue::Text(text.clone())),
+        ICRC3Value::Array(values) => values
+            .iter()
+            .map(value_to_cbor)
+            .collect::<Result<Vec<_>, _>>()
+            .map(CborValue::Array),
+        ICRC3Value::Map(map) => map
+            .iter()
+            .map(|(k, v)| Ok((CborValue::Text(k.clone()), value_to_cbor(v)?)))
+            .collect::<Result<Vec<_>, String>>()
+            .map(CborValue::Map),
+    }
+}
+
+#[test]
+fn check_block_to_value() {
+    use crate::{Account, Block, Transaction};
+    use ic_base_types::PrincipalId;
+    use ic_ledger_core::block::BlockType;
+    use ic_ledger_core::{timestamp::TimeStamp, tokens::Tokens};
+
+    let from = Account {
+        owner: PrincipalId::new_user_test_id(1),
+        subaccount: None,
+    };
+    let to = Account {
+        owner: PrincipalId::new_user_test_id(2),
+        subaccount: Some([1; 32]),
+    };
+
+    let parent = Block::from_transaction(
+        None,
+        Transaction::mint(from, Tokens::from_e8s(1_000_000), None, None),
+        TimeStamp::from_nanos_since_unix_epoch(1),
+        Tokens::ZERO,
+    )
+    .encode();
+    let block = Block::from_transaction(
+        Some(Block::block_hash(&parent)),
+        Transaction::transfer(from, to, Tokens::from_e8s(1_000), None, None, None),
+        TimeStamp::from_nanos_since_unix_epoch(2),
+        Tokens::from_e8s(10),
+    )
+    .encode();
+
+    let value = encoded_block_to_value(&block).unwrap();
+    assert_eq!(&value.hash()[..], Block::block_hash(&block).as_slice());
+
+    let reencoded = value_to_encoded_block(&value).unwrap();
+    assert_eq!(Block::block_hash(&reencoded), Block::block_hash(&block));
+    assert_eq!(
+        Block::decode(reencoded).unwrap(),
+        Block::decode(block).unwrap()
+    );
+
+    let map = match value {
+        ICRC3Value::Map(map) => map,
+        v => panic!("expected a map, got {:?}", v),
+    };
+
+    assert_eq!(
+        map.get("phash"),
+        Some(&ICRC3Value::Blob(ByteBuf::from(
+            Block::block_hash(&parent).as_slice().to_vec()
+        )))
+    );
+    assert_eq!(map.get("ts"), Some(&ICRC3Value::from(2u64)));
+    assert_eq!(map.get("fee"), Some(&ICRC3Value::from(10u64)));
+
+    let tx = match map.get("tx") {
+        Some(ICRC3Value::Map(tx)) => tx,
+        v => panic!("expected a transaction map, got {:?}", v),
+    };
+    assert_eq!(tx.get("op"), Some(&ICRC3Value::from("xfer")));
+    assert_eq!(tx.get("amt"), Some(&ICRC3Value::from(1_000u64)));
+    assert_eq!(
+        tx.get("to"),
+        Some(&ICRC3Value::Array(vec![
+            ICRC3Value::Blob(ByteBuf::from(to.owner.as_slice().to_vec())),
+            ICRC3Value::Blob(ByteBuf::from(vec![1; 32])),
+        ]))
+    );
+}
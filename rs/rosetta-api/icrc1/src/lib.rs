@@ -1,12 +1,14 @@
 pub mod endpoints;
 pub mod hash;
+pub mod icrc3;
 
 use candid::CandidType;
 use ciborium::tag::Required;
 use ic_base_types::PrincipalId;
-use ic_ledger_canister_core::ledger::LedgerTransaction;
+use ic_ledger_canister_core::ledger::{LedgerContext, LedgerTransaction, TxApplyError};
 use ic_ledger_core::{
-    balances::{BalanceError, Balances, BalancesStore},
+    approvals::{AllowanceTable, Approvals},
+    balances::Balances,
     block::{BlockType, EncodedBlock, HashOf},
     timestamp::TimeStamp,
     tokens::Tokens,
@@ -16,6 +18,7 @@ use serde_bytes::ByteBuf;
 use std::collections::HashMap;
 use std::convert::TryFrom;
 use std::fmt;
+use std::str::FromStr;
 
 pub type Subaccount = [u8; 32];
 
@@ -74,6 +77,34 @@ impl fmt::Display for Account {
     }
 }
 
+impl FromStr for Account {
+    type Err = String;
+
+    /// Parses the textual representation produced by `Display`: either `<owner>` or
+    /// `0x<hex subaccount>.<owner>`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.strip_prefix("0x").and_then(|rest| rest.split_once('.')) {
+            Some((subaccount, owner)) => {
+                let bytes = hex::decode(subaccount)
+                    .map_err(|e| format!("invalid subaccount {}: {}", subaccount, e))?;
+                let subaccount = Subaccount::try_from(&bytes[..]).map_err(|_| {
+                    format!("subaccount must be 32 bytes long, got {}", bytes.len())
+                })?;
+                Ok(Self {
+                    owner: PrincipalId::from_str(owner)
+                        .map_err(|e| format!("invalid owner {}: {}", owner, e))?,
+                    subaccount: Some(subaccount),
+                })
+            }
+            None => Ok(Self {
+                owner: PrincipalId::from_str(s)
+                    .map_err(|e| format!("invalid principal {}: {}", s, e))?,
+                subaccount: None,
+            }),
+        }
+    }
+}
+
 impl From<PrincipalId> for Account {
     fn from(owner: PrincipalId) -> Self {
         Self {
@@ -99,6 +130,24 @@ where
     Account::try_from(compact_account).map_err(D::Error::custom)
 }
 
+fn ser_opt_compact_account<S>(acc: &Option<Account>, s: S) -> Result<S::Ok, S::Error>
+where
+    S: serde::ser::Serializer,
+{
+    acc.map(CompactAccount::from).serialize(s)
+}
+
+fn de_opt_compact_account<'de, D>(d: D) -> Result<Option<Account>, D::Error>
+where
+    D: serde::de::Deserializer<'de>,
+{
+    use serde::de::Error;
+    Option::<CompactAccount>::deserialize(d)?
+        .map(Account::try_from)
+        .transpose()
+        .map_err(D::Error::custom)
+}
+
 /// A compact representation of an Account.
 ///
 /// Instead of encoding accounts as structs with named fields,
@@ -174,11 +223,35 @@ pub enum Operation {
         #[serde(serialize_with = "ser_compact_account")]
         #[serde(deserialize_with = "de_compact_account")]
         to: Account,
+        #[serde(default)]
+        #[serde(skip_serializing_if = "Option::is_none")]
+        #[serde(serialize_with = "ser_opt_compact_account")]
+        #[serde(deserialize_with = "de_opt_compact_account")]
+        spender: Option<Account>,
         #[serde(rename = "amt")]
         amount: u64,
         #[serde(skip_serializing_if = "Option::is_none")]
         fee: Option<u64>,
     },
+    #[serde(rename = "approve")]
+    Approve {
+        #[serde(serialize_with = "ser_compact_account")]
+        #[serde(deserialize_with = "de_compact_account")]
+        from: Account,
+        #[serde(serialize_with = "ser_compact_account")]
+        #[serde(deserialize_with = "de_compact_account")]
+        spender: Account,
+        #[serde(rename = "amt")]
+        amount: u64,
+        #[serde(default)]
+        #[serde(skip_serializing_if = "Option::is_none")]
+        expected_allowance: Option<u64>,
+        #[serde(default)]
+        #[serde(skip_serializing_if = "Option::is_none")]
+        expires_at: Option<u64>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        fee: Option<u64>,
+    },
     #[serde(rename = "burn")]
     Burn {
         #[serde(serialize_with = "ser_compact_account")]
@@ -309,28 +382,71 @@ impl LedgerTransaction for Transaction {
             })
     }
 
-    fn apply<S>(
+    fn apply<C>(
         &self,
-        balances: &mut Balances<S>,
+        context: &mut C,
+        now: TimeStamp,
         effective_fee: Tokens,
-    ) -> Result<(), BalanceError>
+    ) -> Result<(), TxApplyError>
     where
-        S: Default + BalancesStore<AccountId = Self::AccountId>,
+        C: LedgerContext<AccountId = Self::AccountId>,
     {
         match &self.operation {
             Operation::Transfer {
                 from,
                 to,
+                spender,
                 amount,
                 fee,
-            } => balances.transfer(
+            } => {
+                let amount = Tokens::from_e8s(*amount);
+                let fee = fee.map(Tokens::from_e8s).unwrap_or(effective_fee);
+                if let Some(spender) = spender {
+                    let balance = context.balances().account_balance(from);
+                    let required =
+                        (amount + fee).map_err(|_| TxApplyError::InsufficientFunds { balance })?;
+                    if balance < required {
+                        return Err(TxApplyError::InsufficientFunds { balance });
+                    }
+                    context
+                        .approvals_mut()
+                        .use_allowance(from, spender, required, now)?;
+                }
+                Ok(context.balances_mut().transfer(from, to, amount, fee)?)
+            }
+            Operation::Approve {
                 from,
-                to,
-                Tokens::from_e8s(*amount),
-                fee.map(Tokens::from_e8s).unwrap_or(effective_fee),
-            ),
-            Operation::Burn { from, amount } => balances.burn(from, Tokens::from_e8s(*amount)),
-            Operation::Mint { to, amount } => balances.mint(to, Tokens::from_e8s(*amount)),
+                spender,
+                amount,
+                expected_allowance,
+                expires_at,
+                fee,
+            } => {
+                let fee = fee.map(Tokens::from_e8s).unwrap_or(effective_fee);
+                let balance = context.balances().account_balance(from);
+                if balance < fee {
+                    return Err(TxApplyError::InsufficientFunds { balance });
+                }
+                context.approvals_mut().approve(
+                    from,
+                    spender,
+                    Tokens::from_e8s(*amount),
+                    expected_allowance.map(Tokens::from_e8s),
+                    expires_at.map(TimeStamp::from_nanos_since_unix_epoch),
+                    now,
+                )?;
+                context
+                    .balances_mut()
+                    .burn(from, fee)
+                    .expect("bug: cannot pay the approval fee");
+                Ok(())
+            }
+            Operation::Burn { from, amount } => Ok(context
+                .balances_mut()
+                .burn(from, Tokens::from_e8s(*amount))?),
+            Operation::Mint { to, amount } => {
+                Ok(context.balances_mut().mint(to, Tokens::from_e8s(*amount))?)
+            }
         }
     }
 }
@@ -364,6 +480,7 @@ impl Transaction {
             operation: Operation::Transfer {
                 from,
                 to,
+                spender: None,
                 amount: amount.get_e8s(),
                 fee: fee.map(Tokens::get_e8s),
             },
@@ -371,6 +488,54 @@ impl Transaction {
             memo,
         }
     }
+
+    /// Constructs a transfer that the spender executes on behalf of the `from` account.
+    pub fn transfer_from(
+        from: Account,
+        to: Account,
+        spender: Account,
+        amount: Tokens,
+        fee: Option<Tokens>,
+        created_at_time: Option<TimeStamp>,
+        memo: Option<Memo>,
+    ) -> Self {
+        Self {
+            operation: Operation::Transfer {
+                from,
+                to,
+                spender: Some(spender),
+                amount: amount.get_e8s(),
+                fee: fee.map(Tokens::get_e8s),
+            },
+            created_at_time: created_at_time.map(|t| t.as_nanos_since_unix_epoch()),
+            memo,
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn approve(
+        from: Account,
+        spender: Account,
+        amount: Tokens,
+        expected_allowance: Option<Tokens>,
+        expires_at: Option<TimeStamp>,
+        fee: Option<Tokens>,
+        created_at_time: Option<TimeStamp>,
+        memo: Option<Memo>,
+    ) -> Self {
+        Self {
+            operation: Operation::Approve {
+                from,
+                spender,
+                amount: amount.get_e8s(),
+                expected_allowance: expected_allowance.map(Tokens::get_e8s),
+                expires_at: expires_at.map(|t| t.as_nanos_since_unix_epoch()),
+                fee: fee.map(Tokens::get_e8s),
+            },
+            created_at_time: created_at_time.map(|t| t.as_nanos_since_unix_epoch()),
+            memo,
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone, Hash, Debug, PartialEq, Eq, PartialOrd, Ord)]
@@ -432,10 +597,11 @@ impl BlockType for Block {
         timestamp: TimeStamp,
         effective_fee: Tokens,
     ) -> Self {
-        let effective_fee = if let Operation::Transfer { fee, .. } = &transaction.operation {
-            fee.is_none().then_some(effective_fee.get_e8s())
-        } else {
-            None
+        let effective_fee = match &transaction.operation {
+            Operation::Transfer { fee, .. } | Operation::Approve { fee, .. } => {
+                fee.is_none().then_some(effective_fee.get_e8s())
+            }
+            _ => None,
         };
         Self {
             parent_hash,
@@ -447,3 +613,34 @@ impl BlockType for Block {
 }
 
 pub type LedgerBalances = Balances<HashMap<Account, Tokens>>;
+
+/// The key of the allowance table: the pair of the approving account and the spender.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct ApprovalKey(Account, Account);
+
+impl From<(&Account, &Account)> for ApprovalKey {
+    fn from((account, spender): (&Account, &Account)) -> Self {
+        Self(*account, *spender)
+    }
+}
+
+pub type LedgerAllowances = AllowanceTable<ApprovalKey, Account, Account>;
+
+#[test]
+fn check_account_display_from_str_roundtrip() {
+    let accounts = [
+        Account {
+            owner: PrincipalId::new_user_test_id(1),
+            subaccount: None,
+        },
+        Account {
+            owner: PrincipalId::new_user_test_id(2),
+            subaccount: Some([7; 32]),
+        },
+    ];
+    for account in accounts {
+        assert_eq!(Account::from_str(&account.to_string()), Ok(account));
+    }
+    assert!(Account::from_str("0x0102.aaaaa-aa").is_err());
+    assert!(Account::from_str("not a principal").is_err());
+}
@@ -1,5 +1,7 @@
-use ic_ledger_core::block::{BlockIndex, BlockType, EncodedBlock, HashOf};
-use icp_ledger::{AccountIdentifier, Block, Tokens};
+use crate::checkpoint::Checkpoint;
+use crate::ledger_kind::{icrc1_account_key, LedgerKind};
+use ic_ledger_core::block::{BlockIndex, EncodedBlock, HashOf};
+use icp_ledger::{AccountIdentifier, Tokens};
 use rusqlite::params;
 use serde::{Deserialize, Serialize};
 use std::convert::TryInto;
@@ -9,6 +11,8 @@ use std::sync::Mutex;
 mod database_access {
     use super::vec_into_array;
     use crate::blocks::{BlockStoreError, HashedBlock};
+    use crate::ledger_kind::{icrc1_account_key, LedgerKind};
+    use ic_icrc1::{Account as Icrc1Account, Operation as Icrc1Operation};
     use ic_ledger_canister_core::ledger::LedgerTransaction;
     use ic_ledger_core::{
         block::{BlockType, EncodedBlock, HashOf},
@@ -16,6 +20,7 @@ mod database_access {
     };
     use icp_ledger::{AccountIdentifier, Block, Operation};
     use rusqlite::{params, types::Null, Connection, Error, Statement};
+    use std::collections::BTreeMap;
 
     pub fn push_hashed_block(
         con: &mut Connection,
@@ -45,13 +50,91 @@ mod database_access {
 
     pub fn push_transaction(
         connection: &mut Connection,
-        tx: &icp_ledger::Transaction,
-        index: &u64,
+        hb: &HashedBlock,
+        ledger_kind: LedgerKind,
     ) -> Result<(), BlockStoreError> {
         let mut stmt = connection
         .prepare("INSERT INTO transactions (block_idx,tx_hash,operation_type,from_account,to_account,amount,fee) VALUES (?1, ?2, ?3, ?4, ?5,?6,?7)")
         .map_err(|e| BlockStoreError::Other(e.to_string()))?;
-        push_transaction_execution(tx, &mut stmt, index)
+        push_block_transaction_execution(hb, ledger_kind, &mut stmt)
+    }
+
+    pub fn push_block_transaction_execution(
+        hb: &HashedBlock,
+        ledger_kind: LedgerKind,
+        stmt: &mut Statement,
+    ) -> Result<(), BlockStoreError> {
+        match ledger_kind {
+            LedgerKind::Icp => push_transaction_execution(
+                &Block::decode(hb.block.clone()).unwrap().transaction,
+                stmt,
+                &hb.index,
+            ),
+            LedgerKind::Icrc1 => push_icrc1_transaction_execution(
+                &ic_icrc1::Block::decode(hb.block.clone()).map_err(BlockStoreError::Other)?,
+                stmt,
+                &hb.index,
+            ),
+        }
+    }
+
+    pub fn push_icrc1_transaction_execution(
+        block: &ic_icrc1::Block,
+        stmt: &mut Statement,
+        index: &u64,
+    ) -> Result<(), BlockStoreError> {
+        let tx_hash = block.transaction.hash().into_bytes().to_vec();
+        let effective_fee = |fee: &Option<u64>| fee.or(block.effective_fee);
+        let (op_string, from_account, to_account, tokens, fees) = match &block.transaction.operation
+        {
+            Icrc1Operation::Mint { to, amount } => {
+                ("Mint", None, Some(icrc1_account_key(to)), *amount, None)
+            }
+            Icrc1Operation::Burn { from, amount } => {
+                ("Burn", Some(icrc1_account_key(from)), None, *amount, None)
+            }
+            Icrc1Operation::Transfer {
+                from,
+                to,
+                spender,
+                amount,
+                fee,
+            } => (
+                if spender.is_some() {
+                    "TransferFrom"
+                } else {
+                    "Transfer"
+                },
+                Some(icrc1_account_key(from)),
+                Some(icrc1_account_key(to)),
+                *amount,
+                effective_fee(fee),
+            ),
+            Icrc1Operation::Approve {
+                from,
+                spender,
+                amount,
+                fee,
+                ..
+            } => (
+                "Approve",
+                Some(icrc1_account_key(from)),
+                Some(icrc1_account_key(spender)),
+                *amount,
+                effective_fee(fee),
+            ),
+        };
+        stmt.execute(params![
+            index,
+            tx_hash,
+            op_string,
+            from_account,
+            to_account,
+            tokens,
+            fees
+        ])
+        .map_err(|e| BlockStoreError::Other(e.to_string()))?;
+        Ok(())
     }
 
     pub fn push_transaction_execution(
@@ -315,17 +398,20 @@ mod database_access {
         };
         let mut blocks = read_hashed_block(con, command.as_str())?.into_iter();
         match blocks.next() {
-            Some(genesis_block) => match blocks.next() {
-                Some(first_block) => {
-                    let block = first_block.map_err(|e| BlockStoreError::Other(e.to_string()))?;
-                    if block.index > 1 {
-                        Ok(block)
-                    } else {
-                        Ok(genesis_block.map_err(|e| BlockStoreError::Other(e.to_string()))?)
+            Some(first_block) => {
+                let first_block = first_block.map_err(|e| BlockStoreError::Other(e.to_string()))?;
+                // Pruning keeps the genesis block, the first block of a pruned store is the
+                // one after it. Stores bootstrapped from a checkpoint have no genesis block.
+                if first_block.index == 0 {
+                    if let Some(block) = blocks.next() {
+                        let block = block.map_err(|e| BlockStoreError::Other(e.to_string()))?;
+                        if block.index > 1 {
+                            return Ok(block);
+                        }
                     }
                 }
-                None => Ok(genesis_block.map_err(|e| BlockStoreError::Other(e.to_string()))?),
-            },
+                Ok(first_block)
+            }
             None => Err(BlockStoreError::Other("Blockchain is empty".to_string())),
         }
     }
@@ -350,7 +436,7 @@ mod database_access {
     pub fn get_account_balance(
         connection: &mut Connection,
         block_idx: &u64,
-        account: &AccountIdentifier,
+        account: &str,
     ) -> Result<Option<u64>, BlockStoreError> {
         let command = "SELECT tokens FROM account_balances WHERE block_idx<=?1 AND account=?2 ORDER BY block_idx DESC LIMIT 1";
         let mut stmt = connection
@@ -358,9 +444,7 @@ mod database_access {
             .map_err(|e| BlockStoreError::Other(e.to_string()))
             .unwrap();
         let amount = stmt
-            .query_map(params![block_idx, account.to_hex()], |row| {
-                Ok(row.get(0).unwrap())
-            })
+            .query_map(params![block_idx, account], |row| Ok(row.get(0).unwrap()))
             .unwrap()
             .next();
         match amount {
@@ -500,9 +584,131 @@ mod database_access {
         Ok(())
     }
 
+    pub fn update_block_balance_book_execution(
+        hb: &HashedBlock,
+        ledger_kind: LedgerKind,
+        stmt_select: &mut Statement,
+        stmt_insert: &mut Statement,
+    ) -> Result<(), BlockStoreError> {
+        match ledger_kind {
+            LedgerKind::Icp => update_balance_book_execution(hb, stmt_select, stmt_insert),
+            LedgerKind::Icrc1 => update_icrc1_balance_book_execution(hb, stmt_select, stmt_insert),
+        }
+    }
+
+    pub fn update_icrc1_balance_book_execution(
+        hb: &HashedBlock,
+        stmt_select: &mut Statement,
+        stmt_insert: &mut Statement,
+    ) -> Result<(), BlockStoreError> {
+        let block = ic_icrc1::Block::decode(hb.block.clone()).map_err(BlockStoreError::Other)?;
+        let effective_fee = |fee: Option<u64>| fee.or(block.effective_fee).unwrap_or(0);
+        // Balances are accumulated in memory first, so that a transfer to self
+        // debits and credits the same entry.
+        let mut balances = BTreeMap::new();
+        match block.transaction.operation {
+            Icrc1Operation::Mint { to, amount } => {
+                credit_icrc1_account(&mut balances, stmt_select, &to, amount, hb.index)?;
+            }
+            Icrc1Operation::Burn { from, amount } => {
+                debit_icrc1_account(&mut balances, stmt_select, &from, amount, hb.index)?;
+            }
+            Icrc1Operation::Transfer {
+                from,
+                to,
+                amount,
+                fee,
+                ..
+            } => {
+                let payable = amount.checked_add(effective_fee(fee)).ok_or_else(|| {
+                    BlockStoreError::Other(format!("Payable amount overflow at block {}", hb.index))
+                })?;
+                debit_icrc1_account(&mut balances, stmt_select, &from, payable, hb.index)?;
+                credit_icrc1_account(&mut balances, stmt_select, &to, amount, hb.index)?;
+            }
+            Icrc1Operation::Approve { from, fee, .. } => {
+                debit_icrc1_account(
+                    &mut balances,
+                    stmt_select,
+                    &from,
+                    effective_fee(fee),
+                    hb.index,
+                )?;
+            }
+        }
+
+        for (account, tokens) in balances {
+            stmt_insert
+                .execute(params![hb.index, account, tokens])
+                .map_err(|e| {
+                    BlockStoreError::Other(
+                        e.to_string()
+                            + format!(" | Block IDX: {} , Account {}", hb.index, account).as_str(),
+                    )
+                })?;
+        }
+        Ok(())
+    }
+
+    fn icrc1_account_balance<'a>(
+        balances: &'a mut BTreeMap<String, u64>,
+        stmt_select: &mut Statement,
+        account: &Icrc1Account,
+        block_idx: u64,
+    ) -> Result<&'a mut u64, BlockStoreError> {
+        let key = icrc1_account_key(account);
+        if !balances.contains_key(&key) {
+            let latest_balance = stmt_select
+                .query_map(params![key, block_idx], |row| row.get::<_, u64>(2))
+                .map_err(|e| BlockStoreError::Other(e.to_string()))?
+                .next()
+                .transpose()
+                .map_err(|e| BlockStoreError::Other(e.to_string()))?
+                .unwrap_or(0);
+            balances.insert(key.clone(), latest_balance);
+        }
+        Ok(balances.get_mut(&key).unwrap())
+    }
+
+    fn credit_icrc1_account(
+        balances: &mut BTreeMap<String, u64>,
+        stmt_select: &mut Statement,
+        account: &Icrc1Account,
+        amount: u64,
+        block_idx: u64,
+    ) -> Result<(), BlockStoreError> {
+        let balance = icrc1_account_balance(balances, stmt_select, account, block_idx)?;
+        *balance = balance.checked_add(amount).ok_or_else(|| {
+            BlockStoreError::Other(format!(
+                "Balance overflow for account {} at block {}",
+                account, block_idx
+            ))
+        })?;
+        Ok(())
+    }
+
+    fn debit_icrc1_account(
+        balances: &mut BTreeMap<String, u64>,
+        stmt_select: &mut Statement,
+        account: &Icrc1Account,
+        amount: u64,
+        block_idx: u64,
+    ) -> Result<(), BlockStoreError> {
+        let balance = icrc1_account_balance(balances, stmt_select, account, block_idx)?;
+        if *balance < amount {
+            return Err(BlockStoreError::Other(format!(
+                "Account {} does not have enough funds at block {}. Current balance is {}, payable amount is {}.",
+                account, block_idx, balance, amount
+            )));
+        }
+        *balance -= amount;
+        Ok(())
+    }
+
     pub fn update_balance_book(
         con: &mut Connection,
         hb: &HashedBlock,
+        ledger_kind: LedgerKind,
     ) -> Result<(), BlockStoreError> {
         let mut stmt_select =  con
         .prepare("SELECT block_idx,account,tokens FROM account_balances WHERE account=?1 AND block_idx<=?2 ORDER BY block_idx DESC LIMIT 1")
@@ -510,12 +716,10 @@ mod database_access {
         let mut stmt_insert = con
             .prepare("INSERT INTO account_balances (block_idx,account,tokens) VALUES (?1,?2,?3)")
             .expect("Couldn't prepare statement");
-        update_balance_book_execution(hb, &mut stmt_select, &mut stmt_insert)
+        update_block_balance_book_execution(hb, ledger_kind, &mut stmt_select, &mut stmt_insert)
     }
 
-    pub fn get_all_accounts(
-        connection: &mut Connection,
-    ) -> Result<Vec<AccountIdentifier>, BlockStoreError> {
+    pub fn get_all_accounts(connection: &mut Connection) -> Result<Vec<String>, BlockStoreError> {
         let mut accounts = vec![];
         let mut stmt = connection
             .prepare("SELECT DISTINCT account FROM account_balances")
@@ -527,7 +731,7 @@ mod database_access {
             let account: String = row
                 .get(0)
                 .map_err(|e| BlockStoreError::Other(e.to_string()))?;
-            accounts.push(AccountIdentifier::from_hex(account.as_str()).unwrap());
+            accounts.push(account);
         }
         Ok(accounts)
     }
@@ -573,7 +777,7 @@ mod database_access {
 
     pub fn get_account_balance_history(
         connection: &mut Connection,
-        acc: &AccountIdentifier,
+        account: &str,
         max_block: Option<u64>,
     ) -> Result<Vec<(u64, Tokens)>, BlockStoreError> {
         let first_idx = get_first_hashed_block(connection, Some(true))?.index;
@@ -596,7 +800,6 @@ mod database_access {
                 }
                 }
         };
-        let account = acc.to_hex();
         let mut result = Vec::new();
         let mut stmt = connection
             .prepare(command.as_str())
@@ -645,9 +848,18 @@ impl HashedBlock {
         block: EncodedBlock,
         parent_hash: Option<HashOf<EncodedBlock>>,
         index: BlockIndex,
+    ) -> HashedBlock {
+        Self::hash_block_of_kind(block, parent_hash, index, LedgerKind::Icp)
+    }
+
+    pub fn hash_block_of_kind(
+        block: EncodedBlock,
+        parent_hash: Option<HashOf<EncodedBlock>>,
+        index: BlockIndex,
+        ledger_kind: LedgerKind,
     ) -> HashedBlock {
         HashedBlock {
-            hash: Block::block_hash(&block),
+            hash: ledger_kind.block_hash(&block),
             block,
             parent_hash,
             index,
@@ -672,28 +884,46 @@ fn vec_into_array(v: Vec<u8>) -> [u8; 32] {
 
 pub struct Blocks {
     connection: Mutex<rusqlite::Connection>,
+    ledger_kind: LedgerKind,
 }
 
 impl Blocks {
     pub fn new_persistent(location: &Path) -> Result<Self, BlockStoreError> {
+        Self::new_persistent_with_kind(location, LedgerKind::Icp)
+    }
+
+    /// Constructs a new SQLite on-disk store for the blocks of the given kind of ledger.
+    pub fn new_persistent_with_kind(
+        location: &Path,
+        ledger_kind: LedgerKind,
+    ) -> Result<Self, BlockStoreError> {
         std::fs::create_dir_all(location)
             .expect("Unable to create directory for SQLite on-disk store.");
         let path = location.join("db.sqlite");
         let connection =
             rusqlite::Connection::open(path).expect("Unable to open SQLite database connection");
-        Self::new(connection)
+        Self::new(connection, ledger_kind)
     }
 
     /// Constructs a new SQLite in-memory store.
     pub fn new_in_memory() -> Result<Self, BlockStoreError> {
+        Self::new_in_memory_with_kind(LedgerKind::Icp)
+    }
+
+    /// Constructs a new SQLite in-memory store for the blocks of the given kind of ledger.
+    pub fn new_in_memory_with_kind(ledger_kind: LedgerKind) -> Result<Self, BlockStoreError> {
         let connection = rusqlite::Connection::open_in_memory()
             .expect("Unable to open SQLite in-memory database connection");
-        Self::new(connection)
+        Self::new(connection, ledger_kind)
     }
 
-    fn new(connection: rusqlite::Connection) -> Result<Self, BlockStoreError> {
+    fn new(
+        connection: rusqlite::Connection,
+        ledger_kind: LedgerKind,
+    ) -> Result<Self, BlockStoreError> {
         let store = Self {
             connection: Mutex::new(connection),
+            ledger_kind,
         };
         store
             .connection
@@ -709,6 +939,10 @@ impl Blocks {
         Ok(store)
     }
 
+    pub fn ledger_kind(&self) -> LedgerKind {
+        self.ledger_kind
+    }
+
     fn create_tables(&self) -> Result<(), rusqlite::Error> {
         let connection = self.connection.lock().unwrap();
         connection.execute(
@@ -779,6 +1013,72 @@ impl Blocks {
 
         Ok(())
     }
+
+    /// Returns a checkpoint of the balances right after the given verified block.
+    pub fn get_checkpoint(&self, block_idx: &u64) -> Result<Checkpoint, BlockStoreError> {
+        // Pruning collapses the balances before the first block of the store.
+        let first_idx = self.get_first_verified_hashed_block()?.index;
+        if *block_idx < first_idx || !self.is_verified_by_idx(block_idx)? {
+            return Err(BlockStoreError::NotAvailable(*block_idx));
+        }
+        let mut connection = self.connection.lock().unwrap();
+        let block = database_access::get_hashed_block(&mut connection, block_idx)?;
+        let mut balances = vec![];
+        for account in database_access::get_all_accounts(&mut connection)? {
+            match database_access::get_account_balance(&mut connection, block_idx, &account)? {
+                Some(tokens) if tokens > 0 => balances.push((account, tokens)),
+                _ => (),
+            }
+        }
+        Ok(Checkpoint { block, balances })
+    }
+
+    /// Initializes an empty store from a checkpoint.
+    ///
+    /// The block of the checkpoint becomes the first block of the store. It is
+    /// not verified, it is up to the caller to link it to the certified tip of
+    /// the ledger.
+    pub fn bootstrap_from_checkpoint(
+        &mut self,
+        checkpoint: &Checkpoint,
+    ) -> Result<(), BlockStoreError> {
+        let hb = &checkpoint.block;
+        let hash = self.ledger_kind.block_hash(&hb.block);
+        if hb.hash != hash {
+            return Err(BlockStoreError::Other(format!(
+                "Checkpoint block {} has hash {} but its content hashes to {}",
+                hb.index, hb.hash, hash
+            )));
+        }
+        let mut con = self.connection.lock().unwrap();
+        if database_access::get_latest_hashed_block(&mut con, None).is_ok() {
+            return Err(BlockStoreError::Other(
+                "Cannot bootstrap a store that already contains blocks".to_string(),
+            ));
+        }
+        con.execute_batch("BEGIN TRANSACTION;")
+            .map_err(|e| BlockStoreError::Other(format!("{}", e)))?;
+        database_access::push_hashed_block(&mut con, hb)?;
+        database_access::push_transaction(&mut con, hb, self.ledger_kind)?;
+        {
+            let mut stmt_insert = con
+                .prepare(
+                    "INSERT INTO account_balances (block_idx,account,tokens) VALUES (?1,?2,?3)",
+                )
+                .map_err(|e| BlockStoreError::Other(e.to_string()))?;
+            for (account, tokens) in &checkpoint.balances {
+                stmt_insert
+                    .execute(params![hb.index, account, tokens])
+                    .map_err(|e| BlockStoreError::Other(e.to_string()))?;
+            }
+        }
+        con.execute_batch("COMMIT TRANSACTION;")
+            .map_err(|e| BlockStoreError::Other(format!("{}", e)))?;
+        drop(con);
+        self.sanity_check(hb)?;
+        Ok(())
+    }
+
     pub fn get_block_idx_by_block_hash(
         &self,
         hash: &HashOf<EncodedBlock>,
@@ -801,7 +1101,24 @@ impl Blocks {
         limit_num_blocks: Option<u64>,
     ) -> Result<Vec<(u64, Tokens)>, BlockStoreError> {
         let mut connection = self.connection.lock().unwrap();
-        database_access::get_account_balance_history(&mut connection, acc, limit_num_blocks)
+        database_access::get_account_balance_history(
+            &mut connection,
+            &acc.to_hex(),
+            limit_num_blocks,
+        )
+    }
+
+    pub fn get_icrc1_account_balance_history(
+        &self,
+        acc: &ic_icrc1::Account,
+        limit_num_blocks: Option<u64>,
+    ) -> Result<Vec<(u64, Tokens)>, BlockStoreError> {
+        let mut connection = self.connection.lock().unwrap();
+        database_access::get_account_balance_history(
+            &mut connection,
+            &icrc1_account_key(acc),
+            limit_num_blocks,
+        )
     }
 
     /// Sanity check (sum of tokens equal pool size).
@@ -843,6 +1160,7 @@ impl Blocks {
         database_access::get_hashed_block(&mut connection, block_idx)
     }
 
+    /// Returns the transaction of an ICP ledger block.
     pub fn get_transaction(
         &self,
         block_idx: &u64,
@@ -915,8 +1233,8 @@ impl Blocks {
                     database_access::get_hashed_block(&mut connection, &missing_index)?;
                 database_access::push_transaction(
                     &mut connection,
-                    &Block::decode(missing_block.block).unwrap().transaction,
-                    &missing_index,
+                    &missing_block,
+                    self.ledger_kind,
                 )?;
             }
             let difference_account_balances_indices: Vec<u64> = vec_sorted_diff(
@@ -926,7 +1244,11 @@ impl Blocks {
             for missing_index in difference_account_balances_indices {
                 let missing_block =
                     database_access::get_hashed_block(&mut connection, &missing_index)?;
-                database_access::update_balance_book(&mut connection, &missing_block)?;
+                database_access::update_balance_book(
+                    &mut connection,
+                    &missing_block,
+                    self.ledger_kind,
+                )?;
             }
         }
         Ok(())
@@ -971,6 +1293,22 @@ impl Blocks {
         &self,
         account: &AccountIdentifier,
         block_idx: &u64,
+    ) -> Result<Tokens, BlockStoreError> {
+        self.get_balance_by_key(&account.to_hex(), block_idx)
+    }
+
+    pub fn get_icrc1_account_balance(
+        &self,
+        account: &ic_icrc1::Account,
+        block_idx: &u64,
+    ) -> Result<Tokens, BlockStoreError> {
+        self.get_balance_by_key(&icrc1_account_key(account), block_idx)
+    }
+
+    fn get_balance_by_key(
+        &self,
+        account: &str,
+        block_idx: &u64,
     ) -> Result<Tokens, BlockStoreError> {
         if self.is_verified_by_idx(block_idx)? {
             let mut connection = self.connection.lock().unwrap();
@@ -1027,12 +1365,8 @@ impl Blocks {
         con.execute_batch("BEGIN TRANSACTION;")
             .map_err(|e| BlockStoreError::Other(format!("{}", e)))?;
         database_access::push_hashed_block(&mut con, hb)?;
-        database_access::push_transaction(
-            &mut con,
-            &Block::decode(hb.block.clone()).unwrap().transaction,
-            &hb.index,
-        )?;
-        database_access::update_balance_book(&mut con, hb)?;
+        database_access::push_transaction(&mut con, hb, self.ledger_kind)?;
+        database_access::update_balance_book(&mut con, hb, self.ledger_kind)?;
         con.execute_batch("COMMIT TRANSACTION;")
             .map_err(|e| BlockStoreError::Other(format!("{}", e)))?;
         drop(con);
@@ -1041,7 +1375,14 @@ impl Blocks {
     }
     pub fn get_all_accounts(&self) -> Result<Vec<AccountIdentifier>, BlockStoreError> {
         let mut connection = self.connection.lock().unwrap();
-        database_access::get_all_accounts(&mut connection)
+        database_access::get_all_accounts(&mut connection)?
+            .iter()
+            .map(|account| {
+                AccountIdentifier::from_hex(account).map_err(|e| {
+                    BlockStoreError::Other(format!("Invalid account {}: {}", account, e))
+                })
+            })
+            .collect()
     }
 
     pub fn push_batch(&mut self, batch: Vec<HashedBlock>) -> Result<(), BlockStoreError> {
@@ -1070,10 +1411,10 @@ impl Blocks {
                     return Err(e);
                 }
             };
-            match database_access::push_transaction_execution(
-                &Block::decode(hb.block.clone()).unwrap().transaction,
+            match database_access::push_block_transaction_execution(
+                hb,
+                self.ledger_kind,
                 &mut stmt_tx,
-                &hb.index,
             ) {
                 Ok(_) => (),
                 Err(e) => {
@@ -1083,8 +1424,9 @@ impl Blocks {
                     return Err(e);
                 }
             }
-            match database_access::update_balance_book_execution(
+            match database_access::update_block_balance_book_execution(
                 hb,
+                self.ledger_kind,
                 &mut stmt_select,
                 &mut stmt_insert,
             ) {
@@ -1,13 +1,22 @@
+use crate::certification::VerificationInfo;
+use crate::ledger_kind::LedgerKind;
+use candid::{CandidType, Encode};
 use dfn_protobuf::{ProtoBuf, ToProto};
 use ic_agent::agent::http_transport::ReqwestHttpReplicaV2Transport;
 use ic_agent::identity::AnonymousIdentity;
 use ic_agent::{Agent, AgentError, NonceGenerator};
-use ic_ledger_core::block::EncodedBlock;
+use ic_certification::verify_certified_data;
+use ic_crypto_tree_hash::{LookupStatus, MixedHashTree};
+use ic_icrc1::icrc3::value_to_encoded_block;
+use ic_ledger_canister_core::icrc3;
+use ic_ledger_core::block::{BlockType, EncodedBlock};
 use ic_types::CanisterId;
 use icp_ledger::protobuf::{ArchiveIndexEntry, ArchiveIndexResponse, TipOfChainRequest};
 use icp_ledger::{BlockArg, BlockIndex, BlockRes, GetBlocksArgs, GetBlocksRes, TipOfChainRes};
 use log::{debug, trace, warn};
+use num_traits::ToPrimitive;
 use on_wire::{FromWire, IntoWire};
+use serde::de::DeserializeOwned;
 use std::collections::VecDeque;
 use std::convert::TryFrom;
 use std::sync::Arc;
@@ -31,6 +40,8 @@ impl NonceGenerator for TimestampBlob {
 pub struct CanisterAccess {
     pub agent: Agent,
     pub canister_id: CanisterId,
+    ledger_kind: LedgerKind,
+    icrc1_verification_info: Option<VerificationInfo>,
     archive_list: Arc<tokio::sync::Mutex<Option<ArchiveIndexResponse>>>,
     #[allow(clippy::type_complexity)]
     ongoing_block_queries: tokio::sync::Mutex<
@@ -50,6 +61,22 @@ impl CanisterAccess {
         url: Url,
         canister_id: CanisterId,
         root_key: Option<Vec<u8>>,
+    ) -> Result<Self, AgentError> {
+        Self::new_with_kind(url, canister_id, root_key, LedgerKind::Icp, None).await
+    }
+
+    /// Creates an access to the blocks of the given kind of ledger.
+    ///
+    /// ICRC-1 ledgers certify a hash tree containing the tip hash rather than
+    /// the tip hash itself, so for those ledgers `query_tip` checks the
+    /// certificate against `icrc1_verification_info`, if set, and returns the
+    /// tip without a certification. The argument is ignored for the ICP ledger.
+    pub async fn new_with_kind(
+        url: Url,
+        canister_id: CanisterId,
+        root_key: Option<Vec<u8>>,
+        ledger_kind: LedgerKind,
+        icrc1_verification_info: Option<VerificationInfo>,
     ) -> Result<Self, AgentError> {
         let agent = Agent::builder()
             .with_identity(AnonymousIdentity)
@@ -69,6 +96,8 @@ impl CanisterAccess {
         Ok(Self {
             agent,
             canister_id,
+            ledger_kind,
+            icrc1_verification_info,
             archive_list: Arc::new(tokio::sync::Mutex::new(None)),
             ongoing_block_queries: Default::default(),
         })
@@ -99,16 +128,134 @@ impl CanisterAccess {
         ProtoBuf::from_bytes(bytes).map(|c| c.0)
     }
 
-    pub async fn query_tip(&self) -> Result<TipOfChainRes, String> {
-        self.query("tip_of_chain_pb", TipOfChainRequest {})
+    async fn query_candid<Res: CandidType + DeserializeOwned>(
+        &self,
+        canister_id: CanisterId,
+        method: &str,
+        arg: Result<Vec<u8>, candid::Error>,
+    ) -> Result<Res, String> {
+        let arg = arg.map_err(|e| format!("Serialization failed: {}", e))?;
+        let bytes = self
+            .agent
+            .query(&canister_id.get().0, method)
+            .with_arg(arg)
+            .call()
             .await
-            .map_err(|e| format!("In tip: {}", e))
+            .map_err(|e| format!("{}", e))?;
+        candid::decode_one(&bytes).map_err(|e| format!("Deserialization failed: {}", e))
+    }
+
+    pub async fn query_tip(&self) -> Result<TipOfChainRes, String> {
+        match self.ledger_kind {
+            LedgerKind::Icp => self.query("tip_of_chain_pb", TipOfChainRequest {}).await,
+            LedgerKind::Icrc1 => self.icrc1_query_tip().await,
+        }
+        .map_err(|e| format!("In tip: {}", e))
+    }
+
+    /// Returns the tip of an ICRC-1 ledger from its ICRC-3 tip certificate.
+    async fn icrc1_query_tip(&self) -> Result<TipOfChainRes, String> {
+        let certificate: Option<icrc3::DataCertificate> = self
+            .query_candid(self.canister_id, "icrc3_get_tip_certificate", Encode!())
+            .await?;
+        let certificate = certificate.ok_or("the ledger has no blocks")?;
+        let tree: MixedHashTree = ciborium::de::from_reader(certificate.hash_tree.as_slice())
+            .map_err(|e| format!("failed to decode the hash tree: {}", e))?;
+        let lookup_leaf = |label: &str| match tree.lookup(&[label]) {
+            LookupStatus::Found(MixedHashTree::Leaf(bytes)) => Ok(bytes.clone()),
+            _ => Err(format!("the hash tree has no {} leaf", label)),
+        };
+        let tip_index = leb128::read::unsigned(&mut lookup_leaf("last_block_index")?.as_slice())
+            .map_err(|e| format!("failed to decode the last block index: {}", e))?;
+        let tip_hash = lookup_leaf("last_block_hash")?;
+
+        if let Some(info) = &self.icrc1_verification_info {
+            verify_certified_data(
+                &certificate.certificate,
+                &info.canister_id,
+                &info.root_key,
+                &tree.digest().0,
+            )
+            .map_err(|e| format!("Certification error: {:?}", e))?;
+            let tip_block = self
+                .icrc1_query_blocks(tip_index, tip_index + 1)
+                .await?
+                .pop()
+                .ok_or_else(|| format!("tip block {} not found", tip_index))?;
+            if ic_icrc1::Block::block_hash(&tip_block).as_slice() != tip_hash.as_slice() {
+                return Err(format!(
+                    "the hash of block {} does not match the certified tip hash",
+                    tip_index
+                ));
+            }
+        }
+
+        Ok(TipOfChainRes {
+            certification: None,
+            tip_index,
+        })
+    }
+
+    /// Fetches the blocks in the range `[start, end)` of an ICRC-1 ledger,
+    /// following the archive callbacks returned by `icrc3_get_blocks`.
+    ///
+    /// Returns the longest prefix of the range that could be fetched.
+    async fn icrc1_query_blocks(
+        &self,
+        start: BlockIndex,
+        end: BlockIndex,
+    ) -> Result<Vec<EncodedBlock>, String> {
+        let result: icrc3::GetBlocksResult = self
+            .query_candid(
+                self.canister_id,
+                "icrc3_get_blocks",
+                Encode!(&icrc3::GetBlocksArgs::new(start, end - start)),
+            )
+            .await?;
+        let mut blocks = result.blocks;
+        for archived in result.archived_blocks {
+            let archived_result: icrc3::GetBlocksResult = self
+                .query_candid(
+                    archived.callback.canister_id,
+                    &archived.callback.method,
+                    Encode!(&archived.args),
+                )
+                .await?;
+            blocks.extend(archived_result.blocks);
+        }
+
+        let mut blocks = blocks
+            .into_iter()
+            .map(|b| {
+                b.id.0
+                    .to_u64()
+                    .map(|id| (id, b.block))
+                    .ok_or_else(|| format!("invalid block index {}", b.id))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        blocks.sort_by_key(|(id, _)| *id);
+
+        let mut res = vec![];
+        for ((id, value), expected_id) in blocks.into_iter().zip(start..end) {
+            if id != expected_id {
+                break;
+            }
+            res.push(value_to_encoded_block(&value)?);
+        }
+        Ok(res)
     }
 
     pub async fn query_raw_block(
         &self,
         height: BlockIndex,
     ) -> Result<Option<EncodedBlock>, String> {
+        if self.ledger_kind == LedgerKind::Icrc1 {
+            return self
+                .icrc1_query_blocks(height, height + 1)
+                .await
+                .map(|blocks| blocks.into_iter().next())
+                .map_err(|e| format!("In block: {}", e));
+        }
         let BlockRes(b) = self
             .query("block_pb", BlockArg(height))
             .await
@@ -211,6 +358,13 @@ impl CanisterAccess {
         start: BlockIndex,
         end: BlockIndex,
     ) -> Result<Vec<EncodedBlock>, String> {
+        if self.ledger_kind == LedgerKind::Icrc1 {
+            return self
+                .icrc1_query_blocks(start, end)
+                .await
+                .map_err(|e| format!("In blocks: {}", e));
+        }
+
         // asking for a low number of blocks means we are close to the tip
         // so we can try fetching from ledger first
         if end - start < Self::BLOCKS_BATCH_LEN {
@@ -0,0 +1,33 @@
+use crate::blocks::HashedBlock;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// A snapshot of the balances of all accounts right after `block` was applied.
+///
+/// A node can be bootstrapped from a checkpoint instead of replaying the
+/// ledger from genesis. The block of the checkpoint is checked against the
+/// certified tip of the ledger during the bootstrap, while the balances are
+/// taken on trust from whoever exported the checkpoint.
+#[derive(candid::CandidType, Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct Checkpoint {
+    pub block: HashedBlock,
+    /// Accounts, in the format of the `account_balances` table, and their
+    /// non-zero balances.
+    pub balances: Vec<(String, u64)>,
+}
+
+impl Checkpoint {
+    pub fn read_from_file(path: &Path) -> Result<Self, String> {
+        let bytes = std::fs::read(path)
+            .map_err(|e| format!("Cannot read checkpoint file {}: {}", path.display(), e))?;
+        candid::decode_one(&bytes)
+            .map_err(|e| format!("Cannot decode checkpoint file {}: {}", path.display(), e))
+    }
+
+    pub fn write_to_file(&self, path: &Path) -> Result<(), String> {
+        let bytes =
+            candid::encode_one(self).map_err(|e| format!("Cannot encode checkpoint: {}", e))?;
+        std::fs::write(path, bytes)
+            .map_err(|e| format!("Cannot write checkpoint file {}: {}", path.display(), e))
+    }
+}
@@ -1,4 +1,4 @@
-use icp_ledger::{Block, BlockIndex};
+use ic_ledger_core::block::{BlockIndex, EncodedBlock, HashOf};
 
 use crate::blocks::BlockStoreError;
 
@@ -10,8 +10,12 @@ pub enum Error {
 }
 
 impl Error {
-    pub fn invalid_tip_of_chain(index: BlockIndex, expected: Block, found: Block) -> Error {
-        let msg = format!("The tip of the chain at index {} is different from the expected one. Expected: {:?}, found: {:?}",
+    pub fn invalid_tip_of_chain(
+        index: BlockIndex,
+        expected: HashOf<EncodedBlock>,
+        found: HashOf<EncodedBlock>,
+    ) -> Error {
+        let msg = format!("The tip of the chain at index {} is different from the expected one. Expected hash: {}, found hash: {}",
                         index, expected, found);
         Error::InvalidTipOfChain(msg)
     }
@@ -5,8 +5,8 @@ use std::sync::Arc;
 use core::ops::Deref;
 use std::time::Instant;
 
-use ic_ledger_core::block::{BlockIndex, BlockType, EncodedBlock, HashOf};
-use icp_ledger::{Block, TipOfChainRes};
+use ic_ledger_core::block::{BlockIndex, EncodedBlock, HashOf};
+use icp_ledger::TipOfChainRes;
 use log::{debug, error, info, trace, warn};
 use tokio::sync::RwLock;
 
@@ -14,7 +14,9 @@ use crate::blocks::BlockStoreError;
 use crate::blocks::{Blocks, HashedBlock};
 use crate::blocks_access::BlocksAccess;
 use crate::certification::{verify_block_hash, VerificationInfo};
+use crate::checkpoint::Checkpoint;
 use crate::errors::Error;
+use crate::ledger_kind::LedgerKind;
 
 // If pruning is enabled, instead of pruning after each new block
 // we'll wait for PRUNE_DELAY blocks to accumulate and prune them in one go
@@ -27,7 +29,7 @@ const DATABASE_WRITE_BLOCKS_BATCH_SIZE: u64 = 500000;
 const MAX_RETRY: u8 = 5;
 
 struct BlockWithIndex {
-    block: Block,
+    hash: HashOf<EncodedBlock>,
     index: BlockIndex,
 }
 
@@ -58,6 +60,7 @@ where
     // TODO: move store_max_blocks in sync or move up_to_block here
     store_max_blocks: Option<u64>,
     verification_info: Option<VerificationInfo>,
+    ledger_kind: LedgerKind,
     metrics: Box<dyn LedgerBlocksSynchronizerMetrics + Send + Sync>,
 }
 
@@ -67,11 +70,12 @@ impl<B: BlocksAccess> LedgerBlocksSynchronizer<B> {
         store_location: Option<&std::path::Path>,
         store_max_blocks: Option<u64>,
         verification_info: Option<VerificationInfo>,
+        ledger_kind: LedgerKind,
         metrics: Box<dyn LedgerBlocksSynchronizerMetrics + Send + Sync>,
     ) -> Result<LedgerBlocksSynchronizer<B>, Error> {
         let mut blocks = match store_location {
-            Some(loc) => Blocks::new_persistent(loc)?,
-            None => Blocks::new_in_memory()?,
+            Some(loc) => Blocks::new_persistent_with_kind(loc, ledger_kind)?,
+            None => Blocks::new_in_memory_with_kind(ledger_kind)?,
         };
 
         if let Some(blocks_access) = &blocks_access {
@@ -79,7 +83,7 @@ impl<B: BlocksAccess> LedgerBlocksSynchronizer<B> {
             if let Some(verification_info) = &verification_info {
                 // verify if we have the right certificate/we are connecting to the right
                 // canister
-                Self::verify_tip_of_chain(blocks_access, verification_info).await?;
+                Self::verify_tip_of_chain(blocks_access, verification_info, ledger_kind).await?;
             }
         }
 
@@ -114,12 +118,14 @@ impl<B: BlocksAccess> LedgerBlocksSynchronizer<B> {
             blocks_access,
             store_max_blocks,
             verification_info,
+            ledger_kind,
             metrics,
         })
     }
 
     async fn verify_store(blocks: &Blocks, canister_access: &B) -> Result<(), Error> {
         debug!("Verifying store...");
+        let ledger_kind = blocks.ledger_kind();
         let first_block = blocks.get_first_hashed_block().ok();
         match blocks.get_hashed_block(&0) {
             Ok(store_genesis) => {
@@ -129,22 +135,25 @@ impl<B: BlocksAccess> LedgerBlocksSynchronizer<B> {
                     .map_err(Error::InternalError)?
                     .expect("Blockchain in the ledger canister is empty");
 
-                if store_genesis.hash != Block::block_hash(&genesis) {
+                if store_genesis.hash != ledger_kind.block_hash(&genesis) {
                     let msg = format!(
                         "Genesis block from the store is different than \
                         in the ledger canister. Store hash: {}, canister hash: {}",
                         store_genesis.hash,
-                        Block::block_hash(&genesis)
+                        ledger_kind.block_hash(&genesis)
                     );
                     error!("{}", msg);
                     return Err(Error::InternalError(msg));
                 }
             }
             Err(BlockStoreError::NotFound(0)) => {
-                if first_block.is_some() {
-                    let msg = "Snapshot found, but genesis block not present in the store";
-                    error!("{}", msg);
-                    return Err(Error::InternalError(msg.to_string()));
+                // Stores bootstrapped from a checkpoint start at the checkpoint's block,
+                // which is checked against the ledger below.
+                if let Some(first_block) = &first_block {
+                    info!(
+                        "Genesis block not present in the store, the store starts at block {}",
+                        first_block.index
+                    );
                 }
             }
             Err(e) => {
@@ -170,13 +179,13 @@ impl<B: BlocksAccess> LedgerBlocksSynchronizer<B> {
                 return Err(Error::InternalError(msg));
             }
             let queried_block = queried_block.unwrap();
-            if first_block.hash != Block::block_hash(&queried_block) {
+            if first_block.hash != ledger_kind.block_hash(&queried_block) {
                 let msg = format!(
                     "Oldest block snapshot does not match the block on \
                     the blockchain. Index: {}, snapshot hash: {}, canister hash: {}",
                     first_block.index,
                     first_block.hash,
-                    Block::block_hash(&queried_block)
+                    ledger_kind.block_hash(&queried_block)
                 );
                 error!("{}", msg);
                 return Err(Error::InternalError(msg));
@@ -190,6 +199,7 @@ impl<B: BlocksAccess> LedgerBlocksSynchronizer<B> {
     async fn verify_tip_of_chain(
         canister_access: &B,
         verification_info: &VerificationInfo,
+        ledger_kind: LedgerKind,
     ) -> Result<(), Error> {
         let TipOfChainRes {
             tip_index,
@@ -205,13 +215,97 @@ impl<B: BlocksAccess> LedgerBlocksSynchronizer<B> {
             .expect("Blockchain in the ledger canister is empty");
         verify_block_hash(
             &certification,
-            Block::block_hash(&tip_block),
+            ledger_kind.block_hash(&tip_block),
             verification_info,
         )
         .map_err(Error::InternalError)?;
         Ok(())
     }
 
+    /// Initializes an empty store from `checkpoint` instead of replaying the
+    /// ledger from genesis, and synchronizes it up to the tip of the ledger.
+    ///
+    /// The checkpoint is rejected unless its block is on the chain that ends
+    /// at the certified tip of the ledger. A store that already contains
+    /// blocks is left untouched.
+    pub async fn bootstrap_from_checkpoint(
+        &self,
+        checkpoint: &Checkpoint,
+        stopped: Arc<AtomicBool>,
+    ) -> Result<(), Error> {
+        let mut blockchain = self.blockchain.write().await;
+        if let Ok(hb) = blockchain.get_latest_hashed_block() {
+            warn!(
+                "The store already contains blocks up to {}, ignoring the checkpoint",
+                hb.index
+            );
+            return Ok(());
+        }
+
+        let checkpoint_block = &checkpoint.block;
+        let tip = self
+            .query_verified_tip()
+            .await
+            .map_err(Error::InternalError)?;
+        if checkpoint_block.index > tip.index {
+            return Err(Error::InternalError(format!(
+                "Checkpoint block {} is past the tip of the ledger at {}",
+                checkpoint_block.index, tip.index
+            )));
+        }
+        self.metrics.set_target_height(tip.index);
+        let canister = self.blocks_access.as_ref().unwrap();
+        let ledger_block = canister
+            .query_raw_block(checkpoint_block.index)
+            .await
+            .map_err(Error::InternalError)?
+            .ok_or_else(|| {
+                Error::InternalError(format!(
+                    "Checkpoint block {} not found in the ledger",
+                    checkpoint_block.index
+                ))
+            })?;
+        let ledger_hash = self.ledger_kind.block_hash(&ledger_block);
+        if checkpoint_block.hash != ledger_hash {
+            return Err(Error::InternalError(format!(
+                "Checkpoint block {} does not match the ledger. Checkpoint hash: {}, canister hash: {}",
+                checkpoint_block.index, checkpoint_block.hash, ledger_hash
+            )));
+        }
+        if checkpoint_block.index == tip.index && checkpoint_block.hash != tip.hash {
+            return Err(Error::invalid_tip_of_chain(
+                tip.index,
+                tip.hash,
+                checkpoint_block.hash,
+            ));
+        }
+
+        info!(
+            "Bootstrapping the store from the checkpoint at block {}",
+            checkpoint_block.index
+        );
+        blockchain.bootstrap_from_checkpoint(checkpoint)?;
+        self.metrics.set_synced_height(checkpoint_block.index);
+        if checkpoint_block.index == tip.index {
+            blockchain.set_hashed_block_to_verified(&tip.index)?;
+            self.metrics.set_verified_height(tip.index);
+            return Ok(());
+        }
+        // Syncing checks that the parent hashes link the checkpoint to the
+        // certified tip before marking the checkpoint as verified.
+        self.sync_range_of_blocks(
+            Range {
+                start: checkpoint_block.index + 1,
+                end: tip.index + 1,
+            },
+            Some(checkpoint_block.hash),
+            stopped,
+            tip,
+            &mut blockchain,
+        )
+        .await
+    }
+
     pub async fn read_blocks(&self) -> Box<dyn Deref<Target = Blocks> + '_> {
         Box::new(self.blockchain.read().await)
     }
@@ -230,13 +324,12 @@ impl<B: BlocksAccess> LedgerBlocksSynchronizer<B> {
             "Tip of the chain has index {} but no block found at that index!",
             tip_index
         ))?;
-        let block = Block::decode(encoded_block.clone())?;
+        let hash = self.ledger_kind.block_hash(&encoded_block);
         if let Some(info) = &self.verification_info {
-            let hash = HashedBlock::hash_block(encoded_block, block.parent_hash, tip_index).hash;
             verify_block_hash(&certification, hash, info)?;
         }
         Ok(BlockWithIndex {
-            block,
+            hash,
             index: tip_index,
         })
     }
@@ -377,20 +470,27 @@ impl<B: BlocksAccess> LedgerBlocksSynchronizer<B> {
                 )));
             }
             for raw_block in batch {
-                let block = Block::decode(raw_block.clone())
+                let parent_hash = self
+                    .ledger_kind
+                    .parent_hash(&raw_block)
                     .map_err(|err| Error::InternalError(format!("Cannot decode block: {}", err)))?;
-                if block.parent_hash != last_block_hash {
+                if parent_hash != last_block_hash {
                     let err_msg = format!(
                         "Block at {}: parent hash mismatch. Expected: {:?}, got: {:?}",
-                        i, last_block_hash, block.parent_hash
+                        i, last_block_hash, parent_hash
                     );
                     error!("{}", err_msg);
                     return Err(Error::InternalError(err_msg));
                 }
-                if i == tip.index && block != tip.block {
-                    return Err(Error::invalid_tip_of_chain(tip.index, tip.block, block));
+                let hb = HashedBlock::hash_block_of_kind(
+                    raw_block,
+                    last_block_hash,
+                    i,
+                    self.ledger_kind,
+                );
+                if i == tip.index && hb.hash != tip.hash {
+                    return Err(Error::invalid_tip_of_chain(tip.index, tip.hash, hb.hash));
                 }
-                let hb = HashedBlock::hash_block(raw_block, last_block_hash, i);
                 last_block_hash = Some(hb.hash);
                 block_batch.push(hb);
                 i += 1;
@@ -428,8 +528,10 @@ mod test {
         AccountIdentifier, Block, BlockIndex, Memo, TipOfChainRes, DEFAULT_TRANSFER_FEE,
     };
 
+    use crate::blocks::BlockStoreError;
     use crate::blocks_access::BlocksAccess;
     use crate::ledger_blocks_sync::LedgerBlocksSynchronizer;
+    use crate::ledger_kind::LedgerKind;
 
     use super::NopMetrics;
 
@@ -473,12 +575,20 @@ mod test {
 
     async fn new_ledger_blocks_synchronizer(
         blocks: Vec<EncodedBlock>,
+    ) -> LedgerBlocksSynchronizer<RangeOfBlocks> {
+        new_ledger_blocks_synchronizer_of_kind(blocks, LedgerKind::Icp).await
+    }
+
+    async fn new_ledger_blocks_synchronizer_of_kind(
+        blocks: Vec<EncodedBlock>,
+        ledger_kind: LedgerKind,
     ) -> LedgerBlocksSynchronizer<RangeOfBlocks> {
         LedgerBlocksSynchronizer::new(
             Some(Arc::new(RangeOfBlocks::new(blocks))),
             /* store_location = */ None,
             /* store_max_blocks = */ None,
             /* verification_info = */ None,
+            ledger_kind,
             Box::new(NopMetrics {}),
         )
         .await
@@ -596,4 +706,235 @@ mod test {
             );
         }
     }
+
+    #[tokio::test]
+    async fn bootstrap_from_checkpoint() {
+        let blocks = dummy_blocks(4);
+        let full_sync = new_ledger_blocks_synchronizer(blocks.clone()).await;
+        full_sync
+            .sync_blocks(Arc::new(AtomicBool::new(false)), None)
+            .await
+            .unwrap();
+        let full_blocks = full_sync.read_blocks().await;
+        let checkpoint = full_blocks.get_checkpoint(&1).unwrap();
+
+        let blocks_sync = new_ledger_blocks_synchronizer(blocks.clone()).await;
+        blocks_sync
+            .bootstrap_from_checkpoint(&checkpoint, Arc::new(AtomicBool::new(false)))
+            .await
+            .unwrap();
+        let actual_blocks = blocks_sync.read_blocks().await;
+        assert_eq!(
+            actual_blocks.get_hashed_block(&0).unwrap_err(),
+            BlockStoreError::NotFound(0)
+        );
+        assert_eq!(actual_blocks.get_first_hashed_block().unwrap().index, 1);
+        for (idx, eb) in blocks.iter().enumerate().skip(1) {
+            let hb = actual_blocks.get_hashed_block(&(idx as u64)).unwrap();
+            assert!(actual_blocks.is_verified_by_idx(&(idx as u64)).unwrap());
+            assert_eq!(Block::block_hash(eb), hb.hash);
+        }
+        for account in full_blocks.get_all_accounts().unwrap() {
+            assert_eq!(
+                actual_blocks.get_account_balance(&account, &3).unwrap(),
+                full_blocks.get_account_balance(&account, &3).unwrap()
+            );
+        }
+    }
+
+    /// Serves only the blocks from `first_index` on, so that fetching any
+    /// earlier block fails.
+    struct BlocksFrom {
+        blocks: RangeOfBlocks,
+        first_index: BlockIndex,
+    }
+
+    #[async_trait]
+    impl BlocksAccess for BlocksFrom {
+        async fn query_raw_block(
+            &self,
+            height: BlockIndex,
+        ) -> Result<Option<EncodedBlock>, String> {
+            if height < self.first_index {
+                return Err(format!("Block {} is not available", height));
+            }
+            self.blocks.query_raw_block(height).await
+        }
+
+        async fn query_tip(&self) -> Result<TipOfChainRes, String> {
+            self.blocks.query_tip().await
+        }
+
+        async fn multi_query_blocks(
+            self: Arc<Self>,
+            range: Range<BlockIndex>,
+        ) -> Result<Vec<EncodedBlock>, String> {
+            if range.start < self.first_index {
+                return Err(format!("Block {} is not available", range.start));
+            }
+            Ok(self.blocks.blocks[range.start as usize..range.end as usize].to_vec())
+        }
+    }
+
+    #[tokio::test]
+    async fn bootstrap_from_checkpoint_fetches_no_blocks_before_checkpoint() {
+        let blocks = dummy_blocks(4);
+        let full_sync = new_ledger_blocks_synchronizer(blocks.clone()).await;
+        full_sync
+            .sync_blocks(Arc::new(AtomicBool::new(false)), None)
+            .await
+            .unwrap();
+        let checkpoint = full_sync.read_blocks().await.get_checkpoint(&2).unwrap();
+
+        let blocks_sync = LedgerBlocksSynchronizer::new(
+            Some(Arc::new(BlocksFrom {
+                blocks: RangeOfBlocks::new(blocks),
+                first_index: 2,
+            })),
+            /* store_location = */ None,
+            /* store_max_blocks = */ None,
+            /* verification_info = */ None,
+            LedgerKind::Icp,
+            Box::new(NopMetrics {}),
+        )
+        .await
+        .unwrap();
+        blocks_sync
+            .bootstrap_from_checkpoint(&checkpoint, Arc::new(AtomicBool::new(false)))
+            .await
+            .unwrap();
+        let actual_blocks = blocks_sync.read_blocks().await;
+        assert_eq!(actual_blocks.get_first_hashed_block().unwrap().index, 2);
+        assert_eq!(actual_blocks.get_latest_hashed_block().unwrap().index, 3);
+        assert!(actual_blocks.is_verified_by_idx(&3).unwrap());
+    }
+
+    #[tokio::test]
+    async fn bootstrap_from_checkpoint_not_in_ledger() {
+        let blocks = dummy_blocks(2);
+        let full_sync = new_ledger_blocks_synchronizer(blocks.clone()).await;
+        full_sync
+            .sync_blocks(Arc::new(AtomicBool::new(false)), None)
+            .await
+            .unwrap();
+        let checkpoint = full_sync.read_blocks().await.get_checkpoint(&1).unwrap();
+
+        let other_blocks = vec![blocks[0].clone(), dummy_block(None)];
+        let blocks_sync = new_ledger_blocks_synchronizer(other_blocks).await;
+        assert!(blocks_sync
+            .bootstrap_from_checkpoint(&checkpoint, Arc::new(AtomicBool::new(false)))
+            .await
+            .is_err());
+        assert!(blocks_sync
+            .read_blocks()
+            .await
+            .get_first_hashed_block()
+            .is_err());
+    }
+
+    fn icrc1_account(n: u64) -> ic_icrc1::Account {
+        ic_icrc1::Account {
+            owner: PrincipalId::new_user_test_id(n),
+            subaccount: None,
+        }
+    }
+
+    fn icrc1_blocks() -> Vec<EncodedBlock> {
+        let transactions = vec![
+            ic_icrc1::Transaction::mint(icrc1_account(1), Tokens::from_e8s(1_000_000), None, None),
+            ic_icrc1::Transaction::transfer(
+                icrc1_account(1),
+                icrc1_account(2),
+                Tokens::from_e8s(100_000),
+                None,
+                None,
+                None,
+            ),
+            ic_icrc1::Transaction::transfer(
+                icrc1_account(2),
+                icrc1_account(2),
+                Tokens::from_e8s(10_000),
+                Some(Tokens::from_e8s(1_000)),
+                None,
+                None,
+            ),
+            ic_icrc1::Transaction::approve(
+                icrc1_account(1),
+                icrc1_account(2),
+                Tokens::from_e8s(500_000),
+                None,
+                None,
+                None,
+                None,
+                None,
+            ),
+        ];
+        let mut res = vec![];
+        let mut parent_hash = None;
+        for (i, tx) in transactions.into_iter().enumerate() {
+            let block = ic_icrc1::Block::from_transaction(
+                parent_hash,
+                tx,
+                TimeStamp::from_nanos_since_unix_epoch(i as u64),
+                Tokens::from_e8s(10_000),
+            )
+            .encode();
+            parent_hash = Some(ic_icrc1::Block::block_hash(&block));
+            res.push(block);
+        }
+        res
+    }
+
+    #[tokio::test]
+    async fn sync_icrc1_blocks() {
+        let blocks = icrc1_blocks();
+        let blocks_sync =
+            new_ledger_blocks_synchronizer_of_kind(blocks.clone(), LedgerKind::Icrc1).await;
+        blocks_sync
+            .sync_blocks(Arc::new(AtomicBool::new(false)), None)
+            .await
+            .unwrap();
+        let actual_blocks = blocks_sync.read_blocks().await;
+        for (idx, eb) in blocks.iter().enumerate() {
+            let hb = actual_blocks.get_hashed_block(&(idx as u64)).unwrap();
+            assert!(actual_blocks.is_verified_by_idx(&(idx as u64)).unwrap());
+            assert_eq!(ic_icrc1::Block::block_hash(eb), hb.hash);
+        }
+
+        let balance_at = |n: u64, idx: u64| {
+            actual_blocks
+                .get_icrc1_account_balance(&icrc1_account(n), &idx)
+                .unwrap()
+                .get_e8s()
+        };
+        assert_eq!(balance_at(1, 0), 1_000_000);
+        assert_eq!(balance_at(2, 0), 0);
+        // The transfer pays the effective fee of the block.
+        assert_eq!(balance_at(1, 1), 890_000);
+        assert_eq!(balance_at(2, 1), 100_000);
+        // A transfer to self only pays the fee set in the transaction.
+        assert_eq!(balance_at(2, 2), 99_000);
+        // An approval only pays the fee.
+        assert_eq!(balance_at(1, 3), 880_000);
+
+        // The default subaccount and no subaccount denote the same account.
+        let explicit_default = ic_icrc1::Account {
+            subaccount: Some([0; 32]),
+            ..icrc1_account(1)
+        };
+        assert_eq!(
+            actual_blocks
+                .get_icrc1_account_balance(&explicit_default, &3)
+                .unwrap()
+                .get_e8s(),
+            880_000
+        );
+        assert_eq!(
+            actual_blocks
+                .get_icrc1_account_balance_history(&icrc1_account(1), None)
+                .unwrap()
+                .len(),
+            3
+        );
+    }
 }
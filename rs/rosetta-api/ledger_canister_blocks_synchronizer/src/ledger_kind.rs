@@ -0,0 +1,53 @@
+use ic_icrc1::{Account, DEFAULT_SUBACCOUNT};
+use ic_ledger_core::block::{BlockType, EncodedBlock, HashOf};
+use ic_ledger_core::timestamp::TimeStamp;
+
+/// The kind of ledger the blocks are synchronized from.
+///
+/// The kind determines how blocks are decoded and how accounts are stored in
+/// the `transactions` and `account_balances` tables.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LedgerKind {
+    /// The ICP ledger. Accounts are stored as hex-encoded `AccountIdentifier`s.
+    Icp,
+    /// An ICRC-1 ledger. Accounts are stored as textual ICRC-1 `Account`s, see
+    /// [icrc1_account_key].
+    Icrc1,
+}
+
+impl LedgerKind {
+    pub fn block_hash(self, block: &EncodedBlock) -> HashOf<EncodedBlock> {
+        match self {
+            LedgerKind::Icp => icp_ledger::Block::block_hash(block),
+            LedgerKind::Icrc1 => ic_icrc1::Block::block_hash(block),
+        }
+    }
+
+    pub fn parent_hash(self, block: &EncodedBlock) -> Result<Option<HashOf<EncodedBlock>>, String> {
+        match self {
+            LedgerKind::Icp => icp_ledger::Block::decode(block.clone()).map(|b| b.parent_hash),
+            LedgerKind::Icrc1 => ic_icrc1::Block::decode(block.clone()).map(|b| b.parent_hash),
+        }
+    }
+
+    pub fn timestamp(self, block: &EncodedBlock) -> Result<TimeStamp, String> {
+        match self {
+            LedgerKind::Icp => icp_ledger::Block::decode(block.clone()).map(|b| b.timestamp),
+            LedgerKind::Icrc1 => ic_icrc1::Block::decode(block.clone())
+                .map(|b| TimeStamp::from_nanos_since_unix_epoch(b.timestamp)),
+        }
+    }
+}
+
+/// Returns the key under which the transactions and balances of an ICRC-1
+/// account are stored.
+///
+/// Accounts with the default subaccount are stored as the bare owner principal,
+/// so that both representations of the same account share one balance.
+pub fn icrc1_account_key(account: &Account) -> String {
+    if account.effective_subaccount() == DEFAULT_SUBACCOUNT {
+        account.owner.to_string()
+    } else {
+        account.to_string()
+    }
+}
@@ -3,5 +3,7 @@ pub mod blocks;
 pub mod blocks_access;
 pub mod canister_access;
 pub mod certification;
+pub mod checkpoint;
 pub mod errors;
 pub mod ledger_blocks_sync;
+pub mod ledger_kind;
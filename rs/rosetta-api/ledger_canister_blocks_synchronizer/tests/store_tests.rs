@@ -1,6 +1,7 @@
 use ic_ledger_canister_blocks_synchronizer::{
     balance_book::BalanceBook,
     blocks::{BlockStoreError, Blocks},
+    checkpoint::Checkpoint,
 };
 use ic_ledger_canister_blocks_synchronizer_test_utils::{
     create_tmp_dir, init_test_logger, sample_data::Scribe,
@@ -245,6 +246,58 @@ async fn store_prune_and_load_test() {
     verify_balance_snapshot(&scribe, &mut store, 30);
 }
 
+#[actix_rt::test]
+async fn store_bootstrap_from_checkpoint_test() {
+    init_test_logger();
+    let tmpdir = create_tmp_dir();
+    let mut store = sqlite_on_disk_store(tmpdir.path());
+    let scribe = Scribe::new_with_sample_data(10, 100);
+
+    for hb in &scribe.blockchain {
+        store.push(hb).unwrap();
+        store.set_hashed_block_to_verified(&hb.index).unwrap();
+    }
+    let checkpoint = store.get_checkpoint(&50).unwrap();
+    let checkpoint_path = tmpdir.path().join("checkpoint");
+    checkpoint.write_to_file(&checkpoint_path).unwrap();
+    assert_eq!(
+        Checkpoint::read_from_file(&checkpoint_path).unwrap(),
+        checkpoint
+    );
+
+    let bootstrap_dir = create_tmp_dir();
+    let mut bootstrapped = sqlite_on_disk_store(bootstrap_dir.path());
+    bootstrapped.bootstrap_from_checkpoint(&checkpoint).unwrap();
+    assert!(bootstrapped.bootstrap_from_checkpoint(&checkpoint).is_err());
+    for hb in scribe.blockchain.iter().skip(51) {
+        bootstrapped.push(hb).unwrap();
+    }
+    let last_idx = scribe.blockchain.back().unwrap().index;
+    bootstrapped
+        .set_hashed_block_to_verified(&last_idx)
+        .unwrap();
+    verify_balance_snapshot(&scribe, &mut bootstrapped, 50);
+
+    drop(bootstrapped);
+    // Reload from disk, then prune the bootstrapped store
+    let mut bootstrapped = sqlite_on_disk_store(bootstrap_dir.path());
+    assert_eq!(
+        bootstrapped.get_hashed_block(&0).unwrap_err(),
+        BlockStoreError::NotFound(0)
+    );
+    verify_balance_snapshot(&scribe, &mut bootstrapped, 50);
+    prune(&scribe, &mut bootstrapped, 80);
+    verify_balance_snapshot(&scribe, &mut bootstrapped, 80);
+    for account in store.get_all_accounts().unwrap() {
+        assert_eq!(
+            bootstrapped
+                .get_account_balance(&account, &last_idx)
+                .unwrap(),
+            store.get_account_balance(&account, &last_idx).unwrap()
+        );
+    }
+}
+
 fn prune(scribe: &Scribe, store: &mut Blocks, prune_at: u64) {
     let oldest_idx = prune_at;
     let oldest_block = scribe.blockchain.get(oldest_idx as usize).unwrap();
@@ -0,0 +1,292 @@
+//! Types and helpers implementing the ICRC-3 generic block log interface.
+//!
+//! See https://github.com/dfinity/ICRC-1/tree/main/standards/ICRC-3
+use crate::{
+    ledger::{block_locations, LedgerData},
+    range_utils,
+};
+use candid::{types::reference::Func, CandidType, Int, Nat, Principal};
+use ic_base_types::CanisterId;
+use ic_crypto_sha::Sha256;
+use ic_ledger_core::block::EncodedBlock;
+use num_traits::ToPrimitive;
+use serde::Deserialize;
+use serde_bytes::ByteBuf;
+use std::collections::BTreeMap;
+
+/// The generic representation of a block in the ICRC-3 block log.
+#[derive(CandidType, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub enum ICRC3Value {
+    Blob(ByteBuf),
+    Text(String),
+    Nat(Nat),
+    Int(Int),
+    Array(Vec<ICRC3Value>),
+    Map(BTreeMap<String, ICRC3Value>),
+}
+
+impl ICRC3Value {
+    /// Computes the representation-independent hash of the value.
+    ///
+    /// See https://internetcomputer.org/docs/current/references/ic-interface-spec/#hash-of-map
+    pub fn hash(&self) -> [u8; 32] {
+        match self {
+            ICRC3Value::Blob(bytes) => Sha256::hash(bytes),
+            ICRC3Value::Text(text) => Sha256::hash(text.as_bytes()),
+            ICRC3Value::Nat(nat) => {
+                let mut buf = vec![];
+                nat.encode(&mut buf).expect("bug: failed to encode a nat");
+                Sha256::hash(&buf)
+            }
+            ICRC3Value::Int(int) => {
+                let mut buf = vec![];
+                int.encode(&mut buf).expect("bug: failed to encode an int");
+                Sha256::hash(&buf)
+            }
+            ICRC3Value::Array(values) => {
+                let mut hasher = Sha256::new();
+                for v in values.iter() {
+                    hasher.write(&v.hash());
+                }
+                hasher.finish()
+            }
+            ICRC3Value::Map(map) => {
+                let mut hpairs: Vec<_> = map
+                    .iter()
+                    .map(|(k, v)| (Sha256::hash(k.as_bytes()), v.hash()))
+                    .collect();
+                hpairs.sort_unstable();
+
+                let mut hasher = Sha256::new();
+                for (khash, vhash) in hpairs.iter() {
+                    hasher.write(&khash[..]);
+                    hasher.write(&vhash[..]);
+                }
+                hasher.finish()
+            }
+        }
+    }
+}
+
+impl From<u64> for ICRC3Value {
+    fn from(n: u64) -> Self {
+        ICRC3Value::Nat(Nat::from(n))
+    }
+}
+
+impl From<String> for ICRC3Value {
+    fn from(s: String) -> Self {
+        ICRC3Value::Text(s)
+    }
+}
+
+impl From<&str> for ICRC3Value {
+    fn from(s: &str) -> Self {
+        ICRC3Value::Text(s.to_string())
+    }
+}
+
+impl From<ByteBuf> for ICRC3Value {
+    fn from(bytes: ByteBuf) -> Self {
+        ICRC3Value::Blob(bytes)
+    }
+}
+
+impl From<Vec<ICRC3Value>> for ICRC3Value {
+    fn from(values: Vec<ICRC3Value>) -> Self {
+        ICRC3Value::Array(values)
+    }
+}
+
+impl From<BTreeMap<String, ICRC3Value>> for ICRC3Value {
+    fn from(map: BTreeMap<String, ICRC3Value>) -> Self {
+        ICRC3Value::Map(map)
+    }
+}
+
+#[derive(CandidType, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct GetBlocksArgs {
+    pub start: Nat,
+    pub length: Nat,
+}
+
+impl GetBlocksArgs {
+    pub fn new(start: u64, length: u64) -> Self {
+        Self {
+            start: Nat::from(start),
+            length: Nat::from(length),
+        }
+    }
+
+    pub fn as_start_and_length(&self) -> Result<(u64, u64), String> {
+        let start = self.start.0.to_u64().ok_or_else(|| {
+            format!(
+                "block index {} is too large, max allowed: {}",
+                self.start,
+                u64::MAX
+            )
+        })?;
+        let length = self.length.0.to_u64().ok_or_else(|| {
+            format!(
+                "requested length {} is too large, max allowed: {}",
+                self.length,
+                u64::MAX
+            )
+        })?;
+        Ok((start, length))
+    }
+}
+
+#[derive(CandidType, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct BlockWithId {
+    pub id: Nat,
+    pub block: ICRC3Value,
+}
+
+/// A reference to the `icrc3_get_blocks` method of an archive canister.
+#[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
+#[serde(try_from = "candid::types::reference::Func")]
+pub struct QueryBlockArchiveFn {
+    pub canister_id: CanisterId,
+    pub method: String,
+}
+
+impl From<QueryBlockArchiveFn> for Func {
+    fn from(archive_fn: QueryBlockArchiveFn) -> Self {
+        let p: &ic_base_types::PrincipalId = archive_fn.canister_id.as_ref();
+        Self {
+            principal: p.0,
+            method: archive_fn.method,
+        }
+    }
+}
+
+impl TryFrom<Func> for QueryBlockArchiveFn {
+    type Error = String;
+    fn try_from(func: Func) -> Result<Self, Self::Error> {
+        let canister_id = CanisterId::try_from(func.principal.as_slice())
+            .map_err(|e| format!("principal is not a canister id: {}", e))?;
+        Ok(QueryBlockArchiveFn {
+            canister_id,
+            method: func.method,
+        })
+    }
+}
+
+impl CandidType for QueryBlockArchiveFn {
+    fn _ty() -> candid::types::Type {
+        candid::types::Type::Func(candid::types::Function {
+            modes: vec![candid::parser::types::FuncMode::Query],
+            args: vec![GetBlocksArgs::_ty()],
+            rets: vec![GetBlocksResult::_ty()],
+        })
+    }
+
+    fn idl_serialize<S>(&self, serializer: S) -> Result<(), S::Error>
+    where
+        S: candid::types::Serializer,
+    {
+        Func::from(self.clone()).idl_serialize(serializer)
+    }
+}
+
+#[derive(CandidType, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ArchivedBlocks {
+    pub args: GetBlocksArgs,
+    pub callback: QueryBlockArchiveFn,
+}
+
+#[derive(CandidType, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct GetBlocksResult {
+    pub log_length: Nat,
+    pub blocks: Vec<BlockWithId>,
+    pub archived_blocks: Vec<ArchivedBlocks>,
+}
+
+#[derive(CandidType, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct DataCertificate {
+    /// The certificate returned by the system API.
+    pub certificate: ByteBuf,
+    /// The CBOR-encoded hash tree containing the `last_block_index` and the
+    /// `last_block_hash` labels.
+    pub hash_tree: ByteBuf,
+}
+
+#[derive(CandidType, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct GetArchivesArgs {
+    /// The last archive seen by the client; the ledger returns archives with
+    /// larger principals.
+    pub from: Option<Principal>,
+}
+
+#[derive(CandidType, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ICRC3ArchiveInfo {
+    pub canister_id: Principal,
+    pub start: Nat,
+    pub end: Nat,
+}
+
+/// Returns the blocks in the specified range in the ICRC-3 format.
+///
+/// At most `max_blocks` blocks are returned from the ledger itself, the ranges
+/// that live in archives are returned as callbacks to the archive's
+/// `icrc3_get_blocks` endpoint.
+pub fn get_blocks<L: LedgerData>(
+    ledger: &L,
+    start: u64,
+    length: usize,
+    max_blocks: usize,
+    to_value: impl Fn(&EncodedBlock) -> ICRC3Value,
+) -> GetBlocksResult {
+    let locations = block_locations(ledger, start, length);
+
+    let local_blocks = range_utils::take(&locations.local_blocks, max_blocks);
+
+    let blocks = ledger
+        .blockchain()
+        .block_slice(local_blocks.clone())
+        .iter()
+        .zip(local_blocks)
+        .map(|(enc_block, id)| BlockWithId {
+            id: Nat::from(id),
+            block: to_value(enc_block),
+        })
+        .collect();
+
+    let archived_blocks = locations
+        .archived_blocks
+        .into_iter()
+        .map(|(canister_id, slice)| ArchivedBlocks {
+            args: GetBlocksArgs::new(slice.start, range_utils::range_len(&slice)),
+            callback: QueryBlockArchiveFn {
+                canister_id,
+                method: "icrc3_get_blocks".to_string(),
+            },
+        })
+        .collect();
+
+    GetBlocksResult {
+        log_length: Nat::from(ledger.blockchain().chain_length()),
+        blocks,
+        archived_blocks,
+    }
+}
+
+/// Returns the archives of the ledger ordered by principal, skipping all
+/// archives up to and including `args.from`.
+pub fn get_archives<L: LedgerData>(ledger: &L, args: GetArchivesArgs) -> Vec<ICRC3ArchiveInfo> {
+    let archive = ledger.blockchain().archive.read().unwrap();
+
+    let mut archives: Vec<ICRC3ArchiveInfo> = archive
+        .iter()
+        .flat_map(|archive| archive.index().into_iter())
+        .map(|((start, end), canister_id)| ICRC3ArchiveInfo {
+            canister_id: canister_id.get().0,
+            start: Nat::from(start),
+            end: Nat::from(end),
+        })
+        .filter(|info| args.from.map_or(true, |from| info.canister_id > from))
+        .collect();
+    archives.sort_by(|l, r| l.canister_id.cmp(&r.canister_id));
+    archives
+}
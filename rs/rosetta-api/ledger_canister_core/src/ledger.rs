@@ -6,7 +6,10 @@ use std::collections::{BTreeMap, VecDeque};
 use std::ops::Range;
 use std::time::Duration;
 
-use ic_ledger_core::balances::{BalanceError, Balances, BalancesStore, InspectableBalancesStore};
+use ic_ledger_core::approvals::{
+    Approvals, ApproveError, InsufficientAllowance, PrunableApprovals,
+};
+use ic_ledger_core::balances::{BalanceError, Balances, InspectableBalancesStore};
 use ic_ledger_core::block::{BlockIndex, BlockType, EncodedBlock, HashOf};
 use ic_ledger_core::timestamp::TimeStamp;
 use ic_ledger_core::tokens::Tokens;
@@ -14,6 +17,9 @@ use ic_ledger_core::tokens::Tokens;
 /// The memo to use for balances burned during trimming
 const TRIMMED_MEMO: u64 = u64::MAX;
 
+/// The maximum number of expired approvals to prune per transaction.
+const APPROVE_PRUNE_LIMIT: usize = 100;
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct TransactionInfo<TransactionType> {
     pub block_timestamp: TimeStamp,
@@ -38,14 +44,63 @@ pub trait LedgerTransaction: Sized {
     /// Returns the hash of this transaction.
     fn hash(&self) -> HashOf<Self>;
 
-    /// Applies this transaction to the balance book.
-    fn apply<S>(
+    /// Applies this transaction to the balance book and the approvals table.
+    fn apply<C>(
         &self,
-        balances: &mut Balances<S>,
+        context: &mut C,
+        now: TimeStamp,
         effective_fee: Tokens,
-    ) -> Result<(), BalanceError>
+    ) -> Result<(), TxApplyError>
     where
-        S: Default + BalancesStore<AccountId = Self::AccountId>;
+        C: LedgerContext<AccountId = Self::AccountId>;
+}
+
+/// The part of the ledger state that transactions can modify.
+pub trait LedgerContext {
+    type AccountId: std::hash::Hash + Ord + Eq + Clone;
+    type BalancesStore: InspectableBalancesStore<AccountId = Self::AccountId> + Default;
+    type Approvals: Approvals<AccountId = Self::AccountId, SpenderId = Self::AccountId>
+        + PrunableApprovals;
+
+    fn balances(&self) -> &Balances<Self::BalancesStore>;
+    fn balances_mut(&mut self) -> &mut Balances<Self::BalancesStore>;
+
+    fn approvals(&self) -> &Self::Approvals;
+    fn approvals_mut(&mut self) -> &mut Self::Approvals;
+}
+
+/// An error that prevents a transaction from being applied to the ledger state.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TxApplyError {
+    InsufficientFunds { balance: Tokens },
+    InsufficientAllowance { allowance: Tokens },
+    ExpiredApproval { now: TimeStamp },
+    AllowanceChanged { current_allowance: Tokens },
+}
+
+impl From<BalanceError> for TxApplyError {
+    fn from(e: BalanceError) -> Self {
+        match e {
+            BalanceError::InsufficientFunds { balance } => Self::InsufficientFunds { balance },
+        }
+    }
+}
+
+impl From<InsufficientAllowance> for TxApplyError {
+    fn from(e: InsufficientAllowance) -> Self {
+        Self::InsufficientAllowance { allowance: e.0 }
+    }
+}
+
+impl From<ApproveError> for TxApplyError {
+    fn from(e: ApproveError) -> Self {
+        match e {
+            ApproveError::ExpiredApproval { now } => Self::ExpiredApproval { now },
+            ApproveError::AllowanceChanged { current_allowance } => {
+                Self::AllowanceChanged { current_allowance }
+            }
+        }
+    }
 }
 
 pub trait LedgerAccess {
@@ -66,13 +121,11 @@ pub trait LedgerAccess {
     fn with_ledger_mut<R>(f: impl FnOnce(&mut Self::Ledger) -> R) -> R;
 }
 
-pub trait LedgerData {
-    type AccountId: std::hash::Hash + Ord + Eq + Clone;
+pub trait LedgerData: LedgerContext {
     type ArchiveWasm: ArchiveCanisterWasm;
     type Runtime: Runtime;
     type Block: BlockType<Transaction = Self::Transaction>;
     type Transaction: LedgerTransaction<AccountId = Self::AccountId> + Ord + Clone;
-    type BalancesStore: InspectableBalancesStore<AccountId = Self::AccountId> + Default;
 
     // Purge configuration
 
@@ -103,9 +156,6 @@ pub trait LedgerData {
 
     // Ledger data structures
 
-    fn balances(&self) -> &Balances<Self::BalancesStore>;
-    fn balances_mut(&mut self) -> &mut Balances<Self::BalancesStore>;
-
     fn blockchain(&self) -> &Blockchain<Self::Runtime, Self::ArchiveWasm>;
     fn blockchain_mut(&mut self) -> &mut Blockchain<Self::Runtime, Self::ArchiveWasm>;
 
@@ -127,6 +177,24 @@ pub enum TransferError {
     TxCreatedInFuture { ledger_time: TimeStamp },
     TxThrottled,
     TxDuplicate { duplicate_of: BlockIndex },
+    InsufficientAllowance { allowance: Tokens },
+    ExpiredApproval { now: TimeStamp },
+    AllowanceChanged { current_allowance: Tokens },
+}
+
+impl From<TxApplyError> for TransferError {
+    fn from(e: TxApplyError) -> Self {
+        match e {
+            TxApplyError::InsufficientFunds { balance } => Self::InsufficientFunds { balance },
+            TxApplyError::InsufficientAllowance { allowance } => {
+                Self::InsufficientAllowance { allowance }
+            }
+            TxApplyError::ExpiredApproval { now } => Self::ExpiredApproval { now },
+            TxApplyError::AllowanceChanged { current_allowance } => {
+                Self::AllowanceChanged { current_allowance }
+            }
+        }
+    }
 }
 
 /// Adds a new block with the specified transaction to the ledger.
@@ -137,6 +205,7 @@ pub fn apply_transaction<L: LedgerData>(
     effective_fee: Tokens,
 ) -> Result<(BlockIndex, HashOf<EncodedBlock>), TransferError> {
     let num_pruned = purge_old_transactions(ledger, now);
+    ledger.approvals_mut().prune(now, APPROVE_PRUNE_LIMIT);
 
     // If we pruned some transactions, let this one through
     // otherwise throttle if there are too many
@@ -167,13 +236,7 @@ pub fn apply_transaction<L: LedgerData>(
         }
     }
 
-    transaction
-        .apply(ledger.balances_mut(), effective_fee)
-        .map_err(|e| match e {
-            BalanceError::InsufficientFunds { balance } => {
-                TransferError::InsufficientFunds { balance }
-            }
-        })?;
+    transaction.apply(ledger, now, effective_fee)?;
 
     let block = L::Block::from_transaction(
         ledger.blockchain().last_hash,
@@ -213,7 +276,7 @@ pub fn apply_transaction<L: LedgerData>(
         let burn_tx = L::Transaction::burn(account, balance, Some(now), Some(TRIMMED_MEMO));
 
         burn_tx
-            .apply(ledger.balances_mut(), Tokens::from_e8s(0))
+            .apply(ledger, now, Tokens::from_e8s(0))
             .expect("failed to burn funds that must have existed");
 
         let parent_hash = ledger.blockchain().last_hash;
@@ -1,5 +1,6 @@
 pub mod archive;
 pub mod blockchain;
+pub mod icrc3;
 pub mod ledger;
 pub mod range_utils;
 pub mod runtime;
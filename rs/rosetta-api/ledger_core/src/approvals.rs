@@ -17,6 +17,20 @@ pub struct ExpiredApproval {
     pub now: TimeStamp,
 }
 
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ApproveError {
+    /// The requested expiration time is not in the future.
+    ExpiredApproval { now: TimeStamp },
+    /// The current allowance does not match the expected allowance.
+    AllowanceChanged { current_allowance: Tokens },
+}
+
+impl From<ExpiredApproval> for ApproveError {
+    fn from(e: ExpiredApproval) -> Self {
+        Self::ExpiredApproval { now: e.now }
+    }
+}
+
 pub trait Approvals {
     type AccountId;
     type SpenderId;
@@ -29,15 +43,20 @@ pub trait Approvals {
         now: TimeStamp,
     ) -> Allowance;
 
-    /// Increases the spender's allowance for the account by the specified amount.
+    /// Sets the spender's allowance for the account to the specified amount.
+    ///
+    /// If `expected_allowance` is set, the table updates the allowance only
+    /// if the current allowance matches the expected value.
+    /// Setting the allowance to zero removes the approval.
     fn approve(
         &mut self,
         account: &Self::AccountId,
         spender: &Self::SpenderId,
         amount: Tokens,
+        expected_allowance: Option<Tokens>,
         expires_at: Option<TimeStamp>,
         now: TimeStamp,
-    ) -> Result<Tokens, ExpiredApproval>;
+    ) -> Result<Tokens, ApproveError>;
 
     /// Decreases the spender's allowance for the account by the specified amount.
     ///
@@ -130,17 +149,28 @@ where
         account: &AccountId,
         spender: &SpenderId,
         amount: Tokens,
+        expected_allowance: Option<Tokens>,
         expires_at: Option<TimeStamp>,
         now: TimeStamp,
-    ) -> Result<Tokens, ExpiredApproval> {
+    ) -> Result<Tokens, ApproveError> {
         if expires_at.unwrap_or_else(remote_future) <= now {
-            return Err(ExpiredApproval { now });
+            return Err(ApproveError::ExpiredApproval { now });
         }
 
         let key = K::from((account, spender));
 
         match self.allowances.entry(key.clone()) {
             Entry::Vacant(e) => {
+                if let Some(expected_allowance) = expected_allowance {
+                    if expected_allowance != Tokens::ZERO {
+                        return Err(ApproveError::AllowanceChanged {
+                            current_allowance: Tokens::ZERO,
+                        });
+                    }
+                }
+                if amount == Tokens::ZERO {
+                    return Ok(amount);
+                }
                 if let Some(expires_at) = expires_at {
                     self.expiration_queue.push(Reverse((expires_at, key)));
                 }
@@ -148,23 +178,32 @@ where
                 Ok(amount)
             }
             Entry::Occupied(mut e) => {
-                let old_expiration = if e.get().expires_at.unwrap_or_else(remote_future) <= now {
-                    // Overwrite the allowance if the previous approval expired.
-                    let allowance = e.get_mut();
-                    allowance.amount = amount;
-                    std::mem::replace(&mut allowance.expires_at, expires_at)
+                let current_allowance = if e.get().expires_at.unwrap_or_else(remote_future) <= now {
+                    // The previous approval expired.
+                    Tokens::ZERO
                 } else {
-                    let allowance = e.get_mut();
-                    allowance.amount = allowance.amount.saturating_add(amount);
-                    std::mem::replace(&mut allowance.expires_at, expires_at)
+                    e.get().amount
                 };
+                if let Some(expected_allowance) = expected_allowance {
+                    if expected_allowance != current_allowance {
+                        return Err(ApproveError::AllowanceChanged { current_allowance });
+                    }
+                }
+                if amount == Tokens::ZERO {
+                    e.remove();
+                    return Ok(amount);
+                }
+
+                let allowance = e.get_mut();
+                allowance.amount = amount;
+                let old_expiration = std::mem::replace(&mut allowance.expires_at, expires_at);
 
                 if expires_at != old_expiration {
                     if let Some(expires_at) = expires_at {
                         self.expiration_queue.push(Reverse((expires_at, key)));
                     }
                 }
-                Ok(e.get().amount)
+                Ok(amount)
             }
         }
     }
@@ -252,7 +291,6 @@ where
         for _ in 0..limit {
             match self.expiration_queue.peek() {
                 Some(Reverse((ts, _key))) => {
-                    println!("{:?}", ts);
                     if *ts > now {
                         return pruned;
                     }
@@ -34,7 +34,7 @@ fn allowance_table_default() {
 }
 
 #[test]
-fn allowance_table_cumulative() {
+fn allowance_table_overwrite() {
     let mut table = TestAllowanceTable::default();
 
     assert_eq!(
@@ -43,7 +43,7 @@ fn allowance_table_cumulative() {
     );
 
     table
-        .approve(&Account(1), &Spender(1), tokens(5), None, ts(1))
+        .approve(&Account(1), &Spender(1), tokens(5), None, None, ts(1))
         .unwrap();
 
     assert_eq!(
@@ -55,25 +55,32 @@ fn allowance_table_cumulative() {
     );
 
     table
-        .approve(&Account(1), &Spender(1), tokens(15), None, ts(1))
+        .approve(&Account(1), &Spender(1), tokens(15), None, None, ts(1))
         .unwrap();
 
     assert_eq!(
         table.allowance(&Account(1), &Spender(1), ts(1)),
         Allowance {
-            amount: tokens(20),
+            amount: tokens(15),
             expires_at: None
         }
     );
 
     table
-        .approve(&Account(1), &Spender(1), tokens(10), Some(ts(5)), ts(1))
+        .approve(
+            &Account(1),
+            &Spender(1),
+            tokens(10),
+            None,
+            Some(ts(5)),
+            ts(1),
+        )
         .unwrap();
 
     assert_eq!(
         table.allowance(&Account(1), &Spender(1), ts(1)),
         Allowance {
-            amount: tokens(30),
+            amount: tokens(10),
             expires_at: Some(ts(5))
         }
     );
@@ -84,12 +91,102 @@ fn allowance_table_cumulative() {
     );
 }
 
+#[test]
+fn allowance_table_expected_allowance() {
+    let mut table = TestAllowanceTable::default();
+
+    assert_eq!(
+        table
+            .approve(
+                &Account(1),
+                &Spender(1),
+                tokens(5),
+                Some(tokens(10)),
+                None,
+                ts(1)
+            )
+            .unwrap_err(),
+        ApproveError::AllowanceChanged {
+            current_allowance: tokens(0)
+        }
+    );
+
+    table
+        .approve(
+            &Account(1),
+            &Spender(1),
+            tokens(5),
+            Some(tokens(0)),
+            None,
+            ts(1),
+        )
+        .unwrap();
+
+    assert_eq!(
+        table
+            .approve(
+                &Account(1),
+                &Spender(1),
+                tokens(7),
+                Some(tokens(4)),
+                None,
+                ts(1)
+            )
+            .unwrap_err(),
+        ApproveError::AllowanceChanged {
+            current_allowance: tokens(5)
+        }
+    );
+
+    assert_eq!(
+        table
+            .approve(
+                &Account(1),
+                &Spender(1),
+                tokens(7),
+                Some(tokens(5)),
+                None,
+                ts(1)
+            )
+            .unwrap(),
+        tokens(7)
+    );
+
+    assert_eq!(
+        table
+            .approve(&Account(1), &Spender(1), tokens(0), None, None, ts(1))
+            .unwrap(),
+        tokens(0)
+    );
+
+    assert_eq!(table.len(), 0);
+}
+
+#[test]
+fn allowance_table_rejects_expired_approval() {
+    let mut table = TestAllowanceTable::default();
+
+    assert_eq!(
+        table
+            .approve(
+                &Account(1),
+                &Spender(1),
+                tokens(5),
+                None,
+                Some(ts(1)),
+                ts(2)
+            )
+            .unwrap_err(),
+        ApproveError::ExpiredApproval { now: ts(2) }
+    );
+}
+
 #[test]
 fn allowance_use_approval() {
     let mut table = TestAllowanceTable::default();
 
     table
-        .approve(&Account(1), &Spender(1), tokens(100), None, ts(1))
+        .approve(&Account(1), &Spender(1), tokens(100), None, None, ts(1))
         .unwrap();
 
     assert_eq!(
@@ -128,7 +225,7 @@ fn decrease_allowance() {
     let mut table = TestAllowanceTable::default();
 
     table
-        .approve(&Account(1), &Spender(1), tokens(100), None, ts(1))
+        .approve(&Account(1), &Spender(1), tokens(100), None, None, ts(1))
         .unwrap();
 
     assert_eq!(
@@ -179,11 +276,18 @@ fn allowance_table_pruning() {
     let mut table = TestAllowanceTable::default();
 
     table
-        .approve(&Account(1), &Spender(1), tokens(100), None, ts(1))
+        .approve(&Account(1), &Spender(1), tokens(100), None, None, ts(1))
         .unwrap();
 
     table
-        .approve(&Account(1), &Spender(2), tokens(100), Some(ts(100)), ts(1))
+        .approve(
+            &Account(1),
+            &Spender(2),
+            tokens(100),
+            None,
+            Some(ts(100)),
+            ts(1),
+        )
         .unwrap();
 
     assert_eq!(table.len(), 2);
@@ -199,11 +303,25 @@ fn allowance_table_pruning_obsolete_expirations() {
     let mut table = TestAllowanceTable::default();
 
     table
-        .approve(&Account(1), &Spender(1), tokens(100), Some(ts(100)), ts(1))
+        .approve(
+            &Account(1),
+            &Spender(1),
+            tokens(100),
+            None,
+            Some(ts(100)),
+            ts(1),
+        )
         .unwrap();
 
     table
-        .approve(&Account(1), &Spender(1), tokens(100), Some(ts(300)), ts(1))
+        .approve(
+            &Account(1),
+            &Spender(1),
+            tokens(100),
+            None,
+            Some(ts(300)),
+            ts(1),
+        )
         .unwrap();
 
     assert_eq!(table.len(), 1);
@@ -215,7 +333,7 @@ fn allowance_table_pruning_obsolete_expirations() {
     assert_eq!(
         table.allowance(&Account(1), &Spender(1), ts(200)),
         Allowance {
-            amount: tokens(200),
+            amount: tokens(100),
             expires_at: Some(ts(300))
         }
     );
@@ -1,3 +1,4 @@
+pub mod icrc1;
 mod state;
 
 use crate::convert::state::State;
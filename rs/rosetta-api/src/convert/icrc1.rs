@@ -0,0 +1,240 @@
+//! Conversions between ICRC-1 ledger data structures and Rosetta data
+//! structures, used when the node serves an ICRC-1 ledger.
+
+use crate::convert::principal_id_from_public_key;
+use crate::errors::ApiError;
+use crate::models::amount::from_amount_with_decimals;
+use crate::models::operation::OperationType;
+use crate::models::{self, operation::Operation, AccountIdentifier};
+use crate::request::Request;
+use crate::request_types::{Icrc1Transfer, TransactionBuilder, STATUS_COMPLETED};
+use crate::transaction_id::TransactionIdentifier;
+use candid::Nat;
+use ic_icrc1::endpoints::TransferArg;
+use ic_icrc1::{Account, Block};
+use ic_ledger_canister_blocks_synchronizer::blocks::HashedBlock;
+use ic_ledger_canister_blocks_synchronizer::ledger_kind::icrc1_account_key;
+use ic_ledger_canister_core::ledger::LedgerTransaction;
+use ic_ledger_core::block::BlockType;
+use icp_ledger::Tokens;
+use num_traits::ToPrimitive;
+use serde_json::map::Map;
+use serde_json::{Number, Value};
+use std::str::FromStr;
+
+pub fn block_to_transaction(
+    hb: &HashedBlock,
+    token_name: &str,
+    decimals: u32,
+) -> Result<models::Transaction, ApiError> {
+    let block = Block::decode(hb.block.clone())
+        .map_err(|err| ApiError::internal_error(format!("Cannot decode block: {}", err)))?;
+    let transaction = block.transaction;
+    let transaction_identifier = TransactionIdentifier {
+        hash: format!("{}", transaction.hash()),
+    };
+    let fee = match &transaction.operation {
+        ic_icrc1::Operation::Transfer { fee, .. } | ic_icrc1::Operation::Approve { fee, .. } => {
+            fee.or(block.effective_fee).unwrap_or(0)
+        }
+        ic_icrc1::Operation::Mint { .. } | ic_icrc1::Operation::Burn { .. } => 0,
+    };
+    let operations = {
+        let mut builder = TransactionBuilder::default();
+        builder.icrc1_operation(&transaction.operation, fee, token_name, decimals);
+        let mut ops = builder.build();
+        for op in ops.iter_mut() {
+            op.status = Some(STATUS_COMPLETED.to_string());
+        }
+        ops
+    };
+    let mut t = models::Transaction::new(transaction_identifier, operations);
+    let mut metadata = Map::new();
+    if let Some(memo) = transaction.memo {
+        metadata.insert("memo".to_string(), Value::String(hex::encode(memo.0)));
+    }
+    metadata.insert(
+        "block_height".to_string(),
+        Value::Number(Number::from(hb.index)),
+    );
+    metadata.insert(
+        "timestamp".to_string(),
+        Value::Number(Number::from(block.timestamp)),
+    );
+    t.metadata = Some(metadata);
+    Ok(t)
+}
+
+/// Convert from operations to ICRC-1 transfer requests.
+///
+/// Only `TRANSACTION` and `FEE` operations are supported; every transfer
+/// consists of a debit, a credit and a fee operation.
+pub fn operations_to_requests(
+    ops: &[Operation],
+    preprocessing: bool,
+    token_name: &str,
+    decimals: u32,
+) -> Result<Vec<Request>, ApiError> {
+    let op_error = |op: &Operation, e| {
+        let msg = format!("In operation '{:?}': {}", op, e);
+        ApiError::InvalidTransaction(false, msg.into())
+    };
+
+    let mut state = State {
+        preprocessing,
+        decimals,
+        ..State::default()
+    };
+
+    for o in ops {
+        if o.coin_change.is_some() {
+            return Err(op_error(o, "Coin changes are not permitted".into()));
+        }
+        let account = o
+            .account
+            .as_ref()
+            .ok_or_else(|| op_error(o, "Account must be populated".into()))
+            .and_then(|a| from_model_account_identifier(a).map_err(|e| op_error(o, e)))?;
+        let amount = o
+            .amount
+            .as_ref()
+            .ok_or_else(|| op_error(o, "Amount must be populated".into()))
+            .and_then(|a| {
+                from_amount_with_decimals(a, token_name, decimals).map_err(|e| op_error(o, e))
+            })?;
+
+        match o._type {
+            OperationType::Transaction => state.transaction(account, amount)?,
+            OperationType::Fee => state.fee(account, Tokens::from_e8s((-amount) as u64))?,
+            _ => {
+                let msg = format!("Unsupported operation type: {:?}", o._type);
+                return Err(op_error(o, msg));
+            }
+        }
+    }
+
+    state.flush()?;
+
+    if state.actions.is_empty() {
+        return Err(ApiError::InvalidTransaction(
+            false,
+            "Operations don't contain any actions.".into(),
+        ));
+    }
+
+    Ok(state.actions)
+}
+
+/// Helper for `operations_to_requests` that creates `Icrc1Transfer`s from
+/// related debit/credit/fee operations.
+#[derive(Default)]
+struct State {
+    preprocessing: bool,
+    decimals: u32,
+    actions: Vec<Request>,
+    credit: Option<(Account, Tokens)>,
+    debit: Option<(Account, Tokens)>,
+    fee: Option<(Account, Tokens)>,
+}
+
+impl State {
+    fn flush(&mut self) -> Result<(), ApiError> {
+        let trans_err = |msg: &str| {
+            let msg = format!("Bad transaction: {}", msg);
+            Err(ApiError::InvalidTransaction(false, msg.into()))
+        };
+
+        if self.credit.is_none() && self.debit.is_none() && self.fee.is_none() {
+            return Ok(());
+        }
+
+        // The fee is only known after construction_metadata.
+        if self.preprocessing && self.fee.is_none() {
+            if let Some((from, _)) = self.debit {
+                self.fee = Some((from, Tokens::ZERO));
+            }
+        }
+
+        let (to, cr_amount, from, db_amount, fee_acc, fee) =
+            match (self.credit.take(), self.debit.take(), self.fee.take()) {
+                (Some((to, cr_amount)), Some((from, db_amount)), Some((fee_acc, fee))) => {
+                    (to, cr_amount, from, db_amount, fee_acc, fee)
+                }
+                _ => {
+                    return trans_err(
+                        "Operations do not combine to make a recognizable transaction",
+                    )
+                }
+            };
+
+        if fee_acc != from {
+            return trans_err(&format!("Fee should be taken from {}", from));
+        }
+        if cr_amount != db_amount {
+            return trans_err("Debit_amount should be equal -credit_amount");
+        }
+
+        self.actions.push(Request::Icrc1Transfer(Icrc1Transfer {
+            from,
+            to,
+            amount: cr_amount,
+            fee,
+            decimals: self.decimals,
+        }));
+        Ok(())
+    }
+
+    fn transaction(&mut self, account: Account, amount: i128) -> Result<(), ApiError> {
+        if amount > 0 || self.debit.is_some() && amount == 0 {
+            if self.credit.is_some() {
+                self.flush()?;
+            }
+            self.credit = Some((account, Tokens::from_e8s(amount as u64)));
+        } else {
+            if self.debit.is_some() {
+                self.flush()?;
+            }
+            self.debit = Some((account, Tokens::from_e8s((-amount) as u64)));
+        }
+        Ok(())
+    }
+
+    fn fee(&mut self, account: Account, amount: Tokens) -> Result<(), ApiError> {
+        if self.fee.is_some() {
+            self.flush()?;
+        }
+        self.fee = Some((account, amount));
+        Ok(())
+    }
+}
+
+pub fn to_model_account_identifier(account: &Account) -> AccountIdentifier {
+    AccountIdentifier::new(icrc1_account_key(account))
+}
+
+pub fn from_model_account_identifier(aid: &AccountIdentifier) -> Result<Account, String> {
+    Account::from_str(&aid.address)
+}
+
+pub fn account_from_public_key(pk: &models::PublicKey) -> Result<AccountIdentifier, ApiError> {
+    let pid = principal_id_from_public_key(pk)?;
+    Ok(to_model_account_identifier(&Account::from(pid)))
+}
+
+pub fn nat_to_tokens(n: &Nat) -> Result<Tokens, ApiError> {
+    n.0.to_u64()
+        .map(Tokens::from_e8s)
+        .ok_or_else(|| ApiError::internal_error(format!("{} does not fit into u64", n)))
+}
+
+pub fn from_transfer_arg(encoded: &[u8]) -> Result<TransferArg, ApiError> {
+    candid::decode_one(encoded).map_err(|e| {
+        ApiError::internal_error(format!("Could not decode icrc1_transfer argument: {}", e))
+    })
+}
+
+pub fn to_transfer_arg(arg: &TransferArg) -> Result<Vec<u8>, ApiError> {
+    candid::encode_one(arg).map_err(|e| {
+        ApiError::internal_error(format!("Could not encode icrc1_transfer argument: {}", e))
+    })
+}
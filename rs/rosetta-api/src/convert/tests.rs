@@ -203,3 +203,36 @@ fn account_identifier_decode_test() {
     )
     .unwrap_err();
 }
+
+#[test]
+fn test_icrc1_transfer_operations_roundtrip() {
+    let decimals = 6;
+    let transfer = Request::Icrc1Transfer(crate::request_types::Icrc1Transfer {
+        from: ic_icrc1::Account {
+            owner: PrincipalId::new_user_test_id(1),
+            subaccount: Some([1; 32]),
+        },
+        to: ic_icrc1::Account::from(PrincipalId::new_user_test_id(2)),
+        amount: Tokens::from_e8s(100),
+        fee: Tokens::from_e8s(10),
+        decimals,
+    });
+
+    let ops = Request::requests_to_operations(&[transfer.clone()], DEFAULT_TOKEN_SYMBOL).unwrap();
+    assert_eq!(ops.len(), 3);
+    assert_eq!(
+        ops[1].account,
+        Some(models::AccountIdentifier::new(
+            PrincipalId::new_user_test_id(2).to_string()
+        ))
+    );
+    assert!(ops
+        .iter()
+        .all(|op| op.amount.as_ref().unwrap().currency.decimals == decimals));
+
+    assert_eq!(
+        icrc1::operations_to_requests(&ops, false, DEFAULT_TOKEN_SYMBOL, decimals),
+        Ok(vec![transfer])
+    );
+    assert!(icrc1::operations_to_requests(&ops, false, DEFAULT_TOKEN_SYMBOL, 8).is_err());
+}
@@ -2,6 +2,7 @@ mod handle_add_hotkey;
 mod handle_change_auto_stake_maturity;
 mod handle_disburse;
 mod handle_follow;
+mod handle_icrc1_transfer;
 mod handle_merge_maturity;
 mod handle_neuron_info;
 mod handle_register_vote;
@@ -30,9 +31,12 @@ use dfn_candid::CandidOne;
 use ic_ledger_canister_blocks_synchronizer::blocks::Blocks;
 use ic_ledger_canister_blocks_synchronizer::canister_access::CanisterAccess;
 use ic_ledger_canister_blocks_synchronizer::certification::VerificationInfo;
+use ic_ledger_canister_blocks_synchronizer::checkpoint::Checkpoint;
 use ic_ledger_canister_blocks_synchronizer::ledger_blocks_sync::{
     LedgerBlocksSynchronizer, LedgerBlocksSynchronizerMetrics,
 };
+use ic_ledger_canister_blocks_synchronizer::ledger_kind::LedgerKind;
+use ic_ledger_core::tokens::DECIMAL_PLACES;
 use ic_nns_governance::pb::v1::{manage_neuron::NeuronIdOrSubaccount, GovernanceError, NeuronInfo};
 use ic_types::messages::{HttpCallContent, MessageId};
 use ic_types::CanisterId;
@@ -47,18 +51,18 @@ use crate::ledger_client::{
     handle_add_hotkey::handle_add_hotkey,
     handle_change_auto_stake_maturity::handle_change_auto_stake_maturity,
     handle_disburse::handle_disburse, handle_follow::handle_follow,
-    handle_merge_maturity::handle_merge_maturity, handle_neuron_info::handle_neuron_info,
-    handle_register_vote::handle_register_vote, handle_remove_hotkey::handle_remove_hotkey,
-    handle_send::handle_send, handle_set_dissolve_timestamp::handle_set_dissolve_timestamp,
-    handle_spawn::handle_spawn, handle_stake::handle_stake,
-    handle_stake_maturity::handle_stake_maturity, handle_start_dissolve::handle_start_dissolve,
-    handle_stop_dissolve::handle_stop_dissolve,
+    handle_icrc1_transfer::handle_icrc1_transfer, handle_merge_maturity::handle_merge_maturity,
+    handle_neuron_info::handle_neuron_info, handle_register_vote::handle_register_vote,
+    handle_remove_hotkey::handle_remove_hotkey, handle_send::handle_send,
+    handle_set_dissolve_timestamp::handle_set_dissolve_timestamp, handle_spawn::handle_spawn,
+    handle_stake::handle_stake, handle_stake_maturity::handle_stake_maturity,
+    handle_start_dissolve::handle_start_dissolve, handle_stop_dissolve::handle_stop_dissolve,
 };
 use crate::models::{EnvelopePair, Object, SignedTransaction};
 use crate::request::request_result::RequestResult;
 use crate::request::transaction_results::TransactionResults;
 use crate::request::Request;
-use crate::request_types::{RequestType, Status};
+use crate::request_types::{Icrc1Transfer, RequestType, Status};
 use crate::transaction_id::TransactionIdentifier;
 
 fn waiter() -> garcon::Delay {
@@ -92,6 +96,14 @@ pub trait LedgerAccess {
     fn ledger_canister_id(&self) -> &CanisterId;
     fn governance_canister_id(&self) -> &CanisterId;
     fn token_symbol(&self) -> &str;
+    /// The kind of ledger this node serves.
+    fn ledger_kind(&self) -> LedgerKind {
+        LedgerKind::Icp
+    }
+    /// The number of decimals of the ledger's token.
+    fn token_decimals(&self) -> u32 {
+        DECIMAL_PLACES
+    }
     async fn submit(&self, _envelopes: SignedTransaction) -> Result<TransactionResults, ApiError>;
     async fn cleanup(&self);
     async fn neuron_info(
@@ -110,6 +122,8 @@ pub struct LedgerClient {
     canister_access: Option<Arc<CanisterAccess>>,
     ic_url: Url,
     token_symbol: String,
+    token_decimals: u32,
+    ledger_kind: LedgerKind,
     offline: bool,
 }
 
@@ -130,43 +144,67 @@ impl LedgerClient {
         ic_url: Url,
         canister_id: CanisterId,
         token_symbol: String,
+        token_decimals: u32,
+        ledger_kind: LedgerKind,
         governance_canister_id: CanisterId,
         store_location: Option<&std::path::Path>,
         store_max_blocks: Option<u64>,
+        checkpoint: Option<Checkpoint>,
         offline: bool,
         root_key: Option<ThresholdSigPublicKey>,
     ) -> Result<LedgerClient, ApiError> {
+        let verification_info = root_key.map(|root_key| VerificationInfo {
+            root_key,
+            canister_id,
+        });
+        // ICRC-1 ledgers don't certify the tip hash directly, so the canister
+        // access verifies their tip certificates itself.
+        let (access_verification_info, sync_verification_info) = match ledger_kind {
+            LedgerKind::Icp => (None, verification_info),
+            LedgerKind::Icrc1 => (verification_info, None),
+        };
         let canister_access = if offline {
             None
         } else {
-            let canister_access = CanisterAccess::new(
+            let canister_access = CanisterAccess::new_with_kind(
                 ic_url.clone(),
                 canister_id,
                 root_key.map(public_key_to_der).transpose()?,
+                ledger_kind,
+                access_verification_info,
             )
             .await
             .map_err(|e| ApiError::internal_error(format!("{}", e)))?;
-            LedgerClient::check_ledger_symbol(&token_symbol, &canister_access).await?;
+            LedgerClient::check_ledger_symbol(&token_symbol, ledger_kind, &canister_access).await?;
             Some(Arc::new(canister_access))
         };
-        let verification_info = root_key.map(|root_key| VerificationInfo {
-            root_key,
-            canister_id,
-        });
         let ledger_blocks_synchronizer = LedgerBlocksSynchronizer::new(
             canister_access.clone(),
             store_location,
             store_max_blocks,
-            verification_info,
+            sync_verification_info,
+            ledger_kind,
             Box::new(LedgerBlocksSynchronizerMetricsImpl {}),
         )
         .await?;
+        if let Some(checkpoint) = checkpoint {
+            if offline {
+                return Err(ApiError::internal_error(
+                    "Cannot bootstrap from a checkpoint in offline mode",
+                ));
+            }
+            ledger_blocks_synchronizer
+                .bootstrap_from_checkpoint(&checkpoint, Arc::new(AtomicBool::new(false)))
+                .await?;
+        }
 
         Ok(Self {
             ledger_blocks_synchronizer,
             canister_id,
             root_key,
             token_symbol,
+            token_decimals,
+            ledger_kind,
             governance_canister_id,
             canister_access,
             ic_url,
@@ -176,23 +214,31 @@ impl LedgerClient {
 
     async fn check_ledger_symbol(
         token_symbol: &str,
+        ledger_kind: LedgerKind,
         canister_access: &CanisterAccess,
     ) -> Result<(), ApiError> {
         let arg = CandidOne(())
             .into_bytes()
             .map_err(|e| ApiError::internal_error(format!("Serialization failed: {:?}", e)))?;
 
-        let symbol_res: Result<Symbol, String> = canister_access
+        let method = match ledger_kind {
+            LedgerKind::Icp => "symbol",
+            LedgerKind::Icrc1 => "icrc1_symbol",
+        };
+        let symbol_res: Result<String, String> = canister_access
             .agent
-            .query(&canister_access.canister_id.get().0, "symbol")
+            .query(&canister_access.canister_id.get().0, method)
             .with_arg(arg)
             .call()
             .await
             .map_err(|e| format!("{}", e))
-            .and_then(|bytes| CandidOne::from_bytes(bytes).map(|c| c.0));
+            .and_then(|bytes| match ledger_kind {
+                LedgerKind::Icp => CandidOne::<Symbol>::from_bytes(bytes).map(|c| c.0.symbol),
+                LedgerKind::Icrc1 => CandidOne::<String>::from_bytes(bytes).map(|c| c.0),
+            });
 
         match symbol_res {
-            Ok(Symbol { symbol }) => {
+            Ok(symbol) => {
                 if symbol != token_symbol {
                     return Err(ApiError::internal_error(format!(
                         "The ledger serves a different token ({}) than specified ({})",
@@ -243,6 +289,14 @@ impl LedgerAccess for LedgerClient {
         &self.token_symbol
     }
 
+    fn ledger_kind(&self) -> LedgerKind {
+        self.ledger_kind
+    }
+
+    fn token_decimals(&self) -> u32 {
+        self.token_decimals
+    }
+
     async fn submit(&self, envelopes: SignedTransaction) -> Result<TransactionResults, ApiError> {
         if self.offline {
             return Err(ApiError::NotAvailableOffline(false, Details::default()));
@@ -254,7 +308,7 @@ impl LedgerAccess for LedgerClient {
             .iter()
             .map(|e| {
                 Request::try_from(e).map(|_type| RequestResult {
-                    _type,
+                    _type: self.with_token_decimals(_type),
                     block_index: None,
                     neuron_id: None,
                     transaction_identifier: None,
@@ -349,6 +403,25 @@ impl LedgerAccess for LedgerClient {
 
     async fn transfer_fee(&self) -> Result<TransferFee, ApiError> {
         let agent = &self.canister_access.as_ref().unwrap().agent;
+        if self.ledger_kind == LedgerKind::Icrc1 {
+            let arg = CandidOne(())
+                .into_bytes()
+                .map_err(|e| ApiError::internal_error(format!("Serialization failed: {:?}", e)))?;
+            let bytes = agent
+                .query(&self.canister_id.get().0, "icrc1_fee")
+                .with_arg(arg)
+                .call()
+                .await
+                .map_err(|e| {
+                    ApiError::internal_error(format!("Error querying icrc1_fee: {}", e))
+                })?;
+            let fee: candid::Nat = CandidOne::from_bytes(bytes).map(|c| c.0).map_err(|e| {
+                ApiError::internal_error(format!("Error decoding icrc1_fee: {}", e))
+            })?;
+            return Ok(TransferFee {
+                transfer_fee: convert::icrc1::nat_to_tokens(&fee)?,
+            });
+        }
         let arg = CandidOne(TransferFeeArgs {})
             .into_bytes()
             .map_err(|e| ApiError::internal_error(format!("Serialization failed: {:?}", e)))?;
@@ -386,6 +459,18 @@ impl LedgerAccess for LedgerClient {
 }
 
 impl LedgerClient {
+    /// Requests parsed from envelopes don't know the decimals of the ledger's
+    /// token, which are needed to render ICRC-1 transfers as operations.
+    fn with_token_decimals(&self, request: Request) -> Request {
+        match request {
+            Request::Icrc1Transfer(transfer) => Request::Icrc1Transfer(Icrc1Transfer {
+                decimals: self.token_decimals,
+                ..transfer
+            }),
+            request => request,
+        }
+    }
+
     // Exponential backoff from 100ms to 10s with a multiplier of 1.3.
     const MIN_POLL_INTERVAL: Duration = Duration::from_millis(100);
     const MAX_POLL_INTERVAL: Duration = Duration::from_secs(10);
@@ -672,6 +757,7 @@ impl LedgerClient {
             RequestType::AddHotKey { .. } => handle_add_hotkey(bytes),
             RequestType::Disburse { .. } => handle_disburse(bytes),
             RequestType::Follow { .. } => handle_follow(bytes),
+            RequestType::Icrc1Transfer => handle_icrc1_transfer(bytes),
             RequestType::MergeMaturity { .. } => handle_merge_maturity(bytes),
             RequestType::RegisterVote { .. } => handle_register_vote(bytes),
             RequestType::StakeMaturity { .. } => handle_stake_maturity(bytes),
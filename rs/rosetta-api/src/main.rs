@@ -1,6 +1,10 @@
 use clap::Parser;
 use ic_crypto_internal_threshold_sig_bls12381 as bls12_381;
 use ic_crypto_utils_threshold_sig_der::parse_threshold_sig_key;
+use ic_ledger_canister_blocks_synchronizer::checkpoint::Checkpoint;
+use ic_ledger_canister_blocks_synchronizer::ledger_kind::LedgerKind;
+use ic_ledger_core::tokens::DECIMAL_PLACES;
+use ic_rosetta_api::ledger_client::LedgerAccess;
 use ic_rosetta_api::request_handler::RosettaRequestHandler;
 use ic_rosetta_api::rosetta_server::{RosettaApiServer, RosettaApiServerOpt};
 use ic_rosetta_api::{ledger_client, DEFAULT_BLOCKCHAIN, DEFAULT_TOKEN_SYMBOL};
@@ -38,8 +42,18 @@ struct Opt {
     store_type: String,
     #[clap(long = "store-location", default_value = "./data")]
     store_location: PathBuf,
+    /// Prune the store to keep only about the last N blocks, plus the
+    /// balances of all accounts.
     #[clap(long = "store-max-blocks")]
     store_max_blocks: Option<u64>,
+    /// Bootstrap an empty store from a checkpoint file written with
+    /// --export-checkpoint instead of syncing from the genesis block.
+    #[clap(long = "bootstrap-checkpoint")]
+    bootstrap_checkpoint: Option<PathBuf>,
+    /// Write a checkpoint of the latest verified block of the store to the
+    /// given file and exit.
+    #[clap(long = "export-checkpoint")]
+    export_checkpoint: Option<PathBuf>,
     #[clap(long = "exit-on-sync")]
     exit_on_sync: bool,
     #[clap(long = "offline")]
@@ -53,6 +67,17 @@ struct Opt {
     not_whitelisted: bool,
     #[clap(long = "expose-metrics")]
     expose_metrics: bool,
+    /// Id of an ICRC-1 ledger canister. If set, the node serves this ledger
+    /// instead of the ICP ledger.
+    #[clap(long = "icrc1-ledger-canister-id")]
+    icrc1_ledger_canister_id: Option<String>,
+    /// The symbol of the ICRC-1 ledger's token, required with
+    /// --icrc1-ledger-canister-id.
+    #[clap(long = "icrc1-symbol")]
+    icrc1_symbol: Option<String>,
+    /// The number of decimals of the ICRC-1 ledger's token.
+    #[clap(long = "icrc1-decimals", default_value = "8")]
+    icrc1_decimals: u32,
 }
 
 #[actix_web::main]
@@ -136,9 +161,29 @@ async fn main() -> std::io::Result<()> {
         (root_key, canister_id, governance_canister_id, url)
     };
 
-    let token_symbol = opt
-        .token_symbol
-        .unwrap_or_else(|| DEFAULT_TOKEN_SYMBOL.to_string());
+    let (ledger_kind, canister_id, token_symbol, token_decimals) = match opt
+        .icrc1_ledger_canister_id
+    {
+        Some(cid) => {
+            let canister_id = CanisterId::new(PrincipalId::from_str(&cid[..]).unwrap()).unwrap();
+            let token_symbol = opt
+                .icrc1_symbol
+                .expect("--icrc1-symbol is required with --icrc1-ledger-canister-id");
+            log::info!("Serving the ICRC-1 ledger {}", canister_id);
+            (
+                LedgerKind::Icrc1,
+                canister_id,
+                token_symbol,
+                opt.icrc1_decimals,
+            )
+        }
+        None => {
+            let token_symbol = opt
+                .token_symbol
+                .unwrap_or_else(|| DEFAULT_TOKEN_SYMBOL.to_string());
+            (LedgerKind::Icp, canister_id, token_symbol, DECIMAL_PLACES)
+        }
+    };
     log::info!("Token symbol set to {}", token_symbol);
 
     let store_location: Option<&Path> = match opt.store_type.as_ref() {
@@ -153,8 +198,14 @@ async fn main() -> std::io::Result<()> {
         }
     };
 
+    let checkpoint = opt.bootstrap_checkpoint.as_ref().map(|path| {
+        Checkpoint::read_from_file(path)
+            .unwrap_or_else(|e| panic!("Failed to load the checkpoint: {}", e))
+    });
+
     let Opt {
         store_max_blocks,
+        export_checkpoint,
         offline,
         exit_on_sync,
         mainnet,
@@ -167,9 +218,12 @@ async fn main() -> std::io::Result<()> {
         url,
         canister_id,
         token_symbol,
+        token_decimals,
+        ledger_kind,
         governance_canister_id,
         store_location,
         store_max_blocks,
+        checkpoint,
         offline,
         root_key,
     )
@@ -182,6 +236,23 @@ async fn main() -> std::io::Result<()> {
     })
     .unwrap_or_else(|(e, is_403)| panic!("Failed to initialize ledger client{}: {:?}", is_403, e));
 
+    if let Some(path) = export_checkpoint {
+        let blocks = client.read_blocks().await;
+        let checkpoint = blocks
+            .get_latest_verified_hashed_block()
+            .and_then(|hb| blocks.get_checkpoint(&hb.index))
+            .unwrap_or_else(|e| panic!("Failed to create a checkpoint: {:?}", e));
+        checkpoint
+            .write_to_file(&path)
+            .unwrap_or_else(|e| panic!("Failed to write the checkpoint: {}", e));
+        log::info!(
+            "Wrote the checkpoint at block {} to {}",
+            checkpoint.block.index,
+            path.display()
+        );
+        return Ok(());
+    }
+
     let ledger = Arc::new(client);
     let req_handler = RosettaRequestHandler::new(blockchain, ledger.clone());
 
@@ -35,24 +35,45 @@ impl Amount {
 }
 
 pub fn tokens_to_amount(tokens: Tokens, token_name: &str) -> Result<Amount, ApiError> {
+    tokens_to_amount_with_decimals(tokens, token_name, DECIMAL_PLACES)
+}
+
+/// Like [tokens_to_amount], for tokens that use `decimals` decimal places.
+pub fn tokens_to_amount_with_decimals(
+    tokens: Tokens,
+    token_name: &str,
+    decimals: u32,
+) -> Result<Amount, ApiError> {
     let amount = tokens.get_e8s();
     Ok(Amount {
         value: format!("{}", amount),
-        currency: Currency::new(token_name.into(), DECIMAL_PLACES),
+        currency: Currency::new(token_name.into(), decimals),
         metadata: None,
     })
 }
 
 pub fn signed_amount(amount: i128, token_name: &str) -> Amount {
+    signed_amount_with_decimals(amount, token_name, DECIMAL_PLACES)
+}
+
+pub fn signed_amount_with_decimals(amount: i128, token_name: &str, decimals: u32) -> Amount {
     Amount {
         value: format!("{}", amount),
-        currency: Currency::new(token_name.into(), DECIMAL_PLACES),
+        currency: Currency::new(token_name.into(), decimals),
         metadata: None,
     }
 }
 
 pub fn from_amount(amount: &Amount, token_name: &str) -> Result<i128, String> {
-    let cur = Currency::new(token_name.into(), DECIMAL_PLACES);
+    from_amount_with_decimals(amount, token_name, DECIMAL_PLACES)
+}
+
+pub fn from_amount_with_decimals(
+    amount: &Amount,
+    token_name: &str,
+    decimals: u32,
+) -> Result<i128, String> {
+    let cur = Currency::new(token_name.into(), decimals);
     match amount {
         Amount {
             value,
@@ -58,6 +58,8 @@ pub enum Request {
     NeuronInfo(NeuronInfo),
     #[serde(rename = "FOLLOW")]
     Follow(Follow),
+    #[serde(rename = "ICRC1_TRANSFER")]
+    Icrc1Transfer(Icrc1Transfer),
 }
 
 impl Request {
@@ -147,6 +149,7 @@ impl Request {
                 neuron_index: *neuron_index,
                 controller: controller.map(PublicKeyOrPrincipal::Principal),
             }),
+            Request::Icrc1Transfer(_) => Ok(RequestType::Icrc1Transfer),
         }
     }
 
@@ -176,13 +179,14 @@ impl Request {
                 Request::StakeMaturity(o) => builder.stake_maturity(o),
                 Request::NeuronInfo(o) => builder.neuron_info(o),
                 Request::Follow(o) => builder.follow(o),
+                Request::Icrc1Transfer(o) => builder.icrc1_transfer(o, token_name),
             };
         }
         Ok(builder.build())
     }
 
     pub fn is_transfer(&self) -> bool {
-        matches!(self, Request::Transfer(_))
+        matches!(self, Request::Transfer(_) | Request::Icrc1Transfer(_))
     }
 
     pub fn is_neuron_management(&self) -> bool {
@@ -494,6 +498,29 @@ impl TryFrom<&models::Request> for Request {
                     Err(ApiError::invalid_request("Invalid follow request."))
                 }
             }
+            RequestType::Icrc1Transfer => {
+                let ic_icrc1::endpoints::TransferArg {
+                    from_subaccount,
+                    to,
+                    fee,
+                    amount,
+                    ..
+                } = convert::icrc1::from_transfer_arg(&payload.update_content().arg.0)?;
+                Ok(Request::Icrc1Transfer(Icrc1Transfer {
+                    from: ic_icrc1::Account {
+                        owner: pid,
+                        subaccount: from_subaccount,
+                    },
+                    to,
+                    amount: convert::icrc1::nat_to_tokens(&amount)?,
+                    fee: fee
+                        .as_ref()
+                        .map(convert::icrc1::nat_to_tokens)
+                        .transpose()?
+                        .unwrap_or(Tokens::ZERO),
+                    decimals: ic_ledger_core::tokens::DECIMAL_PLACES,
+                }))
+            }
         }
     }
 }
@@ -10,21 +10,22 @@ mod construction_submit;
 use crate::{convert, models, API_VERSION, NODE_VERSION};
 use ic_ledger_canister_blocks_synchronizer::blocks::Blocks;
 use ic_ledger_canister_blocks_synchronizer::blocks::HashedBlock;
-use ic_ledger_core::block::BlockType;
+use ic_ledger_canister_blocks_synchronizer::ledger_kind::LedgerKind;
 use ic_nns_common::pb::v1::NeuronId;
 use ic_nns_governance::pb::v1::manage_neuron::NeuronIdOrSubaccount;
 use ic_types::crypto::DOMAIN_IC_REQUEST;
 use ic_types::messages::MessageId;
 use ic_types::CanisterId;
-use icp_ledger::{Block, BlockIndex};
+use icp_ledger::BlockIndex;
 use std::convert::{TryFrom, TryInto};
+use std::str::FromStr;
 use std::sync::Arc;
 use strum::IntoEnumIterator;
 
 use crate::convert::{from_model_account_identifier, neuron_account_from_public_key};
 use crate::errors::ApiError;
 use crate::ledger_client::LedgerAccess;
-use crate::models::amount::tokens_to_amount;
+use crate::models::amount::tokens_to_amount_with_decimals;
 use crate::models::{
     AccountBalanceRequest, AccountBalanceResponse, Allow, BalanceAccountType, BlockIdentifier,
     BlockResponse, BlockTransaction, BlockTransactionResponse, Error, MempoolResponse,
@@ -135,18 +136,33 @@ impl RosettaRequestHandler {
             None
         };
 
-        let account_id = icp_ledger::AccountIdentifier::from_hex(&msg.account_identifier.address)
-            .map_err(|e| {
+        let invalid_account_id = |e| {
             ApiError::invalid_account_id(format!(
                 "Account {} is not valid address, {}",
                 &msg.account_identifier.address, e,
             ))
-        })?;
+        };
         let blocks = self.ledger.read_blocks().await;
         let block = get_block(&blocks, msg.block_identifier)?;
 
-        let tokens = blocks.get_account_balance(&account_id, &block.index)?;
-        let amount = tokens_to_amount(tokens, self.ledger.token_symbol())?;
+        let tokens = match self.ledger.ledger_kind() {
+            LedgerKind::Icp => {
+                let account_id =
+                    icp_ledger::AccountIdentifier::from_hex(&msg.account_identifier.address)
+                        .map_err(invalid_account_id)?;
+                blocks.get_account_balance(&account_id, &block.index)?
+            }
+            LedgerKind::Icrc1 => {
+                let account = ic_icrc1::Account::from_str(&msg.account_identifier.address)
+                    .map_err(invalid_account_id)?;
+                blocks.get_icrc1_account_balance(&account, &block.index)?
+            }
+        };
+        let amount = tokens_to_amount_with_decimals(
+            tokens,
+            self.ledger.token_symbol(),
+            self.ledger.token_decimals(),
+        )?;
         let b = convert::block_id(&block)?;
         Ok(AccountBalanceResponse {
             block_identifier: b,
@@ -161,19 +177,18 @@ impl RosettaRequestHandler {
 
         let blocks = self.ledger.read_blocks().await;
         let hb = get_block(&blocks, Some(msg.block_identifier))?;
-        let block = Block::decode(hb.block.clone())
+        let timestamp = blocks
+            .ledger_kind()
+            .timestamp(&hb.block)
             .map_err(|err| ApiError::internal_error(format!("Cannot decode block: {}", err)))?;
         let b_id = convert::block_id(&hb)?;
         let parent_id = create_parent_block_id(&blocks, &hb)?;
 
-        let transactions = vec![convert::block_to_transaction(
-            &hb,
-            self.ledger.token_symbol(),
-        )?];
+        let transactions = vec![self.block_to_transaction(&hb)?];
         let block = Some(models::Block::new(
             b_id,
             parent_id,
-            models::timestamp::from_system_time(block.timestamp.into())?,
+            models::timestamp::from_system_time(timestamp.into())?,
             transactions,
         ));
 
@@ -195,10 +210,22 @@ impl RosettaRequestHandler {
             hash: Some(msg.block_identifier.hash),
         });
         let hb = get_block(&blocks, b_id)?;
-        let transaction = convert::block_to_transaction(&hb, self.ledger.token_symbol())?;
+        let transaction = self.block_to_transaction(&hb)?;
         Ok(BlockTransactionResponse::new(transaction))
     }
 
+    /// Converts a block of the served ledger to a Rosetta transaction.
+    fn block_to_transaction(&self, hb: &HashedBlock) -> Result<models::Transaction, ApiError> {
+        match self.ledger.ledger_kind() {
+            LedgerKind::Icp => convert::block_to_transaction(hb, self.ledger.token_symbol()),
+            LedgerKind::Icrc1 => convert::icrc1::block_to_transaction(
+                hb,
+                self.ledger.token_symbol(),
+                self.ledger.token_decimals(),
+            ),
+        }
+    }
+
     /// Get All Mempool Transactions
     pub async fn mempool(&self, msg: models::NetworkRequest) -> Result<MempoolResponse, ApiError> {
         verify_network_id(self.ledger.ledger_canister_id(), &msg.network_identifier)?;
@@ -293,7 +320,7 @@ impl RosettaRequestHandler {
         let tip = blocks.get_latest_verified_hashed_block()?;
         let tip_id = convert::block_id(&tip)?;
         let tip_timestamp = models::timestamp::from_system_time(
-            Block::decode(tip.block).unwrap().timestamp.into(),
+            blocks.ledger_kind().timestamp(&tip.block).unwrap().into(),
         )?;
 
         let genesis_block = blocks.get_hashed_block(&0)?;
@@ -376,7 +403,7 @@ impl RosettaRequestHandler {
         for hb in block_range.into_iter().rev() {
             txs.push(BlockTransaction::new(
                 convert::block_id(&hb)?,
-                convert::block_to_transaction(&hb, self.ledger.token_symbol())?,
+                self.block_to_transaction(&hb)?,
             ));
         }
 
@@ -481,10 +508,18 @@ impl RosettaRequestHandler {
         let mut next_offset = None;
 
         if let Some(aid) = &msg.account_identifier {
-            let acc = from_model_account_identifier(aid)
-                .map_err(|e| ApiError::InvalidAccountId(false, e.into()))?;
-
-            let hist = blocks.get_account_balance_history(&acc, max_block)?;
+            let hist = match self.ledger.ledger_kind() {
+                LedgerKind::Icp => {
+                    let acc = from_model_account_identifier(aid)
+                        .map_err(|e| ApiError::InvalidAccountId(false, e.into()))?;
+                    blocks.get_account_balance_history(&acc, max_block)?
+                }
+                LedgerKind::Icrc1 => {
+                    let acc = convert::icrc1::from_model_account_identifier(aid)
+                        .map_err(|e| ApiError::InvalidAccountId(false, e.into()))?;
+                    blocks.get_icrc1_account_balance_history(&acc, max_block)?
+                }
+            };
             heights = hist
                 .iter()
                 .map(|(h, _)| *h)
@@ -517,7 +552,7 @@ impl RosettaRequestHandler {
                 let hb = blocks.get_hashed_block(&i)?;
                 txs.push(BlockTransaction::new(
                     convert::block_id(&hb)?,
-                    convert::block_to_transaction(&hb, self.ledger.token_symbol())?,
+                    self.block_to_transaction(&hb)?,
                 ));
             } else {
                 return Err(ApiError::InvalidBlockId(true, Default::default()));
@@ -1,9 +1,10 @@
-use crate::convert::{account_from_public_key, neuron_account_from_public_key};
+use crate::convert::{self, account_from_public_key, neuron_account_from_public_key};
 use crate::errors::ApiError;
 use crate::models::{
     self, AccountType, ConstructionDeriveRequestMetadata, ConstructionDeriveResponse,
 };
 use crate::request_handler::{verify_network_id, RosettaRequestHandler};
+use ic_ledger_canister_blocks_synchronizer::ledger_kind::LedgerKind;
 
 impl RosettaRequestHandler {
     /// Derive an AccountIdentifier from a PublicKey.
@@ -23,7 +24,10 @@ impl RosettaRequestHandler {
                 &msg.public_key,
                 neuron_index,
             )?,
-            _ => account_from_public_key(&msg.public_key)?,
+            _ => match self.ledger.ledger_kind() {
+                LedgerKind::Icp => account_from_public_key(&msg.public_key)?,
+                LedgerKind::Icrc1 => convert::icrc1::account_from_public_key(&msg.public_key)?,
+            },
         });
 
         Ok(ConstructionDeriveResponse {
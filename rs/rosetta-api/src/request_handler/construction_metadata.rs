@@ -1,5 +1,5 @@
 use crate::errors::ApiError;
-use crate::models::amount::tokens_to_amount;
+use crate::models::amount::tokens_to_amount_with_decimals;
 use crate::models::{
     ConstructionMetadataRequest, ConstructionMetadataResponse, ConstructionPayloadsRequestMetadata,
 };
@@ -25,9 +25,10 @@ impl RosettaRequestHandler {
             }
             _ => {
                 let transfer_fee = self.ledger.transfer_fee().await?.transfer_fee;
-                Some(vec![tokens_to_amount(
+                Some(vec![tokens_to_amount_with_decimals(
                     transfer_fee,
                     self.ledger.token_symbol(),
+                    self.ledger.token_decimals(),
                 )?])
             }
         };
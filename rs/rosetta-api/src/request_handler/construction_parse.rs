@@ -3,7 +3,7 @@ use crate::errors::ApiError;
 use crate::models::{ConstructionParseRequest, ConstructionParseResponse, ParsedTransaction};
 use crate::request_handler::{verify_network_id, RosettaRequestHandler};
 use crate::request_types::{
-    AddHotKey, ChangeAutoStakeMaturity, Disburse, Follow, MergeMaturity, NeuronInfo,
+    AddHotKey, ChangeAutoStakeMaturity, Disburse, Follow, Icrc1Transfer, MergeMaturity, NeuronInfo,
     PublicKeyOrPrincipal, RegisterVote, RemoveHotKey, RequestType, SetDissolveTimestamp, Spawn,
     Stake, StakeMaturity, StartDissolve, StopDissolve,
 };
@@ -15,6 +15,7 @@ use ic_nns_governance::pb::v1::{
 
 use crate::models::seconds::Seconds;
 use crate::request::Request;
+use ic_ledger_canister_blocks_synchronizer::ledger_kind::LedgerKind;
 use ic_types::messages::{Blob, HttpCallContent, HttpCanisterUpdate};
 use ic_types::PrincipalId;
 use icp_ledger::{AccountIdentifier, Operation, SendArgs};
@@ -42,14 +43,14 @@ impl RosettaRequestHandler {
         };
 
         let mut requests = vec![];
-        let mut from_ai = vec![];
+        let mut signers = vec![];
 
         for (request_type, HttpCanisterUpdate { arg, sender, .. }) in updates {
-            let from = PrincipalId::try_from(sender.0)
-                .map_err(|e| ApiError::internal_error(e.to_string()))?
-                .into();
+            let sender = PrincipalId::try_from(sender.0)
+                .map_err(|e| ApiError::internal_error(e.to_string()))?;
+            let from = sender.into();
             if msg.signed {
-                from_ai.push(from);
+                signers.push(sender);
             }
 
             match request_type {
@@ -98,12 +99,29 @@ impl RosettaRequestHandler {
                     neuron_index,
                     controller,
                 } => follow(&mut requests, arg, from, neuron_index, controller)?,
+                RequestType::Icrc1Transfer => {
+                    icrc1_transfer(&mut requests, arg, sender, self.ledger.token_decimals())?
+                }
             }
         }
 
-        from_ai.sort();
-        from_ai.dedup();
-        let from_ai = from_ai.iter().map(to_model_account_identifier).collect();
+        let from_ai = match self.ledger.ledger_kind() {
+            LedgerKind::Icp => {
+                let mut from_ai: Vec<AccountIdentifier> =
+                    signers.into_iter().map(AccountIdentifier::from).collect();
+                from_ai.sort();
+                from_ai.dedup();
+                from_ai.iter().map(to_model_account_identifier).collect()
+            }
+            LedgerKind::Icrc1 => {
+                signers.sort();
+                signers.dedup();
+                signers
+                    .into_iter()
+                    .map(|p| convert::icrc1::to_model_account_identifier(&p.into()))
+                    .collect()
+            }
+        };
 
         Ok(ConstructionParseResponse {
             operations: Request::requests_to_operations(&requests, self.ledger.token_symbol())?,
@@ -128,6 +146,36 @@ fn send(requests: &mut Vec<Request>, arg: Blob, from: AccountIdentifier) -> Resu
     Ok(())
 }
 
+/// Handle ICRC1_TRANSFER.
+fn icrc1_transfer(
+    requests: &mut Vec<Request>,
+    arg: Blob,
+    sender: PrincipalId,
+    decimals: u32,
+) -> Result<(), ApiError> {
+    let ic_icrc1::endpoints::TransferArg {
+        from_subaccount,
+        to,
+        fee,
+        amount,
+        ..
+    } = convert::icrc1::from_transfer_arg(&arg.0)?;
+    let fee = fee.ok_or_else(|| {
+        ApiError::invalid_request("ICRC-1 transfers without an explicit fee are not supported")
+    })?;
+    requests.push(Request::Icrc1Transfer(Icrc1Transfer {
+        from: ic_icrc1::Account {
+            owner: sender,
+            subaccount: from_subaccount,
+        },
+        to,
+        amount: convert::icrc1::nat_to_tokens(&amount)?,
+        fee: convert::icrc1::nat_to_tokens(&fee)?,
+        decimals,
+    }));
+    Ok(())
+}
+
 /// Handle STAKE.
 fn stake(
     requests: &mut Vec<Request>,
@@ -1,4 +1,5 @@
 use dfn_candid::CandidOne;
+use ic_ledger_canister_blocks_synchronizer::ledger_kind::LedgerKind;
 use ic_nns_common::pb::v1::NeuronId;
 use ic_types::messages::{Blob, HttpCanisterUpdate, MessageId};
 use ic_types::PrincipalId;
@@ -24,7 +25,7 @@ use crate::models::{
 use crate::request::Request;
 use crate::request_handler::{make_sig_data, verify_network_id, RosettaRequestHandler};
 use crate::request_types::{
-    AddHotKey, ChangeAutoStakeMaturity, Disburse, Follow, MergeMaturity, NeuronInfo,
+    AddHotKey, ChangeAutoStakeMaturity, Disburse, Follow, Icrc1Transfer, MergeMaturity, NeuronInfo,
     PublicKeyOrPrincipal, RegisterVote, RemoveHotKey, RequestType, SetDissolveTimestamp, Spawn,
     Stake, StakeMaturity, StartDissolve, StopDissolve,
 };
@@ -47,8 +48,17 @@ impl RosettaRequestHandler {
         let pks = msg.public_keys.clone().ok_or_else(|| {
             ApiError::internal_error("Expected field 'public_keys' to be populated")
         })?;
-        let transactions =
-            convert::operations_to_requests(&ops, false, self.ledger.token_symbol())?;
+        let transactions = match self.ledger.ledger_kind() {
+            LedgerKind::Icp => {
+                convert::operations_to_requests(&ops, false, self.ledger.token_symbol())?
+            }
+            LedgerKind::Icrc1 => convert::icrc1::operations_to_requests(
+                &ops,
+                false,
+                self.ledger.token_symbol(),
+                self.ledger.token_decimals(),
+            )?,
+        };
 
         let interval = ic_constants::MAX_INGRESS_TTL
             - ic_constants::PERMITTED_DRIFT
@@ -209,6 +219,16 @@ impl RosettaRequestHandler {
                     &pks_map,
                     &ingress_expiries,
                 )?,
+                Request::Icrc1Transfer(req) => handle_icrc1_transfer(
+                    req,
+                    memo,
+                    created_at_time,
+                    &self.ledger,
+                    &mut payloads,
+                    &mut updates,
+                    &pks_map,
+                    &ingress_expiries,
+                )?,
             }
         }
 
@@ -319,6 +339,61 @@ fn handle_transfer_operation(
     Ok(())
 }
 
+/// Handle ICRC1_TRANSFER.
+fn handle_icrc1_transfer(
+    req: Icrc1Transfer,
+    memo: Memo,
+    created_at_time: ic_ledger_core::timestamp::TimeStamp,
+    ledger: &Arc<dyn LedgerAccess + Send + Sync>,
+    payloads: &mut Vec<SigningPayload>,
+    updates: &mut Vec<(RequestType, HttpCanisterUpdate)>,
+    pks_map: &HashMap<icp_ledger::AccountIdentifier, &PublicKey>,
+    ingress_expiries: &[u64],
+) -> Result<(), ApiError> {
+    let Icrc1Transfer {
+        from,
+        to,
+        amount,
+        fee,
+        ..
+    } = req;
+    let signer = ic_icrc1::Account::from(from.owner);
+    let pk = pks_map
+        .get(&icp_ledger::AccountIdentifier::from(from.owner))
+        .ok_or_else(|| {
+            ApiError::internal_error(format!("Cannot find public key for account {}", from))
+        })?;
+
+    // The argument we send to the canister
+    let transfer_arg = ic_icrc1::endpoints::TransferArg {
+        from_subaccount: from.subaccount,
+        to,
+        fee: Some(candid::Nat::from(fee.get_e8s())),
+        created_at_time: Some(created_at_time.as_nanos_since_unix_epoch()),
+        memo: Some(ic_icrc1::Memo::from(memo.0)),
+        amount: candid::Nat::from(amount.get_e8s()),
+    };
+
+    let update = HttpCanisterUpdate {
+        canister_id: Blob(ledger.ledger_canister_id().get().to_vec()),
+        method_name: "icrc1_transfer".to_string(),
+        arg: Blob(convert::icrc1::to_transfer_arg(&transfer_arg)?),
+        // See handle_transfer_operation for why there is no nonce.
+        nonce: None,
+        sender: Blob(convert::principal_id_from_public_key(pk)?.into_vec()),
+        ingress_expiry: 0,
+    };
+
+    add_payloads(
+        payloads,
+        ingress_expiries,
+        &convert::icrc1::to_model_account_identifier(&signer),
+        &update,
+    );
+    updates.push((RequestType::Icrc1Transfer, update));
+    Ok(())
+}
+
 /// Handle NEURON_INFO.
 fn handle_neuron_info(
     req: NeuronInfo,
@@ -1,5 +1,6 @@
 use crate::convert::{self, to_model_account_identifier};
 use crate::errors::ApiError;
+use crate::models;
 use crate::models::{
     ConstructionMetadataRequestOptions, ConstructionPreprocessRequest,
     ConstructionPreprocessResponse,
@@ -7,11 +8,12 @@ use crate::models::{
 use crate::request::Request;
 use crate::request_handler::{verify_network_id, RosettaRequestHandler};
 use crate::request_types::{
-    AddHotKey, ChangeAutoStakeMaturity, Disburse, Follow, MergeMaturity, NeuronInfo, RegisterVote,
-    RemoveHotKey, SetDissolveTimestamp, Spawn, Stake, StakeMaturity, StartDissolve, StopDissolve,
+    AddHotKey, ChangeAutoStakeMaturity, Disburse, Follow, Icrc1Transfer, MergeMaturity, NeuronInfo,
+    RegisterVote, RemoveHotKey, SetDissolveTimestamp, Spawn, Stake, StakeMaturity, StartDissolve,
+    StopDissolve,
 };
+use ic_ledger_canister_blocks_synchronizer::ledger_kind::LedgerKind;
 use icp_ledger::Operation;
-use std::collections::HashSet;
 
 impl RosettaRequestHandler {
     /// Create a Request to Fetch Metadata.
@@ -21,8 +23,17 @@ impl RosettaRequestHandler {
         msg: ConstructionPreprocessRequest,
     ) -> Result<ConstructionPreprocessResponse, ApiError> {
         verify_network_id(self.ledger.ledger_canister_id(), &msg.network_identifier)?;
-        let transfers =
-            convert::operations_to_requests(&msg.operations, true, self.ledger.token_symbol())?;
+        let transfers = match self.ledger.ledger_kind() {
+            LedgerKind::Icp => {
+                convert::operations_to_requests(&msg.operations, true, self.ledger.token_symbol())?
+            }
+            LedgerKind::Icrc1 => convert::icrc1::operations_to_requests(
+                &msg.operations,
+                true,
+                self.ledger.token_symbol(),
+                self.ledger.token_decimals(),
+            )?,
+        };
         let options = Some(ConstructionMetadataRequestOptions {
             request_types: transfers
                 .iter()
@@ -30,13 +41,13 @@ impl RosettaRequestHandler {
                 .collect::<Result<_, _>>()?,
         });
 
-        let required_public_keys: Result<HashSet<icp_ledger::AccountIdentifier>, ApiError> =
-            transfers.into_iter().map(required_public_key).collect();
-
-        let required_public_keys: Vec<_> = required_public_keys?
-            .into_iter()
-            .map(|x| to_model_account_identifier(&x))
-            .collect();
+        let mut required_public_keys: Vec<models::AccountIdentifier> = vec![];
+        for transfer in transfers {
+            let account = required_public_key(transfer)?;
+            if !required_public_keys.contains(&account) {
+                required_public_keys.push(account);
+            }
+        }
 
         Ok(ConstructionPreprocessResponse {
             required_public_keys: Some(required_public_keys),
@@ -46,9 +57,15 @@ impl RosettaRequestHandler {
 }
 
 /// Return the public key required to complete a request.
-fn required_public_key(request: Request) -> Result<icp_ledger::AccountIdentifier, ApiError> {
+fn required_public_key(request: Request) -> Result<models::AccountIdentifier, ApiError> {
     match request {
-        Request::Transfer(Operation::Transfer { from, .. }) => Ok(from),
+        Request::Transfer(Operation::Transfer { from, .. }) => {
+            Ok(to_model_account_identifier(&from))
+        }
+        // The signer is the owner of the account, whatever the subaccount.
+        Request::Icrc1Transfer(Icrc1Transfer { from, .. }) => Ok(
+            convert::icrc1::to_model_account_identifier(&ic_icrc1::Account::from(from.owner)),
+        ),
         Request::Transfer(Operation::Burn { .. }) => Err(ApiError::invalid_request(
             "Burn operations are not supported through rosetta",
         )),
@@ -74,6 +91,6 @@ fn required_public_key(request: Request) -> Result<icp_ledger::AccountIdentifier
         | Request::MergeMaturity(MergeMaturity { account, .. })
         | Request::StakeMaturity(StakeMaturity { account, .. })
         | Request::NeuronInfo(NeuronInfo { account, .. })
-        | Request::Follow(Follow { account, .. }) => Ok(account),
+        | Request::Follow(Follow { account, .. }) => Ok(to_model_account_identifier(&account)),
     }
 }
@@ -1,4 +1,4 @@
-use crate::models::amount::{signed_amount, tokens_to_amount};
+use crate::models::amount::{signed_amount, signed_amount_with_decimals, tokens_to_amount};
 use crate::models::operation::{OperationIdentifier, OperationType};
 use crate::models::seconds::Seconds;
 use crate::{
@@ -7,6 +7,7 @@ use crate::{
     models::{self, operation::Operation, Object},
     transaction_id::TransactionIdentifier,
 };
+use ic_icrc1::Operation as Icrc1Operation;
 use ic_types::PrincipalId;
 use icp_ledger::{AccountIdentifier, BlockIndex, Operation as LedgerOperation, Tokens};
 use serde::{Deserialize, Serialize};
@@ -94,12 +95,14 @@ pub enum RequestType {
         neuron_index: u64,
         controller: Option<PublicKeyOrPrincipal>,
     },
+    #[serde(rename = "ICRC1_TRANSFER")]
+    Icrc1Transfer,
 }
 
 impl RequestType {
     pub fn into_str(self) -> &'static str {
         match self {
-            RequestType::Send { .. } => TRANSACTION,
+            RequestType::Send { .. } | RequestType::Icrc1Transfer => TRANSACTION,
             RequestType::Stake { .. } => STAKE,
             RequestType::SetDissolveTimestamp { .. } => SET_DISSOLVE_TIMESTAMP,
             RequestType::ChangeAutoStakeMaturity { .. } => CHANGE_AUTO_STAKE_MATURITY,
@@ -118,7 +121,7 @@ impl RequestType {
     }
 
     pub const fn is_transfer(&self) -> bool {
-        matches!(self, RequestType::Send)
+        matches!(self, RequestType::Send | RequestType::Icrc1Transfer)
     }
 
     pub const fn is_neuron_management(&self) -> bool {
@@ -331,6 +334,18 @@ pub struct Follow {
     pub neuron_index: u64,
 }
 
+/// A transfer on an ICRC-1 ledger, built into an `icrc1_transfer` call.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Icrc1Transfer {
+    pub from: ic_icrc1::Account,
+    pub to: ic_icrc1::Account,
+    pub amount: Tokens,
+    pub fee: Tokens,
+    /// The number of decimals of the ledger's token, used to render the
+    /// amounts of the resulting operations.
+    pub decimals: u32,
+}
+
 #[derive(Debug, Clone, Eq, PartialOrd, Ord, Deserialize, Serialize)]
 #[serde(rename_all = "snake_case")]
 // Externally tagged by default.
@@ -885,6 +900,71 @@ impl TransactionBuilder {
         Ok(())
     }
 
+    /// Add a `Request::Icrc1Transfer` to the Transaction.
+    pub fn icrc1_transfer(&mut self, transfer: &Icrc1Transfer, token_name: &str) {
+        let Icrc1Transfer {
+            from,
+            to,
+            amount,
+            fee,
+            decimals,
+        } = transfer;
+        let operation = Icrc1Operation::Transfer {
+            from: *from,
+            to: *to,
+            spender: None,
+            amount: amount.get_e8s(),
+            fee: Some(fee.get_e8s()),
+        };
+        self.icrc1_operation(&operation, fee.get_e8s(), token_name, *decimals);
+    }
+
+    /// Add an operation of an ICRC-1 ledger block to the Transaction.
+    /// `fee` is the fee the ledger charged for the operation.
+    pub fn icrc1_operation(
+        &mut self,
+        operation: &Icrc1Operation,
+        fee: u64,
+        token_name: &str,
+        decimals: u32,
+    ) {
+        let mut push_op = |_type: OperationType, account: &ic_icrc1::Account, amount: i128| {
+            let operation_identifier = self.allocate_op_id();
+            self.ops.push(Operation {
+                operation_identifier,
+                _type,
+                status: None,
+                account: Some(crate::convert::icrc1::to_model_account_identifier(account)),
+                amount: Some(signed_amount_with_decimals(amount, token_name, decimals)),
+                related_operations: None,
+                coin_change: None,
+                metadata: None,
+            });
+        };
+
+        match operation {
+            Icrc1Operation::Burn { from, amount } => {
+                push_op(OperationType::Burn, from, -i128::from(*amount));
+            }
+            Icrc1Operation::Mint { to, amount } => {
+                push_op(OperationType::Mint, to, i128::from(*amount));
+            }
+            Icrc1Operation::Approve { from, spender, .. } => {
+                push_op(OperationType::Transaction, from, 0);
+                push_op(OperationType::Transaction, spender, 0);
+                push_op(OperationType::Fee, from, -i128::from(fee));
+            }
+            Icrc1Operation::Transfer {
+                from, to, amount, ..
+            } => {
+                let amount = i128::from(*amount);
+                push_op(OperationType::Transaction, from, -amount);
+                push_op(OperationType::Transaction, to, amount);
+                push_op(OperationType::Fee, from, -i128::from(fee));
+            }
+        }
+    }
+
     pub fn stake(&mut self, stake: &Stake) {
         let Stake {
             account,
@@ -2,6 +2,7 @@ use std::{convert::TryFrom, str::FromStr};
 
 use ic_ledger_canister_core::ledger::LedgerTransaction;
 use ic_ledger_core::block::HashOf;
+use ic_ledger_core::timestamp::TimeStamp;
 use ic_types::{
     messages::{HttpCallContent, HttpRequestEnvelope},
     PrincipalId,
@@ -64,6 +65,41 @@ impl TransactionIdentifier {
 
                 Ok(TransactionIdentifier::from(&hash))
             }
+            RequestType::Icrc1Transfer => {
+                let HttpCallContent::Call { update } = &signed_transaction.content;
+                let owner = PrincipalId::try_from(update.sender.clone().0)
+                    .map_err(|e| ApiError::internal_error(e.to_string()))?;
+                let ic_icrc1::endpoints::TransferArg {
+                    from_subaccount,
+                    to,
+                    fee,
+                    created_at_time,
+                    memo,
+                    amount,
+                } = convert::icrc1::from_transfer_arg(&update.arg.0)?;
+                let created_at_time = created_at_time.ok_or_else(|| ApiError::internal_error(
+                    "A transaction ID cannot be generated from a constructed transaction without an explicit 'created_at_time'"
+                ))?;
+
+                let from = ic_icrc1::Account {
+                    owner,
+                    subaccount: from_subaccount,
+                };
+                let tx = ic_icrc1::Transaction::transfer(
+                    from,
+                    to,
+                    convert::icrc1::nat_to_tokens(&amount)?,
+                    fee.as_ref()
+                        .map(convert::icrc1::nat_to_tokens)
+                        .transpose()?,
+                    Some(TimeStamp::from_nanos_since_unix_epoch(created_at_time)),
+                    memo,
+                );
+
+                Ok(TransactionIdentifier {
+                    hash: format!("{}", tx.hash()),
+                })
+            }
             RequestType::Stake { .. }
             | RequestType::StartDissolve { .. }
             | RequestType::StopDissolve { .. }
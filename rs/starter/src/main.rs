@@ -108,6 +108,8 @@ fn main() -> Result<()> {
         let ecdsa_config = config.ecdsa_keyid.clone().map(|key_id| EcdsaConfig {
             quadruples_to_create_in_advance: 1,
             key_ids: vec![(&key_id).into()],
+            schnorr_key_ids: vec![],
+            vetkd_key_ids: vec![],
             max_queue_size: 64,
             signature_request_timeout_ns: None,
             idkg_key_rotation_period_ms: None,
@@ -323,6 +325,7 @@ struct CliArgs {
             "bitcoin_regtest",
             "bitcoin_regtest_syncing",
             "bitcoin_regtest_paused",
+            "quic_transport",
         ],
         multiple_values(true))]
     subnet_features: Vec<String>,
@@ -652,6 +655,10 @@ fn to_subnet_features(features: &[String]) -> SubnetFeatures {
         bitcoin_testnet_feature: None,
         bitcoin,
         sev_status,
+        quic_transport: features
+            .iter()
+            .any(|s| s.as_str() == "quic_transport")
+            .then_some(true),
     }
 }
 
@@ -3,6 +3,7 @@ use crate::utils::do_copy;
 
 use bitcoin::{hashes::Hash, Network, OutPoint, Script, TxOut, Txid};
 use ic_base_types::{NumBytes, NumSeconds};
+use ic_ic00_types::LogVisibility;
 use ic_logger::{error, info, ReplicaLogger};
 use ic_metrics::{buckets::decimal_buckets, MetricsRegistry};
 use ic_protobuf::{
@@ -14,13 +15,19 @@ use ic_protobuf::{
     },
 };
 use ic_replicated_state::{
-    bitcoin_state, canister_state::execution_state::WasmMetadata, CallContextManager,
-    CanisterStatus, ExecutionTask, ExportedFunctions, Global, NumWasmPages,
+    bitcoin_state,
+    canister_state::{
+        execution_state::WasmMetadata,
+        system_state::{CanisterHistory, WasmChunkStore},
+    },
+    CallContextManager, CanisterStatus, ExecutionTask, ExportedFunctions, Global, NumWasmPages,
+    SnapshotId,
 };
 use ic_sys::mmap::ScopedMmap;
 use ic_types::{
-    nominal_cycles::NominalCycles, AccumulatedPriority, CanisterId, ComputeAllocation, Cycles,
-    ExecutionRound, Height, MemoryAllocation, NumInstructions, PrincipalId,
+    canister_log::CanisterLog, nominal_cycles::NominalCycles, AccumulatedPriority, CanisterId,
+    ComputeAllocation, Cycles, ExecutionRound, Height, MemoryAllocation, NumInstructions,
+    PrincipalId,
 };
 use ic_utils::fs::sync_path;
 use ic_utils::thread::parallel_map;
@@ -138,6 +145,27 @@ pub struct CanisterStateBits {
     pub time_of_last_allocation_charge_nanos: u64,
     pub global_timer_nanos: Option<u64>,
     pub canister_version: u64,
+    pub snapshots_memory_usage: NumBytes,
+    pub next_snapshot_id: u64,
+    pub wasm_chunk_store: WasmChunkStore,
+    pub canister_log: CanisterLog,
+    pub log_visibility: LogVisibility,
+    pub canister_history: CanisterHistory,
+}
+
+/// This struct contains bits of a `CanisterSnapshot` that are not already
+/// covered somewhere else and are too small to be serialized separately.
+#[derive(Debug)]
+pub struct CanisterSnapshotBits {
+    pub taken_at_timestamp: u64,
+    pub canister_version: u64,
+    pub certified_data: Vec<u8>,
+    pub exported_globals: Vec<Global>,
+    pub exports: ExportedFunctions,
+    pub metadata: WasmMetadata,
+    pub binary_hash: WasmHash,
+    pub wasm_memory_size: NumWasmPages,
+    pub stable_memory_size: NumWasmPages,
 }
 
 /// This struct contains bits of the `BitcoinState` that are not already
@@ -360,6 +388,27 @@ impl TipHandler {
         }
     }
 
+    /// Deletes canister snapshots from tip if they are not in ids.
+    pub fn filter_tip_snapshots(
+        &mut self,
+        height: Height,
+        ids: &BTreeSet<SnapshotId>,
+    ) -> Result<(), LayoutError> {
+        let tip = self.tip(height)?;
+        let snapshots_on_disk = tip.snapshot_ids()?;
+        for id in snapshots_on_disk {
+            if !ids.contains(&id) {
+                let snapshot_path = tip.snapshot(&id)?.raw_path();
+                std::fs::remove_dir_all(&snapshot_path).map_err(|err| LayoutError::IoError {
+                    path: snapshot_path,
+                    message: "Cannot remove snapshot.".to_string(),
+                    io_err: err,
+                })?;
+            }
+        }
+        Ok(())
+    }
+
     /// Deletes canisters from tip if they are not in ids.
     pub fn filter_tip_canisters(
         &mut self,
@@ -1257,6 +1306,50 @@ impl<Permissions: AccessPolicy> CheckpointLayout<Permissions> {
         )
     }
 
+    pub fn snapshot_ids(&self) -> Result<Vec<SnapshotId>, LayoutError> {
+        let snapshots_dir = self.root.join("snapshots");
+        Permissions::check_dir(&snapshots_dir)?;
+        let canister_dirs = collect_subdirs(snapshots_dir.as_path(), |p| p.to_string())?;
+        let mut ids = Vec::new();
+        for canister_dir in canister_dirs {
+            let blob = hex::decode(&canister_dir).unwrap_or_else(|err| {
+                panic!(
+                    "Failed to convert directory name {} into a canister id: {}",
+                    canister_dir, err
+                )
+            });
+            let canister_id = CanisterId::new(
+                PrincipalId::try_from(&blob[..]).expect("failed to parse principal id"),
+            )
+            .unwrap();
+            ids.extend(collect_subdirs(
+                snapshots_dir.join(&canister_dir).as_path(),
+                |p| {
+                    let local_id = p.parse::<u64>().unwrap_or_else(|err| {
+                        panic!(
+                            "Failed to convert directory name {} into a snapshot id: {}",
+                            p, err
+                        )
+                    });
+                    SnapshotId::new(canister_id, local_id)
+                },
+            )?);
+        }
+        Ok(ids)
+    }
+
+    pub fn snapshot(
+        &self,
+        snapshot_id: &SnapshotId,
+    ) -> Result<SnapshotLayout<Permissions>, LayoutError> {
+        SnapshotLayout::new(
+            self.root
+                .join("snapshots")
+                .join(hex::encode(snapshot_id.canister_id().get_ref().as_slice()))
+                .join(snapshot_id.local_id().to_string()),
+        )
+    }
+
     pub fn bitcoin(&self) -> Result<BitcoinStateLayout<Permissions>, LayoutError> {
         // TODO(EXC-1113): Rename this path to "bitcoin", as it stores data for either network.
         BitcoinStateLayout::new(self.root.join("bitcoin").join("testnet"))
@@ -1312,6 +1405,43 @@ impl<Permissions: AccessPolicy> CanisterLayout<Permissions> {
     }
 }
 
+pub struct SnapshotLayout<Permissions: AccessPolicy> {
+    snapshot_root: PathBuf,
+    permissions_tag: PhantomData<Permissions>,
+}
+
+impl<Permissions: AccessPolicy> SnapshotLayout<Permissions> {
+    pub fn new(snapshot_root: PathBuf) -> Result<Self, LayoutError> {
+        Permissions::check_dir(&snapshot_root)?;
+        Ok(Self {
+            snapshot_root,
+            permissions_tag: PhantomData,
+        })
+    }
+
+    pub fn raw_path(&self) -> PathBuf {
+        self.snapshot_root.clone()
+    }
+
+    pub fn snapshot(
+        &self,
+    ) -> ProtoFileWith<pb_canister_state_bits::CanisterSnapshotBits, Permissions> {
+        self.snapshot_root.join("snapshot.pbuf").into()
+    }
+
+    pub fn wasm(&self) -> WasmFile<Permissions> {
+        self.snapshot_root.join("software.wasm").into()
+    }
+
+    pub fn vmemory_0(&self) -> PathBuf {
+        self.snapshot_root.join("vmemory_0.bin")
+    }
+
+    pub fn stable_memory_blob(&self) -> PathBuf {
+        self.snapshot_root.join("stable_memory.bin")
+    }
+}
+
 pub struct BitcoinStateLayout<Permissions: AccessPolicy> {
     bitcoin_root: PathBuf,
     permissions_tag: PhantomData<Permissions>,
@@ -1578,6 +1708,23 @@ impl From<CanisterStateBits> for pb_canister_state_bits::CanisterStateBits {
             task_queue: item.task_queue.iter().map(|v| v.into()).collect(),
             global_timer_nanos: item.global_timer_nanos,
             canister_version: item.canister_version,
+            snapshots_memory_usage: item.snapshots_memory_usage.get(),
+            next_snapshot_id: item.next_snapshot_id,
+            wasm_chunk_store: item
+                .wasm_chunk_store
+                .chunks()
+                .map(|chunk| chunk.to_vec())
+                .collect(),
+            canister_log_records: item
+                .canister_log
+                .records()
+                .iter()
+                .map(|record| record.into())
+                .collect(),
+            next_canister_log_record_idx: item.canister_log.next_idx(),
+            log_visibility: pb_canister_state_bits::LogVisibility::from(&item.log_visibility)
+                .into(),
+            canister_history: Some((&item.canister_history).into()),
         }
     }
 }
@@ -1663,6 +1810,81 @@ impl TryFrom<pb_canister_state_bits::CanisterStateBits> for CanisterStateBits {
             task_queue,
             global_timer_nanos: value.global_timer_nanos,
             canister_version: value.canister_version,
+            snapshots_memory_usage: NumBytes::from(value.snapshots_memory_usage),
+            next_snapshot_id: value.next_snapshot_id,
+            wasm_chunk_store: value.wasm_chunk_store.into_iter().collect(),
+            canister_log: CanisterLog::new(
+                value.next_canister_log_record_idx,
+                value
+                    .canister_log_records
+                    .into_iter()
+                    .map(|record| record.into())
+                    .collect(),
+            ),
+            // Checkpoints written before log visibility was introduced leave
+            // it unspecified, which means the default.
+            log_visibility: pb_canister_state_bits::LogVisibility::from_i32(value.log_visibility)
+                .and_then(|visibility| LogVisibility::try_from(visibility).ok())
+                .unwrap_or_default(),
+            // Checkpoints written before the canister history was introduced
+            // don't have it, which means an empty history.
+            canister_history: value
+                .canister_history
+                .map(CanisterHistory::try_from)
+                .transpose()?
+                .unwrap_or_default(),
+        })
+    }
+}
+
+impl From<CanisterSnapshotBits> for pb_canister_state_bits::CanisterSnapshotBits {
+    fn from(item: CanisterSnapshotBits) -> Self {
+        Self {
+            taken_at_timestamp: item.taken_at_timestamp,
+            canister_version: item.canister_version,
+            certified_data: item.certified_data,
+            exported_globals: item
+                .exported_globals
+                .iter()
+                .map(|global| global.into())
+                .collect(),
+            exports: (&item.exports).into(),
+            metadata: Some((&item.metadata).into()),
+            binary_hash: item.binary_hash.to_vec(),
+            wasm_memory_size: item.wasm_memory_size.get() as u64,
+            stable_memory_size: item.stable_memory_size.get() as u64,
+        }
+    }
+}
+
+impl TryFrom<pb_canister_state_bits::CanisterSnapshotBits> for CanisterSnapshotBits {
+    type Error = ProxyDecodeError;
+
+    fn try_from(value: pb_canister_state_bits::CanisterSnapshotBits) -> Result<Self, Self::Error> {
+        let mut exported_globals = Vec::with_capacity(value.exported_globals.len());
+        for g in value.exported_globals.into_iter() {
+            exported_globals.push(g.try_into()?);
+        }
+        let binary_hash: [u8; 32] =
+            value
+                .binary_hash
+                .try_into()
+                .map_err(|e| ProxyDecodeError::ValueOutOfRange {
+                    typ: "BinaryHash",
+                    err: format!("Expected a 32-byte long module hash, got {:?}", e),
+                })?;
+
+        Ok(Self {
+            taken_at_timestamp: value.taken_at_timestamp,
+            canister_version: value.canister_version,
+            certified_data: value.certified_data,
+            exported_globals,
+            exports: value.exports.try_into()?,
+            metadata: try_from_option_field(value.metadata, "CanisterSnapshotBits::metadata")
+                .unwrap_or_default(),
+            binary_hash: binary_hash.into(),
+            wasm_memory_size: NumWasmPages::from(value.wasm_memory_size as usize),
+            stable_memory_size: NumWasmPages::from(value.stable_memory_size as usize),
         })
     }
 }
@@ -2056,12 +2278,14 @@ fn build_copy_plan(src: &Path, dst: &Path, plan: &mut CopyPlan) -> std::io::Resu
 mod test {
     use super::*;
 
-    use ic_ic00_types::IC_00;
+    use ic_ic00_types::{
+        CanisterChange, CanisterChangeDetails, CanisterChangeOrigin, CanisterInstallMode, IC_00,
+    };
     use ic_interfaces::messages::{CanisterCall, CanisterMessage, CanisterMessageOrTask};
     use ic_test_utilities::{
         mock_time,
         types::{
-            ids::canister_test_id,
+            ids::{canister_test_id, user_test_id},
             messages::{IngressBuilder, RequestBuilder, ResponseBuilder},
         },
     };
@@ -2095,6 +2319,12 @@ mod test {
             task_queue: vec![],
             global_timer_nanos: None,
             canister_version: 0,
+            snapshots_memory_usage: NumBytes::from(0),
+            next_snapshot_id: 0,
+            wasm_chunk_store: WasmChunkStore::default(),
+            canister_log: CanisterLog::default(),
+            log_visibility: LogVisibility::default(),
+            canister_history: CanisterHistory::default(),
         }
     }
 
@@ -2156,6 +2386,80 @@ mod test {
         assert_eq!(canister_state_bits.controllers, expected_controllers);
     }
 
+    #[test]
+    fn test_encode_decode_wasm_chunk_store() {
+        let wasm_chunk_store: WasmChunkStore =
+            vec![vec![1, 2, 3], vec![4, 5]].into_iter().collect();
+        let canister_state_bits = CanisterStateBits {
+            wasm_chunk_store: wasm_chunk_store.clone(),
+            ..default_canister_state_bits()
+        };
+
+        let pb_bits = pb_canister_state_bits::CanisterStateBits::from(canister_state_bits);
+        let canister_state_bits = CanisterStateBits::try_from(pb_bits).unwrap();
+        assert_eq!(canister_state_bits.wasm_chunk_store, wasm_chunk_store);
+    }
+
+    #[test]
+    fn test_encode_decode_canister_log() {
+        let mut canister_log = CanisterLog::new_with_next_index(3);
+        canister_log.add_record(100, b"hello".to_vec());
+        let canister_state_bits = CanisterStateBits {
+            canister_log: canister_log.clone(),
+            log_visibility: LogVisibility::Public,
+            ..default_canister_state_bits()
+        };
+
+        let pb_bits = pb_canister_state_bits::CanisterStateBits::from(canister_state_bits);
+        let canister_state_bits = CanisterStateBits::try_from(pb_bits).unwrap();
+        assert_eq!(canister_state_bits.canister_log, canister_log);
+        assert_eq!(canister_state_bits.log_visibility, LogVisibility::Public);
+    }
+
+    #[test]
+    fn test_encode_decode_canister_history() {
+        let mut canister_history = CanisterHistory::default();
+        canister_history.add_canister_change(CanisterChange::new(
+            42,
+            0,
+            CanisterChangeOrigin::from_user(user_test_id(1).get()),
+            CanisterChangeDetails::canister_creation(vec![user_test_id(1).get()]),
+        ));
+        canister_history.add_canister_change(CanisterChange::new(
+            43,
+            1,
+            CanisterChangeOrigin::from_canister(canister_test_id(2).get(), Some(7)),
+            CanisterChangeDetails::code_deployment(CanisterInstallMode::Install, [3; 32]),
+        ));
+        let canister_state_bits = CanisterStateBits {
+            canister_history: canister_history.clone(),
+            ..default_canister_state_bits()
+        };
+
+        let pb_bits = pb_canister_state_bits::CanisterStateBits::from(canister_state_bits);
+        let canister_state_bits = CanisterStateBits::try_from(pb_bits).unwrap();
+        assert_eq!(canister_state_bits.canister_history, canister_history);
+    }
+
+    #[test]
+    fn test_snapshot_ids_are_read_back_from_layout() {
+        let tmp = tmpdir("checkpoint");
+        let layout: CheckpointLayout<RwPolicy<()>> =
+            CheckpointLayout::new_untracked(tmp.path().to_owned(), Height::new(0)).unwrap();
+        let snapshot_ids = vec![
+            SnapshotId::new(canister_test_id(1), 0),
+            SnapshotId::new(canister_test_id(1), 3),
+            SnapshotId::new(canister_test_id(2), 1),
+        ];
+        for snapshot_id in &snapshot_ids {
+            layout.snapshot(snapshot_id).unwrap();
+        }
+
+        let mut ids_on_disk = layout.snapshot_ids().unwrap();
+        ids_on_disk.sort();
+        assert_eq!(ids_on_disk, snapshot_ids);
+    }
+
     #[test]
     fn test_encode_decode_task_queue() {
         let ingress = Arc::new(IngressBuilder::new().method_name("test_ingress").build());
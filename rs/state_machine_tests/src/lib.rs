@@ -217,6 +217,8 @@ fn make_nodes_registry(
         .with_ecdsa_config(EcdsaConfig {
             quadruples_to_create_in_advance: 1,
             key_ids: ecdsa_keys.to_vec(),
+            schnorr_key_ids: vec![],
+            vetkd_key_ids: vec![],
             max_queue_size: Some(DEFAULT_ECDSA_MAX_QUEUE_SIZE),
             signature_request_timeout_ns: None,
             idkg_key_rotation_period_ms: None,
@@ -753,6 +755,7 @@ impl StateMachine {
             payload,
             randomness: Randomness::from(seed),
             ecdsa_subnet_public_keys: self.ecdsa_subnet_public_keys.clone(),
+            schnorr_subnet_public_keys: BTreeMap::new(),
             registry_version: self.registry_client.get_latest_version(),
             time: self.time.get(),
             consensus_responses: vec![],
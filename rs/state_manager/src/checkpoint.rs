@@ -10,12 +10,15 @@ use ic_replicated_state::page_map::PageAllocatorFileDescriptor;
 use ic_replicated_state::Memory;
 use ic_replicated_state::{
     bitcoin_state::{BitcoinState, UtxoSet},
+    canister_snapshots::{ExecutionStateSnapshot, PageMemory},
     canister_state::execution_state::WasmBinary,
     page_map::PageMap,
-    CanisterMetrics, CanisterState, ExecutionState, ReplicatedState, SchedulerState, SystemState,
+    CanisterMetrics, CanisterSnapshot, CanisterSnapshots, CanisterState, ExecutionState,
+    ReplicatedState, SchedulerState, SnapshotId, SystemState,
 };
 use ic_state_layout::{
-    BitcoinStateBits, CanisterLayout, CanisterStateBits, CheckpointLayout, ReadOnly, ReadPolicy,
+    BitcoinStateBits, CanisterLayout, CanisterSnapshotBits, CanisterStateBits, CheckpointLayout,
+    ReadOnly, ReadPolicy,
 };
 use ic_types::{CanisterTimer, Height, LongExecutionMode, Time};
 use ic_utils::thread::parallel_map;
@@ -65,6 +68,17 @@ pub fn make_checkpoint(
         })
         .unwrap();
 
+    tip_channel
+        .send(TipRequest::FilterTipSnapshots {
+            height,
+            ids: state
+                .canister_snapshots
+                .iter()
+                .map(|(snapshot_id, _)| *snapshot_id)
+                .collect(),
+        })
+        .unwrap();
+
     let cp = {
         let _timer = metrics
             .make_checkpoint_step_duration
@@ -213,6 +227,21 @@ pub fn load_checkpoint<P: ReadPolicy + Send + Sync>(
         canister_states
     };
 
+    let canister_snapshots = {
+        let _timer = metrics
+            .load_checkpoint_step_duration
+            .with_label_values(&["canister_snapshots"])
+            .start_timer();
+
+        let mut snapshots = BTreeMap::new();
+        for snapshot_id in checkpoint_layout.snapshot_ids()? {
+            let snapshot =
+                load_snapshot_from_checkpoint(checkpoint_layout, &snapshot_id, &fd_factory)?;
+            snapshots.insert(snapshot_id, Arc::new(snapshot));
+        }
+        CanisterSnapshots::new(snapshots)
+    };
+
     let bitcoin = {
         let _timer = metrics
             .load_checkpoint_step_duration
@@ -222,8 +251,13 @@ pub fn load_checkpoint<P: ReadPolicy + Send + Sync>(
         load_bitcoin_state(checkpoint_layout)?
     };
 
-    let state =
-        ReplicatedState::new_from_checkpoint(canister_states, metadata, subnet_queues, bitcoin);
+    let state = ReplicatedState::new_from_checkpoint(
+        canister_states,
+        metadata,
+        subnet_queues,
+        bitcoin,
+        canister_snapshots,
+    );
 
     Ok(state)
 }
@@ -355,6 +389,12 @@ pub fn load_canister_state<P: ReadPolicy>(
         canister_state_bits.task_queue.into_iter().collect(),
         CanisterTimer::from_nanos_since_unix_epoch(canister_state_bits.global_timer_nanos),
         canister_state_bits.canister_version,
+        canister_state_bits.snapshots_memory_usage,
+        canister_state_bits.next_snapshot_id,
+        canister_state_bits.wasm_chunk_store,
+        canister_state_bits.canister_log,
+        canister_state_bits.log_visibility,
+        canister_state_bits.canister_history,
     );
 
     let canister_state = CanisterState {
@@ -395,6 +435,55 @@ fn load_canister_state_from_checkpoint<P: ReadPolicy>(
     )
 }
 
+fn load_snapshot_from_checkpoint<P: ReadPolicy>(
+    checkpoint_layout: &CheckpointLayout<P>,
+    snapshot_id: &SnapshotId,
+    fd_factory: &Arc<dyn PageAllocatorFileDescriptor>,
+) -> Result<CanisterSnapshot, CheckpointError> {
+    let snapshot_layout = checkpoint_layout.snapshot(snapshot_id)?;
+    let height = checkpoint_layout.height();
+
+    let snapshot_bits: CanisterSnapshotBits = CanisterSnapshotBits::try_from(
+        snapshot_layout.snapshot().deserialize()?,
+    )
+    .map_err(|err| CheckpointError::ProtoError {
+        path: snapshot_layout.raw_path(),
+        field: format!("canister_snapshots[{}]::snapshot_bits", snapshot_id),
+        proto_err: err.to_string(),
+    })?;
+
+    let wasm_memory = PageMemory::new(
+        PageMap::open(&snapshot_layout.vmemory_0(), height, Arc::clone(fd_factory))?,
+        snapshot_bits.wasm_memory_size,
+    );
+    let stable_memory = PageMemory::new(
+        PageMap::open(
+            &snapshot_layout.stable_memory_blob(),
+            height,
+            Arc::clone(fd_factory),
+        )?,
+        snapshot_bits.stable_memory_size,
+    );
+    let wasm_binary = snapshot_layout
+        .wasm()
+        .deserialize(Some(snapshot_bits.binary_hash))?;
+
+    Ok(CanisterSnapshot::new(
+        snapshot_id.canister_id(),
+        Time::from_nanos_since_unix_epoch(snapshot_bits.taken_at_timestamp),
+        snapshot_bits.canister_version,
+        snapshot_bits.certified_data,
+        ExecutionStateSnapshot {
+            wasm_binary,
+            exported_globals: snapshot_bits.exported_globals,
+            exports: snapshot_bits.exports,
+            metadata: snapshot_bits.metadata,
+            wasm_memory,
+            stable_memory,
+        },
+    ))
+}
+
 fn load_bitcoin_state<P: ReadPolicy>(
     checkpoint_layout: &CheckpointLayout<P>,
 ) -> Result<BitcoinState, CheckpointError> {
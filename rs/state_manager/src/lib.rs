@@ -135,6 +135,8 @@ pub struct StateSyncMetrics {
     remaining: IntGauge,
     corrupted_chunks_critical: IntCounter,
     corrupted_chunks: IntCounterVec,
+    compressed_chunk_bytes: IntCounter,
+    compression_saved_bytes: IntCounter,
 }
 
 #[derive(Clone)]
@@ -423,6 +425,16 @@ impl StateSyncMetrics {
             corrupted_chunks.with_label_values(&[*source]);
         }
 
+        let compressed_chunk_bytes = metrics_registry.int_counter(
+            "state_sync_compressed_chunk_bytes_total",
+            "Size of fetched compressed chunks before decompression during all the state sync in bytes.",
+        );
+
+        let compression_saved_bytes = metrics_registry.int_counter(
+            "state_sync_compression_saved_bytes_total",
+            "Number of bytes saved by fetching compressed chunks during all the state sync.",
+        );
+
         Self {
             size,
             duration,
@@ -430,6 +442,8 @@ impl StateSyncMetrics {
             remaining,
             corrupted_chunks_critical,
             corrupted_chunks,
+            compressed_chunk_bytes,
+            compression_saved_bytes,
         }
     }
 }
@@ -1154,6 +1168,20 @@ fn switch_to_checkpoint(tip: &mut ReplicatedState, src: &ReplicatedState) {
             tip_state.stable_memory.sandbox_memory = SandboxMemory::new();
         }
     }
+
+    // Snapshots are immutable, so the checkpoint-backed copies can replace
+    // the ones in the tip wholesale.
+    assert_eq!(
+        tip.canister_snapshots
+            .iter()
+            .map(|(snapshot_id, _)| *snapshot_id)
+            .collect::<Vec<_>>(),
+        src.canister_snapshots
+            .iter()
+            .map(|(snapshot_id, _)| *snapshot_id)
+            .collect::<Vec<_>>(),
+    );
+    tip.canister_snapshots = src.canister_snapshots.clone();
 }
 
 /// Persists metadata after releasing the write lock
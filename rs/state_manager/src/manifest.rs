@@ -41,13 +41,21 @@ pub const STATE_SYNC_V1: u32 = 1;
 /// Compute the manifest hash based on the encoded manifest.
 pub const STATE_SYNC_V2: u32 = 2;
 
+/// Compress all chunks except the manifest chunk with zstd during state sync.
+/// Chunk hashes are still computed on the uncompressed content.
+pub const STATE_SYNC_V3: u32 = ic_types::state_sync::MIN_COMPRESSED_CHUNKS_VERSION;
+
 /// The version of StateSync protocol that should be used for all newly created manifests.
+///
+/// Only raised in a release after `MAX_SUPPORTED_STATE_SYNC_VERSION`, so that
+/// replicas running the previous release can still load the checkpoints after
+/// a rollback or in a mixed-version subnet.
 pub const CURRENT_STATE_SYNC_VERSION: u32 = STATE_SYNC_V2;
 
 /// Maximum supported StateSync version.
 ///
 /// The replica will panic if trying to deal with a manifest with a version higher than this.
-pub const MAX_SUPPORTED_STATE_SYNC_VERSION: u32 = STATE_SYNC_V2;
+pub const MAX_SUPPORTED_STATE_SYNC_VERSION: u32 = STATE_SYNC_V3;
 
 pub const DEFAULT_CHUNK_SIZE: u32 = 1 << 20; // 1 MiB.
 
@@ -1,5 +1,5 @@
 use crate::{
-    manifest::{build_file_group_chunks, filter_out_zero_chunks, DiffScript},
+    manifest::{build_file_group_chunks, filter_out_zero_chunks, DiffScript, STATE_SYNC_V3},
     StateManagerMetrics, StateSyncMetrics, StateSyncRefs,
     CRITICAL_ERROR_STATE_SYNC_CORRUPTED_CHUNKS, LABEL_COPY_CHUNKS, LABEL_COPY_FILES, LABEL_FETCH,
     LABEL_PREALLOCATE, LABEL_STATE_SYNC_MAKE_CHECKPOINT,
@@ -743,6 +743,52 @@ impl IncompleteState {
         metrics.remaining.sub(1);
     }
 
+    /// Decompresses the payload of the chunk `ix` fetched for a manifest with
+    /// version `STATE_SYNC_V3` or higher.
+    ///
+    /// The payload is rejected if it decompresses to more bytes than the chunk
+    /// table entries it covers.
+    fn decompress_chunk(
+        log: &ReplicaLogger,
+        metrics: &StateSyncMetrics,
+        ix: usize,
+        payload: &[u8],
+        manifest: &Manifest,
+        state_sync_file_group: &FileGroupChunks,
+    ) -> Result<Vec<u8>, ArtifactErrorCode> {
+        let max_size: usize = if ix < FILE_GROUP_CHUNK_ID_OFFSET as usize {
+            manifest
+                .chunk_table
+                .get(ix - 1)
+                .ok_or(ChunkVerificationFailed)?
+                .size_bytes as usize
+        } else {
+            state_sync_file_group
+                .get(&(ix as u32))
+                .ok_or(ChunkVerificationFailed)?
+                .iter()
+                .map(|chunk_table_index| {
+                    manifest.chunk_table[*chunk_table_index as usize].size_bytes as usize
+                })
+                .sum()
+        };
+
+        let decompressed = zstd::bulk::decompress(payload, max_size).map_err(|err| {
+            warn!(log, "Failed to decompress chunk {}: {}", ix, err);
+            metrics
+                .corrupted_chunks
+                .with_label_values(&[LABEL_FETCH])
+                .inc();
+            ChunkVerificationFailed
+        })?;
+
+        metrics.compressed_chunk_bytes.inc_by(payload.len() as u64);
+        metrics
+            .compression_saved_bytes
+            .inc_by((decompressed.len() as u64).saturating_sub(payload.len() as u64));
+        Ok(decompressed)
+    }
+
     fn build_artifact(
         state_layout: &StateLayout,
         height: Height,
@@ -1256,6 +1302,23 @@ impl Chunkable for IncompleteState {
                     return Err(ChunksMoreNeeded);
                 }
 
+                // Chunks are hashed on their uncompressed content, so decompress
+                // the payload before splitting and validating it.
+                let decompressed_payload;
+                let payload = if manifest.version >= STATE_SYNC_V3 {
+                    decompressed_payload = Self::decompress_chunk(
+                        &self.log,
+                        &self.metrics.state_sync_metrics,
+                        ix,
+                        payload,
+                        manifest,
+                        state_sync_file_group,
+                    )?;
+                    &decompressed_payload
+                } else {
+                    payload
+                };
+
                 // Each index in `chunk_table_indices` is mapped to a piece of payload bytes
                 // with its corresponding start and end position.
                 let (chunk_table_indices, payload_pieces) =
@@ -3,12 +3,13 @@ use crossbeam_channel::{unbounded, Sender};
 use ic_logger::{fatal, ReplicaLogger};
 #[allow(unused)]
 use ic_replicated_state::{
-    canister_state::execution_state::SandboxMemory, BitcoinState, CanisterState, NumWasmPages,
-    PageMap, ReplicatedState,
+    canister_state::execution_state::SandboxMemory, BitcoinState, CanisterSnapshot, CanisterState,
+    NumWasmPages, PageMap, ReplicatedState, SnapshotId,
 };
 use ic_state_layout::{
-    error::LayoutError, BitcoinStateBits, BitcoinStateLayout, CanisterStateBits, CheckpointLayout,
-    ExecutionStateBits, ReadOnly, RwPolicy, StateLayout, TipHandler,
+    error::LayoutError, BitcoinStateBits, BitcoinStateLayout, CanisterSnapshotBits,
+    CanisterStateBits, CheckpointLayout, ExecutionStateBits, ReadOnly, RwPolicy, StateLayout,
+    TipHandler,
 };
 use ic_types::{CanisterId, Height};
 use ic_utils::fs::defrag_file_partially;
@@ -38,6 +39,11 @@ pub enum TipRequest {
         height: Height,
         ids: BTreeSet<CanisterId>,
     },
+    /// Filter canister snapshots in tip. Remove ones not present in the set.
+    FilterTipSnapshots {
+        height: Height,
+        ids: BTreeSet<SnapshotId>,
+    },
     /// Truncate PageMaps's path.
     TruncatePageMapsPath {
         height: Height,
@@ -118,6 +124,19 @@ pub fn spawn_tip_thread(
                                     )
                                 });
                         }
+                        TipRequest::FilterTipSnapshots { height, ids } => {
+                            let _timer = request_timer(&metrics, "filter_tip_snapshots");
+                            tip_handler
+                                .filter_tip_snapshots(height, &ids)
+                                .unwrap_or_else(|err| {
+                                    fatal!(
+                                        log,
+                                        "Failed to filter tip snapshots for height @{}: {}",
+                                        height,
+                                        err
+                                    )
+                                });
+                        }
                         TipRequest::TipToCheckpoint { height, sender } => {
                             let cp = {
                                 let _timer =
@@ -282,6 +301,16 @@ fn serialize_to_tip(
         result?;
     }
 
+    let results = parallel_map(
+        thread_pool,
+        state.canister_snapshots.iter(),
+        |(snapshot_id, snapshot)| serialize_snapshot_to_tip(log, snapshot_id, snapshot, tip),
+    );
+
+    for result in results.into_iter() {
+        result?;
+    }
+
     serialize_bitcoin_state_to_tip(state.bitcoin(), &tip.bitcoin()?)?;
 
     Ok(())
@@ -402,6 +431,67 @@ fn serialize_canister_to_tip(
                     .global_timer
                     .to_nanos_since_unix_epoch(),
                 canister_version: canister_state.system_state.canister_version,
+                snapshots_memory_usage: canister_state.system_state.snapshots_memory_usage,
+                next_snapshot_id: canister_state.system_state.next_snapshot_id,
+                wasm_chunk_store: canister_state.system_state.wasm_chunk_store.clone(),
+                canister_log: canister_state.system_state.canister_log.clone(),
+                log_visibility: canister_state.system_state.log_visibility,
+                canister_history: canister_state.system_state.get_canister_history().clone(),
+            }
+            .into(),
+        )
+        .map_err(CheckpointError::from)
+}
+
+fn serialize_snapshot_to_tip(
+    log: &ReplicaLogger,
+    snapshot_id: &SnapshotId,
+    snapshot: &CanisterSnapshot,
+    tip: &CheckpointLayout<RwPolicy<TipHandler>>,
+) -> Result<(), CheckpointError> {
+    let snapshot_layout = tip.snapshot(snapshot_id)?;
+    // Snapshots are immutable, so a snapshot that is already in the tip
+    // was fully written when its checkpoint was created.
+    if snapshot_layout.snapshot().raw_path().exists() {
+        return Ok(());
+    }
+
+    let execution_snapshot = snapshot.execution_snapshot();
+    let wasm_binary = &execution_snapshot.wasm_binary;
+    match wasm_binary.file() {
+        Some(path) => {
+            ic_state_layout::utils::do_copy(log, path, snapshot_layout.wasm().raw_path()).map_err(
+                |io_err| CheckpointError::IoError {
+                    path: path.to_path_buf(),
+                    message: "failed to copy Wasm file".to_string(),
+                    io_err: io_err.to_string(),
+                },
+            )?;
+        }
+        None => snapshot_layout.wasm().serialize(wasm_binary)?,
+    }
+    execution_snapshot
+        .wasm_memory
+        .page_map
+        .persist_all(&snapshot_layout.vmemory_0())?;
+    execution_snapshot
+        .stable_memory
+        .page_map
+        .persist_all(&snapshot_layout.stable_memory_blob())?;
+
+    snapshot_layout
+        .snapshot()
+        .serialize(
+            CanisterSnapshotBits {
+                taken_at_timestamp: snapshot.taken_at_timestamp().as_nanos_since_unix_epoch(),
+                canister_version: snapshot.canister_version(),
+                certified_data: snapshot.certified_data().clone(),
+                exported_globals: execution_snapshot.exported_globals.clone(),
+                exports: execution_snapshot.exports.clone(),
+                metadata: execution_snapshot.metadata.clone(),
+                binary_hash: wasm_binary.module_hash().into(),
+                wasm_memory_size: execution_snapshot.wasm_memory.size,
+                stable_memory_size: execution_snapshot.stable_memory.size,
             }
             .into(),
         )
@@ -8,12 +8,13 @@ use ic_interfaces_state_manager::*;
 use ic_logger::replica_logger::no_op_logger;
 use ic_metrics::MetricsRegistry;
 use ic_replicated_state::{
-    page_map::PageIndex, testing::ReplicatedStateTesting, Memory, NumWasmPages, PageMap,
-    ReplicatedState, Stream,
+    page_map::PageIndex, testing::ReplicatedStateTesting, CanisterSnapshot, Memory, NumWasmPages,
+    PageMap, ReplicatedState, SnapshotId, Stream,
 };
 use ic_state_machine_tests::{StateMachine, StateMachineBuilder};
 use ic_state_manager::{
-    tip::TipRequest, BitcoinPageMap, DirtyPageMap, FileType, PageMapType, StateManagerImpl,
+    manifest::STATE_SYNC_V3, tip::TipRequest, BitcoinPageMap, DirtyPageMap, FileType, PageMapType,
+    StateManagerImpl,
 };
 use ic_sys::PAGE_SIZE;
 use ic_test_utilities::{
@@ -26,10 +27,12 @@ use ic_test_utilities::{
     },
 };
 use ic_test_utilities_logger::with_test_replica_logger;
-use ic_test_utilities_metrics::{fetch_int_counter_vec, fetch_int_gauge, Labels};
+use ic_test_utilities_metrics::{
+    fetch_int_counter, fetch_int_counter_vec, fetch_int_gauge, Labels,
+};
 use ic_test_utilities_tmpdir::tmpdir;
 use ic_types::{
-    artifact::{Priority, StateSyncArtifactId, StateSyncAttribute},
+    artifact::{Priority, StateSyncArtifactId, StateSyncAttribute, StateSyncMessage},
     chunkable::ChunkId,
     crypto::CryptoHash,
     ingress::{IngressState, IngressStatus, WasmResult},
@@ -470,6 +473,51 @@ fn stable_memory_is_persisted() {
     });
 }
 
+/// Inserts a canister with non-empty stable memory and takes a snapshot of it.
+/// The canister's stable memory is modified afterwards, so that it differs
+/// from the snapshot.
+fn insert_canister_with_snapshot(state: &mut ReplicatedState, canister_id: CanisterId) {
+    insert_dummy_canister(state, canister_id);
+    let canister_state = state.canister_state_mut(&canister_id).unwrap();
+    let execution_state = canister_state.execution_state.as_mut().unwrap();
+    execution_state.stable_memory.size = NumWasmPages::new(1);
+    execution_state.stable_memory.page_map = PageMap::from(&[1; 100][..]);
+
+    let snapshot =
+        CanisterSnapshot::from_canister(state.canister_state(&canister_id).unwrap(), mock_time())
+            .unwrap();
+    state
+        .canister_snapshots
+        .push(SnapshotId::new(canister_id, 0), Arc::new(snapshot));
+
+    let canister_state = state.canister_state_mut(&canister_id).unwrap();
+    canister_state
+        .execution_state
+        .as_mut()
+        .unwrap()
+        .stable_memory
+        .page_map = PageMap::from(&[2; 100][..]);
+}
+
+#[test]
+fn canister_snapshots_are_persisted() {
+    state_manager_restart_test(|state_manager, restart_fn| {
+        let (_height, mut state) = state_manager.take_tip();
+        insert_canister_with_snapshot(&mut state, canister_test_id(100));
+        let canister_snapshots = state.canister_snapshots.clone();
+        state_manager.commit_and_certify(state, height(1), CertificationScope::Full);
+
+        let (_height, state) = state_manager.take_tip();
+        assert_eq!(canister_snapshots, state.canister_snapshots);
+
+        let state_manager = restart_fn(state_manager, None);
+
+        let recovered = state_manager.get_latest_state();
+        assert_eq!(height(1), recovered.height());
+        assert_eq!(canister_snapshots, recovered.take().canister_snapshots);
+    });
+}
+
 #[test]
 fn missing_stable_memory_file_is_handled() {
     use ic_state_layout::{CheckpointLayout, RwPolicy};
@@ -1813,6 +1861,128 @@ fn can_do_simple_state_sync_transfer() {
     })
 }
 
+#[test]
+fn state_sync_transfers_canister_snapshots() {
+    state_manager_test_with_state_sync(|src_metrics, src_state_manager, src_state_sync| {
+        let (_height, mut state) = src_state_manager.take_tip();
+        insert_canister_with_snapshot(&mut state, canister_test_id(100));
+        let canister_snapshots = state.canister_snapshots.clone();
+        let time_source = ic_test_utilities::FastForwardTimeSource::new();
+
+        src_state_manager.commit_and_certify(state, height(1), CertificationScope::Full);
+        let hash = wait_for_checkpoint(&*src_state_manager, height(1));
+        let id = StateSyncArtifactId {
+            height: height(1),
+            hash,
+        };
+
+        let msg = src_state_sync
+            .get_validated_by_identifier(&id)
+            .expect("failed to get state sync messages");
+
+        assert_error_counters(src_metrics);
+
+        state_manager_test_with_state_sync(|dst_metrics, dst_state_manager, dst_state_sync| {
+            let chunkable = dst_state_sync.create_chunkable_state(&id);
+
+            let dst_msg = pipe_state_sync(msg, chunkable);
+            dst_state_sync.process_changes(
+                time_source.as_ref(),
+                vec![UnvalidatedArtifact {
+                    message: dst_msg,
+                    peer_id: node_test_id(0),
+                    timestamp: mock_time(),
+                }],
+            );
+
+            let recovered_state = dst_state_manager
+                .get_state_at(height(1))
+                .expect("Destination state manager didn't receive the state")
+                .take();
+            assert_eq!(canister_snapshots, recovered_state.canister_snapshots);
+
+            let (_height, tip) = dst_state_manager.take_tip();
+            assert_eq!(canister_snapshots, tip.canister_snapshots);
+
+            assert_error_counters(dst_metrics);
+            assert_no_remaining_chunks(dst_metrics);
+        })
+    })
+}
+
+#[test]
+fn state_sync_transfers_compressed_chunks() {
+    use ic_state_manager::manifest::{compute_manifest, manifest_hash, DEFAULT_CHUNK_SIZE};
+    use ic_state_manager::ManifestMetrics;
+
+    state_manager_test_with_state_sync(|_src_metrics, src_state_manager, src_state_sync| {
+        let (_height, mut state) = src_state_manager.take_tip();
+        insert_dummy_canister(&mut state, canister_test_id(100));
+
+        // Pages filled with a single byte compress well.
+        let canister_state = state.canister_state_mut(&canister_test_id(100)).unwrap();
+        let execution_state = canister_state.execution_state.as_mut().unwrap();
+        execution_state.wasm_memory.page_map.update(&[
+            (PageIndex::new(0), &[1u8; PAGE_SIZE]),
+            (PageIndex::new(1), &[2u8; PAGE_SIZE]),
+        ]);
+
+        src_state_manager.commit_and_certify(state, height(1), CertificationScope::Full);
+        let hash = wait_for_checkpoint(&*src_state_manager, height(1));
+        let id = StateSyncArtifactId {
+            height: height(1),
+            hash,
+        };
+
+        let msg = src_state_sync
+            .get_validated_by_identifier(&id)
+            .expect("failed to get state sync messages");
+
+        // New checkpoints are not created with `STATE_SYNC_V3` yet, so recompute
+        // the manifest of the checkpoint with that version.
+        let checkpoint = src_state_manager
+            .state_layout()
+            .checkpoint(height(1))
+            .unwrap();
+        let mut thread_pool = scoped_threadpool::Pool::new(NUM_THREADS);
+        let manifest = compute_manifest(
+            &mut thread_pool,
+            &ManifestMetrics::new(&MetricsRegistry::new()),
+            &no_op_logger(),
+            STATE_SYNC_V3,
+            &checkpoint,
+            DEFAULT_CHUNK_SIZE,
+            None,
+        )
+        .expect("failed to compute manifest");
+        let hash = CryptoHashOfState::from(CryptoHash(manifest_hash(&manifest).to_vec()));
+        let id = StateSyncArtifactId {
+            height: height(1),
+            hash: hash.clone(),
+        };
+        let msg = StateSyncMessage {
+            root_hash: hash,
+            manifest,
+            ..msg
+        };
+
+        state_manager_test_with_state_sync(|dst_metrics, _dst_state_manager, dst_state_sync| {
+            let chunkable = dst_state_sync.create_chunkable_state(&id);
+            pipe_state_sync(msg, chunkable);
+
+            let compressed_bytes =
+                fetch_int_counter(dst_metrics, "state_sync_compressed_chunk_bytes_total").unwrap();
+            let saved_bytes =
+                fetch_int_counter(dst_metrics, "state_sync_compression_saved_bytes_total").unwrap();
+            assert!(compressed_bytes > 0);
+            assert!(saved_bytes >= PAGE_SIZE as u64);
+
+            assert_error_counters(dst_metrics);
+            assert_no_remaining_chunks(dst_metrics);
+        })
+    })
+}
+
 #[test]
 fn can_state_sync_from_cache() {
     state_manager_test_with_state_sync(|src_metrics, src_state_manager, src_state_sync| {
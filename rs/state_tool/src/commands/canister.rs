@@ -0,0 +1,298 @@
+//! Inspects and extracts the persisted state of a single canister within a
+//! checkpoint.
+
+use ic_protobuf::{
+    proxy::try_from_option_field,
+    state::queues::v1::{self as pb_queues, QueueEntry},
+};
+use ic_replicated_state::canister_state::system_state::CanisterStatus;
+use ic_state_layout::{CanisterLayout, CanisterStateBits, CompleteCheckpointLayout, ReadOnly};
+use ic_types::{
+    messages::{Ingress, Payload, RequestOrResponse, NO_DEADLINE},
+    CanisterId, Height,
+};
+use std::convert::TryFrom;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+/// Prints the metadata persisted in the `canister.pbuf` of the given canister.
+pub fn do_canister_metadata(path: PathBuf, canister_id: String) -> Result<(), String> {
+    let canister_layout = canister_layout(path, &canister_id)?;
+    let bits = CanisterStateBits::try_from(
+        canister_layout
+            .canister()
+            .deserialize()
+            .map_err(|e| format!("failed to read canister.pbuf: {}", e))?,
+    )
+    .map_err(|e| format!("failed to decode canister.pbuf: {}", e))?;
+
+    let controllers: Vec<_> = bits.controllers.iter().map(|c| c.to_string()).collect();
+    let status = match &bits.status {
+        CanisterStatus::Running { .. } => "running",
+        CanisterStatus::Stopping { .. } => "stopping",
+        CanisterStatus::Stopped => "stopped",
+    };
+
+    println!("canister id:               {}", canister_id);
+    println!("status:                    {}", status);
+    println!("controllers:               {}", controllers.join(", "));
+    println!("canister version:          {}", bits.canister_version);
+    println!("cycles balance:            {}", bits.cycles_balance);
+    println!("cycles debit:              {}", bits.cycles_debit);
+    println!("compute allocation:        {}", bits.compute_allocation);
+    println!("memory allocation:         {}", bits.memory_allocation);
+    println!("freezing threshold:        {}", bits.freeze_threshold);
+    println!("stable memory size:        {}", bits.stable_memory_size);
+    println!(
+        "certified data:            {}",
+        hex::encode(&bits.certified_data)
+    );
+    println!(
+        "global timer (ns):         {}",
+        bits.global_timer_nanos
+            .map_or_else(|| "inactive".to_string(), |t| t.to_string())
+    );
+    println!(
+        "last full execution round: {}",
+        bits.last_full_execution_round
+    );
+    println!("log visibility:            {:?}", bits.log_visibility);
+    println!(
+        "history changes:           {}",
+        bits.canister_history.get_total_num_changes()
+    );
+    println!("pending tasks:             {}", bits.task_queue.len());
+    match &bits.execution_state_bits {
+        Some(execution_state_bits) => {
+            println!(
+                "module hash:               {}",
+                execution_state_bits
+                    .binary_hash
+                    .as_ref()
+                    .map_or_else(|| "unknown".to_string(), |h| hex::encode(h.to_vec()))
+            );
+            println!(
+                "wasm heap size:            {}",
+                execution_state_bits.heap_size
+            );
+            println!(
+                "exported globals:          {}",
+                execution_state_bits.exported_globals.len()
+            );
+            println!(
+                "last executed round:       {}",
+                execution_state_bits.last_executed_round
+            );
+        }
+        None => println!("module hash:               none (empty canister)"),
+    }
+
+    Ok(())
+}
+
+/// Lists the ingress queue and the per-counterpart input and output queues
+/// persisted in the `queues.pbuf` of the given canister.
+pub fn do_canister_queues(path: PathBuf, canister_id: String) -> Result<(), String> {
+    let canister_layout = canister_layout(path, &canister_id)?;
+    let queues = canister_layout
+        .queues()
+        .deserialize()
+        .map_err(|e| format!("failed to read queues.pbuf: {}", e))?;
+
+    println!("INGRESS QUEUE ({} messages)", queues.ingress_queue.len());
+    for ingress in queues.ingress_queue {
+        let ingress = Ingress::try_from(ingress)
+            .map_err(|e| format!("failed to decode ingress message: {}", e))?;
+        println!(
+            "    {} {} -> {} {} ({} bytes, expires {})",
+            ingress.message_id,
+            ingress.source,
+            ingress.receiver,
+            ingress.method_name,
+            ingress.method_payload.len(),
+            ingress.expiry_time
+        );
+    }
+
+    print_queues("INPUT QUEUES", queues.input_queues)?;
+    print_queues("OUTPUT QUEUES", queues.output_queues)
+}
+
+/// Copies the Wasm module, the Wasm heap and the stable memory of the given
+/// canister into `output`, one standalone file each.
+pub fn do_extract_canister(
+    path: PathBuf,
+    canister_id: String,
+    output: PathBuf,
+) -> Result<(), String> {
+    let canister_layout = canister_layout(path, &canister_id)?;
+    std::fs::create_dir_all(&output)
+        .map_err(|e| format!("failed to create directory {}: {}", output.display(), e))?;
+
+    for src in [
+        canister_layout.wasm().raw_path().to_path_buf(),
+        canister_layout.vmemory_0(),
+        canister_layout.stable_memory_blob(),
+    ] {
+        let file_name = src.file_name().expect("layout paths have a file name");
+        if !src.exists() {
+            println!("{}: not present, skipping", file_name.to_string_lossy());
+            continue;
+        }
+        let dst = output.join(file_name);
+        let bytes = std::fs::copy(&src, &dst).map_err(|e| {
+            format!(
+                "failed to copy {} to {}: {}",
+                src.display(),
+                dst.display(),
+                e
+            )
+        })?;
+        println!("{}: {} bytes", dst.display(), bytes);
+    }
+
+    Ok(())
+}
+
+/// Opens the layout of canister `canister_id` (in textual representation)
+/// within the checkpoint at `path`.
+fn canister_layout(path: PathBuf, canister_id: &str) -> Result<CanisterLayout<ReadOnly>, String> {
+    let canister_id = CanisterId::from_str(canister_id)
+        .map_err(|e| format!("failed to parse canister id {}: {}", canister_id, e))?;
+    let cp_layout = CompleteCheckpointLayout::new_untracked(path, Height::new(0))
+        .map_err(|e| format!("failed to create checkpoint layout: {}", e))?;
+    let canister_layout = cp_layout
+        .canister(&canister_id)
+        .map_err(|e| format!("failed to access canister {}: {}", canister_id, e))?;
+    // `ReadOnly` layouts do not check that the directory exists.
+    if !canister_layout.raw_path().is_dir() {
+        return Err(format!(
+            "canister {} not found in checkpoint {}",
+            canister_id,
+            cp_layout.raw_path().display()
+        ));
+    }
+    Ok(canister_layout)
+}
+
+/// Prints one line per queue entry, followed by one line per message.
+fn print_queues(title: &str, entries: Vec<QueueEntry>) -> Result<(), String> {
+    println!("{} ({} queues)", title, entries.len());
+    for entry in entries {
+        let counterpart: CanisterId = try_from_option_field(entry.canister_id, "QueueEntry::K")
+            .map_err(|e| format!("failed to decode queue counterpart: {}", e))?;
+        let queue = entry.queue.unwrap_or_default();
+        println!(
+            "  {}: {} messages, begin {}, {} reserved slots",
+            counterpart,
+            queue.queue.len(),
+            queue.begin,
+            queue.num_slots_reserved
+        );
+        for msg in queue.queue {
+            println!("    {}", describe_message(msg)?);
+        }
+    }
+    Ok(())
+}
+
+/// Returns a one-line description of a persisted queue item.
+fn describe_message(msg: pb_queues::RequestOrResponse) -> Result<String, String> {
+    if msg.r.is_none() {
+        // Placeholder left behind by a timed out or shed message.
+        return Ok("<stale>".to_string());
+    }
+    let msg = RequestOrResponse::try_from(msg)
+        .map_err(|e| format!("failed to decode queue message: {}", e))?;
+    let (description, deadline) = match &msg {
+        RequestOrResponse::Request(req) => (
+            format!(
+                "request {} -> {} {} ({} bytes, {})",
+                req.sender,
+                req.receiver,
+                req.method_name,
+                req.method_payload.len(),
+                req.payment
+            ),
+            req.deadline,
+        ),
+        RequestOrResponse::Response(rep) => (
+            format!(
+                "response {} -> {} {} ({})",
+                rep.respondent,
+                rep.originator,
+                match &rep.response_payload {
+                    Payload::Data(data) => format!("reply, {} bytes", data.len()),
+                    Payload::Reject(context) => format!("reject {:?}", context.code()),
+                },
+                rep.refund
+            ),
+            rep.deadline,
+        ),
+    };
+    if deadline == NO_DEADLINE {
+        Ok(description)
+    } else {
+        Ok(format!(
+            "{}, deadline {}s",
+            description,
+            deadline.as_secs_since_unix_epoch()
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+    use tempfile::tempdir;
+
+    /// Returns the directory that `CanisterLayout` maps `canister_id` to.
+    fn canister_dir(root: &Path, canister_id: &CanisterId) -> PathBuf {
+        root.join("canister_states")
+            .join(hex::encode(canister_id.get_ref().as_slice()))
+    }
+
+    #[test]
+    fn extract_canister_copies_memory_and_module() {
+        let checkpoint = tempdir().unwrap();
+        let output = tempdir().unwrap();
+        let canister_id = CanisterId::from_u64(42);
+        let dir = canister_dir(checkpoint.path(), &canister_id);
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("software.wasm"), b"\0asm").unwrap();
+        std::fs::write(dir.join("vmemory_0.bin"), vec![1; 4096]).unwrap();
+
+        do_extract_canister(
+            checkpoint.path().to_path_buf(),
+            canister_id.to_string(),
+            output.path().join("extracted"),
+        )
+        .unwrap();
+
+        let extracted = output.path().join("extracted");
+        assert_eq!(
+            std::fs::read(extracted.join("software.wasm")).unwrap(),
+            b"\0asm"
+        );
+        assert_eq!(
+            std::fs::read(extracted.join("vmemory_0.bin")).unwrap(),
+            vec![1; 4096]
+        );
+        // Absent files are skipped rather than created empty.
+        assert!(!extracted.join("stable_memory.bin").exists());
+    }
+
+    #[test]
+    fn unknown_canister_is_an_error() {
+        let checkpoint = tempdir().unwrap();
+        std::fs::create_dir_all(checkpoint.path().join("canister_states")).unwrap();
+
+        let err = do_canister_metadata(
+            checkpoint.path().to_path_buf(),
+            CanisterId::from_u64(42).to_string(),
+        )
+        .unwrap_err();
+        assert!(err.contains("not found in checkpoint"), "{}", err);
+    }
+}
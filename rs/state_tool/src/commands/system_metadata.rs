@@ -0,0 +1,74 @@
+//! Prints a summary of the subnet-level metadata of a checkpoint.
+
+use ic_replicated_state::SystemMetadata;
+use ic_state_layout::CompleteCheckpointLayout;
+use ic_types::Height;
+use std::collections::BTreeMap;
+use std::convert::TryFrom;
+use std::path::PathBuf;
+
+/// Prints the `SystemMetadata` persisted in the `system_metadata.pbuf` of the
+/// checkpoint at `path`: subnet identity, streams and an ingress history
+/// summary.
+pub fn do_system_metadata(path: PathBuf) -> Result<(), String> {
+    let cp_layout = CompleteCheckpointLayout::new_untracked(path, Height::new(0))
+        .map_err(|e| format!("failed to create checkpoint layout: {}", e))?;
+    let metadata = SystemMetadata::try_from(
+        cp_layout
+            .system_metadata()
+            .deserialize()
+            .map_err(|e| format!("failed to read system_metadata.pbuf: {}", e))?,
+    )
+    .map_err(|e| format!("failed to decode system_metadata.pbuf: {}", e))?;
+
+    println!("subnet id:             {}", metadata.own_subnet_id);
+    println!("subnet type:           {:?}", metadata.own_subnet_type);
+    println!("batch time:            {}", metadata.batch_time);
+    println!("state sync version:    {}", metadata.state_sync_version);
+    println!(
+        "certification version: {:?}",
+        metadata.certification_version
+    );
+    println!(
+        "previous state hash:   {}",
+        metadata
+            .prev_state_hash
+            .as_ref()
+            .map_or_else(|| "none".to_string(), |h| hex::encode(&h.get_ref().0))
+    );
+
+    let streams = metadata.streams();
+    println!();
+    println!("STREAMS ({})", streams.streams().len());
+    println!(
+        "{:<65} {:>12} {:>12} {:>12} {:>8}",
+        "DESTINATION", "BEGIN", "END", "SIGNALS_END", "REJECTS"
+    );
+    for (subnet_id, stream) in streams.iter() {
+        println!(
+            "{:<65} {:>12} {:>12} {:>12} {:>8}",
+            subnet_id.to_string(),
+            stream.messages_begin(),
+            stream.messages_end(),
+            stream.signals_end(),
+            stream.reject_signals().len()
+        );
+    }
+
+    let ingress_history = &metadata.ingress_history;
+    let mut by_status = BTreeMap::<&str, usize>::new();
+    for (_, status) in ingress_history.statuses() {
+        *by_status.entry(status.as_str()).or_default() += 1;
+    }
+    println!();
+    println!("INGRESS HISTORY ({} entries)", ingress_history.len());
+    for (status, count) in by_status {
+        println!("  {:<12} {}", status, count);
+    }
+    println!(
+        "  memory usage {} bytes",
+        ingress_history.memory_usage().get()
+    );
+
+    Ok(())
+}
@@ -2,7 +2,8 @@
 //!
 //! A command-line tool to manage Internet Computer replicated states (decode
 //! persisted state files, diff checkpoints, compute partial state hashes and
-//! checkpoint manifests, import state trees).
+//! checkpoint manifests, import state trees, inspect and extract the state of
+//! individual canisters).
 
 use clap::Parser;
 use std::path::PathBuf;
@@ -121,6 +122,53 @@ enum Opt {
         file: PathBuf,
     },
 
+    /// Displays the metadata of a canister persisted in a checkpoint.
+    #[clap(name = "canister_metadata")]
+    CanisterMetadata {
+        /// Path to a checkpoint.
+        #[clap(long = "state")]
+        path: PathBuf,
+        /// The canister to inspect, in textual representation.
+        #[clap(long = "canister")]
+        canister: String,
+    },
+
+    /// Lists the input and output queues of a canister persisted in a
+    /// checkpoint.
+    #[clap(name = "canister_queues")]
+    CanisterQueues {
+        /// Path to a checkpoint.
+        #[clap(long = "state")]
+        path: PathBuf,
+        /// The canister to inspect, in textual representation.
+        #[clap(long = "canister")]
+        canister: String,
+    },
+
+    /// Extracts the Wasm module, heap and stable memory of a canister
+    /// persisted in a checkpoint into standalone files.
+    #[clap(name = "extract_canister")]
+    ExtractCanister {
+        /// Path to a checkpoint.
+        #[clap(long = "state")]
+        path: PathBuf,
+        /// The canister to extract, in textual representation.
+        #[clap(long = "canister")]
+        canister: String,
+        /// Directory to write the extracted files to.
+        #[clap(long = "output")]
+        output: PathBuf,
+    },
+
+    /// Displays a summary of the subnet-level metadata persisted in a
+    /// checkpoint (streams, ingress history).
+    #[clap(name = "system_metadata")]
+    SystemMetadata {
+        /// Path to a checkpoint.
+        #[clap(long = "state")]
+        path: PathBuf,
+    },
+
     /// Converts textual principal representation to hex.
     #[clap(name = "canister_id_to_hex")]
     CanisterIdToHex {
@@ -163,6 +211,18 @@ fn main() {
         }
         Opt::ListStates { config } => commands::list::do_list(config),
         Opt::Decode { file } => commands::decode::do_decode(file),
+        Opt::CanisterMetadata { path, canister } => {
+            commands::canister::do_canister_metadata(path, canister)
+        }
+        Opt::CanisterQueues { path, canister } => {
+            commands::canister::do_canister_queues(path, canister)
+        }
+        Opt::ExtractCanister {
+            path,
+            canister,
+            output,
+        } => commands::canister::do_extract_canister(path, canister, output),
+        Opt::SystemMetadata { path } => commands::system_metadata::do_system_metadata(path),
         Opt::CanisterIdToHex { canister_id } => {
             commands::convert_ids::do_canister_id_to_hex(canister_id)
         }
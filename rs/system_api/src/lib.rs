@@ -7,6 +7,7 @@ pub mod system_api_empty;
 
 use ic_config::flag_status::FlagStatus;
 use ic_error_types::RejectCode;
+use ic_ic00_types::{EcdsaCurve, EcdsaKeyId};
 use ic_interfaces::execution_environment::{
     ExecutionComplexity, ExecutionMode,
     HypervisorError::{self, *},
@@ -19,6 +20,7 @@ use ic_registry_subnet_type::SubnetType;
 use ic_replicated_state::{memory_required_to_push_request, Memory, NumWasmPages, PageIndex};
 use ic_sys::PageBytes;
 use ic_types::{
+    canister_log::{CanisterLog, MAX_ALLOWED_CANISTER_LOG_BUFFER_SIZE},
     ingress::WasmResult,
     messages::{CallContextId, RejectContext, Request, MAX_INTER_CANISTER_PAYLOAD_IN_BYTES},
     methods::{SystemMethod, WasmClosure},
@@ -26,6 +28,7 @@ use ic_types::{
     PrincipalId, SubnetId, Time,
 };
 use ic_utils::deterministic_operations::deterministic_copy_from_slice;
+pub use request_in_prep::MAX_CALL_TIMEOUT_SECONDS;
 use request_in_prep::{into_request, RequestInPrep};
 use sandbox_safe_system_state::{CanisterStatusView, SandboxSafeSystemState, SystemStateChanges};
 use serde::{Deserialize, Serialize};
@@ -37,7 +40,11 @@ use std::{
 
 const MULTIPLIER_MAX_SIZE_LOCAL_SUBNET: u64 = 5;
 const MAX_NON_REPLICATED_QUERY_REPLY_SIZE: NumBytes = NumBytes::new(3 << 20);
-const CERTIFIED_DATA_MAX_LENGTH: u32 = 32;
+const CERTIFIED_DATA_MAX_LENGTH: usize = 32;
+
+// Return codes of `ic0.cost_sign_with_ecdsa` when the cost is unknown.
+const COST_SIGN_WITH_ECDSA_UNKNOWN_CURVE: u32 = 1;
+const COST_SIGN_WITH_ECDSA_UNKNOWN_KEY: u32 = 2;
 
 // Enables tracing of system calls for local debugging.
 const TRACE_SYSCALLS: bool = false;
@@ -74,10 +81,10 @@ macro_rules! trace_syscall {
 
 // This helper is used in system calls for displaying a summary hash of a heap region.
 #[inline]
-fn summarize(heap: &[u8], start: u32, size: u32) -> u64 {
+fn summarize(heap: &[u8], start: usize, size: usize) -> u64 {
     if TRACE_SYSCALLS {
-        let start = (start as usize).min(heap.len());
-        let end = (start + (size as usize)).min(heap.len());
+        let start = start.min(heap.len());
+        let end = start.saturating_add(size).min(heap.len());
         // The actual hash function doesn't matter much as long as it is
         // cheap to compute and maps the input to u64 reasonably well.
         let mut sum = 0;
@@ -1032,6 +1039,23 @@ impl SystemApiImpl {
         }
     }
 
+    /// Checks that the `ic0.cost_*` calls are available in the current context.
+    fn cost_api_helper(&self, method_name: &str) -> HypervisorResult<()> {
+        match &self.api_type {
+            ApiType::Start {} => Err(self.error_for(method_name)),
+            ApiType::Init { .. }
+            | ApiType::SystemTask { .. }
+            | ApiType::Update { .. }
+            | ApiType::Cleanup { .. }
+            | ApiType::ReplicatedQuery { .. }
+            | ApiType::NonReplicatedQuery { .. }
+            | ApiType::PreUpgrade { .. }
+            | ApiType::ReplyCallback { .. }
+            | ApiType::RejectCallback { .. }
+            | ApiType::InspectMessage { .. } => Ok(()),
+        }
+    }
+
     fn ic0_msg_cycles_available_helper(&self, method_name: &str) -> HypervisorResult<Cycles> {
         match &self.api_type {
             ApiType::Start { .. }
@@ -1120,6 +1144,11 @@ impl SystemApiImpl {
         self.sandbox_safe_system_state.take_changes()
     }
 
+    /// Returns the log records produced so far by this execution.
+    pub fn take_canister_log(&mut self) -> CanisterLog {
+        self.sandbox_safe_system_state.take_canister_log()
+    }
+
     pub fn stable_memory_size(&self) -> NumWasmPages {
         self.stable_memory
             .as_ref()
@@ -1249,19 +1278,19 @@ impl SystemApi for SystemApiImpl {
         NumInstructions::from(result)
     }
 
-    fn ic0_msg_caller_size(&self) -> HypervisorResult<u32> {
+    fn ic0_msg_caller_size(&self) -> HypervisorResult<usize> {
         let result = self
             .get_msg_caller_id("ic0_msg_caller_size")
-            .map(|caller_id| caller_id.as_slice().len() as u32);
+            .map(|caller_id| caller_id.as_slice().len());
         trace_syscall!(self, ic0_msg_caller_size, result);
         result
     }
 
     fn ic0_msg_caller_copy(
         &self,
-        dst: u32,
-        offset: u32,
-        size: u32,
+        dst: usize,
+        offset: usize,
+        size: usize,
         heap: &mut [u8],
     ) -> HypervisorResult<()> {
         let result = match self.get_msg_caller_id("ic0_msg_caller_copy") {
@@ -1269,7 +1298,6 @@ impl SystemApi for SystemApiImpl {
                 let id_bytes = caller_id.as_slice();
                 valid_subslice("ic0.msg_caller_copy heap", dst, size, heap)?;
                 let slice = valid_subslice("ic0.msg_caller_copy id", offset, size, id_bytes)?;
-                let (dst, size) = (dst as usize, size as usize);
                 deterministic_copy_from_slice(&mut heap[dst..dst + size], slice);
                 Ok(())
             }
@@ -1287,7 +1315,7 @@ impl SystemApi for SystemApiImpl {
         result
     }
 
-    fn ic0_msg_arg_data_size(&self) -> HypervisorResult<u32> {
+    fn ic0_msg_arg_data_size(&self) -> HypervisorResult<usize> {
         let result = match &self.api_type {
             ApiType::Start { .. }
             | ApiType::Cleanup { .. }
@@ -1311,7 +1339,7 @@ impl SystemApi for SystemApiImpl {
             }
             | ApiType::NonReplicatedQuery {
                 incoming_payload, ..
-            } => Ok(incoming_payload.len() as u32),
+            } => Ok(incoming_payload.len()),
         };
         trace_syscall!(self, ic0_msg_arg_data_size, result);
         result
@@ -1319,9 +1347,9 @@ impl SystemApi for SystemApiImpl {
 
     fn ic0_msg_arg_data_copy(
         &self,
-        dst: u32,
-        offset: u32,
-        size: u32,
+        dst: usize,
+        offset: usize,
+        size: usize,
         heap: &mut [u8],
     ) -> HypervisorResult<()> {
         let result = match &self.api_type {
@@ -1355,7 +1383,6 @@ impl SystemApi for SystemApiImpl {
                     size,
                     incoming_payload,
                 )?;
-                let (dst, size) = (dst as usize, size as usize);
                 deterministic_copy_from_slice(&mut heap[dst..dst + size], payload_subslice);
                 Ok(())
             }
@@ -1372,7 +1399,7 @@ impl SystemApi for SystemApiImpl {
         result
     }
 
-    fn ic0_msg_method_name_size(&self) -> HypervisorResult<u32> {
+    fn ic0_msg_method_name_size(&self) -> HypervisorResult<usize> {
         let result = match &self.api_type {
             ApiType::Start { .. }
             | ApiType::RejectCallback { .. }
@@ -1384,7 +1411,7 @@ impl SystemApi for SystemApiImpl {
             | ApiType::ReplicatedQuery { .. }
             | ApiType::NonReplicatedQuery { .. }
             | ApiType::Init { .. } => Err(self.error_for("ic0_msg_method_name_size")),
-            ApiType::InspectMessage { method_name, .. } => Ok(method_name.len() as u32),
+            ApiType::InspectMessage { method_name, .. } => Ok(method_name.len()),
         };
         trace_syscall!(self, ic0_msg_method_name_size, result);
         result
@@ -1392,9 +1419,9 @@ impl SystemApi for SystemApiImpl {
 
     fn ic0_msg_method_name_copy(
         &self,
-        dst: u32,
-        offset: u32,
-        size: u32,
+        dst: usize,
+        offset: usize,
+        size: usize,
         heap: &mut [u8],
     ) -> HypervisorResult<()> {
         let result = match &self.api_type {
@@ -1416,7 +1443,6 @@ impl SystemApi for SystemApiImpl {
                     size,
                     method_name.as_bytes(),
                 )?;
-                let (dst, size) = (dst as usize, size as usize);
                 deterministic_copy_from_slice(&mut heap[dst..dst + size], payload_subslice);
                 Ok(())
             }
@@ -1483,15 +1509,24 @@ impl SystemApi for SystemApiImpl {
 
     fn ic0_msg_reply_data_append(
         &mut self,
-        src: u32,
-        size: u32,
+        src: usize,
+        size: usize,
         heap: &[u8],
     ) -> HypervisorResult<()> {
         let result = match self.get_response_info() {
             None => Err(self.error_for("ic0_msg_reply_data_append")),
             Some((data, max_reply_size, response_status)) => match response_status {
                 ResponseStatus::NotRepliedYet => {
-                    let payload_size = (data.len() + size as usize) as u64;
+                    let payload_size = match data.len().checked_add(size) {
+                        Some(payload_size) => payload_size as u64,
+                        None => {
+                            return Err(ContractViolation(format!(
+                                "ic0.msg_reply_data_append: application payload size ({} + {}) overflows",
+                                data.len(),
+                                size,
+                            )))
+                        }
+                    };
                     if payload_size > max_reply_size.get() {
                         let string = format!(
                             "ic0.msg_reply_data_append: application payload size ({}) cannot be larger than {}",
@@ -1521,7 +1556,7 @@ impl SystemApi for SystemApiImpl {
         result
     }
 
-    fn ic0_msg_reject(&mut self, src: u32, size: u32, heap: &[u8]) -> HypervisorResult<()> {
+    fn ic0_msg_reject(&mut self, src: usize, size: usize, heap: &[u8]) -> HypervisorResult<()> {
         let result = match self.get_response_info() {
             None => Err(self.error_for("ic0_msg_reject")),
             Some((_, max_reply_size, response_status)) => match response_status {
@@ -1567,20 +1602,20 @@ impl SystemApi for SystemApiImpl {
         result
     }
 
-    fn ic0_msg_reject_msg_size(&self) -> HypervisorResult<u32> {
+    fn ic0_msg_reject_msg_size(&self) -> HypervisorResult<usize> {
         let reject_context = self
             .get_reject_context()
             .ok_or_else(|| self.error_for("ic0_msg_reject_msg_size"))?;
-        let result = Ok(reject_context.message().len() as u32);
+        let result = Ok(reject_context.message().len());
         trace_syscall!(self, ic0_msg_reject_msg_size, result);
         result
     }
 
     fn ic0_msg_reject_msg_copy(
         &self,
-        dst: u32,
-        offset: u32,
-        size: u32,
+        dst: usize,
+        offset: usize,
+        size: usize,
         heap: &mut [u8],
     ) -> HypervisorResult<()> {
         let result = {
@@ -1590,10 +1625,8 @@ impl SystemApi for SystemApiImpl {
             valid_subslice("ic0.msg_reject_msg_copy heap", dst, size, heap)?;
 
             let msg = reject_context.message();
-            let dst = dst as usize;
             let msg_bytes =
                 valid_subslice("ic0.msg_reject_msg_copy msg", offset, size, msg.as_bytes())?;
-            let size = size as usize;
             deterministic_copy_from_slice(&mut heap[dst..dst + size], msg_bytes);
             Ok(())
         };
@@ -1634,9 +1667,9 @@ impl SystemApi for SystemApiImpl {
 
     fn ic0_canister_self_copy(
         &mut self,
-        dst: u32,
-        offset: u32,
-        size: u32,
+        dst: usize,
+        offset: usize,
+        size: usize,
         heap: &mut [u8],
     ) -> HypervisorResult<()> {
         let result = match &self.api_type {
@@ -1655,7 +1688,6 @@ impl SystemApi for SystemApiImpl {
                 let canister_id = self.sandbox_safe_system_state.canister_id;
                 let id_bytes = canister_id.get_ref().as_slice();
                 let slice = valid_subslice("ic0.canister_self_copy id", offset, size, id_bytes)?;
-                let (dst, size) = (dst as usize, size as usize);
                 deterministic_copy_from_slice(&mut heap[dst..dst + size], slice);
                 Ok(())
             }
@@ -1694,9 +1726,9 @@ impl SystemApi for SystemApiImpl {
 
     fn ic0_controller_copy(
         &mut self,
-        dst: u32,
-        offset: u32,
-        size: u32,
+        dst: usize,
+        offset: usize,
+        size: usize,
         heap: &mut [u8],
     ) -> HypervisorResult<()> {
         let result = match &self.api_type {
@@ -1715,7 +1747,6 @@ impl SystemApi for SystemApiImpl {
                 let controller = self.sandbox_safe_system_state.controller;
                 let id_bytes = controller.as_slice();
                 let slice = valid_subslice("ic0.controller_copy id", offset, size, id_bytes)?;
-                let (dst, size) = (dst as usize, size as usize);
                 deterministic_copy_from_slice(&mut heap[dst..dst + size], slice);
                 Ok(())
             }
@@ -1726,10 +1757,10 @@ impl SystemApi for SystemApiImpl {
 
     fn ic0_call_new(
         &mut self,
-        callee_src: u32,
-        callee_size: u32,
-        name_src: u32,
-        name_len: u32,
+        callee_src: usize,
+        callee_size: usize,
+        name_src: usize,
+        name_len: usize,
         reply_fun: u32,
         reply_env: u32,
         reject_fun: u32,
@@ -1804,7 +1835,12 @@ impl SystemApi for SystemApiImpl {
         result
     }
 
-    fn ic0_call_data_append(&mut self, src: u32, size: u32, heap: &[u8]) -> HypervisorResult<()> {
+    fn ic0_call_data_append(
+        &mut self,
+        src: usize,
+        size: usize,
+        heap: &[u8],
+    ) -> HypervisorResult<()> {
         let result = match &mut self.api_type {
             ApiType::Start { .. }
             | ApiType::Init { .. }
@@ -1892,6 +1928,50 @@ impl SystemApi for SystemApiImpl {
         result
     }
 
+    fn ic0_call_with_best_effort_response(&mut self, timeout_seconds: u32) -> HypervisorResult<()> {
+        let result = match &mut self.api_type {
+            ApiType::Start { .. }
+            | ApiType::Init { .. }
+            | ApiType::ReplicatedQuery { .. }
+            | ApiType::NonReplicatedQuery {
+                query_kind: NonReplicatedQueryKind::Pure,
+                ..
+            }
+            | ApiType::Cleanup { .. }
+            | ApiType::PreUpgrade { .. }
+            | ApiType::InspectMessage { .. } => {
+                Err(self.error_for("ic0_call_with_best_effort_response"))
+            }
+            ApiType::Update {
+                outgoing_request, ..
+            }
+            | ApiType::NonReplicatedQuery {
+                query_kind:
+                    NonReplicatedQueryKind::Stateful {
+                        outgoing_request, ..
+                    },
+                ..
+            }
+            | ApiType::SystemTask {
+                outgoing_request, ..
+            }
+            | ApiType::ReplyCallback {
+                outgoing_request, ..
+            }
+            | ApiType::RejectCallback {
+                outgoing_request, ..
+            } => match outgoing_request {
+                None => Err(HypervisorError::ContractViolation(
+                    "ic0.call_with_best_effort_response called when no call is under construction."
+                        .to_string(),
+                )),
+                Some(request) => request.set_timeout(timeout_seconds),
+            },
+        };
+        trace_syscall!(self, ic0_call_with_best_effort_response, timeout_seconds);
+        result
+    }
+
     fn ic0_call_cycles_add(&mut self, amount: u64) -> HypervisorResult<()> {
         let result = self.ic0_call_cycles_add_helper("ic0_call_cycles_add", Cycles::from(amount));
         trace_syscall!(self, ic0_call_cycles_add, result, amount);
@@ -1928,24 +2008,29 @@ impl SystemApi for SystemApiImpl {
             ApiType::Update {
                 call_context_id,
                 outgoing_request,
+                time,
                 ..
             }
             | ApiType::SystemTask {
                 call_context_id,
                 outgoing_request,
+                time,
                 ..
             }
             | ApiType::ReplyCallback {
                 call_context_id,
                 outgoing_request,
+                time,
                 ..
             }
             | ApiType::RejectCallback {
                 call_context_id,
                 outgoing_request,
+                time,
                 ..
             }
             | ApiType::NonReplicatedQuery {
+                time,
                 query_kind:
                     NonReplicatedQueryKind::Stateful {
                         call_context_id,
@@ -1962,6 +2047,7 @@ impl SystemApi for SystemApiImpl {
                 let req = into_request(
                     req_in_prep,
                     *call_context_id,
+                    *time,
                     &mut self.sandbox_safe_system_state,
                     &self.log,
                 )?;
@@ -2075,7 +2161,7 @@ impl SystemApi for SystemApiImpl {
             dst,
             offset,
             size,
-            summarize(heap, dst, size)
+            summarize(heap, dst as usize, size as usize)
         );
         result
     }
@@ -2217,7 +2303,7 @@ impl SystemApi for SystemApiImpl {
             dst,
             offset,
             size,
-            summarize(heap, dst as u32, size as u32)
+            summarize(heap, dst as usize, size as usize)
         );
         result
     }
@@ -2256,7 +2342,7 @@ impl SystemApi for SystemApiImpl {
             offset,
             src,
             size,
-            summarize(heap, src as u32, size as u32)
+            summarize(heap, src as usize, size as usize)
         );
         result
     }
@@ -2351,6 +2437,161 @@ impl SystemApi for SystemApiImpl {
         result
     }
 
+    fn ic0_is_controller(&self, src: usize, size: usize, heap: &[u8]) -> HypervisorResult<u32> {
+        let result = match &self.api_type {
+            ApiType::Start { .. } => Err(self.error_for("ic0_is_controller")),
+            ApiType::Init { .. }
+            | ApiType::SystemTask { .. }
+            | ApiType::Update { .. }
+            | ApiType::Cleanup { .. }
+            | ApiType::NonReplicatedQuery { .. }
+            | ApiType::ReplicatedQuery { .. }
+            | ApiType::PreUpgrade { .. }
+            | ApiType::ReplyCallback { .. }
+            | ApiType::RejectCallback { .. }
+            | ApiType::InspectMessage { .. } => {
+                let bytes = valid_subslice("ic0.is_controller", src, size, heap)?;
+                PrincipalId::try_from(bytes)
+                    .map(|principal_id| {
+                        self.sandbox_safe_system_state
+                            .is_controller(&principal_id)
+                            .into()
+                    })
+                    .map_err(InvalidPrincipalId)
+            }
+        };
+        trace_syscall!(
+            self,
+            ic0_is_controller,
+            result,
+            src,
+            size,
+            summarize(heap, src, size)
+        );
+        result
+    }
+
+    fn ic0_in_replicated_execution(&self) -> HypervisorResult<i32> {
+        let result = match self.execution_parameters.execution_mode {
+            ExecutionMode::Replicated => Ok(1),
+            ExecutionMode::NonReplicated => Ok(0),
+        };
+        trace_syscall!(self, ic0_in_replicated_execution, result);
+        result
+    }
+
+    fn ic0_cost_call(
+        &self,
+        method_name_size: u64,
+        payload_size: u64,
+        dst: usize,
+        heap: &mut [u8],
+    ) -> HypervisorResult<()> {
+        let method_name = "ic0_cost_call";
+        let result = self.cost_api_helper(method_name).and_then(|()| {
+            let cost = self
+                .sandbox_safe_system_state
+                .cost_call(method_name_size, payload_size);
+            copy_cycles_to_heap(cost, dst, heap, method_name)
+        });
+        trace_syscall!(
+            self,
+            ic0_cost_call,
+            result,
+            method_name_size,
+            payload_size,
+            dst,
+            summarize(heap, dst, 16)
+        );
+        result
+    }
+
+    fn ic0_cost_create_canister(&self, dst: usize, heap: &mut [u8]) -> HypervisorResult<()> {
+        let method_name = "ic0_cost_create_canister";
+        let result = self.cost_api_helper(method_name).and_then(|()| {
+            let cost = self.sandbox_safe_system_state.cost_create_canister();
+            copy_cycles_to_heap(cost, dst, heap, method_name)
+        });
+        trace_syscall!(
+            self,
+            ic0_cost_create_canister,
+            result,
+            dst,
+            summarize(heap, dst, 16)
+        );
+        result
+    }
+
+    fn ic0_cost_http_request(
+        &self,
+        request_size: u64,
+        max_res_bytes: u64,
+        dst: usize,
+        heap: &mut [u8],
+    ) -> HypervisorResult<()> {
+        let method_name = "ic0_cost_http_request";
+        let result = self.cost_api_helper(method_name).and_then(|()| {
+            let cost = self
+                .sandbox_safe_system_state
+                .cost_http_request(request_size, max_res_bytes);
+            copy_cycles_to_heap(cost, dst, heap, method_name)
+        });
+        trace_syscall!(
+            self,
+            ic0_cost_http_request,
+            result,
+            request_size,
+            max_res_bytes,
+            dst,
+            summarize(heap, dst, 16)
+        );
+        result
+    }
+
+    fn ic0_cost_sign_with_ecdsa(
+        &self,
+        src: usize,
+        size: usize,
+        curve: u32,
+        dst: usize,
+        heap: &mut [u8],
+    ) -> HypervisorResult<u32> {
+        let method_name = "ic0_cost_sign_with_ecdsa";
+        let result = self.cost_api_helper(method_name).and_then(|()| {
+            let curve = match curve {
+                0 => EcdsaCurve::Secp256k1,
+                1 => EcdsaCurve::Secp256r1,
+                _ => return Ok(COST_SIGN_WITH_ECDSA_UNKNOWN_CURVE),
+            };
+            let name = valid_subslice("ic0.cost_sign_with_ecdsa", src, size, heap)?;
+            let name = match std::str::from_utf8(name) {
+                Ok(name) => name.to_string(),
+                Err(_) => return Ok(COST_SIGN_WITH_ECDSA_UNKNOWN_KEY),
+            };
+            match self
+                .sandbox_safe_system_state
+                .cost_sign_with_ecdsa(&EcdsaKeyId { curve, name })
+            {
+                Some(cost) => {
+                    copy_cycles_to_heap(cost, dst, heap, method_name)?;
+                    Ok(0)
+                }
+                None => Ok(COST_SIGN_WITH_ECDSA_UNKNOWN_KEY),
+            }
+        });
+        trace_syscall!(
+            self,
+            ic0_cost_sign_with_ecdsa,
+            result,
+            src,
+            size,
+            curve,
+            dst,
+            summarize(heap, dst, 16)
+        );
+        result
+    }
+
     fn out_of_instructions(&mut self, instruction_counter: i64) -> HypervisorResult<i64> {
         let execution_complexity = self.execution_complexity().clone();
         let result = self
@@ -2434,7 +2675,7 @@ impl SystemApi for SystemApiImpl {
         result
     }
 
-    fn ic0_canister_cycle_balance128(&self, dst: u32, heap: &mut [u8]) -> HypervisorResult<()> {
+    fn ic0_canister_cycle_balance128(&self, dst: usize, heap: &mut [u8]) -> HypervisorResult<()> {
         let result = {
             let method_name = "ic0_canister_cycle_balance128";
             let cycles = self.ic0_canister_cycle_balance_helper(method_name)?;
@@ -2464,7 +2705,7 @@ impl SystemApi for SystemApiImpl {
         result
     }
 
-    fn ic0_msg_cycles_available128(&self, dst: u32, heap: &mut [u8]) -> HypervisorResult<()> {
+    fn ic0_msg_cycles_available128(&self, dst: usize, heap: &mut [u8]) -> HypervisorResult<()> {
         let result = {
             let method_name = "ic0_msg_cycles_available128";
             let cycles = self.ic0_msg_cycles_available_helper(method_name)?;
@@ -2489,7 +2730,7 @@ impl SystemApi for SystemApiImpl {
         result
     }
 
-    fn ic0_msg_cycles_refunded128(&self, dst: u32, heap: &mut [u8]) -> HypervisorResult<()> {
+    fn ic0_msg_cycles_refunded128(&self, dst: usize, heap: &mut [u8]) -> HypervisorResult<()> {
         let result = {
             let method_name = "ic0_msg_cycles_refunded128";
             let cycles = self.ic0_msg_cycles_refunded_helper(method_name)?;
@@ -2528,7 +2769,7 @@ impl SystemApi for SystemApiImpl {
     fn ic0_msg_cycles_accept128(
         &mut self,
         max_amount: Cycles,
-        dst: u32,
+        dst: usize,
         heap: &mut [u8],
     ) -> HypervisorResult<()> {
         let result = {
@@ -2566,7 +2807,7 @@ impl SystemApi for SystemApiImpl {
         result
     }
 
-    fn ic0_data_certificate_size(&self) -> HypervisorResult<i32> {
+    fn ic0_data_certificate_size(&self) -> HypervisorResult<usize> {
         let result = match &self.api_type {
             ApiType::Start { .. }
             | ApiType::Init { .. }
@@ -2583,7 +2824,7 @@ impl SystemApi for SystemApiImpl {
             | ApiType::NonReplicatedQuery {
                 data_certificate, ..
             } => match data_certificate {
-                Some(data_certificate) => Ok(data_certificate.len() as i32),
+                Some(data_certificate) => Ok(data_certificate.len()),
                 None => Err(self.error_for("ic0_data_certificate_size")),
             },
         };
@@ -2593,9 +2834,9 @@ impl SystemApi for SystemApiImpl {
 
     fn ic0_data_certificate_copy(
         &self,
-        dst: u32,
-        offset: u32,
-        size: u32,
+        dst: usize,
+        offset: usize,
+        size: usize,
         heap: &mut [u8],
     ) -> HypervisorResult<()> {
         let result = match &self.api_type {
@@ -2615,8 +2856,6 @@ impl SystemApi for SystemApiImpl {
                 data_certificate, ..
             } => match data_certificate {
                 Some(data_certificate) => {
-                    let (dst, offset, size) = (dst as usize, offset as usize, size as usize);
-
                     let (upper_bound, overflow) = offset.overflowing_add(size);
                     if overflow || upper_bound > data_certificate.len() {
                         return Err(ContractViolation(format!(
@@ -2662,7 +2901,12 @@ impl SystemApi for SystemApiImpl {
         result
     }
 
-    fn ic0_certified_data_set(&mut self, src: u32, size: u32, heap: &[u8]) -> HypervisorResult<()> {
+    fn ic0_certified_data_set(
+        &mut self,
+        src: usize,
+        size: usize,
+        heap: &[u8],
+    ) -> HypervisorResult<()> {
         let result = match &mut self.api_type {
             ApiType::Start { .. }
             | ApiType::ReplicatedQuery { .. }
@@ -2683,7 +2927,6 @@ impl SystemApi for SystemApiImpl {
                     )));
                 }
 
-                let (src, size) = (src as usize, size as usize);
                 let (upper_bound, overflow) = src.overflowing_add(size);
                 if overflow || upper_bound > heap.len() {
                     return Err(ContractViolation(format!(
@@ -2758,8 +3001,8 @@ impl SystemApi for SystemApiImpl {
         result
     }
 
-    fn ic0_debug_print(&self, src: u32, size: u32, heap: &[u8]) -> HypervisorResult<()> {
-        const MAX_DEBUG_MESSAGE_SIZE: u32 = 32 * 1024;
+    fn ic0_debug_print(&self, src: usize, size: usize, heap: &[u8]) -> HypervisorResult<()> {
+        const MAX_DEBUG_MESSAGE_SIZE: usize = 32 * 1024;
         let size = size.min(MAX_DEBUG_MESSAGE_SIZE);
         let msg = match valid_subslice("ic0.debug_print", src, size, heap) {
             Ok(bytes) => String::from_utf8_lossy(bytes).to_string(),
@@ -2778,8 +3021,8 @@ impl SystemApi for SystemApiImpl {
         Ok(())
     }
 
-    fn ic0_trap(&self, src: u32, size: u32, heap: &[u8]) -> HypervisorResult<()> {
-        const MAX_ERROR_MESSAGE_SIZE: u32 = 16 * 1024;
+    fn ic0_trap(&self, src: usize, size: usize, heap: &[u8]) -> HypervisorResult<()> {
+        const MAX_ERROR_MESSAGE_SIZE: usize = 16 * 1024;
         let size = size.min(MAX_ERROR_MESSAGE_SIZE);
         let result = {
             let msg = valid_subslice("trap", src, size, heap)
@@ -2790,6 +3033,27 @@ impl SystemApi for SystemApiImpl {
         trace_syscall!(self, ic0_trap, src, size, summarize(heap, src, size));
         Err(result)
     }
+
+    fn save_log_message(&mut self, is_trap: bool, src: usize, size: usize, heap: &[u8]) {
+        if let ExecutionMode::NonReplicated = self.execution_parameters.execution_mode {
+            return;
+        }
+        // `ic0.time` fails only in `canister_start`, whose output is not logged.
+        let time = match self.ic0_time() {
+            Ok(time) => time,
+            Err(_) => return,
+        };
+        let size = size.min(MAX_ALLOWED_CANISTER_LOG_BUFFER_SIZE);
+        let mut content = match valid_subslice("save_log_message", src, size, heap) {
+            Ok(bytes) => bytes.to_vec(),
+            Err(_) => b"(message out of memory bounds)".to_vec(),
+        };
+        if is_trap {
+            content = [b"[TRAP]: ".as_slice(), &content].concat();
+        }
+        self.sandbox_safe_system_state
+            .append_canister_log(time, content);
+    }
 }
 
 /// The default implementation of the `OutOfInstructionHandler` trait.
@@ -2808,7 +3072,7 @@ impl OutOfInstructionsHandler for DefaultOutOfInstructionsHandler {
 
 pub(crate) fn copy_cycles_to_heap(
     cycles: Cycles,
-    dst: u32,
+    dst: usize,
     heap: &mut [u8],
     method_name: &str,
 ) -> HypervisorResult<()> {
@@ -2817,7 +3081,6 @@ pub(crate) fn copy_cycles_to_heap(
     let size = bytes.len();
     assert_eq!(size, 16);
 
-    let dst = dst as usize;
     let (upper_bound, overflow) = dst.overflowing_add(size);
     if overflow || upper_bound > heap.len() {
         return Err(ContractViolation(format!(
@@ -2835,13 +3098,15 @@ pub(crate) fn copy_cycles_to_heap(
 
 pub(crate) fn valid_subslice<'a>(
     ctx: &str,
-    src: u32,
-    len: u32,
+    src: usize,
+    len: usize,
     slice: &'a [u8],
 ) -> HypervisorResult<&'a [u8]> {
-    let len = len as usize;
-    let src = src as usize;
-    if slice.len() < src + len {
+    let out_of_bounds = match src.checked_add(len) {
+        None => true,
+        Some(end) => slice.len() < end,
+    };
+    if out_of_bounds {
         return Err(ContractViolation(format!(
             "{}: src={} + length={} exceeds the slice size={}",
             ctx,
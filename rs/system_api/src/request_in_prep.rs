@@ -2,13 +2,18 @@ use crate::{sandbox_safe_system_state::SandboxSafeSystemState, valid_subslice};
 use ic_interfaces::execution_environment::{HypervisorError, HypervisorResult};
 use ic_logger::ReplicaLogger;
 use ic_types::{
-    messages::{CallContextId, Request},
+    messages::{CallContextId, Request, NO_DEADLINE},
     methods::{Callback, WasmClosure},
-    CanisterId, Cycles, NumBytes, PrincipalId,
+    CanisterId, CoarseTime, Cycles, NumBytes, PrincipalId, Time,
 };
 use serde::{Deserialize, Serialize};
 use std::convert::TryFrom;
 
+/// Upper bound on the timeout of a best-effort call, in seconds. Timeouts
+/// requested via `ic0.call_with_best_effort_response` are clamped to
+/// `[1, MAX_CALL_TIMEOUT_SECONDS]`.
+pub const MAX_CALL_TIMEOUT_SECONDS: u32 = 300;
+
 /// Represents an under construction `Request`.
 ///
 /// The main differences from a `Request` are:
@@ -35,6 +40,9 @@ pub struct RequestInPrep {
     on_reject: WasmClosure,
     on_cleanup: Option<WasmClosure>,
     cycles: Cycles,
+    /// Timeout of a best-effort call, in seconds; `None` for a guaranteed
+    /// response call.
+    timeout_seconds: Option<u32>,
     method_name: String,
     method_payload: Vec<u8>,
     /// The maximum size of a message that will go to a canister on another
@@ -53,10 +61,10 @@ impl RequestInPrep {
     #[allow(clippy::too_many_arguments)]
     pub(crate) fn new(
         sender: CanisterId,
-        callee_src: u32,
-        callee_size: u32,
-        method_name_src: u32,
-        method_name_len: u32,
+        callee_src: usize,
+        callee_size: usize,
+        method_name_src: usize,
+        method_name_len: usize,
         heap: &[u8],
         on_reply: WasmClosure,
         on_reject: WasmClosure,
@@ -92,6 +100,7 @@ impl RequestInPrep {
             on_reject,
             on_cleanup: None,
             cycles: Cycles::zero(),
+            timeout_seconds: None,
             method_name,
             method_payload: Vec::new(),
             max_size_remote_subnet,
@@ -111,14 +120,26 @@ impl RequestInPrep {
         }
     }
 
+    pub(crate) fn set_timeout(&mut self, timeout_seconds: u32) -> HypervisorResult<()> {
+        if self.timeout_seconds.is_some() {
+            Err(HypervisorError::ContractViolation(
+                "ic0.call_with_best_effort_response can be called at most once between `ic0.call_new` and `ic0.call_perform`"
+                    .to_string(),
+            ))
+        } else {
+            self.timeout_seconds = Some(timeout_seconds.clamp(1, MAX_CALL_TIMEOUT_SECONDS));
+            Ok(())
+        }
+    }
+
     pub(crate) fn take_cycles(self) -> Cycles {
         self.cycles
     }
 
     pub(crate) fn extend_method_payload(
         &mut self,
-        src: u32,
-        size: u32,
+        src: usize,
+        size: usize,
         heap: &[u8],
     ) -> HypervisorResult<()> {
         let current_size = self.method_name.len() + self.method_payload.len();
@@ -157,12 +178,14 @@ pub(crate) fn into_request(
         on_reject,
         on_cleanup,
         cycles,
+        timeout_seconds,
         method_name,
         method_payload,
         max_size_remote_subnet,
         multiplier_max_size_local_subnet,
     }: RequestInPrep,
     call_context_id: CallContextId,
+    time: Time,
     sandbox_safe_system_state: &mut SandboxSafeSystemState,
     _logger: &ReplicaLogger,
 ) -> HypervisorResult<RequestWithPrepayment> {
@@ -180,6 +203,11 @@ pub(crate) fn into_request(
         }
     }
 
+    let deadline = match timeout_seconds {
+        Some(timeout_seconds) => CoarseTime::floor(time).saturating_add_secs(timeout_seconds),
+        None => NO_DEADLINE,
+    };
+
     let prepayment_for_response_execution =
         sandbox_safe_system_state.prepayment_for_response_execution();
     let prepayment_for_response_transmission =
@@ -195,6 +223,7 @@ pub(crate) fn into_request(
         on_reply,
         on_reject,
         on_cleanup,
+        deadline,
     ))?;
 
     let req = Request {
@@ -203,6 +232,7 @@ pub(crate) fn into_request(
         method_name,
         method_payload,
         sender_reply_callback: callback_id,
+        deadline,
         payment: cycles,
     };
     // We cannot call `Request::payload_size_bytes()` before constructing the
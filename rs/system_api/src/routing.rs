@@ -6,10 +6,12 @@ use ic_base_types::{CanisterId, PrincipalId, SubnetId};
 use ic_btc_types::NetworkInRequest as BitcoinNetwork;
 use ic_ic00_types::{
     BitcoinGetBalanceArgs, BitcoinGetCurrentFeePercentilesArgs, BitcoinGetUtxosArgs,
-    BitcoinSendTransactionArgs, CanisterIdRecord, ComputeInitialEcdsaDealingsArgs,
-    ECDSAPublicKeyArgs, EcdsaKeyId, InstallCodeArgs, Method as Ic00Method, Payload,
-    ProvisionalTopUpCanisterArgs, SetControllerArgs, SignWithECDSAArgs, UninstallCodeArgs,
-    UpdateSettingsArgs,
+    BitcoinSendTransactionArgs, CanisterIdRecord, CanisterInfoRequest,
+    ComputeInitialEcdsaDealingsArgs, DeleteCanisterSnapshotArgs, ECDSAPublicKeyArgs, EcdsaKeyId,
+    InstallChunkedCodeArgs, InstallCodeArgs, LoadCanisterSnapshotArgs, Method as Ic00Method,
+    Payload, ProvisionalTopUpCanisterArgs, SchnorrKeyId, SchnorrPublicKeyArgs, SetControllerArgs,
+    SignWithECDSAArgs, SignWithSchnorrArgs, TakeCanisterSnapshotArgs, UninstallCodeArgs,
+    UpdateSettingsArgs, UploadChunkArgs, VetKdEncryptedKeyArgs, VetKdKeyId, VetKdPublicKeyArgs,
 };
 use ic_replicated_state::NetworkTopology;
 
@@ -20,6 +22,8 @@ pub(super) enum ResolveDestinationError {
     SubnetNotFound(CanisterId, Ic00Method),
     AlreadyResolved(PrincipalId),
     EcdsaKeyError(String),
+    SchnorrKeyError(String),
+    VetKdKeyError(String),
 }
 
 impl From<candid::Error> for ResolveDestinationError {
@@ -92,6 +96,10 @@ pub(super) fn resolve_destination(
         | Ok(Ic00Method::StartCanister)
         | Ok(Ic00Method::StopCanister)
         | Ok(Ic00Method::DeleteCanister)
+        | Ok(Ic00Method::ListCanisterSnapshots)
+        | Ok(Ic00Method::ClearChunkStore)
+        | Ok(Ic00Method::StoredChunks)
+        | Ok(Ic00Method::FetchCanisterLogs)
         | Ok(Ic00Method::DepositCycles) => {
             let args = Decode!(payload, CanisterIdRecord)?;
             let canister_id = args.get_canister_id();
@@ -114,6 +122,72 @@ pub(super) fn resolve_destination(
                     ResolveDestinationError::SubnetNotFound(canister_id, method.unwrap())
                 })
         }
+        Ok(Ic00Method::TakeCanisterSnapshot) => {
+            let args = Decode!(payload, TakeCanisterSnapshotArgs)?;
+            let canister_id = args.get_canister_id();
+            network_topology
+                .routing_table
+                .route(canister_id.get())
+                .map(|subnet_id| subnet_id.get())
+                .ok_or_else(|| {
+                    ResolveDestinationError::SubnetNotFound(canister_id, method.unwrap())
+                })
+        }
+        Ok(Ic00Method::LoadCanisterSnapshot) => {
+            let args = Decode!(payload, LoadCanisterSnapshotArgs)?;
+            let canister_id = args.get_canister_id();
+            network_topology
+                .routing_table
+                .route(canister_id.get())
+                .map(|subnet_id| subnet_id.get())
+                .ok_or_else(|| {
+                    ResolveDestinationError::SubnetNotFound(canister_id, method.unwrap())
+                })
+        }
+        Ok(Ic00Method::DeleteCanisterSnapshot) => {
+            let args = Decode!(payload, DeleteCanisterSnapshotArgs)?;
+            let canister_id = args.get_canister_id();
+            network_topology
+                .routing_table
+                .route(canister_id.get())
+                .map(|subnet_id| subnet_id.get())
+                .ok_or_else(|| {
+                    ResolveDestinationError::SubnetNotFound(canister_id, method.unwrap())
+                })
+        }
+        Ok(Ic00Method::UploadChunk) => {
+            let args = Decode!(payload, UploadChunkArgs)?;
+            let canister_id = args.get_canister_id();
+            network_topology
+                .routing_table
+                .route(canister_id.get())
+                .map(|subnet_id| subnet_id.get())
+                .ok_or_else(|| {
+                    ResolveDestinationError::SubnetNotFound(canister_id, method.unwrap())
+                })
+        }
+        Ok(Ic00Method::CanisterInfo) => {
+            let args = Decode!(payload, CanisterInfoRequest)?;
+            let canister_id = args.get_canister_id();
+            network_topology
+                .routing_table
+                .route(canister_id.get())
+                .map(|subnet_id| subnet_id.get())
+                .ok_or_else(|| {
+                    ResolveDestinationError::SubnetNotFound(canister_id, method.unwrap())
+                })
+        }
+        Ok(Ic00Method::InstallChunkedCode) => {
+            let args = Decode!(payload, InstallChunkedCodeArgs)?;
+            let canister_id = args.target_canister_id();
+            network_topology
+                .routing_table
+                .route(canister_id.get())
+                .map(|subnet_id| subnet_id.get())
+                .ok_or_else(|| {
+                    ResolveDestinationError::SubnetNotFound(canister_id, method.unwrap())
+                })
+        }
         Ok(Ic00Method::ProvisionalTopUpCanister) => {
             let args = ProvisionalTopUpCanisterArgs::decode(payload)?;
             let canister_id = args.get_canister_id();
@@ -179,6 +253,22 @@ pub(super) fn resolve_destination(
                 EcdsaSubnetKind::HoldsAndSignWithKey,
             )
         }
+        Ok(Ic00Method::SchnorrPublicKey) => {
+            let key_id = Decode!(payload, SchnorrPublicKeyArgs)?.key_id;
+            route_schnorr_message(&key_id, network_topology)
+        }
+        Ok(Ic00Method::SignWithSchnorr) => {
+            let key_id = Decode!(payload, SignWithSchnorrArgs)?.key_id;
+            route_schnorr_message(&key_id, network_topology)
+        }
+        Ok(Ic00Method::VetKdPublicKey) => {
+            let key_id = Decode!(payload, VetKdPublicKeyArgs)?.key_id;
+            route_vetkd_message(&key_id, network_topology)
+        }
+        Ok(Ic00Method::VetKdEncryptedKey) => {
+            let key_id = Decode!(payload, VetKdEncryptedKeyArgs)?.key_id;
+            route_vetkd_message(&key_id, network_topology)
+        }
         Ok(Ic00Method::ComputeInitialEcdsaDealings) => {
             let args = Decode!(payload, ComputeInitialEcdsaDealingsArgs)?;
             route_ecdsa_message(
@@ -273,6 +363,53 @@ fn route_ecdsa_message(
     }
 }
 
+/// Routes a Schnorr request to the first subnet holding the requested key.
+///
+/// Requests for keys with an unsupported algorithm are rejected, as no subnet
+/// could ever produce the signature.
+fn route_schnorr_message(
+    key_id: &SchnorrKeyId,
+    network_topology: &NetworkTopology,
+) -> Result<PrincipalId, ResolveDestinationError> {
+    if !key_id.algorithm.is_supported() {
+        return Err(ResolveDestinationError::SchnorrKeyError(format!(
+            "Requested Schnorr key: {}, algorithm {} is not supported",
+            key_id, key_id.algorithm
+        )));
+    }
+    let mut keys = BTreeSet::new();
+    for (subnet_id, topology) in &network_topology.subnets {
+        if topology.schnorr_keys_held.contains(key_id) {
+            return Ok((*subnet_id).get());
+        }
+        keys.extend(topology.schnorr_keys_held.iter().map(|key| key.to_string()));
+    }
+    Err(ResolveDestinationError::SchnorrKeyError(format!(
+        "Requested Schnorr key: {}, existing keys: [{}]",
+        key_id,
+        keys.into_iter().collect::<Vec<_>>().join(", ")
+    )))
+}
+
+/// Routes a vetKD request to the first subnet holding the requested key.
+fn route_vetkd_message(
+    key_id: &VetKdKeyId,
+    network_topology: &NetworkTopology,
+) -> Result<PrincipalId, ResolveDestinationError> {
+    let mut keys = BTreeSet::new();
+    for (subnet_id, topology) in &network_topology.subnets {
+        if topology.vetkd_keys_held.contains(key_id) {
+            return Ok((*subnet_id).get());
+        }
+        keys.extend(topology.vetkd_keys_held.iter().map(|key| key.to_string()));
+    }
+    Err(ResolveDestinationError::VetKdKeyError(format!(
+        "Requested vetKD key: {}, existing keys: [{}]",
+        key_id,
+        keys.into_iter().collect::<Vec<_>>().join(", ")
+    )))
+}
+
 fn route_bitcoin_message(
     network: BitcoinNetwork,
     network_topology: &NetworkTopology,
@@ -332,7 +469,8 @@ mod tests {
     use candid::Encode;
     use ic_base_types::RegistryVersion;
     use ic_ic00_types::{
-        ComputeInitialEcdsaDealingsArgs, EcdsaCurve, EcdsaKeyId, SignWithECDSAArgs,
+        ComputeInitialEcdsaDealingsArgs, EcdsaCurve, EcdsaKeyId, SchnorrAlgorithm,
+        SignWithECDSAArgs, VetKdCurve,
     };
     use ic_replicated_state::SubnetTopology;
     use ic_test_utilities::types::ids::{canister_test_id, node_test_id, subnet_test_id};
@@ -560,4 +698,207 @@ mod tests {
             PrincipalId::new_subnet_test_id(0)
         )
     }
+
+    fn schnorr_key_id() -> SchnorrKeyId {
+        SchnorrKeyId {
+            algorithm: SchnorrAlgorithm::Bip340Secp256k1,
+            name: "some_key".to_string(),
+        }
+    }
+
+    fn network_with_schnorr_subnet() -> NetworkTopology {
+        NetworkTopology {
+            subnets: btreemap! {
+                subnet_test_id(0) => SubnetTopology::default(),
+                subnet_test_id(1) => SubnetTopology {
+                    schnorr_keys_held: vec![schnorr_key_id()].into_iter().collect(),
+                    ..SubnetTopology::default()
+                },
+            },
+            ..NetworkTopology::default()
+        }
+    }
+
+    fn schnorr_sign_req(key_id: SchnorrKeyId) -> Vec<u8> {
+        let args = SignWithSchnorrArgs {
+            message: vec![1; 32],
+            derivation_path: vec![vec![0; 10]],
+            key_id,
+        };
+        Encode!(&args).unwrap()
+    }
+
+    #[test]
+    fn resolve_schnorr_sign_and_public_key() {
+        let public_key_req = Encode!(&SchnorrPublicKeyArgs {
+            canister_id: None,
+            derivation_path: vec![],
+            key_id: schnorr_key_id(),
+        })
+        .unwrap();
+        for (method, payload) in [
+            (
+                Ic00Method::SignWithSchnorr,
+                schnorr_sign_req(schnorr_key_id()),
+            ),
+            (Ic00Method::SchnorrPublicKey, public_key_req),
+        ] {
+            assert_eq!(
+                resolve_destination(
+                    &network_with_schnorr_subnet(),
+                    &method.to_string(),
+                    &payload,
+                    subnet_test_id(0),
+                )
+                .unwrap(),
+                PrincipalId::new_subnet_test_id(1)
+            )
+        }
+    }
+
+    #[test]
+    fn resolve_schnorr_sign_error() {
+        let unknown_key_id = SchnorrKeyId {
+            algorithm: SchnorrAlgorithm::Bip340Secp256k1,
+            name: "other_key".to_string(),
+        };
+        assert_matches!(resolve_destination(
+            &network_with_schnorr_subnet(),
+            &Ic00Method::SignWithSchnorr.to_string(),
+            &schnorr_sign_req(unknown_key_id.clone()),
+            subnet_test_id(0),
+        )
+        .unwrap_err(),
+        ResolveDestinationError::SchnorrKeyError(err) => assert_eq!(
+                err,
+                format!("Requested Schnorr key: {}, existing keys: [{}]", unknown_key_id, schnorr_key_id())
+            )
+        )
+    }
+
+    fn vetkd_key_id() -> VetKdKeyId {
+        VetKdKeyId {
+            curve: VetKdCurve::Bls12381G2,
+            name: "some_key".to_string(),
+        }
+    }
+
+    fn network_with_vetkd_subnet() -> NetworkTopology {
+        NetworkTopology {
+            subnets: btreemap! {
+                subnet_test_id(0) => SubnetTopology::default(),
+                subnet_test_id(1) => SubnetTopology {
+                    vetkd_keys_held: vec![vetkd_key_id()].into_iter().collect(),
+                    ..SubnetTopology::default()
+                },
+            },
+            ..NetworkTopology::default()
+        }
+    }
+
+    fn vetkd_encrypted_key_req(key_id: VetKdKeyId) -> Vec<u8> {
+        let args = VetKdEncryptedKeyArgs {
+            derivation_path: vec![vec![0; 10]],
+            input: vec![1; 32],
+            key_id,
+            transport_public_key: vec![2; 48],
+        };
+        Encode!(&args).unwrap()
+    }
+
+    #[test]
+    fn resolve_vetkd_encrypted_key_and_public_key() {
+        let public_key_req = Encode!(&VetKdPublicKeyArgs {
+            canister_id: None,
+            derivation_path: vec![],
+            key_id: vetkd_key_id(),
+        })
+        .unwrap();
+        for (method, payload) in [
+            (
+                Ic00Method::VetKdEncryptedKey,
+                vetkd_encrypted_key_req(vetkd_key_id()),
+            ),
+            (Ic00Method::VetKdPublicKey, public_key_req),
+        ] {
+            assert_eq!(
+                resolve_destination(
+                    &network_with_vetkd_subnet(),
+                    &method.to_string(),
+                    &payload,
+                    subnet_test_id(0),
+                )
+                .unwrap(),
+                PrincipalId::new_subnet_test_id(1)
+            )
+        }
+    }
+
+    #[test]
+    fn resolve_vetkd_encrypted_key_error() {
+        let unknown_key_id = VetKdKeyId {
+            curve: VetKdCurve::Bls12381G2,
+            name: "other_key".to_string(),
+        };
+        assert_matches!(resolve_destination(
+            &network_with_vetkd_subnet(),
+            &Ic00Method::VetKdEncryptedKey.to_string(),
+            &vetkd_encrypted_key_req(unknown_key_id.clone()),
+            subnet_test_id(0),
+        )
+        .unwrap_err(),
+        ResolveDestinationError::VetKdKeyError(err) => assert_eq!(
+                err,
+                format!("Requested vetKD key: {}, existing keys: [{}]", unknown_key_id, vetkd_key_id())
+            )
+        )
+    }
+
+    #[test]
+    fn resolve_schnorr_rejects_unsupported_algorithm() {
+        let ed25519_key_id = SchnorrKeyId {
+            algorithm: SchnorrAlgorithm::Ed25519,
+            name: "some_key".to_string(),
+        };
+        // Even a subnet claiming to hold the key cannot produce signatures.
+        let network_topology = NetworkTopology {
+            subnets: btreemap! {
+                subnet_test_id(1) => SubnetTopology {
+                    schnorr_keys_held: vec![ed25519_key_id.clone()].into_iter().collect(),
+                    ..SubnetTopology::default()
+                },
+            },
+            ..NetworkTopology::default()
+        };
+        let public_key_req = Encode!(&SchnorrPublicKeyArgs {
+            canister_id: None,
+            derivation_path: vec![],
+            key_id: ed25519_key_id.clone(),
+        })
+        .unwrap();
+        for (method, payload) in [
+            (
+                Ic00Method::SignWithSchnorr,
+                schnorr_sign_req(ed25519_key_id.clone()),
+            ),
+            (Ic00Method::SchnorrPublicKey, public_key_req),
+        ] {
+            assert_matches!(
+                resolve_destination(
+                    &network_topology,
+                    &method.to_string(),
+                    &payload,
+                    subnet_test_id(0),
+                )
+                .unwrap_err(),
+                ResolveDestinationError::SchnorrKeyError(err) => assert_eq!(
+                    err,
+                    format!(
+                        "Requested Schnorr key: {}, algorithm Ed25519 is not supported",
+                        ed25519_key_id
+                    )
+                )
+            )
+        }
+    }
 }
@@ -6,7 +6,8 @@ use ic_constants::{LOG_CANISTER_OPERATION_CYCLES_THRESHOLD, SMALL_APP_SUBNET_MAX
 use ic_cycles_account_manager::{CyclesAccountManager, CyclesAccountManagerError};
 use ic_error_types::{ErrorCode, RejectCode, UserError};
 use ic_ic00_types::{
-    CreateCanisterArgs, InstallCodeArgs, Method as Ic00Method, Payload,
+    CreateCanisterArgs, EcdsaKeyId, InstallChunkedCodeArgs, InstallCodeArgs,
+    LoadCanisterSnapshotArgs, Method as Ic00Method, Payload,
     ProvisionalCreateCanisterWithCyclesArgs, SetControllerArgs, UninstallCodeArgs,
     UpdateSettingsArgs, IC_00,
 };
@@ -19,6 +20,7 @@ use ic_replicated_state::{
     SystemState,
 };
 use ic_types::{
+    canister_log::CanisterLog,
     messages::{CallContextId, CallbackId, RejectContext, Request},
     methods::Callback,
     CanisterTimer, ComputeAllocation, Cycles, MemoryAllocation, NumInstructions, NumPages, Time,
@@ -222,8 +224,15 @@ impl SystemStateChanges {
                     .map(|record| record.get_sender_canister_version())
                     .map_err(Self::candid_error_to_user_error)
             }
+            Ok(Ic00Method::LoadCanisterSnapshot) => LoadCanisterSnapshotArgs::decode(payload)
+                .map(|record| record.get_sender_canister_version())
+                .map_err(Self::candid_error_to_user_error),
+            Ok(Ic00Method::InstallChunkedCode) => InstallChunkedCodeArgs::decode(payload)
+                .map(|record| record.get_sender_canister_version())
+                .map_err(Self::candid_error_to_user_error),
             Ok(Ic00Method::SignWithECDSA)
             | Ok(Ic00Method::CanisterStatus)
+            | Ok(Ic00Method::CanisterInfo)
             | Ok(Ic00Method::StartCanister)
             | Ok(Ic00Method::StopCanister)
             | Ok(Ic00Method::DeleteCanister)
@@ -232,6 +241,10 @@ impl SystemStateChanges {
             | Ok(Ic00Method::HttpRequest)
             | Ok(Ic00Method::SetupInitialDKG)
             | Ok(Ic00Method::ECDSAPublicKey)
+            | Ok(Ic00Method::SchnorrPublicKey)
+            | Ok(Ic00Method::SignWithSchnorr)
+            | Ok(Ic00Method::VetKdPublicKey)
+            | Ok(Ic00Method::VetKdEncryptedKey)
             | Ok(Ic00Method::ComputeInitialEcdsaDealings)
             | Ok(Ic00Method::ProvisionalTopUpCanister)
             | Ok(Ic00Method::BitcoinSendTransactionInternal)
@@ -239,7 +252,14 @@ impl SystemStateChanges {
             | Ok(Ic00Method::BitcoinGetBalance)
             | Ok(Ic00Method::BitcoinGetUtxos)
             | Ok(Ic00Method::BitcoinSendTransaction)
-            | Ok(Ic00Method::BitcoinGetCurrentFeePercentiles) => Ok(None),
+            | Ok(Ic00Method::BitcoinGetCurrentFeePercentiles)
+            | Ok(Ic00Method::TakeCanisterSnapshot)
+            | Ok(Ic00Method::ListCanisterSnapshots)
+            | Ok(Ic00Method::DeleteCanisterSnapshot)
+            | Ok(Ic00Method::UploadChunk)
+            | Ok(Ic00Method::ClearChunkStore)
+            | Ok(Ic00Method::StoredChunks)
+            | Ok(Ic00Method::FetchCanisterLogs) => Ok(None),
             Err(_) => Err(UserError::new(
                 ErrorCode::CanisterMethodNotFound,
                 format!("Management canister has no method '{}'", msg.method_name),
@@ -302,7 +322,7 @@ impl SystemStateChanges {
                 })?;
                 if (*amount_taken).get() > LOG_CANISTER_OPERATION_CYCLES_THRESHOLD {
                     match call_context.call_origin() {
-                        CallOrigin::CanisterUpdate(origin_canister_id, _)
+                        CallOrigin::CanisterUpdate(origin_canister_id, _, _)
                         | CallOrigin::CanisterQuery(origin_canister_id, _) => info!(
                             logger,
                             "Canister {} accepted {} cycles from canister {}.",
@@ -447,6 +467,7 @@ pub struct SandboxSafeSystemState {
     pub system_state_changes: SystemStateChanges,
     pub(super) canister_id: CanisterId,
     pub(super) controller: PrincipalId,
+    controllers: BTreeSet<PrincipalId>,
     pub(super) status: CanisterStatusView,
     pub(super) subnet_type: SubnetType,
     pub(super) subnet_size: usize,
@@ -465,6 +486,11 @@ pub struct SandboxSafeSystemState {
     ic00_aliases: BTreeSet<CanisterId>,
     global_timer: CanisterTimer,
     canister_version: u64,
+    // The size of the subnet that signs with each ECDSA key.
+    ecdsa_signing_subnet_sizes: BTreeMap<EcdsaKeyId, usize>,
+    // Log records produced during this execution. Record indices continue
+    // from those of the canister's log.
+    canister_log: CanisterLog,
 }
 
 impl SandboxSafeSystemState {
@@ -474,6 +500,7 @@ impl SandboxSafeSystemState {
     pub fn new_internal(
         canister_id: CanisterId,
         controller: PrincipalId,
+        controllers: BTreeSet<PrincipalId>,
         status: CanisterStatusView,
         freeze_threshold: NumSeconds,
         memory_allocation: MemoryAllocation,
@@ -488,10 +515,13 @@ impl SandboxSafeSystemState {
         dirty_page_overhead: NumInstructions,
         global_timer: CanisterTimer,
         canister_version: u64,
+        ecdsa_signing_subnet_sizes: BTreeMap<EcdsaKeyId, usize>,
+        canister_log: CanisterLog,
     ) -> Self {
         Self {
             canister_id,
             controller,
+            controllers,
             status,
             subnet_type: cycles_account_manager.subnet_type(),
             subnet_size,
@@ -508,6 +538,8 @@ impl SandboxSafeSystemState {
             ic00_aliases,
             global_timer,
             canister_version,
+            ecdsa_signing_subnet_sizes,
+            canister_log,
         }
     }
 
@@ -548,10 +580,21 @@ impl SandboxSafeSystemState {
         let subnet_size = network_topology
             .get_subnet_size(&cycles_account_manager.get_subnet_id())
             .unwrap_or(SMALL_APP_SUBNET_MAX_SIZE);
+        // Signature requests are routed to the first subnet enabled to sign
+        // with the key, whose size determines the signature fee.
+        let ecdsa_signing_subnet_sizes = network_topology
+            .ecdsa_signing_subnets
+            .iter()
+            .filter_map(|(key_id, subnets)| {
+                let subnet_size = network_topology.get_subnet_size(subnets.first()?)?;
+                Some((key_id.clone(), subnet_size))
+            })
+            .collect();
 
         Self::new_internal(
             system_state.canister_id,
             *system_state.controller(),
+            system_state.controllers.clone(),
             CanisterStatusView::from_full_status(&system_state.status),
             system_state.freeze_threshold,
             system_state.memory_allocation,
@@ -568,6 +611,8 @@ impl SandboxSafeSystemState {
             dirty_page_overhead,
             system_state.global_timer,
             system_state.canister_version,
+            ecdsa_signing_subnet_sizes,
+            CanisterLog::new_with_next_index(system_state.canister_log.next_idx()),
         )
     }
 
@@ -583,6 +628,63 @@ impl SandboxSafeSystemState {
         self.canister_version
     }
 
+    /// Returns true if `principal_id` is one of the controllers of the canister.
+    pub fn is_controller(&self, principal_id: &PrincipalId) -> bool {
+        self.controllers.contains(principal_id)
+    }
+
+    /// Returns the cycles withdrawn when making an inter-canister call with
+    /// the given method name and argument sizes.
+    pub fn cost_call(&self, method_name_size: u64, payload_size: u64) -> Cycles {
+        self.cycles_account_manager.xnet_call_total_fee(
+            NumBytes::from(method_name_size.saturating_add(payload_size)),
+            self.subnet_size,
+        )
+    }
+
+    /// Returns the fee for creating a canister on this subnet.
+    pub fn cost_create_canister(&self) -> Cycles {
+        self.cycles_account_manager
+            .canister_creation_fee(self.subnet_size)
+    }
+
+    /// Returns the fee for an HTTP outcall with the given request size and
+    /// maximum response size.
+    pub fn cost_http_request(&self, request_size: u64, max_response_bytes: u64) -> Cycles {
+        self.cycles_account_manager.http_request_fee(
+            NumBytes::from(request_size),
+            Some(NumBytes::from(max_response_bytes)),
+            self.subnet_size,
+        )
+    }
+
+    /// Returns the fee for a signature with the given ECDSA key, or `None` if
+    /// no subnet signs with the key.
+    pub fn cost_sign_with_ecdsa(&self, key_id: &EcdsaKeyId) -> Option<Cycles> {
+        self.ecdsa_signing_subnet_sizes
+            .get(key_id)
+            .map(|subnet_size| {
+                self.cycles_account_manager
+                    .ecdsa_signature_fee(*subnet_size)
+            })
+    }
+
+    /// Appends a record to the log of this execution.
+    pub fn append_canister_log(&mut self, time: Time, content: Vec<u8>) {
+        self.canister_log
+            .add_record(time.as_nanos_since_unix_epoch(), content);
+    }
+
+    /// Returns the log records of this execution, leaving an empty log that
+    /// continues with the same record indices.
+    pub fn take_canister_log(&mut self) -> CanisterLog {
+        let next_idx = self.canister_log.next_idx();
+        std::mem::replace(
+            &mut self.canister_log,
+            CanisterLog::new_with_next_index(next_idx),
+        )
+    }
+
     pub fn set_global_timer(&mut self, timer: CanisterTimer) {
         // Update both sandbox global timer and the changes.
         self.system_state_changes.new_global_timer = Some(timer);
@@ -52,26 +52,38 @@ impl SystemApi for SystemApiEmpty {
     fn slice_instructions_executed(&self, _instruction_counter: i64) -> NumInstructions {
         unimplemented!("{}", MESSAGE_UNIMPLEMENTED)
     }
-    fn ic0_msg_caller_copy(&self, _: u32, _: u32, _: u32, _: &mut [u8]) -> HypervisorResult<()> {
+    fn ic0_msg_caller_copy(
+        &self,
+        _: usize,
+        _: usize,
+        _: usize,
+        _: &mut [u8],
+    ) -> HypervisorResult<()> {
         unimplemented!("{}", MESSAGE_UNIMPLEMENTED)
     }
-    fn ic0_msg_caller_size(&self) -> HypervisorResult<u32> {
+    fn ic0_msg_caller_size(&self) -> HypervisorResult<usize> {
         unimplemented!("{}", MESSAGE_UNIMPLEMENTED)
     }
-    fn ic0_msg_arg_data_size(&self) -> HypervisorResult<u32> {
+    fn ic0_msg_arg_data_size(&self) -> HypervisorResult<usize> {
         unimplemented!("{}", MESSAGE_UNIMPLEMENTED)
     }
-    fn ic0_msg_arg_data_copy(&self, _: u32, _: u32, _: u32, _: &mut [u8]) -> HypervisorResult<()> {
+    fn ic0_msg_arg_data_copy(
+        &self,
+        _: usize,
+        _: usize,
+        _: usize,
+        _: &mut [u8],
+    ) -> HypervisorResult<()> {
         unimplemented!("{}", MESSAGE_UNIMPLEMENTED)
     }
-    fn ic0_msg_method_name_size(&self) -> HypervisorResult<u32> {
+    fn ic0_msg_method_name_size(&self) -> HypervisorResult<usize> {
         unimplemented!("{}", MESSAGE_UNIMPLEMENTED)
     }
     fn ic0_msg_method_name_copy(
         &self,
-        _: u32,
-        _: u32,
-        _: u32,
+        _: usize,
+        _: usize,
+        _: usize,
         _: &mut [u8],
     ) -> HypervisorResult<()> {
         unimplemented!("{}", MESSAGE_UNIMPLEMENTED)
@@ -79,7 +91,7 @@ impl SystemApi for SystemApiEmpty {
     fn ic0_accept_message(&mut self) -> HypervisorResult<()> {
         unimplemented!("{}", MESSAGE_UNIMPLEMENTED)
     }
-    fn ic0_msg_reply_data_append(&mut self, _: u32, _: u32, _: &[u8]) -> HypervisorResult<()> {
+    fn ic0_msg_reply_data_append(&mut self, _: usize, _: usize, _: &[u8]) -> HypervisorResult<()> {
         unimplemented!("{}", MESSAGE_UNIMPLEMENTED)
     }
     fn ic0_msg_reply(&mut self) -> HypervisorResult<()> {
@@ -88,17 +100,17 @@ impl SystemApi for SystemApiEmpty {
     fn ic0_msg_reject_code(&self) -> HypervisorResult<i32> {
         unimplemented!("{}", MESSAGE_UNIMPLEMENTED)
     }
-    fn ic0_msg_reject(&mut self, _: u32, _: u32, _: &[u8]) -> HypervisorResult<()> {
+    fn ic0_msg_reject(&mut self, _: usize, _: usize, _: &[u8]) -> HypervisorResult<()> {
         unimplemented!("{}", MESSAGE_UNIMPLEMENTED)
     }
-    fn ic0_msg_reject_msg_size(&self) -> HypervisorResult<u32> {
+    fn ic0_msg_reject_msg_size(&self) -> HypervisorResult<usize> {
         unimplemented!("{}", MESSAGE_UNIMPLEMENTED)
     }
     fn ic0_msg_reject_msg_copy(
         &self,
-        _: u32,
-        _: u32,
-        _: u32,
+        _: usize,
+        _: usize,
+        _: usize,
         _: &mut [u8],
     ) -> HypervisorResult<()> {
         unimplemented!("{}", MESSAGE_UNIMPLEMENTED)
@@ -108,9 +120,9 @@ impl SystemApi for SystemApiEmpty {
     }
     fn ic0_canister_self_copy(
         &mut self,
-        _: u32,
-        _: u32,
-        _: u32,
+        _: usize,
+        _: usize,
+        _: usize,
         _: &mut [u8],
     ) -> HypervisorResult<()> {
         unimplemented!("{}", MESSAGE_UNIMPLEMENTED)
@@ -120,25 +132,28 @@ impl SystemApi for SystemApiEmpty {
     }
     fn ic0_controller_copy(
         &mut self,
-        _: u32,
-        _: u32,
-        _: u32,
+        _: usize,
+        _: usize,
+        _: usize,
         _: &mut [u8],
     ) -> HypervisorResult<()> {
         unimplemented!("{}", MESSAGE_UNIMPLEMENTED)
     }
-    fn ic0_debug_print(&self, _: u32, _: u32, _: &[u8]) -> HypervisorResult<()> {
+    fn ic0_debug_print(&self, _: usize, _: usize, _: &[u8]) -> HypervisorResult<()> {
         unimplemented!("{}", MESSAGE_UNIMPLEMENTED)
     }
-    fn ic0_trap(&self, _: u32, _: u32, _: &[u8]) -> HypervisorResult<()> {
+    fn ic0_trap(&self, _: usize, _: usize, _: &[u8]) -> HypervisorResult<()> {
+        unimplemented!("{}", MESSAGE_UNIMPLEMENTED)
+    }
+    fn save_log_message(&mut self, _: bool, _: usize, _: usize, _: &[u8]) {
         unimplemented!("{}", MESSAGE_UNIMPLEMENTED)
     }
     fn ic0_call_new(
         &mut self,
-        _: u32,
-        _: u32,
-        _: u32,
-        _: u32,
+        _: usize,
+        _: usize,
+        _: usize,
+        _: usize,
         _: u32,
         _: u32,
         _: u32,
@@ -147,12 +162,15 @@ impl SystemApi for SystemApiEmpty {
     ) -> HypervisorResult<()> {
         unimplemented!("{}", MESSAGE_UNIMPLEMENTED)
     }
-    fn ic0_call_data_append(&mut self, _: u32, _: u32, _: &[u8]) -> HypervisorResult<()> {
+    fn ic0_call_data_append(&mut self, _: usize, _: usize, _: &[u8]) -> HypervisorResult<()> {
         unimplemented!("{}", MESSAGE_UNIMPLEMENTED)
     }
     fn ic0_call_on_cleanup(&mut self, _: u32, _: u32) -> HypervisorResult<()> {
         unimplemented!("{}", MESSAGE_UNIMPLEMENTED)
     }
+    fn ic0_call_with_best_effort_response(&mut self, _: u32) -> HypervisorResult<()> {
+        unimplemented!("{}", MESSAGE_UNIMPLEMENTED)
+    }
     fn ic0_call_cycles_add(&mut self, _: u64) -> HypervisorResult<()> {
         unimplemented!("{}", MESSAGE_UNIMPLEMENTED)
     }
@@ -201,6 +219,43 @@ impl SystemApi for SystemApiEmpty {
     fn ic0_canister_version(&self) -> HypervisorResult<u64> {
         unimplemented!("{}", MESSAGE_UNIMPLEMENTED)
     }
+    fn ic0_is_controller(&self, _src: usize, _size: usize, _heap: &[u8]) -> HypervisorResult<u32> {
+        unimplemented!("{}", MESSAGE_UNIMPLEMENTED)
+    }
+    fn ic0_in_replicated_execution(&self) -> HypervisorResult<i32> {
+        unimplemented!("{}", MESSAGE_UNIMPLEMENTED)
+    }
+    fn ic0_cost_call(
+        &self,
+        _method_name_size: u64,
+        _payload_size: u64,
+        _dst: usize,
+        _heap: &mut [u8],
+    ) -> HypervisorResult<()> {
+        unimplemented!("{}", MESSAGE_UNIMPLEMENTED)
+    }
+    fn ic0_cost_create_canister(&self, _dst: usize, _heap: &mut [u8]) -> HypervisorResult<()> {
+        unimplemented!("{}", MESSAGE_UNIMPLEMENTED)
+    }
+    fn ic0_cost_http_request(
+        &self,
+        _request_size: u64,
+        _max_res_bytes: u64,
+        _dst: usize,
+        _heap: &mut [u8],
+    ) -> HypervisorResult<()> {
+        unimplemented!("{}", MESSAGE_UNIMPLEMENTED)
+    }
+    fn ic0_cost_sign_with_ecdsa(
+        &self,
+        _src: usize,
+        _size: usize,
+        _curve: u32,
+        _dst: usize,
+        _heap: &mut [u8],
+    ) -> HypervisorResult<u32> {
+        unimplemented!("{}", MESSAGE_UNIMPLEMENTED)
+    }
     fn out_of_instructions(&mut self, _instruction_counter: i64) -> Result<i64, HypervisorError> {
         unimplemented!("{}", MESSAGE_UNIMPLEMENTED)
     }
@@ -221,19 +276,19 @@ impl SystemApi for SystemApiEmpty {
     fn ic0_canister_cycle_balance(&self) -> HypervisorResult<u64> {
         unimplemented!("{}", MESSAGE_UNIMPLEMENTED)
     }
-    fn ic0_canister_cycle_balance128(&self, _: u32, _: &mut [u8]) -> HypervisorResult<()> {
+    fn ic0_canister_cycle_balance128(&self, _: usize, _: &mut [u8]) -> HypervisorResult<()> {
         unimplemented!("{}", MESSAGE_UNIMPLEMENTED)
     }
     fn ic0_msg_cycles_available(&self) -> HypervisorResult<u64> {
         unimplemented!("{}", MESSAGE_UNIMPLEMENTED)
     }
-    fn ic0_msg_cycles_available128(&self, _: u32, _: &mut [u8]) -> HypervisorResult<()> {
+    fn ic0_msg_cycles_available128(&self, _: usize, _: &mut [u8]) -> HypervisorResult<()> {
         unimplemented!("{}", MESSAGE_UNIMPLEMENTED)
     }
     fn ic0_msg_cycles_refunded(&self) -> HypervisorResult<u64> {
         unimplemented!("{}", MESSAGE_UNIMPLEMENTED)
     }
-    fn ic0_msg_cycles_refunded128(&self, _: u32, _: &mut [u8]) -> HypervisorResult<()> {
+    fn ic0_msg_cycles_refunded128(&self, _: usize, _: &mut [u8]) -> HypervisorResult<()> {
         unimplemented!("{}", MESSAGE_UNIMPLEMENTED)
     }
     fn ic0_msg_cycles_accept(&mut self, _: u64) -> HypervisorResult<u64> {
@@ -242,25 +297,25 @@ impl SystemApi for SystemApiEmpty {
     fn ic0_msg_cycles_accept128(
         &mut self,
         _: Cycles,
-        _: u32,
+        _: usize,
         _: &mut [u8],
     ) -> HypervisorResult<()> {
         unimplemented!("{}", MESSAGE_UNIMPLEMENTED)
     }
-    fn ic0_certified_data_set(&mut self, _: u32, _: u32, _: &[u8]) -> HypervisorResult<()> {
+    fn ic0_certified_data_set(&mut self, _: usize, _: usize, _: &[u8]) -> HypervisorResult<()> {
         unimplemented!("{}", MESSAGE_UNIMPLEMENTED)
     }
     fn ic0_data_certificate_present(&self) -> HypervisorResult<i32> {
         unimplemented!("{}", MESSAGE_UNIMPLEMENTED)
     }
-    fn ic0_data_certificate_size(&self) -> HypervisorResult<i32> {
+    fn ic0_data_certificate_size(&self) -> HypervisorResult<usize> {
         unimplemented!("{}", MESSAGE_UNIMPLEMENTED)
     }
     fn ic0_data_certificate_copy(
         &self,
-        _: u32,
-        _: u32,
-        _: u32,
+        _: usize,
+        _: usize,
+        _: usize,
         _: &mut [u8],
     ) -> HypervisorResult<()> {
         unimplemented!("{}", MESSAGE_UNIMPLEMENTED)
@@ -20,7 +20,7 @@ use ic_test_utilities::{
 };
 use ic_test_utilities_execution_environment::default_memory_for_system_api;
 use ic_types::{
-    messages::{CallContextId, CallbackId, RejectContext},
+    messages::{CallContextId, CallbackId, RejectContext, NO_DEADLINE},
     methods::SystemMethod,
     ComputeAllocation, Cycles, NumInstructions, Time,
 };
@@ -140,7 +140,7 @@ pub fn get_system_state() -> SystemState {
         .call_context_manager_mut()
         .unwrap()
         .new_call_context(
-            CallOrigin::CanisterUpdate(canister_test_id(33), CallbackId::from(5)),
+            CallOrigin::CanisterUpdate(canister_test_id(33), CallbackId::from(5), NO_DEADLINE),
             Cycles::new(50),
             Time::from_nanos_since_unix_epoch(0),
         );
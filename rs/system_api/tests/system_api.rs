@@ -1,36 +1,39 @@
+use assert_matches::assert_matches;
 use ic_base_types::NumSeconds;
 use ic_config::{
     embedders::Config as EmbeddersConfig, flag_status::FlagStatus, subnet_config::SchedulerConfig,
 };
 use ic_constants::SMALL_APP_SUBNET_MAX_SIZE;
 use ic_error_types::RejectCode;
+use ic_ic00_types::{EcdsaCurve, EcdsaKeyId};
 use ic_interfaces::execution_environment::{
-    CanisterOutOfCyclesError, HypervisorError, HypervisorResult, PerformanceCounterType,
-    SubnetAvailableMemory, SystemApi, TrapCode,
+    CanisterOutOfCyclesError, ExecutionMode, HypervisorError, HypervisorResult,
+    PerformanceCounterType, SubnetAvailableMemory, SystemApi, TrapCode,
 };
 use ic_logger::replica_logger::no_op_logger;
 use ic_registry_subnet_type::SubnetType;
 use ic_replicated_state::{
-    testing::CanisterQueuesTesting, CallOrigin, NetworkTopology, SystemState,
+    testing::CanisterQueuesTesting, CallOrigin, NetworkTopology, NodeTopology, SubnetTopology,
+    SystemState,
 };
 use ic_system_api::{
     sandbox_safe_system_state::SandboxSafeSystemState, ApiType, DefaultOutOfInstructionsHandler,
-    NonReplicatedQueryKind, SystemApiImpl,
+    ExecutionParameters, NonReplicatedQueryKind, SystemApiImpl, MAX_CALL_TIMEOUT_SECONDS,
 };
 use ic_test_utilities::{
     cycles_account_manager::CyclesAccountManagerBuilder,
     mock_time,
     state::SystemStateBuilder,
     types::{
-        ids::{call_context_test_id, canister_test_id, subnet_test_id, user_test_id},
+        ids::{call_context_test_id, canister_test_id, node_test_id, subnet_test_id, user_test_id},
         messages::RequestBuilder,
     },
 };
 use ic_test_utilities_execution_environment::default_memory_for_system_api;
 use ic_types::{
-    messages::{CallContextId, CallbackId, RejectContext, MAX_RESPONSE_COUNT_BYTES},
+    messages::{CallContextId, CallbackId, RejectContext, MAX_RESPONSE_COUNT_BYTES, NO_DEADLINE},
     methods::{Callback, WasmClosure},
-    time, CanisterTimer, CountBytes, Cycles, NumBytes, NumInstructions, Time,
+    time, CanisterTimer, CoarseTime, CountBytes, Cycles, NumBytes, NumInstructions, Time,
 };
 use std::{
     convert::{From, TryInto},
@@ -169,11 +172,18 @@ fn test_canister_init_support() {
     assert_api_not_supported(api.ic0_call_new(0, 0, 0, 0, 0, 0, 0, 0, &[]));
     assert_api_not_supported(api.ic0_call_data_append(0, 0, &[]));
     assert_api_not_supported(api.ic0_call_on_cleanup(0, 0));
+    assert_api_not_supported(api.ic0_call_with_best_effort_response(0));
     assert_api_not_supported(api.ic0_call_cycles_add(0));
     assert_api_not_supported(api.ic0_call_cycles_add128(Cycles::new(0)));
     assert_api_not_supported(api.ic0_call_perform());
     assert_api_supported(api.ic0_time());
     assert_api_supported(api.ic0_canister_version());
+    assert_api_supported(api.ic0_is_controller(0, 0, &[]));
+    assert_api_supported(api.ic0_in_replicated_execution());
+    assert_api_supported(api.ic0_cost_call(0, 0, 0, &mut [0; 16]));
+    assert_api_supported(api.ic0_cost_create_canister(0, &mut [0; 16]));
+    assert_api_supported(api.ic0_cost_http_request(0, 0, 0, &mut [0; 16]));
+    assert_api_supported(api.ic0_cost_sign_with_ecdsa(0, 0, 0, 0, &mut [0; 16]));
     assert_api_supported(api.ic0_global_timer_set(time::UNIX_EPOCH));
     assert_api_supported(
         api.ic0_performance_counter(PerformanceCounterType::Instructions(0.into())),
@@ -226,11 +236,18 @@ fn test_canister_update_support() {
     assert_api_supported(api.ic0_call_new(0, 0, 0, 0, 0, 0, 0, 0, &[]));
     assert_api_supported(api.ic0_call_data_append(0, 0, &[]));
     assert_api_supported(api.ic0_call_on_cleanup(0, 0));
+    assert_api_supported(api.ic0_call_with_best_effort_response(0));
     assert_api_supported(api.ic0_call_cycles_add(0));
     assert_api_supported(api.ic0_call_cycles_add128(Cycles::new(0)));
     assert_api_supported(api.ic0_call_perform());
     assert_api_supported(api.ic0_time());
     assert_api_supported(api.ic0_canister_version());
+    assert_api_supported(api.ic0_is_controller(0, 0, &[]));
+    assert_api_supported(api.ic0_in_replicated_execution());
+    assert_api_supported(api.ic0_cost_call(0, 0, 0, &mut [0; 16]));
+    assert_api_supported(api.ic0_cost_create_canister(0, &mut [0; 16]));
+    assert_api_supported(api.ic0_cost_http_request(0, 0, 0, &mut [0; 16]));
+    assert_api_supported(api.ic0_cost_sign_with_ecdsa(0, 0, 0, 0, &mut [0; 16]));
     assert_api_supported(api.ic0_global_timer_set(time::UNIX_EPOCH));
     assert_api_supported(
         api.ic0_performance_counter(PerformanceCounterType::Instructions(0.into())),
@@ -283,11 +300,18 @@ fn test_canister_replicated_query_support() {
     assert_api_not_supported(api.ic0_call_new(0, 0, 0, 0, 0, 0, 0, 0, &[]));
     assert_api_not_supported(api.ic0_call_data_append(0, 0, &[]));
     assert_api_not_supported(api.ic0_call_on_cleanup(0, 0));
+    assert_api_not_supported(api.ic0_call_with_best_effort_response(0));
     assert_api_not_supported(api.ic0_call_cycles_add(0));
     assert_api_not_supported(api.ic0_call_cycles_add128(Cycles::new(0)));
     assert_api_not_supported(api.ic0_call_perform());
     assert_api_supported(api.ic0_time());
     assert_api_supported(api.ic0_canister_version());
+    assert_api_supported(api.ic0_is_controller(0, 0, &[]));
+    assert_api_supported(api.ic0_in_replicated_execution());
+    assert_api_supported(api.ic0_cost_call(0, 0, 0, &mut [0; 16]));
+    assert_api_supported(api.ic0_cost_create_canister(0, &mut [0; 16]));
+    assert_api_supported(api.ic0_cost_http_request(0, 0, 0, &mut [0; 16]));
+    assert_api_supported(api.ic0_cost_sign_with_ecdsa(0, 0, 0, 0, &mut [0; 16]));
     assert_api_not_supported(api.ic0_global_timer_set(time::UNIX_EPOCH));
     assert_api_supported(
         api.ic0_performance_counter(PerformanceCounterType::Instructions(0.into())),
@@ -340,11 +364,18 @@ fn test_canister_pure_query_support() {
     assert_api_not_supported(api.ic0_call_new(0, 0, 0, 0, 0, 0, 0, 0, &[]));
     assert_api_not_supported(api.ic0_call_data_append(0, 0, &[]));
     assert_api_not_supported(api.ic0_call_on_cleanup(0, 0));
+    assert_api_not_supported(api.ic0_call_with_best_effort_response(0));
     assert_api_not_supported(api.ic0_call_cycles_add(0));
     assert_api_not_supported(api.ic0_call_cycles_add128(Cycles::new(0)));
     assert_api_not_supported(api.ic0_call_perform());
     assert_api_supported(api.ic0_time());
     assert_api_supported(api.ic0_canister_version());
+    assert_api_supported(api.ic0_is_controller(0, 0, &[]));
+    assert_api_supported(api.ic0_in_replicated_execution());
+    assert_api_supported(api.ic0_cost_call(0, 0, 0, &mut [0; 16]));
+    assert_api_supported(api.ic0_cost_create_canister(0, &mut [0; 16]));
+    assert_api_supported(api.ic0_cost_http_request(0, 0, 0, &mut [0; 16]));
+    assert_api_supported(api.ic0_cost_sign_with_ecdsa(0, 0, 0, 0, &mut [0; 16]));
     assert_api_not_supported(api.ic0_global_timer_set(time::UNIX_EPOCH));
     assert_api_supported(
         api.ic0_performance_counter(PerformanceCounterType::Instructions(0.into())),
@@ -407,11 +438,18 @@ fn test_canister_stateful_query_support() {
     assert_api_supported(api.ic0_call_new(0, 0, 0, 0, 0, 0, 0, 0, &[]));
     assert_api_supported(api.ic0_call_data_append(0, 0, &[]));
     assert_api_supported(api.ic0_call_on_cleanup(0, 0));
+    assert_api_supported(api.ic0_call_with_best_effort_response(0));
     assert_api_not_supported(api.ic0_call_cycles_add(0));
     assert_api_not_supported(api.ic0_call_cycles_add128(Cycles::new(0)));
     assert_api_supported(api.ic0_call_perform());
     assert_api_supported(api.ic0_time());
     assert_api_supported(api.ic0_canister_version());
+    assert_api_supported(api.ic0_is_controller(0, 0, &[]));
+    assert_api_supported(api.ic0_in_replicated_execution());
+    assert_api_supported(api.ic0_cost_call(0, 0, 0, &mut [0; 16]));
+    assert_api_supported(api.ic0_cost_create_canister(0, &mut [0; 16]));
+    assert_api_supported(api.ic0_cost_http_request(0, 0, 0, &mut [0; 16]));
+    assert_api_supported(api.ic0_cost_sign_with_ecdsa(0, 0, 0, 0, &mut [0; 16]));
     assert_api_not_supported(api.ic0_global_timer_set(time::UNIX_EPOCH));
     assert_api_supported(
         api.ic0_performance_counter(PerformanceCounterType::Instructions(0.into())),
@@ -464,11 +502,18 @@ fn test_reply_api_support_on_nns() {
     assert_api_supported(api.ic0_call_new(0, 0, 0, 0, 0, 0, 0, 0, &[]));
     assert_api_supported(api.ic0_call_data_append(0, 0, &[]));
     assert_api_supported(api.ic0_call_on_cleanup(0, 0));
+    assert_api_supported(api.ic0_call_with_best_effort_response(0));
     assert_api_supported(api.ic0_call_cycles_add(0));
     assert_api_supported(api.ic0_call_cycles_add128(Cycles::new(0)));
     assert_api_supported(api.ic0_call_perform());
     assert_api_supported(api.ic0_time());
     assert_api_supported(api.ic0_canister_version());
+    assert_api_supported(api.ic0_is_controller(0, 0, &[]));
+    assert_api_supported(api.ic0_in_replicated_execution());
+    assert_api_supported(api.ic0_cost_call(0, 0, 0, &mut [0; 16]));
+    assert_api_supported(api.ic0_cost_create_canister(0, &mut [0; 16]));
+    assert_api_supported(api.ic0_cost_http_request(0, 0, 0, &mut [0; 16]));
+    assert_api_supported(api.ic0_cost_sign_with_ecdsa(0, 0, 0, 0, &mut [0; 16]));
     assert_api_supported(api.ic0_global_timer_set(time::UNIX_EPOCH));
     assert_api_supported(
         api.ic0_performance_counter(PerformanceCounterType::Instructions(0.into())),
@@ -521,11 +566,18 @@ fn test_reply_api_support_non_nns() {
     assert_api_supported(api.ic0_call_new(0, 0, 0, 0, 0, 0, 0, 0, &[]));
     assert_api_supported(api.ic0_call_data_append(0, 0, &[]));
     assert_api_supported(api.ic0_call_on_cleanup(0, 0));
+    assert_api_supported(api.ic0_call_with_best_effort_response(0));
     assert_api_supported(api.ic0_call_cycles_add(0));
     assert_api_supported(api.ic0_call_cycles_add128(Cycles::new(0)));
     assert_api_supported(api.ic0_call_perform());
     assert_api_supported(api.ic0_time());
     assert_api_supported(api.ic0_canister_version());
+    assert_api_supported(api.ic0_is_controller(0, 0, &[]));
+    assert_api_supported(api.ic0_in_replicated_execution());
+    assert_api_supported(api.ic0_cost_call(0, 0, 0, &mut [0; 16]));
+    assert_api_supported(api.ic0_cost_create_canister(0, &mut [0; 16]));
+    assert_api_supported(api.ic0_cost_http_request(0, 0, 0, &mut [0; 16]));
+    assert_api_supported(api.ic0_cost_sign_with_ecdsa(0, 0, 0, 0, &mut [0; 16]));
     assert_api_supported(api.ic0_global_timer_set(time::UNIX_EPOCH));
     assert_api_supported(
         api.ic0_performance_counter(PerformanceCounterType::Instructions(0.into())),
@@ -581,11 +633,18 @@ fn test_reject_api_support_on_nns() {
     assert_api_supported(api.ic0_call_new(0, 0, 0, 0, 0, 0, 0, 0, &[]));
     assert_api_supported(api.ic0_call_data_append(0, 0, &[]));
     assert_api_supported(api.ic0_call_on_cleanup(0, 0));
+    assert_api_supported(api.ic0_call_with_best_effort_response(0));
     assert_api_supported(api.ic0_call_cycles_add(0));
     assert_api_supported(api.ic0_call_cycles_add128(Cycles::new(0)));
     assert_api_supported(api.ic0_call_perform());
     assert_api_supported(api.ic0_time());
     assert_api_supported(api.ic0_canister_version());
+    assert_api_supported(api.ic0_is_controller(0, 0, &[]));
+    assert_api_supported(api.ic0_in_replicated_execution());
+    assert_api_supported(api.ic0_cost_call(0, 0, 0, &mut [0; 16]));
+    assert_api_supported(api.ic0_cost_create_canister(0, &mut [0; 16]));
+    assert_api_supported(api.ic0_cost_http_request(0, 0, 0, &mut [0; 16]));
+    assert_api_supported(api.ic0_cost_sign_with_ecdsa(0, 0, 0, 0, &mut [0; 16]));
     assert_api_supported(api.ic0_global_timer_set(time::UNIX_EPOCH));
     assert_api_supported(
         api.ic0_performance_counter(PerformanceCounterType::Instructions(0.into())),
@@ -641,11 +700,18 @@ fn test_reject_api_support_non_nns() {
     assert_api_supported(api.ic0_call_new(0, 0, 0, 0, 0, 0, 0, 0, &[]));
     assert_api_supported(api.ic0_call_data_append(0, 0, &[]));
     assert_api_supported(api.ic0_call_on_cleanup(0, 0));
+    assert_api_supported(api.ic0_call_with_best_effort_response(0));
     assert_api_supported(api.ic0_call_cycles_add(0));
     assert_api_supported(api.ic0_call_cycles_add128(Cycles::new(0)));
     assert_api_supported(api.ic0_call_perform());
     assert_api_supported(api.ic0_time());
     assert_api_supported(api.ic0_canister_version());
+    assert_api_supported(api.ic0_is_controller(0, 0, &[]));
+    assert_api_supported(api.ic0_in_replicated_execution());
+    assert_api_supported(api.ic0_cost_call(0, 0, 0, &mut [0; 16]));
+    assert_api_supported(api.ic0_cost_create_canister(0, &mut [0; 16]));
+    assert_api_supported(api.ic0_cost_http_request(0, 0, 0, &mut [0; 16]));
+    assert_api_supported(api.ic0_cost_sign_with_ecdsa(0, 0, 0, 0, &mut [0; 16]));
     assert_api_supported(api.ic0_global_timer_set(time::UNIX_EPOCH));
     assert_api_supported(
         api.ic0_performance_counter(PerformanceCounterType::Instructions(0.into())),
@@ -698,11 +764,18 @@ fn test_pre_upgrade_support() {
     assert_api_not_supported(api.ic0_call_new(0, 0, 0, 0, 0, 0, 0, 0, &[]));
     assert_api_not_supported(api.ic0_call_data_append(0, 0, &[]));
     assert_api_not_supported(api.ic0_call_on_cleanup(0, 0));
+    assert_api_not_supported(api.ic0_call_with_best_effort_response(0));
     assert_api_not_supported(api.ic0_call_cycles_add(0));
     assert_api_not_supported(api.ic0_call_cycles_add128(Cycles::new(0)));
     assert_api_not_supported(api.ic0_call_perform());
     assert_api_supported(api.ic0_time());
     assert_api_supported(api.ic0_canister_version());
+    assert_api_supported(api.ic0_is_controller(0, 0, &[]));
+    assert_api_supported(api.ic0_in_replicated_execution());
+    assert_api_supported(api.ic0_cost_call(0, 0, 0, &mut [0; 16]));
+    assert_api_supported(api.ic0_cost_create_canister(0, &mut [0; 16]));
+    assert_api_supported(api.ic0_cost_http_request(0, 0, 0, &mut [0; 16]));
+    assert_api_supported(api.ic0_cost_sign_with_ecdsa(0, 0, 0, 0, &mut [0; 16]));
     assert_api_supported(api.ic0_global_timer_set(time::UNIX_EPOCH));
     assert_api_supported(
         api.ic0_performance_counter(PerformanceCounterType::Instructions(0.into())),
@@ -755,11 +828,18 @@ fn test_start_support() {
     assert_api_not_supported(api.ic0_call_new(0, 0, 0, 0, 0, 0, 0, 0, &[]));
     assert_api_not_supported(api.ic0_call_data_append(0, 0, &[]));
     assert_api_not_supported(api.ic0_call_on_cleanup(0, 0));
+    assert_api_not_supported(api.ic0_call_with_best_effort_response(0));
     assert_api_not_supported(api.ic0_call_cycles_add(0));
     assert_api_not_supported(api.ic0_call_cycles_add128(Cycles::new(0)));
     assert_api_not_supported(api.ic0_call_perform());
     assert_api_not_supported(api.ic0_time());
     assert_api_not_supported(api.ic0_canister_version());
+    assert_api_not_supported(api.ic0_is_controller(0, 0, &[]));
+    assert_api_supported(api.ic0_in_replicated_execution());
+    assert_api_not_supported(api.ic0_cost_call(0, 0, 0, &mut [0; 16]));
+    assert_api_not_supported(api.ic0_cost_create_canister(0, &mut [0; 16]));
+    assert_api_not_supported(api.ic0_cost_http_request(0, 0, 0, &mut [0; 16]));
+    assert_api_not_supported(api.ic0_cost_sign_with_ecdsa(0, 0, 0, 0, &mut [0; 16]));
     assert_api_not_supported(api.ic0_global_timer_set(time::UNIX_EPOCH));
     assert_api_supported(
         api.ic0_performance_counter(PerformanceCounterType::Instructions(0.into())),
@@ -812,11 +892,18 @@ fn test_cleanup_support() {
     assert_api_not_supported(api.ic0_call_new(0, 0, 0, 0, 0, 0, 0, 0, &[]));
     assert_api_not_supported(api.ic0_call_data_append(0, 0, &[]));
     assert_api_not_supported(api.ic0_call_on_cleanup(0, 0));
+    assert_api_not_supported(api.ic0_call_with_best_effort_response(0));
     assert_api_not_supported(api.ic0_call_cycles_add(0));
     assert_api_not_supported(api.ic0_call_cycles_add128(Cycles::new(0)));
     assert_api_not_supported(api.ic0_call_perform());
     assert_api_supported(api.ic0_time());
     assert_api_supported(api.ic0_canister_version());
+    assert_api_supported(api.ic0_is_controller(0, 0, &[]));
+    assert_api_supported(api.ic0_in_replicated_execution());
+    assert_api_supported(api.ic0_cost_call(0, 0, 0, &mut [0; 16]));
+    assert_api_supported(api.ic0_cost_create_canister(0, &mut [0; 16]));
+    assert_api_supported(api.ic0_cost_http_request(0, 0, 0, &mut [0; 16]));
+    assert_api_supported(api.ic0_cost_sign_with_ecdsa(0, 0, 0, 0, &mut [0; 16]));
     assert_api_supported(api.ic0_global_timer_set(time::UNIX_EPOCH));
     assert_api_supported(
         api.ic0_performance_counter(PerformanceCounterType::Instructions(0.into())),
@@ -874,11 +961,18 @@ fn test_inspect_message_support() {
     assert_api_not_supported(api.ic0_call_new(0, 0, 0, 0, 0, 0, 0, 0, &[]));
     assert_api_not_supported(api.ic0_call_data_append(0, 0, &[]));
     assert_api_not_supported(api.ic0_call_on_cleanup(0, 0));
+    assert_api_not_supported(api.ic0_call_with_best_effort_response(0));
     assert_api_not_supported(api.ic0_call_cycles_add(0));
     assert_api_not_supported(api.ic0_call_cycles_add128(Cycles::new(0)));
     assert_api_not_supported(api.ic0_call_perform());
     assert_api_supported(api.ic0_time());
     assert_api_supported(api.ic0_canister_version());
+    assert_api_supported(api.ic0_is_controller(0, 0, &[]));
+    assert_api_supported(api.ic0_in_replicated_execution());
+    assert_api_supported(api.ic0_cost_call(0, 0, 0, &mut [0; 16]));
+    assert_api_supported(api.ic0_cost_create_canister(0, &mut [0; 16]));
+    assert_api_supported(api.ic0_cost_http_request(0, 0, 0, &mut [0; 16]));
+    assert_api_supported(api.ic0_cost_sign_with_ecdsa(0, 0, 0, 0, &mut [0; 16]));
     assert_api_not_supported(api.ic0_global_timer_set(time::UNIX_EPOCH));
     assert_api_supported(
         api.ic0_performance_counter(PerformanceCounterType::Instructions(0.into())),
@@ -932,11 +1026,18 @@ fn test_canister_heartbeat_support() {
     assert_api_supported(api.ic0_call_new(0, 0, 0, 0, 0, 0, 0, 0, &[]));
     assert_api_supported(api.ic0_call_data_append(0, 0, &[]));
     assert_api_supported(api.ic0_call_on_cleanup(0, 0));
+    assert_api_supported(api.ic0_call_with_best_effort_response(0));
     assert_api_supported(api.ic0_call_cycles_add(0));
     assert_api_supported(api.ic0_call_cycles_add128(Cycles::new(0)));
     assert_api_supported(api.ic0_call_perform());
     assert_api_supported(api.ic0_time());
     assert_api_supported(api.ic0_canister_version());
+    assert_api_supported(api.ic0_is_controller(0, 0, &[]));
+    assert_api_supported(api.ic0_in_replicated_execution());
+    assert_api_supported(api.ic0_cost_call(0, 0, 0, &mut [0; 16]));
+    assert_api_supported(api.ic0_cost_create_canister(0, &mut [0; 16]));
+    assert_api_supported(api.ic0_cost_http_request(0, 0, 0, &mut [0; 16]));
+    assert_api_supported(api.ic0_cost_sign_with_ecdsa(0, 0, 0, 0, &mut [0; 16]));
     assert_api_supported(api.ic0_global_timer_set(time::UNIX_EPOCH));
     assert_api_supported(
         api.ic0_performance_counter(PerformanceCounterType::Instructions(0.into())),
@@ -989,11 +1090,18 @@ fn test_canister_heartbeat_support_nns() {
     assert_api_supported(api.ic0_call_new(0, 0, 0, 0, 0, 0, 0, 0, &[]));
     assert_api_supported(api.ic0_call_data_append(0, 0, &[]));
     assert_api_supported(api.ic0_call_on_cleanup(0, 0));
+    assert_api_supported(api.ic0_call_with_best_effort_response(0));
     assert_api_supported(api.ic0_call_cycles_add(0));
     assert_api_supported(api.ic0_call_cycles_add128(Cycles::new(0)));
     assert_api_supported(api.ic0_call_perform());
     assert_api_supported(api.ic0_time());
     assert_api_supported(api.ic0_canister_version());
+    assert_api_supported(api.ic0_is_controller(0, 0, &[]));
+    assert_api_supported(api.ic0_in_replicated_execution());
+    assert_api_supported(api.ic0_cost_call(0, 0, 0, &mut [0; 16]));
+    assert_api_supported(api.ic0_cost_create_canister(0, &mut [0; 16]));
+    assert_api_supported(api.ic0_cost_http_request(0, 0, 0, &mut [0; 16]));
+    assert_api_supported(api.ic0_cost_sign_with_ecdsa(0, 0, 0, 0, &mut [0; 16]));
     assert_api_supported(api.ic0_global_timer_set(time::UNIX_EPOCH));
     assert_api_supported(
         api.ic0_performance_counter(PerformanceCounterType::Instructions(0.into())),
@@ -1162,7 +1270,7 @@ fn test_canister_balance() {
         .call_context_manager_mut()
         .unwrap()
         .new_call_context(
-            CallOrigin::CanisterUpdate(canister_test_id(33), CallbackId::from(5)),
+            CallOrigin::CanisterUpdate(canister_test_id(33), CallbackId::from(5), NO_DEADLINE),
             Cycles::new(50),
             Time::from_nanos_since_unix_epoch(0),
         );
@@ -1190,7 +1298,7 @@ fn test_canister_cycle_balance() {
         .call_context_manager_mut()
         .unwrap()
         .new_call_context(
-            CallOrigin::CanisterUpdate(canister_test_id(33), CallbackId::from(5)),
+            CallOrigin::CanisterUpdate(canister_test_id(33), CallbackId::from(5), NO_DEADLINE),
             Cycles::new(50),
             Time::from_nanos_since_unix_epoch(0),
         );
@@ -1224,7 +1332,7 @@ fn test_msg_cycles_available_traps() {
         .call_context_manager_mut()
         .unwrap()
         .new_call_context(
-            CallOrigin::CanisterUpdate(canister_test_id(33), CallbackId::from(5)),
+            CallOrigin::CanisterUpdate(canister_test_id(33), CallbackId::from(5), NO_DEADLINE),
             available_cycles,
             Time::from_nanos_since_unix_epoch(0),
         );
@@ -1385,7 +1493,7 @@ fn msg_cycles_accept_all_cycles_in_call_context() {
         .call_context_manager_mut()
         .unwrap()
         .new_call_context(
-            CallOrigin::CanisterUpdate(canister_test_id(33), CallbackId::from(5)),
+            CallOrigin::CanisterUpdate(canister_test_id(33), CallbackId::from(5), NO_DEADLINE),
             Cycles::from(amount),
             Time::from_nanos_since_unix_epoch(0),
         );
@@ -1408,7 +1516,7 @@ fn msg_cycles_accept_all_cycles_in_call_context_when_more_asked() {
         .call_context_manager_mut()
         .unwrap()
         .new_call_context(
-            CallOrigin::CanisterUpdate(canister_test_id(33), CallbackId::from(5)),
+            CallOrigin::CanisterUpdate(canister_test_id(33), CallbackId::from(5), NO_DEADLINE),
             Cycles::new(40),
             Time::from_nanos_since_unix_epoch(0),
         );
@@ -1439,7 +1547,7 @@ fn call_perform_not_enough_cycles_resets_state() {
         .call_context_manager_mut()
         .unwrap()
         .new_call_context(
-            CallOrigin::CanisterUpdate(canister_test_id(33), CallbackId::from(5)),
+            CallOrigin::CanisterUpdate(canister_test_id(33), CallbackId::from(5), NO_DEADLINE),
             Cycles::new(40),
             Time::from_nanos_since_unix_epoch(0),
         );
@@ -1468,6 +1576,60 @@ fn call_perform_not_enough_cycles_resets_state() {
     assert_eq!(call_context_manager.callbacks().len(), 0);
 }
 
+#[test]
+fn call_with_best_effort_response_sets_deadline() {
+    let cycles_account_manager = CyclesAccountManagerBuilder::new().build();
+    let mut system_state = get_system_state_with_cycles(Cycles::from(1_000_000_000_000u128));
+    system_state
+        .call_context_manager_mut()
+        .unwrap()
+        .new_call_context(
+            CallOrigin::CanisterUpdate(canister_test_id(33), CallbackId::from(5), NO_DEADLINE),
+            Cycles::new(40),
+            Time::from_nanos_since_unix_epoch(0),
+        );
+    let mut api = get_system_api(
+        ApiTypeBuilder::build_update_api(),
+        &system_state,
+        cycles_account_manager,
+    );
+
+    api.ic0_call_new(0, 10, 0, 10, 0, 0, 0, 0, &[0; 1024])
+        .unwrap();
+    // The timeout is capped at `MAX_CALL_TIMEOUT_SECONDS`.
+    api.ic0_call_with_best_effort_response(MAX_CALL_TIMEOUT_SECONDS + 1)
+        .unwrap();
+    // Can only be called once per call.
+    assert_matches!(
+        api.ic0_call_with_best_effort_response(10),
+        Err(HypervisorError::ContractViolation(_))
+    );
+    assert_eq!(api.ic0_call_perform().unwrap(), 0);
+
+    let system_state_changes = api.into_system_state_changes();
+    system_state_changes
+        .apply_changes(
+            mock_time(),
+            &mut system_state,
+            &default_network_topology(),
+            subnet_test_id(1),
+            &no_op_logger(),
+        )
+        .unwrap();
+
+    // `mock_time()` is the UNIX epoch, so the deadline is just the timeout.
+    let expected_deadline = CoarseTime::from_secs_since_unix_epoch(MAX_CALL_TIMEOUT_SECONDS);
+    let call_context_manager = system_state.call_context_manager().unwrap();
+    assert_eq!(call_context_manager.callbacks().len(), 1);
+    let callback = call_context_manager.callbacks().values().next().unwrap();
+    assert!(callback.is_best_effort());
+    assert_eq!(expected_deadline, callback.deadline);
+
+    let canister_id = system_state.canister_id();
+    let (_, msg) = system_state.output_into_iter(canister_id).next().unwrap();
+    assert_eq!(expected_deadline, msg.deadline());
+}
+
 #[test]
 fn update_available_memory_updates_subnet_available_memory() {
     let wasm_page_size = 64 << 10;
@@ -1524,6 +1686,7 @@ fn take_execution_result_properly_frees_memory() {
             WasmClosure::new(0, 0),
             WasmClosure::new(0, 0),
             None,
+            NO_DEADLINE,
         ))
         .unwrap();
     let mut api = SystemApiImpl::new(
@@ -1588,6 +1751,7 @@ fn push_output_request_respects_memory_limits() {
                 WasmClosure::new(0, 0),
                 WasmClosure::new(0, 0),
                 None,
+                NO_DEADLINE,
             ))
             .unwrap();
         let mut api = SystemApiImpl::new(
@@ -1691,6 +1855,7 @@ fn push_output_request_oversized_request_memory_limits() {
             WasmClosure::new(0, 0),
             WasmClosure::new(0, 0),
             None,
+            NO_DEADLINE,
         ))
         .unwrap();
     let mut api = SystemApiImpl::new(
@@ -1800,3 +1965,164 @@ fn ic0_global_timer_set_is_propagated_from_sandbox() {
         CanisterTimer::Active(Time::from_nanos_since_unix_epoch(2))
     );
 }
+
+#[test]
+fn msg_reply_data_append_rejects_overflowing_payload_size() {
+    let cycles_account_manager = CyclesAccountManagerBuilder::new()
+        .with_subnet_type(SubnetType::System)
+        .build();
+    let api_type = ApiTypeBuilder::build_update_api();
+    let mut api = get_system_api(api_type, &get_cmc_system_state(), cycles_account_manager);
+
+    api.ic0_msg_reply_data_append(0, 1, &[1]).unwrap();
+    let err = api
+        .ic0_msg_reply_data_append(0, usize::MAX, &[1])
+        .unwrap_err();
+    assert!(
+        matches!(err, HypervisorError::ContractViolation(_)),
+        "unexpected error: {:?}",
+        err
+    );
+}
+
+#[test]
+fn ic0_is_controller_checks_controllers() {
+    let system_state = get_system_state_with_cycles(INITIAL_CYCLES);
+    let api = get_system_api(
+        ApiTypeBuilder::build_update_api(),
+        &system_state,
+        CyclesAccountManagerBuilder::new().build(),
+    );
+
+    let controller = user_test_id(24).get();
+    let heap = controller.as_slice();
+    assert_eq!(api.ic0_is_controller(0, heap.len(), heap), Ok(1));
+
+    let not_controller = user_test_id(25).get();
+    let heap = not_controller.as_slice();
+    assert_eq!(api.ic0_is_controller(0, heap.len(), heap), Ok(0));
+
+    // Principals are at most 29 bytes long.
+    let heap = [0; 30];
+    assert!(matches!(
+        api.ic0_is_controller(0, heap.len(), &heap),
+        Err(HypervisorError::InvalidPrincipalId(_))
+    ));
+}
+
+#[test]
+fn ic0_in_replicated_execution_depends_on_execution_mode() {
+    let system_state = SystemStateBuilder::default().build();
+    let api = get_system_api(
+        ApiTypeBuilder::build_update_api(),
+        &system_state,
+        CyclesAccountManagerBuilder::new().build(),
+    );
+    assert_eq!(api.ic0_in_replicated_execution(), Ok(1));
+
+    let sandbox_safe_system_state = SandboxSafeSystemState::new(
+        &system_state,
+        CyclesAccountManagerBuilder::new().build(),
+        &NetworkTopology::default(),
+        SchedulerConfig::application_subnet().dirty_page_overhead,
+    );
+    let api = SystemApiImpl::new(
+        ApiType::non_replicated_query(
+            mock_time(),
+            user_test_id(1).get(),
+            subnet_test_id(1),
+            vec![],
+            None,
+            NonReplicatedQueryKind::Pure,
+        ),
+        sandbox_safe_system_state,
+        CANISTER_CURRENT_MEMORY_USAGE,
+        ExecutionParameters {
+            execution_mode: ExecutionMode::NonReplicated,
+            ..execution_parameters()
+        },
+        SubnetAvailableMemory::new(i64::MAX / 2, i64::MAX / 2),
+        default_memory_for_system_api(),
+        Arc::new(DefaultOutOfInstructionsHandler {}),
+        no_op_logger(),
+    );
+    assert_eq!(api.ic0_in_replicated_execution(), Ok(0));
+}
+
+#[test]
+fn ic0_cost_apis_match_cycles_account_manager_fees() {
+    let cycles_account_manager = CyclesAccountManagerBuilder::new().build();
+    let key_id = EcdsaKeyId {
+        curve: EcdsaCurve::Secp256k1,
+        name: "key".to_string(),
+    };
+    let signing_subnet_id = subnet_test_id(42);
+    let signing_subnet_size = 34;
+    let mut network_topology = NetworkTopology::default();
+    network_topology.subnets.insert(
+        signing_subnet_id,
+        SubnetTopology {
+            nodes: (0..signing_subnet_size as u64)
+                .map(|i| (node_test_id(i), NodeTopology::default()))
+                .collect(),
+            ..SubnetTopology::default()
+        },
+    );
+    network_topology
+        .ecdsa_signing_subnets
+        .insert(key_id.clone(), vec![signing_subnet_id]);
+    let sandbox_safe_system_state = SandboxSafeSystemState::new(
+        &SystemStateBuilder::default().build(),
+        cycles_account_manager.clone(),
+        &network_topology,
+        SchedulerConfig::application_subnet().dirty_page_overhead,
+    );
+    let api = SystemApiImpl::new(
+        ApiTypeBuilder::build_update_api(),
+        sandbox_safe_system_state,
+        CANISTER_CURRENT_MEMORY_USAGE,
+        execution_parameters(),
+        SubnetAvailableMemory::new(i64::MAX / 2, i64::MAX / 2),
+        default_memory_for_system_api(),
+        Arc::new(DefaultOutOfInstructionsHandler {}),
+        no_op_logger(),
+    );
+    // The own subnet is not part of the topology.
+    let subnet_size = SMALL_APP_SUBNET_MAX_SIZE;
+    let mut heap = vec![0; 16];
+    let cycles_from_heap = |heap: &[u8]| Cycles::new(u128::from_le_bytes(heap.try_into().unwrap()));
+
+    api.ic0_cost_call(6, 1000, 0, &mut heap).unwrap();
+    assert_eq!(
+        cycles_from_heap(&heap),
+        cycles_account_manager.xnet_call_total_fee(NumBytes::from(1006), subnet_size)
+    );
+
+    api.ic0_cost_create_canister(0, &mut heap).unwrap();
+    assert_eq!(
+        cycles_from_heap(&heap),
+        cycles_account_manager.canister_creation_fee(subnet_size)
+    );
+
+    api.ic0_cost_http_request(100, 2000, 0, &mut heap).unwrap();
+    assert_eq!(
+        cycles_from_heap(&heap),
+        cycles_account_manager.http_request_fee(
+            NumBytes::from(100),
+            Some(NumBytes::from(2000)),
+            subnet_size
+        )
+    );
+
+    // The key name is stored after the 16 bytes for the cost.
+    let mut heap = [vec![0; 16], b"key".to_vec()].concat();
+    assert_eq!(api.ic0_cost_sign_with_ecdsa(16, 3, 0, 0, &mut heap), Ok(0));
+    assert_eq!(
+        cycles_from_heap(&heap[0..16]),
+        cycles_account_manager.ecdsa_signature_fee(signing_subnet_size)
+    );
+    // Unknown curve.
+    assert_eq!(api.ic0_cost_sign_with_ecdsa(16, 3, 1, 0, &mut heap), Ok(1));
+    // Unknown key name.
+    assert_eq!(api.ic0_cost_sign_with_ecdsa(16, 2, 0, 0, &mut heap), Ok(2));
+}
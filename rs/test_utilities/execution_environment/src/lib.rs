@@ -23,9 +23,11 @@ use ic_execution_environment::{
     InternalHttpQueryHandler, RoundInstructions, RoundLimits,
 };
 use ic_ic00_types::{
-    CanisterIdRecord, CanisterInstallMode, CanisterSettingsArgs, CanisterStatusType, EcdsaKeyId,
-    EmptyBlob, InstallCodeArgs, Method, Payload, ProvisionalCreateCanisterWithCyclesArgs,
-    UpdateSettingsArgs,
+    CanisterIdRecord, CanisterInstallMode, CanisterSettingsArgs, CanisterStatusType,
+    DeleteCanisterSnapshotArgs, EcdsaKeyId, EmptyBlob, FetchCanisterLogsRequest,
+    InstallChunkedCodeArgs, InstallCodeArgs, LoadCanisterSnapshotArgs, LogVisibility, Method,
+    Payload, ProvisionalCreateCanisterWithCyclesArgs, SchnorrKeyId, TakeCanisterSnapshotArgs,
+    UpdateSettingsArgs, UploadChunkArgs, VetKdKeyId,
 };
 use ic_interfaces::{
     execution_environment::{
@@ -103,6 +105,8 @@ pub fn generate_subnets(
                 subnet_type,
                 subnet_features: SubnetFeatures::default(),
                 ecdsa_keys_held: BTreeSet::new(),
+                schnorr_keys_held: BTreeSet::new(),
+                vetkd_keys_held: BTreeSet::new(),
             },
         );
     }
@@ -206,6 +210,7 @@ pub struct ExecutionTest {
     manual_execution: bool,
     caller_canister_id: Option<CanisterId>,
     ecdsa_subnet_public_keys: BTreeMap<EcdsaKeyId, MasterEcdsaPublicKey>,
+    schnorr_subnet_public_keys: BTreeMap<SchnorrKeyId, MasterEcdsaPublicKey>,
 
     // The actual implementation.
     exec_env: ExecutionEnvironment,
@@ -381,6 +386,18 @@ impl ExecutionTest {
         )
     }
 
+    pub fn non_replicated_http_request_fee(
+        &self,
+        request_size: NumBytes,
+        response_size_limit: Option<NumBytes>,
+    ) -> Cycles {
+        self.cycles_account_manager.non_replicated_http_request_fee(
+            request_size,
+            response_size_limit,
+            self.subnet_size(),
+        )
+    }
+
     pub fn reduced_wasm_compilation_fee(&self, wasm: &[u8]) -> Cycles {
         let cost = wasm_compilation_cost(wasm);
         self.cycles_account_manager()
@@ -510,6 +527,23 @@ impl ExecutionTest {
         self.subnet_message(Method::UpdateSettings, payload)
     }
 
+    /// Updates the log visibility of the given canister.
+    pub fn canister_update_log_visibility(
+        &mut self,
+        canister_id: CanisterId,
+        log_visibility: LogVisibility,
+    ) -> Result<WasmResult, UserError> {
+        let mut settings = CanisterSettingsArgs::new(None, None, None, None);
+        settings.log_visibility = Some(log_visibility);
+        let payload = UpdateSettingsArgs {
+            canister_id: canister_id.into(),
+            settings,
+            sender_canister_version: None,
+        }
+        .encode();
+        self.subnet_message(Method::UpdateSettings, payload)
+    }
+
     /// Sends an `install_code` message to the IC management canister.
     /// Consider using higher-level helpers like `canister_from_wat()`.
     pub fn install_code(&mut self, args: InstallCodeArgs) -> Result<WasmResult, UserError> {
@@ -531,6 +565,75 @@ impl ExecutionTest {
         self.subnet_message(Method::UninstallCode, payload)
     }
 
+    /// Sends a `take_canister_snapshot` message to the IC management canister.
+    pub fn take_canister_snapshot(
+        &mut self,
+        canister_id: CanisterId,
+        replace_snapshot: Option<Vec<u8>>,
+    ) -> Result<WasmResult, UserError> {
+        let payload = TakeCanisterSnapshotArgs::new(canister_id, replace_snapshot).encode();
+        self.subnet_message(Method::TakeCanisterSnapshot, payload)
+    }
+
+    /// Sends a `load_canister_snapshot` message to the IC management canister.
+    pub fn load_canister_snapshot(
+        &mut self,
+        canister_id: CanisterId,
+        snapshot_id: Vec<u8>,
+    ) -> Result<WasmResult, UserError> {
+        let payload = LoadCanisterSnapshotArgs::new(canister_id, snapshot_id, None).encode();
+        self.subnet_message(Method::LoadCanisterSnapshot, payload)
+    }
+
+    /// Sends a `list_canister_snapshots` message to the IC management canister.
+    pub fn list_canister_snapshots(
+        &mut self,
+        canister_id: CanisterId,
+    ) -> Result<WasmResult, UserError> {
+        let payload = CanisterIdRecord::from(canister_id).encode();
+        self.subnet_message(Method::ListCanisterSnapshots, payload)
+    }
+
+    /// Sends a `delete_canister_snapshot` message to the IC management canister.
+    pub fn delete_canister_snapshot(
+        &mut self,
+        canister_id: CanisterId,
+        snapshot_id: Vec<u8>,
+    ) -> Result<WasmResult, UserError> {
+        let payload = DeleteCanisterSnapshotArgs::new(canister_id, snapshot_id).encode();
+        self.subnet_message(Method::DeleteCanisterSnapshot, payload)
+    }
+
+    /// Sends an `upload_chunk` message to the IC management canister.
+    pub fn upload_chunk(
+        &mut self,
+        canister_id: CanisterId,
+        chunk: Vec<u8>,
+    ) -> Result<WasmResult, UserError> {
+        let payload = UploadChunkArgs::new(canister_id, chunk).encode();
+        self.subnet_message(Method::UploadChunk, payload)
+    }
+
+    /// Sends a `clear_chunk_store` message to the IC management canister.
+    pub fn clear_chunk_store(&mut self, canister_id: CanisterId) -> Result<WasmResult, UserError> {
+        let payload = CanisterIdRecord::from(canister_id).encode();
+        self.subnet_message(Method::ClearChunkStore, payload)
+    }
+
+    /// Sends a `stored_chunks` message to the IC management canister.
+    pub fn stored_chunks(&mut self, canister_id: CanisterId) -> Result<WasmResult, UserError> {
+        let payload = CanisterIdRecord::from(canister_id).encode();
+        self.subnet_message(Method::StoredChunks, payload)
+    }
+
+    /// Sends an `install_chunked_code` message to the IC management canister.
+    pub fn install_chunked_code(
+        &mut self,
+        args: InstallChunkedCodeArgs,
+    ) -> Result<WasmResult, UserError> {
+        self.subnet_message(Method::InstallChunkedCode, args.encode())
+    }
+
     /// Starts running the given canister.
     /// Consider using higher-level helpers like `canister_from_wat()`.
     pub fn start_canister(&mut self, canister_id: CanisterId) -> Result<WasmResult, UserError> {
@@ -1005,6 +1108,7 @@ impl ExecutionTest {
             self.install_code_instruction_limits.clone(),
             &mut mock_random_number_generator(),
             &self.ecdsa_subnet_public_keys,
+            &self.schnorr_subnet_public_keys,
             &self.registry_settings,
             &mut round_limits,
         );
@@ -1316,6 +1420,24 @@ impl ExecutionTest {
         self.query_handler.query(query, state, data_certificate)
     }
 
+    /// Sends a `fetch_canister_logs` query to the IC management canister on
+    /// behalf of `sender`.
+    pub fn fetch_canister_logs(
+        &self,
+        sender: UserId,
+        canister_id: CanisterId,
+    ) -> Result<WasmResult, UserError> {
+        let query = UserQuery {
+            source: sender,
+            receiver: CanisterId::ic_00(),
+            method_name: Method::FetchCanisterLogs.to_string(),
+            method_payload: FetchCanisterLogsRequest::new(canister_id).encode(),
+            ingress_expiry: 0,
+            nonce: None,
+        };
+        self.query(query, Arc::new(self.state().clone()), vec![])
+    }
+
     /// Returns a reference to the query handler of this test.
     ///
     /// Note that the return type is `Any` so that the caller is forced to
@@ -1344,7 +1466,10 @@ pub struct ExecutionTestBuilder {
     log: ReplicaLogger,
     caller_canister_id: Option<CanisterId>,
     ecdsa_signature_fee: Option<Cycles>,
+    schnorr_signature_fee: Option<Cycles>,
     ecdsa_key: Option<EcdsaKeyId>,
+    schnorr_key: Option<SchnorrKeyId>,
+    vetkd_key: Option<VetKdKeyId>,
     instruction_limit: NumInstructions,
     slice_instruction_limit: NumInstructions,
     install_code_instruction_limit: NumInstructions,
@@ -1390,7 +1515,10 @@ impl Default for ExecutionTestBuilder {
             log: no_op_logger(),
             caller_canister_id: None,
             ecdsa_signature_fee: None,
+            schnorr_signature_fee: None,
             ecdsa_key: None,
+            schnorr_key: None,
+            vetkd_key: None,
             instruction_limit: scheduler_config.max_instructions_per_message,
             slice_instruction_limit: scheduler_config.max_instructions_per_slice,
             install_code_instruction_limit: scheduler_config.max_instructions_per_install_code,
@@ -1477,6 +1605,13 @@ impl ExecutionTestBuilder {
         }
     }
 
+    pub fn with_schnorr_signature_fee(self, schnorr_signing_fee: u128) -> Self {
+        Self {
+            schnorr_signature_fee: Some(Cycles::new(schnorr_signing_fee)),
+            ..self
+        }
+    }
+
     pub fn with_ecdsa_key(self, ecdsa_key: EcdsaKeyId) -> Self {
         Self {
             ecdsa_key: Some(ecdsa_key),
@@ -1484,6 +1619,20 @@ impl ExecutionTestBuilder {
         }
     }
 
+    pub fn with_schnorr_key(self, schnorr_key: SchnorrKeyId) -> Self {
+        Self {
+            schnorr_key: Some(schnorr_key),
+            ..self
+        }
+    }
+
+    pub fn with_vetkd_key(self, vetkd_key: VetKdKeyId) -> Self {
+        Self {
+            vetkd_key: Some(vetkd_key),
+            ..self
+        }
+    }
+
     pub fn with_instruction_limit(self, limit: u64) -> Self {
         Self {
             instruction_limit: NumInstructions::from(limit),
@@ -1685,6 +1834,9 @@ impl ExecutionTestBuilder {
         if let Some(ecdsa_signature_fee) = self.ecdsa_signature_fee {
             config.ecdsa_signature_fee = ecdsa_signature_fee;
         }
+        if let Some(schnorr_signature_fee) = self.schnorr_signature_fee {
+            config.schnorr_signature_fee = schnorr_signature_fee;
+        }
         if let Some(ecdsa_key) = &self.ecdsa_key {
             state
                 .metadata
@@ -1713,6 +1865,39 @@ impl ExecutionTestBuilder {
                 )
             })
             .collect();
+        if let Some(schnorr_key) = &self.schnorr_key {
+            state
+                .metadata
+                .network_topology
+                .subnets
+                .get_mut(&self.own_subnet_id)
+                .unwrap()
+                .schnorr_keys_held
+                .insert(schnorr_key.clone());
+        }
+        let schnorr_subnet_public_keys = self
+            .schnorr_key
+            .into_iter()
+            .map(|key| {
+                (
+                    key,
+                    MasterEcdsaPublicKey {
+                        algorithm_id: AlgorithmId::SchnorrSecp256k1,
+                        public_key: b"abababab".to_vec(),
+                    },
+                )
+            })
+            .collect();
+        if let Some(vetkd_key) = &self.vetkd_key {
+            state
+                .metadata
+                .network_topology
+                .subnets
+                .get_mut(&self.own_subnet_id)
+                .unwrap()
+                .vetkd_keys_held
+                .insert(vetkd_key.clone());
+        }
         let cycles_account_manager = Arc::new(CyclesAccountManager::new(
             self.instruction_limit,
             self.subnet_type,
@@ -1832,6 +2017,7 @@ impl ExecutionTestBuilder {
             ingress_history_writer,
             manual_execution: self.manual_execution,
             ecdsa_subnet_public_keys,
+            schnorr_subnet_public_keys,
             log: self.log,
         }
     }
@@ -13,7 +13,8 @@ use ic_interfaces::crypto::{
     CurrentNodePublicKeysError, IDkgDealingEncryptionKeyRotationError, IDkgProtocol,
     IdkgDealingEncPubKeysCountError, KeyManager, LoadTranscriptResult, NiDkgAlgorithm,
     PublicKeyRegistrationStatus, ThresholdEcdsaSigVerifier, ThresholdEcdsaSigner,
-    ThresholdSigVerifier, ThresholdSigVerifierByPublicKey, ThresholdSigner,
+    ThresholdSchnorrSigVerifier, ThresholdSchnorrSigner, ThresholdSigVerifier,
+    ThresholdSigVerifierByPublicKey, ThresholdSigner, VetKdProtocol,
 };
 use ic_interfaces::crypto::{MultiSigVerifier, MultiSigner};
 use ic_interfaces_registry::RegistryClient;
@@ -31,6 +32,10 @@ use ic_types::crypto::threshold_sig::ni_dkg::errors::verify_dealing_error::DkgVe
 use ic_types::crypto::threshold_sig::ni_dkg::{
     config::NiDkgConfig, DkgId, NiDkgDealing, NiDkgId, NiDkgTranscript,
 };
+use ic_types::crypto::vetkd::{
+    VetKdArgs, VetKdEncryptedKey, VetKdEncryptedKeyShare, VetKdKeyShareCombinationError,
+    VetKdKeyShareCreationError, VetKdKeyShareVerificationError, VetKdKeyVerificationError,
+};
 use ic_types::crypto::{
     AlgorithmId, BasicSig, BasicSigOf, CanisterSigOf, CombinedMultiSig, CombinedMultiSigOf,
     CombinedThresholdSig, CombinedThresholdSigOf, CryptoResult, CurrentNodePublicKeys,
@@ -649,6 +654,82 @@ impl ThresholdEcdsaSigVerifier for CryptoReturningOk {
     }
 }
 
+impl ThresholdSchnorrSigner for CryptoReturningOk {
+    fn create_schnorr_sig_share(
+        &self,
+        _inputs: &ThresholdSchnorrSigInputs,
+    ) -> Result<ThresholdSchnorrSigShare, ThresholdSchnorrSignShareError> {
+        Ok(ThresholdSchnorrSigShare {
+            sig_share_raw: vec![],
+        })
+    }
+}
+
+impl ThresholdSchnorrSigVerifier for CryptoReturningOk {
+    fn verify_schnorr_sig_share(
+        &self,
+        _signer: NodeId,
+        _inputs: &ThresholdSchnorrSigInputs,
+        _share: &ThresholdSchnorrSigShare,
+    ) -> Result<(), ThresholdSchnorrVerifySigShareError> {
+        Ok(())
+    }
+
+    fn combine_schnorr_sig_shares(
+        &self,
+        _inputs: &ThresholdSchnorrSigInputs,
+        _shares: &BTreeMap<NodeId, ThresholdSchnorrSigShare>,
+    ) -> Result<ThresholdSchnorrCombinedSignature, ThresholdSchnorrCombineSigSharesError> {
+        Ok(ThresholdSchnorrCombinedSignature { signature: vec![] })
+    }
+
+    fn verify_schnorr_combined_sig(
+        &self,
+        _inputs: &ThresholdSchnorrSigInputs,
+        _signature: &ThresholdSchnorrCombinedSignature,
+    ) -> Result<(), ThresholdSchnorrVerifyCombinedSignatureError> {
+        Ok(())
+    }
+}
+
+impl VetKdProtocol for CryptoReturningOk {
+    fn create_encrypted_key_share(
+        &self,
+        _args: VetKdArgs,
+    ) -> Result<VetKdEncryptedKeyShare, VetKdKeyShareCreationError> {
+        Ok(VetKdEncryptedKeyShare {
+            encrypted_key_share: vec![],
+        })
+    }
+
+    fn verify_encrypted_key_share(
+        &self,
+        _signer: NodeId,
+        _key_share: &VetKdEncryptedKeyShare,
+        _args: &VetKdArgs,
+    ) -> Result<(), VetKdKeyShareVerificationError> {
+        Ok(())
+    }
+
+    fn combine_encrypted_key_shares(
+        &self,
+        _shares: &BTreeMap<NodeId, VetKdEncryptedKeyShare>,
+        _args: &VetKdArgs,
+    ) -> Result<VetKdEncryptedKey, VetKdKeyShareCombinationError> {
+        Ok(VetKdEncryptedKey {
+            encrypted_key: vec![],
+        })
+    }
+
+    fn verify_encrypted_key(
+        &self,
+        _key: &VetKdEncryptedKey,
+        _args: &VetKdArgs,
+    ) -> Result<(), VetKdKeyVerificationError> {
+        Ok(())
+    }
+}
+
 pub fn mock_random_number_generator() -> Box<dyn RngCore> {
     Box::new(StdRng::from_seed([0u8; 32]))
 }
@@ -31,7 +31,7 @@ use ic_types::messages::CallbackId;
 use ic_types::methods::{Callback, WasmClosure};
 use ic_types::time::UNIX_EPOCH;
 use ic_types::{
-    messages::{Ingress, Request, RequestOrResponse},
+    messages::{Ingress, Request, RequestOrResponse, NO_DEADLINE},
     xnet::{StreamHeader, StreamIndex, StreamIndexedQueue},
     CanisterId, ComputeAllocation, Cycles, ExecutionRound, MemoryAllocation, NumBytes, PrincipalId,
     SubnetId, Time,
@@ -698,7 +698,7 @@ pub fn register_callback(
         .call_context_manager_mut()
         .unwrap();
     let call_context_id = call_context_manager.new_call_context(
-        CallOrigin::CanisterUpdate(originator, callback_id),
+        CallOrigin::CanisterUpdate(originator, callback_id, NO_DEADLINE),
         Cycles::zero(),
         Time::from_nanos_since_unix_epoch(0),
     );
@@ -713,6 +713,7 @@ pub fn register_callback(
         WasmClosure::new(0, 2),
         WasmClosure::new(0, 2),
         None,
+        NO_DEADLINE,
     ));
 }
 
@@ -20,6 +20,7 @@ impl Default for BatchBuilder {
                 payload: super::payload::PayloadBuilder::default().build(),
                 randomness: Randomness::from([0; 32]),
                 ecdsa_subnet_public_keys: BTreeMap::new(),
+                schnorr_subnet_public_keys: BTreeMap::new(),
                 registry_version: RegistryVersion::from(1),
                 time: mock_time(),
                 consensus_responses: vec![],
@@ -138,6 +138,8 @@ async fn enable_ecdsa_signing(governance: &Canister<'_>, subnet_id: SubnetId, ke
         ecdsa_config: Some(EcdsaConfig {
             quadruples_to_create_in_advance: 10,
             key_ids: vec![key_id.clone()],
+            schnorr_key_ids: vec![],
+            vetkd_key_ids: vec![],
             max_queue_size: Some(DEFAULT_ECDSA_MAX_QUEUE_SIZE),
             signature_request_timeout_ns: None,
             idkg_key_rotation_period_ms: None,
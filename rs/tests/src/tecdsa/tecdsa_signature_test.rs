@@ -378,6 +378,8 @@ pub(crate) async fn add_ecdsa_key_with_timeout_and_rotation_period(
         ecdsa_config: Some(EcdsaConfig {
             quadruples_to_create_in_advance: 10,
             key_ids: vec![key_id],
+            schnorr_key_ids: vec![],
+            vetkd_key_ids: vec![],
             max_queue_size: Some(DEFAULT_ECDSA_MAX_QUEUE_SIZE),
             signature_request_timeout_ns: timeout.map(|t| t.as_nanos() as u64),
             idkg_key_rotation_period_ms: period.map(|t| t.as_millis() as u64),
@@ -38,10 +38,10 @@ enum TransportTlsHandshakeError {
 const DEFAULT_CHANNEL_ID: usize = 0;
 
 /// Time to wait before retrying an unsuccessful connection attempt
-const CONNECT_RETRY_SECONDS: u64 = 3;
+pub(crate) const CONNECT_RETRY_SECONDS: u64 = 3;
 
 /// Time to wait for the TLS handshake (for both client/server sides)
-const TLS_HANDSHAKE_TIMEOUT_SECONDS: u64 = 30;
+pub(crate) const TLS_HANDSHAKE_TIMEOUT_SECONDS: u64 = 30;
 
 const CONNECT_TASK_NAME: &str = "connect";
 const ACCEPT_TASK_NAME: &str = "accept";
@@ -495,7 +495,7 @@ async fn connect_to_server(
 }
 
 /// Returns our role wrt the peer connection
-fn connection_role(my_id: &NodeId, peer: &NodeId) -> ConnectionRole {
+pub(crate) fn connection_role(my_id: &NodeId, peer: &NodeId) -> ConnectionRole {
     assert!(*my_id != *peer);
     if *my_id > *peer {
         ConnectionRole::Server
@@ -26,12 +26,13 @@ use crate::{
     },
     utils::get_peer_label,
 };
+use async_trait::async_trait;
 use ic_base_types::NodeId;
 use ic_crypto_tls_interfaces::TlsStream;
 use ic_interfaces_transport::{
     TransportChannelId, TransportEvent, TransportEventHandler, TransportMessage, TransportPayload,
 };
-use ic_logger::{info, warn};
+use ic_logger::{info, warn, ReplicaLogger};
 use std::convert::TryInto;
 use std::net::SocketAddr;
 use std::sync::Weak;
@@ -69,6 +70,29 @@ const READ_RESULT_ERROR: &str = "error";
 const READ_RESULT_HEARTBEAT: &str = "heartbeat";
 const READ_RESULT_MESSAGE: &str = "message";
 
+/// The owner of the connections served by the data plane tasks. The tasks
+/// report a broken connection back to it, so that it can be re-established.
+#[async_trait]
+pub(crate) trait ConnectionManager: Send + Sync + 'static {
+    /// Logger used by the data plane tasks
+    fn log(&self) -> &ReplicaLogger;
+
+    /// Called by a data plane task once it detected that the connection to
+    /// the peer is broken. The task exits afterwards.
+    async fn on_disconnect(&self, peer_id: NodeId, channel_id: TransportChannelId);
+}
+
+#[async_trait]
+impl ConnectionManager for TransportImpl {
+    fn log(&self) -> &ReplicaLogger {
+        &self.log
+    }
+
+    async fn on_disconnect(&self, peer_id: NodeId, channel_id: TransportChannelId) {
+        TransportImpl::on_disconnect(self, peer_id, channel_id).await
+    }
+}
+
 /// Create header bytes to send with payload.
 fn pack_header(payload: Option<&TransportPayload>, heartbeat: bool) -> Vec<u8> {
     let mut result = Vec::<u8>::new();
@@ -116,20 +140,20 @@ fn unpack_header(data: Vec<u8>) -> TransportHeader {
 
 /// Per-flow send task. Reads the requests from the send queue and writes to
 /// the socket.
-fn spawn_write_task<W: AsyncWrite + Unpin + Send + 'static>(
+pub(crate) fn spawn_write_task<W: AsyncWrite + Unpin + Send + 'static, M: ConnectionManager>(
     peer_id: NodeId,
     channel_id: TransportChannelId,
     mut send_queue_reader: Box<dyn SendQueueReader + Send + Sync>,
     mut writer: W,
     data_plane_metrics: DataPlaneMetrics,
-    weak_self: Weak<TransportImpl>,
+    weak_self: Weak<M>,
     rt_handle: tokio::runtime::Handle,
 ) -> JoinHandle<()> {
     let channel_id_str = channel_id.to_string();
     rt_handle.spawn(async move  {
         let _ = &data_plane_metrics;
         let _raii_gauge = IntGaugeResource::new(data_plane_metrics.write_tasks.clone());
-        // If the TransportImpl has been deleted, exist the loop and exist the task.
+        // If the connection manager has been deleted, exist the loop and exist the task.
         while let Some(arc_self) = weak_self.upgrade() {
             // Wait for the send requests
             let dequeued = send_queue_reader
@@ -144,8 +168,7 @@ fn spawn_write_task<W: AsyncWrite + Unpin + Send + 'static>(
                 // There is nothing to send, so issue a heartbeat message
                 bytes_to_send.append(&mut pack_header(None, true));
 
-                arc_self
-                    .data_plane_metrics
+                data_plane_metrics
                     .heart_beats_sent
                     .with_label_values(&[&channel_id_str])
                     .inc();
@@ -163,7 +186,7 @@ fn spawn_write_task<W: AsyncWrite + Unpin + Send + 'static>(
             let message_len = bytes_to_send.len();
             if let Err(err) = write_one_message(&mut writer, bytes_to_send).await {
                 warn!(
-                    arc_self.log,
+                    arc_self.log(),
                     "DataPlane::spawn_write_task(): failed to write payload: peer_id = {:?}, channel_id = {:?}, error ={:?}",
                     peer_id,
                     channel_id,
@@ -172,13 +195,11 @@ fn spawn_write_task<W: AsyncWrite + Unpin + Send + 'static>(
                 arc_self.on_disconnect(peer_id, channel_id).await;
                 return;
             }
-            arc_self
-                .data_plane_metrics
+            data_plane_metrics
                 .send_message_duration
                 .with_label_values(&[&channel_id_str])
                 .observe(start_time.elapsed().as_secs_f64());
-            arc_self
-                .data_plane_metrics
+            data_plane_metrics
                 .write_bytes_total
                 .with_label_values(&[&channel_id_str])
                 .inc_by(message_len as u64);
@@ -196,13 +217,13 @@ async fn write_one_message<W: AsyncWrite + Unpin>(
 
 /// Per-flow receive task. Reads the messages from the socket and passes to
 /// the client.
-fn spawn_read_task<R: AsyncRead + Unpin + Send + 'static>(
+pub(crate) fn spawn_read_task<R: AsyncRead + Unpin + Send + 'static, M: ConnectionManager>(
     peer_id: NodeId,
     channel_id: TransportChannelId,
     mut event_handler: TransportEventHandler,
     mut reader: R,
     data_plane_metrics: DataPlaneMetrics,
-    weak_self: Weak<TransportImpl>,
+    weak_self: Weak<M>,
     rt_handle: tokio::runtime::Handle,
 ) -> JoinHandle<()> {
     rt_handle.spawn(async move {
@@ -210,7 +231,7 @@ fn spawn_read_task<R: AsyncRead + Unpin + Send + 'static>(
         let _raii_gauge = IntGaugeResource::new(data_plane_metrics.read_tasks.clone());
         let heartbeat_timeout = Duration::from_millis(TRANSPORT_HEARTBEAT_WAIT_INTERVAL_MS);
         let channel_id_str = channel_id.to_string();
-        // If the TransportImpl has been deleted, exist the loop and exist the task.
+        // If the connection manager has been deleted, exist the loop and exist the task.
         while let Some(arc_self) = weak_self.upgrade() {
             // Read the next message from the socket
             let read_message_start = Instant::now();
@@ -219,18 +240,18 @@ fn spawn_read_task<R: AsyncRead + Unpin + Send + 'static>(
             match read_one_msg_result {
                 Err(err) => {
                     info!(
-                        arc_self.log,
+                        arc_self.log(),
                         "DataPlane::spawn_read_task(): failed to receive a single message: peer_id = {:?}, channel_id = {:?}, error = {:?}",
                         peer_id,
                         channel_id,
                         err,
                     );
-                    arc_self.data_plane_metrics
+                    data_plane_metrics
                         .read_message_duration
                         .with_label_values(&[&channel_id_str, READ_RESULT_ERROR])
                         .observe(read_message_start.elapsed().as_secs_f64());
 
-                    arc_self.data_plane_metrics
+                    data_plane_metrics
                         .message_read_errors_total
                         .with_label_values(&[&channel_id_str, err.into()])
                         .inc();
@@ -240,17 +261,17 @@ fn spawn_read_task<R: AsyncRead + Unpin + Send + 'static>(
                 Ok((header, payload)) => {
                     if header.flags & TRANSPORT_FLAGS_IS_HEARTBEAT != 0 {
                         // It's an empty heartbeat message -- do nothing
-                        arc_self.data_plane_metrics
+                        data_plane_metrics
                             .heart_beats_received
                             .with_label_values(&[&channel_id_str])
                             .inc();
-                        arc_self.data_plane_metrics
+                        data_plane_metrics
                             .read_message_duration
                             .with_label_values(&[&channel_id_str, READ_RESULT_HEARTBEAT])
                             .observe(read_message_start.elapsed().as_secs_f64());
                         continue;
                     }
-                    arc_self.data_plane_metrics
+                    data_plane_metrics
                         .read_message_duration
                         .with_label_values(&[&channel_id_str, READ_RESULT_MESSAGE])
                         .observe(read_message_start.elapsed().as_secs_f64());
@@ -258,11 +279,11 @@ fn spawn_read_task<R: AsyncRead + Unpin + Send + 'static>(
                     // Pass up the received message.
                     // Errors out for unsolicited messages, decoding errors and p2p
                     // shutdowns.
-                    arc_self.data_plane_metrics
+                    data_plane_metrics
                         .read_bytes_total
                         .with_label_values(&[&channel_id_str])
                         .inc_by(payload.0.len() as u64);
-                    let _callback_start_time = arc_self.data_plane_metrics
+                    let _callback_start_time = data_plane_metrics
                         .event_handler_message_duration
                         .with_label_values(&[&channel_id_str]).start_timer();
                     event_handler
@@ -35,6 +35,8 @@
 mod control_plane;
 mod data_plane;
 mod metrics;
+mod quic;
+mod switching;
 pub mod transport;
 mod types;
 mod utils;
@@ -0,0 +1,878 @@
+//! QUIC transport - An alternative to TLS over TCP.
+//!
+//! All flows with a peer are multiplexed over a single QUIC connection. Each
+//! flow (channel) is carried by its own pair of unidirectional streams, one
+//! per direction, so that a stalled or lossy flow does not hold back the
+//! others. The first bytes written to a stream are the channel id (u32, little
+//! endian), followed by the same framing (transport header + payload,
+//! heartbeats) that the TCP data plane uses.
+//!
+//! The TLS handshake is performed by QUIC itself, with the configurations from
+//! [`TlsConfig`]. As with TCP, the node with the lower node id is the client
+//! of the connection, and the server only accepts its allowed clients: the
+//! server configuration is replaced whenever they change. A single UDP
+//! endpoint on the listening port is used both to accept and to initiate
+//! connections.
+
+use crate::{
+    control_plane::{connection_role, CONNECT_RETRY_SECONDS, TLS_HANDSHAKE_TIMEOUT_SECONDS},
+    data_plane::{spawn_read_task, spawn_write_task, ConnectionManager},
+    metrics::{
+        ControlPlaneMetrics, DataPlaneMetrics, IntGaugeResource, SendQueueMetrics,
+        TransportMetrics, STATUS_SUCCESS,
+    },
+    types::{Connecting, ConnectionRole, SendQueue},
+    utils::{get_peer_label, SendQueueImpl},
+};
+use async_trait::async_trait;
+use futures::StreamExt;
+use ic_base_types::{NodeId, PrincipalId, RegistryVersion};
+use ic_config::transport::TransportConfig;
+use ic_crypto_tls_interfaces::{AllowedClients, TlsConfig, TlsConfigError, TlsPublicKeyCert};
+use ic_interfaces_transport::{
+    Transport, TransportChannelId, TransportError, TransportEvent, TransportEventHandler,
+    TransportPayload,
+};
+use ic_logger::{info, warn, ReplicaLogger};
+use openssl::nid::Nid;
+use quinn::{Connection, Endpoint, IncomingUniStreams, NewConnection, RecvStream, VarInt};
+use std::{
+    collections::{BTreeSet, HashMap},
+    net::{IpAddr, SocketAddr},
+    str::FromStr,
+    sync::{Arc, Weak},
+    time::Duration,
+};
+use strum::AsRefStr;
+use tokio::{
+    runtime::Handle,
+    sync::{Mutex, RwLock},
+    task::JoinHandle,
+    time::sleep,
+};
+use tower::Service;
+
+/// Receive window of a single stream. Flows carry messages of up to a few MB
+/// (e.g. state sync chunks), so the QUIC default is too small.
+const QUIC_STREAM_RECEIVE_WINDOW: u64 = 4 * 1024 * 1024;
+
+/// Server name sent in the TLS client hello. The node certificates are
+/// verified against the registry instead of a DNS name.
+const QUIC_SERVER_NAME: &str = "domain.is-irrelevant-as-hostname-verification-is.disabled";
+
+const QUIC_CONNECT_TASK_NAME: &str = "quic_connect";
+const QUIC_ACCEPT_TASK_NAME: &str = "quic_accept";
+const QUIC_TRANSITION_FROM_ACCEPT_TASK_NAME: &str = "quic_transition_from_accept";
+const QUIC_ACCEPT_STREAMS_TASK_NAME: &str = "quic_accept_streams";
+
+#[derive(Debug, AsRefStr)]
+#[strum(serialize_all = "snake_case")]
+enum QuicHandshakeError {
+    DeadlineExceeded,
+    Internal(String),
+    PeerNotAllowed,
+}
+
+/// QUIC based implementation of the `Transport` interface.
+pub(crate) struct QuicTransportImpl {
+    /// The node ID of this replica
+    node_id: NodeId,
+    /// The IP address of this node
+    node_ip: IpAddr,
+    /// Configuration
+    config: TransportConfig,
+
+    /// The UDP endpoint used for all connections, bound in `set_event_handler`
+    endpoint: Mutex<Option<Endpoint>>,
+    /// Task accepting incoming connections on the endpoint
+    accept_task: Mutex<Option<JoinHandle<()>>>,
+    /// Mapping of peers to their corresponding state
+    peer_map: RwLock<HashMap<NodeId, RwLock<QuicPeerState>>>,
+    /// Event handler to report back to the transport client
+    event_handler: Mutex<Option<TransportEventHandler>>,
+
+    /// Clients that are allowed to connect to this node
+    allowed_clients: RwLock<BTreeSet<NodeId>>,
+    /// The registry version that is used
+    registry_version: RwLock<RegistryVersion>,
+    /// Reference to the crypto component
+    tls_config: Arc<dyn TlsConfig + Send + Sync>,
+    /// QUIC parameters shared by the client and server configurations
+    quic_transport_config: Arc<quinn::TransportConfig>,
+
+    /// Data plane metrics
+    data_plane_metrics: DataPlaneMetrics,
+    /// Control plane metrics
+    control_plane_metrics: ControlPlaneMetrics,
+    /// Send queue metrics
+    send_queue_metrics: SendQueueMetrics,
+
+    /// The tokio runtime
+    rt_handle: Handle,
+    /// Logger
+    log: ReplicaLogger,
+    /// Guarded self weak-reference
+    weak_self: std::sync::RwLock<Weak<QuicTransportImpl>>,
+}
+
+/// Per-peer state
+struct QuicPeerState {
+    /// Peer label, used for metrics
+    peer_label: String,
+    /// The address of the peer
+    peer_addr: SocketAddr,
+    /// Connection state
+    connection_state: QuicConnectionState,
+    /// One send queue per channel, indexed by the channel id
+    send_queues: Vec<Box<dyn SendQueue + Send + Sync>>,
+    /// Metrics
+    control_plane_metrics: ControlPlaneMetrics,
+}
+
+enum QuicConnectionState {
+    /// We are the server, waiting for peer to connect
+    Listening,
+    /// We are the client, connection in progress
+    Connecting(Connecting),
+    /// Connection established
+    Connected(QuicConnected),
+}
+
+/// Info about a peer in QuicConnectionState::Connected
+struct QuicConnected {
+    /// The QUIC connection, closed on drop
+    connection: Connection,
+    /// The write tasks of all channels and the task accepting the peer's
+    /// streams (which owns the read tasks)
+    tasks: Vec<JoinHandle<()>>,
+}
+
+impl Drop for QuicConnected {
+    fn drop(&mut self) {
+        for task in &self.tasks {
+            task.abort();
+        }
+        self.connection.close(VarInt::from_u32(0), b"disconnect");
+    }
+}
+
+/// Aborts the read tasks of a connection when the task accepting the
+/// streams is aborted.
+struct ReadTasks(Vec<JoinHandle<()>>);
+
+impl Drop for ReadTasks {
+    fn drop(&mut self) {
+        for task in &self.0 {
+            task.abort();
+        }
+    }
+}
+
+impl QuicPeerState {
+    fn new(
+        peer_label: String,
+        peer_addr: SocketAddr,
+        connection_state: QuicConnectionState,
+        config: &TransportConfig,
+        send_queue_metrics: SendQueueMetrics,
+        control_plane_metrics: ControlPlaneMetrics,
+    ) -> Self {
+        let send_queues = (0..config.max_streams)
+            .map(|channel_id| {
+                Box::new(SendQueueImpl::new(
+                    peer_label.clone(),
+                    TransportChannelId::from(channel_id),
+                    config.send_queue_size,
+                    send_queue_metrics.clone(),
+                )) as Box<dyn SendQueue + Send + Sync>
+            })
+            .collect();
+        let ret = Self {
+            peer_label,
+            peer_addr,
+            connection_state,
+            send_queues,
+            control_plane_metrics,
+        };
+        ret.report_connection_state();
+        ret
+    }
+
+    fn update(&mut self, connection_state: QuicConnectionState) {
+        self.connection_state = connection_state;
+        self.report_connection_state();
+    }
+
+    fn is_connected(&self) -> bool {
+        matches!(self.connection_state, QuicConnectionState::Connected(_))
+    }
+
+    fn report_connection_state(&self) {
+        let idx = match self.connection_state {
+            QuicConnectionState::Listening => 1,
+            QuicConnectionState::Connecting(_) => 2,
+            QuicConnectionState::Connected(_) => 3,
+        };
+        self.control_plane_metrics
+            .connection_state
+            .with_label_values(&[&self.peer_label])
+            .set(idx);
+    }
+}
+
+impl Drop for QuicPeerState {
+    fn drop(&mut self) {
+        let _ = self
+            .control_plane_metrics
+            .connection_state
+            .remove_label_values(&[&self.peer_label]);
+    }
+}
+
+impl QuicTransportImpl {
+    /// Creates a new QUIC transport instance
+    pub(crate) fn new(
+        node_id: NodeId,
+        config: TransportConfig,
+        registry_version: RegistryVersion,
+        metrics: TransportMetrics,
+        tls_config: Arc<dyn TlsConfig + Send + Sync>,
+        rt_handle: Handle,
+        log: ReplicaLogger,
+    ) -> Arc<Self> {
+        let node_ip = IpAddr::from_str(&config.node_ip)
+            .unwrap_or_else(|_| panic!("Invalid node IP: {}", &config.node_ip));
+        let quic_transport_config = Arc::new(quic_transport_config(config.max_streams));
+        let arc = Arc::new(Self {
+            node_id,
+            node_ip,
+            config,
+            endpoint: Mutex::new(None),
+            accept_task: Mutex::new(None),
+            peer_map: RwLock::new(HashMap::new()),
+            event_handler: Mutex::new(None),
+            allowed_clients: RwLock::new(BTreeSet::new()),
+            registry_version: RwLock::new(registry_version),
+            tls_config,
+            quic_transport_config,
+            data_plane_metrics: metrics.data_plane,
+            control_plane_metrics: metrics.control_plane,
+            send_queue_metrics: metrics.send_queue,
+            rt_handle,
+            log,
+            weak_self: std::sync::RwLock::new(Weak::new()),
+        });
+        *arc.weak_self.write().unwrap() = Arc::downgrade(&arc);
+        arc
+    }
+
+    /// Returns the server configuration for the current allowed clients and
+    /// registry version, or `None` if there are no allowed clients.
+    fn server_config(&self) -> Result<Option<quinn::ServerConfig>, TlsConfigError> {
+        let registry_version = *self.registry_version.blocking_read();
+        let current_allowed_clients = self.allowed_clients.blocking_read().clone();
+        let allowed_clients = match AllowedClients::new_with_nodes(current_allowed_clients) {
+            Ok(allowed_clients) => allowed_clients,
+            // There are no allowed clients.
+            Err(_) => return Ok(None),
+        };
+        let crypto = self
+            .tls_config
+            .server_config(allowed_clients, registry_version)?;
+        let mut server_config = quinn::ServerConfig::default();
+        server_config.transport = self.quic_transport_config.clone();
+        server_config.crypto = Arc::new(crypto);
+        Ok(Some(server_config))
+    }
+
+    /// Replaces the server configuration of the endpoint, after the allowed
+    /// clients or the registry version changed. Only affects connections
+    /// accepted after the call.
+    fn refresh_server_config(&self) {
+        let endpoint = match self.endpoint.blocking_lock().clone() {
+            Some(endpoint) => endpoint,
+            None => return,
+        };
+        match self.server_config() {
+            Ok(server_config) => endpoint.set_server_config(server_config),
+            Err(err) => warn!(
+                self.log,
+                "QuicTransport::refresh_server_config(): failed to create the server config, \
+                keeping the current one: error = {:?}",
+                err
+            ),
+        }
+    }
+
+    /// Starts the async task to accept the incoming QUIC connections.
+    fn spawn_accept_task(&self, mut incoming: quinn::Incoming) -> JoinHandle<()> {
+        let weak_self = self.weak_self.read().unwrap().clone();
+        let async_tasks_gauge_vec = self.control_plane_metrics.async_tasks.clone();
+        let rt_handle = self.rt_handle.clone();
+        self.rt_handle.spawn(async move {
+            let task_gauge = async_tasks_gauge_vec.with_label_values(&[QUIC_ACCEPT_TASK_NAME]);
+            let _gauge_guard = IntGaugeResource::new(task_gauge);
+            while let Some(connecting) = incoming.next().await {
+                // If the QuicTransportImpl has been deleted, abort.
+                let arc_self = match weak_self.upgrade() {
+                    Some(arc_self) => arc_self,
+                    _ => return,
+                };
+                rt_handle.spawn(async move {
+                    let task_gauge = arc_self
+                        .control_plane_metrics
+                        .async_tasks
+                        .with_label_values(&[QUIC_TRANSITION_FROM_ACCEPT_TASK_NAME]);
+                    let _gauge_guard = IntGaugeResource::new(task_gauge);
+                    let peer_addr = connecting.remote_address();
+                    let (peer_id, new_connection) = match arc_self
+                        .quic_server_handshake(connecting)
+                        .await
+                    {
+                        Ok(result) => {
+                            arc_self
+                                .control_plane_metrics
+                                .tls_handshakes
+                                .with_label_values(&[
+                                    ConnectionRole::Server.as_ref(),
+                                    STATUS_SUCCESS,
+                                ])
+                                .inc();
+                            result
+                        }
+                        Err(err) => {
+                            arc_self
+                                .control_plane_metrics
+                                .tls_handshakes
+                                .with_label_values(&[ConnectionRole::Server.as_ref(), err.as_ref()])
+                                .inc();
+                            warn!(
+                                arc_self.log,
+                                "QuicTransport::spawn_accept_task(): handshake failed: \
+                                    error = {:?}, peer_addr = {:?}",
+                                err,
+                                peer_addr,
+                            );
+                            return;
+                        }
+                    };
+                    arc_self
+                        .on_connect(peer_id, ConnectionRole::Server, new_connection)
+                        .await;
+                });
+            }
+        })
+    }
+
+    /// Spawn a task that tries to connect to a peer (forever, or until
+    /// connection is established or peer is removed)
+    fn spawn_connect_task(&self, peer_id: NodeId, peer_addr: SocketAddr) -> JoinHandle<()> {
+        let weak_self = self.weak_self.read().unwrap().clone();
+        let async_tasks_gauge_vec = self.control_plane_metrics.async_tasks.clone();
+        self.rt_handle.spawn(async move {
+            let gauge = async_tasks_gauge_vec.with_label_values(&[QUIC_CONNECT_TASK_NAME]);
+            let _raii_gauge = IntGaugeResource::new(gauge);
+            let mut retries: u32 = 0;
+            // If the QuicTransportImpl has been deleted, exit the loop and the task.
+            while let Some(arc_self) = weak_self.upgrade() {
+                retries += 1;
+                match arc_self.quic_client_handshake(peer_id, peer_addr).await {
+                    Ok(new_connection) => {
+                        arc_self
+                            .control_plane_metrics
+                            .tls_handshakes
+                            .with_label_values(&[ConnectionRole::Client.as_ref(), STATUS_SUCCESS])
+                            .inc();
+                        arc_self
+                            .on_connect(peer_id, ConnectionRole::Client, new_connection)
+                            .await;
+                        // Stop this task, the connection is up (or the peer was removed).
+                        return;
+                    }
+                    Err(err) => {
+                        arc_self
+                            .control_plane_metrics
+                            .tls_handshakes
+                            .with_label_values(&[ConnectionRole::Client.as_ref(), err.as_ref()])
+                            .inc();
+                        warn!(
+                            arc_self.log,
+                            "QuicTransport::spawn_connect_task(): handshake failed: error = {:?}, \
+                            peer = {:?}/{:?}, retries = {}",
+                            err,
+                            peer_id,
+                            peer_addr,
+                            retries,
+                        );
+                    }
+                }
+                // Do not keep the transport alive while sleeping.
+                drop(arc_self);
+                sleep(Duration::from_secs(CONNECT_RETRY_SECONDS)).await;
+            }
+        })
+    }
+
+    /// Accepts an incoming connection and authenticates the client.
+    async fn quic_server_handshake(
+        &self,
+        connecting: quinn::Connecting,
+    ) -> Result<(NodeId, NewConnection), QuicHandshakeError> {
+        let new_connection = match tokio::time::timeout(
+            Duration::from_secs(TLS_HANDSHAKE_TIMEOUT_SECONDS),
+            connecting,
+        )
+        .await
+        {
+            Err(_) => Err(QuicHandshakeError::DeadlineExceeded),
+            Ok(Ok(new_connection)) => Ok(new_connection),
+            Ok(Err(err)) => Err(QuicHandshakeError::Internal(format!("{:?}", err))),
+        }?;
+        let peer_id =
+            authenticated_peer(&new_connection.connection).map_err(QuicHandshakeError::Internal)?;
+        // The handshake may have started before the peer was removed.
+        if !self.allowed_clients.read().await.contains(&peer_id) {
+            return Err(QuicHandshakeError::PeerNotAllowed);
+        }
+        Ok((peer_id, new_connection))
+    }
+
+    /// Connects to the peer, which must authenticate as `peer_id`.
+    async fn quic_client_handshake(
+        &self,
+        peer_id: NodeId,
+        peer_addr: SocketAddr,
+    ) -> Result<NewConnection, QuicHandshakeError> {
+        let endpoint = self
+            .endpoint
+            .lock()
+            .await
+            .clone()
+            .ok_or_else(|| QuicHandshakeError::Internal("Endpoint not bound".to_string()))?;
+        let registry_version = *self.registry_version.read().await;
+        let crypto = self
+            .tls_config
+            .client_config(peer_id, registry_version)
+            .map_err(|err| QuicHandshakeError::Internal(format!("{:?}", err)))?;
+        let client_config = quinn::ClientConfig {
+            transport: self.quic_transport_config.clone(),
+            crypto: Arc::new(crypto),
+        };
+        let connecting = endpoint
+            .connect_with(client_config, &peer_addr, QUIC_SERVER_NAME)
+            .map_err(|err| QuicHandshakeError::Internal(format!("{:?}", err)))?;
+        match tokio::time::timeout(
+            Duration::from_secs(TLS_HANDSHAKE_TIMEOUT_SECONDS),
+            connecting,
+        )
+        .await
+        {
+            Err(_) => Err(QuicHandshakeError::DeadlineExceeded),
+            Ok(Ok(new_connection)) => Ok(new_connection),
+            Ok(Err(err)) => Err(QuicHandshakeError::Internal(format!("{:?}", err))),
+        }
+    }
+
+    /// Handles an established connection: opens one stream per channel, starts
+    /// the data plane tasks and reports the peer as up.
+    async fn on_connect(
+        &self,
+        peer_id: NodeId,
+        role: ConnectionRole,
+        new_connection: NewConnection,
+    ) {
+        let NewConnection {
+            connection,
+            uni_streams,
+            ..
+        } = new_connection;
+        let peer_map = self.peer_map.read().await;
+        let peer_state_mu = match peer_map.get(&peer_id) {
+            Some(peer_state) => peer_state,
+            None => return,
+        };
+        let mut peer_state = peer_state_mu.write().await;
+        let mut event_handler = match self.event_handler.lock().await.as_ref() {
+            Some(event_handler) => event_handler.clone(),
+            None => return,
+        };
+        match (&peer_state.connection_state, role) {
+            (QuicConnectionState::Listening, ConnectionRole::Server)
+            | (QuicConnectionState::Connecting(_), ConnectionRole::Client) => (),
+            // The peer reconnected before we noticed that the previous connection
+            // broke, e.g. because the peer restarted. As the peer only connects
+            // again once it gave up on the previous connection, the new one
+            // replaces it.
+            (QuicConnectionState::Connected(_), ConnectionRole::Server) => {
+                info!(
+                    self.log,
+                    "QuicTransport::on_connect(): replacing the connection: peer_id = {:?}",
+                    peer_id
+                );
+                event_handler
+                    .call(TransportEvent::PeerDown(peer_id))
+                    .await
+                    .expect("Can't panic on infallible");
+                // Drops the previous connection, which aborts its data plane tasks.
+                peer_state.update(QuicConnectionState::Listening);
+            }
+            // A connection we did not wait for, e.g. a client connection that
+            // completed after the peer connected to us. Only one connection per
+            // peer is used, so close it.
+            (_, _) => {
+                warn!(
+                    self.log,
+                    "QuicTransport::on_connect(): unexpected connection, closing it: \
+                    peer_id = {:?}, role = {:?}",
+                    peer_id,
+                    role
+                );
+                connection.close(VarInt::from_u32(0), b"unexpected connection");
+                return;
+            }
+        }
+        let weak_self = self.weak_self.read().unwrap().clone();
+
+        // Closes the connection and aborts the tasks if we bail out early.
+        let mut connected = QuicConnected {
+            connection,
+            tasks: Vec::with_capacity(peer_state.send_queues.len() + 1),
+        };
+        for (channel_id, send_queue) in peer_state.send_queues.iter_mut().enumerate() {
+            let mut send_stream = match connected.connection.open_uni().await {
+                Ok(send_stream) => send_stream,
+                Err(err) => {
+                    warn!(
+                        self.log,
+                        "QuicTransport::on_connect(): failed to open stream: peer_id = {:?}, \
+                        channel_id = {:?}, error = {:?}",
+                        peer_id,
+                        channel_id,
+                        err
+                    );
+                    return;
+                }
+            };
+            if let Err(err) = send_stream
+                .write_all(&(channel_id as u32).to_le_bytes())
+                .await
+            {
+                warn!(
+                    self.log,
+                    "QuicTransport::on_connect(): failed to write stream header: \
+                    peer_id = {:?}, channel_id = {:?}, error = {:?}",
+                    peer_id,
+                    channel_id,
+                    err
+                );
+                return;
+            }
+            connected.tasks.push(spawn_write_task(
+                peer_id,
+                TransportChannelId::from(channel_id),
+                send_queue.get_reader(),
+                send_stream,
+                self.data_plane_metrics.clone(),
+                weak_self.clone(),
+                self.rt_handle.clone(),
+            ));
+        }
+        connected.tasks.push(self.spawn_accept_streams_task(
+            peer_id,
+            uni_streams,
+            event_handler.clone(),
+        ));
+
+        event_handler
+            .call(TransportEvent::PeerUp(peer_id))
+            .await
+            .expect("Can't panic on infallible");
+        peer_state.update(QuicConnectionState::Connected(connected));
+        info!(
+            self.log,
+            "QuicTransport::on_connect(): connected: peer_id = {:?}, role = {:?}", peer_id, role
+        );
+    }
+
+    /// Spawns a task that accepts the streams opened by the peer and starts a
+    /// read task for each of them.
+    fn spawn_accept_streams_task(
+        &self,
+        peer_id: NodeId,
+        mut uni_streams: IncomingUniStreams,
+        event_handler: TransportEventHandler,
+    ) -> JoinHandle<()> {
+        let weak_self = self.weak_self.read().unwrap().clone();
+        let async_tasks_gauge_vec = self.control_plane_metrics.async_tasks.clone();
+        let data_plane_metrics = self.data_plane_metrics.clone();
+        let max_streams = self.config.max_streams;
+        let rt_handle = self.rt_handle.clone();
+        self.rt_handle.spawn(async move {
+            let gauge = async_tasks_gauge_vec.with_label_values(&[QUIC_ACCEPT_STREAMS_TASK_NAME]);
+            let _raii_gauge = IntGaugeResource::new(gauge);
+            let mut read_tasks = ReadTasks(Vec::with_capacity(max_streams));
+            let error = loop {
+                let mut recv_stream = match uni_streams.next().await {
+                    Some(Ok(recv_stream)) => recv_stream,
+                    Some(Err(err)) => break format!("{:?}", err),
+                    None => break "connection closed".to_string(),
+                };
+                let channel_id = match read_channel_id(&mut recv_stream).await {
+                    Ok(channel_id) if channel_id < max_streams => channel_id,
+                    Ok(channel_id) => break format!("invalid channel id {}", channel_id),
+                    Err(err) => break err,
+                };
+                read_tasks.0.push(spawn_read_task(
+                    peer_id,
+                    TransportChannelId::from(channel_id),
+                    event_handler.clone(),
+                    recv_stream,
+                    data_plane_metrics.clone(),
+                    weak_self.clone(),
+                    rt_handle.clone(),
+                ));
+            };
+            if let Some(arc_self) = weak_self.upgrade() {
+                info!(
+                    arc_self.log,
+                    "QuicTransport::spawn_accept_streams_task(): stopped accepting streams: \
+                    peer_id = {:?}, error = {}",
+                    peer_id,
+                    error
+                );
+                arc_self
+                    .on_disconnect(peer_id, TransportChannelId::from(0))
+                    .await;
+            }
+        })
+    }
+}
+
+#[async_trait]
+impl ConnectionManager for QuicTransportImpl {
+    fn log(&self) -> &ReplicaLogger {
+        &self.log
+    }
+
+    /// Tears down the connection and waits for, or initiates, the reconnect.
+    async fn on_disconnect(&self, peer_id: NodeId, channel_id: TransportChannelId) {
+        let peer_map = self.peer_map.read().await;
+        let peer_state_mu = match peer_map.get(&peer_id) {
+            Some(peer_state) => peer_state,
+            None => return,
+        };
+        let mut peer_state = peer_state_mu.write().await;
+        if !peer_state.is_connected() {
+            // Connection is already down/reconnecting, skip reconnect processing
+            return;
+        }
+        let mut event_handler = match self.event_handler.lock().await.as_ref() {
+            Some(event_handler) => event_handler.clone(),
+            None => return,
+        };
+        warn!(
+            self.log,
+            "QuicTransport::on_disconnect(): node_id = {:?}, channel_id = {:?}, peer_id = {:?}",
+            self.node_id,
+            channel_id,
+            peer_id
+        );
+        self.control_plane_metrics
+            .retry_connection
+            .with_label_values(&[&peer_id.to_string()])
+            .inc();
+
+        let peer_addr = peer_state.peer_addr;
+        let connection_state = if connection_role(&self.node_id, &peer_id) == ConnectionRole::Server
+        {
+            QuicConnectionState::Listening
+        } else {
+            QuicConnectionState::Connecting(Connecting {
+                peer_addr,
+                connecting_task: self.spawn_connect_task(peer_id, peer_addr),
+            })
+        };
+        event_handler
+            .call(TransportEvent::PeerDown(peer_id))
+            .await
+            .expect("Can't panic on infallible");
+        // Drops the connected state, which aborts the data plane tasks (possibly
+        // including the calling one) and closes the connection.
+        peer_state.update(connection_state);
+    }
+}
+
+impl Transport for QuicTransportImpl {
+    fn set_event_handler(&self, event_handler: TransportEventHandler) {
+        // Binding the endpoint requires that we are within a tokio runtime context.
+        let _rt_enter_guard = self.rt_handle.enter();
+        let server_addr = SocketAddr::new(self.node_ip, self.config.listening_port);
+        let (endpoint, incoming) = Endpoint::builder()
+            .bind(&server_addr)
+            .unwrap_or_else(|err| {
+                panic!(
+                    "Failed to bind the QUIC endpoint to {:?}: {:?}",
+                    server_addr, err
+                )
+            });
+        *self.endpoint.blocking_lock() = Some(endpoint);
+        self.refresh_server_config();
+        *self.accept_task.blocking_lock() = Some(self.spawn_accept_task(incoming));
+        *self.event_handler.blocking_lock() = Some(event_handler);
+    }
+
+    fn start_connection(
+        &self,
+        peer_id: &NodeId,
+        peer_addr: SocketAddr,
+        registry_version: RegistryVersion,
+    ) {
+        info!(
+            self.log,
+            "QuicTransport::start_connection(): peer_id = {:?}", peer_id
+        );
+        let role = connection_role(&self.node_id, peer_id);
+        let mut server_config_outdated = false;
+        // If we are the server, we should add the peer to the allowed_clients.
+        if role == ConnectionRole::Server {
+            server_config_outdated |= self.allowed_clients.blocking_write().insert(*peer_id);
+        }
+        {
+            let mut current_registry_version = self.registry_version.blocking_write();
+            server_config_outdated |= *current_registry_version != registry_version;
+            *current_registry_version = registry_version;
+        }
+        if server_config_outdated {
+            self.refresh_server_config();
+        }
+
+        let mut peer_map = self.peer_map.blocking_write();
+        if peer_map.get(peer_id).is_some() {
+            return;
+        }
+        let connection_state = match role {
+            ConnectionRole::Server => QuicConnectionState::Listening,
+            ConnectionRole::Client => QuicConnectionState::Connecting(Connecting {
+                peer_addr,
+                connecting_task: self.spawn_connect_task(*peer_id, peer_addr),
+            }),
+        };
+        let peer_state = QuicPeerState::new(
+            get_peer_label(&peer_addr.ip().to_string(), peer_id),
+            peer_addr,
+            connection_state,
+            &self.config,
+            self.send_queue_metrics.clone(),
+            self.control_plane_metrics.clone(),
+        );
+        peer_map.insert(*peer_id, RwLock::new(peer_state));
+    }
+
+    fn stop_connection(&self, peer_id: &NodeId) {
+        info!(
+            self.log,
+            "QuicTransport::stop_connection(): peer_id = {:?}", peer_id,
+        );
+        if self.allowed_clients.blocking_write().remove(peer_id) {
+            self.refresh_server_config();
+        }
+        self.peer_map.blocking_write().remove(peer_id);
+    }
+
+    fn send(
+        &self,
+        peer_id: &NodeId,
+        channel_id: TransportChannelId,
+        message: TransportPayload,
+    ) -> Result<(), TransportError> {
+        let peer_map = self.peer_map.blocking_read();
+        let peer_state_mu = match peer_map.get(peer_id) {
+            Some(peer_state) => peer_state,
+            None => return Err(TransportError::NotFound),
+        };
+        let peer_state = peer_state_mu.blocking_read();
+        let send_queue = match peer_state.send_queues.get(channel_id.get()) {
+            Some(send_queue) => send_queue,
+            None => return Err(TransportError::NotFound),
+        };
+        match send_queue.enqueue(message) {
+            Some(unsent) => Err(TransportError::SendQueueFull(unsent)),
+            None => Ok(()),
+        }
+    }
+
+    fn clear_send_queues(&self, peer_id: &NodeId) {
+        let peer_map = self.peer_map.blocking_read();
+        let peer_state = peer_map.get(peer_id).expect("Transport client not found");
+        for send_queue in peer_state.blocking_write().send_queues.iter_mut() {
+            send_queue.clear();
+        }
+    }
+}
+
+impl Drop for QuicTransportImpl {
+    fn drop(&mut self) {
+        if let Some(accept_task) = self.accept_task.get_mut().take() {
+            accept_task.abort();
+        }
+    }
+}
+
+/// Returns the QUIC parameters for connections with `max_streams` channels.
+fn quic_transport_config(max_streams: usize) -> quinn::TransportConfig {
+    let mut transport_config = quinn::TransportConfig::default();
+    transport_config
+        .max_concurrent_uni_streams(max_streams as u64)
+        .expect("Invalid number of streams")
+        .max_concurrent_bidi_streams(0)
+        .expect("Invalid number of streams")
+        .stream_receive_window(QUIC_STREAM_RECEIVE_WINDOW)
+        .expect("Invalid stream receive window")
+        .receive_window(QUIC_STREAM_RECEIVE_WINDOW * max_streams as u64)
+        .expect("Invalid connection receive window");
+    transport_config
+}
+
+/// Reads the channel id that the peer writes at the start of each stream.
+async fn read_channel_id(recv_stream: &mut RecvStream) -> Result<usize, String> {
+    let mut buf = [0u8; std::mem::size_of::<u32>()];
+    match tokio::time::timeout(
+        Duration::from_secs(TLS_HANDSHAKE_TIMEOUT_SECONDS),
+        recv_stream.read_exact(&mut buf),
+    )
+    .await
+    {
+        Err(_) => Err("timed out reading the stream header".to_string()),
+        Ok(Ok(())) => Ok(u32::from_le_bytes(buf) as usize),
+        Ok(Err(err)) => Err(format!("{:?}", err)),
+    }
+}
+
+/// Returns the node ID of the authenticated peer of the connection, i.e., the
+/// subject CN of the node certificate it presented in the handshake. The
+/// certificate itself was already verified against the registry by the TLS
+/// configuration.
+fn authenticated_peer(connection: &Connection) -> Result<NodeId, String> {
+    let cert_der = connection
+        .authentication_data()
+        .peer_certificates
+        .and_then(|certs| certs.iter().next().map(|cert| cert.0.clone()))
+        .ok_or_else(|| "The peer did not present a certificate".to_string())?;
+    let cert = TlsPublicKeyCert::new_from_der(cert_der)
+        .map_err(|e| format!("The peer certificate could not be parsed: {:?}", e))?;
+    let mut common_names = cert
+        .as_x509()
+        .subject_name()
+        .entries_by_nid(Nid::COMMONNAME);
+    let common_name = match (common_names.next(), common_names.next()) {
+        (Some(common_name), None) => common_name,
+        _ => return Err("Expected exactly one subject CN".to_string()),
+    };
+    let common_name = common_name
+        .data()
+        .as_utf8()
+        .map_err(|e| format!("ASN1 to UTF-8 conversion error: {}", e))?;
+    PrincipalId::from_str(common_name.as_ref())
+        .map(NodeId::from)
+        .map_err(|e| format!("Principal ID parse error: {}", e))
+}
@@ -0,0 +1,202 @@
+//! Switching transport - Runs the TCP and the QUIC implementations side by
+//! side, so that a subnet can move from one to the other without restarting
+//! the replicas.
+//!
+//! Both implementations listen all the time, on the TCP and the UDP listening
+//! port respectively, but only the active one connects to the peers. When the
+//! active implementation changes, the connections of all peers are stopped on
+//! the previous implementation and started on the new one. All nodes of a
+//! subnet must switch at the same point, so the caller derives the active
+//! implementation from a registry version the nodes agree on, e.g. the one of
+//! the latest CUP.
+
+use ic_base_types::{NodeId, RegistryVersion};
+use ic_interfaces_transport::{
+    Transport, TransportChannelId, TransportError, TransportEventHandler, TransportPayload,
+};
+use ic_logger::{info, ReplicaLogger};
+use std::{
+    collections::HashMap,
+    net::SocketAddr,
+    sync::{Arc, RwLock},
+};
+
+/// Implementation of the `Transport` interface that delegates to either the
+/// TCP or the QUIC implementation.
+pub struct SwitchingTransport {
+    /// The TLS over TCP implementation
+    tcp: Arc<dyn Transport>,
+    /// The QUIC implementation
+    quic: Arc<dyn Transport>,
+    /// The active implementation and the peers
+    state: RwLock<SwitchingState>,
+    /// Logger
+    log: ReplicaLogger,
+}
+
+struct SwitchingState {
+    /// Whether the QUIC implementation is the active one
+    use_quic: bool,
+    /// The peers passed to `start_connection`, with the arguments of the call
+    peers: HashMap<NodeId, (SocketAddr, RegistryVersion)>,
+}
+
+impl SwitchingTransport {
+    pub(crate) fn new(
+        tcp: Arc<dyn Transport>,
+        quic: Arc<dyn Transport>,
+        use_quic: bool,
+        log: ReplicaLogger,
+    ) -> Self {
+        Self {
+            tcp,
+            quic,
+            state: RwLock::new(SwitchingState {
+                use_quic,
+                peers: HashMap::new(),
+            }),
+            log,
+        }
+    }
+
+    /// Returns true if the QUIC implementation is the active one.
+    pub fn uses_quic(&self) -> bool {
+        self.state.read().unwrap().use_quic
+    }
+
+    /// Makes the QUIC implementation the active one if `use_quic` is true, and
+    /// the TCP one otherwise. The connections of all peers are restarted on
+    /// the new implementation, and the transport client receives a `PeerUp`
+    /// event for each peer once the peer switched too.
+    ///
+    /// This method blocks, so it must not be called from an async context.
+    pub fn set_use_quic(&self, use_quic: bool) {
+        let mut state = self.state.write().unwrap();
+        if state.use_quic == use_quic {
+            return;
+        }
+        info!(
+            self.log,
+            "SwitchingTransport::set_use_quic(): switching transport: use_quic = {}", use_quic
+        );
+        let (previous, next) = if use_quic {
+            (&self.tcp, &self.quic)
+        } else {
+            (&self.quic, &self.tcp)
+        };
+        for (peer_id, (peer_addr, registry_version)) in state.peers.iter() {
+            previous.stop_connection(peer_id);
+            next.start_connection(peer_id, *peer_addr, *registry_version);
+        }
+        state.use_quic = use_quic;
+    }
+
+    fn active(&self, state: &SwitchingState) -> &Arc<dyn Transport> {
+        if state.use_quic {
+            &self.quic
+        } else {
+            &self.tcp
+        }
+    }
+}
+
+impl Transport for SwitchingTransport {
+    fn set_event_handler(&self, event_handler: TransportEventHandler) {
+        self.tcp.set_event_handler(event_handler.clone());
+        self.quic.set_event_handler(event_handler);
+    }
+
+    fn start_connection(
+        &self,
+        peer_id: &NodeId,
+        peer_addr: SocketAddr,
+        registry_version: RegistryVersion,
+    ) {
+        let mut state = self.state.write().unwrap();
+        state.peers.insert(*peer_id, (peer_addr, registry_version));
+        self.active(&state)
+            .start_connection(peer_id, peer_addr, registry_version);
+    }
+
+    fn stop_connection(&self, peer_id: &NodeId) {
+        let mut state = self.state.write().unwrap();
+        state.peers.remove(peer_id);
+        self.active(&state).stop_connection(peer_id);
+    }
+
+    fn send(
+        &self,
+        peer_id: &NodeId,
+        channel_id: TransportChannelId,
+        message: TransportPayload,
+    ) -> Result<(), TransportError> {
+        let state = self.state.read().unwrap();
+        self.active(&state).send(peer_id, channel_id, message)
+    }
+
+    fn clear_send_queues(&self, peer_id: &NodeId) {
+        let state = self.state.read().unwrap();
+        self.active(&state).clear_send_queues(peer_id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ic_base_types::PrincipalId;
+    use ic_interfaces_transport_mocks::MockTransport;
+    use ic_test_utilities_logger::with_test_replica_logger;
+    use mockall::predicate::eq;
+
+    fn node_test_id(id: u64) -> NodeId {
+        NodeId::from(PrincipalId::new_node_test_id(id))
+    }
+
+    #[test]
+    fn switching_restarts_connections_on_the_new_transport() {
+        with_test_replica_logger(|log| {
+            let peer_id = node_test_id(1);
+            let peer_addr = SocketAddr::from(([127, 0, 0, 1], 4100));
+            let registry_version = RegistryVersion::from(3);
+
+            let mut tcp = MockTransport::new();
+            tcp.expect_start_connection()
+                .with(eq(peer_id), eq(peer_addr), eq(registry_version))
+                .times(1)
+                .return_const(());
+            tcp.expect_stop_connection()
+                .with(eq(peer_id))
+                .times(1)
+                .return_const(());
+            tcp.expect_send().never();
+            let mut quic = MockTransport::new();
+            quic.expect_start_connection()
+                .with(eq(peer_id), eq(peer_addr), eq(registry_version))
+                .times(1)
+                .return_const(());
+            quic.expect_send()
+                .with(
+                    eq(peer_id),
+                    eq(TransportChannelId::from(2)),
+                    eq(TransportPayload(vec![])),
+                )
+                .times(1)
+                .returning(|_, _, _| Ok(()));
+
+            let transport = SwitchingTransport::new(Arc::new(tcp), Arc::new(quic), false, log);
+            transport.start_connection(&peer_id, peer_addr, registry_version);
+            transport.set_use_quic(true);
+            assert!(transport.uses_quic());
+            // Switching to the active transport again is a no-op.
+            transport.set_use_quic(true);
+            assert_eq!(
+                transport.send(
+                    &peer_id,
+                    TransportChannelId::from(2),
+                    TransportPayload(vec![])
+                ),
+                Ok(())
+            );
+        });
+    }
+}
@@ -12,7 +12,7 @@ use ic_metrics::MetricsRegistry;
 use ic_registry_client_fake::FakeRegistryClient;
 use ic_registry_keys::make_crypto_tls_cert_key;
 use ic_registry_proto_data_provider::ProtoRegistryDataProvider;
-use ic_transport::transport::create_transport;
+use ic_transport::transport::{create_quic_transport, create_transport};
 use ic_types_test_utils::ids::{NODE_1, NODE_2, NODE_3, NODE_4};
 use std::{collections::HashMap, convert::Infallible, net::SocketAddr, str::FromStr, sync::Arc};
 use tokio::{net::TcpSocket, task::JoinHandle};
@@ -168,6 +168,8 @@ pub struct TestPeerBuilder {
     send_queue_size: usize,
     crypto: Option<Arc<dyn TlsHandshake + Send + Sync>>,
     h2: bool,
+    quic: bool,
+    max_streams: usize,
     registry_version: RegistryVersion,
 }
 
@@ -193,6 +195,8 @@ impl TestPeerBuilder {
             send_queue_size: 51200,
             crypto: None,
             h2: false,
+            quic: false,
+            max_streams: 1,
             registry_version: REG_V1,
         }
     }
@@ -200,6 +204,16 @@ impl TestPeerBuilder {
         self.h2 = use_h2;
         self
     }
+    /// Uses the QUIC transport. The peer then always uses a temp crypto
+    /// component, a crypto set with `crypto()` is ignored.
+    pub fn quic(mut self, use_quic: bool) -> Self {
+        self.quic = use_quic;
+        self
+    }
+    pub fn max_streams(mut self, n: usize) -> Self {
+        self.max_streams = n;
+        self
+    }
     pub fn send_queue_size(mut self, n: usize) -> Self {
         self.send_queue_size = n;
         self
@@ -213,12 +227,6 @@ impl TestPeerBuilder {
         self
     }
     pub fn build(self) -> TestPeer {
-        let crypto = self.crypto.unwrap_or_else(|| {
-            let crypto =
-                temp_crypto_component_with_tls_keys_in_registry(&self.registry_data, self.node_id);
-            Arc::new(crypto)
-        });
-
         let (event_handler, handle) = create_mock_event_handler();
 
         let listening_port = get_free_localhost_port().expect("Failed to get free localhost port");
@@ -226,19 +234,40 @@ impl TestPeerBuilder {
             node_ip: "127.0.0.1".to_string(),
             listening_port,
             send_queue_size: self.send_queue_size,
-            ..Default::default()
+            max_streams: self.max_streams,
         };
 
-        let transport = create_transport(
-            self.node_id,
-            node_config,
-            self.registry_version,
-            MetricsRegistry::new(),
-            crypto,
-            self.rt_handle,
-            self.log,
-            self.h2,
-        );
+        let transport = if self.quic {
+            let crypto =
+                temp_crypto_component_with_tls_keys_in_registry(&self.registry_data, self.node_id);
+            create_quic_transport(
+                self.node_id,
+                node_config,
+                self.registry_version,
+                MetricsRegistry::new(),
+                Arc::new(crypto),
+                self.rt_handle,
+                self.log,
+            )
+        } else {
+            let crypto = self.crypto.unwrap_or_else(|| {
+                let crypto = temp_crypto_component_with_tls_keys_in_registry(
+                    &self.registry_data,
+                    self.node_id,
+                );
+                Arc::new(crypto)
+            });
+            create_transport(
+                self.node_id,
+                node_config,
+                self.registry_version,
+                MetricsRegistry::new(),
+                crypto,
+                self.rt_handle,
+                self.log,
+                self.h2,
+            )
+        };
         transport.set_event_handler(event_handler);
 
         TestPeer {
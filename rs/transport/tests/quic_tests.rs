@@ -0,0 +1,247 @@
+use futures::{future::BoxFuture, FutureExt};
+use ic_interfaces_transport::{
+    TransportChannelId, TransportError, TransportEvent, TransportPayload,
+};
+use ic_test_utilities_logger::with_test_replica_logger;
+use ic_transport_test_utils::{
+    basic_transport_message, large_transport_message, RegistryAndDataProvider, TestPeerBuilder,
+    TestTopologyBuilder, NODE_ID_1, NODE_ID_2, TRANSPORT_CHANNEL_ID,
+};
+use std::sync::Arc;
+use tokio::sync::{
+    mpsc::{channel, Sender},
+    Barrier,
+};
+use tower_test::mock::Handle;
+
+// Returns event handler expectations that wait for the PeerUp event on
+// `peer_up` and then forward all received messages to `sender`.
+fn forward_messages(
+    peer_up: Arc<Barrier>,
+    sender: Sender<(TransportChannelId, TransportPayload)>,
+) -> impl FnOnce(Handle<TransportEvent, ()>) -> BoxFuture<'static, ()> + Send {
+    |mut handle: Handle<TransportEvent, ()>| {
+        async move {
+            match handle.next_request().await {
+                Some((TransportEvent::PeerUp(_), resp)) => {
+                    resp.send_response(());
+                }
+                e => panic!("Unexpected event {:?}", e),
+            }
+            peer_up.wait().await;
+            while let Some((event, resp)) = handle.next_request().await {
+                let payload = match event {
+                    TransportEvent::Message(msg) => msg.payload,
+                    e => panic!("Unexpected event {:?}", e),
+                };
+                // Events do not carry the channel, so the tests tag each
+                // payload with the channel it is sent on.
+                let channel_id = TransportChannelId::from(payload.0[0] as usize);
+                sender
+                    .send((channel_id, payload))
+                    .await
+                    .expect("Channel busy");
+                resp.send_response(());
+            }
+        }
+        .boxed()
+    }
+}
+
+// Test scenario: Two peers connect to each other over QUIC, later one of them
+// disconnects.
+// Test expectation: Each peer should receive a PeerUp event, the peer that
+// didn't issue the 'stop_connection' should receive a PeerDown event.
+#[test]
+fn test_basic_conn_quic() {
+    with_test_replica_logger(|logger| {
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        let registry_data = RegistryAndDataProvider::new();
+
+        let wait_after_peer_up = Arc::new(Barrier::new(3));
+
+        let w1 = wait_after_peer_up.clone();
+        let peer1_expectations = |mut handle: Handle<TransportEvent, ()>| {
+            async move {
+                match handle.next_request().await {
+                    Some((TransportEvent::PeerUp(_), resp)) => {
+                        resp.send_response(());
+                    }
+                    e => panic!("Unexpected event {:?}", e),
+                }
+                w1.wait().await;
+                match handle.next_request().await {
+                    Some((TransportEvent::PeerDown(_), resp)) => {
+                        resp.send_response(());
+                    }
+                    e => panic!("Unexpected event {:?}", e),
+                }
+            }
+            .boxed()
+        };
+        let w2 = wait_after_peer_up.clone();
+        let peer2_expectations = |mut handle: Handle<TransportEvent, ()>| {
+            async move {
+                match handle.next_request().await {
+                    Some((TransportEvent::PeerUp(_), resp)) => {
+                        resp.send_response(());
+                    }
+                    e => panic!("Unexpected event {:?}", e),
+                }
+                w2.wait().await;
+            }
+            .boxed()
+        };
+
+        let peer1 = TestPeerBuilder::new(
+            NODE_ID_1,
+            rt.handle().clone(),
+            registry_data.clone(),
+            logger.clone(),
+        )
+        .quic(true)
+        .build();
+        let peer2 = TestPeerBuilder::new(
+            NODE_ID_2,
+            rt.handle().clone(),
+            registry_data.clone(),
+            logger,
+        )
+        .quic(true)
+        .build();
+
+        let mut test_transport = TestTopologyBuilder::new(registry_data, rt.handle().clone())
+            .add_node(peer1, peer1_expectations)
+            .add_node(peer2, peer2_expectations)
+            .full_mesh();
+
+        // Wait for PeerUp events to make sure we are connected and do not stop a connection
+        // that is not yet established.
+        rt.block_on(wait_after_peer_up.wait());
+
+        test_transport.stop_peer_connection(NODE_ID_2, NODE_ID_1);
+
+        test_transport.verify_all_peers_down();
+    });
+}
+
+// StateSync may send chunks that are 100 MB big so we want to make sure a
+// message of this size can be sent and received in both directions.
+#[test]
+fn test_send_big_message_quic() {
+    with_test_replica_logger(|logger| {
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        let registry_data = RegistryAndDataProvider::new();
+        let peer_up = Arc::new(Barrier::new(3));
+
+        let (peer_1_sender, mut peer_1_receiver) = channel(1);
+        let (peer_2_sender, mut peer_2_receiver) = channel(1);
+        let peer1 = TestPeerBuilder::new(
+            NODE_ID_1,
+            rt.handle().clone(),
+            registry_data.clone(),
+            logger.clone(),
+        )
+        .quic(true)
+        .build();
+        let peer2 = TestPeerBuilder::new(
+            NODE_ID_2,
+            rt.handle().clone(),
+            registry_data.clone(),
+            logger,
+        )
+        .quic(true)
+        .build();
+
+        let test_transport = TestTopologyBuilder::new(registry_data, rt.handle().clone())
+            .add_node(peer1, forward_messages(peer_up.clone(), peer_1_sender))
+            .add_node(peer2, forward_messages(peer_up.clone(), peer_2_sender))
+            .full_mesh();
+        rt.block_on(peer_up.wait());
+
+        let channel_id = TransportChannelId::from(TRANSPORT_CHANNEL_ID);
+        let mut message = large_transport_message();
+        message.0[0] = TRANSPORT_CHANNEL_ID as u8;
+
+        // 1 sends message to 2
+        let res = test_transport.send_payload(NODE_ID_1, NODE_ID_2, channel_id, message.clone());
+        assert_eq!(res, Ok(()));
+        assert_eq!(
+            peer_2_receiver.blocking_recv(),
+            Some((channel_id, message.clone()))
+        );
+
+        // 2 sends message to 1
+        let res = test_transport.send_payload(NODE_ID_2, NODE_ID_1, channel_id, message.clone());
+        assert_eq!(res, Ok(()));
+        assert_eq!(peer_1_receiver.blocking_recv(), Some((channel_id, message)));
+    });
+}
+
+// Test scenario: Two peers are connected with two channels, one of them sends
+// a message on each channel.
+// Test expectation: All messages arrive and sending on a channel that does not
+// exist fails.
+#[test]
+fn test_send_on_multiple_channels_quic() {
+    with_test_replica_logger(|logger| {
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        let registry_data = RegistryAndDataProvider::new();
+        let peer_up = Arc::new(Barrier::new(3));
+        let max_streams = 2;
+
+        let (peer_1_sender, _peer_1_receiver) = channel(max_streams);
+        let (peer_2_sender, mut peer_2_receiver) = channel(max_streams);
+        let peer1 = TestPeerBuilder::new(
+            NODE_ID_1,
+            rt.handle().clone(),
+            registry_data.clone(),
+            logger.clone(),
+        )
+        .quic(true)
+        .max_streams(max_streams)
+        .build();
+        let peer2 = TestPeerBuilder::new(
+            NODE_ID_2,
+            rt.handle().clone(),
+            registry_data.clone(),
+            logger,
+        )
+        .quic(true)
+        .max_streams(max_streams)
+        .build();
+
+        let test_transport = TestTopologyBuilder::new(registry_data, rt.handle().clone())
+            .add_node(peer1, forward_messages(peer_up.clone(), peer_1_sender))
+            .add_node(peer2, forward_messages(peer_up.clone(), peer_2_sender))
+            .full_mesh();
+        rt.block_on(peer_up.wait());
+
+        let mut received = Vec::new();
+        for channel in 0..max_streams {
+            let mut message = basic_transport_message();
+            message.0[0] = channel as u8;
+            let channel_id = TransportChannelId::from(channel);
+            let res = test_transport.send_payload(NODE_ID_1, NODE_ID_2, channel_id, message);
+            assert_eq!(res, Ok(()));
+        }
+        for _ in 0..max_streams {
+            received.push(peer_2_receiver.blocking_recv().unwrap().0);
+        }
+        received.sort();
+        assert_eq!(
+            received,
+            (0..max_streams)
+                .map(TransportChannelId::from)
+                .collect::<Vec<_>>()
+        );
+
+        let res = test_transport.send_payload(
+            NODE_ID_1,
+            NODE_ID_2,
+            TransportChannelId::from(max_streams),
+            basic_transport_message(),
+        );
+        assert_eq!(res, Err(TransportError::NotFound));
+    });
+}
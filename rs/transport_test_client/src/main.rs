@@ -17,9 +17,11 @@
 /// cargo run --bin transport_client --
 ///     --node <node_id>
 ///     --message_count <count>
+///     [--quic]
 ///
 /// If not specified, message_count = 100 (default, applies only for the source
-/// node)
+/// node). With --quic, the QUIC transport is used instead of TLS over TCP (all
+/// nodes must agree on it).
 use clap::{Arg, ArgMatches, Command};
 use crossbeam_channel::{self, Receiver, RecvTimeoutError, Sender};
 use rand::Rng;
@@ -44,7 +46,7 @@ use ic_interfaces_transport::{
 };
 use ic_logger::{info, warn, LoggerImpl, ReplicaLogger};
 use ic_metrics::MetricsRegistry;
-use ic_transport::transport::create_transport;
+use ic_transport::transport::{create_quic_transport, create_transport};
 use ic_types::{NodeId, PrincipalId, RegistryVersion, SubnetId};
 use std::path::PathBuf;
 use std::str::FromStr;
@@ -65,6 +67,7 @@ type MpscReceiver = Receiver<TestMessage>;
 
 const ARG_NODE_ID: &str = "node";
 const ARG_MSG_COUNT: &str = "count";
+const ARG_QUIC: &str = "quic";
 
 const REG_V1: RegistryVersion = RegistryVersion::new(1);
 const SUBNET_ID: u8 = 100;
@@ -398,6 +401,11 @@ fn cmd_line_matches() -> ArgMatches {
                 .default_value("100")
                 .takes_value(true),
         )
+        .arg(
+            Arg::new(ARG_QUIC)
+                .long("quic")
+                .help("Use the QUIC transport instead of TLS over TCP"),
+        )
         .get_matches()
 }
 
@@ -484,6 +492,7 @@ fn task_main(
     node_id_val: u8,
     message_count: usize,
     active_flag: Arc<AtomicBool>,
+    use_quic: bool,
 ) -> Result<(), TestClientErrorCode> {
     let v: Vec<u8> = vec![SUBNET_ID];
     let subnet_id = SubnetId::from(PrincipalId::try_from(v.as_slice()).unwrap());
@@ -514,7 +523,13 @@ fn task_main(
 
     println!("creating crypto... [Node: {}]", node_id_val);
     let registry_version = REG_V1;
-    let crypto = match create_crypto(node_number, 3, node_id, registry_version) {
+    // Keep the certificates of concurrent TCP and QUIC runs apart.
+    let cert_file_prefix = if use_quic {
+        "quic_tls_pubkey_cert"
+    } else {
+        "tls_pubkey_cert"
+    };
+    let crypto = match create_crypto(node_number, 3, node_id, registry_version, cert_file_prefix) {
         Ok(crypto) => crypto,
         Err(_) => {
             panic!("unable to create crypto");
@@ -523,16 +538,28 @@ fn task_main(
 
     println!("starting transport...");
     println!("starting transport... [Node: {}]", node_id_val);
-    let transport = create_transport(
-        node_id,
-        config_and_records.config.clone(),
-        registry_version,
-        MetricsRegistry::new(),
-        crypto,
-        rt.handle().clone(),
-        log.clone(),
-        false,
-    );
+    let transport = if use_quic {
+        create_quic_transport(
+            node_id,
+            config_and_records.config.clone(),
+            registry_version,
+            MetricsRegistry::new(),
+            crypto,
+            rt.handle().clone(),
+            log.clone(),
+        )
+    } else {
+        create_transport(
+            node_id,
+            config_and_records.config.clone(),
+            registry_version,
+            MetricsRegistry::new(),
+            crypto,
+            rt.handle().clone(),
+            log.clone(),
+            false,
+        )
+    };
 
     println!("starting test client... [Node: {}]", node_id_val);
     let test_client = TestClient::new(
@@ -583,7 +610,14 @@ fn main() {
         .unwrap()
         .parse::<usize>()
         .unwrap();
-    task_main(node_id_val, message_count, Arc::new(AtomicBool::new(true))).unwrap()
+    let use_quic = matches.is_present(ARG_QUIC);
+    task_main(
+        node_id_val,
+        message_count,
+        Arc::new(AtomicBool::new(true)),
+        use_quic,
+    )
+    .unwrap()
 }
 
 #[cfg(test)]
@@ -593,13 +627,24 @@ const TEST_MESSAGE_COUNT: usize = 10;
 
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 async fn test_transport_spawn_tasks() {
+    spawn_tasks_and_wait(false);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_quic_transport_spawn_tasks() {
+    spawn_tasks_and_wait(true);
+}
+
+#[cfg(test)]
+fn spawn_tasks_and_wait(use_quic: bool) {
     let active_flag = Arc::new(AtomicBool::new(true));
     let mut handles = Vec::new();
 
     // Spawn tokio tasks
     for node_id in 1..(TEST_NODE_COUNT + 1) {
         let flag = active_flag.clone();
-        let handle = std::thread::spawn(move || task_main(node_id, TEST_MESSAGE_COUNT, flag));
+        let handle =
+            std::thread::spawn(move || task_main(node_id, TEST_MESSAGE_COUNT, flag, use_quic));
         handles.push(handle);
     }
 
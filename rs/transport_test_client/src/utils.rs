@@ -1,7 +1,6 @@
 //! Helper functionality for the tests
 
 use ic_crypto_temp_crypto::{NodeKeysToGenerate, TempCryptoComponent};
-use ic_crypto_tls_interfaces::TlsHandshake;
 use ic_registry_client_fake::FakeRegistryClient;
 use ic_registry_keys::make_crypto_tls_cert_key;
 use ic_registry_proto_data_provider::ProtoRegistryDataProvider;
@@ -28,10 +27,11 @@ pub fn create_crypto(
     nodes: usize,
     node_id: NodeId,
     registry_version: RegistryVersion,
-) -> Result<Arc<dyn TlsHandshake + Send + Sync>> {
+    cert_file_prefix: &str,
+) -> Result<Arc<TempCryptoComponent>> {
     if node_index == 1 {
         for i in 1..(nodes + 1) {
-            let filename = format!("tls_pubkey_cert.{}", i);
+            let filename = format!("{}.{}", cert_file_prefix, i);
             if fs::remove_file(filename).is_ok() {
                 println!("removing {}", node_id);
             }
@@ -47,7 +47,7 @@ pub fn create_crypto(
         .build();
     let tls_pubkey_cert = crypto.node_tls_public_key_certificate();
     {
-        let filename = format!("tls_pubkey_cert.{}", node_index);
+        let filename = format!("{}.{}", cert_file_prefix, node_index);
         println!("writing {}", filename);
         let mut file = fs::File::create(filename).expect("write tls cert");
         file.write_all(tls_pubkey_cert.as_der())?;
@@ -71,7 +71,7 @@ pub fn create_crypto(
             if done[i] {
                 continue;
             }
-            let filename = format!("tls_pubkey_cert.{}", i);
+            let filename = format!("{}.{}", cert_file_prefix, i);
             if let Ok(mut file) = fs::File::open(filename.clone()) {
                 let mut contents = vec![];
                 file.read_to_end(&mut contents)?;
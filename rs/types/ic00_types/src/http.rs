@@ -57,12 +57,13 @@ pub struct TransformContext {
 //     url : text;
 //     max_response_bytes: opt nat64;
 //     headers : vec http_header;
-//     method : variant { get; head; post };
+//     method : variant { get; head; post; put; delete; patch };
 //     body : opt blob;
 //     transform : opt record {
 //       function : func (record {response : http_response; context : blob}) -> (http_response) query;
 //       context : blob;
 //     };
+//     is_replicated : opt bool;
 //   })`
 #[derive(CandidType, Deserialize, Debug, Clone)]
 pub struct CanisterHttpRequestArgs {
@@ -72,6 +73,10 @@ pub struct CanisterHttpRequestArgs {
     pub body: Option<Vec<u8>>,
     pub method: HttpMethod,
     pub transform: Option<TransformContext>,
+    /// Whether the request is made by all replicas of the subnet and the
+    /// response goes through consensus (the default), or whether it is made
+    /// by a single replica whose response is returned without consensus.
+    pub is_replicated: Option<bool>,
 }
 
 impl Payload<'_> for CanisterHttpRequestArgs {}
@@ -84,6 +89,12 @@ impl CanisterHttpRequestArgs {
             .as_ref()
             .map(|transform_context| PrincipalId::from(transform_context.function.0.principal))
     }
+
+    /// Returns false if the caller explicitly asked for a non-replicated
+    /// request, true otherwise.
+    pub fn is_replicated(&self) -> bool {
+        self.is_replicated.unwrap_or(true)
+    }
 }
 
 /// Struct used for encoding/decoding
@@ -107,6 +118,12 @@ pub enum HttpMethod {
     POST,
     #[serde(rename = "head")]
     HEAD,
+    #[serde(rename = "put")]
+    PUT,
+    #[serde(rename = "delete")]
+    DELETE,
+    #[serde(rename = "patch")]
+    PATCH,
 }
 
 /// Represents the response for a canister http request.
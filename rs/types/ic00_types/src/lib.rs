@@ -7,7 +7,11 @@ use ic_base_types::{CanisterId, NodeId, NumBytes, PrincipalId, RegistryVersion,
 use ic_error_types::{ErrorCode, UserError};
 use ic_protobuf::registry::crypto::v1::PublicKey;
 use ic_protobuf::registry::subnet::v1::{InitialIDkgDealings, InitialNiDkgTranscriptRecord};
-use ic_protobuf::{proxy::ProxyDecodeError, registry::crypto::v1 as pb_registry_crypto};
+use ic_protobuf::state::canister_state_bits::v1 as pb_canister_state_bits;
+use ic_protobuf::{
+    proxy::{try_from_option_field, ProxyDecodeError},
+    registry::crypto::v1 as pb_registry_crypto,
+};
 use num_traits::cast::ToPrimitive;
 use serde::Serialize;
 use std::{collections::BTreeSet, convert::TryFrom, fmt, slice::Iter, str::FromStr};
@@ -26,6 +30,7 @@ pub use provisional::{ProvisionalCreateCanisterWithCyclesArgs, ProvisionalTopUpC
 #[derive(Debug, EnumString, EnumIter, Display, Copy, Clone)]
 #[strum(serialize_all = "snake_case")]
 pub enum Method {
+    CanisterInfo,
     CanisterStatus,
     CreateCanister,
     DeleteCanister,
@@ -38,6 +43,12 @@ pub enum Method {
     SetController,
     SetupInitialDKG,
     SignWithECDSA,
+    SchnorrPublicKey,
+    SignWithSchnorr,
+    #[strum(serialize = "vetkd_public_key")]
+    VetKdPublicKey,
+    #[strum(serialize = "vetkd_encrypted_key")]
+    VetKdEncryptedKey,
     StartCanister,
     StopCanister,
     UninstallCode,
@@ -57,6 +68,21 @@ pub enum Method {
     // They should be removed afterwards.
     ProvisionalCreateCanisterWithCycles,
     ProvisionalTopUpCanister,
+
+    // Canister snapshots.
+    TakeCanisterSnapshot,
+    LoadCanisterSnapshot,
+    ListCanisterSnapshots,
+    DeleteCanisterSnapshot,
+
+    // Chunked Wasm module upload.
+    UploadChunk,
+    ClearChunkStore,
+    StoredChunks,
+    InstallChunkedCode,
+
+    // Canister logging.
+    FetchCanisterLogs,
 }
 
 /// A trait to be implemented by all structs that are used as payloads
@@ -122,6 +148,138 @@ impl UninstallCodeArgs {
 
 impl Payload<'_> for UninstallCodeArgs {}
 
+/// Struct used for encoding/decoding
+/// `(record {
+///     canister_id: principal;
+///     replace_snapshot: opt blob;
+/// })`
+#[derive(CandidType, Serialize, Deserialize, Debug)]
+pub struct TakeCanisterSnapshotArgs {
+    canister_id: PrincipalId,
+    replace_snapshot: Option<Vec<u8>>,
+}
+
+impl TakeCanisterSnapshotArgs {
+    pub fn new(canister_id: CanisterId, replace_snapshot: Option<Vec<u8>>) -> Self {
+        Self {
+            canister_id: canister_id.into(),
+            replace_snapshot,
+        }
+    }
+
+    pub fn get_canister_id(&self) -> CanisterId {
+        // Safe as this was converted from CanisterId when Self was constructed.
+        CanisterId::new(self.canister_id).unwrap()
+    }
+
+    pub fn replace_snapshot(&self) -> Option<&[u8]> {
+        self.replace_snapshot.as_deref()
+    }
+}
+
+impl Payload<'_> for TakeCanisterSnapshotArgs {}
+
+/// Struct used for encoding/decoding
+/// `(record {
+///     canister_id: principal;
+///     snapshot_id: blob;
+///     sender_canister_version: opt nat64;
+/// })`
+#[derive(CandidType, Serialize, Deserialize, Debug)]
+pub struct LoadCanisterSnapshotArgs {
+    canister_id: PrincipalId,
+    snapshot_id: Vec<u8>,
+    sender_canister_version: Option<u64>,
+}
+
+impl LoadCanisterSnapshotArgs {
+    pub fn new(
+        canister_id: CanisterId,
+        snapshot_id: Vec<u8>,
+        sender_canister_version: Option<u64>,
+    ) -> Self {
+        Self {
+            canister_id: canister_id.into(),
+            snapshot_id,
+            sender_canister_version,
+        }
+    }
+
+    pub fn get_canister_id(&self) -> CanisterId {
+        // Safe as this was converted from CanisterId when Self was constructed.
+        CanisterId::new(self.canister_id).unwrap()
+    }
+
+    pub fn snapshot_id(&self) -> &[u8] {
+        &self.snapshot_id
+    }
+
+    pub fn get_sender_canister_version(&self) -> Option<u64> {
+        self.sender_canister_version
+    }
+}
+
+impl Payload<'_> for LoadCanisterSnapshotArgs {}
+
+/// Struct used for encoding/decoding
+/// `(record {
+///     canister_id: principal;
+///     snapshot_id: blob;
+/// })`
+#[derive(CandidType, Serialize, Deserialize, Debug)]
+pub struct DeleteCanisterSnapshotArgs {
+    canister_id: PrincipalId,
+    snapshot_id: Vec<u8>,
+}
+
+impl DeleteCanisterSnapshotArgs {
+    pub fn new(canister_id: CanisterId, snapshot_id: Vec<u8>) -> Self {
+        Self {
+            canister_id: canister_id.into(),
+            snapshot_id,
+        }
+    }
+
+    pub fn get_canister_id(&self) -> CanisterId {
+        // Safe as this was converted from CanisterId when Self was constructed.
+        CanisterId::new(self.canister_id).unwrap()
+    }
+
+    pub fn snapshot_id(&self) -> &[u8] {
+        &self.snapshot_id
+    }
+}
+
+impl Payload<'_> for DeleteCanisterSnapshotArgs {}
+
+/// Struct used for encoding/decoding
+/// `(record {
+///     id: blob;
+///     taken_at_timestamp: nat64;
+///     total_size: nat64;
+/// })`
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct CanisterSnapshotResponse {
+    pub id: Vec<u8>,
+    pub taken_at_timestamp: u64,
+    pub total_size: u64,
+}
+
+impl Payload<'_> for CanisterSnapshotResponse {}
+
+#[test]
+fn canister_snapshot_response_round_trip() {
+    let response = CanisterSnapshotResponse {
+        id: vec![1, 2, 3],
+        taken_at_timestamp: 42,
+        total_size: 1024,
+    };
+    assert_eq!(
+        CanisterSnapshotResponse::decode(&response.encode()).unwrap(),
+        response
+    );
+}
+
 /// Struct used for encoding/decoding
 /// `(record {
 ///     controller : principal;
@@ -403,6 +561,36 @@ impl From<CanisterInstallMode> for String {
     }
 }
 
+impl From<&CanisterInstallMode> for pb_canister_state_bits::CanisterInstallMode {
+    fn from(item: &CanisterInstallMode) -> Self {
+        match item {
+            CanisterInstallMode::Install => pb_canister_state_bits::CanisterInstallMode::Install,
+            CanisterInstallMode::Reinstall => {
+                pb_canister_state_bits::CanisterInstallMode::Reinstall
+            }
+            CanisterInstallMode::Upgrade => pb_canister_state_bits::CanisterInstallMode::Upgrade,
+        }
+    }
+}
+
+impl TryFrom<pb_canister_state_bits::CanisterInstallMode> for CanisterInstallMode {
+    type Error = ProxyDecodeError;
+
+    fn try_from(item: pb_canister_state_bits::CanisterInstallMode) -> Result<Self, Self::Error> {
+        match item {
+            pb_canister_state_bits::CanisterInstallMode::Install => Ok(Self::Install),
+            pb_canister_state_bits::CanisterInstallMode::Reinstall => Ok(Self::Reinstall),
+            pb_canister_state_bits::CanisterInstallMode::Upgrade => Ok(Self::Upgrade),
+            pb_canister_state_bits::CanisterInstallMode::Unspecified => {
+                Err(ProxyDecodeError::ValueOutOfRange {
+                    typ: "CanisterInstallMode",
+                    err: format!("Unable to convert {:?} to a CanisterInstallMode", item),
+                })
+            }
+        }
+    }
+}
+
 impl Payload<'_> for CanisterStatusResultV2 {}
 
 /// Struct used for encoding/decoding
@@ -497,38 +685,23 @@ impl InstallCodeArgs {
     }
 }
 
-/// Represents the empty blob.
-#[derive(CandidType, Deserialize)]
-pub struct EmptyBlob;
-
-impl<'a> Payload<'a> for EmptyBlob {
-    fn encode(&self) -> Vec<u8> {
-        Encode!().unwrap()
-    }
-
-    fn decode(blob: &'a [u8]) -> Result<EmptyBlob, candid::Error> {
-        Decode!(blob).map(|_| EmptyBlob)
-    }
-}
-
 /// Struct used for encoding/decoding
 /// `(record {
-///     canister_id : principal;
-///     settings: canister_settings;
+///     canister_id: principal;
+///     chunk: blob;
 /// })`
-#[derive(CandidType, Deserialize)]
-pub struct UpdateSettingsArgs {
-    pub canister_id: PrincipalId,
-    pub settings: CanisterSettingsArgs,
-    pub sender_canister_version: Option<u64>,
+#[derive(CandidType, Serialize, Deserialize, Debug)]
+pub struct UploadChunkArgs {
+    canister_id: PrincipalId,
+    #[serde(with = "serde_bytes")]
+    chunk: Vec<u8>,
 }
 
-impl UpdateSettingsArgs {
-    pub fn new(canister_id: CanisterId, settings: CanisterSettingsArgs) -> Self {
+impl UploadChunkArgs {
+    pub fn new(canister_id: CanisterId, chunk: Vec<u8>) -> Self {
         Self {
             canister_id: canister_id.into(),
-            settings,
-            sender_canister_version: None,
+            chunk,
         }
     }
 
@@ -537,302 +710,1402 @@ impl UpdateSettingsArgs {
         CanisterId::new(self.canister_id).unwrap()
     }
 
-    pub fn get_sender_canister_version(&self) -> Option<u64> {
-        self.sender_canister_version
+    pub fn take_chunk(self) -> Vec<u8> {
+        self.chunk
     }
 }
 
-impl Payload<'_> for UpdateSettingsArgs {}
+impl Payload<'_> for UploadChunkArgs {}
+
+/// Struct used for encoding/decoding `(record {hash: blob})`.
+///
+/// It is the reply of `upload_chunk` and the element type of the reply of
+/// `stored_chunks` and of the chunk list of `install_chunked_code`.
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ChunkHash {
+    #[serde(with = "serde_bytes")]
+    pub hash: Vec<u8>,
+}
+
+impl Payload<'_> for ChunkHash {}
 
 /// Struct used for encoding/decoding
 /// `(record {
-///     controller: opt principal;
-///     controllers: opt vec principal;
-///     compute_allocation: opt nat;
-///     memory_allocation: opt nat;
+///     mode : variant { install; reinstall; upgrade };
+///     target_canister: principal;
+///     store_canister: opt principal;
+///     chunk_hashes_list: vec record {hash: blob};
+///     wasm_module_hash: blob;
+///     arg: blob;
+///     sender_canister_version : opt nat64;
 /// })`
-#[derive(Default, Clone, CandidType, Deserialize, Debug)]
-pub struct CanisterSettingsArgs {
-    /// The field controller is deprecated and should not be used in new code.
-    controller: Option<PrincipalId>,
-    pub controllers: Option<Vec<PrincipalId>>,
-    pub compute_allocation: Option<candid::Nat>,
-    pub memory_allocation: Option<candid::Nat>,
-    pub freezing_threshold: Option<candid::Nat>,
+#[derive(CandidType, Serialize, Deserialize, Debug)]
+pub struct InstallChunkedCodeArgs {
+    pub mode: CanisterInstallMode,
+    target_canister: PrincipalId,
+    store_canister: Option<PrincipalId>,
+    pub chunk_hashes_list: Vec<ChunkHash>,
+    #[serde(with = "serde_bytes")]
+    pub wasm_module_hash: Vec<u8>,
+    #[serde(with = "serde_bytes")]
+    pub arg: Vec<u8>,
+    sender_canister_version: Option<u64>,
 }
 
-impl Payload<'_> for CanisterSettingsArgs {}
-
-impl CanisterSettingsArgs {
+impl InstallChunkedCodeArgs {
     pub fn new(
-        controllers: Option<Vec<PrincipalId>>,
-        compute_allocation: Option<u64>,
-        memory_allocation: Option<u64>,
-        freezing_threshold: Option<u64>,
+        mode: CanisterInstallMode,
+        target_canister: CanisterId,
+        store_canister: Option<CanisterId>,
+        chunk_hashes_list: Vec<Vec<u8>>,
+        wasm_module_hash: Vec<u8>,
+        arg: Vec<u8>,
     ) -> Self {
         Self {
-            controller: None,
-            controllers,
-            compute_allocation: compute_allocation.map(candid::Nat::from),
-            memory_allocation: memory_allocation.map(candid::Nat::from),
-            freezing_threshold: freezing_threshold.map(candid::Nat::from),
+            mode,
+            target_canister: target_canister.into(),
+            store_canister: store_canister.map(|canister_id| canister_id.into()),
+            chunk_hashes_list: chunk_hashes_list
+                .into_iter()
+                .map(|hash| ChunkHash { hash })
+                .collect(),
+            wasm_module_hash,
+            arg,
+            sender_canister_version: None,
         }
     }
 
-    pub fn get_controller(&self) -> Option<PrincipalId> {
-        self.controller
+    pub fn target_canister_id(&self) -> CanisterId {
+        // Safe as this was converted from CanisterId when Self was constructed.
+        CanisterId::new(self.target_canister).unwrap()
+    }
+
+    /// Returns the canister whose chunk store holds the chunks. Defaults to
+    /// the target canister.
+    pub fn store_canister_id(&self) -> CanisterId {
+        // Safe as this was converted from CanisterId when Self was constructed.
+        CanisterId::new(self.store_canister.unwrap_or(self.target_canister)).unwrap()
+    }
+
+    pub fn get_sender_canister_version(&self) -> Option<u64> {
+        self.sender_canister_version
     }
 }
 
+impl Payload<'_> for InstallChunkedCodeArgs {}
+
 /// Struct used for encoding/decoding
 /// `(record {
-///     settings : opt canister_settings;
+///     idx : nat64;
+///     timestamp_nanos : nat64;
+///     content : blob;
 /// })`
-#[derive(Default, Clone, CandidType, Deserialize)]
-pub struct CreateCanisterArgs {
-    pub settings: Option<CanisterSettingsArgs>,
-    pub sender_canister_version: Option<u64>,
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct CanisterLogRecord {
+    pub idx: u64,
+    pub timestamp_nanos: u64,
+    #[serde(with = "serde_bytes")]
+    pub content: Vec<u8>,
 }
 
-impl CreateCanisterArgs {
-    pub fn encode(&self) -> Vec<u8> {
-        Encode!(&self).unwrap()
-    }
+impl Payload<'_> for CanisterLogRecord {}
 
-    pub fn decode(blob: &[u8]) -> Result<Self, UserError> {
-        let result = Decode!(blob, Self);
-        match result {
-            Err(_) => match EmptyBlob::decode(blob) {
-                Err(_) => Err(UserError::new(
-                    ErrorCode::CanisterContractViolation,
-                    "Payload deserialization error.".to_string(),
-                )),
-                Ok(_) => Ok(CreateCanisterArgs::default()),
-            },
-            Ok(settings) => Ok(settings),
+impl From<pb_canister_state_bits::CanisterLogRecord> for CanisterLogRecord {
+    fn from(item: pb_canister_state_bits::CanisterLogRecord) -> Self {
+        Self {
+            idx: item.idx,
+            timestamp_nanos: item.timestamp_nanos,
+            content: item.content,
         }
     }
+}
 
-    pub fn get_sender_canister_version(&self) -> Option<u64> {
-        self.sender_canister_version
+impl From<&CanisterLogRecord> for pb_canister_state_bits::CanisterLogRecord {
+    fn from(item: &CanisterLogRecord) -> Self {
+        Self {
+            idx: item.idx,
+            timestamp_nanos: item.timestamp_nanos,
+            content: item.content.clone(),
+        }
     }
 }
 
-/// This API is deprecated and should not be used in new code.
-/// Struct used for encoding/decoding
-/// `(record {
-///     canister_id : principal;
-///     controller: principal;
-/// })`
-#[derive(CandidType, Deserialize)]
-pub struct SetControllerArgs {
+/// Struct used for encoding/decoding `(record {canister_id})` of
+/// `fetch_canister_logs`.
+#[derive(CandidType, Serialize, Deserialize, Debug)]
+pub struct FetchCanisterLogsRequest {
     canister_id: PrincipalId,
-    new_controller: PrincipalId,
-    sender_canister_version: Option<u64>,
 }
 
-impl SetControllerArgs {
+impl FetchCanisterLogsRequest {
+    pub fn new(canister_id: CanisterId) -> Self {
+        Self {
+            canister_id: canister_id.into(),
+        }
+    }
+
     pub fn get_canister_id(&self) -> CanisterId {
         // Safe as this was converted from CanisterId when Self was constructed.
         CanisterId::new(self.canister_id).unwrap()
     }
+}
 
-    pub fn get_new_controller(&self) -> PrincipalId {
-        self.new_controller
-    }
+impl Payload<'_> for FetchCanisterLogsRequest {}
 
-    pub fn get_sender_canister_version(&self) -> Option<u64> {
-        self.sender_canister_version
-    }
+/// Struct used for encoding/decoding
+/// `(record {
+///     canister_log_records : vec canister_log_record;
+/// })`
+#[derive(CandidType, Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct FetchCanisterLogsResponse {
+    pub canister_log_records: Vec<CanisterLogRecord>,
 }
 
-impl Payload<'_> for SetControllerArgs {}
+impl Payload<'_> for FetchCanisterLogsResponse {}
 
 /// Struct used for encoding/decoding
 /// `(record {
-///     node_ids : vec principal;
-///     registry_version: nat;
+///     user_id : principal;
 /// })`
-#[derive(CandidType, Deserialize, Debug)]
-pub struct SetupInitialDKGArgs {
-    node_ids: Vec<PrincipalId>,
-    registry_version: u64,
+#[derive(Clone, CandidType, Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct CanisterChangeFromUser {
+    user_id: PrincipalId,
 }
 
-impl Payload<'_> for SetupInitialDKGArgs {}
-
-impl SetupInitialDKGArgs {
-    pub fn new(node_ids: Vec<NodeId>, registry_version: RegistryVersion) -> Self {
-        Self {
-            node_ids: node_ids.iter().map(|node_id| node_id.get()).collect(),
+/// Struct used for encoding/decoding
+/// `(record {
+///     canister_id : principal;
+///     canister_version : opt nat64;
+/// })`
+#[derive(Clone, CandidType, Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct CanisterChangeFromCanister {
+    canister_id: PrincipalId,
+    canister_version: Option<u64>,
+}
+
+/// Who initiated a change recorded in the canister history.
+///
+/// `variant {
+///     from_user : record { user_id : principal };
+///     from_canister : record { canister_id : principal; canister_version : opt nat64 };
+/// }`
+#[derive(Clone, CandidType, Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub enum CanisterChangeOrigin {
+    #[serde(rename = "from_user")]
+    CanisterChangeFromUser(CanisterChangeFromUser),
+    #[serde(rename = "from_canister")]
+    CanisterChangeFromCanister(CanisterChangeFromCanister),
+}
+
+impl CanisterChangeOrigin {
+    pub fn from_user(user_id: PrincipalId) -> Self {
+        Self::CanisterChangeFromUser(CanisterChangeFromUser { user_id })
+    }
+
+    pub fn from_canister(canister_id: PrincipalId, canister_version: Option<u64>) -> Self {
+        Self::CanisterChangeFromCanister(CanisterChangeFromCanister {
+            canister_id,
+            canister_version,
+        })
+    }
+
+    /// Returns the principal that initiated the change.
+    pub fn origin(&self) -> PrincipalId {
+        match self {
+            Self::CanisterChangeFromUser(from_user) => from_user.user_id,
+            Self::CanisterChangeFromCanister(from_canister) => from_canister.canister_id,
+        }
+    }
+}
+
+/// Struct used for encoding/decoding
+/// `(record {
+///     controllers : vec principal;
+/// })`
+#[derive(Clone, CandidType, Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct CanisterCreationRecord {
+    controllers: Vec<PrincipalId>,
+}
+
+impl CanisterCreationRecord {
+    pub fn controllers(&self) -> &[PrincipalId] {
+        &self.controllers
+    }
+}
+
+/// Struct used for encoding/decoding
+/// `(record {
+///     mode : variant { install; reinstall; upgrade };
+///     module_hash : blob;
+/// })`
+#[derive(Clone, CandidType, Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct CanisterCodeDeploymentRecord {
+    mode: CanisterInstallMode,
+    #[serde(with = "serde_bytes")]
+    module_hash: Vec<u8>,
+}
+
+impl CanisterCodeDeploymentRecord {
+    pub fn mode(&self) -> CanisterInstallMode {
+        self.mode
+    }
+
+    pub fn module_hash(&self) -> &[u8] {
+        &self.module_hash
+    }
+}
+
+/// Struct used for encoding/decoding
+/// `(record {
+///     controllers : vec principal;
+/// })`
+#[derive(Clone, CandidType, Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct CanisterControllersChangeRecord {
+    controllers: Vec<PrincipalId>,
+}
+
+impl CanisterControllersChangeRecord {
+    pub fn controllers(&self) -> &[PrincipalId] {
+        &self.controllers
+    }
+}
+
+/// What changed in a change recorded in the canister history.
+///
+/// `variant {
+///     creation : record { controllers : vec principal };
+///     code_uninstall;
+///     code_deployment : record {
+///         mode : variant { install; reinstall; upgrade };
+///         module_hash : blob;
+///     };
+///     controllers_change : record { controllers : vec principal };
+/// }`
+#[derive(Clone, CandidType, Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub enum CanisterChangeDetails {
+    #[serde(rename = "creation")]
+    CanisterCreation(CanisterCreationRecord),
+    #[serde(rename = "code_uninstall")]
+    CanisterCodeUninstall,
+    #[serde(rename = "code_deployment")]
+    CanisterCodeDeployment(CanisterCodeDeploymentRecord),
+    #[serde(rename = "controllers_change")]
+    CanisterControllersChange(CanisterControllersChangeRecord),
+}
+
+impl CanisterChangeDetails {
+    pub fn canister_creation(controllers: Vec<PrincipalId>) -> Self {
+        Self::CanisterCreation(CanisterCreationRecord { controllers })
+    }
+
+    pub fn code_uninstall() -> Self {
+        Self::CanisterCodeUninstall
+    }
+
+    pub fn code_deployment(mode: CanisterInstallMode, module_hash: [u8; 32]) -> Self {
+        Self::CanisterCodeDeployment(CanisterCodeDeploymentRecord {
+            mode,
+            module_hash: module_hash.to_vec(),
+        })
+    }
+
+    pub fn controllers_change(controllers: Vec<PrincipalId>) -> Self {
+        Self::CanisterControllersChange(CanisterControllersChangeRecord { controllers })
+    }
+}
+
+/// A change recorded in the canister history.
+///
+/// `(record {
+///     timestamp_nanos : nat64;
+///     canister_version : nat64;
+///     origin : change_origin;
+///     details : change_details;
+/// })`
+#[derive(Clone, CandidType, Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct CanisterChange {
+    timestamp_nanos: u64,
+    canister_version: u64,
+    origin: CanisterChangeOrigin,
+    details: CanisterChangeDetails,
+}
+
+impl CanisterChange {
+    pub fn new(
+        timestamp_nanos: u64,
+        canister_version: u64,
+        origin: CanisterChangeOrigin,
+        details: CanisterChangeDetails,
+    ) -> CanisterChange {
+        CanisterChange {
+            timestamp_nanos,
+            canister_version,
+            origin,
+            details,
+        }
+    }
+
+    pub fn timestamp_nanos(&self) -> u64 {
+        self.timestamp_nanos
+    }
+
+    pub fn canister_version(&self) -> u64 {
+        self.canister_version
+    }
+
+    pub fn origin(&self) -> &CanisterChangeOrigin {
+        &self.origin
+    }
+
+    pub fn details(&self) -> &CanisterChangeDetails {
+        &self.details
+    }
+}
+
+impl From<&CanisterChange> for pb_canister_state_bits::CanisterChange {
+    fn from(item: &CanisterChange) -> Self {
+        use pb_canister_state_bits::canister_change::{ChangeDetails, ChangeOrigin};
+
+        let change_origin = match &item.origin {
+            CanisterChangeOrigin::CanisterChangeFromUser(from_user) => {
+                ChangeOrigin::CanisterChangeFromUser(
+                    pb_canister_state_bits::CanisterChangeFromUser {
+                        user_id: Some(from_user.user_id.into()),
+                    },
+                )
+            }
+            CanisterChangeOrigin::CanisterChangeFromCanister(from_canister) => {
+                ChangeOrigin::CanisterChangeFromCanister(
+                    pb_canister_state_bits::CanisterChangeFromCanister {
+                        canister_id: Some(from_canister.canister_id.into()),
+                        canister_version: from_canister.canister_version,
+                    },
+                )
+            }
+        };
+        let change_details = match &item.details {
+            CanisterChangeDetails::CanisterCreation(creation) => {
+                ChangeDetails::CanisterCreation(pb_canister_state_bits::CanisterCreation {
+                    controllers: creation.controllers.iter().map(|c| (*c).into()).collect(),
+                })
+            }
+            CanisterChangeDetails::CanisterCodeUninstall => ChangeDetails::CanisterCodeUninstall(
+                pb_canister_state_bits::CanisterCodeUninstall {},
+            ),
+            CanisterChangeDetails::CanisterCodeDeployment(deployment) => {
+                ChangeDetails::CanisterCodeDeployment(
+                    pb_canister_state_bits::CanisterCodeDeployment {
+                        mode: pb_canister_state_bits::CanisterInstallMode::from(&deployment.mode)
+                            as i32,
+                        module_hash: deployment.module_hash.clone(),
+                    },
+                )
+            }
+            CanisterChangeDetails::CanisterControllersChange(controllers_change) => {
+                ChangeDetails::CanisterControllersChange(
+                    pb_canister_state_bits::CanisterControllersChange {
+                        controllers: controllers_change
+                            .controllers
+                            .iter()
+                            .map(|c| (*c).into())
+                            .collect(),
+                    },
+                )
+            }
+        };
+        Self {
+            timestamp_nanos: item.timestamp_nanos,
+            canister_version: item.canister_version,
+            change_origin: Some(change_origin),
+            change_details: Some(change_details),
+        }
+    }
+}
+
+impl TryFrom<pb_canister_state_bits::CanisterChange> for CanisterChange {
+    type Error = ProxyDecodeError;
+
+    fn try_from(item: pb_canister_state_bits::CanisterChange) -> Result<Self, Self::Error> {
+        use pb_canister_state_bits::canister_change::{ChangeDetails, ChangeOrigin};
+
+        let origin = match item.change_origin.ok_or(ProxyDecodeError::MissingField(
+            "CanisterChange::change_origin",
+        ))? {
+            ChangeOrigin::CanisterChangeFromUser(from_user) => CanisterChangeOrigin::from_user(
+                try_from_option_field(from_user.user_id, "CanisterChangeFromUser::user_id")?,
+            ),
+            ChangeOrigin::CanisterChangeFromCanister(from_canister) => {
+                CanisterChangeOrigin::from_canister(
+                    try_from_option_field(
+                        from_canister.canister_id,
+                        "CanisterChangeFromCanister::canister_id",
+                    )?,
+                    from_canister.canister_version,
+                )
+            }
+        };
+        let details = match item.change_details.ok_or(ProxyDecodeError::MissingField(
+            "CanisterChange::change_details",
+        ))? {
+            ChangeDetails::CanisterCreation(creation) => CanisterChangeDetails::canister_creation(
+                creation
+                    .controllers
+                    .into_iter()
+                    .map(PrincipalId::try_from)
+                    .collect::<Result<_, _>>()?,
+            ),
+            ChangeDetails::CanisterCodeUninstall(_) => CanisterChangeDetails::code_uninstall(),
+            ChangeDetails::CanisterCodeDeployment(deployment) => {
+                let mode = pb_canister_state_bits::CanisterInstallMode::from_i32(deployment.mode)
+                    .ok_or(ProxyDecodeError::ValueOutOfRange {
+                    typ: "CanisterInstallMode",
+                    err: format!(
+                        "Unexpected value of canister install mode: {}",
+                        deployment.mode
+                    ),
+                })?;
+                let module_hash: [u8; 32] =
+                    deployment.module_hash.try_into().map_err(|hash: Vec<u8>| {
+                        ProxyDecodeError::InvalidDigestLength {
+                            expected: 32,
+                            actual: hash.len(),
+                        }
+                    })?;
+                CanisterChangeDetails::code_deployment(mode.try_into()?, module_hash)
+            }
+            ChangeDetails::CanisterControllersChange(controllers_change) => {
+                CanisterChangeDetails::controllers_change(
+                    controllers_change
+                        .controllers
+                        .into_iter()
+                        .map(PrincipalId::try_from)
+                        .collect::<Result<_, _>>()?,
+                )
+            }
+        };
+        Ok(Self {
+            timestamp_nanos: item.timestamp_nanos,
+            canister_version: item.canister_version,
+            origin,
+            details,
+        })
+    }
+}
+
+/// Struct used for encoding/decoding
+/// `(record {
+///     canister_id : principal;
+///     num_requested_changes : opt nat64;
+/// })`
+#[derive(CandidType, Serialize, Deserialize, Debug)]
+pub struct CanisterInfoRequest {
+    canister_id: PrincipalId,
+    num_requested_changes: Option<u64>,
+}
+
+impl CanisterInfoRequest {
+    pub fn new(canister_id: CanisterId, num_requested_changes: Option<u64>) -> Self {
+        Self {
+            canister_id: canister_id.into(),
+            num_requested_changes,
+        }
+    }
+
+    pub fn get_canister_id(&self) -> CanisterId {
+        // Safe as this was converted from CanisterId when Self was constructed.
+        CanisterId::new(self.canister_id).unwrap()
+    }
+
+    pub fn num_requested_changes(&self) -> Option<u64> {
+        self.num_requested_changes
+    }
+}
+
+impl Payload<'_> for CanisterInfoRequest {}
+
+/// Struct used for encoding/decoding
+/// `(record {
+///     total_num_changes : nat64;
+///     recent_changes : vec change;
+///     module_hash : opt blob;
+///     controllers : vec principal;
+/// })`
+#[derive(CandidType, Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct CanisterInfoResponse {
+    total_num_changes: u64,
+    recent_changes: Vec<CanisterChange>,
+    #[serde(with = "serde_bytes")]
+    module_hash: Option<Vec<u8>>,
+    controllers: Vec<PrincipalId>,
+}
+
+impl CanisterInfoResponse {
+    pub fn new(
+        total_num_changes: u64,
+        recent_changes: Vec<CanisterChange>,
+        module_hash: Option<Vec<u8>>,
+        controllers: Vec<PrincipalId>,
+    ) -> Self {
+        Self {
+            total_num_changes,
+            recent_changes,
+            module_hash,
+            controllers,
+        }
+    }
+
+    pub fn total_num_changes(&self) -> u64 {
+        self.total_num_changes
+    }
+
+    pub fn changes(&self) -> &[CanisterChange] {
+        &self.recent_changes
+    }
+
+    pub fn module_hash(&self) -> Option<&[u8]> {
+        self.module_hash.as_deref()
+    }
+
+    pub fn controllers(&self) -> &[PrincipalId] {
+        &self.controllers
+    }
+}
+
+impl Payload<'_> for CanisterInfoResponse {}
+
+/// Represents the empty blob.
+#[derive(CandidType, Deserialize)]
+pub struct EmptyBlob;
+
+impl<'a> Payload<'a> for EmptyBlob {
+    fn encode(&self) -> Vec<u8> {
+        Encode!().unwrap()
+    }
+
+    fn decode(blob: &'a [u8]) -> Result<EmptyBlob, candid::Error> {
+        Decode!(blob).map(|_| EmptyBlob)
+    }
+}
+
+/// Struct used for encoding/decoding
+/// `(record {
+///     canister_id : principal;
+///     settings: canister_settings;
+/// })`
+#[derive(CandidType, Deserialize)]
+pub struct UpdateSettingsArgs {
+    pub canister_id: PrincipalId,
+    pub settings: CanisterSettingsArgs,
+    pub sender_canister_version: Option<u64>,
+}
+
+impl UpdateSettingsArgs {
+    pub fn new(canister_id: CanisterId, settings: CanisterSettingsArgs) -> Self {
+        Self {
+            canister_id: canister_id.into(),
+            settings,
+            sender_canister_version: None,
+        }
+    }
+
+    pub fn get_canister_id(&self) -> CanisterId {
+        // Safe as this was converted from CanisterId when Self was constructed.
+        CanisterId::new(self.canister_id).unwrap()
+    }
+
+    pub fn get_sender_canister_version(&self) -> Option<u64> {
+        self.sender_canister_version
+    }
+}
+
+impl Payload<'_> for UpdateSettingsArgs {}
+
+/// Who is allowed to read the logs of a canister via `fetch_canister_logs`.
+///
+/// Struct used for encoding/decoding `variant { controllers; public }`.
+#[derive(CandidType, Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LogVisibility {
+    #[serde(rename = "controllers")]
+    Controllers,
+    #[serde(rename = "public")]
+    Public,
+}
+
+impl Default for LogVisibility {
+    fn default() -> Self {
+        Self::Controllers
+    }
+}
+
+impl From<&LogVisibility> for pb_canister_state_bits::LogVisibility {
+    fn from(item: &LogVisibility) -> Self {
+        match item {
+            LogVisibility::Controllers => pb_canister_state_bits::LogVisibility::Controllers,
+            LogVisibility::Public => pb_canister_state_bits::LogVisibility::Public,
+        }
+    }
+}
+
+impl TryFrom<pb_canister_state_bits::LogVisibility> for LogVisibility {
+    type Error = ProxyDecodeError;
+
+    fn try_from(item: pb_canister_state_bits::LogVisibility) -> Result<Self, Self::Error> {
+        match item {
+            pb_canister_state_bits::LogVisibility::Controllers => Ok(Self::Controllers),
+            pb_canister_state_bits::LogVisibility::Public => Ok(Self::Public),
+            pb_canister_state_bits::LogVisibility::Unspecified => {
+                Err(ProxyDecodeError::ValueOutOfRange {
+                    typ: "LogVisibility",
+                    err: format!("Unable to convert {:?} to a LogVisibility", item),
+                })
+            }
+        }
+    }
+}
+
+/// Struct used for encoding/decoding
+/// `(record {
+///     controller: opt principal;
+///     controllers: opt vec principal;
+///     compute_allocation: opt nat;
+///     memory_allocation: opt nat;
+///     log_visibility: opt log_visibility;
+/// })`
+#[derive(Default, Clone, CandidType, Deserialize, Debug)]
+pub struct CanisterSettingsArgs {
+    /// The field controller is deprecated and should not be used in new code.
+    controller: Option<PrincipalId>,
+    pub controllers: Option<Vec<PrincipalId>>,
+    pub compute_allocation: Option<candid::Nat>,
+    pub memory_allocation: Option<candid::Nat>,
+    pub freezing_threshold: Option<candid::Nat>,
+    pub log_visibility: Option<LogVisibility>,
+}
+
+impl Payload<'_> for CanisterSettingsArgs {}
+
+impl CanisterSettingsArgs {
+    pub fn new(
+        controllers: Option<Vec<PrincipalId>>,
+        compute_allocation: Option<u64>,
+        memory_allocation: Option<u64>,
+        freezing_threshold: Option<u64>,
+    ) -> Self {
+        Self {
+            controller: None,
+            controllers,
+            compute_allocation: compute_allocation.map(candid::Nat::from),
+            memory_allocation: memory_allocation.map(candid::Nat::from),
+            freezing_threshold: freezing_threshold.map(candid::Nat::from),
+            log_visibility: None,
+        }
+    }
+
+    pub fn get_controller(&self) -> Option<PrincipalId> {
+        self.controller
+    }
+}
+
+/// Struct used for encoding/decoding
+/// `(record {
+///     settings : opt canister_settings;
+/// })`
+#[derive(Default, Clone, CandidType, Deserialize)]
+pub struct CreateCanisterArgs {
+    pub settings: Option<CanisterSettingsArgs>,
+    pub sender_canister_version: Option<u64>,
+}
+
+impl CreateCanisterArgs {
+    pub fn encode(&self) -> Vec<u8> {
+        Encode!(&self).unwrap()
+    }
+
+    pub fn decode(blob: &[u8]) -> Result<Self, UserError> {
+        let result = Decode!(blob, Self);
+        match result {
+            Err(_) => match EmptyBlob::decode(blob) {
+                Err(_) => Err(UserError::new(
+                    ErrorCode::CanisterContractViolation,
+                    "Payload deserialization error.".to_string(),
+                )),
+                Ok(_) => Ok(CreateCanisterArgs::default()),
+            },
+            Ok(settings) => Ok(settings),
+        }
+    }
+
+    pub fn get_sender_canister_version(&self) -> Option<u64> {
+        self.sender_canister_version
+    }
+}
+
+/// This API is deprecated and should not be used in new code.
+/// Struct used for encoding/decoding
+/// `(record {
+///     canister_id : principal;
+///     controller: principal;
+/// })`
+#[derive(CandidType, Deserialize)]
+pub struct SetControllerArgs {
+    canister_id: PrincipalId,
+    new_controller: PrincipalId,
+    sender_canister_version: Option<u64>,
+}
+
+impl SetControllerArgs {
+    pub fn get_canister_id(&self) -> CanisterId {
+        // Safe as this was converted from CanisterId when Self was constructed.
+        CanisterId::new(self.canister_id).unwrap()
+    }
+
+    pub fn get_new_controller(&self) -> PrincipalId {
+        self.new_controller
+    }
+
+    pub fn get_sender_canister_version(&self) -> Option<u64> {
+        self.sender_canister_version
+    }
+}
+
+impl Payload<'_> for SetControllerArgs {}
+
+/// Struct used for encoding/decoding
+/// `(record {
+///     node_ids : vec principal;
+///     registry_version: nat;
+/// })`
+#[derive(CandidType, Deserialize, Debug)]
+pub struct SetupInitialDKGArgs {
+    node_ids: Vec<PrincipalId>,
+    registry_version: u64,
+}
+
+impl Payload<'_> for SetupInitialDKGArgs {}
+
+impl SetupInitialDKGArgs {
+    pub fn new(node_ids: Vec<NodeId>, registry_version: RegistryVersion) -> Self {
+        Self {
+            node_ids: node_ids.iter().map(|node_id| node_id.get()).collect(),
             registry_version: registry_version.get(),
         }
     }
 
-    pub fn get_set_of_node_ids(&self) -> Result<BTreeSet<NodeId>, UserError> {
-        let mut set = BTreeSet::<NodeId>::new();
-        for node_id in self.node_ids.iter() {
-            if !set.insert(NodeId::new(*node_id)) {
-                return Err(UserError::new(
-                    ErrorCode::CanisterContractViolation,
-                    format!(
-                        "Expected a set of NodeIds. The NodeId {} is repeated",
-                        node_id
-                    ),
-                ));
+    pub fn get_set_of_node_ids(&self) -> Result<BTreeSet<NodeId>, UserError> {
+        let mut set = BTreeSet::<NodeId>::new();
+        for node_id in self.node_ids.iter() {
+            if !set.insert(NodeId::new(*node_id)) {
+                return Err(UserError::new(
+                    ErrorCode::CanisterContractViolation,
+                    format!(
+                        "Expected a set of NodeIds. The NodeId {} is repeated",
+                        node_id
+                    ),
+                ));
+            }
+        }
+        Ok(set)
+    }
+
+    pub fn get_registry_version(&self) -> RegistryVersion {
+        RegistryVersion::new(self.registry_version)
+    }
+}
+
+/// Represents the response for a request to setup an initial DKG for a new
+/// subnet.
+#[derive(Debug)]
+pub struct SetupInitialDKGResponse {
+    pub low_threshold_transcript_record: InitialNiDkgTranscriptRecord,
+    pub high_threshold_transcript_record: InitialNiDkgTranscriptRecord,
+    pub fresh_subnet_id: SubnetId,
+    pub subnet_threshold_public_key: PublicKey,
+}
+
+impl SetupInitialDKGResponse {
+    pub fn encode(&self) -> Vec<u8> {
+        let serde_encoded_transcript_records = self.encode_with_serde_cbor();
+        Encode!(&serde_encoded_transcript_records).unwrap()
+    }
+
+    fn encode_with_serde_cbor(&self) -> Vec<u8> {
+        let transcript_records = (
+            &self.low_threshold_transcript_record,
+            &self.high_threshold_transcript_record,
+            &self.fresh_subnet_id,
+            &self.subnet_threshold_public_key,
+        );
+        serde_cbor::to_vec(&transcript_records).unwrap()
+    }
+
+    pub fn decode(blob: &[u8]) -> Result<Self, UserError> {
+        let serde_encoded_transcript_records = Decode!(blob, Vec<u8>).map_err(|err| {
+            UserError::new(
+                ErrorCode::CanisterContractViolation,
+                format!("Error decoding candid: {}", err),
+            )
+        })?;
+        match serde_cbor::from_slice::<(
+            InitialNiDkgTranscriptRecord,
+            InitialNiDkgTranscriptRecord,
+            SubnetId,
+            PublicKey,
+        )>(&serde_encoded_transcript_records)
+        {
+            Err(err) => Err(UserError::new(
+                ErrorCode::CanisterContractViolation,
+                format!("Payload deserialization error: '{}'", err),
+            )),
+            Ok((
+                low_threshold_transcript_record,
+                high_threshold_transcript_record,
+                fresh_subnet_id,
+                subnet_threshold_public_key,
+            )) => Ok(Self {
+                low_threshold_transcript_record,
+                high_threshold_transcript_record,
+                fresh_subnet_id,
+                subnet_threshold_public_key,
+            }),
+        }
+    }
+}
+
+/// Types of curves that can be used for ECDSA signing.
+/// ```text
+/// (variant { secp256k1; secp256r1; })
+/// ```
+#[derive(
+    CandidType, Copy, Clone, Debug, PartialOrd, Ord, PartialEq, Eq, Serialize, Deserialize, Hash,
+)]
+pub enum EcdsaCurve {
+    #[serde(rename = "secp256k1")]
+    Secp256k1,
+    #[serde(rename = "secp256r1")]
+    Secp256r1,
+}
+
+impl TryFrom<pb_registry_crypto::EcdsaCurve> for EcdsaCurve {
+    type Error = ProxyDecodeError;
+
+    fn try_from(item: pb_registry_crypto::EcdsaCurve) -> Result<Self, Self::Error> {
+        match item {
+            pb_registry_crypto::EcdsaCurve::Secp256k1 => Ok(EcdsaCurve::Secp256k1),
+            pb_registry_crypto::EcdsaCurve::Secp256r1 => Ok(EcdsaCurve::Secp256r1),
+            pb_registry_crypto::EcdsaCurve::Unspecified => Err(ProxyDecodeError::ValueOutOfRange {
+                typ: "EcdsaCurve",
+                err: format!("Unable to convert {:?} to an EcdsaCurve", item),
+            }),
+        }
+    }
+}
+
+impl From<EcdsaCurve> for pb_registry_crypto::EcdsaCurve {
+    fn from(item: EcdsaCurve) -> Self {
+        match item {
+            EcdsaCurve::Secp256k1 => pb_registry_crypto::EcdsaCurve::Secp256k1,
+            EcdsaCurve::Secp256r1 => pb_registry_crypto::EcdsaCurve::Secp256r1,
+        }
+    }
+}
+
+impl std::fmt::Display for EcdsaCurve {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl FromStr for EcdsaCurve {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Secp256k1" => Ok(Self::Secp256k1),
+            "Secp256r1" => Ok(Self::Secp256r1),
+            _ => Err(format!("{} is not a recognized ECDSA curve", s)),
+        }
+    }
+}
+
+#[test]
+fn ecdsa_curve_round_trip() {
+    for curve in [EcdsaCurve::Secp256k1, EcdsaCurve::Secp256r1] {
+        assert_eq!(format!("{}", curve).parse::<EcdsaCurve>().unwrap(), curve);
+    }
+}
+
+/// Unique identifier for a key that can be used for ECDSA signatures. The name
+/// is just a identifier, but it may be used to convey some information about
+/// the key (e.g. that the key is meant to be used for testing purposes).
+/// ```text
+/// (record { curve: ecdsa_curve; name: text})
+/// ```
+#[derive(
+    CandidType, Clone, Debug, PartialOrd, Ord, PartialEq, Eq, Serialize, Deserialize, Hash,
+)]
+pub struct EcdsaKeyId {
+    pub curve: EcdsaCurve,
+    pub name: String,
+}
+
+impl TryFrom<pb_registry_crypto::EcdsaKeyId> for EcdsaKeyId {
+    type Error = ProxyDecodeError;
+    fn try_from(item: pb_registry_crypto::EcdsaKeyId) -> Result<Self, Self::Error> {
+        Ok(Self {
+            curve: EcdsaCurve::try_from(
+                pb_registry_crypto::EcdsaCurve::from_i32(item.curve).ok_or(
+                    ProxyDecodeError::ValueOutOfRange {
+                        typ: "EcdsaKeyId",
+                        err: format!("Unable to convert {} to an EcdsaCurve", item.curve),
+                    },
+                )?,
+            )?,
+            name: item.name,
+        })
+    }
+}
+
+impl From<&EcdsaKeyId> for pb_registry_crypto::EcdsaKeyId {
+    fn from(item: &EcdsaKeyId) -> Self {
+        Self {
+            curve: pb_registry_crypto::EcdsaCurve::from(item.curve) as i32,
+            name: item.name.clone(),
+        }
+    }
+}
+
+impl std::fmt::Display for EcdsaKeyId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.curve, self.name)
+    }
+}
+
+impl FromStr for EcdsaKeyId {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (curve, name) = s
+            .split_once(':')
+            .ok_or_else(|| format!("ECDSA key id {} does not contain a ':'", s))?;
+        Ok(EcdsaKeyId {
+            curve: curve.parse::<EcdsaCurve>()?,
+            name: name.to_string(),
+        })
+    }
+}
+
+#[test]
+fn ecdsa_key_id_round_trip() {
+    for curve in [EcdsaCurve::Secp256k1, EcdsaCurve::Secp256r1] {
+        for name in ["secp256k1", "", "other_key", "other key", "other:key"] {
+            let key = EcdsaKeyId {
+                curve,
+                name: name.to_string(),
+            };
+            assert_eq!(format!("{}", key).parse::<EcdsaKeyId>().unwrap(), key);
+        }
+    }
+}
+
+/// Represents the argument of the sign_with_ecdsa API.
+/// ```text
+/// (record {
+///   message_hash : blob;
+///   derivation_path : vec blob;
+///   key_id : ecdsa_key_id;
+/// })
+/// ```
+#[derive(CandidType, Deserialize, Debug)]
+pub struct SignWithECDSAArgs {
+    pub message_hash: [u8; 32],
+    pub derivation_path: Vec<Vec<u8>>,
+    pub key_id: EcdsaKeyId,
+}
+
+impl Payload<'_> for SignWithECDSAArgs {}
+
+/// Struct used to return an ECDSA signature.
+#[derive(CandidType, Deserialize, Debug)]
+pub struct SignWithECDSAReply {
+    pub signature: Vec<u8>,
+}
+
+impl Payload<'_> for SignWithECDSAReply {}
+
+/// Represents the argument of the ecdsa_public_key API.
+/// ```text
+/// (record {
+///   canister_id : opt canister_id;
+///   derivation_path : vec blob;
+///   key_id : ecdsa_key_id;
+/// })
+/// ```
+#[derive(CandidType, Deserialize, Debug)]
+pub struct ECDSAPublicKeyArgs {
+    pub canister_id: Option<CanisterId>,
+    pub derivation_path: Vec<Vec<u8>>,
+    pub key_id: EcdsaKeyId,
+}
+
+impl Payload<'_> for ECDSAPublicKeyArgs {}
+
+/// Represents the response of the ecdsa_public_key API.
+/// ```text
+/// (record {
+///   public_key : blob;
+///   chain_code : blob;
+/// })
+/// ```
+#[derive(CandidType, Deserialize, Debug)]
+pub struct ECDSAPublicKeyResponse {
+    pub public_key: Vec<u8>,
+    pub chain_code: Vec<u8>,
+}
+
+impl Payload<'_> for ECDSAPublicKeyResponse {}
+
+/// Types of algorithms that can be used for Schnorr signing.
+/// ```text
+/// (variant { bip340secp256k1; ed25519; })
+/// ```
+#[derive(
+    CandidType, Copy, Clone, Debug, PartialOrd, Ord, PartialEq, Eq, Serialize, Deserialize, Hash,
+)]
+pub enum SchnorrAlgorithm {
+    #[serde(rename = "bip340secp256k1")]
+    Bip340Secp256k1,
+    #[serde(rename = "ed25519")]
+    Ed25519,
+}
+
+impl TryFrom<pb_registry_crypto::SchnorrAlgorithm> for SchnorrAlgorithm {
+    type Error = ProxyDecodeError;
+
+    fn try_from(item: pb_registry_crypto::SchnorrAlgorithm) -> Result<Self, Self::Error> {
+        match item {
+            pb_registry_crypto::SchnorrAlgorithm::Bip340Secp256k1 => Ok(Self::Bip340Secp256k1),
+            pb_registry_crypto::SchnorrAlgorithm::Ed25519 => Ok(Self::Ed25519),
+            pb_registry_crypto::SchnorrAlgorithm::Unspecified => {
+                Err(ProxyDecodeError::ValueOutOfRange {
+                    typ: "SchnorrAlgorithm",
+                    err: format!("Unable to convert {:?} to a SchnorrAlgorithm", item),
+                })
+            }
+        }
+    }
+}
+
+impl From<SchnorrAlgorithm> for pb_registry_crypto::SchnorrAlgorithm {
+    fn from(item: SchnorrAlgorithm) -> Self {
+        match item {
+            SchnorrAlgorithm::Bip340Secp256k1 => {
+                pb_registry_crypto::SchnorrAlgorithm::Bip340Secp256k1
             }
+            SchnorrAlgorithm::Ed25519 => pb_registry_crypto::SchnorrAlgorithm::Ed25519,
         }
-        Ok(set)
     }
+}
 
-    pub fn get_registry_version(&self) -> RegistryVersion {
-        RegistryVersion::new(self.registry_version)
+impl SchnorrAlgorithm {
+    /// Returns whether the IDKG protocol can produce threshold signatures with
+    /// this algorithm. Ed25519 is part of the interface but not implemented yet,
+    /// so Ed25519 keys can neither be configured on a subnet nor requested.
+    pub fn is_supported(&self) -> bool {
+        match self {
+            SchnorrAlgorithm::Bip340Secp256k1 => true,
+            SchnorrAlgorithm::Ed25519 => false,
+        }
     }
 }
 
-/// Represents the response for a request to setup an initial DKG for a new
-/// subnet.
-#[derive(Debug)]
-pub struct SetupInitialDKGResponse {
-    pub low_threshold_transcript_record: InitialNiDkgTranscriptRecord,
-    pub high_threshold_transcript_record: InitialNiDkgTranscriptRecord,
-    pub fresh_subnet_id: SubnetId,
-    pub subnet_threshold_public_key: PublicKey,
+impl std::fmt::Display for SchnorrAlgorithm {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
 }
 
-impl SetupInitialDKGResponse {
-    pub fn encode(&self) -> Vec<u8> {
-        let serde_encoded_transcript_records = self.encode_with_serde_cbor();
-        Encode!(&serde_encoded_transcript_records).unwrap()
+impl FromStr for SchnorrAlgorithm {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Bip340Secp256k1" => Ok(Self::Bip340Secp256k1),
+            "Ed25519" => Ok(Self::Ed25519),
+            _ => Err(format!("{} is not a recognized Schnorr algorithm", s)),
+        }
     }
+}
 
-    fn encode_with_serde_cbor(&self) -> Vec<u8> {
-        let transcript_records = (
-            &self.low_threshold_transcript_record,
-            &self.high_threshold_transcript_record,
-            &self.fresh_subnet_id,
-            &self.subnet_threshold_public_key,
+#[test]
+fn schnorr_algorithm_round_trip() {
+    for algorithm in [SchnorrAlgorithm::Bip340Secp256k1, SchnorrAlgorithm::Ed25519] {
+        assert_eq!(
+            format!("{}", algorithm)
+                .parse::<SchnorrAlgorithm>()
+                .unwrap(),
+            algorithm
         );
-        serde_cbor::to_vec(&transcript_records).unwrap()
     }
+}
 
-    pub fn decode(blob: &[u8]) -> Result<Self, UserError> {
-        let serde_encoded_transcript_records = Decode!(blob, Vec<u8>).map_err(|err| {
-            UserError::new(
-                ErrorCode::CanisterContractViolation,
-                format!("Error decoding candid: {}", err),
-            )
-        })?;
-        match serde_cbor::from_slice::<(
-            InitialNiDkgTranscriptRecord,
-            InitialNiDkgTranscriptRecord,
-            SubnetId,
-            PublicKey,
-        )>(&serde_encoded_transcript_records)
-        {
-            Err(err) => Err(UserError::new(
-                ErrorCode::CanisterContractViolation,
-                format!("Payload deserialization error: '{}'", err),
-            )),
-            Ok((
-                low_threshold_transcript_record,
-                high_threshold_transcript_record,
-                fresh_subnet_id,
-                subnet_threshold_public_key,
-            )) => Ok(Self {
-                low_threshold_transcript_record,
-                high_threshold_transcript_record,
-                fresh_subnet_id,
-                subnet_threshold_public_key,
-            }),
+/// Unique identifier for a key that can be used for Schnorr signatures. The
+/// name is just a identifier, but it may be used to convey some information
+/// about the key (e.g. that the key is meant to be used for testing purposes).
+/// ```text
+/// (record { algorithm: schnorr_algorithm; name: text})
+/// ```
+#[derive(
+    CandidType, Clone, Debug, PartialOrd, Ord, PartialEq, Eq, Serialize, Deserialize, Hash,
+)]
+pub struct SchnorrKeyId {
+    pub algorithm: SchnorrAlgorithm,
+    pub name: String,
+}
+
+impl TryFrom<pb_registry_crypto::SchnorrKeyId> for SchnorrKeyId {
+    type Error = ProxyDecodeError;
+    fn try_from(item: pb_registry_crypto::SchnorrKeyId) -> Result<Self, Self::Error> {
+        Ok(Self {
+            algorithm: SchnorrAlgorithm::try_from(
+                pb_registry_crypto::SchnorrAlgorithm::from_i32(item.algorithm).ok_or(
+                    ProxyDecodeError::ValueOutOfRange {
+                        typ: "SchnorrKeyId",
+                        err: format!("Unable to convert {} to a SchnorrAlgorithm", item.algorithm),
+                    },
+                )?,
+            )?,
+            name: item.name,
+        })
+    }
+}
+
+impl From<&SchnorrKeyId> for pb_registry_crypto::SchnorrKeyId {
+    fn from(item: &SchnorrKeyId) -> Self {
+        Self {
+            algorithm: pb_registry_crypto::SchnorrAlgorithm::from(item.algorithm) as i32,
+            name: item.name.clone(),
         }
     }
 }
 
-/// Types of curves that can be used for ECDSA signing.
+impl std::fmt::Display for SchnorrKeyId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.algorithm, self.name)
+    }
+}
+
+impl FromStr for SchnorrKeyId {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (algorithm, name) = s
+            .split_once(':')
+            .ok_or_else(|| format!("Schnorr key id {} does not contain a ':'", s))?;
+        Ok(SchnorrKeyId {
+            algorithm: algorithm.parse::<SchnorrAlgorithm>()?,
+            name: name.to_string(),
+        })
+    }
+}
+
+#[test]
+fn schnorr_key_id_round_trip() {
+    for algorithm in [SchnorrAlgorithm::Bip340Secp256k1, SchnorrAlgorithm::Ed25519] {
+        for name in ["Ed25519", "", "other_key", "other key", "other:key"] {
+            let key = SchnorrKeyId {
+                algorithm,
+                name: name.to_string(),
+            };
+            assert_eq!(format!("{}", key).parse::<SchnorrKeyId>().unwrap(), key);
+        }
+    }
+}
+
+/// Unique identifier for a threshold master public key, regardless of the
+/// signature scheme it is used with.
+#[derive(Clone, Debug, PartialOrd, Ord, PartialEq, Eq, Serialize, Deserialize, Hash)]
+pub enum MasterPublicKeyId {
+    Ecdsa(EcdsaKeyId),
+    Schnorr(SchnorrKeyId),
+}
+
+impl TryFrom<pb_registry_crypto::MasterPublicKeyId> for MasterPublicKeyId {
+    type Error = ProxyDecodeError;
+    fn try_from(item: pb_registry_crypto::MasterPublicKeyId) -> Result<Self, Self::Error> {
+        use pb_registry_crypto::master_public_key_id::KeyId;
+        match item.key_id {
+            Some(KeyId::Ecdsa(key_id)) => Ok(Self::Ecdsa(EcdsaKeyId::try_from(key_id)?)),
+            Some(KeyId::Schnorr(key_id)) => Ok(Self::Schnorr(SchnorrKeyId::try_from(key_id)?)),
+            None => Err(ProxyDecodeError::MissingField("MasterPublicKeyId::key_id")),
+        }
+    }
+}
+
+impl From<&MasterPublicKeyId> for pb_registry_crypto::MasterPublicKeyId {
+    fn from(item: &MasterPublicKeyId) -> Self {
+        use pb_registry_crypto::master_public_key_id::KeyId;
+        let key_id = match item {
+            MasterPublicKeyId::Ecdsa(key_id) => KeyId::Ecdsa(key_id.into()),
+            MasterPublicKeyId::Schnorr(key_id) => KeyId::Schnorr(key_id.into()),
+        };
+        Self {
+            key_id: Some(key_id),
+        }
+    }
+}
+
+impl std::fmt::Display for MasterPublicKeyId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Ecdsa(key_id) => write!(f, "ecdsa:{}", key_id),
+            Self::Schnorr(key_id) => write!(f, "schnorr:{}", key_id),
+        }
+    }
+}
+
+/// Represents the argument of the sign_with_schnorr API.
+/// ```text
+/// (record {
+///   message : blob;
+///   derivation_path : vec blob;
+///   key_id : schnorr_key_id;
+/// })
+/// ```
+#[derive(CandidType, Deserialize, Debug)]
+pub struct SignWithSchnorrArgs {
+    #[serde(with = "serde_bytes")]
+    pub message: Vec<u8>,
+    pub derivation_path: Vec<Vec<u8>>,
+    pub key_id: SchnorrKeyId,
+}
+
+impl Payload<'_> for SignWithSchnorrArgs {}
+
+/// Struct used to return a Schnorr signature.
+#[derive(CandidType, Deserialize, Debug)]
+pub struct SignWithSchnorrReply {
+    #[serde(with = "serde_bytes")]
+    pub signature: Vec<u8>,
+}
+
+impl Payload<'_> for SignWithSchnorrReply {}
+
+/// Represents the argument of the schnorr_public_key API.
+/// ```text
+/// (record {
+///   canister_id : opt canister_id;
+///   derivation_path : vec blob;
+///   key_id : schnorr_key_id;
+/// })
+/// ```
+#[derive(CandidType, Deserialize, Debug)]
+pub struct SchnorrPublicKeyArgs {
+    pub canister_id: Option<CanisterId>,
+    pub derivation_path: Vec<Vec<u8>>,
+    pub key_id: SchnorrKeyId,
+}
+
+impl Payload<'_> for SchnorrPublicKeyArgs {}
+
+/// Represents the response of the schnorr_public_key API.
+/// ```text
+/// (record {
+///   public_key : blob;
+///   chain_code : blob;
+/// })
+/// ```
+#[derive(CandidType, Deserialize, Debug)]
+pub struct SchnorrPublicKeyResponse {
+    #[serde(with = "serde_bytes")]
+    pub public_key: Vec<u8>,
+    #[serde(with = "serde_bytes")]
+    pub chain_code: Vec<u8>,
+}
+
+impl Payload<'_> for SchnorrPublicKeyResponse {}
+
+/// Types of curves that can be used for vetKD.
 /// ```text
-/// (variant { secp256k1; })
+/// (variant { bls12_381_g2; })
 /// ```
 #[derive(
     CandidType, Copy, Clone, Debug, PartialOrd, Ord, PartialEq, Eq, Serialize, Deserialize, Hash,
 )]
-pub enum EcdsaCurve {
-    #[serde(rename = "secp256k1")]
-    Secp256k1,
+pub enum VetKdCurve {
+    #[serde(rename = "bls12_381_g2")]
+    Bls12381G2,
 }
 
-impl TryFrom<pb_registry_crypto::EcdsaCurve> for EcdsaCurve {
+impl TryFrom<pb_registry_crypto::VetKdCurve> for VetKdCurve {
     type Error = ProxyDecodeError;
 
-    fn try_from(item: pb_registry_crypto::EcdsaCurve) -> Result<Self, Self::Error> {
+    fn try_from(item: pb_registry_crypto::VetKdCurve) -> Result<Self, Self::Error> {
         match item {
-            pb_registry_crypto::EcdsaCurve::Secp256k1 => Ok(EcdsaCurve::Secp256k1),
-            pb_registry_crypto::EcdsaCurve::Unspecified => Err(ProxyDecodeError::ValueOutOfRange {
-                typ: "EcdsaCurve",
-                err: format!("Unable to convert {:?} to an EcdsaCurve", item),
+            pb_registry_crypto::VetKdCurve::Bls12381G2 => Ok(Self::Bls12381G2),
+            pb_registry_crypto::VetKdCurve::Unspecified => Err(ProxyDecodeError::ValueOutOfRange {
+                typ: "VetKdCurve",
+                err: format!("Unable to convert {:?} to a VetKdCurve", item),
             }),
         }
     }
 }
 
-impl From<EcdsaCurve> for pb_registry_crypto::EcdsaCurve {
-    fn from(item: EcdsaCurve) -> Self {
+impl From<VetKdCurve> for pb_registry_crypto::VetKdCurve {
+    fn from(item: VetKdCurve) -> Self {
         match item {
-            EcdsaCurve::Secp256k1 => pb_registry_crypto::EcdsaCurve::Secp256k1,
+            VetKdCurve::Bls12381G2 => pb_registry_crypto::VetKdCurve::Bls12381G2,
         }
     }
 }
 
-impl std::fmt::Display for EcdsaCurve {
+impl std::fmt::Display for VetKdCurve {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{:?}", self)
     }
 }
 
-impl FromStr for EcdsaCurve {
+impl FromStr for VetKdCurve {
     type Err = String;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         match s {
-            "Secp256k1" => Ok(Self::Secp256k1),
-            _ => Err(format!("{} is not a recognized ECDSA curve", s)),
+            "Bls12381G2" => Ok(Self::Bls12381G2),
+            _ => Err(format!("{} is not a recognized vetKD curve", s)),
         }
     }
 }
 
 #[test]
-fn ecdsa_curve_round_trip() {
+fn vetkd_curve_round_trip() {
     assert_eq!(
-        format!("{}", EcdsaCurve::Secp256k1)
-            .parse::<EcdsaCurve>()
+        format!("{}", VetKdCurve::Bls12381G2)
+            .parse::<VetKdCurve>()
             .unwrap(),
-        EcdsaCurve::Secp256k1
+        VetKdCurve::Bls12381G2
     );
 }
 
-/// Unique identifier for a key that can be used for ECDSA signatures. The name
-/// is just a identifier, but it may be used to convey some information about
-/// the key (e.g. that the key is meant to be used for testing purposes).
+/// Unique identifier for a key that can be used for vetKD. The name is just
+/// an identifier, but it may be used to convey some information about the key
+/// (e.g. that the key is meant to be used for testing purposes).
 /// ```text
-/// (record { curve: ecdsa_curve; name: text})
+/// (record { curve: vetkd_curve; name: text})
 /// ```
 #[derive(
     CandidType, Clone, Debug, PartialOrd, Ord, PartialEq, Eq, Serialize, Deserialize, Hash,
 )]
-pub struct EcdsaKeyId {
-    pub curve: EcdsaCurve,
+pub struct VetKdKeyId {
+    pub curve: VetKdCurve,
     pub name: String,
 }
 
-impl TryFrom<pb_registry_crypto::EcdsaKeyId> for EcdsaKeyId {
+impl TryFrom<pb_registry_crypto::VetKdKeyId> for VetKdKeyId {
     type Error = ProxyDecodeError;
-    fn try_from(item: pb_registry_crypto::EcdsaKeyId) -> Result<Self, Self::Error> {
+    fn try_from(item: pb_registry_crypto::VetKdKeyId) -> Result<Self, Self::Error> {
         Ok(Self {
-            curve: EcdsaCurve::try_from(
-                pb_registry_crypto::EcdsaCurve::from_i32(item.curve).ok_or(
+            curve: VetKdCurve::try_from(
+                pb_registry_crypto::VetKdCurve::from_i32(item.curve).ok_or(
                     ProxyDecodeError::ValueOutOfRange {
-                        typ: "EcdsaKeyId",
-                        err: format!("Unable to convert {} to an EcdsaCurve", item.curve),
+                        typ: "VetKdKeyId",
+                        err: format!("Unable to convert {} to a VetKdCurve", item.curve),
                     },
                 )?,
             )?,
@@ -841,101 +2114,110 @@ impl TryFrom<pb_registry_crypto::EcdsaKeyId> for EcdsaKeyId {
     }
 }
 
-impl From<&EcdsaKeyId> for pb_registry_crypto::EcdsaKeyId {
-    fn from(item: &EcdsaKeyId) -> Self {
+impl From<&VetKdKeyId> for pb_registry_crypto::VetKdKeyId {
+    fn from(item: &VetKdKeyId) -> Self {
         Self {
-            curve: pb_registry_crypto::EcdsaCurve::from(item.curve) as i32,
+            curve: pb_registry_crypto::VetKdCurve::from(item.curve) as i32,
             name: item.name.clone(),
         }
     }
 }
 
-impl std::fmt::Display for EcdsaKeyId {
+impl std::fmt::Display for VetKdKeyId {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}:{}", self.curve, self.name)
     }
 }
 
-impl FromStr for EcdsaKeyId {
+impl FromStr for VetKdKeyId {
     type Err = String;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let (curve, name) = s
             .split_once(':')
-            .ok_or_else(|| format!("ECDSA key id {} does not contain a ':'", s))?;
-        Ok(EcdsaKeyId {
-            curve: curve.parse::<EcdsaCurve>()?,
+            .ok_or_else(|| format!("vetKD key id {} does not contain a ':'", s))?;
+        Ok(VetKdKeyId {
+            curve: curve.parse::<VetKdCurve>()?,
             name: name.to_string(),
         })
     }
 }
 
 #[test]
-fn ecdsa_key_id_round_trip() {
-    for name in ["secp256k1", "", "other_key", "other key", "other:key"] {
-        let key = EcdsaKeyId {
-            curve: EcdsaCurve::Secp256k1,
+fn vetkd_key_id_round_trip() {
+    for name in ["bls12_381_g2", "", "other_key", "other key", "other:key"] {
+        let key = VetKdKeyId {
+            curve: VetKdCurve::Bls12381G2,
             name: name.to_string(),
         };
-        assert_eq!(format!("{}", key).parse::<EcdsaKeyId>().unwrap(), key);
+        assert_eq!(format!("{}", key).parse::<VetKdKeyId>().unwrap(), key);
     }
 }
 
-/// Represents the argument of the sign_with_ecdsa API.
+/// Represents the argument of the vetkd_public_key API.
 /// ```text
 /// (record {
-///   message_hash : blob;
+///   canister_id : opt canister_id;
 ///   derivation_path : vec blob;
-///   key_id : ecdsa_key_id;
+///   key_id : vetkd_key_id;
 /// })
 /// ```
 #[derive(CandidType, Deserialize, Debug)]
-pub struct SignWithECDSAArgs {
-    pub message_hash: [u8; 32],
+pub struct VetKdPublicKeyArgs {
+    pub canister_id: Option<CanisterId>,
     pub derivation_path: Vec<Vec<u8>>,
-    pub key_id: EcdsaKeyId,
+    pub key_id: VetKdKeyId,
 }
 
-impl Payload<'_> for SignWithECDSAArgs {}
+impl Payload<'_> for VetKdPublicKeyArgs {}
 
-/// Struct used to return an ECDSA signature.
+/// Represents the response of the vetkd_public_key API.
+/// ```text
+/// (record {
+///   public_key : blob;
+/// })
+/// ```
 #[derive(CandidType, Deserialize, Debug)]
-pub struct SignWithECDSAReply {
-    pub signature: Vec<u8>,
+pub struct VetKdPublicKeyResponse {
+    #[serde(with = "serde_bytes")]
+    pub public_key: Vec<u8>,
 }
 
-impl Payload<'_> for SignWithECDSAReply {}
+impl Payload<'_> for VetKdPublicKeyResponse {}
 
-/// Represents the argument of the ecdsa_public_key API.
+/// Represents the argument of the vetkd_encrypted_key API.
 /// ```text
 /// (record {
-///   canister_id : opt canister_id;
 ///   derivation_path : vec blob;
-///   key_id : ecdsa_key_id;
+///   input : blob;
+///   key_id : vetkd_key_id;
+///   transport_public_key : blob;
 /// })
 /// ```
 #[derive(CandidType, Deserialize, Debug)]
-pub struct ECDSAPublicKeyArgs {
-    pub canister_id: Option<CanisterId>,
+pub struct VetKdEncryptedKeyArgs {
     pub derivation_path: Vec<Vec<u8>>,
-    pub key_id: EcdsaKeyId,
+    #[serde(with = "serde_bytes")]
+    pub input: Vec<u8>,
+    pub key_id: VetKdKeyId,
+    #[serde(with = "serde_bytes")]
+    pub transport_public_key: Vec<u8>,
 }
 
-impl Payload<'_> for ECDSAPublicKeyArgs {}
+impl Payload<'_> for VetKdEncryptedKeyArgs {}
 
-/// Represents the response of the ecdsa_public_key API.
+/// Struct used to return an encrypted vetKD key.
 /// ```text
 /// (record {
-///   public_key : blob;
-///   chain_code : blob;
+///   encrypted_key : blob;
 /// })
 /// ```
 #[derive(CandidType, Deserialize, Debug)]
-pub struct ECDSAPublicKeyResponse {
-    pub public_key: Vec<u8>,
-    pub chain_code: Vec<u8>,
+pub struct VetKdEncryptedKeyReply {
+    #[serde(with = "serde_bytes")]
+    pub encrypted_key: Vec<u8>,
 }
 
-impl Payload<'_> for ECDSAPublicKeyResponse {}
+impl Payload<'_> for VetKdEncryptedKeyReply {}
 
 /// Argument of the compute_initial_ecdsa_dealings API.
 /// `(record {
@@ -526,6 +526,15 @@ impl ChunkableArtifact for StateSyncMessage {
                 }
             }
 
+            // P2P serves state sync chunks by calling `get_chunk` on the message
+            // returned by the state manager, so this is the only place where the
+            // sending side can compress them.
+            if _chunk_id != crate::state_sync::MANIFEST_CHUNK
+                && self.manifest.version >= crate::state_sync::MIN_COMPRESSED_CHUNKS_VERSION
+            {
+                payload = zstd::bulk::compress(&payload, zstd::DEFAULT_COMPRESSION_LEVEL).ok()?;
+            }
+
             Some(ArtifactChunk {
                 chunk_id: _chunk_id,
                 witness: Vec::new(),
@@ -41,11 +41,19 @@
 //! The blockmaker indicates, which requests have timed out, i.e. the blocktime of the latest finalized block is higher than
 //! the timestamp of a request plus the timeout interval. This condition is verifiable by the other nodes in the network.
 //! Once a timeout has made it into a finalized block, the request is answered with an error message.
+//!
+//! 5. A canister may opt out of consensus on the response by setting `is_replicated = false`.
+//! In that case, execution designates a single node of the subnet in the [`Replication`] of the request context.
+//! Only that node makes the request and signs the resulting metadata. A single share signed by the designated
+//! node is sufficient to include the response in a block, and no divergence detection takes place.
+//! The canister therefore has to trust the designated node to faithfully relay the server's response,
+//! i.e. a malicious designated node can return arbitrary content.
 use crate::{
     crypto::{CryptoHashOf, Signed},
-    messages::{CallbackId, RejectContext, Request},
+    messages::{CallbackId, RejectContext, Request, NO_DEADLINE},
+    node_id_into_protobuf, node_id_try_from_protobuf,
     signature::*,
-    CanisterId, CountBytes, RegistryVersion, Time,
+    CanisterId, CountBytes, NodeId, RegistryVersion, Time,
 };
 use ic_base_types::{NumBytes, PrincipalId};
 use ic_error_types::{ErrorCode, RejectCode, UserError};
@@ -108,6 +116,18 @@ impl From<TransformContext> for Transform {
     }
 }
 
+/// Specifies which nodes make a canister http request and how much agreement
+/// on the response is required before it is delivered.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Replication {
+    /// All nodes of the subnet make the request and a threshold of them have
+    /// to agree on the response.
+    FullyReplicated,
+    /// Only the given node makes the request and its signed response is
+    /// delivered without any further agreement.
+    NonReplicated(NodeId),
+}
+
 #[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct CanisterHttpRequestContext {
     pub request: Request,
@@ -119,6 +139,7 @@ pub struct CanisterHttpRequestContext {
     pub http_method: CanisterHttpMethod,
     pub transform: Option<Transform>,
     pub time: Time,
+    pub replication: Replication,
 }
 
 impl From<&CanisterHttpRequestContext> for pb_metadata::CanisterHttpRequestContext {
@@ -149,6 +170,10 @@ impl From<&CanisterHttpRequestContext> for pb_metadata::CanisterHttpRequestConte
                 .map(|transform| transform.context.clone()),
             http_method: pb_metadata::HttpMethod::from(&context.http_method).into(),
             time: context.time.as_nanos_since_unix_epoch(),
+            non_replicated_node_id: match context.replication {
+                Replication::FullyReplicated => None,
+                Replication::NonReplicated(node_id) => Some(node_id_into_protobuf(node_id)),
+            },
         }
     }
 }
@@ -182,6 +207,11 @@ impl TryFrom<pb_metadata::CanisterHttpRequestContext> for CanisterHttpRequestCon
             (None, None) => None,
         };
 
+        let replication = match context.non_replicated_node_id {
+            Some(node_id) => Replication::NonReplicated(node_id_try_from_protobuf(node_id)?),
+            None => Replication::FullyReplicated,
+        };
+
         Ok(CanisterHttpRequestContext {
             request,
             url: context.url,
@@ -206,6 +236,7 @@ impl TryFrom<pb_metadata::CanisterHttpRequestContext> for CanisterHttpRequestCon
                 .try_into()?,
             transform,
             time: Time::from_nanos_since_unix_epoch(context.time),
+            replication,
         })
     }
 }
@@ -262,6 +293,11 @@ pub fn validate_http_headers_and_body(
     Ok(())
 }
 
+/// Converts the arguments of a canister http request into a
+/// [`CanisterHttpRequestContext`].
+///
+/// The resulting context is always [`Replication::FullyReplicated`]. Choosing
+/// the designated node of a non-replicated request is up to the caller.
 impl TryFrom<(Time, &Request, CanisterHttpRequestArgs)> for CanisterHttpRequestContext {
     type Error = CanisterHttpRequestContextError;
 
@@ -321,9 +357,13 @@ impl TryFrom<(Time, &Request, CanisterHttpRequestArgs)> for CanisterHttpRequestC
                 HttpMethod::GET => CanisterHttpMethod::GET,
                 HttpMethod::POST => CanisterHttpMethod::POST,
                 HttpMethod::HEAD => CanisterHttpMethod::HEAD,
+                HttpMethod::PUT => CanisterHttpMethod::PUT,
+                HttpMethod::DELETE => CanisterHttpMethod::DELETE,
+                HttpMethod::PATCH => CanisterHttpMethod::PATCH,
             },
             transform: args.transform.map(From::from),
             time,
+            replication: Replication::FullyReplicated,
         })
     }
 }
@@ -517,6 +557,9 @@ pub enum CanisterHttpMethod {
     GET,
     POST,
     HEAD,
+    PUT,
+    DELETE,
+    PATCH,
 }
 
 impl From<&CanisterHttpMethod> for pb_metadata::HttpMethod {
@@ -525,6 +568,9 @@ impl From<&CanisterHttpMethod> for pb_metadata::HttpMethod {
             CanisterHttpMethod::GET => pb_metadata::HttpMethod::Get,
             CanisterHttpMethod::POST => pb_metadata::HttpMethod::Post,
             CanisterHttpMethod::HEAD => pb_metadata::HttpMethod::Head,
+            CanisterHttpMethod::PUT => pb_metadata::HttpMethod::Put,
+            CanisterHttpMethod::DELETE => pb_metadata::HttpMethod::Delete,
+            CanisterHttpMethod::PATCH => pb_metadata::HttpMethod::Patch,
         }
     }
 }
@@ -537,6 +583,9 @@ impl TryFrom<pb_metadata::HttpMethod> for CanisterHttpMethod {
             pb_metadata::HttpMethod::Get => Ok(CanisterHttpMethod::GET),
             pb_metadata::HttpMethod::Post => Ok(CanisterHttpMethod::POST),
             pb_metadata::HttpMethod::Head => Ok(CanisterHttpMethod::HEAD),
+            pb_metadata::HttpMethod::Put => Ok(CanisterHttpMethod::PUT),
+            pb_metadata::HttpMethod::Delete => Ok(CanisterHttpMethod::DELETE),
+            pb_metadata::HttpMethod::Patch => Ok(CanisterHttpMethod::PATCH),
             pb_metadata::HttpMethod::Unspecified => Err(ProxyDecodeError::ValueOutOfRange {
                 typ: "ic_protobuf::state::system_metadata::v1::HttpMethod",
                 err: "Unspecified HttpMethod".to_string(),
@@ -645,11 +694,13 @@ mod tests {
                 receiver: CanisterId::ic_00(),
                 sender: CanisterId::ic_00(),
                 sender_reply_callback: CallbackId::from(3),
+                deadline: NO_DEADLINE,
                 payment: Cycles::new(10),
                 method_name: "tansform".to_string(),
                 method_payload: Vec::new(),
             },
             time: UNIX_EPOCH,
+            replication: Replication::FullyReplicated,
         };
 
         let expected_size = context.url.len()
@@ -685,11 +736,13 @@ mod tests {
                 receiver: CanisterId::ic_00(),
                 sender: CanisterId::ic_00(),
                 sender_reply_callback: CallbackId::from(3),
+                deadline: NO_DEADLINE,
                 payment: Cycles::new(10),
                 method_name: "tansform".to_string(),
                 method_payload: Vec::new(),
             },
             time: UNIX_EPOCH,
+            replication: Replication::FullyReplicated,
         };
 
         let expected_size = context.url.len()
@@ -701,4 +754,19 @@ mod tests {
             NumBytes::from(expected_size as u64)
         );
     }
+
+    #[test]
+    fn test_http_method_proto_round_trip() {
+        for method in [
+            CanisterHttpMethod::GET,
+            CanisterHttpMethod::POST,
+            CanisterHttpMethod::HEAD,
+            CanisterHttpMethod::PUT,
+            CanisterHttpMethod::DELETE,
+            CanisterHttpMethod::PATCH,
+        ] {
+            let proto = pb_metadata::HttpMethod::from(&method);
+            assert_eq!(CanisterHttpMethod::try_from(proto).unwrap(), method);
+        }
+    }
 }
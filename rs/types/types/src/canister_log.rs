@@ -0,0 +1,139 @@
+//! The log of a canister: the output of `ic0.debug_print` and `ic0.trap`
+//! produced during replicated execution.
+use ic_ic00_types::CanisterLogRecord;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+/// The maximum total size of the log records kept for a canister, in bytes.
+/// Once it is exceeded, the oldest records are dropped.
+pub const MAX_ALLOWED_CANISTER_LOG_BUFFER_SIZE: usize = 4 * 1024;
+
+/// The size of the fixed part of a log record (`idx` and `timestamp_nanos`).
+const RECORD_METADATA_SIZE: usize = 2 * std::mem::size_of::<u64>();
+
+/// A bounded buffer of the most recent log records of a canister.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CanisterLog {
+    next_idx: u64,
+    records: VecDeque<CanisterLogRecord>,
+    records_size: usize,
+}
+
+impl CanisterLog {
+    /// Creates a log from the given records. `next_idx` is the index the
+    /// next added record gets.
+    pub fn new(next_idx: u64, records: Vec<CanisterLogRecord>) -> Self {
+        let mut log = Self {
+            next_idx,
+            records: VecDeque::new(),
+            records_size: 0,
+        };
+        for record in records {
+            log.push_record(record);
+        }
+        log
+    }
+
+    /// Creates an empty log whose next added record gets index `next_idx`.
+    pub fn new_with_next_index(next_idx: u64) -> Self {
+        Self::new(next_idx, vec![])
+    }
+
+    /// Returns the index the next added record gets.
+    pub fn next_idx(&self) -> u64 {
+        self.next_idx
+    }
+
+    /// Returns the records in the order they were added.
+    pub fn records(&self) -> &VecDeque<CanisterLogRecord> {
+        &self.records
+    }
+
+    /// Returns the total size of the records, in bytes.
+    pub fn used_space(&self) -> usize {
+        self.records_size
+    }
+
+    /// Adds a record with the given timestamp and content, truncating the
+    /// content if it does not fit into the buffer on its own.
+    pub fn add_record(&mut self, timestamp_nanos: u64, mut content: Vec<u8>) {
+        content.truncate(MAX_ALLOWED_CANISTER_LOG_BUFFER_SIZE - RECORD_METADATA_SIZE);
+        let record = CanisterLogRecord {
+            idx: self.next_idx,
+            timestamp_nanos,
+            content,
+        };
+        self.next_idx += 1;
+        self.push_record(record);
+    }
+
+    /// Moves all records of `other` into this log, leaving `other` empty.
+    pub fn append(&mut self, other: &mut CanisterLog) {
+        self.next_idx = self.next_idx.max(other.next_idx);
+        other.records_size = 0;
+        for record in other.records.drain(..) {
+            self.push_record(record);
+        }
+    }
+
+    /// Removes all records. Record indices keep increasing.
+    pub fn clear(&mut self) {
+        self.records.clear();
+        self.records_size = 0;
+    }
+
+    fn push_record(&mut self, record: CanisterLogRecord) {
+        self.next_idx = self.next_idx.max(record.idx + 1);
+        self.records_size += record_size(&record);
+        self.records.push_back(record);
+        while self.records_size > MAX_ALLOWED_CANISTER_LOG_BUFFER_SIZE {
+            let dropped = self.records.pop_front().unwrap();
+            self.records_size -= record_size(&dropped);
+        }
+    }
+}
+
+fn record_size(record: &CanisterLogRecord) -> usize {
+    RECORD_METADATA_SIZE + record.content.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn records_get_increasing_indices() {
+        let mut log = CanisterLog::new_with_next_index(5);
+        log.add_record(100, b"a".to_vec());
+        log.add_record(200, b"b".to_vec());
+        let idx: Vec<_> = log.records().iter().map(|r| r.idx).collect();
+        assert_eq!(idx, vec![5, 6]);
+        assert_eq!(log.next_idx(), 7);
+    }
+
+    #[test]
+    fn oldest_records_are_dropped_when_buffer_is_full() {
+        let mut log = CanisterLog::default();
+        let content = vec![0; 1000];
+        for i in 0..10 {
+            log.add_record(i, content.clone());
+        }
+        assert!(log.used_space() <= MAX_ALLOWED_CANISTER_LOG_BUFFER_SIZE);
+        assert_eq!(log.records().back().unwrap().idx, 9);
+        assert_eq!(log.records().front().unwrap().idx, 6);
+    }
+
+    #[test]
+    fn append_moves_records_and_keeps_indices() {
+        let mut log = CanisterLog::default();
+        log.add_record(1, b"first".to_vec());
+        let mut delta = CanisterLog::new_with_next_index(log.next_idx());
+        delta.add_record(2, b"second".to_vec());
+
+        log.append(&mut delta);
+        assert!(delta.records().is_empty());
+        assert_eq!(log.next_idx(), 2);
+        let contents: Vec<_> = log.records().iter().map(|r| r.content.clone()).collect();
+        assert_eq!(contents, vec![b"first".to_vec(), b"second".to_vec()]);
+    }
+}
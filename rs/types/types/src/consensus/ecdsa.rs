@@ -13,7 +13,9 @@ pub use crate::consensus::ecdsa_refs::{
     IDkgTranscriptOperationRef, IDkgTranscriptParamsRef, MaskedTranscript,
     PreSignatureQuadrupleRef, PseudoRandomId, QuadrupleId, QuadrupleInCreation,
     RandomTranscriptParams, RequestId, ReshareOfMaskedParams, ReshareOfUnmaskedParams,
-    ThresholdEcdsaSigInputsError, ThresholdEcdsaSigInputsRef, TranscriptAttributes,
+    SchnorrPreSignatureInCreation, SchnorrPreSignatureTranscriptError,
+    SchnorrPreSignatureTranscriptRef, ThresholdEcdsaSigInputsError, ThresholdEcdsaSigInputsRef,
+    ThresholdSchnorrSigInputsError, ThresholdSchnorrSigInputsRef, TranscriptAttributes,
     TranscriptCastError, TranscriptLookupError, TranscriptParamsError, TranscriptRef,
     UnmaskedTimesMaskedParams, UnmaskedTranscript,
 };
@@ -24,14 +26,17 @@ use crate::crypto::{
         IDkgComplaint, IDkgDealingSupport, IDkgOpening, IDkgTranscript, IDkgTranscriptId,
         IDkgTranscriptParams, InitialIDkgDealings, SignedIDkgDealing,
     },
-    canister_threshold_sig::ThresholdEcdsaSigShare,
-    crypto_hash, AlgorithmId, CryptoHash, CryptoHashOf, CryptoHashable, Signed,
+    canister_threshold_sig::{ThresholdEcdsaSigShare, ThresholdSchnorrSigShare},
+    crypto_hash,
+    threshold_sig::ni_dkg::NiDkgId,
+    vetkd::{VetKdArgs, VetKdEncryptedKeyShare},
+    AlgorithmId, CryptoHash, CryptoHashOf, CryptoHashable, Signed,
     SignedBytesWithoutDomainSeparator,
 };
 use crate::{node_id_into_protobuf, node_id_try_from_protobuf};
 use crate::{Height, NodeId, RegistryVersion, SubnetId};
 use ic_crypto_sha::Sha256;
-use ic_ic00_types::EcdsaKeyId;
+use ic_ic00_types::{EcdsaKeyId, SchnorrKeyId};
 use ic_protobuf::registry::subnet::v1 as subnet_pb;
 use ic_protobuf::types::v1 as pb;
 use phantom_newtype::Id;
@@ -76,6 +81,21 @@ pub struct EcdsaPayload {
 
     /// State of the key transcripts.
     pub key_transcript: EcdsaKeyTranscript,
+
+    /// State of the threshold Schnorr key transcript, if the subnet holds one.
+    pub schnorr_key_transcript: Option<SchnorrKeyTranscript>,
+
+    /// The `RequestIds` for which we are currently generating Schnorr signatures.
+    pub ongoing_schnorr_signatures: BTreeMap<RequestId, ThresholdSchnorrSigInputsRef>,
+
+    /// Schnorr pre-signatures that we can use to create Schnorr signatures.
+    pub available_schnorr_presigs: BTreeMap<QuadrupleId, SchnorrPreSignatureTranscriptRef>,
+
+    /// Schnorr pre-signatures in creation.
+    pub schnorr_presigs_in_creation: BTreeMap<QuadrupleId, SchnorrPreSignatureInCreation>,
+
+    /// The `RequestIds` for which we are currently deriving vetKD keys.
+    pub ongoing_vetkd_requests: BTreeMap<RequestId, VetKdArgs>,
 }
 
 impl EcdsaPayload {
@@ -93,6 +113,16 @@ impl EcdsaPayload {
             .transcript_config_in_creation()
             .into_iter()
             .chain(iter);
+        let iter = self
+            .schnorr_key_transcript
+            .iter()
+            .flat_map(|key_transcript| key_transcript.transcript_config_in_creation())
+            .chain(iter);
+        let iter = self
+            .schnorr_presigs_in_creation
+            .values()
+            .flat_map(|presig| presig.iter_transcript_configs_in_creation())
+            .chain(iter);
         Box::new(
             self.quadruples_in_creation
                 .iter()
@@ -157,6 +187,30 @@ impl EcdsaPayload {
         )
     }
 
+    /// Return an iterator of all ids of Schnorr pre-signatures in the payload.
+    pub fn iter_schnorr_presig_ids(&self) -> Box<dyn Iterator<Item = QuadrupleId> + '_> {
+        Box::new(
+            self.available_schnorr_presigs
+                .keys()
+                .chain(self.schnorr_presigs_in_creation.keys())
+                .cloned(),
+        )
+    }
+
+    /// Return an iterator of all Schnorr pre-signature ids that are not yet
+    /// paired with a Schnorr signature request in `ongoing_schnorr_signatures`.
+    pub fn unassigned_schnorr_presig_ids(&self) -> Box<dyn Iterator<Item = QuadrupleId> + '_> {
+        let assigned = self
+            .ongoing_schnorr_signatures
+            .keys()
+            .map(|id| id.quadruple_id)
+            .collect::<BTreeSet<_>>();
+        Box::new(
+            self.iter_schnorr_presig_ids()
+                .filter(move |id| !assigned.contains(id)),
+        )
+    }
+
     /// Return active transcript references in the  payload.
     pub fn active_transcripts(&self) -> BTreeSet<TranscriptRef> {
         let mut active_refs = BTreeSet::new();
@@ -178,6 +232,18 @@ impl EcdsaPayload {
             insert(obj.as_ref().get_refs())
         }
         insert(self.key_transcript.get_refs());
+        for obj in self.ongoing_schnorr_signatures.values() {
+            insert(obj.get_refs())
+        }
+        for obj in self.available_schnorr_presigs.values() {
+            insert(obj.get_refs())
+        }
+        for obj in self.schnorr_presigs_in_creation.values() {
+            insert(obj.get_refs())
+        }
+        if let Some(key_transcript) = &self.schnorr_key_transcript {
+            insert(key_transcript.get_refs());
+        }
         active_refs
     }
 
@@ -195,7 +261,19 @@ impl EcdsaPayload {
         for obj in self.ongoing_xnet_reshares.values_mut() {
             obj.as_mut().update(height);
         }
-        self.key_transcript.update_refs(height)
+        self.key_transcript.update_refs(height);
+        for obj in self.ongoing_schnorr_signatures.values_mut() {
+            obj.update(height);
+        }
+        for obj in self.available_schnorr_presigs.values_mut() {
+            obj.update(height);
+        }
+        for obj in self.schnorr_presigs_in_creation.values_mut() {
+            obj.update(height);
+        }
+        if let Some(key_transcript) = &mut self.schnorr_key_transcript {
+            key_transcript.update_refs(height);
+        }
     }
 
     /// Return the oldest registry version required to keep nodes in the subnet
@@ -206,8 +284,7 @@ impl EcdsaPayload {
     /// Note that we do not consider available quadruples here because it would
     /// prevent nodes from leaving when the quadruples are not consumed.
     pub(crate) fn get_oldest_registry_version_in_use(&self) -> Option<RegistryVersion> {
-        // Both current key transcript and next_in_creation are considered.
-        use KeyTranscriptCreation::*;
+        // Both current key transcripts and next_in_creation are considered.
         let idkg_transcripts = &self.idkg_transcripts;
         let min_version = |version_1: Option<RegistryVersion>, version_2| {
             if version_1.is_none() {
@@ -221,26 +298,38 @@ impl EcdsaPayload {
             .current
             .as_ref()
             .map(|transcript| transcript.registry_version());
-        let in_creation_version = match &self.key_transcript.next_in_creation {
-            Begin => None,
-            RandomTranscriptParams(params) => Some(params.as_ref().registry_version()),
-            ReshareOfMaskedParams(params) => Some(params.as_ref().registry_version()),
-            ReshareOfUnmaskedParams(params) => Some(params.as_ref().registry_version()),
-            XnetReshareOfUnmaskedParams(_) => None,
-            Created(transcript) => idkg_transcripts
-                .get(&transcript.as_ref().transcript_id)
-                .map(|transcript| transcript.registry_version),
-        };
+        let in_creation_version = self
+            .key_transcript
+            .next_in_creation
+            .registry_version(idkg_transcripts);
         let mut registry_version = min_version(key_version, in_creation_version);
-        for (_, sig_input_ref) in self.ongoing_signatures.iter() {
-            for r in sig_input_ref.get_refs().iter() {
-                registry_version = min_version(
-                    registry_version,
-                    idkg_transcripts
-                        .get(&r.transcript_id)
-                        .map(|transcript| transcript.registry_version),
-                );
-            }
+        if let Some(schnorr_key_transcript) = &self.schnorr_key_transcript {
+            let key_version = schnorr_key_transcript
+                .current
+                .as_ref()
+                .map(|transcript| transcript.registry_version());
+            registry_version = min_version(registry_version, key_version);
+            let in_creation_version = schnorr_key_transcript
+                .next_in_creation
+                .registry_version(idkg_transcripts);
+            registry_version = min_version(registry_version, in_creation_version);
+        }
+        let ongoing_refs = self
+            .ongoing_signatures
+            .values()
+            .flat_map(|sig_input_ref| sig_input_ref.get_refs())
+            .chain(
+                self.ongoing_schnorr_signatures
+                    .values()
+                    .flat_map(|sig_input_ref| sig_input_ref.get_refs()),
+            );
+        for r in ongoing_refs {
+            registry_version = min_version(
+                registry_version,
+                idkg_transcripts
+                    .get(&r.transcript_id)
+                    .map(|transcript| transcript.registry_version),
+            );
         }
         registry_version
     }
@@ -338,16 +427,7 @@ pub struct EcdsaKeyTranscript {
 
 impl EcdsaKeyTranscript {
     pub fn get_refs(&self) -> Vec<TranscriptRef> {
-        let mut active_refs = match &self.next_in_creation {
-            KeyTranscriptCreation::Begin => vec![],
-            KeyTranscriptCreation::RandomTranscriptParams(params) => params.as_ref().get_refs(),
-            KeyTranscriptCreation::ReshareOfMaskedParams(params) => params.as_ref().get_refs(),
-            KeyTranscriptCreation::ReshareOfUnmaskedParams(params) => params.as_ref().get_refs(),
-            KeyTranscriptCreation::XnetReshareOfUnmaskedParams((_, params)) => {
-                params.as_ref().get_refs()
-            }
-            KeyTranscriptCreation::Created(unmasked) => vec![*unmasked.as_ref()],
-        };
+        let mut active_refs = self.next_in_creation.get_refs();
         if let Some(unmasked) = &self.current {
             active_refs.push(*unmasked.as_ref());
         }
@@ -355,32 +435,14 @@ impl EcdsaKeyTranscript {
     }
 
     fn update_refs(&mut self, height: Height) {
-        match &mut self.next_in_creation {
-            KeyTranscriptCreation::Begin => (),
-            KeyTranscriptCreation::RandomTranscriptParams(params) => params.as_mut().update(height),
-            KeyTranscriptCreation::ReshareOfMaskedParams(params) => params.as_mut().update(height),
-            KeyTranscriptCreation::ReshareOfUnmaskedParams(params) => {
-                params.as_mut().update(height)
-            }
-            KeyTranscriptCreation::XnetReshareOfUnmaskedParams((_, params)) => {
-                params.as_mut().update(height)
-            }
-            KeyTranscriptCreation::Created(unmasked) => unmasked.as_mut().update(height),
-        }
+        self.next_in_creation.update_refs(height);
         if let Some(unmasked) = &mut self.current {
             unmasked.as_mut().update(height);
         }
     }
 
     pub fn transcript_config_in_creation(&self) -> Option<&IDkgTranscriptParamsRef> {
-        match &self.next_in_creation {
-            KeyTranscriptCreation::Begin => None,
-            KeyTranscriptCreation::RandomTranscriptParams(x) => Some(x.as_ref()),
-            KeyTranscriptCreation::ReshareOfMaskedParams(x) => Some(x.as_ref()),
-            KeyTranscriptCreation::ReshareOfUnmaskedParams(x) => Some(x.as_ref()),
-            KeyTranscriptCreation::XnetReshareOfUnmaskedParams((_, x)) => Some(x.as_ref()),
-            KeyTranscriptCreation::Created(_) => None,
-        }
+        self.next_in_creation.transcript_config_in_creation()
     }
 }
 
@@ -422,6 +484,94 @@ impl Display for EcdsaKeyTranscript {
     }
 }
 
+/// State of the threshold Schnorr key transcript.
+///
+/// The key transcript is created by the same state machine as the ECDSA key
+/// transcript (see [`KeyTranscriptCreation`]), using a threshold Schnorr
+/// algorithm id for all of its transcripts.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct SchnorrKeyTranscript {
+    /// The Schnorr key transcript used for the current interval.
+    pub current: Option<UnmaskedTranscriptWithAttributes>,
+    /// Progress of creating the next Schnorr key transcript.
+    pub next_in_creation: KeyTranscriptCreation,
+    /// Key id.
+    pub key_id: SchnorrKeyId,
+}
+
+impl SchnorrKeyTranscript {
+    pub fn new(key_id: SchnorrKeyId) -> Self {
+        Self {
+            current: None,
+            next_in_creation: KeyTranscriptCreation::Begin,
+            key_id,
+        }
+    }
+
+    pub fn get_refs(&self) -> Vec<TranscriptRef> {
+        let mut active_refs = self.next_in_creation.get_refs();
+        if let Some(unmasked) = &self.current {
+            active_refs.push(*unmasked.as_ref());
+        }
+        active_refs
+    }
+
+    fn update_refs(&mut self, height: Height) {
+        self.next_in_creation.update_refs(height);
+        if let Some(unmasked) = &mut self.current {
+            unmasked.as_mut().update(height);
+        }
+    }
+
+    pub fn transcript_config_in_creation(&self) -> Option<&IDkgTranscriptParamsRef> {
+        self.next_in_creation.transcript_config_in_creation()
+    }
+}
+
+impl From<&SchnorrKeyTranscript> for pb::SchnorrKeyTranscript {
+    fn from(key_transcript: &SchnorrKeyTranscript) -> Self {
+        Self {
+            current: key_transcript
+                .current
+                .as_ref()
+                .map(|transcript| transcript.into()),
+            next_in_creation: Some((&key_transcript.next_in_creation).into()),
+            key_id: Some((&key_transcript.key_id).into()),
+        }
+    }
+}
+
+impl TryFrom<&pb::SchnorrKeyTranscript> for SchnorrKeyTranscript {
+    type Error = String;
+    fn try_from(key_transcript: &pb::SchnorrKeyTranscript) -> Result<Self, Self::Error> {
+        let current = key_transcript
+            .current
+            .as_ref()
+            .map(UnmaskedTranscriptWithAttributes::try_from)
+            .transpose()?;
+        let next_in_creation = key_transcript
+            .next_in_creation
+            .as_ref()
+            .ok_or("pb::SchnorrKeyTranscript:: Missing next_in_creation")?
+            .try_into()?;
+        let key_id = key_transcript
+            .key_id
+            .clone()
+            .ok_or("pb::SchnorrKeyTranscript:: Missing key_id")?;
+        let key_id = SchnorrKeyId::try_from(key_id).map_err(|err| {
+            format!(
+                "pb::SchnorrKeyTranscript:: Failed to convert key_id: {:?}",
+                err
+            )
+        })?;
+        Ok(Self {
+            current,
+            next_in_creation,
+            key_id,
+        })
+    }
+}
+
 /// The creation of an ecdsa key transcript goes through one of the three paths below:
 /// 1. Begin -> RandomTranscript -> ReshareOfMasked -> Created
 /// 2. Begin -> ReshareOfUnmasked -> Created
@@ -452,6 +602,70 @@ pub enum KeyTranscriptCreation {
     Created(UnmaskedTranscript),
 }
 
+impl KeyTranscriptCreation {
+    fn get_refs(&self) -> Vec<TranscriptRef> {
+        match self {
+            KeyTranscriptCreation::Begin => vec![],
+            KeyTranscriptCreation::RandomTranscriptParams(params) => params.as_ref().get_refs(),
+            KeyTranscriptCreation::ReshareOfMaskedParams(params) => params.as_ref().get_refs(),
+            KeyTranscriptCreation::ReshareOfUnmaskedParams(params) => params.as_ref().get_refs(),
+            KeyTranscriptCreation::XnetReshareOfUnmaskedParams((_, params)) => {
+                params.as_ref().get_refs()
+            }
+            KeyTranscriptCreation::Created(unmasked) => vec![*unmasked.as_ref()],
+        }
+    }
+
+    fn update_refs(&mut self, height: Height) {
+        match self {
+            KeyTranscriptCreation::Begin => (),
+            KeyTranscriptCreation::RandomTranscriptParams(params) => params.as_mut().update(height),
+            KeyTranscriptCreation::ReshareOfMaskedParams(params) => params.as_mut().update(height),
+            KeyTranscriptCreation::ReshareOfUnmaskedParams(params) => {
+                params.as_mut().update(height)
+            }
+            KeyTranscriptCreation::XnetReshareOfUnmaskedParams((_, params)) => {
+                params.as_mut().update(height)
+            }
+            KeyTranscriptCreation::Created(unmasked) => unmasked.as_mut().update(height),
+        }
+    }
+
+    fn transcript_config_in_creation(&self) -> Option<&IDkgTranscriptParamsRef> {
+        match self {
+            KeyTranscriptCreation::Begin => None,
+            KeyTranscriptCreation::RandomTranscriptParams(x) => Some(x.as_ref()),
+            KeyTranscriptCreation::ReshareOfMaskedParams(x) => Some(x.as_ref()),
+            KeyTranscriptCreation::ReshareOfUnmaskedParams(x) => Some(x.as_ref()),
+            KeyTranscriptCreation::XnetReshareOfUnmaskedParams((_, x)) => Some(x.as_ref()),
+            KeyTranscriptCreation::Created(_) => None,
+        }
+    }
+
+    /// Returns the registry version of the transcript in creation, if known.
+    fn registry_version(
+        &self,
+        idkg_transcripts: &BTreeMap<IDkgTranscriptId, IDkgTranscript>,
+    ) -> Option<RegistryVersion> {
+        match self {
+            KeyTranscriptCreation::Begin => None,
+            KeyTranscriptCreation::RandomTranscriptParams(params) => {
+                Some(params.as_ref().registry_version())
+            }
+            KeyTranscriptCreation::ReshareOfMaskedParams(params) => {
+                Some(params.as_ref().registry_version())
+            }
+            KeyTranscriptCreation::ReshareOfUnmaskedParams(params) => {
+                Some(params.as_ref().registry_version())
+            }
+            KeyTranscriptCreation::XnetReshareOfUnmaskedParams(_) => None,
+            KeyTranscriptCreation::Created(transcript) => idkg_transcripts
+                .get(&transcript.as_ref().transcript_id)
+                .map(|transcript| transcript.registry_version),
+        }
+    }
+}
+
 impl From<&KeyTranscriptCreation> for pb::KeyTranscriptCreation {
     fn from(key_transcript_in_creation: &KeyTranscriptCreation) -> Self {
         let mut ret = pb::KeyTranscriptCreation {
@@ -657,6 +871,8 @@ pub enum EcdsaMessage {
     EcdsaSigShare(EcdsaSigShare),
     EcdsaComplaint(EcdsaComplaint),
     EcdsaOpening(EcdsaOpening),
+    SchnorrSigShare(SchnorrSigShare),
+    VetKdKeyShare(VetKdKeyShare),
 }
 
 /// EcdsaArtifactId is the unique identifier for the artifacts. It is made of a prefix + crypto
@@ -761,6 +977,28 @@ pub fn sig_share_prefix(
     EcdsaPrefixOf::new(EcdsaPrefix::new(request_id.quadruple_id.0, hasher.finish()))
 }
 
+pub fn schnorr_sig_share_prefix(
+    request_id: &RequestId,
+    sig_share_node_id: &NodeId,
+) -> EcdsaPrefixOf<SchnorrSigShare> {
+    // Group_tag: pre-signature Id, Meta info: <sig share sender>
+    let mut hasher = Sha256::new();
+    sig_share_node_id.hash(&mut hasher);
+
+    EcdsaPrefixOf::new(EcdsaPrefix::new(request_id.quadruple_id.0, hasher.finish()))
+}
+
+pub fn vetkd_key_share_prefix(
+    request_id: &RequestId,
+    key_share_node_id: &NodeId,
+) -> EcdsaPrefixOf<VetKdKeyShare> {
+    // Group_tag: request's quadruple Id, Meta info: <key share sender>
+    let mut hasher = Sha256::new();
+    key_share_node_id.hash(&mut hasher);
+
+    EcdsaPrefixOf::new(EcdsaPrefix::new(request_id.quadruple_id.0, hasher.finish()))
+}
+
 pub fn complaint_prefix(
     transcript_id: &IDkgTranscriptId,
     dealer_id: &NodeId,
@@ -801,6 +1039,11 @@ pub enum EcdsaArtifactId {
     SigShare(EcdsaPrefixOf<EcdsaSigShare>, CryptoHashOf<EcdsaSigShare>),
     Complaint(EcdsaPrefixOf<EcdsaComplaint>, CryptoHashOf<EcdsaComplaint>),
     Opening(EcdsaPrefixOf<EcdsaOpening>, CryptoHashOf<EcdsaOpening>),
+    SchnorrSigShare(
+        EcdsaPrefixOf<SchnorrSigShare>,
+        CryptoHashOf<SchnorrSigShare>,
+    ),
+    VetKdKeyShare(EcdsaPrefixOf<VetKdKeyShare>, CryptoHashOf<VetKdKeyShare>),
 }
 
 impl EcdsaArtifactId {
@@ -811,6 +1054,8 @@ impl EcdsaArtifactId {
             EcdsaArtifactId::SigShare(prefix, _) => prefix.as_ref().clone(),
             EcdsaArtifactId::Complaint(prefix, _) => prefix.as_ref().clone(),
             EcdsaArtifactId::Opening(prefix, _) => prefix.as_ref().clone(),
+            EcdsaArtifactId::SchnorrSigShare(prefix, _) => prefix.as_ref().clone(),
+            EcdsaArtifactId::VetKdKeyShare(prefix, _) => prefix.as_ref().clone(),
         }
     }
 
@@ -821,6 +1066,8 @@ impl EcdsaArtifactId {
             EcdsaArtifactId::SigShare(_, hash) => hash.as_ref().clone(),
             EcdsaArtifactId::Complaint(_, hash) => hash.as_ref().clone(),
             EcdsaArtifactId::Opening(_, hash) => hash.as_ref().clone(),
+            EcdsaArtifactId::SchnorrSigShare(_, hash) => hash.as_ref().clone(),
+            EcdsaArtifactId::VetKdKeyShare(_, hash) => hash.as_ref().clone(),
         }
     }
 
@@ -855,6 +1102,14 @@ impl From<(EcdsaMessageType, EcdsaPrefix, CryptoHash)> for EcdsaArtifactId {
             EcdsaMessageType::Opening => {
                 EcdsaArtifactId::Opening(EcdsaPrefixOf::new(prefix), CryptoHashOf::new(crypto_hash))
             }
+            EcdsaMessageType::SchnorrSigShare => EcdsaArtifactId::SchnorrSigShare(
+                EcdsaPrefixOf::new(prefix),
+                CryptoHashOf::new(crypto_hash),
+            ),
+            EcdsaMessageType::VetKdKeyShare => EcdsaArtifactId::VetKdKeyShare(
+                EcdsaPrefixOf::new(prefix),
+                CryptoHashOf::new(crypto_hash),
+            ),
         }
     }
 }
@@ -868,6 +1123,8 @@ pub enum EcdsaMessageType {
     SigShare,
     Complaint,
     Opening,
+    SchnorrSigShare,
+    VetKdKeyShare,
 }
 
 impl From<&EcdsaMessage> for EcdsaMessageType {
@@ -878,6 +1135,8 @@ impl From<&EcdsaMessage> for EcdsaMessageType {
             EcdsaMessage::EcdsaSigShare(_) => EcdsaMessageType::SigShare,
             EcdsaMessage::EcdsaComplaint(_) => EcdsaMessageType::Complaint,
             EcdsaMessage::EcdsaOpening(_) => EcdsaMessageType::Opening,
+            EcdsaMessage::SchnorrSigShare(_) => EcdsaMessageType::SchnorrSigShare,
+            EcdsaMessage::VetKdKeyShare(_) => EcdsaMessageType::VetKdKeyShare,
         }
     }
 }
@@ -890,6 +1149,8 @@ impl From<&EcdsaArtifactId> for EcdsaMessageType {
             EcdsaArtifactId::SigShare(..) => EcdsaMessageType::SigShare,
             EcdsaArtifactId::Complaint(..) => EcdsaMessageType::Complaint,
             EcdsaArtifactId::Opening(..) => EcdsaMessageType::Opening,
+            EcdsaArtifactId::SchnorrSigShare(..) => EcdsaMessageType::SchnorrSigShare,
+            EcdsaArtifactId::VetKdKeyShare(..) => EcdsaMessageType::VetKdKeyShare,
         }
     }
 }
@@ -902,6 +1163,8 @@ impl EcdsaMessageType {
             Self::SigShare => "sig_share",
             Self::Complaint => "complaint",
             Self::Opening => "opening",
+            Self::SchnorrSigShare => "schnorr_sig_share",
+            Self::VetKdKeyShare => "vetkd_key_share",
         }
     }
 }
@@ -929,6 +1192,52 @@ impl Display for EcdsaSigShare {
     }
 }
 
+/// The threshold Schnorr signature share
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Hash)]
+pub struct SchnorrSigShare {
+    /// The node that signed the share
+    pub signer_id: NodeId,
+
+    /// The request this signature share belongs to
+    pub request_id: RequestId,
+
+    /// The signature share
+    pub share: ThresholdSchnorrSigShare,
+}
+
+impl Display for SchnorrSigShare {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "SchnorrSigShare[request_id = {:?}, signer_id = {:?}]",
+            self.request_id, self.signer_id,
+        )
+    }
+}
+
+/// The vetKD encrypted key share
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Hash)]
+pub struct VetKdKeyShare {
+    /// The node that created the share
+    pub signer_id: NodeId,
+
+    /// The request this key share belongs to
+    pub request_id: RequestId,
+
+    /// The encrypted key share
+    pub share: VetKdEncryptedKeyShare,
+}
+
+impl Display for VetKdKeyShare {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "VetKdKeyShare[request_id = {:?}, signer_id = {:?}]",
+            self.request_id, self.signer_id,
+        )
+    }
+}
+
 /// Complaint related defines
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Hash)]
 pub struct EcdsaComplaintContent {
@@ -1015,6 +1324,8 @@ pub enum EcdsaMessageAttribute {
     EcdsaSigShare(RequestId),
     EcdsaComplaint(IDkgTranscriptId),
     EcdsaOpening(IDkgTranscriptId),
+    SchnorrSigShare(RequestId),
+    VetKdKeyShare(RequestId),
 }
 
 impl From<&EcdsaMessage> for EcdsaMessageAttribute {
@@ -1035,6 +1346,12 @@ impl From<&EcdsaMessage> for EcdsaMessageAttribute {
             EcdsaMessage::EcdsaOpening(opening) => {
                 EcdsaMessageAttribute::EcdsaOpening(opening.content.idkg_opening.transcript_id)
             }
+            EcdsaMessage::SchnorrSigShare(share) => {
+                EcdsaMessageAttribute::SchnorrSigShare(share.request_id)
+            }
+            EcdsaMessage::VetKdKeyShare(share) => {
+                EcdsaMessageAttribute::VetKdKeyShare(share.request_id)
+            }
         }
     }
 }
@@ -1047,6 +1364,8 @@ impl EcdsaMessageAttribute {
             Self::EcdsaSigShare(_) => "sig_share",
             Self::EcdsaComplaint(_) => "complaint",
             Self::EcdsaOpening(_) => "opening",
+            Self::SchnorrSigShare(_) => "schnorr_sig_share",
+            Self::VetKdKeyShare(_) => "vetkd_key_share",
         }
     }
 }
@@ -1101,6 +1420,26 @@ impl TryFrom<EcdsaMessage> for EcdsaOpening {
     }
 }
 
+impl TryFrom<EcdsaMessage> for SchnorrSigShare {
+    type Error = EcdsaMessage;
+    fn try_from(msg: EcdsaMessage) -> Result<Self, Self::Error> {
+        match msg {
+            EcdsaMessage::SchnorrSigShare(x) => Ok(x),
+            _ => Err(msg),
+        }
+    }
+}
+
+impl TryFrom<EcdsaMessage> for VetKdKeyShare {
+    type Error = EcdsaMessage;
+    fn try_from(msg: EcdsaMessage) -> Result<Self, Self::Error> {
+        match msg {
+            EcdsaMessage::VetKdKeyShare(x) => Ok(x),
+            _ => Err(msg),
+        }
+    }
+}
+
 pub type Summary = Option<EcdsaPayload>;
 
 pub type Payload = Option<EcdsaPayload>;
@@ -1192,6 +1531,47 @@ impl From<&EcdsaPayload> for pb::EcdsaPayload {
         let next_key_in_creation = Some((&payload.key_transcript.next_in_creation).into());
         let key_id = Some((&payload.key_transcript.key_id).into());
 
+        let schnorr_key_transcript = payload
+            .schnorr_key_transcript
+            .as_ref()
+            .map(|key_transcript| key_transcript.into());
+
+        // ongoing_schnorr_signatures
+        let mut ongoing_schnorr_signatures = Vec::new();
+        for (request_id, ongoing) in &payload.ongoing_schnorr_signatures {
+            ongoing_schnorr_signatures.push(pb::OngoingSchnorrSignature {
+                request_id: Some((*request_id).into()),
+                sig_inputs: Some(ongoing.into()),
+            })
+        }
+
+        // available_schnorr_presigs
+        let mut available_schnorr_presigs = Vec::new();
+        for (presig_id, presig) in &payload.available_schnorr_presigs {
+            available_schnorr_presigs.push(pb::AvailableSchnorrPreSignature {
+                quadruple_id: presig_id.0,
+                presig: Some(presig.into()),
+            });
+        }
+
+        // schnorr_presigs_in_creation
+        let mut schnorr_presigs_in_creation = Vec::new();
+        for (presig_id, presig) in &payload.schnorr_presigs_in_creation {
+            schnorr_presigs_in_creation.push(pb::SchnorrPreSignatureInProgress {
+                quadruple_id: presig_id.0,
+                presig: Some(presig.into()),
+            });
+        }
+
+        // ongoing_vetkd_requests
+        let mut ongoing_vetkd_requests = Vec::new();
+        for (request_id, args) in &payload.ongoing_vetkd_requests {
+            ongoing_vetkd_requests.push(pb::OngoingVetKdRequest {
+                request_id: Some((*request_id).into()),
+                args: Some(vetkd_args_into_protobuf(args)),
+            });
+        }
+
         Self {
             signature_agreements,
             ongoing_signatures,
@@ -1205,6 +1585,11 @@ impl From<&EcdsaPayload> for pb::EcdsaPayload {
             current_key_transcript,
             next_key_in_creation,
             key_id,
+            schnorr_key_transcript,
+            ongoing_schnorr_signatures,
+            available_schnorr_presigs,
+            schnorr_presigs_in_creation,
+            ongoing_vetkd_requests,
         }
     }
 }
@@ -1394,6 +1779,68 @@ impl TryFrom<&pb::EcdsaPayload> for EcdsaPayload {
             .ok_or("pb::EcdsaPayload:: Missing next_key_in_creation")?;
         let next_key_in_creation: KeyTranscriptCreation = proto.try_into()?;
 
+        let schnorr_key_transcript = payload
+            .schnorr_key_transcript
+            .as_ref()
+            .map(SchnorrKeyTranscript::try_from)
+            .transpose()?;
+
+        // ongoing_schnorr_signatures
+        let mut ongoing_schnorr_signatures = BTreeMap::new();
+        for ongoing_signature in &payload.ongoing_schnorr_signatures {
+            let request_id = ongoing_signature
+                .request_id
+                .as_ref()
+                .ok_or("pb::EcdsaPayload:: Missing ongoing_schnorr_signature request Id")
+                .and_then(RequestId::try_from)?;
+            let proto = ongoing_signature
+                .sig_inputs
+                .as_ref()
+                .ok_or("pb::EcdsaPayload:: Missing Schnorr sig inputs")?;
+            let sig_inputs: ThresholdSchnorrSigInputsRef = proto.try_into()?;
+            ongoing_schnorr_signatures.insert(request_id, sig_inputs);
+        }
+
+        // available_schnorr_presigs
+        let mut available_schnorr_presigs = BTreeMap::new();
+        for available_presig in &payload.available_schnorr_presigs {
+            let presig_id = QuadrupleId(available_presig.quadruple_id);
+            let proto = available_presig
+                .presig
+                .as_ref()
+                .ok_or("pb::EcdsaPayload:: Missing available_schnorr_presig")?;
+            let presig: SchnorrPreSignatureTranscriptRef = proto.try_into()?;
+            available_schnorr_presigs.insert(presig_id, presig);
+        }
+
+        // schnorr_presigs_in_creation
+        let mut schnorr_presigs_in_creation = BTreeMap::new();
+        for presig_in_creation in &payload.schnorr_presigs_in_creation {
+            let presig_id = QuadrupleId(presig_in_creation.quadruple_id);
+            let proto = presig_in_creation
+                .presig
+                .as_ref()
+                .ok_or("pb::EcdsaPayload:: Missing schnorr_presig_in_creation")?;
+            let presig: SchnorrPreSignatureInCreation = proto.try_into()?;
+            schnorr_presigs_in_creation.insert(presig_id, presig);
+        }
+
+        // ongoing_vetkd_requests
+        let mut ongoing_vetkd_requests = BTreeMap::new();
+        for ongoing_request in &payload.ongoing_vetkd_requests {
+            let request_id = ongoing_request
+                .request_id
+                .as_ref()
+                .ok_or("pb::EcdsaPayload:: Missing ongoing_vetkd_request request Id")
+                .and_then(RequestId::try_from)?;
+            let proto = ongoing_request
+                .args
+                .as_ref()
+                .ok_or("pb::EcdsaPayload:: Missing vetKD args")?;
+            let args = vetkd_args_try_from_protobuf(proto)?;
+            ongoing_vetkd_requests.insert(request_id, args);
+        }
+
         Ok(Self {
             signature_agreements,
             ongoing_signatures,
@@ -1408,10 +1855,50 @@ impl TryFrom<&pb::EcdsaPayload> for EcdsaPayload {
                 next_in_creation: next_key_in_creation,
                 key_id,
             },
+            schnorr_key_transcript,
+            ongoing_schnorr_signatures,
+            available_schnorr_presigs,
+            schnorr_presigs_in_creation,
+            ongoing_vetkd_requests,
         })
     }
 }
 
+fn vetkd_args_into_protobuf(args: &VetKdArgs) -> pb::VetKdArgs {
+    pb::VetKdArgs {
+        ni_dkg_id: Some(args.ni_dkg_id.into()),
+        derivation_path: Some(args.derivation_path.clone().into()),
+        input: args.input.clone(),
+        transport_public_key: args.transport_public_key.clone(),
+    }
+}
+
+fn vetkd_args_try_from_protobuf(args: &pb::VetKdArgs) -> Result<VetKdArgs, String> {
+    let ni_dkg_id = args
+        .ni_dkg_id
+        .clone()
+        .ok_or("pb::VetKdArgs:: Missing ni_dkg_id")?;
+    let ni_dkg_id = NiDkgId::try_from(ni_dkg_id)
+        .map_err(|err| format!("pb::VetKdArgs:: Failed to convert ni_dkg_id: {:?}", err))?;
+    let derivation_path = args
+        .derivation_path
+        .clone()
+        .ok_or("pb::VetKdArgs:: Missing derivation_path")?
+        .try_into()
+        .map_err(|err| {
+            format!(
+                "pb::VetKdArgs:: Failed to convert derivation_path: {:?}",
+                err
+            )
+        })?;
+    Ok(VetKdArgs {
+        ni_dkg_id,
+        derivation_path,
+        input: args.input.clone(),
+        transport_public_key: args.transport_public_key.clone(),
+    })
+}
+
 ///
 /// Processing/updates for a particular entity like TranscriptId is scattered across
 /// several paths, called from different contexts (e.g)
@@ -1547,6 +2034,26 @@ impl EcdsaObject for EcdsaOpening {
     }
 }
 
+impl EcdsaObject for SchnorrSigShare {
+    fn message_prefix(&self) -> EcdsaPrefixOf<Self> {
+        schnorr_sig_share_prefix(&self.request_id, &self.signer_id)
+    }
+
+    fn message_id(&self) -> EcdsaArtifactId {
+        EcdsaArtifactId::SchnorrSigShare(self.message_prefix(), crypto_hash(self))
+    }
+}
+
+impl EcdsaObject for VetKdKeyShare {
+    fn message_prefix(&self) -> EcdsaPrefixOf<Self> {
+        vetkd_key_share_prefix(&self.request_id, &self.signer_id)
+    }
+
+    fn message_id(&self) -> EcdsaArtifactId {
+        EcdsaArtifactId::VetKdKeyShare(self.message_prefix(), crypto_hash(self))
+    }
+}
+
 pub fn ecdsa_msg_id(msg: &EcdsaMessage) -> EcdsaArtifactId {
     match msg {
         EcdsaMessage::EcdsaSignedDealing(object) => object.message_id(),
@@ -1554,5 +2061,7 @@ pub fn ecdsa_msg_id(msg: &EcdsaMessage) -> EcdsaArtifactId {
         EcdsaMessage::EcdsaSigShare(object) => object.message_id(),
         EcdsaMessage::EcdsaComplaint(object) => object.message_id(),
         EcdsaMessage::EcdsaOpening(object) => object.message_id(),
+        EcdsaMessage::SchnorrSigShare(object) => object.message_id(),
+        EcdsaMessage::VetKdKeyShare(object) => object.message_id(),
     }
 }
@@ -8,15 +8,18 @@ use std::convert::{AsMut, AsRef, TryFrom, TryInto};
 use crate::crypto::{
     canister_threshold_sig::error::{
         IDkgParamsValidationError, PresignatureQuadrupleCreationError,
-        ThresholdEcdsaSigInputsCreationError,
+        SchnorrPreSignatureTranscriptCreationError, ThresholdEcdsaSigInputsCreationError,
+        ThresholdSchnorrSigInputsCreationError,
     },
     canister_threshold_sig::idkg::{
         IDkgTranscript, IDkgTranscriptId, IDkgTranscriptOperation, IDkgTranscriptParams,
         IDkgTranscriptType,
     },
     canister_threshold_sig::{
-        ExtendedDerivationPath, PreSignatureQuadruple, ThresholdEcdsaSigInputs,
+        ExtendedDerivationPath, PreSignatureQuadruple, SchnorrPreSignatureTranscript,
+        ThresholdEcdsaSigInputs, ThresholdSchnorrSigInputs,
     },
+    vetkd::VetKdArgs,
     AlgorithmId,
 };
 use crate::{Height, Randomness, RegistryVersion};
@@ -887,6 +890,148 @@ impl TryFrom<&pb::QuadrupleInCreation> for QuadrupleInCreation {
     }
 }
 
+/// Threshold Schnorr pre-signature in creation.
+///
+/// The blinder is first created as a masked random sharing, which is then
+/// reshared into an unmasked transcript.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct SchnorrPreSignatureInCreation {
+    pub blinder_config: RandomTranscriptParams,
+    pub blinder_masked: Option<MaskedTranscript>,
+
+    pub unmask_blinder_config: Option<ReshareOfMaskedParams>,
+    pub blinder_unmasked: Option<UnmaskedTranscript>,
+}
+
+impl SchnorrPreSignatureInCreation {
+    /// Initialization with the given random param.
+    pub fn new(blinder_config: RandomTranscriptParams) -> Self {
+        SchnorrPreSignatureInCreation {
+            blinder_config,
+            blinder_masked: None,
+            unmask_blinder_config: None,
+            blinder_unmasked: None,
+        }
+    }
+
+    /// Return an iterator of all transcript configs that have no matching
+    /// results yet.
+    pub fn iter_transcript_configs_in_creation(
+        &self,
+    ) -> Box<dyn Iterator<Item = &IDkgTranscriptParamsRef> + '_> {
+        let mut params = Vec::new();
+        if self.blinder_masked.is_none() {
+            params.push(self.blinder_config.as_ref())
+        }
+        if let (Some(config), None) = (&self.unmask_blinder_config, &self.blinder_unmasked) {
+            params.push(config.as_ref())
+        }
+        Box::new(params.into_iter())
+    }
+
+    /// Returns the refs held
+    pub fn get_refs(&self) -> Vec<TranscriptRef> {
+        let mut ret = Vec::new();
+        ret.append(&mut self.blinder_config.as_ref().get_refs());
+        if let Some(r) = &self.blinder_masked {
+            ret.push(*r.as_ref());
+        }
+        if let Some(config) = &self.unmask_blinder_config {
+            ret.append(&mut config.as_ref().get_refs());
+        }
+        if let Some(r) = &self.blinder_unmasked {
+            ret.push(*r.as_ref());
+        }
+        ret
+    }
+
+    /// Updates the height of the references.
+    pub fn update(&mut self, height: Height) {
+        self.blinder_config.as_mut().update(height);
+        if let Some(r) = &mut self.blinder_masked {
+            r.as_mut().update(height);
+        }
+        if let Some(config) = &mut self.unmask_blinder_config {
+            config.as_mut().update(height);
+        }
+        if let Some(r) = &mut self.blinder_unmasked {
+            r.as_mut().update(height);
+        }
+    }
+
+    /// Returns the refs held and updates the height if specified
+    pub fn get_refs_and_update(&mut self, height: Option<Height>) -> Vec<TranscriptRef> {
+        let mut ret = Vec::new();
+        ret.append(&mut self.blinder_config.as_mut().get_refs_and_update(height));
+        if let Some(r) = &mut self.blinder_masked {
+            ret.push(r.as_mut().get_and_update(height));
+        }
+        if let Some(config) = &mut self.unmask_blinder_config {
+            ret.append(&mut config.as_mut().get_refs_and_update(height));
+        }
+        if let Some(r) = &mut self.blinder_unmasked {
+            ret.push(r.as_mut().get_and_update(height));
+        }
+        ret
+    }
+}
+
+impl From<&SchnorrPreSignatureInCreation> for pb::SchnorrPreSignatureInCreation {
+    fn from(presig: &SchnorrPreSignatureInCreation) -> Self {
+        Self {
+            blinder_config: Some((&presig.blinder_config).into()),
+            blinder_masked: presig
+                .blinder_masked
+                .as_ref()
+                .map(|transcript| transcript.into()),
+            unmask_blinder_config: presig
+                .unmask_blinder_config
+                .as_ref()
+                .map(|params| params.into()),
+            blinder_unmasked: presig
+                .blinder_unmasked
+                .as_ref()
+                .map(|transcript| transcript.into()),
+        }
+    }
+}
+
+impl TryFrom<&pb::SchnorrPreSignatureInCreation> for SchnorrPreSignatureInCreation {
+    type Error = String;
+    fn try_from(presig: &pb::SchnorrPreSignatureInCreation) -> Result<Self, Self::Error> {
+        let blinder_config: RandomTranscriptParams = presig
+            .blinder_config
+            .as_ref()
+            .ok_or("pb::SchnorrPreSignatureInCreation:: Missing blinder config")?
+            .try_into()?;
+        let blinder_masked: Option<MaskedTranscript> = presig
+            .blinder_masked
+            .as_ref()
+            .map(|transcript| transcript.try_into())
+            .transpose()?;
+
+        let (unmask_blinder_config, blinder_unmasked) =
+            if let Some(config_proto) = &presig.unmask_blinder_config {
+                let config: ReshareOfMaskedParams = config_proto.try_into()?;
+                let transcript: Option<UnmaskedTranscript> = presig
+                    .blinder_unmasked
+                    .as_ref()
+                    .map(|transcript| transcript.try_into())
+                    .transpose()?;
+                (Some(config), transcript)
+            } else {
+                (None, None)
+            };
+
+        Ok(Self {
+            blinder_config,
+            blinder_masked,
+            unmask_blinder_config,
+            blinder_unmasked,
+        })
+    }
+}
+
 pub type TranscriptLookupError = String;
 
 /// Wrapper to access the ECDSA related info from the blocks.
@@ -902,6 +1047,14 @@ pub trait EcdsaBlockReader: Send + Sync {
         &self,
     ) -> Box<dyn Iterator<Item = (&RequestId, &ThresholdEcdsaSigInputsRef)> + '_>;
 
+    /// Returns the threshold Schnorr signatures requested by the tip.
+    fn requested_schnorr_signatures(
+        &self,
+    ) -> Box<dyn Iterator<Item = (&RequestId, &ThresholdSchnorrSigInputsRef)> + '_>;
+
+    /// Returns the vetKD encrypted keys requested by the tip.
+    fn requested_vetkd_keys(&self) -> Box<dyn Iterator<Item = (&RequestId, &VetKdArgs)> + '_>;
+
     /// Returns the set of all the active references.
     fn active_transcripts(&self) -> BTreeSet<TranscriptRef>;
 
@@ -1534,3 +1687,212 @@ impl TryFrom<&pb::ThresholdEcdsaSigInputsRef> for ThresholdEcdsaSigInputsRef {
         ))
     }
 }
+
+/// Counterpart of SchnorrPreSignatureTranscript that holds transcript
+/// references, instead of the transcripts.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct SchnorrPreSignatureTranscriptRef {
+    pub blinder_unmasked_ref: UnmaskedTranscript,
+}
+
+#[derive(Clone, Debug)]
+pub enum SchnorrPreSignatureTranscriptError {
+    BlinderUnmasked(TranscriptLookupError),
+    Failed(SchnorrPreSignatureTranscriptCreationError),
+}
+
+impl SchnorrPreSignatureTranscriptRef {
+    pub fn new(blinder_unmasked_ref: UnmaskedTranscript) -> Self {
+        Self {
+            blinder_unmasked_ref,
+        }
+    }
+
+    /// Resolves the refs to get the SchnorrPreSignatureTranscript.
+    pub fn translate(
+        &self,
+        resolver: &dyn EcdsaBlockReader,
+    ) -> Result<SchnorrPreSignatureTranscript, SchnorrPreSignatureTranscriptError> {
+        let blinder_unmasked = resolver
+            .transcript(self.blinder_unmasked_ref.as_ref())
+            .map_err(SchnorrPreSignatureTranscriptError::BlinderUnmasked)?;
+        SchnorrPreSignatureTranscript::new(blinder_unmasked)
+            .map_err(SchnorrPreSignatureTranscriptError::Failed)
+    }
+
+    /// Returns the refs held
+    pub fn get_refs(&self) -> Vec<TranscriptRef> {
+        vec![*self.blinder_unmasked_ref.as_ref()]
+    }
+
+    /// Updates the height of the references.
+    pub fn update(&mut self, height: Height) {
+        self.blinder_unmasked_ref.as_mut().update(height);
+    }
+
+    /// Returns the refs held and updates the height if specified
+    pub fn get_refs_and_update(&mut self, height: Option<Height>) -> Vec<TranscriptRef> {
+        vec![self.blinder_unmasked_ref.as_mut().get_and_update(height)]
+    }
+}
+
+impl From<&SchnorrPreSignatureTranscriptRef> for pb::SchnorrPreSignatureTranscriptRef {
+    fn from(presig: &SchnorrPreSignatureTranscriptRef) -> Self {
+        Self {
+            blinder_unmasked_ref: Some((&presig.blinder_unmasked_ref).into()),
+        }
+    }
+}
+
+impl TryFrom<&pb::SchnorrPreSignatureTranscriptRef> for SchnorrPreSignatureTranscriptRef {
+    type Error = String;
+    fn try_from(presig: &pb::SchnorrPreSignatureTranscriptRef) -> Result<Self, Self::Error> {
+        let proto = presig
+            .blinder_unmasked_ref
+            .as_ref()
+            .ok_or("pb::SchnorrPreSignatureTranscriptRef:: Missing blinder unmasked")?;
+        let blinder_unmasked_ref: UnmaskedTranscript = proto.try_into().map_err(|err| {
+            format!(
+                "pb::SchnorrPreSignatureTranscriptRef:: Failed to convert blinder_unmasked_ref : {:?}",
+                err
+            )
+        })?;
+        Ok(Self::new(blinder_unmasked_ref))
+    }
+}
+
+/// Counterpart of ThresholdSchnorrSigInputs that holds transcript references,
+/// instead of the transcripts.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct ThresholdSchnorrSigInputsRef {
+    pub derivation_path: ExtendedDerivationPath,
+    pub message: Vec<u8>,
+    pub nonce: Randomness,
+    pub presig_transcript_ref: SchnorrPreSignatureTranscriptRef,
+    pub key_transcript_ref: UnmaskedTranscript,
+}
+
+#[derive(Clone, Debug)]
+pub enum ThresholdSchnorrSigInputsError {
+    PreSignatureTranscript(SchnorrPreSignatureTranscriptError),
+    KeyTranscript(TranscriptLookupError),
+    Failed(ThresholdSchnorrSigInputsCreationError),
+}
+
+impl ThresholdSchnorrSigInputsRef {
+    pub fn new(
+        derivation_path: ExtendedDerivationPath,
+        message: Vec<u8>,
+        nonce: Randomness,
+        presig_transcript_ref: SchnorrPreSignatureTranscriptRef,
+        key_transcript_ref: UnmaskedTranscript,
+    ) -> Self {
+        Self {
+            derivation_path,
+            message,
+            nonce,
+            presig_transcript_ref,
+            key_transcript_ref,
+        }
+    }
+
+    /// Resolves the refs to get the ThresholdSchnorrSigInputs.
+    pub fn translate(
+        &self,
+        resolver: &dyn EcdsaBlockReader,
+    ) -> Result<ThresholdSchnorrSigInputs, ThresholdSchnorrSigInputsError> {
+        let presig_transcript = self
+            .presig_transcript_ref
+            .translate(resolver)
+            .map_err(ThresholdSchnorrSigInputsError::PreSignatureTranscript)?;
+        let key_transcript = resolver
+            .transcript(self.key_transcript_ref.as_ref())
+            .map_err(ThresholdSchnorrSigInputsError::KeyTranscript)?;
+        ThresholdSchnorrSigInputs::new(
+            &self.derivation_path,
+            &self.message,
+            self.nonce,
+            presig_transcript,
+            key_transcript,
+        )
+        .map_err(ThresholdSchnorrSigInputsError::Failed)
+    }
+
+    /// Returns the refs held
+    pub fn get_refs(&self) -> Vec<TranscriptRef> {
+        let mut ret = self.presig_transcript_ref.get_refs();
+        ret.push(*self.key_transcript_ref.as_ref());
+        ret
+    }
+
+    /// Updates the height of the references.
+    pub fn update(&mut self, height: Height) {
+        self.presig_transcript_ref.update(height);
+        self.key_transcript_ref.as_mut().update(height);
+    }
+
+    /// Returns the refs held and updates the height if specified
+    pub fn get_refs_and_update(&mut self, height: Option<Height>) -> Vec<TranscriptRef> {
+        let mut ret = self.presig_transcript_ref.get_refs_and_update(height);
+        ret.push(self.key_transcript_ref.as_mut().get_and_update(height));
+        ret
+    }
+}
+
+impl From<&ThresholdSchnorrSigInputsRef> for pb::ThresholdSchnorrSigInputsRef {
+    fn from(sig_inputs: &ThresholdSchnorrSigInputsRef) -> Self {
+        Self {
+            derivation_path: Some((sig_inputs.derivation_path.clone()).into()),
+            message: sig_inputs.message.clone(),
+            nonce: sig_inputs.nonce.get().to_vec(),
+            presig_transcript_ref: Some((&sig_inputs.presig_transcript_ref).into()),
+            key_transcript_ref: Some((&sig_inputs.key_transcript_ref).into()),
+        }
+    }
+}
+
+impl TryFrom<&pb::ThresholdSchnorrSigInputsRef> for ThresholdSchnorrSigInputsRef {
+    type Error = String;
+    fn try_from(sig_inputs: &pb::ThresholdSchnorrSigInputsRef) -> Result<Self, Self::Error> {
+        let proto = sig_inputs
+            .derivation_path
+            .as_ref()
+            .ok_or("pb::ThresholdSchnorrSigInputsRef:: Missing derivation_path")?;
+        let derivation_path: ExtendedDerivationPath = proto.clone().try_into().map_err(|err| {
+            format!(
+                "pb::ThresholdSchnorrSigInputsRef:: Failed to convert derivation_path : {:?}",
+                err
+            )
+        })?;
+
+        if sig_inputs.nonce.len() != 32 {
+            return Err(format!(
+                "pb::ThresholdSchnorrSigInputsRef:: Invalid nonce length: {:?}",
+                sig_inputs.nonce.len()
+            ));
+        }
+        let mut nonce = [0; 32];
+        nonce.copy_from_slice(&sig_inputs.nonce[0..32]);
+        let nonce = Randomness::from(nonce);
+
+        let proto = sig_inputs
+            .presig_transcript_ref
+            .as_ref()
+            .ok_or("pb::ThresholdSchnorrSigInputsRef:: Missing presig_transcript_ref")?;
+        let presig_transcript_ref: SchnorrPreSignatureTranscriptRef = proto.try_into()?;
+
+        let proto = sig_inputs
+            .key_transcript_ref
+            .as_ref()
+            .ok_or("pb::ThresholdSchnorrSigInputsRef:: Missing key_transcript_ref")?;
+        let key_transcript_ref: UnmaskedTranscript = proto.try_into()?;
+
+        Ok(Self::new(
+            derivation_path,
+            sig_inputs.message.clone(),
+            nonce,
+            presig_transcript_ref,
+            key_transcript_ref,
+        ))
+    }
+}
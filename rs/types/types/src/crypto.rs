@@ -15,6 +15,7 @@ pub use sign::{Signable, SignableMock};
 
 pub mod error;
 pub mod threshold_sig;
+pub mod vetkd;
 
 use crate::crypto::threshold_sig::ni_dkg::DkgId;
 use crate::registry::RegistryClientError;
@@ -143,12 +144,23 @@ pub enum AlgorithmId {
     RsaSha256 = 14,
     ThresholdEcdsaSecp256k1 = 15,
     MegaSecp256k1 = 16,
+    ThresholdSchnorrBip340 = 17,
+    ThresholdEd25519 = 18,
+    ThresholdEcdsaSecp256r1 = 19,
 }
 
 impl AlgorithmId {
     pub fn as_u8(&self) -> u8 {
         u8::try_from(*self as isize).expect("could not convert AlgorithmId to u8")
     }
+
+    /// Returns true if this is an algorithm for threshold Schnorr signatures.
+    pub fn is_threshold_schnorr(&self) -> bool {
+        matches!(
+            self,
+            AlgorithmId::ThresholdSchnorrBip340 | AlgorithmId::ThresholdEd25519
+        )
+    }
 }
 
 impl From<CspThresholdSigPublicKey> for AlgorithmId {
@@ -199,6 +211,9 @@ impl From<i32> for AlgorithmId {
             14 => AlgorithmId::RsaSha256,
             15 => AlgorithmId::ThresholdEcdsaSecp256k1,
             16 => AlgorithmId::MegaSecp256k1,
+            17 => AlgorithmId::ThresholdSchnorrBip340,
+            18 => AlgorithmId::ThresholdEd25519,
+            19 => AlgorithmId::ThresholdEcdsaSecp256r1,
             _ => AlgorithmId::Placeholder,
         }
     }
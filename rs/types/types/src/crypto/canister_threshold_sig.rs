@@ -321,6 +321,9 @@ pub struct ThresholdEcdsaSigInputs {
 // The byte length of an hashed message for ECDSA signatures over the curve secp256k1.
 pub const ECDSA_SECP256K1_HASH_BYTE_LENGTH: usize = 32;
 
+// The byte length of an hashed message for ECDSA signatures over the curve secp256r1.
+pub const ECDSA_SECP256R1_HASH_BYTE_LENGTH: usize = 32;
+
 impl Display for ThresholdEcdsaSigInputs {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         write!(f, "{:?}", self)
@@ -437,6 +440,12 @@ impl ThresholdEcdsaSigInputs {
                 }
                 Ok(())
             }
+            AlgorithmId::ThresholdEcdsaSecp256r1 => {
+                if hashed_message.len() != ECDSA_SECP256R1_HASH_BYTE_LENGTH {
+                    return Err(error::ThresholdEcdsaSigInputsCreationError::InvalidHashLength);
+                }
+                Ok(())
+            }
             _ => Err(error::ThresholdEcdsaSigInputsCreationError::UnsupportedAlgorithm),
         }
     }
@@ -491,3 +500,220 @@ impl fmt::Debug for ThresholdEcdsaSigShare {
         )
     }
 }
+
+/// Pre-signature transcript consumed by a canister-requested threshold Schnorr
+/// signature. Each pre-signature MUST be used *at most once* for a signature.
+/// Otherwise, the private key may be leaked!
+///
+/// Unlike threshold ECDSA, threshold Schnorr only requires an unmasked
+/// transcript for the sharing of a random value `blinder`, which is
+/// independent of the message to be signed.
+#[derive(Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct SchnorrPreSignatureTranscript {
+    blinder_unmasked: IDkgTranscript,
+}
+
+impl Display for SchnorrPreSignatureTranscript {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl fmt::Debug for SchnorrPreSignatureTranscript {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "SchnorrPreSignatureTranscript {{ blinder_unmasked: {:?} }}",
+            self.blinder_unmasked.transcript_id
+        )
+    }
+}
+
+impl SchnorrPreSignatureTranscript {
+    /// Creates a `SchnorrPreSignatureTranscript` that can be used in the
+    /// threshold Schnorr protocol.
+    ///
+    /// A `SchnorrPreSignatureTranscript` can only be created if the following
+    /// invariants hold:
+    /// * The algorithm ID of the `blinder_unmasked` transcript is a threshold
+    ///   Schnorr algorithm (error: `UnsupportedAlgorithm`)
+    /// * The `blinder_unmasked` transcript is of type `Unmasked` with origin
+    ///   `ReshareMasked` (error: `InvalidTranscriptOrigin`)
+    pub fn new(
+        blinder_unmasked: IDkgTranscript,
+    ) -> Result<Self, error::SchnorrPreSignatureTranscriptCreationError> {
+        if !blinder_unmasked.algorithm_id.is_threshold_schnorr() {
+            return Err(error::SchnorrPreSignatureTranscriptCreationError::UnsupportedAlgorithm);
+        }
+        match &blinder_unmasked.transcript_type {
+            IDkgTranscriptType::Unmasked(IDkgUnmaskedTranscriptOrigin::ReshareMasked(_)) => {
+                Ok(Self { blinder_unmasked })
+            }
+            _ => Err(
+                error::SchnorrPreSignatureTranscriptCreationError::InvalidTranscriptOrigin(
+                    format!(
+                        "`blinder_unmasked` transcript expected to have type `Unmasked` with origin of type `ReshareMasked(_)`, but found transcript of type {:?}",
+                        blinder_unmasked.transcript_type
+                    ),
+                ),
+            ),
+        }
+    }
+
+    pub fn blinder_unmasked(&self) -> &IDkgTranscript {
+        &self.blinder_unmasked
+    }
+}
+
+/// All inputs required to generate a canister threshold Schnorr signature.
+#[derive(Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct ThresholdSchnorrSigInputs {
+    derivation_path: ExtendedDerivationPath,
+    #[serde(with = "serde_bytes")]
+    message: Vec<u8>,
+    nonce: Randomness,
+    presig_transcript: SchnorrPreSignatureTranscript,
+    key_transcript: IDkgTranscript,
+}
+
+impl Display for ThresholdSchnorrSigInputs {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl fmt::Debug for ThresholdSchnorrSigInputs {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "ThresholdSchnorrSigInputs {{ ")?;
+        write!(f, "derivation_path: {:?}", self.derivation_path)?;
+        write!(f, ", message: 0x{}", hex::encode(&self.message))?;
+        write!(f, ", nonce: 0x{}", hex::encode(self.nonce.as_ref()))?;
+        write!(f, ", presig_transcript: {}", self.presig_transcript)?;
+        write!(f, ", key_transcript: {}", self.key_transcript.transcript_id)?;
+        write!(f, " }}")?;
+        Ok(())
+    }
+}
+
+impl ThresholdSchnorrSigInputs {
+    /// Creates the inputs to the threshold Schnorr signing protocol.
+    ///
+    /// A `ThresholdSchnorrSigInputs` can only be created if the following invariants hold:
+    /// * The algorithm ID of the `key_transcript` is the same as the algorithm ID
+    ///   of the `presig_transcript` (error: `InconsistentAlgorithmIds`)
+    /// * The algorithm ID of the `key_transcript` is supported for the creation
+    ///   of threshold Schnorr signatures (error: `UnsupportedAlgorithm`)
+    /// * All transcripts have the same receiver set (error: `InconsistentReceivers`)
+    ///
+    /// In contrast to threshold ECDSA, the message is signed as is and may
+    /// have an arbitrary length.
+    pub fn new(
+        derivation_path: &ExtendedDerivationPath,
+        message: &[u8],
+        nonce: Randomness,
+        presig_transcript: SchnorrPreSignatureTranscript,
+        key_transcript: IDkgTranscript,
+    ) -> Result<Self, error::ThresholdSchnorrSigInputsCreationError> {
+        if !key_transcript.algorithm_id.is_threshold_schnorr() {
+            return Err(error::ThresholdSchnorrSigInputsCreationError::UnsupportedAlgorithm);
+        }
+        if presig_transcript.blinder_unmasked().algorithm_id != key_transcript.algorithm_id {
+            return Err(error::ThresholdSchnorrSigInputsCreationError::InconsistentAlgorithmIds);
+        }
+        if presig_transcript.blinder_unmasked().receivers != key_transcript.receivers {
+            return Err(error::ThresholdSchnorrSigInputsCreationError::InconsistentReceivers);
+        }
+
+        Ok(Self {
+            derivation_path: derivation_path.clone(),
+            message: message.to_vec(),
+            nonce,
+            presig_transcript,
+            key_transcript,
+        })
+    }
+
+    pub fn derivation_path(&self) -> &ExtendedDerivationPath {
+        &self.derivation_path
+    }
+
+    pub fn message(&self) -> &[u8] {
+        &self.message
+    }
+
+    pub fn nonce(&self) -> &Randomness {
+        &self.nonce
+    }
+
+    pub fn presig_transcript(&self) -> &SchnorrPreSignatureTranscript {
+        &self.presig_transcript
+    }
+
+    pub fn key_transcript(&self) -> &IDkgTranscript {
+        &self.key_transcript
+    }
+
+    /// Number of contributions needed to reconstruct a sharing.
+    pub fn reconstruction_threshold(&self) -> NumberOfNodes {
+        // We already checked that all receiver sets are equal
+        self.key_transcript.reconstruction_threshold()
+    }
+
+    pub fn receivers(&self) -> &IDkgReceivers {
+        // We already checked that all receiver sets are equal
+        &self.key_transcript.receivers
+    }
+
+    pub fn algorithm_id(&self) -> AlgorithmId {
+        // We already checked that all transcripts have the same alg_id
+        self.key_transcript.algorithm_id
+    }
+}
+
+/// A single threshold Schnorr signature share.
+#[derive(Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct ThresholdSchnorrSigShare {
+    #[serde(with = "serde_bytes")]
+    pub sig_share_raw: Vec<u8>,
+}
+
+impl Display for ThresholdSchnorrSigShare {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl fmt::Debug for ThresholdSchnorrSigShare {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "ThresholdSchnorrSigShare {{ sig_share_raw: 0x{} }}",
+            hex::encode(&self.sig_share_raw)
+        )
+    }
+}
+
+/// A combined threshold Schnorr signature.
+///
+/// The signature itself is stored as raw bytes.
+#[derive(Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct ThresholdSchnorrCombinedSignature {
+    #[serde(with = "serde_bytes")]
+    pub signature: Vec<u8>,
+}
+
+impl Display for ThresholdSchnorrCombinedSignature {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl fmt::Debug for ThresholdSchnorrCombinedSignature {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "ThresholdSchnorrCombinedSignature {{ signature: 0x{} }}",
+            hex::encode(&self.signature)
+        )
+    }
+}
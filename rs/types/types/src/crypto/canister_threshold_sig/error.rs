@@ -339,3 +339,57 @@ pub enum ThresholdEcdsaCombineSigSharesError {
     SignerNotAllowed { node_id: NodeId },
 }
 impl_display_using_debug!(ThresholdEcdsaCombineSigSharesError);
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SchnorrPreSignatureTranscriptCreationError {
+    InvalidTranscriptOrigin(String),
+    UnsupportedAlgorithm,
+}
+impl_display_using_debug!(SchnorrPreSignatureTranscriptCreationError);
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ThresholdSchnorrSigInputsCreationError {
+    InconsistentAlgorithmIds,
+    InconsistentReceivers,
+    UnsupportedAlgorithm,
+}
+impl_display_using_debug!(ThresholdSchnorrSigInputsCreationError);
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ThresholdSchnorrSignShareError {
+    InternalError { internal_error: String },
+    NotAReceiver,
+    SerializationError { internal_error: String },
+    SecretSharesNotFound { commitment_string: String },
+    UnsupportedAlgorithm,
+}
+impl_display_using_debug!(ThresholdSchnorrSignShareError);
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ThresholdSchnorrVerifySigShareError {
+    InternalError { internal_error: String },
+    SerializationError { internal_error: String },
+    InvalidSignatureShare,
+    InvalidArgumentMissingSignerInTranscript { signer_id: NodeId },
+    UnsupportedAlgorithm,
+}
+impl_display_using_debug!(ThresholdSchnorrVerifySigShareError);
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ThresholdSchnorrCombineSigSharesError {
+    InternalError { internal_error: String },
+    UnsatisfiedReconstructionThreshold { threshold: u32, share_count: usize },
+    SerializationError { internal_error: String },
+    SignerNotAllowed { node_id: NodeId },
+    UnsupportedAlgorithm,
+}
+impl_display_using_debug!(ThresholdSchnorrCombineSigSharesError);
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ThresholdSchnorrVerifyCombinedSignatureError {
+    InternalError { internal_error: String },
+    InvalidSignature,
+    SerializationError { internal_error: String },
+    UnsupportedAlgorithm,
+}
+impl_display_using_debug!(ThresholdSchnorrVerifyCombinedSignatureError);
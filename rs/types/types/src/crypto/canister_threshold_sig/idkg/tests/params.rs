@@ -411,6 +411,43 @@ fn should_not_create_with_wrong_algid() {
     );
 }
 
+#[test]
+fn should_create_with_threshold_ecdsa_secp256r1_algid() {
+    let nodes = set_of_nodes(&[1]);
+
+    let result = IDkgTranscriptParams::new(
+        random_transcript_id(),
+        nodes.clone(),
+        nodes,
+        RegistryVersion::from(0),
+        AlgorithmId::ThresholdEcdsaSecp256r1,
+        IDkgTranscriptOperation::Random,
+    );
+
+    assert!(result.is_ok(), "{:?}", result);
+}
+
+#[test]
+fn should_create_with_threshold_schnorr_algids() {
+    let nodes = set_of_nodes(&[1]);
+
+    for algorithm_id in [
+        AlgorithmId::ThresholdSchnorrBip340,
+        AlgorithmId::ThresholdEd25519,
+    ] {
+        let result = IDkgTranscriptParams::new(
+            random_transcript_id(),
+            nodes.clone(),
+            nodes.clone(),
+            RegistryVersion::from(0),
+            algorithm_id,
+            IDkgTranscriptOperation::Random,
+        );
+
+        assert!(result.is_ok(), "{:?}: {:?}", algorithm_id, result);
+    }
+}
+
 #[test]
 fn should_not_create_reshare_masked_with_wrong_original_type() {
     let previous_transcript = mock_transcript(
@@ -0,0 +1,139 @@
+//! Defines types used for verifiably encrypted threshold key derivation (vetKD).
+use crate::crypto::canister_threshold_sig::ExtendedDerivationPath;
+use crate::crypto::threshold_sig::ni_dkg::NiDkgId;
+use crate::NodeId;
+use core::fmt;
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+
+/// All inputs required to create a vetKD encrypted key share.
+#[derive(Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct VetKdArgs {
+    /// The NI-DKG transcript whose threshold key is used as master key.
+    pub ni_dkg_id: NiDkgId,
+    pub derivation_path: ExtendedDerivationPath,
+    #[serde(with = "serde_bytes")]
+    pub input: Vec<u8>,
+    #[serde(with = "serde_bytes")]
+    pub transport_public_key: Vec<u8>,
+}
+
+impl Display for VetKdArgs {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl fmt::Debug for VetKdArgs {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "VetKdArgs {{ ")?;
+        write!(f, "ni_dkg_id: {}", self.ni_dkg_id)?;
+        write!(f, ", derivation_path: {:?}", self.derivation_path)?;
+        write!(f, ", input: 0x{}", hex::encode(&self.input))?;
+        write!(
+            f,
+            ", transport_public_key: 0x{}",
+            hex::encode(&self.transport_public_key)
+        )?;
+        write!(f, " }}")?;
+        Ok(())
+    }
+}
+
+/// A single vetKD encrypted key share, created by one node.
+#[derive(Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct VetKdEncryptedKeyShare {
+    #[serde(with = "serde_bytes")]
+    pub encrypted_key_share: Vec<u8>,
+}
+
+impl Display for VetKdEncryptedKeyShare {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl fmt::Debug for VetKdEncryptedKeyShare {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "VetKdEncryptedKeyShare {{ encrypted_key_share: 0x{} }}",
+            hex::encode(&self.encrypted_key_share)
+        )
+    }
+}
+
+/// A combined vetKD encrypted key.
+#[derive(Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct VetKdEncryptedKey {
+    #[serde(with = "serde_bytes")]
+    pub encrypted_key: Vec<u8>,
+}
+
+impl Display for VetKdEncryptedKey {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl fmt::Debug for VetKdEncryptedKey {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "VetKdEncryptedKey {{ encrypted_key: 0x{} }}",
+            hex::encode(&self.encrypted_key)
+        )
+    }
+}
+
+macro_rules! impl_display_using_debug {
+    ($t:ty) => {
+        impl std::fmt::Display for $t {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                write!(f, "{:?}", self)
+            }
+        }
+    };
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum VetKdKeyShareCreationError {
+    ThresholdSigDataNotFound(String),
+    SecretKeyNotFound { dkg_id: NiDkgId, key_id: String },
+    InvalidArgumentTransportPublicKey,
+    InternalError { internal_error: String },
+}
+impl_display_using_debug!(VetKdKeyShareCreationError);
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum VetKdKeyShareVerificationError {
+    ThresholdSigDataNotFound(String),
+    InvalidArgumentTransportPublicKey,
+    InvalidArgumentEncryptedKeyShare,
+    VerificationError,
+    InternalError { internal_error: String },
+}
+impl_display_using_debug!(VetKdKeyShareVerificationError);
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum VetKdKeyShareCombinationError {
+    ThresholdSigDataNotFound(String),
+    InvalidArgumentTransportPublicKey,
+    InvalidArgumentEncryptedKeyShare,
+    IndividualPublicKeyNotFound { node_id: NodeId },
+    UnsatisfiedReconstructionThreshold { threshold: u32, share_count: usize },
+    InsufficientValidKeyShares,
+    CombinationError(String),
+    InternalError { internal_error: String },
+}
+impl_display_using_debug!(VetKdKeyShareCombinationError);
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum VetKdKeyVerificationError {
+    ThresholdSigDataNotFound(String),
+    InvalidArgumentEncryptedKey,
+    InvalidArgumentTransportPublicKey,
+    VerificationError,
+    InternalError { internal_error: String },
+}
+impl_display_using_debug!(VetKdKeyVerificationError);
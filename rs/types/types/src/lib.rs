@@ -67,6 +67,7 @@ pub mod artifact;
 pub mod artifact_kind;
 pub mod batch;
 pub mod canister_http;
+pub mod canister_log;
 pub mod chunkable;
 pub mod consensus;
 pub mod crypto;
@@ -90,7 +91,7 @@ pub mod time;
 pub mod xnet;
 
 pub use crate::replica_version::ReplicaVersion;
-pub use crate::time::Time;
+pub use crate::time::{CoarseTime, Time};
 pub use funds::*;
 pub use ic_base_types::{
     subnet_id_into_protobuf, subnet_id_try_from_protobuf, CanisterId, CanisterIdBlobParseError,
@@ -513,6 +514,27 @@ impl MemoryAllocation {
             MemoryAllocation::BestEffort => NumBytes::from(0),
         }
     }
+
+    /// Same as `MemoryAllocation::try_from()`, but with `max` as the upper
+    /// limit instead of `MAX_MEMORY_ALLOCATION`.
+    pub fn try_from_with_max(
+        bytes: NumBytes,
+        max: NumBytes,
+    ) -> Result<Self, InvalidMemoryAllocationError> {
+        if bytes > max {
+            return Err(InvalidMemoryAllocationError::with_max(
+                candid::Nat::from(bytes.get()),
+                max,
+            ));
+        }
+        // A memory allocation of 0 means that the canister's memory growth will be
+        // best-effort.
+        if bytes.get() == 0 {
+            Ok(MemoryAllocation::BestEffort)
+        } else {
+            Ok(MemoryAllocation::Reserved(bytes))
+        }
+    }
 }
 
 impl fmt::Display for MemoryAllocation {
@@ -551,15 +573,29 @@ pub const MAX_STABLE_MEMORY_IN_BYTES: u64 = 48 * GB;
 /// it is public and `u64` (`NumBytes` cannot be used in const expressions).
 pub const MAX_WASM_MEMORY_IN_BYTES: u64 = 4 * GB;
 
+/// The upper limit on the Wasm memory size of canisters that use a 64-bit
+/// main memory (the memory64 proposal).
+/// This constant is used by other crates to define other constants, that's why
+/// it is public and `u64` (`NumBytes` cannot be used in const expressions).
+pub const MAX_WASM64_MEMORY_IN_BYTES: u64 = 8 * GB;
+
 const MIN_MEMORY_ALLOCATION: NumBytes = NumBytes::new(0);
 pub const MAX_MEMORY_ALLOCATION: NumBytes =
     NumBytes::new(MAX_STABLE_MEMORY_IN_BYTES + MAX_WASM_MEMORY_IN_BYTES);
+/// The upper limit on the memory allocation if canisters may use a 64-bit
+/// main memory.
+pub const MAX_WASM64_MEMORY_ALLOCATION: NumBytes =
+    NumBytes::new(MAX_STABLE_MEMORY_IN_BYTES + MAX_WASM64_MEMORY_IN_BYTES);
 
 impl InvalidMemoryAllocationError {
     pub fn new(given: candid::Nat) -> Self {
+        Self::with_max(given, MAX_MEMORY_ALLOCATION)
+    }
+
+    pub fn with_max(given: candid::Nat, max: NumBytes) -> Self {
         Self {
             min: candid::Nat::from(MIN_MEMORY_ALLOCATION.get()),
-            max: candid::Nat::from(MAX_MEMORY_ALLOCATION.get()),
+            max: candid::Nat::from(max.get()),
             given,
         }
     }
@@ -569,18 +605,7 @@ impl TryFrom<NumBytes> for MemoryAllocation {
     type Error = InvalidMemoryAllocationError;
 
     fn try_from(bytes: NumBytes) -> Result<Self, Self::Error> {
-        if bytes > MAX_MEMORY_ALLOCATION {
-            return Err(InvalidMemoryAllocationError::new(candid::Nat::from(
-                bytes.get(),
-            )));
-        }
-        // A memory allocation of 0 means that the canister's memory growth will be
-        // best-effort.
-        if bytes.get() == 0 {
-            Ok(MemoryAllocation::BestEffort)
-        } else {
-            Ok(MemoryAllocation::Reserved(bytes))
-        }
+        Self::try_from_with_max(bytes, MAX_MEMORY_ALLOCATION)
     }
 }
 
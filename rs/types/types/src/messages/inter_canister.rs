@@ -1,8 +1,9 @@
-use crate::{ingress::WasmResult, CanisterId, CountBytes, Cycles, Funds, NumBytes};
+use crate::{ingress::WasmResult, CanisterId, CoarseTime, CountBytes, Cycles, Funds, NumBytes};
 use ic_error_types::{RejectCode, TryFromError, UserError};
 use ic_ic00_types::{
-    CanisterIdRecord, InstallCodeArgs, Method, Payload as _, ProvisionalTopUpCanisterArgs,
-    SetControllerArgs, UpdateSettingsArgs,
+    CanisterIdRecord, CanisterInfoRequest, DeleteCanisterSnapshotArgs, InstallChunkedCodeArgs,
+    InstallCodeArgs, LoadCanisterSnapshotArgs, Method, Payload as _, ProvisionalTopUpCanisterArgs,
+    SetControllerArgs, TakeCanisterSnapshotArgs, UpdateSettingsArgs, UploadChunkArgs,
 };
 use ic_protobuf::{
     proxy::{try_from_option_field, ProxyDecodeError},
@@ -34,6 +35,9 @@ pub enum CallContextIdTag {}
 /// Identifies an incoming call.
 pub type CallContextId = Id<CallContextIdTag, u64>;
 
+/// The deadline of guaranteed response messages and callbacks.
+pub const NO_DEADLINE: CoarseTime = CoarseTime::from_secs_since_unix_epoch(0);
+
 /// Canister-to-canister request message.
 #[derive(Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct Request {
@@ -44,6 +48,9 @@ pub struct Request {
     pub method_name: String,
     #[serde(with = "serde_bytes")]
     pub method_payload: Vec<u8>,
+    /// Deadline of a best-effort request; `NO_DEADLINE` for guaranteed
+    /// response requests.
+    pub deadline: CoarseTime,
 }
 
 impl Request {
@@ -62,6 +69,12 @@ impl Request {
         &self.method_payload
     }
 
+    /// Returns `true` if this is a best-effort request, i.e. it has a deadline
+    /// and may be dropped by the system.
+    pub fn is_best_effort(&self) -> bool {
+        self.deadline != NO_DEADLINE
+    }
+
     /// Returns the size of the user-controlled part of this `Request`,
     /// in bytes.
     pub fn payload_size_bytes(&self) -> NumBytes {
@@ -78,6 +91,10 @@ impl Request {
             | Ok(Method::DeleteCanister)
             | Ok(Method::UninstallCode)
             | Ok(Method::DepositCycles)
+            | Ok(Method::ListCanisterSnapshots)
+            | Ok(Method::ClearChunkStore)
+            | Ok(Method::StoredChunks)
+            | Ok(Method::FetchCanisterLogs)
             | Ok(Method::StopCanister) => match CanisterIdRecord::decode(&self.method_payload) {
                 Ok(record) => Some(record.get_canister_id()),
                 Err(_) => None,
@@ -100,12 +117,48 @@ impl Request {
                     Err(_) => None,
                 }
             }
+            Ok(Method::TakeCanisterSnapshot) => {
+                match TakeCanisterSnapshotArgs::decode(&self.method_payload) {
+                    Ok(record) => Some(record.get_canister_id()),
+                    Err(_) => None,
+                }
+            }
+            Ok(Method::LoadCanisterSnapshot) => {
+                match LoadCanisterSnapshotArgs::decode(&self.method_payload) {
+                    Ok(record) => Some(record.get_canister_id()),
+                    Err(_) => None,
+                }
+            }
+            Ok(Method::DeleteCanisterSnapshot) => {
+                match DeleteCanisterSnapshotArgs::decode(&self.method_payload) {
+                    Ok(record) => Some(record.get_canister_id()),
+                    Err(_) => None,
+                }
+            }
+            Ok(Method::UploadChunk) => match UploadChunkArgs::decode(&self.method_payload) {
+                Ok(record) => Some(record.get_canister_id()),
+                Err(_) => None,
+            },
+            Ok(Method::CanisterInfo) => match CanisterInfoRequest::decode(&self.method_payload) {
+                Ok(record) => Some(record.get_canister_id()),
+                Err(_) => None,
+            },
+            Ok(Method::InstallChunkedCode) => {
+                match InstallChunkedCodeArgs::decode(&self.method_payload) {
+                    Ok(record) => Some(record.target_canister_id()),
+                    Err(_) => None,
+                }
+            }
             Ok(Method::CreateCanister)
             | Ok(Method::SetupInitialDKG)
             | Ok(Method::HttpRequest)
             | Ok(Method::RawRand)
             | Ok(Method::ECDSAPublicKey)
             | Ok(Method::SignWithECDSA)
+            | Ok(Method::SchnorrPublicKey)
+            | Ok(Method::SignWithSchnorr)
+            | Ok(Method::VetKdPublicKey)
+            | Ok(Method::VetKdEncryptedKey)
             | Ok(Method::ComputeInitialEcdsaDealings)
             | Ok(Method::BitcoinGetBalance)
             | Ok(Method::BitcoinGetUtxos)
@@ -131,6 +184,9 @@ impl std::fmt::Debug for Request {
             self.sender_reply_callback
         )?;
         write!(f, "payment: {:?}, ", self.payment)?;
+        if self.deadline != NO_DEADLINE {
+            write!(f, "deadline: {:?}, ", self.deadline)?;
+        }
         if self.method_name.len() <= 103 {
             write!(f, "method_name: {:?}, ", self.method_name)?;
         } else {
@@ -270,6 +326,9 @@ pub struct Response {
     pub originator_reply_callback: CallbackId,
     pub refund: Cycles,
     pub response_payload: Payload,
+    /// Deadline of a best-effort response (copied from the request);
+    /// `NO_DEADLINE` for guaranteed responses.
+    pub deadline: CoarseTime,
 }
 
 impl Response {
@@ -277,6 +336,12 @@ impl Response {
     pub fn payload_size_bytes(&self) -> NumBytes {
         self.response_payload.size_bytes()
     }
+
+    /// Returns `true` if this is a best-effort response, i.e. it has a
+    /// deadline and may be dropped by the system.
+    pub fn is_best_effort(&self) -> bool {
+        self.deadline != NO_DEADLINE
+    }
 }
 
 /// Canister-to-canister message.
@@ -323,6 +388,20 @@ impl RequestOrResponse {
             RequestOrResponse::Response(resp) => resp.refund,
         }
     }
+
+    /// Returns the deadline of this message; `NO_DEADLINE` for guaranteed
+    /// response messages.
+    pub fn deadline(&self) -> CoarseTime {
+        match self {
+            RequestOrResponse::Request(req) => req.deadline,
+            RequestOrResponse::Response(resp) => resp.deadline,
+        }
+    }
+
+    /// Returns `true` if this is a best-effort message.
+    pub fn is_best_effort(&self) -> bool {
+        self.deadline() != NO_DEADLINE
+    }
 }
 
 /// Convenience `CountBytes` implementation that returns the same value as
@@ -381,6 +460,7 @@ impl From<&Request> for pb_queues::Request {
             method_name: req.method_name.clone(),
             method_payload: req.method_payload.clone(),
             cycles_payment: Some((req.payment).into()),
+            deadline_seconds: req.deadline.as_secs_since_unix_epoch(),
         }
     }
 }
@@ -404,6 +484,7 @@ impl TryFrom<pb_queues::Request> for Request {
             payment,
             method_name: req.method_name,
             method_payload: req.method_payload,
+            deadline: CoarseTime::from_secs_since_unix_epoch(req.deadline_seconds),
         })
     }
 }
@@ -446,6 +527,7 @@ impl From<&Response> for pb_queues::Response {
             refund: Some((&Funds::new(rep.refund)).into()),
             response_payload: Some(p),
             cycles_refund: Some((rep.refund).into()),
+            deadline_seconds: rep.deadline.as_secs_since_unix_epoch(),
         }
     }
 }
@@ -476,6 +558,7 @@ impl TryFrom<pb_queues::Response> for Response {
             originator_reply_callback: rep.originator_reply_callback.into(),
             refund,
             response_payload,
+            deadline: CoarseTime::from_secs_since_unix_epoch(rep.deadline_seconds),
         })
     }
 }
@@ -65,6 +65,11 @@
 //! ```
 //! * When the manifest version is greater than or equal to `STATE_SYNC_V2`,
 //!   the hash of the meta-manifest functions as the manifest hash.
+//!
+//! When the manifest version is greater than or equal to `STATE_SYNC_V3`, all
+//! chunks except the manifest chunk are compressed with zstd before they are
+//! sent to a peer. The chunk hashes above are always computed on the
+//! uncompressed content, so compression does not affect the manifest hash.
 pub mod proto;
 
 use crate::chunkable::ChunkId;
@@ -91,6 +96,12 @@ pub const MANIFEST_CHUNK: ChunkId = ChunkId::new(0);
 // The real number of canisters and size of state are not even close to the assumption so the value of `FILE_GROUP_CHUNK_ID_OFFSET` is chosen safely.
 pub const FILE_GROUP_CHUNK_ID_OFFSET: u32 = 1 << 30;
 
+/// Manifest version starting from which all chunks except the manifest chunk
+/// are compressed with zstd on the wire.
+///
+/// Corresponds to `STATE_SYNC_V3` in the state manager.
+pub const MIN_COMPRESSED_CHUNKS_VERSION: u32 = 3;
+
 /// An entry of the file table.
 #[derive(Debug, Clone, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
 pub struct FileInfo {
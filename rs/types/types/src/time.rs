@@ -139,6 +139,54 @@ impl Time {
     }
 }
 
+/// Time since UNIX_EPOCH, in seconds. Used e.g. for the deadlines of
+/// best-effort messages, where second granularity is sufficient and a compact
+/// representation is desirable.
+#[derive(
+    Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, Default, Hash, Serialize, Deserialize,
+)]
+#[cfg_attr(test, derive(Arbitrary))]
+pub struct CoarseTime(u32);
+
+const NANOS_PER_SEC: u64 = 1_000_000_000;
+
+impl CoarseTime {
+    pub const fn from_secs_since_unix_epoch(secs: u32) -> Self {
+        CoarseTime(secs)
+    }
+
+    /// Number of seconds since UNIX EPOCH.
+    pub fn as_secs_since_unix_epoch(&self) -> u32 {
+        self.0
+    }
+
+    /// Converts a `Time` to a `CoarseTime`, rounding down. Saturates at
+    /// `u32::MAX` seconds.
+    pub fn floor(time: Time) -> Self {
+        let secs = time.as_nanos_since_unix_epoch() / NANOS_PER_SEC;
+        CoarseTime(secs.min(u32::MAX as u64) as u32)
+    }
+
+    /// Converts a `Time` to a `CoarseTime`, rounding up. Saturates at
+    /// `u32::MAX` seconds.
+    pub fn ceil(time: Time) -> Self {
+        let nanos = time.as_nanos_since_unix_epoch();
+        let secs = nanos / NANOS_PER_SEC + (nanos % NANOS_PER_SEC != 0) as u64;
+        CoarseTime(secs.min(u32::MAX as u64) as u32)
+    }
+
+    /// Saturating `CoarseTime` addition.
+    pub fn saturating_add_secs(self, secs: u32) -> Self {
+        CoarseTime(self.0.saturating_add(secs))
+    }
+}
+
+impl From<CoarseTime> for Time {
+    fn from(val: CoarseTime) -> Self {
+        Time::from_nanos_since_unix_epoch(val.0 as u64 * NANOS_PER_SEC)
+    }
+}
+
 #[derive(Error, Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum TimeInstantiationError {
     #[error("Time cannot be instantiated as it would overflow: {0}")]
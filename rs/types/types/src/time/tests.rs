@@ -65,3 +65,37 @@ fn should_convert_from_system_time_and_back() {
     let back: SystemTime = time.into();
     assert_eq!(system_time, back);
 }
+
+mod coarse_time {
+    use crate::time::CoarseTime;
+    use crate::Time;
+
+    #[test]
+    fn should_round_to_seconds() {
+        let time = Time::from_nanos_since_unix_epoch(1_500_000_000);
+        assert_eq!(CoarseTime::floor(time).as_secs_since_unix_epoch(), 1);
+        assert_eq!(CoarseTime::ceil(time).as_secs_since_unix_epoch(), 2);
+
+        let time = Time::from_nanos_since_unix_epoch(2_000_000_000);
+        assert_eq!(CoarseTime::floor(time).as_secs_since_unix_epoch(), 2);
+        assert_eq!(CoarseTime::ceil(time).as_secs_since_unix_epoch(), 2);
+    }
+
+    #[test]
+    fn should_saturate() {
+        let time = Time::from_nanos_since_unix_epoch(u64::MAX);
+        assert_eq!(CoarseTime::floor(time).as_secs_since_unix_epoch(), u32::MAX);
+        assert_eq!(
+            CoarseTime::from_secs_since_unix_epoch(u32::MAX).saturating_add_secs(1),
+            CoarseTime::from_secs_since_unix_epoch(u32::MAX)
+        );
+    }
+
+    #[test]
+    fn should_convert_to_time() {
+        assert_eq!(
+            Time::from(CoarseTime::from_secs_since_unix_epoch(3)),
+            Time::from_nanos_since_unix_epoch(3_000_000_000)
+        );
+    }
+}
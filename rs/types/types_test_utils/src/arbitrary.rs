@@ -1,7 +1,9 @@
 use crate::ids::{canister_test_id, node_test_id, subnet_test_id, user_test_id};
 use ic_types::{
     crypto::{AlgorithmId, KeyPurpose, UserPublicKey},
-    messages::{CallbackId, Payload, RejectContext, Request, RequestOrResponse, Response},
+    messages::{
+        CallbackId, Payload, RejectContext, Request, RequestOrResponse, Response, NO_DEADLINE,
+    },
     state_sync::{ChunkInfo, FileInfo},
     time::UNIX_EPOCH,
     xnet::StreamIndex,
@@ -120,6 +122,7 @@ prop_compose! {
             receiver,
             sender,
             sender_reply_callback: CallbackId::from(callback),
+            deadline: NO_DEADLINE,
             payment: Cycles::from(cycles_payment),
             method_name,
             method_payload,
@@ -155,6 +158,7 @@ prop_compose! {
             originator,
             respondent,
             originator_reply_callback: CallbackId::from(callback),
+            deadline: NO_DEADLINE,
             refund: Cycles::from(cycles_refund),
             response_payload
         }